@@ -0,0 +1,120 @@
+use crate::ui::MoonflareUI;
+use crate::utils::cloudflare::{ACCESS_PERMISSION, CloudflareClient};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::manifest::{AccessRecord, DeploymentManifest};
+use crate::utils::projects::{WorkspaceProject, find_project, project_not_found};
+use crate::utils::routes::route_claims;
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Result, bail};
+use colored::*;
+
+pub struct AccessCommand {
+    ui: MoonflareUI,
+}
+
+impl AccessCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Put a Cloudflare Access application with an email-domain allow policy in front of the
+    /// project's custom domain, route or workers.dev URL
+    pub async fn execute_protect(
+        &self,
+        project: &str,
+        policy: &str,
+        domain: Option<&str>,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let email_domain = policy.trim_start_matches('@');
+        if !email_domain.contains('.') || email_domain.contains(char::is_whitespace) {
+            bail!(
+                "'{}' isn't an email domain. Pass the domain people sign in with, e.g. --policy example.com",
+                policy
+            );
+        }
+
+        let project = find_project(project).ok_or_else(|| project_not_found(project))?;
+        let Some(config) = read_wrangler_config(&project.path)? else {
+            bail!("Project '{}' has no Wrangler configuration", project.name);
+        };
+
+        let mut manifest = DeploymentManifest::load()?;
+        if let Some(record) = manifest.access.get(&project.name) {
+            bail!(
+                "'{}' is already protected by Access application {} on {}",
+                project.name,
+                record.app_id,
+                record.domain
+            );
+        }
+
+        let client = CloudflareClient::from_env(config["account_id"].as_str(), ACCESS_PERMISSION)?;
+        let domain = match domain {
+            Some(domain) => domain.to_string(),
+            None => match routed_domain(&project)? {
+                Some(domain) => domain,
+                None => {
+                    let name = config["name"].as_str().unwrap_or(&project.name);
+                    format!("{}.{}.workers.dev", name, client.workers_subdomain().await?)
+                }
+            },
+        };
+
+        println!(
+            "{}",
+            format!("Creating Access application for {}...", domain)
+                .cyan()
+                .bold()
+        );
+        let app = client.create_access_app(&project.name, &domain).await?;
+        let policy = client
+            .create_email_domain_policy(&app.id, email_domain)
+            .await?;
+        println!("  Application {} ({})", app.id, app.domain);
+        println!("  Policy '{}' ({})", policy.name, policy.id);
+
+        manifest.access.insert(
+            project.name.clone(),
+            AccessRecord {
+                account_id: client.account_id.clone(),
+                app_id: app.id,
+                domain: domain.clone(),
+                policy: email_domain.to_string(),
+                created_at: chrono::Local::now().to_rfc3339(),
+            },
+        );
+        manifest.save()?;
+
+        self.ui
+            .render_success(&format!(
+                "{} now requires a sign-in with an @{} email address",
+                domain, email_domain
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
+
+/// Domain (and path) of the project's first custom domain or non-wildcard route
+fn routed_domain(project: &WorkspaceProject) -> Result<Option<String>> {
+    let claims = route_claims(std::slice::from_ref(project), None)?;
+    Ok(claims
+        .iter()
+        .map(|claim| {
+            claim
+                .pattern
+                .trim_start_matches("https://")
+                .trim_start_matches("http://")
+                .trim_end_matches('*')
+                .trim_end_matches('/')
+                .to_string()
+        })
+        .find(|domain| !domain.starts_with('*') && !domain.is_empty()))
+}
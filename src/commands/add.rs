@@ -1,14 +1,77 @@
-use crate::templates::{embedded, engine::TemplateEngine};
+use crate::templates::{
+    embedded,
+    engine::TemplateEngine,
+    registry::{self, LIST_TYPES_SCHEMA, PROJECT_KINDS, ProjectKind, labels_with},
+};
 use crate::ui::MoonflareUI;
+use crate::utils::aliases::sync_aliases;
+use crate::utils::cargo_workspace::{CARGO_WORKSPACE_FILE, sync_cargo_workspace};
+use crate::utils::config::{LayoutConfig, MoonflareConfig};
+use crate::utils::containers;
+use crate::utils::dev_vars::{DEV_VARS_EXAMPLE_FILE, DEV_VARS_FILE, render_example, required_vars};
+use crate::utils::e2e;
 use crate::utils::fs::{
     add_crate_build_dependency_to_shared_wasm, add_wasm_dependency_to_project,
     create_directory_if_not_exists, get_project_directory, get_typescript_projects, has_crates,
-    has_wasm_dependency, is_moonflare_workspace,
+    has_wasm_dependency, is_moonflare_workspace, non_empty_directory_message, path_to_root,
+    replace_identifiers,
+};
+use crate::utils::node::NODE_ENGINE;
+use crate::utils::projects::{
+    WorkspaceProject, discover_projects, find_project, grouped_project_id, project_not_found,
+};
+use crate::utils::readme::write_readme;
+use crate::utils::smoke::initial_checks;
+use crate::utils::task_env;
+use crate::utils::workspace_globs::sync_workspace_globs;
+use crate::utils::wrangler::{
+    STORYBOOK_WRANGLER_CONFIG, find_wrangler_config, read_wrangler_config, set_config_name,
 };
 use anyhow::{Result, bail};
+use colored::*;
+use convert_case::{Case, Casing};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::Path;
+use walkdir::WalkDir;
+
+/// Which template `moonflare add crate` generates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CrateFlavor {
+    #[default]
+    Standard,
+    /// no_std and size-optimized (`--small`)
+    Small,
+    /// A WASM component with a WIT interface (`--component-model`)
+    Component,
+    /// A plain Rust library shared with the other crates, never built to WASM (`--lib-only`)
+    LibOnly,
+}
+
+impl CrateFlavor {
+    fn flag(self) -> &'static str {
+        match self {
+            CrateFlavor::Standard => "",
+            CrateFlavor::Small => "--small",
+            CrateFlavor::Component => "--component-model",
+            CrateFlavor::LibOnly => "--lib-only",
+        }
+    }
+}
+
+/// Options for `moonflare add`
+#[derive(Debug, Default)]
+pub struct AddOptions {
+    /// Features to include, like `storybook` or `hashed-assets`
+    pub features: Vec<String>,
+    /// Existing project to copy instead of rendering a template
+    pub from: Option<String>,
+    /// Generate into a directory that already has files
+    pub force: bool,
+    pub flavor: CrateFlavor,
+    /// Worker a container is attached to
+    pub attach: Option<String>,
+}
 
 pub struct AddCommand {
     template_engine: TemplateEngine,
@@ -23,71 +86,246 @@ impl AddCommand {
         }
     }
 
-    pub async fn execute(&self, project_type: &str, name: &str) -> Result<()> {
+    pub async fn execute(
+        &self,
+        project_type: &str,
+        name: Option<&str>,
+        options: &AddOptions,
+    ) -> Result<()> {
+        let features = &options.features;
+        let from = options.from.as_deref();
+        let (force, flavor) = (options.force, options.flavor);
+        let attach = options.attach.as_deref();
         // Check if we're in a Moonflare workspace
         if !is_moonflare_workspace() {
             bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
         }
+        let kind =
+            registry::find(project_type).ok_or_else(|| registry::unknown_type(project_type))?;
+        // Aliases such as `worker` are generated as the type they stand for
+        let project_type = kind.name;
+        let name = match (name, kind.default_name) {
+            (Some(name), _) => name,
+            (None, Some(default)) => default,
+            (None, None) => bail!(
+                "A project name is required: moonflare add {} <NAME>",
+                project_type
+            ),
+        };
+
+        for feature in features {
+            if kind.has_feature(feature) {
+                continue;
+            }
+            if let Some(feature) = registry::find_feature(feature) {
+                bail!("{}", feature.unavailable);
+            }
+            let mut names: Vec<&str> = PROJECT_KINDS
+                .iter()
+                .flat_map(|k| k.features.iter().map(|f| f.name))
+                .collect();
+            names.sort();
+            names.dedup();
+            bail!(
+                "Unknown feature '{}'. Available features: {}",
+                feature,
+                names.join(", ")
+            );
+        }
+        if flavor != CrateFlavor::Standard && !kind.has_flag(flavor.flag()) {
+            bail!(
+                "{} is only available for {}",
+                flavor.flag(),
+                labels_with(|k| k.has_flag(flavor.flag()))
+            );
+        }
+        // Containers run inside a Worker's Durable Object, so they need one to attach to
+        let worker = match (attach, project_type) {
+            (Some(worker), "container") => {
+                let worker = find_project(worker).ok_or_else(|| project_not_found(worker))?;
+                if !worker.is_worker() {
+                    bail!(
+                        "'{}' isn't a Worker; containers attach to Workers",
+                        worker.name
+                    );
+                }
+                Some(worker)
+            }
+            (None, "container") => bail!(
+                "A container needs a Worker to run it: moonflare add container {} --attach <WORKER>",
+                name
+            ),
+            (Some(_), _) => bail!(
+                "--attach is only available for {}",
+                labels_with(|k| k.has_flag("--attach"))
+            ),
+            (None, _) => None,
+        };
+        // `shop/frontend` nests the project in the `shop` group as `shop-frontend`
+        let id = project_id(name)?;
+        if id != name && !kind.groupable {
+            bail!(
+                "Only sites, apps and Workers can be grouped; shared-wasm gathers crates from a single directory"
+            );
+        }
+        let hashed_assets = features.iter().any(|f| f == "hashed-assets");
+        let config = MoonflareConfig::load()?;
+        let worker_name = config.worker_name(&id);
+
+        // Get the appropriate directory for this project type
+        let project_dir = get_project_directory(project_type);
+        let target_path = Path::new(&project_dir).join(name);
+
+        let source_path = from.map(|source| Path::new(&project_dir).join(source));
+        if let (Some(source), Some(source_path)) = (from, &source_path) {
+            if !source_path.is_dir() {
+                bail!(
+                    "{} project '{}' not found in {}/",
+                    project_type,
+                    source,
+                    project_dir
+                );
+            }
+            if project_id(source)? == id {
+                bail!("Can't clone '{}' onto itself", source);
+            }
+        }
 
         self.ui
             .render_header(
                 "Adding project",
-                Some(&format!("Creating {} project '{}'", project_type, name)),
+                Some(&match from {
+                    Some(source) => format!(
+                        "Cloning {} project '{}' as '{}'",
+                        project_type, source, name
+                    ),
+                    None => format!("Creating {} project '{}'", project_type, name),
+                }),
             )
             .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
 
-        // Get the appropriate directory for this project type
-        let project_dir = get_project_directory(project_type);
-        let target_path = Path::new(project_dir).join(name);
-
-        // Create project directory
-        create_directory_if_not_exists(&target_path)?;
+        // An existing directory is only scaffolded into when it's empty, as with init
+        if target_path.exists() {
+            if !target_path.is_dir() {
+                bail!(
+                    "'{}' already exists and is not a directory",
+                    target_path.display()
+                );
+            }
 
-        // Get template content
-        let template = embedded::get_template(project_type)
-            .ok_or_else(|| anyhow::anyhow!("Unknown project type: {}", project_type))?;
+            let entries = std::fs::read_dir(&target_path)?.collect::<Result<Vec<_>, _>>()?;
+            if !entries.is_empty() && !force {
+                let message = non_empty_directory_message(name, &entries);
+                let add_command = format!("moonflare add {} {} --force", project_type, name);
+                self.ui
+                    .render_error(
+                        "Directory is not empty",
+                        &message,
+                        vec![
+                            &add_command,
+                            "Choose a different project name",
+                            "Remove existing files first",
+                        ],
+                    )
+                    .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
 
-        // Prepare template context
-        let mut context = HashMap::new();
-        context.insert("name".to_string(), Value::String(name.to_string()));
-
-        // Add additional context based on project type
-        if project_type == "durable-object" {
-            context.insert("name_upper".to_string(), Value::String(name.to_uppercase()));
-            context.insert("name_title".to_string(), Value::String(to_title_case(name)));
+                // The UI has already shown the error
+                std::process::exit(1);
+            } else if !entries.is_empty() {
+                self.ui
+                    .render_section_start(&format!(
+                        "Warning: Directory '{}' contains {} files. Proceeding with --force.",
+                        target_path.display(),
+                        entries.len()
+                    ))
+                    .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+            }
         }
 
-        // For TypeScript projects, check if we need WASM dependencies
-        let is_typescript_project = matches!(project_type, "astro" | "react" | "durable-object");
-        let should_add_wasm_deps = is_typescript_project && has_crates();
+        // Create project directory
+        create_directory_if_not_exists(&target_path)?;
 
-        // Add WASM context if needed
-        if should_add_wasm_deps {
-            context.insert("has_wasm".to_string(), Value::Bool(true));
+        match (from, &source_path) {
+            (Some(source), Some(source_path)) => {
+                let renamed = clone_project(source_path, &target_path, &project_id(source)?, &id)?;
+                name_cloned_workers(&target_path, &worker_name)?;
+                println!(
+                    "Copied {} and renamed '{}' in {} files",
+                    source_path.display(),
+                    source,
+                    renamed
+                );
+            }
+            _ => self.generate_from_template(
+                kind,
+                &id,
+                &worker_name,
+                options,
+                worker.as_ref(),
+                &target_path,
+            )?,
         }
 
-        // Generate project files
-        self.template_engine
-            .process_template_files(template, &target_path, &context)?;
+        if id != name {
+            write_moon_id(&target_path, &id)?;
+        }
 
         // Handle special post-generation tasks
         match project_type {
+            // Lib-only crates have no WASM for TypeScript projects to use
+            "crate" if flavor == CrateFlavor::LibOnly => self.update_gather_script()?,
             "crate" => {
                 // When adding a crate, update all existing TypeScript projects to depend on WASM
                 self.add_wasm_dependencies_to_existing_projects().await?;
                 // Update shared-wasm to depend on this new crate
                 self.add_crate_dependency_to_shared_wasm(name).await?;
+                self.update_gather_script()?;
             }
-            "react" | "durable-object" => {
+            "react" | "durable-object" | "maintenance-bot" => {
                 // Generate Wrangler types for TypeScript support
-                self.generate_wrangler_types(&target_path).await?;
+                self.generate_wrangler_types(&target_path, &id).await?;
             }
             "astro" => {
                 // WASM dependencies are handled by template context
             }
+            "container" => {
+                if let Some(worker) = &worker {
+                    containers::attach(worker, &id, &target_path)?;
+                    println!(
+                        "Attached to '{}' as {} (binding {})",
+                        worker.name,
+                        containers::class_name(&id),
+                        containers::binding(&id)
+                    );
+                }
+            }
             _ => {}
         }
 
+        // Document the project's tasks, bindings and variables in its README; the smoke and
+        // end-to-end tests and containers come with their own
+        if !matches!(project_type, "smoke-tests" | "e2e" | "container") {
+            write_readme(&WorkspaceProject {
+                name: id.clone(),
+                path: target_path.clone(),
+            })?;
+        }
+
+        // Keep pnpm and Moon workspace globs in line with the project directories on disk
+        for drift in sync_workspace_globs(Path::new("."))? {
+            println!("Updated {}: {}", drift.source, drift.describe());
+        }
+        // ...and the @wasm/@packages import aliases in line with what the workspace contains
+        for path in sync_aliases()? {
+            println!("Updated aliases in {}", path.display());
+        }
+        // ...and the root Cargo workspace in line with the crates
+        if project_type == "crate" {
+            for change in sync_cargo_workspace()? {
+                println!("Updated {}: {}", CARGO_WORKSPACE_FILE, change);
+            }
+        }
+
         self.ui
             .render_success(&format!(
                 "Successfully created {} project '{}'",
@@ -95,9 +333,221 @@ impl AddCommand {
             ))
             .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
 
+        let next_steps_for = match &worker {
+            Some(worker) => worker.name.clone(),
+            None => id.clone(),
+        };
         self.ui
-            .render_next_steps_for_project(name, project_type)
+            .render_next_steps_for_project(&next_steps_for, project_type)
             .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if hashed_assets && !config.assets.hashing {
+            println!(
+                "Set hashing = true under [assets] in moonflare.toml to serve its hashed assets with long-lived cache headers"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Every project type with its directory, `--with` features and flags. The JSON is a
+    /// stable interface for editors, versioned by its `schema` field.
+    pub fn execute_list_types(&self, json: bool) -> Result<()> {
+        let layout = LayoutConfig::current();
+        if json {
+            let types = PROJECT_KINDS
+                .iter()
+                .map(|kind| {
+                    let mut value = serde_json::to_value(kind)?;
+                    value["directory"] = Value::String(kind.directory.resolve(&layout));
+                    value["template_version"] = Value::String(kind.template_version());
+                    Ok(value)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let listing = serde_json::json!({
+                "schema": LIST_TYPES_SCHEMA,
+                "moonflare_version": env!("CARGO_PKG_VERSION"),
+                "types": types,
+            });
+            println!("{}", serde_json::to_string_pretty(&listing)?);
+            return Ok(());
+        }
+
+        for kind in PROJECT_KINDS {
+            println!(
+                "{:<16} {:<12} {}",
+                kind.name.bold(),
+                format!("{}/", kind.directory.resolve(&layout)),
+                kind.description
+            );
+            let options: Vec<String> = kind
+                .features
+                .iter()
+                .map(|feature| format!("--with {}", feature.name))
+                .chain(kind.flags.iter().map(|flag| match flag.value {
+                    Some(value) => format!("{} <{}>", flag.name, value),
+                    None => flag.name.to_string(),
+                }))
+                .collect();
+            if !options.is_empty() {
+                println!("{:<29} {}", "", options.join(", ").dimmed());
+            }
+        }
+        Ok(())
+    }
+
+    /// Render the project type's template (and any features) into `target_path`
+    fn generate_from_template(
+        &self,
+        kind: &ProjectKind,
+        name: &str,
+        worker_name: &str,
+        options: &AddOptions,
+        worker: Option<&WorkspaceProject>,
+        target_path: &Path,
+    ) -> Result<()> {
+        let project_type = kind.name;
+        let (features, flavor) = (&options.features, options.flavor);
+        let storybook = features.iter().any(|f| f == "storybook");
+        // Get template content; component and lib-only crates replace the crate template
+        // entirely, since neither builds the wasm-bindgen module its benchmarks need
+        let template = match flavor {
+            CrateFlavor::Component => embedded::COMPONENT_CRATE_TEMPLATE,
+            CrateFlavor::LibOnly => embedded::LIB_CRATE_TEMPLATE,
+            _ => kind.template,
+        };
+
+        // Prepare template context
+        let mut context = HashMap::new();
+        context.insert("name".to_string(), Value::String(name.to_string()));
+        context.insert(
+            "worker_name".to_string(),
+            Value::String(worker_name.to_string()),
+        );
+        context.insert(
+            "node_engine".to_string(),
+            Value::String(NODE_ENGINE.to_string()),
+        );
+        context.insert("root".to_string(), Value::String(path_to_root(target_path)));
+
+        // Add additional context based on project type
+        if project_type == "durable-object" {
+            context.insert("name_upper".to_string(), Value::String(name.to_uppercase()));
+            context.insert("name_title".to_string(), Value::String(to_title_case(name)));
+        }
+
+        if storybook {
+            context.insert("storybook".to_string(), Value::Bool(true));
+        }
+        if features.iter().any(|f| f == "hashed-assets") {
+            context.insert("hashed_assets".to_string(), Value::Bool(true));
+        }
+        let debug_route = features.iter().any(|f| f == "debug-route");
+        let queue = features.iter().any(|f| f == "queue");
+        if debug_route {
+            context.insert("debug_route".to_string(), Value::Bool(true));
+        }
+        if queue {
+            context.insert("queue".to_string(), Value::Bool(true));
+        }
+        // Both features guard their routes with a var, and wrangler.toml has one [vars] table
+        if debug_route || queue {
+            context.insert("vars".to_string(), Value::Bool(true));
+        }
+
+        if project_type == "maintenance-bot" {
+            context.insert(
+                "projects".to_string(),
+                Value::String(serde_json::to_string_pretty(&watched_workers()?)?),
+            );
+            context.insert(
+                "account_id".to_string(),
+                Value::String(std::env::var("CLOUDFLARE_ACCOUNT_ID").unwrap_or_default()),
+            );
+        }
+
+        if let Some(worker) = worker {
+            context.insert("worker".to_string(), Value::String(worker.name.clone()));
+            context.insert(
+                "worker_path".to_string(),
+                Value::String(worker.relative_path()),
+            );
+            context.insert(
+                "class_name".to_string(),
+                Value::String(containers::class_name(name)),
+            );
+            context.insert(
+                "binding".to_string(),
+                Value::String(containers::binding(name)),
+            );
+        }
+
+        if project_type == "e2e" {
+            let frontends: Vec<Value> = e2e::frontends()
+                .into_iter()
+                .map(|project| Value::String(project.name))
+                .collect();
+            context.insert("frontends".to_string(), Value::Array(frontends));
+        }
+
+        if project_type == "smoke-tests" {
+            context.insert(
+                "checks".to_string(),
+                Value::String(
+                    serde_json::to_string_pretty(&initial_checks(&MoonflareConfig::load()?))?
+                        .replace('\n', "\n  "),
+                ),
+            );
+        }
+
+        // For TypeScript projects, check if we need WASM dependencies
+        let is_typescript_project = kind.typescript;
+        let should_add_wasm_deps = is_typescript_project && has_crates();
+
+        // Add WASM context if needed
+        if should_add_wasm_deps {
+            context.insert("has_wasm".to_string(), Value::Bool(true));
+        }
+
+        // Generate project files
+        self.template_engine
+            .process_template_files(template, target_path, &context)?;
+        if matches!(project_type, "react" | "astro") {
+            self.template_engine.process_template_files(
+                embedded::WASM_RELOAD_TEMPLATE,
+                target_path,
+                &context,
+            )?;
+        }
+        if storybook {
+            self.template_engine.process_template_files(
+                embedded::STORYBOOK_TEMPLATE,
+                target_path,
+                &context,
+            )?;
+        }
+        if queue {
+            self.template_engine.process_template_files(
+                embedded::QUEUE_TEMPLATE,
+                target_path,
+                &context,
+            )?;
+        }
+        if flavor == CrateFlavor::Small {
+            self.template_engine.process_template_files(
+                embedded::SMALL_CRATE_TEMPLATE,
+                target_path,
+                &context,
+            )?;
+        }
+
+        // Document the variables `wrangler dev` expects for Worker projects
+        if is_typescript_project {
+            let vars = required_vars(target_path)?;
+            std::fs::write(
+                target_path.join(DEV_VARS_EXAMPLE_FILE),
+                render_example(&vars),
+            )?;
+        }
 
         Ok(())
     }
@@ -130,12 +580,40 @@ impl AddCommand {
         Ok(())
     }
 
+    /// Workspaces created before component crates and the root Cargo workspace existed have a
+    /// gather script that only copies core modules from each crate's own `target/`; replace it
+    /// with the current one, which also generates component bindings, reads the root `target/`
+    /// and skips lib-only crates
+    fn update_gather_script(&self) -> Result<()> {
+        let path = Path::new(GATHER_SCRIPT);
+        let Ok(current) = std::fs::read_to_string(path) else {
+            return Ok(());
+        };
+        if current.contains(GATHER_MARKER) {
+            return Ok(());
+        }
+
+        if let Some(section) =
+            TemplateEngine::template_section(embedded::WORKSPACE_TEMPLATE, GATHER_SCRIPT)
+        {
+            let content = self
+                .template_engine
+                .render_template(&section, &HashMap::new())?;
+            std::fs::write(path, content)?;
+            println!(
+                "Updated {} to gather crates built through the Cargo workspace",
+                GATHER_SCRIPT
+            );
+        }
+        Ok(())
+    }
+
     async fn add_crate_dependency_to_shared_wasm(&self, crate_name: &str) -> Result<()> {
         add_crate_build_dependency_to_shared_wasm(crate_name)?;
         Ok(())
     }
 
-    async fn generate_wrangler_types(&self, project_path: &Path) -> Result<()> {
+    async fn generate_wrangler_types(&self, project_path: &Path, project: &str) -> Result<()> {
         use std::process::Command;
         use which::which;
 
@@ -150,6 +628,7 @@ impl AddCommand {
         // Run wrangler types in the project directory
         let output = Command::new("wrangler")
             .args(["types"])
+            .envs(task_env::vars(Some(project)))
             .current_dir(project_path)
             .output();
 
@@ -173,6 +652,60 @@ impl AddCommand {
     }
 }
 
+/// Deployed Workers a maintenance bot watches: every project with a Wrangler configuration,
+/// with the worker name and cron triggers it declares
+fn watched_workers() -> Result<Vec<Value>> {
+    let mut workers = Vec::new();
+    for project in discover_projects() {
+        let Some(config) = read_wrangler_config(&project.path)? else {
+            continue;
+        };
+        let crons = config
+            .pointer("/triggers/crons")
+            .cloned()
+            .unwrap_or_else(|| Value::Array(Vec::new()));
+        workers.push(serde_json::json!({
+            "project": project.name,
+            "worker": config["name"].as_str().unwrap_or(&project.name),
+            "crons": crons,
+        }));
+    }
+    Ok(workers)
+}
+
+/// Moon id for a project name: `shop/frontend` nests `frontend` in the `shop` group as
+/// `shop-frontend`, and any other name is its own id
+fn project_id(name: &str) -> Result<String> {
+    let Some((group, project)) = name.split_once('/') else {
+        return Ok(name.to_string());
+    };
+    if group.is_empty() || project.is_empty() || project.contains('/') {
+        bail!(
+            "'{}' isn't a valid project name; groups nest one level, as in shop/frontend",
+            name
+        );
+    }
+    Ok(grouped_project_id(group, project))
+}
+
+/// Set a grouped project's `id` in its moon.yml, since Moon otherwise names a project after
+/// its directory alone
+fn write_moon_id(target_path: &Path, id: &str) -> Result<()> {
+    let path = target_path.join("moon.yml");
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let mut lines: Vec<String> = content
+        .lines()
+        .filter(|line| !line.starts_with("id:"))
+        .map(String::from)
+        .collect();
+    let position = usize::from(lines.first().is_some_and(|l| l.starts_with("$schema:")));
+    lines.insert(position, format!("id: '{}'", id));
+    std::fs::write(&path, lines.join("\n") + "\n")?;
+    Ok(())
+}
+
 fn to_title_case(s: &str) -> String {
     s.chars()
         .enumerate()
@@ -185,3 +718,87 @@ fn to_title_case(s: &str) -> String {
         })
         .collect()
 }
+
+/// The shared-wasm script that collects crate builds for TypeScript projects
+const GATHER_SCRIPT: &str = "shared-wasm/gather-wasm.cjs";
+
+/// Text only gather scripts that read the root Cargo workspace's `target/` contain
+const GATHER_MARKER: &str = "workspaceWasmDir";
+
+/// Build output and installed dependencies aren't copied when cloning a project
+const CLONE_SKIPPED_DIRECTORIES: [&str; 6] = [
+    "node_modules",
+    "target",
+    "dist",
+    ".wrangler",
+    ".astro",
+    "pkg",
+];
+
+/// Copy `source_path` to `target_path`, renaming `from` to `name` in every text file. Local
+/// `.dev.vars` secrets stay behind. Returns the number of files that mentioned `from`.
+fn clone_project(source_path: &Path, target_path: &Path, from: &str, name: &str) -> Result<usize> {
+    let walker = WalkDir::new(source_path).into_iter().filter_entry(|entry| {
+        !(entry.file_type().is_dir()
+            && CLONE_SKIPPED_DIRECTORIES
+                .iter()
+                .any(|skipped| entry.file_name() == *skipped))
+    });
+
+    let mut renamed = 0;
+    for entry in walker {
+        let entry = entry?;
+        let target = target_path.join(entry.path().strip_prefix(source_path)?);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else if entry.file_name() != DEV_VARS_FILE {
+            let bytes = std::fs::read(entry.path())?;
+            match String::from_utf8(bytes) {
+                Ok(content) => {
+                    let updated = rename_identifiers(&content, from, name);
+                    if updated != content {
+                        renamed += 1;
+                    }
+                    std::fs::write(&target, updated)?;
+                }
+                Err(binary) => std::fs::write(&target, binary.into_bytes())?,
+            }
+        }
+    }
+
+    Ok(renamed)
+}
+
+/// Point a clone's Wrangler configurations at its own worker, since a name following
+/// `[workspace] worker_name` isn't a bare project name `rename_identifiers` would catch
+fn name_cloned_workers(target_path: &Path, worker_name: &str) -> Result<()> {
+    if let Some(config) = find_wrangler_config(target_path) {
+        set_config_name(&config, worker_name)?;
+    }
+    let storybook = target_path.join(STORYBOOK_WRANGLER_CONFIG);
+    if storybook.exists() {
+        set_config_name(&storybook, &format!("{}-storybook", worker_name))?;
+    }
+    Ok(())
+}
+
+/// Replace every spelling of a project name the templates generate: the name itself, its
+/// snake_case form (Rust crate paths) and the Durable Object identifiers built with the
+/// `title` and `upper` helpers
+fn rename_identifiers(content: &str, from: &str, name: &str) -> String {
+    let replacements = [
+        (from.to_string(), name.to_string()),
+        (from.to_case(Case::Snake), name.to_case(Case::Snake)),
+        (
+            format!("{}DurableObject", from.to_case(Case::Pascal)),
+            format!("{}DurableObject", name.to_case(Case::Pascal)),
+        ),
+        (
+            format!("{}_DURABLE_OBJECT", from.to_case(Case::ScreamingSnake)),
+            format!("{}_DURABLE_OBJECT", name.to_case(Case::ScreamingSnake)),
+        ),
+    ];
+
+    // Only whole identifiers are renamed, so `api` leaves `api-client` and `rapid` alone
+    replace_identifiers(content, &replacements)
+}
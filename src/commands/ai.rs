@@ -0,0 +1,158 @@
+use crate::ui::MoonflareUI;
+use crate::utils::ai_gateway::{
+    GATEWAY_VAR, ai_binding, declare_var, route_calls, set_gateway_var, validate_name,
+};
+use crate::utils::backup::create_snapshot;
+use crate::utils::binding_audit::read_sources;
+use crate::utils::cloudflare::{AI_GATEWAY_PERMISSION, AiGatewaySettings, CloudflareClient};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{
+    WorkspaceProject, discover_projects, find_project, project_not_found,
+};
+use crate::utils::wrangler::{find_wrangler_config, parse_wrangler_file, write_wrangler_config};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::fs;
+
+pub struct AiCommand {
+    ui: MoonflareUI,
+}
+
+impl AiCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Create an AI Gateway, store its ID in the project's vars and route the project's
+    /// Workers AI calls through it
+    pub async fn execute_gateway_create(
+        &self,
+        name: &str,
+        project: Option<&str>,
+        settings: &AiGatewaySettings,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        validate_name(name)?;
+        if settings
+            .rate_limit
+            .is_some_and(|(limit, interval)| limit == 0 || interval == 0)
+        {
+            bail!("--rate-limit and --rate-limit-interval must be greater than 0");
+        }
+
+        let project = match project {
+            Some(name) => find_project(name).ok_or_else(|| project_not_found(name))?,
+            None => ai_project()?,
+        };
+        let Some(config_path) = find_wrangler_config(&project.path) else {
+            bail!("Project '{}' has no Wrangler configuration", project.name);
+        };
+        let mut config = parse_wrangler_file(&config_path)?;
+        let Some(binding) = ai_binding(&config).map(str::to_string) else {
+            bail!(
+                "Project '{}' has no Workers AI binding. Add an [ai] section with a binding to {} first",
+                project.name,
+                config_path.display()
+            );
+        };
+
+        let client =
+            CloudflareClient::from_env(config["account_id"].as_str(), AI_GATEWAY_PERMISSION)?;
+        println!(
+            "{}",
+            format!(
+                "Creating AI Gateway '{}' ({})...",
+                name,
+                settings.describe()
+            )
+            .cyan()
+            .bold()
+        );
+        let gateway = client.create_ai_gateway(name, settings).await?;
+
+        let mut sources = Vec::new();
+        let mut skipped = Vec::new();
+        for (path, content) in read_sources(&project.path) {
+            let (routed, calls) = route_calls(&content, &binding);
+            let routed = declare_var(&routed, &binding).unwrap_or(routed);
+            skipped.extend(calls.into_iter().map(|call| (path.clone(), call.line)));
+            if routed != content {
+                sources.push((path, routed));
+            }
+        }
+
+        let mut paths = vec![config_path.as_path()];
+        paths.extend(sources.iter().map(|(path, _)| path.as_path()));
+        let snapshot = create_snapshot("ai gateway create", &paths)?;
+
+        set_gateway_var(&mut config, &gateway.id);
+        write_wrangler_config(&config_path, &config)?;
+        println!("  Set {} in {}", GATEWAY_VAR, config_path.display());
+        for (path, content) in &sources {
+            fs::write(path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("  Routed {}.run calls in {}", binding, path.display());
+        }
+        for (path, line) in &skipped {
+            println!(
+                "{}",
+                format!(
+                    "  {}:{}: {}.run doesn't take (model, inputs); pass {{ gateway: {{ id: env.{} }} }} in its options by hand",
+                    path.display(),
+                    line,
+                    binding,
+                    GATEWAY_VAR
+                )
+                .yellow()
+            );
+        }
+        println!(
+            "{}",
+            format!(
+                "Comments in {} were not preserved; 'moonflare restore {}' brings the old files back.",
+                config_path.display(),
+                snapshot.id
+            )
+            .dimmed()
+        );
+
+        self.ui
+            .render_success(&format!(
+                "{}'s Workers AI calls now go through gateway '{}'",
+                project.name, gateway.id
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
+
+/// The one project with a Workers AI binding
+fn ai_project() -> Result<WorkspaceProject> {
+    let mut projects: Vec<_> = discover_projects()
+        .into_iter()
+        .filter(|project| {
+            find_wrangler_config(&project.path)
+                .and_then(|path| parse_wrangler_file(&path).ok())
+                .is_some_and(|config| ai_binding(&config).is_some())
+        })
+        .collect();
+    match projects.len() {
+        0 => bail!(
+            "No project has a Workers AI binding. Add an [ai] section to a project's Wrangler configuration first"
+        ),
+        1 => Ok(projects.remove(0)),
+        _ => bail!(
+            "Several projects use Workers AI ({}). Pick one with --project",
+            projects
+                .iter()
+                .map(|project| project.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
@@ -0,0 +1,181 @@
+use crate::ui::MoonflareUI;
+use crate::utils::aliases::sync_aliases;
+use crate::utils::backup::create_snapshot;
+use crate::utils::cargo_workspace::{CARGO_WORKSPACE_FILE, sync_cargo_workspace};
+use crate::utils::fs::{
+    add_crate_build_dependency_to_shared_wasm, is_moonflare_workspace,
+    remove_crate_build_dependency_from_shared_wasm,
+};
+use crate::utils::projects::{
+    ARCHIVE_DIR, WorkspaceProject, archived_projects, find_project, project_not_found,
+};
+use crate::utils::vcs::move_path;
+use crate::utils::workspace_globs::{
+    GlobDrift, GlobSource, MOON_WORKSPACE_FILE, PNPM_WORKSPACE_FILE, detect_drift, fix_drift,
+    remove_project_globs,
+};
+use anyhow::{Result, bail};
+use std::path::{Path, PathBuf};
+
+const SHARED_WASM_CONFIG: &str = "shared-wasm/moon.yml";
+
+pub struct ArchiveCommand {
+    ui: MoonflareUI,
+}
+
+impl ArchiveCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Move a project under `archive/`, out of the workspace globs and shared-wasm:gather,
+    /// keeping its files and git history
+    pub async fn execute_archive(&self, name: &str) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let project = find_project(name).ok_or_else(|| project_not_found(name))?;
+        let relative_path = project.relative_path();
+        let archived_path = Path::new(ARCHIVE_DIR).join(&relative_path);
+        if archived_path.exists() {
+            bail!(
+                "{} already exists; unarchive or remove it first",
+                archived_path.display()
+            );
+        }
+
+        let snapshot =
+            self.snapshot(&format!("archive {}", name), &project.path, &archived_path)?;
+        println!("Saved snapshot {}", snapshot);
+
+        move_path(&project.path, &archived_path)?;
+        println!("Moved {} to {}", relative_path, archived_path.display());
+
+        for source in remove_project_globs(Path::new("."), &relative_path)? {
+            println!("Removed {} from {}", relative_path, source);
+        }
+        if project.is_crate() && remove_crate_build_dependency_from_shared_wasm(&project.name)? {
+            println!(
+                "Removed {}:build from the shared-wasm:gather dependencies",
+                project.name
+            );
+        }
+        for path in sync_aliases()? {
+            println!("Updated aliases in {}", path.display());
+        }
+        for change in sync_cargo_workspace()? {
+            println!("Updated {}: {}", CARGO_WORKSPACE_FILE, change);
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Archived '{}'. Bring it back with 'moonflare unarchive {}'",
+                project.name, project.name
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+
+    /// Move an archived project back to where it came from
+    pub async fn execute_unarchive(&self, name: &str) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let archived = archived_projects();
+        let Some(project) = archived.iter().find(|p| {
+            p.name == name
+                || p.path
+                    .strip_prefix(ARCHIVE_DIR)
+                    .is_ok_and(|rest| rest == Path::new(name))
+        }) else {
+            let names: Vec<&str> = archived.iter().map(|p| p.name.as_str()).collect();
+            if names.is_empty() {
+                bail!("No archived project named '{}'; archive/ is empty", name);
+            }
+            bail!(
+                "No archived project named '{}'. Archived projects: {}",
+                name,
+                names.join(", ")
+            );
+        };
+
+        let restored = WorkspaceProject {
+            name: project.name.clone(),
+            path: project.path.strip_prefix(ARCHIVE_DIR)?.to_path_buf(),
+        };
+        if restored.path.exists() {
+            bail!(
+                "{} already exists; rename or remove it before unarchiving",
+                restored.path.display()
+            );
+        }
+
+        let snapshot = self.snapshot(
+            &format!("unarchive {}", name),
+            &project.path,
+            &restored.path,
+        )?;
+        println!("Saved snapshot {}", snapshot);
+
+        move_path(&project.path, &restored.path)?;
+        println!(
+            "Moved {} to {}",
+            project.path.display(),
+            restored.relative_path()
+        );
+
+        // Only add globs for projects nothing covers; stale globs are `moonflare doctor`'s call
+        let drift: Vec<GlobDrift> = detect_drift(Path::new("."))?
+            .into_iter()
+            .filter(|drift| !drift.missing.is_empty())
+            .map(|drift| GlobDrift {
+                stale: Vec::new(),
+                ..drift
+            })
+            .collect();
+        fix_drift(Path::new("."), &drift)?;
+        for source_drift in &drift {
+            println!(
+                "Added {} to {}",
+                source_drift.missing.join(", "),
+                source_drift.source
+            );
+        }
+        if restored.is_crate() {
+            add_crate_build_dependency_to_shared_wasm(&restored.name)?;
+        }
+        for path in sync_aliases()? {
+            println!("Updated aliases in {}", path.display());
+        }
+        for change in sync_cargo_workspace()? {
+            println!("Updated {}: {}", CARGO_WORKSPACE_FILE, change);
+        }
+
+        self.ui
+            .render_success(&format!("Unarchived '{}'", restored.name))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+
+    /// Snapshot both locations of the project and every file the move edits, returning its id
+    fn snapshot(&self, operation: &str, from: &Path, to: &Path) -> Result<String> {
+        let mut paths: Vec<PathBuf> = vec![from.to_path_buf(), to.to_path_buf()];
+        paths.extend(
+            [
+                PNPM_WORKSPACE_FILE,
+                GlobSource::PackageJson.file(),
+                MOON_WORKSPACE_FILE,
+                SHARED_WASM_CONFIG,
+            ]
+            .iter()
+            .map(PathBuf::from)
+            .filter(|path| path.exists()),
+        );
+        let paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+        Ok(create_snapshot(operation, &paths)?.id)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::ui::MoonflareUI;
+use crate::utils::backup::create_snapshot;
+use crate::utils::binding_audit::{BindingAudit, audit, remove_binding};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{discover_projects, find_project, project_not_found};
+use crate::utils::wrangler::{find_wrangler_config, parse_wrangler_file, write_wrangler_config};
+use anyhow::{Result, bail};
+use colored::*;
+use std::path::{Path, PathBuf};
+
+pub struct AuditBindingsCommand {
+    ui: MoonflareUI,
+}
+
+impl AuditBindingsCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Report bindings no source uses and `env` accesses with nothing declared behind them,
+    /// removing the unused declarations with `fix`
+    pub async fn execute(&self, project: Option<&str>, fix: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects = match project {
+            Some(name) => vec![find_project(name).ok_or_else(|| project_not_found(name))?],
+            None => discover_projects(),
+        };
+
+        let mut audits = Vec::new();
+        for project in projects.iter().filter(|p| p.has_wrangler_config()) {
+            let findings = audit(project)?;
+            if !findings.is_empty() {
+                audits.push((project, findings));
+            }
+        }
+
+        for (project, findings) in &audits {
+            println!("{}", project.name.bold());
+            for (name, kind) in &findings.unused {
+                println!(
+                    "  {} {} ({}) is declared but never used",
+                    "unused ".yellow(),
+                    name,
+                    kind
+                );
+            }
+            for (name, file) in &findings.missing {
+                println!(
+                    "  {} env.{} is used in {} but has no binding, var or .dev.vars entry",
+                    "missing".red(),
+                    name,
+                    file.display()
+                );
+            }
+        }
+        let unused: usize = audits.iter().map(|(_, f)| f.unused.len()).sum();
+        let missing: usize = audits.iter().map(|(_, f)| f.missing.len()).sum();
+
+        let mut removed = 0;
+        let fixable: Vec<(PathBuf, &BindingAudit)> = audits
+            .iter()
+            .filter(|(_, findings)| fix && !findings.unused.is_empty())
+            .filter_map(|(project, findings)| {
+                Some((find_wrangler_config(&project.path)?, findings))
+            })
+            .collect();
+        if !fixable.is_empty() {
+            let paths: Vec<&Path> = fixable.iter().map(|(path, _)| path.as_path()).collect();
+            let snapshot = create_snapshot("audit-bindings --fix", &paths)?;
+            for (path, findings) in &fixable {
+                let mut config = parse_wrangler_file(path)?;
+                for name in findings.unused.keys() {
+                    if remove_binding(&mut config, name) {
+                        removed += 1;
+                    } else {
+                        println!(
+                            "{} comes from the workspace's Wrangler defaults; remove it there",
+                            name
+                        );
+                    }
+                }
+                write_wrangler_config(path, &config)?;
+                println!("Removed unused bindings from {}", path.display());
+            }
+            println!(
+                "Undo with 'moonflare restore {}' (comments in JSONC configurations aren't kept)",
+                snapshot.id
+            );
+        }
+
+        if unused == 0 && missing == 0 {
+            self.ui
+                .render_success("Every binding is used and every env access has a binding")
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+            return Ok(());
+        }
+        if fix && removed > 0 {
+            self.ui
+                .render_success(&format!("Removed {} unused binding(s)", removed))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        }
+
+        let remaining = missing + unused - removed;
+        if remaining > 0 {
+            let hint = if !fix && unused > 0 {
+                ". Run 'moonflare audit-bindings --fix' to remove the unused bindings."
+            } else {
+                ""
+            };
+            bail!("{} binding issue(s) found{}", remaining, hint);
+        }
+        Ok(())
+    }
+}
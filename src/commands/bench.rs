@@ -0,0 +1,251 @@
+use crate::utils::{
+    fs::is_moonflare_workspace,
+    moon::run_moon_command,
+    projects::{
+        ProjectFilter, WorkspaceProject, discover_projects, find_project, project_not_found,
+    },
+    vcs::current_commit,
+};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const BENCH_REPORT_DIR: &str = ".moonflare/bench";
+
+/// Results file written by the Worker latency harness
+const WORKER_RESULTS_FILE: &str = "bench-results.json";
+
+/// Aggregated results of a `moonflare bench` run
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub generated_at: String,
+    pub git_sha: Option<String>,
+    pub projects: BTreeMap<String, Vec<BenchResult>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub name: String,
+    pub unit: String,
+    pub mean: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p50: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p95: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p99: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkerResults {
+    benchmarks: Vec<BenchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CriterionEstimates {
+    mean: CriterionEstimate,
+    median: CriterionEstimate,
+}
+
+#[derive(Debug, Deserialize)]
+struct CriterionEstimate {
+    point_estimate: f64,
+}
+
+pub struct BenchCommand {}
+
+impl BenchCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub async fn execute(
+        &self,
+        project: Option<&str>,
+        filter: &ProjectFilter,
+        output: Option<&Path>,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects: Vec<WorkspaceProject> = match project {
+            Some(name) => {
+                let project = find_project(name).ok_or_else(|| project_not_found(name))?;
+                if !project.has_task("bench") {
+                    bail!("Project '{}' has no 'bench' task", name);
+                }
+                vec![project]
+            }
+            None => filter
+                .apply(discover_projects())
+                .into_iter()
+                .filter(|p| p.has_task("bench"))
+                .collect(),
+        };
+
+        if projects.is_empty() {
+            bail!("No projects with a 'bench' task found");
+        }
+
+        println!(
+            "{}",
+            format!("Running benchmarks for {} project(s)...", projects.len())
+                .cyan()
+                .bold()
+        );
+
+        let targets: Vec<String> = projects
+            .iter()
+            .map(|p| format!("{}:bench", p.name))
+            .collect();
+        let mut args = vec!["run"];
+        args.extend(targets.iter().map(|t| t.as_str()));
+        run_moon_command(&args).await?;
+
+        let report = BenchReport {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            git_sha: current_commit(),
+            projects: projects
+                .iter()
+                .map(|p| Ok((p.name.clone(), collect_results(p)?)))
+                .collect::<Result<_>>()?,
+        };
+
+        let previous = latest_report()?;
+        print_report(&report, previous.as_ref());
+
+        let path = write_report(&report, output)?;
+        println!();
+        println!("Report written to {}", path.display());
+
+        Ok(())
+    }
+}
+
+fn collect_results(project: &WorkspaceProject) -> Result<Vec<BenchResult>> {
+    let worker_results = project.path.join(WORKER_RESULTS_FILE);
+    if worker_results.exists() {
+        let content = fs::read_to_string(&worker_results)?;
+        let results: WorkerResults = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid {}", worker_results.display()))?;
+        return Ok(results.benchmarks);
+    }
+
+    // Criterion writes one estimates.json per benchmark under target/criterion/<name>/new
+    let criterion_dir = project.path.join("target").join("criterion");
+    let mut results = Vec::new();
+    for entry in WalkDir::new(&criterion_dir).into_iter().flatten() {
+        let path = entry.path();
+        let is_new_estimate = path.file_name().is_some_and(|n| n == "estimates.json")
+            && path
+                .parent()
+                .and_then(|p| p.file_name())
+                .is_some_and(|n| n == "new");
+        if !is_new_estimate {
+            continue;
+        }
+
+        let name = path
+            .parent()
+            .and_then(|p| p.parent())
+            .and_then(|p| p.strip_prefix(&criterion_dir).ok())
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        let estimates: CriterionEstimates = serde_json::from_str(&fs::read_to_string(path)?)
+            .with_context(|| format!("Invalid {}", path.display()))?;
+
+        results.push(BenchResult {
+            name,
+            unit: "ns".to_string(),
+            mean: estimates.mean.point_estimate,
+            p50: Some(estimates.median.point_estimate),
+            p95: None,
+            p99: None,
+        });
+    }
+
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(results)
+}
+
+/// Most recent report in `.moonflare/bench`, used to show changes between runs
+fn latest_report() -> Result<Option<BenchReport>> {
+    let dir = Path::new(BENCH_REPORT_DIR);
+    if !dir.exists() {
+        return Ok(None);
+    }
+
+    let mut reports: Vec<PathBuf> = fs::read_dir(dir)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    reports.sort();
+
+    match reports.last() {
+        Some(path) => Ok(serde_json::from_str(&fs::read_to_string(path)?).ok()),
+        None => Ok(None),
+    }
+}
+
+fn write_report(report: &BenchReport, output: Option<&Path>) -> Result<PathBuf> {
+    let path = match output {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+            Path::new(BENCH_REPORT_DIR).join(format!("{}.json", timestamp))
+        }
+    };
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(report)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(path)
+}
+
+fn print_report(report: &BenchReport, previous: Option<&BenchReport>) {
+    for (project, results) in &report.projects {
+        println!();
+        println!("{}", project.bold());
+
+        if results.is_empty() {
+            println!("  (no results found)");
+            continue;
+        }
+
+        for result in results {
+            let change = previous
+                .and_then(|p| p.projects.get(project))
+                .and_then(|results| results.iter().find(|r| r.name == result.name))
+                .filter(|r| r.unit == result.unit && r.mean > 0.0)
+                .map(|r| format_change((result.mean - r.mean) / r.mean * 100.0))
+                .unwrap_or_default();
+
+            println!(
+                "  {:<32} {:>12.2} {}{}",
+                result.name, result.mean, result.unit, change
+            );
+        }
+    }
+}
+
+fn format_change(percent: f64) -> String {
+    let text = format!("  ({:+.1}%)", percent);
+    if percent > 5.0 {
+        text.red().to_string()
+    } else if percent < -5.0 {
+        text.green().to_string()
+    } else {
+        text
+    }
+}
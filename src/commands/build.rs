@@ -1,11 +1,24 @@
+use crate::commands::clean::prune_after_build;
 use crate::errors::MoonflareError;
 use crate::utils::{
+    artifacts::{record_artifacts, source_hash},
+    build_log::record_build,
+    cache_headers::write_cache_headers,
+    config::MoonflareConfig,
+    explain::{Explanation, Step},
     fs::is_moonflare_workspace,
-    moon::{run_moon_command_with_error, validate_project_exists},
+    licenses::write_notices,
+    moon::{run_moon_command_with_error, validate_project_exists, validate_task_exists},
+    node::check_project_node_version,
+    projects::{ProjectFilter, WorkspaceProject, discover_projects, filtered_targets},
+    reporter::{TaskLog, run_targets_individually},
+    task_env, wrangler_defaults,
+    wrangler_schema::check_wrangler_configs,
 };
 use colored::*;
 use miette::{IntoDiagnostic, Result};
 use std::env;
+use std::time::{Instant, SystemTime};
 
 pub struct BuildCommand {}
 
@@ -14,41 +27,46 @@ impl BuildCommand {
         Self {}
     }
 
-    pub async fn execute(&self, project: Option<&str>) -> Result<()> {
+    pub async fn execute(
+        &self,
+        project: Option<&str>,
+        filter: &ProjectFilter,
+        task: &str,
+        environment: Option<&str>,
+        explain: bool,
+        tasks: &TaskLog,
+    ) -> Result<()> {
         if !is_moonflare_workspace() {
             let current_dir = env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
             return Err(MoonflareError::not_in_workspace(current_dir, vec![])).into_diagnostic();
         }
+        if environment.is_some() {
+            task_env::set_environment(environment);
+        }
 
-        match project {
-            Some(proj) => {
-                println!(
-                    "{}",
-                    format!("Building project '{}'...", proj).cyan().bold()
-                );
+        check_project_node_version(project).map_err(|e| miette::miette!("{}", e))?;
+        check_wrangler_configs(project, filter).map_err(|e| *e)?;
+
+        if task != "build" {
+            validate_task_exists(project, task)
+                .await
+                .map_err(|e| miette::miette!("{}", e))?;
+        }
 
+        let (heading, args, targets) = match project {
+            Some(proj) => {
                 // Check if the project exists by querying Moon for available projects
                 match validate_project_exists(proj).await {
                     Ok(Some(available_projects)) => {
-                        // Project doesn't exist, show helpful error with available projects
+                        // Project doesn't exist, suggest the closest ones
                         let current_dir =
                             env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-
-                        let available_list = if available_projects.is_empty() {
-                            "No projects found in this workspace. Add projects with 'moonflare add <type> <name>'.".to_string()
-                        } else {
-                            format!(
-                                "Available projects:\n  • {}",
-                                available_projects.join("\n  • ")
-                            )
-                        };
-
                         return Err(MoonflareError::project_not_found(
                             proj,
                             current_dir,
-                            Some(available_list),
-                        ))
-                        .into_diagnostic();
+                            &available_projects,
+                        )
+                        .into());
                     }
                     Ok(None) => {
                         // Project exists, proceed with build
@@ -59,24 +77,196 @@ impl BuildCommand {
                     }
                 }
 
-                // Run the actual build command with structured error handling
-                if let Err(moon_error) =
-                    run_moon_command_with_error(&["run", &format!("{}:build", proj)]).await
-                {
-                    return Err(moon_error).into_diagnostic();
-                }
+                let target = format!("{}:{}", proj, task);
+                (
+                    format!("Building project '{}'{}...", proj, for_environment()),
+                    vec!["run".to_string(), target.clone()],
+                    vec![target],
+                )
             }
-            None => {
-                println!("{}", "Building all projects...".cyan().bold());
-
-                // Run build all with structured error handling
-                if let Err(moon_error) = run_moon_command_with_error(&[":build"]).await {
-                    return Err(moon_error).into_diagnostic();
-                }
+            None if !filter.is_empty() => {
+                let targets =
+                    filtered_targets(filter, task).map_err(|e| miette::miette!("{}", e))?;
+                let mut args = vec!["run".to_string()];
+                args.extend(targets.iter().cloned());
+                (
+                    format!(
+                        "Building {} project(s) ({}){}...",
+                        targets.len(),
+                        filter.describe(),
+                        for_environment()
+                    ),
+                    args,
+                    targets,
+                )
             }
+            None => (
+                format!("Building all projects{}...", for_environment()),
+                vec![format!(":{}", task)],
+                filtered_targets(&ProjectFilter::default(), task).unwrap_or_default(),
+            ),
+        };
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        if explain {
+            return explain_build(&args, &targets, tasks);
         }
 
+        println!("{}", heading.cyan().bold());
+        // Run the actual build command with structured error handling
+        run_build(&args, &targets, tasks).await?;
+
         println!("{}", "Build completed successfully!".green().bold());
+
+        prune_after_build().map_err(|e| miette::miette!("Post-build pruning failed: {}", e))?;
+
         Ok(())
     }
 }
+
+/// " for <env>" when the build uses an environment's vars
+fn for_environment() -> String {
+    task_env::environment()
+        .map(|environment| format!(" for {}", environment))
+        .unwrap_or_default()
+}
+
+/// Run the build through Moon, recording an outcome per target for reporters and a summary
+/// in the build log for `moonflare stats`
+async fn run_build(args: &[&str], targets: &[String], tasks: &TaskLog) -> Result<()> {
+    bundle_licenses(targets, true).map_err(|e| miette::miette!("License bundle failed: {}", e))?;
+    let sources = artifact_sources(targets);
+    let _effective = wrangler_defaults::materialize(&discover_projects())
+        .map_err(|e| miette::miette!("Merging the Wrangler defaults failed: {}", e))?;
+
+    let started_at = SystemTime::now();
+    let started = Instant::now();
+    let result = if runs_individually(targets, tasks) {
+        run_targets_individually(targets, tasks)
+            .await
+            .map_err(|e| miette::miette!("{}", e))
+    } else {
+        let result = run_moon_command_with_error(args).await;
+        let message = result.as_ref().err().map(|e| e.to_string());
+        for target in targets {
+            tasks.record(target, result.is_ok(), started.elapsed(), message.clone());
+        }
+        result.into_diagnostic()
+    };
+
+    record_build(started_at, started.elapsed(), result.is_ok(), targets);
+    if result.is_ok() {
+        bundle_licenses(targets, false)
+            .map_err(|e| miette::miette!("License bundle failed: {}", e))?;
+        cache_assets(targets)
+            .map_err(|e| miette::miette!("Writing cache headers failed: {}", e))?;
+        if let Err(e) = record_artifacts(&sources, task_env::environment().as_deref()) {
+            println!(
+                "{}",
+                format!("Warning: build manifest not updated: {}", e).yellow()
+            );
+        }
+    }
+    result
+}
+
+/// Whether each target needs a Moon invocation of its own, for a granular report or its own
+/// variables
+fn runs_individually(targets: &[String], tasks: &TaskLog) -> bool {
+    tasks.is_granular() || task_env::needs_separate_runs(targets)
+}
+
+/// Show the Moon invocations [`run_build`] would make, and what it does around them
+fn explain_build(args: &[&str], targets: &[String], tasks: &TaskLog) -> Result<()> {
+    let config = MoonflareConfig::load().map_err(|e| miette::miette!("{}", e))?;
+    let defaults = wrangler_defaults::find_defaults();
+    let step = |args: &[&str]| {
+        let step = Step::moon(args);
+        match &defaults {
+            Some(_) => step.env(
+                task_env::WRANGLER_CONFIG_VAR,
+                wrangler_defaults::EFFECTIVE_CONFIG_FILE,
+            ),
+            None => step,
+        }
+    };
+
+    let mut explanation = Explanation::new();
+    if runs_individually(targets, tasks) {
+        for target in targets {
+            explanation.step(step(&["run", target]));
+        }
+    } else {
+        explanation.step(step(args));
+    }
+    if let Some(defaults) = &defaults {
+        explanation.note(format!(
+            "Merges {} into each Worker's Wrangler config as {} for the build",
+            defaults.display(),
+            wrangler_defaults::EFFECTIVE_CONFIG_FILE
+        ));
+    }
+    if config.licenses.bundle {
+        explanation.note("Writes license notices for Workers before the build and for frontends after it ([licenses] bundle)");
+    }
+    if config.assets.hashing {
+        explanation
+            .note("Writes cache headers for the frontends' hashed assets ([assets] hashing)");
+    }
+    explanation.note("Records the build for 'moonflare stats' and the build manifest 'deploy --from-artifacts' checks");
+    explanation.render();
+    Ok(())
+}
+
+/// Give the frontends behind `targets` cache headers for their hashed assets when
+/// `[assets] hashing` is enabled
+fn cache_assets(targets: &[String]) -> anyhow::Result<()> {
+    let config = MoonflareConfig::load()?.assets;
+    if !config.hashing {
+        return Ok(());
+    }
+
+    for project in discover_projects().into_iter().filter(|p| {
+        !p.is_worker()
+            && !p.is_crate()
+            && targets
+                .iter()
+                .any(|target| target.split(':').next() == Some(p.name.as_str()))
+    }) {
+        for path in write_cache_headers(&project, &config)? {
+            println!("{}", format!("Wrote {}", path.display()).dimmed());
+        }
+    }
+    Ok(())
+}
+
+/// Deployable projects whose `build` task is among `targets`, with the hash of the sources
+/// they're about to be built from, for the build manifest `deploy --from-artifacts` checks
+fn artifact_sources(targets: &[String]) -> Vec<(WorkspaceProject, String)> {
+    discover_projects()
+        .into_iter()
+        .filter(|p| p.has_wrangler_config() && targets.contains(&format!("{}:build", p.name)))
+        .filter_map(|p| source_hash(&p).ok().map(|hash| (p, hash)))
+        .collect()
+}
+
+/// Refresh the license notices of the projects behind `targets` when `[licenses] bundle` is
+/// enabled: worker modules before the build bundles them, frontend notices after it
+fn bundle_licenses(targets: &[String], workers: bool) -> anyhow::Result<()> {
+    if !MoonflareConfig::load()?.licenses.bundle {
+        return Ok(());
+    }
+
+    let built: Vec<WorkspaceProject> = discover_projects()
+        .into_iter()
+        .filter(|p| {
+            targets
+                .iter()
+                .any(|target| target.split(':').next() == Some(p.name.as_str()))
+        })
+        .collect();
+    for path in write_notices(&built, workers)? {
+        println!("{}", format!("Wrote {}", path.display()).dimmed());
+    }
+    Ok(())
+}
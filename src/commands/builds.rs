@@ -0,0 +1,175 @@
+use crate::ui::MoonflareUI;
+use crate::utils::builds::{origin_repository, resolve_repository, trigger_settings};
+use crate::utils::cloudflare::{BUILDS_PERMISSION, CloudflareClient};
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::manifest::{BuildTriggerRecord, DEFAULT_ENVIRONMENT, DeploymentManifest};
+use crate::utils::projects::{
+    WorkspaceProject, discover_projects, find_project, project_not_found,
+};
+use crate::utils::vcs::current_branch;
+use crate::utils::wrangler::{env_worker_name, read_wrangler_config};
+use anyhow::{Result, bail};
+use colored::*;
+
+pub struct BuildsCommand {
+    ui: MoonflareUI,
+}
+
+impl BuildsCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Connect the workspace's repository to Workers Builds and create (or update) a trigger
+    /// per deployable project, so `deploy --build-remote` can build in Cloudflare
+    pub async fn execute_setup(
+        &self,
+        project: Option<&str>,
+        env: Option<&str>,
+        branch: Option<&str>,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects: Vec<WorkspaceProject> = match project {
+            Some(name) => {
+                let project = find_project(name).ok_or_else(|| project_not_found(name))?;
+                if !project.has_wrangler_config() {
+                    bail!("Project '{}' has no Wrangler configuration", name);
+                }
+                vec![project]
+            }
+            None => discover_projects()
+                .into_iter()
+                .filter(|p| p.has_wrangler_config())
+                .collect(),
+        };
+        if projects.is_empty() {
+            bail!("No deployable projects to build remotely");
+        }
+
+        let (provider, path) = origin_repository()?;
+        let branch = branch
+            .map(str::to_string)
+            .or_else(current_branch)
+            .unwrap_or_else(|| "main".to_string());
+
+        self.ui
+            .render_header(
+                "Setting up Workers Builds",
+                Some(&format!(
+                    "Building {} project(s) from {} ({}) on pushes to {}",
+                    projects.len(),
+                    path,
+                    provider,
+                    branch
+                )),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let repository = resolve_repository(&provider, &path).await?;
+        let crates_dir = MoonflareConfig::load()?.layout.crates;
+        let mut manifest = DeploymentManifest::load()?;
+
+        for project in &projects {
+            let Some(config) = read_wrangler_config(&project.path)? else {
+                continue;
+            };
+            let worker = env_worker_name(&config, &project.name, env);
+            let client =
+                CloudflareClient::from_env(config["account_id"].as_str(), BUILDS_PERMISSION)?;
+
+            let Some(script) = client.worker_script(&worker).await? else {
+                bail!(
+                    "Worker '{}' doesn't exist yet; deploy it once with 'moonflare deploy {}' first",
+                    worker,
+                    project.name
+                );
+            };
+            let connection = client.upsert_repo_connection(&repository).await?;
+            let settings = trigger_settings(project, env, &branch, &crates_dir);
+
+            let existing = manifest
+                .build_trigger(env, &project.name)
+                .filter(|record| record.account_id == client.account_id)
+                .map(|record| record.trigger_uuid.clone());
+            let trigger = match existing {
+                Some(trigger_uuid) => {
+                    let trigger = client
+                        .update_build_trigger(&trigger_uuid, &settings)
+                        .await?;
+                    println!(
+                        "{} {} (updated trigger {})",
+                        "✓".green(),
+                        project.name,
+                        trigger.trigger_uuid
+                    );
+                    trigger
+                }
+                None => {
+                    let trigger = client
+                        .create_build_trigger(
+                            &script.tag,
+                            &connection.repo_connection_uuid,
+                            &settings,
+                        )
+                        .await?;
+                    println!(
+                        "{} {} (trigger {})",
+                        "✓".green(),
+                        project.name,
+                        trigger.trigger_uuid
+                    );
+                    trigger
+                }
+            };
+            println!(
+                "  {}",
+                format!(
+                    "Build: {}",
+                    settings["build_command"].as_str().unwrap_or_default()
+                )
+                .dimmed()
+            );
+            println!(
+                "  {}",
+                format!(
+                    "Deploy: {}",
+                    settings["deploy_command"].as_str().unwrap_or_default()
+                )
+                .dimmed()
+            );
+
+            manifest
+                .builds
+                .entry(env.unwrap_or(DEFAULT_ENVIRONMENT).to_string())
+                .or_default()
+                .insert(
+                    project.name.clone(),
+                    BuildTriggerRecord {
+                        account_id: client.account_id.clone(),
+                        trigger_uuid: trigger.trigger_uuid,
+                        worker,
+                        repository: path.clone(),
+                        branch: branch.clone(),
+                        created_at: chrono::Local::now().to_rfc3339(),
+                    },
+                );
+            manifest.save()?;
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Workers Builds set up; pushes to {} build in Cloudflare, and 'moonflare deploy --build-remote{}' starts a build on demand",
+                branch,
+                env.map(|env| format!(" --env {}", env)).unwrap_or_default()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
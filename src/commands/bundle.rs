@@ -0,0 +1,96 @@
+use crate::ui::MoonflareUI;
+use crate::utils::bundle::{self, BundleReport};
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{discover_projects, find_project, project_not_found};
+use anyhow::{Result, bail};
+
+pub struct BundleCommand {
+    ui: MoonflareUI,
+}
+
+impl BundleCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Bundle each Worker without deploying it and list what takes up its size, failing when
+    /// one would be refused at deploy
+    pub async fn execute_analyze(
+        &self,
+        project: Option<&str>,
+        env: Option<&str>,
+        top: usize,
+        json: bool,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let config = MoonflareConfig::load()?;
+        let projects = match project {
+            Some(name) => {
+                let project = find_project(name).ok_or_else(|| project_not_found(name))?;
+                if !bundle::is_bundled(&project) {
+                    bail!(
+                        "'{}' has no Worker entry point ('main' in its Wrangler configuration) to bundle",
+                        name
+                    );
+                }
+                vec![project]
+            }
+            None => discover_projects()
+                .into_iter()
+                .filter(bundle::is_bundled)
+                .collect(),
+        };
+
+        if !json {
+            self.ui
+                .render_header(
+                    "Bundle Analysis",
+                    Some("What goes into each Worker's bundle, largest first"),
+                )
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        }
+        let mut reports: Vec<BundleReport> = Vec::new();
+        for project in &projects {
+            let report = bundle::analyze(project, env, &config)?;
+            if !json {
+                print!("{}", bundle::render(&report, top));
+                println!();
+            }
+            reports.push(report);
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&reports)?);
+        }
+        let failing: Vec<&str> = reports
+            .iter()
+            .filter(|report| !report.problems().is_empty())
+            .map(|report| report.project.as_str())
+            .collect();
+        if !failing.is_empty() {
+            bail!(
+                "{} Worker(s) would be refused at deploy: {}",
+                failing.len(),
+                failing.join(", ")
+            );
+        }
+        if json {
+            return Ok(());
+        }
+        if reports.is_empty() {
+            return self
+                .ui
+                .render_success("No Workers to bundle")
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e));
+        }
+        self.ui
+            .render_success(&format!("Analyzed {} Worker bundle(s)", reports.len()))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))
+    }
+}
@@ -0,0 +1,139 @@
+use crate::templates::{embedded, engine::TemplateEngine};
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{discover_projects, project_directories};
+use anyhow::{Result, bail};
+use colored::*;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Workspace files outside project directories that should trigger the generated workflows
+const WORKSPACE_WATCHED_PATHS: [&str; 4] = [
+    ".moon/**",
+    "moonflare.toml",
+    "package.json",
+    "pnpm-lock.yaml",
+];
+
+pub struct CiCommand {
+    template_engine: TemplateEngine,
+    ui: MoonflareUI,
+}
+
+impl CiCommand {
+    pub fn new() -> Self {
+        Self {
+            template_engine: TemplateEngine::new(),
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Write GitHub Actions workflows generated from the workspace's current project layout
+    pub async fn execute_generate(&self, with_ai_review: bool, force: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let mut templates = vec![embedded::CI_TEMPLATE];
+        if with_ai_review {
+            templates.push(embedded::AI_REVIEW_TEMPLATE);
+        }
+
+        let files: Vec<String> = templates
+            .iter()
+            .flat_map(|t| TemplateEngine::template_file_paths(t))
+            .collect();
+        let existing: Vec<&String> = files.iter().filter(|f| Path::new(f).exists()).collect();
+        if !existing.is_empty() && !force {
+            bail!(
+                "Refusing to overwrite {} (use --force to regenerate)",
+                existing
+                    .iter()
+                    .map(|f| f.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        let context = workflow_context()?;
+        for template in templates {
+            self.template_engine
+                .process_template_files(template, Path::new("."), &context)?;
+        }
+
+        for file in &files {
+            println!("  {}", file);
+        }
+
+        if with_ai_review {
+            println!();
+            println!(
+                "{}",
+                "Add these repository secrets before the review runs:".yellow()
+            );
+            println!("  ANTHROPIC_API_KEY   gh secret set ANTHROPIC_API_KEY");
+        }
+
+        self.ui
+            .render_success("Generated GitHub Actions workflows")
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
+
+/// Template context describing the repository structure the workflows target
+fn workflow_context() -> Result<HashMap<String, Value>> {
+    let config = MoonflareConfig::load()?;
+    let name = config.workspace.name.clone().unwrap_or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "moonflare".to_string())
+    });
+
+    let projects = discover_projects();
+    let directories = project_directories();
+    let kinds: HashMap<&str, &str> = directories
+        .iter()
+        .map(|(directory, kind)| (directory.as_str(), *kind))
+        .collect();
+
+    let mut watched_paths: Vec<String> = directories
+        .iter()
+        .map(|(directory, _)| directory)
+        .filter(|directory| projects.iter().any(|p| p.path.starts_with(directory)))
+        .map(|directory| format!("{}/**", directory))
+        .collect();
+    watched_paths.extend(WORKSPACE_WATCHED_PATHS.iter().map(|p| p.to_string()));
+
+    let project_entries: Vec<Value> = projects
+        .iter()
+        .map(|p| {
+            let directory = p.relative_path();
+            let directory = directory.split('/').next().unwrap_or_default();
+            json!({
+                "name": p.name,
+                "path": p.relative_path(),
+                "kind": kinds.get(directory).copied().unwrap_or("project"),
+            })
+        })
+        .collect();
+
+    let mut context = HashMap::new();
+    context.insert("name".to_string(), Value::String(name));
+    context.insert(
+        "moonflare_version".to_string(),
+        Value::String(env!("CARGO_PKG_VERSION").to_string()),
+    );
+    context.insert("watched_paths".to_string(), json!(watched_paths));
+    context.insert("projects".to_string(), Value::Array(project_entries));
+    context.insert(
+        "has_crates".to_string(),
+        Value::Bool(projects.iter().any(|p| p.is_crate())),
+    );
+
+    Ok(context)
+}
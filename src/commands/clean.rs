@@ -0,0 +1,139 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::{get_typescript_projects, is_moonflare_workspace};
+use crate::utils::retention::{
+    PruneCandidate, disk_usage, format_bytes, prune_candidates, remove_candidates,
+};
+use anyhow::{Result, bail};
+use colored::*;
+use std::fs;
+use std::path::Path;
+
+pub struct CleanCommand {
+    ui: MoonflareUI,
+}
+
+impl CleanCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Remove build outputs, or with `auto` only what the retention policy says is expired
+    pub async fn execute(&self, auto: bool, dry_run: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let candidates = if auto {
+            let config = MoonflareConfig::load()?;
+            prune_candidates(&config.retention)?
+        } else {
+            build_outputs()
+        };
+
+        if candidates.is_empty() {
+            println!("Nothing to clean");
+            return Ok(());
+        }
+
+        print_candidates(&candidates);
+        let total: u64 = candidates.iter().map(|c| c.bytes).sum();
+
+        if dry_run {
+            println!();
+            println!(
+                "{}",
+                format!(
+                    "Dry run: {} item(s), {} would be removed",
+                    candidates.len(),
+                    format_bytes(total)
+                )
+                .yellow()
+            );
+            return Ok(());
+        }
+
+        remove_candidates(&candidates)?;
+
+        self.ui
+            .render_success(&format!(
+                "Removed {} item(s), freed {}",
+                candidates.len(),
+                format_bytes(total)
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
+
+/// Apply the retention policy after a build when `prune_after_build` is enabled
+pub fn prune_after_build() -> Result<()> {
+    let config = MoonflareConfig::load()?;
+    if !config.retention.prune_after_build {
+        return Ok(());
+    }
+
+    let candidates = prune_candidates(&config.retention)?;
+    if candidates.is_empty() {
+        return Ok(());
+    }
+
+    let total: u64 = candidates.iter().map(|c| c.bytes).sum();
+    remove_candidates(&candidates)?;
+    println!(
+        "{}",
+        format!(
+            "Pruned {} expired artifact(s), freed {}",
+            candidates.len(),
+            format_bytes(total)
+        )
+        .blue()
+    );
+
+    Ok(())
+}
+
+/// Every project's dist/ plus gathered WASM in shared-wasm/
+fn build_outputs() -> Vec<PruneCandidate> {
+    let mut candidates: Vec<PruneCandidate> = get_typescript_projects()
+        .into_iter()
+        .map(|project| project.join("dist"))
+        .filter(|dist| dist.is_dir())
+        .map(|dist| PruneCandidate {
+            bytes: disk_usage(&dist),
+            path: dist,
+            reason: "build output".to_string(),
+        })
+        .collect();
+
+    if let Ok(entries) = fs::read_dir(Path::new("shared-wasm")) {
+        let mut wasm: Vec<_> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "wasm"))
+            .collect();
+        wasm.sort();
+        candidates.extend(wasm.into_iter().map(|path| PruneCandidate {
+            bytes: disk_usage(&path),
+            path,
+            reason: "gathered WASM".to_string(),
+        }));
+    }
+
+    candidates.sort_by(|a, b| a.path.cmp(&b.path));
+    candidates
+}
+
+fn print_candidates(candidates: &[PruneCandidate]) {
+    for candidate in candidates {
+        println!(
+            "  {:<48} {:>10}  {}",
+            candidate.path.display(),
+            format_bytes(candidate.bytes),
+            candidate.reason.dimmed()
+        );
+    }
+}
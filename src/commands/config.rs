@@ -0,0 +1,262 @@
+use crate::ui::MoonflareUI;
+use crate::utils::backup::create_snapshot;
+use crate::utils::config::config_path;
+use crate::utils::config_lint::{ConfigLint, fix_projects, lint_projects};
+use crate::utils::config_schema::{self, CONFIG_VERSION};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{discover_projects, find_project, project_not_found};
+use crate::utils::wrangler::read_wrangler_config;
+use crate::utils::wrangler_defaults::{effective_config, find_defaults, inherited_keys};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::fs;
+use std::path::Path;
+
+pub struct ConfigCommand {
+    ui: MoonflareUI,
+}
+
+impl ConfigCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Print the Wrangler configuration a build or deploy of `project` uses: its own with the
+    /// workspace's Wrangler defaults underneath
+    pub async fn execute_effective(&self, project: &str, json: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let info = find_project(project).ok_or_else(|| project_not_found(project))?;
+        let (Some(own), Some(effective)) = (
+            read_wrangler_config(&info.path)?,
+            effective_config(&info.path)?,
+        ) else {
+            bail!("Project '{}' has no Wrangler configuration", project);
+        };
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&effective)?);
+            return Ok(());
+        }
+
+        self.ui
+            .render_header(
+                &format!("Effective Wrangler configuration: {}", project),
+                Some("The project's configuration merged over the workspace defaults"),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!("{}", serde_json::to_string_pretty(&effective)?);
+        println!();
+
+        match find_defaults() {
+            Some(defaults) => {
+                let inherited = inherited_keys(&own, &effective);
+                if inherited.is_empty() {
+                    println!(
+                        "The project sets everything in {} itself",
+                        defaults.display()
+                    );
+                } else {
+                    println!("From {}:", defaults.display());
+                    for key in inherited {
+                        println!("  {}", key.cyan());
+                    }
+                }
+            }
+            None => println!(
+                "{}",
+                "No wrangler-defaults.jsonc in the workspace; this is the project's own configuration"
+                    .dimmed()
+            ),
+        }
+        Ok(())
+    }
+
+    /// Report deprecated keys in the projects' Wrangler configuration, rewriting them to their
+    /// modern equivalents with `fix`
+    pub async fn execute_lint(&self, project: Option<&str>, fix: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects = match project {
+            Some(name) => vec![find_project(name).ok_or_else(|| project_not_found(name))?],
+            None => discover_projects(),
+        };
+
+        self.ui
+            .render_header(
+                "Config lint",
+                Some("Checking Wrangler configuration for deprecated keys"),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let lints = lint_projects(&projects)?;
+        if lints.is_empty() {
+            self.ui
+                .render_success("No deprecated Wrangler configuration keys")
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+            return Ok(());
+        }
+        render_lints(&lints, fix);
+
+        let manual = lints
+            .iter()
+            .flat_map(|lint| &lint.deprecations)
+            .filter(|d| !d.fixable)
+            .count();
+        let fixable = lints
+            .iter()
+            .flat_map(|lint| &lint.deprecations)
+            .filter(|d| d.fixable)
+            .count();
+
+        if !fix {
+            bail!(
+                "{} deprecated key(s) found{}",
+                fixable + manual,
+                if fixable > 0 {
+                    "; run 'moonflare config lint --fix' to upgrade them"
+                } else {
+                    ""
+                }
+            );
+        }
+
+        if let Some(snapshot) = fix_projects(&lints)? {
+            println!("Saved snapshot {}", snapshot.id);
+            if lints
+                .iter()
+                .any(|lint| lint.path.extension().is_some_and(|ext| ext == "jsonc"))
+            {
+                println!(
+                    "{}",
+                    format!(
+                        "Comments in rewritten .jsonc files were not preserved; 'moonflare restore {}' brings them back.",
+                        snapshot.id
+                    )
+                    .yellow()
+                );
+            }
+        }
+        if manual > 0 {
+            bail!(
+                "Upgraded {} key(s); {} need changing by hand",
+                fixable,
+                manual
+            );
+        }
+
+        self.ui
+            .render_success(&format!("Upgraded {} deprecated key(s)", fixable))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Print the JSON Schema of moonflare.toml, or write it to `output`
+    pub async fn execute_schema(&self, output: Option<&Path>) -> Result<()> {
+        let schema = serde_json::to_string_pretty(&config_schema::schema())? + "\n";
+        let Some(output) = output else {
+            print!("{}", schema);
+            return Ok(());
+        };
+        fs::write(output, schema)
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        self.ui
+            .render_success(&format!("Wrote {}", output.display()))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!(
+            "{}",
+            format!(
+                "Point Taplo or Even Better TOML at it with '#:schema ./{}' as the first line of moonflare.toml",
+                output.display()
+            )
+            .dimmed()
+        );
+        Ok(())
+    }
+
+    /// Upgrade moonflare.toml to the current config version. Only the version line changes
+    /// when no keys move, so comments survive; otherwise the file is rewritten after a snapshot.
+    pub async fn execute_migrate(&self) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let path = config_path();
+        if !path.exists() {
+            bail!(
+                "{} doesn't exist; there's nothing to migrate",
+                path.display()
+            );
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut config: toml::Value =
+            toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+        let from = config_schema::version(&config)?;
+        if from == CONFIG_VERSION {
+            self.ui
+                .render_success(&format!(
+                    "{} is already at config version {}",
+                    path.display(),
+                    CONFIG_VERSION
+                ))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+            return Ok(());
+        }
+
+        let changes = config_schema::migrate(&mut config)?;
+        if changes.is_empty() {
+            fs::write(&path, config_schema::set_version(&content))?;
+        } else {
+            let snapshot = create_snapshot("config migrate", &[path.as_path()])?;
+            fs::write(&path, toml::to_string_pretty(&config)?)?;
+            for change in &changes {
+                println!("  {} {}", "✓".green(), change);
+            }
+            println!(
+                "{}",
+                format!(
+                    "Comments were not preserved; 'moonflare restore {}' brings the old file back.",
+                    snapshot.id
+                )
+                .yellow()
+            );
+        }
+        self.ui
+            .render_success(&format!(
+                "Migrated {} from config version {} to {}",
+                path.display(),
+                from,
+                CONFIG_VERSION
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let problems = config_schema::check(&config);
+        if !problems.is_empty() {
+            println!("{}", "Keys to fix by hand:".yellow());
+            for problem in problems {
+                println!("  {} {}", "⚠".yellow(), problem);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn render_lints(lints: &[ConfigLint], fix: bool) {
+    for lint in lints {
+        println!("  {} {}", lint.project.cyan(), lint.path.display());
+        for deprecation in &lint.deprecations {
+            let marker = match (deprecation.fixable, fix) {
+                (true, true) => "✓".green(),
+                (true, false) => "⚠".yellow(),
+                (false, _) => "✗".red(),
+            };
+            println!("    {} {}", marker, deprecation.describe());
+        }
+    }
+}
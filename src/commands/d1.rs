@@ -0,0 +1,236 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::d1::{
+    self, Database, branch_database_name, databases, pull_request_closed, pull_request_number,
+};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{
+    WorkspaceProject, discover_projects, find_project, project_not_found,
+};
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Result, bail};
+use colored::*;
+use std::collections::BTreeMap;
+
+pub struct D1Command {
+    ui: MoonflareUI,
+}
+
+impl D1Command {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Copy an environment's D1 database into a new one and bind it in a preview environment,
+    /// so preview deploys don't write to the database they were copied from
+    pub async fn execute_branch(
+        &self,
+        project: &str,
+        name: &str,
+        from: &str,
+        env: Option<&str>,
+        binding: Option<&str>,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            bail!(
+                "Branch name '{}' can only use lowercase letters, digits and dashes",
+                name
+            );
+        }
+
+        let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+        let Some(config) = read_wrangler_config(&project_info.path)? else {
+            bail!("'{}' has no Wrangler config", project);
+        };
+
+        let workspace_config = MoonflareConfig::load()?;
+        let env = env
+            .map(str::to_string)
+            .or(workspace_config.deploy.pull_request_env)
+            .unwrap_or_else(|| "preview".to_string());
+        if env == "production" || env == from {
+            bail!(
+                "Branches are bound in a preview environment; pass --env with one other than '{}'",
+                env
+            );
+        }
+
+        let from_env = (from != "production").then_some(from);
+        let source = source_database(&databases(&config, from_env), binding, project, from)?;
+        let branch = branch_database_name(&source.name, name);
+        if d1::list(&project_info)?.contains(&branch) {
+            bail!(
+                "D1 database {} already exists; remove it with 'moonflare d1 branch cleanup --name {}'",
+                branch,
+                name
+            );
+        }
+
+        self.ui
+            .render_header(
+                "Branching D1 database",
+                Some(&format!("{} → {}", source.name, branch)),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        println!("  Exporting {} ({})", source.name, from);
+        let export = d1::export(&project_info, &source.name, from_env)?;
+        println!("  Creating {}", branch);
+        let database_id = d1::create(&project_info, &branch)?;
+        d1::bind_branch(&project_info, &env, &source, &branch, &database_id)?;
+        println!("  Importing into {}", branch);
+        let imported = d1::import(&project_info, &branch, &export, &env);
+        let _ = std::fs::remove_file(&export);
+        imported?;
+
+        self.ui
+            .render_success(&format!(
+                "Branched {} into {}, bound as {} in env.{} of '{}'",
+                source.name, branch, source.binding, env, project
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!(
+            "{}",
+            format!(
+                "Deploy with 'moonflare deploy {} --env {}'; remove it with 'moonflare d1 branch cleanup' once the pull request is merged",
+                project, env
+            )
+            .dimmed()
+        );
+        Ok(())
+    }
+
+    /// Delete branch databases whose pull request is merged or closed, or the one named, and
+    /// remove their bindings
+    pub async fn execute_cleanup(&self, project: Option<&str>, name: Option<&str>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects: Vec<WorkspaceProject> = match project {
+            Some(project) => vec![find_project(project).ok_or_else(|| project_not_found(project))?],
+            None => discover_projects()
+                .into_iter()
+                .filter(|project| project.has_wrangler_config())
+                .collect(),
+        };
+
+        // Branches are named after the database they were copied from
+        let mut sources: BTreeMap<String, &WorkspaceProject> = BTreeMap::new();
+        for project in &projects {
+            let Some(config) = read_wrangler_config(&project.path)? else {
+                continue;
+            };
+            let envs: Vec<Option<&str>> = std::iter::once(None)
+                .chain(
+                    config["env"]
+                        .as_object()
+                        .into_iter()
+                        .flat_map(|envs| envs.keys().map(|env| Some(env.as_str()))),
+                )
+                .collect();
+            for env in envs {
+                for database in databases(&config, env) {
+                    sources.entry(database.name).or_insert(project);
+                }
+            }
+        }
+        // Branches that are still bound aren't sources themselves
+        let names: Vec<String> = sources.keys().cloned().collect();
+        sources.retain(|source, _| {
+            !names
+                .iter()
+                .any(|other| source.starts_with(&format!("{}-", other)))
+        });
+        let Some(first) = sources.values().next() else {
+            bail!("No project binds a D1 database");
+        };
+
+        self.ui
+            .render_header("Cleaning up D1 branches", None)
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let mut removed = 0;
+        for database in d1::list(first)? {
+            let Some((source, project)) = sources.iter().find(|(source, _)| {
+                database.len() > source.len() + 1 && database.starts_with(&format!("{}-", source))
+            }) else {
+                continue;
+            };
+            let branch = &database[source.len() + 1..];
+            let remove = match (name, pull_request_number(&database)) {
+                (Some(name), _) => branch == name,
+                (None, Some(number)) => pull_request_closed(number)?,
+                (None, None) => false,
+            };
+            if !remove {
+                continue;
+            }
+
+            d1::delete(project, &database)?;
+            let envs = d1::unbind(project, &database)?;
+            if envs.is_empty() {
+                println!("  Deleted {}", database);
+            } else {
+                println!(
+                    "  Deleted {} and its binding in env.{} of '{}'",
+                    database,
+                    envs.join(", env."),
+                    project.name
+                );
+            }
+            removed += 1;
+        }
+
+        if removed == 0 {
+            println!("{}", "No branch databases to clean up".dimmed());
+        } else {
+            self.ui
+                .render_success(&format!("Removed {} branch database(s)", removed))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// The database to copy: the one bound as `binding`, or the environment's only one
+fn source_database(
+    databases: &[Database],
+    binding: Option<&str>,
+    project: &str,
+    from: &str,
+) -> Result<Database> {
+    match binding {
+        Some(binding) => databases
+            .iter()
+            .find(|database| database.binding == binding)
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "'{}' has no D1 binding named {} in {}",
+                    project,
+                    binding,
+                    from
+                )
+            }),
+        None => match databases {
+            [database] => Ok(database.clone()),
+            [] => bail!("'{}' binds no D1 database in {}", project, from),
+            _ => bail!(
+                "'{}' binds {} D1 databases in {}; choose one with --binding",
+                project,
+                databases.len(),
+                from
+            ),
+        },
+    }
+}
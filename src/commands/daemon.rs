@@ -0,0 +1,106 @@
+use crate::utils::daemon::{LOG_FILE, Request, SOCKET_FILE, request, serve};
+use crate::utils::fs::is_moonflare_workspace;
+use anyhow::{Result, bail};
+use colored::*;
+use std::fs::{self, File};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// How long `daemon start` waits for the background daemon to accept connections
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct DaemonCommand {}
+
+impl DaemonCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Start the workspace daemon, in the background unless `foreground` is set
+    pub async fn execute_start(&self, foreground: bool) -> Result<()> {
+        ensure_workspace()?;
+        if request(&Request::Ping).await.is_some() {
+            println!("{}", "Daemon is already running".yellow());
+            return Ok(());
+        }
+
+        if foreground {
+            println!(
+                "{} {}",
+                "Daemon listening on".green().bold(),
+                SOCKET_FILE.cyan()
+            );
+            return serve(Path::new(".")).await;
+        }
+
+        if let Some(parent) = Path::new(LOG_FILE).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let log = File::create(LOG_FILE)?;
+        let mut command = Command::new(std::env::current_exe()?);
+        command
+            .args(["daemon", "start", "--foreground"])
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log);
+        // Keep the daemon alive when the terminal that started it sends Ctrl-C
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command.spawn()?;
+
+        let deadline = std::time::Instant::now() + STARTUP_TIMEOUT;
+        while std::time::Instant::now() < deadline {
+            if request(&Request::Ping).await.is_some() {
+                println!(
+                    "{} (pid {}, log {})",
+                    "Daemon started".green().bold(),
+                    child.id(),
+                    LOG_FILE
+                );
+                return Ok(());
+            }
+            if let Some(status) = child.try_wait()? {
+                bail!("Daemon exited with {}; see {}", status, LOG_FILE);
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        bail!(
+            "Daemon did not start within {:?}; see {}",
+            STARTUP_TIMEOUT,
+            LOG_FILE
+        )
+    }
+
+    pub async fn execute_stop(&self) -> Result<()> {
+        ensure_workspace()?;
+        match request(&Request::Shutdown).await {
+            Some(_) => println!("{}", "Daemon stopped".green().bold()),
+            None => println!("No daemon is running"),
+        }
+        Ok(())
+    }
+
+    pub async fn execute_status(&self) -> Result<()> {
+        ensure_workspace()?;
+        let Some(status) = request(&Request::Status).await.and_then(|r| r.status) else {
+            println!("No daemon is running");
+            return Ok(());
+        };
+
+        println!("{}", "Daemon is running".green().bold());
+        println!("  pid:            {}", status.pid);
+        println!("  socket:         {}", SOCKET_FILE);
+        println!("  uptime:         {}s", status.uptime_secs);
+        println!("  cached queries: {}", status.cached_queries);
+        println!("  invalidations:  {}", status.invalidations);
+        Ok(())
+    }
+}
+
+fn ensure_workspace() -> Result<()> {
+    if !is_moonflare_workspace() {
+        bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+    }
+    Ok(())
+}
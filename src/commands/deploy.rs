@@ -1,7 +1,93 @@
-use crate::utils::{fs::is_moonflare_workspace, moon::run_moon_command};
-use anyhow::Result;
+#[cfg(not(feature = "api"))]
+use crate::utils::cloudflare::api_unavailable;
+use crate::utils::{
+    artifacts::restore_artifacts,
+    audit::{Severity, audit},
+    bundle,
+    config::{AuditConfig, DeployStrategy, LayoutConfig, MoonflareConfig, ProjectDeployConfig},
+    deploy_strategy,
+    dev_vars::parse_dev_vars,
+    events::{self, Event},
+    explain::{Explanation, Step},
+    fs::is_moonflare_workspace,
+    interrupt, lock,
+    manifest::{DeploymentManifest, DeploymentRecord},
+    migrations::d1_status,
+    moon::{graph_projects, moon_command, run_moon_command, validate_task_exists},
+    moon_report::RunReport,
+    notify::{self, DeploySummary, ProjectDeploy},
+    plan::{self, Plan},
+    projects::{
+        ProjectFilter, WorkspaceProject, discover_projects, find_project, project_not_found,
+    },
+    reporter::TaskLog,
+    repro::SECRET_KEY_PATTERN,
+    retention::format_bytes,
+    retry,
+    routes::{find_conflicts, render_conflicts, route_claims},
+    sbom::{sbom_path, write_sbom},
+    smoke, task_env, timeout,
+    vcs::{current_branch, current_commit, is_pull_request, triggered_by, uncommitted_changes},
+    watch::FileSnapshot,
+    wrangler::{missing_env_bindings, read_wrangler_config},
+    wrangler_defaults::{self, EffectiveConfigs, InjectedVars},
+    wrangler_output::{DeployOutput, OutputFile},
+    wrangler_schema::check_wrangler_configs,
+};
+#[cfg(feature = "api")]
+use crate::utils::{
+    builds,
+    cloudflare::{BUILDS_PERMISSION, CloudflareClient},
+};
+use anyhow::{Context, Result};
 use colored::*;
-use std::path::Path;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Options controlling which projects are deployed and where
+#[derive(Debug, Default)]
+pub struct DeployOptions {
+    pub env: Option<String>,
+    pub auto_env: bool,
+    pub filter: ProjectFilter,
+    pub require_clean: bool,
+    /// Generate an SBOM per project and attach its hash to the deploy
+    pub sbom: bool,
+    /// Run `pnpm audit`/`cargo audit` first and block on advisories above the threshold
+    pub audit: bool,
+    /// Refuse to deploy while D1 migrations are pending in the target environment
+    pub require_migrations: bool,
+    /// Bundle each Worker first and refuse to deploy one over its size budget, as happens
+    /// anyway for projects with a budget
+    pub analyze_bundle: bool,
+    /// Keep running and redeploy changed projects to a non-production environment
+    pub watch: bool,
+    /// Moon task run instead of `deploy`, e.g. `deploy:pages`
+    pub task: String,
+    /// Environment name repeated to confirm a deploy to a protected environment
+    pub confirm: Option<String>,
+    /// Pass `--keep-vars` so wrangler keeps variables set in the Cloudflare dashboard
+    pub keep_vars: bool,
+    /// Override whether a summary is posted to the `[deploy.notify]` webhook
+    pub notify: Option<bool>,
+    /// Start Workers Builds in Cloudflare instead of running wrangler locally
+    pub build_remote: bool,
+    /// Deploy the prebuilt output under this directory instead of building
+    pub from_artifacts: Option<PathBuf>,
+    /// Leave objects missing from the output in the bucket of an `r2-static` deploy
+    pub no_delete: bool,
+    /// Run the generated smoke tests against the deployed projects
+    pub smoke: bool,
+    /// Print what each project's deploy would run instead of running it
+    pub explain_plan: bool,
+    /// Environment whose deployment this one promotes, set by `moonflare promote`
+    pub promoted_from: Option<String>,
+    /// Worker vars set over each project's Wrangler configuration, from `--vars-file`
+    pub vars: BTreeMap<String, String>,
+}
 
 pub struct DeployCommand {}
 
@@ -10,92 +96,1400 @@ impl DeployCommand {
         Self {}
     }
 
-    pub async fn execute(&self, project: Option<&str>, env: Option<&str>) -> Result<()> {
+    pub async fn execute(
+        &self,
+        project: Option<&str>,
+        options: &DeployOptions,
+        tasks: &TaskLog,
+    ) -> Result<()> {
         if !is_moonflare_workspace() {
             anyhow::bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
         }
+        // Two deploys at once would race on Cloudflare and on the deployment manifest
+        let _lock = lock::acquire("deploy")?;
 
-        match project {
-            Some(proj) => {
-                println!(
-                    "{}",
-                    format!("Deploying project '{}'...", proj).cyan().bold()
-                );
+        let config = MoonflareConfig::load()?;
+        if options.build_remote && options.task != "deploy" {
+            anyhow::bail!(
+                "--build-remote runs the deploy command set up by 'moonflare builds setup'; it can't run --task {}",
+                options.task
+            );
+        }
+        if options.task != "deploy" {
+            validate_task_exists(project, &options.task).await?;
+        }
+        check_wrangler_configs(project, &options.filter).map_err(|e| *e)?;
+        if options.watch {
+            return watch(project, options, &config, tasks).await;
+        }
+        if options.smoke && !smoke::is_set_up() {
+            anyhow::bail!(
+                "--smoke runs the workspace's smoke tests; add them with 'moonflare add smoke-tests' first"
+            );
+        }
 
-                // Try to find the project in different directories
-                let possible_paths = [
-                    format!("workers/{}", proj),
-                    format!("sites/{}", proj),
-                    format!("apps/{}", proj),
-                ];
+        let resolved_env = if options.auto_env {
+            Some(resolve_auto_env(&config)?)
+        } else {
+            options.env.clone()
+        };
+        let env = resolved_env.as_deref();
+        if options.explain_plan {
+            task_env::set_environment(env);
+            return explain(project, options, env, &config).await;
+        }
+        if plan::plan_only() {
+            return print_plan(project, options, env, &config).await;
+        }
+        task_env::set_environment(env);
+        print_injected_vars(&options.vars);
+        let filter = &options.filter;
+        if let Some(environment) = env {
+            check_promotion(&config, environment, options.promoted_from.as_deref())?;
+        }
 
-                let _project_path = possible_paths
-                    .iter()
-                    .find(|path| Path::new(path).exists())
-                    .ok_or_else(|| anyhow::anyhow!("Project '{}' not found", proj))?;
+        let protection = env.and_then(|e| config.deploy.protection(e).map(|rules| (e, rules)));
+        let (require_clean, audit, require_migrations) = match protection {
+            Some((environment, rules)) => {
+                confirm_protected_deploy(environment, options.confirm.as_deref())?;
+                (
+                    options.require_clean || rules.require_clean,
+                    options.audit || rules.require_audit,
+                    options.require_migrations || rules.require_migrations,
+                )
+            }
+            None => (
+                options.require_clean,
+                options.audit,
+                options.require_migrations,
+            ),
+        };
 
-                // Use Moon to run the deploy task, which handles project-level dependencies
-                let moon_target = format!("{}:deploy", proj);
-                if let Some(environment) = env {
+        let started = Instant::now();
+        let mut deploys = Vec::new();
+        let result: Result<()> = async {
+            match project {
+                Some(proj) => {
                     println!(
                         "{}",
-                        format!("Deploying to environment: {}", environment).yellow()
+                        format!("Deploying project '{}'...", proj).cyan().bold()
                     );
-                }
 
-                // Moon will inherit environment variables from the current process
-                // We can use the moon command directly with environment variables
-                if let Some(environment) = env {
-                    let cmd_args = vec!["run", &moon_target];
-                    // We'll set the WRANGLER_ENV environment variable for this execution
-                    std::process::Command::new("moon")
-                        .args(&cmd_args)
-                        .env("WRANGLER_ENV", environment)
-                        .status()?;
-                } else {
-                    run_moon_command(&["run", &moon_target]).await?;
+                    let project_info = find_project(proj).ok_or_else(|| project_not_found(proj))?;
+                    let project_path = &project_info.path;
+
+                    check_route_conflicts(&[proj], env)?;
+                    warn_missing_env_bindings(&[(proj, Path::new(project_path))], env)?;
+                    let dirty = check_working_tree(&[Path::new(project_path)], require_clean)?;
+                    if audit {
+                        check_audit(&[Path::new(project_path)], &config.deploy.audit)?;
+                    }
+                    if require_migrations {
+                        check_migrations(&[(proj, Path::new(project_path))], env)?;
+                    }
+                    check_bundles(&[&project_info], env, options, &config)?;
+
+                    if let Some(environment) = env {
+                        println!(
+                            "{}",
+                            format!("Deploying to environment: {}", environment).yellow()
+                        );
+                    }
+
+                    let mut sboms = BTreeMap::new();
+                    let message = if options.sbom {
+                        let hash = generate_deploy_sbom(&project_info)?;
+                        sboms.insert(proj.to_string(), hash.clone());
+                        Some(sbom_message(&hash))
+                    } else {
+                        None
+                    };
+
+                    // Use Moon to run the deploy task, which handles project-level dependencies
+                    let project_started = Instant::now();
+                    let deployed =
+                        run_deploy_task(proj, options, env, message.as_deref(), tasks).await;
+                    deploys.push(ProjectDeploy::new(
+                        &project_info,
+                        project_started.elapsed(),
+                        deployed.is_ok(),
+                    ));
+                    let mut outputs = BTreeMap::new();
+                    match deployed {
+                        Ok(Some(output)) => {
+                            outputs.insert(proj.to_string(), output);
+                        }
+                        Ok(None) => {}
+                        Err(e) => anyhow::bail!("Deploy of '{}' failed: {}", proj, e),
+                    }
+
+                    record_deployments(
+                        env,
+                        &[proj],
+                        dirty,
+                        &sboms,
+                        &outputs,
+                        options.promoted_from.as_deref(),
+                    )?;
+                    if options.smoke {
+                        smoke::run(&[proj], env).await?;
+                    }
                 }
-            }
-            None => {
-                println!("{}", "Deploying all deployable projects...".cyan().bold());
-
-                // Deploy all projects that have Wrangler configuration files (wrangler.toml, wrangler.json, or wrangler.jsonc)
-                let dirs = ["workers", "sites", "apps"];
-                for dir in dirs {
-                    if let Ok(entries) = std::fs::read_dir(dir) {
-                        for entry in entries.flatten() {
-                            let project_path = entry.path();
-                            // Check for any Wrangler configuration file format
-                            if (project_path.join("wrangler.toml").exists()
-                                || project_path.join("wrangler.json").exists()
-                                || project_path.join("wrangler.jsonc").exists())
-                                && let Some(name) = project_path.file_name()
-                            {
-                                println!(
-                                    "{}",
-                                    format!("Deploying {}...", name.to_string_lossy()).blue()
-                                );
-                                // Use Moon to run the deploy task for each project
-                                let moon_target = format!("{}:deploy", name.to_string_lossy());
-
-                                // Moon will inherit environment variables from the current process
-                                if let Some(environment) = env {
-                                    let cmd_args = vec!["run", &moon_target];
-                                    std::process::Command::new("moon")
-                                        .args(&cmd_args)
-                                        .env("WRANGLER_ENV", environment)
-                                        .status()?;
-                                } else {
-                                    run_moon_command(&["run", &moon_target]).await?;
+                None => {
+                    if filter.is_empty() {
+                        println!("{}", "Deploying all deployable projects...".cyan().bold());
+                    } else {
+                        println!(
+                            "{}",
+                            format!("Deploying filtered projects ({})...", filter.describe())
+                                .cyan()
+                                .bold()
+                        );
+                    }
+
+                    let deployable = deployable_projects(filter, &options.task, &config).await;
+
+                    if deployable.is_empty() && !filter.is_empty() {
+                        anyhow::bail!(
+                            "No deployable projects match the filter ({})",
+                            filter.describe()
+                        );
+                    }
+
+                    let names: Vec<&str> = deployable.iter().map(|p| p.name.as_str()).collect();
+                    check_route_conflicts(&names, env)?;
+                    let projects: Vec<(&str, &Path)> = deployable
+                        .iter()
+                        .map(|p| (p.name.as_str(), p.path.as_path()))
+                        .collect();
+                    warn_missing_env_bindings(&projects, env)?;
+                    let paths: Vec<&Path> = deployable.iter().map(|p| p.path.as_path()).collect();
+                    let dirty = !paths.is_empty() && check_working_tree(&paths, require_clean)?;
+                    if audit && !paths.is_empty() {
+                        check_audit(&paths, &config.deploy.audit)?;
+                    }
+                    if require_migrations {
+                        check_migrations(&projects, env)?;
+                    }
+                    check_bundles(
+                        &deployable.iter().collect::<Vec<_>>(),
+                        env,
+                        options,
+                        &config,
+                    )?;
+
+                    let mut deployed = Vec::new();
+                    let mut sboms = BTreeMap::new();
+                    let mut outputs = BTreeMap::new();
+                    for project in &deployable {
+                        println!("{}", format!("Deploying {}...", project.name).blue());
+
+                        let message = if options.sbom {
+                            let hash = generate_deploy_sbom(project)?;
+                            sboms.insert(project.name.clone(), hash.clone());
+                            Some(sbom_message(&hash))
+                        } else {
+                            None
+                        };
+
+                        let project_started = Instant::now();
+                        let result =
+                            run_deploy_task(&project.name, options, env, message.as_deref(), tasks)
+                                .await;
+                        deploys.push(ProjectDeploy::new(
+                            project,
+                            project_started.elapsed(),
+                            result.is_ok(),
+                        ));
+                        match result {
+                            Ok(Some(output)) => {
+                                outputs.insert(project.name.clone(), output);
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                record_deployments(
+                                    env,
+                                    &deployed,
+                                    dirty,
+                                    &sboms,
+                                    &outputs,
+                                    options.promoted_from.as_deref(),
+                                )?;
+                                if timeout::is_timeout(&e) {
+                                    let not_deployed: Vec<&str> = deployable
+                                        .iter()
+                                        .skip(deployed.len() + 1)
+                                        .map(|p| p.name.as_str())
+                                        .collect();
+                                    anyhow::bail!(
+                                        "Deploy of '{}' {}; deployed: {}; not deployed: {}",
+                                        project.name,
+                                        e,
+                                        list_or_none(&deployed),
+                                        list_or_none(&not_deployed)
+                                    );
                                 }
+                                anyhow::bail!("Deploy of '{}' failed: {}", project.name, e);
                             }
                         }
+                        deployed.push(project.name.as_str());
+                    }
+
+                    record_deployments(
+                        env,
+                        &deployed,
+                        dirty,
+                        &sboms,
+                        &outputs,
+                        options.promoted_from.as_deref(),
+                    )?;
+                    if options.smoke {
+                        smoke::run(&deployed, env).await?;
                     }
                 }
             }
+            Ok(())
+        }
+        .await;
+
+        if should_notify(options, &config) {
+            notify_deploy(&config, env, deploys, started.elapsed(), &result).await;
         }
+        result?;
 
         println!("{}", "Deployment completed successfully!".green().bold());
         Ok(())
     }
 }
+
+/// Notify when `--notify` is passed or a webhook is configured and enabled, unless
+/// `--no-notify` is
+fn should_notify(options: &DeployOptions, config: &MoonflareConfig) -> bool {
+    match options.notify {
+        Some(notify) => notify,
+        None => config.deploy.notify.enabled && config.deploy.notify.webhook.is_some(),
+    }
+}
+
+/// Post the deploy summary to the `[deploy.notify]` webhook. A failed notification is only a
+/// warning; it never changes the outcome of the deploy.
+async fn notify_deploy(
+    config: &MoonflareConfig,
+    env: Option<&str>,
+    projects: Vec<ProjectDeploy>,
+    duration: Duration,
+    result: &Result<()>,
+) {
+    let summary = DeploySummary {
+        environment: env.map(str::to_string),
+        projects,
+        git_sha: current_commit(),
+        triggered_by: triggered_by(),
+        duration,
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+    match notify::send(&config.deploy.notify, &summary).await {
+        Ok(()) => println!("{}", "Sent deploy notification".dimmed()),
+        Err(e) => println!(
+            "{}",
+            format!("Warning: deploy notification not sent: {}", e).yellow()
+        ),
+    }
+}
+
+/// A deploy triggered by watch mode, shown in the terminal history
+struct WatchDeploy {
+    started_at: chrono::DateTime<chrono::Local>,
+    projects: Vec<String>,
+    success: bool,
+    duration: Duration,
+}
+
+/// Environment names watch mode refuses to deploy to
+const PRODUCTION_ENVIRONMENTS: [&str; 2] = ["production", "prod"];
+
+/// How often watched files are polled for changes
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Number of deploys kept in the watch history
+const WATCH_HISTORY_LEN: usize = 10;
+
+/// How many files and packages a refused bundle lists
+const BUNDLE_CONTRIBUTORS: usize = 5;
+
+/// Deploy the selected projects, then redeploy the affected ones whenever their files (or any
+/// crate, whose WASM they bundle) change. Moon rebuilds through the deploy task's build dependency.
+async fn watch(
+    project: Option<&str>,
+    options: &DeployOptions,
+    config: &MoonflareConfig,
+    tasks: &TaskLog,
+) -> Result<()> {
+    // The CLI already rejects these with --watch; callers building DeployOptions get the same
+    if options.auto_env || options.require_clean {
+        anyhow::bail!("--auto-env and --require-clean can't be used with --watch");
+    }
+    let env = options
+        .env
+        .clone()
+        .or_else(|| config.deploy.watch.environment.clone())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Watch mode needs a staging environment. Pass --env or set environment under [deploy.watch] in moonflare.toml."
+            )
+        })?;
+    if PRODUCTION_ENVIRONMENTS.contains(&env.to_lowercase().as_str())
+        || config.deploy.protection(&env).is_some()
+        || config.deploy.promotion_source(&env).is_some()
+    {
+        anyhow::bail!(
+            "Watch mode only deploys to non-production, unprotected environments, not '{}'",
+            env
+        );
+    }
+    task_env::set_environment(Some(&env));
+    print_injected_vars(&options.vars);
+
+    let projects: Vec<WorkspaceProject> = match project {
+        Some(name) => {
+            let project = find_project(name).ok_or_else(|| project_not_found(name))?;
+            vec![project]
+        }
+        None => deployable_projects(&options.filter, &options.task, config).await,
+    };
+    if projects.is_empty() {
+        anyhow::bail!("No deployable projects to watch");
+    }
+    let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+    check_route_conflicts(&names, Some(&env))?;
+    let targets: Vec<(&str, &Path)> = projects
+        .iter()
+        .map(|p| (p.name.as_str(), p.path.as_path()))
+        .collect();
+    warn_missing_env_bindings(&targets, Some(&env))?;
+
+    let mut roots: Vec<&Path> = projects.iter().map(|p| p.path.as_path()).collect();
+    let crates_dir = PathBuf::from(&config.layout.crates);
+    if crates_dir.exists() {
+        roots.push(&crates_dir);
+    }
+    if options.audit {
+        check_audit(&roots[..projects.len()], &config.deploy.audit)?;
+    }
+
+    let debounce = Duration::from_millis(config.deploy.watch.debounce_ms);
+    let mut history: Vec<WatchDeploy> = Vec::new();
+
+    println!(
+        "{}",
+        format!(
+            "Watching {} project(s), deploying to '{}' (Ctrl-C to stop)",
+            projects.len(),
+            env
+        )
+        .cyan()
+        .bold()
+    );
+
+    let all: Vec<&WorkspaceProject> = projects.iter().collect();
+    history.push(watch_deploy(&all, &env, options, tasks).await?);
+    print_watch_history(&history, &env);
+    let mut baseline = FileSnapshot::capture(&roots);
+
+    // Ctrl-C ends the loop so the summary below still prints
+    let _graceful = interrupt::handle_gracefully();
+    loop {
+        tokio::select! {
+            _ = interrupt::cancelled() => break,
+            _ = tokio::time::sleep(WATCH_POLL_INTERVAL) => {}
+        }
+
+        let mut settled = FileSnapshot::capture(&roots);
+        if settled.changed_since(&baseline).is_empty() {
+            continue;
+        }
+
+        // Wait for the burst of saves to finish before deploying
+        loop {
+            tokio::time::sleep(debounce).await;
+            let next = FileSnapshot::capture(&roots);
+            if next == settled {
+                break;
+            }
+            settled = next;
+        }
+
+        let changed = settled.changed_since(&baseline);
+        let affected: Vec<&WorkspaceProject> = if changed.iter().any(|p| p.starts_with(&crates_dir))
+        {
+            projects.iter().collect()
+        } else {
+            projects
+                .iter()
+                .filter(|project| changed.iter().any(|p| p.starts_with(&project.path)))
+                .collect()
+        };
+
+        println!();
+        println!(
+            "{}",
+            format!(
+                "{} file(s) changed: {}",
+                changed.len(),
+                changed
+                    .iter()
+                    .take(3)
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .dimmed()
+        );
+
+        history.push(watch_deploy(&affected, &env, options, tasks).await?);
+        if history.len() > WATCH_HISTORY_LEN {
+            history.remove(0);
+        }
+        print_watch_history(&history, &env);
+
+        // Build output written during the deploy shouldn't trigger another one
+        baseline = FileSnapshot::capture(&roots);
+    }
+
+    println!();
+    println!("{}", "Stopped watching".yellow());
+    Ok(())
+}
+
+/// Deploy the projects, keeping going past failures so the watcher stays alive
+async fn watch_deploy(
+    projects: &[&WorkspaceProject],
+    env: &str,
+    options: &DeployOptions,
+    tasks: &TaskLog,
+) -> Result<WatchDeploy> {
+    let started_at = chrono::Local::now();
+    let started = Instant::now();
+    let mut deployed = Vec::new();
+    let mut sboms = BTreeMap::new();
+    let mut outputs = BTreeMap::new();
+    let mut success = true;
+
+    for project in projects {
+        println!("{}", format!("Deploying {}...", project.name).blue());
+        let message = if options.sbom {
+            let hash = generate_deploy_sbom(project)?;
+            sboms.insert(project.name.clone(), hash.clone());
+            Some(sbom_message(&hash))
+        } else {
+            None
+        };
+
+        match run_deploy_task(&project.name, options, Some(env), message.as_deref(), tasks).await {
+            Ok(output) => {
+                if let Some(output) = output {
+                    outputs.insert(project.name.clone(), output);
+                }
+                deployed.push(project.name.as_str());
+            }
+            Err(e) => {
+                println!(
+                    "{}",
+                    format!("Deploy of '{}' failed: {}", project.name, e).red()
+                );
+                success = false;
+            }
+        }
+    }
+
+    let paths: Vec<&Path> = projects.iter().map(|p| p.path.as_path()).collect();
+    // A tree git can't vouch for is recorded as dirty
+    let dirty = uncommitted_changes(&paths).map_or(true, |changes| !changes.is_empty());
+    record_deployments(Some(env), &deployed, dirty, &sboms, &outputs, None)?;
+
+    Ok(WatchDeploy {
+        started_at,
+        projects: projects.iter().map(|p| p.name.clone()).collect(),
+        success,
+        duration: started.elapsed(),
+    })
+}
+
+fn print_watch_history(history: &[WatchDeploy], env: &str) {
+    println!();
+    println!("{}", format!("Deploy history ({})", env).bold());
+    for deploy in history {
+        let status = if deploy.success {
+            "✓".green()
+        } else {
+            "✗".red()
+        };
+        println!(
+            "  {}  {}  {:<40} {:>6.1}s",
+            deploy.started_at.format("%H:%M:%S"),
+            status,
+            deploy.projects.join(", "),
+            deploy.duration.as_secs_f64()
+        );
+    }
+    println!();
+}
+
+/// An environment with `promote_from` set only takes promotions from that environment
+pub fn check_promotion(
+    config: &MoonflareConfig,
+    environment: &str,
+    promoted_from: Option<&str>,
+) -> Result<()> {
+    match config.deploy.promotion_source(environment) {
+        Some(source) if promoted_from != Some(source) => anyhow::bail!(
+            "'{}' only takes what {} runs; promote it with 'moonflare promote {} {}'",
+            environment,
+            source,
+            source,
+            environment
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// A protected environment needs its name repeated with `--confirm`, or typed at a prompt
+pub fn confirm_protected_deploy(environment: &str, confirm: Option<&str>) -> Result<()> {
+    match confirm {
+        Some(confirmed) if confirmed == environment => return Ok(()),
+        Some(confirmed) => anyhow::bail!(
+            "--confirm {} doesn't match the protected environment '{}'",
+            confirmed,
+            environment
+        ),
+        None => {}
+    }
+
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "'{}' is a protected environment. Pass --confirm {} to deploy to it.",
+            environment,
+            environment
+        );
+    }
+
+    print!(
+        "{} Type '{}' to deploy to it: ",
+        format!("'{}' is a protected environment.", environment)
+            .yellow()
+            .bold(),
+        environment
+    );
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    if line.trim() != environment {
+        anyhow::bail!("Deploy to '{}' not confirmed", environment);
+    }
+    Ok(())
+}
+
+/// Warn about uncommitted changes in the deploy set, or fail when a clean tree is required.
+/// Returns whether the tree was dirty.
+fn check_working_tree(paths: &[&Path], require_clean: bool) -> Result<bool> {
+    let changes = match uncommitted_changes(paths) {
+        Ok(changes) => changes,
+        Err(e) if require_clean => {
+            return Err(e.context("--require-clean needs git to confirm the tree is clean"));
+        }
+        Err(e) => {
+            println!(
+                "{}",
+                format!(
+                    "Warning: couldn't check for uncommitted changes ({}); recording the deploy as dirty",
+                    e
+                )
+                .yellow()
+            );
+            return Ok(true);
+        }
+    };
+    if changes.is_empty() {
+        return Ok(false);
+    }
+
+    let listing = changes
+        .iter()
+        .take(10)
+        .map(|c| format!("  {}", c))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let more = if changes.len() > 10 {
+        format!("\n  ... and {} more", changes.len() - 10)
+    } else {
+        String::new()
+    };
+
+    if require_clean {
+        anyhow::bail!(
+            "Refusing to deploy with uncommitted changes (--require-clean):\n{}{}",
+            listing,
+            more
+        );
+    }
+
+    println!(
+        "{}",
+        "Warning: deploying uncommitted changes; the recorded git SHA won't match what ships:"
+            .yellow()
+    );
+    println!("{}{}", listing, more);
+    Ok(true)
+}
+
+/// Fail when a project being deployed claims a route or custom domain that another project
+/// also claims in the target environment
+fn check_route_conflicts(deploying: &[&str], environment: Option<&str>) -> Result<()> {
+    let claims = route_claims(&discover_projects(), environment)?;
+    let conflicts: Vec<_> = find_conflicts(&claims)
+        .into_iter()
+        .filter(|c| deploying.iter().any(|project| c.involves(project)))
+        .collect();
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Route conflicts in {}:",
+            environment.map_or("the default environment".to_string(), |e| format!(
+                "environment '{}'",
+                e
+            ))
+        )
+        .red()
+        .bold()
+    );
+    print!("{}", render_conflicts(&conflicts));
+    anyhow::bail!(
+        "Refusing to deploy: {} route conflict(s) would send traffic to the wrong Worker",
+        conflicts.len()
+    );
+}
+
+/// Bundle the Workers that have a size budget, or every Worker with `--analyze-bundle`, and
+/// refuse the deploy when one is over budget, includes a native binary or imports Node.js
+/// builtins without `nodejs_compat`
+fn check_bundles(
+    projects: &[&WorkspaceProject],
+    environment: Option<&str>,
+    options: &DeployOptions,
+    config: &MoonflareConfig,
+) -> Result<()> {
+    if options.build_remote || options.task != "deploy" {
+        return Ok(());
+    }
+    let checked: Vec<_> = projects
+        .iter()
+        .filter(|p| options.analyze_bundle || config.bundle_budget_kb(&p.name).is_some())
+        .filter(|p| config.deploy_settings(&p.name).strategy == DeployStrategy::Wrangler)
+        .filter(|p| bundle::is_bundled(p))
+        .collect();
+    if checked.is_empty() {
+        return Ok(());
+    }
+
+    println!("{}", "Analyzing bundles...".blue());
+    let mut refused = Vec::new();
+    for project in checked {
+        let report = bundle::analyze(project, environment, config)?;
+        if report.problems().is_empty() {
+            println!(
+                "  {} {}",
+                project.name,
+                match report.budget_bytes {
+                    Some(budget) => format!(
+                        "{} of {} budget",
+                        format_bytes(report.bytes),
+                        format_bytes(budget)
+                    ),
+                    None => format_bytes(report.bytes),
+                }
+                .dimmed()
+            );
+        } else {
+            print!("{}", bundle::render(&report, BUNDLE_CONTRIBUTORS));
+            refused.push(project.name.as_str());
+        }
+    }
+    if !refused.is_empty() {
+        anyhow::bail!(
+            "Refusing to deploy {}: see the bundle analysis above, or run 'moonflare bundle analyze' for the full list",
+            refused.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Warn about top-level vars and bindings an environment's section doesn't repeat, since
+/// Wrangler deploys the environment without them
+fn warn_missing_env_bindings(projects: &[(&str, &Path)], environment: Option<&str>) -> Result<()> {
+    let Some(environment) = environment else {
+        return Ok(());
+    };
+
+    for (name, path) in projects {
+        let Some(config) = read_wrangler_config(path)? else {
+            continue;
+        };
+        if config.pointer(&format!("/env/{}", environment)).is_none() {
+            continue;
+        }
+
+        let missing = missing_env_bindings(&config, environment);
+        if missing.is_empty() {
+            continue;
+        }
+        let listing = missing
+            .iter()
+            .map(|b| format!("{} {}", b.key, b.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "{}",
+            format!(
+                "Warning: [env.{}] of '{}' doesn't declare {} (Wrangler environments don't inherit vars or bindings). Run 'moonflare env sync {} {}' to copy them.",
+                environment, name, listing, name, environment
+            )
+            .yellow()
+        );
+    }
+    Ok(())
+}
+
+/// Fail when a deployed project's D1 databases have migrations the environment hasn't applied
+fn check_migrations(projects: &[(&str, &Path)], environment: Option<&str>) -> Result<()> {
+    println!("{}", "Checking D1 migrations...".blue());
+
+    let mut pending = Vec::new();
+    for (name, path) in projects {
+        let Some(config) = read_wrangler_config(path)? else {
+            continue;
+        };
+        let project = WorkspaceProject {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+        };
+        for status in d1_status(&project, &config, environment)? {
+            if !status.pending.is_empty() {
+                pending.push(format!(
+                    "  {} {}: {}",
+                    status.project,
+                    status.target,
+                    status.pending.join(", ")
+                ));
+            }
+        }
+    }
+    if !pending.is_empty() {
+        anyhow::bail!(
+            "Refusing to deploy with pending D1 migrations (--require-migrations):\n{}\nApply them with 'pnpm exec wrangler d1 migrations apply <DATABASE> --remote' first",
+            pending.join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Audit the deploy set and every crate (their WASM ships with it), failing on advisories at or
+/// above the configured severity
+fn check_audit(paths: &[&Path], config: &AuditConfig) -> Result<()> {
+    println!("{}", "Auditing dependencies...".blue());
+
+    let crates: Vec<_> = std::fs::read_dir(LayoutConfig::current().crates)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    let crate_paths: Vec<&Path> = crates.iter().map(|p| p.as_path()).collect();
+    let npm_projects: Vec<&Path> = paths
+        .iter()
+        .copied()
+        .filter(|p| p.join("package.json").exists())
+        .collect();
+
+    let report = audit(&npm_projects, &crate_paths)?;
+    for skipped in &report.skipped {
+        println!("{}", format!("  Skipped {}", skipped).yellow());
+    }
+
+    let counts = report.counts();
+    let summary = Severity::ALL
+        .iter()
+        .map(|s| format!("{} {}", counts.get(s).copied().unwrap_or(0), s))
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!("  Advisories: {}", summary);
+
+    let blocking: Vec<String> = report
+        .advisories
+        .iter()
+        .filter(|a| a.severity >= config.fail_on && !config.ignore.contains(&a.id))
+        .map(|a| format!("  [{}] {} {}: {}", a.severity, a.package, a.id, a.title))
+        .collect();
+    if !blocking.is_empty() {
+        anyhow::bail!(
+            "Refusing to deploy with {} advisories at or above '{}' (--audit):\n{}",
+            blocking.len(),
+            config.fail_on,
+            blocking.join("\n")
+        );
+    }
+
+    Ok(())
+}
+
+/// The projects the filter selects that can be deployed, taken from Moon's project graph so
+/// projects in custom layouts are found and directories without the task are skipped. When
+/// Moon can't be queried, the layout directories are scanned instead.
+async fn deployable_projects(
+    filter: &ProjectFilter,
+    task: &str,
+    config: &MoonflareConfig,
+) -> Vec<WorkspaceProject> {
+    let projects = match graph_projects().await {
+        Ok(graph) => graph
+            .into_iter()
+            .filter(|(project, tasks)| is_deployable(project, Some(tasks), task, config))
+            .map(|(project, _)| project)
+            .collect(),
+        Err(_) => discover_projects()
+            .into_iter()
+            .filter(|project| is_deployable(project, None, task, config))
+            .collect(),
+    };
+    filter.apply(projects)
+}
+
+/// Projects with a deploy strategy of their own are deployable, since the strategy replaces
+/// the deploy task. Otherwise the project needs Wrangler configuration and the task, which
+/// Moon's graph lists in `tasks`; without it only an overridden task is checked.
+fn is_deployable(
+    project: &WorkspaceProject,
+    tasks: Option<&BTreeSet<String>>,
+    task: &str,
+    config: &MoonflareConfig,
+) -> bool {
+    if task == "deploy"
+        && config.deploy_settings(&project.name).strategy != DeployStrategy::Wrangler
+    {
+        return true;
+    }
+    project.has_wrangler_config()
+        && match tasks {
+            Some(tasks) => tasks.contains(task),
+            None => task == "deploy" || project.has_task(task),
+        }
+}
+
+/// The deploy task each project would run, for `--plan`
+async fn print_plan(
+    project: Option<&str>,
+    options: &DeployOptions,
+    env: Option<&str>,
+    config: &MoonflareConfig,
+) -> Result<()> {
+    let prefix = env
+        .map(|environment| format!("WRANGLER_ENV={} ", environment))
+        .unwrap_or_default();
+    let mut plan = Plan::new();
+    for project in deploy_targets(project, options, config).await? {
+        plan.run(format!(
+            "{}moon run {}:{}",
+            prefix, project.name, options.task
+        ));
+    }
+    plan.render();
+    Ok(())
+}
+
+/// Every process each project's deploy would start, with its working directory and
+/// variables, for `--explain-plan`
+async fn explain(
+    project: Option<&str>,
+    options: &DeployOptions,
+    env: Option<&str>,
+    config: &MoonflareConfig,
+) -> Result<()> {
+    let merged = wrangler_defaults::find_defaults().is_some() || !options.vars.is_empty();
+    let message = options
+        .sbom
+        .then(|| sbom_message("<hash of the generated SBOM>"));
+    let mut explanation = Explanation::new();
+    if options.audit {
+        explanation.note("Runs pnpm audit and cargo audit in the projects before deploying");
+    }
+    if options.sbom {
+        explanation.note("Generates an SBOM for each project before deploying it");
+    }
+    if options.analyze_bundle {
+        explanation.note(
+            "Bundles each Worker with 'wrangler deploy --dry-run' and checks it before deploying",
+        );
+    }
+
+    for project in deploy_targets(project, options, config).await? {
+        if let Some(artifacts) = &options.from_artifacts {
+            explanation.note(format!(
+                "Restores '{}' from the prebuilt artifacts in {}",
+                project.name,
+                artifacts.display()
+            ));
+        }
+        let settings = config.deploy_settings(&project.name);
+        if options.task == "deploy" && settings.strategy != DeployStrategy::Wrangler {
+            if options.from_artifacts.is_none() {
+                explanation.step(Step::moon(&["run", &format!("{}:build", project.name)]));
+            }
+            explanation.note(format!(
+                "Uploads the output of '{}' with the {} strategy",
+                project.name,
+                String::from(settings.strategy.clone())
+            ));
+            continue;
+        }
+        if options.build_remote {
+            explanation.note(format!(
+                "Starts the Workers Builds trigger of '{}' for the checked-out commit",
+                project.name
+            ));
+            continue;
+        }
+
+        let step = match &options.from_artifacts {
+            Some(_) => Step::in_project(&project, "pnpm", &["run", &options.task]),
+            None => Step::moon(&["run", &format!("{}:{}", project.name, options.task)]),
+        };
+        let step = step.envs(wrangler_deploy_vars(
+            env,
+            message.as_deref(),
+            options.keep_vars,
+        ));
+        explanation.step(if merged && project.has_wrangler_config() {
+            step.env(
+                task_env::WRANGLER_CONFIG_VAR,
+                wrangler_defaults::EFFECTIVE_CONFIG_FILE,
+            )
+        } else {
+            step
+        });
+    }
+
+    if merged {
+        explanation.note(format!(
+            "Writes each Worker's merged Wrangler config to {} for the deploy",
+            wrangler_defaults::EFFECTIVE_CONFIG_FILE
+        ));
+    }
+    if options.smoke {
+        explanation.note("Runs the smoke tests against the deployed projects");
+    }
+    explanation.note("Records the deploys in the deployment manifest");
+    explanation.render();
+    Ok(())
+}
+
+/// The project passed on the command line, or every deployable project the filter selects
+async fn deploy_targets(
+    project: Option<&str>,
+    options: &DeployOptions,
+    config: &MoonflareConfig,
+) -> Result<Vec<WorkspaceProject>> {
+    Ok(match project {
+        Some(proj) => vec![find_project(proj).ok_or_else(|| project_not_found(proj))?],
+        None => deployable_projects(&options.filter, &options.task, config).await,
+    })
+}
+
+/// Run a project's deploy task through Moon. The environment, deploy message and
+/// `--keep-vars` reach `wrangler deploy` through the `WRANGLER_ENV`, `WRANGLER_MESSAGE` and
+/// `WRANGLER_KEEP_VARS` variables, and the configuration merged with the workspace's Wrangler
+/// defaults through `WRANGLER_CONFIG`.
+async fn run_deploy_task(
+    project: &str,
+    options: &DeployOptions,
+    env: Option<&str>,
+    message: Option<&str>,
+    tasks: &TaskLog,
+) -> Result<Option<DeployOutput>> {
+    let moon_target = format!("{}:{}", project, options.task);
+    let started = Instant::now();
+    // A strategy replaces the deploy task; an overridden task always runs as is
+    let settings = MoonflareConfig::load()?.deploy_settings(project);
+    let strategy = (options.task == "deploy" && settings.strategy != DeployStrategy::Wrangler)
+        .then_some(&settings);
+    let result = if let Some(settings) = strategy {
+        if options.build_remote {
+            Err(anyhow::anyhow!(
+                "--build-remote deploys with Wrangler, but '{}' uses the {} strategy",
+                project,
+                String::from(settings.strategy.clone())
+            ))
+        } else {
+            deploy_with_strategy(project, settings, options, env, message)
+                .await
+                .map(|_| None)
+        }
+    } else if options.build_remote {
+        build_remote(project, env).await.map(|_| None)
+    } else if let Some(artifacts) = &options.from_artifacts {
+        deploy_artifacts(
+            project,
+            artifacts,
+            &options.task,
+            env,
+            message,
+            options.keep_vars,
+            &options.vars,
+        )
+        .await
+    } else {
+        invoke_deploy_task(&moon_target, env, message, options.keep_vars, &options.vars).await
+    };
+    let error = result.as_ref().err().map(|e| e.to_string());
+    events::emit(Event::DeployFinished {
+        project,
+        env,
+        success: result.is_ok(),
+        duration_secs: events::secs(started.elapsed()),
+        message: error.as_deref(),
+    });
+    tasks.record(&moon_target, result.is_ok(), started.elapsed(), error);
+    if let Ok(Some(output)) = &result
+        && !output.urls.is_empty()
+    {
+        println!("  {} {}", "Live at".green(), output.urls.join(", "));
+    }
+    result
+}
+
+/// Build the project, or restore its prebuilt artifacts, and ship the output with the strategy
+/// set under `[projects.<name>.deploy]`
+async fn deploy_with_strategy(
+    project: &str,
+    settings: &ProjectDeployConfig,
+    options: &DeployOptions,
+    env: Option<&str>,
+    message: Option<&str>,
+) -> Result<()> {
+    let config = MoonflareConfig::load()?;
+    let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+    match &options.from_artifacts {
+        Some(artifacts) => {
+            restore_artifacts(artifacts, &project_info, env)?;
+            println!("  Using prebuilt artifacts from {}", artifacts.display());
+        }
+        None => run_moon_command(&["run", &format!("{}:build", project)]).await?,
+    }
+
+    println!(
+        "  Deploying with the {} strategy",
+        String::from(settings.strategy.clone())
+    );
+    if !options.vars.is_empty() {
+        println!(
+            "  {}",
+            "Static output has no Worker to set --vars-file vars on; they're skipped".yellow()
+        );
+    }
+    let wrangler = read_wrangler_config(&project_info.path)?;
+    let worker_name = wrangler
+        .as_ref()
+        .and_then(|wrangler| wrangler["name"].as_str().map(String::from))
+        .unwrap_or_else(|| config.worker_name(project));
+    deploy_strategy::deploy(
+        &project_info,
+        settings,
+        &deploy_strategy::Target {
+            worker_name: &worker_name,
+            account_id: wrangler
+                .as_ref()
+                .and_then(|wrangler| wrangler["account_id"].as_str()),
+            assets: &config.assets,
+            env,
+            message,
+            delete: !options.no_delete,
+        },
+    )
+    .await
+}
+
+/// Build and deploy the checked-out commit with the project's Workers Builds trigger
+#[cfg(feature = "api")]
+async fn build_remote(project: &str, env: Option<&str>) -> Result<()> {
+    let manifest = DeploymentManifest::load()?;
+    let Some(record) = manifest.build_trigger(env, project) else {
+        anyhow::bail!(
+            "No Workers Builds trigger for '{}'{}; run 'moonflare builds setup {}{}' first",
+            project,
+            env.map(|env| format!(" in {}", env)).unwrap_or_default(),
+            project,
+            env.map(|env| format!(" --env {}", env)).unwrap_or_default()
+        );
+    };
+    let commit = current_commit()
+        .ok_or_else(|| anyhow::anyhow!("Remote builds need a git commit to build"))?;
+    if !builds::is_pushed(&commit) {
+        anyhow::bail!(
+            "Commit {} isn't on a remote branch yet; push it so Workers Builds can check it out",
+            &commit[..commit.len().min(7)]
+        );
+    }
+    let branch = current_branch().unwrap_or_else(|| record.branch.clone());
+    let client = CloudflareClient::from_env(Some(&record.account_id), BUILDS_PERMISSION)?;
+    builds::run_remote_build(&client, record, &branch, &commit).await
+}
+
+#[cfg(not(feature = "api"))]
+async fn build_remote(_project: &str, _env: Option<&str>) -> Result<()> {
+    Err(api_unavailable())
+}
+
+/// Deploy checked, prebuilt output by running the project's deploy script directly, since the
+/// Moon task would build first
+async fn deploy_artifacts(
+    project: &str,
+    artifacts: &Path,
+    task: &str,
+    env: Option<&str>,
+    message: Option<&str>,
+    keep_vars: bool,
+    vars: &BTreeMap<String, String>,
+) -> Result<Option<DeployOutput>> {
+    let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+    restore_artifacts(artifacts, &project_info, env)?;
+    println!("  Using prebuilt artifacts from {}", artifacts.display());
+    let _effective = materialize_vars(std::slice::from_ref(&project_info), env, vars)?;
+
+    let output = OutputFile::new()?;
+    let build = || {
+        let mut command = std::process::Command::new("pnpm");
+        command
+            .args(["run", task])
+            .current_dir(&project_info.path)
+            .envs(task_env::vars(Some(project)));
+        wrangler_deploy_env(&mut command, env, message, keep_vars);
+        output.apply(&mut command);
+        command
+    };
+    retry::command(&format!("pnpm run {}", task), build, None).await?;
+    Ok(output.deploy())
+}
+
+async fn invoke_deploy_task(
+    moon_target: &str,
+    env: Option<&str>,
+    message: Option<&str>,
+    keep_vars: bool,
+    vars: &BTreeMap<String, String>,
+) -> Result<Option<DeployOutput>> {
+    let project = moon_target.split(':').next().unwrap_or_default();
+    let _effective = match find_project(project) {
+        Some(project) => materialize_vars(&[project], env, vars)?,
+        None => Default::default(),
+    };
+    let moon = moon_command(&["run", moon_target])?;
+    let output = OutputFile::new()?;
+    let build = || {
+        let mut command = std::process::Command::new(moon.get_program());
+        command.args(moon.get_args());
+        task_env::apply(&mut command, &["run", moon_target]);
+        wrangler_deploy_env(&mut command, env, message, keep_vars);
+        output.apply(&mut command);
+        command
+    };
+    let started = SystemTime::now();
+    retry::command(&format!("moon run {}", moon_target), build, None)
+        .await
+        .map_err(|e| {
+            // Moon's run report says which task failed and why, whatever it printed
+            match RunReport::load_since(started).and_then(|r| r.failure_message()) {
+                Some(failures) => e.context(failures),
+                None => e,
+            }
+        })?;
+    Ok(output.deploy())
+}
+
+/// Merge the projects' configurations with the Wrangler defaults and the `--vars-file` vars,
+/// saying which configured vars the file replaces
+fn materialize_vars(
+    projects: &[WorkspaceProject],
+    env: Option<&str>,
+    vars: &BTreeMap<String, String>,
+) -> Result<EffectiveConfigs> {
+    let effective = wrangler_defaults::materialize_with_vars(
+        projects,
+        Some(InjectedVars {
+            vars,
+            environment: env,
+        }),
+    )?;
+    for (project, keys) in &effective.overridden {
+        println!(
+            "  {} {} in {}'s Wrangler config",
+            "--vars-file replaces".yellow(),
+            keys.join(", "),
+            project
+        );
+    }
+    Ok(effective)
+}
+
+/// Name the vars `--vars-file` sets, keeping their values out of the log
+fn print_injected_vars(vars: &BTreeMap<String, String>) {
+    if vars.is_empty() {
+        return;
+    }
+    let keys: Vec<&str> = vars.keys().map(String::as_str).collect();
+    println!(
+        "{} {} {}",
+        "Setting Worker vars:".yellow(),
+        keys.join(", "),
+        "(values hidden)".dimmed()
+    );
+}
+
+/// Read a `--vars-file` of `KEY=value` lines. Worker vars are plain text in the dashboard, so
+/// keys named like credentials are refused.
+pub fn read_vars_file(path: &Path) -> Result<BTreeMap<String, String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read vars file {}", path.display()))?;
+    let vars = parse_dev_vars(&content);
+    let secret = Regex::new(SECRET_KEY_PATTERN).expect("valid regex");
+    if let Some(key) = vars.keys().find(|key| secret.is_match(key)) {
+        anyhow::bail!(
+            "{} in {} looks like a secret, and Worker vars are visible in the dashboard; set it with 'moonflare secrets push' instead",
+            key,
+            path.display()
+        );
+    }
+    Ok(vars)
+}
+
+/// Pass the environment, deploy message and `--keep-vars` on to the project's deploy script
+fn wrangler_deploy_env(
+    command: &mut std::process::Command,
+    env: Option<&str>,
+    message: Option<&str>,
+    keep_vars: bool,
+) {
+    command.envs(wrangler_deploy_vars(env, message, keep_vars));
+}
+
+fn wrangler_deploy_vars(
+    env: Option<&str>,
+    message: Option<&str>,
+    keep_vars: bool,
+) -> Vec<(&'static str, String)> {
+    let mut vars = Vec::new();
+    if let Some(environment) = env {
+        vars.push(("WRANGLER_ENV", environment.to_string()));
+    }
+    if let Some(message) = message {
+        vars.push(("WRANGLER_MESSAGE", message.to_string()));
+    }
+    if keep_vars {
+        vars.push(("WRANGLER_KEEP_VARS", "1".to_string()));
+    }
+    vars
+}
+
+fn list_or_none(names: &[&str]) -> String {
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Write the project's SBOM next to the deployment manifest and return its SHA-256
+fn generate_deploy_sbom(project: &WorkspaceProject) -> Result<String> {
+    let path = sbom_path(&project.name);
+    let (hash, _) = write_sbom(project, &path)?;
+    println!("  SBOM {} (sha256:{})", path.display(), &hash[..12]);
+    Ok(hash)
+}
+
+fn sbom_message(hash: &str) -> String {
+    format!("sbom sha256:{}", hash)
+}
+
+/// Record successful deploys with the current commit in the deployment manifest
+fn record_deployments(
+    env: Option<&str>,
+    projects: &[&str],
+    dirty: bool,
+    sboms: &BTreeMap<String, String>,
+    outputs: &BTreeMap<String, DeployOutput>,
+    promoted_from: Option<&str>,
+) -> Result<()> {
+    if projects.is_empty() {
+        return Ok(());
+    }
+
+    let mut manifest = DeploymentManifest::load()?;
+    let git_sha = current_commit();
+    let deployed_at = chrono::Utc::now().to_rfc3339();
+
+    for project in projects {
+        let output = outputs.get(*project).cloned().unwrap_or_default();
+        manifest.record(
+            env,
+            project,
+            DeploymentRecord {
+                deployed_at: deployed_at.clone(),
+                git_sha: git_sha.clone(),
+                dirty,
+                sbom_sha256: sboms.get(*project).cloned(),
+                promoted_from: promoted_from.map(str::to_string),
+                version_id: output.version_id,
+                urls: output.urls,
+            },
+        );
+    }
+
+    manifest.save()
+}
+
+/// Resolve the deploy environment from the current branch and CI context
+fn resolve_auto_env(config: &MoonflareConfig) -> Result<String> {
+    if is_pull_request()
+        && let Some(env) = &config.deploy.pull_request_env
+    {
+        println!(
+            "{}",
+            format!("Pull request detected, using environment: {}", env).yellow()
+        );
+        return Ok(env.clone());
+    }
+
+    let branch = current_branch().ok_or_else(|| {
+        anyhow::anyhow!(
+            "Could not determine the current git branch for --auto-env. Pass --env explicitly."
+        )
+    })?;
+
+    match config.deploy.environment_for_branch(&branch) {
+        Some(env) => {
+            println!(
+                "{}",
+                format!("Branch '{}' maps to environment: {}", branch, env).yellow()
+            );
+            Ok(env.to_string())
+        }
+        None => anyhow::bail!(
+            "No deploy environment is mapped to branch '{}'. Add it under [deploy.branches] in moonflare.toml or pass --env.",
+            branch
+        ),
+    }
+}
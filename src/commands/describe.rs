@@ -0,0 +1,61 @@
+use crate::ui::MoonflareUI;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{
+    WorkspaceProject, discover_projects, find_project, project_not_found,
+};
+use crate::utils::readme::{render_section, write_readme};
+use anyhow::{Result, bail};
+
+pub struct DescribeCommand {
+    ui: MoonflareUI,
+}
+
+impl DescribeCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Print the generated documentation of a project (every project when none is given), or
+    /// write it into their READMEs
+    pub async fn execute(&self, project: Option<&str>, write: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects: Vec<WorkspaceProject> = match project {
+            Some(name) => vec![find_project(name).ok_or_else(|| project_not_found(name))?],
+            None => discover_projects(),
+        };
+        if projects.is_empty() {
+            bail!("No projects to describe. Add one with 'moonflare add'.");
+        }
+
+        if !write {
+            for (index, project) in projects.iter().enumerate() {
+                if index > 0 {
+                    println!();
+                }
+                print!("{}", render_section(project)?);
+            }
+            return Ok(());
+        }
+
+        let mut updated = 0;
+        for project in &projects {
+            match write_readme(project)? {
+                Some(path) => {
+                    updated += 1;
+                    println!("  Updated {}", path.display());
+                }
+                None => println!("  {} is up to date", project.name),
+            }
+        }
+        self.ui
+            .render_success(&format!("Updated {} README(s)", updated))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
@@ -1,6 +1,62 @@
-use crate::utils::{fs::is_moonflare_workspace, moon::run_moon_command};
+use crate::utils::{
+    certs::{DevCertificate, dev_certificate_paths, ensure_dev_certificate, trust_instructions},
+    chaos::{self, Upstream},
+    config::MoonflareConfig,
+    crons::{self, ScheduledWorker},
+    e2e,
+    explain::{Explanation, Step},
+    fs::{has_crates, is_moonflare_workspace},
+    mock_api::{self, MockServer},
+    moon::{
+        StagedCommand, run_moon_command, run_moon_commands_concurrently, run_moon_commands_staged,
+        validate_task_exists,
+    },
+    node::check_project_node_version,
+    ports::{self, PortAssignments},
+    projects::{
+        ProjectFilter, WorkspaceProject, discover_projects, filtered_targets, find_project,
+    },
+    readiness::{DEFAULT_READY_TIMEOUT, ReadyCheck},
+    seeds::{self, Seed, SeedKind, SeedTarget},
+    task_env,
+    wasm_reload::{SIGNAL_ENV, SIGNAL_FILE, refresh_plugins, uses_plugin, watch_wasm},
+    wrangler_schema::check_wrangler_configs,
+};
 use anyhow::Result;
 use colored::*;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Options controlling which dev servers run and how
+#[derive(Debug, Default)]
+pub struct DevOptions {
+    pub remote: bool,
+    pub https: bool,
+    pub filter: ProjectFilter,
+    /// Moon task run instead of `dev`
+    pub task: String,
+    /// Front Worker dev servers with a proxy that injects latency, errors and dropped connections
+    pub chaos: bool,
+    /// Serve mock responses on the Workers' dev ports instead of running them
+    pub mock_api: bool,
+    /// Start from empty local Wrangler data, loaded with the projects' seeds
+    pub fresh: bool,
+    /// Start dev servers in stages, at most this many waiting to become ready at once
+    pub max_parallel: Option<usize>,
+    /// Run Workers' cron triggers on schedule, on a clock this many times faster than real time
+    pub crons: Option<u32>,
+    /// Print what would run to start the dev servers instead of starting them
+    pub explain_plan: bool,
+}
+
+/// What moonflare adds when it runs the dev servers itself instead of leaving them to Moon
+struct Supervision<'a> {
+    certificate: Option<&'a DevCertificate>,
+    wasm_reload: bool,
+    upstreams: &'a [Upstream],
+    /// Start in stages, at most this many waiting to become ready at once
+    stages: Option<usize>,
+}
 
 pub struct DevCommand {}
 
@@ -9,11 +65,236 @@ impl DevCommand {
         Self {}
     }
 
-    pub async fn execute(&self, project: Option<&str>) -> Result<()> {
+    pub async fn execute(&self, project: Option<&str>, options: &DevOptions) -> Result<()> {
         if !is_moonflare_workspace() {
             anyhow::bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
         }
 
+        check_project_node_version(project)?;
+        check_wrangler_configs(project, &options.filter).map_err(|e| *e)?;
+
+        let config = MoonflareConfig::load()?;
+        let filter = &options.filter;
+        let task = options.task.as_str();
+        if task != "dev" {
+            validate_task_exists(project, task).await?;
+        }
+
+        let mut candidates: Vec<WorkspaceProject> = match project {
+            Some(proj) => find_project(proj).into_iter().collect(),
+            None => filter
+                .apply(discover_projects())
+                .into_iter()
+                .filter(|p| p.has_task(task))
+                .collect(),
+        };
+
+        // Mocks stand in for every Worker, so only frontends' dev servers run
+        if options.mock_api {
+            if project.is_some() && candidates.iter().any(|p| p.is_worker()) {
+                anyhow::bail!(
+                    "--mock-api replaces Workers with mocks; pass a frontend project instead"
+                );
+            }
+            candidates.retain(|p| !p.is_worker());
+            if candidates.is_empty() {
+                anyhow::bail!("--mock-api needs a frontend project to serve the mocks to");
+            }
+        }
+
+        let remote: Vec<&str> = candidates
+            .iter()
+            .filter(|p| runs_remote(p, &config, options.remote))
+            .map(|p| p.name.as_str())
+            .collect();
+
+        if options.remote && remote.is_empty() {
+            println!(
+                "{}",
+                "No Worker projects selected; --remote has no effect".yellow()
+            );
+        }
+
+        let separate_env = candidates.len() > 1
+            && task_env::has_project_overrides(candidates.iter().map(|p| p.name.as_str()));
+
+        // Vite-based projects reload when crates are rebuilt, which needs moonflare's watcher
+        let wasm_reload = task == "dev" && has_crates() && candidates.iter().any(uses_plugin);
+
+        let upstreams = if options.chaos {
+            chaos::plan(&candidates, &config)?
+        } else {
+            Vec::new()
+        };
+        if options.chaos && upstreams.is_empty() {
+            println!(
+                "{}",
+                "No Worker projects selected; --chaos has no effect".yellow()
+            );
+        }
+
+        let scheduled = match options.crons {
+            Some(_) if task == "dev" => {
+                crons::plan(&candidates, &config, &upstreams, options.https)?
+            }
+            _ => Vec::new(),
+        };
+        if options.crons.is_some() && scheduled.is_empty() {
+            println!(
+                "{}",
+                "No selected Worker has cron triggers; --crons has no effect".yellow()
+            );
+        }
+
+        let stages = options.max_parallel.or(config.dev.max_parallel);
+        if stages == Some(0) {
+            anyhow::bail!("max_parallel must be at least 1");
+        }
+        let stages = stages.filter(|_| candidates.len() > 1);
+
+        if options.explain_plan {
+            let certificate = options.https.then(dev_certificate_paths);
+            let reserved = PortAssignments::load()?.remapped;
+            let supervised = !candidates.is_empty()
+                && (options.https
+                    || !remote.is_empty()
+                    || separate_env
+                    || wasm_reload
+                    || !upstreams.is_empty()
+                    || options.mock_api
+                    || options.fresh
+                    || !scheduled.is_empty()
+                    || stages.is_some()
+                    || candidates.iter().any(|p| reserved.contains_key(&p.name)));
+            let supervision = Supervision {
+                certificate: certificate.as_ref(),
+                wasm_reload,
+                upstreams: &upstreams,
+                stages,
+            };
+            return explain(
+                project,
+                &candidates,
+                &config,
+                options,
+                supervised.then_some(&supervision),
+                &scheduled,
+            );
+        }
+
+        let mocks = if options.mock_api {
+            let workers: Vec<WorkspaceProject> = discover_projects()
+                .into_iter()
+                .filter(|p| p.is_worker())
+                .collect();
+            mock_api::prepare(&workers)?
+        } else {
+            Vec::new()
+        };
+        let seed_scripts = if options.fresh {
+            load_fresh_data(&candidates)?
+        } else {
+            Vec::new()
+        };
+
+        // Dev servers whose port is taken move to the next free one, which they keep
+        let mut moved_ports = false;
+        if task == "dev" {
+            let direct: Vec<WorkspaceProject> = candidates
+                .iter()
+                .filter(|p| !upstreams.iter().any(|u| u.project == p.name))
+                .cloned()
+                .collect();
+            let proxied: Vec<u16> = upstreams.iter().map(|u| u.listen_port).collect();
+            for moved in ports::resolve_conflicts(&direct, &config, &proxied)? {
+                println!(
+                    "{}",
+                    format!(
+                        "Port {} is taken; '{}' now runs on port {} ('moonflare ports free {}' to go back)",
+                        moved.from, moved.project, moved.to, moved.project
+                    )
+                    .yellow()
+                );
+            }
+            let reserved = PortAssignments::load()?.remapped;
+            moved_ports = direct.iter().any(|p| reserved.contains_key(&p.name));
+        }
+
+        // Tell `moonflare test --e2e` where the dev servers listen
+        if task == "dev" && !candidates.is_empty() {
+            e2e::write_dev_targets(&candidates, &config, options.https)?;
+        }
+
+        if !candidates.is_empty()
+            && (options.https
+                || !remote.is_empty()
+                || separate_env
+                || wasm_reload
+                || !upstreams.is_empty()
+                || !mocks.is_empty()
+                || !seed_scripts.is_empty()
+                || !scheduled.is_empty()
+                || stages.is_some()
+                || moved_ports)
+        {
+            let certificate = if options.https {
+                Some(ensure_dev_certificate()?)
+            } else {
+                None
+            };
+
+            if !remote.is_empty() {
+                println!(
+                    "{}",
+                    format!(
+                        "Starting development servers ({} using remote Cloudflare resources)...",
+                        remote.join(", ")
+                    )
+                    .cyan()
+                    .bold()
+                );
+            }
+            if let Some(cert) = &certificate {
+                print_https_notice(cert);
+            }
+
+            if wasm_reload {
+                refresh_plugins(&candidates)?;
+                println!(
+                    "{}",
+                    "Watching crates/ for changes; Vite dev servers reload when WASM is rebuilt"
+                        .cyan()
+                );
+                tokio::spawn(watch_wasm(std::env::current_dir()?));
+            }
+
+            if !upstreams.is_empty() {
+                start_chaos_proxies(&upstreams).await?;
+            }
+            if !mocks.is_empty() {
+                start_mock_servers(&mocks).await?;
+            }
+            if !seed_scripts.is_empty() {
+                tokio::spawn(run_seed_scripts(seed_scripts));
+            }
+            if let Some(speed) = options.crons.filter(|_| !scheduled.is_empty()) {
+                start_cron_scheduler(scheduled, speed);
+            }
+
+            return run_dev_servers(
+                &candidates,
+                &config,
+                options,
+                &Supervision {
+                    certificate: certificate.as_ref(),
+                    wasm_reload,
+                    upstreams: &upstreams,
+                    stages,
+                },
+            )
+            .await;
+        }
+
         match project {
             Some(proj) => {
                 println!(
@@ -22,14 +303,467 @@ impl DevCommand {
                         .cyan()
                         .bold()
                 );
-                run_moon_command(&["run", &format!("{}:dev", proj)]).await?;
+                run_moon_command(&["run", &format!("{}:{}", proj, task)]).await?;
+            }
+            None if !filter.is_empty() => {
+                let targets = filtered_targets(filter, task)?;
+                println!(
+                    "{}",
+                    format!(
+                        "Starting {} development server(s) ({})...",
+                        targets.len(),
+                        filter.describe()
+                    )
+                    .cyan()
+                    .bold()
+                );
+
+                let mut args = vec!["run"];
+                args.extend(targets.iter().map(|t| t.as_str()));
+                run_moon_command(&args).await?;
             }
             None => {
                 println!("{}", "Starting all development servers...".cyan().bold());
-                run_moon_command(&[":dev"]).await?;
+                run_moon_command(&[&format!(":{}", task)]).await?;
             }
         }
 
         Ok(())
     }
 }
+
+/// Worker projects run `wrangler dev --remote` when requested, unless moonflare.toml overrides
+/// it with `[projects.<name>.dev] remote = ...`. Frontend dev servers always stay local.
+fn runs_remote(project: &WorkspaceProject, config: &MoonflareConfig, remote_flag: bool) -> bool {
+    project.is_worker()
+        && config
+            .project(&project.name)
+            .and_then(|p| p.dev.remote)
+            .unwrap_or(remote_flag)
+}
+
+/// Extra dev server arguments for a project: the port reserved for it in place of a taken one,
+/// which Wrangler, Vite and Astro all read from `--port`, and for Workers the other
+/// `wrangler dev` arguments. Frontend dev servers (Vite/Astro) pick up HTTPS from the
+/// MOONFLARE_HTTPS_* environment variables instead.
+fn dev_server_args(
+    project: &WorkspaceProject,
+    config: &MoonflareConfig,
+    options: &DevOptions,
+    certificate: Option<&DevCertificate>,
+    upstreams: &[Upstream],
+    reserved: &BTreeMap<String, u16>,
+) -> Vec<String> {
+    let mut args = Vec::new();
+    // The chaos proxy takes the port the dev server would use
+    if let Some(upstream) = upstreams.iter().find(|u| u.project == project.name) {
+        args.extend([
+            "--ip".to_string(),
+            "127.0.0.1".to_string(),
+            "--port".to_string(),
+            upstream.target_port.to_string(),
+        ]);
+    } else if let Some(port) = reserved.get(&project.name) {
+        args.extend(["--port".to_string(), port.to_string()]);
+    }
+    if !project.is_worker() {
+        return args;
+    }
+
+    if runs_remote(project, config, options.remote) {
+        args.push("--remote".to_string());
+    }
+    if options.crons.is_some() && crons::has_triggers(project) {
+        args.push("--test-scheduled".to_string());
+    }
+    if let Some(cert) = certificate {
+        args.extend([
+            "--local-protocol".to_string(),
+            "https".to_string(),
+            "--https-key-path".to_string(),
+            cert.key_path.display().to_string(),
+            "--https-cert-path".to_string(),
+            cert.cert_path.display().to_string(),
+        ]);
+    }
+    args
+}
+
+/// Moon passes arguments after `--` to every target in a run, so projects needing different
+/// wrangler arguments get their own Moon invocation, run side by side. Started in stages, every
+/// project gets its own.
+async fn run_dev_servers(
+    projects: &[WorkspaceProject],
+    config: &MoonflareConfig,
+    options: &DevOptions,
+    supervision: &Supervision<'_>,
+) -> Result<()> {
+    let envs = server_envs(supervision)?;
+    if let Some(max_parallel) = supervision.stages {
+        let commands = staged_commands(projects, config, options, supervision)?;
+        println!(
+            "{}",
+            format!(
+                "Starting {} dev servers, {} at a time: {}",
+                commands.len(),
+                max_parallel,
+                commands
+                    .iter()
+                    .map(|c| c.project.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" → ")
+            )
+            .cyan()
+            .bold()
+        );
+        let ready_timeout = config
+            .dev
+            .ready_timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_READY_TIMEOUT);
+        return run_moon_commands_staged(&commands, &envs, max_parallel, ready_timeout).await;
+    }
+
+    let commands = grouped_commands(projects, config, options, supervision)?;
+    run_moon_commands_concurrently(&commands, &envs).await
+}
+
+/// Variables every dev server gets: the certificate for HTTPS and the file WASM rebuilds touch
+fn server_envs(supervision: &Supervision) -> Result<Vec<(&'static str, String)>> {
+    let mut envs = match supervision.certificate {
+        Some(cert) => vec![
+            ("MOONFLARE_HTTPS_CERT", cert.cert_path.display().to_string()),
+            ("MOONFLARE_HTTPS_KEY", cert.key_path.display().to_string()),
+        ],
+        None => Vec::new(),
+    };
+    if supervision.wasm_reload {
+        let signal = std::env::current_dir()?.join(SIGNAL_FILE);
+        envs.push((SIGNAL_ENV, signal.display().to_string()));
+    }
+    Ok(envs)
+}
+
+/// One Moon command per project, in start order, with the check saying it's ready
+fn staged_commands(
+    projects: &[WorkspaceProject],
+    config: &MoonflareConfig,
+    options: &DevOptions,
+    supervision: &Supervision,
+) -> Result<Vec<StagedCommand>> {
+    let reserved = PortAssignments::load()?.remapped;
+    let upstreams = supervision.upstreams;
+    let mut commands = Vec::new();
+    for project in start_order(projects, config) {
+        let mut args = vec![
+            "run".to_string(),
+            format!("{}:{}", project.name, options.task),
+        ];
+        let passthrough = dev_server_args(
+            project,
+            config,
+            options,
+            supervision.certificate,
+            upstreams,
+            &reserved,
+        );
+        if !passthrough.is_empty() {
+            args.push("--".to_string());
+            args.extend(passthrough);
+        }
+        let mut ready =
+            ReadyCheck::for_project(project, config, supervision.certificate.is_some())?;
+        // The chaos proxy already listens on the dev port, so watch the server behind it
+        if let (ReadyCheck::Port(_), Some(upstream)) =
+            (&ready, upstreams.iter().find(|u| u.project == project.name))
+        {
+            ready = ReadyCheck::Port(upstream.target_port);
+        }
+        commands.push(StagedCommand {
+            project: project.name.clone(),
+            args,
+            ready,
+        });
+    }
+    Ok(commands)
+}
+
+/// Moon commands run side by side, one per set of projects sharing passthrough arguments.
+/// Projects with their own [projects.<name>.env] also need an invocation to themselves.
+fn grouped_commands(
+    projects: &[WorkspaceProject],
+    config: &MoonflareConfig,
+    options: &DevOptions,
+    supervision: &Supervision,
+) -> Result<Vec<Vec<String>>> {
+    let reserved = PortAssignments::load()?.remapped;
+    let mut groups: BTreeMap<(Vec<String>, Option<&str>), Vec<String>> = BTreeMap::new();
+    for project in projects {
+        let own_env = task_env::has_project_overrides([project.name.as_str()])
+            .then_some(project.name.as_str());
+        groups
+            .entry((
+                dev_server_args(
+                    project,
+                    config,
+                    options,
+                    supervision.certificate,
+                    supervision.upstreams,
+                    &reserved,
+                ),
+                own_env,
+            ))
+            .or_default()
+            .push(format!("{}:{}", project.name, options.task));
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|((passthrough, _), targets)| {
+            let mut args = vec!["run".to_string()];
+            args.extend(targets);
+            if !passthrough.is_empty() {
+                args.push("--".to_string());
+                args.extend(passthrough);
+            }
+            args
+        })
+        .collect())
+}
+
+/// The Moon commands `dev` would start, for `--explain-plan`. Proxies, mocks, seeds and the
+/// cron scheduler run inside moonflare, so they're listed after the commands.
+fn explain(
+    project: Option<&str>,
+    candidates: &[WorkspaceProject],
+    config: &MoonflareConfig,
+    options: &DevOptions,
+    supervision: Option<&Supervision>,
+    scheduled: &[ScheduledWorker],
+) -> Result<()> {
+    let task = options.task.as_str();
+    let (commands, envs) = match supervision {
+        Some(supervision) => (
+            match supervision.stages {
+                Some(_) => staged_commands(candidates, config, options, supervision)?
+                    .into_iter()
+                    .map(|command| command.args)
+                    .collect(),
+                None => grouped_commands(candidates, config, options, supervision)?,
+            },
+            server_envs(supervision)?,
+        ),
+        None => {
+            let args = match project {
+                Some(proj) => vec!["run".to_string(), format!("{}:{}", proj, task)],
+                None if !options.filter.is_empty() => {
+                    let mut args = vec!["run".to_string()];
+                    args.extend(filtered_targets(&options.filter, task)?);
+                    args
+                }
+                None => vec![format!(":{}", task)],
+            };
+            (vec![args], Vec::new())
+        }
+    };
+
+    let mut explanation = Explanation::new();
+    for args in &commands {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        explanation.step(Step::moon(&args).envs(envs.clone()));
+    }
+    if let Some(max_parallel) = supervision.and_then(|s| s.stages) {
+        explanation.note(format!(
+            "Starts the commands in order, at most {} waiting to become ready at once",
+            max_parallel
+        ));
+    } else if commands.len() > 1 {
+        explanation.note("Starts the commands side by side");
+    }
+    if options.fresh {
+        explanation.note(
+            "Clears the projects' local Wrangler data and loads their seeds/ before starting",
+        );
+    }
+    if options.mock_api {
+        explanation.note(format!(
+            "Serves each Worker's {} on its dev port in place of the Worker",
+            mock_api::MOCK_FILE
+        ));
+    }
+    for upstream in supervision.map(|s| s.upstreams).unwrap_or_default() {
+        explanation.note(format!(
+            "Proxies localhost:{} to '{}' on port {}: {}",
+            upstream.listen_port,
+            upstream.project,
+            upstream.target_port,
+            upstream.settings.describe()
+        ));
+    }
+    for worker in scheduled {
+        let expressions: Vec<&str> = worker
+            .schedules
+            .iter()
+            .map(|schedule| schedule.expression.as_str())
+            .collect();
+        explanation.note(format!(
+            "Triggers the crons of '{}' on localhost:{}: {}",
+            worker.project,
+            worker.port,
+            expressions.join(", ")
+        ));
+    }
+    if supervision.is_some_and(|s| s.wasm_reload) {
+        explanation.note("Rebuilds crates/ on change and reloads the Vite dev servers");
+    }
+    explanation.render();
+    Ok(())
+}
+
+/// Projects listed in `[dev] order` first, in that order, then Workers, which frontends
+/// usually call, then everything else
+fn start_order<'a>(
+    projects: &'a [WorkspaceProject],
+    config: &MoonflareConfig,
+) -> Vec<&'a WorkspaceProject> {
+    let mut ordered: Vec<&WorkspaceProject> = projects.iter().collect();
+    ordered.sort_by_key(|project| {
+        (
+            config
+                .dev
+                .order
+                .iter()
+                .position(|name| *name == project.name)
+                .unwrap_or(usize::MAX),
+            !project.is_worker(),
+        )
+    });
+    ordered
+}
+
+/// Bind every proxy before the dev servers start, so a taken port fails the command, then
+/// leave them serving in the background
+async fn start_chaos_proxies(upstreams: &[Upstream]) -> Result<()> {
+    println!(
+        "{}",
+        "Injecting faults into Worker traffic (--chaos)"
+            .cyan()
+            .bold()
+    );
+    for upstream in upstreams {
+        println!(
+            "  {} on localhost:{}: {}",
+            upstream.project,
+            upstream.listen_port,
+            upstream.settings.describe()
+        );
+        let listener = chaos::bind(upstream).await?;
+        tokio::spawn(chaos::serve(listener, upstream.clone()));
+    }
+    Ok(())
+}
+
+/// Clear the projects' local Wrangler data and load their SQL and KV seeds, which Wrangler
+/// writes straight to local state. Returns the seed scripts, which need the dev servers.
+fn load_fresh_data(projects: &[WorkspaceProject]) -> Result<Vec<(WorkspaceProject, Vec<Seed>)>> {
+    println!(
+        "{}",
+        "Clearing local Wrangler data and loading seeds (--fresh)..."
+            .cyan()
+            .bold()
+    );
+    let mut scripts = Vec::new();
+    for project in projects {
+        seeds::clear_local_state(project)?;
+        let (pending, data): (Vec<Seed>, Vec<Seed>) = seeds::discover(project, None)?
+            .into_iter()
+            .partition(|seed| seed.kind == SeedKind::Script);
+        seeds::run(project, &data, &SeedTarget::default())?;
+        if !pending.is_empty() {
+            scripts.push((project.clone(), pending));
+        }
+    }
+    Ok(scripts)
+}
+
+/// Run seed scripts once each project's dev server is up. Failures are reported without
+/// stopping the dev servers.
+async fn run_seed_scripts(scripts: Vec<(WorkspaceProject, Vec<Seed>)>) {
+    for (project, pending) in scripts {
+        let result = match seeds::wait_for_dev_server(&project, Duration::from_secs(60)).await {
+            Ok(()) => tokio::task::spawn_blocking(move || {
+                seeds::run(&project, &pending, &SeedTarget::default())
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.into())),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("{}", format!("Seeding failed: {}", e).yellow());
+        }
+    }
+}
+
+/// Trigger the Workers' crons in the background while the dev servers run
+fn start_cron_scheduler(workers: Vec<ScheduledWorker>, speed: u32) {
+    let pace = if speed > 1 {
+        format!(", {}x real time", speed)
+    } else {
+        String::new()
+    };
+    println!(
+        "{}",
+        format!("Running cron triggers on schedule (--crons{})", pace)
+            .cyan()
+            .bold()
+    );
+    for worker in &workers {
+        let expressions: Vec<&str> = worker
+            .schedules
+            .iter()
+            .map(|schedule| schedule.expression.as_str())
+            .collect();
+        println!(
+            "  {} on localhost:{}: {}",
+            worker.project,
+            worker.port,
+            expressions.join(", ")
+        );
+    }
+    tokio::spawn(crons::run(workers, speed));
+}
+
+async fn start_mock_servers(mocks: &[MockServer]) -> Result<()> {
+    println!(
+        "{}",
+        "Serving mock APIs in place of Workers (--mock-api)"
+            .cyan()
+            .bold()
+    );
+    for mock in mocks {
+        println!(
+            "  {} on localhost:{}: {} route(s) from {}",
+            mock.project,
+            mock.port,
+            mock.routes.len(),
+            mock_api::MOCK_FILE
+        );
+        let listeners = mock_api::bind(mock).await?;
+        tokio::spawn(mock_api::serve(listeners, mock.clone()));
+    }
+    Ok(())
+}
+
+fn print_https_notice(certificate: &DevCertificate) {
+    println!("{}", "Serving development servers over HTTPS".cyan().bold());
+    println!("  Certificate: {}", certificate.cert_path.display());
+
+    if certificate.ca_created {
+        println!(
+            "{}",
+            "Generated a new local certificate authority. Trust it once so browsers accept the certificate:"
+                .yellow()
+        );
+        println!("  {}", trust_instructions(&certificate.ca_path));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::devcontainer::{self, DEVCONTAINER_DIR};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::toolchain::Toolchain;
+use anyhow::{Result, bail};
+use colored::*;
+use std::path::Path;
+
+pub struct DevcontainerCommand {
+    ui: MoonflareUI,
+}
+
+impl DevcontainerCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Write .devcontainer/ with the workspace's toolchain preinstalled at its pinned versions
+    pub fn execute_generate(&self, force: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let config = MoonflareConfig::load()?;
+        let existing: Vec<String> = devcontainer::render(&config)?
+            .into_iter()
+            .map(|(path, _)| path)
+            .filter(|path| path.exists())
+            .map(|path| path.display().to_string())
+            .collect();
+        if !existing.is_empty() && !force {
+            bail!(
+                "Refusing to overwrite {} (use --force to regenerate)",
+                existing.join(", ")
+            );
+        }
+
+        for path in devcontainer::write(&config)? {
+            println!("  {}", path.display());
+        }
+
+        let toolchain = Toolchain::detect(Path::new("."));
+        println!();
+        println!("{}", "Preinstalled:".bold());
+        for (tool, version) in [
+            ("proto", &toolchain.proto),
+            ("node", &toolchain.node),
+            ("pnpm", &toolchain.pnpm),
+            ("moon", &toolchain.moon),
+            (
+                "rust",
+                &format!("{} (wasm32-unknown-unknown)", toolchain.rust),
+            ),
+            ("wrangler", &toolchain.wrangler),
+        ] {
+            println!("  {:<9}{}", tool, version.cyan());
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Generated {}; 'moonflare doctor --fix' keeps it in sync with toolchain changes",
+                DEVCONTAINER_DIR
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
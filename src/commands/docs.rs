@@ -0,0 +1,45 @@
+use crate::ui::MoonflareUI;
+use crate::utils::docs_site::{self, CommandDoc};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::interrupt;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use tokio::net::TcpListener;
+
+pub struct DocsCommand {
+    ui: MoonflareUI,
+}
+
+impl DocsCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Serve the command reference, error codes and each project's generated documentation on
+    /// localhost until interrupted
+    pub async fn execute_serve(&self, port: u16, commands: Vec<CommandDoc>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .with_context(|| {
+                format!("Couldn't listen on port {}; pick another with --port", port)
+            })?;
+        let url = format!("http://localhost:{}", listener.local_addr()?.port());
+        self.ui
+            .render_header("Docs", Some("Workspace, command and error documentation"))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!("Serving {} (Ctrl-C to stop)", url.cyan().bold());
+
+        let _graceful = interrupt::handle_gracefully();
+        tokio::select! {
+            _ = docs_site::serve(listener, commands) => {}
+            _ = interrupt::cancelled() => {}
+        }
+        Ok(())
+    }
+}
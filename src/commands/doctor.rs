@@ -0,0 +1,415 @@
+use crate::ui::MoonflareUI;
+use crate::utils::aliases::sync_aliases;
+use crate::utils::cargo_workspace::{CARGO_WORKSPACE_FILE, sync_cargo_workspace};
+#[cfg(feature = "api")]
+use crate::utils::cloudflare::{ACCESS_PERMISSION, CloudflareClient};
+use crate::utils::config::MoonflareConfig;
+use crate::utils::config_lint::{fix_projects, lint_projects};
+use crate::utils::devcontainer::{self, DEVCONTAINER_DIR};
+use crate::utils::diagnostics::Diagnostic;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::http;
+#[cfg(feature = "api")]
+use crate::utils::manifest::DeploymentManifest;
+use crate::utils::nix::{self, FLAKE_FILE};
+use crate::utils::node::{active_version, install_guidance, requirements, satisfies};
+use crate::utils::npmrc::{
+    HOISTING_KEYS, NPMRC_FILE, check_settings, expected_settings, parse_settings, render,
+};
+use crate::utils::package_config::package_config_issues;
+use crate::utils::projects::discover_projects;
+use crate::utils::routes::{environments, find_conflicts, route_claims};
+use crate::utils::workspace_globs::{detect_drift, sync_workspace_globs};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::fs;
+use std::path::Path;
+
+pub struct DoctorCommand {
+    ui: MoonflareUI,
+}
+
+impl DoctorCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute(&self, fix: bool, network: bool) -> Result<()> {
+        if network {
+            return self.check_network().await;
+        }
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let config = MoonflareConfig::load()?;
+        if fix {
+            write_npmrc(&config)?;
+            for drift in sync_workspace_globs(Path::new("."))? {
+                println!("Updated {}: {}", drift.source, drift.describe());
+            }
+            for path in sync_aliases()? {
+                println!("Updated aliases in {}", path.display());
+            }
+            for change in sync_cargo_workspace()? {
+                println!("Updated {}: {}", CARGO_WORKSPACE_FILE, change);
+            }
+            if !devcontainer::stale_files(&config)?.is_empty() {
+                devcontainer::write(&config)?;
+                println!("Regenerated {}", DEVCONTAINER_DIR);
+            }
+            if !nix::stale_files(&config)?.is_empty() {
+                nix::write(&config)?;
+                println!("Regenerated {}", FLAKE_FILE);
+            }
+            if let Some(snapshot) = fix_projects(&lint_projects(&discover_projects())?)? {
+                println!(
+                    "Upgraded deprecated Wrangler keys (snapshot {})",
+                    snapshot.id
+                );
+            }
+        }
+
+        let mut issues = Vec::new();
+        issues.extend(self.render_section(
+            "Checking pnpm hoisting",
+            &format!("{} matches moonflare.toml", NPMRC_FILE),
+            hoisting_issues(&config),
+        )?);
+        issues.extend(self.render_section(
+            "Checking workspace globs",
+            "Workspace globs match the project directories",
+            glob_issues()?,
+        )?);
+        issues.extend(self.render_section(
+            "Checking Node.js",
+            &match active_version() {
+                Some(version) => format!("Node.js v{} satisfies every engines.node", version),
+                None => "Node.js will be provided by Moon's toolchain".to_string(),
+            },
+            node_issues(),
+        )?);
+        issues.extend(self.render_section(
+            "Checking devcontainer and Nix flake",
+            "Generated environments match the workspace toolchain",
+            environment_issues(&config)?,
+        )?);
+        issues.extend(self.render_section(
+            "Checking Wrangler configuration",
+            "No deprecated Wrangler configuration keys",
+            deprecation_issues()?,
+        )?);
+        issues.extend(self.render_section(
+            "Checking routes",
+            "No two projects claim the same routes in any environment",
+            route_issues()?,
+        )?);
+
+        issues.extend(self.render_section(
+            "Checking package.json#moonflare",
+            "Every package.json#moonflare field is applied",
+            package_config_issues(),
+        )?);
+
+        #[cfg(feature = "api")]
+        let manifest = DeploymentManifest::load()?;
+        #[cfg(feature = "api")]
+        if !manifest.access.is_empty() {
+            issues.extend(self.render_section(
+                "Checking Cloudflare Access",
+                "Every protected project's Access application still has a policy",
+                access_issues(&manifest).await,
+            )?);
+        }
+
+        let errors = issues.iter().filter(|i| i.error).count();
+        if errors > 0 {
+            bail!("{} problem(s) found", errors);
+        }
+
+        self.ui
+            .render_success(&format!(
+                "No problems found ({} warning(s))",
+                issues.len() - errors
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Reach every endpoint moonflare and its tools need through the configured proxy and
+    /// certificates. Works outside a workspace, where only the environment applies.
+    async fn check_network(&self) -> Result<()> {
+        let network = MoonflareConfig::load().unwrap_or_default().network;
+        self.ui
+            .render_section_start("Checking network")
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!(
+            "  Proxy: {}",
+            http::proxy(&network).unwrap_or_else(|| "none".to_string())
+        );
+        println!(
+            "  Extra CA certificates: {}",
+            http::ca_bundle(&network)
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "none".to_string())
+        );
+
+        let client = http::client()?;
+        let mut unreachable = Vec::new();
+        for endpoint in http::required_endpoints() {
+            match http::probe(&client, &endpoint.url).await {
+                Ok(status) => println!(
+                    "  {} {} ({}) answered {}",
+                    "✓".green(),
+                    endpoint.name,
+                    endpoint.url,
+                    status.as_u16()
+                ),
+                Err(e) => {
+                    println!(
+                        "  {} {} ({}): {}",
+                        "✗".red(),
+                        endpoint.name,
+                        endpoint.url,
+                        e
+                    );
+                    unreachable.push(endpoint.name);
+                }
+            }
+        }
+
+        if !unreachable.is_empty() {
+            bail!("Couldn't reach {}", unreachable.join(", "));
+        }
+        self.ui
+            .render_success("Every required endpoint is reachable")
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+
+    fn render_section(
+        &self,
+        title: &str,
+        healthy: &str,
+        issues: Vec<Diagnostic>,
+    ) -> Result<Vec<Diagnostic>> {
+        self.ui
+            .render_section_start(title)
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        if issues.is_empty() {
+            println!("  {} {}", "✓".green(), healthy);
+        }
+        for issue in &issues {
+            if issue.error {
+                println!("  {} {}", "✗".red(), issue.message);
+            } else {
+                println!("  {} {}", "⚠".yellow(), issue.message);
+            }
+        }
+        Ok(issues)
+    }
+}
+
+/// Rewrite the hoisting settings in the workspace `.npmrc` from moonflare.toml
+pub fn write_npmrc(config: &MoonflareConfig) -> Result<()> {
+    write_npmrc_in(Path::new("."), config)
+}
+
+/// Rewrite the hoisting settings in `<root>/.npmrc`, keeping any other settings
+pub fn write_npmrc_in(root: &Path, config: &MoonflareConfig) -> Result<()> {
+    let path = root.join(NPMRC_FILE);
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let content = render(&existing, &expected_settings(config));
+
+    if content.is_empty() && existing.is_empty() {
+        return Ok(());
+    }
+    fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Projects outside every glob are never built, so they're errors; globs for deleted
+/// directories only add noise
+fn glob_issues() -> Result<Vec<Diagnostic>> {
+    let mut issues = Vec::new();
+    for drift in detect_drift(Path::new("."))? {
+        for glob in &drift.missing {
+            issues.push(Diagnostic::error(format!(
+                "{} is missing '{}', so projects there are ignored; run 'moonflare doctor --fix'",
+                drift.source, glob
+            )));
+        }
+        for glob in &drift.stale {
+            issues.push(Diagnostic::warning(format!(
+                "{} lists '{}' but that directory no longer exists; run 'moonflare doctor --fix'",
+                drift.source, glob
+            )));
+        }
+    }
+    Ok(issues)
+}
+
+/// An unsupported Node.js makes Vite and wrangler fail with confusing errors
+fn node_issues() -> Vec<Diagnostic> {
+    let Some(active) = active_version() else {
+        return Vec::new();
+    };
+
+    let mut sources: Vec<Option<&Path>> = vec![None];
+    let projects = discover_projects();
+    sources.extend(projects.iter().map(|p| Some(p.path.as_path())));
+
+    let mut unmet = Vec::new();
+    for source in sources {
+        for requirement in requirements(source) {
+            if !satisfies(active, &requirement.range) && !unmet.contains(&requirement) {
+                unmet.push(requirement);
+            }
+        }
+    }
+
+    unmet
+        .into_iter()
+        .map(|requirement| {
+            Diagnostic::error(format!(
+                "Node.js v{} doesn't satisfy \"node\": \"{}\" in {}. {}",
+                active,
+                requirement.range,
+                requirement.source,
+                install_guidance()
+            ))
+        })
+        .collect()
+}
+
+/// A devcontainer or flake pinning old versions provides a different toolchain than the
+/// workspace uses
+fn environment_issues(config: &MoonflareConfig) -> Result<Vec<Diagnostic>> {
+    let mut stale = devcontainer::stale_files(config)?;
+    stale.extend(nix::stale_files(config)?);
+    Ok(stale
+        .into_iter()
+        .map(|path| {
+            Diagnostic::warning(format!(
+                "{} is out of date with the toolchain versions or projects; run 'moonflare doctor --fix'",
+                path.display()
+            ))
+        })
+        .collect())
+}
+
+/// Keys Wrangler 4 rejects fail every build and deploy; the rest are ignored or superseded
+fn deprecation_issues() -> Result<Vec<Diagnostic>> {
+    let mut issues = Vec::new();
+    for lint in lint_projects(&discover_projects())? {
+        for deprecation in &lint.deprecations {
+            let message = format!(
+                "{}: {}{}",
+                lint.path.display(),
+                deprecation.describe(),
+                if deprecation.fixable {
+                    "; run 'moonflare config lint --fix'"
+                } else {
+                    ""
+                }
+            );
+            issues.push(if deprecation.rejected {
+                Diagnostic::error(message)
+            } else {
+                Diagnostic::warning(message)
+            });
+        }
+    }
+    Ok(issues)
+}
+
+/// Overlapping routes let one Worker silently take another's traffic
+fn route_issues() -> Result<Vec<Diagnostic>> {
+    let projects = discover_projects();
+    let mut scopes = vec![None];
+    scopes.extend(environments(&projects)?.into_iter().map(Some));
+
+    let mut issues = Vec::new();
+    for environment in scopes {
+        for conflict in find_conflicts(&route_claims(&projects, environment.as_deref())?) {
+            issues.push(Diagnostic::error(format!(
+                "{}: {} claims {} and {} claims {} ({})",
+                environment.as_deref().unwrap_or("default environment"),
+                conflict.first.project,
+                conflict.first.pattern,
+                conflict.second.project,
+                conflict.second.pattern,
+                conflict.reason
+            )));
+        }
+    }
+    Ok(issues)
+}
+
+/// Access applications recorded by `moonflare access protect` that were deleted or lost their
+/// policies, which leaves the project either unreachable or open
+#[cfg(feature = "api")]
+async fn access_issues(manifest: &DeploymentManifest) -> Vec<Diagnostic> {
+    let mut issues = Vec::new();
+    for (project, record) in &manifest.access {
+        let client = match CloudflareClient::from_env(Some(&record.account_id), ACCESS_PERMISSION) {
+            Ok(client) => client,
+            Err(e) => {
+                issues.push(Diagnostic::warning(format!(
+                    "Skipped checking Access for {}: {}",
+                    project, e
+                )));
+                continue;
+            }
+        };
+        match client.access_policies(&record.app_id).await {
+            Ok(policies) if policies.is_empty() => issues.push(Diagnostic::error(format!(
+                "{}: Access application {} on {} has no policies; run 'moonflare access protect {} --policy {}' after deleting it",
+                project, record.app_id, record.domain, project, record.policy
+            ))),
+            Ok(_) => {}
+            Err(e) => issues.push(Diagnostic::error(format!(
+                "{}: couldn't read Access application {}: {}",
+                project, record.app_id, e
+            ))),
+        }
+    }
+    issues
+}
+
+fn hoisting_issues(config: &MoonflareConfig) -> Vec<Diagnostic> {
+    let content = fs::read_to_string(NPMRC_FILE).unwrap_or_default();
+    let actual = parse_settings(&content);
+    let mut issues = check_settings(&actual);
+
+    if actual != expected_settings(config) {
+        issues.push(Diagnostic::warning(format!(
+            "{} hoisting settings differ from moonflare.toml [pnpm]; run 'moonflare doctor --fix'",
+            NPMRC_FILE
+        )));
+    }
+
+    for project in discover_projects() {
+        let Ok(content) = fs::read_to_string(project.path.join(NPMRC_FILE)) else {
+            continue;
+        };
+        let ignored: Vec<&str> = HOISTING_KEYS
+            .into_iter()
+            .filter(|key| parse_settings(&content).contains_key(*key))
+            .collect();
+        if !ignored.is_empty() {
+            issues.push(Diagnostic::warning(format!(
+                "{}/{} sets {}, which pnpm ignores outside the workspace root; use [projects.{}.pnpm] in moonflare.toml",
+                project.relative_path(),
+                NPMRC_FILE,
+                ignored.join(", "),
+                project.name
+            )));
+        }
+    }
+
+    issues
+}
@@ -0,0 +1,285 @@
+use crate::ui::MoonflareUI;
+#[cfg(not(feature = "api"))]
+use crate::utils::cloudflare::api_unavailable;
+#[cfg(feature = "api")]
+use crate::utils::cloudflare::{CloudflareClient, SCRIPTS_PERMISSION};
+use crate::utils::durable_objects::{defined_classes, find_local_object, local_objects};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{
+    WorkspaceProject, discover_projects, find_project, project_not_found,
+};
+use crate::utils::retention::format_bytes;
+#[cfg(feature = "api")]
+use crate::utils::wrangler::env_worker_name;
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use serde::Serialize;
+use std::process::Command;
+
+/// Objects listed per namespace before the rest are counted
+const LISTED_OBJECTS: usize = 20;
+
+/// A deployed Durable Object namespace and the objects in it
+#[derive(Debug, Serialize)]
+struct NamespaceListing {
+    project: String,
+    worker: String,
+    class: String,
+    namespace_id: String,
+    sqlite: bool,
+    objects: Vec<ObjectListing>,
+}
+
+#[derive(Debug, Serialize)]
+struct ObjectListing {
+    id: String,
+    has_stored_data: bool,
+}
+
+pub struct DurableObjectsCommand {
+    ui: MoonflareUI,
+}
+
+impl DurableObjectsCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Durable Object namespaces of the workspace's Workers and the objects in them, from the
+    /// Cloudflare API, or from `wrangler dev`'s local state with `local`
+    pub async fn execute_list(
+        &self,
+        project: Option<&str>,
+        env: Option<&str>,
+        local: bool,
+        json: bool,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let projects = projects_with_classes(project)?;
+
+        if local {
+            let objects: Vec<_> = projects
+                .iter()
+                .flat_map(|(project, _)| local_objects(project))
+                .collect();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&objects)?);
+                return Ok(());
+            }
+            self.ui
+                .render_header("Durable Objects", Some("Local state from 'moonflare dev'"))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+            if objects.is_empty() {
+                println!(
+                    "No local Durable Object state; run 'moonflare dev' and send requests first"
+                );
+                return Ok(());
+            }
+            for object in &objects {
+                println!(
+                    "  {:<16} {:<24} {} {}",
+                    object.project,
+                    object.class,
+                    object.id,
+                    format_bytes(object.size).dimmed()
+                );
+            }
+            println!();
+            println!(
+                "{}",
+                "Open one with 'moonflare do inspect <project> --object <ID>'".dimmed()
+            );
+            return Ok(());
+        }
+
+        if projects.is_empty() {
+            println!("No project defines Durable Object classes");
+            return Ok(());
+        }
+        let listings = deployed_namespaces(&projects, env).await?;
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&listings)?);
+            return Ok(());
+        }
+        self.ui
+            .render_header(
+                "Durable Objects",
+                Some(&format!("Deployed to {}", env.unwrap_or("production"))),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if listings.is_empty() {
+            println!("No deployed Durable Object namespaces; deploy the Workers first");
+            return Ok(());
+        }
+        for listing in &listings {
+            println!(
+                "{} {} {}",
+                listing.project.bold(),
+                listing.class,
+                format!(
+                    "({}, {}, {} object(s))",
+                    listing.worker,
+                    if listing.sqlite {
+                        "SQLite"
+                    } else {
+                        "key-value"
+                    },
+                    listing.objects.len()
+                )
+                .dimmed()
+            );
+            for object in listing.objects.iter().take(LISTED_OBJECTS) {
+                let data = if object.has_stored_data {
+                    "stored data".green()
+                } else {
+                    "empty".dimmed()
+                };
+                println!("  {} {}", object.id, data);
+            }
+            if listing.objects.len() > LISTED_OBJECTS {
+                println!(
+                    "  {}",
+                    format!(
+                        "... and {} more; --json lists every object",
+                        listing.objects.len() - LISTED_OBJECTS
+                    )
+                    .dimmed()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Open a SQLite shell on one object's local state, or run `sql` against it. The database is
+    /// opened read-only unless `write` is set, since `wrangler dev` may have it open too.
+    pub async fn execute_inspect(
+        &self,
+        project: &str,
+        class: Option<&str>,
+        object: Option<&str>,
+        sql: Option<&str>,
+        write: bool,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+        let objects = local_objects(&project_info);
+        let object = find_local_object(&objects, project, class, object)?;
+        let sqlite = which::which("sqlite3").context(
+            "Install the sqlite3 CLI to inspect local Durable Object state (https://sqlite.org/download.html)",
+        )?;
+
+        let mut command = Command::new(sqlite);
+        if !write {
+            command.arg("-readonly");
+        }
+        if sql.is_some() {
+            command.args(["-header", "-column"]);
+        }
+        command.arg(&object.path);
+        if let Some(sql) = sql {
+            command.arg(sql);
+        } else {
+            println!(
+                "{}",
+                format!(
+                    "{} {} ({}); key-value storage is in the _cf_KV table. .tables lists tables, .quit exits.",
+                    object.class,
+                    object.id,
+                    object.path.display()
+                )
+                .dimmed()
+            );
+        }
+        let status = command.status().context("Failed to run sqlite3")?;
+        if !status.success() {
+            bail!("sqlite3 exited with {}", status);
+        }
+        Ok(())
+    }
+}
+
+/// Deployed namespaces of the projects' Durable Object classes and the objects in them
+#[cfg(feature = "api")]
+async fn deployed_namespaces(
+    projects: &[(WorkspaceProject, serde_json::Value)],
+    env: Option<&str>,
+) -> Result<Vec<NamespaceListing>> {
+    let account_id = projects
+        .first()
+        .and_then(|(_, config)| config["account_id"].as_str());
+    let client = CloudflareClient::from_env(account_id, SCRIPTS_PERMISSION)?;
+    let namespaces = client.durable_object_namespaces().await?;
+    let mut listings = Vec::new();
+    for (project, config) in projects {
+        let worker = env_worker_name(config, &project.name, env);
+        let classes = defined_classes(config);
+        for namespace in &namespaces {
+            let class = namespace.class.clone().unwrap_or_default();
+            if namespace.script.as_deref() != Some(worker.as_str()) || !classes.contains(&class) {
+                continue;
+            }
+            let objects = client
+                .durable_objects(&namespace.id)
+                .await?
+                .into_iter()
+                .map(|object| ObjectListing {
+                    id: object.id,
+                    has_stored_data: object.has_stored_data,
+                })
+                .collect();
+            listings.push(NamespaceListing {
+                project: project.name.clone(),
+                worker: worker.clone(),
+                class,
+                namespace_id: namespace.id.clone(),
+                sqlite: namespace.use_sqlite,
+                objects,
+            });
+        }
+    }
+    Ok(listings)
+}
+
+#[cfg(not(feature = "api"))]
+async fn deployed_namespaces(
+    _projects: &[(WorkspaceProject, serde_json::Value)],
+    _env: Option<&str>,
+) -> Result<Vec<NamespaceListing>> {
+    Err(api_unavailable())
+}
+
+/// The project, or every project, whose Wrangler config defines Durable Object classes
+fn projects_with_classes(
+    project: Option<&str>,
+) -> Result<Vec<(WorkspaceProject, serde_json::Value)>> {
+    let projects = match project {
+        Some(name) => vec![find_project(name).ok_or_else(|| project_not_found(name))?],
+        None => discover_projects()
+            .into_iter()
+            .filter(|project| project.has_wrangler_config())
+            .collect(),
+    };
+    let mut with_classes = Vec::new();
+    for project in projects {
+        let Some(config) = read_wrangler_config(&project.path)? else {
+            continue;
+        };
+        if !defined_classes(&config).is_empty() {
+            with_classes.push((project, config));
+        }
+    }
+    if let Some(name) = project
+        && with_classes.is_empty()
+    {
+        bail!("'{}' doesn't define any Durable Object classes", name);
+    }
+    Ok(with_classes)
+}
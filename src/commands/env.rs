@@ -0,0 +1,425 @@
+use crate::ui::MoonflareUI;
+use crate::utils::backup::create_snapshot;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::dev_vars::{
+    DEV_VARS_EXAMPLE_FILE, DEV_VARS_FILE, parse_dev_vars, render_dev_vars, render_example,
+    required_vars,
+};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{
+    WorkspaceProject, discover_projects, find_project, project_not_found,
+};
+use crate::utils::readme::refresh_readmes;
+use crate::utils::secrets::{DEVELOPMENT_ENV, PRODUCTION_ENV, provider_for};
+use crate::utils::task_env;
+use crate::utils::wrangler::{
+    add_env_bindings, env_vars, find_wrangler_config, missing_env_bindings, read_wrangler_config,
+    write_wrangler_config,
+};
+use crate::utils::wrangler_defaults::effective_config;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::process::Command;
+
+pub struct EnvCommand {
+    ui: MoonflareUI,
+}
+
+impl EnvCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Write `.dev.vars.example` for each Worker project
+    pub async fn execute_example(&self, project: Option<&str>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        for project in worker_projects(project)? {
+            let vars = required_vars(&project.path)?;
+            fs::write(
+                project.path.join(DEV_VARS_EXAMPLE_FILE),
+                render_example(&vars),
+            )?;
+            println!(
+                "  {}  {} variable(s)",
+                project.path.join(DEV_VARS_EXAMPLE_FILE).display(),
+                vars.len()
+            );
+        }
+
+        self.ui
+            .render_success("Generated .dev.vars.example files")
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Create or update `.dev.vars` from the example, prompting for values that are still missing
+    pub async fn execute_pull(&self, project: Option<&str>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let interactive = std::io::stdin().is_terminal();
+        let provider = provider_for(&MoonflareConfig::load()?.secrets);
+        let mut missing = Vec::new();
+
+        for project in worker_projects(project)? {
+            let example_path = project.path.join(DEV_VARS_EXAMPLE_FILE);
+            let example = if example_path.exists() {
+                fs::read_to_string(&example_path)?
+            } else {
+                let content = render_example(&required_vars(&project.path)?);
+                fs::write(&example_path, &content)?;
+                content
+            };
+
+            let dev_vars_path = project.path.join(DEV_VARS_FILE);
+            let mut values = match fs::read_to_string(&dev_vars_path) {
+                Ok(content) => parse_dev_vars(&content),
+                Err(_) => Default::default(),
+            };
+
+            let needed: Vec<(String, String)> = parse_dev_vars(&example)
+                .into_iter()
+                .filter(|(key, _)| values.get(key).is_none_or(|v| v.is_empty()))
+                .collect();
+
+            // Secrets come from the configured provider before falling back to a prompt
+            let provided = match &provider {
+                Some(provider) if needed.iter().any(|(_, default)| default.is_empty()) => {
+                    let provided = provider
+                        .fetch(&project.name, DEVELOPMENT_ENV)
+                        .with_context(|| {
+                            format!(
+                                "Failed to read secrets for '{}' from {}",
+                                project.name,
+                                provider.name()
+                            )
+                        })?;
+                    let count = needed
+                        .iter()
+                        .filter(|(key, default)| default.is_empty() && provided.contains_key(key))
+                        .count();
+                    println!(
+                        "  {} {} secret(s) from {}",
+                        project.name.cyan(),
+                        count,
+                        provider.name()
+                    );
+                    provided
+                }
+                _ => Default::default(),
+            };
+
+            for (key, default) in needed {
+                let value = if !default.is_empty() {
+                    default
+                } else if let Some(value) = provided.get(&key) {
+                    value.clone()
+                } else if interactive {
+                    prompt(&format!("{} {}", project.name, key))?
+                } else {
+                    String::new()
+                };
+
+                if value.is_empty() {
+                    missing.push(format!("{}: {}", project.name, key));
+                }
+                values.insert(key, value);
+            }
+
+            fs::write(&dev_vars_path, render_dev_vars(&values))?;
+            println!("  {}", dev_vars_path.display());
+        }
+
+        if !missing.is_empty() {
+            println!(
+                "{}",
+                "These values are still empty; edit .dev.vars before running dev:".yellow()
+            );
+            for entry in &missing {
+                println!("  {}", entry);
+            }
+        }
+
+        self.ui
+            .render_success("Updated .dev.vars files")
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Copy top-level vars and bindings into `[env.<environment>]`, since Wrangler environments
+    /// don't inherit them
+    pub async fn execute_sync(&self, project: &str, environment: &str) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let project = worker_projects(Some(project))?.remove(0);
+        let path = find_wrangler_config(&project.path)
+            .with_context(|| format!("Project '{}' has no Wrangler configuration", project.name))?;
+        let Some(mut config) = read_wrangler_config(&project.path)? else {
+            bail!("Project '{}' has no Wrangler configuration", project.name);
+        };
+
+        let missing = missing_env_bindings(&config, environment);
+        if missing.is_empty() {
+            self.ui
+                .render_success(&format!(
+                    "[env.{}] already declares every top-level var and binding",
+                    environment
+                ))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+            return Ok(());
+        }
+
+        let snapshot = create_snapshot(
+            &format!("env sync {} {}", project.name, environment),
+            &[&path],
+        )?;
+        println!("Saved snapshot {}", snapshot.id);
+
+        add_env_bindings(&mut config, environment, &missing);
+        write_wrangler_config(&path, &config)?;
+        for binding in &missing {
+            println!("  {} {}", binding.key.dimmed(), binding.name);
+        }
+        refresh_readmes([&project]);
+
+        self.ui
+            .render_success(&format!(
+                "Copied {} var(s) and binding(s) into [env.{}] of {}",
+                missing.len(),
+                environment,
+                path.display()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!(
+            "{}",
+            "Copied bindings point at the same resources as the top level; change their IDs to give the environment its own."
+                .yellow()
+        );
+        if path.extension().is_some_and(|ext| ext == "jsonc") {
+            println!(
+                "{}",
+                format!(
+                    "Comments in {} were not preserved; 'moonflare restore {}' brings them back.",
+                    path.display(),
+                    snapshot.id
+                )
+                .yellow()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Compare the var and secret names two environments of a Worker define, failing when
+    /// either has keys the other lacks
+    pub async fn execute_diff(&self, project: &str, from: &str, to: &str) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let project = worker_projects(Some(project))?.remove(0);
+        let config = effective_config(&project.path)?.unwrap_or_default();
+        let environments = [from, to];
+
+        let mut keys: Vec<BTreeMap<String, KeyKind>> = Vec::new();
+        let mut compared_secrets = true;
+        for environment in environments {
+            let env = wrangler_env(&config, environment)?;
+            let mut env_keys: BTreeMap<String, KeyKind> = env_vars(&config, env)
+                .into_keys()
+                .map(|name| (name, KeyKind::Var))
+                .collect();
+            match secret_names(&project, environment, env) {
+                Ok(secrets) => {
+                    env_keys.extend(secrets.into_iter().map(|name| (name, KeyKind::Secret)))
+                }
+                Err(e) => {
+                    compared_secrets = false;
+                    println!(
+                        "{}",
+                        format!(
+                            "Comparing vars only; couldn't list {}'s secrets: {}",
+                            environment, e
+                        )
+                        .yellow()
+                    );
+                }
+            }
+            keys.push(env_keys);
+        }
+        if !compared_secrets {
+            keys.iter_mut()
+                .for_each(|env_keys| env_keys.retain(|_, kind| *kind == KeyKind::Var));
+        }
+
+        let names: BTreeSet<&String> = keys.iter().flat_map(|env_keys| env_keys.keys()).collect();
+        let mut differences = 0;
+        println!("{}: {} ↔ {}", project.name.bold(), from, to);
+        for name in &names {
+            match (keys[0].get(*name), keys[1].get(*name)) {
+                (Some(a), Some(b)) if a == b => {}
+                (Some(a), Some(b)) => {
+                    differences += 1;
+                    println!(
+                        "  {} {} is a {} in {} but a {} in {}",
+                        "~".yellow(),
+                        name,
+                        a,
+                        from,
+                        b,
+                        to
+                    );
+                }
+                (Some(kind), None) | (None, Some(kind)) => {
+                    differences += 1;
+                    let (present, missing) = if keys[0].contains_key(*name) {
+                        (from, to)
+                    } else {
+                        (to, from)
+                    };
+                    println!(
+                        "  {} {} ({}) is set in {} but missing from {}",
+                        "-".red(),
+                        name,
+                        kind,
+                        present,
+                        missing
+                    );
+                }
+                (None, None) => {}
+            }
+        }
+
+        let matching = names.len() - differences;
+        if differences > 0 {
+            bail!(
+                "{} key(s) differ between {} and {} ({} match)",
+                differences,
+                from,
+                to,
+                matching
+            );
+        }
+        self.ui
+            .render_success(&format!(
+                "{} and {} define the same {} key(s)",
+                from, to, matching
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Whether a key is a plain-text var or a secret, in one environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyKind {
+    Var,
+    Secret,
+}
+
+impl std::fmt::Display for KeyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            KeyKind::Var => "var",
+            KeyKind::Secret => "secret",
+        })
+    }
+}
+
+/// The `--env` Wrangler knows an environment by: `production` is the top level unless the
+/// configuration declares an `[env.production]` of its own
+fn wrangler_env<'a>(config: &Value, environment: &'a str) -> Result<Option<&'a str>> {
+    let declared = config
+        .get("env")
+        .and_then(|envs| envs.get(environment))
+        .is_some();
+    match environment {
+        PRODUCTION_ENV if !declared => Ok(None),
+        _ if declared => Ok(Some(environment)),
+        _ => bail!(
+            "The Wrangler configuration has no [env.{}]; use {} for the top level",
+            environment,
+            PRODUCTION_ENV
+        ),
+    }
+}
+
+/// Names of the secrets a Worker has in an environment: from the configured provider when
+/// there is one, otherwise from `wrangler secret list`. Values are never read into output.
+fn secret_names(
+    project: &WorkspaceProject,
+    environment: &str,
+    env: Option<&str>,
+) -> Result<BTreeSet<String>> {
+    if let Some(provider) = provider_for(&MoonflareConfig::load()?.secrets) {
+        return Ok(provider
+            .fetch(&project.name, environment)
+            .with_context(|| format!("Failed to read secrets from {}", provider.name()))?
+            .into_keys()
+            .collect());
+    }
+
+    let mut command = Command::new("pnpm");
+    command.args(["exec", "wrangler", "secret", "list"]);
+    if let Some(env) = env {
+        command.args(["--env", env]);
+    }
+    let output = command
+        .envs(task_env::vars(Some(&project.name)))
+        .current_dir(&project.path)
+        .output()
+        .context("Failed to run wrangler secret list")?;
+    if !output.status.success() {
+        bail!(
+            "wrangler secret list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let secrets: Value =
+        serde_json::from_slice(&output.stdout).context("wrangler secret list didn't print JSON")?;
+    Ok(secrets
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|secret| secret["name"].as_str().map(String::from))
+        .collect())
+}
+
+fn worker_projects(project: Option<&str>) -> Result<Vec<WorkspaceProject>> {
+    match project {
+        Some(name) => {
+            let project = find_project(name).ok_or_else(|| project_not_found(name))?;
+            if !project.has_wrangler_config() {
+                bail!("Project '{}' has no Wrangler configuration", name);
+            }
+            Ok(vec![project])
+        }
+        None => Ok(discover_projects()
+            .into_iter()
+            .filter(|p| p.has_wrangler_config())
+            .collect()),
+    }
+}
+
+fn prompt(label: &str) -> Result<String> {
+    print!("{}: ", label);
+    std::io::stdout().flush()?;
+
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
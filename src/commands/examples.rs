@@ -0,0 +1,213 @@
+use crate::templates::{
+    embedded,
+    engine::{TemplateEngine, write_files},
+};
+use crate::ui::MoonflareUI;
+use crate::utils::aliases::sync_aliases;
+use crate::utils::cargo_workspace::{CARGO_WORKSPACE_FILE, sync_cargo_workspace};
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::{get_project_directory, is_moonflare_workspace};
+use crate::utils::node::NODE_ENGINE;
+use crate::utils::projects::{WorkspaceProject, similar_names};
+use crate::utils::readme::write_readme;
+use crate::utils::workspace_globs::sync_workspace_globs;
+use anyhow::{Result, bail};
+use colored::*;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A runnable multi-file example, scaffolded into the workspace as one or more projects
+pub struct Example {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// `(project type, project name)`; the type picks the layout directory
+    pub projects: &'static [(&'static str, &'static str)],
+    /// Files of every project, under `FILE:<project>/...` sections
+    pub template: &'static str,
+    /// Commands to run before the first deploy, rendered with the same context as the template
+    pub setup: &'static [&'static str],
+}
+
+pub const EXAMPLES: [Example; 3] = [
+    Example {
+        name: "chat",
+        description: "Chat rooms over WebSockets, one Durable Object per room, with a web front end",
+        projects: &[("durable-object", "chat-api"), ("react", "chat-web")],
+        template: embedded::EXAMPLE_CHAT_TEMPLATE,
+        setup: &[],
+    },
+    Example {
+        name: "image-resizer",
+        description: "Resize images stored in R2 on the fly with the Images binding",
+        projects: &[("durable-object", "image-resizer")],
+        template: embedded::EXAMPLE_IMAGE_RESIZER_TEMPLATE,
+        setup: &[
+            "pnpm --dir {{lookup dirs \"image-resizer\"}} wrangler r2 bucket create {{lookup workers \"image-resizer\"}}-originals",
+        ],
+    },
+    Example {
+        name: "rag",
+        description: "Answer questions from your documents with Workers AI and Vectorize",
+        projects: &[("durable-object", "rag-api")],
+        template: embedded::EXAMPLE_RAG_TEMPLATE,
+        setup: &[
+            "pnpm --dir {{lookup dirs \"rag-api\"}} wrangler vectorize create {{lookup workers \"rag-api\"}}-index --dimensions=768 --metric=cosine",
+        ],
+    },
+];
+
+pub struct ExamplesCommand {
+    ui: MoonflareUI,
+}
+
+impl ExamplesCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub fn execute_list(&self) -> Result<()> {
+        let width = EXAMPLES.iter().map(|e| e.name.len()).max().unwrap_or(0);
+        for example in &EXAMPLES {
+            let projects: Vec<&str> = example.projects.iter().map(|(_, name)| *name).collect();
+            println!(
+                "{:width$}  {} ({})",
+                example.name.bold(),
+                example.description,
+                projects.join(", "),
+                width = width
+            );
+        }
+        println!();
+        println!("Add one with 'moonflare examples add <example>'");
+        Ok(())
+    }
+
+    /// Write the example's projects into the workspace's layout directories, wired to each
+    /// other and named like any other project
+    pub fn execute_add(&self, name: &str) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let example = find_example(name)?;
+
+        let targets: Vec<(&str, PathBuf)> = example
+            .projects
+            .iter()
+            .map(|(project_type, project)| {
+                (
+                    *project,
+                    Path::new(&get_project_directory(project_type)).join(project),
+                )
+            })
+            .collect();
+        if let Some((_, existing)) = targets.iter().find(|(_, path)| path.exists()) {
+            bail!(
+                "{} already exists; remove or rename it before adding the '{}' example",
+                existing.display(),
+                example.name
+            );
+        }
+
+        let context = example_context(&targets)?;
+        let engine = TemplateEngine::new();
+        let mut files = Vec::new();
+        for (path, content) in engine.render_template_to_memory(example.template, &context)? {
+            let mut components = path.components();
+            let project = components
+                .next()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .unwrap_or_default();
+            let Some((_, target)) = targets.iter().find(|(name, _)| *name == project) else {
+                bail!("Example file {} is outside its projects", path.display());
+            };
+            files.push((target.join(components.as_path()), content));
+        }
+        write_files(&files)?;
+
+        for (project, path) in &targets {
+            write_readme(&WorkspaceProject {
+                name: project.to_string(),
+                path: path.clone(),
+            })?;
+        }
+        for drift in sync_workspace_globs(Path::new("."))? {
+            println!("Updated {}: {}", drift.source, drift.describe());
+        }
+        for path in sync_aliases()? {
+            println!("Updated aliases in {}", path.display());
+        }
+        for change in sync_cargo_workspace()? {
+            println!("Updated {}: {}", CARGO_WORKSPACE_FILE, change);
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Added the '{}' example: {}",
+                example.name,
+                targets
+                    .iter()
+                    .map(|(_, path)| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        println!("Next steps:");
+        println!("  pnpm install");
+        for step in example.setup {
+            println!("  {}", engine.render_template(step, &context)?);
+        }
+        let projects: Vec<&str> = targets.iter().map(|(name, _)| *name).collect();
+        println!("  moonflare dev {}", projects.last().unwrap_or(&""));
+        println!("  moonflare deploy {}", projects.join(" "));
+        Ok(())
+    }
+}
+
+fn find_example(name: &str) -> Result<&'static Example> {
+    if let Some(example) = EXAMPLES.iter().find(|e| e.name == name) {
+        return Ok(example);
+    }
+    let suggestions = similar_names(name, EXAMPLES.iter().map(|e| e.name));
+    match suggestions.first() {
+        Some(suggestion) => bail!(
+            "Unknown example '{}'; did you mean '{}'? Run 'moonflare examples list' to see them all.",
+            name,
+            suggestion
+        ),
+        None => bail!(
+            "Unknown example '{}'. Run 'moonflare examples list' to see them all.",
+            name
+        ),
+    }
+}
+
+/// Worker names follow `[workspace] worker_name`, so service bindings between the example's
+/// projects point at the names they'll deploy under
+fn example_context(targets: &[(&str, PathBuf)]) -> Result<HashMap<String, Value>> {
+    let config = MoonflareConfig::load()?;
+    let mut workers = Map::new();
+    let mut dirs = Map::new();
+    for (project, path) in targets {
+        workers.insert(
+            project.to_string(),
+            Value::String(config.worker_name(project)),
+        );
+        dirs.insert(
+            project.to_string(),
+            Value::String(path.to_string_lossy().replace('\\', "/")),
+        );
+    }
+
+    let mut context = HashMap::new();
+    context.insert(
+        "node_engine".to_string(),
+        Value::String(NODE_ENGINE.to_string()),
+    );
+    context.insert("workers".to_string(), Value::Object(workers));
+    context.insert("dirs".to_string(), Value::Object(dirs));
+    Ok(context)
+}
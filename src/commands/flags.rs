@@ -0,0 +1,164 @@
+use crate::templates::{embedded, engine::TemplateEngine};
+use crate::ui::MoonflareUI;
+use crate::utils::aliases::sync_aliases;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::flags::{
+    FLAGS_BINDING, FLAGS_FILE, FLAGS_PROJECT, PLACEHOLDER_NAMESPACE_ID, STARTER_FLAGS,
+    check_namespace, read_flags, write_client,
+};
+use crate::utils::fs::{get_project_directory, is_moonflare_workspace};
+use crate::utils::node::NODE_ENGINE;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::readme::write_readme;
+use crate::utils::workspace_globs::sync_workspace_globs;
+use crate::utils::{retry, task_env};
+use anyhow::{Result, bail};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub struct FlagsCommand {
+    ui: MoonflareUI,
+}
+
+fn worker_dir() -> PathBuf {
+    Path::new(&get_project_directory("durable-object")).join(FLAGS_PROJECT)
+}
+
+impl FlagsCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Write flags.toml and the flags worker when they're missing, and regenerate the typed
+    /// client from flags.toml. Safe to run again after editing the flags.
+    pub fn execute_init(&self) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        if !Path::new(FLAGS_FILE).exists() {
+            fs::write(FLAGS_FILE, STARTER_FLAGS)?;
+            println!("Created {}", FLAGS_FILE);
+        }
+        let flags = read_flags()?;
+        if write_client(&flags)? {
+            println!("Generated the typed client in packages/flags");
+        }
+
+        let worker = worker_dir();
+        let created = !worker.exists();
+        if created {
+            let config = MoonflareConfig::load()?;
+            let mut context = HashMap::new();
+            context.insert("name".to_string(), Value::String(FLAGS_PROJECT.to_string()));
+            context.insert(
+                "worker_name".to_string(),
+                Value::String(config.worker_name(FLAGS_PROJECT)),
+            );
+            context.insert(
+                "node_engine".to_string(),
+                Value::String(NODE_ENGINE.to_string()),
+            );
+            context.insert(
+                "namespace_placeholder".to_string(),
+                Value::String(PLACEHOLDER_NAMESPACE_ID.to_string()),
+            );
+            TemplateEngine::new().process_template_files(
+                embedded::FLAGS_WORKER_TEMPLATE,
+                &worker,
+                &context,
+            )?;
+            write_readme(&WorkspaceProject {
+                name: FLAGS_PROJECT.to_string(),
+                path: worker.clone(),
+            })?;
+            println!("Created the flags worker in {}", worker.display());
+        }
+
+        for drift in sync_workspace_globs(Path::new("."))? {
+            println!("Updated {}: {}", drift.source, drift.describe());
+        }
+        for path in sync_aliases()? {
+            println!("Updated aliases in {}", path.display());
+        }
+
+        self.ui
+            .render_success(&format!(
+                "{} flag(s) ready; import them with `import {{ createFlagsClient }} from \"@packages/flags\"`",
+                flags.flags.len()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if created {
+            println!("Next steps:");
+            println!(
+                "  pnpm --dir {} wrangler kv namespace create {}",
+                worker.display(),
+                FLAGS_BINDING
+            );
+            println!(
+                "  Put the namespace id in {}/wrangler.toml",
+                worker.display()
+            );
+            println!("  moonflare deploy {}", FLAGS_PROJECT);
+        }
+        Ok(())
+    }
+
+    /// Store a flag's value in the flags worker's KV namespace
+    pub async fn execute_set(
+        &self,
+        flag: &str,
+        value: &str,
+        env: Option<&str>,
+        local: bool,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let flags = read_flags()?;
+        let value = flags.get(flag)?.parse_value(flag, value)?;
+
+        let worker = worker_dir();
+        if !worker.exists() {
+            bail!(
+                "There's no flags worker in {}; run 'moonflare flags init' first",
+                worker.display()
+            );
+        }
+        if !local {
+            check_namespace(&worker, env)?;
+        }
+
+        let key = format!("flag:{}", flag);
+        let json = value.to_string();
+        let build = || {
+            let mut command = Command::new("pnpm");
+            command
+                .args(["exec", "wrangler", "kv", "key", "put", &key, &json])
+                .args(["--binding", FLAGS_BINDING])
+                .arg(if local { "--local" } else { "--remote" })
+                .current_dir(&worker)
+                .envs(task_env::vars(Some(FLAGS_PROJECT)));
+            if let Some(env) = env {
+                command.args(["--env", env]);
+            }
+            command
+        };
+        retry::command("wrangler kv key put", build, None).await?;
+
+        let target = match (env, local) {
+            (_, true) => "local".to_string(),
+            (Some(env), false) => env.to_string(),
+            (None, false) => "production".to_string(),
+        };
+        self.ui
+            .render_success(&format!("Set {} to {} ({})", flag, json, target))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+use crate::utils::config::LayoutConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{
+    GENERATED_DIRS, ProjectFilter, WorkspaceProject, discover_projects, find_project,
+    project_not_found,
+};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use grep::matcher::Matcher;
+use grep::regex::{RegexMatcher, RegexMatcherBuilder};
+use grep::searcher::sinks::Lossy;
+use grep::searcher::{BinaryDetection, Searcher, SearcherBuilder};
+use serde::Serialize;
+use walkdir::WalkDir;
+
+/// Kind of project to search, by the layout directory it lives in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProjectKind {
+    /// Astro sites
+    Site,
+    /// React apps
+    App,
+    /// Workers and Durable Objects
+    Worker,
+    /// Rust crates
+    Crate,
+}
+
+impl ProjectKind {
+    fn matches(self, project: &WorkspaceProject, layout: &LayoutConfig) -> bool {
+        let directory = match self {
+            ProjectKind::Site => &layout.sites,
+            ProjectKind::App => &layout.apps,
+            ProjectKind::Worker => &layout.workers,
+            ProjectKind::Crate => &layout.crates,
+        };
+        project.path.starts_with(directory)
+    }
+}
+
+/// What to search for and where
+#[derive(Debug, Default)]
+pub struct GrepOptions {
+    pub filter: ProjectFilter,
+    pub kinds: Vec<ProjectKind>,
+    pub ignore_case: bool,
+    /// Treat the pattern as a literal string instead of a regular expression
+    pub fixed_strings: bool,
+    pub json: bool,
+}
+
+/// A matching line
+#[derive(Debug, Serialize)]
+struct Match {
+    project: String,
+    /// Relative to the project directory
+    path: String,
+    line: u64,
+    text: String,
+}
+
+pub struct GrepCommand {}
+
+impl GrepCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Search the source files of the selected projects, printing matches grouped by project
+    pub async fn execute(
+        &self,
+        pattern: &str,
+        project: Option<&str>,
+        options: &GrepOptions,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let matcher = RegexMatcherBuilder::new()
+            .case_insensitive(options.ignore_case)
+            .fixed_strings(options.fixed_strings)
+            .build(pattern)
+            .with_context(|| format!("Invalid pattern '{}'", pattern))?;
+
+        let layout = LayoutConfig::current();
+        let projects: Vec<WorkspaceProject> = match project {
+            Some(name) => vec![find_project(name).ok_or_else(|| project_not_found(name))?],
+            None => options.filter.apply(discover_projects()),
+        }
+        .into_iter()
+        .filter(|p| options.kinds.is_empty() || options.kinds.iter().any(|k| k.matches(p, &layout)))
+        .collect();
+
+        let mut searcher = SearcherBuilder::new()
+            .binary_detection(BinaryDetection::quit(b'\x00'))
+            .line_number(true)
+            .build();
+        let mut matches = Vec::new();
+        for project in &projects {
+            matches.extend(search_project(&mut searcher, &matcher, project));
+        }
+
+        if options.json {
+            println!("{}", serde_json::to_string_pretty(&matches)?);
+            return Ok(());
+        }
+        if matches.is_empty() {
+            println!("No matches in {} project(s)", projects.len());
+            return Ok(());
+        }
+
+        let mut files = 0;
+        for project in &projects {
+            let project_matches: Vec<&Match> = matches
+                .iter()
+                .filter(|m| m.project == project.name)
+                .collect();
+            if project_matches.is_empty() {
+                continue;
+            }
+            println!(
+                "{}  {}",
+                project.name.cyan().bold(),
+                project.relative_path().dimmed()
+            );
+            let mut previous_path = "";
+            for m in project_matches {
+                if m.path != previous_path {
+                    files += 1;
+                    println!("  {}", m.path.magenta());
+                    previous_path = &m.path;
+                }
+                println!(
+                    "    {}: {}",
+                    m.line.to_string().green(),
+                    highlight(&matcher, &m.text)
+                );
+            }
+            println!();
+        }
+        let project_count = projects
+            .iter()
+            .filter(|p| matches.iter().any(|m| m.project == p.name))
+            .count();
+        println!(
+            "{} match(es) in {} file(s) across {} project(s)",
+            matches.len(),
+            files,
+            project_count
+        );
+        Ok(())
+    }
+}
+
+fn search_project(
+    searcher: &mut Searcher,
+    matcher: &RegexMatcher,
+    project: &WorkspaceProject,
+) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let entries = WalkDir::new(&project.path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            !(entry.file_type().is_dir()
+                && entry.depth() > 0
+                && GENERATED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file());
+
+    for entry in entries {
+        let relative = entry
+            .path()
+            .strip_prefix(&project.path)
+            .unwrap_or(entry.path())
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        let result = searcher.search_path(
+            matcher,
+            entry.path(),
+            Lossy(|line, text| {
+                matches.push(Match {
+                    project: project.name.clone(),
+                    path: relative.clone(),
+                    line,
+                    text: text.trim_end().to_string(),
+                });
+                Ok(true)
+            }),
+        );
+        // Unreadable files are skipped, as ripgrep does
+        if let Err(e) = result {
+            eprintln!(
+                "{}",
+                format!("Skipping {}: {}", entry.path().display(), e).yellow()
+            );
+        }
+    }
+    matches
+}
+
+/// The line with every match highlighted
+fn highlight(matcher: &RegexMatcher, text: &str) -> String {
+    let mut output = String::new();
+    let mut last = 0;
+    let _ = matcher.find_iter(text.as_bytes(), |m| {
+        // Matches always fall on character boundaries of the UTF-8 text
+        output.push_str(&text[last..m.start()]);
+        output.push_str(&text[m.start()..m.end()].red().bold().to_string());
+        last = m.end();
+        true
+    });
+    output.push_str(&text[last..]);
+    output
+}
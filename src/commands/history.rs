@@ -0,0 +1,305 @@
+use crate::utils::build_log::read_builds;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::history::{
+    HISTORY_FILE, HistoryEntry, USAGE_FILE, UsageEntry, parse_since, read_history, read_usage,
+};
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Which history entries to show
+#[derive(Debug, Default)]
+pub struct HistoryFilter {
+    /// Entries whose command starts with this (`tag` matches `tag add` and `tag remove`)
+    pub command: Option<String>,
+    pub user: Option<String>,
+    pub since: Option<String>,
+    pub failed: bool,
+    pub limit: usize,
+}
+
+/// Runs, failures and timings of one command or project
+#[derive(Debug, Default, Serialize)]
+struct UsageStats {
+    name: String,
+    runs: usize,
+    failures: usize,
+    average_secs: f64,
+    max_secs: f64,
+}
+
+impl UsageStats {
+    fn failure_rate(&self) -> f64 {
+        self.failures as f64 / self.runs.max(1) as f64
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SlowBuild {
+    timestamp: String,
+    duration_secs: f64,
+    success: bool,
+    targets: Vec<String>,
+}
+
+/// What `moonflare history stats` reports
+#[derive(Debug, Serialize)]
+struct StatsReport {
+    runs: usize,
+    users: usize,
+    /// Most run first
+    commands: Vec<UsageStats>,
+    /// Projects named in the commands' arguments, highest failure rate first
+    projects: Vec<UsageStats>,
+    /// Slowest first
+    slowest_builds: Vec<SlowBuild>,
+}
+
+pub struct HistoryCommand {}
+
+impl HistoryCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub async fn execute(&self, filter: &HistoryFilter, json: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let since = filter
+            .since
+            .as_deref()
+            .map(parse_since)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Invalid --since: {}", e))?;
+
+        let mut entries: Vec<HistoryEntry> = read_history()?
+            .into_iter()
+            .filter(|entry| {
+                filter.command.as_deref().is_none_or(|command| {
+                    entry.command == command || entry.command.starts_with(&format!("{} ", command))
+                })
+            })
+            .filter(|entry| filter.user.as_deref().is_none_or(|user| entry.user == user))
+            .filter(|entry| !filter.failed || !entry.success)
+            .filter(|entry| since.is_none_or(|since| entry.time().is_some_and(|t| t >= since)))
+            .collect();
+        // Keep the most recent entries, still listed oldest first
+        if filter.limit > 0 && entries.len() > filter.limit {
+            entries.drain(..entries.len() - filter.limit);
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+            return Ok(());
+        }
+
+        if entries.is_empty() {
+            println!("No matching operations in {}", HISTORY_FILE);
+            return Ok(());
+        }
+
+        println!(
+            "  {:<20} {:<16} {:<18} {:<8} ARGS",
+            "TIME", "USER", "COMMAND", "OUTCOME"
+        );
+        for entry in &entries {
+            let time = entry
+                .time()
+                .map(|t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                })
+                .unwrap_or_else(|| entry.timestamp.clone());
+            let outcome = if entry.success {
+                format!("{:<8}", "ok").green()
+            } else {
+                format!("{:<8}", "failed").red()
+            };
+            println!(
+                "  {:<20} {:<16} {:<18} {} {}",
+                time,
+                entry.user,
+                entry.command,
+                outcome,
+                entry.args.join(" ")
+            );
+            if let Some(error) = &entry.error {
+                println!("  {:<20} {}", "", error.dimmed());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Aggregate the usage log into the most-run commands, failure rates per project and the
+    /// slowest builds
+    pub async fn execute_stats(&self, since: Option<&str>, top: usize, json: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let since = since
+            .map(parse_since)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Invalid --since: {}", e))?;
+        let after = |time: Option<DateTime<Utc>>| {
+            since.is_none_or(|since| time.is_some_and(|time| time >= since))
+        };
+
+        let usage: Vec<UsageEntry> = read_usage()?
+            .into_iter()
+            .filter(|entry| after(entry.time()))
+            .collect();
+        let mut slowest_builds: Vec<SlowBuild> = read_builds()
+            .into_iter()
+            .filter(|build| {
+                after(
+                    DateTime::parse_from_rfc3339(&build.timestamp)
+                        .ok()
+                        .map(|time| time.with_timezone(&Utc)),
+                )
+            })
+            .map(|build| SlowBuild {
+                timestamp: build.timestamp,
+                duration_secs: build.duration_secs,
+                success: build.success,
+                targets: build.tasks.into_iter().map(|task| task.target).collect(),
+            })
+            .collect();
+        slowest_builds.sort_by(|a, b| b.duration_secs.total_cmp(&a.duration_secs));
+        slowest_builds.truncate(top);
+
+        let mut commands = aggregate(usage.iter().map(|entry| (entry.command.as_str(), entry)));
+        commands.sort_by(|a, b| b.runs.cmp(&a.runs).then(a.name.cmp(&b.name)));
+        commands.truncate(top);
+        let mut projects = aggregate(usage.iter().flat_map(|entry| {
+            entry
+                .projects
+                .iter()
+                .map(move |project| (project.as_str(), entry))
+        }));
+        projects.sort_by(|a, b| {
+            b.failure_rate()
+                .total_cmp(&a.failure_rate())
+                .then(b.runs.cmp(&a.runs))
+                .then(a.name.cmp(&b.name))
+        });
+        projects.truncate(top);
+
+        let report = StatsReport {
+            runs: usage.len(),
+            users: usage
+                .iter()
+                .map(|entry| entry.user.as_str())
+                .collect::<BTreeSet<_>>()
+                .len(),
+            commands,
+            projects,
+            slowest_builds,
+        };
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+        if report.runs == 0 && report.slowest_builds.is_empty() {
+            println!("No command runs recorded in {} yet", USAGE_FILE);
+            return Ok(());
+        }
+        render_stats(&report);
+        Ok(())
+    }
+}
+
+/// Stats per name, from `(name, entry)` pairs
+fn aggregate<'a>(entries: impl Iterator<Item = (&'a str, &'a UsageEntry)>) -> Vec<UsageStats> {
+    let mut stats: BTreeMap<&str, (UsageStats, f64)> = BTreeMap::new();
+    for (name, entry) in entries {
+        let (stat, total) = stats.entry(name).or_default();
+        stat.runs += 1;
+        if !entry.success {
+            stat.failures += 1;
+        }
+        stat.max_secs = stat.max_secs.max(entry.duration_secs);
+        *total += entry.duration_secs;
+    }
+    stats
+        .into_iter()
+        .map(|(name, (stat, total))| UsageStats {
+            name: name.to_string(),
+            average_secs: total / stat.runs as f64,
+            ..stat
+        })
+        .collect()
+}
+
+fn render_stats(report: &StatsReport) {
+    println!(
+        "{} run(s) by {} user(s)",
+        report.runs.to_string().bold(),
+        report.users
+    );
+
+    println!();
+    println!("{}", "Most-run commands".bold());
+    println!(
+        "  {:<22} {:>6} {:>8} {:>9} {:>9}",
+        "COMMAND", "RUNS", "FAILED", "AVERAGE", "SLOWEST"
+    );
+    for command in &report.commands {
+        println!(
+            "  {:<22} {:>6} {:>8} {:>8.1}s {:>8.1}s",
+            command.name, command.runs, command.failures, command.average_secs, command.max_secs
+        );
+    }
+
+    println!();
+    println!("{}", "Projects".bold());
+    if report.projects.is_empty() {
+        println!("  No commands named a project");
+    }
+    for project in &report.projects {
+        let rate = format!("{:>5.0}% failed", project.failure_rate() * 100.0);
+        println!(
+            "  {:<22} {:>6} run(s) {} {:>8.1}s average",
+            project.name,
+            project.runs,
+            if project.failures > 0 {
+                rate.red()
+            } else {
+                rate.normal()
+            },
+            project.average_secs
+        );
+    }
+
+    println!();
+    println!("{}", "Slowest builds".bold());
+    if report.slowest_builds.is_empty() {
+        println!("  No builds recorded yet");
+    }
+    for build in &report.slowest_builds {
+        let time = DateTime::parse_from_rfc3339(&build.timestamp)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| build.timestamp.clone());
+        let outcome = if build.success {
+            "ok".green()
+        } else {
+            "failed".red()
+        };
+        println!(
+            "  {:<17} {:>8.1}s {:<6} {}",
+            time,
+            build.duration_secs,
+            outcome,
+            build.targets.join(", ").dimmed()
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::{HookManager, MoonflareConfig};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::plan::{self, Plan};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use serde_yaml::{Mapping, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// At the top of every file moonflare writes, so only those are replaced or removed
+const MARKER: &str = "# Managed by moonflare; remove with 'moonflare hooks uninstall'";
+
+/// Lefthook's configuration, at the repository root
+const LEFTHOOK_FILE: &str = "lefthook.yml";
+
+/// Where the workspace sits in its git repository
+struct Repository {
+    root: PathBuf,
+    hooks_dir: PathBuf,
+    /// Workspace directory relative to the root, with a trailing `/`; empty at the root
+    prefix: String,
+}
+
+pub struct HooksCommand {
+    ui: MoonflareUI,
+}
+
+impl HooksCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Write git hooks running the `[hooks]` commands, as plain scripts or a lefthook.yml
+    pub async fn execute_install(&self, manager: Option<HookManager>, force: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let config = MoonflareConfig::load()?;
+        let manager = manager.unwrap_or(config.hooks.manager);
+        let repository = repository()?;
+        let hooks: Vec<(&str, &[String])> = [
+            ("pre-commit", config.hooks.pre_commit.as_slice()),
+            ("pre-push", config.hooks.pre_push.as_slice()),
+        ]
+        .into_iter()
+        .filter(|(_, commands)| !commands.is_empty())
+        .collect();
+        if hooks.is_empty() {
+            bail!("[hooks] in moonflare.toml has no pre_commit or pre_push commands");
+        }
+
+        self.ui
+            .render_header("Installing git hooks", None)
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        match manager {
+            HookManager::Git => {
+                let lefthook = repository.root.join(LEFTHOOK_FILE);
+                if is_managed(&lefthook) {
+                    run_lefthook(&repository, "uninstall");
+                    fs::remove_file(&lefthook)?;
+                }
+                for hook in ["pre-commit", "pre-push"] {
+                    let path = repository.hooks_dir.join(hook);
+                    match hooks.iter().find(|(name, _)| *name == hook) {
+                        Some((_, commands)) => {
+                            if path.exists() && !is_managed(&path) && !force {
+                                bail!(
+                                    "{} already exists and wasn't written by moonflare; pass --force to replace it",
+                                    path.display()
+                                );
+                            }
+                            write_hook_script(&path, &repository.prefix, commands)?;
+                        }
+                        // A hook whose commands were removed from moonflare.toml
+                        None if is_managed(&path) => fs::remove_file(&path)?,
+                        None => {}
+                    }
+                }
+            }
+            HookManager::Lefthook => {
+                let path = repository.root.join(LEFTHOOK_FILE);
+                if path.exists() && !is_managed(&path) && !force {
+                    bail!(
+                        "{} already exists and wasn't written by moonflare; pass --force to replace it",
+                        path.display()
+                    );
+                }
+                for hook in ["pre-commit", "pre-push"] {
+                    let script = repository.hooks_dir.join(hook);
+                    if is_managed(&script) {
+                        fs::remove_file(&script)?;
+                    }
+                }
+                fs::write(&path, render_lefthook(&repository.prefix, &hooks)?)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                if !run_lefthook(&repository, "install") {
+                    println!(
+                        "{}",
+                        format!(
+                            "Wrote {}; run 'lefthook install' to activate it",
+                            path.display()
+                        )
+                        .yellow()
+                    );
+                }
+            }
+        }
+
+        for (hook, commands) in &hooks {
+            println!("  {}", hook.bold());
+            for command in *commands {
+                println!("    {}", command);
+            }
+        }
+        self.ui
+            .render_success(&format!(
+                "Installed {} hook(s) with {}",
+                hooks.len(),
+                match manager {
+                    HookManager::Git => "git",
+                    HookManager::Lefthook => "lefthook",
+                }
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!(
+            "{}",
+            "Rerun after changing [hooks] in moonflare.toml; skip the hooks once with 'git commit --no-verify'".dimmed()
+        );
+        Ok(())
+    }
+
+    /// Remove the hook scripts and lefthook.yml moonflare wrote, leaving any others
+    pub async fn execute_uninstall(&self) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let repository = repository()?;
+
+        let mut plan = Plan::new();
+        for hook in ["pre-commit", "pre-push"] {
+            let path = repository.hooks_dir.join(hook);
+            if is_managed(&path) {
+                plan.delete(path);
+            }
+        }
+        let lefthook = repository.root.join(LEFTHOOK_FILE);
+        let uses_lefthook = is_managed(&lefthook);
+        if uses_lefthook {
+            plan.run("lefthook uninstall");
+            plan.delete(lefthook);
+        }
+
+        if plan.is_empty() {
+            println!("No hooks installed by moonflare");
+            return Ok(());
+        }
+        plan.render();
+        if plan::plan_only() {
+            return Ok(());
+        }
+        if !plan.confirm(false)? {
+            println!("Uninstall cancelled");
+            return Ok(());
+        }
+        if uses_lefthook {
+            run_lefthook(&repository, "uninstall");
+        }
+        plan.apply()?;
+
+        let removed: Vec<String> = plan
+            .paths()
+            .iter()
+            .filter_map(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect();
+        self.ui
+            .render_success(&format!("Removed {}", removed.join(", ")))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+}
+
+fn repository() -> Result<Repository> {
+    let git = |args: &[&str]| -> Option<String> {
+        let output = Command::new("git").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let root = git(&["rev-parse", "--show-toplevel"])
+        .context("The workspace isn't in a git repository; run 'git init' first")?;
+    // Relative to the current directory, and honors core.hooksPath
+    let hooks_dir =
+        git(&["rev-parse", "--git-path", "hooks"]).context("Failed to find git hooks")?;
+    let hooks_dir = std::env::current_dir()?.join(hooks_dir);
+    fs::create_dir_all(&hooks_dir)
+        .with_context(|| format!("Failed to create {}", hooks_dir.display()))?;
+
+    Ok(Repository {
+        root: PathBuf::from(root),
+        hooks_dir,
+        prefix: git(&["rev-parse", "--show-prefix"]).unwrap_or_default(),
+    })
+}
+
+fn is_managed(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.lines().take(2).any(|l| l == MARKER))
+}
+
+/// A hook that stops at the first failing command
+fn write_hook_script(path: &Path, prefix: &str, commands: &[String]) -> Result<()> {
+    let mut script = format!(
+        "#!/bin/sh\n{}\nset -e\ncd \"$(git rev-parse --show-toplevel)/{}\"\n",
+        MARKER, prefix
+    );
+    for command in commands {
+        script.push_str(command);
+        script.push('\n');
+    }
+    fs::write(path, script).with_context(|| format!("Failed to write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+/// Each hook runs its commands in order (`piped`), stopping at the first failure
+fn render_lefthook(prefix: &str, hooks: &[(&str, &[String])]) -> Result<String> {
+    let mut config = Mapping::new();
+    for (hook, commands) in hooks {
+        let mut entries = Mapping::new();
+        for (index, command) in commands.iter().enumerate() {
+            let mut entry = Mapping::new();
+            entry.insert("run".into(), command.as_str().into());
+            if !prefix.is_empty() {
+                entry.insert("root".into(), prefix.into());
+            }
+            // lefthook runs piped commands sorted by name
+            entries.insert(format!("{:02}", index + 1).into(), Value::Mapping(entry));
+        }
+        let mut section = Mapping::new();
+        section.insert("piped".into(), true.into());
+        section.insert("commands".into(), Value::Mapping(entries));
+        config.insert((*hook).into(), Value::Mapping(section));
+    }
+    Ok(format!("{}\n{}", MARKER, serde_yaml::to_string(&config)?))
+}
+
+/// Run `lefthook <command>` at the repository root; false when lefthook isn't installed
+fn run_lefthook(repository: &Repository, command: &str) -> bool {
+    ["lefthook", "pnpm"].iter().any(|program| {
+        let mut lefthook = Command::new(program);
+        if *program == "pnpm" {
+            lefthook.args(["exec", "lefthook"]);
+        }
+        lefthook
+            .arg(command)
+            .current_dir(&repository.root)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
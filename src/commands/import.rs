@@ -0,0 +1,743 @@
+use crate::ui::MoonflareUI;
+use crate::utils::aliases::sync_aliases;
+use crate::utils::cargo_workspace::{CARGO_WORKSPACE_FILE, sync_cargo_workspace};
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::{
+    add_crate_build_dependency_to_shared_wasm, add_wasm_dependency_to_project,
+    get_project_directory, has_crates, is_moonflare_workspace,
+};
+use crate::utils::node::NODE_ENGINE;
+use crate::utils::workspace_globs::sync_workspace_globs;
+use crate::utils::wrangler::{
+    DEPLOY_SCRIPT, WRANGLER_CONFIG_FILES, find_wrangler_config, set_config_name,
+};
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Installed dependencies, build output and VCS metadata, left behind when copying a starter
+const SKIPPED_DIRECTORIES: [&str; 7] = [
+    ".git",
+    "node_modules",
+    "target",
+    "dist",
+    ".wrangler",
+    ".astro",
+    "pkg",
+];
+
+/// Lockfiles and workspace files the moonflare workspace's own pnpm setup replaces
+const REPLACED_FILES: [&str; 5] = [
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "pnpm-workspace.yaml",
+    "bun.lockb",
+];
+
+/// Files only cargo-generate leaves behind, in the output or the template it came from
+const CARGO_GENERATE_MARKERS: [&str; 4] = [
+    "cargo-generate.toml",
+    ".cargo-generate",
+    ".genignore",
+    ".cargo-ok",
+];
+
+/// Where moonflare's tasks, stats and license notices expect build output
+const OUT_DIR: &str = "dist";
+
+/// The starter a directory was generated from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Archetype {
+    CreateVite,
+    CreateAstro,
+    CreateCloudflare,
+    CargoGenerate,
+    Generic,
+}
+
+impl Archetype {
+    pub fn label(self) -> &'static str {
+        match self {
+            Archetype::CreateVite => "create-vite",
+            Archetype::CreateAstro => "create-astro",
+            Archetype::CreateCloudflare => "create-cloudflare (C3)",
+            Archetype::CargoGenerate => "cargo-generate",
+            Archetype::Generic => "generic",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Detection {
+    pub archetype: Archetype,
+    pub project_type: &'static str,
+    /// Wrangler configuration relative to the project root, when there is one
+    pub wrangler_config: Option<PathBuf>,
+}
+
+pub struct ImportCommand {
+    ui: MoonflareUI,
+}
+
+impl ImportCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Copy a project generated by a popular starter into the workspace and map its layout
+    /// onto moonflare's task conventions
+    pub async fn execute(
+        &self,
+        source: &str,
+        name: Option<&str>,
+        project_type: Option<&str>,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let source_path = Path::new(source);
+        if !source_path.is_dir() {
+            bail!("'{}' isn't a directory", source);
+        }
+        let source_path = source_path
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", source))?;
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => source_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .context("Can't derive a project name from the source; pass --name")?
+                .to_string(),
+        };
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!(
+                "'{}' isn't a valid project name; use letters, digits, '-' and '_' (or pass --name)",
+                name
+            );
+        }
+
+        let mut detection = detect(&source_path)?;
+        if let Some(project_type) = project_type {
+            detection.project_type = match project_type {
+                "astro" => "astro",
+                "react" => "react",
+                "durable-object" | "worker" => "durable-object",
+                "crate" => "crate",
+                other => bail!(
+                    "Unknown project type '{}'. Expected astro, react, durable-object or crate",
+                    other
+                ),
+            };
+        }
+
+        let project_dir = get_project_directory(detection.project_type);
+        let target_path = Path::new(&project_dir).join(&name);
+        if target_path.exists() {
+            bail!("'{}' already exists", target_path.display());
+        }
+
+        self.ui
+            .render_header(
+                "Importing project",
+                Some(&format!(
+                    "Detected a {} {} project; importing as '{}'",
+                    detection.archetype.label(),
+                    detection.project_type,
+                    name
+                )),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        copy_project(&source_path, &target_path)?;
+        println!(
+            "Copied {} to {}",
+            source_path.display(),
+            target_path.display()
+        );
+
+        let worker_name = MoonflareConfig::load()?.worker_name(&name);
+        let changes = if detection.project_type == "crate" {
+            adapt_crate(&target_path, &name)?
+        } else {
+            adapt_typescript_project(&target_path, &name, &worker_name, &detection)?
+        };
+        for change in &changes {
+            println!("  {}", change);
+        }
+
+        if detection.project_type == "crate" {
+            add_crate_build_dependency_to_shared_wasm(&name)?;
+        } else if has_crates() {
+            add_wasm_dependency_to_project(&target_path)?;
+        }
+
+        for drift in sync_workspace_globs(Path::new("."))? {
+            println!("Updated {}: {}", drift.source, drift.describe());
+        }
+        for path in sync_aliases()? {
+            println!("Updated aliases in {}", path.display());
+        }
+        for change in sync_cargo_workspace()? {
+            println!("Updated {}: {}", CARGO_WORKSPACE_FILE, change);
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Imported {} as {} project '{}'",
+                source_path.display(),
+                detection.project_type,
+                name
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        self.ui
+            .render_next_steps_for_project(&name, detection.project_type)
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
+
+/// Work out which starter produced `root` from the files it's known to generate
+pub fn detect(root: &Path) -> Result<Detection> {
+    let package = read_package_json(root)?;
+    let wrangler_config = find_nested_wrangler_config(root);
+
+    let Some(package) = package else {
+        if !root.join("Cargo.toml").is_file() {
+            bail!(
+                "'{}' has neither a package.json nor a Cargo.toml to import",
+                root.display()
+            );
+        }
+        if wrangler_config.is_some() {
+            bail!(
+                "'{}' is a Rust Worker (workers-rs), which moonflare doesn't manage; import its library code as a crate instead",
+                root.display()
+            );
+        }
+        let archetype = if CARGO_GENERATE_MARKERS
+            .iter()
+            .any(|marker| root.join(marker).exists())
+            || is_wasm_pack_template(root)
+        {
+            Archetype::CargoGenerate
+        } else {
+            Archetype::Generic
+        };
+        return Ok(Detection {
+            archetype,
+            project_type: "crate",
+            wrangler_config: None,
+        });
+    };
+
+    let has_dependency = |dependency: &str| {
+        ["dependencies", "devDependencies"]
+            .iter()
+            .any(|section| package[section].get(dependency).is_some())
+    };
+    let has_script = |script: &str| package["scripts"].get(script).is_some();
+    let react = has_dependency("react");
+    let astro = has_dependency("astro") && find_config(root, "astro.config").is_some();
+    let vite = has_dependency("vite")
+        && find_config(root, "vite.config").is_some()
+        && root.join("index.html").is_file();
+
+    // C3 adds a `cf-typegen` script to every template, framework or not
+    let (archetype, project_type) = if wrangler_config.is_some() && has_script("cf-typegen") {
+        let project_type = if astro {
+            "astro"
+        } else if vite && react {
+            "react"
+        } else {
+            "durable-object"
+        };
+        (Archetype::CreateCloudflare, project_type)
+    } else if astro {
+        (Archetype::CreateAstro, "astro")
+    } else if vite {
+        (Archetype::CreateVite, "react")
+    } else if wrangler_config.is_some() {
+        (Archetype::Generic, "durable-object")
+    } else if has_dependency("astro") {
+        (Archetype::Generic, "astro")
+    } else {
+        (Archetype::Generic, "react")
+    };
+
+    Ok(Detection {
+        archetype,
+        project_type,
+        wrangler_config,
+    })
+}
+
+/// rustwasm's wasm-pack-template, the most common cargo-generate starter for WASM crates
+fn is_wasm_pack_template(root: &Path) -> bool {
+    fs::read_to_string(root.join("src/utils.rs"))
+        .is_ok_and(|utils| utils.contains("set_panic_hook"))
+}
+
+fn read_package_json(root: &Path) -> Result<Option<Value>> {
+    let path = root.join("package.json");
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)?;
+    let package = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(package))
+}
+
+/// `<stem>.mjs`, `.ts`, `.js` or `.mts` in the project root
+fn find_config(root: &Path, stem: &str) -> Option<PathBuf> {
+    ["mjs", "ts", "js", "mts", "cjs"]
+        .iter()
+        .map(|ext| root.join(format!("{}.{}", stem, ext)))
+        .find(|path| path.is_file())
+}
+
+/// The Wrangler configuration in the root or, for starters that keep the Worker apart from the
+/// frontend, one directory down
+fn find_nested_wrangler_config(root: &Path) -> Option<PathBuf> {
+    if let Some(config) = find_wrangler_config(root) {
+        return config.strip_prefix(root).ok().map(Path::to_path_buf);
+    }
+    let mut directories: Vec<PathBuf> = fs::read_dir(root)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && !path
+                    .file_name()
+                    .is_some_and(|n| SKIPPED_DIRECTORIES.iter().any(|s| n == *s))
+        })
+        .collect();
+    directories.sort();
+    directories.iter().find_map(|directory| {
+        WRANGLER_CONFIG_FILES
+            .iter()
+            .map(|file| directory.join(file))
+            .find(|path| path.is_file())
+            .and_then(|path| path.strip_prefix(root).ok().map(Path::to_path_buf))
+    })
+}
+
+fn copy_project(source: &Path, target: &Path) -> Result<()> {
+    let walker = WalkDir::new(source).into_iter().filter_entry(|entry| {
+        !(entry.file_type().is_dir()
+            && SKIPPED_DIRECTORIES
+                .iter()
+                .any(|skipped| entry.file_name() == *skipped))
+    });
+    for entry in walker {
+        let entry = entry?;
+        let destination = target.join(entry.path().strip_prefix(source)?);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&destination)?;
+        } else {
+            fs::copy(entry.path(), &destination)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Fit a frontend or Worker starter to moonflare's conventions: Wrangler config in the project
+/// root, build output in `dist/`, the standard dev/build/deploy scripts and a moon.yml
+pub fn adapt_typescript_project(
+    root: &Path,
+    name: &str,
+    worker_name: &str,
+    detection: &Detection,
+) -> Result<Vec<String>> {
+    let mut changes = remove_replaced_files(root)?;
+
+    // Wrangler only looks in the project root, so a config kept beside the Worker code moves up
+    // and its paths are rebased
+    let mut wrangler_config = detection.wrangler_config.as_ref().map(|c| root.join(c));
+    if let Some(relative) = &detection.wrangler_config
+        && let Some(parent) = relative.parent().filter(|p| !p.as_os_str().is_empty())
+    {
+        let from = root.join(relative);
+        let to = root.join(relative.file_name().unwrap_or_default());
+        let content = fs::read_to_string(&from)?;
+        fs::write(&to, rebase_wrangler_paths(&content, parent))?;
+        fs::remove_file(&from)?;
+        changes.push(format!(
+            "Moved {} to the project root and rebased its paths",
+            relative.display()
+        ));
+        wrangler_config = Some(to);
+    }
+
+    // Frontends build to dist/, wherever the starter put its output
+    if detection.project_type != "durable-object" {
+        let stem = if detection.project_type == "astro" {
+            "astro.config"
+        } else {
+            "vite.config"
+        };
+        if let Some(config) = find_config(root, stem) {
+            let content = fs::read_to_string(&config)?;
+            if let Some(out_dir) = configured_out_dir(&content)
+                && normalize(&out_dir) != OUT_DIR
+            {
+                fs::write(&config, set_out_dir(&content))?;
+                changes.push(format!(
+                    "Changed the build outDir in {} from '{}' to './{}'",
+                    file_name(&config),
+                    out_dir,
+                    OUT_DIR
+                ));
+                if let Some(wrangler) = &wrangler_config {
+                    let content = fs::read_to_string(wrangler)?;
+                    let updated = retarget_assets(&content, &out_dir);
+                    if updated != content {
+                        fs::write(wrangler, updated)?;
+                        changes.push(format!(
+                            "Pointed {} assets at ./{}",
+                            file_name(wrangler),
+                            OUT_DIR
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Static starters get the assets-only Worker moonflare's own templates deploy with
+        if wrangler_config.is_none() {
+            let path = root.join("wrangler.jsonc");
+            fs::write(&path, assets_wrangler_config(worker_name, detection))?;
+            changes.push(format!(
+                "Added wrangler.jsonc serving ./{} as static assets",
+                OUT_DIR
+            ));
+            wrangler_config = Some(path);
+        }
+    }
+
+    if let Some(config) = &wrangler_config
+        && set_config_name(config, worker_name)?
+    {
+        changes.push(format!(
+            "Named the Worker '{}' in {}",
+            worker_name,
+            file_name(config)
+        ));
+    }
+
+    changes.extend(adapt_package_json(root, name, detection)?);
+
+    let moon_yml = root.join("moon.yml");
+    if !moon_yml.exists() {
+        fs::write(&moon_yml, typescript_moon_yml(detection.project_type))?;
+        changes.push("Added moon.yml with a build task writing to dist/".to_string());
+    }
+
+    Ok(changes)
+}
+
+/// Scripts moonflare's inherited tasks run: `dev`, `build` and `deploy`
+fn adapt_package_json(root: &Path, name: &str, detection: &Detection) -> Result<Vec<String>> {
+    let path = root.join("package.json");
+    let Some(mut package) = read_package_json(root)? else {
+        return Ok(Vec::new());
+    };
+    let mut changes = Vec::new();
+
+    package["name"] = json!(name);
+    if package.get("engines").and_then(|e| e.get("node")).is_none() {
+        if !package["engines"].is_object() {
+            package["engines"] = json!({});
+        }
+        package["engines"]["node"] = json!(NODE_ENGINE);
+    }
+
+    if !package["scripts"].is_object() {
+        package["scripts"] = json!({});
+    }
+    let scripts = package["scripts"]
+        .as_object_mut()
+        .expect("scripts is an object");
+
+    // Older C3 and Worker starters only have `start`; Moon's dev task runs `pnpm dev`
+    if !scripts.contains_key("dev")
+        && let Some(start) = scripts.get("start").cloned()
+    {
+        scripts.insert("dev".to_string(), start.clone());
+        changes.push(format!(
+            "Added a dev script running '{}'",
+            start.as_str().unwrap_or_default()
+        ));
+    }
+    if !scripts.contains_key("build") && detection.project_type == "durable-object" {
+        let build = format!("wrangler deploy --dry-run --outdir {}", OUT_DIR);
+        changes.push(format!("Added a build script running '{}'", build));
+        scripts.insert("build".to_string(), json!(build));
+    }
+    if scripts.get("deploy").and_then(Value::as_str) != Some(DEPLOY_SCRIPT) {
+        scripts.insert("deploy".to_string(), json!(DEPLOY_SCRIPT));
+        changes.push(
+            "Set the deploy script to pass moonflare's environment, message and vars flags"
+                .to_string(),
+        );
+    }
+
+    let has_wrangler = ["dependencies", "devDependencies"]
+        .iter()
+        .any(|section| package[section].get("wrangler").is_some());
+    if !has_wrangler {
+        if !package["devDependencies"].is_object() {
+            package["devDependencies"] = json!({});
+        }
+        package["devDependencies"]["wrangler"] = json!("^4.32.0");
+        changes.push("Added wrangler to devDependencies".to_string());
+    }
+
+    fs::write(&path, serde_json::to_string_pretty(&package)? + "\n")?;
+    Ok(changes)
+}
+
+/// Fit a crate to moonflare's WASM conventions: a cdylib named after the project, with a moon.yml
+fn adapt_crate(root: &Path, name: &str) -> Result<Vec<String>> {
+    let mut changes = Vec::new();
+    if root.join(".cargo-ok").exists() {
+        fs::remove_file(root.join(".cargo-ok"))?;
+    }
+
+    let cargo_toml = root.join("Cargo.toml");
+    let content = fs::read_to_string(&cargo_toml)?;
+    let mut updated = set_package_name(&content, name);
+    if updated != content {
+        changes.push(format!("Renamed the package to '{}' in Cargo.toml", name));
+    }
+    if !updated.contains("cdylib") {
+        updated = add_cdylib(&updated);
+        changes.push("Added crate-type = [\"cdylib\", \"rlib\"] so it builds to WASM".to_string());
+    }
+    if updated != content {
+        fs::write(&cargo_toml, updated)?;
+    }
+
+    let moon_yml = root.join("moon.yml");
+    if !moon_yml.exists() {
+        fs::write(&moon_yml, CRATE_MOON_YML)?;
+        changes.push("Added moon.yml; the build task comes from .moon/tasks/rust.yml".to_string());
+    }
+    Ok(changes)
+}
+
+fn remove_replaced_files(root: &Path) -> Result<Vec<String>> {
+    let mut changes = Vec::new();
+    for file in REPLACED_FILES {
+        let path = root.join(file);
+        if path.is_file() {
+            fs::remove_file(&path)?;
+            changes.push(format!(
+                "Removed {}; the workspace's pnpm lockfile takes over",
+                file
+            ));
+        }
+    }
+    Ok(changes)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// A relative path with `./` and trailing slashes dropped
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if parts.last().is_some_and(|p| p != "..") => {
+                parts.pop();
+            }
+            other => parts.push(other.as_os_str().to_string_lossy().into_owned()),
+        }
+    }
+    parts.join("/")
+}
+
+/// `outDir: '...'` from an Astro config, or `build.outDir` from a Vite one
+fn configured_out_dir(config: &str) -> Option<String> {
+    let out_dir = Regex::new(r#"outDir\s*:\s*['"]([^'"]+)['"]"#).expect("valid outDir regex");
+    out_dir.captures(config).map(|caps| caps[1].to_string())
+}
+
+fn set_out_dir(config: &str) -> String {
+    let out_dir = Regex::new(r#"(outDir\s*:\s*)(['"])[^'"]+['"]"#).expect("valid outDir regex");
+    out_dir
+        .replace(config, |caps: &regex::Captures| {
+            format!("{}{}./{}{}", &caps[1], &caps[2], OUT_DIR, &caps[2])
+        })
+        .into_owned()
+}
+
+/// Point the assets directory at `dist/` when it served the starter's old build output
+fn retarget_assets(config: &str, old_out_dir: &str) -> String {
+    let directory = Regex::new(r#"((?:"directory"\s*:|\bdirectory\s*=)\s*)"([^"]+)""#)
+        .expect("valid directory regex");
+    directory
+        .replace_all(config, |caps: &regex::Captures| {
+            if normalize(&caps[2]) == normalize(old_out_dir) {
+                format!("{}\"./{}\"", &caps[1], OUT_DIR)
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .into_owned()
+}
+
+/// Rewrite the relative `main` and assets `directory` of a config moving from `parent` to the
+/// project root
+fn rebase_wrangler_paths(config: &str, parent: &Path) -> String {
+    let paths =
+        Regex::new(r#"((?:"(?:main|directory)"\s*:|\b(?:main|directory)\s*=)\s*)"([^"]+)""#)
+            .expect("valid path regex");
+    paths
+        .replace_all(config, |caps: &regex::Captures| {
+            let path = &caps[2];
+            if path.starts_with('/') {
+                return caps[0].to_string();
+            }
+            let rebased = normalize(&parent.join(path).to_string_lossy());
+            format!("{}\"./{}\"", &caps[1], rebased)
+        })
+        .into_owned()
+}
+
+fn set_package_name(cargo_toml: &str, name: &str) -> String {
+    let mut in_package = false;
+    let mut renamed = false;
+    cargo_toml
+        .split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                in_package = trimmed == "[package]";
+            } else if in_package
+                && !renamed
+                && trimmed
+                    .split_once('=')
+                    .is_some_and(|(key, _)| key.trim() == "name")
+            {
+                renamed = true;
+                let ending = if line.ends_with('\n') { "\n" } else { "" };
+                return format!("name = \"{}\"{}", name, ending);
+            }
+            line.to_string()
+        })
+        .collect()
+}
+
+/// Add `crate-type = ["cdylib", "rlib"]` to the `[lib]` table, creating it when missing
+fn add_cdylib(cargo_toml: &str) -> String {
+    let crate_type = "crate-type = [\"cdylib\", \"rlib\"]";
+    if let Some(index) = cargo_toml.find("[lib]\n") {
+        let (head, tail) = cargo_toml.split_at(index + "[lib]\n".len());
+        let tail = Regex::new(r"(?m)^crate-type\s*=.*\n")
+            .expect("valid crate-type regex")
+            .replace(tail, "");
+        return format!("{}{}\n{}", head, crate_type, tail);
+    }
+    let mut content = cargo_toml.trim_end().to_string();
+    content.push_str(&format!("\n\n[lib]\n{}\n", crate_type));
+    content
+}
+
+fn assets_wrangler_config(worker_name: &str, detection: &Detection) -> String {
+    let not_found = if detection.project_type == "react" {
+        ",\n\t\t\"not_found_handling\": \"single-page-application\""
+    } else {
+        ""
+    };
+    format!(
+        "{{\n\t\"$schema\": \"node_modules/wrangler/config-schema.json\",\n\t\"name\": \"{}\",\n\t\"compatibility_date\": \"2025-08-23\",\n\t\"assets\": {{\n\t\t\"directory\": \"./{}\"{}\n\t}}\n}}\n",
+        worker_name, OUT_DIR, not_found
+    )
+}
+
+fn typescript_moon_yml(project_type: &str) -> String {
+    let (stack, sources, inputs) = match project_type {
+        "astro" => (
+            "frontend",
+            ["'src/**/*'", "'*.astro'", "'*.ts'", "'*.js'"].as_slice(),
+            [
+                "\"src/**/*\"",
+                "\"public/**/*\"",
+                "\"astro.config.*\"",
+                "\"package.json\"",
+                "\"tsconfig.*\"",
+                "\"wrangler.*\"",
+            ]
+            .as_slice(),
+        ),
+        "react" => (
+            "frontend",
+            ["'src/**/*'", "'*.tsx'", "'*.ts'", "'*.jsx'", "'*.js'"].as_slice(),
+            [
+                "\"public/**/*\"",
+                "\"src/**/*\"",
+                "\"index.html\"",
+                "\"package.json\"",
+                "\"tsconfig.*\"",
+                "\"vite.config.*\"",
+                "\"wrangler.*\"",
+            ]
+            .as_slice(),
+        ),
+        _ => (
+            "backend",
+            ["'src/**/*'", "'*.ts'", "'*.js'"].as_slice(),
+            [
+                "'src/**/*'",
+                "\"package.json\"",
+                "\"tsconfig.*\"",
+                "\"wrangler.*\"",
+            ]
+            .as_slice(),
+        ),
+    };
+    let list = |items: &[&str]| {
+        items
+            .iter()
+            .map(|item| format!("      - {}\n", item))
+            .collect::<String>()
+    };
+    format!(
+        "$schema: 'https://moonrepo.dev/schemas/project.json'\nlanguage: 'typescript'\nlayer: 'application'\nstack: '{}'\n\nfileGroups:\n  sources:\n{}\ntasks:\n  build:\n    command: 'pnpm'\n    args: ['build']\n    inputs:\n{}    outputs:\n      - '{}'\n    deps:\n      - '~:install'\n",
+        stack,
+        list(sources).replace("      - ", "    - "),
+        list(inputs),
+        OUT_DIR
+    )
+}
+
+const CRATE_MOON_YML: &str = "$schema: 'https://moonrepo.dev/schemas/project.json'
+language: 'rust'
+layer: 'library'
+
+fileGroups:
+  sources:
+    - 'src/**/*'
+";
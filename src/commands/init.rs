@@ -1,9 +1,15 @@
+use crate::commands::doctor::write_npmrc_in;
 use crate::errors::{MoonflareError, validate_workspace_name};
 use crate::templates::{embedded, engine::TemplateEngine};
 use crate::ui::MoonflareUI;
 use crate::utils::{
-    fs::create_directory_if_not_exists,
+    config::{MoonflareConfig, PnpmConfig},
+    config_schema::CONFIG_VERSION,
+    fs::{create_directory_if_not_exists, enclosing_workspace, non_empty_directory_message},
     moon::{check_moon_installation, moon_setup},
+    moon_tasks::write_managed_files,
+    node::NODE_ENGINE,
+    platform::Shell,
 };
 use miette::{IntoDiagnostic, Result};
 use serde_json::Value;
@@ -23,7 +29,14 @@ impl InitCommand {
         }
     }
 
-    pub async fn execute(&self, name: &str, path: Option<&str>, force: bool) -> Result<()> {
+    pub async fn execute(
+        &self,
+        name: &str,
+        path: Option<&str>,
+        force: bool,
+        force_nested: bool,
+        pnpm: PnpmConfig,
+    ) -> Result<()> {
         self.ui
             .render_header(
                 "Moonflare: Supersonic Cloudflare monorepo",
@@ -81,6 +94,26 @@ impl InitCommand {
             (target_dir, name.to_string())
         };
 
+        // A workspace inside another one is usually a project created in the wrong place
+        if !force_nested && let Some(workspace) = enclosing_workspace(&target_dir) {
+            let target = std::path::absolute(&target_dir).unwrap_or_else(|_| target_dir.clone());
+            let layout = MoonflareConfig::load_from(&workspace.join("moonflare.toml"))
+                .map(|config| config.layout)
+                .unwrap_or_default();
+            let project_dir = target
+                .strip_prefix(&workspace)
+                .ok()
+                .and_then(|relative| relative.components().next())
+                .and_then(|first| first.as_os_str().to_str())
+                .filter(|first| {
+                    layout
+                        .directories()
+                        .iter()
+                        .any(|(dir, _)| dir.as_str() == *first)
+                });
+            return Err(MoonflareError::nested_workspace(&target, &workspace, project_dir).into());
+        }
+
         // Check if directory already exists and has content
         if target_dir.exists() {
             if target_dir.is_dir() {
@@ -98,33 +131,7 @@ impl InitCommand {
                         .and_then(|n| n.to_str())
                         .unwrap_or("directory");
 
-                    let sample_files = entries
-                        .iter()
-                        .take(3)
-                        .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
-                        .collect::<Vec<_>>()
-                        .join(", ");
-
-                    let message = if entries.len() == 1 {
-                        format!(
-                            "Directory is not empty. Directory '{}' contains 1 file: {}",
-                            dir_name, sample_files
-                        )
-                    } else if entries.len() <= 3 {
-                        format!(
-                            "Directory is not empty. Directory '{}' contains {} files: {}",
-                            dir_name,
-                            entries.len(),
-                            sample_files
-                        )
-                    } else {
-                        format!(
-                            "Directory is not empty. Directory '{}' contains {} files including: {}",
-                            dir_name,
-                            entries.len(),
-                            sample_files
-                        )
-                    };
+                    let message = non_empty_directory_message(dir_name, &entries);
 
                     let init_command = if name == "." {
                         "moonflare init . --force"
@@ -208,19 +215,56 @@ impl InitCommand {
         // Prepare template context
         let mut context = HashMap::new();
         context.insert("name".to_string(), Value::String(workspace_name.clone()));
+        context.insert(
+            "moonflare_version".to_string(),
+            Value::String(env!("CARGO_PKG_VERSION").to_string()),
+        );
+        context.insert("config_version".to_string(), Value::from(CONFIG_VERSION));
+        context.insert(
+            "node_engine".to_string(),
+            Value::String(NODE_ENGINE.to_string()),
+        );
+        context.insert(
+            "shamefully_hoist".to_string(),
+            Value::Bool(pnpm.shamefully_hoist),
+        );
+        context.insert(
+            "public_hoist_pattern".to_string(),
+            serde_json::json!(pnpm.public_hoist_pattern),
+        );
 
         // Generate workspace files
-        if let Some(template) = embedded::get_template("workspace") {
-            self.template_engine
-                .process_template_files(template, &target_dir, &context)
-                .map_err(|e| {
+        self.template_engine
+            .process_template_files(embedded::WORKSPACE_TEMPLATE, &target_dir, &context)
+            .map_err(|e| {
+                // Missing variables already name the file they were rendering
+                e.downcast::<MoonflareError>().unwrap_or_else(|e| {
                     MoonflareError::template_error(
                         "workspace",
                         Box::new(std::io::Error::other(e.to_string())),
                     )
                 })
-                .into_diagnostic()?;
-        }
+            })?;
+
+        // Shared task defaults that project moon.yml files inherit
+        write_managed_files(&target_dir).map_err(|e| {
+            MoonflareError::template_error("tasks", Box::new(std::io::Error::other(e.to_string())))
+        })?;
+
+        // Write the hoisting strategy chosen at init into .npmrc
+        let config = MoonflareConfig {
+            pnpm,
+            ..Default::default()
+        };
+        write_npmrc_in(&target_dir, &config)
+            .map_err(|e| {
+                MoonflareError::file_system_error(
+                    "write .npmrc",
+                    target_dir.join(".npmrc"),
+                    std::io::Error::other(e.to_string()),
+                )
+            })
+            .into_diagnostic()?;
 
         // Create directory structure
         let dirs = ["apps", "sites", "workers", "crates"];
@@ -332,7 +376,7 @@ impl InitCommand {
 
         let mut steps = vec![];
         if name != "." {
-            steps.push(format!("cd {}", workspace_name));
+            steps.push(Shell::detect().change_dir(&workspace_name));
         }
         steps.push("moonflare add <type> <name>  # Add a new project".to_string());
 
@@ -0,0 +1,190 @@
+use crate::ui::MoonflareUI;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::kv::{self, KvTarget, KvValue, read_jsonl, resolve_binding, to_jsonl};
+use crate::utils::projects::{WorkspaceProject, find_project, project_not_found};
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+
+pub struct KvCommand {
+    ui: MoonflareUI,
+}
+
+impl KvCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// List the keys of a project's KV namespace
+    pub async fn execute_keys(
+        &self,
+        project: &str,
+        binding: &str,
+        prefix: Option<&str>,
+        target: &KvTarget,
+        json: bool,
+    ) -> Result<()> {
+        let (project, binding) = resolve(project, binding, target)?;
+        let keys = kv::list_keys(&project, &binding, prefix, target)?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&keys)?);
+            return Ok(());
+        }
+
+        self.ui
+            .render_header(
+                "KV keys",
+                Some(&format!(
+                    "{} of '{}' ({})",
+                    binding,
+                    project.name,
+                    target.describe()
+                )),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if keys.is_empty() {
+            println!(
+                "No keys{}",
+                prefix.map_or(String::new(), |p| format!(" starting with '{}'", p))
+            );
+            return Ok(());
+        }
+        for key in &keys {
+            match key.expiration {
+                Some(expiration) => {
+                    let expires = chrono::DateTime::from_timestamp(expiration as i64, 0)
+                        .map_or(expiration.to_string(), |time| time.to_rfc3339());
+                    println!("  {} {}", key.name, format!("expires {}", expires).dimmed());
+                }
+                None => println!("  {}", key.name),
+            }
+        }
+        println!();
+        println!("{} key(s)", keys.len());
+        Ok(())
+    }
+
+    /// Print the value stored under a key
+    pub async fn execute_get(
+        &self,
+        project: &str,
+        binding: &str,
+        key: &str,
+        target: &KvTarget,
+    ) -> Result<()> {
+        let (project, binding) = resolve(project, binding, target)?;
+        let value = kv::get(&project, &binding, key, target)?;
+        let mut stdout = std::io::stdout();
+        stdout.write_all(&value)?;
+        // Values are printed as stored; a newline only keeps the prompt off the value's last line
+        if stdout.is_terminal() && !value.ends_with(b"\n") {
+            writeln!(stdout)?;
+        }
+        Ok(())
+    }
+
+    /// Store a value, or a file's contents, under a key
+    pub async fn execute_put(
+        &self,
+        project: &str,
+        binding: &str,
+        key: &str,
+        value: KvValue<'_>,
+        ttl: Option<u64>,
+        target: &KvTarget,
+    ) -> Result<()> {
+        let (project, binding) = resolve(project, binding, target)?;
+        if let KvValue::File(path) = value
+            && !path.is_file()
+        {
+            bail!("{} is not a file", path.display());
+        }
+        kv::put(&project, &binding, key, value, ttl, target)?;
+        self.ui
+            .render_success(&format!(
+                "Put {} in {} of '{}' ({})",
+                key,
+                binding,
+                project.name,
+                target.describe()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+
+    /// Write every key and value of a namespace as JSON Lines, to `output` or stdout
+    pub async fn execute_export(
+        &self,
+        project: &str,
+        binding: &str,
+        prefix: Option<&str>,
+        output: Option<&Path>,
+        target: &KvTarget,
+    ) -> Result<()> {
+        let (project, binding) = resolve(project, binding, target)?;
+        let entries = kv::export(&project, &binding, prefix, target)?;
+        let content = to_jsonl(&entries)?;
+        let Some(output) = output else {
+            print!("{}", content);
+            return Ok(());
+        };
+
+        fs::write(output, content)
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        self.ui
+            .render_success(&format!(
+                "Exported {} key(s) from {} of '{}' ({}) to {}",
+                entries.len(),
+                binding,
+                project.name,
+                target.describe(),
+                output.display()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+
+    /// Write the entries of a JSON Lines file, as `export` writes them, into a namespace
+    pub async fn execute_import(
+        &self,
+        project: &str,
+        binding: &str,
+        file: &Path,
+        target: &KvTarget,
+    ) -> Result<()> {
+        let (project, binding) = resolve(project, binding, target)?;
+        let entries = read_jsonl(file)?;
+        if entries.is_empty() {
+            bail!("{} has no entries to import", file.display());
+        }
+        kv::import(&project, &binding, &entries, target)?;
+        self.ui
+            .render_success(&format!(
+                "Imported {} key(s) into {} of '{}' ({})",
+                entries.len(),
+                binding,
+                project.name,
+                target.describe()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+}
+
+/// The project and its KV binding `binding` in the target's environment
+fn resolve(project: &str, binding: &str, target: &KvTarget) -> Result<(WorkspaceProject, String)> {
+    if !is_moonflare_workspace() {
+        bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+    }
+    let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+    let Some(config) = read_wrangler_config(&project_info.path)? else {
+        bail!("'{}' has no Wrangler config", project);
+    };
+    let binding = resolve_binding(&config, project, target.env.as_deref(), binding)?;
+    Ok((project_info, binding))
+}
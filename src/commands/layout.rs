@@ -0,0 +1,309 @@
+use crate::ui::MoonflareUI;
+use crate::utils::aliases::sync_aliases;
+use crate::utils::backup::create_move_snapshot;
+use crate::utils::cargo_workspace::{CARGO_WORKSPACE_FILE, sync_cargo_workspace};
+use crate::utils::config::{CONFIG_FILE, LayoutConfig, MoonflareConfig};
+use crate::utils::fs::{is_moonflare_workspace, replace_identifiers};
+use crate::utils::projects::discover_projects;
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Workspace files that refer to project directories by path
+const REFERENCING_FILES: [&str; 7] = [
+    "package.json",
+    "pnpm-workspace.yaml",
+    "justfile",
+    ".moon/workspace.yml",
+    ".moon/tasks.yml",
+    "shared-wasm/moon.yml",
+    "shared-wasm/gather-wasm.cjs",
+];
+
+/// Directories of shared Moon tasks and CI workflows, which refer to project directories too
+const REFERENCING_DIRECTORIES: [&str; 2] = [".moon/tasks", ".github/workflows"];
+
+/// Files in each project that may point at another project directory
+const PROJECT_FILES: [&str; 7] = [
+    "moon.yml",
+    "package.json",
+    "tsconfig.json",
+    "tsconfig.app.json",
+    "vite.config.ts",
+    "astro.config.mjs",
+    "wrangler.toml",
+];
+
+/// Names a project directory can't take
+const RESERVED_DIRECTORIES: [&str; 4] = ["shared-wasm", "packages", "node_modules", "target"];
+
+pub struct LayoutCommand {
+    ui: MoonflareUI,
+}
+
+impl LayoutCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute_show(&self) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let layout = MoonflareConfig::load()?.layout;
+        for (kind, _) in LayoutConfig::KINDS {
+            let directory = layout.directory(kind).unwrap_or(kind);
+            println!("{:<8} {}/", kind, directory);
+        }
+        Ok(())
+    }
+
+    /// Move project directories, e.g. `sites=websites`, and every reference to them
+    pub async fn execute_set(&self, assignments: &[String]) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let current = MoonflareConfig::load()?.layout;
+        let mut layout = current.clone();
+        for assignment in assignments {
+            let Some((kind, directory)) = assignment.split_once('=') else {
+                bail!(
+                    "Expected <kind>=<directory> (e.g. sites=websites), got '{}'",
+                    assignment
+                );
+            };
+            validate_directory(directory)?;
+            if !layout.set(kind.trim(), directory) {
+                bail!(
+                    "Unknown layout key '{}'; expected one of sites, apps, workers or crates",
+                    kind
+                );
+            }
+        }
+
+        let directories = layout.directories();
+        for (index, (directory, _)) in directories.iter().enumerate() {
+            if directories[..index]
+                .iter()
+                .any(|(other, _)| other == directory)
+            {
+                bail!(
+                    "Two kinds of project can't share the '{}' directory",
+                    directory
+                );
+            }
+        }
+
+        let moves: Vec<(&'static str, String, String)> = LayoutConfig::KINDS
+            .iter()
+            .filter_map(|(kind, _)| {
+                let old = current.directory(kind)?;
+                let new = layout.directory(kind)?;
+                (old != new).then(|| (*kind, old.to_string(), new.to_string()))
+            })
+            .collect();
+        if moves.is_empty() {
+            println!("The layout already matches; nothing to move.");
+            return Ok(());
+        }
+        for (_, _, new) in &moves {
+            if Path::new(new).exists() {
+                bail!("'{}' already exists", new);
+            }
+        }
+
+        let summary: Vec<String> = moves
+            .iter()
+            .map(|(_, old, new)| format!("{}/ to {}/", old, new))
+            .collect();
+        self.ui
+            .render_header("Changing workspace layout", Some(&summary.join(", ")))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let mut paths: Vec<PathBuf> = referencing_files();
+        paths.push(PathBuf::from(CONFIG_FILE));
+        paths.push(PathBuf::from(CARGO_WORKSPACE_FILE));
+        for (_, old, new) in &moves {
+            paths.push(PathBuf::from(old));
+            paths.push(PathBuf::from(new));
+        }
+        let moved: Vec<(&Path, &Path)> = moves
+            .iter()
+            .map(|(_, old, new)| (Path::new(old.as_str()), Path::new(new.as_str())))
+            .collect();
+        let snapshot = create_move_snapshot(
+            &format!(
+                "layout set {}",
+                moves
+                    .iter()
+                    .map(|(kind, _, new)| format!("{}={}", kind, new))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            &paths.iter().map(PathBuf::as_path).collect::<Vec<_>>(),
+            &moved,
+        )?;
+        println!("Saved snapshot {}", snapshot.id);
+
+        for (_, old, new) in &moves {
+            if Path::new(old).exists() {
+                fs::rename(old, new)
+                    .with_context(|| format!("Failed to move {}/ to {}/", old, new))?;
+                println!("Moved {}/ to {}/", old, new);
+            }
+        }
+
+        let config = fs::read_to_string(CONFIG_FILE).unwrap_or_default();
+        let keys: Vec<(&str, &str)> = moves
+            .iter()
+            .map(|(kind, _, new)| (*kind, new.as_str()))
+            .collect();
+        fs::write(CONFIG_FILE, set_layout_keys(&config, &keys))
+            .with_context(|| format!("Failed to write {}", CONFIG_FILE))?;
+        println!("Updated {}", CONFIG_FILE);
+
+        // Project files are listed again now that discovery follows the new layout
+        for path in referencing_files() {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let updated = moves
+                .iter()
+                .fold(content.clone(), |content, (_, old, new)| {
+                    rewrite_references(&content, old, new)
+                });
+            if updated != content {
+                fs::write(&path, updated)?;
+                println!("Updated {}", path.display());
+            }
+        }
+
+        for path in sync_aliases()? {
+            println!("Updated aliases in {}", path.display());
+        }
+        for change in sync_cargo_workspace()? {
+            println!("Updated {}: {}", CARGO_WORKSPACE_FILE, change);
+        }
+
+        self.ui
+            .render_success(&format!("Moved {}", summary.join(", ")))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
+
+pub fn validate_directory(directory: &str) -> Result<()> {
+    if directory.is_empty()
+        || !directory
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "'{}' isn't a valid directory name; use letters, digits, '-' and '_'",
+            directory
+        );
+    }
+    if RESERVED_DIRECTORIES.contains(&directory) {
+        bail!("'{}' is reserved and can't hold projects", directory);
+    }
+    Ok(())
+}
+
+/// Existing workspace and project files that may mention a project directory
+fn referencing_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = REFERENCING_FILES.iter().map(PathBuf::from).collect();
+    for directory in REFERENCING_DIRECTORIES {
+        if let Ok(entries) = fs::read_dir(directory) {
+            let mut entries: Vec<PathBuf> = entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.extension()
+                        .is_some_and(|ext| ext == "yml" || ext == "yaml")
+                })
+                .collect();
+            entries.sort();
+            files.extend(entries);
+        }
+    }
+    for project in discover_projects() {
+        files.extend(PROJECT_FILES.iter().map(|file| project.path.join(file)));
+    }
+    files.retain(|path| path.is_file());
+    files
+}
+
+/// Point references to the `old` directory at `new`: paths such as `apps/*` or
+/// `../../apps/web`, quoted names such as `'crates'`, and `mkdir` arguments
+fn rewrite_references(content: &str, old: &str, new: &str) -> String {
+    let reference = Regex::new(&format!(
+        r#"(^|[\s'"`(]|\.\./|\./){}(/|['"`])"#,
+        regex::escape(old)
+    ))
+    .expect("valid reference regex");
+    let replacements = [(old.to_string(), new.to_string())];
+
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim_start().trim_start_matches('@');
+            if trimmed.starts_with("mkdir ") {
+                replace_identifiers(line, &replacements)
+            } else {
+                reference
+                    .replace_all(line, |caps: &regex::Captures| {
+                        format!("{}{}{}", &caps[1], new, &caps[2])
+                    })
+                    .into_owned()
+            }
+        })
+        .collect()
+}
+
+/// Set keys in moonflare.toml's `[layout]` table, adding the table when it's missing and
+/// leaving the rest of the file, comments included, as written
+pub fn set_layout_keys(content: &str, keys: &[(&str, &str)]) -> String {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let Some(start) = lines.iter().position(|line| line.trim() == "[layout]") else {
+        let mut content = content.trim_end().to_string();
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str("[layout]\n");
+        for (key, value) in keys {
+            content.push_str(&format!("{} = \"{}\"\n", key, value));
+        }
+        return content;
+    };
+
+    for (key, value) in keys {
+        let end = lines[start + 1..]
+            .iter()
+            .position(|line| line.trim_start().starts_with('['))
+            .map_or(lines.len(), |offset| start + 1 + offset);
+        let entry = format!("{} = \"{}\"", key, value);
+        let existing = (start + 1..end).find(|&index| {
+            lines[index]
+                .split_once('=')
+                .is_some_and(|(name, _)| name.trim() == *key)
+        });
+        match existing {
+            Some(index) => lines[index] = entry,
+            None => {
+                // After the table's last entry, before any blank lines separating the next table
+                let last = (start..end)
+                    .rev()
+                    .find(|&index| !lines[index].trim().is_empty())
+                    .unwrap_or(start);
+                lines.insert(last + 1, entry);
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,98 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::licenses::{PackageLicense, collect_licenses, group_by_license, render_csv};
+use crate::utils::projects::{ProjectFilter, discover_projects};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::fs;
+use std::path::Path;
+
+/// Output format of the license report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LicenseFormat {
+    /// Packages grouped by license
+    Table,
+    Csv,
+    Json,
+}
+
+pub struct LicensesCommand {}
+
+impl LicensesCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub async fn execute(
+        &self,
+        filter: &ProjectFilter,
+        format: LicenseFormat,
+        output: Option<&Path>,
+        include_dev: bool,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let config = MoonflareConfig::load()?;
+        let projects = filter.apply(discover_projects());
+        let packages = collect_licenses(&projects, include_dev, &config.licenses.disallowed)?;
+
+        let report = match format {
+            LicenseFormat::Table => render_table(&packages),
+            LicenseFormat::Csv => render_csv(&packages),
+            LicenseFormat::Json => serde_json::to_string_pretty(&packages)? + "\n",
+        };
+
+        match output {
+            Some(path) => {
+                fs::write(path, &report)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                println!("License report written to {}", path.display());
+            }
+            None => print!("{}", report),
+        }
+
+        let disallowed: Vec<String> = packages
+            .iter()
+            .filter(|p| p.disallowed)
+            .map(|p| format!("  {}@{} ({})", p.name, p.version, p.license))
+            .collect();
+        if !disallowed.is_empty() {
+            bail!(
+                "{} package(s) use disallowed licenses:\n{}",
+                disallowed.len(),
+                disallowed.join("\n")
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn render_table(packages: &[PackageLicense]) -> String {
+    if packages.is_empty() {
+        return "No dependencies found\n".to_string();
+    }
+
+    let mut table = String::new();
+    for (license, members) in group_by_license(packages) {
+        let heading = format!("{} ({})", license, members.len());
+        let heading = if members.iter().any(|p| p.disallowed) {
+            heading.red().bold().to_string()
+        } else {
+            heading.bold().to_string()
+        };
+        table.push_str(&format!("{}\n", heading));
+        for package in members {
+            table.push_str(&format!(
+                "  {:<40} {:<12} {}\n",
+                package.name,
+                package.version,
+                package.ecosystem.dimmed()
+            ));
+        }
+        table.push('\n');
+    }
+    table
+}
@@ -0,0 +1,256 @@
+use crate::utils::{
+    config::{LoadTestThresholds, MoonflareConfig},
+    fs::is_moonflare_workspace,
+    http,
+    ports::dev_url,
+    projects::{WorkspaceProject, find_project, project_not_found},
+};
+use anyhow::{Result, bail};
+use colored::*;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+/// Where the load test sends traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LoadTestTarget {
+    /// The project's local dev server
+    Dev,
+    /// The project's deployed URL from moonflare.toml
+    Deployed,
+}
+
+#[derive(Debug)]
+pub struct LoadTestOptions {
+    pub rps: u32,
+    pub duration: Duration,
+    pub against: LoadTestTarget,
+    pub url: Option<String>,
+    pub path: String,
+}
+
+#[derive(Debug)]
+struct LoadTestSummary {
+    requests: usize,
+    errors: usize,
+    latencies: Vec<Duration>,
+    elapsed: Duration,
+}
+
+impl LoadTestSummary {
+    fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.requests as f64
+        }
+    }
+
+    fn percentile_ms(&self, p: f64) -> f64 {
+        if self.latencies.is_empty() {
+            return 0.0;
+        }
+        let index = ((p * self.latencies.len() as f64) as usize).min(self.latencies.len() - 1);
+        self.latencies[index].as_secs_f64() * 1000.0
+    }
+}
+
+pub struct LoadTestCommand {}
+
+impl LoadTestCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub async fn execute(&self, project: &str, options: &LoadTestOptions) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        if options.rps == 0 {
+            bail!("--rps must be greater than zero");
+        }
+
+        let config = MoonflareConfig::load()?;
+        let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+
+        let base_url = match &options.url {
+            Some(url) => url.clone(),
+            None => target_url(&project_info, &config, options.against)?,
+        };
+        let url = format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            options.path.trim_start_matches('/')
+        );
+
+        println!(
+            "{}",
+            format!(
+                "Load testing {} at {} req/s for {}s...",
+                url,
+                options.rps,
+                options.duration.as_secs_f64()
+            )
+            .cyan()
+            .bold()
+        );
+
+        let summary = run_load(&url, options.rps, options.duration).await?;
+        print_summary(&summary);
+
+        let thresholds = config.loadtest_thresholds(project);
+        let violations = check_thresholds(&summary, &thresholds);
+        if !violations.is_empty() {
+            bail!(
+                "Load test thresholds exceeded:\n  {}",
+                violations.join("\n  ")
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse durations like `30s`, `2m`, `500ms` or a bare number of seconds
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let (value, unit) = match input.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(index) => input.split_at(index),
+        None => (input, "s"),
+    };
+
+    let value: f64 = value
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", input))?;
+    let seconds = match unit {
+        "ms" => value / 1000.0,
+        "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => {
+            return Err(format!(
+                "invalid duration unit in '{}' (use ms, s, m or h)",
+                input
+            ));
+        }
+    };
+
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+fn target_url(
+    project: &WorkspaceProject,
+    config: &MoonflareConfig,
+    against: LoadTestTarget,
+) -> Result<String> {
+    match against {
+        LoadTestTarget::Dev => dev_url(project, config, false),
+        LoadTestTarget::Deployed => config
+            .project(&project.name)
+            .and_then(|p| p.url.clone())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No deployed URL known for '{}'. Set url under [projects.{}] in moonflare.toml or pass --url.",
+                    project.name,
+                    project.name
+                )
+            }),
+    }
+}
+
+/// Open-loop load: requests start on a fixed schedule regardless of how long earlier ones take
+async fn run_load(url: &str, rps: u32, duration: Duration) -> Result<LoadTestSummary> {
+    let client = http::builder()?.timeout(Duration::from_secs(30)).build()?;
+
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(1.0 / rps as f64));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Burst);
+
+    let mut requests = JoinSet::new();
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        interval.tick().await;
+        let client = client.clone();
+        let url = url.to_string();
+        requests.spawn(async move {
+            let sent = Instant::now();
+            let ok = match client.get(&url).send().await {
+                Ok(response) => {
+                    let success = !response.status().is_client_error()
+                        && !response.status().is_server_error();
+                    response.bytes().await.is_ok() && success
+                }
+                Err(_) => false,
+            };
+            (sent.elapsed(), ok)
+        });
+    }
+
+    let mut summary = LoadTestSummary {
+        requests: 0,
+        errors: 0,
+        latencies: Vec::new(),
+        elapsed: Duration::ZERO,
+    };
+    while let Some(result) = requests.join_next().await {
+        let (latency, ok) = result?;
+        summary.requests += 1;
+        if ok {
+            summary.latencies.push(latency);
+        } else {
+            summary.errors += 1;
+        }
+    }
+    summary.elapsed = start.elapsed();
+    summary.latencies.sort();
+
+    Ok(summary)
+}
+
+fn print_summary(summary: &LoadTestSummary) {
+    println!();
+    println!(
+        "  Requests     {} ({:.1} req/s)",
+        summary.requests,
+        summary.requests as f64 / summary.elapsed.as_secs_f64().max(f64::EPSILON)
+    );
+    println!(
+        "  Errors       {} ({:.2}%)",
+        summary.errors,
+        summary.error_rate() * 100.0
+    );
+    println!("  Latency p50  {:.2} ms", summary.percentile_ms(0.50));
+    println!("  Latency p95  {:.2} ms", summary.percentile_ms(0.95));
+    println!("  Latency p99  {:.2} ms", summary.percentile_ms(0.99));
+}
+
+fn check_thresholds(summary: &LoadTestSummary, thresholds: &LoadTestThresholds) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    let latency_checks = [
+        ("p50", 0.50, thresholds.max_p50_ms),
+        ("p95", 0.95, thresholds.max_p95_ms),
+        ("p99", 0.99, thresholds.max_p99_ms),
+    ];
+    for (label, percentile, limit) in latency_checks {
+        if let Some(limit) = limit {
+            let actual = summary.percentile_ms(percentile);
+            if actual > limit {
+                violations.push(format!(
+                    "{} latency {:.2} ms exceeds {:.2} ms",
+                    label, actual, limit
+                ));
+            }
+        }
+    }
+
+    if let Some(limit) = thresholds.max_error_rate
+        && summary.error_rate() > limit
+    {
+        violations.push(format!(
+            "error rate {:.2}% exceeds {:.2}%",
+            summary.error_rate() * 100.0,
+            limit * 100.0
+        ));
+    }
+
+    violations
+}
@@ -0,0 +1,233 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::manifest::{DEPLOYMENT_MANIFEST, DeploymentManifest};
+use crate::utils::meta::{META_CONFIG_FILE, MetaWorkspace, workspaces};
+use anyhow::{Result, bail};
+use colored::*;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// How one workspace's part of a meta run ended
+enum Outcome {
+    Succeeded,
+    Failed(String),
+    Skipped(String),
+}
+
+struct MetaRun {
+    workspace: String,
+    outcome: Outcome,
+    duration: Duration,
+}
+
+pub struct MetaCommand {
+    ui: MoonflareUI,
+}
+
+impl MetaCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute_build(&self, selected: &[String], args: &[String]) -> Result<()> {
+        let workspaces = select_workspaces(selected)?;
+        let mut command = vec!["build".to_string()];
+        command.extend(args.iter().cloned());
+        self.run_across(&workspaces, &command, None)
+    }
+
+    pub async fn execute_deploy(
+        &self,
+        env: Option<&str>,
+        selected: &[String],
+        args: &[String],
+    ) -> Result<()> {
+        let workspaces = select_workspaces(selected)?;
+        let mut command = vec!["deploy".to_string()];
+        if let Some(env) = env {
+            command.extend(["--env".to_string(), env.to_string()]);
+        }
+        command.extend(args.iter().cloned());
+        self.run_across(&workspaces, &command, env)
+    }
+
+    pub async fn execute_status(&self, selected: &[String]) -> Result<()> {
+        let workspaces = select_workspaces(selected)?;
+
+        println!(
+            "  {:<32} {:>8}  {:<14} {:<26} {:<8} DEPLOYED",
+            "WORKSPACE", "PROJECTS", "ENVIRONMENT", "LAST DEPLOY", "COMMIT"
+        );
+        for workspace in &workspaces {
+            let manifest =
+                DeploymentManifest::load_from(&workspace.path.join(DEPLOYMENT_MANIFEST))?;
+            let projects = project_count(&workspace.path);
+            if manifest.environments.is_empty() {
+                println!(
+                    "  {:<32} {:>8}  {}",
+                    workspace.name(),
+                    projects,
+                    "never deployed".dimmed()
+                );
+                continue;
+            }
+
+            for (environment, records) in &manifest.environments {
+                let Some(latest) = records
+                    .values()
+                    .max_by(|a, b| a.deployed_at.cmp(&b.deployed_at))
+                else {
+                    continue;
+                };
+                let commit = latest
+                    .git_sha
+                    .as_deref()
+                    .map(|sha| sha[..sha.len().min(7)].to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                println!(
+                    "  {:<32} {:>8}  {:<14} {:<26} {:<8} {} project(s){}",
+                    workspace.name(),
+                    projects,
+                    environment,
+                    latest.deployed_at.chars().take(25).collect::<String>(),
+                    commit,
+                    records.len(),
+                    if latest.dirty { ", dirty" } else { "" }
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run a moonflare command in each workspace in turn, continuing past failures, and
+    /// summarise the results
+    fn run_across(
+        &self,
+        workspaces: &[MetaWorkspace],
+        command: &[String],
+        env: Option<&str>,
+    ) -> Result<()> {
+        let moonflare = std::env::current_exe()?;
+        let mut runs = Vec::new();
+
+        for workspace in workspaces {
+            if !workspace.deploys_to(env) {
+                runs.push(MetaRun {
+                    workspace: workspace.name(),
+                    outcome: Outcome::Skipped(format!(
+                        "doesn't deploy to '{}'",
+                        env.unwrap_or_default()
+                    )),
+                    duration: Duration::ZERO,
+                });
+                continue;
+            }
+
+            self.ui
+                .render_section_start(&format!(
+                    "{} → moonflare {}",
+                    workspace.name(),
+                    command.join(" ")
+                ))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+            let started = Instant::now();
+            let outcome = match Command::new(&moonflare)
+                .args(command)
+                .current_dir(&workspace.path)
+                .status()
+            {
+                Ok(status) if status.success() => Outcome::Succeeded,
+                Ok(status) => Outcome::Failed(status.to_string()),
+                Err(e) => Outcome::Failed(e.to_string()),
+            };
+            runs.push(MetaRun {
+                workspace: workspace.name(),
+                outcome,
+                duration: started.elapsed(),
+            });
+        }
+
+        println!();
+        println!("{}", format!("moonflare meta {}", command[0]).bold());
+        for run in &runs {
+            let (status, detail) = match &run.outcome {
+                Outcome::Succeeded => ("✓".green(), String::new()),
+                Outcome::Failed(reason) => ("✗".red(), reason.clone()),
+                Outcome::Skipped(reason) => ("-".dimmed(), reason.clone()),
+            };
+            println!(
+                "  {}  {:<32} {:>7.1}s  {}",
+                status,
+                run.workspace,
+                run.duration.as_secs_f64(),
+                detail
+            );
+        }
+
+        let failed = runs
+            .iter()
+            .filter(|run| matches!(run.outcome, Outcome::Failed(_)))
+            .count();
+        if failed > 0 {
+            bail!("{} of {} workspace(s) failed", failed, runs.len());
+        }
+
+        self.ui
+            .render_success(&format!(
+                "{} workspace(s) finished 'moonflare {}'",
+                runs.iter()
+                    .filter(|run| matches!(run.outcome, Outcome::Succeeded))
+                    .count(),
+                command[0]
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Workspaces below the current directory, narrowed to those named by `--workspace`
+fn select_workspaces(selected: &[String]) -> Result<Vec<MetaWorkspace>> {
+    let all = workspaces(Path::new("."))?;
+    if all.is_empty() {
+        bail!(
+            "No Moonflare workspaces found below this directory. List them in {} or run from the repository root.",
+            META_CONFIG_FILE
+        );
+    }
+    if selected.is_empty() {
+        return Ok(all);
+    }
+
+    for selector in selected {
+        if !all.iter().any(|w| w.matches(selector)) {
+            bail!(
+                "No workspace matches '{}'. Known workspaces: {}",
+                selector,
+                all.iter().map(|w| w.name()).collect::<Vec<_>>().join(", ")
+            );
+        }
+    }
+    Ok(all
+        .into_iter()
+        .filter(|w| selected.iter().any(|s| w.matches(s)))
+        .collect())
+}
+
+fn project_count(workspace: &Path) -> usize {
+    // Each workspace may lay its projects out differently
+    let layout = MoonflareConfig::load_from(&workspace.join("moonflare.toml"))
+        .map(|config| config.layout)
+        .unwrap_or_default();
+    layout
+        .directories()
+        .iter()
+        .filter_map(|(directory, _)| std::fs::read_dir(workspace.join(directory)).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|entry| entry.path().is_dir())
+        .count()
+}
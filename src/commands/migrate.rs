@@ -0,0 +1,1203 @@
+use crate::commands::import::{Archetype, Detection, adapt_typescript_project, detect};
+use crate::commands::init::InitCommand;
+use crate::commands::layout::{set_layout_keys, validate_directory};
+use crate::ui::MoonflareUI;
+use crate::utils::aliases::sync_aliases;
+use crate::utils::backup::create_snapshot;
+use crate::utils::cargo_workspace::{CARGO_WORKSPACE_FILE, sync_cargo_workspace};
+#[cfg(feature = "api")]
+use crate::utils::cloudflare::{CloudflareClient, SCRIPTS_PERMISSION};
+use crate::utils::config::{CONFIG_FILE, LayoutConfig, MoonflareConfig, PnpmConfig};
+use crate::utils::fs::{get_project_directory, is_moonflare_workspace};
+use crate::utils::migrations::{MigrationStatus, d1_status, durable_object_status};
+use crate::utils::moon::check_moon_installation;
+use crate::utils::pipelines::{MonorepoTool, Pipeline, SourceProject};
+use crate::utils::projects::{
+    WorkspaceProject, discover_projects, find_project, project_not_found,
+};
+use crate::utils::vcs::{move_path, uncommitted_changes};
+use crate::utils::workspace_globs::sync_workspace_globs;
+use crate::utils::wrangler::{
+    DEPLOY_SCRIPT, find_wrangler_config, read_wrangler_config, write_wrangler_config,
+};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use regex::Regex;
+use serde_json::{Value, json};
+use serde_yaml::{Mapping, Value as Yaml};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Pages-only key naming the build output; Workers serve it through `assets.directory`
+const PAGES_OUTPUT_KEY: &str = "pages_build_output_dir";
+
+/// Where Pages projects keep `_routes.json`, `_headers` and `_redirects`, besides the output
+const STATIC_DIRECTORIES: [&str; 3] = ["public", "static", "."];
+
+/// Label for the top-level Wrangler configuration, which deploys without `--env`
+const PRODUCTION: &str = "production";
+
+/// Routing Worker written by `--routing-worker`
+const ROUTING_WORKER: &str = "src/worker.ts";
+
+/// Binding the routing Worker reaches the static assets through
+const ASSETS_BINDING: &str = "ASSETS";
+
+/// Where `migrate c3` keeps the original files while the workspace is generated
+const C3_STAGING_DIR: &str = ".moonflare-c3";
+
+/// Project scripts the workspace's Moon tasks and moonflare commands already cover
+const COVERED_SCRIPTS: [&str; 9] = [
+    "dev", "start", "build", "deploy", "test", "lint", "format", "check", "preview",
+];
+
+/// Root files a Turborepo or Nx repository keeps over the generated workspace's
+const PRESERVED_ROOT_FILES: [&str; 4] = ["README.md", "justfile", ".npmrc", "pnpm-workspace.yaml"];
+
+/// Root lockfiles of other package managers, replaced by pnpm's
+const REPLACED_LOCKFILES: [&str; 3] = ["package-lock.json", "yarn.lock", "bun.lockb"];
+
+/// Scripts moonflare's inherited `dev` and `deploy` tasks run
+const INHERITED_SCRIPTS: [&str; 2] = ["dev", "deploy"];
+
+pub struct MigrateCommand {
+    ui: MoonflareUI,
+}
+
+impl MigrateCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Convert a Cloudflare Pages project to a Worker serving static assets
+    pub async fn execute_pages_to_worker(&self, project: &str, routing_worker: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let Some(project) = find_project(project) else {
+            return Err(project_not_found(project));
+        };
+
+        let root = project.path.clone();
+        let config = read_wrangler_config(&root)?;
+        let package = fs::read_to_string(root.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+        let pages_script = package.as_ref().and_then(|package| {
+            package["scripts"]
+                .as_object()?
+                .values()
+                .filter_map(Value::as_str)
+                .find(|script| script.contains("wrangler pages"))
+                .map(String::from)
+        });
+        let routes_file = find_static_file(&root, "_routes.json");
+        let is_pages = config
+            .as_ref()
+            .is_some_and(|config| config.get(PAGES_OUTPUT_KEY).is_some())
+            || pages_script.is_some()
+            || routes_file.is_some();
+        if !is_pages {
+            bail!(
+                "'{}' doesn't look like a Pages project: no {}, 'wrangler pages' script or _routes.json",
+                project.name,
+                PAGES_OUTPUT_KEY
+            );
+        }
+
+        let output_dir = config
+            .as_ref()
+            .and_then(|config| config.get(PAGES_OUTPUT_KEY)?.as_str().map(String::from))
+            .or_else(|| pages_script.as_deref().and_then(pages_deploy_directory))
+            .unwrap_or_else(|| "./dist".to_string());
+        let worker_path = root.join(ROUTING_WORKER);
+        if routing_worker && worker_path.exists() {
+            bail!("{} already exists", worker_path.display());
+        }
+
+        self.ui
+            .render_header(
+                "Migrating Pages project",
+                Some(&format!(
+                    "Converting '{}' to a Worker serving {} as static assets",
+                    project.name, output_dir
+                )),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let snapshot = create_snapshot(
+            &format!("migrate pages-to-worker {}", project.name),
+            &[project.path.as_path()],
+        )?;
+        println!("Saved snapshot {}", snapshot.id);
+
+        let routes = match &routes_file {
+            Some(path) => read_routes(path)?,
+            None => Routes::default(),
+        };
+        let functions = function_files(&root);
+
+        let config_path =
+            find_wrangler_config(&root).unwrap_or_else(|| root.join("wrangler.jsonc"));
+        let mut config = config.unwrap_or_else(|| json!({}));
+        convert_config(&project, &mut config, &output_dir, routing_worker, &routes)?;
+        write_wrangler_config(&config_path, &config)?;
+        println!(
+            "Updated {} to serve {} as Workers static assets",
+            file_name(&config_path),
+            output_dir
+        );
+
+        if routing_worker {
+            if let Some(parent) = worker_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&worker_path, render_routing_worker(&routes, &functions))?;
+            println!(
+                "Wrote {} for the routes Pages Functions served",
+                ROUTING_WORKER
+            );
+        }
+
+        // `run_worker_first` replaces `_routes.json`, which only means something with a Worker
+        match (&routes_file, routing_worker) {
+            (Some(path), true) => {
+                fs::remove_file(path)?;
+                println!(
+                    "Removed {}; its routes are now assets.run_worker_first",
+                    path.display()
+                );
+            }
+            (Some(path), false) => println!(
+                "Kept {}: without --routing-worker there's no Worker to route to",
+                path.display()
+            ),
+            _ => {}
+        }
+
+        // Workers static assets read `_headers` and `_redirects` from the assets directory, and
+        // only files under public/ end up there after a build
+        for file in ["_headers", "_redirects"] {
+            let at_root = root.join(file);
+            if at_root.is_file() && root.join("public").is_dir() {
+                let target = root.join("public").join(file);
+                if !target.exists() {
+                    fs::rename(&at_root, &target)?;
+                    println!(
+                        "Moved {} to public/ so builds copy it into the assets",
+                        file
+                    );
+                }
+            } else if find_static_file(&root, file).is_some() {
+                println!("Kept {}; Workers static assets apply it as Pages did", file);
+            }
+        }
+
+        if let Some(package) = package {
+            let updated = convert_scripts(package.clone());
+            if updated != package {
+                fs::write(
+                    root.join("package.json"),
+                    serde_json::to_string_pretty(&updated)? + "\n",
+                )?;
+                println!(
+                    "Switched package.json scripts from 'wrangler pages' to 'wrangler deploy'"
+                );
+            }
+        }
+
+        let moon_yml = root.join("moon.yml");
+        if let Ok(content) = fs::read_to_string(&moon_yml) {
+            let updated = replace_pages_commands(&content);
+            if updated != content {
+                fs::write(&moon_yml, updated)?;
+                println!("Switched moon.yml tasks from 'wrangler pages' to 'wrangler deploy'");
+            }
+        }
+
+        if !functions.is_empty() && !routing_worker {
+            println!(
+                "Warning: {} Pages Function(s) in functions/ won't run on a Worker. Rerun with --routing-worker to generate one, or restore snapshot {}.",
+                functions.len(),
+                snapshot.id
+            );
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Migrated '{}' to Workers static assets; deploy it with 'moonflare deploy {}'",
+                project.name, project.name
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Turn a single-Worker repository created with `npm create cloudflare` into a moonflare
+    /// workspace, in place: the Worker moves under `workers/` (or `sites/`/`apps/` for
+    /// framework templates) and the workspace is generated around it
+    pub async fn execute_c3(&self, path: &str, name: Option<&str>, force: bool) -> Result<()> {
+        let root = Path::new(path)
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", path))?;
+        if !root.is_dir() {
+            bail!("'{}' isn't a directory", path);
+        }
+        if root.join(".moon/workspace.yml").exists() || root.join("moonflare.toml").exists() {
+            bail!(
+                "'{}' is already a workspace; use 'moonflare import' to bring a C3 project into it",
+                path
+            );
+        }
+
+        let detection = detect(&root)?;
+        if detection.archetype != Archetype::CreateCloudflare {
+            bail!(
+                "'{}' doesn't look like a create-cloudflare project (a Wrangler config and a cf-typegen script)",
+                path
+            );
+        }
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => fs::read_to_string(root.join("package.json"))
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+                .and_then(|package| package["name"].as_str().map(String::from))
+                .filter(|name| !name.is_empty() && !name.starts_with('@'))
+                .or_else(|| {
+                    root.file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                })
+                .context("Can't derive a project name; pass --name")?,
+        };
+        check_moon_installation().context("Moon is needed to set up the workspace")?;
+
+        let original_dir = std::env::current_dir()?;
+        std::env::set_current_dir(&root)?;
+        let result = self.migrate_c3(&root, &name, &detection, force).await;
+        std::env::set_current_dir(original_dir)?;
+        result
+    }
+
+    async fn migrate_c3(
+        &self,
+        root: &Path,
+        name: &str,
+        detection: &Detection,
+        force: bool,
+    ) -> Result<()> {
+        let changes = uncommitted_changes(&[Path::new(".")]).with_context(|| {
+            "Couldn't check for uncommitted changes, so the migration couldn't be reverted on its own; pass --force to migrate anyway"
+        });
+        let changes = match changes {
+            Ok(changes) => changes,
+            Err(e) if !force => return Err(e),
+            Err(_) => Vec::new(),
+        };
+        if !changes.is_empty() && !force {
+            bail!(
+                "{} uncommitted change(s); commit or stash them first so the migration can be reviewed and reverted on its own, or pass --force",
+                changes.len()
+            );
+        }
+
+        self.ui
+            .render_header(
+                "Migrating a create-cloudflare project",
+                Some(&format!(
+                    "Moving the {} project '{}' into a new workspace at {}",
+                    detection.project_type,
+                    name,
+                    root.display()
+                )),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        // Move everything but the repository aside, so the workspace renders into an empty root
+        let staging = Path::new(C3_STAGING_DIR);
+        let mut entries: Vec<PathBuf> = fs::read_dir(".")?
+            .flatten()
+            .map(|entry| PathBuf::from(entry.file_name()))
+            .filter(|entry| entry != Path::new(".git") && entry != staging)
+            .collect();
+        entries.sort();
+        for entry in &entries {
+            move_path(entry, &staging.join(entry))?;
+        }
+
+        InitCommand::new()
+            .execute(".", None, true, false, PnpmConfig::default())
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "{}\nThe original files are in {}; move them back to undo the migration",
+                    e,
+                    root.join(staging).display()
+                )
+            })?;
+
+        let target = Path::new(&get_project_directory(detection.project_type)).join(name);
+        move_path(staging, &target)?;
+        println!("Moved the project to {}", target.display());
+
+        let worker_name = MoonflareConfig::load()?.worker_name(name);
+        for change in adapt_typescript_project(&target, name, &worker_name, detection)? {
+            println!("  {}", change);
+        }
+        for script in merge_root_scripts(&target, name)? {
+            println!("  Added a root '{}' script running it in {}", script, name);
+        }
+        for drift in sync_workspace_globs(Path::new("."))? {
+            println!("Updated {}: {}", drift.source, drift.describe());
+        }
+        for path in sync_aliases()? {
+            println!("Updated aliases in {}", path.display());
+        }
+        for change in sync_cargo_workspace()? {
+            println!("Updated {}: {}", CARGO_WORKSPACE_FILE, change);
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Migrated '{}' into a moonflare workspace. Run 'pnpm install', then 'moonflare dev {}'",
+                name, name
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+
+    /// Turn a Turborepo or Nx workspace into a moonflare workspace, in place: pipeline tasks
+    /// become Moon tasks, Cloudflare projects move into the layout (or the layout adopts their
+    /// directories) and shared packages stay where they are
+    pub async fn execute_monorepo(
+        &self,
+        tool: MonorepoTool,
+        path: &str,
+        keep_layout: bool,
+        force: bool,
+    ) -> Result<()> {
+        let root = Path::new(path)
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", path))?;
+        if !root.is_dir() {
+            bail!("'{}' isn't a directory", path);
+        }
+        if root.join(".moon/workspace.yml").exists() || root.join("moonflare.toml").exists() {
+            bail!("'{}' is already a moonflare workspace", path);
+        }
+        if !root.join(tool.config_file()).is_file() {
+            bail!(
+                "'{}' has no {}; is it a {} workspace?",
+                path,
+                tool.config_file(),
+                tool.label()
+            );
+        }
+        check_moon_installation().context("Moon is needed to set up the workspace")?;
+
+        let original_dir = std::env::current_dir()?;
+        std::env::set_current_dir(&root)?;
+        let result = self.migrate_monorepo(&root, tool, keep_layout, force).await;
+        std::env::set_current_dir(original_dir)?;
+        result
+    }
+
+    async fn migrate_monorepo(
+        &self,
+        root: &Path,
+        tool: MonorepoTool,
+        keep_layout: bool,
+        force: bool,
+    ) -> Result<()> {
+        let changes = uncommitted_changes(&[Path::new(".")]).with_context(|| {
+            "Couldn't check for uncommitted changes, so the migration couldn't be reverted on its own; pass --force to migrate anyway"
+        });
+        let changes = match changes {
+            Ok(changes) => changes,
+            Err(e) if !force => return Err(e),
+            Err(_) => Vec::new(),
+        };
+        if !changes.is_empty() && !force {
+            bail!(
+                "{} uncommitted change(s); commit or stash them first so the migration can be reviewed and reverted on its own, or pass --force",
+                changes.len()
+            );
+        }
+
+        let pipeline = Pipeline::load(Path::new("."), tool)?;
+        if pipeline.projects.is_empty() {
+            bail!("Found no projects in {}", root.display());
+        }
+        let mut notes = pipeline.notes.clone();
+
+        // Cloudflare projects move into the layout; everything else is a shared package
+        let placements: Vec<(&SourceProject, Option<(&'static str, Detection)>)> = pipeline
+            .projects
+            .iter()
+            .map(|project| (project, cloudflare_kind(&project.path)))
+            .collect();
+
+        let mut layout = LayoutConfig::default();
+        let mut adopted: Vec<(&'static str, String)> = Vec::new();
+        if keep_layout {
+            for (kind, project_type) in LayoutConfig::KINDS {
+                let of_kind = |placement: &Option<(&str, Detection)>| {
+                    placement.as_ref().is_some_and(|(k, _)| *k == kind)
+                };
+                let parents: BTreeSet<&Path> = placements
+                    .iter()
+                    .filter(|(_, placement)| of_kind(placement))
+                    .filter_map(|(project, _)| project.path.parent())
+                    .collect();
+                let Some(&parent) = parents.first() else {
+                    continue;
+                };
+                let directory = parent.to_string_lossy().into_owned();
+                let shared = placements.iter().any(|(project, placement)| {
+                    project.path.parent() == Some(parent) && !of_kind(placement)
+                });
+                let taken = layout
+                    .directories()
+                    .iter()
+                    .any(|(other, other_type)| *other == directory && *other_type != project_type);
+                if parents.len() > 1
+                    || parent.components().count() != 1
+                    || shared
+                    || taken
+                    || validate_directory(&directory).is_err()
+                {
+                    notes.push(format!(
+                        "Couldn't keep the {} where they are, since {}; they move to {}/",
+                        kind,
+                        if parents.len() > 1 {
+                            "they're spread over several directories".to_string()
+                        } else {
+                            format!(
+                                "{}/ holds other projects or can't be a layout directory",
+                                directory
+                            )
+                        },
+                        layout.directory(kind).unwrap_or(kind)
+                    ));
+                    continue;
+                }
+                if layout.directory(kind) != Some(directory.as_str()) {
+                    layout.set(kind, &directory);
+                    adopted.push((kind, directory));
+                }
+            }
+        }
+
+        let mut targets = Vec::new();
+        for (project, placement) in &placements {
+            let target = match placement {
+                Some((kind, _)) => {
+                    Path::new(layout.directory(kind).unwrap_or(kind)).join(&project.id)
+                }
+                None => project.path.clone(),
+            };
+            if target != project.path && target.exists() {
+                bail!(
+                    "Can't move {} to {}: it already exists",
+                    project.path.display(),
+                    target.display()
+                );
+            }
+            targets.push(target);
+        }
+
+        self.ui
+            .render_header(
+                &format!("Migrating a {} workspace", tool.label()),
+                Some(&format!(
+                    "Converting {} project(s) at {} into a moonflare workspace",
+                    placements.len(),
+                    root.display()
+                )),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        // The workspace generator writes these too; the repository's own versions win
+        let preserved: Vec<(&str, String)> = PRESERVED_ROOT_FILES
+            .iter()
+            .filter_map(|file| Some((*file, fs::read_to_string(file).ok()?)))
+            .collect();
+        let gitignore = fs::read_to_string(".gitignore").ok();
+        let package = fs::read_to_string("package.json")
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+
+        InitCommand::new()
+            .execute(".", None, true, false, PnpmConfig::default())
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "{}\nRun 'git checkout . && git clean -fd' to undo the migration",
+                    e
+                )
+            })?;
+
+        for (file, content) in &preserved {
+            fs::write(file, content)?;
+        }
+        if let Some(original) = gitignore {
+            let generated = fs::read_to_string(".gitignore").unwrap_or_default();
+            let missing: Vec<&str> = generated
+                .lines()
+                .filter(|line| !line.trim().is_empty() && !original.lines().any(|l| l == *line))
+                .collect();
+            let mut merged = original.trim_end().to_string();
+            if !missing.is_empty() {
+                merged.push_str("\n\n");
+                merged.push_str(&missing.join("\n"));
+            }
+            fs::write(".gitignore", merged + "\n")?;
+        }
+        if let Some(original) = package {
+            for change in merge_root_package(tool, original)? {
+                println!("  {}", change);
+            }
+        }
+        for lockfile in REPLACED_LOCKFILES {
+            if Path::new(lockfile).is_file() {
+                fs::remove_file(lockfile)?;
+                println!(
+                    "Removed {}; pnpm manages the workspace's dependencies",
+                    lockfile
+                );
+            }
+        }
+        if !adopted.is_empty() {
+            let config = fs::read_to_string(CONFIG_FILE).unwrap_or_default();
+            let keys: Vec<(&str, &str)> = adopted
+                .iter()
+                .map(|(kind, directory)| (*kind, directory.as_str()))
+                .collect();
+            fs::write(CONFIG_FILE, set_layout_keys(&config, &keys))?;
+            for (kind, directory) in &adopted {
+                println!(
+                    "Kept {} in {}/ ([layout] {} = \"{}\")",
+                    kind, directory, kind, directory
+                );
+            }
+        }
+
+        let worker_names = MoonflareConfig::load()?;
+        for ((project, placement), target) in placements.iter().zip(&targets) {
+            if *target != project.path {
+                move_path(&project.path, target)?;
+                println!("Moved {} to {}", project.path.display(), target.display());
+            }
+
+            let moon_yml = target.join("moon.yml");
+            let mut tasks = pipeline.moon_tasks(project, &mut notes);
+            let depends_on = pipeline.depends_on(project, &mut notes);
+            if let Some((_, detection)) = placement {
+                let package_name = fs::read_to_string(target.join("package.json"))
+                    .ok()
+                    .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+                    .and_then(|package| package["name"].as_str().map(String::from))
+                    .unwrap_or_else(|| project.id.clone());
+                let worker_name = worker_names.worker_name(&project.id);
+                for change in
+                    adapt_typescript_project(target, &package_name, &worker_name, detection)?
+                {
+                    println!("  {}", change);
+                }
+                // moonflare's inherited tasks run the same scripts
+                for inherited in INHERITED_SCRIPTS {
+                    tasks.remove(inherited);
+                }
+            }
+
+            let mut config: Mapping = match fs::read_to_string(&moon_yml) {
+                Ok(content) => serde_yaml::from_str(&content)
+                    .with_context(|| format!("Failed to parse {}", moon_yml.display()))?,
+                Err(_) => library_moon_config(target),
+            };
+            let translated: Vec<String> = tasks
+                .keys()
+                .filter_map(|name| name.as_str().map(String::from))
+                .collect();
+            if !tasks.is_empty() {
+                let existing = config
+                    .entry("tasks".into())
+                    .or_insert_with(|| Yaml::Mapping(Mapping::new()));
+                if let Some(existing) = existing.as_mapping_mut() {
+                    existing.extend(tasks);
+                }
+            }
+            if !depends_on.is_empty() {
+                config.insert(
+                    "dependsOn".into(),
+                    Yaml::Sequence(depends_on.into_iter().map(Yaml::String).collect()),
+                );
+            }
+            fs::write(&moon_yml, serde_yaml::to_string(&config)?)?;
+            if translated.is_empty() {
+                println!("Wrote {}", moon_yml.display());
+            } else {
+                println!(
+                    "Wrote {} with the {} task(s) {}",
+                    moon_yml.display(),
+                    tool.label(),
+                    translated.join(", ")
+                );
+            }
+        }
+
+        fs::remove_file(tool.config_file())?;
+        println!(
+            "Removed {}; its tasks are in each project's moon.yml",
+            tool.config_file()
+        );
+        for drift in sync_workspace_globs(Path::new("."))? {
+            println!("Updated {}: {}", drift.source, drift.describe());
+        }
+        for path in sync_aliases()? {
+            println!("Updated aliases in {}", path.display());
+        }
+        for change in sync_cargo_workspace()? {
+            println!("Updated {}: {}", CARGO_WORKSPACE_FILE, change);
+        }
+
+        if !notes.is_empty() {
+            println!();
+            println!(
+                "{}",
+                format!("Not translated from {}:", tool.label())
+                    .yellow()
+                    .bold()
+            );
+            for note in &notes {
+                println!("  - {}", note);
+            }
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Migrated {} project(s) from {}. Run 'pnpm install', then 'moonflare build'",
+                placements.len(),
+                tool.label()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+
+    /// List applied and pending D1 and Durable Object migrations of each project, per
+    /// environment
+    pub async fn execute_status(
+        &self,
+        project: Option<&str>,
+        environments: &[String],
+        json: bool,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects: Vec<WorkspaceProject> = match project {
+            Some(name) => vec![find_project(name).ok_or_else(|| project_not_found(name))?],
+            None => discover_projects()
+                .into_iter()
+                .filter(|project| project.has_wrangler_config())
+                .collect(),
+        };
+
+        let mut statuses = Vec::new();
+        #[cfg(feature = "api")]
+        let mut client = None;
+        for project in &projects {
+            let Some(config) = read_wrangler_config(&project.path)? else {
+                continue;
+            };
+            // Every environment the project declares unless some are asked for
+            let scopes: Vec<Option<String>> = if environments.is_empty() {
+                std::iter::once(None)
+                    .chain(
+                        config["env"]
+                            .as_object()
+                            .into_iter()
+                            .flat_map(|envs| envs.keys().cloned().map(Some)),
+                    )
+                    .collect()
+            } else {
+                environments
+                    .iter()
+                    .map(|env| (env != PRODUCTION).then(|| env.clone()))
+                    .collect()
+            };
+            for scope in &scopes {
+                let env = scope.as_deref();
+                statuses.extend(d1_status(project, &config, env)?);
+                // Without an API token, Durable Object migrations are listed as unknown
+                #[cfg(feature = "api")]
+                let status = {
+                    let client = client.get_or_insert_with(|| {
+                        CloudflareClient::from_env(
+                            config["account_id"].as_str(),
+                            SCRIPTS_PERMISSION,
+                        )
+                        .map_err(|e| e.to_string())
+                    });
+                    durable_object_status(project, &config, env, client).await?
+                };
+                #[cfg(not(feature = "api"))]
+                let status = durable_object_status(project, &config, env);
+                statuses.extend(status);
+            }
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&statuses)?);
+            return Ok(());
+        }
+
+        self.ui
+            .render_header(
+                "Migrations",
+                Some("Applied and pending D1 and Durable Object migrations per environment"),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if statuses.is_empty() {
+            println!("No project has D1 migration files or Durable Object migrations");
+            return Ok(());
+        }
+        print!("{}", render_status_table(&statuses));
+
+        let pending: Vec<&MigrationStatus> =
+            statuses.iter().filter(|s| !s.pending.is_empty()).collect();
+        for status in &pending {
+            println!();
+            println!(
+                "{}",
+                format!(
+                    "{} {} ({}) has {} pending:",
+                    status.project,
+                    status.target,
+                    status.environment.as_deref().unwrap_or(PRODUCTION),
+                    status.pending.len()
+                )
+                .yellow()
+            );
+            for name in &status.pending {
+                println!("  {}", name);
+            }
+        }
+        if pending
+            .iter()
+            .any(|status| status.target.starts_with("d1:"))
+        {
+            println!();
+            println!(
+                "{}",
+                "Apply D1 migrations with 'pnpm exec wrangler d1 migrations apply <DATABASE> --remote'; Durable Object migrations apply on the next deploy".dimmed()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// One row per database or Worker and environment
+fn render_status_table(statuses: &[MigrationStatus]) -> String {
+    let rows: Vec<[String; 5]> = statuses
+        .iter()
+        .map(|status| {
+            let (applied, pending) = match &status.unknown {
+                Some(_) => ("?".to_string(), "?".to_string()),
+                None => (
+                    status.applied.len().to_string(),
+                    status.pending.len().to_string(),
+                ),
+            };
+            [
+                status.project.clone(),
+                status
+                    .environment
+                    .clone()
+                    .unwrap_or_else(|| PRODUCTION.to_string()),
+                status.target.clone(),
+                applied,
+                match &status.unknown {
+                    Some(reason) => format!("{} ({})", pending, reason),
+                    None => pending,
+                },
+            ]
+        })
+        .collect();
+
+    let headers = ["PROJECT", "ENVIRONMENT", "TARGET", "APPLIED", "PENDING"];
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain(std::iter::once(headers[column].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: [&str; 5]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        format!("  {}\n", padded.join("  ").trim_end())
+    };
+
+    let mut table = line(headers);
+    for row in &rows {
+        table.push_str(&line([&row[0], &row[1], &row[2], &row[3], &row[4]]));
+    }
+    table
+}
+
+/// Swap Pages' output directory for a Workers `assets` table
+fn convert_config(
+    project: &WorkspaceProject,
+    config: &mut Value,
+    output_dir: &str,
+    routing_worker: bool,
+    routes: &Routes,
+) -> Result<()> {
+    let Some(object) = config.as_object_mut() else {
+        bail!("The Wrangler configuration isn't a table");
+    };
+    object.remove(PAGES_OUTPUT_KEY);
+    if !object.contains_key("name") {
+        let name = MoonflareConfig::load()?.worker_name(&project.name);
+        object.insert("name".to_string(), json!(name));
+    }
+    if !object.contains_key("compatibility_date") {
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        object.insert("compatibility_date".to_string(), json!(today));
+    }
+    if routing_worker {
+        object.insert("main".to_string(), json!(ROUTING_WORKER));
+    }
+
+    // Pages falls back to index.html for unmatched paths when there's no 404 page; apps
+    // relying on that need the SPA handling Workers leave off by default
+    let not_found = if project.path.starts_with(LayoutConfig::current().sites) {
+        "404-page"
+    } else {
+        "single-page-application"
+    };
+    let mut assets = serde_json::Map::new();
+    assets.insert("directory".to_string(), json!(output_dir));
+    assets.insert("not_found_handling".to_string(), json!(not_found));
+    if routing_worker {
+        assets.insert("binding".to_string(), json!(ASSETS_BINDING));
+        let run_worker_first = routes.run_worker_first();
+        if !run_worker_first.is_empty() {
+            assets.insert("run_worker_first".to_string(), json!(run_worker_first));
+        }
+    }
+    object.insert("assets".to_string(), Value::Object(assets));
+    Ok(())
+}
+
+/// Include and exclude rules from Pages' `_routes.json`
+#[derive(Debug, Default)]
+struct Routes {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl Routes {
+    /// `assets.run_worker_first` patterns: includes as-is, excludes negated
+    fn run_worker_first(&self) -> Vec<String> {
+        self.include
+            .iter()
+            .cloned()
+            .chain(self.exclude.iter().map(|rule| format!("!{}", rule)))
+            .collect()
+    }
+}
+
+fn read_routes(path: &Path) -> Result<Routes> {
+    let content = fs::read_to_string(path)?;
+    let routes: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let rules = |key: &str| {
+        routes[key]
+            .as_array()
+            .map(|rules| {
+                rules
+                    .iter()
+                    .filter_map(|rule| rule.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    Ok(Routes {
+        include: rules("include"),
+        exclude: rules("exclude"),
+    })
+}
+
+/// A Pages file such as `_routes.json` in public/, static/ or the project root
+fn find_static_file(root: &Path, file: &str) -> Option<PathBuf> {
+    STATIC_DIRECTORIES
+        .iter()
+        .map(|directory| root.join(directory).join(file))
+        .find(|path| path.is_file())
+}
+
+/// Pages Functions, as paths relative to the project
+fn function_files(root: &Path) -> Vec<String> {
+    let functions = root.join("functions");
+    let mut files: Vec<String> = WalkDir::new(&functions)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(root)
+                .ok()
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// The directory argument of `wrangler pages deploy <dir>`
+fn pages_deploy_directory(script: &str) -> Option<String> {
+    let deploy = Regex::new(r"wrangler pages deploy\s+([^-\s]\S*)").expect("valid deploy regex");
+    deploy.captures(script).map(|caps| caps[1].to_string())
+}
+
+fn convert_scripts(mut package: Value) -> Value {
+    if let Some(scripts) = package["scripts"].as_object_mut() {
+        for (name, script) in scripts.iter_mut() {
+            let Some(command) = script.as_str() else {
+                continue;
+            };
+            if command.contains("wrangler pages deploy") {
+                *script = json!(if name == "deploy" {
+                    DEPLOY_SCRIPT.to_string()
+                } else {
+                    replace_pages_commands(command)
+                });
+            } else if command.contains("wrangler pages") {
+                *script = json!(replace_pages_commands(command));
+            }
+        }
+    }
+    package
+}
+
+/// `wrangler pages deploy <dir>` becomes `wrangler deploy` and `wrangler pages dev <dir>`
+/// becomes `wrangler dev`; the assets directory now comes from the Wrangler configuration
+fn replace_pages_commands(content: &str) -> String {
+    let pages = Regex::new(r#"wrangler pages (deploy|dev)(\s+[^-\s'"][^\s'"]*)?"#)
+        .expect("valid pages regex");
+    pages.replace_all(content, "wrangler $1").into_owned()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn render_routing_worker(routes: &Routes, functions: &[String]) -> String {
+    let mut todo = String::new();
+    if functions.is_empty() {
+        todo.push_str("\t\t// Handle requests matched by assets.run_worker_first here\n");
+    } else {
+        todo.push_str("\t\t// Port these Pages Functions, then handle their routes here:\n");
+        for file in functions {
+            todo.push_str(&format!("\t\t//   {}\n", file));
+        }
+    }
+    let routes = routes
+        .run_worker_first()
+        .iter()
+        .map(|rule| format!("\n *   {}", rule))
+        .collect::<String>();
+    let routes = if routes.is_empty() {
+        String::from("\n *   (none; every request is served from static assets)")
+    } else {
+        routes
+    };
+
+    format!(
+        r#"/**
+ * Routing Worker generated by `moonflare migrate pages-to-worker`.
+ *
+ * Static assets are served before this Worker runs, except for the routes listed under
+ * assets.run_worker_first in the Wrangler configuration:{}
+ */
+interface Env {{
+	{}: Fetcher;
+}}
+
+export default {{
+	async fetch(request: Request, env: Env): Promise<Response> {{
+{}		return env.{}.fetch(request);
+	}},
+}} satisfies ExportedHandler<Env>;
+"#,
+        routes, ASSETS_BINDING, todo, ASSETS_BINDING
+    )
+}
+
+/// Give the workspace root a script for each of the project's own scripts, such as C3's
+/// `cf-typegen`, running it in the project. Returns the scripts added.
+fn merge_root_scripts(project: &Path, name: &str) -> Result<Vec<String>> {
+    let package: Value = serde_json::from_str(&fs::read_to_string(project.join("package.json"))?)?;
+    let root_path = Path::new("package.json");
+    let mut root: Value = serde_json::from_str(&fs::read_to_string(root_path)?)?;
+    if !root["scripts"].is_object() {
+        root["scripts"] = json!({});
+    }
+
+    let mut added = Vec::new();
+    for script in package["scripts"]
+        .as_object()
+        .into_iter()
+        .flat_map(|scripts| scripts.keys())
+    {
+        if COVERED_SCRIPTS.contains(&script.as_str()) || root["scripts"].get(script).is_some() {
+            continue;
+        }
+        root["scripts"][script] = json!(format!("pnpm --filter {} {}", name, script));
+        added.push(script.clone());
+    }
+    if !added.is_empty() {
+        fs::write(root_path, serde_json::to_string_pretty(&root)? + "\n")?;
+    }
+    Ok(added)
+}
+
+/// The layout key and detection of a Cloudflare project: a Worker, or an Astro or Vite
+/// frontend. `None` for shared packages, which stay where they are.
+fn cloudflare_kind(path: &Path) -> Option<(&'static str, Detection)> {
+    let detection = detect(path).ok()?;
+    let cloudflare = detection.wrangler_config.is_some()
+        || matches!(
+            detection.archetype,
+            Archetype::CreateAstro | Archetype::CreateVite | Archetype::CreateCloudflare
+        );
+    let (kind, _) = LayoutConfig::KINDS
+        .iter()
+        .find(|(_, project_type)| *project_type == detection.project_type)?;
+    (cloudflare && *kind != "crates").then_some((*kind, detection))
+}
+
+/// moon.yml for a shared package, which has no dev server and isn't deployed
+fn library_moon_config(root: &Path) -> Mapping {
+    let language = if root.join("package.json").is_file() {
+        "typescript"
+    } else {
+        "unknown"
+    };
+    let mut config = Mapping::new();
+    config.insert(
+        "$schema".into(),
+        "https://moonrepo.dev/schemas/project.json".into(),
+    );
+    config.insert("language".into(), language.into());
+    config.insert("layer".into(), "library".into());
+
+    let mut inherited = Mapping::new();
+    inherited.insert(
+        "exclude".into(),
+        Yaml::Sequence(INHERITED_SCRIPTS.iter().map(|s| (*s).into()).collect()),
+    );
+    let mut workspace = Mapping::new();
+    workspace.insert("inheritedTasks".into(), Yaml::Mapping(inherited));
+    config.insert("workspace".into(), Yaml::Mapping(workspace));
+    config
+}
+
+/// Keep the repository's root package.json, minus the monorepo tool: its dependencies go and
+/// the scripts running it make way for the workspace's `moon run` ones. Returns the changes.
+fn merge_root_package(tool: MonorepoTool, mut package: Value) -> Result<Vec<String>> {
+    let path = Path::new("package.json");
+    let generated: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let mut changes = Vec::new();
+
+    for section in ["dependencies", "devDependencies"] {
+        if let Some(dependencies) = package[section].as_object_mut() {
+            let owned: Vec<String> = dependencies
+                .keys()
+                .filter(|dependency| tool.owns_dependency(dependency))
+                .cloned()
+                .collect();
+            for dependency in owned {
+                dependencies.remove(&dependency);
+                changes.push(format!("Removed {} from the root {}", dependency, section));
+            }
+        }
+    }
+    if let Some(object) = package.as_object_mut()
+        && object.remove("workspaces").is_some()
+    {
+        changes.push(
+            "Removed package.json workspaces; pnpm-workspace.yaml lists the projects".to_string(),
+        );
+    }
+
+    if !package["scripts"].is_object() {
+        package["scripts"] = json!({});
+    }
+    let scripts = package["scripts"]
+        .as_object_mut()
+        .expect("scripts is an object");
+    for (script, command) in generated["scripts"].as_object().into_iter().flatten() {
+        match scripts.get(script).and_then(Value::as_str) {
+            Some(existing) if !tool.runs_in_script(existing) => continue,
+            Some(existing) => changes.push(format!(
+                "Replaced the root '{}' script ({}) with '{}'",
+                script,
+                existing,
+                command.as_str().unwrap_or_default()
+            )),
+            None => {}
+        }
+        scripts.insert(script.clone(), command.clone());
+    }
+    let stale: Vec<String> = scripts
+        .iter()
+        .filter(|(_, command)| command.as_str().is_some_and(|c| tool.runs_in_script(c)))
+        .map(|(script, _)| script.clone())
+        .collect();
+    for script in stale {
+        scripts.remove(&script);
+        changes.push(format!(
+            "Removed the root '{}' script, which ran {}",
+            script,
+            tool.label()
+        ));
+    }
+
+    for (dependency, version) in generated["devDependencies"]
+        .as_object()
+        .into_iter()
+        .flatten()
+    {
+        if !package["devDependencies"].is_object() {
+            package["devDependencies"] = json!({});
+        }
+        if package["devDependencies"].get(dependency).is_none() {
+            package["devDependencies"][dependency] = version.clone();
+        }
+    }
+    if package.get("engines").and_then(|e| e.get("node")).is_none() {
+        if !package["engines"].is_object() {
+            package["engines"] = json!({});
+        }
+        package["engines"]["node"] = generated["engines"]["node"].clone();
+    }
+
+    fs::write(path, serde_json::to_string_pretty(&package)? + "\n")?;
+    Ok(changes)
+}
@@ -1,6 +1,69 @@
+#[cfg(feature = "api")]
+pub mod access;
 pub mod add;
+#[cfg(feature = "api")]
+pub mod ai;
+pub mod archive;
+pub mod audit_bindings;
+pub mod bench;
 pub mod build;
+#[cfg(feature = "api")]
+pub mod builds;
+pub mod bundle;
+pub mod ci;
+pub mod clean;
+pub mod config;
+pub mod d1;
+pub mod daemon;
 pub mod deploy;
+pub mod describe;
 pub mod dev;
+pub mod devcontainer;
+pub mod docs;
+pub mod doctor;
+pub mod durable_objects;
+pub mod env;
+pub mod examples;
+pub mod flags;
+pub mod grep;
+pub mod history;
+pub mod hooks;
+pub mod import;
 pub mod init;
+pub mod kv;
+pub mod layout;
+pub mod licenses;
+pub mod loadtest;
+pub mod meta;
+pub mod migrate;
+pub mod nix;
+pub mod placement;
+pub mod ports;
+pub mod preview;
+pub mod promote;
+pub mod publish;
+pub mod queues;
+#[cfg(feature = "api")]
+pub mod r2;
+pub mod refactor;
+pub mod release;
 pub mod rename;
+pub mod repro;
+pub mod restore;
+pub mod routes;
+pub mod sbom;
+pub mod secrets;
+pub mod seed;
+pub mod self_update;
+pub mod stats;
+pub mod sync_names;
+pub mod tag;
+pub mod tasks;
+pub mod test;
+pub mod token;
+pub mod types;
+pub mod upgrade;
+pub mod verify_matrix;
+pub mod watch;
+pub mod why;
+pub mod workspace;
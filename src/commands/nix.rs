@@ -0,0 +1,63 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::nix::{self, FLAKE_FILE};
+use crate::utils::toolchain::Toolchain;
+use anyhow::{Result, bail};
+use colored::*;
+use std::path::Path;
+
+pub struct NixCommand {
+    ui: MoonflareUI,
+}
+
+impl NixCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Write flake.nix with a dev shell providing the workspace's pinned toolchain
+    pub fn execute_generate(&self, force: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        if Path::new(FLAKE_FILE).exists() && !force {
+            bail!(
+                "Refusing to overwrite {} (use --force to regenerate)",
+                FLAKE_FILE
+            );
+        }
+
+        let config = MoonflareConfig::load()?;
+        for path in nix::write(&config)? {
+            println!("  {}", path.display());
+        }
+
+        let toolchain = Toolchain::detect(Path::new("."));
+        println!();
+        println!("{}", "Dev shell:".bold());
+        for (tool, package) in [
+            ("node", nix::node_package(&toolchain.node)),
+            ("pnpm", nix::pnpm_package(&toolchain.pnpm)),
+            (
+                "rust",
+                format!("{} (wasm32-unknown-unknown)", toolchain.rust),
+            ),
+            ("moon", "moon".to_string()),
+            ("wrangler", "wrangler".to_string()),
+        ] {
+            println!("  {:<9}{}", tool, package.cyan());
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Generated {}; enter it with 'nix develop' and commit flake.lock to pin nixpkgs",
+                FLAKE_FILE
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,365 @@
+use crate::ui::MoonflareUI;
+use crate::utils::backup::create_snapshot;
+use crate::utils::config::{
+    MoonflareConfig, PlacementMode, ProjectPlacementConfig, config_path as moonflare_config_path,
+};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::placement::{
+    LOCATION_HINTS, ScopePlacement, durable_object_bindings, edits, hint_var, scopes,
+};
+use crate::utils::plan::{self, Plan};
+use crate::utils::projects::{discover_projects, find_project, project_not_found};
+use crate::utils::wrangler::{find_wrangler_config, parse_wrangler_file};
+use crate::utils::wrangler_edit::{ConfigEdit, edit_config, edit_text};
+use anyhow::{Result, bail};
+use colored::*;
+use serde::Serialize;
+use std::fs;
+
+/// Label for the top-level Wrangler configuration, outside any `[env.<name>]`
+const TOP_LEVEL: &str = "(top-level)";
+
+/// What a project's Wrangler configuration sets, next to what moonflare.toml declares
+#[derive(Debug, Serialize)]
+struct ProjectPlacement {
+    project: String,
+    declared: ProjectPlacementConfig,
+    scopes: Vec<ScopeStatus>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScopeStatus {
+    #[serde(flatten)]
+    placement: ScopePlacement,
+    /// Where the scope departs from moonflare.toml
+    drift: Vec<String>,
+}
+
+pub struct PlacementCommand {
+    ui: MoonflareUI,
+}
+
+impl PlacementCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Record the project's placement mode and location hints in moonflare.toml, then write
+    /// them to its Wrangler configuration in every environment, keeping comments
+    pub async fn execute_set(
+        &self,
+        project: &str,
+        mode: Option<PlacementMode>,
+        hints: &[String],
+        clear_hints: &[String],
+        dry_run: bool,
+        yes: bool,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let project = find_project(project).ok_or_else(|| project_not_found(project))?;
+        let Some(config_path) = find_wrangler_config(&project.path) else {
+            bail!("Project '{}' has no Wrangler configuration", project.name);
+        };
+        let wrangler = parse_wrangler_file(&config_path)?;
+        let bindings = durable_object_bindings(&wrangler);
+
+        let mut declared = MoonflareConfig::load()?
+            .project(&project.name)
+            .map(|config| config.placement.clone())
+            .unwrap_or_default();
+        let placement_key = |keys: &[&str]| -> Vec<String> {
+            ["projects", project.name.as_str(), "placement"]
+                .iter()
+                .chain(keys)
+                .map(|key| key.to_string())
+                .collect()
+        };
+        let mut recorded = Vec::new();
+        if let Some(mode) = mode {
+            declared.mode = Some(mode);
+            recorded.push(ConfigEdit::set(&placement_key(&["mode"]), mode.as_str()));
+        }
+        for hint in hints {
+            let Some((binding, region)) = hint.split_once('=') else {
+                bail!("'{}' isn't BINDING=REGION, e.g. COUNTER=weur", hint);
+            };
+            if !bindings.contains(binding) {
+                bail!(
+                    "'{}' isn't a Durable Object binding of {} (bindings: {})",
+                    binding,
+                    project.name,
+                    if bindings.is_empty() {
+                        "none".to_string()
+                    } else {
+                        bindings.iter().cloned().collect::<Vec<_>>().join(", ")
+                    }
+                );
+            }
+            if !LOCATION_HINTS.contains(&region) {
+                bail!(
+                    "'{}' isn't a location hint. Use one of {}",
+                    region,
+                    LOCATION_HINTS.join(", ")
+                );
+            }
+            declared
+                .location_hints
+                .insert(binding.to_string(), region.to_string());
+            recorded.push(ConfigEdit::set(
+                &placement_key(&["location_hints", binding]),
+                region,
+            ));
+        }
+        for binding in clear_hints {
+            declared.location_hints.remove(binding);
+            recorded.push(ConfigEdit::remove(&placement_key(&[
+                "location_hints",
+                binding,
+            ])));
+        }
+
+        let mut plan = Plan::new();
+        if !recorded.is_empty() {
+            let path = moonflare_config_path();
+            let before = fs::read_to_string(&path).unwrap_or_default();
+            plan.write(path, edit_text(&before, true, &recorded)?.content)?;
+        }
+        let changes = edits(&wrangler, &declared);
+        let edited = edit_config(&config_path, &changes)?;
+        if !changes.is_empty() {
+            plan.modify(
+                &config_path,
+                fs::read_to_string(&config_path)?,
+                edited.content.clone(),
+            );
+        }
+
+        if plan.is_empty() {
+            self.ui
+                .render_success(&format!(
+                    "{}'s Wrangler configuration already matches its placement",
+                    project.name
+                ))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+            return Ok(());
+        }
+        self.ui
+            .render_header(&format!("Placement of {}", project.name), None)
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        plan.render();
+        if dry_run || plan::plan_only() {
+            println!("{}", "Dry run: nothing was changed".yellow());
+            return Ok(());
+        }
+        if !plan.confirm(yes)? {
+            bail!("Placement change cancelled");
+        }
+        let snapshot = create_snapshot("placement set", &plan.paths())?;
+        plan.apply()?;
+
+        for change in &changes {
+            println!("  {} {}", "~".yellow(), change.key());
+        }
+        if !edited.preserved {
+            println!(
+                "{}",
+                format!(
+                    "Comments in {} were not preserved; 'moonflare restore {}' brings the old file back.",
+                    config_path.display(),
+                    snapshot.id
+                )
+                .dimmed()
+            );
+        }
+        self.ui
+            .render_success(&format!(
+                "Set the placement of {}: {}",
+                project.name,
+                describe(&declared)
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if let Some(binding) = declared.location_hints.keys().next() {
+            println!(
+                "Pass the hint when getting a stub, e.g. env.{}.get(id, {{ locationHint: env.{} }})",
+                binding,
+                hint_var(binding)
+            );
+        }
+        Ok(())
+    }
+
+    /// The placement mode and location hints every project's Wrangler configuration sets,
+    /// per environment, and where they depart from moonflare.toml
+    pub async fn execute_status(&self, json: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let config = MoonflareConfig::load()?;
+        let mut statuses = Vec::new();
+        for project in discover_projects() {
+            let Some(path) = find_wrangler_config(&project.path) else {
+                continue;
+            };
+            let declared = config
+                .project(&project.name)
+                .map(|config| config.placement.clone())
+                .unwrap_or_default();
+            let scopes = scopes(&parse_wrangler_file(&path)?)
+                .into_iter()
+                .map(|placement| ScopeStatus {
+                    drift: drift(&placement, &declared),
+                    placement,
+                })
+                .collect();
+            statuses.push(ProjectPlacement {
+                project: project.name.clone(),
+                declared,
+                scopes,
+            });
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&statuses)?);
+            return Ok(());
+        }
+
+        self.ui
+            .render_header(
+                "Placement",
+                Some("Smart Placement and Durable Object location hints per environment"),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if statuses.is_empty() {
+            println!("No projects with Wrangler configuration");
+            return Ok(());
+        }
+        print!("{}", render_table(&statuses));
+
+        let drifted: Vec<&str> = statuses
+            .iter()
+            .filter(|status| status.scopes.iter().any(|scope| !scope.drift.is_empty()))
+            .map(|status| status.project.as_str())
+            .collect();
+        if !drifted.is_empty() {
+            println!();
+            println!(
+                "{}",
+                format!(
+                    "{} differ from moonflare.toml; 'moonflare placement set <project>' writes it out",
+                    drifted.join(", ")
+                )
+                .yellow()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// `Smart Placement, COUNTER in weur`
+fn describe(declared: &ProjectPlacementConfig) -> String {
+    let mut parts = Vec::new();
+    match declared.mode {
+        Some(PlacementMode::Smart) => parts.push("Smart Placement".to_string()),
+        Some(PlacementMode::Off) => parts.push("no Smart Placement".to_string()),
+        None => {}
+    }
+    parts.extend(
+        declared
+            .location_hints
+            .iter()
+            .map(|(binding, region)| format!("{} in {}", binding, region)),
+    );
+    if parts.is_empty() {
+        "nothing declared".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Nothing is compared for projects moonflare.toml doesn't place
+fn drift(scope: &ScopePlacement, declared: &ProjectPlacementConfig) -> Vec<String> {
+    let mut drift = Vec::new();
+    if declared.mode.is_none() && declared.location_hints.is_empty() {
+        return drift;
+    }
+    if let Some(mode) = declared.mode
+        && scope.mode.as_deref() != Some(mode.as_str())
+    {
+        drift.push(format!("moonflare.toml sets {}", mode.as_str()));
+    }
+    if scope.location_hints != declared.location_hints {
+        drift.push("location hints differ from moonflare.toml".to_string());
+    }
+    drift
+}
+
+fn render_table(statuses: &[ProjectPlacement]) -> String {
+    let mut rows = Vec::new();
+    for status in statuses {
+        for scope in &status.scopes {
+            let placement = &scope.placement;
+            let mode = match (&placement.mode, placement.inherited) {
+                (Some(mode), true) => format!("{} (inherited)", mode),
+                (Some(mode), false) => mode.clone(),
+                (None, _) => "default".to_string(),
+            };
+            let hints = if placement.location_hints.is_empty() {
+                "-".to_string()
+            } else {
+                placement
+                    .location_hints
+                    .iter()
+                    .map(|(binding, region)| format!("{}={}", binding, region))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            rows.push([
+                status.project.clone(),
+                placement
+                    .environment
+                    .clone()
+                    .unwrap_or_else(|| TOP_LEVEL.to_string()),
+                mode,
+                hints,
+                scope.drift.join("; "),
+            ]);
+        }
+    }
+
+    let headers = [
+        "PROJECT",
+        "ENVIRONMENT",
+        "PLACEMENT",
+        "LOCATION HINTS",
+        "NOTES",
+    ];
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain(std::iter::once(headers[column].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: [&str; 5]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        format!("  {}\n", padded.join("  ").trim_end())
+    };
+
+    let mut table = line(headers);
+    for row in &rows {
+        table.push_str(&line([&row[0], &row[1], &row[2], &row[3], &row[4]]));
+    }
+    table
+}
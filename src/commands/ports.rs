@@ -0,0 +1,90 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::ports::{self, PORTS_FILE, statuses};
+use crate::utils::projects::{discover_projects, find_project, project_not_found};
+use anyhow::{Result, bail};
+use colored::*;
+
+pub struct PortsCommand {
+    ui: MoonflareUI,
+}
+
+impl PortsCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Each project's dev port, where it was moved from, and what else wants it
+    pub fn execute_list(&self) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let config = MoonflareConfig::load()?;
+        let statuses = statuses(&discover_projects(), &config)?;
+        if statuses.is_empty() {
+            println!("No projects with dev servers");
+            return Ok(());
+        }
+
+        let width = statuses.iter().map(|s| s.project.len()).max().unwrap_or(0);
+        let mut conflicts = 0;
+        for status in &statuses {
+            let mut notes = Vec::new();
+            if let Some(usual) = status.moved_from {
+                notes.push(format!("reserved, usually {}", usual).cyan().to_string());
+            }
+            if !status.shared_with.is_empty() {
+                conflicts += 1;
+                notes.push(
+                    format!("conflicts with {}", status.shared_with.join(", "))
+                        .red()
+                        .to_string(),
+                );
+            }
+            if status.in_use {
+                notes.push("in use".yellow().to_string());
+            }
+            println!(
+                "{:width$}  {:>5}  {}",
+                status.project.bold(),
+                status.port,
+                notes.join(", "),
+                width = width
+            );
+        }
+
+        println!();
+        if conflicts > 0 {
+            println!(
+                "Projects sharing a port can't run side by side; 'moonflare dev' moves the later ones to free ports"
+            );
+        }
+        println!(
+            "'in use' may be the project's own dev server. Reservations live in {}; release one with 'moonflare ports free <project>'",
+            PORTS_FILE
+        );
+        Ok(())
+    }
+
+    /// Drop a project's reservation, so its dev server goes back to its usual port
+    pub fn execute_free(&self, project: &str) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let workspace_project = find_project(project).ok_or_else(|| project_not_found(project))?;
+        let Some(port) = ports::free(project)? else {
+            bail!("'{}' has no port reservation", project);
+        };
+        let usual = ports::default_port(&workspace_project, &MoonflareConfig::load()?)?;
+        self.ui
+            .render_success(&format!(
+                "Released port {} for '{}'; its dev server goes back to port {}",
+                port, project, usual
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::moon::run_moon_command;
+use crate::utils::projects::{WorkspaceProject, find_project, project_not_found};
+use crate::utils::task_env;
+use anyhow::{Result, bail};
+use colored::*;
+use std::fs;
+use std::process::Command;
+
+/// How a built project is served
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PreviewMode {
+    /// `wrangler dev --local`: the built Worker and assets under workerd
+    Workerd,
+    /// The project's `preview` script or `vite preview`, for pure-static builds
+    Static,
+}
+
+pub struct PreviewCommand {}
+
+impl PreviewCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Build the project with its production build task, then serve the built artifacts
+    pub async fn execute(&self, project: &str, port: Option<u16>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+        if project_info.is_crate() {
+            bail!(
+                "'{}' is a crate; preview a project that bundles its WASM instead",
+                project
+            );
+        }
+        let mode = if project_info.has_wrangler_config() {
+            PreviewMode::Workerd
+        } else {
+            PreviewMode::Static
+        };
+
+        println!(
+            "{}",
+            format!("Building '{}' for production...", project)
+                .cyan()
+                .bold()
+        );
+        run_moon_command(&["run", &format!("{}:build", project)]).await?;
+
+        let mut command = preview_command(&project_info, mode);
+        command.envs(task_env::vars(Some(project)));
+        if let Some(port) = port {
+            command.args(["--port", &port.to_string()]);
+        }
+
+        println!();
+        println!("{}", " PRODUCTION PREVIEW ".on_yellow().black().bold());
+        println!(
+            "{}",
+            format!(
+                "Serving the production build of '{}' {} (Ctrl-C to stop)",
+                project,
+                match mode {
+                    PreviewMode::Workerd => "under workerd with wrangler dev --local",
+                    PreviewMode::Static => "as static files",
+                }
+            )
+            .yellow()
+        );
+        println!(
+            "{}",
+            "No hot reload: rerun 'moonflare preview' after changing code".dimmed()
+        );
+        println!();
+
+        let status = command
+            .current_dir(&project_info.path)
+            .env("NODE_ENV", "production")
+            .env("MOONFLARE_PREVIEW", "1")
+            .status()?;
+        if !status.success() {
+            bail!("Preview server exited with {}", status);
+        }
+        Ok(())
+    }
+}
+
+fn preview_command(project: &WorkspaceProject, mode: PreviewMode) -> Command {
+    let mut command = Command::new("pnpm");
+    match mode {
+        PreviewMode::Workerd => {
+            command.args(["exec", "wrangler", "dev", "--local"]);
+        }
+        PreviewMode::Static if has_preview_script(project) => {
+            command.args(["run", "preview", "--"]);
+        }
+        PreviewMode::Static => {
+            command.args(["exec", "vite", "preview"]);
+        }
+    }
+    command
+}
+
+fn has_preview_script(project: &WorkspaceProject) -> bool {
+    fs::read_to_string(project.path.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .is_some_and(|package| package["scripts"]["preview"].is_string())
+}
@@ -0,0 +1,334 @@
+use crate::commands::deploy::{
+    DeployCommand, DeployOptions, check_promotion, confirm_protected_deploy,
+};
+use crate::ui::MoonflareUI;
+use crate::utils::artifacts::BuildManifest;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::manifest::{DeploymentManifest, DeploymentRecord};
+use crate::utils::projects::{WorkspaceProject, find_project, project_not_found};
+use crate::utils::reporter::TaskLog;
+use crate::utils::task_env;
+use crate::utils::vcs::{current_commit, uncommitted_changes};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+/// Options controlling how a promotion ships
+#[derive(Debug, Default)]
+pub struct PromoteOptions {
+    /// Directory holding the build manifest and output to ship, the workspace by default
+    pub artifacts: Option<PathBuf>,
+    /// Target environment name repeated to confirm promoting to a protected environment
+    pub confirm: Option<String>,
+}
+
+/// A project whose deployment is being promoted
+struct Promotion {
+    project: WorkspaceProject,
+    /// What `from` records for the project, carried over to `to` with the lineage
+    record: DeploymentRecord,
+    source: PromotionSource,
+}
+
+/// What ships to the target environment. Nothing is ever rebuilt.
+enum PromotionSource {
+    /// The Worker version `from` runs, deployed with `wrangler versions deploy`
+    Version(String),
+    /// Build output recorded for the deployed commit and built for the target environment
+    Artifacts,
+}
+
+pub struct PromoteCommand {
+    ui: MoonflareUI,
+}
+
+impl PromoteCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Deploy to `to` exactly what `from` runs: the Worker version each project runs there, or
+    /// the build output recorded for its commit, never a fresh build
+    pub async fn execute(
+        &self,
+        from: &str,
+        to: &str,
+        project: Option<&str>,
+        options: &PromoteOptions,
+        tasks: &TaskLog,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        if from == to {
+            bail!(
+                "Promote from one environment to another, not '{}' to itself",
+                from
+            );
+        }
+
+        let promotions = plan(from, to, project, options)?;
+        self.ui
+            .render_header(
+                "Promote",
+                Some(&format!(
+                    "Shipping what {} runs to {}",
+                    from.bold(),
+                    to.bold()
+                )),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        for promotion in &promotions {
+            let shipped = match &promotion.source {
+                PromotionSource::Version(version_id) => format!("version {}", version_id),
+                PromotionSource::Artifacts => "its build artifacts".to_string(),
+            };
+            println!(
+                "  {}  {} as {}",
+                promotion.project.name,
+                promotion
+                    .record
+                    .git_sha
+                    .as_deref()
+                    .map(short)
+                    .unwrap_or("-"),
+                shipped
+            );
+        }
+
+        let config = MoonflareConfig::load()?;
+        let confirm = match config.deploy.protection(to) {
+            Some(_) => {
+                confirm_protected_deploy(to, options.confirm.as_deref())?;
+                Some(to.to_string())
+            }
+            None => options.confirm.clone(),
+        };
+
+        for promotion in &promotions {
+            let result = match &promotion.source {
+                PromotionSource::Version(version_id) => {
+                    check_promotion(&config, to, Some(from))?;
+                    deploy_version(promotion, version_id, from, to, tasks)
+                }
+                PromotionSource::Artifacts => {
+                    let deploy = DeployOptions {
+                        env: Some(to.to_string()),
+                        task: "deploy".to_string(),
+                        confirm: confirm.clone(),
+                        from_artifacts: Some(artifacts_dir(options).to_path_buf()),
+                        promoted_from: Some(from.to_string()),
+                        ..DeployOptions::default()
+                    };
+                    DeployCommand::new()
+                        .execute(Some(&promotion.project.name), &deploy, tasks)
+                        .await
+                }
+            };
+            result.with_context(|| {
+                format!(
+                    "Promoting '{}' from {} to {} failed",
+                    promotion.project.name, from, to
+                )
+            })?;
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Promoted {} project(s) from {} to {}",
+                promotions.len(),
+                from,
+                to
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+}
+
+fn artifacts_dir(options: &PromoteOptions) -> &Path {
+    options.artifacts.as_deref().unwrap_or(Path::new("."))
+}
+
+/// Deploy the version `from` runs to `to` and record it with its lineage
+fn deploy_version(
+    promotion: &Promotion,
+    version_id: &str,
+    from: &str,
+    to: &str,
+    tasks: &TaskLog,
+) -> Result<()> {
+    let project = &promotion.project;
+    let target = format!("{}:promote", project.name);
+    let started = Instant::now();
+    let result = wrangler_versions_deploy(project, version_id, from, to);
+    tasks.record(
+        &target,
+        result.is_ok(),
+        started.elapsed(),
+        result.as_ref().err().map(|e| e.to_string()),
+    );
+    result?;
+
+    let mut manifest = DeploymentManifest::load()?;
+    manifest.record(
+        Some(to),
+        &project.name,
+        DeploymentRecord {
+            deployed_at: chrono::Utc::now().to_rfc3339(),
+            promoted_from: Some(from.to_string()),
+            version_id: Some(version_id.to_string()),
+            urls: Vec::new(),
+            ..promotion.record.clone()
+        },
+    );
+    manifest.save()
+}
+
+fn wrangler_versions_deploy(
+    project: &WorkspaceProject,
+    version_id: &str,
+    from: &str,
+    to: &str,
+) -> Result<()> {
+    let status = Command::new("pnpm")
+        .args(["exec", "wrangler", "versions", "deploy"])
+        .arg(format!("{}@100%", version_id))
+        .args(["--yes", "--env", to, "--message"])
+        .arg(format!("Promoted from {}", from))
+        .current_dir(&project.path)
+        .envs(task_env::vars(Some(&project.name)))
+        .status()
+        .context("Failed to run wrangler through pnpm")?;
+    if !status.success() {
+        bail!(
+            "wrangler versions deploy {} exited with {}; {} and {} must deploy the same Worker to promote a version",
+            version_id,
+            status,
+            from,
+            to
+        );
+    }
+    Ok(())
+}
+
+/// Work out what ships for every project: the Worker version `from` runs, or else build
+/// artifacts built for `to` from the deployed commit. A project with neither is refused rather
+/// than rebuilt.
+fn plan(
+    from: &str,
+    to: &str,
+    project: Option<&str>,
+    options: &PromoteOptions,
+) -> Result<Vec<Promotion>> {
+    let manifest = DeploymentManifest::load()?;
+    let Some(deployed) = manifest.environments.get(from).filter(|d| !d.is_empty()) else {
+        bail!(
+            "Nothing has been deployed to {}; deploy it with 'moonflare deploy --env {}' first",
+            from,
+            from
+        );
+    };
+    let records: Vec<_> = match project {
+        Some(name) => {
+            let record = deployed
+                .get(name)
+                .with_context(|| format!("'{}' hasn't been deployed to {}", name, from))?;
+            vec![(name, record)]
+        }
+        None => deployed
+            .iter()
+            .map(|(name, record)| (name.as_str(), record))
+            .collect(),
+    };
+
+    let builds = BuildManifest::load_from(artifacts_dir(options))?;
+    let mut promotions = Vec::new();
+    for (name, record) in records {
+        let project = find_project(name).ok_or_else(|| project_not_found(name))?;
+        let source = match &record.version_id {
+            Some(version_id) => PromotionSource::Version(version_id.clone()),
+            None => check_artifacts(&project, record, &builds, from, to)?,
+        };
+        promotions.push(Promotion {
+            project,
+            record: record.clone(),
+            source,
+        });
+    }
+    Ok(promotions)
+}
+
+/// Without a recorded version, the build output recorded for the deployed commit ships. It
+/// must have been built for `to`, since output built for another environment has its vars
+/// baked in, and the commit must be checked out unchanged for its Wrangler configuration.
+fn check_artifacts(
+    project: &WorkspaceProject,
+    record: &DeploymentRecord,
+    builds: &BuildManifest,
+    from: &str,
+    to: &str,
+) -> Result<PromotionSource> {
+    let name = project.name.as_str();
+    let Some(git_sha) = record.git_sha.as_deref().filter(|_| !record.dirty) else {
+        bail!(
+            "'{}' was deployed to {} with uncommitted changes and no recorded version, so there's nothing to promote; deploy it again from a clean checkout",
+            name,
+            from
+        );
+    };
+    let Some(artifact) = builds.projects.get(name) else {
+        bail!(
+            "Wrangler reported no version for '{}' in {} and there are no build artifacts of it; build {} with 'moonflare build {} --env {}', since promoting never rebuilds",
+            name,
+            from,
+            short(git_sha),
+            name,
+            to
+        );
+    };
+    if artifact.git_sha.as_deref() != Some(git_sha) {
+        bail!(
+            "The build artifacts of '{}' are from {}, but {} runs {}; use the artifacts built for that deploy",
+            name,
+            artifact
+                .git_sha
+                .as_deref()
+                .map(short)
+                .unwrap_or("an unknown commit"),
+            from,
+            short(git_sha)
+        );
+    }
+    artifact.check_environment(name, Some(to))?;
+
+    let head = current_commit();
+    if head.as_deref() != Some(git_sha) {
+        bail!(
+            "'{}' runs {} in {}, but {} is checked out; check out {} to promote its artifacts",
+            name,
+            short(git_sha),
+            from,
+            head.as_deref().map(short).unwrap_or("no commit"),
+            short(git_sha)
+        );
+    }
+    let changes = uncommitted_changes(&[project.path.as_path()])?;
+    if !changes.is_empty() {
+        bail!(
+            "'{}' has uncommitted changes, which would ship instead of what {} runs:\n  {}",
+            name,
+            from,
+            changes.join("\n  ")
+        );
+    }
+    Ok(PromotionSource::Artifacts)
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
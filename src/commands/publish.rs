@@ -0,0 +1,192 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::{MoonflareConfig, ProjectPublishConfig};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{WorkspaceProject, find_project, project_not_found};
+use crate::utils::release::{manifest_path, manifest_version};
+use crate::utils::reporter::{TaskLog, run_targets};
+use crate::utils::vcs::uncommitted_changes;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::fs;
+use std::process::Command;
+use std::time::Instant;
+
+/// Options controlling how a project is published
+#[derive(Debug, Default)]
+pub struct PublishOptions {
+    /// Pack and verify the package without uploading it
+    pub dry_run: bool,
+    /// Attach an npm provenance attestation, on top of `[projects.<name>.publish] provenance`
+    pub provenance: bool,
+    /// Don't run the project's build and test tasks first
+    pub skip_checks: bool,
+}
+
+pub struct PublishCommand {
+    ui: MoonflareUI,
+}
+
+impl PublishCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Build and test the project, then `cargo publish` a crate or `pnpm publish` a package
+    pub async fn execute(
+        &self,
+        project: &str,
+        options: &PublishOptions,
+        tasks: &TaskLog,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+        let config = MoonflareConfig::load()?;
+        let publish = config
+            .project(project)
+            .map(|p| p.publish.clone())
+            .unwrap_or_default();
+        let provenance = options.provenance || publish.provenance;
+        if provenance && project_info.is_crate() {
+            bail!("Provenance attestations are only supported for npm packages");
+        }
+
+        let package = package_id(&project_info)?;
+        if !options.dry_run {
+            let changes = uncommitted_changes(&[project_info.path.as_path()])?;
+            if !changes.is_empty() {
+                bail!(
+                    "{} has uncommitted changes; commit them so the published {} matches the repository:\n  {}",
+                    project_info.relative_path(),
+                    package,
+                    changes.join("\n  ")
+                );
+            }
+        }
+
+        self.ui
+            .render_header(&format!("Publishing {}", package), None)
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        if !options.skip_checks {
+            let targets: Vec<String> = ["build", "test"]
+                .into_iter()
+                .filter(|task| project_info.has_task(task))
+                .map(|task| format!("{}:{}", project, task))
+                .collect();
+            if !targets.is_empty() {
+                println!("{}", format!("Running {}...", targets.join(", ")).cyan());
+                let mut args = vec!["run"];
+                args.extend(targets.iter().map(|t| t.as_str()));
+                run_targets(&args, &targets, tasks)
+                    .await
+                    .context("Checks failed; nothing was published")?;
+            }
+        }
+
+        let mut command = publish_command(&project_info, &publish, provenance, options.dry_run);
+        let started = Instant::now();
+        let status = command
+            .current_dir(&project_info.path)
+            .status()
+            .with_context(|| format!("Failed to run {:?}", command.get_program()))?;
+        let target = format!("{}:publish", project);
+        if !status.success() {
+            let message = format!("Publishing {} failed", package);
+            tasks.record(&target, false, started.elapsed(), Some(message.clone()));
+            bail!(message);
+        }
+        tasks.record(&target, true, started.elapsed(), None);
+
+        if options.dry_run {
+            println!(
+                "{}",
+                format!("Dry run: {} was packed but not uploaded", package).yellow()
+            );
+        } else {
+            self.ui
+                .render_success(&format!("Published {}", package))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// `name@version` of a publishable project, refusing ones marked private
+fn package_id(project: &WorkspaceProject) -> Result<String> {
+    let manifest = manifest_path(project);
+    let content = fs::read_to_string(&manifest)
+        .with_context(|| format!("{} not found", manifest.display()))?;
+    let version = manifest_version(&manifest)?
+        .with_context(|| format!("{} has no version", manifest.display()))?;
+
+    let name = if project.is_crate() {
+        let cargo: toml::Value =
+            toml::from_str(&content).with_context(|| format!("Invalid {}", manifest.display()))?;
+        let package = cargo.get("package");
+        if package.and_then(|p| p.get("publish")) == Some(&toml::Value::Boolean(false)) {
+            bail!(
+                "{} sets publish = false; remove it to publish the crate",
+                manifest.display()
+            );
+        }
+        package
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .map(str::to_string)
+    } else {
+        let package: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid {}", manifest.display()))?;
+        if package["private"] == serde_json::Value::Bool(true) {
+            bail!(
+                "{} sets \"private\": true; remove it to publish the package",
+                manifest.display()
+            );
+        }
+        package["name"].as_str().map(str::to_string)
+    };
+    let name = name.with_context(|| format!("{} has no name", manifest.display()))?;
+    Ok(format!("{}@{}", name, version))
+}
+
+/// `pnpm publish` rewrites `workspace:` dependencies to real versions, which `npm publish`
+/// would upload as-is. moonflare checks the tree itself, so pnpm's git checks are skipped.
+fn publish_command(
+    project: &WorkspaceProject,
+    publish: &ProjectPublishConfig,
+    provenance: bool,
+    dry_run: bool,
+) -> Command {
+    let mut command = if project.is_crate() {
+        let mut cargo = Command::new("cargo");
+        cargo.arg("publish");
+        if let Some(registry) = &publish.registry {
+            cargo.args(["--registry", registry]);
+        }
+        cargo
+    } else {
+        let mut pnpm = Command::new("pnpm");
+        pnpm.args(["publish", "--no-git-checks"]);
+        if let Some(registry) = &publish.registry {
+            pnpm.args(["--registry", registry]);
+        }
+        if let Some(access) = &publish.access {
+            pnpm.args(["--access", access]);
+        }
+        if let Some(tag) = &publish.tag {
+            pnpm.args(["--tag", tag]);
+        }
+        if provenance {
+            pnpm.arg("--provenance");
+        }
+        pnpm
+    };
+    if dry_run {
+        command.arg("--dry-run");
+    }
+    command
+}
@@ -0,0 +1,150 @@
+use crate::ui::MoonflareUI;
+#[cfg(not(feature = "api"))]
+use crate::utils::cloudflare::api_unavailable;
+#[cfg(feature = "api")]
+use crate::utils::cloudflare::{CloudflareClient, QUEUES_PERMISSION};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{WorkspaceProject, discover_projects};
+use crate::utils::queues::{self, MessageBody, find_consumer, find_producer, queue_names};
+use anyhow::{Result, bail};
+use colored::*;
+
+pub struct QueuesCommand {
+    ui: MoonflareUI,
+}
+
+impl QueuesCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Send one message to a queue: through the Cloudflare API, or with `local` through the
+    /// producer binding of the Worker's running dev server
+    pub async fn execute_send(&self, queue: &str, body: &str, local: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let body = MessageBody::parse(body)?;
+        let projects = discover_projects();
+        let producer = find_producer(&projects, queue)?;
+
+        if local {
+            let Some((project, _, binding)) = producer else {
+                bail!(unknown_queue(&projects, queue, "produces to"));
+            };
+            queues::send_local(&project, &binding, &body).await?;
+            self.ui
+                .render_success(&format!(
+                    "Sent a {} message to {} through {} of '{}' (local)",
+                    body.content_type, queue, binding, project.name
+                ))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+            println!(
+                "{}",
+                format!(
+                    "See what its consumer received with 'moonflare queues peek {}'",
+                    queue
+                )
+                .dimmed()
+            );
+            return Ok(());
+        }
+
+        // Any queue of the account can be sent to; a producer only tells which account
+        let account_id =
+            producer.and_then(|(_, config, _)| config["account_id"].as_str().map(str::to_string));
+        send_remote(account_id.as_deref(), queue, &body).await?;
+        self.ui
+            .render_success(&format!(
+                "Sent a {} message to {}",
+                body.content_type, queue
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+
+    /// Messages the consumer of a queue received while running under `moonflare dev`
+    pub async fn execute_peek(&self, queue: &str, json: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let projects = discover_projects();
+        let Some(project) = find_consumer(&projects, queue)? else {
+            bail!(unknown_queue(&projects, queue, "consumes"));
+        };
+        let messages = queues::peek_local(&project, queue).await?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&messages)?);
+            return Ok(());
+        }
+
+        self.ui
+            .render_header(
+                "Queue messages",
+                Some(&format!("{} consumed by '{}' (local)", queue, project.name)),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if messages.is_empty() {
+            println!(
+                "No messages consumed yet; send one with 'moonflare queues send {} --body <JSON> --local'",
+                queue
+            );
+            return Ok(());
+        }
+        for message in &messages {
+            let attempts = if message.attempts > 1 {
+                format!(", attempt {}", message.attempts)
+            } else {
+                String::new()
+            };
+            println!(
+                "  {} {}",
+                format!("{} {}{}", message.timestamp, message.id, attempts).dimmed(),
+                message.body
+            );
+        }
+        println!();
+        println!("{} message(s), most recent first", messages.len());
+        Ok(())
+    }
+}
+
+/// Send a message through the Cloudflare API to the queue of that name in the account
+#[cfg(feature = "api")]
+async fn send_remote(account_id: Option<&str>, queue: &str, body: &MessageBody) -> Result<()> {
+    let client = CloudflareClient::from_env(account_id, QUEUES_PERMISSION)?;
+    let Some(remote) = client.queue(queue).await? else {
+        bail!(
+            "Queue '{}' doesn't exist in account {}; create it with 'pnpm exec wrangler queues create {}'",
+            queue,
+            client.account_id,
+            queue
+        );
+    };
+    client
+        .send_queue_message(&remote.queue_id, &body.value, body.content_type)
+        .await
+}
+
+#[cfg(not(feature = "api"))]
+async fn send_remote(_account_id: Option<&str>, _queue: &str, _body: &MessageBody) -> Result<()> {
+    Err(api_unavailable())
+}
+
+fn unknown_queue(projects: &[WorkspaceProject], queue: &str, role: &str) -> String {
+    let names = queue_names(projects);
+    if names.is_empty() {
+        return format!(
+            "No Worker {} queue '{}'; the workspace's Workers have no queue bindings",
+            role, queue
+        );
+    }
+    format!(
+        "No Worker {} queue '{}'; the workspace's queues are {}",
+        role,
+        queue,
+        names.into_iter().collect::<Vec<_>>().join(", ")
+    )
+}
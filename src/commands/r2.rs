@@ -0,0 +1,66 @@
+use crate::ui::MoonflareUI;
+use crate::utils::cloudflare::{CloudflareClient, R2_PERMISSION};
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::r2_sync::sync_dir;
+use anyhow::{Result, bail};
+use std::path::Path;
+
+pub struct R2Command {
+    ui: MoonflareUI,
+}
+
+impl R2Command {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Mirror a local directory into a bucket, e.g. to back up assets kept outside the repo
+    pub async fn execute_sync(
+        &self,
+        dir: &Path,
+        bucket: &str,
+        prefix: Option<&str>,
+        no_delete: bool,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        if !dir.is_dir() {
+            bail!("{} is not a directory", dir.display());
+        }
+
+        self.ui
+            .render_header(
+                "Syncing to R2",
+                Some(&format!("{} → {}", dir.display(), bucket)),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let config = MoonflareConfig::load()?;
+        let client = CloudflareClient::from_env(None, R2_PERMISSION)?;
+        let stats = sync_dir(
+            client,
+            dir,
+            bucket,
+            prefix.unwrap_or_default(),
+            !no_delete,
+            &config.assets,
+            &[],
+        )
+        .await?;
+
+        println!("Synced {} to R2 bucket {}", dir.display(), bucket);
+        println!("  {}", stats.summary());
+        if stats.kept > 0 {
+            println!(
+                "  {} object(s) no longer in {} were kept; sync without --no-delete to remove them",
+                stats.kept,
+                dir.display()
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,261 @@
+use crate::ui::MoonflareUI;
+use crate::utils::backup::create_snapshot;
+use crate::utils::dev_vars::{DEV_VARS_FILE, SOURCE_EXTENSIONS};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::plan::{self, Plan};
+use crate::utils::projects::{
+    GENERATED_DIRS, WorkspaceProject, discover_projects, find_project, project_not_found,
+};
+use crate::utils::task_env;
+use crate::utils::wrangler::{
+    STORYBOOK_WRANGLER_CONFIG, WRANGLER_CONFIG_FILES, parse_wrangler_file, rename_binding,
+    rename_binding_in_file,
+};
+use crate::utils::wrangler_defaults::{self, EFFECTIVE_CONFIG_FILE, find_defaults};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+/// A file's content before and after the rename
+struct FileEdit {
+    path: PathBuf,
+    before: String,
+    after: String,
+    /// A Wrangler configuration, whose projects need their types regenerated
+    config: bool,
+}
+
+pub struct RefactorCommand {
+    ui: MoonflareUI,
+}
+
+impl RefactorCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Rename a binding or var in Wrangler configurations, `.dev.vars` files and `env.<name>`
+    /// usages in sources, after previewing the changes, then regenerate Worker types
+    pub async fn execute_rename_binding(
+        &self,
+        old: &str,
+        new: &str,
+        project: Option<&str>,
+        dry_run: bool,
+        yes: bool,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let identifier = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").expect("valid regex");
+        for name in [old, new] {
+            if !identifier.is_match(name) {
+                bail!("'{}' isn't a valid binding name", name);
+            }
+        }
+        if old == new {
+            bail!("The old and new names are the same");
+        }
+
+        let projects = match project {
+            Some(name) => vec![find_project(name).ok_or_else(|| project_not_found(name))?],
+            None => discover_projects(),
+        };
+
+        let mut edits = Vec::new();
+        let mut typed_projects = Vec::new();
+        // Bindings set in the shared defaults apply to every project
+        let defaults = find_defaults().filter(|_| project.is_none());
+        if let Some(path) = &defaults
+            && let Some(edit) = config_edit(path, old, new)?
+        {
+            edits.push(edit);
+        }
+        for project in &projects {
+            let mut config_changed = false;
+            for file in WRANGLER_CONFIG_FILES
+                .iter()
+                .chain([&STORYBOOK_WRANGLER_CONFIG])
+            {
+                let path = project.path.join(file);
+                if path.exists()
+                    && let Some(edit) = config_edit(&path, old, new)?
+                {
+                    config_changed = true;
+                    edits.push(edit);
+                }
+            }
+            if config_changed {
+                typed_projects.push(project.clone());
+            }
+            edits.extend(source_edits(project, old, new));
+        }
+        if edits
+            .iter()
+            .any(|e| e.config && Some(&e.path) == defaults.as_ref())
+        {
+            typed_projects = projects
+                .iter()
+                .filter(|p| p.has_wrangler_config())
+                .cloned()
+                .collect();
+        }
+
+        if edits.is_empty() {
+            bail!("No binding, var or env.{} usage found", old);
+        }
+
+        self.ui
+            .render_header(&format!("Renaming {} to {}", old, new), None)
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        let mut plan = Plan::new();
+        for edit in &edits {
+            plan.modify(&edit.path, edit.before.clone(), edit.after.clone());
+        }
+        plan.render();
+
+        if dry_run || plan::plan_only() {
+            println!("{}", "Dry run: nothing was changed".yellow());
+            return Ok(());
+        }
+        if !plan.confirm(yes)? {
+            bail!("Rename cancelled");
+        }
+        let snapshot = create_snapshot("refactor rename-binding", &plan.paths())?;
+        plan.apply()?;
+
+        if !typed_projects.is_empty() {
+            let _effective = wrangler_defaults::materialize(&typed_projects)?;
+            for project in &typed_projects {
+                generate_types(project);
+            }
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Renamed {} to {} in {} file(s)",
+                old,
+                new,
+                edits.len()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!(
+            "{}",
+            format!(
+                "Deploy the changed Workers together; 'moonflare restore {}' undoes the rename",
+                snapshot.id
+            )
+            .dimmed()
+        );
+        Ok(())
+    }
+}
+
+fn config_edit(path: &Path, old: &str, new: &str) -> Result<Option<FileEdit>> {
+    // Renaming onto a name already in use would bind two resources to it
+    if rename_binding(&mut parse_wrangler_file(path)?, new, "_") {
+        bail!(
+            "{} already has a binding or var named {}",
+            path.display(),
+            new
+        );
+    }
+    let before =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let Some(after) = rename_binding_in_file(path, old, new)? else {
+        bail!(
+            "Couldn't rename {} in {} without reformatting it; rename it there by hand",
+            old,
+            path.display()
+        );
+    };
+    Ok((after != before).then(|| FileEdit {
+        path: path.to_path_buf(),
+        before,
+        after,
+        config: true,
+    }))
+}
+
+/// `env.OLD` and `env["OLD"]` in the project's sources, and `OLD=` in its `.dev.vars` files
+fn source_edits(project: &WorkspaceProject, old: &str, new: &str) -> Vec<FileEdit> {
+    let old = regex::escape(old);
+    let usage =
+        Regex::new(&format!(r#"\benv(\.{old}\b|\[(["'`]){old}["'`]\])"#)).expect("valid regex");
+    let dev_var =
+        Regex::new(&format!(r"(?m)^(\s*(?:export\s+)?){}(\s*=)", old)).expect("valid regex");
+
+    WalkDir::new(&project.path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            !(entry.file_type().is_dir()
+                && entry.depth() > 0
+                && GENERATED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy();
+            let is_source = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+            let is_dev_vars = entry.depth() == 1 && name.starts_with(DEV_VARS_FILE);
+            if !is_source && !is_dev_vars {
+                return None;
+            }
+
+            let before = fs::read_to_string(path).ok()?;
+            let after = if is_dev_vars {
+                dev_var.replace_all(&before, format!("${{1}}{}${{2}}", new).as_str())
+            } else {
+                usage.replace_all(&before, |caps: &regex::Captures| match caps.get(2) {
+                    Some(quote) => format!("env[{0}{1}{0}]", quote.as_str(), new),
+                    None => format!("env.{}", new),
+                })
+            }
+            .into_owned();
+            (after != before).then(|| FileEdit {
+                path: path.to_path_buf(),
+                before,
+                after,
+                config: false,
+            })
+        })
+        .collect()
+}
+
+/// Regenerate `worker-configuration.d.ts`. A failure only warns: the sources were already
+/// rewritten and the next build regenerates the types.
+pub fn generate_types(project: &WorkspaceProject) {
+    let mut wrangler = Command::new("pnpm");
+    wrangler.args(["exec", "wrangler", "types"]);
+    if project.path.join(EFFECTIVE_CONFIG_FILE).exists() {
+        wrangler.args(["--config", EFFECTIVE_CONFIG_FILE]);
+    }
+    let output = wrangler
+        .current_dir(&project.path)
+        .envs(task_env::vars(Some(&project.name)))
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            println!("Regenerated types for {}", project.name);
+        }
+        _ => println!(
+            "{}",
+            format!(
+                "Couldn't regenerate types for {}; the next build will",
+                project.name
+            )
+            .yellow()
+        ),
+    }
+}
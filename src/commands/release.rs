@@ -0,0 +1,173 @@
+use crate::commands::deploy::{DeployCommand, DeployOptions};
+use crate::ui::MoonflareUI;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{find_project, project_not_found};
+use crate::utils::release::{
+    Bump, CHANGELOG_FILE, changelog_entry, commits_since, latest_tag, manifest_path,
+    manifest_version, prepend_changelog, set_manifest_version, tag_name,
+};
+use crate::utils::reporter::TaskLog;
+use crate::utils::vcs::uncommitted_changes;
+use crate::utils::version::Version;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::process::Command;
+
+/// Options controlling how a project is released
+#[derive(Debug, Default)]
+pub struct ReleaseOptions {
+    /// Bump by this much instead of deriving it from the commits
+    pub bump: Option<Bump>,
+    /// Print the next version and changelog entry without changing anything
+    pub dry_run: bool,
+    /// Deploy the project once it's tagged
+    pub deploy: bool,
+    /// Environment the release is deployed to
+    pub env: Option<String>,
+}
+
+pub struct ReleaseCommand {
+    ui: MoonflareUI,
+}
+
+impl ReleaseCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Bump the project's version from its conventional commits, write a changelog entry, then
+    /// commit and tag the release
+    pub async fn execute(
+        &self,
+        project: &str,
+        options: &ReleaseOptions,
+        tasks: &TaskLog,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+        let manifest = manifest_path(&project_info);
+        if !manifest.exists() {
+            bail!("{} not found", manifest.display());
+        }
+
+        let previous = latest_tag(project);
+        let commits = commits_since(
+            &project_info,
+            previous.as_ref().map(|(tag, _)| tag.as_str()),
+        )?;
+        let since = match &previous {
+            Some((tag, _)) => format!("since {}", tag),
+            None => "yet".to_string(),
+        };
+        let Some(bump) = options
+            .bump
+            .or_else(|| commits.iter().filter_map(|c| c.bump()).max())
+        else {
+            bail!(
+                "No feat, fix or breaking commits touch {} {}; pass --bump to release anyway",
+                project_info.relative_path(),
+                since
+            );
+        };
+
+        let current = [
+            manifest_version(&manifest)?,
+            previous.as_ref().map(|(_, version)| *version),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(Version {
+            major: 0,
+            minor: 0,
+            patch: 0,
+        });
+        let next = bump.apply(current);
+        let tag = tag_name(project, next);
+
+        let entry = changelog_entry(
+            next,
+            &chrono::Local::now().format("%Y-%m-%d").to_string(),
+            &commits,
+        );
+
+        self.ui
+            .render_header(&format!("Releasing {}", project), None)
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!(
+            "  {} → {}  ({} conventional commit(s) {})",
+            current.to_string().dimmed(),
+            next.to_string().bold(),
+            commits.len(),
+            since
+        );
+        println!();
+        println!("{}", entry);
+
+        if options.dry_run {
+            println!("{}", "Dry run: nothing was changed".yellow());
+            return Ok(());
+        }
+
+        let changes = uncommitted_changes(&[project_info.path.as_path()])?;
+        if !changes.is_empty() {
+            bail!(
+                "{} has uncommitted changes; commit or stash them before releasing:\n  {}",
+                project_info.relative_path(),
+                changes.join("\n  ")
+            );
+        }
+
+        let changelog = project_info.path.join(CHANGELOG_FILE);
+        set_manifest_version(&manifest, next)?;
+        prepend_changelog(&changelog, &entry)?;
+
+        let message = format!("chore(release): {} v{}", project, next);
+        git(&["add", "--"], &[&manifest, &changelog])?;
+        git(&["commit", "-m", &message, "--"], &[&manifest, &changelog])?;
+        git(&["tag", "-a", &tag, "-m", &message], &[])?;
+
+        self.ui
+            .render_success(&format!("Released {} as {}", project, tag))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!(
+            "{}",
+            "Push the release commit and tag with 'git push --follow-tags'".dimmed()
+        );
+
+        if options.deploy {
+            let deploy = DeployOptions {
+                env: options.env.clone(),
+                task: "deploy".to_string(),
+                ..DeployOptions::default()
+            };
+            DeployCommand::new()
+                .execute(Some(project), &deploy, tasks)
+                .await
+                .with_context(|| format!("{} was tagged but its deploy failed", tag))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn git(args: &[&str], paths: &[&std::path::Path]) -> Result<()> {
+    let output = Command::new("git")
+        .args(args)
+        .args(paths)
+        .output()
+        .with_context(|| format!("Failed to run git {}", args[0]))?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
@@ -1,5 +1,11 @@
 use crate::ui::MoonflareUI;
+use crate::utils::aliases::{alias_files, sync_aliases};
+use crate::utils::backup::create_move_snapshot;
+use crate::utils::cargo_workspace::{CARGO_WORKSPACE_FILE, sync_cargo_workspace};
+use crate::utils::config::MoonflareConfig;
 use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{WorkspaceProject, project_directories, project_not_found};
+use crate::utils::readme::refresh_readmes;
 use anyhow::{Result, bail};
 use serde_json::Value;
 use std::fs;
@@ -39,13 +45,42 @@ impl RenameCommand {
             bail!("A project with the name '{}' already exists", new_name);
         }
 
+        // The project's README moves with its directory; aliases and the Cargo workspace are
+        // rewritten in place after the move
+        let mut paths: Vec<PathBuf> = vec![project_path.clone(), new_project_path.clone()];
+        paths.extend(
+            alias_files()
+                .into_iter()
+                .filter(|path| !path.starts_with(&project_path)),
+        );
+        paths.push(PathBuf::from(CARGO_WORKSPACE_FILE));
+        let snapshot = create_move_snapshot(
+            &format!("rename {} {}", current_name, new_name),
+            &paths.iter().map(PathBuf::as_path).collect::<Vec<_>>(),
+            &[(&project_path, &new_project_path)],
+        )?;
+        println!("Saved snapshot {}", snapshot.id);
+
         // Update wrangler config if it's a web project
         if matches!(project_type.as_str(), "astro" | "react" | "durable-object") {
-            self.update_wrangler_config(&project_path, new_name)?;
+            let worker_name = MoonflareConfig::load()?.worker_name(new_name);
+            self.update_wrangler_config(&project_path, &worker_name)?;
         }
 
         // Rename the directory
         fs::rename(&project_path, &new_project_path)?;
+        refresh_readmes([&WorkspaceProject {
+            name: new_name.to_string(),
+            path: new_project_path.clone(),
+        }]);
+
+        // Keep the @wasm/@packages import aliases in line with the renamed workspace
+        for path in sync_aliases()? {
+            println!("Updated aliases in {}", path.display());
+        }
+        for change in sync_cargo_workspace()? {
+            println!("Updated {}: {}", CARGO_WORKSPACE_FILE, change);
+        }
 
         self.ui
             .render_success(&format!(
@@ -59,24 +94,17 @@ impl RenameCommand {
 
     fn find_project(&self, name: &str) -> Result<(PathBuf, String)> {
         // Check each project type directory
-        let project_types = vec![
-            ("astro", "sites"),
-            ("react", "apps"),
-            ("durable-object", "workers"),
-            ("crate", "crates"),
-        ];
-
-        for (project_type, directory) in project_types {
-            let project_path = Path::new(directory).join(name);
+        for (directory, project_type) in project_directories() {
+            let project_path = Path::new(&directory).join(name);
             if project_path.exists() {
                 return Ok((project_path, project_type.to_string()));
             }
         }
 
-        bail!("Project '{}' not found", name);
+        Err(project_not_found(name))
     }
 
-    fn update_wrangler_config(&self, project_path: &Path, new_name: &str) -> Result<()> {
+    pub fn update_wrangler_config(&self, project_path: &Path, new_name: &str) -> Result<()> {
         // Check for wrangler.toml first
         let toml_path = project_path.join("wrangler.toml");
         if toml_path.exists() {
@@ -0,0 +1,118 @@
+use crate::ui::MoonflareUI;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::repro::{REPRO_INFO_FILE, ReproInfo, pack, stage, unpack};
+use anyhow::{Result, bail};
+use colored::*;
+use std::path::{Path, PathBuf};
+
+/// Suffix of repro archives, stripped to name the directory they unpack into
+const ARCHIVE_SUFFIX: &str = ".tar.gz";
+
+pub struct ReproCommand {
+    ui: MoonflareUI,
+}
+
+impl ReproCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Pack the workspace's configuration, manifests, history and tool versions into an
+    /// archive to attach to an issue
+    pub async fn execute_pack(&self, output: Option<&Path>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let archive = output.map(Path::to_path_buf).unwrap_or_else(|| {
+            PathBuf::from(format!(
+                "moonflare-repro-{}{}",
+                chrono::Local::now().format("%Y%m%d-%H%M%S"),
+                ARCHIVE_SUFFIX
+            ))
+        });
+        let staging = tempfile::tempdir()?;
+        let info = stage(Path::new("."), staging.path())?;
+        pack(staging.path(), &archive)?;
+
+        println!("Packed {} file(s)", info.files.len());
+        for (file, count) in &info.redacted {
+            println!("  {} {} ({} value(s))", "redacted".yellow(), file, count);
+        }
+        println!(
+            "{}",
+            "Sources, .dev.vars, .env files and node_modules are left out; check the archive before sharing it"
+                .dimmed()
+        );
+        self.ui
+            .render_success(&format!("Wrote {}", archive.display()))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+
+    /// Restore a repro archive into a directory and show the environment it was packed in
+    pub async fn execute_unpack(&self, archive: &Path, into: Option<&Path>) -> Result<()> {
+        if !archive.is_file() {
+            bail!("{} doesn't exist", archive.display());
+        }
+        let dir = match into {
+            Some(dir) => dir.to_path_buf(),
+            None => {
+                let name = archive
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                PathBuf::from(name.strip_suffix(ARCHIVE_SUFFIX).unwrap_or(&name))
+            }
+        };
+        if dir.exists() && dir.read_dir()?.next().is_some() {
+            bail!(
+                "{} already exists and isn't empty; pass --into with another directory",
+                dir.display()
+            );
+        }
+
+        let info = unpack(archive, &dir)?;
+        self.ui
+            .render_success(&format!(
+                "Unpacked {} file(s) into {}",
+                info.files.len(),
+                dir.display()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        print_environment(&info);
+        println!();
+        println!(
+            "Run 'pnpm install' in {} to reproduce; {} has these details",
+            dir.display(),
+            REPRO_INFO_FILE
+        );
+        Ok(())
+    }
+}
+
+fn print_environment(info: &ReproInfo) {
+    println!(
+        "Packed {} with moonflare {} on {}/{}",
+        info.created_at, info.moonflare, info.os, info.arch
+    );
+    for (tool, version) in &info.tools {
+        println!(
+            "  {:<9} {}",
+            tool,
+            version.as_deref().unwrap_or("not installed")
+        );
+    }
+    if !info.redacted.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Secret-looking values were replaced with <redacted> in {} file(s); fill them in to reproduce",
+                info.redacted.len()
+            )
+            .yellow()
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::ui::MoonflareUI;
+use crate::utils::backup::{
+    BACKUP_DIR, create_snapshot, find_snapshot, list_snapshots, restore_snapshot,
+};
+use crate::utils::fs::is_moonflare_workspace;
+use anyhow::{Result, bail};
+
+pub struct RestoreCommand {
+    ui: MoonflareUI,
+}
+
+impl RestoreCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute(&self, snapshot_id: Option<&str>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let Some(snapshot_id) = snapshot_id else {
+            return self.list();
+        };
+
+        let snapshot = find_snapshot(snapshot_id)?;
+
+        self.ui
+            .render_header(
+                "Restoring snapshot",
+                Some(&format!(
+                    "Undoing '{}' from {}",
+                    snapshot.operation, snapshot.id
+                )),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        // Snapshot the current state first so a restore can itself be undone
+        let paths: Vec<&std::path::Path> =
+            snapshot.entries.iter().map(|e| e.path.as_path()).collect();
+        let undo = create_snapshot(&format!("restore {}", snapshot.id), &paths)?;
+
+        restore_snapshot(&snapshot)?;
+
+        for entry in &snapshot.entries {
+            if entry.existed {
+                println!("  restored {}", entry.path.display());
+            } else {
+                println!("  removed  {}", entry.path.display());
+            }
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Restored snapshot {} (previous state saved as {})",
+                snapshot.id, undo.id
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+
+    fn list(&self) -> Result<()> {
+        let snapshots = list_snapshots()?;
+        if snapshots.is_empty() {
+            println!("No snapshots found in {}", BACKUP_DIR);
+            return Ok(());
+        }
+
+        self.ui
+            .render_section_start("Snapshots")
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        for snapshot in snapshots {
+            let paths: Vec<String> = snapshot
+                .entries
+                .iter()
+                .map(|e| e.path.display().to_string())
+                .collect();
+            println!(
+                "  {}  {}  ({})",
+                snapshot.id,
+                snapshot.operation,
+                paths.join(", ")
+            );
+        }
+
+        println!();
+        println!(
+            "Run 'moonflare restore <snapshot>' to restore one, or 'moonflare restore latest'"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+use crate::ui::MoonflareUI;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{WorkspaceProject, discover_projects};
+use crate::utils::routes::{RouteClaim, environments, find_conflicts, route_claims, workers_dev};
+use crate::utils::wrangler::{env_worker_name, read_wrangler_config};
+use anyhow::{Result, bail};
+use colored::*;
+use serde::Serialize;
+
+/// Label for the top-level Wrangler configuration, outside any `[env.<name>]`
+const TOP_LEVEL: &str = "(top-level)";
+
+/// How one project's Worker is reachable in one environment
+#[derive(Debug, Serialize)]
+struct WorkerExposure {
+    project: String,
+    environment: Option<String>,
+    worker: String,
+    routes: Vec<Route>,
+    workers_dev: bool,
+    /// Whether `workers_dev` is set in the config rather than Wrangler's default
+    workers_dev_explicit: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Route {
+    pattern: String,
+    custom_domain: bool,
+    zone: Option<String>,
+    /// Other projects claiming overlapping traffic in the same environment
+    conflicts_with: Vec<String>,
+}
+
+pub struct RoutesCommand {
+    ui: MoonflareUI,
+}
+
+impl RoutesCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute_list(&self, environment: Option<&str>, json: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects: Vec<WorkspaceProject> = discover_projects()
+            .into_iter()
+            .filter(|p| p.has_wrangler_config())
+            .collect();
+        let scopes: Vec<Option<String>> = match environment {
+            Some(env) => vec![Some(env.to_string())],
+            None => std::iter::once(None)
+                .chain(environments(&projects)?.into_iter().map(Some))
+                .collect(),
+        };
+
+        let mut exposures = Vec::new();
+        for scope in &scopes {
+            exposures.extend(exposures_in(&projects, scope.as_deref())?);
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&exposures)?);
+            return Ok(());
+        }
+
+        self.ui
+            .render_header(
+                "Routes",
+                Some("Routes, custom domains and workers.dev exposure per environment"),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if exposures.is_empty() {
+            println!("No projects with Wrangler configuration");
+            return Ok(());
+        }
+        print!("{}", render_table(&exposures));
+
+        let conflicts: usize = exposures
+            .iter()
+            .flat_map(|e| &e.routes)
+            .filter(|r| !r.conflicts_with.is_empty())
+            .count();
+        if conflicts > 0 {
+            println!();
+            println!(
+                "{}",
+                format!(
+                    "{} route(s) overlap another project's; 'moonflare doctor' explains each conflict",
+                    conflicts
+                )
+                .yellow()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The exposure in `environment` of every project that declares it (all of them at the top
+/// level)
+fn exposures_in(
+    projects: &[WorkspaceProject],
+    environment: Option<&str>,
+) -> Result<Vec<WorkerExposure>> {
+    let mut configs = Vec::new();
+    for project in projects {
+        let Some(config) = read_wrangler_config(&project.path)? else {
+            continue;
+        };
+        if let Some(env) = environment
+            && config.pointer(&format!("/env/{}", env)).is_none()
+        {
+            continue;
+        }
+        configs.push((project.clone(), config));
+    }
+    let declared: Vec<WorkspaceProject> = configs.iter().map(|(p, _)| p.clone()).collect();
+    let claims = route_claims(&declared, environment)?;
+    let conflicts = find_conflicts(&claims);
+
+    let mut exposures = Vec::new();
+    for (project, config) in configs {
+        let routes: Vec<Route> = claims
+            .iter()
+            .filter(|claim| claim.project == project.name)
+            .map(|claim| Route {
+                pattern: claim.pattern.clone(),
+                custom_domain: claim.custom_domain,
+                zone: claim.zone.clone(),
+                conflicts_with: conflicts
+                    .iter()
+                    .filter_map(|c| other_project(claim, &c.first, &c.second))
+                    .collect(),
+            })
+            .collect();
+        let (workers_dev, workers_dev_explicit) =
+            workers_dev(&config, environment, !routes.is_empty());
+
+        exposures.push(WorkerExposure {
+            project: project.name.clone(),
+            environment: environment.map(str::to_string),
+            worker: env_worker_name(&config, &project.name, environment),
+            routes,
+            workers_dev,
+            workers_dev_explicit,
+        });
+    }
+    Ok(exposures)
+}
+
+/// The project on the other side of a conflict `claim` is part of
+fn other_project(claim: &RouteClaim, first: &RouteClaim, second: &RouteClaim) -> Option<String> {
+    if first == claim {
+        Some(second.project.clone())
+    } else if second == claim {
+        Some(first.project.clone())
+    } else {
+        None
+    }
+}
+
+/// One row per route, custom domain and workers.dev subdomain, and one for Workers reachable
+/// by none of them
+fn render_table(exposures: &[WorkerExposure]) -> String {
+    let mut rows = Vec::new();
+    for exposure in exposures {
+        let environment = exposure.environment.as_deref().unwrap_or(TOP_LEVEL);
+        for route in &exposure.routes {
+            let mut note = route
+                .zone
+                .as_ref()
+                .map(|zone| format!("zone {}", zone))
+                .unwrap_or_default();
+            if !route.conflicts_with.is_empty() {
+                if !note.is_empty() {
+                    note.push_str("; ");
+                }
+                note.push_str(&format!(
+                    "conflicts with {}",
+                    route.conflicts_with.join(", ")
+                ));
+            }
+            rows.push([
+                exposure.project.clone(),
+                environment.to_string(),
+                exposure.worker.clone(),
+                if route.custom_domain {
+                    "custom domain"
+                } else {
+                    "route"
+                }
+                .to_string(),
+                route.pattern.clone(),
+                note,
+            ]);
+        }
+        if exposure.workers_dev {
+            rows.push([
+                exposure.project.clone(),
+                environment.to_string(),
+                exposure.worker.clone(),
+                "workers.dev".to_string(),
+                format!("{}.<subdomain>.workers.dev", exposure.worker),
+                if exposure.workers_dev_explicit {
+                    String::new()
+                } else {
+                    "Wrangler default".to_string()
+                },
+            ]);
+        }
+        if exposure.routes.is_empty() && !exposure.workers_dev {
+            rows.push([
+                exposure.project.clone(),
+                environment.to_string(),
+                exposure.worker.clone(),
+                "-".to_string(),
+                "not publicly reachable".to_string(),
+                String::new(),
+            ]);
+        }
+    }
+
+    let headers = [
+        "PROJECT",
+        "ENVIRONMENT",
+        "WORKER",
+        "TYPE",
+        "ADDRESS",
+        "NOTES",
+    ];
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain(std::iter::once(headers[column].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: [&str; 6]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        format!("  {}\n", padded.join("  ").trim_end())
+    };
+
+    let mut table = line(headers);
+    for row in &rows {
+        table.push_str(&line([
+            &row[0], &row[1], &row[2], &row[3], &row[4], &row[5],
+        ]));
+    }
+    table
+}
@@ -0,0 +1,41 @@
+use crate::ui::MoonflareUI;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{find_project, project_not_found};
+use crate::utils::sbom::{sbom_path, write_sbom};
+use anyhow::{Result, bail};
+use std::path::Path;
+
+pub struct SbomCommand {
+    ui: MoonflareUI,
+}
+
+impl SbomCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute(&self, project: &str, output: Option<&Path>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+
+        let path = output
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| sbom_path(project));
+        let (sha256, components) = write_sbom(&project_info, &path)?;
+
+        println!("  {}", path.display());
+        println!("  {} component(s)", components);
+        println!("  sha256:{}", sha256);
+
+        self.ui
+            .render_success(&format!("Generated CycloneDX SBOM for '{}'", project))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::dev_vars::required_vars;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{find_project, project_not_found};
+use crate::utils::secrets::{PRODUCTION_ENV, provider_for};
+use crate::utils::{retry, task_env};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::collections::BTreeMap;
+use std::process::Command;
+
+pub struct SecretsCommand {
+    ui: MoonflareUI,
+}
+
+impl SecretsCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Read the secrets a Worker references from the configured provider and upload them with
+    /// `wrangler secret bulk`. Values travel over stdin and are never written to disk.
+    pub async fn execute_push(&self, project: &str, env: Option<&str>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let config = MoonflareConfig::load()?;
+        let Some(provider) = provider_for(&config.secrets) else {
+            bail!("No secret provider configured. Set provider under [secrets] in moonflare.toml.");
+        };
+
+        let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+        if !project_info.has_wrangler_config() {
+            bail!("Project '{}' has no Wrangler configuration", project);
+        }
+
+        let names: Vec<String> = required_vars(&project_info.path)?
+            .into_iter()
+            .filter(|var| var.default.is_none())
+            .map(|var| var.name)
+            .collect();
+        if names.is_empty() {
+            println!("'{}' doesn't reference any secrets", project);
+            return Ok(());
+        }
+
+        let available = provider
+            .fetch(project, env.unwrap_or(PRODUCTION_ENV))
+            .with_context(|| {
+                format!(
+                    "Failed to read secrets for '{}' from {}",
+                    project,
+                    provider.name()
+                )
+            })?;
+        let (found, missing): (Vec<&String>, Vec<&String>) =
+            names.iter().partition(|name| available.contains_key(*name));
+        if found.is_empty() {
+            bail!(
+                "{} has none of the secrets '{}' uses: {}",
+                provider.name(),
+                project,
+                names.join(", ")
+            );
+        }
+
+        let secrets: BTreeMap<&str, &str> = found
+            .iter()
+            .map(|name| (name.as_str(), available[*name].as_str()))
+            .collect();
+        upload(
+            &project_info.path,
+            env,
+            &serde_json::to_string(&secrets)?,
+            project,
+        )
+        .await?;
+
+        for name in &found {
+            println!("  {} {}", "✓".green(), name);
+        }
+        if !missing.is_empty() {
+            println!(
+                "{}",
+                format!(
+                    "Not found in {}: {}",
+                    provider.name(),
+                    missing
+                        .iter()
+                        .map(|n| n.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+                .yellow()
+            );
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Pushed {} secret(s) from {} to '{}'{}",
+                found.len(),
+                provider.name(),
+                project,
+                env.map(|e| format!(" ({})", e)).unwrap_or_default()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+}
+
+/// `wrangler secret bulk` is idempotent, so a transient API failure is retried with the same
+/// secrets on stdin
+async fn upload(
+    project_path: &std::path::Path,
+    env: Option<&str>,
+    secrets_json: &str,
+    project: &str,
+) -> Result<()> {
+    let build = || {
+        let mut command = Command::new("pnpm");
+        command.args(["exec", "wrangler", "secret", "bulk"]);
+        if let Some(env) = env {
+            command.args(["--env", env]);
+        }
+        command
+            .envs(task_env::vars(Some(project)))
+            .current_dir(project_path);
+        command
+    };
+    retry::command("wrangler secret bulk", build, Some(secrets_json.as_bytes())).await
+}
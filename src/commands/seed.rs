@@ -0,0 +1,92 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{
+    WorkspaceProject, discover_projects, find_project, project_not_found,
+};
+use crate::utils::seeds::{SeedKind, SeedTarget, dev_server_running, discover, has_seeds, run};
+use anyhow::{Result, bail};
+
+pub struct SeedCommand {
+    ui: MoonflareUI,
+}
+
+impl SeedCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Load the seed files of one project, or of every project with a `seeds/` directory
+    pub async fn execute(&self, project: Option<&str>, target: &SeedTarget) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        // Seeding writes test data, so it's kept away from protected environments
+        let config = MoonflareConfig::load()?;
+        if target.is_remote() {
+            let env = target.env.as_deref().unwrap_or("production");
+            if config
+                .deploy
+                .environments
+                .get(env)
+                .is_some_and(|rules| rules.protected)
+            {
+                bail!(
+                    "'{}' is a protected environment; seed data only goes to unprotected ones",
+                    env
+                );
+            }
+        }
+
+        let projects: Vec<WorkspaceProject> = match project {
+            Some(name) => {
+                let project = find_project(name).ok_or_else(|| project_not_found(name))?;
+                if !has_seeds(&project) {
+                    bail!(
+                        "'{}' has no seeds/ directory; add .sql, .json or script files there",
+                        name
+                    );
+                }
+                vec![project]
+            }
+            None => discover_projects().into_iter().filter(has_seeds).collect(),
+        };
+        if projects.is_empty() {
+            bail!("No project has a seeds/ directory");
+        }
+
+        self.ui
+            .render_header("Seeding", Some(&target.describe()))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let mut loaded = 0;
+        for project in &projects {
+            let seeds = discover(project, target.env.as_deref())?;
+            if !target.is_remote()
+                && seeds.iter().any(|seed| seed.kind == SeedKind::Script)
+                && !dev_server_running(project).await?
+            {
+                bail!(
+                    "Seed scripts of '{}' call its local dev server; start 'moonflare dev {}' first",
+                    project.name,
+                    project.name
+                );
+            }
+            run(project, &seeds, target)?;
+            loaded += seeds.len();
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Loaded {} seed(s) into {} project(s) ({})",
+                loaded,
+                projects.len(),
+                target.describe()
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use crate::ui::MoonflareUI;
+use crate::utils::download::{self, Checksums};
+use crate::utils::http;
+use crate::utils::platform::{Os, Target};
+use crate::utils::version::Version;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use serde::Deserialize;
+use std::path::Path;
+use std::process::Command;
+
+const RELEASES_API: &str = "https://api.github.com/repos/marclove/moonflare/releases/latest";
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+pub struct SelfUpdateCommand {
+    ui: MoonflareUI,
+}
+
+impl SelfUpdateCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Check for and install the latest release. `target` overrides the detected platform
+    /// when picking the artifact to download.
+    pub async fn execute(&self, check_only: bool, target: Option<Target>) -> Result<()> {
+        let current = Version::current();
+        let target = match target {
+            Some(target) => target,
+            None => Target::detect().map_err(|e| anyhow::anyhow!(e))?,
+        };
+        println!("{}", "Checking for moonflare updates...".cyan().bold());
+
+        let client = http::client()?;
+        let release: Release = client
+            .get(RELEASES_API)
+            .send()
+            .await
+            .context("Failed to reach GitHub releases")?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Failed to parse GitHub release response")?;
+
+        let latest = Version::parse(&release.tag_name)
+            .ok_or_else(|| anyhow::anyhow!("Unrecognized release tag '{}'", release.tag_name))?;
+
+        if latest <= current {
+            self.ui
+                .render_success(&format!("moonflare {} is up to date", current))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!("New version available: {} → {}", current, latest).yellow()
+        );
+        if check_only {
+            return Ok(());
+        }
+
+        if target.os == Os::Windows {
+            bail!(
+                "Self update isn't supported on Windows yet. Download the latest release from https://github.com/marclove/moonflare/releases"
+            );
+        }
+        let asset_name = target.release_archive("moonflare");
+        let asset = release
+            .assets
+            .iter()
+            .find(|a| a.name == asset_name)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Release {} has no '{}' artifact for {}",
+                    release.tag_name,
+                    asset_name,
+                    target
+                )
+            })?;
+        let checksums_asset = release
+            .assets
+            .iter()
+            .find(|a| a.name.ends_with("checksums.txt"))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Release {} publishes no checksums; refusing to install it",
+                    release.tag_name
+                )
+            })?;
+
+        println!("Downloading {} ({})...", asset.name, target);
+        let checksums = download::fetch(&client, &checksums_asset.browser_download_url).await?;
+        let checksums = Checksums::parse(&String::from_utf8_lossy(&checksums));
+        let bytes = download::fetch(&client, &asset.browser_download_url).await?;
+        checksums.verify(&asset.name, &bytes)?;
+
+        let temp_dir = tempfile::tempdir()?;
+        let archive_path = temp_dir.path().join(&asset.name);
+        std::fs::write(&archive_path, &bytes)?;
+
+        let status = Command::new("tar")
+            .arg("-xzf")
+            .arg(&archive_path)
+            .arg("-C")
+            .arg(temp_dir.path())
+            .status()
+            .context("Failed to run tar to extract the release archive")?;
+        if !status.success() {
+            bail!("Failed to extract {}", asset.name);
+        }
+
+        replace_current_executable(&temp_dir.path().join("moonflare"))?;
+
+        self.ui
+            .render_success(&format!("Updated moonflare {} → {}", current, latest))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
+
+fn replace_current_executable(new_binary: &Path) -> Result<()> {
+    if !new_binary.exists() {
+        bail!("Release archive did not contain a moonflare binary");
+    }
+
+    let current_exe = std::env::current_exe()?;
+    let staged = current_exe.with_extension("new");
+
+    std::fs::copy(new_binary, &staged)
+        .with_context(|| format!("Failed to write {}", staged.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    // Renaming over the running binary is atomic on Unix
+    std::fs::rename(&staged, &current_exe)
+        .with_context(|| format!("Failed to replace {}", current_exe.display()))?;
+
+    Ok(())
+}
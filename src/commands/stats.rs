@@ -0,0 +1,268 @@
+use crate::ui::MoonflareUI;
+use crate::utils::build_log::{BuildRecord, read_builds};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{discover_projects, project_directories};
+use crate::utils::retention::{disk_usage, format_bytes};
+use anyhow::{Result, bail};
+use colored::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Dependencies and build output, left out of line counts
+const SKIPPED_DIRECTORIES: [&str; 8] = [
+    "node_modules",
+    "target",
+    "dist",
+    "pkg",
+    ".wrangler",
+    ".astro",
+    ".moon",
+    "storybook-static",
+];
+
+/// How many bundles and slow targets the report lists
+const TOP_ENTRIES: usize = 5;
+
+#[derive(Debug, Serialize)]
+struct WorkspaceStats {
+    projects: BTreeMap<String, usize>,
+    lines_of_code: BTreeMap<String, LanguageStats>,
+    builds: Option<BuildStats>,
+    largest_bundles: Vec<Bundle>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct LanguageStats {
+    files: usize,
+    lines: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct BuildStats {
+    runs: usize,
+    succeeded: usize,
+    average_secs: f64,
+    /// Targets by average duration, slowest first
+    slowest_targets: Vec<(String, f64)>,
+    /// Share of tasks Moon restored from cache, when the run reports said
+    cache_hit_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Bundle {
+    path: String,
+    bytes: u64,
+}
+
+pub struct StatsCommand {
+    ui: MoonflareUI,
+}
+
+impl StatsCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute(&self, runs: usize, json: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let builds = read_builds();
+        let stats = WorkspaceStats {
+            projects: project_counts(),
+            lines_of_code: lines_of_code(),
+            builds: build_stats(&builds[builds.len().saturating_sub(runs)..]),
+            largest_bundles: largest_bundles(),
+        };
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+            return Ok(());
+        }
+
+        self.ui
+            .render_header("Workspace stats", None)
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        render(&stats);
+        Ok(())
+    }
+}
+
+fn render(stats: &WorkspaceStats) {
+    println!("{}", "Projects".bold());
+    if stats.projects.is_empty() {
+        println!("  none yet");
+    }
+    for (project_type, count) in &stats.projects {
+        println!("  {:<16} {}", project_type, count);
+    }
+
+    println!();
+    println!("{}", "Lines of code".bold());
+    let mut languages: Vec<_> = stats.lines_of_code.iter().collect();
+    languages.sort_by_key(|(_, language)| std::cmp::Reverse(language.lines));
+    for (name, language) in languages {
+        println!(
+            "  {:<16} {:>8} lines in {} file(s)",
+            name, language.lines, language.files
+        );
+    }
+
+    println!();
+    println!("{}", "Builds".bold());
+    match &stats.builds {
+        Some(builds) => {
+            println!(
+                "  {} of the last {} build(s) succeeded, averaging {:.1}s",
+                builds.succeeded, builds.runs, builds.average_secs
+            );
+            match builds.cache_hit_rate {
+                Some(rate) => println!("  Cache hit rate: {:.0}%", rate * 100.0),
+                None => println!("  Cache hit rate: unknown (no Moon run reports)"),
+            }
+            for (target, secs) in &builds.slowest_targets {
+                println!("  {:<32} {:.1}s average", target, secs);
+            }
+        }
+        None => println!("  No builds recorded yet. Run 'moonflare build' to start tracking."),
+    }
+
+    println!();
+    println!("{}", "Largest bundles".bold());
+    if stats.largest_bundles.is_empty() {
+        println!("  No build output yet");
+    }
+    for bundle in &stats.largest_bundles {
+        println!("  {:<40} {}", bundle.path, format_bytes(bundle.bytes));
+    }
+}
+
+/// Projects per type, keyed by the type each project directory holds
+fn project_counts() -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for project in discover_projects() {
+        if let Some((_, project_type)) = project_directories()
+            .iter()
+            .find(|(directory, _)| project.path.starts_with(directory))
+        {
+            *counts.entry(project_type.to_string()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+fn language(path: &Path) -> Option<&'static str> {
+    Some(match path.extension()?.to_str()? {
+        "rs" => "Rust",
+        "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "astro" => "Astro",
+        "css" | "scss" => "CSS",
+        "html" => "HTML",
+        "wit" => "WIT",
+        _ => return None,
+    })
+}
+
+/// Non-blank lines per language across the project directories and shared packages
+fn lines_of_code() -> BTreeMap<String, LanguageStats> {
+    let mut languages: BTreeMap<String, LanguageStats> = BTreeMap::new();
+    let roots = project_directories()
+        .into_iter()
+        .map(|(directory, _)| directory)
+        .chain(["packages".to_string()]);
+    for root in roots {
+        let walker = WalkDir::new(root).into_iter().filter_entry(|entry| {
+            !(entry.file_type().is_dir()
+                && SKIPPED_DIRECTORIES
+                    .iter()
+                    .any(|skipped| entry.file_name() == *skipped))
+        });
+        for entry in walker.flatten().filter(|e| e.file_type().is_file()) {
+            let Some(name) = language(entry.path()) else {
+                continue;
+            };
+            let Ok(content) = fs::read_to_string(entry.path()) else {
+                continue;
+            };
+            let stats = languages.entry(name.to_string()).or_default();
+            stats.files += 1;
+            stats.lines += content.lines().filter(|l| !l.trim().is_empty()).count();
+        }
+    }
+    languages
+}
+
+fn build_stats(builds: &[BuildRecord]) -> Option<BuildStats> {
+    if builds.is_empty() {
+        return None;
+    }
+
+    let mut durations: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+    let (mut cached, mut known) = (0, 0);
+    for task in builds.iter().flat_map(|build| &build.tasks) {
+        durations
+            .entry(&task.target)
+            .or_default()
+            .push(task.duration_secs);
+        if let Some(hit) = task.cached {
+            known += 1;
+            cached += usize::from(hit);
+        }
+    }
+    let mut slowest: Vec<(String, f64)> = durations
+        .into_iter()
+        .map(|(target, secs)| (target.to_string(), average(&secs)))
+        .collect();
+    slowest.sort_by(|a, b| b.1.total_cmp(&a.1));
+    slowest.truncate(TOP_ENTRIES);
+
+    Some(BuildStats {
+        runs: builds.len(),
+        succeeded: builds.iter().filter(|b| b.success).count(),
+        average_secs: average(&builds.iter().map(|b| b.duration_secs).collect::<Vec<_>>()),
+        slowest_targets: slowest,
+        cache_hit_rate: (known > 0).then(|| cached as f64 / known as f64),
+    })
+}
+
+fn average(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len().max(1) as f64
+}
+
+/// Each project's `dist/` and every gathered WASM module, largest first
+fn largest_bundles() -> Vec<Bundle> {
+    let mut bundles: Vec<Bundle> = discover_projects()
+        .into_iter()
+        .map(|project| project.path.join("dist"))
+        .filter(|dist| dist.is_dir())
+        .map(|dist| Bundle {
+            path: dist.display().to_string(),
+            bytes: disk_usage(&dist),
+        })
+        .collect();
+
+    if let Ok(entries) = fs::read_dir("shared-wasm") {
+        bundles.extend(
+            entries
+                .flatten()
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "wasm"))
+                .filter_map(|e| {
+                    Some(Bundle {
+                        path: e.path().display().to_string(),
+                        bytes: e.metadata().ok()?.len(),
+                    })
+                }),
+        );
+    }
+
+    bundles.sort_by_key(|bundle| std::cmp::Reverse(bundle.bytes));
+    bundles.truncate(TOP_ENTRIES);
+    bundles
+}
@@ -0,0 +1,129 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::plan::{self, Plan};
+use crate::utils::projects::discover_projects;
+use crate::utils::readme::refresh_readmes;
+use crate::utils::wrangler::{
+    STORYBOOK_WRANGLER_CONFIG, config_with_name, find_wrangler_config, read_wrangler_config,
+    strip_jsonc,
+};
+use anyhow::{Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A Wrangler configuration whose worker name doesn't follow `[workspace] worker_name`
+struct NameDrift {
+    config: PathBuf,
+    current: String,
+    expected: String,
+}
+
+pub struct SyncNamesCommand {
+    ui: MoonflareUI,
+}
+
+impl SyncNamesCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute(&self, check: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let config = MoonflareConfig::load()?;
+        let drifts = name_drifts(&config)?;
+        if drifts.is_empty() {
+            self.ui
+                .render_success("Every worker name follows the workspace's naming")
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+            return Ok(());
+        }
+
+        for drift in &drifts {
+            println!(
+                "{}: '{}' → '{}'",
+                drift.config.display(),
+                drift.current,
+                drift.expected
+            );
+        }
+
+        if check {
+            bail!(
+                "{} worker name(s) don't follow the workspace's naming. Run 'moonflare sync-names' to rename them.",
+                drifts.len()
+            );
+        }
+
+        let mut plan = Plan::new();
+        for drift in &drifts {
+            if let Some(updated) = config_with_name(&drift.config, &drift.expected)? {
+                plan.write(&drift.config, updated)?;
+            }
+        }
+        println!();
+        plan.render();
+        if plan::plan_only() {
+            return Ok(());
+        }
+        if !plan.confirm(false)? {
+            bail!("Rename cancelled");
+        }
+        plan.apply()?;
+        refresh_readmes(&discover_projects());
+
+        self.ui
+            .render_success(&format!("Renamed {} worker(s)", drifts.len()))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!(
+            "Workers already deployed under the old names keep running until you delete them."
+        );
+
+        Ok(())
+    }
+}
+
+/// Worker and Storybook Worker names that differ from what the configuration prescribes
+fn name_drifts(config: &MoonflareConfig) -> Result<Vec<NameDrift>> {
+    let mut drifts = Vec::new();
+    for project in discover_projects() {
+        let expected = config.worker_name(&project.name);
+
+        if let (Some(path), Some(wrangler)) = (
+            find_wrangler_config(&project.path),
+            read_wrangler_config(&project.path)?,
+        ) && let Some(current) = wrangler["name"].as_str()
+            && current != expected
+        {
+            drifts.push(NameDrift {
+                config: path,
+                current: current.to_string(),
+                expected: expected.clone(),
+            });
+        }
+
+        let storybook = project.path.join(STORYBOOK_WRANGLER_CONFIG);
+        let storybook_expected = format!("{}-storybook", expected);
+        if let Some(current) = storybook_name(&storybook)
+            && current != storybook_expected
+        {
+            drifts.push(NameDrift {
+                config: storybook,
+                current,
+                expected: storybook_expected,
+            });
+        }
+    }
+    Ok(drifts)
+}
+
+fn storybook_name(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    let config: serde_json::Value = serde_json::from_str(&strip_jsonc(&content)).ok()?;
+    config["name"].as_str().map(str::to_string)
+}
@@ -0,0 +1,98 @@
+use crate::ui::MoonflareUI;
+use crate::utils::fs::{add_tag_to_project, is_moonflare_workspace, remove_tag_from_project};
+use crate::utils::projects::{discover_projects, find_project, project_not_found};
+use anyhow::{Result, bail};
+
+pub struct TagCommand {
+    ui: MoonflareUI,
+}
+
+impl TagCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute_add(&self, project: &str, tag: &str) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        validate_tag(tag)?;
+
+        let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+
+        if add_tag_to_project(&project_info.path, tag)? {
+            self.ui
+                .render_success(&format!("Tagged project '{}' with '{}'", project, tag))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        } else {
+            println!("Project '{}' is already tagged with '{}'", project, tag);
+        }
+
+        Ok(())
+    }
+
+    pub async fn execute_remove(&self, project: &str, tag: &str) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let project_info = find_project(project).ok_or_else(|| project_not_found(project))?;
+
+        if remove_tag_from_project(&project_info.path, tag)? {
+            self.ui
+                .render_success(&format!("Removed tag '{}' from project '{}'", tag, project))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        } else {
+            println!("Project '{}' is not tagged with '{}'", project, tag);
+        }
+
+        Ok(())
+    }
+
+    pub async fn execute_list(&self, project: Option<&str>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects = match project {
+            Some(name) => vec![find_project(name).ok_or_else(|| project_not_found(name))?],
+            None => discover_projects(),
+        };
+
+        self.ui
+            .render_section_start("Project tags")
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        for project in projects {
+            let tags = project.tags();
+            if tags.is_empty() {
+                println!("  {}  (no tags)", project.name);
+            } else {
+                println!("  {}  {}", project.name, tags.join(", "));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Tags follow Moon's identifier rules: lowercase letters, digits, hyphens and underscores
+fn validate_tag(tag: &str) -> Result<()> {
+    let valid = !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        && tag.chars().next().is_some_and(|c| c.is_ascii_lowercase());
+
+    if !valid {
+        bail!(
+            "Invalid tag '{}'. Tags must start with a lowercase letter and contain only lowercase letters, numbers, hyphens, and underscores",
+            tag
+        );
+    }
+
+    Ok(())
+}
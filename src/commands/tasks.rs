@@ -0,0 +1,89 @@
+use crate::ui::MoonflareUI;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::moon_tasks::{migrate_project, render_managed_files};
+use crate::utils::plan::{self, Plan};
+use crate::utils::projects::discover_projects;
+use crate::utils::readme::refresh_readmes;
+use anyhow::{Result, bail};
+use colored::*;
+
+pub struct TasksCommand {
+    ui: MoonflareUI,
+}
+
+impl TasksCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Regenerate the managed `.moon/tasks/*.yml` files and slim every project's moon.yml
+    /// down to the tasks it overrides
+    pub async fn execute_sync(&self, dry_run: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let managed = render_managed_files()?;
+        let mut plan = Plan::new();
+        for (path, content) in &managed {
+            plan.write(path, content.clone())?;
+        }
+
+        let mut migrated = 0;
+        for project in discover_projects() {
+            let moon_yml = project.path.join("moon.yml");
+            let migration = migrate_project(&moon_yml, &managed)?;
+            if let Some((before, after)) = migration.content.clone() {
+                plan.modify(moon_yml, before, after);
+            }
+            if !migration.is_empty() {
+                migrated += 1;
+                let mut changes = Vec::new();
+                if !migration.removed.is_empty() {
+                    changes.push(format!("inherits {}", migration.removed.join(", ")));
+                }
+                if migration.added_sources {
+                    changes.push("added sources file group".to_string());
+                }
+                println!("  {} {}", project.name.cyan(), changes.join("; "));
+            }
+            if !migration.kept.is_empty() {
+                println!(
+                    "  {} keeps customized {}",
+                    project.name.cyan(),
+                    migration.kept.join(", ")
+                );
+            }
+        }
+
+        if plan.is_empty() {
+            return self
+                .ui
+                .render_success("Task inheritance is up to date")
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e));
+        }
+        plan.render();
+        if dry_run || plan::plan_only() {
+            println!("{} project(s) would be migrated", migrated);
+            return Ok(());
+        }
+        if !plan.confirm(false)? {
+            println!("Sync cancelled");
+            return Ok(());
+        }
+        plan.apply()?;
+        refresh_readmes(&discover_projects());
+
+        let message = match migrated {
+            0 => "Task inheritance files are up to date".to_string(),
+            _ => format!("Migrated {} project(s) to inherited tasks", migrated),
+        };
+        self.ui
+            .render_success(&message)
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        Ok(())
+    }
+}
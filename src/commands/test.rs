@@ -0,0 +1,72 @@
+use crate::utils::{
+    e2e,
+    fs::is_moonflare_workspace,
+    projects::{ProjectFilter, filtered_targets},
+    reporter::{TaskLog, run_targets},
+};
+use anyhow::Result;
+use colored::*;
+
+pub struct TestCommand {}
+
+impl TestCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub async fn execute(
+        &self,
+        project: Option<&str>,
+        filter: &ProjectFilter,
+        tasks: &TaskLog,
+    ) -> Result<()> {
+        if !is_moonflare_workspace() {
+            anyhow::bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        match project {
+            Some(proj) => {
+                println!("{}", format!("Testing project '{}'...", proj).cyan().bold());
+                let target = format!("{}:test", proj);
+                run_targets(&["run", &target], std::slice::from_ref(&target), tasks).await?;
+            }
+            None if !filter.is_empty() => {
+                let targets = filtered_targets(filter, "test")?;
+                println!(
+                    "{}",
+                    format!(
+                        "Testing {} project(s) ({})...",
+                        targets.len(),
+                        filter.describe()
+                    )
+                    .cyan()
+                    .bold()
+                );
+
+                let mut args = vec!["run"];
+                args.extend(targets.iter().map(|t| t.as_str()));
+                run_targets(&args, &targets, tasks).await?;
+            }
+            None => {
+                println!("{}", "Testing all projects...".cyan().bold());
+                let targets =
+                    filtered_targets(&ProjectFilter::default(), "test").unwrap_or_default();
+                run_targets(&[":test"], &targets, tasks).await?;
+            }
+        }
+
+        println!("{}", "Tests completed successfully!".green().bold());
+        Ok(())
+    }
+
+    /// Run the end-to-end tests against the dev servers or an environment's deployments
+    pub async fn execute_e2e(&self, against: &str, filter: &ProjectFilter) -> Result<()> {
+        if !is_moonflare_workspace() {
+            anyhow::bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        e2e::run(against, filter).await?;
+        println!("{}", "End-to-end tests passed!".green().bold());
+        Ok(())
+    }
+}
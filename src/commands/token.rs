@@ -0,0 +1,151 @@
+use crate::ui::MoonflareUI;
+use crate::utils::auth::{self, AUTH_TTL_SECS, AuthInfo};
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::{WorkspaceProject, discover_projects};
+use crate::utils::token_scopes::{Resource, TokenScopes};
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::process::Command;
+
+pub struct TokenCommand {
+    ui: MoonflareUI,
+}
+
+impl TokenCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Print the permissions an API token needs for what the workspace deploys, with a link
+    /// that opens the dashboard's token form prefilled with them
+    pub async fn execute_scopes(&self, json: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects: Vec<WorkspaceProject> = discover_projects()
+            .into_iter()
+            .filter(|p| p.has_wrangler_config())
+            .collect();
+        let workspace = MoonflareConfig::load()?.workspace.name;
+        let name = format!(
+            "{} deploy",
+            workspace.as_deref().unwrap_or("moonflare workspace")
+        );
+        let scopes = TokenScopes::compute(&projects, &name)?;
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&scopes)?);
+            return Ok(());
+        }
+
+        self.ui
+            .render_header(
+                "Token scopes",
+                Some("The least a Cloudflare API token needs for this workspace"),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if scopes.permissions.is_empty() {
+            println!("No projects with Wrangler configuration; nothing needs a token yet");
+            return Ok(());
+        }
+
+        for (resource, title) in [
+            (Resource::Account, "Account permissions"),
+            (Resource::Zone, "Zone permissions"),
+        ] {
+            let permissions: Vec<_> = scopes
+                .permissions
+                .iter()
+                .filter(|p| p.resource == resource)
+                .collect();
+            if permissions.is_empty() {
+                continue;
+            }
+            println!("{}", title.bold());
+            for permission in permissions {
+                println!("  {}", permission.permission.green());
+                for reason in &permission.reasons {
+                    println!("    {}", reason.dimmed());
+                }
+            }
+        }
+
+        if scopes
+            .permissions
+            .iter()
+            .any(|p| p.resource == Resource::Zone)
+        {
+            println!();
+            match scopes.zones.as_slice() {
+                [] => println!(
+                    "Zone resources: limit them to the zones your routes are on, or all zones"
+                ),
+                zones => println!("Zone resources: {}", zones.join(", ")),
+            }
+        }
+
+        println!();
+        println!("Create the token with these permissions filled in:");
+        println!("  {}", scopes.template_url.cyan());
+        println!(
+            "{}",
+            "Pick the account under Account Resources, then store the token as CLOUDFLARE_API_TOKEN"
+                .dimmed()
+        );
+        Ok(())
+    }
+
+    /// Show who Wrangler is logged in as and the accounts it reaches, from the cache every
+    /// command needing Cloudflare credentials shares
+    pub fn execute_whoami(&self, refresh: bool, json: bool) -> Result<()> {
+        let info = auth::account_info(refresh)?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&info)?);
+            return Ok(());
+        }
+        self.print_account(&info);
+        Ok(())
+    }
+
+    /// Run `wrangler login` and forget the cached account, so the next command sees the new one
+    pub fn execute_login(&self) -> Result<()> {
+        let status = Command::new("npx")
+            .args(["wrangler", "login"])
+            .status()
+            .context("Failed to run wrangler login")?;
+        auth::invalidate()?;
+        if !status.success() {
+            bail!("wrangler login exited with {}", status);
+        }
+
+        let info = auth::account_info(true)?;
+        self.print_account(&info);
+        Ok(())
+    }
+
+    fn print_account(&self, info: &AuthInfo) {
+        match &info.email {
+            Some(email) => println!("Logged in to Cloudflare as {}", email.bold()),
+            None => println!("Logged in to Cloudflare with an API token"),
+        }
+        for account in &info.accounts {
+            println!("  {} {}", account.name.green(), account.id.dimmed());
+        }
+        if !info.scopes.is_empty() {
+            println!("Scopes: {}", info.scopes.join(", "));
+        }
+        println!(
+            "{}",
+            format!(
+                "Checked at {}; reused for {} minutes unless the login changes (--refresh to check now)",
+                info.fetched_at,
+                AUTH_TTL_SECS / 60
+            )
+            .dimmed()
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use crate::commands::refactor::generate_types;
+use crate::ui::MoonflareUI;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::plan;
+use crate::utils::projects::{discover_projects, find_project, project_not_found};
+use crate::utils::rpc_types::{BINDINGS_FILE, BindingKind, plan as rpc_plan, rpc_bindings};
+use anyhow::{Result, bail};
+use colored::*;
+
+pub struct TypesCommand {
+    ui: MoonflareUI,
+}
+
+impl TypesCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Regenerate each Worker's binding types, and give every Worker another one binds to a
+    /// `bindings.d.ts` its consumers depend on through the pnpm workspace, so RPC calls across
+    /// projects are typed
+    pub async fn execute(&self, project: Option<&str>) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let projects = discover_projects();
+        if let Some(name) = project
+            && find_project(name).is_none()
+        {
+            return Err(project_not_found(name));
+        }
+        let bindings: Vec<_> = rpc_bindings(&projects)?
+            .into_iter()
+            .filter(|b| project.is_none_or(|p| b.consumer == p || b.provider == p))
+            .collect();
+        let (changes, warnings) = rpc_plan(&projects, &bindings)?;
+
+        self.ui
+            .render_header(
+                "Types",
+                Some("Binding types for each Worker, and RPC types across projects"),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if plan::plan_only() {
+            changes.render();
+            return Ok(());
+        }
+
+        for binding in &bindings {
+            println!(
+                "  {} {} {} {}{}",
+                binding.consumer,
+                "→".dimmed(),
+                binding.provider,
+                match binding.kind {
+                    BindingKind::Service => "service ",
+                    BindingKind::DurableObject => "Durable Object ",
+                }
+                .dimmed(),
+                match &binding.class {
+                    Some(class) => format!("{} ({})", binding.binding, class),
+                    None => binding.binding.clone(),
+                }
+            );
+        }
+        let linked = changes
+            .paths()
+            .iter()
+            .any(|path| path.ends_with("package.json"));
+        changes.apply()?;
+        for warning in &warnings {
+            println!("{}", format!("  {}", warning).yellow());
+        }
+
+        for project in projects
+            .iter()
+            .filter(|p| p.has_wrangler_config())
+            .filter(|p| project.is_none_or(|name| p.name == name))
+        {
+            generate_types(project);
+        }
+
+        if linked {
+            println!(
+                "{}",
+                "Run 'pnpm install' to link the Workers that now depend on the ones they bind to"
+                    .dimmed()
+            );
+        }
+        self.ui
+            .render_success(&format!(
+                "Types up to date; {} cross-project binding(s) typed through {}",
+                bindings.len(),
+                BINDINGS_FILE
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))
+    }
+}
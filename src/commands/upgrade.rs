@@ -0,0 +1,191 @@
+use crate::ui::MoonflareUI;
+use crate::utils::backup::create_snapshot;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::plan;
+use crate::utils::projects::discover_projects;
+use crate::utils::upgrade::{DECISIONS_FILE, Decision, Decisions, UpgradeStep, detect};
+use crate::utils::version::Version;
+use anyhow::{Result, bail};
+use colored::*;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+
+pub struct UpgradeCommand {
+    ui: MoonflareUI,
+}
+
+impl UpgradeCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Walk through each breaking change with its diff, asking whether to apply it, and record
+    /// the answers. With `apply_recorded`, nothing is asked: the recorded answers are replayed,
+    /// and a change without one fails the run before anything is written.
+    pub async fn execute(&self, apply_recorded: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        let steps = detect(&discover_projects())?;
+        let mut decisions = Decisions::load()?;
+        if steps.is_empty() {
+            return self
+                .ui
+                .render_success(&format!(
+                    "The workspace is up to date with moonflare {}",
+                    Version::current()
+                ))
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e));
+        }
+
+        self.ui
+            .render_header(
+                "Upgrade",
+                Some(&format!(
+                    "{} breaking change(s) to bring the workspace up to moonflare {}",
+                    steps.len(),
+                    Version::current()
+                )),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if plan::plan_only() {
+            for (index, step) in steps.iter().enumerate() {
+                render_step(index, steps.len(), step);
+            }
+            return Ok(());
+        }
+        if apply_recorded {
+            let undecided: Vec<&str> = steps
+                .iter()
+                .map(|step| step.id.as_str())
+                .filter(|id| !decisions.decisions.contains_key(*id))
+                .collect();
+            if !undecided.is_empty() {
+                bail!(
+                    "{} has no decision for {}. Run 'moonflare upgrade' in a terminal to decide, then commit it.",
+                    DECISIONS_FILE,
+                    undecided.join(", ")
+                );
+            }
+        } else if !std::io::stdin().is_terminal() {
+            bail!(
+                "'moonflare upgrade' asks about each change, so it needs a terminal. Pass --apply-recorded to replay the decisions in {}.",
+                DECISIONS_FILE
+            );
+        }
+
+        let mut applied: Vec<&UpgradeStep> = Vec::new();
+        let mut skipped = 0;
+        for (index, step) in steps.iter().enumerate() {
+            let recorded = decisions.decisions.get(&step.id).copied();
+            let decision = if apply_recorded {
+                println!(
+                    "{} {}",
+                    format!("[{}/{}]", index + 1, steps.len()).dimmed(),
+                    step.title.bold()
+                );
+                recorded.unwrap_or(Decision::Skip)
+            } else {
+                render_step(index, steps.len(), step);
+                let Some(decision) = ask(recorded)? else {
+                    println!(
+                        "{}",
+                        "Stopped; the remaining changes are undecided".yellow()
+                    );
+                    break;
+                };
+                decisions.decisions.insert(step.id.clone(), decision);
+                decision
+            };
+            match decision {
+                Decision::Apply => {
+                    println!("  {} apply {}", "✓".green(), step.id);
+                    applied.push(step);
+                }
+                Decision::Skip => {
+                    println!("  {} skip {}", "-".dimmed(), step.id);
+                    skipped += 1;
+                }
+            }
+        }
+
+        let snapshot = if applied.is_empty() {
+            None
+        } else {
+            let paths: Vec<&Path> = applied.iter().flat_map(|step| step.plan.paths()).collect();
+            let snapshot = create_snapshot("upgrade", &paths)?;
+            for step in &applied {
+                step.plan.apply()?;
+            }
+            Some(snapshot)
+        };
+        if !apply_recorded {
+            decisions.save()?;
+            println!(
+                "{}",
+                format!(
+                    "Decisions recorded in {}; commit it so CI can replay them with 'moonflare upgrade --apply-recorded'",
+                    DECISIONS_FILE
+                )
+                .dimmed()
+            );
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Applied {} change(s), skipped {}",
+                applied.len(),
+                skipped
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        if let Some(snapshot) = snapshot {
+            println!(
+                "'moonflare restore {}' brings the old files back",
+                snapshot.id
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The step's title and reason, then its diff
+fn render_step(index: usize, total: usize, step: &UpgradeStep) {
+    println!();
+    println!(
+        "{} {}",
+        format!("[{}/{}]", index + 1, total).dimmed(),
+        step.title.bold()
+    );
+    for line in step.reason.lines() {
+        println!("  {}", line);
+    }
+    println!();
+    step.plan.render();
+}
+
+/// Apply, skip or quit; Enter keeps the recorded decision. None when the user quits.
+fn ask(recorded: Option<Decision>) -> Result<Option<Decision>> {
+    let default = match recorded {
+        Some(Decision::Apply) => " (Enter: apply, as recorded)",
+        Some(Decision::Skip) => " (Enter: skip, as recorded)",
+        None => "",
+    };
+    loop {
+        print!("[a]pply, [s]kip or [q]uit{}? ", default);
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if std::io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        match line.trim() {
+            "a" | "apply" => return Ok(Some(Decision::Apply)),
+            "s" | "skip" => return Ok(Some(Decision::Skip)),
+            "q" | "quit" => return Ok(None),
+            "" if recorded.is_some() => return Ok(recorded),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::{MatrixConfig, MoonflareConfig};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::matrix::{CellResult, cells, run_cell};
+use crate::utils::toolchain::Toolchain;
+use anyhow::{Result, bail};
+use colored::*;
+use std::path::Path;
+
+/// Versions passed on the command line, each replacing its `[matrix]` list when given
+pub struct MatrixOptions {
+    pub node: Vec<String>,
+    pub wrangler: Vec<String>,
+    pub moon: Vec<String>,
+    pub task: Option<String>,
+    pub keep: bool,
+    pub json: bool,
+}
+
+pub struct VerifyMatrixCommand {
+    ui: MoonflareUI,
+}
+
+impl VerifyMatrixCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Build the workspace once per combination of tool versions and report a pass/fail grid.
+    /// Fails when any combination does.
+    pub fn execute(&self, options: MatrixOptions) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let root = std::env::current_dir()?;
+        let configured = MoonflareConfig::load()?.matrix;
+        let pick = |cli: Vec<String>, configured: Vec<String>| {
+            if cli.is_empty() { configured } else { cli }
+        };
+        let config = MatrixConfig {
+            node: pick(options.node, configured.node),
+            wrangler: pick(options.wrangler, configured.wrangler),
+            moon: pick(options.moon, configured.moon),
+            task: options.task.unwrap_or(configured.task),
+        };
+        let matrix = cells(&config, &Toolchain::detect(&root));
+
+        if !options.json {
+            self.ui
+                .render_header(
+                    "Verify matrix",
+                    Some(&format!(
+                        "moon run :{} with {} combinations of tool versions",
+                        config.task,
+                        matrix.len()
+                    )),
+                )
+                .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        }
+
+        let mut results = Vec::new();
+        for (index, cell) in matrix.iter().enumerate() {
+            if !options.json {
+                println!(
+                    "{} node {}, wrangler {}, moon {}",
+                    format!("[{}/{}]", index + 1, matrix.len()).dimmed(),
+                    cell.node,
+                    cell.wrangler,
+                    cell.moon
+                );
+            }
+            results.push(run_cell(&root, cell, &config.task, options.keep)?);
+        }
+
+        if options.json {
+            println!("{}", serde_json::to_string_pretty(&results)?);
+        } else {
+            print_grid(&root, &results);
+        }
+
+        let failed = results.iter().filter(|result| !result.passed).count();
+        if failed > 0 {
+            bail!(
+                "{} of {} combinations failed; see the logs in the grid",
+                failed,
+                results.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+fn print_grid(root: &Path, results: &[CellResult]) {
+    println!();
+    println!(
+        "  {:<12} {:<12} {:<12} {:<8} {:>8}  LOG",
+        "NODE", "WRANGLER", "MOON", "RESULT", "TIME"
+    );
+    for result in results {
+        let status = match &result.failed_step {
+            None => format!("{:<8}", "passed").green(),
+            Some(_) => format!("{:<8}", "failed").red(),
+        };
+        let log = result.log.strip_prefix(root).unwrap_or(&result.log);
+        println!(
+            "  {:<12} {:<12} {:<12} {} {:>7.1}s  {}{}",
+            result.cell.node,
+            result.cell.wrangler,
+            result.cell.moon,
+            status,
+            result.duration_secs,
+            log.display(),
+            result
+                .failed_step
+                .as_ref()
+                .map(|step| format!(" ({} failed)", step))
+                .unwrap_or_default()
+        );
+        if let Some(workspace) = &result.workspace {
+            println!("  {}", format!("kept at {}", workspace.display()).dimmed());
+        }
+    }
+}
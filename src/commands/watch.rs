@@ -0,0 +1,221 @@
+use crate::ui::MoonflareUI;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::interrupt;
+use crate::utils::moon::run_moon_command;
+use crate::utils::moon_report::RunReport;
+use crate::utils::reporter::TaskLog;
+use crate::utils::watch::FileSnapshot;
+use crate::utils::workspace_globs::{glob_base, path_matches};
+use anyhow::{Result, bail};
+use colored::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often watched files are polled for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How one target went in the last run
+struct TargetResult {
+    target: String,
+    success: bool,
+    cached: bool,
+    duration: Duration,
+}
+
+pub struct WatchCommand {
+    ui: MoonflareUI,
+}
+
+impl WatchCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    /// Run the Moon targets of the `[watch.rules]` globs whenever matching files change. Saves
+    /// are debounced, and every target of a batch of changes runs in one `moon run`.
+    pub async fn execute(&self, tasks: &TaskLog) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        let config = MoonflareConfig::load()?.watch;
+        if config.rules.is_empty() {
+            bail!(
+                "No watch rules; map globs to Moon targets under [watch.rules] in moonflare.toml, e.g. \"workers/api/src/**\" = [\"api:test\"]"
+            );
+        }
+        for (glob, targets) in &config.rules {
+            if let Some(target) = targets.iter().find(|t| !t.contains(':')) {
+                bail!(
+                    "[watch.rules] \"{}\" runs '{}', which isn't a Moon target like 'api:test'",
+                    glob,
+                    target
+                );
+            }
+        }
+
+        let mut roots: Vec<PathBuf> = config
+            .rules
+            .keys()
+            .map(|glob| match glob_base(glob) {
+                base if base.is_empty() => PathBuf::from("."),
+                base => PathBuf::from(base),
+            })
+            .collect();
+        roots.sort();
+        roots.dedup();
+        let roots: Vec<&Path> = roots.iter().map(PathBuf::as_path).collect();
+        let debounce = Duration::from_millis(config.debounce_ms);
+
+        self.ui
+            .render_header(
+                "Watching",
+                Some(&format!("{} rule(s), Ctrl-C to stop", config.rules.len())),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        for (glob, targets) in &config.rules {
+            println!("  {} → {}", glob.cyan(), targets.join(", "));
+        }
+        println!();
+
+        let mut baseline = FileSnapshot::capture(&roots);
+        let _graceful = interrupt::handle_gracefully();
+        loop {
+            tokio::select! {
+                _ = interrupt::cancelled() => break,
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+
+            let mut settled = FileSnapshot::capture(&roots);
+            if settled.changed_since(&baseline).is_empty() {
+                continue;
+            }
+            // Wait for the burst of saves to finish, so they run as one batch
+            loop {
+                tokio::time::sleep(debounce).await;
+                let next = FileSnapshot::capture(&roots);
+                if next == settled {
+                    break;
+                }
+                settled = next;
+            }
+
+            let changed = settled.changed_since(&baseline);
+            let targets = matching_targets(&config.rules, &changed);
+            baseline = settled;
+            if targets.is_empty() {
+                continue;
+            }
+
+            println!(
+                "{}",
+                format!(
+                    "{} file(s) changed: {}",
+                    changed.len(),
+                    changed
+                        .iter()
+                        .take(3)
+                        .map(|p| p.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+                .dimmed()
+            );
+            let results = run_targets(&targets, tasks).await;
+            print_status_line(&results);
+
+            // Output the targets wrote shouldn't start another run
+            baseline = FileSnapshot::capture(&roots);
+        }
+
+        println!();
+        println!("{}", "Stopped watching".yellow());
+        Ok(())
+    }
+}
+
+/// Targets of every rule matching a changed file, each once, in rule order
+fn matching_targets(rules: &BTreeMap<String, Vec<String>>, changed: &[PathBuf]) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    for (glob, rule_targets) in rules {
+        let matched = changed
+            .iter()
+            .any(|path| path_matches(glob, &path.to_string_lossy().replace('\\', "/")));
+        if matched {
+            for target in rule_targets {
+                if !targets.contains(target) {
+                    targets.push(target.clone());
+                }
+            }
+        }
+    }
+    targets
+}
+
+/// Run the batch in one `moon run`, reading each target's result from Moon's run report
+async fn run_targets(targets: &[String], tasks: &TaskLog) -> Vec<TargetResult> {
+    let started_at = SystemTime::now();
+    let started = Instant::now();
+    let mut args = vec!["run"];
+    args.extend(targets.iter().map(String::as_str));
+    let result = run_moon_command(&args).await;
+    if let Err(e) = &result {
+        println!("{}", e.to_string().red());
+    }
+    let report = RunReport::load_since(started_at);
+
+    targets
+        .iter()
+        .map(|target| {
+            let action = report
+                .as_ref()
+                .and_then(|report| report.tasks().find(|a| a.target() == Some(target)));
+            let target_result = match action {
+                Some(action) => TargetResult {
+                    target: target.clone(),
+                    success: !action.failed(),
+                    cached: action.cached(),
+                    duration: Duration::from_secs_f64(action.duration_secs()),
+                },
+                None => TargetResult {
+                    target: target.clone(),
+                    success: result.is_ok(),
+                    cached: false,
+                    duration: started.elapsed(),
+                },
+            };
+            let error = (!target_result.success).then(|| format!("{} failed", target));
+            tasks.record(target, target_result.success, target_result.duration, error);
+            target_result
+        })
+        .collect()
+}
+
+/// `12:04:31  ✓ api:test 1.2s  ✗ web:lint 0.4s`
+fn print_status_line(results: &[TargetResult]) {
+    let statuses: Vec<String> = results
+        .iter()
+        .map(|result| {
+            let mark = match (result.success, result.cached) {
+                (false, _) => "✗".red(),
+                (true, true) => "✓".dimmed(),
+                (true, false) => "✓".green(),
+            };
+            let timing = if result.cached {
+                "cached".to_string()
+            } else {
+                format!("{:.1}s", result.duration.as_secs_f64())
+            };
+            format!("{} {} {}", mark, result.target, timing.dimmed())
+        })
+        .collect();
+    println!(
+        "{}  {}",
+        chrono::Local::now().format("%H:%M:%S").to_string().bold(),
+        statuses.join("  ")
+    );
+    println!();
+}
@@ -0,0 +1,202 @@
+use crate::ui::MoonflareUI;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::hashes::{HashDiff, task_hashes};
+use crate::utils::projects::{discover_projects, find_project, project_not_found};
+use crate::utils::task_env::{self, ENVIRONMENT_VAR};
+use anyhow::{Result, bail};
+use colored::*;
+use serde_yaml::Value;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct WhyCommand {
+    ui: MoonflareUI,
+}
+
+impl WhyCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute(&self, project: &str, task: &str) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+        if find_project(project).is_none() {
+            return Err(project_not_found(project));
+        }
+
+        let target = format!("{}:{}", project, task);
+        let hashes = task_hashes(Path::new("."), &target);
+        let [.., previous, latest] = hashes.as_slice() else {
+            match hashes.first() {
+                Some(only) => bail!(
+                    "Moon has recorded a single run of {} (hash {}), so there's nothing to compare it with yet",
+                    target,
+                    short(&only.hash)
+                ),
+                None => bail!(
+                    "Moon hasn't recorded a run of {}. Run it with 'moon run {}' first.",
+                    target,
+                    target
+                ),
+            }
+        };
+
+        self.ui
+            .render_header(
+                &format!("Why {} ran", target),
+                Some(&format!(
+                    "Comparing hash {} with the previous run's {}",
+                    short(&latest.hash),
+                    short(&previous.hash)
+                )),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let diff = latest.diff(previous);
+        if diff.is_empty() {
+            println!("Nothing Moon hashes changed between the last two runs.");
+        } else {
+            render_diff(project, &diff);
+        }
+
+        let affected = downstream(&target);
+        if !affected.is_empty() {
+            println!();
+            println!("{}", "Downstream targets affected:".bold());
+            for target in &affected {
+                println!("  {}", target);
+            }
+        }
+
+        println!();
+        println!(
+            "Full diff: moon query hash-diff {} {}",
+            previous.hash, latest.hash
+        );
+
+        Ok(())
+    }
+}
+
+fn short(hash: &str) -> &str {
+    &hash[..hash.len().min(12)]
+}
+
+fn render_diff(project: &str, diff: &HashDiff) {
+    // Gathered WASM is an input of TypeScript builds; it changes whenever a crate is rebuilt
+    let is_wasm = |path: &&String| path.ends_with(".wasm") || path.starts_with("shared-wasm/");
+    let files = [
+        ("~", &diff.changed),
+        ("+", &diff.added),
+        ("-", &diff.removed),
+    ];
+
+    let mut inputs = Vec::new();
+    let mut wasm = Vec::new();
+    for (marker, paths) in files {
+        for path in paths {
+            let line = format!("  {} {}", marker, path);
+            if is_wasm(&path) {
+                wasm.push(line);
+            } else {
+                inputs.push(line);
+            }
+        }
+    }
+
+    if !inputs.is_empty() {
+        println!("{}", "Inputs changed:".bold());
+        inputs.iter().for_each(|line| println!("{}", line));
+    }
+    if !wasm.is_empty() {
+        println!("{}", "WASM modules changed (a crate was rebuilt):".bold());
+        wasm.iter().for_each(|line| println!("{}", line));
+    }
+    if !diff.env.is_empty() {
+        let injected = task_env::vars(Some(project));
+        println!("{}", "Environment changed:".bold());
+        for name in &diff.env {
+            let source = if name == ENVIRONMENT_VAR || name == "WRANGLER_ENV" {
+                " (target environment)"
+            } else if injected.contains_key(name) {
+                " (set in moonflare.toml)"
+            } else {
+                ""
+            };
+            println!("  ~ {}{}", name, source.dimmed());
+        }
+    }
+    if !diff.deps.is_empty() {
+        println!("{}", "Upstream targets changed:".bold());
+        for target in &diff.deps {
+            println!("  ~ {}", target);
+        }
+    }
+    if diff.command {
+        println!("{}", "The task's command or arguments changed".bold());
+    }
+}
+
+/// Targets that depend on `target`, directly or through other targets, according to the
+/// `deps` in each project's moon.yml (crate builds feed shared-wasm:gather this way)
+fn downstream(target: &str) -> Vec<String> {
+    let mut projects: Vec<(String, PathBuf)> = discover_projects()
+        .into_iter()
+        .map(|p| (p.name, p.path))
+        .collect();
+    if Path::new("shared-wasm/moon.yml").exists() {
+        projects.push(("shared-wasm".to_string(), PathBuf::from("shared-wasm")));
+    }
+
+    let mut dependents: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, path) in projects {
+        let Some(config) = fs::read_to_string(path.join("moon.yml"))
+            .ok()
+            .and_then(|content| serde_yaml::from_str::<Value>(&content).ok())
+        else {
+            continue;
+        };
+        let Some(tasks) = config.get("tasks").and_then(|t| t.as_mapping()) else {
+            continue;
+        };
+        for (task, definition) in tasks {
+            let Some(task) = task.as_str() else { continue };
+            let deps = definition
+                .get("deps")
+                .and_then(|d| d.as_sequence())
+                .into_iter()
+                .flatten();
+            for dep in deps {
+                let dep = dep
+                    .as_str()
+                    .or_else(|| dep.get("target").and_then(|t| t.as_str()));
+                if let Some(dep) = dep {
+                    let dep = dep
+                        .strip_prefix('~')
+                        .map_or(dep.to_string(), |task| format!("{}{}", name, task));
+                    dependents
+                        .entry(dep)
+                        .or_default()
+                        .push(format!("{}:{}", name, task));
+                }
+            }
+        }
+    }
+
+    let mut affected = BTreeSet::new();
+    let mut queue = VecDeque::from([target.to_string()]);
+    while let Some(next) = queue.pop_front() {
+        for dependent in dependents.get(&next).into_iter().flatten() {
+            if affected.insert(dependent.clone()) {
+                queue.push_back(dependent.clone());
+            }
+        }
+    }
+    affected.remove(target);
+    affected.into_iter().collect()
+}
@@ -0,0 +1,136 @@
+use crate::commands::rename::RenameCommand;
+use crate::errors::validate_workspace_name;
+use crate::ui::MoonflareUI;
+use crate::utils::backup::create_snapshot;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::fs::{is_moonflare_workspace, replace_identifiers};
+use crate::utils::projects::discover_projects;
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Workspace files that mention the workspace name
+const NAMED_FILES: [&str; 5] = [
+    "moonflare.toml",
+    "package.json",
+    "README.md",
+    ".moon/workspace.yml",
+    ".moon/toolchain.yml",
+];
+
+pub struct WorkspaceCommand {
+    ui: MoonflareUI,
+}
+
+impl WorkspaceCommand {
+    pub fn new() -> Self {
+        Self {
+            ui: MoonflareUI::new(),
+        }
+    }
+
+    pub async fn execute_rename(&self, new_name: &str, prefix_workers: bool) -> Result<()> {
+        if !is_moonflare_workspace() {
+            bail!("Not in a Moonflare workspace. Run 'moonflare init <name>' first.");
+        }
+
+        validate_workspace_name(new_name).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let Some(current_name) = current_workspace_name() else {
+            bail!("Couldn't determine the workspace name from moonflare.toml or package.json");
+        };
+        if current_name == new_name {
+            bail!("The workspace is already named '{}'", new_name);
+        }
+
+        self.ui
+            .render_header(
+                "Renaming workspace",
+                Some(&format!("Renaming '{}' to '{}'", current_name, new_name)),
+            )
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let workers: Vec<(PathBuf, String)> = if prefix_workers {
+            discover_projects()
+                .into_iter()
+                .filter(|p| p.has_wrangler_config())
+                .filter_map(|p| {
+                    let config = read_wrangler_config(&p.path).ok()??;
+                    let name = config["name"].as_str()?.to_string();
+                    Some((p.path, name))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut paths: Vec<&Path> = NAMED_FILES
+            .iter()
+            .map(Path::new)
+            .filter(|p| p.exists())
+            .collect();
+        paths.extend(workers.iter().map(|(path, _)| path.as_path()));
+        let snapshot = create_snapshot(
+            &format!("workspace rename {} {}", current_name, new_name),
+            &paths,
+        )?;
+        println!("Saved snapshot {}", snapshot.id);
+
+        for file in NAMED_FILES {
+            if rename_in_file(Path::new(file), &current_name, new_name)? {
+                println!("Updated {}", file);
+            }
+        }
+
+        // Worker names share a `<workspace>-` prefix so they don't collide within an account
+        let old_prefix = format!("{}-", current_name);
+        let renamer = RenameCommand::new();
+        for (path, name) in &workers {
+            let base = name.strip_prefix(&old_prefix).unwrap_or(name);
+            let worker_name = format!("{}-{}", new_name, base);
+            if &worker_name != name {
+                renamer.update_wrangler_config(path, &worker_name)?;
+                println!("Renamed worker '{}' to '{}'", name, worker_name);
+            }
+        }
+
+        self.ui
+            .render_success(&format!(
+                "Successfully renamed workspace '{}' to '{}'",
+                current_name, new_name
+            ))
+            .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+        println!("The workspace directory itself was not renamed.");
+
+        Ok(())
+    }
+}
+
+/// Name from `[workspace]` in moonflare.toml, falling back to the root package.json
+fn current_workspace_name() -> Option<String> {
+    MoonflareConfig::load()
+        .ok()
+        .and_then(|config| config.workspace.name)
+        .or_else(|| {
+            let content = fs::read_to_string("package.json").ok()?;
+            let package: serde_json::Value = serde_json::from_str(&content).ok()?;
+            package["name"].as_str().map(str::to_string)
+        })
+}
+
+/// Rename whole-word mentions of the workspace name, returning whether the file changed
+fn rename_in_file(path: &Path, current_name: &str, new_name: &str) -> Result<bool> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(false);
+    };
+    let updated = replace_identifiers(
+        &content,
+        &[(current_name.to_string(), new_name.to_string())],
+    );
+    if updated == content {
+        return Ok(false);
+    }
+    fs::write(path, updated)?;
+    Ok(true)
+}
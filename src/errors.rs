@@ -1,5 +1,8 @@
-use miette::{Diagnostic, NamedSource, SourceSpan};
-use std::path::PathBuf;
+use crate::utils::platform::Shell;
+use crate::utils::projects::similar_names;
+use crate::utils::wrangler_schema::Violation;
+use miette::{Diagnostic, LabeledSpan, NamedSource, SourceSpan};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Error, Debug, Diagnostic)]
@@ -27,6 +30,16 @@ pub enum MoonflareError {
     )]
     WorkspaceDirectoryExists { path: String, suggestion: String },
 
+    #[error("{target} is inside the workspace at {workspace}")]
+    #[diagnostic(code(moonflare::init::nested_workspace))]
+    NestedWorkspace {
+        target: String,
+        workspace: String,
+        /// `moonflare add` for project directories, and `--force-nested` for a sub-workspace
+        #[help]
+        suggestion: String,
+    },
+
     #[error("Permission denied")]
     #[diagnostic(
         code(moonflare::init::permission_denied),
@@ -41,24 +54,63 @@ pub enum MoonflareError {
     #[error("Moon CLI not found")]
     #[diagnostic(
         code(moonflare::init::moon_not_found),
-        help(
-            "Install Moon CLI manually with: curl -fsSL https://moonrepo.dev/install/moon.sh | bash"
-        ),
         url("https://moonrepo.dev/docs/install")
     )]
-    MoonNotFound { auto_install_failed: Option<String> },
+    MoonNotFound {
+        auto_install_failed: Option<String>,
+        #[help]
+        install_hint: String,
+    },
 
-    #[error("Template processing failed")]
+    #[error(
+        "Template processing failed for {template_name}{}",
+        variable.as_ref().map(|v| format!(": '{}' is missing from the template context", v)).unwrap_or_default()
+    )]
     #[diagnostic(
         code(moonflare::init::template_error),
         help(
-            "This is likely a bug in Moonflare. Please report it at https://github.com/moonflare-dev/moonflare/issues"
+            "This is likely a bug in Moonflare. Please report it at https://github.com/moonflare-dev/moonflare/issues. Pass --lenient to render missing variables as empty strings in the meantime."
         )
     )]
     TemplateError {
         template_name: String,
+        /// Variable the template referenced that its context doesn't set
+        variable: Option<String>,
         #[source]
-        source: Box<dyn std::error::Error + Send + Sync>,
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+
+    #[error("Generated {file} isn't valid {format} ({section}): {reason}")]
+    #[diagnostic(
+        code(moonflare::template::invalid_output),
+        help(
+            "This is a bug in the template. Please report it at https://github.com/moonflare-dev/moonflare/issues"
+        )
+    )]
+    InvalidGeneratedFile {
+        file: String,
+        format: String,
+        reason: String,
+        /// The `FILE:` section that rendered the file and where it starts
+        section: String,
+    },
+
+    #[error("{file} isn't a valid Wrangler configuration")]
+    #[diagnostic(
+        code(moonflare::wrangler::invalid_config),
+        help(
+            "Fix the marked lines before Wrangler reads the file. The keys Wrangler accepts are documented at https://developers.cloudflare.com/workers/wrangler/configuration/"
+        )
+    )]
+    InvalidWranglerConfig {
+        file: String,
+        #[source_code]
+        source_code: NamedSource<String>,
+        #[label(collection)]
+        problems: Vec<LabeledSpan>,
+        /// The other invalid configs when several are checked at once
+        #[related]
+        others: Vec<MoonflareError>,
     },
 
     #[error("Moon workspace setup failed")]
@@ -90,6 +142,15 @@ pub enum MoonflareError {
         exit_code: Option<i32>,
     },
 
+    #[error("Moon command '{command}' timed out after {limit}")]
+    #[diagnostic(
+        code(moonflare::moon::timed_out),
+        help(
+            "Moon was stopped when the command's time box ran out. Raise it with --timeout or under [timeouts] in moonflare.toml."
+        )
+    )]
+    MoonCommandTimedOut { command: String, limit: String },
+
     #[error("Not in a Moonflare workspace")]
     #[diagnostic(
         code(moonflare::build::not_in_workspace),
@@ -102,17 +163,32 @@ pub enum MoonflareError {
         searched_paths: Vec<String>,
     },
 
-    #[error("Project not found")]
-    #[diagnostic(
-        code(moonflare::build::project_not_found),
-        help("List available projects with 'moon query projects'")
-    )]
+    #[error("Project '{project_name}' not found")]
+    #[diagnostic(code(moonflare::build::project_not_found))]
     ProjectNotFound {
         project_name: String,
         workspace_path: String,
-        available_projects: Option<String>,
+        /// The closest project names, or every project when none is close
+        #[help]
+        suggestion: String,
     },
 
+    #[error("This workspace requires moonflare {required} or newer (running {current})")]
+    #[diagnostic(
+        code(moonflare::workspace::version_too_old),
+        help(
+            "Upgrade with 'moonflare self update', or download the latest release from https://github.com/marclove/moonflare/releases"
+        )
+    )]
+    WorkspaceRequiresNewerVersion { required: String, current: String },
+
+    #[error("moonflare.toml's min_version '{value}' isn't a version")]
+    #[diagnostic(
+        code(moonflare::workspace::invalid_min_version),
+        help("Set [workspace].min_version to a version like \"0.4.0\"")
+    )]
+    InvalidMinVersion { value: String },
+
     #[error("File system error")]
     #[diagnostic(
         code(moonflare::fs::operation_failed),
@@ -126,7 +202,83 @@ pub enum MoonflareError {
     },
 }
 
+/// What each diagnostic code means and what usually fixes it, for `moonflare docs serve`
+pub const ERROR_CODES: [(&str, &str); 16] = [
+    (
+        "moonflare::init::invalid_name",
+        "Workspace names become package names and Worker name prefixes, so they may only contain lowercase letters, numbers and hyphens. The error suggests a cleaned-up name.",
+    ),
+    (
+        "moonflare::init::directory_exists",
+        "'moonflare init' won't write into an existing directory. Pick another name, remove the directory, or pass --force to initialize over it.",
+    ),
+    (
+        "moonflare::init::nested_workspace",
+        "The target directory is inside another moonflare workspace. Add a project to that workspace with 'moonflare add', or pass --force-nested to create a separate workspace inside it.",
+    ),
+    (
+        "moonflare::init::permission_denied",
+        "moonflare couldn't create files in the target directory. Check that you own the parent directory and it isn't read-only.",
+    ),
+    (
+        "moonflare::init::moon_not_found",
+        "Workspaces are driven by Moon, which wasn't on PATH and couldn't be installed through proto. Install Moon from https://moonrepo.dev/docs/install and run the command again.",
+    ),
+    (
+        "moonflare::init::template_error",
+        "A template referenced a variable moonflare didn't provide. This is a bug in moonflare; --lenient renders missing variables as empty strings until it's fixed.",
+    ),
+    (
+        "moonflare::template::invalid_output",
+        "A template produced a JSON, TOML or YAML file that doesn't parse. Nothing was written. This is a bug in the template.",
+    ),
+    (
+        "moonflare::wrangler::invalid_config",
+        "A Wrangler configuration has keys or values Wrangler rejects. The marked lines show where; 'moonflare config lint' lists deprecated keys it can rewrite.",
+    ),
+    (
+        "moonflare::init::moon_setup_failed",
+        "The workspace was generated but 'moon setup' failed, usually because the toolchain couldn't be downloaded. Run 'moon setup' in the workspace once the network is available.",
+    ),
+    (
+        "moonflare::moon::command_failed",
+        "A Moon task exited with an error. Moon's own output above the error shows which task failed and why.",
+    ),
+    (
+        "moonflare::moon::timed_out",
+        "The command's time box ran out and Moon was stopped. Raise the limit with --timeout or under [timeouts] in moonflare.toml.",
+    ),
+    (
+        "moonflare::build::not_in_workspace",
+        "The command needs a moonflare workspace, found by its .moon/workspace.yml. Run it from the workspace root, or create one with 'moonflare init <name>'.",
+    ),
+    (
+        "moonflare::build::project_not_found",
+        "No project has that name. The error lists the closest names; 'moonflare describe' lists every project.",
+    ),
+    (
+        "moonflare::workspace::version_too_old",
+        "moonflare.toml sets a min_version newer than the running moonflare. Upgrade with 'moonflare self update'.",
+    ),
+    (
+        "moonflare::workspace::invalid_min_version",
+        "moonflare.toml sets a min_version that doesn't parse as a version, so moonflare can't tell whether it's new enough. Use a version like \"0.4.0\".",
+    ),
+    (
+        "moonflare::fs::operation_failed",
+        "Reading or writing a file failed. The error names the file and operation; check its permissions and the free disk space.",
+    ),
+];
+
 impl MoonflareError {
+    /// Errors that point at a bug in moonflare or its templates rather than at the project
+    pub fn is_internal(&self) -> bool {
+        matches!(
+            self,
+            Self::TemplateError { .. } | Self::InvalidGeneratedFile { .. }
+        )
+    }
+
     pub fn invalid_workspace_name(name: &str, suggestions: Vec<String>) -> Self {
         let name_source = NamedSource::new("workspace_name", name.to_string());
         let invalid_span = SourceSpan::new(0.into(), name.len());
@@ -141,7 +293,10 @@ impl MoonflareError {
     pub fn workspace_directory_exists(path: PathBuf) -> Self {
         let path_str = path.display().to_string();
         let suggestion = if path.exists() && path.is_dir() {
-            format!("Remove existing directory: rm -rf {}", path.display())
+            format!(
+                "Remove existing directory: {}",
+                Shell::detect().remove_dir(&path)
+            )
         } else {
             "Choose a different workspace name".to_string()
         };
@@ -152,6 +307,30 @@ impl MoonflareError {
         }
     }
 
+    /// `target` is somewhere below the workspace at `workspace`, which Moon would keep treating
+    /// as the root; `project_dir` is the workspace's project directory it's in, such as `apps`
+    pub fn nested_workspace(target: &Path, workspace: &Path, project_dir: Option<&str>) -> Self {
+        let add = match project_dir {
+            Some(dir) => format!(
+                "'{}' holds the workspace's projects; add one with 'moonflare add <type> <name>' from {}",
+                dir,
+                workspace.display()
+            ),
+            None => format!(
+                "To add a project to it, run 'moonflare add <type> <name>' from {}",
+                workspace.display()
+            ),
+        };
+        Self::NestedWorkspace {
+            target: target.display().to_string(),
+            workspace: workspace.display().to_string(),
+            suggestion: format!(
+                "{}. Moon would treat the new workspace's projects as part of the outer one. Pass --force-nested to create a separate workspace here anyway.",
+                add
+            ),
+        }
+    }
+
     pub fn permission_denied(path: PathBuf, source: std::io::Error) -> Self {
         Self::PermissionDenied {
             path: path.display().to_string(),
@@ -162,6 +341,10 @@ impl MoonflareError {
     pub fn moon_not_found(auto_install_error: Option<String>) -> Self {
         Self::MoonNotFound {
             auto_install_failed: auto_install_error,
+            install_hint: format!(
+                "Install Moon CLI manually with: {}",
+                Shell::detect().install_moon()
+            ),
         }
     }
 
@@ -171,7 +354,51 @@ impl MoonflareError {
     ) -> Self {
         Self::TemplateError {
             template_name: template_name.to_string(),
-            source,
+            variable: None,
+            source: Some(source),
+        }
+    }
+
+    /// A template referenced `variable`, which its context doesn't set, while rendering `file`
+    pub fn missing_template_variable(file: &str, variable: &str) -> Self {
+        Self::TemplateError {
+            template_name: file.to_string(),
+            variable: Some(variable.to_string()),
+            source: None,
+        }
+    }
+
+    /// A rendered file that doesn't parse as `format`; `line` is where its `FILE:` section
+    /// starts in the template
+    pub fn invalid_generated_file(file: &str, format: &str, reason: &str, line: usize) -> Self {
+        Self::InvalidGeneratedFile {
+            file: file.to_string(),
+            format: format.to_string(),
+            reason: reason.to_string(),
+            section: format!(
+                "rendered from the FILE:{} section at template line {}",
+                file, line
+            ),
+        }
+    }
+
+    /// A Wrangler config with syntax errors or schema violations, each marked in `source`
+    pub fn invalid_wrangler_config(
+        file: &str,
+        source: String,
+        violations: Vec<Violation>,
+        others: Vec<MoonflareError>,
+    ) -> Self {
+        Self::InvalidWranglerConfig {
+            file: file.to_string(),
+            source_code: NamedSource::new(file, source).with_language("json"),
+            problems: violations
+                .into_iter()
+                .map(|violation| {
+                    LabeledSpan::new_with_span(Some(violation.message), violation.span)
+                })
+                .collect(),
+            others,
         }
     }
 
@@ -185,15 +412,29 @@ impl MoonflareError {
         }
     }
 
+    /// `available_projects` are Moon project ids, optionally followed by a description
     pub fn project_not_found(
         project_name: &str,
         workspace_path: PathBuf,
-        available_projects: Option<String>,
+        available_projects: &[String],
     ) -> Self {
+        let ids = available_projects
+            .iter()
+            .filter_map(|project| project.split_whitespace().next());
+        let similar = similar_names(project_name, ids);
+        let suggestion = match similar.as_slice() {
+            [] if available_projects.is_empty() => "No projects found in this workspace. Add projects with 'moonflare add <type> <name>'.".to_string(),
+            [] => format!(
+                "Available projects:\n  • {}",
+                available_projects.join("\n  • ")
+            ),
+            [id] => format!("Did you mean '{}'? Pass --auto-correct to use it", id),
+            ids => format!("Did you mean '{}'?", ids.join("' or '")),
+        };
         Self::ProjectNotFound {
             project_name: project_name.to_string(),
             workspace_path: workspace_path.display().to_string(),
-            available_projects,
+            suggestion,
         }
     }
 
@@ -231,6 +472,38 @@ impl MoonflareError {
         }
     }
 
+    /// A failed `moon run`, described by the tasks Moon's run report says failed rather than by
+    /// its terminal output
+    pub fn moon_tasks_failed(command: &str, failures: &str, exit_code: Option<i32>) -> Self {
+        Self::MoonCommandFailed {
+            command: command.to_string(),
+            main_error: failures.to_string(),
+            stderr_output: None,
+            error_span: None,
+            exit_code,
+        }
+    }
+
+    pub fn moon_command_timed_out(command: &str, limit: std::time::Duration) -> Self {
+        Self::MoonCommandTimedOut {
+            command: command.to_string(),
+            limit: crate::utils::timeout::format_duration(limit),
+        }
+    }
+
+    pub fn workspace_requires_newer_version(required: &str, current: &str) -> Self {
+        Self::WorkspaceRequiresNewerVersion {
+            required: required.to_string(),
+            current: current.to_string(),
+        }
+    }
+
+    pub fn invalid_min_version(value: &str) -> Self {
+        Self::InvalidMinVersion {
+            value: value.to_string(),
+        }
+    }
+
     pub fn file_system_error(operation: &str, path: PathBuf, source: std::io::Error) -> Self {
         Self::FileSystemError {
             operation: operation.to_string(),
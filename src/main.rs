@@ -1,18 +1,104 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
 use miette::Result;
 use std::env;
 
 mod commands;
 mod errors;
 mod templates;
+// Builds without the `ui` feature print plain compact output and use clap's help pages
+#[cfg_attr(not(feature = "ui"), path = "ui_plain.rs")]
 mod ui;
+mod ui_shared;
 mod utils;
 
+#[cfg(feature = "api")]
+use commands::{access::AccessCommand, ai::AiCommand, builds::BuildsCommand, r2::R2Command};
 use commands::{
-    add::AddCommand, build::BuildCommand, deploy::DeployCommand, dev::DevCommand,
-    init::InitCommand, rename::RenameCommand,
+    add::{AddCommand, AddOptions, CrateFlavor},
+    archive::ArchiveCommand,
+    audit_bindings::AuditBindingsCommand,
+    bench::BenchCommand,
+    build::BuildCommand,
+    bundle::BundleCommand,
+    ci::CiCommand,
+    clean::CleanCommand,
+    config::ConfigCommand,
+    d1::D1Command,
+    daemon::DaemonCommand,
+    deploy::{DeployCommand, DeployOptions, read_vars_file},
+    describe::DescribeCommand,
+    dev::{DevCommand, DevOptions},
+    devcontainer::DevcontainerCommand,
+    docs::DocsCommand,
+    doctor::DoctorCommand,
+    durable_objects::DurableObjectsCommand,
+    env::EnvCommand,
+    examples::ExamplesCommand,
+    flags::FlagsCommand,
+    grep::{GrepCommand, GrepOptions, ProjectKind},
+    history::{HistoryCommand, HistoryFilter},
+    hooks::HooksCommand,
+    import::ImportCommand,
+    init::InitCommand,
+    kv::KvCommand,
+    layout::LayoutCommand,
+    licenses::{LicenseFormat, LicensesCommand},
+    loadtest::{LoadTestCommand, LoadTestOptions, LoadTestTarget, parse_duration},
+    meta::MetaCommand,
+    migrate::MigrateCommand,
+    nix::NixCommand,
+    placement::PlacementCommand,
+    ports::PortsCommand,
+    preview::PreviewCommand,
+    promote::{PromoteCommand, PromoteOptions},
+    publish::{PublishCommand, PublishOptions},
+    queues::QueuesCommand,
+    refactor::RefactorCommand,
+    release::{ReleaseCommand, ReleaseOptions},
+    rename::RenameCommand,
+    repro::ReproCommand,
+    restore::RestoreCommand,
+    routes::RoutesCommand,
+    sbom::SbomCommand,
+    secrets::SecretsCommand,
+    seed::SeedCommand,
+    self_update::SelfUpdateCommand,
+    stats::StatsCommand,
+    sync_names::SyncNamesCommand,
+    tag::TagCommand,
+    tasks::TasksCommand,
+    test::TestCommand,
+    token::TokenCommand,
+    types::TypesCommand,
+    upgrade::UpgradeCommand,
+    verify_matrix::{MatrixOptions, VerifyMatrixCommand},
+    watch::WatchCommand,
+    why::WhyCommand,
+    workspace::WorkspaceCommand,
 };
+#[cfg(feature = "ui")]
 use ui::MoonflareUI;
+use ui::OutputProfile;
+#[cfg(feature = "api")]
+use utils::cloudflare::AiGatewaySettings;
+use utils::config::{self, HookManager, MoonflareConfig, PlacementMode, PnpmConfig};
+use utils::crash;
+use utils::daemon;
+use utils::direct_tasks;
+use utils::docs_site;
+use utils::events::{self, Event};
+use utils::history::{self, HistoryEntry, UsageEntry};
+use utils::interrupt;
+use utils::kv::{KvTarget, KvValue};
+use utils::picker::pick_project;
+use utils::pipelines::MonorepoTool;
+use utils::projects::{self, ProjectFilter, is_project_pattern, resolve_project_name};
+use utils::release::Bump;
+use utils::reporter::{self, CommandReport, ReporterKind, TaskLog, emit_report};
+use utils::seeds::SeedTarget;
+#[cfg(feature = "telemetry")]
+use utils::{metrics, trace};
+use utils::{onboarding, package_config, plan, platform, task_env, timeout};
 
 #[derive(Parser)]
 #[command(
@@ -23,6 +109,1016 @@ use ui::MoonflareUI;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        help = "How build, test and deploy report results (console by default)"
+    )]
+    reporter: Option<ReporterKind>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Write the json/github/junit/markdown report to this file instead of stdout"
+    )]
+    reporter_output: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Append newline-delimited JSON progress events to this file"
+    )]
+    events_file: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "events_file",
+        help = "Write newline-delimited JSON progress events to this open file descriptor"
+    )]
+    events_fd: Option<i32>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Pass the KEY=value lines of this file to every moon and wrangler process"
+    )]
+    env_file: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Read this file instead of the workspace's moonflare.toml (or set MOONFLARE_CONFIG)"
+    )]
+    config: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        help = "How much output to print around results: full, compact or minimal"
+    )]
+    ui: Option<OutputProfile>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Use the closest project when a project name has a single close match"
+    )]
+    auto_correct: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Print what the command would change and exit (refactor, sync-names, tasks sync, types, deploy, upgrade, hooks uninstall)"
+    )]
+    plan: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Apply the command's plan without asking for confirmation"
+    )]
+    auto_approve: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "DURATION",
+        value_parser = timeout::parse_duration,
+        help = "Stop the command's moon and wrangler processes after this long (e.g. 90s, 20m)"
+    )]
+    timeout: Option<std::time::Duration>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "URL",
+        help = "Export OpenTelemetry spans of the command to this OTLP/HTTP endpoint"
+    )]
+    otel_endpoint: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Render template variables missing from the context as empty strings instead of failing"
+    )]
+    lenient: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Run tasks straight from moon.yml instead of through Moon (also MOONFLARE_NO_MOON=1; automatic when Moon isn't installed)"
+    )]
+    no_moon: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Send the diagnostics bundle to [crash] endpoint if moonflare crashes"
+    )]
+    report: bool,
+}
+
+#[derive(Args)]
+struct ProjectFilterArgs {
+    #[arg(
+        long,
+        conflicts_with = "project",
+        help = "Only include projects matching this glob (repeatable)"
+    )]
+    only: Vec<String>,
+    #[arg(
+        long,
+        conflicts_with = "project",
+        help = "Exclude projects matching this glob (repeatable)"
+    )]
+    exclude: Vec<String>,
+    #[arg(
+        long = "tag",
+        conflicts_with = "project",
+        help = "Only include projects with this tag (repeatable)"
+    )]
+    tags: Vec<String>,
+}
+
+impl ProjectFilterArgs {
+    fn to_filter(&self) -> ProjectFilter {
+        ProjectFilter::new(&self.only, &self.exclude, &self.tags)
+    }
+
+    /// A single project, or a filter selecting every project a group pattern such as `shop/*`
+    /// matches
+    fn select(&self, project: Option<String>) -> (Option<String>, ProjectFilter) {
+        match project {
+            Some(pattern) if is_project_pattern(&pattern) => (
+                None,
+                ProjectFilter::new(&[pattern], &self.exclude, &self.tags),
+            ),
+            project => (project, self.to_filter()),
+        }
+    }
+}
+
+/// Without a project or filter, ask which project to run when attached to a terminal
+async fn pick_if_unspecified(
+    project: Option<String>,
+    filter: &ProjectFilter,
+    command: &str,
+) -> miette::Result<Option<String>> {
+    match project {
+        None if filter.is_empty() => pick_project(command)
+            .await
+            .map_err(|e| miette::miette!("Project picker failed: {}", e)),
+        project => Ok(project),
+    }
+}
+
+/// Leave out the projects that opted out of `command`, unless a project is named or
+/// `--include-ignored` is passed
+fn without_ignored(
+    project: &Option<String>,
+    filter: ProjectFilter,
+    include_ignored: bool,
+    command: &str,
+) -> ProjectFilter {
+    if project.is_some() || include_ignored {
+        filter
+    } else {
+        filter.ignoring(command)
+    }
+}
+
+/// A failed command's error, keeping a Moonflare diagnostic's source snippet and labels, and
+/// whether it's an internal error that gets a crash bundle
+fn command_error(command: &str, error: anyhow::Error) -> miette::Report {
+    match error.downcast::<errors::MoonflareError>() {
+        Ok(diagnostic) => miette::Report::new(diagnostic),
+        Err(error) if crash::is_internal(&error) => miette::Report::new(crash::Internal(format!(
+            "{} command failed: {}",
+            command, error
+        ))),
+        Err(error) => miette::miette!("{} command failed: {}", command, error),
+    }
+}
+
+/// Project arguments accept a grouped project's path (`shop/frontend`) for its Moon id
+fn project_arg(value: &str) -> Result<String, String> {
+    Ok(resolve_project_name(value))
+}
+
+#[derive(Subcommand)]
+enum TagAction {
+    #[command(about = "Add a tag to a project")]
+    Add {
+        #[arg(value_parser = project_arg, help = "Project to tag")]
+        project: String,
+        #[arg(help = "Tag to add")]
+        tag: String,
+    },
+    #[command(about = "Remove a tag from a project")]
+    Remove {
+        #[arg(value_parser = project_arg, help = "Project to untag")]
+        project: String,
+        #[arg(help = "Tag to remove")]
+        tag: String,
+    },
+    #[command(about = "List project tags")]
+    List {
+        #[arg(value_parser = project_arg, help = "Specific project to list (optional)")]
+        project: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    #[command(about = "Find deprecated Wrangler configuration keys and upgrade them")]
+    Lint {
+        #[arg(value_parser = project_arg, long, help = "Only this project")]
+        project: Option<String>,
+        #[arg(long, help = "Rewrite deprecated keys to their modern equivalents")]
+        fix: bool,
+    },
+    #[command(about = "Show a project's Wrangler configuration merged with the workspace defaults")]
+    Effective {
+        #[arg(value_parser = project_arg, help = "Project to show")]
+        project: String,
+        #[arg(long, help = "Print only the merged configuration as JSON")]
+        json: bool,
+    },
+    #[command(about = "Print the JSON Schema of moonflare.toml for editor completion")]
+    Schema {
+        #[arg(long, value_name = "FILE", help = "Write the schema to a file instead")]
+        output: Option<std::path::PathBuf>,
+    },
+    #[command(about = "Upgrade moonflare.toml to the current config version")]
+    Migrate,
+}
+
+#[derive(Subcommand)]
+enum RoutesAction {
+    #[command(about = "List every project's routes, custom domains and workers.dev exposure")]
+    List {
+        #[arg(long, help = "Only this Wrangler environment")]
+        env: Option<String>,
+        #[arg(long, help = "Print the routes as JSON")]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PlacementAction {
+    #[command(
+        about = "Record a project's placement in moonflare.toml and write it to its Wrangler configuration"
+    )]
+    Set {
+        #[arg(value_parser = project_arg, help = "Project to place")]
+        project: String,
+        #[arg(
+            long,
+            value_enum,
+            help = "Smart Placement, or off to run where requests arrive"
+        )]
+        mode: Option<PlacementMode>,
+        #[arg(
+            long = "hint",
+            value_name = "BINDING=REGION",
+            help = "Create a Durable Object binding's objects in a region, e.g. COUNTER=weur"
+        )]
+        hints: Vec<String>,
+        #[arg(
+            long = "clear-hint",
+            value_name = "BINDING",
+            help = "Drop a binding's location hint"
+        )]
+        clear_hints: Vec<String>,
+        #[arg(long, help = "Preview the changes without writing them")]
+        dry_run: bool,
+        #[arg(short, long, help = "Apply the changes without asking")]
+        yes: bool,
+    },
+    #[command(about = "Show the placement and location hints of every project, per environment")]
+    Status {
+        #[arg(long, help = "Print the placement as JSON")]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum R2Action {
+    #[command(about = "Upload new and changed files in a directory to an R2 bucket")]
+    Sync {
+        #[arg(help = "Directory to upload")]
+        dir: std::path::PathBuf,
+        #[arg(help = "R2 bucket to sync into")]
+        bucket: String,
+        #[arg(long, help = "Key prefix for the uploaded objects, e.g. backups/")]
+        prefix: Option<String>,
+        #[arg(long, help = "Keep objects that are no longer in the directory")]
+        no_delete: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum QueuesAction {
+    #[command(about = "Send a message to a queue, deployed or through a running dev server")]
+    Send {
+        #[arg(help = "Queue name, as in the producer's wrangler config")]
+        queue: String,
+        #[arg(
+            long,
+            value_name = "JSON|@FILE",
+            help = "Message body, or @path to read it from a file; anything but JSON is sent as text"
+        )]
+        body: String,
+        #[arg(
+            long,
+            help = "Send through the producer Worker's 'moonflare dev' server instead of the Cloudflare API"
+        )]
+        local: bool,
+    },
+    #[command(about = "Show the messages a queue's consumer received under 'moonflare dev'")]
+    Peek {
+        #[arg(help = "Queue name, as in the consumer's wrangler config")]
+        queue: String,
+        #[arg(long, help = "Print the messages as JSON")]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum D1Action {
+    #[command(
+        about = "Copy a D1 database into a branch database bound in a preview environment",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
+    Branch {
+        #[command(subcommand)]
+        action: Option<D1BranchAction>,
+        #[arg(value_parser = project_arg, required = true, help = "Project whose database to branch")]
+        project: Option<String>,
+        #[arg(long, required = true, help = "Branch name, e.g. pr-123")]
+        name: Option<String>,
+        #[arg(
+            long,
+            default_value = "production",
+            help = "Environment whose database is copied"
+        )]
+        from: String,
+        #[arg(
+            long,
+            help = "Environment to bind the branch in (default: the pull request environment, else preview)"
+        )]
+        env: Option<String>,
+        #[arg(long, help = "D1 binding to branch, when the project has several")]
+        binding: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DoAction {
+    #[command(about = "List Durable Object namespaces and objects of the workspace's Workers")]
+    List {
+        #[arg(value_parser = project_arg, help = "Only this project")]
+        project: Option<String>,
+        #[arg(long, help = "Environment whose Workers to list (default: production)")]
+        env: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "env",
+            help = "List the objects 'moonflare dev' has persisted locally instead"
+        )]
+        local: bool,
+        #[arg(long, help = "Print the objects as JSON")]
+        json: bool,
+    },
+    #[command(about = "Open a SQLite shell on a Durable Object's local state")]
+    Inspect {
+        #[arg(value_parser = project_arg, help = "Project the object belongs to")]
+        project: String,
+        #[arg(long, help = "Durable Object class, when the project defines several")]
+        class: Option<String>,
+        #[arg(long, value_name = "ID", help = "Object id, or a prefix of it")]
+        object: Option<String>,
+        #[arg(
+            long,
+            help = "Run this SQL and print the result instead of opening a shell"
+        )]
+        sql: Option<String>,
+        #[arg(long, help = "Open the database writable rather than read-only")]
+        write: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum KvAction {
+    #[command(about = "List the keys of a KV namespace")]
+    Keys {
+        #[arg(value_parser = project_arg, help = "Project the namespace is bound to")]
+        project: String,
+        #[arg(help = "KV binding, e.g. CACHE")]
+        binding: String,
+        #[arg(long, help = "Only keys starting with this prefix")]
+        prefix: Option<String>,
+        #[command(flatten)]
+        target: KvTargetArgs,
+        #[arg(
+            long,
+            help = "Print the keys with their expiration and metadata as JSON"
+        )]
+        json: bool,
+    },
+    #[command(about = "Print the value stored under a key")]
+    Get {
+        #[arg(value_parser = project_arg, help = "Project the namespace is bound to")]
+        project: String,
+        #[arg(help = "KV binding, e.g. CACHE")]
+        binding: String,
+        #[arg(help = "Key to read")]
+        key: String,
+        #[command(flatten)]
+        target: KvTargetArgs,
+    },
+    #[command(about = "Store a value, or a file's contents, under a key")]
+    Put {
+        #[arg(value_parser = project_arg, help = "Project the namespace is bound to")]
+        project: String,
+        #[arg(help = "KV binding, e.g. CACHE")]
+        binding: String,
+        #[arg(help = "Key to write")]
+        key: String,
+        #[arg(required_unless_present = "path", help = "Value to store")]
+        value: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "value",
+            help = "Store this file's contents instead"
+        )]
+        path: Option<std::path::PathBuf>,
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            help = "Expire the key after this many seconds"
+        )]
+        ttl: Option<u64>,
+        #[command(flatten)]
+        target: KvTargetArgs,
+    },
+    #[command(about = "Write every key and value of a namespace as JSON Lines")]
+    Export {
+        #[arg(value_parser = project_arg, help = "Project the namespace is bound to")]
+        project: String,
+        #[arg(help = "KV binding, e.g. CACHE")]
+        binding: String,
+        #[arg(long, help = "Only keys starting with this prefix")]
+        prefix: Option<String>,
+        #[arg(short, long, help = "File to write (default: stdout)")]
+        output: Option<std::path::PathBuf>,
+        #[command(flatten)]
+        target: KvTargetArgs,
+    },
+    #[command(about = "Write the entries of a JSON Lines file into a namespace")]
+    Import {
+        #[arg(value_parser = project_arg, help = "Project the namespace is bound to")]
+        project: String,
+        #[arg(help = "KV binding, e.g. CACHE")]
+        binding: String,
+        #[arg(help = "JSON Lines file, one {\"key\", \"value\"} object per line")]
+        file: std::path::PathBuf,
+        #[command(flatten)]
+        target: KvTargetArgs,
+    },
+}
+
+#[derive(Args)]
+struct KvTargetArgs {
+    #[arg(
+        long,
+        help = "Environment whose namespace to use (default: production)"
+    )]
+    env: Option<String>,
+    #[arg(
+        long,
+        conflicts_with = "env",
+        help = "Use the local state 'moonflare dev' reads instead of the remote namespace"
+    )]
+    local: bool,
+}
+
+impl KvTargetArgs {
+    fn to_target(&self) -> KvTarget {
+        KvTarget {
+            env: self.env.clone(),
+            local: self.local,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum D1BranchAction {
+    #[command(about = "Delete branch databases of merged or closed pull requests")]
+    Cleanup {
+        #[arg(value_parser = project_arg, help = "Only this project's branches")]
+        project: Option<String>,
+        #[arg(
+            long,
+            help = "Delete this branch regardless of its pull request, e.g. pr-123"
+        )]
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TasksAction {
+    #[command(about = "Regenerate inherited task files and migrate project moon.yml files")]
+    Sync {
+        #[arg(long, help = "Show what would change without writing files")]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum LayoutAction {
+    #[command(about = "Show the directory each kind of project lives in")]
+    Show,
+    #[command(about = "Move project directories and update every reference to them")]
+    Set {
+        #[arg(
+            required = true,
+            help = "Directories to use, as <kind>=<directory> (e.g. sites=websites)"
+        )]
+        assignments: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkspaceAction {
+    #[command(about = "Rename the workspace in moonflare.toml, package.json and README")]
+    Rename {
+        #[arg(help = "New workspace name")]
+        new_name: String,
+        #[arg(
+            long,
+            help = "Prefix every worker name with the new workspace name, replacing the old prefix"
+        )]
+        prefix_workers: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum RefactorAction {
+    #[command(
+        name = "rename-binding",
+        about = "Rename a binding or var in Wrangler configs, .dev.vars and env usages"
+    )]
+    RenameBinding {
+        #[arg(help = "Current binding or var name")]
+        old: String,
+        #[arg(help = "New name")]
+        new: String,
+        #[arg(value_parser = project_arg, long, help = "Only rename in this project")]
+        project: Option<String>,
+        #[arg(long, help = "Preview the changes without writing them")]
+        dry_run: bool,
+        #[arg(short, long, help = "Apply the changes without asking")]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum HooksAction {
+    #[command(about = "Install git hooks running the [hooks] commands from moonflare.toml")]
+    Install {
+        #[arg(
+            long,
+            value_enum,
+            help = "Write plain git hooks or a lefthook.yml (default: [hooks] manager)"
+        )]
+        manager: Option<HookManager>,
+        #[arg(long, help = "Replace hooks moonflare didn't write")]
+        force: bool,
+    },
+    #[command(about = "Remove the git hooks moonflare installed")]
+    Uninstall,
+}
+
+#[derive(Subcommand)]
+enum DaemonAction {
+    #[command(about = "Start the workspace daemon in the background")]
+    Start {
+        #[arg(long, help = "Run in the foreground instead of detaching")]
+        foreground: bool,
+    },
+    #[command(about = "Stop the workspace daemon")]
+    Stop,
+    #[command(about = "Show whether the daemon is running and what it has cached")]
+    Status,
+}
+
+#[derive(Subcommand)]
+enum CiAction {
+    #[command(about = "Generate GitHub Actions workflows from the workspace layout")]
+    Generate {
+        #[arg(long, help = "Also generate the AI code review workflow")]
+        with_ai_review: bool,
+        #[arg(long, help = "Overwrite previously generated workflows")]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DevcontainerAction {
+    #[command(about = "Generate .devcontainer/ with the workspace's pinned toolchain")]
+    Generate {
+        #[arg(long, help = "Overwrite a previously generated devcontainer")]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DocsAction {
+    #[command(
+        about = "Serve the workspace's project docs, the command reference and error codes on localhost"
+    )]
+    Serve {
+        #[arg(long, default_value_t = docs_site::DEFAULT_PORT, help = "Port to listen on")]
+        port: u16,
+    },
+}
+
+#[derive(Subcommand)]
+enum NixAction {
+    #[command(about = "Generate flake.nix with a dev shell for the workspace's pinned toolchain")]
+    Generate {
+        #[arg(long, help = "Overwrite an existing flake.nix")]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum EnvAction {
+    #[command(about = "Generate .dev.vars.example from wrangler vars and secret references")]
+    Example {
+        #[arg(value_parser = project_arg, help = "Specific project (optional)")]
+        project: Option<String>,
+    },
+    #[command(about = "Create .dev.vars from .dev.vars.example, prompting for missing values")]
+    Pull {
+        #[arg(value_parser = project_arg, help = "Specific project (optional)")]
+        project: Option<String>,
+    },
+    #[command(about = "Copy top-level vars and bindings into a Wrangler environment")]
+    Sync {
+        #[arg(value_parser = project_arg, help = "Worker project to update")]
+        project: String,
+        #[arg(help = "Wrangler environment, e.g. staging")]
+        env: String,
+    },
+    #[command(about = "Compare the vars and secret names of two environments")]
+    Diff {
+        #[arg(value_parser = project_arg, help = "Worker project to compare")]
+        project: String,
+        #[arg(help = "Environment to compare, e.g. staging")]
+        from: String,
+        #[arg(help = "Environment to compare against; production is the top level")]
+        to: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum BuildsAction {
+    #[command(about = "Connect the repository to Workers Builds and create a trigger per project")]
+    Setup {
+        #[arg(value_parser = project_arg, help = "Project to set up (optional - all deployable projects if omitted)")]
+        project: Option<String>,
+        #[arg(long, help = "Environment the trigger deploys to")]
+        env: Option<String>,
+        #[arg(
+            long,
+            help = "Branch whose pushes build automatically (defaults to the current branch)"
+        )]
+        branch: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BundleAction {
+    #[command(
+        about = "Bundle Workers without deploying and list what takes up their size, largest first"
+    )]
+    Analyze {
+        #[arg(value_parser = project_arg, help = "Project to analyze (optional - every Worker if omitted)")]
+        project: Option<String>,
+        #[arg(long, help = "Environment to bundle for")]
+        env: Option<String>,
+        #[arg(
+            long,
+            default_value_t = 10,
+            help = "How many files and packages to list per Worker"
+        )]
+        top: usize,
+        #[arg(long, help = "Print the analysis as JSON")]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MigrateAction {
+    #[command(about = "Convert a Cloudflare Pages project to a Worker serving static assets")]
+    PagesToWorker {
+        #[arg(value_parser = project_arg, help = "Pages project to migrate")]
+        project: String,
+        #[arg(
+            long,
+            help = "Generate a routing Worker for the routes in _routes.json and Pages Functions"
+        )]
+        routing_worker: bool,
+    },
+    #[command(about = "Turn a repository created with 'npm create cloudflare' into a workspace")]
+    C3 {
+        #[arg(default_value = ".", help = "Repository to migrate, in place")]
+        path: String,
+        #[arg(
+            long,
+            help = "Project name for the Worker (defaults to its package.json name)"
+        )]
+        name: Option<String>,
+        #[arg(long, help = "Migrate even with uncommitted changes")]
+        force: bool,
+    },
+    #[command(about = "Turn a Turborepo workspace into a moonflare workspace")]
+    Turbo {
+        #[arg(default_value = ".", help = "Repository to migrate, in place")]
+        path: String,
+        #[arg(
+            long,
+            help = "Keep project directories, recording them as the [layout], where possible"
+        )]
+        keep_layout: bool,
+        #[arg(long, help = "Migrate even with uncommitted changes")]
+        force: bool,
+    },
+    #[command(about = "Turn an Nx workspace into a moonflare workspace")]
+    Nx {
+        #[arg(default_value = ".", help = "Repository to migrate, in place")]
+        path: String,
+        #[arg(
+            long,
+            help = "Keep project directories, recording them as the [layout], where possible"
+        )]
+        keep_layout: bool,
+        #[arg(long, help = "Migrate even with uncommitted changes")]
+        force: bool,
+    },
+    #[command(about = "List applied and pending D1 and Durable Object migrations per environment")]
+    Status {
+        #[arg(value_parser = project_arg, help = "Only this project")]
+        project: Option<String>,
+        #[arg(
+            long = "env",
+            value_name = "ENV",
+            help = "Only this environment, or production for the top level (repeatable)"
+        )]
+        environments: Vec<String>,
+        #[arg(long, help = "Print the statuses as JSON")]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetaAction {
+    #[command(about = "Build every workspace in the repository")]
+    Build {
+        #[arg(
+            long = "workspace",
+            value_name = "PATH",
+            help = "Only this workspace, by path or directory name (repeatable)"
+        )]
+        workspaces: Vec<String>,
+        #[arg(
+            last = true,
+            help = "Arguments passed to 'moonflare build' in each workspace"
+        )]
+        args: Vec<String>,
+    },
+    #[command(about = "Deploy every workspace in the repository")]
+    Deploy {
+        #[arg(long, help = "Environment to deploy to")]
+        env: Option<String>,
+        #[arg(
+            long = "workspace",
+            value_name = "PATH",
+            help = "Only this workspace, by path or directory name (repeatable)"
+        )]
+        workspaces: Vec<String>,
+        #[arg(
+            last = true,
+            help = "Arguments passed to 'moonflare deploy' in each workspace"
+        )]
+        args: Vec<String>,
+    },
+    #[command(about = "Show each workspace's projects and most recent deploys")]
+    Status {
+        #[arg(
+            long = "workspace",
+            value_name = "PATH",
+            help = "Only this workspace, by path or directory name (repeatable)"
+        )]
+        workspaces: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AccessAction {
+    #[command(about = "Create a Cloudflare Access application and policy for a project")]
+    Protect {
+        #[arg(value_parser = project_arg, help = "Deployed project to protect")]
+        project: String,
+        #[arg(long, help = "Allow sign-ins from email addresses at this domain")]
+        policy: String,
+        #[arg(
+            long,
+            help = "Domain to protect instead of the project's route or workers.dev URL"
+        )]
+        domain: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AiAction {
+    #[command(about = "Manage AI Gateways")]
+    Gateway {
+        #[command(subcommand)]
+        action: AiGatewayAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum AiGatewayAction {
+    #[command(about = "Create an AI Gateway and route a project's Workers AI calls through it")]
+    Create {
+        #[arg(help = "Gateway name: lowercase letters, digits and hyphens")]
+        name: String,
+        #[arg(
+            long,
+            value_parser = project_arg,
+            help = "Project to route through the gateway (defaults to the only one with an [ai] binding)"
+        )]
+        project: Option<String>,
+        #[arg(
+            long,
+            default_value_t = 300,
+            help = "Seconds to cache identical requests; 0 turns caching off"
+        )]
+        cache_ttl: u64,
+        #[arg(
+            long,
+            value_name = "REQUESTS",
+            help = "Allow at most this many requests per interval"
+        )]
+        rate_limit: Option<u64>,
+        #[arg(
+            long,
+            default_value_t = 60,
+            requires = "rate_limit",
+            help = "Rate limit interval in seconds"
+        )]
+        rate_limit_interval: u64,
+        #[arg(long, help = "Don't log requests in the gateway")]
+        no_logs: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum FlagsAction {
+    #[command(about = "Create flags.toml and the flags worker, and regenerate the typed client")]
+    Init,
+    #[command(about = "Set a flag's value in the flags worker's KV namespace")]
+    Set {
+        #[arg(help = "Flag name from flags.toml")]
+        flag: String,
+        #[arg(help = "New value: true/false, a number or a string, matching the flag's type")]
+        value: String,
+        #[arg(long, help = "Set the value for this deploy environment")]
+        env: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "env",
+            help = "Set the value in Wrangler's local state, for 'moonflare dev'"
+        )]
+        local: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SecretsAction {
+    #[command(about = "Upload a Worker's secrets from the configured provider with wrangler")]
+    Push {
+        #[arg(value_parser = project_arg, help = "Worker project to push secrets to")]
+        project: String,
+        #[arg(long, help = "Wrangler environment to push to")]
+        env: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReproAction {
+    #[command(about = "Pack the workspace's configuration and environment into an archive")]
+    Pack {
+        #[arg(
+            long,
+            short,
+            value_name = "FILE",
+            help = "Archive to write (default: moonflare-repro-<timestamp>.tar.gz)"
+        )]
+        output: Option<std::path::PathBuf>,
+    },
+    #[command(about = "Restore a repro archive into a directory")]
+    Unpack {
+        #[arg(help = "Archive written by 'moonflare repro pack'")]
+        archive: std::path::PathBuf,
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Directory to restore into (default: the archive's name)"
+        )]
+        into: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TokenAction {
+    #[command(about = "List the least API token permissions the workspace needs")]
+    Scopes {
+        #[arg(long, help = "Print the permissions as JSON")]
+        json: bool,
+    },
+    #[command(about = "Show the Cloudflare user and accounts the credentials reach")]
+    Whoami {
+        #[arg(long, help = "Ask Wrangler again instead of using the cached answer")]
+        refresh: bool,
+        #[arg(long, help = "Print the account details as JSON")]
+        json: bool,
+    },
+    #[command(about = "Log in to Cloudflare with Wrangler and forget the cached account")]
+    Login,
+}
+
+#[derive(Subcommand)]
+enum ExamplesAction {
+    #[command(about = "List the examples you can add")]
+    List,
+    #[command(about = "Add an example's projects to the workspace")]
+    Add {
+        #[arg(help = "Example to add, e.g. chat")]
+        example: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PortsAction {
+    #[command(about = "List each project's dev port, reservations and conflicts")]
+    List,
+    #[command(about = "Release a project's reserved port so it goes back to its usual one")]
+    Free {
+        #[arg(value_parser = project_arg, help = "Project whose reservation to release")]
+        project: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryAction {
+    #[command(about = "Most-run commands, failure rates per project and the slowest builds")]
+    Stats {
+        #[arg(long, help = "Only runs since a date, timestamp or age like 7d")]
+        since: Option<String>,
+        #[arg(
+            long,
+            default_value_t = 10,
+            help = "List at most this many commands, projects and builds"
+        )]
+        top: usize,
+        #[arg(long, help = "Print the stats as JSON")]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SelfAction {
+    #[command(about = "Update moonflare to the latest release")]
+    Update {
+        #[arg(long, help = "Only check whether a newer version is available")]
+        check: bool,
+        #[arg(
+            long,
+            value_parser = platform::Target::parse,
+            help = "Platform to download for, e.g. darwin-arm64 (defaults to this machine)"
+        )]
+        target: Option<platform::Target>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -35,34 +1131,877 @@ enum Commands {
         path: Option<String>,
         #[arg(long, help = "Force initialization in non-empty directories")]
         force: bool,
+        #[arg(
+            long,
+            help = "Create the monorepo even inside another workspace, as a separate sub-workspace"
+        )]
+        force_nested: bool,
+        #[arg(long, help = "Hoist all dependencies to the root node_modules")]
+        shamefully_hoist: bool,
+        #[arg(
+            long = "public-hoist-pattern",
+            value_name = "PATTERN",
+            help = "Hoist matching packages to the root node_modules (repeatable)"
+        )]
+        public_hoist_pattern: Vec<String>,
     },
 
     #[command(about = "Add a new project to the monorepo")]
     Add {
-        #[arg(help = "Type of project (astro, react, worker, durable-object, crate)")]
-        project_type: String,
-        #[arg(help = "Name of the project")]
-        name: String,
+        #[arg(
+            required_unless_present = "list_types",
+            help = "Type of project (astro, react, worker, durable-object, crate, maintenance-bot, smoke-tests, e2e, container)"
+        )]
+        project_type: Option<String>,
+        #[arg(help = "Name of the project (smoke-tests defaults to smoke, e2e to e2e)")]
+        name: Option<String>,
+        #[arg(
+            long = "with",
+            value_name = "FEATURE",
+            help = "Scaffold an optional feature (storybook, hashed-assets, debug-route, queue; see --list-types)"
+        )]
+        with: Vec<String>,
+        #[arg(
+            long,
+            value_name = "PROJECT",
+            conflicts_with = "with",
+            help = "Copy an existing project of the same type instead of the template"
+        )]
+        from: Option<String>,
+        #[arg(
+            long,
+            help = "Scaffold into the project directory even if it isn't empty"
+        )]
+        force: bool,
+        #[arg(
+            long,
+            conflicts_with = "from",
+            help = "Generate a no_std crate optimized for WASM size (crates only)"
+        )]
+        small: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["from", "small"],
+            help = "Generate a WASM component with a WIT interface and TypeScript bindings (crates only)"
+        )]
+        component_model: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["from", "small", "component_model"],
+            help = "Generate a Rust library shared with the other crates, not compiled to WASM (crates only)"
+        )]
+        lib_only: bool,
+        #[arg(
+            long,
+            value_name = "WORKER",
+            value_parser = project_arg,
+            help = "Worker whose Durable Object runs the container (containers only)"
+        )]
+        attach: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "project_type",
+            help = "List the project types with their directories, features and flags"
+        )]
+        list_types: bool,
+        #[arg(
+            long,
+            requires = "list_types",
+            conflicts_with = "project_type",
+            help = "Print the project types as JSON"
+        )]
+        json: bool,
+    },
+
+    #[command(
+        about = "Import a project generated by create-vite, create-astro, C3 or cargo-generate"
+    )]
+    Import {
+        #[arg(help = "Directory the starter generated")]
+        source: String,
+        #[arg(long, help = "Project name (defaults to the directory's name)")]
+        name: Option<String>,
+        #[arg(
+            long = "as",
+            value_name = "TYPE",
+            help = "Project type to import as instead of the detected one (astro, react, durable-object, crate)"
+        )]
+        as_type: Option<String>,
     },
 
     #[command(about = "Build project(s)")]
     Build {
-        #[arg(help = "Specific project to build (optional)")]
+        #[arg(value_parser = project_arg, help = "Specific project to build (optional)")]
         project: Option<String>,
+        #[arg(
+            long,
+            value_name = "TASK",
+            default_value = "build",
+            help = "Moon task to run instead of build (e.g. build:storybook)"
+        )]
+        task: String,
+        #[arg(
+            long,
+            help = "Environment to build for, using its [env.<name>] vars (default: MOONFLARE_ENV)"
+        )]
+        env: Option<String>,
+        #[arg(
+            long,
+            help = "Print the commands each step would run, with their working directories and variables, without running them"
+        )]
+        explain_plan: bool,
+        #[arg(
+            long,
+            help = "Also run projects ignored in moonflare.toml or skipping this command in moon.yml"
+        )]
+        include_ignored: bool,
+        #[command(flatten)]
+        filter: ProjectFilterArgs,
     },
 
     #[command(about = "Start development server")]
     Dev {
-        #[arg(help = "Specific project to run (optional)")]
+        #[arg(value_parser = project_arg, help = "Specific project to run (optional)")]
         project: Option<String>,
+        #[arg(
+            long,
+            help = "Run Worker dev servers against remote Cloudflare resources"
+        )]
+        remote: bool,
+        #[arg(
+            long,
+            help = "Serve dev servers over HTTPS with a locally trusted certificate"
+        )]
+        https: bool,
+        #[arg(
+            long,
+            value_name = "TASK",
+            default_value = "dev",
+            help = "Moon task to run instead of dev (e.g. dev:storybook)"
+        )]
+        task: String,
+        #[arg(
+            long,
+            conflicts_with = "task",
+            help = "Run the project's Storybook instead of its dev server"
+        )]
+        storybook: bool,
+        #[arg(
+            long,
+            conflicts_with = "https",
+            help = "Inject latency, errors and dropped connections into Worker traffic ([dev.chaos] in moonflare.toml)"
+        )]
+        chaos: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["chaos", "remote"],
+            help = "Serve mock responses from each Worker's mock-api.json instead of running the Workers"
+        )]
+        mock_api: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["remote", "mock_api"],
+            help = "Clear local Wrangler data and load each project's seeds/ before starting"
+        )]
+        fresh: bool,
+        #[arg(
+            long,
+            conflicts_with = "max_parallel",
+            help = "Start dev servers one at a time, each once the one before is ready"
+        )]
+        serial: bool,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Start dev servers in stages, at most N waiting to become ready at once ([dev] max_parallel)"
+        )]
+        max_parallel: Option<usize>,
+        #[arg(
+            long,
+            conflicts_with = "mock_api",
+            help = "Trigger Workers' cron triggers on schedule through wrangler dev's /__scheduled"
+        )]
+        crons: bool,
+        #[arg(
+            long,
+            value_name = "FACTOR",
+            value_parser = clap::value_parser!(u32).range(1..),
+            conflicts_with = "mock_api",
+            help = "Run the cron schedule this many times faster than real time; implies --crons"
+        )]
+        fast_forward: Option<u32>,
+        #[arg(
+            long,
+            help = "Print the commands each step would run, with their working directories and variables, without running them"
+        )]
+        explain_plan: bool,
+        #[arg(
+            long,
+            help = "Also run projects ignored in moonflare.toml or skipping this command in moon.yml"
+        )]
+        include_ignored: bool,
+        #[command(flatten)]
+        filter: ProjectFilterArgs,
     },
 
     #[command(about = "Deploy project(s) to Cloudflare")]
     Deploy {
-        #[arg(help = "Specific project to deploy (optional)")]
+        #[arg(value_parser = project_arg, help = "Specific project to deploy (optional)")]
         project: Option<String>,
         #[arg(long, help = "Environment to deploy to")]
         env: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "env",
+            help = "Resolve the environment from the current git branch"
+        )]
+        auto_env: bool,
+        #[arg(
+            long,
+            help = "Refuse to deploy when the deployed projects have uncommitted changes"
+        )]
+        require_clean: bool,
+        #[arg(
+            long,
+            help = "Generate an SBOM for each deployed project and record its hash"
+        )]
+        sbom: bool,
+        #[arg(
+            long,
+            help = "Run pnpm audit and cargo audit first, blocking on advisories above [deploy.audit] fail_on"
+        )]
+        audit: bool,
+        #[arg(
+            long,
+            help = "Refuse to deploy while the deployed projects have pending D1 migrations"
+        )]
+        require_migrations: bool,
+        #[arg(
+            long,
+            help = "Bundle each Worker first and refuse to deploy one over its size budget or needing nodejs_compat"
+        )]
+        analyze_bundle: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["auto_env", "require_clean"],
+            help = "Redeploy to a staging environment whenever project files change"
+        )]
+        watch: bool,
+        #[arg(
+            long,
+            value_name = "TASK",
+            default_value = "deploy",
+            help = "Moon task to run instead of deploy (e.g. deploy:pages)"
+        )]
+        task: String,
+        #[arg(
+            long,
+            value_name = "ENV",
+            help = "Confirm a deploy to a protected environment by repeating its name"
+        )]
+        confirm: Option<String>,
+        #[arg(
+            long,
+            help = "Keep variables set in the Cloudflare dashboard instead of replacing them"
+        )]
+        keep_vars: bool,
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with = "build_remote",
+            help = "Set the KEY=value lines of FILE as Worker vars, over the projects' Wrangler config"
+        )]
+        vars_file: Option<std::path::PathBuf>,
+        #[arg(
+            long,
+            overrides_with = "no_notify",
+            help = "Post a deploy summary to the [deploy.notify] webhook"
+        )]
+        notify: bool,
+        #[arg(
+            long,
+            help = "Don't post a deploy summary, even with a webhook configured"
+        )]
+        no_notify: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["watch", "sbom", "keep_vars"],
+            help = "Build and deploy in Cloudflare with Workers Builds instead of locally"
+        )]
+        build_remote: bool,
+        #[arg(
+            long,
+            value_name = "DIR",
+            conflicts_with_all = ["watch", "build_remote"],
+            help = "Deploy prebuilt output from DIR, checked against its build manifest, without building"
+        )]
+        from_artifacts: Option<std::path::PathBuf>,
+        #[arg(
+            long,
+            help = "Keep objects missing from the build output in the bucket of an r2-static deploy"
+        )]
+        no_delete: bool,
+        #[arg(
+            long,
+            conflicts_with = "watch",
+            help = "Run the smoke tests from 'moonflare add smoke-tests' against the deployed projects"
+        )]
+        smoke: bool,
+        #[arg(
+            long,
+            conflicts_with = "watch",
+            help = "Print the commands each step would run, with their working directories and variables, without running them"
+        )]
+        explain_plan: bool,
+        #[arg(
+            long,
+            help = "Also run projects ignored in moonflare.toml or skipping this command in moon.yml"
+        )]
+        include_ignored: bool,
+        #[command(flatten)]
+        filter: ProjectFilterArgs,
+    },
+
+    #[command(about = "Deploy exactly what one environment runs to another")]
+    Promote {
+        #[arg(help = "Environment to promote from, e.g. staging")]
+        from: String,
+        #[arg(help = "Environment to promote to, e.g. production")]
+        to: String,
+        #[arg(value_parser = project_arg, help = "Specific project to promote (optional)")]
+        project: Option<String>,
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Directory with the build manifest and output to ship (default: the workspace)"
+        )]
+        artifacts: Option<std::path::PathBuf>,
+        #[arg(
+            long,
+            value_name = "ENV",
+            help = "Confirm promoting to a protected environment by repeating its name"
+        )]
+        confirm: Option<String>,
+    },
+
+    #[command(about = "Version, changelog and tag a project from its conventional commits")]
+    Release {
+        #[arg(value_parser = project_arg, help = "Project to release")]
+        project: String,
+        #[arg(
+            long,
+            value_enum,
+            help = "Bump by this much instead of deriving it from the commits"
+        )]
+        bump: Option<Bump>,
+        #[arg(
+            long,
+            help = "Print the next version and changelog entry without changing anything"
+        )]
+        dry_run: bool,
+        #[arg(long, help = "Deploy the project after tagging the release")]
+        deploy: bool,
+        #[arg(
+            long,
+            requires = "deploy",
+            help = "Environment to deploy the release to"
+        )]
+        env: Option<String>,
+    },
+
+    #[command(about = "Publish a crate to crates.io or a package to npm")]
+    Publish {
+        #[arg(value_parser = project_arg, help = "Crate or package project to publish")]
+        project: String,
+        #[arg(long, help = "Pack and verify without uploading")]
+        dry_run: bool,
+        #[arg(long, help = "Publish an npm package with a provenance attestation")]
+        provenance: bool,
+        #[arg(long, help = "Don't run the project's build and test tasks first")]
+        skip_checks: bool,
+    },
+
+    #[command(about = "Run project tests")]
+    Test {
+        #[arg(value_parser = project_arg, help = "Specific project to test (optional)")]
+        project: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "project",
+            help = "Run the Playwright tests from 'moonflare add e2e' instead of the projects' tests"
+        )]
+        e2e: bool,
+        #[arg(
+            long,
+            value_name = "TARGET",
+            requires = "e2e",
+            default_value = "dev",
+            help = "Run the end-to-end tests against the dev servers (dev) or an environment's deployments"
+        )]
+        against: String,
+        #[arg(
+            long,
+            help = "Also run projects ignored in moonflare.toml or skipping this command in moon.yml"
+        )]
+        include_ignored: bool,
+        #[command(flatten)]
+        filter: ProjectFilterArgs,
+    },
+
+    #[command(about = "Report the licenses of npm and cargo dependencies across the workspace")]
+    Licenses {
+        #[arg(long, value_enum, default_value = "table", help = "Output format")]
+        format: LicenseFormat,
+        #[arg(long, help = "Write the report to this path instead of stdout")]
+        output: Option<std::path::PathBuf>,
+        #[arg(
+            long,
+            help = "Include devDependencies, which don't ship with deployments"
+        )]
+        include_dev: bool,
+        #[command(flatten)]
+        filter: ProjectFilterArgs,
+    },
+
+    #[command(about = "Run benchmarks and write a JSON report")]
+    Bench {
+        #[arg(value_parser = project_arg, help = "Specific project to benchmark (optional)")]
+        project: Option<String>,
+        #[arg(
+            long,
+            help = "Write the report to this path instead of .moonflare/bench"
+        )]
+        output: Option<std::path::PathBuf>,
+        #[command(flatten)]
+        filter: ProjectFilterArgs,
+    },
+
+    #[command(about = "Generate HTTP load against a project and report latency")]
+    Loadtest {
+        #[arg(value_parser = project_arg, help = "Project to load test")]
+        project: String,
+        #[arg(long, default_value_t = 100, help = "Requests per second")]
+        rps: u32,
+        #[arg(long, default_value = "30s", value_parser = parse_duration, help = "How long to generate load (e.g. 30s, 2m)")]
+        duration: std::time::Duration,
+        #[arg(
+            long,
+            value_enum,
+            default_value = "dev",
+            help = "Target the local dev server or the deployed URL"
+        )]
+        against: LoadTestTarget,
+        #[arg(long, help = "Explicit base URL to target")]
+        url: Option<String>,
+        #[arg(long, default_value = "/", help = "Request path")]
+        path: String,
+    },
+
+    #[command(about = "Manage project tags")]
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+
+    #[command(about = "Manage Moon task inheritance")]
+    Tasks {
+        #[command(subcommand)]
+        action: TasksAction,
+    },
+
+    #[command(
+        about = "Regenerate binding types and share RPC types between Workers that bind to each other"
+    )]
+    Types {
+        #[arg(value_parser = project_arg, help = "Only this project and the Workers it binds to or is bound by")]
+        project: Option<String>,
+    },
+
+    #[command(about = "Rename workers to follow the workspace's worker_name pattern")]
+    SyncNames {
+        #[arg(
+            long,
+            help = "Report names that need renaming and fail instead of rewriting"
+        )]
+        check: bool,
+    },
+
+    #[command(about = "Find declared bindings no source uses and env accesses with no binding")]
+    AuditBindings {
+        #[arg(value_parser = project_arg, help = "Only audit this project")]
+        project: Option<String>,
+        #[arg(long, help = "Remove unused bindings from the Wrangler configurations")]
+        fix: bool,
+    },
+
+    #[command(about = "Summarize workspace composition and build health")]
+    Stats {
+        #[arg(long, default_value_t = 20, help = "Recent builds to average over")]
+        runs: usize,
+        #[arg(long, help = "Print the summary as JSON")]
+        json: bool,
+    },
+
+    #[command(
+        about = "Show the log of operations that changed the workspace",
+        args_conflicts_with_subcommands = true
+    )]
+    History {
+        #[command(subcommand)]
+        action: Option<HistoryAction>,
+        #[arg(long, help = "Only this command (e.g. 'deploy' or 'tag add')")]
+        command: Option<String>,
+        #[arg(long, help = "Only operations by this user")]
+        user: Option<String>,
+        #[arg(long, help = "Only operations since a date, timestamp or age like 7d")]
+        since: Option<String>,
+        #[arg(long, help = "Only operations that failed")]
+        failed: bool,
+        #[arg(
+            long,
+            default_value_t = 50,
+            help = "Show at most this many (0 for all)"
+        )]
+        limit: usize,
+        #[arg(long, help = "Print entries as JSON")]
+        json: bool,
+    },
+
+    #[command(about = "Search project sources, grouped by project")]
+    Grep {
+        #[arg(help = "Regular expression to search for")]
+        pattern: String,
+        #[arg(value_parser = project_arg, help = "Only search this project")]
+        project: Option<String>,
+        #[arg(
+            long = "type",
+            value_enum,
+            help = "Only search projects of this type (repeatable)"
+        )]
+        kinds: Vec<ProjectKind>,
+        #[arg(short, long, help = "Match case-insensitively")]
+        ignore_case: bool,
+        #[arg(
+            short = 'F',
+            long,
+            help = "Treat the pattern as a literal string instead of a regular expression"
+        )]
+        fixed_strings: bool,
+        #[arg(long, help = "Print matches as JSON")]
+        json: bool,
+        #[command(flatten)]
+        filter: ProjectFilterArgs,
+    },
+
+    #[command(about = "Explain why Moon re-ran a project's task")]
+    Why {
+        #[arg(value_parser = project_arg, help = "Project whose task re-ran")]
+        project: String,
+        #[arg(long, default_value = "build", help = "Task to explain")]
+        task: String,
+    },
+
+    #[command(about = "Run Moon targets when files matching the [watch.rules] globs change")]
+    Watch,
+
+    #[command(about = "Document a project's tasks, bindings and variables in its README")]
+    Describe {
+        #[arg(value_parser = project_arg, long, help = "Only this project")]
+        project: Option<String>,
+        #[arg(
+            long,
+            help = "Write the documentation into the README instead of printing it"
+        )]
+        write: bool,
+    },
+
+    #[command(about = "Show the routes and domains projects are reachable on")]
+    Routes {
+        #[command(subcommand)]
+        action: RoutesAction,
+    },
+
+    #[command(about = "Set and review Smart Placement and Durable Object location hints")]
+    Placement {
+        #[command(subcommand)]
+        action: PlacementAction,
+    },
+
+    #[command(about = "Sync directories to R2 buckets")]
+    R2 {
+        #[command(subcommand)]
+        action: R2Action,
+    },
+
+    #[command(about = "Branch D1 databases for preview environments")]
+    D1 {
+        #[command(subcommand)]
+        action: D1Action,
+    },
+
+    #[command(about = "Inspect Durable Object namespaces, objects and local state")]
+    Do {
+        #[command(subcommand)]
+        action: DoAction,
+    },
+
+    #[command(about = "Browse, export and import KV namespace data")]
+    Kv {
+        #[command(subcommand)]
+        action: KvAction,
+    },
+
+    #[command(about = "Send queue messages and inspect what local consumers received")]
+    Queues {
+        #[command(subcommand)]
+        action: QueuesAction,
+    },
+
+    #[command(about = "Show or change the directories projects live in")]
+    Layout {
+        #[command(subcommand)]
+        action: LayoutAction,
+    },
+
+    #[command(about = "Manage the workspace itself")]
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceAction,
+    },
+
+    #[command(about = "Build and deploy projects in Cloudflare with Workers Builds")]
+    Builds {
+        #[command(subcommand)]
+        action: BuildsAction,
+    },
+
+    #[command(about = "Analyze Worker bundles against their size budgets")]
+    Bundle {
+        #[command(subcommand)]
+        action: BundleAction,
+    },
+
+    #[command(about = "Migrate projects between Cloudflare products and check database migrations")]
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateAction,
+    },
+
+    #[command(about = "Build, deploy or inspect several workspaces in one repository")]
+    Meta {
+        #[command(subcommand)]
+        action: MetaAction,
+    },
+
+    #[command(about = "Run a background daemon that caches the project graph")]
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
+    },
+
+    #[command(about = "Refactor code and configuration across projects")]
+    Refactor {
+        #[command(subcommand)]
+        action: RefactorAction,
+    },
+
+    #[command(about = "Install git hooks that check changes before commit and push")]
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+
+    #[command(about = "Check the workspace for known-bad configuration")]
+    Doctor {
+        #[arg(
+            long,
+            help = "Sync .npmrc, workspace globs and a generated devcontainer or flake with the workspace and upgrade deprecated Wrangler keys"
+        )]
+        fix: bool,
+        #[arg(
+            long,
+            conflicts_with = "fix",
+            help = "Check that the Cloudflare API, npm registry and release downloads are reachable"
+        )]
+        network: bool,
+    },
+
+    #[command(about = "Check project configuration")]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    #[command(about = "Generate CI configuration for the workspace")]
+    Ci {
+        #[command(subcommand)]
+        action: CiAction,
+    },
+
+    #[command(about = "Generate a devcontainer for GitHub Codespaces and VS Code")]
+    Devcontainer {
+        #[command(subcommand)]
+        action: DevcontainerAction,
+    },
+
+    #[command(about = "Browse workspace and moonflare documentation offline")]
+    Docs {
+        #[command(subcommand)]
+        action: DocsAction,
+    },
+
+    #[command(about = "Generate a Nix flake for the workspace toolchain")]
+    Nix {
+        #[command(subcommand)]
+        action: NixAction,
+    },
+
+    #[command(about = "Remove build outputs and expired artifacts")]
+    Clean {
+        #[arg(
+            long,
+            help = "Only remove what the [retention] policy in moonflare.toml has expired"
+        )]
+        auto: bool,
+        #[arg(long, help = "List what would be removed without deleting anything")]
+        dry_run: bool,
+    },
+
+    #[command(about = "Manage local development variables")]
+    Env {
+        #[command(subcommand)]
+        action: EnvAction,
+    },
+
+    #[command(about = "Push Worker secrets from 1Password, Vault or Doppler")]
+    Secrets {
+        #[command(subcommand)]
+        action: SecretsAction,
+    },
+
+    #[command(about = "Pack or restore a sanitized workspace for a bug report")]
+    Repro {
+        #[command(subcommand)]
+        action: ReproAction,
+    },
+
+    #[command(about = "Work out the Cloudflare API token the workspace needs")]
+    Token {
+        #[command(subcommand)]
+        action: TokenAction,
+    },
+
+    #[command(about = "Manage workspace feature flags served from KV")]
+    Flags {
+        #[command(subcommand)]
+        action: FlagsAction,
+    },
+
+    #[command(about = "Load seed data from projects' seeds/ directories into D1, KV and Workers")]
+    Seed {
+        #[arg(value_parser = project_arg, help = "Project to seed (all projects with seeds/ when omitted)")]
+        project: Option<String>,
+        #[arg(long, help = "Seed the remote resources of this environment")]
+        env: Option<String>,
+        #[arg(
+            long,
+            help = "Seed remote resources instead of Wrangler's local state (implied by --env)"
+        )]
+        remote: bool,
+    },
+
+    #[command(about = "Put Cloudflare Access in front of a deployed project")]
+    Access {
+        #[command(subcommand)]
+        action: AccessAction,
+    },
+
+    #[command(about = "Manage Workers AI resources such as AI Gateways")]
+    Ai {
+        #[command(subcommand)]
+        action: AiAction,
+    },
+
+    #[command(about = "Restore files from a snapshot taken before a destructive operation")]
+    Restore {
+        #[arg(help = "Snapshot to restore, or 'latest' (lists snapshots when omitted)")]
+        snapshot: Option<String>,
+    },
+
+    #[command(
+        about = "Walk through breaking template and tooling changes, applying or skipping each"
+    )]
+    Upgrade {
+        #[arg(
+            long,
+            help = "Replay the decisions recorded in .moonflare/upgrades.json without asking"
+        )]
+        apply_recorded: bool,
+    },
+
+    #[command(
+        name = "verify-matrix",
+        about = "Build the workspace with every combination of Node.js, Wrangler and Moon versions"
+    )]
+    VerifyMatrix {
+        #[arg(
+            long,
+            help = "Node.js version to build with (repeatable; replaces [matrix] node)"
+        )]
+        node: Vec<String>,
+        #[arg(
+            long,
+            help = "Wrangler version to build with (repeatable; replaces [matrix] wrangler)"
+        )]
+        wrangler: Vec<String>,
+        #[arg(
+            long,
+            help = "Moon version to build with (repeatable; replaces [matrix] moon)"
+        )]
+        moon: Vec<String>,
+        #[arg(long, help = "Moon task to run in each combination (build by default)")]
+        task: Option<String>,
+        #[arg(long, help = "Keep each combination's copy of the workspace")]
+        keep: bool,
+        #[arg(long, help = "Print the results as JSON")]
+        json: bool,
+    },
+
+    #[command(about = "Build a project for production and serve it locally")]
+    Preview {
+        #[arg(value_parser = project_arg, help = "Project to preview")]
+        project: String,
+        #[arg(long, help = "Port for the preview server")]
+        port: Option<u16>,
+    },
+
+    #[command(about = "Generate a CycloneDX SBOM of a project's npm and cargo dependencies")]
+    Sbom {
+        #[arg(value_parser = project_arg, help = "Project to describe")]
+        project: String,
+        #[arg(
+            long,
+            help = "Write the SBOM to this path instead of .moonflare/sbom/<project>.cdx.json"
+        )]
+        output: Option<std::path::PathBuf>,
+    },
+
+    #[command(about = "Browse and add runnable examples")]
+    Examples {
+        #[command(subcommand)]
+        action: ExamplesAction,
+    },
+
+    #[command(about = "Show dev port assignments and conflicts, and release reserved ports")]
+    Ports {
+        #[command(subcommand)]
+        action: Option<PortsAction>,
+    },
+
+    #[command(name = "self", about = "Manage the moonflare installation")]
+    SelfManage {
+        #[command(subcommand)]
+        action: SelfAction,
     },
 
     #[command(about = "Rename a project")]
@@ -72,6 +2011,125 @@ enum Commands {
         #[arg(help = "New project name")]
         new_name: String,
     },
+
+    #[command(about = "Move a project under archive/, out of the workspace")]
+    Archive {
+        #[arg(value_parser = project_arg, help = "Project to archive")]
+        project: String,
+    },
+
+    #[command(about = "Move an archived project back into the workspace")]
+    Unarchive {
+        #[arg(help = "Archived project to restore")]
+        project: String,
+    },
+}
+
+// Run a command that records task results and hand them to the selected reporter
+async fn run_reported<E: std::fmt::Display>(
+    command: &str,
+    chosen: Option<ReporterKind>,
+    output: Option<&std::path::Path>,
+    run: impl AsyncFnOnce(&TaskLog) -> std::result::Result<(), E>,
+) -> std::result::Result<(), E> {
+    let reporter = chosen.unwrap_or_default();
+    let actions = reporter::reports_to_actions(command, chosen);
+    let tasks = TaskLog::new(reporter != ReporterKind::Console || actions);
+    let started = std::time::Instant::now();
+    let result = run(&tasks).await;
+
+    let report = CommandReport {
+        command: command.to_string(),
+        success: result.is_ok(),
+        duration: started.elapsed(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+        tasks: tasks.into_tasks(),
+    };
+    if let Err(e) = emit_report(reporter, &report, output) {
+        eprintln!("Failed to write {:?} report: {}", reporter, e);
+    }
+    if actions && let Err(e) = reporter::emit_actions_reports(&report) {
+        eprintln!("Failed to write the GitHub Actions reports: {}", e);
+    }
+
+    result
+}
+
+// Render the custom help page for a command, or None to fall back to clap
+#[cfg(feature = "ui")]
+fn render_command_help(
+    ui: &MoonflareUI,
+    command: &str,
+) -> Option<Result<(), starbase_console::ConsoleError>> {
+    match command {
+        "init" => Some(ui.render_init_help()),
+        "add" => Some(ui.render_add_help()),
+        "import" => Some(ui.render_import_help()),
+        "build" => Some(ui.render_build_help()),
+        "dev" => Some(ui.render_dev_help()),
+        "deploy" => Some(ui.render_deploy_help()),
+        "promote" => Some(ui.render_promote_help()),
+        "release" => Some(ui.render_release_help()),
+        "publish" => Some(ui.render_publish_help()),
+        "rename" => Some(ui.render_rename_help()),
+        "archive" => Some(ui.render_archive_help()),
+        "unarchive" => Some(ui.render_unarchive_help()),
+        "test" => Some(ui.render_test_help()),
+        "tag" => Some(ui.render_tag_help()),
+        "bench" => Some(ui.render_bench_help()),
+        "licenses" => Some(ui.render_licenses_help()),
+        "loadtest" => Some(ui.render_loadtest_help()),
+        "ci" => Some(ui.render_ci_help()),
+        "config" => Some(ui.render_config_help()),
+        "daemon" => Some(ui.render_daemon_help()),
+        "devcontainer" => Some(ui.render_devcontainer_help()),
+        "docs" => Some(ui.render_docs_help()),
+        "doctor" => Some(ui.render_doctor_help()),
+        "tasks" => Some(ui.render_tasks_help()),
+        "types" => Some(ui.render_types_help()),
+        "workspace" => Some(ui.render_workspace_help()),
+        "layout" => Some(ui.render_layout_help()),
+        "describe" => Some(ui.render_describe_help()),
+        "r2" => Some(ui.render_r2_help()),
+        "d1" => Some(ui.render_d1_help()),
+        "do" => Some(ui.render_do_help()),
+        "kv" => Some(ui.render_kv_help()),
+        "queues" => Some(ui.render_queues_help()),
+        "refactor" => Some(ui.render_refactor_help()),
+        "hooks" => Some(ui.render_hooks_help()),
+        "routes" => Some(ui.render_routes_help()),
+        "placement" => Some(ui.render_placement_help()),
+        "sync-names" => Some(ui.render_sync_names_help()),
+        "audit-bindings" => Some(ui.render_audit_bindings_help()),
+        "grep" => Some(ui.render_grep_help()),
+        "why" => Some(ui.render_why_help()),
+        "watch" => Some(ui.render_watch_help()),
+        "history" => Some(ui.render_history_help()),
+        "stats" => Some(ui.render_stats_help()),
+        "clean" => Some(ui.render_clean_help()),
+        "env" => Some(ui.render_env_help()),
+        "flags" => Some(ui.render_flags_help()),
+        "seed" => Some(ui.render_seed_help()),
+        "access" => Some(ui.render_access_help()),
+        "ai" => Some(ui.render_ai_help()),
+        "meta" => Some(ui.render_meta_help()),
+        "migrate" => Some(ui.render_migrate_help()),
+        "builds" => Some(ui.render_builds_help()),
+        "bundle" => Some(ui.render_bundle_help()),
+        "secrets" => Some(ui.render_secrets_help()),
+        "repro" => Some(ui.render_repro_help()),
+        "token" => Some(ui.render_token_help()),
+        "restore" => Some(ui.render_restore_help()),
+        "upgrade" => Some(ui.render_upgrade_help()),
+        "verify-matrix" => Some(ui.render_verify_matrix_help()),
+        "preview" => Some(ui.render_preview_help()),
+        "sbom" => Some(ui.render_sbom_help()),
+        "examples" => Some(ui.render_examples_help()),
+        "ports" => Some(ui.render_ports_help()),
+        "nix" => Some(ui.render_nix_help()),
+        "self" => Some(ui.render_self_help()),
+        _ => None,
+    }
 }
 
 #[tokio::main]
@@ -79,145 +2137,1334 @@ async fn main() -> Result<()> {
     // Install miette panic and error hooks for better error reporting
     miette::set_panic_hook();
 
-    let ui = MoonflareUI::new();
-
     // Check for help requests before parsing with clap
     let args: Vec<String> = env::args().collect();
 
+    // Panics leave a diagnostics bundle behind; `--report` has to be known before clap runs
+    crash::install(&args, args.iter().any(|a| a == "--report"));
+    #[cfg(feature = "debug-panic")]
+    if env::var_os(crash::PANIC_ENV).is_some_and(|value| value != "error") {
+        panic!("{} is set", crash::PANIC_ENV);
+    }
+
     // Handle version requests
     if args.len() == 2 && (args[1] == "--version" || args[1] == "-v" || args[1] == "-V") {
         println!("{}", env!("CARGO_PKG_VERSION"));
         return Ok(());
     }
 
-    // Handle main help
-    if (args.len() == 1 || args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()))
-        && args.len() == 2
-        && (args[1] == "--help" || args[1] == "-h")
+    // Handle help requests with the boxed help pages
+    #[cfg(feature = "ui")]
     {
-        ui.render_main_help()
-            .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-        return Ok(());
-    }
+        let ui = MoonflareUI::new();
 
-    // Handle subcommand help
-    if args.len() >= 3 && (args[2] == "--help" || args[2] == "-h") {
-        match args[1].as_str() {
-            "init" => {
-                ui.render_init_help()
-                    .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-                return Ok(());
-            }
-            "add" => {
-                ui.render_add_help()
-                    .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-                return Ok(());
-            }
-            "build" => {
-                ui.render_build_help()
-                    .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-                return Ok(());
-            }
-            "dev" => {
-                ui.render_dev_help()
-                    .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-                return Ok(());
-            }
-            "deploy" => {
-                ui.render_deploy_help()
+        // Handle main help
+        if (args.len() == 1
+            || args.contains(&"--help".to_string())
+            || args.contains(&"-h".to_string()))
+            && args.len() == 2
+            && (args[1] == "--help" || args[1] == "-h")
+        {
+            ui.render_main_help()
+                .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
+            return Ok(());
+        }
+
+        // Handle subcommand help
+        if args.len() >= 3
+            && (args[2] == "--help" || args[2] == "-h")
+            && let Some(rendered) = render_command_help(&ui, &args[1])
+        {
+            rendered.map_err(|e| miette::miette!("Failed to render help: {}", e))?;
+            return Ok(());
+        }
+
+        // Handle standalone help command
+        if args.len() >= 2 && args[1] == "help" {
+            if args.len() == 2 {
+                // "moonflare help" - show main help
+                ui.render_main_help()
                     .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
                 return Ok(());
-            }
-            "rename" => {
-                ui.render_rename_help()
-                    .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
+            } else if args.len() == 3
+                && let Some(rendered) = render_command_help(&ui, &args[2])
+            {
+                // "moonflare help <command>" - show command help
+                rendered.map_err(|e| miette::miette!("Failed to render help: {}", e))?;
                 return Ok(());
             }
-            _ => {
-                // Fall back to clap for other commands
-            }
         }
     }
 
-    // Handle standalone help command
-    if args.len() >= 2 && args[1] == "help" {
-        if args.len() == 2 {
-            // "moonflare help" - show main help
-            ui.render_main_help()
-                .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-            return Ok(());
-        } else if args.len() == 3 {
-            // "moonflare help <command>" - show command help
-            match args[2].as_str() {
-                "init" => {
-                    ui.render_init_help()
-                        .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-                    return Ok(());
-                }
-                "add" => {
-                    ui.render_add_help()
-                        .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-                    return Ok(());
-                }
-                "build" => {
-                    ui.render_build_help()
-                        .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-                    return Ok(());
-                }
-                "dev" => {
-                    ui.render_dev_help()
-                        .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-                    return Ok(());
-                }
-                "deploy" => {
-                    ui.render_deploy_help()
-                        .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-                    return Ok(());
-                }
-                "rename" => {
-                    ui.render_rename_help()
-                        .map_err(|e| miette::miette!("Failed to render help: {}", e))?;
-                    return Ok(());
-                }
-                _ => {
-                    // Fall back to clap for other commands
+    // Project arguments are resolved while clap parses them, so this has to be known first
+    projects::set_auto_correct(args.iter().any(|a| a == "--auto-correct"));
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let command = matches.subcommand_name().unwrap_or_default().to_string();
+
+    events::init(cli.events_file.as_deref(), cli.events_fd)
+        .map_err(|e| miette::miette!("Failed to open event stream: {}", e))?;
+    events::emit(Event::CommandStarted {
+        command: &command,
+        args: &args[1..],
+    });
+    #[cfg(feature = "telemetry")]
+    trace::init(cli.otel_endpoint.as_deref(), &command, &args[1..]);
+    #[cfg(not(feature = "telemetry"))]
+    warn_without_telemetry(cli.otel_endpoint.as_deref(), &command);
+
+    let started = std::time::Instant::now();
+    #[cfg(feature = "telemetry")]
+    let started_at = std::time::SystemTime::now();
+    interrupt::install(&command);
+    let result = run(cli, &command).await;
+    if interrupt::is_stopping() {
+        // The Ctrl-C handler reports what happened and exits once the processes are stopped
+        std::future::pending::<()>().await;
+    }
+    if let Err(e) = &result {
+        crash::report_error(e);
+        events::emit(Event::Error {
+            command: &command,
+            message: &e.to_string(),
+        });
+    }
+    // A plan only shows changes, so there's nothing to undo
+    if let Some(operation) = history::mutating_command(&matches).filter(|_| !plan::plan_only()) {
+        let outcome = result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+        history::record(&HistoryEntry::new(
+            operation,
+            &args[1..],
+            &outcome,
+            events::secs(started.elapsed()),
+        ));
+    }
+    if let Some(name) = history::command_name(&matches) {
+        history::record_usage(&UsageEntry::new(
+            name,
+            &args[1..],
+            result.is_ok(),
+            events::secs(started.elapsed()),
+        ));
+    }
+    #[cfg(feature = "telemetry")]
+    {
+        metrics::record(&command, result.is_ok(), started_at, started.elapsed()).await;
+        trace::finish(result.as_ref().err().map(|e| e.to_string()).as_deref()).await;
+    }
+    events::emit(Event::CommandFinished {
+        command: &command,
+        success: result.is_ok(),
+        duration_secs: events::secs(started.elapsed()),
+    });
+
+    result
+}
+
+/// Builds without the `telemetry` feature say so instead of silently dropping traces and
+/// metrics the workspace asks for
+#[cfg(not(feature = "telemetry"))]
+fn warn_without_telemetry(otel_endpoint: Option<&str>, command: &str) {
+    let config = MoonflareConfig::load().unwrap_or_default();
+    if let Some(endpoint) = config.tracing.endpoint(otel_endpoint) {
+        eprintln!(
+            "Warning: not tracing to {}; this moonflare was built without the `telemetry` feature",
+            endpoint
+        );
+    }
+    if config.metrics.exports(command) {
+        eprintln!(
+            "Warning: not exporting metrics; this moonflare was built without the `telemetry` feature"
+        );
+    }
+}
+
+async fn run(cli: Cli, command: &str) -> Result<()> {
+    #[cfg(feature = "debug-panic")]
+    if env::var_os(crash::PANIC_ENV).is_some_and(|value| value == "error") {
+        return Err(miette::Report::new(crash::Internal(format!(
+            "{} is set to error",
+            crash::PANIC_ENV
+        ))));
+    }
+    templates::engine::set_lenient(cli.lenient);
+    direct_tasks::set_forced(cli.no_moon);
+    if let Some(path) = &cli.config {
+        config::set_config_path(path);
+    }
+    if let Some(path) = config::config_override()
+        && !path.exists()
+    {
+        return Err(miette::miette!(
+            "Config file {} doesn't exist",
+            path.display()
+        ));
+    }
+
+    if let Some(path) = &cli.env_file {
+        task_env::load_env_file(path).map_err(|e| miette::miette!("{}", e))?;
+    }
+
+    let timeouts = MoonflareConfig::load()
+        .map(|c| c.timeouts)
+        .unwrap_or_default();
+    timeout::set_limit(
+        cli.timeout.or_else(|| timeouts.limit(command)),
+        timeouts.grace(),
+    );
+
+    let profile = cli
+        .ui
+        .or_else(|| MoonflareConfig::load().ok().and_then(|c| c.ui.profile))
+        .unwrap_or_default();
+    ui::set_output_profile(profile);
+
+    if cli.plan
+        && !matches!(
+            cli.command,
+            Commands::Refactor { .. }
+                | Commands::SyncNames { .. }
+                | Commands::Tasks { .. }
+                | Commands::Types { .. }
+                | Commands::Deploy { .. }
+                | Commands::Upgrade { .. }
+                | Commands::Hooks {
+                    action: HooksAction::Uninstall
                 }
+        )
+    {
+        return Err(miette::miette!(
+            "--plan isn't supported by '{}'; it works with refactor, sync-names, tasks sync, types, deploy, upgrade and hooks uninstall",
+            command
+        ));
+    }
+    plan::set_plan_only(cli.plan);
+    plan::set_auto_approve(cli.auto_approve);
+
+    // Refuse to operate on workspaces created by a newer moonflare, or with a moonflare.toml
+    // that doesn't load; `config` commands still run so it can be inspected and migrated
+    if !matches!(
+        cli.command,
+        Commands::Init { .. } | Commands::SelfManage { .. }
+    ) {
+        match MoonflareConfig::load() {
+            Ok(config) => config.check_min_version().map_err(|e| *e)?,
+            Err(e) if !matches!(cli.command, Commands::Config { .. }) => {
+                return Err(miette::miette!("{:#}", e));
             }
+            Err(_) => {}
+        }
+    }
+
+    // Projects described in package.json#moonflare get their moon.yml regenerated before Moon
+    // reads it
+    if !cli.plan
+        && !matches!(
+            cli.command,
+            Commands::Init { .. } | Commands::SelfManage { .. }
+        )
+        && std::path::Path::new(".moon/workspace.yml").exists()
+    {
+        let report = package_config::sync_package_configs();
+        for path in &report.written {
+            eprintln!("Generated {} from package.json#moonflare", path.display());
+        }
+        for (_, error) in &report.invalid {
+            eprintln!("Warning: {}", error);
         }
     }
 
-    let cli = Cli::parse();
+    let reporter = cli.reporter;
+    let reporter_output = cli.reporter_output.as_deref();
+    let changes_projects = matches!(
+        &cli.command,
+        Commands::Add { .. }
+            | Commands::Import { .. }
+            | Commands::Rename { .. }
+            | Commands::Archive { .. }
+            | Commands::Unarchive { .. }
+            | Commands::Restore { .. }
+            | Commands::Tag {
+                action: TagAction::Add { .. } | TagAction::Remove { .. }
+            }
+            | Commands::Layout {
+                action: LayoutAction::Set { .. }
+            }
+            | Commands::Workspace {
+                action: WorkspaceAction::Rename { .. }
+            }
+            | Commands::Migrate {
+                action: MigrateAction::PagesToWorker { .. }
+                    | MigrateAction::C3 { .. }
+                    | MigrateAction::Turbo { .. }
+                    | MigrateAction::Nx { .. }
+            }
+            | Commands::Examples {
+                action: ExamplesAction::Add { .. }
+            }
+    );
 
     match cli.command {
-        Commands::Init { name, path, force } => {
+        Commands::Init {
+            name,
+            path,
+            force,
+            force_nested,
+            shamefully_hoist,
+            public_hoist_pattern,
+        } => {
             let init_cmd = InitCommand::new();
-            init_cmd.execute(&name, path.as_deref(), force).await?;
+            let pnpm = PnpmConfig {
+                shamefully_hoist,
+                public_hoist_pattern,
+                ..Default::default()
+            };
+            init_cmd
+                .execute(&name, path.as_deref(), force, force_nested, pnpm)
+                .await?;
+
+            let workspace = if name == "." {
+                std::path::PathBuf::from(".")
+            } else {
+                std::path::Path::new(path.as_deref().unwrap_or(".")).join(&name)
+            };
+            onboarding::run_checklist(&workspace)
+                .map_err(|e| miette::miette!("Onboarding checklist failed: {}", e))?;
         }
-        Commands::Add { project_type, name } => {
+        Commands::Add {
+            project_type,
+            name,
+            with,
+            from,
+            force,
+            small,
+            component_model,
+            lib_only,
+            attach,
+            list_types,
+            json,
+        } => {
             let add_cmd = AddCommand::new();
-            add_cmd
-                .execute(&project_type, &name)
+            let flavor = if component_model {
+                CrateFlavor::Component
+            } else if lib_only {
+                CrateFlavor::LibOnly
+            } else if small {
+                CrateFlavor::Small
+            } else {
+                CrateFlavor::Standard
+            };
+            match project_type {
+                Some(project_type) if !list_types => {
+                    add_cmd
+                        .execute(
+                            &project_type,
+                            name.as_deref(),
+                            &AddOptions {
+                                features: with,
+                                from,
+                                force,
+                                flavor,
+                                attach,
+                            },
+                        )
+                        .await
+                }
+                _ => add_cmd.execute_list_types(json),
+            }
+            .map_err(|e| command_error("Add", e))?;
+        }
+        Commands::Import {
+            source,
+            name,
+            as_type,
+        } => {
+            let import_cmd = ImportCommand::new();
+            import_cmd
+                .execute(&source, name.as_deref(), as_type.as_deref())
                 .await
-                .map_err(|e| miette::miette!("Add command failed: {}", e))?;
+                .map_err(|e| command_error("Import", e))?;
         }
-        Commands::Build { project } => {
+        Commands::Build {
+            project,
+            task,
+            env,
+            explain_plan,
+            include_ignored,
+            filter,
+        } => {
+            let (project, filter) = filter.select(project);
+            let project = pick_if_unspecified(project, &filter, "build").await?;
+            let filter = without_ignored(&project, filter, include_ignored, "build");
             let build_cmd = BuildCommand::new();
-            build_cmd.execute(project.as_deref()).await?;
+            run_reported("build", reporter, reporter_output, async |tasks| {
+                build_cmd
+                    .execute(
+                        project.as_deref(),
+                        &filter,
+                        &task,
+                        env.as_deref(),
+                        explain_plan,
+                        tasks,
+                    )
+                    .await
+            })
+            .await?;
         }
-        Commands::Dev { project } => {
+        Commands::Dev {
+            project,
+            remote,
+            https,
+            task,
+            storybook,
+            chaos,
+            mock_api,
+            fresh,
+            serial,
+            max_parallel,
+            crons,
+            fast_forward,
+            explain_plan,
+            include_ignored,
+            filter,
+        } => {
+            let (project, filter) = filter.select(project);
+            let project = pick_if_unspecified(project, &filter, "dev").await?;
+            let filter = without_ignored(&project, filter, include_ignored, "dev");
             let dev_cmd = DevCommand::new();
+            let task = if storybook {
+                "storybook".to_string()
+            } else {
+                task
+            };
+            let options = DevOptions {
+                remote,
+                https,
+                filter,
+                task,
+                chaos,
+                mock_api,
+                fresh,
+                max_parallel: if serial { Some(1) } else { max_parallel },
+                crons: fast_forward.or(crons.then_some(1)),
+                explain_plan,
+            };
             dev_cmd
-                .execute(project.as_deref())
+                .execute(project.as_deref(), &options)
                 .await
-                .map_err(|e| miette::miette!("Dev command failed: {}", e))?;
+                .map_err(|e| command_error("Dev", e))?;
         }
-        Commands::Deploy { project, env } => {
+        Commands::Deploy {
+            project,
+            env,
+            auto_env,
+            require_clean,
+            sbom,
+            audit,
+            require_migrations,
+            analyze_bundle,
+            watch,
+            task,
+            confirm,
+            keep_vars,
+            vars_file,
+            notify,
+            no_notify,
+            build_remote,
+            from_artifacts,
+            no_delete,
+            smoke,
+            explain_plan,
+            include_ignored,
+            filter,
+        } => {
+            let (project, filter) = filter.select(project);
+            let project = pick_if_unspecified(project, &filter, "deploy").await?;
+            let filter = without_ignored(&project, filter, include_ignored, "deploy");
             let deploy_cmd = DeployCommand::new();
-            deploy_cmd
-                .execute(project.as_deref(), env.as_deref())
+            let options = DeployOptions {
+                env,
+                auto_env,
+                filter,
+                require_clean,
+                sbom,
+                audit,
+                require_migrations,
+                analyze_bundle,
+                watch,
+                task,
+                confirm,
+                keep_vars,
+                notify: match (notify, no_notify) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                },
+                build_remote,
+                from_artifacts,
+                no_delete,
+                smoke,
+                explain_plan,
+                promoted_from: None,
+                vars: match &vars_file {
+                    Some(path) => read_vars_file(path).map_err(|e| miette::miette!("{}", e))?,
+                    None => Default::default(),
+                },
+            };
+            run_reported("deploy", reporter, reporter_output, async |tasks| {
+                deploy_cmd
+                    .execute(project.as_deref(), &options, tasks)
+                    .await
+            })
+            .await
+            .map_err(|e| command_error("Deploy", e))?;
+        }
+        Commands::Promote {
+            from,
+            to,
+            project,
+            artifacts,
+            confirm,
+        } => {
+            let promote_cmd = PromoteCommand::new();
+            let options = PromoteOptions { artifacts, confirm };
+            run_reported("promote", reporter, reporter_output, async |tasks| {
+                promote_cmd
+                    .execute(&from, &to, project.as_deref(), &options, tasks)
+                    .await
+            })
+            .await
+            .map_err(|e| command_error("Promote", e))?;
+        }
+        Commands::Release {
+            project,
+            bump,
+            dry_run,
+            deploy,
+            env,
+        } => {
+            let release_cmd = ReleaseCommand::new();
+            let options = ReleaseOptions {
+                bump,
+                dry_run,
+                deploy,
+                env,
+            };
+            run_reported("release", reporter, reporter_output, async |tasks| {
+                release_cmd.execute(&project, &options, tasks).await
+            })
+            .await
+            .map_err(|e| command_error("Release", e))?;
+        }
+        Commands::Publish {
+            project,
+            dry_run,
+            provenance,
+            skip_checks,
+        } => {
+            let publish_cmd = PublishCommand::new();
+            let options = PublishOptions {
+                dry_run,
+                provenance,
+                skip_checks,
+            };
+            run_reported("publish", reporter, reporter_output, async |tasks| {
+                publish_cmd.execute(&project, &options, tasks).await
+            })
+            .await
+            .map_err(|e| command_error("Publish", e))?;
+        }
+        Commands::Test {
+            project,
+            e2e,
+            against,
+            include_ignored,
+            filter,
+        } => {
+            let (project, filter) = filter.select(project);
+            let filter = without_ignored(&project, filter, include_ignored, "test");
+            let test_cmd = TestCommand::new();
+            if e2e {
+                test_cmd.execute_e2e(&against, &filter).await
+            } else {
+                run_reported("test", reporter, reporter_output, async |tasks| {
+                    test_cmd.execute(project.as_deref(), &filter, tasks).await
+                })
+                .await
+            }
+            .map_err(|e| command_error("Test", e))?;
+        }
+        Commands::Licenses {
+            format,
+            output,
+            include_dev,
+            filter,
+        } => {
+            let licenses_cmd = LicensesCommand::new();
+            licenses_cmd
+                .execute(&filter.to_filter(), format, output.as_deref(), include_dev)
+                .await
+                .map_err(|e| command_error("Licenses", e))?;
+        }
+        Commands::Bench {
+            project,
+            output,
+            filter,
+        } => {
+            let (project, filter) = filter.select(project);
+            let bench_cmd = BenchCommand::new();
+            bench_cmd
+                .execute(project.as_deref(), &filter, output.as_deref())
+                .await
+                .map_err(|e| command_error("Bench", e))?;
+        }
+        Commands::Loadtest {
+            project,
+            rps,
+            duration,
+            against,
+            url,
+            path,
+        } => {
+            let loadtest_cmd = LoadTestCommand::new();
+            let options = LoadTestOptions {
+                rps,
+                duration,
+                against,
+                url,
+                path,
+            };
+            loadtest_cmd
+                .execute(&project, &options)
+                .await
+                .map_err(|e| command_error("Loadtest", e))?;
+        }
+        Commands::Tag { action } => {
+            let tag_cmd = TagCommand::new();
+            match action {
+                TagAction::Add { project, tag } => tag_cmd.execute_add(&project, &tag).await,
+                TagAction::Remove { project, tag } => tag_cmd.execute_remove(&project, &tag).await,
+                TagAction::List { project } => tag_cmd.execute_list(project.as_deref()).await,
+            }
+            .map_err(|e| command_error("Tag", e))?;
+        }
+        Commands::Tasks { action } => {
+            let tasks_cmd = TasksCommand::new();
+            match action {
+                TasksAction::Sync { dry_run } => tasks_cmd.execute_sync(dry_run).await,
+            }
+            .map_err(|e| command_error("Tasks", e))?;
+        }
+        Commands::Types { project } => {
+            let types_cmd = TypesCommand::new();
+            types_cmd
+                .execute(project.as_deref())
+                .await
+                .map_err(|e| command_error("Types", e))?;
+        }
+        Commands::SyncNames { check } => {
+            let sync_names_cmd = SyncNamesCommand::new();
+            sync_names_cmd
+                .execute(check)
+                .await
+                .map_err(|e| command_error("Sync-names", e))?;
+        }
+        Commands::AuditBindings { project, fix } => {
+            let audit_cmd = AuditBindingsCommand::new();
+            audit_cmd
+                .execute(project.as_deref(), fix)
+                .await
+                .map_err(|e| command_error("Audit-bindings", e))?;
+        }
+        Commands::Stats { runs, json } => {
+            let stats_cmd = StatsCommand::new();
+            stats_cmd
+                .execute(runs, json)
+                .await
+                .map_err(|e| command_error("Stats", e))?;
+        }
+        Commands::History {
+            action: Some(HistoryAction::Stats { since, top, json }),
+            ..
+        } => {
+            let history_cmd = HistoryCommand::new();
+            history_cmd
+                .execute_stats(since.as_deref(), top, json)
+                .await
+                .map_err(|e| command_error("History", e))?;
+        }
+        Commands::History {
+            action: None,
+            command,
+            user,
+            since,
+            failed,
+            limit,
+            json,
+        } => {
+            let history_cmd = HistoryCommand::new();
+            let filter = HistoryFilter {
+                command,
+                user,
+                since,
+                failed,
+                limit,
+            };
+            history_cmd
+                .execute(&filter, json)
+                .await
+                .map_err(|e| command_error("History", e))?;
+        }
+        Commands::Grep {
+            pattern,
+            project,
+            kinds,
+            ignore_case,
+            fixed_strings,
+            json,
+            filter,
+        } => {
+            let (project, filter) = filter.select(project);
+            let grep_cmd = GrepCommand::new();
+            let options = GrepOptions {
+                filter,
+                kinds,
+                ignore_case,
+                fixed_strings,
+                json,
+            };
+            grep_cmd
+                .execute(&pattern, project.as_deref(), &options)
+                .await
+                .map_err(|e| command_error("Grep", e))?;
+        }
+        Commands::Why { project, task } => {
+            let why_cmd = WhyCommand::new();
+            why_cmd
+                .execute(&project, &task)
+                .await
+                .map_err(|e| command_error("Why", e))?;
+        }
+        Commands::Watch => {
+            let watch_cmd = WatchCommand::new();
+            run_reported("watch", reporter, reporter_output, async |tasks| {
+                watch_cmd.execute(tasks).await
+            })
+            .await
+            .map_err(|e| command_error("Watch", e))?;
+        }
+        Commands::Describe { project, write } => {
+            let describe_cmd = DescribeCommand::new();
+            describe_cmd
+                .execute(project.as_deref(), write)
+                .await
+                .map_err(|e| command_error("Describe", e))?;
+        }
+        Commands::Routes { action } => {
+            let routes_cmd = RoutesCommand::new();
+            match action {
+                RoutesAction::List { env, json } => {
+                    routes_cmd.execute_list(env.as_deref(), json).await
+                }
+            }
+            .map_err(|e| command_error("Routes", e))?;
+        }
+        Commands::Placement { action } => {
+            let placement_cmd = PlacementCommand::new();
+            match action {
+                PlacementAction::Set {
+                    project,
+                    mode,
+                    hints,
+                    clear_hints,
+                    dry_run,
+                    yes,
+                } => {
+                    placement_cmd
+                        .execute_set(&project, mode, &hints, &clear_hints, dry_run, yes)
+                        .await
+                }
+                PlacementAction::Status { json } => placement_cmd.execute_status(json).await,
+            }
+            .map_err(|e| command_error("Placement", e))?;
+        }
+        #[cfg(feature = "api")]
+        Commands::R2 { action } => {
+            let r2_cmd = R2Command::new();
+            match action {
+                R2Action::Sync {
+                    dir,
+                    bucket,
+                    prefix,
+                    no_delete,
+                } => {
+                    r2_cmd
+                        .execute_sync(&dir, &bucket, prefix.as_deref(), no_delete)
+                        .await
+                }
+            }
+            .map_err(|e| miette::miette!("R2 command failed: {}", e))?;
+        }
+        Commands::D1 { action } => {
+            let d1_cmd = D1Command::new();
+            match action {
+                D1Action::Branch {
+                    action: Some(D1BranchAction::Cleanup { project, name }),
+                    ..
+                } => {
+                    d1_cmd
+                        .execute_cleanup(project.as_deref(), name.as_deref())
+                        .await
+                }
+                D1Action::Branch {
+                    action: None,
+                    project,
+                    name,
+                    from,
+                    env,
+                    binding,
+                } => {
+                    d1_cmd
+                        .execute_branch(
+                            project.as_deref().unwrap_or_default(),
+                            name.as_deref().unwrap_or_default(),
+                            &from,
+                            env.as_deref(),
+                            binding.as_deref(),
+                        )
+                        .await
+                }
+            }
+            .map_err(|e| miette::miette!("D1 command failed: {}", e))?;
+        }
+        Commands::Do { action } => {
+            let do_cmd = DurableObjectsCommand::new();
+            match action {
+                DoAction::List {
+                    project,
+                    env,
+                    local,
+                    json,
+                } => {
+                    do_cmd
+                        .execute_list(project.as_deref(), env.as_deref(), local, json)
+                        .await
+                }
+                DoAction::Inspect {
+                    project,
+                    class,
+                    object,
+                    sql,
+                    write,
+                } => {
+                    do_cmd
+                        .execute_inspect(
+                            &project,
+                            class.as_deref(),
+                            object.as_deref(),
+                            sql.as_deref(),
+                            write,
+                        )
+                        .await
+                }
+            }
+            .map_err(|e| command_error("Do", e))?;
+        }
+        Commands::Kv { action } => {
+            let kv_cmd = KvCommand::new();
+            match action {
+                KvAction::Keys {
+                    project,
+                    binding,
+                    prefix,
+                    target,
+                    json,
+                } => {
+                    kv_cmd
+                        .execute_keys(
+                            &project,
+                            &binding,
+                            prefix.as_deref(),
+                            &target.to_target(),
+                            json,
+                        )
+                        .await
+                }
+                KvAction::Get {
+                    project,
+                    binding,
+                    key,
+                    target,
+                } => {
+                    kv_cmd
+                        .execute_get(&project, &binding, &key, &target.to_target())
+                        .await
+                }
+                KvAction::Put {
+                    project,
+                    binding,
+                    key,
+                    value,
+                    path,
+                    ttl,
+                    target,
+                } => {
+                    let value = match (&value, &path) {
+                        (_, Some(path)) => KvValue::File(path),
+                        (value, None) => KvValue::Text(value.as_deref().unwrap_or_default()),
+                    };
+                    kv_cmd
+                        .execute_put(&project, &binding, &key, value, ttl, &target.to_target())
+                        .await
+                }
+                KvAction::Export {
+                    project,
+                    binding,
+                    prefix,
+                    output,
+                    target,
+                } => {
+                    kv_cmd
+                        .execute_export(
+                            &project,
+                            &binding,
+                            prefix.as_deref(),
+                            output.as_deref(),
+                            &target.to_target(),
+                        )
+                        .await
+                }
+                KvAction::Import {
+                    project,
+                    binding,
+                    file,
+                    target,
+                } => {
+                    kv_cmd
+                        .execute_import(&project, &binding, &file, &target.to_target())
+                        .await
+                }
+            }
+            .map_err(|e| command_error("Kv", e))?;
+        }
+        Commands::Queues { action } => {
+            let queues_cmd = QueuesCommand::new();
+            match action {
+                QueuesAction::Send { queue, body, local } => {
+                    queues_cmd.execute_send(&queue, &body, local).await
+                }
+                QueuesAction::Peek { queue, json } => queues_cmd.execute_peek(&queue, json).await,
+            }
+            .map_err(|e| command_error("Queues", e))?;
+        }
+        Commands::Layout { action } => {
+            let layout_cmd = LayoutCommand::new();
+            match action {
+                LayoutAction::Show => layout_cmd.execute_show().await,
+                LayoutAction::Set { assignments } => layout_cmd.execute_set(&assignments).await,
+            }
+            .map_err(|e| command_error("Layout", e))?;
+        }
+        Commands::Workspace { action } => {
+            let workspace_cmd = WorkspaceCommand::new();
+            match action {
+                WorkspaceAction::Rename {
+                    new_name,
+                    prefix_workers,
+                } => {
+                    workspace_cmd
+                        .execute_rename(&new_name, prefix_workers)
+                        .await
+                }
+            }
+            .map_err(|e| command_error("Workspace", e))?;
+        }
+        #[cfg(feature = "api")]
+        Commands::Builds { action } => {
+            let builds_cmd = BuildsCommand::new();
+            match action {
+                BuildsAction::Setup {
+                    project,
+                    env,
+                    branch,
+                } => {
+                    builds_cmd
+                        .execute_setup(project.as_deref(), env.as_deref(), branch.as_deref())
+                        .await
+                }
+            }
+            .map_err(|e| command_error("Builds", e))?;
+        }
+        Commands::Bundle { action } => {
+            let bundle_cmd = BundleCommand::new();
+            match action {
+                BundleAction::Analyze {
+                    project,
+                    env,
+                    top,
+                    json,
+                } => {
+                    bundle_cmd
+                        .execute_analyze(project.as_deref(), env.as_deref(), top, json)
+                        .await
+                }
+            }
+            .map_err(|e| command_error("Bundle", e))?;
+        }
+        Commands::Migrate { action } => {
+            let migrate_cmd = MigrateCommand::new();
+            match action {
+                MigrateAction::PagesToWorker {
+                    project,
+                    routing_worker,
+                } => {
+                    migrate_cmd
+                        .execute_pages_to_worker(&project, routing_worker)
+                        .await
+                }
+                MigrateAction::C3 { path, name, force } => {
+                    migrate_cmd.execute_c3(&path, name.as_deref(), force).await
+                }
+                MigrateAction::Turbo {
+                    path,
+                    keep_layout,
+                    force,
+                } => {
+                    migrate_cmd
+                        .execute_monorepo(MonorepoTool::Turbo, &path, keep_layout, force)
+                        .await
+                }
+                MigrateAction::Nx {
+                    path,
+                    keep_layout,
+                    force,
+                } => {
+                    migrate_cmd
+                        .execute_monorepo(MonorepoTool::Nx, &path, keep_layout, force)
+                        .await
+                }
+                MigrateAction::Status {
+                    project,
+                    environments,
+                    json,
+                } => {
+                    migrate_cmd
+                        .execute_status(project.as_deref(), &environments, json)
+                        .await
+                }
+            }
+            .map_err(|e| command_error("Migrate", e))?;
+        }
+        Commands::Meta { action } => {
+            let meta_cmd = MetaCommand::new();
+            match action {
+                MetaAction::Build { workspaces, args } => {
+                    meta_cmd.execute_build(&workspaces, &args).await
+                }
+                MetaAction::Deploy {
+                    env,
+                    workspaces,
+                    args,
+                } => {
+                    meta_cmd
+                        .execute_deploy(env.as_deref(), &workspaces, &args)
+                        .await
+                }
+                MetaAction::Status { workspaces } => meta_cmd.execute_status(&workspaces).await,
+            }
+            .map_err(|e| command_error("Meta", e))?;
+        }
+        Commands::Daemon { action } => {
+            let daemon_cmd = DaemonCommand::new();
+            match action {
+                DaemonAction::Start { foreground } => daemon_cmd.execute_start(foreground).await,
+                DaemonAction::Stop => daemon_cmd.execute_stop().await,
+                DaemonAction::Status => daemon_cmd.execute_status().await,
+            }
+            .map_err(|e| command_error("Daemon", e))?;
+        }
+        Commands::Doctor { fix, network } => {
+            let doctor_cmd = DoctorCommand::new();
+            doctor_cmd
+                .execute(fix, network)
+                .await
+                .map_err(|e| command_error("Doctor", e))?;
+        }
+        Commands::Refactor { action } => {
+            let refactor_cmd = RefactorCommand::new();
+            match action {
+                RefactorAction::RenameBinding {
+                    old,
+                    new,
+                    project,
+                    dry_run,
+                    yes,
+                } => {
+                    refactor_cmd
+                        .execute_rename_binding(&old, &new, project.as_deref(), dry_run, yes)
+                        .await
+                }
+            }
+            .map_err(|e| command_error("Refactor", e))?;
+        }
+        Commands::Hooks { action } => {
+            let hooks_cmd = HooksCommand::new();
+            match action {
+                HooksAction::Install { manager, force } => {
+                    hooks_cmd.execute_install(manager, force).await
+                }
+                HooksAction::Uninstall => hooks_cmd.execute_uninstall().await,
+            }
+            .map_err(|e| command_error("Hooks", e))?;
+        }
+        Commands::Config { action } => {
+            let config_cmd = ConfigCommand::new();
+            match action {
+                ConfigAction::Lint { project, fix } => {
+                    config_cmd.execute_lint(project.as_deref(), fix).await
+                }
+                ConfigAction::Effective { project, json } => {
+                    config_cmd.execute_effective(&project, json).await
+                }
+                ConfigAction::Schema { output } => {
+                    config_cmd.execute_schema(output.as_deref()).await
+                }
+                ConfigAction::Migrate => config_cmd.execute_migrate().await,
+            }
+            .map_err(|e| command_error("Config", e))?;
+        }
+        Commands::Ci { action } => {
+            let ci_cmd = CiCommand::new();
+            match action {
+                CiAction::Generate {
+                    with_ai_review,
+                    force,
+                } => ci_cmd.execute_generate(with_ai_review, force).await,
+            }
+            .map_err(|e| command_error("Ci", e))?;
+        }
+        Commands::Devcontainer { action } => {
+            let devcontainer_cmd = DevcontainerCommand::new();
+            match action {
+                DevcontainerAction::Generate { force } => devcontainer_cmd.execute_generate(force),
+            }
+            .map_err(|e| command_error("Devcontainer", e))?;
+        }
+        Commands::Docs { action } => {
+            let docs_cmd = DocsCommand::new();
+            match action {
+                DocsAction::Serve { port } => {
+                    let commands = docs_site::command_docs(&mut Cli::command());
+                    docs_cmd.execute_serve(port, commands).await
+                }
+            }
+            .map_err(|e| command_error("Docs", e))?;
+        }
+        Commands::Nix { action } => {
+            let nix_cmd = NixCommand::new();
+            match action {
+                NixAction::Generate { force } => nix_cmd.execute_generate(force),
+            }
+            .map_err(|e| command_error("Nix", e))?;
+        }
+        Commands::Clean { auto, dry_run } => {
+            let clean_cmd = CleanCommand::new();
+            clean_cmd
+                .execute(auto, dry_run)
+                .await
+                .map_err(|e| command_error("Clean", e))?;
+        }
+        Commands::Env { action } => {
+            let env_cmd = EnvCommand::new();
+            match action {
+                EnvAction::Example { project } => env_cmd.execute_example(project.as_deref()).await,
+                EnvAction::Pull { project } => env_cmd.execute_pull(project.as_deref()).await,
+                EnvAction::Sync { project, env } => env_cmd.execute_sync(&project, &env).await,
+                EnvAction::Diff { project, from, to } => {
+                    env_cmd.execute_diff(&project, &from, &to).await
+                }
+            }
+            .map_err(|e| command_error("Env", e))?;
+        }
+        Commands::Secrets { action } => {
+            let secrets_cmd = SecretsCommand::new();
+            match action {
+                SecretsAction::Push { project, env } => {
+                    secrets_cmd.execute_push(&project, env.as_deref()).await
+                }
+            }
+            .map_err(|e| command_error("Secrets", e))?;
+        }
+        Commands::Repro { action } => {
+            let repro_cmd = ReproCommand::new();
+            match action {
+                ReproAction::Pack { output } => repro_cmd.execute_pack(output.as_deref()).await,
+                ReproAction::Unpack { archive, into } => {
+                    repro_cmd.execute_unpack(&archive, into.as_deref()).await
+                }
+            }
+            .map_err(|e| command_error("Repro", e))?;
+        }
+        Commands::Token { action } => {
+            let token_cmd = TokenCommand::new();
+            match action {
+                TokenAction::Scopes { json } => token_cmd.execute_scopes(json).await,
+                TokenAction::Whoami { refresh, json } => token_cmd.execute_whoami(refresh, json),
+                TokenAction::Login => token_cmd.execute_login(),
+            }
+            .map_err(|e| command_error("Token", e))?;
+        }
+        Commands::Flags { action } => {
+            let flags_cmd = FlagsCommand::new();
+            match action {
+                FlagsAction::Init => flags_cmd.execute_init(),
+                FlagsAction::Set {
+                    flag,
+                    value,
+                    env,
+                    local,
+                } => {
+                    flags_cmd
+                        .execute_set(&flag, &value, env.as_deref(), local)
+                        .await
+                }
+            }
+            .map_err(|e| command_error("Flags", e))?;
+        }
+        Commands::Seed {
+            project,
+            env,
+            remote,
+        } => {
+            let seed_cmd = SeedCommand::new();
+            seed_cmd
+                .execute(project.as_deref(), &SeedTarget { remote, env })
+                .await
+                .map_err(|e| command_error("Seed", e))?;
+        }
+        #[cfg(feature = "api")]
+        Commands::Access { action } => {
+            let access_cmd = AccessCommand::new();
+            match action {
+                AccessAction::Protect {
+                    project,
+                    policy,
+                    domain,
+                } => {
+                    access_cmd
+                        .execute_protect(&project, &policy, domain.as_deref())
+                        .await
+                }
+            }
+            .map_err(|e| command_error("Access", e))?;
+        }
+        #[cfg(feature = "api")]
+        Commands::Ai { action } => {
+            let ai_cmd = AiCommand::new();
+            match action {
+                AiAction::Gateway {
+                    action:
+                        AiGatewayAction::Create {
+                            name,
+                            project,
+                            cache_ttl,
+                            rate_limit,
+                            rate_limit_interval,
+                            no_logs,
+                        },
+                } => {
+                    let settings = AiGatewaySettings {
+                        cache_ttl,
+                        rate_limit: rate_limit.map(|limit| (limit, rate_limit_interval)),
+                        collect_logs: !no_logs,
+                    };
+                    ai_cmd
+                        .execute_gateway_create(&name, project.as_deref(), &settings)
+                        .await
+                }
+            }
+            .map_err(|e| command_error("Ai", e))?;
+        }
+        #[cfg(not(feature = "api"))]
+        Commands::R2 { .. }
+        | Commands::Builds { .. }
+        | Commands::Access { .. }
+        | Commands::Ai { .. } => {
+            return Err(miette::miette!("{}", utils::cloudflare::api_unavailable()));
+        }
+        Commands::Restore { snapshot } => {
+            let restore_cmd = RestoreCommand::new();
+            restore_cmd
+                .execute(snapshot.as_deref())
+                .await
+                .map_err(|e| command_error("Restore", e))?;
+        }
+        Commands::Upgrade { apply_recorded } => {
+            let upgrade_cmd = UpgradeCommand::new();
+            upgrade_cmd
+                .execute(apply_recorded)
                 .await
-                .map_err(|e| miette::miette!("Deploy command failed: {}", e))?;
+                .map_err(|e| command_error("Upgrade", e))?;
         }
+        Commands::VerifyMatrix {
+            node,
+            wrangler,
+            moon,
+            task,
+            keep,
+            json,
+        } => {
+            let matrix_cmd = VerifyMatrixCommand::new();
+            matrix_cmd
+                .execute(MatrixOptions {
+                    node,
+                    wrangler,
+                    moon,
+                    task,
+                    keep,
+                    json,
+                })
+                .map_err(|e| command_error("Verify matrix", e))?;
+        }
+        Commands::Preview { project, port } => {
+            let preview_cmd = PreviewCommand::new();
+            preview_cmd
+                .execute(&project, port)
+                .await
+                .map_err(|e| command_error("Preview", e))?;
+        }
+        Commands::Sbom { project, output } => {
+            let sbom_cmd = SbomCommand::new();
+            sbom_cmd
+                .execute(&project, output.as_deref())
+                .await
+                .map_err(|e| command_error("Sbom", e))?;
+        }
+        Commands::Examples { action } => {
+            let examples_cmd = ExamplesCommand::new();
+            match action {
+                ExamplesAction::List => examples_cmd.execute_list(),
+                ExamplesAction::Add { example } => examples_cmd.execute_add(&example),
+            }
+            .map_err(|e| command_error("Examples", e))?;
+        }
+        Commands::Ports { action } => {
+            let ports_cmd = PortsCommand::new();
+            match action.unwrap_or(PortsAction::List) {
+                PortsAction::List => ports_cmd.execute_list(),
+                PortsAction::Free { project } => ports_cmd.execute_free(&project),
+            }
+            .map_err(|e| command_error("Ports", e))?;
+        }
+        Commands::SelfManage { action } => match action {
+            SelfAction::Update { check, target } => {
+                let self_update_cmd = SelfUpdateCommand::new();
+                self_update_cmd
+                    .execute(check, target)
+                    .await
+                    .map_err(|e| miette::miette!("Self update failed: {}", e))?;
+            }
+        },
         Commands::Rename {
             current_name,
             new_name,
@@ -226,9 +3473,24 @@ async fn main() -> Result<()> {
             rename_cmd
                 .execute(&current_name, &new_name)
                 .await
-                .map_err(|e| miette::miette!("Rename command failed: {}", e))?;
+                .map_err(|e| command_error("Rename", e))?;
+        }
+        Commands::Archive { project } => {
+            ArchiveCommand::new()
+                .execute_archive(&project)
+                .await
+                .map_err(|e| command_error("Archive", e))?;
+        }
+        Commands::Unarchive { project } => {
+            ArchiveCommand::new()
+                .execute_unarchive(&project)
+                .await
+                .map_err(|e| command_error("Unarchive", e))?;
         }
     }
 
+    if changes_projects {
+        daemon::invalidate().await;
+    }
     Ok(())
 }
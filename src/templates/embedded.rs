@@ -4,15 +4,23 @@ pub const ASTRO_TEMPLATE: &str = include_str!("astro.template");
 pub const REACT_TEMPLATE: &str = include_str!("react.template");
 pub const DURABLE_OBJECT_TEMPLATE: &str = include_str!("durable-object.template");
 pub const CRATE_TEMPLATE: &str = include_str!("crate.template");
+pub const MAINTENANCE_BOT_TEMPLATE: &str = include_str!("maintenance-bot.template");
 pub const WORKSPACE_TEMPLATE: &str = include_str!("workspace.template");
-
-pub fn get_template(project_type: &str) -> Option<&'static str> {
-    match project_type {
-        "astro" => Some(ASTRO_TEMPLATE),
-        "react" => Some(REACT_TEMPLATE),
-        "durable-object" | "worker" => Some(DURABLE_OBJECT_TEMPLATE),
-        "crate" => Some(CRATE_TEMPLATE),
-        "workspace" => Some(WORKSPACE_TEMPLATE),
-        _ => None,
-    }
-}
+pub const TASKS_TEMPLATE: &str = include_str!("tasks.template");
+pub const STORYBOOK_TEMPLATE: &str = include_str!("storybook.template");
+pub const SMALL_CRATE_TEMPLATE: &str = include_str!("small-crate.template");
+pub const QUEUE_TEMPLATE: &str = include_str!("queue.template");
+pub const COMPONENT_CRATE_TEMPLATE: &str = include_str!("component-crate.template");
+pub const LIB_CRATE_TEMPLATE: &str = include_str!("lib-crate.template");
+pub const WASM_RELOAD_TEMPLATE: &str = include_str!("wasm-reload.template");
+pub const CI_TEMPLATE: &str = include_str!("ci.template");
+pub const AI_REVIEW_TEMPLATE: &str = include_str!("ai-review.template");
+pub const SMOKE_TESTS_TEMPLATE: &str = include_str!("smoke-tests.template");
+pub const E2E_TEMPLATE: &str = include_str!("e2e.template");
+pub const CONTAINER_TEMPLATE: &str = include_str!("container.template");
+pub const EXAMPLE_CHAT_TEMPLATE: &str = include_str!("example-chat.template");
+pub const EXAMPLE_IMAGE_RESIZER_TEMPLATE: &str = include_str!("example-image-resizer.template");
+pub const EXAMPLE_RAG_TEMPLATE: &str = include_str!("example-rag.template");
+pub const DEVCONTAINER_TEMPLATE: &str = include_str!("devcontainer.template");
+pub const NIX_TEMPLATE: &str = include_str!("nix.template");
+pub const FLAGS_WORKER_TEMPLATE: &str = include_str!("flags-worker.template");
@@ -1,11 +1,35 @@
-use anyhow::Result;
+use crate::errors::MoonflareError;
+use crate::templates::validate::GeneratedFormat;
+use anyhow::{Context, Result};
 use convert_case::{Case, Casing};
-use handlebars::Handlebars;
+use handlebars::{Handlebars, RenderError, RenderErrorReason};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
+static LENIENT: AtomicBool = AtomicBool::new(false);
+
+/// Render variables missing from a template's context as empty strings instead of failing
+/// (`--lenient`)
+pub fn set_lenient(enabled: bool) {
+    LENIENT.store(enabled, Ordering::Relaxed);
+}
+
+/// Templates can use these helpers beyond Handlebars' built-ins:
+///
+/// - `{{upper name}}`: SCREAMING_SNAKE_CASE, for bindings
+/// - `{{title name}}`: PascalCase, for classes
+/// - `{{snake name}}`: snake_case, for Rust identifiers
+/// - `{{kebab name}}`: kebab-case, for package and Worker names
+/// - `{{camel name}}`: camelCase, for JavaScript identifiers
+/// - `{{env name}}`: SCREAMING_SNAKE_CASE with anything else replaced by `_`, and a leading
+///   `_` before a digit, so it's always a valid environment variable name
+/// - `{{year}}`: the current year
+/// - `{{compat_date}}`: today's date (UTC) as a Workers `compatibility_date`
+///
+/// `year` and `compat_date` are the only output that depends on when a template renders.
 pub struct TemplateEngine {
     handlebars: Handlebars<'static>,
 }
@@ -13,42 +37,24 @@ pub struct TemplateEngine {
 impl TemplateEngine {
     pub fn new() -> Self {
         let mut handlebars = Handlebars::new();
+        // A variable missing from the context would otherwise render as an empty string,
+        // leaving configs with empty names and paths
+        handlebars.set_strict_mode(!LENIENT.load(Ordering::Relaxed));
 
-        // Register helper for uppercase conversion (SCREAMING_SNAKE_CASE)
-        handlebars.register_helper(
-            "upper",
-            Box::new(
-                |h: &handlebars::Helper,
-                 _: &handlebars::Handlebars,
-                 _: &handlebars::Context,
-                 _: &mut handlebars::RenderContext,
-                 out: &mut dyn handlebars::Output|
-                 -> handlebars::HelperResult {
-                    let param = h.param(0).and_then(|v| v.value().as_str()).unwrap_or("");
-                    let upper_case = param.to_case(Case::ScreamingSnake);
-                    out.write(&upper_case)?;
-                    Ok(())
-                },
-            ),
-        );
-
-        // Register helper for title case conversion (PascalCase)
-        handlebars.register_helper(
-            "title",
-            Box::new(
-                |h: &handlebars::Helper,
-                 _: &handlebars::Handlebars,
-                 _: &handlebars::Context,
-                 _: &mut handlebars::RenderContext,
-                 out: &mut dyn handlebars::Output|
-                 -> handlebars::HelperResult {
-                    let param = h.param(0).and_then(|v| v.value().as_str()).unwrap_or("");
-                    let pascal_case = param.to_case(Case::Pascal);
-                    out.write(&pascal_case)?;
-                    Ok(())
-                },
-            ),
-        );
+        register_text_helper(&mut handlebars, "upper", |s| {
+            s.to_case(Case::ScreamingSnake)
+        });
+        register_text_helper(&mut handlebars, "title", |s| s.to_case(Case::Pascal));
+        register_text_helper(&mut handlebars, "snake", |s| s.to_case(Case::Snake));
+        register_text_helper(&mut handlebars, "kebab", |s| s.to_case(Case::Kebab));
+        register_text_helper(&mut handlebars, "camel", |s| s.to_case(Case::Camel));
+        register_text_helper(&mut handlebars, "env", env_var_name);
+        register_value_helper(&mut handlebars, "year", || {
+            chrono::Utc::now().format("%Y").to_string()
+        });
+        register_value_helper(&mut handlebars, "compat_date", || {
+            chrono::Utc::now().format("%Y-%m-%d").to_string()
+        });
 
         Self { handlebars }
     }
@@ -61,52 +67,236 @@ impl TemplateEngine {
         Ok(self.handlebars.render_template(template, context)?)
     }
 
+    /// Paths of the files a `FILE:`-sectioned template writes, relative to its output directory
+    pub fn template_file_paths(template_content: &str) -> Vec<String> {
+        template_content
+            .lines()
+            .filter_map(|line| line.strip_prefix("FILE:"))
+            .map(|path| path.trim().to_string())
+            .collect()
+    }
+
+    /// The unrendered section of a `FILE:`-sectioned template that writes `path`
+    pub fn template_section(template_content: &str, path: &str) -> Option<String> {
+        let mut lines = template_content
+            .lines()
+            .skip_while(|line| line.strip_prefix("FILE:").map(str::trim) != Some(path));
+        lines.next()?;
+        let mut section = String::new();
+        for line in lines.take_while(|line| !line.starts_with("FILE:")) {
+            section.push_str(line);
+            section.push('\n');
+        }
+        Some(section)
+    }
+
+    /// Render a `FILE:`-sectioned template without touching the filesystem.
+    ///
+    /// Output is deterministic: files are sorted by path, a path listed twice keeps its
+    /// last section, and nothing time- or host-dependent is added beyond the context and
+    /// the `year` and `compat_date` helpers. Sections render in parallel, but when several
+    /// fail the error is the one for the earliest section in the template.
+    pub fn render_template_to_memory(
+        &self,
+        template_content: &str,
+        context: &HashMap<String, Value>,
+    ) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+        // Each file's path, the template line its section starts on, and its section
+        let mut sections: Vec<(PathBuf, usize, String)> = Vec::new();
+        for (index, line) in template_content.lines().enumerate() {
+            if let Some(path) = line.strip_prefix("FILE:") {
+                sections.push((PathBuf::from(path.trim()), index + 1, String::new()));
+            } else if let Some((_, _, content)) = sections.last_mut() {
+                content.push_str(line);
+                content.push('\n');
+            }
+        }
+
+        let rendered = parallel_map(&sections, |(file_path, start, content)| {
+            self.render_file(file_path, *start, content, context)
+        });
+        let mut files = BTreeMap::new();
+        for ((file_path, _, _), rendered_content) in sections.into_iter().zip(rendered) {
+            files.insert(file_path, rendered_content?.into_bytes());
+        }
+
+        Ok(files.into_iter().collect())
+    }
+
+    /// Render one `FILE:` section, naming the file and variable when one is missing, and
+    /// check that a generated config parses
+    fn render_file(
+        &self,
+        file_path: &Path,
+        start: usize,
+        content: &str,
+        context: &HashMap<String, Value>,
+    ) -> Result<String> {
+        let file = file_path.to_string_lossy();
+        let rendered = self
+            .handlebars
+            .render_template(content, context)
+            .map_err(|e| match missing_variable(&e) {
+                Some(variable) => MoonflareError::missing_template_variable(&file, variable).into(),
+                None => anyhow::Error::from(e),
+            })?;
+
+        if let Some(format) = GeneratedFormat::of(file_path)
+            && let Some(reason) = format.check(&rendered)
+        {
+            return Err(MoonflareError::invalid_generated_file(
+                &file,
+                format.name(),
+                &reason,
+                start,
+            )
+            .into());
+        }
+        Ok(rendered)
+    }
+
+    /// Render a `FILE:`-sectioned template into `output_dir`. Nothing is written until every
+    /// section has rendered, so a template that fails to render leaves no partial project.
     pub fn process_template_files(
         &self,
         template_content: &str,
         output_dir: &Path,
         context: &HashMap<String, Value>,
     ) -> Result<()> {
-        let lines: Vec<&str> = template_content.lines().collect();
-        let mut current_file: Option<String> = None;
-        let mut current_content = String::new();
-
-        for line in lines {
-            if line.starts_with("FILE:") {
-                // Save previous file if exists
-                if let Some(ref file_path) = current_file {
-                    let rendered_content = self.render_template(&current_content, context)?;
-                    let full_path = output_dir.join(file_path);
-
-                    // Create parent directories
-                    if let Some(parent) = full_path.parent() {
-                        fs::create_dir_all(parent)?;
-                    }
+        let files: Vec<(PathBuf, Vec<u8>)> = self
+            .render_template_to_memory(template_content, context)?
+            .into_iter()
+            .map(|(file_path, content)| (output_dir.join(file_path), content))
+            .collect();
+        write_files(&files)
+    }
+}
 
-                    fs::write(full_path, rendered_content)?;
-                    current_content.clear();
-                }
+/// Write rendered files, in parallel for large templates. Parent directories are created
+/// first so workers never race to create the same one, and when writes fail the error names
+/// the first failing file in `files`' order.
+pub fn write_files(files: &[(PathBuf, Vec<u8>)]) -> Result<()> {
+    let parents: BTreeSet<&Path> = files
+        .iter()
+        .filter_map(|(full_path, _)| full_path.parent())
+        .collect();
+    for parent in parents {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
 
-                // Start new file
-                current_file = Some(line.strip_prefix("FILE:").unwrap().trim().to_string());
-            } else {
-                current_content.push_str(line);
-                current_content.push('\n');
-            }
-        }
+    parallel_map(files, |(full_path, content)| {
+        fs::write(full_path, content)
+            .with_context(|| format!("Failed to write {}", full_path.display()))
+    })
+    .into_iter()
+    .collect()
+}
 
-        // Save last file
-        if let Some(ref file_path) = current_file {
-            let rendered_content = self.render_template(&current_content, context)?;
-            let full_path = output_dir.join(file_path);
+/// Most threads a template renders or writes its files with; past this, large templates are
+/// bound by the filesystem rather than by rendering
+const MAX_WORKERS: usize = 8;
 
-            if let Some(parent) = full_path.parent() {
-                fs::create_dir_all(parent)?;
-            }
+/// Templates with fewer files than this render on the calling thread, since starting
+/// threads costs more than rendering a handful of files
+const PARALLEL_THRESHOLD: usize = 16;
 
-            fs::write(full_path, rendered_content)?;
-        }
+/// `f` of each item on a bounded pool of scoped threads, returned in the items' order
+fn parallel_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_WORKERS)
+        .min(items.len());
+    if workers <= 1 || items.len() < PARALLEL_THRESHOLD {
+        return items.iter().map(f).collect();
+    }
 
-        Ok(())
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break done;
+                        };
+                        done.push((index, f(item)));
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Register a helper writing `transform` of its first parameter
+fn register_text_helper(handlebars: &mut Handlebars, name: &str, transform: fn(&str) -> String) {
+    handlebars.register_helper(
+        name,
+        Box::new(
+            move |h: &handlebars::Helper,
+                  _: &handlebars::Handlebars,
+                  _: &handlebars::Context,
+                  _: &mut handlebars::RenderContext,
+                  out: &mut dyn handlebars::Output|
+                  -> handlebars::HelperResult {
+                let param = h.param(0).and_then(|v| v.value().as_str()).unwrap_or("");
+                out.write(&transform(param))?;
+                Ok(())
+            },
+        ),
+    );
+}
+
+/// Register a helper taking no parameters that writes `value()`
+fn register_value_helper(handlebars: &mut Handlebars, name: &str, value: fn() -> String) {
+    handlebars.register_helper(
+        name,
+        Box::new(
+            move |_: &handlebars::Helper,
+                  _: &handlebars::Handlebars,
+                  _: &handlebars::Context,
+                  _: &mut handlebars::RenderContext,
+                  out: &mut dyn handlebars::Output|
+                  -> handlebars::HelperResult {
+                out.write(&value())?;
+                Ok(())
+            },
+        ),
+    );
+}
+
+/// `name` as an environment variable name: SCREAMING_SNAKE_CASE of ASCII letters, digits
+/// and underscores that doesn't start with a digit
+fn env_var_name(name: &str) -> String {
+    let name: String = name
+        .to_case(Case::ScreamingSnake)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    match name.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => name,
+        _ => format!("_{}", name),
+    }
+}
+
+/// The variable a strict-mode render failed on
+fn missing_variable(error: &RenderError) -> Option<&str> {
+    match error.reason() {
+        RenderErrorReason::MissingVariable(variable) => {
+            Some(variable.as_deref().unwrap_or("<unknown>"))
+        }
+        _ => None,
     }
 }
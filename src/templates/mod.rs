@@ -1,2 +1,4 @@
 pub mod embedded;
 pub mod engine;
+pub mod registry;
+pub mod validate;
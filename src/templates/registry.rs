@@ -0,0 +1,295 @@
+use crate::templates::embedded;
+use crate::utils::config::LayoutConfig;
+use crate::utils::containers::CONTAINERS_DIR;
+use crate::utils::e2e::E2E_PROJECT;
+use crate::utils::sbom::sha256_hex;
+use crate::utils::smoke::{SMOKE_PROJECT, SMOKE_TESTS_DIR};
+use serde::Serialize;
+
+/// Version of the `moonflare add --list-types --json` output; bumped when a field changes
+/// meaning or is removed, not when one is added
+pub const LIST_TYPES_SCHEMA: u32 = 1;
+
+/// Where projects of a type are created
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeDirectory {
+    /// `[layout] sites`
+    Sites,
+    /// `[layout] apps`
+    Apps,
+    /// `[layout] workers`
+    Workers,
+    /// `[layout] crates`
+    Crates,
+    /// A fixed directory outside the layout
+    Fixed(&'static str),
+}
+
+impl TypeDirectory {
+    pub fn resolve(self, layout: &LayoutConfig) -> String {
+        match self {
+            TypeDirectory::Sites => layout.sites.clone(),
+            TypeDirectory::Apps => layout.apps.clone(),
+            TypeDirectory::Workers => layout.workers.clone(),
+            TypeDirectory::Crates => layout.crates.clone(),
+            TypeDirectory::Fixed(dir) => dir.to_string(),
+        }
+    }
+}
+
+/// An optional feature scaffolded with `--with <name>`
+#[derive(Debug, Serialize)]
+pub struct Feature {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Error for asking for the feature on a type without it
+    #[serde(skip)]
+    pub unavailable: &'static str,
+}
+
+/// A type-specific `moonflare add` flag
+#[derive(Debug, Serialize)]
+pub struct Flag {
+    pub name: &'static str,
+    /// Placeholder of the flag's value, for flags that take one
+    pub value: Option<&'static str>,
+    pub description: &'static str,
+    /// Whether `moonflare add` fails without it
+    pub required: bool,
+}
+
+/// A project type `moonflare add` can create
+#[derive(Debug, Serialize)]
+pub struct ProjectKind {
+    pub name: &'static str,
+    /// Other names accepted for the type
+    pub aliases: &'static [&'static str],
+    pub description: &'static str,
+    /// Plural used in messages, e.g. `React apps`
+    #[serde(skip)]
+    pub label: &'static str,
+    #[serde(skip)]
+    pub template: &'static str,
+    #[serde(skip)]
+    pub directory: TypeDirectory,
+    pub features: &'static [Feature],
+    pub flags: &'static [Flag],
+    /// Name used when none is given; the name is required when unset
+    pub default_name: Option<&'static str>,
+    /// Whether the project can be nested in a group, as `shop/frontend`
+    pub groupable: bool,
+    /// Whether the project is TypeScript and depends on the workspace's WASM crates
+    pub typescript: bool,
+}
+
+impl ProjectKind {
+    /// Short hash of the embedded template, changing whenever the generated files do
+    pub fn template_version(&self) -> String {
+        sha256_hex(self.template.as_bytes())[..12].to_string()
+    }
+
+    pub fn has_feature(&self, name: &str) -> bool {
+        self.features.iter().any(|feature| feature.name == name)
+    }
+
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag.name == name)
+    }
+}
+
+const STORYBOOK: Feature = Feature {
+    name: "storybook",
+    description: "Storybook with a static build deployed as an assets-only Worker",
+    unavailable: "Storybook is only available for React apps",
+};
+
+const HASHED_ASSETS: Feature = Feature {
+    name: "hashed-assets",
+    description: "Content-hashed asset filenames served with long-lived cache headers",
+    unavailable: "Hashed assets are only available for React and Astro apps",
+};
+
+const DEBUG_ROUTE: Feature = Feature {
+    name: "debug-route",
+    description: "/__debug/storage route dumping the object's storage, answered only when DO_DEBUG is \"true\"",
+    unavailable: "The debug route is only available for Durable Object Workers",
+};
+
+const QUEUE: Feature = Feature {
+    name: "queue",
+    description: "Queue producer and consumer, with /__queues routes and a test-producer script for local testing",
+    unavailable: "Queues are only available for Durable Object Workers",
+};
+
+pub const PROJECT_KINDS: &[ProjectKind] = &[
+    ProjectKind {
+        name: "astro",
+        aliases: &[],
+        description: "Astro static site",
+        label: "Astro sites",
+        template: embedded::ASTRO_TEMPLATE,
+        directory: TypeDirectory::Sites,
+        features: &[HASHED_ASSETS],
+        flags: &[],
+        default_name: None,
+        groupable: true,
+        typescript: true,
+    },
+    ProjectKind {
+        name: "react",
+        aliases: &[],
+        description: "React application with Vite",
+        label: "React apps",
+        template: embedded::REACT_TEMPLATE,
+        directory: TypeDirectory::Apps,
+        features: &[STORYBOOK, HASHED_ASSETS],
+        flags: &[],
+        default_name: None,
+        groupable: true,
+        typescript: true,
+    },
+    ProjectKind {
+        name: "durable-object",
+        aliases: &["worker"],
+        description: "Cloudflare Worker with Durable Objects",
+        label: "Workers",
+        template: embedded::DURABLE_OBJECT_TEMPLATE,
+        directory: TypeDirectory::Workers,
+        features: &[DEBUG_ROUTE, QUEUE],
+        flags: &[],
+        default_name: None,
+        groupable: true,
+        typescript: true,
+    },
+    ProjectKind {
+        name: "crate",
+        aliases: &[],
+        description: "Rust library compiled to WASM",
+        label: "crates",
+        template: embedded::CRATE_TEMPLATE,
+        directory: TypeDirectory::Crates,
+        features: &[],
+        flags: &[
+            Flag {
+                name: "--small",
+                value: None,
+                description: "no_std crate optimized for WASM size",
+                required: false,
+            },
+            Flag {
+                name: "--component-model",
+                value: None,
+                description: "WASM component with a WIT interface and TypeScript bindings",
+                required: false,
+            },
+            Flag {
+                name: "--lib-only",
+                value: None,
+                description: "Rust library shared with the other crates, not compiled to WASM",
+                required: false,
+            },
+        ],
+        default_name: None,
+        groupable: false,
+        typescript: false,
+    },
+    ProjectKind {
+        name: "maintenance-bot",
+        aliases: &[],
+        description: "Scheduled Worker that alerts on deploy drift and failed crons",
+        label: "maintenance bots",
+        template: embedded::MAINTENANCE_BOT_TEMPLATE,
+        directory: TypeDirectory::Workers,
+        features: &[],
+        flags: &[],
+        default_name: None,
+        groupable: true,
+        typescript: true,
+    },
+    ProjectKind {
+        name: "smoke-tests",
+        aliases: &[],
+        description: "HTTP checks run against deployed projects by 'moonflare deploy --smoke'",
+        label: "smoke tests",
+        template: embedded::SMOKE_TESTS_TEMPLATE,
+        directory: TypeDirectory::Fixed(SMOKE_TESTS_DIR),
+        features: &[],
+        flags: &[],
+        default_name: Some(SMOKE_PROJECT),
+        groupable: false,
+        typescript: false,
+    },
+    ProjectKind {
+        name: "e2e",
+        aliases: &[],
+        description: "Playwright tests run against dev servers or deployments by 'moonflare test --e2e'",
+        label: "end-to-end tests",
+        template: embedded::E2E_TEMPLATE,
+        directory: TypeDirectory::Fixed(SMOKE_TESTS_DIR),
+        features: &[],
+        flags: &[],
+        default_name: Some(E2E_PROJECT),
+        groupable: false,
+        typescript: false,
+    },
+    ProjectKind {
+        name: "container",
+        aliases: &[],
+        description: "Docker image that a Worker runs through Cloudflare Containers",
+        label: "containers",
+        template: embedded::CONTAINER_TEMPLATE,
+        directory: TypeDirectory::Fixed(CONTAINERS_DIR),
+        features: &[],
+        flags: &[Flag {
+            name: "--attach",
+            value: Some("WORKER"),
+            description: "Worker whose Durable Object runs the container",
+            required: true,
+        }],
+        default_name: None,
+        groupable: false,
+        typescript: false,
+    },
+];
+
+/// The project type named `name`, or one of its aliases
+pub fn find(name: &str) -> Option<&'static ProjectKind> {
+    PROJECT_KINDS
+        .iter()
+        .find(|kind| kind.name == name || kind.aliases.contains(&name))
+}
+
+/// `Unknown project type 'x'. Available types: astro, react, ...`
+pub fn unknown_type(name: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Unknown project type '{}'. Available types: {}",
+        name,
+        PROJECT_KINDS
+            .iter()
+            .map(|kind| kind.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// The `--with` feature named `name`, from whichever type offers it
+pub fn find_feature(name: &str) -> Option<&'static Feature> {
+    PROJECT_KINDS
+        .iter()
+        .flat_map(|kind| kind.features)
+        .find(|feature| feature.name == name)
+}
+
+/// Types that have a flag, joined for messages, e.g. `crates` or `Workers and containers`
+pub fn labels_with(has: impl Fn(&ProjectKind) -> bool) -> String {
+    let labels: Vec<&str> = PROJECT_KINDS
+        .iter()
+        .filter(|kind| has(kind))
+        .map(|kind| kind.label)
+        .collect();
+    match labels.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,53 @@
+use crate::utils::wrangler::strip_jsonc;
+use std::path::Path;
+
+/// Formats of the generated files moonflare parses right after rendering, so a template bug
+/// fails `add` or `init` instead of the project's first build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratedFormat {
+    Toml,
+    Json,
+    Jsonc,
+    Yaml,
+}
+
+impl GeneratedFormat {
+    /// The format a generated file is checked as, if it's one moonflare checks
+    pub fn of(path: &Path) -> Option<Self> {
+        match path.file_name()?.to_str()? {
+            "wrangler.toml" | "Cargo.toml" | "moonflare.toml" => Some(Self::Toml),
+            "package.json" | "wrangler.json" => Some(Self::Json),
+            "wrangler.jsonc" | "wrangler.storybook.jsonc" => Some(Self::Jsonc),
+            "moon.yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Toml => "TOML",
+            Self::Json => "JSON",
+            Self::Jsonc => "JSONC",
+            Self::Yaml => "YAML",
+        }
+    }
+
+    /// Why `content` doesn't parse, if it doesn't
+    pub fn check(self, content: &str) -> Option<String> {
+        let error = match self {
+            Self::Toml => toml::from_str::<toml::Value>(content)
+                .err()
+                .map(|e| e.to_string()),
+            Self::Json => serde_json::from_str::<serde_json::Value>(content)
+                .err()
+                .map(|e| e.to_string()),
+            Self::Jsonc => serde_json::from_str::<serde_json::Value>(&strip_jsonc(content))
+                .err()
+                .map(|e| e.to_string()),
+            Self::Yaml => serde_yaml::from_str::<serde_yaml::Value>(content)
+                .err()
+                .map(|e| e.to_string()),
+        };
+        error.map(|e| e.trim().to_string())
+    }
+}
@@ -1,32 +1,69 @@
+use colored::Colorize;
 use iocraft::prelude::*;
+pub use starbase_console::ConsoleError;
 use starbase_console::ui::*;
-use starbase_console::{Console, ConsoleError, EmptyReporter};
+use starbase_console::{Console, EmptyReporter};
+use std::sync::OnceLock;
+
+use crate::templates::registry::PROJECT_KINDS;
+use crate::ui_shared::output_profile;
+pub use crate::ui_shared::{OutputProfile, set_output_profile};
 
 pub struct MoonflareUI {
-    console: Console<EmptyReporter>,
+    /// Created on first render, so commands that print nothing don't pay for it
+    console: OnceLock<Console<EmptyReporter>>,
+    profile: OutputProfile,
+}
+
+/// Content for a command help page rendered by `MoonflareUI::render_help_page`
+pub struct HelpPage<'a> {
+    pub command: &'a str,
+    pub description: &'a str,
+    pub usage: &'a str,
+    pub arguments: Vec<(&'a str, &'a str)>,
+    pub options: Vec<(&'a str, &'a str)>,
+    pub examples: Vec<&'a str>,
+    pub notes: Option<(&'a str, Vec<&'a str>)>,
 }
 
 impl MoonflareUI {
     pub fn new() -> Self {
         Self {
-            console: Console::new(false),
+            console: OnceLock::new(),
+            profile: output_profile(),
         }
     }
 
+    fn console(&self) -> &Console<EmptyReporter> {
+        self.console.get_or_init(|| Console::new(false))
+    }
+
     pub fn render_header(
         &self,
         title: &str,
         description: Option<&str>,
     ) -> Result<(), ConsoleError> {
+        match self.profile {
+            OutputProfile::Full => {}
+            OutputProfile::Compact => {
+                match description {
+                    Some(desc) => println!("{} {}", title.cyan().bold(), desc.dimmed()),
+                    None => println!("{}", title.cyan().bold()),
+                }
+                return Ok(());
+            }
+            OutputProfile::Minimal => return Ok(()),
+        }
+
         match description {
-            Some(desc) => self.console.render(element! {
+            Some(desc) => self.console().render(element! {
                 Container {
                     Notice(variant: Variant::Info, title: title.to_owned()) {
                         Text(content: desc)
                     }
                 }
             }),
-            None => self.console.render(element! {
+            None => self.console().render(element! {
                 Container {
                     Notice(variant: Variant::Info, title: title.to_owned())
                 }
@@ -35,7 +72,12 @@ impl MoonflareUI {
     }
 
     pub fn render_success(&self, message: &str) -> Result<(), ConsoleError> {
-        self.console.render(element! {
+        if self.profile != OutputProfile::Full {
+            println!("{} {}", "✓".green(), message);
+            return Ok(());
+        }
+
+        self.console().render(element! {
             Notice(variant: Variant::Success, no_title: true) {
                 Text(content: format!("✓ {}", message))
             }
@@ -43,44 +85,56 @@ impl MoonflareUI {
     }
 
     pub fn render_section_start(&self, title: &str) -> Result<(), ConsoleError> {
-        self.console.render(element! {
+        match self.profile {
+            OutputProfile::Full => {}
+            OutputProfile::Compact => {
+                println!("{}", title.bold());
+                return Ok(());
+            }
+            OutputProfile::Minimal => return Ok(()),
+        }
+
+        self.console().render(element! {
             Section(title: title.to_owned())
         })
     }
 
     pub fn render_project_types(&self) -> Result<(), ConsoleError> {
-        self.console.render(element! {
+        let names: Vec<&str> = PROJECT_KINDS.iter().map(|kind| kind.name).collect();
+        if self.compact_line("Available project types", &names) {
+            return Ok(());
+        }
+
+        self.console().render(element! {
             Section(title: "Available project types") {
                 List {
-                    ListItem {
-                        Entry(name: "astro") {
-                            Text(content: "Astro static site")
-                        }
-                    }
-                    ListItem {
-                        Entry(name: "react") {
-                            Text(content: "React application")
-                        }
-                    }
-                    ListItem {
-                        Entry(name: "durable-object") {
-                            Text(content: "Cloudflare Durable Object")
-                        }
-                    }
-                    ListItem {
-                        Entry(name: "crate") {
-                            Text(content: "Rust WASM library")
+                    #(PROJECT_KINDS.iter().map(|kind| {
+                        element! {
+                            ListItem {
+                                Entry(name: kind.name) {
+                                    Text(content: kind.description)
+                                }
+                            }
                         }
-                    }
+                    }))
                 }
             }
         })
     }
 
     pub fn render_next_steps(&self, steps: Vec<&str>) -> Result<(), ConsoleError> {
+        let title = if steps.len() == 1 {
+            "Next step"
+        } else {
+            "Next steps"
+        };
+        if self.compact_line(title, &steps) {
+            return Ok(());
+        }
+
         if steps.len() == 1 {
             // Single step - no numbering needed
-            self.console.render(element! {
+            self.console().render(element! {
                 Section(title: "Next step") {
                     List {
                         ListItem {
@@ -91,7 +145,7 @@ impl MoonflareUI {
             })
         } else {
             // Multiple steps - show as numbered sequence
-            self.console.render(element! {
+            self.console().render(element! {
                 Section(title: "Next steps") {
                     List {
                         #(steps.into_iter().enumerate().map(|(i, step)| {
@@ -116,8 +170,11 @@ impl MoonflareUI {
         } else {
             "Next steps (choose one)"
         };
+        if self.compact_line(title, &alternatives) {
+            return Ok(());
+        }
 
-        self.console.render(element! {
+        self.console().render(element! {
             Section(title: title) {
                 List {
                     #(alternatives.into_iter().map(|step| {
@@ -132,33 +189,49 @@ impl MoonflareUI {
         })
     }
 
-    pub fn render_next_steps_for_project(
+    /// Render steps with a ✓ for the ones already done and a ○ for the rest, numbered so
+    /// they can be picked by number
+    pub fn render_checklist(
         &self,
-        project_name: &str,
-        project_type: &str,
+        title: &str,
+        steps: Vec<(bool, &str, &str)>,
     ) -> Result<(), ConsoleError> {
-        match project_type {
-            "astro" | "react" | "durable-object" => {
-                let dev_cmd = format!("moonflare dev {}", project_name);
-                let build_cmd = format!("moonflare build {}", project_name);
-                let deploy_cmd = format!("moonflare deploy {}", project_name);
-                let alternatives = vec![dev_cmd.as_str(), build_cmd.as_str(), deploy_cmd.as_str()];
-                self.render_next_alternatives(alternatives)
-            }
-            "crate" => {
-                let build_cmd = format!("moonflare build {}", project_name);
-                let alternatives = vec![
-                    build_cmd.as_str(),
-                    "moonflare build  # Build all projects to generate WASM",
-                ];
-                self.render_next_alternatives(alternatives)
-            }
-            _ => Ok(()),
+        let mark = |done: bool| if done { "✓" } else { "○" };
+        let items: Vec<String> = steps
+            .iter()
+            .map(|(done, step, _)| format!("{} {}", mark(*done), step))
+            .collect();
+        let item_refs: Vec<&str> = items.iter().map(String::as_str).collect();
+        if self.compact_line(title, &item_refs) {
+            return Ok(());
         }
+
+        self.console().render(element! {
+            Section(title: title) {
+                List {
+                    #(steps.into_iter().enumerate().map(|(i, (done, step, command))| {
+                        element! {
+                            ListItem {
+                                Entry(name: format!("{} {}. {}", mark(done), i + 1, step)) {
+                                    StyledText(content: command, style: Style::Shell)
+                                }
+                            }
+                        }
+                    }))
+                }
+            }
+        })
     }
 
     pub fn render_workspace_structure(&self) -> Result<(), ConsoleError> {
-        self.console.render(element! {
+        if self.compact_line(
+            "Workspace structure",
+            &["apps/", "sites/", "workers/", "crates/", ".moon/"],
+        ) {
+            return Ok(());
+        }
+
+        self.console().render(element! {
             Section(title: "Workspace structure") {
                 List {
                     ListItem { Text(content: "apps/         → React applications") }
@@ -177,8 +250,16 @@ impl MoonflareUI {
         message: &str,
         suggestions: Vec<&str>,
     ) -> Result<(), ConsoleError> {
+        if self.profile != OutputProfile::Full {
+            eprintln!("{} {}: {}", "✗".red(), title.red().bold(), message);
+            if self.profile == OutputProfile::Compact && !suggestions.is_empty() {
+                eprintln!("  {} {}", "Try:".dimmed(), suggestions.join(" · "));
+            }
+            return Ok(());
+        }
+
         // Render error messages to stderr
-        self.console.stderr().render(
+        self.console().stderr().render(
             element! {
                 Container {
                     Notice(variant: Variant::Failure, title: title.to_owned()) {
@@ -198,12 +279,25 @@ impl MoonflareUI {
                     }
                 }
             },
-            self.console.theme(),
+            self.console().theme(),
         )
     }
 
+    /// Print a list as `Title: a · b · c` under the compact profile, or nothing under the
+    /// minimal one. Returns false when the full layout should be rendered instead.
+    fn compact_line(&self, title: &str, items: &[&str]) -> bool {
+        match self.profile {
+            OutputProfile::Full => false,
+            OutputProfile::Compact => {
+                println!("{} {}", format!("{}:", title).bold(), items.join(" · "));
+                true
+            }
+            OutputProfile::Minimal => true,
+        }
+    }
+
     pub fn render_main_help(&self) -> Result<(), ConsoleError> {
-        self.console.render(element! {
+        self.console().render(element! {
             Container {
                 Notice(variant: Variant::Info, title: "Moonflare: Supersonic Cloudflare monorepo".to_owned()) {
                     Text(content: "A CLI utility for managing Cloudflare-focused monorepos with Moon build system.\nUses Moonrepo (https://moonrepo.dev) for task orchestration, caching, and dependency management.")
@@ -225,6 +319,11 @@ impl MoonflareUI {
                                 Text(content: "Add a new project to the monorepo")
                             }
                         }
+                        ListItem {
+                            Entry(name: "import") {
+                                Text(content: "Import a project generated by a popular starter")
+                            }
+                        }
                         ListItem {
                             Entry(name: "build") {
                                 Text(content: "Build project(s) - all projects or specify one")
@@ -241,290 +340,303 @@ impl MoonflareUI {
                             }
                         }
                         ListItem {
-                            Entry(name: "rename") {
-                                Text(content: "Rename a project and update its configuration")
+                            Entry(name: "promote") {
+                                Text(content: "Deploy exactly what one environment runs to another")
                             }
                         }
                         ListItem {
-                            Entry(name: "help") {
-                                Text(content: "Print this message or help for a specific command")
+                            Entry(name: "release") {
+                                Text(content: "Version, changelog and tag a project from its conventional commits")
                             }
                         }
-                    }
-                }
-
-                Section(title: "Options") {
-                    List {
                         ListItem {
-                            Entry(name: "-h, --help") {
-                                Text(content: "Print help")
+                            Entry(name: "publish") {
+                                Text(content: "Publish a crate to crates.io or a package to npm")
                             }
                         }
                         ListItem {
-                            Entry(name: "-V, --version") {
-                                Text(content: "Print version")
+                            Entry(name: "test") {
+                                Text(content: "Run project tests - all projects or specify one")
                             }
                         }
-                    }
-                }
-
-                Section(title: "Examples") {
-                    List {
                         ListItem {
-                            StyledText(content: "moonflare init my-app              # Create new monorepo", style: Style::Shell)
+                            Entry(name: "bench") {
+                                Text(content: "Run crate and Worker benchmarks and write a JSON report")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare add react frontend       # Add React app", style: Style::Shell)
+                            Entry(name: "licenses") {
+                                Text(content: "Report dependency licenses and flag disallowed ones")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare build                    # Build all projects", style: Style::Shell)
+                            Entry(name: "loadtest") {
+                                Text(content: "Generate HTTP load against a project and report latency")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare dev frontend             # Start dev server for one project", style: Style::Shell)
+                            Entry(name: "tag") {
+                                Text(content: "Manage project tags used for targeting groups of projects")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare deploy                   # Deploy all projects", style: Style::Shell)
+                            Entry(name: "rename") {
+                                Text(content: "Rename a project and update its configuration")
+                            }
                         }
-                    }
-                }
-
-                Section(title: "About Moon Integration") {
-                    List {
                         ListItem {
-                            Text(content: "Moonflare uses Moon (https://moonrepo.dev) for task orchestration and caching")
+                            Entry(name: "archive") {
+                                Text(content: "Move a project under archive/, or back with unarchive")
+                            }
                         }
                         ListItem {
-                            Text(content: "Advanced workflows: use 'moon run <target>' for custom task execution")
+                            Entry(name: "ci") {
+                                Text(content: "Generate GitHub Actions workflows, optionally with AI code review")
+                            }
                         }
                         ListItem {
-                            Text(content: "Task dependencies ensure WASM crates build before TypeScript projects")
+                            Entry(name: "config") {
+                                Text(content: "Find deprecated Wrangler configuration keys and upgrade them")
+                            }
                         }
-                    }
-                }
-            }
-        })
-    }
-
-    pub fn render_init_help(&self) -> Result<(), ConsoleError> {
-        self.console.render(element! {
-            Container {
-                Notice(variant: Variant::Info, title: "moonflare init".to_owned()) {
-                    Text(content: "Initialize a new Cloudflare monorepo")
-                }
-
-                Section(title: "Usage") {
-                    StyledText(content: "moonflare init [OPTIONS] <NAME>", style: Style::Shell)
-                }
-
-                Section(title: "Arguments") {
-                    List {
                         ListItem {
-                            Entry(name: "<NAME>") {
-                                Text(content: "Name of the monorepo (use '.' for current directory)")
+                            Entry(name: "clean") {
+                                Text(content: "Remove build outputs and artifacts expired by the retention policy")
                             }
                         }
-                    }
-                }
-
-                Section(title: "Options") {
-                    List {
                         ListItem {
-                            Entry(name: "--path <PATH>") {
-                                Text(content: "Directory to create the monorepo in")
+                            Entry(name: "tasks") {
+                                Text(content: "Sync inherited Moon task defaults into the workspace")
                             }
                         }
                         ListItem {
-                            Entry(name: "--force") {
-                                Text(content: "Force initialization in non-empty directories")
+                            Entry(name: "types") {
+                                Text(content: "Regenerate binding types and share RPC types between Workers")
                             }
                         }
                         ListItem {
-                            Entry(name: "-h, --help") {
-                                Text(content: "Print help")
+                            Entry(name: "sync-names") {
+                                Text(content: "Rename workers to follow the workspace's worker_name pattern")
                             }
                         }
-                    }
-                }
-
-                Section(title: "Examples") {
-                    List {
                         ListItem {
-                            StyledText(content: "moonflare init my-app              # Create in ./my-app/", style: Style::Shell)
+                            Entry(name: "audit-bindings") {
+                                Text(content: "Find declared bindings no source uses and env accesses with no binding")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare init .                   # Create in current directory", style: Style::Shell)
+                            Entry(name: "stats") {
+                                Text(content: "Summarize projects, lines of code, build times, cache hits and bundle sizes")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare init . --force           # Create in non-empty directory", style: Style::Shell)
+                            Entry(name: "history") {
+                                Text(content: "Show who changed the workspace, when and how it went")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare init app --path ~/code   # Create in ~/code/app/", style: Style::Shell)
+                            Entry(name: "grep") {
+                                Text(content: "Search project sources, grouped by project")
+                            }
                         }
-                    }
-                }
-            }
-        })
-    }
-
-    pub fn render_add_help(&self) -> Result<(), ConsoleError> {
-        self.console.render(element! {
-            Container {
-                Notice(variant: Variant::Info, title: "moonflare add".to_owned()) {
-                    Text(content: "Add a new project to the monorepo")
-                }
-
-                Section(title: "Usage") {
-                    StyledText(content: "moonflare add <TYPE> <NAME>", style: Style::Shell)
-                }
-
-                Section(title: "Arguments") {
-                    List {
                         ListItem {
-                            Entry(name: "<TYPE>") {
-                                Text(content: "Type of project (astro, react, durable-object, crate)")
+                            Entry(name: "watch") {
+                                Text(content: "Run Moon targets when matching files change")
                             }
                         }
                         ListItem {
-                            Entry(name: "<NAME>") {
-                                Text(content: "Name of the project")
+                            Entry(name: "why") {
+                                Text(content: "Explain which inputs made Moon re-run a project's task")
                             }
                         }
-                    }
-                }
-
-                Section(title: "Project Types") {
-                    List {
                         ListItem {
-                            Entry(name: "astro") {
-                                Text(content: "Static site with Astro framework")
+                            Entry(name: "workspace") {
+                                Text(content: "Rename the workspace and optionally its worker name prefix")
                             }
                         }
                         ListItem {
-                            Entry(name: "react") {
-                                Text(content: "React application with Vite")
+                            Entry(name: "describe") {
+                                Text(content: "Document a project's tasks, bindings and variables in its README")
                             }
                         }
                         ListItem {
-                            Entry(name: "durable-object") {
-                                Text(content: "Cloudflare Worker with Durable Objects")
+                            Entry(name: "routes") {
+                                Text(content: "List routes, custom domains and workers.dev exposure per environment")
                             }
                         }
                         ListItem {
-                            Entry(name: "crate") {
-                                Text(content: "Rust library compiled to WASM")
+                            Entry(name: "placement") {
+                                Text(content: "Set Smart Placement and Durable Object location hints")
                             }
                         }
-                    }
-                }
-
-                Section(title: "Options") {
-                    List {
                         ListItem {
-                            Entry(name: "-h, --help") {
-                                Text(content: "Print help")
+                            Entry(name: "r2") {
+                                Text(content: "Sync a directory to an R2 bucket, uploading only what changed")
                             }
                         }
-                    }
-                }
-
-                Section(title: "Examples") {
-                    List {
                         ListItem {
-                            StyledText(content: "moonflare add react frontend       # Add React app", style: Style::Shell)
+                            Entry(name: "d1") {
+                                Text(content: "Branch a D1 database for a pull request's preview environment")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare add astro marketing      # Add Astro site", style: Style::Shell)
+                            Entry(name: "do") {
+                                Text(content: "List Durable Objects, or open a SQL shell on their local state")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare add crate utils          # Add Rust WASM crate", style: Style::Shell)
+                            Entry(name: "kv") {
+                                Text(content: "List, read and write KV keys, and export or import a namespace")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare add durable-object api   # Add DO worker", style: Style::Shell)
+                            Entry(name: "queues") {
+                                Text(content: "Send queue messages and peek at what local consumers received")
+                            }
                         }
-                    }
-                }
-            }
-        })
-    }
-
-    pub fn render_build_help(&self) -> Result<(), ConsoleError> {
-        self.console.render(element! {
-            Container {
-                Notice(variant: Variant::Info, title: "moonflare build".to_owned()) {
-                    Text(content: "Build project(s) using Moon's task orchestration")
-                }
-
-                Section(title: "Usage") {
-                    StyledText(content: "moonflare build [PROJECT]", style: Style::Shell)
-                }
-
-                Section(title: "Arguments") {
-                    List {
                         ListItem {
-                            Entry(name: "[PROJECT]") {
-                                Text(content: "Specific project to build (optional - builds all projects if omitted)")
+                            Entry(name: "layout") {
+                                Text(content: "Move project directories, e.g. sites/ to websites/")
                             }
                         }
-                    }
-                }
-
-                Section(title: "Options") {
-                    List {
                         ListItem {
-                            Entry(name: "-h, --help") {
-                                Text(content: "Print help")
+                            Entry(name: "refactor") {
+                                Text(content: "Rename a binding or var across configs and sources")
                             }
                         }
-                    }
-                }
-
-                Section(title: "Examples") {
-                    List {
                         ListItem {
-                            StyledText(content: "moonflare build                    # Build all projects in monorepo", style: Style::Shell)
+                            Entry(name: "hooks") {
+                                Text(content: "Install git hooks that check changes before commit and push")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare build frontend           # Build only the 'frontend' project", style: Style::Shell)
+                            Entry(name: "daemon") {
+                                Text(content: "Run a background daemon that caches the project graph")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare build my-crate           # Build Rust crate (compiles to WASM)", style: Style::Shell)
+                            Entry(name: "devcontainer") {
+                                Text(content: "Generate a devcontainer with the workspace's pinned toolchain")
+                            }
                         }
-                    }
-                }
-
-                Section(title: "Build Process") {
-                    List {
                         ListItem {
-                            Text(content: "Rust crates compile to WASM and are collected in shared-wasm/")
+                            Entry(name: "docs") {
+                                Text(content: "Browse workspace and moonflare documentation offline")
+                            }
                         }
                         ListItem {
-                            Text(content: "TypeScript projects automatically get access to built WASM modules")
+                            Entry(name: "doctor") {
+                                Text(content: "Check the workspace for known-bad configuration")
+                            }
                         }
                         ListItem {
-                            Text(content: "Moon ensures proper build order based on project dependencies")
+                            Entry(name: "env") {
+                                Text(content: "Generate .dev.vars.example files and create .dev.vars for local development")
+                            }
                         }
-                    }
-                }
-            }
-        })
-    }
-
-    pub fn render_dev_help(&self) -> Result<(), ConsoleError> {
-        self.console.render(element! {
-            Container {
-                Notice(variant: Variant::Info, title: "moonflare dev".to_owned()) {
-                    Text(content: "Start development server(s) with hot reloading")
-                }
-
-                Section(title: "Usage") {
-                    StyledText(content: "moonflare dev [PROJECT]", style: Style::Shell)
-                }
-
-                Section(title: "Arguments") {
-                    List {
                         ListItem {
-                            Entry(name: "[PROJECT]") {
-                                Text(content: "Specific project to run (optional - runs all dev servers if omitted)")
+                            Entry(name: "secrets") {
+                                Text(content: "Push Worker secrets from 1Password, Vault or Doppler")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "repro") {
+                                Text(content: "Pack or restore a sanitized workspace for a bug report")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "token") {
+                                Text(content: "Work out the least Cloudflare API token permissions the workspace needs")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "meta") {
+                                Text(content: "Build, deploy or inspect several workspaces in one repository")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "builds") {
+                                Text(content: "Build and deploy in Cloudflare with Workers Builds")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "bundle") {
+                                Text(content: "Analyze Worker bundles against their size budgets")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "nix") {
+                                Text(content: "Generate a Nix flake with the workspace's pinned toolchain")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "migrate") {
+                                Text(content: "Convert a Pages project to a Worker with static assets")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "flags") {
+                                Text(content: "Scaffold workspace feature flags and set their values in KV")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "seed") {
+                                Text(content: "Load seed data from projects' seeds/ directories into D1, KV and Workers")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "access") {
+                                Text(content: "Put Cloudflare Access in front of a deployed project")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "ai") {
+                                Text(content: "Create AI Gateways for Workers AI projects")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "restore") {
+                                Text(content: "Restore files from a snapshot taken before a destructive operation")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "upgrade") {
+                                Text(content: "Walk through breaking template and tooling changes")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "verify-matrix") {
+                                Text(content: "Build the workspace across Node.js, Wrangler and Moon versions")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "preview") {
+                                Text(content: "Build a project for production and serve it locally")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "sbom") {
+                                Text(content: "Generate a CycloneDX SBOM of a project's npm and cargo dependencies")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "examples") {
+                                Text(content: "Browse and add runnable multi-project examples")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "ports") {
+                                Text(content: "Show dev port assignments and conflicts, and release reserved ports")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "self") {
+                                Text(content: "Update moonflare to the latest release")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "help") {
+                                Text(content: "Print this message or help for a specific command")
                             }
                         }
                     }
@@ -537,33 +649,109 @@ impl MoonflareUI {
                                 Text(content: "Print help")
                             }
                         }
+                        ListItem {
+                            Entry(name: "-V, --version") {
+                                Text(content: "Print version")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--config <PATH>") {
+                                Text(content: "Read this file instead of moonflare.toml (or set MOONFLARE_CONFIG)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--ui <PROFILE>") {
+                                Text(content: "Output around results: full, compact (one line per section) or minimal")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--auto-correct") {
+                                Text(content: "Use the closest project when a project name has a single close match")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--plan") {
+                                Text(content: "Print what the command would change and exit")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--auto-approve") {
+                                Text(content: "Apply the command's plan without asking for confirmation")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--timeout <DURATION>") {
+                                Text(content: "Stop the command's moon and wrangler processes after this long")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--env-file <PATH>") {
+                                Text(content: "Pass KEY=value lines to every moon and wrangler process")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--events-file <PATH>") {
+                                Text(content: "Append newline-delimited JSON progress events to a file")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--events-fd <FD>") {
+                                Text(content: "Write JSON progress events to an inherited file descriptor (Unix)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--otel-endpoint <URL>") {
+                                Text(content: "Export OpenTelemetry spans of the command to an OTLP/HTTP endpoint")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--lenient") {
+                                Text(content: "Render template variables missing from the context as empty strings")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--no-moon") {
+                                Text(content: "Run tasks straight from moon.yml without Moon (automatic when it isn't installed)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--report") {
+                                Text(content: "Send the diagnostics bundle to [crash] endpoint if moonflare crashes")
+                            }
+                        }
                     }
                 }
 
                 Section(title: "Examples") {
                     List {
                         ListItem {
-                            StyledText(content: "moonflare dev                      # Start all dev servers", style: Style::Shell)
+                            StyledText(content: "moonflare init my-app              # Create new monorepo", style: Style::Shell)
                         }
                         ListItem {
-                            StyledText(content: "moonflare dev frontend             # Start dev server for 'frontend' only", style: Style::Shell)
+                            StyledText(content: "moonflare add react frontend       # Add React app", style: Style::Shell)
                         }
                         ListItem {
-                            StyledText(content: "moonflare dev marketing            # Start Astro dev server", style: Style::Shell)
+                            StyledText(content: "moonflare build                    # Build all projects", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev frontend             # Start dev server for one project", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare deploy                   # Deploy all projects", style: Style::Shell)
                         }
                     }
                 }
 
-                Section(title: "Development Features") {
+                Section(title: "About Moon Integration") {
                     List {
                         ListItem {
-                            Text(content: "Hot reloading for TypeScript/React/Astro projects")
+                            Text(content: "Moonflare uses Moon (https://moonrepo.dev) for task orchestration and caching")
                         }
                         ListItem {
-                            Text(content: "Automatic WASM rebuilding when Rust crates change")
+                            Text(content: "Advanced workflows: use 'moon run <target>' for custom task execution")
                         }
                         ListItem {
-                            Text(content: "Multiple dev servers can run simultaneously on different ports")
+                            Text(content: "Task dependencies ensure WASM crates build before TypeScript projects")
                         }
                     }
                 }
@@ -571,22 +759,22 @@ impl MoonflareUI {
         })
     }
 
-    pub fn render_deploy_help(&self) -> Result<(), ConsoleError> {
-        self.console.render(element! {
+    pub fn render_init_help(&self) -> Result<(), ConsoleError> {
+        self.console().render(element! {
             Container {
-                Notice(variant: Variant::Info, title: "moonflare deploy".to_owned()) {
-                    Text(content: "Deploy project(s) to Cloudflare using Wrangler")
+                Notice(variant: Variant::Info, title: "moonflare init".to_owned()) {
+                    Text(content: "Initialize a new Cloudflare monorepo")
                 }
 
                 Section(title: "Usage") {
-                    StyledText(content: "moonflare deploy [OPTIONS] [PROJECT]", style: Style::Shell)
+                    StyledText(content: "moonflare init [OPTIONS] <NAME>", style: Style::Shell)
                 }
 
                 Section(title: "Arguments") {
                     List {
                         ListItem {
-                            Entry(name: "[PROJECT]") {
-                                Text(content: "Specific project to deploy (optional - deploys all projects if omitted)")
+                            Entry(name: "<NAME>") {
+                                Text(content: "Name of the monorepo (use '.' for current directory)")
                             }
                         }
                     }
@@ -595,8 +783,28 @@ impl MoonflareUI {
                 Section(title: "Options") {
                     List {
                         ListItem {
-                            Entry(name: "--env <ENV>") {
-                                Text(content: "Environment to deploy to (e.g., staging, production)")
+                            Entry(name: "--path <PATH>") {
+                                Text(content: "Directory to create the monorepo in")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--force") {
+                                Text(content: "Force initialization in non-empty directories")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--force-nested") {
+                                Text(content: "Create the monorepo even inside another workspace")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--shamefully-hoist") {
+                                Text(content: "Hoist all dependencies to the root node_modules")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--public-hoist-pattern <PATTERN>") {
+                                Text(content: "Hoist matching packages to the root node_modules (repeatable)")
                             }
                         }
                         ListItem {
@@ -610,30 +818,27 @@ impl MoonflareUI {
                 Section(title: "Examples") {
                     List {
                         ListItem {
-                            StyledText(content: "moonflare deploy                   # Deploy all projects", style: Style::Shell)
+                            StyledText(content: "moonflare init my-app              # Create in ./my-app/", style: Style::Shell)
                         }
                         ListItem {
-                            StyledText(content: "moonflare deploy api               # Deploy only the 'api' worker", style: Style::Shell)
+                            StyledText(content: "moonflare init .                   # Create in current directory", style: Style::Shell)
                         }
                         ListItem {
-                            StyledText(content: "moonflare deploy --env staging     # Deploy all to staging environment", style: Style::Shell)
+                            StyledText(content: "moonflare init . --force           # Create in non-empty directory", style: Style::Shell)
                         }
                         ListItem {
-                            StyledText(content: "moonflare deploy site --env prod   # Deploy 'site' to production", style: Style::Shell)
+                            StyledText(content: "moonflare init app --path ~/code   # Create in ~/code/app/", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare init app --public-hoist-pattern '*wrangler*'", style: Style::Shell)
                         }
                     }
                 }
 
-                Section(title: "Deployment Process") {
+                Section(title: "Notes") {
                     List {
                         ListItem {
-                            Text(content: "Automatically builds projects before deployment")
-                        }
-                        ListItem {
-                            Text(content: "Uses Wrangler CLI for Cloudflare Workers and Pages deployment")
-                        }
-                        ListItem {
-                            Text(content: "Supports multiple environments via wrangler.toml configuration")
+                            Text(content: "A Get started checklist follows; in a terminal, Enter runs its next step")
                         }
                     }
                 }
@@ -641,72 +846,2891 @@ impl MoonflareUI {
         })
     }
 
-    pub fn render_rename_help(&self) -> Result<(), ConsoleError> {
-        self.console.render(element! {
+    pub fn render_add_help(&self) -> Result<(), ConsoleError> {
+        self.console().render(element! {
             Container {
-                Notice(variant: Variant::Info, title: "moonflare rename".to_owned()) {
-                    Text(content: "Rename a project and update its configuration files")
+                Notice(variant: Variant::Info, title: "moonflare add".to_owned()) {
+                    Text(content: "Add a new project to the monorepo")
                 }
+
                 Section(title: "Usage") {
-                    StyledText(content: "moonflare rename <CURRENT_NAME> <NEW_NAME>", style: Style::Shell)
+                    StyledText(content: "moonflare add <TYPE> [NAME] [--with <FEATURE>] [--from <PROJECT>] [--force] [--attach <WORKER>]", style: Style::Shell)
                 }
 
                 Section(title: "Arguments") {
                     List {
                         ListItem {
-                            Entry(name: "<CURRENT_NAME>") {
-                                Text(content: "Current name of the project to rename")
+                            Entry(name: "<TYPE>") {
+                                Text(content: "Type of project (astro, react, durable-object, crate, maintenance-bot, smoke-tests, e2e, container)")
                             }
                         }
                         ListItem {
-                            Entry(name: "<NEW_NAME>") {
-                                Text(content: "New name for the project")
+                            Entry(name: "[NAME]") {
+                                Text(content: "Name of the project; required except for smoke-tests and e2e. group/name nests it in a group")
                             }
                         }
                     }
                 }
 
-                Section(title: "Options") {
+                Section(title: "Project Types") {
                     List {
                         ListItem {
-                            Entry(name: "-h, --help") {
-                                Text(content: "Print help")
+                            Entry(name: "astro") {
+                                Text(content: "Static site with Astro framework")
                             }
                         }
-                    }
-                }
-
-                Section(title: "Examples") {
-                    List {
                         ListItem {
-                            StyledText(content: "moonflare rename frontend web-app  # Rename React app", style: Style::Shell)
+                            Entry(name: "react") {
+                                Text(content: "React application with Vite")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare rename api backend       # Rename Worker/DO", style: Style::Shell)
+                            Entry(name: "durable-object") {
+                                Text(content: "Cloudflare Worker with Durable Objects")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare rename utils core        # Rename Rust crate", style: Style::Shell)
+                            Entry(name: "crate") {
+                                Text(content: "Rust library compiled to WASM")
+                            }
                         }
                         ListItem {
-                            StyledText(content: "moonflare rename site marketing    # Rename Astro site", style: Style::Shell)
+                            Entry(name: "maintenance-bot") {
+                                Text(content: "Scheduled Worker that alerts on deploy drift and failed crons")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "smoke-tests") {
+                                Text(content: "HTTP checks for deployed projects in tests/smoke (NAME is optional)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "e2e") {
+                                Text(content: "Playwright end-to-end tests in tests/e2e (NAME is optional)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "container") {
+                                Text(content: "Docker image in containers/ that a Worker runs through Cloudflare Containers")
+                            }
                         }
                     }
                 }
 
-                Section(title: "What Gets Updated") {
+                Section(title: "Options") {
                     List {
                         ListItem {
-                            Text(content: "Directory name is changed for all project types")
+                            Entry(name: "--with <FEATURE>") {
+                                Text(content: "Scaffold an optional feature: storybook (React apps), hashed-assets (React and Astro), debug-route and queue (Durable Objects)")
+                            }
                         }
                         ListItem {
-                            Text(content: "wrangler.toml/wrangler.json/wrangler.jsonc 'name' field (web projects)")
+                            Entry(name: "--from <PROJECT>") {
+                                Text(content: "Clone an existing project of the same type, renaming it throughout")
+                            }
                         }
                         ListItem {
-                            Text(content: "Preserves all other project configuration and files")
+                            Entry(name: "--force") {
+                                Text(content: "Scaffold into an existing project directory that isn't empty")
+                            }
                         }
-                    }
-                }
-            }
+                        ListItem {
+                            Entry(name: "--small") {
+                                Text(content: "Generate a no_std crate optimized for WASM size, with a size check in its build")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--component-model") {
+                                Text(content: "Generate a WASM component crate with a WIT interface and TypeScript bindings")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--lib-only") {
+                                Text(content: "Generate a Rust library shared with the other crates, not compiled to WASM")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--attach <WORKER>") {
+                                Text(content: "Worker whose Durable Object runs the container (required for containers)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--list-types") {
+                                Text(content: "List project types with their features, flags and directories")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--json") {
+                                Text(content: "Print --list-types as JSON")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "-h, --help") {
+                                Text(content: "Print help")
+                            }
+                        }
+                    }
+                }
+
+                Section(title: "Examples") {
+                    List {
+                        ListItem {
+                            StyledText(content: "moonflare add react frontend       # Add React app", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare add astro marketing      # Add Astro site", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare add crate utils          # Add Rust WASM crate", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare add durable-object api   # Add DO worker", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare add react ui --with storybook  # React app with Storybook", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare add astro docs --with hashed-assets  # Long-lived caching", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare add durable-object billing --from api  # Clone a worker", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare add crate codec --small  # Size-optimized no_std crate", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare add crate geo --component-model  # WASM component with WIT", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare add crate core --lib-only  # Library shared by crates", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare add container renderer --attach api  # Container run by api", style: Style::Shell)
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn render_build_help(&self) -> Result<(), ConsoleError> {
+        self.console().render(element! {
+            Container {
+                Notice(variant: Variant::Info, title: "moonflare build".to_owned()) {
+                    Text(content: "Build project(s) using Moon's task orchestration")
+                }
+
+                Section(title: "Usage") {
+                    StyledText(content: "moonflare build [PROJECT]", style: Style::Shell)
+                }
+
+                Section(title: "Arguments") {
+                    List {
+                        ListItem {
+                            Entry(name: "[PROJECT]") {
+                                Text(content: "Specific project to build (optional - builds all projects if omitted)")
+                            }
+                        }
+                    }
+                }
+
+                Section(title: "Options") {
+                    List {
+                        ListItem {
+                            Entry(name: "--task <TASK>") {
+                                Text(content: "Run this Moon task instead of 'build' (e.g. build:storybook)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--env <ENV>") {
+                                Text(content: "Build for an environment with its [env.<name>] vars, recorded for --from-artifacts deploys")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--explain-plan") {
+                                Text(content: "Print the Moon commands, working directories and variables the build would use, and what each task runs, without running anything")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--only <GLOB>") {
+                                Text(content: "Only include projects whose name or path matches (repeatable)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--exclude <GLOB>") {
+                                Text(content: "Exclude projects whose name or path matches (repeatable)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--tag <TAG>") {
+                                Text(content: "Only include projects with this tag (repeatable)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--include-ignored") {
+                                Text(content: "Also run projects ignored in moonflare.toml or skipping the command in moon.yml")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--reporter <KIND>") {
+                                Text(content: "console (default), json, github, junit or markdown")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--reporter-output <PATH>") {
+                                Text(content: "Write the report to a file instead of stdout")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "-h, --help") {
+                                Text(content: "Print help")
+                            }
+                        }
+                    }
+                }
+
+                Section(title: "Examples") {
+                    List {
+                        ListItem {
+                            StyledText(content: "moonflare build                    # Build all projects in monorepo", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare build frontend           # Build only the 'frontend' project", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare build my-crate           # Build Rust crate (compiles to WASM)", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare build --only 'workers/*' # Build only worker projects", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare build frontend --task build:storybook", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare build --env staging      # Build with staging's vars", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare build api --explain-plan # What the build would run", style: Style::Shell)
+                        }
+                    }
+                }
+
+                Section(title: "Build Process") {
+                    List {
+                        ListItem {
+                            Text(content: "Rust crates compile to WASM and are collected in shared-wasm/")
+                        }
+                        ListItem {
+                            Text(content: "TypeScript projects automatically get access to built WASM modules")
+                        }
+                        ListItem {
+                            Text(content: "Moon ensures proper build order based on project dependencies")
+                        }
+                        ListItem {
+                            Text(content: "With [licenses] bundle = true, each build writes third-party license notices")
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn render_dev_help(&self) -> Result<(), ConsoleError> {
+        self.console().render(element! {
+            Container {
+                Notice(variant: Variant::Info, title: "moonflare dev".to_owned()) {
+                    Text(content: "Start development server(s) with hot reloading")
+                }
+
+                Section(title: "Usage") {
+                    StyledText(content: "moonflare dev [PROJECT]", style: Style::Shell)
+                }
+
+                Section(title: "Arguments") {
+                    List {
+                        ListItem {
+                            Entry(name: "[PROJECT]") {
+                                Text(content: "Specific project to run (optional - runs all dev servers if omitted)")
+                            }
+                        }
+                    }
+                }
+
+                Section(title: "Options") {
+                    List {
+                        ListItem {
+                            Entry(name: "--remote") {
+                                Text(content: "Run Worker dev servers against remote Cloudflare resources (frontends stay local)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--https") {
+                                Text(content: "Serve over HTTPS using a certificate from a local CA in ~/.moonflare/certs")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--chaos") {
+                                Text(content: "Inject latency, errors and dropped connections into Worker traffic, per [dev.chaos] in moonflare.toml")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--fresh") {
+                                Text(content: "Clear local Wrangler data and load each project's seeds/ before starting (see 'moonflare seed')")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--mock-api") {
+                                Text(content: "Run frontends only, with mocks from each Worker's mock-api.json answering on the Workers' ports")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--crons") {
+                                Text(content: "Trigger each Worker's cron triggers on schedule, through wrangler dev's /__scheduled endpoint")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--fast-forward <FACTOR>") {
+                                Text(content: "Run the cron schedule FACTOR times faster than real time (implies --crons)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--explain-plan") {
+                                Text(content: "Print the Moon commands, working directories and variables that would start the dev servers, without starting them")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--serial") {
+                                Text(content: "Start dev servers one at a time, each once the one before is ready")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--max-parallel <N>") {
+                                Text(content: "Start dev servers in stages, at most N waiting to become ready at once ([dev] in moonflare.toml)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--storybook") {
+                                Text(content: "Run the project's Storybook instead of its dev server")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--task <TASK>") {
+                                Text(content: "Run this Moon task instead of 'dev' (e.g. dev:storybook)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--only <GLOB>") {
+                                Text(content: "Only include projects whose name or path matches (repeatable)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--exclude <GLOB>") {
+                                Text(content: "Exclude projects whose name or path matches (repeatable)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--tag <TAG>") {
+                                Text(content: "Only include projects with this tag (repeatable)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--include-ignored") {
+                                Text(content: "Also run projects ignored in moonflare.toml or skipping the command in moon.yml")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "-h, --help") {
+                                Text(content: "Print help")
+                            }
+                        }
+                    }
+                }
+
+                Section(title: "Examples") {
+                    List {
+                        ListItem {
+                            StyledText(content: "moonflare dev                      # Start all dev servers", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev frontend             # Start dev server for 'frontend' only", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev marketing            # Start Astro dev server", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev --exclude 'legacy-*' # Skip legacy projects", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev --remote             # Use remote bindings for Workers", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev --https              # HTTPS for secure cookies and OAuth", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev --chaos              # Test frontends against flaky Workers", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev --max-parallel 2     # Go easy on the laptop", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev api --fast-forward 60 # Hourly crons run every minute", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev frontend --mock-api  # Frontend against mocked Workers", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare dev --https --explain-plan # How the dev servers would start", style: Style::Shell)
+                        }
+                    }
+                }
+
+                Section(title: "Development Features") {
+                    List {
+                        ListItem {
+                            Text(content: "Hot reloading for TypeScript/React/Astro projects")
+                        }
+                        ListItem {
+                            Text(content: "Automatic WASM rebuilding when Rust crates change")
+                        }
+                        ListItem {
+                            Text(content: "Multiple dev servers can run simultaneously on different ports")
+                        }
+                        ListItem {
+                            Text(content: "Set [projects.<name>.dev] remote = true/false in moonflare.toml to override --remote per project")
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn render_deploy_help(&self) -> Result<(), ConsoleError> {
+        self.console().render(element! {
+            Container {
+                Notice(variant: Variant::Info, title: "moonflare deploy".to_owned()) {
+                    Text(content: "Deploy project(s) to Cloudflare using Wrangler")
+                }
+
+                Section(title: "Usage") {
+                    StyledText(content: "moonflare deploy [OPTIONS] [PROJECT]", style: Style::Shell)
+                }
+
+                Section(title: "Arguments") {
+                    List {
+                        ListItem {
+                            Entry(name: "[PROJECT]") {
+                                Text(content: "Specific project to deploy (optional - deploys every project with a deploy task in Moon's project graph if omitted)")
+                            }
+                        }
+                    }
+                }
+
+                Section(title: "Options") {
+                    List {
+                        ListItem {
+                            Entry(name: "--env <ENV>") {
+                                Text(content: "Environment to deploy to (e.g., staging, production)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--auto-env") {
+                                Text(content: "Resolve the environment from the git branch via [deploy.branches] in moonflare.toml")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--require-clean") {
+                                Text(content: "Fail instead of warning when deployed projects have uncommitted changes")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--sbom") {
+                                Text(content: "Generate an SBOM per project and record its hash with the deployment")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--audit") {
+                                Text(content: "Run pnpm audit and cargo audit first, blocking on advisories above the threshold")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--require-migrations") {
+                                Text(content: "Refuse to deploy while deployed projects have pending D1 migrations")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--analyze-bundle") {
+                                Text(content: "Refuse to deploy Workers over their size budget or needing nodejs_compat (see 'moonflare bundle')")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--watch") {
+                                Text(content: "Redeploy changed projects to a staging environment until Ctrl-C")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--build-remote") {
+                                Text(content: "Start Workers Builds for the pushed commit instead of deploying locally (see 'moonflare builds')")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--from-artifacts <DIR>") {
+                                Text(content: "Deploy prebuilt output from DIR without building; fails if it's stale or doesn't match its build manifest")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--no-delete") {
+                                Text(content: "Keep objects missing from the build output in the bucket of an r2-static deploy")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--smoke") {
+                                Text(content: "Run the smoke tests from 'moonflare add smoke-tests' against the deployed projects; fails the deploy when a check fails")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--explain-plan") {
+                                Text(content: "Print the commands, working directories and variables (WRANGLER_ENV, ...) each project's deploy would use, without deploying")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--task <TASK>") {
+                                Text(content: "Run this Moon task instead of 'deploy' (e.g. deploy:pages)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--confirm <ENV>") {
+                                Text(content: "Confirm a deploy to a protected environment without a prompt")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--keep-vars") {
+                                Text(content: "Keep variables set in the Cloudflare dashboard instead of replacing them")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--vars-file <FILE>") {
+                                Text(content: "Set the KEY=value lines of a file as Worker vars, over the Wrangler config")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--notify, --no-notify") {
+                                Text(content: "Post (or skip) a summary to the [deploy.notify] webhook")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--only <GLOB>") {
+                                Text(content: "Only include projects whose name or path matches (repeatable)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--exclude <GLOB>") {
+                                Text(content: "Exclude projects whose name or path matches (repeatable)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--tag <TAG>") {
+                                Text(content: "Only include projects with this tag (repeatable)")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--include-ignored") {
+                                Text(content: "Also run projects ignored in moonflare.toml or skipping the command in moon.yml")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--reporter <KIND>") {
+                                Text(content: "console (default), json, github, junit or markdown")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "--reporter-output <PATH>") {
+                                Text(content: "Write the report to a file instead of stdout")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "-h, --help") {
+                                Text(content: "Print help")
+                            }
+                        }
+                    }
+                }
+
+                Section(title: "Examples") {
+                    List {
+                        ListItem {
+                            StyledText(content: "moonflare deploy                   # Deploy all projects", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare deploy api               # Deploy only the 'api' worker", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare deploy --env staging     # Deploy all to staging environment", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare deploy site --env prod   # Deploy 'site' to production", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare deploy --auto-env        # Pick environment from current branch", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare deploy --env production --confirm production  # Protected environment", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare deploy api --env staging --explain-plan  # What would run, and how", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare deploy --only 'workers/*' --exclude legacy-*  # Filtered deploy", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare deploy --tag public      # Deploy all projects tagged 'public'", style: Style::Shell)
+                        }
+                    }
+                }
+
+                Section(title: "Deployment Process") {
+                    List {
+                        ListItem {
+                            Text(content: "Automatically builds projects before deployment")
+                        }
+                        ListItem {
+                            Text(content: "Uses Wrangler CLI for Cloudflare Workers and Pages deployment")
+                        }
+                        ListItem {
+                            Text(content: "Supports multiple environments via wrangler.toml configuration")
+                        }
+                        ListItem {
+                            Text(content: "Records the git SHA and dirty state of each deploy in .moonflare/deployments.json")
+                        }
+                        ListItem {
+                            Text(content: "Posts a summary to Slack, Discord or a webhook when [deploy.notify] is set")
+                        }
+                        ListItem {
+                            Text(content: "[projects.<name>.deploy] strategy = pages, r2-static or custom:<command> ships the build output another way")
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn render_rename_help(&self) -> Result<(), ConsoleError> {
+        self.console().render(element! {
+            Container {
+                Notice(variant: Variant::Info, title: "moonflare rename".to_owned()) {
+                    Text(content: "Rename a project and update its configuration files")
+                }
+                Section(title: "Usage") {
+                    StyledText(content: "moonflare rename <CURRENT_NAME> <NEW_NAME>", style: Style::Shell)
+                }
+
+                Section(title: "Arguments") {
+                    List {
+                        ListItem {
+                            Entry(name: "<CURRENT_NAME>") {
+                                Text(content: "Current name of the project to rename")
+                            }
+                        }
+                        ListItem {
+                            Entry(name: "<NEW_NAME>") {
+                                Text(content: "New name for the project")
+                            }
+                        }
+                    }
+                }
+
+                Section(title: "Options") {
+                    List {
+                        ListItem {
+                            Entry(name: "-h, --help") {
+                                Text(content: "Print help")
+                            }
+                        }
+                    }
+                }
+
+                Section(title: "Examples") {
+                    List {
+                        ListItem {
+                            StyledText(content: "moonflare rename frontend web-app  # Rename React app", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare rename api backend       # Rename Worker/DO", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare rename utils core        # Rename Rust crate", style: Style::Shell)
+                        }
+                        ListItem {
+                            StyledText(content: "moonflare rename site marketing    # Rename Astro site", style: Style::Shell)
+                        }
+                    }
+                }
+
+                Section(title: "What Gets Updated") {
+                    List {
+                        ListItem {
+                            Text(content: "Directory name is changed for all project types")
+                        }
+                        ListItem {
+                            Text(content: "wrangler.toml/wrangler.json/wrangler.jsonc 'name' field (web projects)")
+                        }
+                        ListItem {
+                            Text(content: "Preserves all other project configuration and files")
+                        }
+                        ListItem {
+                            Text(content: "A snapshot is saved first - undo with 'moonflare restore latest'")
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn render_help_page(&self, page: HelpPage) -> Result<(), ConsoleError> {
+        let mut options = page.options;
+        options.push(("-h, --help", "Print help"));
+
+        self.console().render(element! {
+            Container {
+                Notice(variant: Variant::Info, title: format!("moonflare {}", page.command)) {
+                    Text(content: page.description)
+                }
+
+                Section(title: "Usage") {
+                    StyledText(content: page.usage, style: Style::Shell)
+                }
+
+                #(if page.arguments.is_empty() {
+                    None
+                } else {
+                    Some(element! {
+                        Section(title: "Arguments") {
+                            List {
+                                #(page.arguments.iter().map(|(name, description)| {
+                                    element! {
+                                        ListItem {
+                                            Entry(name: name.to_string()) {
+                                                Text(content: *description)
+                                            }
+                                        }
+                                    }
+                                }))
+                            }
+                        }
+                    })
+                })
+
+                Section(title: "Options") {
+                    List {
+                        #(options.iter().map(|(name, description)| {
+                            element! {
+                                ListItem {
+                                    Entry(name: name.to_string()) {
+                                        Text(content: *description)
+                                    }
+                                }
+                            }
+                        }))
+                    }
+                }
+
+                Section(title: "Examples") {
+                    List {
+                        #(page.examples.iter().map(|example| {
+                            element! {
+                                ListItem {
+                                    StyledText(content: *example, style: Style::Shell)
+                                }
+                            }
+                        }))
+                    }
+                }
+
+                #(page.notes.as_ref().map(|(title, notes)| {
+                    element! {
+                        Section(title: title.to_string()) {
+                            List {
+                                #(notes.iter().map(|note| {
+                                    element! {
+                                        ListItem {
+                                            Text(content: *note)
+                                        }
+                                    }
+                                }))
+                            }
+                        }
+                    }
+                }))
+            }
+        })
+    }
+
+    pub fn render_test_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "test",
+            description: "Run project tests using Moon's task orchestration",
+            usage: "moonflare test [OPTIONS] [PROJECT]",
+            arguments: vec![(
+                "[PROJECT]",
+                "Specific project to test (optional - tests all projects if omitted)",
+            )],
+            options: vec![
+                (
+                    "--only <GLOB>",
+                    "Only include projects whose name or path matches (repeatable)",
+                ),
+                (
+                    "--exclude <GLOB>",
+                    "Exclude projects whose name or path matches (repeatable)",
+                ),
+                (
+                    "--tag <TAG>",
+                    "Only include projects with this tag (repeatable)",
+                ),
+                (
+                    "--include-ignored",
+                    "Also run projects ignored in moonflare.toml or skipping the command in moon.yml",
+                ),
+                (
+                    "--e2e",
+                    "Run the Playwright tests from 'moonflare add e2e' instead of the projects' tests",
+                ),
+                (
+                    "--against <TARGET>",
+                    "dev (default) for the servers 'moonflare dev' started, or an environment's deployments",
+                ),
+                (
+                    "--reporter <KIND>",
+                    "console (default), json, github, junit or markdown",
+                ),
+                (
+                    "--reporter-output <PATH>",
+                    "Write the report to a file instead of stdout",
+                ),
+            ],
+            examples: vec![
+                "moonflare test                     # Test all projects",
+                "moonflare test --reporter junit --reporter-output junit.xml",
+                "moonflare test my-crate            # Test only the 'my-crate' project",
+                "moonflare test --only 'crates/*'   # Test all Rust crates",
+                "moonflare test --e2e               # End-to-end tests against the dev servers",
+                "moonflare test --e2e --against preview",
+            ],
+            notes: Some((
+                "GitHub Actions",
+                vec![
+                    "Without --reporter, a run in a workflow also writes .moonflare/reports/test.xml",
+                    "and appends a table of results by project to the job summary",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_tag_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "tag",
+            description: "Manage project tags stored in each project's moon.yml",
+            usage: "moonflare tag <add|remove|list> [PROJECT] [TAG]",
+            arguments: vec![
+                ("add <PROJECT> <TAG>", "Add a tag to a project"),
+                ("remove <PROJECT> <TAG>", "Remove a tag from a project"),
+                ("list [PROJECT]", "List tags for all projects or one project"),
+            ],
+            options: vec![],
+            examples: vec![
+                "moonflare tag add api backend      # Tag the 'api' worker as backend",
+                "moonflare tag remove api backend   # Remove the tag again",
+                "moonflare tag list                 # Show tags for every project",
+                "moonflare deploy --tag public      # Deploy all projects tagged 'public'",
+            ],
+            notes: Some((
+                "Tag Targeting",
+                vec![
+                    "build, dev, deploy, and test accept --tag to target tagged projects",
+                    "Tags are stored as Moon project tags, so 'moon query projects --tags' works too",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_licenses_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "licenses",
+            description: "Report the licenses of npm and cargo dependencies across the workspace",
+            usage: "moonflare licenses [--format <FORMAT>] [--output <PATH>] [--include-dev]",
+            arguments: vec![],
+            options: vec![
+                ("--format <FORMAT>", "table (default), csv or json"),
+                ("--output <PATH>", "Write the report to a file instead of stdout"),
+                ("--include-dev", "Include devDependencies, which don't ship"),
+                ("--only/--exclude/--tag", "Limit the report to matching projects"),
+            ],
+            examples: vec![
+                "moonflare licenses                                   # Packages grouped by license",
+                "moonflare licenses --format csv --output licenses.csv",
+                "moonflare licenses --format json --only 'workers/*'",
+            ],
+            notes: Some((
+                "Disallowed Licenses",
+                vec![
+                    "List SPDX ids under [licenses] disallowed in moonflare.toml (globs like GPL-* work)",
+                    "The command fails when any package uses only disallowed licenses",
+                    "npm licenses are read from installed packages; run pnpm install first",
+                    "Crate licenses are read from the cargo registry; run cargo fetch first",
+                    "Set [licenses] bundle = true to ship third-party-licenses.txt with every build",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_bench_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "bench",
+            description: "Run crate and Worker benchmarks and write a JSON report",
+            usage: "moonflare bench [PROJECT] [OPTIONS]",
+            arguments: vec![(
+                "[PROJECT]",
+                "Specific project to benchmark (defaults to every project with a bench task)",
+            )],
+            options: vec![
+                (
+                    "--output <PATH>",
+                    "Write the report here instead of .moonflare/bench/<timestamp>.json",
+                ),
+                (
+                    "--only <GLOB>",
+                    "Only include projects whose name or path matches (repeatable)",
+                ),
+                (
+                    "--exclude <GLOB>",
+                    "Exclude projects whose name or path matches (repeatable)",
+                ),
+                (
+                    "--tag <TAG>",
+                    "Only include projects with this tag (repeatable)",
+                ),
+            ],
+            examples: vec![
+                "moonflare bench                    # Benchmark everything",
+                "moonflare bench utils              # Run criterion benches for 'utils'",
+                "moonflare bench --output bench.json # Write the report for CI",
+            ],
+            notes: Some((
+                "Benchmarks",
+                vec![
+                    "Crates run criterion benches from benches/ (results in target/criterion)",
+                    "Workers run bench/latency.mjs against a local wrangler dev instance",
+                    "Each run is compared with the previous report in .moonflare/bench",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_loadtest_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "loadtest",
+            description: "Generate HTTP load against a project and report latency percentiles",
+            usage: "moonflare loadtest <PROJECT> [OPTIONS]",
+            arguments: vec![("<PROJECT>", "Project to load test")],
+            options: vec![
+                ("--rps <N>", "Requests per second (default: 100)"),
+                ("--duration <TIME>", "How long to run, e.g. 30s or 2m (default: 30s)"),
+                (
+                    "--against <dev|deployed>",
+                    "Local dev server or the url in [projects.<name>] (default: dev)",
+                ),
+                ("--url <URL>", "Explicit base URL to target"),
+                ("--path <PATH>", "Request path (default: /)"),
+            ],
+            examples: vec![
+                "moonflare loadtest api --rps 100 --duration 30s",
+                "moonflare loadtest site --against deployed --path /pricing",
+                "moonflare loadtest api --url https://api.example.com --rps 20",
+            ],
+            notes: Some((
+                "Thresholds",
+                vec![
+                    "Set max_p50_ms, max_p95_ms, max_p99_ms and max_error_rate under [loadtest] in moonflare.toml",
+                    "Override them per project under [projects.<name>.loadtest]",
+                    "The command exits with an error when a threshold is exceeded, failing CI",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_config_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "config",
+            description: "Check project and workspace configuration",
+            usage: "moonflare config <lint|effective|schema|migrate> [OPTIONS]",
+            arguments: vec![
+                (
+                    "lint",
+                    "Find deprecated keys in each project's wrangler.toml, wrangler.json or wrangler.jsonc",
+                ),
+                (
+                    "effective <PROJECT>",
+                    "Show the project's Wrangler configuration merged with wrangler-defaults.jsonc",
+                ),
+                (
+                    "schema",
+                    "Print the JSON Schema of moonflare.toml for editor completion",
+                ),
+                (
+                    "migrate",
+                    "Upgrade moonflare.toml to the current config_version",
+                ),
+            ],
+            options: vec![
+                ("--project <PROJECT>", "Only this project (lint)"),
+                (
+                    "--fix",
+                    "Rewrite deprecated keys to their modern equivalents (lint)",
+                ),
+                (
+                    "--json",
+                    "Print only the merged configuration (effective)",
+                ),
+                ("--output <FILE>", "Write the schema to a file (schema)"),
+            ],
+            examples: vec![
+                "moonflare config lint              # Report deprecated keys",
+                "moonflare config lint --fix        # Upgrade them",
+                "moonflare config lint --project api",
+                "moonflare config effective api     # What builds and deploys of api use",
+                "moonflare config schema --output moonflare.schema.json",
+                "moonflare config migrate           # Record the current config_version",
+            ],
+            notes: Some((
+                "Rules",
+                vec![
+                    "node_compat becomes compatibility_flags = [\"nodejs_compat\"]",
+                    "build.upload becomes main (and rules); experimental_assets becomes assets",
+                    "kv-namespaces, zone_id, usage_model and type are Wrangler 1 leftovers",
+                    "Workers Sites ([site]) and webpack builds need migrating by hand",
+                    "A snapshot is saved first; comments in .jsonc files are not kept",
+                    "wrangler-defaults.jsonc at the workspace root is merged under every project's configuration during builds and deploys",
+                    "Unknown keys in moonflare.toml are warned about, with the closest known key",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_ci_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "ci",
+            description: "Generate CI configuration for the workspace",
+            usage: "moonflare ci generate [--with-ai-review] [--force]",
+            arguments: vec![(
+                "generate",
+                "Write GitHub Actions workflows based on the current project layout",
+            )],
+            options: vec![
+                (
+                    "--with-ai-review",
+                    "Also write the AI code review workflow and secret placeholders",
+                ),
+                ("--force", "Overwrite previously generated workflows"),
+            ],
+            examples: vec![
+                "moonflare ci generate                    # .github/workflows/moonflare-ci.yml",
+                "moonflare ci generate --with-ai-review   # Add .github/workflows/ai-review.yml",
+                "moonflare ci generate --force            # Regenerate after adding projects",
+            ],
+            notes: Some((
+                "Generated Files",
+                vec![
+                    "Path filters and the review prompt list the workspace's current projects",
+                    "Regenerate with --force whenever project directories change",
+                    "The AI review needs the ANTHROPIC_API_KEY repository secret",
+                    ".github/secrets.example lists every secret the workflows reference",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_devcontainer_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "devcontainer",
+            description: "Generate a devcontainer for GitHub Codespaces and VS Code",
+            usage: "moonflare devcontainer generate [--force]",
+            arguments: vec![(
+                "generate",
+                "Write .devcontainer/devcontainer.json and a Dockerfile with the toolchain preinstalled",
+            )],
+            options: vec![("--force", "Overwrite a previously generated devcontainer")],
+            examples: vec![
+                "moonflare devcontainer generate           # .devcontainer/ for Codespaces",
+                "moonflare devcontainer generate --force   # Regenerate after changing versions",
+                "moonflare doctor --fix                    # Also regenerates a stale devcontainer",
+            ],
+            notes: Some((
+                "Toolchain",
+                vec![
+                    "Installs proto, node, pnpm, moon, rust with the wasm32 target, and wrangler",
+                    "Versions come from .prototools, then .moon/toolchain.yml, rust-toolchain.toml and package.json",
+                    "Unpinned tools get their latest stable release when the image builds",
+                    "Every project's usual dev port is forwarded",
+                    "'moonflare doctor' warns when the files no longer match; --fix rewrites them",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_docs_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "docs",
+            description: "Serve a small documentation site for the workspace and moonflare on localhost",
+            usage: "moonflare docs serve [--port <PORT>]",
+            arguments: vec![(
+                "serve",
+                "Serve the site until Ctrl-C",
+            )],
+            options: vec![("--port <PORT>", "Port to listen on (4400 by default)")],
+            examples: vec![
+                "moonflare docs serve              # http://localhost:4400",
+                "moonflare docs serve --port 8080  # Somewhere else",
+            ],
+            notes: Some((
+                "Pages",
+                vec![
+                    "Each project's 'moonflare describe' output: tasks, bindings and variables",
+                    "Every command's help, and what each error code means",
+                    "Nothing is fetched from the network; project pages reflect the files on every request",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_nix_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "nix",
+            description: "Generate a Nix flake for the workspace toolchain",
+            usage: "moonflare nix generate [--force]",
+            arguments: vec![(
+                "generate",
+                "Write flake.nix with a dev shell providing the workspace's toolchain",
+            )],
+            options: vec![("--force", "Overwrite an existing flake.nix")],
+            examples: vec![
+                "moonflare nix generate           # flake.nix, then 'nix develop'",
+                "moonflare nix generate --force   # Regenerate after changing versions",
+                "moonflare doctor --fix           # Also regenerates a stale flake",
+            ],
+            notes: Some((
+                "Toolchain",
+                vec![
+                    "Node.js and pnpm come from the nixpkgs release line of their pinned major version",
+                    "Rust comes from rust-overlay at the pinned channel, with the wasm32 target",
+                    "moon and wrangler are nixpkgs' versions; commit flake.lock to pin them",
+                    "Versions are read like 'moonflare devcontainer generate' reads them",
+                    "'moonflare doctor' warns when flake.nix no longer matches; --fix rewrites it",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_clean_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "clean",
+            description: "Remove build outputs and expired artifacts",
+            usage: "moonflare clean [--auto] [--dry-run]",
+            arguments: vec![],
+            options: vec![
+                (
+                    "--auto",
+                    "Only remove what the [retention] policy has expired",
+                ),
+                (
+                    "--dry-run",
+                    "List what would be removed without deleting anything",
+                ),
+            ],
+            examples: vec![
+                "moonflare clean                    # Remove every dist/ and gathered WASM",
+                "moonflare clean --auto --dry-run   # Preview retention pruning",
+                "moonflare clean --auto             # Apply the retention policy",
+            ],
+            notes: Some((
+                "Retention Policy",
+                vec![
+                    "keep_last: always keep the newest N snapshots and bench reports (default 10)",
+                    "max_age_days: remove older snapshots, reports and stale dist/ directories",
+                    "prune_after_build = true applies the policy after every successful build",
+                    "WASM with no matching crate is always considered stale",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_tasks_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "tasks",
+            description: "Manage Moon task inheritance",
+            usage: "moonflare tasks sync [--dry-run]",
+            arguments: vec![(
+                "sync",
+                "Regenerate .moon/tasks/*.yml and remove tasks projects only repeat",
+            )],
+            options: vec![("--dry-run", "Show what would change without writing files")],
+            examples: vec![
+                "moonflare tasks sync             # Migrate an existing workspace",
+                "moonflare tasks sync --dry-run   # Preview the migration",
+            ],
+            notes: Some((
+                "Inheritance",
+                vec![
+                    ".moon/tasks/typescript.yml holds dev, lint, format, check and deploy",
+                    ".moon/tasks/rust.yml holds the WASM build and cargo tasks",
+                    "Tasks whose command differs from the inherited one are kept as overrides",
+                    "Inputs of removed tasks become the project's 'sources' file group",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_types_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "types",
+            description: "Regenerate binding types and share RPC types between Workers that bind to each other",
+            usage: "moonflare types [PROJECT]",
+            arguments: vec![(
+                "[PROJECT]",
+                "Only this project and the Workers it binds to or is bound by (optional)",
+            )],
+            options: vec![],
+            examples: vec![
+                "moonflare types                  # Every Worker",
+                "moonflare types web              # web and the Workers it binds to",
+                "moonflare --plan types           # Show the files it would write",
+            ],
+            notes: Some((
+                "RPC Types",
+                vec![
+                    "A Worker bound by another's service binding or Durable Object script_name gets a bindings.d.ts",
+                    "bindings.d.ts re-exports the bound classes and WorkerEntrypoint/DurableObject classes from its main module",
+                    "Its package.json exports it as <package>/bindings; consumers get the package as a workspace:* devDependency",
+                    "Then: env.AUTH as Service<AuthService>, with import type { AuthService } from \"auth/bindings\"",
+                    "Runs 'wrangler types' in each Worker afterwards; run 'pnpm install' when dependencies were added",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_stats_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "stats",
+            description: "Summarize workspace composition and build health",
+            usage: "moonflare stats [--runs <N>] [--json]",
+            arguments: vec![],
+            options: vec![
+                ("--runs <N>", "Recent builds to average over (default 20)"),
+                ("--json", "Print the summary as JSON"),
+            ],
+            examples: vec![
+                "moonflare stats                 # Health dashboard",
+                "moonflare stats --runs 100 --json",
+            ],
+            notes: Some((
+                "Sources",
+                vec![
+                    "Project counts by type and non-blank lines of code per language",
+                    "Build times and cache hits from .moonflare/builds.jsonl, which moonflare build appends to",
+                    "Cache hits come from Moon's run report; builds without one count as unknown",
+                    "Largest bundles are each project's dist/ and the modules in shared-wasm/",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_history_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "history",
+            description: "Show the log of operations that changed the workspace",
+            usage: "moonflare history [stats] [OPTIONS]",
+            arguments: vec![(
+                "stats",
+                "Most-run commands, failure rates per project and the slowest builds (--since, --top, --json)",
+            )],
+            options: vec![
+                ("--command <NAME>", "Only this command, e.g. deploy or 'tag add'"),
+                ("--user <USER>", "Only operations by this user"),
+                (
+                    "--since <WHEN>",
+                    "Only operations since a date (2026-01-31), timestamp or age (7d, 12h)",
+                ),
+                ("--failed", "Only operations that failed"),
+                ("--limit <N>", "Show at most the N most recent (default 50, 0 for all)"),
+                ("--json", "Print entries as JSON"),
+            ],
+            examples: vec![
+                "moonflare history                         # Recent operations",
+                "moonflare history --command env --since 7d",
+                "moonflare history --failed --json",
+                "moonflare history stats --since 30d       # Where the team loses time",
+            ],
+            notes: Some((
+                "What Gets Recorded",
+                vec![
+                    "add, rename, deploy, restore, tag add/remove, tasks sync, env, secrets push, access protect, ci generate, workspace rename, meta deploy, sync-names, clean, doctor --fix and audit-bindings --fix",
+                    "--check and --dry-run runs aren't recorded",
+                    "Entries are appended to .moonflare/history.jsonl with the user, arguments and outcome",
+                    "The user is the CI actor when set, otherwise git's user.name or the OS user",
+                    "Every command run, read-only ones included, is counted in .moonflare/usage.jsonl for 'history stats'; it stays on this machine",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_grep_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "grep",
+            description: "Search project sources, grouped by project",
+            usage: "moonflare grep <PATTERN> [PROJECT] [--type <TYPE>] [-i] [-F] [--json]",
+            arguments: vec![
+                ("<PATTERN>", "Regular expression to search for"),
+                ("[PROJECT]", "Only search this project"),
+            ],
+            options: vec![
+                (
+                    "--type <site|app|worker|crate>",
+                    "Only search projects of this type (repeatable)",
+                ),
+                ("-i, --ignore-case", "Match case-insensitively"),
+                (
+                    "-F, --fixed-strings",
+                    "Treat the pattern as a literal string instead of a regular expression",
+                ),
+                ("--json", "Print matches as JSON"),
+                (
+                    "--only / --exclude / --tag",
+                    "Filter projects like build and deploy",
+                ),
+            ],
+            examples: vec![
+                "moonflare grep 'env\\.CACHE' --type worker    # Usages of a binding",
+                "moonflare grep -F 'TODO' --tag public         # Literal search in tagged projects",
+                "moonflare grep 'fetch\\(' api --json          # One project, as JSON",
+            ],
+            notes: Some((
+                "Search",
+                vec![
+                    "node_modules, dist, target, .wrangler, .astro and .git are skipped",
+                    "Binary files are skipped",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_watch_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "watch",
+            description: "Run Moon targets when files matching the [watch.rules] globs change",
+            usage: "moonflare watch",
+            arguments: vec![],
+            options: vec![(
+                "--reporter <KIND>",
+                "Report every target run when watching stops",
+            )],
+            examples: vec![
+                "moonflare watch                      # Run the [watch.rules] targets on save",
+                "moonflare watch --reporter junit     # JUnit report of the runs on Ctrl-C",
+            ],
+            notes: Some((
+                "Rules",
+                vec![
+                    "[watch.rules] maps globs relative to the workspace root to Moon targets, e.g. \"workers/api/src/**\" = [\"api:test\"]",
+                    "Saves within debounce_ms (default 300) of each other are batched into one moon run",
+                    "A status line after each run shows every target's result and time",
+                    "node_modules, dist, target, pkg, .wrangler, .astro and .moon aren't watched",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_why_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "why",
+            description: "Explain which inputs made Moon re-run a project's task",
+            usage: "moonflare why <PROJECT> [--task <TASK>]",
+            arguments: vec![("<PROJECT>", "Project whose task re-ran")],
+            options: vec![("--task <TASK>", "Task to explain (default: build)")],
+            examples: vec![
+                "moonflare why web                # Why web:build rebuilt",
+                "moonflare why api --task deploy  # Why api:deploy ran again",
+            ],
+            notes: Some((
+                "How It Works",
+                vec![
+                    "Compares the task's last two hash manifests in .moon/cache/hashes",
+                    "Lists changed input files, rebuilt WASM modules, environment variables and upstream targets",
+                    "Downstream targets come from the deps in each project's moon.yml",
+                    "Prints the 'moon query hash-diff' command for the full comparison",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_sync_names_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "sync-names",
+            description: "Rename workers to follow the workspace's worker_name pattern",
+            usage: "moonflare sync-names [--check]",
+            arguments: vec![],
+            options: vec![(
+                "--check",
+                "Report names that need renaming and fail instead of rewriting",
+            )],
+            examples: vec![
+                "moonflare sync-names           # Rewrite wrangler names",
+                "moonflare sync-names --check   # Fail in CI when a name drifts",
+            ],
+            notes: Some((
+                "Naming",
+                vec![
+                    "Set worker_name = \"{workspace}-{project}\" under [workspace] in moonflare.toml",
+                    "moonflare add and rename apply the pattern to new workers",
+                    "Storybook Workers are named <worker>-storybook",
+                    "Renamed workers deploy as new workers; delete the old ones yourself",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_audit_bindings_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "audit-bindings",
+            description: "Find declared bindings no source uses and env accesses with no binding",
+            usage: "moonflare audit-bindings [PROJECT] [--fix]",
+            arguments: vec![("[PROJECT]", "Only audit this project")],
+            options: vec![(
+                "--fix",
+                "Remove unused bindings from the Wrangler configurations",
+            )],
+            examples: vec![
+                "moonflare audit-bindings           # Audit every project",
+                "moonflare audit-bindings api --fix # Drop api's unused bindings",
+            ],
+            notes: Some((
+                "What Counts",
+                vec![
+                    "A binding is used when its name appears anywhere in the project's TypeScript or JavaScript",
+                    "env.NAME and env[\"NAME\"] need a binding, a var or a .dev.vars entry; process.env and import.meta.env are skipped",
+                    "Bindings from the workspace's Wrangler defaults count, but --fix leaves them for you to remove",
+                    "The static assets binding is never reported, since framework adapters use it",
+                    "Fails while anything is left to fix, so it can run in CI",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_archive_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "archive",
+            description: "Move a project under archive/ so builds, CI and Moon stop seeing it",
+            usage: "moonflare archive <PROJECT>",
+            arguments: vec![("<PROJECT>", "Project to archive")],
+            options: vec![],
+            examples: vec![
+                "moonflare archive legacy-admin   # Move workers/legacy-admin to archive/workers/legacy-admin",
+                "moonflare unarchive legacy-admin # Move it back",
+            ],
+            notes: Some((
+                "Archiving",
+                vec![
+                    "Workspace globs naming the project's path are removed; wildcard globs stop matching it",
+                    "Crates are taken out of the shared-wasm:gather dependencies",
+                    "Tracked files are moved in the git index too, so the move commits as a rename",
+                    "A snapshot is saved first, so 'moonflare restore' can undo it",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_unarchive_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "unarchive",
+            description: "Move an archived project back into the workspace",
+            usage: "moonflare unarchive <PROJECT>",
+            arguments: vec![(
+                "<PROJECT>",
+                "Archived project, by name or path inside archive/",
+            )],
+            options: vec![],
+            examples: vec!["moonflare unarchive legacy-admin"],
+            notes: Some((
+                "Unarchiving",
+                vec![
+                    "The project returns to the path it was archived from",
+                    "Globs are added when none covers it, and crates rejoin shared-wasm:gather",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_workspace_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "workspace",
+            description: "Manage the workspace itself",
+            usage: "moonflare workspace rename <NEW_NAME> [--prefix-workers]",
+            arguments: vec![(
+                "rename <NEW_NAME>",
+                "Rename the workspace wherever its name appears",
+            )],
+            options: vec![(
+                "--prefix-workers",
+                "Name every worker <NEW_NAME>-<project>, replacing the old workspace prefix",
+            )],
+            examples: vec![
+                "moonflare workspace rename storefront                    # Rename the workspace",
+                "moonflare workspace rename storefront --prefix-workers   # Also rename workers",
+            ],
+            notes: Some((
+                "Renaming",
+                vec![
+                    "Updates moonflare.toml, package.json, README.md and .moon configuration",
+                    "Names must be lowercase letters, digits, '-' and '_'",
+                    "A snapshot is saved first; 'moonflare restore' undoes the rename",
+                    "The workspace directory itself is left as is",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_import_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "import",
+            description: "Import a project generated by a popular starter",
+            usage: "moonflare import <SOURCE> [--name <NAME>] [--as <TYPE>]",
+            arguments: vec![("<SOURCE>", "Directory the starter generated")],
+            options: vec![
+                ("--name <NAME>", "Project name (defaults to the directory's name)"),
+                (
+                    "--as <TYPE>",
+                    "Import as astro, react, durable-object or crate instead of the detected type",
+                ),
+            ],
+            examples: vec![
+                "moonflare import ../my-vite-app                # create-vite React app",
+                "moonflare import ../blog --name marketing      # create-astro site, renamed",
+                "moonflare import ../api                        # create-cloudflare (C3) Worker",
+                "moonflare import ../wasm-lib --as crate        # cargo-generate crate",
+            ],
+            notes: Some((
+                "Starters",
+                vec![
+                    "create-vite and create-astro: the build outDir moves to dist/ and an assets-only wrangler.jsonc is added",
+                    "create-cloudflare: a wrangler config beside the Worker code moves to the project root with its paths rebased",
+                    "cargo-generate: the package is renamed and built as a cdylib for shared-wasm",
+                    "Projects get moonflare's dev, build and deploy scripts and a moon.yml; the source is copied, not moved",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_layout_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "layout",
+            description: "Show or change the directories projects live in",
+            usage: "moonflare layout <show|set> [KIND=DIRECTORY]...",
+            arguments: vec![
+                ("show", "List the directory for each kind of project"),
+                (
+                    "set <KIND=DIRECTORY>...",
+                    "Move sites, apps, workers or crates to a new directory",
+                ),
+            ],
+            options: vec![],
+            examples: vec![
+                "moonflare layout show                           # Where projects live",
+                "moonflare layout set sites=websites             # Move sites/ to websites/",
+                "moonflare layout set apps=web workers=services  # Move several at once",
+            ],
+            notes: Some((
+                "Migration",
+                vec![
+                    "Records the new directories under [layout] in moonflare.toml",
+                    "Updates Moon and pnpm workspace globs, the justfile and shared-wasm scripts",
+                    "Rewrites paths in shared task files, CI workflows and project configuration",
+                    "A snapshot is saved first; 'moonflare restore' undoes the move",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_describe_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "describe",
+            description: "Document a project's tasks, bindings and variables in its README",
+            usage: "moonflare describe [--project <PROJECT>] [--write]",
+            arguments: vec![],
+            options: vec![
+                (
+                    "--project <PROJECT>",
+                    "Only this project (every project by default)",
+                ),
+                (
+                    "--write",
+                    "Update the generated section of the README instead of printing it",
+                ),
+            ],
+            examples: vec![
+                "moonflare describe --project api          # Print api's documentation",
+                "moonflare describe --project api --write  # Refresh apps/api/README.md",
+                "moonflare describe --write                # Refresh every project's README",
+            ],
+            notes: Some((
+                "Generated section",
+                vec![
+                    "Lists tasks, bindings, environment variables and how to dev and deploy",
+                    "Only the part between the moonflare:describe markers is rewritten",
+                    "add, rename, env sync, tasks sync and sync-names keep it up to date",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_routes_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "routes",
+            description: "Show the routes and domains projects are reachable on",
+            usage: "moonflare routes list [--env <ENV>] [--json]",
+            arguments: vec![(
+                "list",
+                "List each project's routes, custom domains and workers.dev subdomain",
+            )],
+            options: vec![
+                ("--env <ENV>", "Only this Wrangler environment"),
+                ("--json", "Print the routes as JSON"),
+            ],
+            examples: vec![
+                "moonflare routes list                # Every environment",
+                "moonflare routes list --env staging  # What staging exposes",
+                "moonflare routes list --json         # For scripts and dashboards",
+            ],
+            notes: Some((
+                "Exposure",
+                vec![
+                    "Read from each project's Wrangler configuration; nothing is fetched from Cloudflare",
+                    "Workers without routes are on workers.dev unless workers_dev is false",
+                    "Overlapping routes are marked; 'moonflare doctor' explains each conflict",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_placement_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "placement",
+            description: "Set and review Smart Placement and Durable Object location hints",
+            usage: "moonflare placement <set|status> [OPTIONS]",
+            arguments: vec![
+                (
+                    "set <PROJECT>",
+                    "Record the placement in moonflare.toml and write it to the Wrangler configuration",
+                ),
+                (
+                    "status",
+                    "Show each project's placement and location hints per environment",
+                ),
+            ],
+            options: vec![
+                ("--mode <smart|off>", "Smart Placement, or run where requests arrive"),
+                (
+                    "--hint <BINDING=REGION>",
+                    "Create a Durable Object binding's objects in a region (repeatable)",
+                ),
+                ("--clear-hint <BINDING>", "Drop a binding's location hint"),
+                ("--dry-run", "Preview the changes without writing them"),
+                ("-y, --yes", "Apply the changes without asking"),
+                ("--json", "Print the status as JSON"),
+            ],
+            examples: vec![
+                "moonflare placement set api --mode smart --yes",
+                "moonflare placement set chat --hint ROOMS=weur --yes",
+                "moonflare placement set chat --yes      # Re-apply what moonflare.toml declares",
+                "moonflare placement status",
+            ],
+            notes: Some((
+                "Placement",
+                vec![
+                    "Declared under [projects.<name>.placement] in moonflare.toml",
+                    "The mode goes in the top-level placement table and environments that set their own",
+                    "Hints become <BINDING>_LOCATION_HINT vars in every environment, for code to pass as locationHint",
+                    "Regions: wnam, enam, sam, weur, eeur, apac, oc, afr, me",
+                    "Edits keep the configuration's comments and formatting",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_r2_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "r2",
+            description: "Sync directories to R2 buckets",
+            usage: "moonflare r2 sync <DIR> <BUCKET> [--prefix <PREFIX>] [--no-delete]",
+            arguments: vec![(
+                "sync",
+                "Upload new and changed files in DIR to BUCKET and delete objects no longer in DIR",
+            )],
+            options: vec![
+                ("--prefix <PREFIX>", "Key prefix for the uploaded objects"),
+                (
+                    "--no-delete",
+                    "Keep objects that are no longer in the directory",
+                ),
+            ],
+            examples: vec![
+                "moonflare r2 sync media media-backup                      # Mirror media/ into the bucket",
+                "moonflare r2 sync media media-backup --prefix 2026/       # Under a key prefix",
+                "moonflare r2 sync media media-backup --no-delete          # Never remove objects",
+            ],
+            notes: Some((
+                "Sync",
+                vec![
+                    "Needs CLOUDFLARE_API_TOKEN with Workers R2 Storage Edit permission and CLOUDFLARE_ACCOUNT_ID",
+                    "Files are compared with what the last sync uploaded (.moonflare/r2/<bucket>.json) and the bucket's ETags",
+                    "Uploads and deletes run eight at a time; objects get the [assets] cache policy",
+                    "The r2-static deploy strategy syncs build output the same way",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_d1_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "d1",
+            description: "Branch D1 databases for preview environments",
+            usage: "moonflare d1 branch <PROJECT|cleanup> [--name <NAME>] [--from <ENV>] [--env <ENV>] [--binding <BINDING>]",
+            arguments: vec![
+                (
+                    "branch",
+                    "Copy a database into <database>-<NAME> and bind it in the preview environment",
+                ),
+                (
+                    "branch cleanup",
+                    "Delete branch databases of merged or closed pull requests and their bindings",
+                ),
+            ],
+            options: vec![
+                ("--name <NAME>", "Branch name, e.g. pr-123"),
+                (
+                    "--from <ENV>",
+                    "Environment whose database is copied (default: production)",
+                ),
+                (
+                    "--env <ENV>",
+                    "Environment to bind the branch in (default: pull_request_env, else preview)",
+                ),
+                (
+                    "--binding <BINDING>",
+                    "D1 binding to branch, when the project has several",
+                ),
+            ],
+            examples: vec![
+                "moonflare d1 branch api --name pr-123                # Copy production into shop-db-pr-123",
+                "moonflare d1 branch api --name pr-123 --from staging # Copy staging instead",
+                "moonflare d1 branch cleanup                          # Remove branches of merged PRs",
+                "moonflare d1 branch cleanup --name pr-123            # Remove one branch now",
+            ],
+            notes: Some((
+                "Branching",
+                vec![
+                    "The copy is made with wrangler d1 export and imported with wrangler d1 execute",
+                    "The preview environment's binding is rewritten in the wrangler config; JSONC comments aren't kept",
+                    "Cleanup asks the GitHub CLI (gh) whether pr-<number> branches are merged or closed",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_do_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "do",
+            description: "Inspect Durable Object namespaces, objects and local state",
+            usage: "moonflare do <list|inspect> [PROJECT] [OPTIONS]",
+            arguments: vec![
+                (
+                    "list",
+                    "List each Worker's Durable Object namespaces and their objects",
+                ),
+                (
+                    "inspect <PROJECT>",
+                    "Open sqlite3 on an object's state persisted by 'moonflare dev'",
+                ),
+            ],
+            options: vec![
+                (
+                    "--env <ENV>",
+                    "Environment whose Workers to list (default: production)",
+                ),
+                ("--local", "List local state instead of deployed objects"),
+                ("--json", "Print the list as JSON"),
+                (
+                    "--class <CLASS>",
+                    "Class to inspect, when the project defines several",
+                ),
+                ("--object <ID>", "Object to inspect, by id or id prefix"),
+                ("--sql <QUERY>", "Run a query instead of opening a shell"),
+                ("--write", "Open the database writable (read-only by default)"),
+            ],
+            examples: vec![
+                "moonflare do list                          # Deployed namespaces and objects",
+                "moonflare do list api --env staging        # One Worker in staging",
+                "moonflare do list --local                  # Objects persisted by moonflare dev",
+                "moonflare do inspect api                   # SQL shell on api's only object",
+                "moonflare do inspect api --object 5f3a --sql 'SELECT * FROM _cf_KV'",
+            ],
+            notes: Some((
+                "Durable Objects",
+                vec![
+                    "Listing deployed objects needs CLOUDFLARE_API_TOKEN with Workers Scripts Read",
+                    "Local state lives in .wrangler/state/v3/do, one SQLite file per object",
+                    "Key-value storage is kept in the _cf_KV table of the object's database",
+                    "'moonflare add durable-object <name> --with debug-route' adds /__debug/storage, answered when DO_DEBUG is \"true\"",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_kv_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "kv",
+            description: "Browse, export and import KV namespace data",
+            usage: "moonflare kv <keys|get|put|export|import> <PROJECT> <BINDING> [ARGS] [OPTIONS]",
+            arguments: vec![
+                ("keys <PROJECT> <BINDING>", "List the namespace's keys"),
+                ("get <PROJECT> <BINDING> <KEY>", "Print the value stored under a key"),
+                (
+                    "put <PROJECT> <BINDING> <KEY> [VALUE]",
+                    "Store a value, or a file's contents with --path",
+                ),
+                (
+                    "export <PROJECT> <BINDING>",
+                    "Write every key and value as JSON Lines",
+                ),
+                (
+                    "import <PROJECT> <BINDING> <FILE>",
+                    "Write the entries of a JSON Lines file",
+                ),
+            ],
+            options: vec![
+                (
+                    "--env <ENV>",
+                    "Environment whose namespace to use (default: production)",
+                ),
+                ("--local", "Use the local state 'moonflare dev' reads"),
+                ("--prefix <PREFIX>", "Only keys starting with the prefix"),
+                ("--json", "Print keys with their expiration and metadata as JSON"),
+                ("--path <FILE>", "Store a file's contents"),
+                ("--ttl <SECONDS>", "Expire the key after this many seconds"),
+                ("-o, --output <FILE>", "File to export to (default: stdout)"),
+            ],
+            examples: vec![
+                "moonflare kv keys api CACHE --prefix user:      # Keys of the CACHE binding",
+                "moonflare kv get api CACHE user:1 --env staging # A value in staging",
+                "moonflare kv put api CACHE greeting hello --local",
+                "moonflare kv export api CACHE -o cache.jsonl    # Back up production",
+                "moonflare kv import api CACHE cache.jsonl --local",
+            ],
+            notes: Some((
+                "KV data",
+                vec![
+                    "Bindings are looked up in the project's Wrangler config, in the environment's own kv_namespaces",
+                    "Each JSON Lines entry has key and value, and optionally expiration, expiration_ttl, metadata and base64",
+                    "Export reads values with 'wrangler kv bulk get', 100 keys per call",
+                    "--local reads and writes .wrangler/state, so 'moonflare dev' sees the data",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_queues_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "queues",
+            description: "Send queue messages and inspect what local consumers received",
+            usage: "moonflare queues <send|peek> <QUEUE> [OPTIONS]",
+            arguments: vec![
+                (
+                    "send <QUEUE> --body <JSON|@FILE>",
+                    "Send one message to the queue",
+                ),
+                (
+                    "peek <QUEUE>",
+                    "Messages the queue's consumer received under 'moonflare dev'",
+                ),
+            ],
+            options: vec![
+                (
+                    "--body <JSON|@FILE>",
+                    "Message body, or @path to read it from a file",
+                ),
+                (
+                    "--local",
+                    "Send through the producer Worker's dev server instead of the Cloudflare API",
+                ),
+                ("--json", "Print peeked messages as JSON"),
+            ],
+            examples: vec![
+                "moonflare queues send api-queue --body @order.json --local # Through 'moonflare dev'",
+                "moonflare queues peek api-queue                            # What the consumer received",
+                "moonflare queues send api-queue --body '{\"id\":1}'         # To the deployed queue",
+            ],
+            notes: Some((
+                "Local queues",
+                vec![
+                    "Queues are found through queues.producers and queues.consumers in the Workers' Wrangler configs",
+                    "--local and peek call /__queues/send and /__queues/peek, which Workers added with '--with queue' answer while QUEUE_DEBUG is \"true\" in .dev.vars",
+                    "Bodies that aren't JSON are sent as text",
+                    "Sending to a deployed queue needs CLOUDFLARE_API_TOKEN with Queues Edit permission",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_promote_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "promote",
+            description: "Deploy exactly what one environment runs to another",
+            usage: "moonflare promote <FROM> <TO> [PROJECT] [--artifacts <DIR>] [--confirm <ENV>]",
+            arguments: vec![
+                ("<FROM>", "Environment whose deployments are promoted, e.g. staging"),
+                ("<TO>", "Environment they're deployed to, e.g. production"),
+                ("[PROJECT]", "Only this project; every project deployed to <FROM> by default"),
+            ],
+            options: vec![
+                (
+                    "--artifacts <DIR>",
+                    "Directory with the build manifest and output to ship (default: the workspace)",
+                ),
+                (
+                    "--confirm <ENV>",
+                    "Confirm promoting to a protected environment by repeating its name",
+                ),
+            ],
+            examples: vec![
+                "moonflare promote staging production                      # Everything staging runs",
+                "moonflare promote staging production api                  # Only api",
+                "moonflare promote staging production --artifacts ./build  # Ship CI's build output",
+            ],
+            notes: Some((
+                "Promotion",
+                vec![
+                    "Each project's Worker version recorded for <FROM> is deployed with 'wrangler versions deploy'",
+                    "Without a version, output built for <TO> from <FROM>'s commit ships as with 'deploy --from-artifacts'",
+                    "Nothing is rebuilt: a project with neither is refused",
+                    "The deployment manifest records which environment each promotion came from",
+                    "promote_from under [deploy.environments.<env>] makes promotion the only way to deploy there",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_release_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "release",
+            description: "Version, changelog and tag a project from its conventional commits",
+            usage: "moonflare release <PROJECT> [--bump <patch|minor|major>] [--dry-run] [--deploy [--env <ENV>]]",
+            arguments: vec![("<PROJECT>", "Project to release")],
+            options: vec![
+                (
+                    "--bump <patch|minor|major>",
+                    "Bump by this much instead of deriving it from the commits",
+                ),
+                (
+                    "--dry-run",
+                    "Print the next version and changelog entry without changing anything",
+                ),
+                ("--deploy", "Deploy the project after tagging the release"),
+                ("--env <ENV>", "Environment to deploy the release to"),
+            ],
+            examples: vec![
+                "moonflare release api --dry-run               # Preview the next version",
+                "moonflare release api                         # Commit and tag api-vX.Y.Z",
+                "moonflare release api --deploy --env staging  # Then deploy it",
+            ],
+            notes: Some((
+                "Versioning",
+                vec![
+                    "Only commits touching the project's directory since its last <project>-v* tag count",
+                    "feat bumps the minor version, fix and perf the patch, '!' or BREAKING CHANGE the major",
+                    "Before 1.0.0 breaking changes bump the minor version",
+                    "Updates package.json (Cargo.toml for crates) and prepends to the project's CHANGELOG.md",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_publish_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "publish",
+            description: "Publish a crate to crates.io or a package to npm",
+            usage: "moonflare publish <PROJECT> [--dry-run] [--provenance] [--skip-checks]",
+            arguments: vec![("<PROJECT>", "Crate or package project to publish")],
+            options: vec![
+                ("--dry-run", "Pack and verify without uploading"),
+                (
+                    "--provenance",
+                    "Publish an npm package with a provenance attestation",
+                ),
+                (
+                    "--skip-checks",
+                    "Don't run the project's build and test tasks first",
+                ),
+            ],
+            examples: vec![
+                "moonflare publish ui-kit --dry-run       # Check what would be uploaded",
+                "moonflare publish ui-kit --provenance    # Publish from CI with provenance",
+                "moonflare publish parser                 # cargo publish a crate",
+            ],
+            notes: Some((
+                "Registries",
+                vec![
+                    "Crates use cargo publish; other projects use pnpm publish, which resolves workspace: dependencies",
+                    "Set registry, access, tag and provenance under [projects.<name>.publish] in moonflare.toml",
+                    "Projects marked private (package.json) or publish = false (Cargo.toml) are refused",
+                    "The project must have no uncommitted changes unless --dry-run is passed",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_refactor_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "refactor",
+            description: "Rename a binding or var across configs and sources",
+            usage: "moonflare refactor rename-binding <OLD> <NEW> [--project <PROJECT>] [--dry-run] [--yes]",
+            arguments: vec![
+                (
+                    "rename-binding <OLD> <NEW>",
+                    "Rename a KV, D1, R2, service or Durable Object binding, or a var",
+                ),
+            ],
+            options: vec![
+                ("--project <PROJECT>", "Only rename in this project"),
+                ("--dry-run", "Preview the changes without writing them"),
+                ("-y, --yes", "Apply the changes without asking"),
+            ],
+            examples: vec![
+                "moonflare refactor rename-binding CACHE SESSIONS --dry-run    # Preview",
+                "moonflare refactor rename-binding CACHE SESSIONS              # Confirm and apply",
+                "moonflare refactor rename-binding API_URL BACKEND_URL --project web",
+            ],
+            notes: Some((
+                "What changes",
+                vec![
+                    "Wrangler configs and the shared defaults, in every environment, keeping comments",
+                    "env.OLD and env[\"OLD\"] in TypeScript and JavaScript sources, and OLD= in .dev.vars files",
+                    "Types are regenerated with 'wrangler types'; 'moonflare restore' undoes the rename",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_hooks_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "hooks",
+            description: "Install git hooks that check changes before commit and push",
+            usage: "moonflare hooks <install|uninstall> [--manager <git|lefthook>] [--force]",
+            arguments: vec![
+                (
+                    "install",
+                    "Write pre-commit and pre-push hooks running the [hooks] commands",
+                ),
+                ("uninstall", "Remove the hooks moonflare installed"),
+            ],
+            options: vec![
+                (
+                    "--manager <git|lefthook>",
+                    "Plain scripts in .git/hooks or a lefthook.yml (default: [hooks] manager, else git)",
+                ),
+                ("--force", "Replace hooks moonflare didn't write"),
+            ],
+            examples: vec![
+                "moonflare hooks install                       # Scripts in .git/hooks",
+                "moonflare hooks install --manager lefthook    # Generate lefthook.yml",
+                "moonflare hooks uninstall                     # Remove them again",
+            ],
+            notes: Some((
+                "Hooks",
+                vec![
+                    "pre-commit runs 'moon run :check --affected --status staged' by default",
+                    "pre-push runs 'moonflare config lint' and 'moon run :lint :typecheck --affected' by default",
+                    "Commands run from the workspace root and stop at the first failure",
+                    "Rerun install after changing [hooks]; 'git commit --no-verify' skips the hooks once",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_daemon_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "daemon",
+            description: "Run a background daemon that caches the Moon project graph",
+            usage: "moonflare daemon <start|stop|status>",
+            arguments: vec![
+                ("start", "Start the daemon in the background"),
+                ("stop", "Stop the daemon"),
+                ("status", "Show the daemon's pid, uptime and cache"),
+            ],
+            options: vec![(
+                "--foreground",
+                "Run 'start' in the foreground instead of detaching",
+            )],
+            examples: vec![
+                "moonflare daemon start    # Cache moon queries for later commands",
+                "moonflare daemon status   # Check the daemon is up",
+                "moonflare daemon stop     # Shut it down",
+            ],
+            notes: Some((
+                "How It Works",
+                vec![
+                    "The daemon listens on .moonflare/daemon.sock and logs to .moonflare/daemon.log",
+                    "Commands send 'moon query' calls to it when it's running and fall back to Moon otherwise",
+                    "Cached results are dropped as soon as any workspace file changes",
+                    "Set MOONFLARE_NO_DAEMON=1 to bypass a running daemon; Unix only",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_doctor_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "doctor",
+            description: "Check the workspace for known-bad configuration",
+            usage: "moonflare doctor [--fix | --network]",
+            arguments: vec![],
+            options: vec![
+                (
+                    "--fix",
+                    "Sync .npmrc, workspace globs and generated environments and upgrade deprecated Wrangler keys before checking",
+                ),
+                (
+                    "--network",
+                    "Only check that required endpoints are reachable through [network] settings",
+                ),
+            ],
+            examples: vec![
+                "moonflare doctor             # Report problems",
+                "moonflare doctor --fix       # Sync .npmrc and workspace globs, upgrade Wrangler keys",
+                "moonflare doctor --network   # Probe the Cloudflare API, npm and downloads",
+            ],
+            notes: Some((
+                "Checks",
+                vec![
+                    "Projects outside pnpm-workspace.yaml or .moon/workspace.yml globs are errors",
+                    "Globs for directories that no longer exist are warnings",
+                    "node-linker=pnp is an error: wrangler can't resolve Plug'n'Play installs",
+                    "Redundant shamefully-hoist/public-hoist-pattern combinations are warnings",
+                    "Hoisting settings in project .npmrc files are ignored by pnpm",
+                    "Per-project patterns go in [projects.<name>.pnpm] and are merged at the root",
+                    "A node on PATH outside any package.json engines.node range is an error",
+                    "Wrangler keys Wrangler 4 rejects (node_compat, type, build.upload) are errors",
+                    "Other deprecated Wrangler keys are warnings; see 'moonflare config lint'",
+                    "Routes or custom domains two projects claim in one environment are errors",
+                    "Access applications from 'moonflare access protect' without policies are errors",
+                    "Invalid package.json#moonflare fields are errors; bindings missing from Wrangler are warnings",
+                    "A generated devcontainer or flake.nix pinning other versions than the workspace is a warning",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_env_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "env",
+            description: "Manage the .dev.vars files used by wrangler dev and Wrangler environments",
+            usage: "moonflare env <example|pull|sync|diff> [PROJECT] [ENV]",
+            arguments: vec![
+                (
+                    "example",
+                    "Generate .dev.vars.example from wrangler vars and env.* references in src/",
+                ),
+                (
+                    "pull",
+                    "Create or update .dev.vars from the example, prompting for missing values",
+                ),
+                (
+                    "sync <PROJECT> <ENV>",
+                    "Copy top-level vars and bindings into [env.<ENV>], which doesn't inherit them",
+                ),
+                (
+                    "diff <PROJECT> <ENV> <ENV>",
+                    "List the vars and secret names one environment has and the other lacks",
+                ),
+                ("[PROJECT]", "Specific project (defaults to all Worker projects)"),
+            ],
+            options: vec![],
+            examples: vec![
+                "moonflare env example              # Refresh every .dev.vars.example",
+                "moonflare env pull                 # Fill in .dev.vars for all projects",
+                "moonflare env pull api             # Fill in .dev.vars for 'api' only",
+                "moonflare env sync api staging     # Give [env.staging] the top-level bindings",
+                "moonflare env diff api staging production # Keys set in only one of them",
+            ],
+            notes: Some((
+                "Variables",
+                vec![
+                    "Wrangler 'vars' are copied with their configured values",
+                    "Other env.NAME references that aren't bindings are treated as secrets",
+                    "Secrets are read from the [secrets] provider when one is configured, otherwise left blank",
+                    "Existing .dev.vars values are never overwritten",
+                    "Deploys to an environment warn about vars and bindings it doesn't declare",
+                    "diff compares secret names only, from the [secrets] provider or 'wrangler secret list', and fails on any difference",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_secrets_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "secrets",
+            description: "Push Worker secrets from a secret provider",
+            usage: "moonflare secrets push <PROJECT> [--env <ENV>]",
+            arguments: vec![
+                (
+                    "push",
+                    "Upload the secrets a Worker references with 'wrangler secret bulk'",
+                ),
+                ("<PROJECT>", "Worker project to push secrets to"),
+            ],
+            options: vec![("--env <ENV>", "Wrangler environment to push to")],
+            examples: vec![
+                "moonflare secrets push api                  # Top-level (production) Worker",
+                "moonflare secrets push api --env staging    # The staging environment",
+            ],
+            notes: Some((
+                "Providers",
+                vec![
+                    "Set provider = \"1password\", \"vault\" or \"doppler\" under [secrets] in moonflare.toml",
+                    "Uses the op, vault or doppler CLI, which must already be signed in",
+                    "{project} and {env} in provider settings become the project and environment",
+                    "Values are piped to wrangler and never written to disk",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_repro_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "repro",
+            description: "Pack or restore a sanitized workspace for a bug report",
+            usage: "moonflare repro pack [--output <FILE>] | moonflare repro unpack <ARCHIVE> [--into <DIR>]",
+            arguments: vec![
+                (
+                    "pack",
+                    "Archive the workspace's configuration, manifests, history and tool versions",
+                ),
+                ("unpack <ARCHIVE>", "Restore an archive and show the environment it came from"),
+            ],
+            options: vec![
+                (
+                    "--output <FILE>",
+                    "Archive to write (default: moonflare-repro-<timestamp>.tar.gz)",
+                ),
+                ("--into <DIR>", "Directory to restore into (default: the archive's name)"),
+            ],
+            examples: vec![
+                "moonflare repro pack                                # Attach the archive to the issue",
+                "moonflare repro unpack moonflare-repro-20250101-120000.tar.gz",
+            ],
+            notes: Some((
+                "What's Packed",
+                vec![
+                    "moon.yml, package.json, Cargo.toml, Wrangler, tsconfig and framework configs in every project",
+                    "moonflare.toml, .moon settings, the deployment and build manifests and the command history",
+                    "Sources, .dev.vars, .env files, node_modules and build output are never packed",
+                    "Values of keys like token, secret, password or webhook are replaced with <redacted>",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_token_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "token",
+            description: "Work out the Cloudflare API token the workspace needs, and who you're logged in as",
+            usage: "moonflare token <scopes|whoami|login> [OPTIONS]",
+            arguments: vec![
+                (
+                    "scopes",
+                    "List the permissions deploying and managing the workspace needs, and why",
+                ),
+                (
+                    "whoami",
+                    "Show the Cloudflare user, accounts and scopes the credentials reach",
+                ),
+                (
+                    "login",
+                    "Run wrangler login and forget the cached account",
+                ),
+            ],
+            options: vec![
+                ("--json", "Print the permissions or account details as JSON"),
+                ("--refresh", "whoami: ask Wrangler again instead of using the cache"),
+            ],
+            examples: vec![
+                "moonflare token scopes            # Permissions and a prefilled token link",
+                "moonflare token scopes --json     # For scripts provisioning tokens",
+                "moonflare token whoami            # Account IDs the credentials reach",
+                "moonflare token login             # Log in and refresh the cached account",
+            ],
+            notes: Some((
+                "What Counts",
+                vec![
+                    "Every Worker needs Workers Scripts Edit; KV, D1, R2, Queues, Vectorize and Hyperdrive bindings add theirs",
+                    "Routes need Workers Routes Edit on their zones, custom domains DNS Edit as well",
+                    "pages and r2-static deploy strategies, Access apps and Workers Builds triggers add their permissions",
+                    "Account Settings Read is only needed while account_id and CLOUDFLARE_ACCOUNT_ID are unset",
+                    "wrangler whoami's answer is cached for 5 minutes in ~/.config/moonflare/auth.json, and dropped when the token or login changes",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_builds_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "builds",
+            description: "Build and deploy projects in Cloudflare with Workers Builds",
+            usage: "moonflare builds setup [PROJECT] [--env <ENV>] [--branch <BRANCH>]",
+            arguments: vec![(
+                "setup [PROJECT]",
+                "Connect the repository and create a build trigger per deployable project",
+            )],
+            options: vec![
+                ("--env <ENV>", "Environment the trigger deploys to"),
+                (
+                    "--branch <BRANCH>",
+                    "Branch whose pushes build automatically (defaults to the current branch)",
+                ),
+            ],
+            examples: vec![
+                "moonflare builds setup                         # Every deployable project",
+                "moonflare builds setup api --env staging       # Deploys api to staging",
+                "moonflare deploy api --build-remote            # Build the pushed commit in Cloudflare",
+            ],
+            notes: Some((
+                "How It Works",
+                vec![
+                    "Triggers build with 'moon run <project>:build' and deploy with 'moon run <project>:deploy'",
+                    "Pushes touching the project, crates or shared packages build automatically",
+                    "The origin remote must be on GitHub or GitLab; the Worker must have been deployed once",
+                    "Needs CLOUDFLARE_API_TOKEN with Workers Builds Configuration Edit permission",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_bundle_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "bundle",
+            description: "Analyze Worker bundles against their size budgets",
+            usage: "moonflare bundle analyze [PROJECT] [--env <ENV>] [--top <N>] [--json]",
+            arguments: vec![(
+                "analyze [PROJECT]",
+                "Bundle Workers without deploying and list what takes up their size",
+            )],
+            options: vec![
+                ("--env <ENV>", "Environment to bundle for"),
+                (
+                    "--top <N>",
+                    "How many files and packages to list per Worker (default: 10)",
+                ),
+                ("--json", "Print the analysis as JSON"),
+            ],
+            examples: vec![
+                "moonflare bundle analyze                       # Every Worker",
+                "moonflare bundle analyze api --top 20          # The 20 largest contributors to api",
+                "moonflare deploy --analyze-bundle              # Check every bundle before deploying",
+            ],
+            notes: Some((
+                "How It Works",
+                vec![
+                    "Bundles with 'wrangler deploy --dry-run --metafile' and reads esbuild's metafile",
+                    "Files under node_modules are grouped by package",
+                    "Flags contributors over [deploy.bundle] large_input_kb (default: 256) and native binaries",
+                    "Flags Node.js builtins imported without compatibility_flags = [\"nodejs_compat\"]",
+                    "Sizes are before compression; budgets come from [deploy.bundle] budget_kb or [projects.<name>.deploy] bundle_budget_kb",
+                    "Deploys check Workers with a budget first and refuse any that fail; exits non-zero when one would",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_migrate_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "migrate",
+            description: "Migrate projects between Cloudflare products and check database migrations",
+            usage: "moonflare migrate <pages-to-worker|c3|turbo|nx|status> [PROJECT|PATH] [--routing-worker] [--name <NAME>] [--keep-layout] [--force] [--env <ENV>] [--json]",
+            arguments: vec![
+                (
+                    "pages-to-worker <PROJECT>",
+                    "Convert a Pages project to a Worker serving static assets",
+                ),
+                (
+                    "c3 [PATH]",
+                    "Turn a repository created with 'npm create cloudflare' into a workspace, in place",
+                ),
+                (
+                    "turbo|nx [PATH]",
+                    "Turn a Turborepo or Nx workspace into a moonflare workspace, in place",
+                ),
+                (
+                    "status [PROJECT]",
+                    "List applied and pending D1 and Durable Object migrations per environment",
+                ),
+            ],
+            options: vec![
+                (
+                    "--routing-worker",
+                    "Generate src/worker.ts for the routes Pages Functions served",
+                ),
+                (
+                    "--env <ENV>",
+                    "Only this environment in status, or production for the top level (repeatable)",
+                ),
+                ("--json", "Print the migration statuses as JSON"),
+                (
+                    "--name <NAME>",
+                    "Project name for the c3 Worker (defaults to its package.json name)",
+                ),
+                (
+                    "--keep-layout",
+                    "Keep turbo/nx project directories as the [layout] where possible",
+                ),
+                ("--force", "Run c3, turbo or nx even with uncommitted changes"),
+            ],
+            examples: vec![
+                "moonflare migrate c3                                      # The current repository",
+                "moonflare migrate c3 ../my-worker --name api              # Another repository, renamed",
+                "moonflare migrate turbo --keep-layout                     # Turborepo, directories kept",
+                "moonflare migrate nx ../platform                          # Nx workspace",
+                "moonflare migrate pages-to-worker docs                    # Static site",
+                "moonflare migrate pages-to-worker web --routing-worker    # Site with Functions",
+                "moonflare migrate status                                  # Every project and environment",
+                "moonflare migrate status api --env staging                # One project in staging",
+            ],
+            notes: Some((
+                "Conversion",
+                vec![
+                    "pages_build_output_dir becomes assets.directory in the Wrangler configuration",
+                    "_routes.json becomes assets.run_worker_first when a routing Worker is generated",
+                    "_headers and _redirects keep working; a copy at the project root moves to public/",
+                    "'wrangler pages deploy/dev' in package.json and moon.yml become 'wrangler deploy/dev'",
+                    "A snapshot is saved first; 'moonflare restore' undoes the migration",
+                    "c3 moves the Worker under workers/ (or sites/, apps/) with git, keeping its history, and adds root scripts for it",
+                    "turbo and nx write the pipeline into each moon.yml and list what they couldn't translate; shared packages stay put",
+                    "status compares migrations_dir with each remote D1 migrations table, and Durable Object tags with the deployed Worker's (needs CLOUDFLARE_API_TOKEN)",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_meta_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "meta",
+            description: "Build, deploy or inspect several workspaces in one repository",
+            usage: "moonflare meta <build|deploy|status> [--workspace <PATH>] [-- <ARGS>...]",
+            arguments: vec![
+                ("build", "Run 'moonflare build' in every workspace"),
+                ("deploy", "Run 'moonflare deploy' in every workspace"),
+                (
+                    "status",
+                    "Show each workspace's projects and most recent deploys",
+                ),
+            ],
+            options: vec![
+                (
+                    "--workspace <PATH>",
+                    "Only this workspace, by path or directory name (repeatable)",
+                ),
+                ("--env <ENV>", "Environment to deploy to (deploy only)"),
+                ("-- <ARGS>...", "Passed to the command in each workspace"),
+            ],
+            examples: vec![
+                "moonflare meta status",
+                "moonflare meta build --workspace storefront",
+                "moonflare meta deploy --env staging -- --require-clean",
+            ],
+            notes: Some((
+                "Workspaces",
+                vec![
+                    "Run from the repository root",
+                    "Workspaces are listed in moonflare-meta.toml, or found by their .moon/workspace.yml",
+                    "meta deploy --env skips workspaces whose environments list doesn't include it",
+                    "Every workspace runs even when one fails; the summary shows which did",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_access_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "access",
+            description: "Put Cloudflare Access in front of a deployed project",
+            usage: "moonflare access protect <PROJECT> --policy <EMAIL_DOMAIN> [--domain <DOMAIN>]",
+            arguments: vec![
+                (
+                    "protect",
+                    "Create an Access application and an allow policy for the project",
+                ),
+                ("<PROJECT>", "Deployed project to protect"),
+            ],
+            options: vec![
+                (
+                    "--policy <EMAIL_DOMAIN>",
+                    "Allow sign-ins from email addresses at this domain",
+                ),
+                (
+                    "--domain <DOMAIN>",
+                    "Domain to protect instead of the project's route or workers.dev URL",
+                ),
+            ],
+            examples: vec![
+                "moonflare access protect admin --policy example.com",
+                "moonflare access protect docs --policy example.com --domain docs.example.com",
+            ],
+            notes: Some((
+                "Credentials",
+                vec![
+                    "Needs CLOUDFLARE_API_TOKEN with Access: Apps and Policies Edit permission",
+                    "The account comes from CLOUDFLARE_ACCOUNT_ID or the wrangler account_id",
+                    "The application id is recorded in .moonflare/deployments.json",
+                    "'moonflare doctor' checks that protected applications still have policies",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_ai_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "ai",
+            description: "Route a Workers AI project through an AI Gateway",
+            usage: "moonflare ai gateway create <NAME> [--project <PROJECT>] [OPTIONS]",
+            arguments: vec![
+                (
+                    "gateway create",
+                    "Create the gateway, set AI_GATEWAY_ID in the project's vars and pass it to env.AI.run calls",
+                ),
+                ("<NAME>", "Gateway name: lowercase letters, digits and hyphens"),
+            ],
+            options: vec![
+                (
+                    "--project <PROJECT>",
+                    "Project to route (defaults to the only one with an [ai] binding)",
+                ),
+                (
+                    "--cache-ttl <SECONDS>",
+                    "Seconds to cache identical requests, 0 for none (default: 300)",
+                ),
+                (
+                    "--rate-limit <REQUESTS>",
+                    "Allow at most this many requests per interval",
+                ),
+                (
+                    "--rate-limit-interval <SECONDS>",
+                    "Rate limit interval (default: 60)",
+                ),
+                ("--no-logs", "Don't log requests in the gateway"),
+            ],
+            examples: vec![
+                "moonflare ai gateway create rag-gateway",
+                "moonflare ai gateway create rag-gateway --project rag-api --cache-ttl 3600 --rate-limit 100",
+            ],
+            notes: Some((
+                "Credentials",
+                vec![
+                    "Needs CLOUDFLARE_API_TOKEN with AI Gateway Edit permission",
+                    "The account comes from CLOUDFLARE_ACCOUNT_ID or the wrangler account_id",
+                    "Calls passing their own options are listed to be routed by hand",
+                    "'moonflare restore' brings back the files as they were before",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_restore_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "restore",
+            description: "Restore files from a workspace snapshot",
+            usage: "moonflare restore [SNAPSHOT]",
+            arguments: vec![(
+                "[SNAPSHOT]",
+                "Snapshot id to restore, or 'latest' (lists snapshots when omitted)",
+            )],
+            options: vec![],
+            examples: vec![
+                "moonflare restore                  # List available snapshots",
+                "moonflare restore latest           # Undo the most recent operation",
+                "moonflare restore 20240101-120000  # Restore a specific snapshot",
+            ],
+            notes: Some((
+                "Snapshots",
+                vec![
+                    "Destructive operations such as rename save affected files to .moonflare/backups/<timestamp>",
+                    "Restoring first snapshots the current state, so a restore can be undone too",
+                    "node_modules, target and other build output are not included",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_upgrade_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "upgrade",
+            description: "Bring the workspace across breaking changes in moonflare's templates and tooling, one change at a time",
+            usage: "moonflare upgrade [--apply-recorded]",
+            arguments: vec![],
+            options: vec![(
+                "--apply-recorded",
+                "Replay the decisions in .moonflare/upgrades.json without asking",
+            )],
+            examples: vec![
+                "moonflare upgrade                   # Review each change and apply or skip it",
+                "moonflare upgrade --plan            # Show every change without asking",
+                "moonflare upgrade --apply-recorded  # Replay recorded decisions in CI",
+            ],
+            notes: Some((
+                "Changes",
+                vec![
+                    "moonflare.toml config version, Wrangler major versions and deprecated Wrangler keys",
+                    "Moon's project 'type' renamed to 'layer', and changed task inheritance files",
+                    "Each answer is recorded in .moonflare/upgrades.json; commit it for CI to replay",
+                    "A change without a recorded decision fails --apply-recorded before anything is written",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_verify_matrix_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "verify-matrix",
+            description: "Build a fresh copy of the workspace with every combination of tool versions and report which pass",
+            usage: "moonflare verify-matrix [--node <v>]... [--wrangler <v>]... [--moon <v>]... [OPTIONS]",
+            arguments: vec![],
+            options: vec![
+                ("--node <VERSION>", "Node.js version to build with (repeatable)"),
+                ("--wrangler <VERSION>", "Wrangler version to build with (repeatable)"),
+                ("--moon <VERSION>", "Moon version to build with (repeatable)"),
+                ("--task <TASK>", "Moon task to run in each combination (build by default)"),
+                ("--keep", "Keep each combination's copy of the workspace"),
+                ("--json", "Print the results as JSON"),
+            ],
+            examples: vec![
+                "moonflare verify-matrix                                # The versions under [matrix]",
+                "moonflare verify-matrix --node 20 --node 22            # Two Node.js versions",
+                "moonflare verify-matrix --wrangler 3 --wrangler 4 --task test",
+            ],
+            notes: Some((
+                "How It Runs",
+                vec![
+                    "Each combination builds a copy without node_modules, build output or Moon's cache",
+                    "Node.js and Moon are installed with proto and picked with PROTO_NODE_VERSION and PROTO_MOON_VERSION",
+                    "Wrangler is forced on every project with a pnpm override",
+                    "A tool without versions stays on the one the workspace pins; each log is under .moonflare/matrix",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_preview_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "preview",
+            description: "Build a project for production and serve the built artifacts locally",
+            usage: "moonflare preview <PROJECT> [--port <PORT>]",
+            arguments: vec![("<PROJECT>", "Project to build and serve")],
+            options: vec![("--port <PORT>", "Port for the preview server")],
+            examples: vec![
+                "moonflare preview api                # Build, then wrangler dev --local",
+                "moonflare preview docs --port 4321   # Serve a static site on port 4321",
+            ],
+            notes: Some((
+                "Servers",
+                vec![
+                    "Projects with a wrangler config run under workerd via 'wrangler dev --local'",
+                    "Pure-static projects use their 'preview' script, or 'vite preview'",
+                    "Nothing is rebuilt on change; rerun preview after editing code",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_flags_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "flags",
+            description: "Manage workspace feature flags served from KV",
+            usage: "moonflare flags <init|set> [FLAG VALUE] [--env <ENV>] [--local]",
+            arguments: vec![
+                (
+                    "init",
+                    "Create flags.toml and the flags worker, and regenerate packages/flags",
+                ),
+                ("set <FLAG> <VALUE>", "Store a flag's value in the FLAGS KV namespace"),
+            ],
+            options: vec![
+                ("--env <ENV>", "Set the value for this deploy environment"),
+                ("--local", "Set the value in Wrangler's local state, for 'moonflare dev'"),
+            ],
+            examples: vec![
+                "moonflare flags init                          # Scaffold flags, or regenerate the client",
+                "moonflare flags set new-checkout true --env staging",
+                "moonflare flags set new-checkout false --local",
+            ],
+            notes: Some((
+                "Flags",
+                vec![
+                    "Flags and their defaults live in flags.toml; the default's type is the flag's type",
+                    "Projects import the typed client from '@packages/flags'",
+                    "Workers read flags through a service binding to the flags worker",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_seed_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "seed",
+            description: "Load seed data from projects' seeds/ directories into D1, KV and Workers",
+            usage: "moonflare seed [PROJECT] [--env <ENV>] [--remote]",
+            arguments: vec![(
+                "[PROJECT]",
+                "Project to seed (all projects with seeds/ when omitted)",
+            )],
+            options: vec![
+                ("--env <ENV>", "Seed the remote resources of this environment"),
+                (
+                    "--remote",
+                    "Seed remote resources instead of Wrangler's local state",
+                ),
+            ],
+            examples: vec![
+                "moonflare seed                       # Seed every project's local dev data",
+                "moonflare seed api --env preview     # Seed the preview environment",
+                "moonflare dev --fresh                # Start dev from freshly seeded data",
+            ],
+            notes: Some((
+                "Seed files",
+                vec![
+                    "seeds/*.sql runs with 'wrangler d1 execute' against the project's D1 database",
+                    "seeds/*.json is written with 'wrangler kv bulk put' to the project's KV namespace",
+                    "seeds/<BINDING>/ picks the binding when a project has several",
+                    "seeds/*.mjs, *.js and *.ts scripts run with SEED_URL set, e.g. to fill Durable Objects",
+                    "Files run in name order; protected environments are never seeded",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_sbom_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "sbom",
+            description: "Generate a CycloneDX SBOM of a project's npm and cargo dependencies",
+            usage: "moonflare sbom <PROJECT> [--output <PATH>]",
+            arguments: vec![("<PROJECT>", "Project to describe")],
+            options: vec![(
+                "--output <PATH>",
+                "Write the SBOM here instead of .moonflare/sbom/<project>.cdx.json",
+            )],
+            examples: vec![
+                "moonflare sbom api                      # Write .moonflare/sbom/api.cdx.json",
+                "moonflare sbom api --output sbom.json   # Write to a custom path",
+                "moonflare deploy --sbom                 # Record SBOM hashes with each deploy",
+            ],
+            notes: Some((
+                "Sources",
+                vec![
+                    "npm versions are resolved from pnpm-lock.yaml, falling back to package.json",
+                    "cargo dependencies are read from the project's Cargo.lock",
+                    "devDependencies are listed with scope 'excluded' since they don't ship",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_examples_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "examples",
+            description: "Browse and add runnable examples",
+            usage: "moonflare examples <list|add> [EXAMPLE]",
+            arguments: vec![
+                ("list", "Show the examples and the projects each one adds"),
+                ("add <EXAMPLE>", "Add an example's projects to the workspace"),
+            ],
+            options: vec![],
+            examples: vec![
+                "moonflare examples list            # See what's available",
+                "moonflare examples add chat        # WebSocket chat: workers/chat-api + apps/chat-web",
+                "moonflare examples add rag         # Workers AI + Vectorize question answering",
+            ],
+            notes: Some((
+                "Wiring",
+                vec![
+                    "Examples go into the workspace's layout directories like 'moonflare add' projects",
+                    "Service bindings between an example's projects use their deployed worker names",
+                    "Resources such as R2 buckets or Vectorize indexes are listed as next steps",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_ports_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "ports",
+            description: "Show dev port assignments and conflicts, and release reserved ports",
+            usage: "moonflare ports [list|free <PROJECT>]",
+            arguments: vec![
+                (
+                    "list",
+                    "Each project's dev port, reservations and conflicts (the default)",
+                ),
+                (
+                    "free <PROJECT>",
+                    "Release the project's reserved port so it goes back to its usual one",
+                ),
+            ],
+            options: vec![],
+            examples: vec![
+                "moonflare ports                    # Which project listens where",
+                "moonflare ports free web           # Send 'web' back to port 4321",
+            ],
+            notes: Some((
+                "Reservations",
+                vec![
+                    "'moonflare dev' moves a dev server whose port is taken to the next free port",
+                    "The project keeps that port in .moonflare/ports.json until it's released",
+                    "Frontends calling a moved Worker need its new port, shown by 'moonflare ports'",
+                ],
+            )),
+        })
+    }
+
+    pub fn render_self_help(&self) -> Result<(), ConsoleError> {
+        self.render_help_page(HelpPage {
+            command: "self",
+            description: "Manage the moonflare installation",
+            usage: "moonflare self update [--check] [--target <OS-ARCH>]",
+            arguments: vec![(
+                "update",
+                "Download, verify and install the latest moonflare release",
+            )],
+            options: vec![
+                ("--check", "Only report whether a newer version is available"),
+                (
+                    "--target <OS-ARCH>",
+                    "Platform to download for, e.g. linux-arm64 (or MOONFLARE_TARGET)",
+                ),
+            ],
+            examples: vec![
+                "moonflare self update              # Upgrade to the latest release",
+                "moonflare self update --check      # Check for a newer release",
+                "moonflare self update --target darwin-arm64  # Force the native Apple silicon build",
+            ],
+            notes: Some((
+                "Workspace Versions",
+                vec![
+                    "Workspaces record the moonflare version that created them in moonflare.toml",
+                    "Older binaries refuse to operate on newer workspaces until upgraded",
+                ],
+            )),
         })
     }
 }
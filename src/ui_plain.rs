@@ -0,0 +1,120 @@
+use colored::Colorize;
+
+use crate::templates::registry::PROJECT_KINDS;
+use crate::ui_shared::output_profile;
+pub use crate::ui_shared::{OutputProfile, set_output_profile};
+
+/// Matches the boxed UI's signatures; plain printing never returns it
+pub type ConsoleError = std::io::Error;
+
+pub struct MoonflareUI {
+    profile: OutputProfile,
+}
+
+impl MoonflareUI {
+    pub fn new() -> Self {
+        Self {
+            profile: output_profile(),
+        }
+    }
+
+    pub fn render_header(
+        &self,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<(), ConsoleError> {
+        if self.profile == OutputProfile::Minimal {
+            return Ok(());
+        }
+        match description {
+            Some(desc) => println!("{} {}", title.cyan().bold(), desc.dimmed()),
+            None => println!("{}", title.cyan().bold()),
+        }
+        Ok(())
+    }
+
+    pub fn render_success(&self, message: &str) -> Result<(), ConsoleError> {
+        println!("{} {}", "✓".green(), message);
+        Ok(())
+    }
+
+    pub fn render_section_start(&self, title: &str) -> Result<(), ConsoleError> {
+        if self.profile != OutputProfile::Minimal {
+            println!("{}", title.bold());
+        }
+        Ok(())
+    }
+
+    pub fn render_project_types(&self) -> Result<(), ConsoleError> {
+        let names: Vec<&str> = PROJECT_KINDS.iter().map(|kind| kind.name).collect();
+        self.compact_line("Available project types", &names);
+        Ok(())
+    }
+
+    pub fn render_next_steps(&self, steps: Vec<&str>) -> Result<(), ConsoleError> {
+        let title = if steps.len() == 1 {
+            "Next step"
+        } else {
+            "Next steps"
+        };
+        self.compact_line(title, &steps);
+        Ok(())
+    }
+
+    pub fn render_next_alternatives(&self, alternatives: Vec<&str>) -> Result<(), ConsoleError> {
+        let title = if alternatives.len() == 1 {
+            "Next step"
+        } else {
+            "Next steps (choose one)"
+        };
+        self.compact_line(title, &alternatives);
+        Ok(())
+    }
+
+    pub fn render_checklist(
+        &self,
+        title: &str,
+        steps: Vec<(bool, &str, &str)>,
+    ) -> Result<(), ConsoleError> {
+        let items: Vec<String> = steps
+            .iter()
+            .map(|(done, step, _)| format!("{} {}", if *done { "✓" } else { "○" }, step))
+            .collect();
+        let item_refs: Vec<&str> = items.iter().map(String::as_str).collect();
+        self.compact_line(title, &item_refs);
+        Ok(())
+    }
+
+    pub fn render_workspace_structure(&self) -> Result<(), ConsoleError> {
+        self.compact_line(
+            "Workspace structure",
+            &["apps/", "sites/", "workers/", "crates/", ".moon/"],
+        );
+        Ok(())
+    }
+
+    pub fn render_error(
+        &self,
+        title: &str,
+        message: &str,
+        suggestions: Vec<&str>,
+    ) -> Result<(), ConsoleError> {
+        eprintln!("{} {}: {}", "✗".red(), title.red().bold(), message);
+        if self.profile != OutputProfile::Minimal && !suggestions.is_empty() {
+            eprintln!("  {} {}", "Try:".dimmed(), suggestions.join(" · "));
+        }
+        Ok(())
+    }
+
+    fn compact_line(&self, title: &str, items: &[&str]) {
+        if self.profile != OutputProfile::Minimal {
+            println!("{} {}", format!("{}:", title).bold(), items.join(" · "));
+        }
+    }
+}
+
+impl Default for MoonflareUI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
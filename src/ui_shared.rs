@@ -0,0 +1,104 @@
+use crate::ui::{ConsoleError, MoonflareUI};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// How much the UI prints around a command's own output, selected with `--ui` or
+/// `[ui] profile` in moonflare.toml. Help pages always render in full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputProfile {
+    /// Boxed headers, sections and next steps
+    #[default]
+    Full,
+    /// Every header, section and list collapsed onto a single line
+    Compact,
+    /// Only results and errors
+    Minimal,
+}
+
+static OUTPUT_PROFILE: OnceLock<OutputProfile> = OnceLock::new();
+
+/// Use `profile` for every `MoonflareUI` created from now on
+pub fn set_output_profile(profile: OutputProfile) {
+    let _ = OUTPUT_PROFILE.set(profile);
+}
+
+/// The profile `MoonflareUI::new` starts with. Without the `ui` feature there is nothing to
+/// box output with, so the full profile prints like the compact one.
+pub fn output_profile() -> OutputProfile {
+    match OUTPUT_PROFILE.get().copied().unwrap_or_default() {
+        OutputProfile::Full if cfg!(not(feature = "ui")) => OutputProfile::Compact,
+        profile => profile,
+    }
+}
+
+impl MoonflareUI {
+    pub fn render_next_steps_for_project(
+        &self,
+        project_name: &str,
+        project_type: &str,
+    ) -> Result<(), ConsoleError> {
+        match project_type {
+            "astro" | "react" | "durable-object" => {
+                let dev_cmd = format!("moonflare dev {}", project_name);
+                let build_cmd = format!("moonflare build {}", project_name);
+                let deploy_cmd = format!("moonflare deploy {}", project_name);
+                let alternatives = vec![dev_cmd.as_str(), build_cmd.as_str(), deploy_cmd.as_str()];
+                self.render_next_alternatives(alternatives)
+            }
+            "maintenance-bot" => {
+                let config = format!("workers/{}/wrangler.toml", project_name);
+                let token_cmd = format!(
+                    "wrangler secret put CLOUDFLARE_API_TOKEN --config {}",
+                    config
+                );
+                let webhook_cmd =
+                    format!("wrangler secret put ALERT_WEBHOOK_URL --config {}", config);
+                let deploy_cmd = format!("moonflare deploy {}", project_name);
+                self.render_next_steps(vec![
+                    token_cmd.as_str(),
+                    webhook_cmd.as_str(),
+                    deploy_cmd.as_str(),
+                ])
+            }
+            "smoke-tests" => {
+                let config = format!("tests/{}/smoke.config.json", project_name);
+                let edit = format!("Add checks for your endpoints to {}", config);
+                self.render_next_steps(vec![
+                    "Set url under [projects.<name>] in moonflare.toml for projects without one",
+                    edit.as_str(),
+                    "moonflare deploy --smoke",
+                ])
+            }
+            "e2e" => {
+                let install = format!(
+                    "pnpm install && pnpm --dir tests/{} install-browsers",
+                    project_name
+                );
+                let specs = format!("Write specs for your pages in tests/{}/specs", project_name);
+                self.render_next_steps(vec![
+                    install.as_str(),
+                    specs.as_str(),
+                    "moonflare dev, then moonflare test --e2e",
+                ])
+            }
+            "container" => {
+                let dev_cmd = format!(
+                    "moonflare dev {}  # Wrangler builds and runs the image with Docker",
+                    project_name
+                );
+                let deploy_cmd = format!("moonflare deploy {}", project_name);
+                self.render_next_steps(vec!["pnpm install", dev_cmd.as_str(), deploy_cmd.as_str()])
+            }
+            "crate" => {
+                let build_cmd = format!("moonflare build {}", project_name);
+                let alternatives = vec![
+                    build_cmd.as_str(),
+                    "moonflare build  # Build all projects to generate WASM",
+                ];
+                self.render_next_alternatives(alternatives)
+            }
+            _ => Ok(()),
+        }
+    }
+}
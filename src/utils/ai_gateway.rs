@@ -0,0 +1,189 @@
+use anyhow::{Result, bail};
+use regex::Regex;
+use serde_json::Value;
+
+/// Var holding the gateway ID that rewritten `AI.run` calls route through
+pub const GATEWAY_VAR: &str = "AI_GATEWAY_ID";
+
+/// An `AI.run` call `route_calls` left alone because its arguments aren't
+/// `(model, inputs)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedCall {
+    pub line: usize,
+}
+
+/// Gateway IDs are lowercase letters, digits and hyphens, at most 64 characters
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        bail!(
+            "'{}' isn't a valid gateway name. Use lowercase letters, digits and hyphens, at most 64 characters",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Name of the Workers AI binding in the `[ai]` section
+pub fn ai_binding(config: &Value) -> Option<&str> {
+    config.get("ai")?.get("binding")?.as_str()
+}
+
+/// Set the gateway var at the top level and in every environment, since Wrangler
+/// environments don't inherit vars
+pub fn set_gateway_var(config: &mut Value, id: &str) {
+    set_var(config, id);
+    if let Some(envs) = config.get_mut("env").and_then(Value::as_object_mut) {
+        for env in envs.values_mut().filter(|env| env.is_object()) {
+            set_var(env, id);
+        }
+    }
+}
+
+fn set_var(table: &mut Value, id: &str) {
+    let vars = table
+        .as_object_mut()
+        .expect("config tables are objects")
+        .entry("vars")
+        .or_insert_with(|| Value::Object(Default::default()));
+    if let Some(vars) = vars.as_object_mut() {
+        vars.insert(GATEWAY_VAR.to_string(), Value::String(id.to_string()));
+    }
+}
+
+/// Pass `{ gateway: { id: env.AI_GATEWAY_ID } }` as the options of every
+/// `env.<binding>.run(model, inputs)` call in `content`. Calls already naming a gateway
+/// are left as they are; calls with other arguments are returned as skipped.
+pub fn route_calls(content: &str, binding: &str) -> (String, Vec<SkippedCall>) {
+    let call = Regex::new(&format!(
+        r"\b([A-Za-z_$][\w$]*(?:\.[A-Za-z_$][\w$]*)*)\.{}\.run\s*\(",
+        regex::escape(binding)
+    ))
+    .expect("valid regex");
+
+    let mut output = String::with_capacity(content.len());
+    let mut skipped = Vec::new();
+    let mut copied = 0;
+    for caps in call.captures_iter(content) {
+        let whole = caps.get(0).expect("match");
+        if whole.start() < copied {
+            continue;
+        }
+        let Some(arguments) = call_arguments(content, whole.end()) else {
+            continue;
+        };
+        let text = &content[whole.end()..arguments.close];
+        if text.contains("gateway") {
+            continue;
+        }
+        if arguments.separators != 1 {
+            skipped.push(SkippedCall {
+                line: content[..whole.start()].lines().count().max(1),
+            });
+            continue;
+        }
+
+        let insert_at = whole.end() + text.trim_end().len();
+        let options = format!("{{ gateway: {{ id: {}.{} }} }}", &caps[1], GATEWAY_VAR);
+        output.push_str(&content[copied..insert_at]);
+        if text.trim_end().ends_with(',') {
+            output.push_str(&format!(" {},", options));
+        } else {
+            output.push_str(&format!(", {}", options));
+        }
+        copied = insert_at;
+    }
+    output.push_str(&content[copied..]);
+    (output, skipped)
+}
+
+/// Declare the gateway var next to the binding in the `Env` interface
+pub fn declare_var(content: &str, binding: &str) -> Option<String> {
+    if content.contains(&format!("{}:", GATEWAY_VAR)) {
+        return None;
+    }
+    let declaration = Regex::new(&format!(
+        r"(?m)^([ \t]*){}\s*:\s*Ai\s*;[ \t]*$",
+        regex::escape(binding)
+    ))
+    .expect("valid regex");
+    let caps = declaration.captures(content)?;
+    let end = caps.get(0).expect("match").end();
+    Some(format!(
+        "{}\n{}{}: string;{}",
+        &content[..end],
+        &caps[1],
+        GATEWAY_VAR,
+        &content[end..]
+    ))
+}
+
+struct Arguments {
+    /// Offset of the closing parenthesis
+    close: usize,
+    /// Top-level commas, not counting a trailing one
+    separators: usize,
+}
+
+/// The argument list opened just before `start`, skipping strings, template literals and
+/// comments
+fn call_arguments(content: &str, start: usize) -> Option<Arguments> {
+    let bytes = content.as_bytes();
+    let mut depth = 0usize;
+    let mut separators = 0;
+    let mut trailing = false;
+    let mut index = start;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        match byte {
+            b'"' | b'\'' | b'`' => {
+                index += 1;
+                while index < bytes.len() && bytes[index] != byte {
+                    if bytes[index] == b'\\' {
+                        index += 1;
+                    }
+                    index += 1;
+                }
+                trailing = false;
+            }
+            b'/' if bytes.get(index + 1) == Some(&b'/') => {
+                while index < bytes.len() && bytes[index] != b'\n' {
+                    index += 1;
+                }
+                continue;
+            }
+            b'/' if bytes.get(index + 1) == Some(&b'*') => {
+                index = content[index + 2..]
+                    .find("*/")
+                    .map(|end| index + 2 + end + 1)?;
+            }
+            b'(' | b'[' | b'{' => {
+                depth += 1;
+                trailing = false;
+            }
+            b')' | b']' | b'}' if depth > 0 => {
+                depth -= 1;
+                trailing = false;
+            }
+            b')' => {
+                return Some(Arguments {
+                    close: index,
+                    separators: separators - usize::from(trailing),
+                });
+            }
+            b',' if depth == 0 => {
+                separators += 1;
+                trailing = true;
+            }
+            byte if byte.is_ascii_whitespace() => {}
+            _ => trailing = false,
+        }
+        index += 1;
+    }
+    None
+}
@@ -0,0 +1,174 @@
+use crate::utils::fs::{get_typescript_projects, has_crates, path_to_root};
+use crate::utils::wrangler::strip_jsonc;
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Import prefix for the WASM modules shared-wasm:gather collects
+pub const WASM_ALIAS: &str = "@wasm";
+/// Import prefix for the workspace's shared TypeScript packages
+pub const PACKAGES_ALIAS: &str = "@packages";
+
+/// Bundler configurations with a managed alias block
+const BUNDLER_CONFIGS: [&str; 2] = ["vite.config.ts", "astro.config.mjs"];
+const ALIASES_START: &str = "// moonflare:aliases:start";
+const ALIASES_END: &str = "// moonflare:aliases:end";
+
+/// Aliases the workspace currently needs, with the root directory each points at
+pub fn workspace_aliases() -> Vec<(&'static str, &'static str)> {
+    let mut aliases = Vec::new();
+    if has_crates() {
+        aliases.push((WASM_ALIAS, "shared-wasm"));
+    }
+    if has_packages() {
+        aliases.push((PACKAGES_ALIAS, "packages"));
+    }
+    aliases
+}
+
+fn has_packages() -> bool {
+    fs::read_dir("packages")
+        .map(|entries| entries.flatten().any(|entry| entry.path().is_dir()))
+        .unwrap_or(false)
+}
+
+/// Bring every TypeScript project's tsconfig `paths` and bundler aliases in line with the
+/// workspace, returning the files that changed. Aliases whose directory is gone are dropped.
+pub fn sync_aliases() -> Result<Vec<PathBuf>> {
+    let aliases = workspace_aliases();
+    let mut updated = Vec::new();
+    for project in get_typescript_projects() {
+        if let Some(tsconfig) = tsconfig_path(&project)
+            && sync_tsconfig(&tsconfig, &project, &aliases)?
+        {
+            updated.push(tsconfig);
+        }
+        for file in BUNDLER_CONFIGS {
+            let path = project.join(file);
+            if path.exists() && sync_bundler_config(&path, &project, &aliases)? {
+                updated.push(path);
+            }
+        }
+    }
+    Ok(updated)
+}
+
+/// Every tsconfig and bundler config [`sync_aliases`] may rewrite, for commands that snapshot
+/// them first
+pub fn alias_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for project in get_typescript_projects() {
+        files.extend(tsconfig_path(&project));
+        files.extend(
+            BUNDLER_CONFIGS
+                .iter()
+                .map(|file| project.join(file))
+                .filter(|path| path.exists()),
+        );
+    }
+    files
+}
+
+/// The tsconfig holding the project's compiler options; Vite apps keep theirs in tsconfig.app.json
+fn tsconfig_path(project: &Path) -> Option<PathBuf> {
+    ["tsconfig.app.json", "tsconfig.json"]
+        .iter()
+        .map(|file| project.join(file))
+        .find(|path| path.exists())
+}
+
+fn sync_tsconfig(path: &Path, project: &Path, aliases: &[(&str, &str)]) -> Result<bool> {
+    let content = fs::read_to_string(path)?;
+    let mut tsconfig: Value = serde_json::from_str(&strip_jsonc(&content))
+        .with_context(|| format!("Invalid JSON in {}", path.display()))?;
+    let Some(root) = tsconfig.as_object_mut() else {
+        return Ok(false);
+    };
+    let options = root
+        .entry("compilerOptions")
+        .or_insert_with(|| Value::Object(Map::new()));
+    let Some(options) = options.as_object_mut() else {
+        return Ok(false);
+    };
+
+    // `paths` resolve against baseUrl when it's set, otherwise against the tsconfig itself
+    let base_depth = options
+        .get("baseUrl")
+        .and_then(|base| base.as_str())
+        .map_or(0, |base| {
+            Path::new(base)
+                .components()
+                .filter(|c| matches!(c, Component::Normal(_)))
+                .count()
+        });
+    let up = "../".repeat(project.components().count() + base_depth);
+
+    let mut paths = options
+        .get("paths")
+        .and_then(|paths| paths.as_object())
+        .cloned()
+        .unwrap_or_default();
+    let before = paths.clone();
+    for alias in [WASM_ALIAS, PACKAGES_ALIAS] {
+        paths.remove(&format!("{}/*", alias));
+    }
+    for (alias, directory) in aliases {
+        paths.insert(
+            format!("{}/*", alias),
+            Value::Array(vec![Value::String(format!("{}{}/*", up, directory))]),
+        );
+    }
+    if paths == before {
+        return Ok(false);
+    }
+
+    if paths.is_empty() {
+        options.remove("paths");
+    } else {
+        options.insert("paths".to_string(), Value::Object(paths));
+    }
+    fs::write(path, serde_json::to_string_pretty(&tsconfig)? + "\n")?;
+    Ok(true)
+}
+
+/// Rewrite the lines between the alias markers. Configurations without the markers were
+/// customised, so they're left for the user to update.
+fn sync_bundler_config(path: &Path, project: &Path, aliases: &[(&str, &str)]) -> Result<bool> {
+    let content = fs::read_to_string(path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let (Some(start), Some(end)) = (
+        lines.iter().position(|l| l.trim() == ALIASES_START),
+        lines.iter().position(|l| l.trim() == ALIASES_END),
+    ) else {
+        return Ok(false);
+    };
+    if end < start {
+        return Ok(false);
+    }
+
+    let indent = &lines[start][..lines[start].len() - lines[start].trim_start().len()];
+    let root = path_to_root(project);
+    let block: Vec<String> = aliases
+        .iter()
+        .map(|(alias, directory)| {
+            format!(
+                "{}'{}': fileURLToPath(new URL('{}/{}', import.meta.url)),",
+                indent, alias, root, directory
+            )
+        })
+        .collect();
+    if lines[start + 1..end]
+        .iter()
+        .copied()
+        .eq(block.iter().map(String::as_str))
+    {
+        return Ok(false);
+    }
+
+    let mut updated: Vec<String> = lines[..=start].iter().map(|l| l.to_string()).collect();
+    updated.extend(block);
+    updated.extend(lines[end..].iter().map(|l| l.to_string()));
+    fs::write(path, updated.join("\n") + "\n")?;
+    Ok(true)
+}
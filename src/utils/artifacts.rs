@@ -0,0 +1,339 @@
+use crate::utils::config::LayoutConfig;
+use crate::utils::platform;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::sbom::sha256_hex;
+use crate::utils::vcs::current_commit;
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+/// What `moonflare build` produced, read back by `moonflare deploy --from-artifacts`
+pub const BUILD_MANIFEST_FILE: &str = ".moonflare/build-manifest.json";
+
+/// Directories never hashed as sources when the workspace isn't a git checkout
+const UNTRACKED_DIRS: [&str; 7] = [
+    "node_modules",
+    "target",
+    "dist",
+    ".wrangler",
+    ".astro",
+    ".moon",
+    "pkg",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildManifest {
+    #[serde(default)]
+    pub projects: BTreeMap<String, ArtifactRecord>,
+}
+
+/// One project's build output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactRecord {
+    pub built_at: String,
+    pub git_sha: Option<String>,
+    /// Workspace-relative directory the build wrote
+    pub output: String,
+    /// SHA-256 over the project's sources and the workspace's crates when it was built
+    pub source_sha256: String,
+    /// Output files, relative to `output`, and their SHA-256
+    pub files: BTreeMap<String, String>,
+    /// Environment whose `[env.<name>]` vars the build saw; none for the default environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+}
+
+impl ArtifactRecord {
+    /// Refuse output built for another environment, whose vars a frontend bundles
+    pub fn check_environment(&self, project: &str, environment: Option<&str>) -> Result<()> {
+        if self.environment.as_deref() == environment {
+            return Ok(());
+        }
+        bail!(
+            "Artifacts for '{}' were built for {}, not {}. Rebuild them with 'moonflare build {}{}'.",
+            project,
+            describe_environment(self.environment.as_deref()),
+            describe_environment(environment),
+            project,
+            environment
+                .map(|environment| format!(" --env {}", environment))
+                .unwrap_or_default()
+        );
+    }
+}
+
+fn describe_environment(environment: Option<&str>) -> String {
+    environment.map_or("the default environment".to_string(), |environment| {
+        format!("'{}'", environment)
+    })
+}
+
+impl BuildManifest {
+    /// The manifest under `root`, empty when nothing has been built there
+    pub fn load_from(root: &Path) -> Result<Self> {
+        let path = root.join(BUILD_MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Path::new(BUILD_MANIFEST_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
+
+/// Where a project's build output goes: its Wrangler `assets.directory`, otherwise `dist/`
+pub fn output_dir(project: &WorkspaceProject) -> PathBuf {
+    read_wrangler_config(&project.path)
+        .ok()
+        .flatten()
+        .and_then(|config| {
+            config
+                .pointer("/assets/directory")
+                .and_then(|d| d.as_str())
+                .map(|d| d.trim_start_matches("./").trim_end_matches('/').to_string())
+        })
+        .filter(|dir| !dir.is_empty() && !dir.starts_with(".."))
+        .map(|dir| project.path.join(dir))
+        .unwrap_or_else(|| project.path.join("dist"))
+}
+
+/// Hash the files a project is built from: its own and every crate's, since their WASM ships
+/// with it. In a git checkout these are the tracked and unignored files, so build output and
+/// installed dependencies never count.
+pub fn source_hash(project: &WorkspaceProject) -> Result<String> {
+    let crates = PathBuf::from(LayoutConfig::current().crates);
+    let output = output_dir(project);
+    let mut roots = vec![project.path.clone()];
+    if crates.is_dir() {
+        roots.push(crates);
+    }
+
+    let mut files = match git_files(&roots) {
+        Some(files) => files,
+        None => roots
+            .iter()
+            .flat_map(|root| {
+                WalkDir::new(root)
+                    .into_iter()
+                    .filter_entry(|entry| {
+                        !UNTRACKED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref())
+                    })
+                    .flatten()
+                    .filter(|entry| entry.file_type().is_file())
+                    .map(|entry| entry.into_path())
+            })
+            .collect(),
+    };
+    files.retain(|file| !file.starts_with(&output));
+    files.sort();
+    files.dedup();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        // Deleted but still tracked files simply don't contribute
+        let Ok(content) = fs::read(&file) else {
+            continue;
+        };
+        hasher.update(file.to_string_lossy().replace('\\', "/").as_bytes());
+        hasher.update([0]);
+        hasher.update(sha256_hex(&content).as_bytes());
+        hasher.update([0]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+fn git_files(roots: &[PathBuf]) -> Option<Vec<PathBuf>> {
+    let output = Command::new("git")
+        .args([
+            "ls-files",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+            "--",
+        ])
+        .args(roots)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(PathBuf::from)
+            .collect(),
+    )
+}
+
+/// Every file under `dir`, relative to it, with its SHA-256
+pub fn file_hashes(dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut hashes = BTreeMap::new();
+    for entry in WalkDir::new(dir) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(dir)?;
+        hashes.insert(
+            relative.to_string_lossy().replace('\\', "/"),
+            sha256_hex(&fs::read(entry.path())?),
+        );
+    }
+    Ok(hashes)
+}
+
+/// Record the output of projects that just built, given the source hashes taken before the
+/// build started, for the environment it ran with. Projects without output (a Worker Wrangler
+/// bundles itself) are left out.
+pub fn record_artifacts(
+    built: &[(WorkspaceProject, String)],
+    environment: Option<&str>,
+) -> Result<()> {
+    let mut manifest = BuildManifest::load_from(Path::new("."))?;
+    let built_at = chrono::Utc::now().to_rfc3339();
+    let git_sha = current_commit();
+
+    for (project, source_sha256) in built {
+        let output = output_dir(project);
+        if !output.is_dir() {
+            manifest.projects.remove(&project.name);
+            continue;
+        }
+        manifest.projects.insert(
+            project.name.clone(),
+            ArtifactRecord {
+                built_at: built_at.clone(),
+                git_sha: git_sha.clone(),
+                output: output.to_string_lossy().replace('\\', "/"),
+                source_sha256: source_sha256.clone(),
+                files: file_hashes(&output)?,
+                environment: environment.map(str::to_string),
+            },
+        );
+    }
+    manifest.save()
+}
+
+/// Check the artifacts of `project` under `artifacts` against the build manifest stored with
+/// them, the checked-out sources and the `environment` being deployed, then put them where the
+/// deploy expects its build output.
+/// `artifacts` mirrors the workspace: `.moonflare/build-manifest.json` plus each output directory
+/// at its workspace path, so a CI artifact downloaded into the workspace itself works as `.`.
+pub fn restore_artifacts(
+    artifacts: &Path,
+    project: &WorkspaceProject,
+    environment: Option<&str>,
+) -> Result<()> {
+    let manifest = BuildManifest::load_from(artifacts)?;
+    let Some(record) = manifest.projects.get(&project.name) else {
+        bail!(
+            "No artifacts for '{}' in {}: {} doesn't list it. Build it with 'moonflare build' and keep the manifest with the output.",
+            project.name,
+            artifacts.display(),
+            BUILD_MANIFEST_FILE
+        );
+    };
+    record.check_environment(&project.name, environment)?;
+
+    let source = artifacts.join(&record.output);
+    if !source.is_dir() {
+        bail!(
+            "Artifacts for '{}' are missing: {} doesn't exist",
+            project.name,
+            source.display()
+        );
+    }
+
+    let files = file_hashes(&source)?;
+    let missing: Vec<&str> = record
+        .files
+        .keys()
+        .filter(|file| !files.contains_key(*file))
+        .map(String::as_str)
+        .collect();
+    let changed: Vec<&str> = files
+        .iter()
+        .filter(|(file, hash)| {
+            record
+                .files
+                .get(*file)
+                .is_none_or(|recorded| recorded != *hash)
+        })
+        .map(|(file, _)| file.as_str())
+        .collect();
+    if !missing.is_empty() || !changed.is_empty() {
+        let mut problems = Vec::new();
+        if !missing.is_empty() {
+            problems.push(format!("missing {}", summarize(&missing)));
+        }
+        if !changed.is_empty() {
+            problems.push(format!("changed or unexpected {}", summarize(&changed)));
+        }
+        bail!(
+            "Artifacts for '{}' don't match the build manifest: {}",
+            project.name,
+            problems.join("; ")
+        );
+    }
+
+    if source_hash(project)? != record.source_sha256 {
+        bail!(
+            "Artifacts for '{}' are stale: its sources changed since they were built{}. Rebuild them.",
+            project.name,
+            record
+                .git_sha
+                .as_deref()
+                .map(|sha| format!(" at {}", &sha[..sha.len().min(7)]))
+                .unwrap_or_default()
+        );
+    }
+
+    let destination = output_dir(project);
+    if destination.to_string_lossy().replace('\\', "/") != record.output {
+        bail!(
+            "Artifacts for '{}' were built to {}, but it now builds to {}. Rebuild them.",
+            project.name,
+            record.output,
+            destination.display()
+        );
+    }
+    let same_dir = fs::canonicalize(&source).ok() == fs::canonicalize(&destination).ok();
+    if !same_dir {
+        if destination.exists() {
+            fs::remove_dir_all(platform::long_path(&destination))?;
+        }
+        for (file, _) in files {
+            let target = platform::long_path(&destination.join(&file));
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(source.join(&file), target)?;
+        }
+    }
+    Ok(())
+}
+
+fn summarize(files: &[&str]) -> String {
+    let mut listing = files.iter().take(3).copied().collect::<Vec<_>>().join(", ");
+    if files.len() > 3 {
+        listing.push_str(&format!(" and {} more", files.len() - 3));
+    }
+    listing
+}
@@ -0,0 +1,320 @@
+use crate::utils::cargo_workspace::lockfile;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use which::which;
+
+/// Advisory severity, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 4] = [
+        Severity::Critical,
+        Severity::High,
+        Severity::Moderate,
+        Severity::Low,
+    ];
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "low" | "info" => Some(Severity::Low),
+            "moderate" | "medium" => Some(Severity::Moderate),
+            "high" => Some(Severity::High),
+            "critical" => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+
+    /// Severity band of a CVSS base score
+    fn from_score(score: f64) -> Self {
+        if score >= 9.0 {
+            Severity::Critical
+        } else if score >= 7.0 {
+            Severity::High
+        } else if score >= 4.0 {
+            Severity::Moderate
+        } else {
+            Severity::Low
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Low => "low",
+            Severity::Moderate => "moderate",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A known vulnerability affecting a dependency of the deploy set
+#[derive(Debug, Clone)]
+pub struct Advisory {
+    pub id: String,
+    pub package: String,
+    pub severity: Severity,
+    pub title: String,
+}
+
+/// Outcome of auditing the deploy set
+#[derive(Debug, Default)]
+pub struct AuditReport {
+    pub advisories: Vec<Advisory>,
+    /// Auditors that could not run because the tool isn't installed
+    pub skipped: Vec<String>,
+}
+
+impl AuditReport {
+    pub fn counts(&self) -> BTreeMap<Severity, usize> {
+        let mut counts = BTreeMap::new();
+        for advisory in &self.advisories {
+            *counts.entry(advisory.severity).or_default() += 1;
+        }
+        counts
+    }
+}
+
+/// Audit npm dependencies of the given projects and the Cargo.lock each crate resolves through
+pub fn audit(npm_projects: &[&Path], crates: &[&Path]) -> Result<AuditReport> {
+    let mut report = AuditReport::default();
+
+    if !npm_projects.is_empty() {
+        if which("pnpm").is_ok() {
+            report.advisories.extend(pnpm_audit(npm_projects)?);
+        } else {
+            report
+                .skipped
+                .push("pnpm audit (pnpm not found)".to_string());
+        }
+    }
+
+    // Crates in the root Cargo workspace share its lockfile, which is audited once
+    let mut lockfiles: Vec<PathBuf> = crates
+        .iter()
+        .map(|c| lockfile(c))
+        .filter(|lock| lock.exists())
+        .collect();
+    lockfiles.sort();
+    lockfiles.dedup();
+    if !lockfiles.is_empty() {
+        if cargo_audit_installed() {
+            for lock in &lockfiles {
+                report.advisories.extend(cargo_audit(lock)?);
+            }
+        } else {
+            report
+                .skipped
+                .push("cargo audit (install with `cargo install cargo-audit`)".to_string());
+        }
+    }
+
+    report
+        .advisories
+        .sort_by(|a, b| b.severity.cmp(&a.severity).then(a.id.cmp(&b.id)));
+    report
+        .advisories
+        .dedup_by(|a, b| a.id == b.id && a.package == b.package);
+    Ok(report)
+}
+
+/// `pnpm audit` covers the whole lockfile, so findings are narrowed to the deploy set by
+/// the importer each dependency path starts from (pnpm writes `workers/api` as `workers__api`)
+fn pnpm_audit(projects: &[&Path]) -> Result<Vec<Advisory>> {
+    let output = Command::new("pnpm")
+        .args(["audit", "--json"])
+        .output()
+        .context("Failed to run pnpm audit")?;
+
+    // pnpm exits non-zero when it finds advisories, so only unparseable output is a failure
+    let report: Value = serde_json::from_slice(&output.stdout).map_err(|_| {
+        anyhow::anyhow!(
+            "pnpm audit failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    })?;
+
+    let importers: Vec<String> = projects
+        .iter()
+        .map(|p| p.to_string_lossy().replace(['/', '\\'], "__"))
+        .collect();
+
+    let mut advisories = Vec::new();
+    let Some(entries) = report.get("advisories").and_then(|a| a.as_object()) else {
+        return Ok(advisories);
+    };
+
+    for (key, entry) in entries {
+        let paths: Vec<&str> = entry["findings"]
+            .as_array()
+            .map(|findings| {
+                findings
+                    .iter()
+                    .filter_map(|f| f["paths"].as_array())
+                    .flatten()
+                    .filter_map(|p| p.as_str())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let in_deploy_set = paths.is_empty()
+            || paths.iter().any(|path| {
+                let importer = path.split('>').next().unwrap_or_default();
+                importers.iter().any(|i| i == importer)
+            });
+        if !in_deploy_set {
+            continue;
+        }
+
+        let id = entry["github_advisory_id"]
+            .as_str()
+            .map(String::from)
+            .unwrap_or_else(|| key.clone());
+        advisories.push(Advisory {
+            id,
+            package: entry["module_name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            severity: entry["severity"]
+                .as_str()
+                .and_then(Severity::parse)
+                .unwrap_or(Severity::Moderate),
+            title: entry["title"].as_str().unwrap_or_default().to_string(),
+        });
+    }
+
+    Ok(advisories)
+}
+
+fn cargo_audit_installed() -> bool {
+    Command::new("cargo")
+        .args(["audit", "--version"])
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+fn cargo_audit(lock: &Path) -> Result<Vec<Advisory>> {
+    let output = Command::new("cargo")
+        .args(["audit", "--json", "--file"])
+        .arg(lock)
+        .output()
+        .with_context(|| format!("Failed to run cargo audit on {}", lock.display()))?;
+
+    let Ok(report) = serde_json::from_slice::<Value>(&output.stdout) else {
+        bail!(
+            "cargo audit failed on {}: {}",
+            lock.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    };
+
+    let list = report
+        .pointer("/vulnerabilities/list")
+        .and_then(|l| l.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    Ok(list
+        .iter()
+        .map(|vulnerability| {
+            let advisory = &vulnerability["advisory"];
+            Advisory {
+                id: advisory["id"].as_str().unwrap_or_default().to_string(),
+                package: vulnerability
+                    .pointer("/package/name")
+                    .and_then(|n| n.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                // RustSec advisories without a CVSS vector are treated as moderate
+                severity: advisory["cvss"]
+                    .as_str()
+                    .and_then(cvss_base_score)
+                    .map(Severity::from_score)
+                    .unwrap_or(Severity::Moderate),
+                title: advisory["title"].as_str().unwrap_or_default().to_string(),
+            }
+        })
+        .collect())
+}
+
+/// Base score of a CVSS v3 vector such as `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`
+pub fn cvss_base_score(vector: &str) -> Option<f64> {
+    let metrics: BTreeMap<&str, &str> = vector
+        .split('/')
+        .skip(1)
+        .filter_map(|part| part.split_once(':'))
+        .collect();
+    let changed = *metrics.get("S")? == "C";
+
+    let attack_vector = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let attack_complexity = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let privileges = match (*metrics.get("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let interaction = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let impact_of = |key: &str| match metrics.get(key).copied() {
+        Some("H") => Some(0.56),
+        Some("L") => Some(0.22),
+        Some("N") => Some(0.0),
+        _ => None,
+    };
+    let (c, i, a) = (impact_of("C")?, impact_of("I")?, impact_of("A")?);
+
+    let base_impact: f64 = 1.0 - (1.0 - c) * (1.0 - i) * (1.0 - a);
+    let impact = if changed {
+        7.52 * (base_impact - 0.029) - 3.25 * (base_impact - 0.02).powi(15)
+    } else {
+        6.42 * base_impact
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+
+    let exploitability = 8.22 * attack_vector * attack_complexity * privileges * interaction;
+    let score = if changed {
+        (1.08 * (impact + exploitability)).min(10.0)
+    } else {
+        (impact + exploitability).min(10.0)
+    };
+
+    // CVSS "round up" to one decimal, done in integers to avoid float artifacts
+    let scaled = (score * 100_000.0).round() as i64;
+    Some(if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        (scaled / 10_000 + 1) as f64 / 10.0
+    })
+}
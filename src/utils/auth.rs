@@ -0,0 +1,191 @@
+use crate::utils::platform::{config_dir, home_dir};
+use crate::utils::sbom::sha256_hex;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// How long an answer from `wrangler whoami` is reused before Wrangler is asked again
+pub const AUTH_TTL_SECS: i64 = 300;
+
+/// An account the credentials can reach
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub name: String,
+    pub id: String,
+}
+
+/// Who Wrangler is logged in as, from `wrangler whoami`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub accounts: Vec<Account>,
+    /// OAuth scopes; API tokens don't list theirs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    /// RFC 3339, in UTC
+    pub fetched_at: String,
+}
+
+/// The cache file's contents: the answer and the credentials it was given for
+#[derive(Serialize, Deserialize)]
+struct CachedAuth {
+    credentials: String,
+    info: AuthInfo,
+}
+
+/// The OAuth configuration `wrangler login` writes, where this platform keeps it
+pub fn wrangler_login_config() -> Option<PathBuf> {
+    let home = home_dir()?;
+    [
+        config_dir().map(|dir| dir.join(".wrangler/config/default.toml")),
+        Some(home.join(".wrangler/config/default.toml")),
+        Some(home.join("Library/Preferences/.wrangler/config/default.toml")),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|path| path.is_file())
+}
+
+/// Whether Wrangler can reach an account: an API token in the environment, or the OAuth
+/// configuration `wrangler login` writes
+pub fn logged_in() -> bool {
+    api_token().is_some() || wrangler_login_config().is_some()
+}
+
+/// Who the current credentials belong to. The answer is cached in the user's configuration
+/// directory for [`AUTH_TTL_SECS`], for as long as the API token or Wrangler's login stay the
+/// same; `refresh` asks Wrangler regardless.
+pub fn account_info(refresh: bool) -> Result<AuthInfo> {
+    let credentials = credentials();
+    if !refresh && let Some(info) = cached(&credentials) {
+        return Ok(info);
+    }
+
+    let output = Command::new("npx")
+        .args(["wrangler", "whoami"])
+        .output()
+        .context("Failed to run wrangler whoami")?;
+    if !output.status.success() {
+        bail!(
+            "wrangler whoami failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let info = parse_whoami(&String::from_utf8_lossy(&output.stdout));
+    if info.accounts.is_empty() {
+        bail!(
+            "Not logged in to Cloudflare; run 'moonflare token login' or set CLOUDFLARE_API_TOKEN"
+        );
+    }
+
+    // Best-effort like the history logs: a read-only config directory only costs the next
+    // command another whoami
+    if let Some(path) = cache_path() {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let cache = CachedAuth {
+            credentials,
+            info: info.clone(),
+        };
+        if let Ok(json) = serde_json::to_string_pretty(&cache) {
+            let _ = fs::write(path, json);
+        }
+    }
+    Ok(info)
+}
+
+/// The ID of the only account the credentials reach, when there's exactly one
+#[cfg(feature = "api")]
+pub fn sole_account_id() -> Option<String> {
+    match account_info(false).ok()?.accounts.as_slice() {
+        [account] => Some(account.id.clone()),
+        _ => None,
+    }
+}
+
+/// Forget the cached answer, after logging in or out
+pub fn invalidate() -> Result<()> {
+    match cache_path() {
+        Some(path) if path.exists() => {
+            fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn cache_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("moonflare").join("auth.json"))
+}
+
+fn api_token() -> Option<String> {
+    std::env::var("CLOUDFLARE_API_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty())
+}
+
+/// Fingerprint of the credentials Wrangler would use, so a different token or a new login
+/// never sees another's accounts. Wrangler rewrites its OAuth configuration when it refreshes
+/// the access token, which also starts over.
+fn credentials() -> String {
+    let secret = match api_token() {
+        Some(token) => token.into_bytes(),
+        None => wrangler_login_config()
+            .and_then(|path| fs::read(path).ok())
+            .unwrap_or_default(),
+    };
+    sha256_hex(&secret)
+}
+
+fn cached(credentials: &str) -> Option<AuthInfo> {
+    let content = fs::read_to_string(cache_path()?).ok()?;
+    let cache: CachedAuth = serde_json::from_str(&content).ok()?;
+    let fetched_at = DateTime::parse_from_rfc3339(&cache.info.fetched_at).ok()?;
+    let age = Utc::now().signed_duration_since(fetched_at);
+    (cache.credentials == credentials && age.num_seconds() < AUTH_TTL_SECS).then_some(cache.info)
+}
+
+/// Read `wrangler whoami`'s report: the email in its greeting, the rows of its account table
+/// and the `- scope` lines under its token permissions
+fn parse_whoami(output: &str) -> AuthInfo {
+    let email = output.lines().find_map(|line| {
+        let (_, rest) = line.split_once("associated with the email ")?;
+        Some(rest.trim().trim_end_matches('.').to_string())
+    });
+    let accounts = output
+        .lines()
+        .filter_map(|line| {
+            let cells: Vec<&str> = line
+                .split(['│', '|'])
+                .map(str::trim)
+                .filter(|cell| !cell.is_empty())
+                .collect();
+            match cells.as_slice() {
+                [name, id] if id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()) => {
+                    Some(Account {
+                        name: name.to_string(),
+                        id: id.to_string(),
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect();
+    let scopes = output
+        .lines()
+        .skip_while(|line| !line.contains("Token Permissions"))
+        .filter_map(|line| line.trim().strip_prefix("- "))
+        .map(|scope| scope.trim().to_string())
+        .collect();
+
+    AuthInfo {
+        email,
+        accounts,
+        scopes,
+        fetched_at: Utc::now().to_rfc3339(),
+    }
+}
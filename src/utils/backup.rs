@@ -0,0 +1,274 @@
+use crate::utils::platform;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const BACKUP_DIR: &str = ".moonflare/backups";
+const SNAPSHOT_MANIFEST: &str = "snapshot.json";
+
+// Generated and installed content that can always be recreated
+const SKIPPED_DIRECTORIES: [&str; 5] = ["node_modules", "target", "dist", ".wrangler", ".astro"];
+
+/// A point-in-time copy of the files touched by a destructive operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    pub operation: String,
+    pub created_at: String,
+    pub entries: Vec<SnapshotEntry>,
+    /// Directories the operation moved, so generated content can follow them back on restore
+    #[serde(default)]
+    pub moves: Vec<SnapshotMove>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    /// Path relative to the workspace root
+    pub path: PathBuf,
+    /// Whether the path existed when the snapshot was taken; paths that didn't are
+    /// deleted on restore
+    pub existed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotMove {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+impl Snapshot {
+    pub fn directory(&self) -> PathBuf {
+        Path::new(BACKUP_DIR).join(&self.id)
+    }
+
+    fn files_directory(&self) -> PathBuf {
+        self.directory().join("files")
+    }
+
+    fn preserved_directory(&self) -> PathBuf {
+        self.directory().join("preserved")
+    }
+}
+
+/// Copy the given workspace-relative paths into `.moonflare/backups/<timestamp>`
+pub fn create_snapshot(operation: &str, paths: &[&Path]) -> Result<Snapshot> {
+    create_move_snapshot(operation, paths, &[])
+}
+
+/// Like [`create_snapshot`], for operations that are about to move directories from one path
+/// to another. Generated directories the snapshot skips are moved back along with the
+/// directory on restore rather than being lost.
+pub fn create_move_snapshot(
+    operation: &str,
+    paths: &[&Path],
+    moves: &[(&Path, &Path)],
+) -> Result<Snapshot> {
+    let now = chrono::Local::now();
+    let mut id = now.format("%Y%m%d-%H%M%S").to_string();
+
+    // Several snapshots can be taken within the same second
+    let mut suffix = 1;
+    while Path::new(BACKUP_DIR).join(&id).exists() {
+        suffix += 1;
+        id = format!("{}-{}", now.format("%Y%m%d-%H%M%S"), suffix);
+    }
+
+    let snapshot = Snapshot {
+        id,
+        operation: operation.to_string(),
+        created_at: now.to_rfc3339(),
+        entries: paths
+            .iter()
+            .map(|path| SnapshotEntry {
+                path: path.to_path_buf(),
+                existed: path.exists(),
+            })
+            .collect(),
+        moves: moves
+            .iter()
+            .map(|(from, to)| SnapshotMove {
+                from: from.to_path_buf(),
+                to: to.to_path_buf(),
+            })
+            .collect(),
+    };
+
+    let files_dir = snapshot.files_directory();
+    fs::create_dir_all(&files_dir)
+        .with_context(|| format!("Failed to create {}", files_dir.display()))?;
+
+    for entry in snapshot.entries.iter().filter(|e| e.existed) {
+        copy_path(&entry.path, &files_dir.join(&entry.path))?;
+    }
+
+    let manifest = serde_json::to_string_pretty(&snapshot)?;
+    fs::write(snapshot.directory().join(SNAPSHOT_MANIFEST), manifest)?;
+
+    Ok(snapshot)
+}
+
+/// All snapshots in the workspace, oldest first
+pub fn list_snapshots() -> Result<Vec<Snapshot>> {
+    let backup_dir = Path::new(BACKUP_DIR);
+    if !backup_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(backup_dir)? {
+        let manifest_path = entry?.path().join(SNAPSHOT_MANIFEST);
+        if !manifest_path.exists() {
+            continue;
+        }
+        let content = fs::read_to_string(&manifest_path)?;
+        let snapshot: Snapshot = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid snapshot manifest {}", manifest_path.display()))?;
+        snapshots.push(snapshot);
+    }
+
+    snapshots.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    Ok(snapshots)
+}
+
+pub fn find_snapshot(id: &str) -> Result<Snapshot> {
+    let snapshots = list_snapshots()?;
+    if id == "latest" {
+        return snapshots
+            .into_iter()
+            .last()
+            .ok_or_else(|| anyhow::anyhow!("No snapshots found in {}", BACKUP_DIR));
+    }
+
+    match snapshots.into_iter().find(|s| s.id == id) {
+        Some(snapshot) => Ok(snapshot),
+        None => bail!("Snapshot '{}' not found in {}", id, BACKUP_DIR),
+    }
+}
+
+/// Put every path recorded in the snapshot back the way it was. Generated directories the
+/// snapshot never copied (`node_modules`, `target`, ...) are kept in place.
+pub fn restore_snapshot(snapshot: &Snapshot) -> Result<()> {
+    let files_dir = snapshot.files_directory();
+    let preserved = preserve_skipped_directories(snapshot)?;
+
+    for entry in &snapshot.entries {
+        remove_path(&entry.path)?;
+        if entry.existed {
+            copy_path(&files_dir.join(&entry.path), &entry.path)?;
+        }
+    }
+
+    for (stash, destination) in preserved {
+        if destination.exists() {
+            continue;
+        }
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&stash, &destination)
+            .with_context(|| format!("Failed to move back {}", destination.display()))?;
+    }
+    let _ = fs::remove_dir_all(snapshot.preserved_directory());
+
+    Ok(())
+}
+
+/// Move the skipped directories under the snapshot's paths out of the way before they're
+/// removed, returning each one's stash location and where it goes once the files are back.
+/// Directories under a moved path go back under the path it was moved from.
+fn preserve_skipped_directories(snapshot: &Snapshot) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let mut sources: Vec<(&Path, &Path)> = snapshot
+        .entries
+        .iter()
+        .filter(|entry| entry.existed)
+        .map(|entry| (entry.path.as_path(), entry.path.as_path()))
+        .collect();
+    sources.extend(
+        snapshot
+            .moves
+            .iter()
+            .map(|m| (m.to.as_path(), m.from.as_path())),
+    );
+
+    let mut preserved = Vec::new();
+    for (current, original) in sources {
+        if !current.is_dir() {
+            continue;
+        }
+        for directory in skipped_directories(current)? {
+            let relative = directory.strip_prefix(current)?;
+            let destination = original.join(relative);
+            let stash = snapshot.preserved_directory().join(&destination);
+            if stash.exists() {
+                continue;
+            }
+            if let Some(parent) = stash.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&directory, &stash)
+                .with_context(|| format!("Failed to move aside {}", directory.display()))?;
+            preserved.push((stash, destination));
+        }
+    }
+
+    Ok(preserved)
+}
+
+/// Skipped directories under `root`, without descending into them
+fn skipped_directories(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    let mut walker = WalkDir::new(root).min_depth(1).into_iter();
+    while let Some(entry) = walker.next() {
+        let entry = entry?;
+        if entry.file_type().is_dir() && is_skipped(entry.file_name()) {
+            found.push(entry.path().to_path_buf());
+            walker.skip_current_dir();
+        }
+    }
+    Ok(found)
+}
+
+fn is_skipped(name: &std::ffi::OsStr) -> bool {
+    SKIPPED_DIRECTORIES.iter().any(|skipped| name == *skipped)
+}
+
+fn copy_path(source: &Path, destination: &Path) -> Result<()> {
+    let destination = &platform::long_path(destination);
+    if source.is_file() {
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(source, destination)
+            .with_context(|| format!("Failed to copy {}", source.display()))?;
+        return Ok(());
+    }
+
+    let walker = WalkDir::new(source)
+        .into_iter()
+        .filter_entry(|entry| !(entry.file_type().is_dir() && is_skipped(entry.file_name())));
+
+    for entry in walker {
+        let entry = entry?;
+        let target = destination.join(entry.path().strip_prefix(source)?);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            fs::copy(platform::long_path(entry.path()), &target)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn remove_path(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(platform::long_path(path))
+            .with_context(|| format!("Failed to remove {}", path.display()))?;
+    } else if path.exists() {
+        fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
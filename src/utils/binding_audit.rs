@@ -0,0 +1,217 @@
+use crate::utils::dev_vars::{DEV_VARS_FILE, SOURCE_EXTENSIONS, parse_dev_vars};
+use crate::utils::projects::{GENERATED_DIRS, WorkspaceProject};
+use crate::utils::wrangler::{binding_names, config_vars};
+use crate::utils::wrangler_defaults::effective_config;
+use anyhow::Result;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// What `moonflare audit-bindings` found in one project
+#[derive(Debug, Default)]
+pub struct BindingAudit {
+    /// Bindings the configuration declares that no source mentions, with the key they're
+    /// declared under, like `kv_namespaces`
+    pub unused: BTreeMap<String, String>,
+    /// `env.NAME` accesses with no binding, var or `.dev.vars` entry, and the first file
+    /// making each
+    pub missing: BTreeMap<String, PathBuf>,
+}
+
+impl BindingAudit {
+    pub fn is_empty(&self) -> bool {
+        self.unused.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Compare the bindings a project's Wrangler configuration (with the workspace defaults)
+/// declares with the `env` accesses in its sources
+pub fn audit(project: &WorkspaceProject) -> Result<BindingAudit> {
+    let config = effective_config(&project.path)?.unwrap_or_default();
+    let sources = read_sources(&project.path);
+
+    let mut known = binding_names(&config);
+    known.extend(config_vars(&config).into_keys());
+    known.extend(local_secrets(&project.path));
+
+    let mut missing = BTreeMap::new();
+    for (path, content) in &sources {
+        for name in env_accesses(content) {
+            if !known.contains(&name) {
+                missing
+                    .entry(name)
+                    .or_insert_with(|| relative_to(&project.path, path));
+            }
+        }
+    }
+
+    // Any mention counts as a use, so destructuring `const { DB } = env` and passing the
+    // binding on keep it declared
+    let unused = declared_bindings(&config)
+        .into_iter()
+        .filter(|(name, _)| {
+            let word = Regex::new(&format!(r"\b{}\b", regex::escape(name))).expect("valid regex");
+            !sources.iter().any(|(_, content)| word.is_match(content))
+        })
+        .collect();
+
+    Ok(BindingAudit { unused, missing })
+}
+
+/// Bindings declared in the configuration and its environments, with the key each is
+/// declared under. The static assets binding is left out: framework adapters read it from
+/// their own code, outside the project's sources.
+pub fn declared_bindings(config: &Value) -> BTreeMap<String, String> {
+    let mut declared = BTreeMap::new();
+    let Some(map) = config.as_object() else {
+        return declared;
+    };
+    for (key, value) in map {
+        match key.as_str() {
+            "vars" | "assets" => {}
+            "env" => {
+                for env in value.as_object().into_iter().flat_map(|envs| envs.values()) {
+                    for (name, kind) in declared_bindings(env) {
+                        declared.entry(name).or_insert(kind);
+                    }
+                }
+            }
+            "durable_objects" => {
+                for name in value["bindings"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|b| b["name"].as_str())
+                {
+                    declared.insert(name.to_string(), key.clone());
+                }
+            }
+            _ => {
+                for name in binding_names(value) {
+                    declared.insert(name, key.clone());
+                }
+            }
+        }
+    }
+    declared
+}
+
+/// Remove the binding from the configuration and every environment: its entry in a list like
+/// `kv_namespaces`, or the whole table of a single binding like `ai`. Returns whether
+/// anything was removed.
+pub fn remove_binding(config: &mut Value, name: &str) -> bool {
+    let Some(map) = config.as_object_mut() else {
+        return false;
+    };
+    let mut emptied = Vec::new();
+    let mut removed = false;
+
+    for (key, value) in map.iter_mut() {
+        let removed_here = match key.as_str() {
+            "vars" | "assets" => false,
+            "env" => value
+                .as_object_mut()
+                .into_iter()
+                .flat_map(|envs| envs.values_mut())
+                .fold(false, |removed, env| remove_binding(env, name) | removed),
+            "durable_objects" => value
+                .get_mut("bindings")
+                .and_then(Value::as_array_mut)
+                .is_some_and(|bindings| {
+                    let before = bindings.len();
+                    bindings.retain(|b| b["name"] != name);
+                    bindings.len() != before
+                }),
+            _ => remove_binding_entries(value, name),
+        };
+        if removed_here && value.as_array().is_some_and(Vec::is_empty) && key != "env" {
+            emptied.push(key.clone());
+        }
+        removed |= removed_here;
+    }
+
+    let singles: Vec<String> = map
+        .iter()
+        .filter(|(key, value)| *key != "assets" && value["binding"] == name)
+        .map(|(key, _)| key.clone())
+        .collect();
+    removed |= !singles.is_empty();
+    for key in singles.iter().chain(&emptied) {
+        map.remove(key);
+    }
+    removed
+}
+
+fn remove_binding_entries(value: &mut Value, name: &str) -> bool {
+    match value {
+        Value::Array(items) => {
+            let before = items.len();
+            items.retain(|item| item["binding"] != name);
+            let removed = items.len() != before;
+            items.iter_mut().fold(removed, |removed, item| {
+                remove_binding_entries(item, name) | removed
+            })
+        }
+        Value::Object(map) => map.values_mut().fold(false, |removed, child| {
+            remove_binding_entries(child, name) | removed
+        }),
+        _ => false,
+    }
+}
+
+/// Names read through `env.NAME` or `env["NAME"]`, leaving out Node's `process.env` and
+/// Vite's `import.meta.env`
+fn env_accesses(content: &str) -> BTreeSet<String> {
+    let pattern = Regex::new(
+        r#"(\w+\.)?\benv(?:\.([A-Z][A-Z0-9_]*)\b|\[["'`]([A-Za-z_][A-Za-z0-9_]*)["'`]\])"#,
+    )
+    .expect("valid regex");
+    pattern
+        .captures_iter(content)
+        .filter(|caps| !matches!(caps.get(1).map(|m| m.as_str()), Some("process." | "meta.")))
+        .filter_map(|caps| caps.get(2).or(caps.get(3)))
+        .map(|name| name.as_str().to_string())
+        .collect()
+}
+
+/// TypeScript and JavaScript sources of the project, skipping build output and generated
+/// declarations like `worker-configuration.d.ts`, which list every binding
+pub fn read_sources(project_path: &Path) -> Vec<(PathBuf, String)> {
+    WalkDir::new(project_path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            !(entry.file_type().is_dir()
+                && entry.depth() > 0
+                && GENERATED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| !entry.file_name().to_string_lossy().ends_with(".d.ts"))
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+        })
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.path()).ok()?;
+            Some((entry.path().to_path_buf(), content))
+        })
+        .collect()
+}
+
+/// Secrets set locally in `.dev.vars`, which Wrangler doesn't declare anywhere else
+fn local_secrets(project_path: &Path) -> BTreeSet<String> {
+    fs::read_to_string(project_path.join(DEV_VARS_FILE))
+        .map(|content| parse_dev_vars(&content).into_keys().collect())
+        .unwrap_or_default()
+}
+
+fn relative_to(base: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(base).unwrap_or(path).to_path_buf()
+}
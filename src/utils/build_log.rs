@@ -0,0 +1,92 @@
+use crate::utils::events::secs;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::moon_report::RunReport;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// One line per `moonflare build`, read back by `moonflare stats`
+pub const BUILD_LOG_FILE: &str = ".moonflare/builds.jsonl";
+
+/// Summary of one build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildRecord {
+    /// RFC 3339, in UTC
+    pub timestamp: String,
+    pub success: bool,
+    pub duration_secs: f64,
+    pub tasks: Vec<BuildTask>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildTask {
+    pub target: String,
+    pub duration_secs: f64,
+    /// Whether Moon restored the task from its cache; unknown without a run report
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached: Option<bool>,
+}
+
+/// Append a build to the log, taking per-task timings and cache hits from Moon's run report
+/// when this build wrote it. Like the history log, this never fails the build.
+pub fn record_build(started: SystemTime, duration: Duration, success: bool, targets: &[String]) {
+    if !is_moonflare_workspace() {
+        return;
+    }
+
+    let tasks = run_report_tasks(started).unwrap_or_else(|| {
+        targets
+            .iter()
+            .map(|target| BuildTask {
+                target: target.clone(),
+                duration_secs: secs(duration),
+                cached: None,
+            })
+            .collect()
+    });
+    let record = BuildRecord {
+        timestamp: chrono::DateTime::<chrono::Utc>::from(started).to_rfc3339(),
+        success,
+        duration_secs: secs(duration),
+        tasks,
+    };
+
+    let path = Path::new(BUILD_LOG_FILE);
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let Ok(line) = serde_json::to_string(&record) else {
+        return;
+    };
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+/// Recorded builds, oldest first
+pub fn read_builds() -> Vec<BuildRecord> {
+    fs::read_to_string(BUILD_LOG_FILE)
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Task actions from Moon's run report, if it was written after `since`
+fn run_report_tasks(since: SystemTime) -> Option<Vec<BuildTask>> {
+    let report = RunReport::load_since(since)?;
+    let tasks: Vec<BuildTask> = report
+        .tasks()
+        .map(|action| BuildTask {
+            target: action.target().unwrap_or_default().to_string(),
+            duration_secs: action.duration_secs(),
+            cached: Some(action.cached()),
+        })
+        .collect();
+    (!tasks.is_empty()).then_some(tasks)
+}
@@ -0,0 +1,262 @@
+use crate::utils::cloudflare::{CloudflareClient, RemoteBuild, Repository};
+use crate::utils::http;
+use crate::utils::interrupt;
+use crate::utils::manifest::BuildTriggerRecord;
+use crate::utils::projects::WorkspaceProject;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use regex::Regex;
+use serde_json::{Value, json};
+use std::process::Command;
+use std::time::Duration;
+
+/// How often a remote build's status is polled
+const BUILD_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Files outside a project that change what it builds: gathered WASM, shared packages and the
+/// workspace's dependency and task configuration
+const SHARED_BUILD_PATHS: [&str; 6] = [
+    "shared-wasm/**",
+    "packages/**",
+    ".moon/**",
+    "package.json",
+    "pnpm-lock.yaml",
+    "moonflare.toml",
+];
+
+/// Hosting provider and `owner/name` path of the `origin` remote
+pub fn origin_repository() -> Result<(String, String)> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .context(
+            "Workers Builds needs the workspace in a git repository with an 'origin' remote",
+        )?;
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    parse_remote(&url).with_context(|| {
+        format!(
+            "Workers Builds connects GitHub and GitLab repositories; 'origin' is {}",
+            url
+        )
+    })
+}
+
+/// `git@github.com:acme/shop.git`, `https://gitlab.com/group/sub/shop` and the like
+fn parse_remote(url: &str) -> Option<(String, String)> {
+    let remote = Regex::new(r"(github|gitlab)\.com[:/]+(.+?)(?:\.git)?/?$").expect("valid regex");
+    let caps = remote.captures(url)?;
+    let path = caps[2].to_string();
+    path.contains('/').then(|| (caps[1].to_string(), path))
+}
+
+/// Look up the numeric account and repository ids the Workers Builds API wants. GitHub Actions'
+/// `GITHUB_API_URL` and GitLab CI's `CI_API_V4_URL` point at self-hosted instances.
+pub async fn resolve_repository(provider: &str, path: &str) -> Result<Repository> {
+    let client = http::client()?;
+    let (url, token_header, token) = match provider {
+        "github" => (
+            format!(
+                "{}/repos/{}",
+                std::env::var("GITHUB_API_URL")
+                    .unwrap_or_else(|_| "https://api.github.com".to_string())
+                    .trim_end_matches('/'),
+                path
+            ),
+            "Authorization",
+            std::env::var("GITHUB_TOKEN")
+                .ok()
+                .map(|token| format!("Bearer {}", token)),
+        ),
+        _ => (
+            format!(
+                "{}/projects/{}",
+                std::env::var("CI_API_V4_URL")
+                    .unwrap_or_else(|_| "https://gitlab.com/api/v4".to_string())
+                    .trim_end_matches('/'),
+                path.replace('/', "%2F")
+            ),
+            "PRIVATE-TOKEN",
+            std::env::var("GITLAB_TOKEN").ok(),
+        ),
+    };
+
+    let mut request = client.get(&url);
+    if let Some(token) = token.filter(|token| !token.is_empty()) {
+        request = request.header(token_header, token);
+    }
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to look up {} on {}", path, provider))?;
+    if !response.status().is_success() {
+        bail!(
+            "Looking up {} on {} failed ({}); set {} for private repositories",
+            path,
+            provider,
+            response.status(),
+            if provider == "github" {
+                "GITHUB_TOKEN"
+            } else {
+                "GITLAB_TOKEN"
+            }
+        );
+    }
+    let repo: Value = response.json().await?;
+
+    let (owner, name) = if provider == "github" {
+        (&repo["owner"], &repo["name"])
+    } else {
+        (&repo["namespace"], &repo["path"])
+    };
+    let id = |value: &Value| match value {
+        Value::Number(number) => Some(number.to_string()),
+        Value::String(string) => Some(string.clone()),
+        _ => None,
+    };
+    Ok(Repository {
+        provider: provider.to_string(),
+        account_id: id(&owner["id"]).context("Repository lookup returned no owner id")?,
+        account_name: owner["login"]
+            .as_str()
+            .or_else(|| owner["full_path"].as_str())
+            .unwrap_or_default()
+            .to_string(),
+        repo_id: id(&repo["id"]).context("Repository lookup returned no id")?,
+        repo_name: name.as_str().unwrap_or_default().to_string(),
+    })
+}
+
+/// The workspace's directory within its repository, `/` at the root
+pub fn root_directory() -> String {
+    Command::new("git")
+        .args(["rev-parse", "--show-prefix"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .trim()
+                .trim_end_matches('/')
+                .to_string()
+        })
+        .filter(|prefix| !prefix.is_empty())
+        .unwrap_or_else(|| "/".to_string())
+}
+
+/// Trigger settings that build and deploy `project` through its Moon targets, as a local
+/// `moonflare deploy` would, whenever `branch` changes the project or what it depends on
+pub fn trigger_settings(
+    project: &WorkspaceProject,
+    env: Option<&str>,
+    branch: &str,
+    crates_dir: &str,
+) -> Value {
+    let deploy_env = env
+        .map(|env| format!("WRANGLER_ENV={} ", env))
+        .unwrap_or_default();
+    let mut paths = vec![
+        format!("{}/**", project.relative_path()),
+        format!("{}/**", crates_dir),
+    ];
+    paths.extend(SHARED_BUILD_PATHS.iter().map(|path| path.to_string()));
+
+    json!({
+        "trigger_name": match env {
+            Some(env) => format!("moonflare {} ({})", project.name, env),
+            None => format!("moonflare {}", project.name),
+        },
+        "build_command": format!(
+            "pnpm install --frozen-lockfile && pnpm exec moon run {}:build",
+            project.name
+        ),
+        "deploy_command": format!("{}pnpm exec moon run {}:deploy", deploy_env, project.name),
+        "root_directory": root_directory(),
+        "branch_includes": [branch],
+        "branch_excludes": [],
+        "path_includes": paths,
+        "path_excludes": [],
+        "build_caching_enabled": true,
+    })
+}
+
+/// Whether `commit` is on a remote branch, so Workers Builds can check it out
+pub fn is_pushed(commit: &str) -> bool {
+    Command::new("git")
+        .args(["branch", "--remotes", "--contains", commit])
+        .output()
+        .is_ok_and(|output| output.status.success() && !output.stdout.trim_ascii().is_empty())
+}
+
+/// Start a Workers Build of `commit` and follow it until it stops, printing each status change.
+/// Ctrl-C stops following; the build carries on in Cloudflare.
+pub async fn run_remote_build(
+    client: &CloudflareClient,
+    record: &BuildTriggerRecord,
+    branch: &str,
+    commit: &str,
+) -> Result<()> {
+    let mut build = client
+        .start_build(&record.trigger_uuid, branch, commit)
+        .await?;
+    println!(
+        "  Workers Build {} started for {} at {}",
+        build.build_uuid,
+        record.worker,
+        &commit[..commit.len().min(7)]
+    );
+    println!(
+        "  {}",
+        format!(
+            "https://dash.cloudflare.com/{}/workers/services/view/{}/production/builds/{}",
+            record.account_id, record.worker, build.build_uuid
+        )
+        .dimmed()
+    );
+
+    // Ctrl-C stops following the build without cancelling it
+    let _graceful = interrupt::handle_gracefully();
+    let mut reported = String::new();
+    loop {
+        if build.status != reported {
+            println!("  {}", describe(&build));
+            reported = build.status.clone();
+        }
+        if build.is_finished() {
+            break;
+        }
+        tokio::select! {
+            _ = interrupt::cancelled() => {
+                println!(
+                    "{}",
+                    format!(
+                        "Stopped following build {}; it continues in Cloudflare",
+                        build.build_uuid
+                    )
+                    .yellow()
+                );
+                bail!("Interrupted while build {} was {}", build.build_uuid, build.status);
+            }
+            _ = tokio::time::sleep(BUILD_POLL_INTERVAL) => {}
+        }
+        build = client.remote_build(&build.build_uuid).await?;
+    }
+
+    if !build.succeeded() {
+        bail!(
+            "Workers Build {} finished with '{}'",
+            build.build_uuid,
+            build.build_outcome.as_deref().unwrap_or("unknown")
+        );
+    }
+    Ok(())
+}
+
+fn describe(build: &RemoteBuild) -> String {
+    match (build.status.as_str(), build.build_outcome.as_deref()) {
+        (_, Some("success")) => "Build succeeded and deployed".green().to_string(),
+        (_, Some(outcome)) => format!("Build stopped: {}", outcome).red().to_string(),
+        (status, None) => format!("Build {}", status).cyan().to_string(),
+    }
+}
@@ -0,0 +1,330 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::retention::format_bytes;
+use crate::utils::task_env;
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::process::Command;
+
+/// Modules Node.js provides that Workers only have with the `nodejs_compat` flag
+const NODE_BUILTINS: [&str; 38] = [
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "timers",
+    "tls",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "worker_threads",
+    "zlib",
+];
+
+/// Extensions of native binaries, which can't run in a Worker however they got in
+const NATIVE_EXTENSIONS: [&str; 4] = [".node", ".dll", ".so", ".dylib"];
+
+/// A file of the project, or a package with all its files, and what it adds to the bundle
+#[derive(Debug, Clone, Serialize)]
+pub struct Contributor {
+    /// Path of the file, or the package name for anything under node_modules
+    pub name: String,
+    pub bytes: u64,
+    pub files: usize,
+    /// Over `[deploy.bundle] large_input_kb`
+    pub large: bool,
+    /// Includes a native binary, e.g. a `.node` or `.dll` file
+    pub native: bool,
+}
+
+/// What goes into a Worker's bundle, from the esbuild metafile of a `wrangler deploy --dry-run`
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleReport {
+    pub project: String,
+    pub environment: Option<String>,
+    /// Size of the bundled JavaScript before compression, source maps left out
+    pub bytes: u64,
+    pub budget_bytes: Option<u64>,
+    /// Largest first
+    pub contributors: Vec<Contributor>,
+    pub nodejs_compat: bool,
+    /// Node.js builtins imported while the Worker lacks `nodejs_compat`
+    pub node_builtins: Vec<String>,
+}
+
+impl BundleReport {
+    pub fn over_budget(&self) -> bool {
+        self.budget_bytes.is_some_and(|budget| self.bytes > budget)
+    }
+
+    /// Whether the bundle would fail to deploy or run: over budget, pulling in native
+    /// binaries, or importing Node.js builtins without `nodejs_compat`
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Some(budget) = self.budget_bytes.filter(|_| self.over_budget()) {
+            problems.push(format!(
+                "{} is over its {} budget",
+                format_bytes(self.bytes),
+                format_bytes(budget)
+            ));
+        }
+        for contributor in self.contributors.iter().filter(|c| c.native) {
+            problems.push(format!("{} is a native binary", contributor.name));
+        }
+        if !self.node_builtins.is_empty() {
+            problems.push(format!(
+                "imports Node.js builtins ({}) without compatibility_flags = [\"nodejs_compat\"]",
+                self.node_builtins.join(", ")
+            ));
+        }
+        problems
+    }
+}
+
+/// Whether the project is a Worker with an entry point Wrangler bundles
+pub fn is_bundled(project: &WorkspaceProject) -> bool {
+    read_wrangler_config(&project.path)
+        .ok()
+        .flatten()
+        .is_some_and(|config| config["main"].is_string())
+}
+
+/// Bundle the project the way `wrangler deploy` would, without deploying, and report what
+/// went into it
+pub fn analyze(
+    project: &WorkspaceProject,
+    env: Option<&str>,
+    config: &MoonflareConfig,
+) -> Result<BundleReport> {
+    let wrangler = read_wrangler_config(&project.path)?.unwrap_or_default();
+    let nodejs_compat = has_nodejs_compat(&wrangler, env);
+    let out = tempfile::tempdir()?;
+    let metafile = out.path().join("bundle-meta.json");
+
+    let mut command = Command::new("pnpm");
+    command
+        .args(["exec", "wrangler", "deploy", "--dry-run", "--outdir"])
+        .arg(out.path())
+        .arg("--metafile")
+        .arg(&metafile);
+    if let Some(env) = env {
+        command.args(["--env", env]);
+    }
+    let output = command
+        .current_dir(&project.path)
+        .envs(task_env::vars(Some(&project.name)))
+        .output()
+        .context("Failed to run wrangler through pnpm")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let unresolved = unresolved_builtins(&stderr);
+        if !unresolved.is_empty() && !nodejs_compat {
+            anyhow::bail!(
+                "'{}' imports Node.js builtins ({}) without compatibility_flags = [\"nodejs_compat\"]; add the flag to its Wrangler configuration or drop the imports",
+                project.name,
+                unresolved.join(", ")
+            );
+        }
+        anyhow::bail!(
+            "Bundling '{}' failed: wrangler deploy --dry-run exited with {}\n{}",
+            project.name,
+            output.status,
+            stderr.trim_end()
+        );
+    }
+
+    let content = std::fs::read_to_string(&metafile).with_context(|| {
+        format!(
+            "wrangler wrote no metafile for '{}'; bundle analysis needs Wrangler 3.30 or newer",
+            project.name
+        )
+    })?;
+    let meta: Value = serde_json::from_str(&content).context("Invalid esbuild metafile")?;
+    let mut report = from_metafile(&project.name, &meta, config.deploy.bundle.large_input_kb);
+    report.environment = env.map(str::to_string);
+    report.budget_bytes = config.bundle_budget_kb(&project.name).map(|kb| kb * 1024);
+    report.nodejs_compat = nodejs_compat;
+    if nodejs_compat {
+        report.node_builtins.clear();
+    }
+    Ok(report)
+}
+
+/// Sizes and imports of an esbuild metafile, grouping files under node_modules by package.
+/// Node.js builtins are every builtin the bundle imports; [`analyze`] keeps them only when
+/// the Worker lacks `nodejs_compat`.
+pub fn from_metafile(project: &str, meta: &Value, large_input_kb: u64) -> BundleReport {
+    let mut bytes = 0;
+    let mut contributors: BTreeMap<String, Contributor> = BTreeMap::new();
+    let mut builtins = BTreeSet::new();
+    let outputs = meta["outputs"].as_object().into_iter().flatten();
+    for (_, output) in outputs.filter(|(path, _)| !path.ends_with(".map")) {
+        bytes += output["bytes"].as_u64().unwrap_or(0);
+        for (path, input) in output["inputs"].as_object().into_iter().flatten() {
+            let name = package_of(path).unwrap_or_else(|| path.to_string());
+            let contributor = contributors
+                .entry(name.clone())
+                .or_insert_with(|| Contributor {
+                    name,
+                    bytes: 0,
+                    files: 0,
+                    large: false,
+                    native: false,
+                });
+            contributor.bytes += input["bytesInOutput"].as_u64().unwrap_or(0);
+            contributor.files += 1;
+            contributor.native |= NATIVE_EXTENSIONS.iter().any(|ext| path.ends_with(ext));
+        }
+        for import in output["imports"].as_array().into_iter().flatten() {
+            if let Some(builtin) = import["path"].as_str().and_then(node_builtin) {
+                builtins.insert(builtin);
+            }
+        }
+    }
+
+    let mut contributors: Vec<Contributor> = contributors.into_values().collect();
+    for contributor in &mut contributors {
+        contributor.large = contributor.bytes >= large_input_kb * 1024;
+    }
+    contributors.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    BundleReport {
+        project: project.to_string(),
+        environment: None,
+        bytes,
+        budget_bytes: None,
+        contributors,
+        nodejs_compat: false,
+        node_builtins: builtins.into_iter().collect(),
+    }
+}
+
+/// The package a file under node_modules belongs to, e.g. `@scope/name` for
+/// `node_modules/.pnpm/@scope+name@1.0.0/node_modules/@scope/name/index.js`
+fn package_of(path: &str) -> Option<String> {
+    let (_, rest) = path.rsplit_once("node_modules/")?;
+    let mut segments = rest.split('/');
+    let first = segments.next()?;
+    if first.starts_with('@') {
+        Some(format!("{}/{}", first, segments.next()?))
+    } else {
+        Some(first.to_string())
+    }
+}
+
+/// The builtin an import names, e.g. `fs` for `node:fs/promises`
+fn node_builtin(specifier: &str) -> Option<String> {
+    let name = specifier.strip_prefix("node:").unwrap_or(specifier);
+    let module = name.split('/').next()?;
+    (specifier.starts_with("node:") || NODE_BUILTINS.contains(&module)).then(|| module.to_string())
+}
+
+/// Builtins esbuild couldn't resolve, from Wrangler's `Could not resolve "fs"` errors
+fn unresolved_builtins(stderr: &str) -> Vec<String> {
+    let pattern = regex::Regex::new(r#"Could not resolve "([^"]+)""#).expect("valid regex");
+    pattern
+        .captures_iter(stderr)
+        .filter_map(|c| node_builtin(&c[1]))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Whether the Worker, in `env` when given, runs with Node.js compatibility
+fn has_nodejs_compat(config: &Value, env: Option<&str>) -> bool {
+    let flags = env
+        .map(|env| &config["env"][env]["compatibility_flags"])
+        .filter(|flags| flags.is_array())
+        .unwrap_or(&config["compatibility_flags"]);
+    flags
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .any(|flag| flag == "nodejs_compat" || flag == "nodejs_compat_v2")
+        || config["node_compat"].as_bool() == Some(true)
+}
+
+/// The bundle's size against its budget and its `top` largest contributors, flagging large
+/// and native ones
+pub fn render(report: &BundleReport, top: usize) -> String {
+    use colored::*;
+
+    let size = match report.budget_bytes {
+        Some(budget) => format!(
+            "{} of {} budget",
+            format_bytes(report.bytes),
+            format_bytes(budget)
+        ),
+        None => format_bytes(report.bytes),
+    };
+    let mut out = format!(
+        "{} {}\n",
+        report.project.bold(),
+        if report.over_budget() {
+            size.red().to_string()
+        } else {
+            size.dimmed().to_string()
+        }
+    );
+    for contributor in report.contributors.iter().take(top) {
+        let share = if report.bytes == 0 {
+            0.0
+        } else {
+            contributor.bytes as f64 * 100.0 / report.bytes as f64
+        };
+        let flag = if contributor.native {
+            "native binary".red().to_string()
+        } else if contributor.large {
+            "large".yellow().to_string()
+        } else {
+            String::new()
+        };
+        out.push_str(&format!(
+            "  {:>10} {:>5.1}%  {} {}\n",
+            format_bytes(contributor.bytes),
+            share,
+            contributor.name,
+            flag
+        ));
+    }
+    if report.contributors.len() > top {
+        out.push_str(&format!(
+            "{}\n",
+            format!("  ... {} more", report.contributors.len() - top).dimmed()
+        ));
+    }
+    for problem in report.problems() {
+        out.push_str(&format!("  {} {}\n", "✗".red(), problem));
+    }
+    out
+}
@@ -0,0 +1,185 @@
+use crate::utils::artifacts::output_dir;
+use crate::utils::config::AssetsConfig;
+use crate::utils::projects::WorkspaceProject;
+use anyhow::Result;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Workers static assets read response headers from this file in the assets directory
+pub const HEADERS_FILE: &str = "_headers";
+/// Logical name to hashed path of every fingerprinted file in a frontend's output
+pub const ASSET_MANIFEST_FILE: &str = "asset-manifest.json";
+/// Files in the assets directory Wrangler doesn't upload
+const ASSETS_IGNORE_FILE: &str = ".assetsignore";
+
+const BLOCK_START: &str = "# moonflare:cache:start";
+const BLOCK_END: &str = "# moonflare:cache:end";
+
+/// Output directories whose every file carries a content hash: Vite's `assets/` and Astro's
+/// `_astro/`, as the hashed-assets templates configure them
+const HASHED_DIRS: [&str; 2] = ["assets", "_astro"];
+
+/// Cloudflare only reads the first 100 rules of a `_headers` file
+const MAX_RULES: usize = 100;
+
+/// Write the cache rules for `project`'s output into its `_headers`, between markers so rules
+/// of its own are kept, along with the asset manifest. Returns the files written, or nothing
+/// when the project has no output.
+pub fn write_cache_headers(
+    project: &WorkspaceProject,
+    config: &AssetsConfig,
+) -> Result<Vec<PathBuf>> {
+    let output = output_dir(project);
+    if !output.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let hashed = hashed_files(&output)?;
+    let mut rules: Vec<String> = HASHED_DIRS
+        .iter()
+        .filter(|dir| output.join(dir).is_dir())
+        .map(|dir| format!("/{}/*", dir))
+        .collect();
+    rules.extend(
+        hashed
+            .values()
+            .filter(|file| {
+                !HASHED_DIRS
+                    .iter()
+                    .any(|dir| file.starts_with(&format!("{}/", dir)))
+            })
+            .map(|file| format!("/{}", file)),
+    );
+    if rules.len() + 1 > MAX_RULES {
+        println!(
+            "Warning: {} has {} hashed files outside {}; only the first {} get cache rules",
+            project.name,
+            rules.len(),
+            HASHED_DIRS.join(" and "),
+            MAX_RULES - 1
+        );
+        rules.truncate(MAX_RULES - 1);
+    }
+
+    let headers_path = output.join(HEADERS_FILE);
+    let existing = fs::read_to_string(&headers_path).unwrap_or_default();
+    fs::write(
+        &headers_path,
+        replace_block(&existing, &render_block(&rules, config)),
+    )?;
+
+    let manifest_path = output.join(ASSET_MANIFEST_FILE);
+    fs::write(
+        &manifest_path,
+        serde_json::to_string_pretty(&hashed)? + "\n",
+    )?;
+    ignore_in_upload(&output, ASSET_MANIFEST_FILE)?;
+
+    Ok(vec![headers_path, manifest_path])
+}
+
+/// `Cache-Control` for a file in the output, by its path relative to the output directory
+#[cfg(feature = "api")]
+pub fn cache_control(path: &str, config: &AssetsConfig) -> String {
+    if is_hashed(path) {
+        long_cache(config)
+    } else {
+        short_cache(config)
+    }
+}
+
+/// Whether a file is fingerprinted: everything in the hashed directories plus WASM modules
+/// shared-wasm:gather hashed
+fn is_hashed(path: &str) -> bool {
+    let hashed_wasm = Regex::new(r"\.[0-9a-f]{8}\.wasm$").expect("valid regex");
+    HASHED_DIRS
+        .iter()
+        .any(|dir| path.starts_with(&format!("{}/", dir)))
+        || hashed_wasm.is_match(path)
+}
+
+fn short_cache(config: &AssetsConfig) -> String {
+    match config.html_max_age {
+        0 => "public, max-age=0, must-revalidate".to_string(),
+        age => format!("public, max-age={}", age),
+    }
+}
+
+fn long_cache(config: &AssetsConfig) -> String {
+    format!("public, max-age={}, immutable", config.immutable_max_age)
+}
+
+/// Fingerprinted files under `output`, keyed by their name without the hash
+fn hashed_files(output: &Path) -> Result<BTreeMap<String, String>> {
+    let hash = Regex::new(r"[-.][A-Za-z0-9_-]{8}(\.[A-Za-z0-9]+)$").expect("valid regex");
+
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(output).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry
+            .path()
+            .strip_prefix(output)?
+            .to_string_lossy()
+            .replace('\\', "/");
+        if is_hashed(&path) {
+            files.insert(hash.replace(&path, "$1").into_owned(), path);
+        }
+    }
+    Ok(files)
+}
+
+/// Short-lived caching for everything, then long-lived caching for hashed files. Rules stack,
+/// so each hashed rule detaches the header the catch-all set.
+fn render_block(rules: &[String], config: &AssetsConfig) -> String {
+    let short = short_cache(config);
+    let long = long_cache(config);
+
+    let mut block = format!(
+        "{} (written by moonflare build from [assets] in moonflare.toml)\n/*\n  Cache-Control: {}\n",
+        BLOCK_START, short
+    );
+    for rule in rules {
+        block.push_str(&format!(
+            "{}\n  ! Cache-Control\n  Cache-Control: {}\n",
+            rule, long
+        ));
+    }
+    block.push_str(BLOCK_END);
+    block.push('\n');
+    block
+}
+
+/// Put `block` in place of the previous one, or ahead of the project's own rules so they can
+/// still override it
+fn replace_block(existing: &str, block: &str) -> String {
+    if let (Some(start), Some(end)) = (existing.find(BLOCK_START), existing.find(BLOCK_END)) {
+        let after = existing[end + BLOCK_END.len()..].trim_start_matches('\n');
+        return format!("{}{}{}", &existing[..start], block, after);
+    }
+    if existing.trim().is_empty() {
+        block.to_string()
+    } else {
+        format!("{}\n{}", block, existing)
+    }
+}
+
+fn ignore_in_upload(output: &Path, file: &str) -> Result<()> {
+    let path = output.join(ASSETS_IGNORE_FILE);
+    let mut content = fs::read_to_string(&path).unwrap_or_default();
+    if content.lines().any(|line| line.trim() == file) {
+        return Ok(());
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(file);
+    content.push('\n');
+    fs::write(path, content)?;
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use crate::utils::projects::{ARCHIVE_DIR, WorkspaceProject, discover_projects};
+use crate::utils::toolchain::GENERATED_MARKER;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The Cargo workspace every crate builds through, at the workspace root
+pub const CARGO_WORKSPACE_FILE: &str = "Cargo.toml";
+
+/// Crates created with `moonflare add crate --lib-only` carry this Moon tag
+pub const LIB_ONLY_TAG: &str = "lib-only";
+
+/// The crates in the Cargo workspace, by their path from the workspace root
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Crates {
+    /// Built through the shared workspace, into the root `target/`
+    members: Vec<String>,
+    /// Built on their own: crates with their own `[profile]` settings, which Cargo ignores in
+    /// members, and components, whose build script reads their own `target/`
+    excluded: Vec<String>,
+    /// Lib-only crates, shared with the others as `[workspace.dependencies]`
+    libraries: Vec<(String, String)>,
+}
+
+/// Whether a crate was created with `--lib-only`: a plain Rust library the WASM crates depend
+/// on, never compiled to WASM itself
+pub fn is_lib_only(project: &WorkspaceProject) -> bool {
+    fs::read_to_string(project.path.join("moon.yml"))
+        .ok()
+        .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())
+        .and_then(|config| config.get("tags")?.as_sequence().cloned())
+        .is_some_and(|tags| tags.iter().any(|tag| tag.as_str() == Some(LIB_ONLY_TAG)))
+}
+
+/// The Cargo.lock a crate resolves through: its own when it builds on its own, otherwise the
+/// root Cargo workspace's
+pub fn lockfile(crate_path: &Path) -> PathBuf {
+    let own = crate_path.join("Cargo.lock");
+    let shared = Path::new("Cargo.lock");
+    if !own.exists() && shared.exists() {
+        return shared.to_path_buf();
+    }
+    own
+}
+
+/// Bring the root Cargo.toml's `members`, `exclude` and lib-only `[workspace.dependencies]` in
+/// line with the crates on disk, creating it with the first crate. Returns what changed. A
+/// Cargo.toml moonflare didn't generate is never rewritten; the crates it's missing are
+/// returned as a note instead.
+pub fn sync_cargo_workspace() -> Result<Vec<String>> {
+    let crates = workspace_crates();
+    let path = Path::new(CARGO_WORKSPACE_FILE);
+    if !path.exists() {
+        // Crates that all build on their own have nothing to share
+        if crates.members.is_empty() {
+            return Ok(Vec::new());
+        }
+        fs::write(path, render(&crates))
+            .with_context(|| format!("Failed to write {}", CARGO_WORKSPACE_FILE))?;
+        return Ok(vec![format!(
+            "created a Cargo workspace of {}",
+            list(&crates.members)
+        )]);
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", CARGO_WORKSPACE_FILE))?;
+    let listed = listed_paths(&content, "members");
+    let missing: Vec<String> = crates
+        .members
+        .iter()
+        .filter(|member| !listed.contains(member))
+        .cloned()
+        .collect();
+    if !content.contains(GENERATED_MARKER) {
+        if missing.is_empty() {
+            return Ok(Vec::new());
+        }
+        return Ok(vec![format!(
+            "add {} to its workspace members by hand; moonflare didn't generate it",
+            list(&missing)
+        )]);
+    }
+
+    let mut changes = Vec::new();
+    let stale: Vec<String> = listed
+        .iter()
+        .filter(|member| !crates.members.contains(member))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        changes.push(format!("added {}", list(&missing)));
+    }
+    if !stale.is_empty() {
+        changes.push(format!("removed {}", list(&stale)));
+    }
+
+    let mut updated = replace_array(&content, "members", &crates.members);
+    updated = replace_array(&updated, "exclude", &excluded_paths(&crates));
+    // Path dependencies on crates that were renamed, moved or archived
+    let path_dependency =
+        Regex::new(r#"(?m)^([\w-]+)\s*=\s*\{\s*path\s*=\s*"([^"]+)"\s*\}\n"#).expect("valid regex");
+    for dependency in path_dependency.captures_iter(&content) {
+        if !Path::new(&dependency[2]).exists() {
+            updated = updated.replacen(&dependency[0], "", 1);
+            changes.push(format!("dropped {}, which is gone", &dependency[1]));
+        }
+    }
+    for (name, crate_path) in &crates.libraries {
+        if Regex::new(&format!(r"(?m)^{}\s*=", regex::escape(name)))
+            .expect("valid regex")
+            .is_match(&updated)
+        {
+            continue;
+        }
+        let line = format!("{} = {{ path = \"{}\" }}\n", name, crate_path);
+        updated = match updated.find("[workspace.dependencies]\n") {
+            Some(start) => {
+                let at = start + "[workspace.dependencies]\n".len();
+                format!("{}{}{}", &updated[..at], line, &updated[at..])
+            }
+            None => format!("{}\n[workspace.dependencies]\n{}", updated, line),
+        };
+        changes.push(format!("shared {} as a workspace dependency", name));
+    }
+
+    if updated != content {
+        fs::write(path, updated)
+            .with_context(|| format!("Failed to write {}", CARGO_WORKSPACE_FILE))?;
+    }
+    Ok(changes)
+}
+
+fn workspace_crates() -> Crates {
+    let mut crates = Crates::default();
+    for project in discover_projects() {
+        let manifest = project.path.join("Cargo.toml");
+        let Ok(content) = fs::read_to_string(&manifest) else {
+            continue;
+        };
+        let crate_path = project
+            .path
+            .to_string_lossy()
+            .replace('\\', "/")
+            .trim_start_matches("./")
+            .to_string();
+        if content.contains("[profile") || project.path.join("wit").is_dir() {
+            crates.excluded.push(crate_path);
+            continue;
+        }
+        if is_lib_only(&project) {
+            let name = content
+                .parse::<toml::Table>()
+                .ok()
+                .and_then(|manifest| {
+                    manifest
+                        .get("package")?
+                        .get("name")?
+                        .as_str()
+                        .map(str::to_string)
+                })
+                .unwrap_or_else(|| project.name.clone());
+            crates.libraries.push((name, crate_path.clone()));
+        }
+        crates.members.push(crate_path);
+    }
+    crates.members.sort();
+    crates.excluded.sort();
+    crates.libraries.sort();
+    crates
+}
+
+/// Paths Cargo leaves alone: the crates built on their own, and archived projects
+fn excluded_paths(crates: &Crates) -> Vec<String> {
+    let mut excluded = crates.excluded.clone();
+    excluded.push(ARCHIVE_DIR.to_string());
+    excluded
+}
+
+fn render(crates: &Crates) -> String {
+    let mut content = format!(
+        "# {}: members and exclude follow the crates in the workspace.\n\
+         # Dependencies the crates share can be added under [workspace.dependencies].\n\
+         [workspace]\n\
+         resolver = \"3\"\n",
+        GENERATED_MARKER
+    );
+    content.push_str(&render_array("members", &crates.members));
+    content.push_str(&render_array("exclude", &excluded_paths(crates)));
+    content.push_str("\n[workspace.dependencies]\n");
+    for (name, crate_path) in &crates.libraries {
+        content.push_str(&format!("{} = {{ path = \"{}\" }}\n", name, crate_path));
+    }
+    content
+}
+
+fn render_array(key: &str, paths: &[String]) -> String {
+    let mut array = format!("{} = [\n", key);
+    for path in paths {
+        array.push_str(&format!("  \"{}\",\n", path));
+    }
+    array.push_str("]\n");
+    array
+}
+
+/// `content` with the `key = [...]` array replaced by `paths`, or added under `[workspace]`
+fn replace_array(content: &str, key: &str, paths: &[String]) -> String {
+    let array = Regex::new(&format!(r"(?ms)^{}\s*=\s*\[.*?\]\n?", key)).expect("valid regex");
+    let rendered = render_array(key, paths);
+    if array.is_match(content) {
+        return array
+            .replace(content, regex::NoExpand(&rendered))
+            .to_string();
+    }
+    match content.find("[workspace]\n") {
+        Some(start) => {
+            let at = start + "[workspace]\n".len();
+            format!("{}{}{}", &content[..at], rendered, &content[at..])
+        }
+        None => format!("{}\n[workspace]\n{}", content, rendered),
+    }
+}
+
+/// Paths in the `[workspace]` table's `key` array
+fn listed_paths(content: &str, key: &str) -> Vec<String> {
+    content
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|manifest| manifest.get("workspace")?.get(key)?.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|path| path.as_str())
+        .map(|path| {
+            path.trim_start_matches("./")
+                .trim_end_matches('/')
+                .to_string()
+        })
+        .collect()
+}
+
+fn list(paths: &[String]) -> String {
+    paths.join(", ")
+}
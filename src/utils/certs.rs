@@ -0,0 +1,220 @@
+use crate::utils::platform;
+use anyhow::{Context, Result};
+use openssl::asn1::Asn1Time;
+use openssl::bn::{BigNum, MsbOption};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::x509::extension::{
+    AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName,
+    SubjectKeyIdentifier,
+};
+use openssl::x509::{X509, X509Builder, X509NameBuilder};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CA_NAME: &str = "Moonflare Local Development CA";
+const CA_VALIDITY_DAYS: u32 = 3650;
+// Apple platforms reject server certificates valid for longer than 825 days
+const CERT_VALIDITY_DAYS: u32 = 825;
+// Regenerate the server certificate when it is about to expire
+const RENEW_WITHIN_DAYS: u32 = 30;
+
+/// A localhost certificate signed by the local development CA
+#[derive(Debug, Clone)]
+pub struct DevCertificate {
+    pub ca_path: PathBuf,
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// The CA was created just now and still needs to be trusted by the system
+    pub ca_created: bool,
+}
+
+/// Certificates live in the user's home so trusting the CA once covers every workspace
+pub fn certs_directory() -> PathBuf {
+    platform::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".moonflare")
+        .join("certs")
+}
+
+/// Where [`ensure_dev_certificate`] keeps the CA and localhost certificate, without generating
+/// either
+pub fn dev_certificate_paths() -> DevCertificate {
+    let dir = certs_directory();
+    DevCertificate {
+        ca_path: dir.join("ca.pem"),
+        cert_path: dir.join("localhost.pem"),
+        key_path: dir.join("localhost-key.pem"),
+        ca_created: false,
+    }
+}
+
+/// Load the local CA and localhost certificate, generating whichever is missing or expiring
+pub fn ensure_dev_certificate() -> Result<DevCertificate> {
+    let dir = certs_directory();
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let DevCertificate {
+        ca_path,
+        cert_path,
+        key_path,
+        ..
+    } = dev_certificate_paths();
+    let ca_key_path = dir.join("ca-key.pem");
+
+    let existing_ca = match (fs::read(&ca_path), fs::read(&ca_key_path)) {
+        (Ok(cert), Ok(key)) => Some((X509::from_pem(&cert)?, PKey::private_key_from_pem(&key)?))
+            .filter(|(cert, _)| !expires_soon(cert)),
+        _ => None,
+    };
+
+    let ca_created = existing_ca.is_none();
+    let (ca_cert, ca_key) = match existing_ca {
+        Some(ca) => ca,
+        None => {
+            let (cert, key) = generate_ca()?;
+            write_pem(&ca_path, &cert.to_pem()?)?;
+            write_pem(&ca_key_path, &key.private_key_to_pem_pkcs8()?)?;
+            (cert, key)
+        }
+    };
+
+    let cert_is_current = !ca_created
+        && fs::read(&cert_path)
+            .ok()
+            .and_then(|pem| X509::from_pem(&pem).ok())
+            .is_some_and(|cert| !expires_soon(&cert))
+        && key_path.exists();
+
+    if !cert_is_current {
+        let (cert, key) = generate_localhost_cert(&ca_cert, &ca_key)?;
+        write_pem(&cert_path, &cert.to_pem()?)?;
+        write_pem(&key_path, &key.private_key_to_pem_pkcs8()?)?;
+    }
+
+    Ok(DevCertificate {
+        ca_path,
+        cert_path,
+        key_path,
+        ca_created,
+    })
+}
+
+/// Platform-specific command for adding the CA to the system trust store
+pub fn trust_instructions(ca_path: &Path) -> String {
+    let ca = ca_path.display();
+    match std::env::consts::OS {
+        "macos" => format!(
+            "sudo security add-trusted-cert -d -r trustRoot -k /Library/Keychains/System.keychain {}",
+            ca
+        ),
+        "windows" => format!("certutil -addstore -f ROOT {}", ca),
+        _ => format!(
+            "sudo cp {} /usr/local/share/ca-certificates/moonflare-dev-ca.crt && sudo update-ca-certificates",
+            ca
+        ),
+    }
+}
+
+fn expires_soon(cert: &X509) -> bool {
+    Asn1Time::days_from_now(RENEW_WITHIN_DAYS)
+        .map(|threshold| cert.not_after() < threshold)
+        .unwrap_or(true)
+}
+
+fn write_pem(path: &Path, pem: &[u8]) -> Result<()> {
+    fs::write(path, pem).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(())
+}
+
+fn generate_key() -> Result<PKey<Private>> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+    Ok(PKey::from_ec_key(EcKey::generate(&group)?)?)
+}
+
+fn new_builder(key: &PKey<Private>, common_name: &str, days: u32) -> Result<X509Builder> {
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_text("O", "Moonflare")?;
+    name.append_entry_by_text("CN", common_name)?;
+    let name = name.build();
+
+    let mut serial = BigNum::new()?;
+    serial.rand(128, MsbOption::MAYBE_ZERO, false)?;
+
+    let mut builder = X509::builder()?;
+    builder.set_version(2)?;
+    let serial = serial.to_asn1_integer()?;
+    builder.set_serial_number(&serial)?;
+    builder.set_subject_name(&name)?;
+    // Self-issued until signed by a CA
+    builder.set_issuer_name(&name)?;
+    builder.set_pubkey(key)?;
+    let not_before = Asn1Time::days_from_now(0)?;
+    let not_after = Asn1Time::days_from_now(days)?;
+    builder.set_not_before(&not_before)?;
+    builder.set_not_after(&not_after)?;
+    Ok(builder)
+}
+
+fn generate_ca() -> Result<(X509, PKey<Private>)> {
+    let key = generate_key()?;
+    let mut builder = new_builder(&key, CA_NAME, CA_VALIDITY_DAYS)?;
+
+    let subject = builder.x509v3_context(None, None);
+    let subject_key_id = SubjectKeyIdentifier::new().build(&subject)?;
+
+    builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
+    builder.append_extension(
+        KeyUsage::new()
+            .critical()
+            .key_cert_sign()
+            .crl_sign()
+            .build()?,
+    )?;
+    builder.append_extension(subject_key_id)?;
+    builder.sign(&key, MessageDigest::sha256())?;
+
+    Ok((builder.build(), key))
+}
+
+fn generate_localhost_cert(
+    ca_cert: &X509,
+    ca_key: &PKey<Private>,
+) -> Result<(X509, PKey<Private>)> {
+    let key = generate_key()?;
+    let mut builder = new_builder(&key, "localhost", CERT_VALIDITY_DAYS)?;
+    builder.set_issuer_name(ca_cert.subject_name())?;
+
+    let context = builder.x509v3_context(Some(ca_cert), None);
+    let subject_alt_names = SubjectAlternativeName::new()
+        .dns("localhost")
+        .dns("*.localhost")
+        .ip("127.0.0.1")
+        .ip("::1")
+        .build(&context)?;
+    let authority_key_id = AuthorityKeyIdentifier::new().keyid(false).build(&context)?;
+
+    builder.append_extension(BasicConstraints::new().build()?)?;
+    builder.append_extension(
+        KeyUsage::new()
+            .critical()
+            .digital_signature()
+            .key_encipherment()
+            .build()?,
+    )?;
+    builder.append_extension(ExtendedKeyUsage::new().server_auth().build()?)?;
+    builder.append_extension(subject_alt_names)?;
+    builder.append_extension(authority_key_id)?;
+    builder.sign(ca_key, MessageDigest::sha256())?;
+
+    Ok((builder.build(), key))
+}
@@ -0,0 +1,249 @@
+use crate::utils::config::{ChaosConfig, MoonflareConfig};
+use crate::utils::ports::local_port;
+use crate::utils::projects::WorkspaceProject;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest request head the proxy reads before giving up on a connection
+const MAX_HEAD: usize = 64 * 1024;
+
+/// Faults applied to one project's traffic, with the built-in defaults filled in
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaosSettings {
+    pub latency_ms: u64,
+    pub jitter_ms: u64,
+    pub error_rate: f64,
+    pub error_status: u16,
+    pub drop_rate: f64,
+}
+
+impl ChaosSettings {
+    pub fn from_config(config: &ChaosConfig) -> Result<Self> {
+        let settings = Self {
+            latency_ms: config.latency_ms.unwrap_or(300),
+            jitter_ms: config.jitter_ms.unwrap_or(200),
+            error_rate: config.error_rate.unwrap_or(0.05),
+            error_status: config.error_status.unwrap_or(503),
+            drop_rate: config.drop_rate.unwrap_or(0.02),
+        };
+        for (name, rate) in [
+            ("error_rate", settings.error_rate),
+            ("drop_rate", settings.drop_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                bail!("{} must be between 0.0 and 1.0, got {}", name, rate);
+            }
+        }
+        if !(100..=599).contains(&settings.error_status) {
+            bail!(
+                "error_status must be an HTTP status, got {}",
+                settings.error_status
+            );
+        }
+        Ok(settings)
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{}±{} ms latency, {}% errors ({}), {}% dropped",
+            self.latency_ms,
+            self.jitter_ms,
+            self.error_rate * 100.0,
+            self.error_status,
+            self.drop_rate * 100.0
+        )
+    }
+}
+
+/// A Worker whose dev server moves to `target_port` while the chaos proxy takes its usual port
+#[derive(Debug, Clone)]
+pub struct Upstream {
+    pub project: String,
+    pub listen_port: u16,
+    pub target_port: u16,
+    pub settings: ChaosSettings,
+}
+
+/// Upstreams for the Worker projects among `projects`, skipping those with chaos disabled
+pub fn plan(projects: &[WorkspaceProject], config: &MoonflareConfig) -> Result<Vec<Upstream>> {
+    let mut upstreams = Vec::new();
+    let mut ports: BTreeMap<u16, &str> = BTreeMap::new();
+    for project in projects.iter().filter(|p| p.is_worker()) {
+        let chaos = config.chaos_settings(&project.name);
+        if chaos.enabled == Some(false) {
+            continue;
+        }
+        let listen_port = local_port(project, config)?;
+        if let Some(other) = ports.insert(listen_port, &project.name) {
+            bail!(
+                "'{}' and '{}' both run their dev server on port {}; set dev.port in their wrangler configs so the chaos proxy can front each",
+                other,
+                project.name,
+                listen_port
+            );
+        }
+        upstreams.push(Upstream {
+            project: project.name.clone(),
+            listen_port,
+            target_port: free_port()?,
+            settings: ChaosSettings::from_config(&chaos).map_err(|e| {
+                anyhow::anyhow!("Invalid chaos settings for '{}': {}", project.name, e)
+            })?,
+        });
+    }
+    Ok(upstreams)
+}
+
+fn free_port() -> Result<u16> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Listen on the upstream's usual port
+pub async fn bind(upstream: &Upstream) -> Result<Vec<TcpListener>> {
+    let listener = TcpListener::bind(("127.0.0.1", upstream.listen_port))
+        .await
+        .with_context(|| {
+            format!(
+                "Chaos proxy for '{}' couldn't listen on port {}",
+                upstream.project, upstream.listen_port
+            )
+        })?;
+    let mut listeners = vec![listener];
+    // `localhost` resolves to ::1 first on some systems; serve it too when it's available
+    if let Ok(ipv6) = TcpListener::bind(("::1", upstream.listen_port)).await {
+        listeners.push(ipv6);
+    }
+    Ok(listeners)
+}
+
+/// Accept connections until the process exits. Each request is delayed, failed or dropped at
+/// random before reaching the dev server, and forwarded requests ask the dev server to close
+/// the connection, so every request gets its own roll.
+pub async fn serve(listeners: Vec<TcpListener>, upstream: Upstream) {
+    for listener in listeners {
+        let upstream = upstream.clone();
+        tokio::spawn(async move {
+            while let Ok((client, _)) = listener.accept().await {
+                let upstream = upstream.clone();
+                tokio::spawn(async move {
+                    let _ = handle(client, &upstream).await;
+                });
+            }
+        });
+    }
+}
+
+async fn handle(mut client: TcpStream, upstream: &Upstream) -> Result<()> {
+    let (head, rest) = read_head(&mut client).await?;
+    let request_line = head.lines().next().unwrap_or_default().to_string();
+    let settings = &upstream.settings;
+
+    if roll() < settings.drop_rate {
+        log_fault(upstream, "dropped", &request_line);
+        return Ok(());
+    }
+    let delay = settings.latency_ms + (roll() * settings.jitter_ms as f64) as u64;
+    tokio::time::sleep(Duration::from_millis(delay)).await;
+    if roll() < settings.error_rate {
+        log_fault(upstream, &settings.error_status.to_string(), &request_line);
+        let body = format!(
+            "moonflare dev --chaos: injected {} for {}\n",
+            settings.error_status, upstream.project
+        );
+        let reason = reqwest::StatusCode::from_u16(settings.error_status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("Error");
+        let response = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nX-Moonflare-Chaos: error\r\nConnection: close\r\n\r\n{}",
+            settings.error_status,
+            reason,
+            body.len(),
+            body
+        );
+        client.write_all(response.as_bytes()).await?;
+        return Ok(());
+    }
+
+    let mut server = TcpStream::connect(("127.0.0.1", upstream.target_port)).await?;
+    server.write_all(close_after(&head).as_bytes()).await?;
+    server.write_all(&rest).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut server).await?;
+    Ok(())
+}
+
+/// Read up to the end of the request head. Returns the head and any body bytes read with it.
+async fn read_head(client: &mut TcpStream) -> Result<(String, Vec<u8>)> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+    loop {
+        let read = client.read(&mut chunk).await?;
+        if read == 0 {
+            bail!("Connection closed before the request head ended");
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buffer.split_off(end + 4);
+            return Ok((String::from_utf8_lossy(&buffer).into_owned(), rest));
+        }
+        if buffer.len() > MAX_HEAD {
+            bail!("Request head is too large");
+        }
+    }
+}
+
+/// The request head with `Connection: close`, so the next request on the client's side comes
+/// through a new connection. WebSocket upgrades are passed through untouched.
+fn close_after(head: &str) -> String {
+    let lines: Vec<&str> = head.trim_end().split("\r\n").collect();
+    let header = |line: &str, name: &str| {
+        line.split_once(':')
+            .is_some_and(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+    };
+    if lines.iter().any(|line| header(line, "upgrade")) {
+        return head.to_string();
+    }
+    let mut rewritten: Vec<&str> = lines
+        .into_iter()
+        .filter(|line| !header(line, "connection") && !header(line, "keep-alive"))
+        .collect();
+    rewritten.push("Connection: close");
+    rewritten.join("\r\n") + "\r\n\r\n"
+}
+
+fn log_fault(upstream: &Upstream, fault: &str, request_line: &str) {
+    let request: Vec<&str> = request_line.split_whitespace().take(2).collect();
+    println!(
+        "{}",
+        format!(
+            "chaos {}: {} {}",
+            upstream.project,
+            fault,
+            request.join(" ")
+        )
+        .dimmed()
+    );
+}
+
+/// A number in [0, 1) from a xorshift generator seeded with the clock
+fn roll() -> f64 {
+    static STATE: AtomicU64 = AtomicU64::new(0);
+    let mut state = STATE.load(Ordering::Relaxed);
+    if state == 0 {
+        state = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0x9e37_79b9_7f4a_7c15, |elapsed| elapsed.as_nanos() as u64)
+            | 1;
+    }
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    STATE.store(state, Ordering::Relaxed);
+    (state >> 11) as f64 / (1u64 << 53) as f64
+}
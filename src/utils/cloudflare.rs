@@ -0,0 +1,649 @@
+#[cfg(feature = "api")]
+use crate::utils::{
+    auth, http,
+    retry::{self, Transient},
+};
+#[cfg(feature = "api")]
+use anyhow::{Context, Result, bail};
+#[cfg(feature = "api")]
+use serde::{Deserialize, de::DeserializeOwned};
+#[cfg(feature = "api")]
+use serde_json::{Value, json};
+
+/// Cloudflare API base URL; `CLOUDFLARE_API_BASE_URL` overrides it, as it does for wrangler
+pub const API_BASE: &str = "https://api.cloudflare.com/client/v4";
+
+/// Response envelope shared by every Cloudflare API endpoint
+#[cfg(feature = "api")]
+#[derive(Debug, Deserialize)]
+struct Envelope<T> {
+    success: bool,
+    #[serde(default)]
+    errors: Vec<ApiError>,
+    result: Option<T>,
+    #[serde(default)]
+    result_info: Option<ResultInfo>,
+}
+
+/// Pagination of list endpoints
+#[cfg(feature = "api")]
+#[derive(Debug, Default, Deserialize)]
+struct ResultInfo {
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    is_truncated: bool,
+}
+
+#[cfg(feature = "api")]
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+/// An Access application, as returned by the API
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccessApp {
+    pub id: String,
+    pub domain: String,
+}
+
+/// An Access policy attached to an application
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccessPolicy {
+    pub id: String,
+    pub name: String,
+}
+
+/// An AI Gateway, as returned by the API
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct AiGateway {
+    pub id: String,
+}
+
+/// Caching, rate limiting and logging of a new AI Gateway
+#[cfg(feature = "api")]
+#[derive(Debug, Clone)]
+pub struct AiGatewaySettings {
+    /// Seconds identical requests are answered from the cache; 0 turns caching off
+    pub cache_ttl: u64,
+    /// At most this many requests per this many seconds
+    pub rate_limit: Option<(u64, u64)>,
+    pub collect_logs: bool,
+}
+
+#[cfg(feature = "api")]
+impl AiGatewaySettings {
+    /// `caching 300s, at most 100 requests per 60s, logging requests`
+    pub fn describe(&self) -> String {
+        let mut parts = vec![match self.cache_ttl {
+            0 => "no caching".to_string(),
+            ttl => format!("caching {}s", ttl),
+        }];
+        if let Some((limit, interval)) = self.rate_limit {
+            parts.push(format!("at most {} requests per {}s", limit, interval));
+        }
+        parts.push(
+            if self.collect_logs {
+                "logging requests"
+            } else {
+                "no request logs"
+            }
+            .to_string(),
+        );
+        parts.join(", ")
+    }
+}
+
+/// Permission `access protect` and doctor's Access checks need
+pub const ACCESS_PERMISSION: &str = "Access: Apps and Policies Edit";
+/// Permission creating AI Gateways needs
+#[cfg(feature = "api")]
+pub const AI_GATEWAY_PERMISSION: &str = "AI Gateway Edit";
+/// Permission connecting repositories and triggering Workers Builds needs
+pub const BUILDS_PERMISSION: &str = "Workers Builds Configuration Edit";
+/// Permission looking up queues and sending messages to them needs
+#[cfg(feature = "api")]
+pub const QUEUES_PERMISSION: &str = "Queues Edit";
+/// Permission listing, uploading and deleting R2 objects needs
+pub const R2_PERMISSION: &str = "Workers R2 Storage Edit";
+/// Permission reading deployed Worker scripts needs
+#[cfg(feature = "api")]
+pub const SCRIPTS_PERMISSION: &str = "Workers Scripts Read";
+
+/// An object in an R2 bucket
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct R2Object {
+    pub key: String,
+    pub etag: String,
+}
+
+/// A queue, as listed by the API
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct Queue {
+    pub queue_id: String,
+    pub queue_name: String,
+}
+
+/// A Worker script, as listed by the API
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkerScript {
+    pub id: String,
+    /// Identifier Workers Builds refers to the script by
+    pub tag: String,
+    /// Tag of the Durable Object migration the script last applied
+    #[serde(default)]
+    pub migration_tag: Option<String>,
+}
+
+/// A Durable Object namespace: one class of one Worker script
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct DurableObjectNamespace {
+    pub id: String,
+    #[serde(default)]
+    pub script: Option<String>,
+    #[serde(default)]
+    pub class: Option<String>,
+    /// Whether the class stores its data in SQLite rather than key-value storage
+    #[serde(default)]
+    pub use_sqlite: bool,
+}
+
+/// A Durable Object in a namespace
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct DurableObjectInstance {
+    pub id: String,
+    #[serde(default, rename = "hasStoredData")]
+    pub has_stored_data: bool,
+}
+
+/// A repository connected to Workers Builds
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepoConnection {
+    pub repo_connection_uuid: String,
+}
+
+/// A Workers Builds trigger: which repository changes build and deploy a Worker, and how
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuildTrigger {
+    pub trigger_uuid: String,
+}
+
+/// One Workers Builds run
+#[cfg(feature = "api")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteBuild {
+    pub build_uuid: String,
+    /// `queued`, `initializing`, `running` or `stopped`
+    pub status: String,
+    /// `success`, `fail`, `cancelled` or `terminated` once the build has stopped
+    #[serde(default)]
+    pub build_outcome: Option<String>,
+}
+
+#[cfg(feature = "api")]
+impl RemoteBuild {
+    pub fn is_finished(&self) -> bool {
+        self.status == "stopped" || self.build_outcome.is_some()
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.build_outcome.as_deref() == Some("success")
+    }
+}
+
+/// Repository settings for a Workers Builds connection
+#[cfg(feature = "api")]
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// `github` or `gitlab`
+    pub provider: String,
+    pub account_id: String,
+    pub account_name: String,
+    pub repo_id: String,
+    pub repo_name: String,
+}
+
+/// Error for commands that call the Cloudflare API in a build without the `api` feature
+#[cfg(not(feature = "api"))]
+pub fn api_unavailable() -> anyhow::Error {
+    anyhow::anyhow!(
+        "This moonflare was built without the `api` feature, which this command needs to call the Cloudflare API"
+    )
+}
+
+/// Cloudflare API client authenticated with the same variables wrangler reads in CI
+#[cfg(feature = "api")]
+pub struct CloudflareClient {
+    client: reqwest::Client,
+    base: String,
+    token: String,
+    pub account_id: String,
+}
+
+#[cfg(feature = "api")]
+impl CloudflareClient {
+    /// Client from `CLOUDFLARE_API_TOKEN` and `CLOUDFLARE_ACCOUNT_ID`, falling back to the
+    /// project's wrangler `account_id`, then to the token's only account. `permission` names
+    /// what the token needs, for the error shown when it's missing.
+    pub fn from_env(account_id: Option<&str>, permission: &str) -> Result<Self> {
+        let token = std::env::var("CLOUDFLARE_API_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty())
+            .with_context(|| {
+                format!(
+                    "Set CLOUDFLARE_API_TOKEN to an API token with {} permission",
+                    permission
+                )
+            })?;
+        let account_id = std::env::var("CLOUDFLARE_ACCOUNT_ID")
+            .ok()
+            .or_else(|| account_id.map(str::to_string))
+            .or_else(auth::sole_account_id)
+            .context("Set CLOUDFLARE_ACCOUNT_ID or account_id in the project's wrangler config")?;
+
+        Ok(Self {
+            client: http::client()?,
+            base: std::env::var("CLOUDFLARE_API_BASE_URL")
+                .unwrap_or_else(|_| API_BASE.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            token,
+            account_id,
+        })
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<T> {
+        let url = format!("{}/accounts/{}{}", self.base, self.account_id, path);
+        let mut request = self
+            .client
+            .request(method.clone(), &url)
+            .bearer_auth(&self.token);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        self.send(request, &method, path)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Send `request`, retrying 5xx and 429 answers and dropped connections under `[retry]`
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        method: &reqwest::Method,
+        path: &str,
+    ) -> Result<(T, ResultInfo)> {
+        retry::run(&format!("Cloudflare API {} {}", method, path), || {
+            let attempt = request.try_clone();
+            async move {
+                let attempt = attempt.context("Cloudflare API request body can't be resent")?;
+                self.send_once(attempt, method, path).await
+            }
+        })
+        .await
+    }
+
+    async fn send_once<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        method: &reqwest::Method,
+        path: &str,
+    ) -> Result<(T, ResultInfo)> {
+        let response = match request.send().await {
+            Ok(response) => response,
+            // A request that never connected is safe to resend; others only when idempotent
+            Err(e) if e.is_connect() || (e.is_timeout() && method.is_idempotent()) => {
+                return Err(anyhow::Error::new(Transient(e.to_string()))).with_context(|| {
+                    format!("Failed to reach the Cloudflare API ({} {})", method, path)
+                });
+            }
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to reach the Cloudflare API ({} {})", method, path)
+                });
+            }
+        };
+        let status = response.status();
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let message = format!("Cloudflare API {} {} answered {}", method, path, status);
+            // A 5xx may come after the change was made, so only idempotent requests are resent
+            if method.is_idempotent() {
+                bail!(Transient(message));
+            }
+            bail!(message);
+        }
+        let envelope: Envelope<T> = response
+            .json()
+            .await
+            .with_context(|| format!("Unexpected Cloudflare API response ({})", status))?;
+
+        let Envelope {
+            success,
+            errors,
+            result,
+            result_info,
+        } = envelope;
+        // Deletes answer with a null result, which a `Value` result accepts
+        if success && let Some(result) = result.or_else(|| serde_json::from_value(Value::Null).ok())
+        {
+            return Ok((result, result_info.unwrap_or_default()));
+        }
+        let messages = errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect::<Vec<_>>()
+            .join("; ");
+        bail!(
+            "Cloudflare API {} {} failed ({}): {}",
+            method,
+            path,
+            status,
+            messages
+        )
+    }
+
+    /// The account's `<subdomain>.workers.dev` subdomain
+    pub async fn workers_subdomain(&self) -> Result<String> {
+        #[derive(Deserialize)]
+        struct Subdomain {
+            subdomain: String,
+        }
+        let result: Subdomain = self
+            .request(reqwest::Method::GET, "/workers/subdomain", None)
+            .await?;
+        Ok(result.subdomain)
+    }
+
+    /// Create a self-hosted Access application in front of `domain`
+    pub async fn create_access_app(&self, name: &str, domain: &str) -> Result<AccessApp> {
+        self.request(
+            reqwest::Method::POST,
+            "/access/apps",
+            Some(json!({
+                "name": name,
+                "domain": domain,
+                "type": "self_hosted",
+                "session_duration": "24h",
+            })),
+        )
+        .await
+    }
+
+    /// Create the AI Gateway `id`. Without a rate limit both rate limiting fields are zero,
+    /// which the API takes as unlimited.
+    pub async fn create_ai_gateway(
+        &self,
+        id: &str,
+        settings: &AiGatewaySettings,
+    ) -> Result<AiGateway> {
+        let (limit, interval) = settings.rate_limit.unwrap_or((0, 0));
+        self.request(
+            reqwest::Method::POST,
+            "/ai-gateway/gateways",
+            Some(json!({
+                "id": id,
+                "cache_ttl": settings.cache_ttl,
+                "cache_invalidate_on_update": true,
+                "collect_logs": settings.collect_logs,
+                "rate_limiting_limit": limit,
+                "rate_limiting_interval": interval,
+                "rate_limiting_technique": "fixed",
+            })),
+        )
+        .await
+    }
+
+    /// Allow everyone with an email address at `email_domain` into the application
+    pub async fn create_email_domain_policy(
+        &self,
+        app_id: &str,
+        email_domain: &str,
+    ) -> Result<AccessPolicy> {
+        self.request(
+            reqwest::Method::POST,
+            &format!("/access/apps/{}/policies", app_id),
+            Some(json!({
+                "name": format!("Allow {}", email_domain),
+                "decision": "allow",
+                "include": [{ "email_domain": { "domain": email_domain } }],
+                "precedence": 1,
+            })),
+        )
+        .await
+    }
+
+    /// The Worker script named `name`, once it has been deployed
+    pub async fn worker_script(&self, name: &str) -> Result<Option<WorkerScript>> {
+        let scripts: Vec<WorkerScript> = self
+            .request(reqwest::Method::GET, "/workers/scripts", None)
+            .await?;
+        Ok(scripts.into_iter().find(|script| script.id == name))
+    }
+
+    pub async fn durable_object_namespaces(&self) -> Result<Vec<DurableObjectNamespace>> {
+        self.request(
+            reqwest::Method::GET,
+            "/workers/durable_objects/namespaces",
+            None,
+        )
+        .await
+    }
+
+    /// Every object in a namespace that has been created, with or without stored data
+    pub async fn durable_objects(&self, namespace_id: &str) -> Result<Vec<DurableObjectInstance>> {
+        let path = format!(
+            "/workers/durable_objects/namespaces/{}/objects",
+            namespace_id
+        );
+        let url = format!("{}/accounts/{}{}", self.base, self.account_id, path);
+        let mut objects = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut query = vec![("limit", "10000".to_string())];
+            if let Some(cursor) = &cursor {
+                query.push(("cursor", cursor.clone()));
+            }
+            let request = self.client.get(&url).query(&query).bearer_auth(&self.token);
+            let (page, info): (Vec<DurableObjectInstance>, ResultInfo) =
+                self.send(request, &reqwest::Method::GET, &path).await?;
+            let empty = page.is_empty();
+            objects.extend(page);
+            // The last page answers with an empty cursor
+            match info.cursor.filter(|next| !next.is_empty() && !empty) {
+                Some(next) => cursor = Some(next),
+                None => return Ok(objects),
+            }
+        }
+    }
+
+    /// Connect a repository to Workers Builds, or return the existing connection
+    pub async fn upsert_repo_connection(&self, repository: &Repository) -> Result<RepoConnection> {
+        self.request(
+            reqwest::Method::PUT,
+            "/builds/repos/connections",
+            Some(json!({
+                "provider_type": repository.provider,
+                "provider_account_id": repository.account_id,
+                "provider_account_name": repository.account_name,
+                "repo_id": repository.repo_id,
+                "repo_name": repository.repo_name,
+            })),
+        )
+        .await
+    }
+
+    /// Create a trigger building and deploying the Worker with `script_tag` from a repository
+    pub async fn create_build_trigger(
+        &self,
+        script_tag: &str,
+        repo_connection_uuid: &str,
+        trigger: &Value,
+    ) -> Result<BuildTrigger> {
+        let mut body = trigger.clone();
+        body["external_script_id"] = json!(script_tag);
+        body["repo_connection_uuid"] = json!(repo_connection_uuid);
+        self.request(reqwest::Method::POST, "/builds/triggers", Some(body))
+            .await
+    }
+
+    /// Update an existing trigger's commands, branches and paths
+    pub async fn update_build_trigger(
+        &self,
+        trigger_uuid: &str,
+        trigger: &Value,
+    ) -> Result<BuildTrigger> {
+        self.request(
+            reqwest::Method::PATCH,
+            &format!("/builds/triggers/{}", trigger_uuid),
+            Some(trigger.clone()),
+        )
+        .await
+    }
+
+    /// Start a build of `commit` on `branch` through a trigger
+    pub async fn start_build(
+        &self,
+        trigger_uuid: &str,
+        branch: &str,
+        commit: &str,
+    ) -> Result<RemoteBuild> {
+        self.request(
+            reqwest::Method::POST,
+            &format!("/builds/triggers/{}/builds", trigger_uuid),
+            Some(json!({ "branch": branch, "commit_hash": commit })),
+        )
+        .await
+    }
+
+    pub async fn remote_build(&self, build_uuid: &str) -> Result<RemoteBuild> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/builds/builds/{}", build_uuid),
+            None,
+        )
+        .await
+    }
+
+    pub async fn access_policies(&self, app_id: &str) -> Result<Vec<AccessPolicy>> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/access/apps/{}/policies", app_id),
+            None,
+        )
+        .await
+    }
+
+    /// The queue named `name`, once it has been created
+    pub async fn queue(&self, name: &str) -> Result<Option<Queue>> {
+        let queues: Vec<Queue> = self.request(reqwest::Method::GET, "/queues", None).await?;
+        Ok(queues.into_iter().find(|queue| queue.queue_name == name))
+    }
+
+    /// Send one message to a queue, as a producer binding would; `content_type` is `json` or
+    /// `text`
+    pub async fn send_queue_message(
+        &self,
+        queue_id: &str,
+        body: &Value,
+        content_type: &str,
+    ) -> Result<()> {
+        let _: Value = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/queues/{}/messages", queue_id),
+                Some(json!({ "body": body, "content_type": content_type })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Every object in `bucket` whose key starts with `prefix`
+    pub async fn list_r2_objects(&self, bucket: &str, prefix: &str) -> Result<Vec<R2Object>> {
+        let path = format!("/r2/buckets/{}/objects", bucket);
+        let url = format!("{}/accounts/{}{}", self.base, self.account_id, path);
+        let mut objects = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut query = vec![("per_page", "1000".to_string())];
+            if !prefix.is_empty() {
+                query.push(("prefix", prefix.to_string()));
+            }
+            if let Some(cursor) = &cursor {
+                query.push(("cursor", cursor.clone()));
+            }
+            let request = self.client.get(&url).query(&query).bearer_auth(&self.token);
+            let (page, info): (Vec<R2Object>, ResultInfo) =
+                self.send(request, &reqwest::Method::GET, &path).await?;
+            objects.extend(page);
+            match info.cursor.filter(|_| info.is_truncated) {
+                Some(next) => cursor = Some(next),
+                None => return Ok(objects),
+            }
+        }
+    }
+
+    /// Upload `body` as `key`, with the headers the object is served with
+    pub async fn put_r2_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        body: Vec<u8>,
+        content_type: &str,
+        cache_control: &str,
+    ) -> Result<R2Object> {
+        let path = format!("/r2/buckets/{}/objects/{}", bucket, encode_key(key));
+        let url = format!("{}/accounts/{}{}", self.base, self.account_id, path);
+        let request = self
+            .client
+            .put(&url)
+            .bearer_auth(&self.token)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .header(reqwest::header::CACHE_CONTROL, cache_control)
+            .body(body);
+        self.send(request, &reqwest::Method::PUT, &path)
+            .await
+            .map(|(object, _)| object)
+    }
+
+    pub async fn delete_r2_object(&self, bucket: &str, key: &str) -> Result<()> {
+        let _: Value = self
+            .request(
+                reqwest::Method::DELETE,
+                &format!("/r2/buckets/{}/objects/{}", bucket, encode_key(key)),
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Percent-encode an object key for a URL path, keeping its `/` separators
+#[cfg(feature = "api")]
+fn encode_key(key: &str) -> String {
+    let mut encoded = String::with_capacity(key.len());
+    for byte in key.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
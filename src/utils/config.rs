@@ -0,0 +1,1118 @@
+use crate::errors::MoonflareError;
+use crate::ui::OutputProfile;
+use crate::utils::audit::Severity;
+use crate::utils::config_schema;
+use crate::utils::projects::glob_match;
+use crate::utils::timeout;
+use crate::utils::version::Version;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+pub const CONFIG_FILE: &str = "moonflare.toml";
+/// Alternate configuration file for a single invocation, like `--config`
+pub const CONFIG_ENV: &str = "MOONFLARE_CONFIG";
+
+/// Set from `--config`; takes precedence over `MOONFLARE_CONFIG`
+static CONFIG_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Read configuration from `path` instead of the workspace's moonflare.toml for the rest of
+/// this invocation
+pub fn set_config_path(path: &Path) {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let _ = CONFIG_OVERRIDE.set(path);
+}
+
+/// The alternate configuration from `--config` or `MOONFLARE_CONFIG`, if either was given
+pub fn config_override() -> Option<PathBuf> {
+    CONFIG_OVERRIDE.get().cloned().or_else(|| {
+        std::env::var_os(CONFIG_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// The configuration file this invocation reads
+pub fn config_path() -> PathBuf {
+    config_override().unwrap_or_else(|| PathBuf::from(CONFIG_FILE))
+}
+
+/// Workspace configuration stored in `moonflare.toml` at the workspace root
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MoonflareConfig {
+    /// Shape of the file, see [`config_schema::CONFIG_VERSION`]
+    pub config_version: Option<u64>,
+    pub workspace: WorkspaceConfig,
+    pub deploy: DeployConfig,
+    pub dev: DevConfig,
+    pub loadtest: LoadTestThresholds,
+    pub retention: RetentionConfig,
+    pub licenses: LicensesConfig,
+    pub assets: AssetsConfig,
+    pub pnpm: PnpmConfig,
+    pub env: EnvConfig,
+    pub secrets: SecretsConfig,
+    pub layout: LayoutConfig,
+    pub ui: UiConfig,
+    pub hooks: HooksConfig,
+    pub timeouts: TimeoutsConfig,
+    pub network: NetworkConfig,
+    pub retry: RetryConfig,
+    pub metrics: MetricsConfig,
+    pub tracing: TracingConfig,
+    pub crash: CrashConfig,
+    pub watch: FileWatchConfig,
+    pub matrix: MatrixConfig,
+    /// Per-project overrides, keyed by project name
+    pub projects: BTreeMap<String, ProjectConfig>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WorkspaceConfig {
+    pub name: Option<String>,
+    /// Minimum moonflare version required to operate on this workspace
+    pub min_version: Option<String>,
+    /// Pattern for deployed worker names, with `{workspace}` and `{project}` placeholders
+    /// (e.g. `{workspace}-{project}`). Workers are named after their project when unset.
+    pub worker_name: Option<String>,
+    /// Projects (names, paths or globs) that build, dev, deploy and test leave out when run
+    /// across the workspace
+    pub ignore: Vec<String>,
+    /// Projects with any of these tags are left out the same way
+    pub ignore_tags: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DeployConfig {
+    /// Git branch (or branch glob such as `release/*`) to deploy environment
+    pub branches: BTreeMap<String, String>,
+    /// Environment used for pull/merge request builds
+    pub pull_request_env: Option<String>,
+    pub audit: AuditConfig,
+    pub watch: WatchConfig,
+    pub notify: NotifyConfig,
+    pub bundle: BundleConfig,
+    /// Protection rules, keyed by environment name
+    pub environments: BTreeMap<String, EnvironmentConfig>,
+}
+
+/// Rules for deploying to one environment
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EnvironmentConfig {
+    /// Deploys need `--confirm <env>` or the environment name typed at a prompt
+    pub protected: bool,
+    /// Refuse deploys with uncommitted changes, as with `--require-clean`
+    pub require_clean: bool,
+    /// Run the audit gate, as with `--audit`
+    pub require_audit: bool,
+    /// Refuse deploys while D1 migrations are pending, as with `--require-migrations`
+    pub require_migrations: bool,
+    /// Only take what this environment runs, through `moonflare promote`, never a fresh
+    /// deploy
+    pub promote_from: Option<String>,
+}
+
+/// Settings for `deploy --watch`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WatchConfig {
+    /// Environment watch mode deploys to when `--env` isn't given
+    pub environment: Option<String>,
+    /// How long files must stay unchanged before a deploy starts
+    pub debounce_ms: u64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            environment: None,
+            debounce_ms: 750,
+        }
+    }
+}
+
+/// Size limits for Worker bundles, checked by `moonflare bundle analyze` and before deploys
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BundleConfig {
+    /// Largest a Worker's bundle may be in KB, before compression; deploys over it are refused
+    pub budget_kb: Option<u64>,
+    /// Flag a single file or package that adds at least this many KB to a bundle
+    pub large_input_kb: u64,
+}
+
+impl Default for BundleConfig {
+    fn default() -> Self {
+        Self {
+            budget_kb: None,
+            large_input_kb: 256,
+        }
+    }
+}
+
+/// Where deploy summaries are posted
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Notify after every deploy; when false, only deploys run with `--notify` notify
+    pub enabled: bool,
+    /// Slack, Discord or generic JSON webhook URL, or `$VAR` to read it from the environment
+    pub webhook: Option<String>,
+    /// Message with `{status}`, `{env}`, `{projects}`, `{duration}`, `{sha}`, `{actor}` and
+    /// `{error}` placeholders
+    pub template: Option<String>,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            webhook: None,
+            template: None,
+        }
+    }
+}
+
+impl NotifyConfig {
+    /// The webhook URL, resolving a `$VAR` reference
+    pub fn webhook_url(&self) -> Result<Option<String>> {
+        match self.webhook.as_deref() {
+            Some(var) if var.starts_with('$') => {
+                std::env::var(&var[1..]).map(Some).with_context(|| {
+                    format!("[deploy.notify] webhook refers to {}, which isn't set", var)
+                })
+            }
+            webhook => Ok(webhook.map(str::to_string)),
+        }
+    }
+}
+
+/// Thresholds for `deploy --audit`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// Block the deploy on advisories of this severity or higher
+    pub fail_on: Severity,
+    /// Advisory ids (GHSA-..., RUSTSEC-...) that never block a deploy
+    pub ignore: Vec<String>,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            fail_on: Severity::High,
+            ignore: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    /// Deployed URL of the project, used when targeting it from the CLI
+    pub url: Option<String>,
+    pub dev: ProjectDevConfig,
+    pub loadtest: LoadTestThresholds,
+    pub pnpm: ProjectPnpmConfig,
+    /// Variables layered over the workspace `[env]` for this project's tasks
+    pub env: EnvConfig,
+    pub deploy: ProjectDeployConfig,
+    pub publish: ProjectPublishConfig,
+    pub placement: ProjectPlacementConfig,
+    /// Commands (build, dev, deploy, test) that leave the project out when run across the
+    /// workspace
+    pub skip: Vec<String>,
+}
+
+/// How `moonflare deploy` ships a project
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProjectDeployConfig {
+    pub strategy: DeployStrategy,
+    /// R2 bucket the `r2-static` strategy uploads to; `{env}` is replaced with the target
+    /// environment, or `production` without one
+    pub bucket: Option<String>,
+    /// Key prefix for uploaded objects, e.g. `docs/`
+    pub prefix: Option<String>,
+    /// Pages project the `pages` strategy deploys to, the worker name by default
+    pub pages_project: Option<String>,
+    /// Bundle size budget in KB for this Worker, over `[deploy.bundle] budget_kb`
+    pub bundle_budget_kb: Option<u64>,
+}
+
+/// Where `moonflare publish` sends a package or crate
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProjectPublishConfig {
+    /// npm registry URL, or the name of a registry in `.cargo/config.toml` for crates
+    pub registry: Option<String>,
+    /// npm access level of scoped packages, `public` or `restricted`
+    pub access: Option<String>,
+    /// npm dist-tag, `latest` by default
+    pub tag: Option<String>,
+    /// Publish npm packages with a provenance attestation
+    pub provenance: bool,
+}
+
+/// Where Cloudflare runs a project's Worker and Durable Objects, written to its Wrangler
+/// configuration by `moonflare placement set`
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProjectPlacementConfig {
+    pub mode: Option<PlacementMode>,
+    /// Region new Durable Objects of each binding are created in, e.g. `COUNTER = "weur"`
+    pub location_hints: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlacementMode {
+    /// Smart Placement: run the Worker close to the back ends it calls
+    Smart,
+    /// Run the Worker in the data center that received the request
+    Off,
+}
+
+impl PlacementMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Smart => "smart",
+            Self::Off => "off",
+        }
+    }
+}
+
+/// `wrangler` runs the project's deploy task; the others build it and then ship its output
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum DeployStrategy {
+    #[default]
+    Wrangler,
+    /// `wrangler pages deploy` of the build output
+    Pages,
+    /// Sync the build output to an R2 bucket fronted by a custom domain or CDN
+    R2Static,
+    /// `custom:<command>`, run in the project directory after the build
+    Custom(String),
+}
+
+impl TryFrom<String> for DeployStrategy {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        match value.as_str() {
+            "wrangler" => Ok(Self::Wrangler),
+            "pages" => Ok(Self::Pages),
+            "r2-static" => Ok(Self::R2Static),
+            _ => match value.strip_prefix("custom:").map(str::trim) {
+                Some(command) if !command.is_empty() => Ok(Self::Custom(command.to_string())),
+                _ => Err(format!(
+                    "unknown deploy strategy '{}'; use wrangler, pages, r2-static or custom:<command>",
+                    value
+                )),
+            },
+        }
+    }
+}
+
+impl From<DeployStrategy> for String {
+    fn from(strategy: DeployStrategy) -> Self {
+        match strategy {
+            DeployStrategy::Wrangler => "wrangler".to_string(),
+            DeployStrategy::Pages => "pages".to_string(),
+            DeployStrategy::R2Static => "r2-static".to_string(),
+            DeployStrategy::Custom(command) => format!("custom:{}", command),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProjectDevConfig {
+    /// Run `wrangler dev --remote` for this project regardless of the `--remote` flag
+    pub remote: Option<bool>,
+    /// Path on the dev server, or a full URL, that answers once the server is ready; without
+    /// it the dev port accepting connections counts as ready
+    pub ready: Option<String>,
+    /// Faults injected into this project's traffic by `dev --chaos`, over `[dev.chaos]`
+    pub chaos: ChaosConfig,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DevConfig {
+    /// Faults `moonflare dev --chaos` injects into every Worker's traffic
+    pub chaos: ChaosConfig,
+    /// Projects whose dev servers start first, in this order, when they start in stages; the
+    /// rest follow, Workers before frontends
+    pub order: Vec<String>,
+    /// Start dev servers in stages, at most this many waiting to become ready at once
+    pub max_parallel: Option<usize>,
+    /// How long a dev server gets to become ready before the next starts anyway
+    pub ready_timeout_secs: Option<u64>,
+}
+
+/// Faults injected by the `dev --chaos` proxy; unset settings use the built-in defaults
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ChaosConfig {
+    /// Proxy this project's traffic at all; false leaves it untouched
+    pub enabled: Option<bool>,
+    /// Delay added before each request is forwarded
+    pub latency_ms: Option<u64>,
+    /// Up to this much more delay, picked at random per request
+    pub jitter_ms: Option<u64>,
+    /// Fraction of requests answered with `error_status` instead, from 0.0 to 1.0
+    pub error_rate: Option<f64>,
+    pub error_status: Option<u16>,
+    /// Fraction of connections closed without a response, from 0.0 to 1.0
+    pub drop_rate: Option<f64>,
+}
+
+impl ChaosConfig {
+    /// Fill settings missing here from `defaults`
+    pub fn or(&self, defaults: &ChaosConfig) -> ChaosConfig {
+        ChaosConfig {
+            enabled: self.enabled.or(defaults.enabled),
+            latency_ms: self.latency_ms.or(defaults.latency_ms),
+            jitter_ms: self.jitter_ms.or(defaults.jitter_ms),
+            error_rate: self.error_rate.or(defaults.error_rate),
+            error_status: self.error_status.or(defaults.error_status),
+            drop_rate: self.drop_rate.or(defaults.drop_rate),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LicensesConfig {
+    /// SPDX identifiers (globs allowed, e.g. `GPL-*`) that `moonflare licenses` flags
+    pub disallowed: Vec<String>,
+    /// Write `third-party-licenses.txt` into each frontend's dist/ and a licenses module into
+    /// each worker on every `moonflare build`
+    pub bundle: bool,
+}
+
+/// Cache headers for frontend output, written on every `moonflare build`
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AssetsConfig {
+    /// Fingerprint gathered WASM and give hashed assets long-lived cache headers in each
+    /// frontend's dist/_headers
+    pub hashing: bool,
+    /// `max-age` in seconds for hashed assets, which are also marked `immutable`
+    pub immutable_max_age: u64,
+    /// `max-age` in seconds for HTML and every other unhashed file
+    pub html_max_age: u64,
+}
+
+impl Default for AssetsConfig {
+    fn default() -> Self {
+        Self {
+            hashing: false,
+            immutable_max_age: 31_536_000,
+            html_max_age: 0,
+        }
+    }
+}
+
+/// Hoisting settings written to the workspace `.npmrc`
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PnpmConfig {
+    /// Hoist every dependency to the root node_modules (`shamefully-hoist`)
+    pub shamefully_hoist: bool,
+    /// Packages hoisted to the root node_modules (`public-hoist-pattern[]`)
+    pub public_hoist_pattern: Vec<String>,
+    /// `hoist`; false keeps undeclared dependencies out of `.pnpm/node_modules`
+    pub hoist: Option<bool>,
+    /// `node-linker`: isolated, hoisted or pnp
+    pub node_linker: Option<String>,
+}
+
+/// Hoisting a single project needs. pnpm only reads hoisting settings from the workspace
+/// root, so these are merged into the root `.npmrc`.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProjectPnpmConfig {
+    pub public_hoist_pattern: Vec<String>,
+}
+
+/// Variables moonflare passes to the moon and wrangler processes it starts. Tables such as
+/// `[env.production]` only apply while that environment is active.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct EnvConfig(BTreeMap<String, EnvEntry>);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum EnvEntry {
+    Environment(BTreeMap<String, toml::Value>),
+    Value(toml::Value),
+}
+
+impl EnvConfig {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Base variables, overridden by those of `environment` when one is active
+    pub fn resolve(&self, environment: Option<&str>) -> BTreeMap<String, String> {
+        let mut vars: BTreeMap<String, String> = self
+            .0
+            .iter()
+            .filter_map(|(name, entry)| match entry {
+                EnvEntry::Value(value) => Some((name.clone(), env_value(value))),
+                EnvEntry::Environment(_) => None,
+            })
+            .collect();
+
+        if let Some(EnvEntry::Environment(overrides)) = environment.and_then(|env| self.0.get(env))
+        {
+            vars.extend(
+                overrides
+                    .iter()
+                    .map(|(name, value)| (name.clone(), env_value(value))),
+            );
+        }
+        vars
+    }
+}
+
+fn env_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(value) => value.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Where `env pull` and `secrets push` read secret values from. Settings may contain
+/// `{project}` and `{env}`, replaced with the project name and target environment.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SecretsConfig {
+    pub provider: Option<SecretProviderKind>,
+    pub onepassword: OnePasswordConfig,
+    pub vault: VaultConfig,
+    pub doppler: DopplerConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretProviderKind {
+    #[serde(rename = "1password")]
+    OnePassword,
+    Vault,
+    Doppler,
+}
+
+/// Fields of a 1Password item, read with `op item get`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OnePasswordConfig {
+    pub vault: Option<String>,
+    pub item: String,
+}
+
+impl Default for OnePasswordConfig {
+    fn default() -> Self {
+        Self {
+            vault: None,
+            item: "{project}-{env}".to_string(),
+        }
+    }
+}
+
+/// A KV secret read with `vault kv get`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct VaultConfig {
+    pub path: String,
+}
+
+impl Default for VaultConfig {
+    fn default() -> Self {
+        Self {
+            path: "secret/{project}/{env}".to_string(),
+        }
+    }
+}
+
+/// A Doppler config read with `doppler secrets download`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DopplerConfig {
+    pub project: String,
+    pub config: String,
+}
+
+impl Default for DopplerConfig {
+    fn default() -> Self {
+        Self {
+            project: "{project}".to_string(),
+            config: "{env}".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Output profile when `--ui` isn't given: full, compact or minimal
+    pub profile: Option<OutputProfile>,
+}
+
+/// Git hooks written by `moonflare hooks install`. Commands run from the workspace root.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub manager: HookManager,
+    /// Run before each commit
+    pub pre_commit: Vec<String>,
+    /// Run before each push
+    pub pre_push: Vec<String>,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            manager: HookManager::Git,
+            pre_commit: vec!["moon run :check --affected --status staged".to_string()],
+            pre_push: vec![
+                "moonflare config lint".to_string(),
+                "moon run :lint :typecheck --affected".to_string(),
+            ],
+        }
+    }
+}
+
+/// How hooks are installed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookManager {
+    /// Scripts in the repository's hooks directory
+    #[default]
+    Git,
+    /// A lefthook.yml at the repository root, installed with `lefthook install`
+    Lefthook,
+}
+
+/// Time boxes for commands, e.g. `build = "20m"`. `--timeout` takes precedence.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TimeoutsConfig {
+    /// Time a stopped subprocess gets between SIGTERM and SIGKILL, 10s by default
+    pub grace: Option<TimeLimit>,
+    /// Limits keyed by command name (`build`, `deploy`, `test`, ...)
+    #[serde(flatten)]
+    pub commands: BTreeMap<String, TimeLimit>,
+}
+
+impl TimeoutsConfig {
+    /// The time box of `command`, if it has one
+    pub fn limit(&self, command: &str) -> Option<Duration> {
+        self.commands.get(command).map(|limit| limit.0)
+    }
+
+    pub fn grace(&self) -> Duration {
+        self.grace.map_or(timeout::DEFAULT_GRACE, |grace| grace.0)
+    }
+}
+
+/// Proxy and certificate settings for moonflare's own HTTP requests and the tools it runs.
+/// Unset values fall back to `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` and `SSL_CERT_FILE`/
+/// `NODE_EXTRA_CA_CERTS`.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Proxy URL for every request, e.g. `http://proxy.corp.example:3128`
+    pub proxy: Option<String>,
+    /// Comma-separated hosts that bypass the proxy
+    pub no_proxy: Option<String>,
+    /// PEM file of extra root certificates, e.g. a TLS-inspecting proxy's CA
+    pub ca_bundle: Option<PathBuf>,
+}
+
+/// Retries for deploys, secret uploads and Cloudflare API calls that fail with a 5xx, a 429
+/// or a dropped connection. `attempts = 1` turns retries off.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Tries in total, including the first; 3 by default
+    pub attempts: Option<u32>,
+    /// Wait after the first failure, doubled after each one after that; 2s by default
+    pub backoff: Option<TimeLimit>,
+    /// Longest wait between attempts; 30s by default
+    pub max_backoff: Option<TimeLimit>,
+}
+
+/// Where command timings go for fleet-wide dashboards; off unless `enabled` and an
+/// `endpoint` are set
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub format: MetricsFormat,
+    /// Collector URL: a Worker writing to Analytics Engine, or an OTLP/HTTP receiver
+    pub endpoint: Option<String>,
+    /// Bearer token for the endpoint, or `$VAR` to read it from the environment
+    pub token: Option<String>,
+    /// Analytics Engine dataset the collector writes to, sent along with every data point
+    pub dataset: Option<String>,
+    /// Commands whose runs are exported
+    pub commands: Vec<String>,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: MetricsFormat::default(),
+            endpoint: None,
+            token: None,
+            dataset: None,
+            commands: ["build", "deploy", "test"].map(str::to_string).to_vec(),
+        }
+    }
+}
+
+impl MetricsConfig {
+    /// Whether runs of `command` are exported
+    pub fn exports(&self, command: &str) -> bool {
+        self.enabled && self.endpoint.is_some() && self.commands.iter().any(|c| c == command)
+    }
+
+    /// The token, resolving a `$VAR` reference
+    #[cfg(feature = "telemetry")]
+    pub fn token(&self) -> Result<Option<String>> {
+        match self.token.as_deref() {
+            Some(var) if var.starts_with('$') => std::env::var(&var[1..])
+                .map(Some)
+                .with_context(|| format!("[metrics] token refers to {}, which isn't set", var)),
+            token => Ok(token.map(str::to_string)),
+        }
+    }
+}
+
+/// Standard OpenTelemetry variable read when neither `--otel-endpoint` nor `[tracing]` is set
+pub const TRACING_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// OTLP receiver for spans of each command, its Moon tasks and its subprocesses. Unset
+/// values fall back to `OTEL_EXPORTER_OTLP_ENDPOINT` and `OTEL_EXPORTER_OTLP_HEADERS`.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TracingConfig {
+    /// OTLP/HTTP base URL; spans go to `<endpoint>/v1/traces`
+    pub endpoint: Option<String>,
+    /// Bearer token for the endpoint, or `$VAR` to read it from the environment
+    pub token: Option<String>,
+}
+
+impl TracingConfig {
+    /// The receiver's base URL: `--otel-endpoint`, then `endpoint`, then
+    /// `OTEL_EXPORTER_OTLP_ENDPOINT`
+    pub fn endpoint(&self, flag: Option<&str>) -> Option<String> {
+        flag.map(str::to_string)
+            .or_else(|| self.endpoint.clone())
+            .or_else(|| std::env::var(TRACING_ENDPOINT_ENV).ok())
+            .filter(|endpoint| !endpoint.trim().is_empty())
+    }
+
+    /// The token, resolving a `$VAR` reference
+    #[cfg(feature = "telemetry")]
+    pub fn token(&self) -> Result<Option<String>> {
+        match self.token.as_deref() {
+            Some(var) if var.starts_with('$') => std::env::var(&var[1..])
+                .map(Some)
+                .with_context(|| format!("[tracing] token refers to {}, which isn't set", var)),
+            token => Ok(token.map(str::to_string)),
+        }
+    }
+}
+
+/// Where `--report` sends the diagnostics bundle of a crash. Without an endpoint, `--report`
+/// prints a link to open an issue with the bundle attached instead.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CrashConfig {
+    pub endpoint: Option<String>,
+    /// Bearer token for the endpoint, or `$VAR` to read it from the environment
+    pub token: Option<String>,
+}
+
+impl CrashConfig {
+    /// The token, resolving a `$VAR` reference
+    pub fn token(&self) -> Result<Option<String>> {
+        match self.token.as_deref() {
+            Some(var) if var.starts_with('$') => std::env::var(&var[1..])
+                .map(Some)
+                .with_context(|| format!("[crash] token refers to {}, which isn't set", var)),
+            token => Ok(token.map(str::to_string)),
+        }
+    }
+}
+
+/// Rules for `moonflare watch`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FileWatchConfig {
+    /// How long files must stay unchanged before the targets run
+    pub debounce_ms: u64,
+    /// Glob of workspace files (e.g. `workers/api/src/**`) to the Moon targets run when one
+    /// of them changes
+    pub rules: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for FileWatchConfig {
+    fn default() -> Self {
+        Self {
+            debounce_ms: 300,
+            rules: BTreeMap::new(),
+        }
+    }
+}
+
+/// Tool versions `moonflare verify-matrix` builds the workspace with, every combination once.
+/// An empty list keeps the version the workspace pins.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MatrixConfig {
+    /// Node.js versions, installed with proto
+    pub node: Vec<String>,
+    /// Wrangler versions, forced on every project with a pnpm override
+    pub wrangler: Vec<String>,
+    /// Moon versions, installed with proto
+    pub moon: Vec<String>,
+    /// Moon task run across the workspace in each combination
+    pub task: String,
+}
+
+impl Default for MatrixConfig {
+    fn default() -> Self {
+        Self {
+            node: Vec::new(),
+            wrangler: Vec::new(),
+            moon: Vec::new(),
+            task: "build".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MetricsFormat {
+    /// Data points shaped like `writeDataPoint` input, for a collector Worker to write
+    #[default]
+    AnalyticsEngine,
+    /// OTLP/HTTP JSON, posted to `<endpoint>/v1/metrics`
+    Otlp,
+}
+
+/// A duration written as seconds (`90`) or with a unit (`"90s"`, `"15m"`, `"2h"`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "TimeLimitValue", into = "String")]
+pub struct TimeLimit(pub Duration);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TimeLimitValue {
+    Seconds(u64),
+    Text(String),
+}
+
+impl TryFrom<TimeLimitValue> for TimeLimit {
+    type Error = String;
+
+    fn try_from(value: TimeLimitValue) -> std::result::Result<Self, Self::Error> {
+        match value {
+            TimeLimitValue::Seconds(secs) => timeout::parse_duration(&secs.to_string()),
+            TimeLimitValue::Text(text) => timeout::parse_duration(&text),
+        }
+        .map(TimeLimit)
+    }
+}
+
+impl From<TimeLimit> for String {
+    fn from(limit: TimeLimit) -> Self {
+        timeout::format_duration(limit.0)
+    }
+}
+
+/// Directories each kind of project lives in, changed with `moonflare layout set`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    /// Astro sites
+    pub sites: String,
+    /// React apps
+    pub apps: String,
+    /// Workers and Durable Objects
+    pub workers: String,
+    /// Rust crates compiled to WebAssembly
+    pub crates: String,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            sites: "sites".to_string(),
+            apps: "apps".to_string(),
+            workers: "workers".to_string(),
+            crates: "crates".to_string(),
+        }
+    }
+}
+
+impl LayoutConfig {
+    /// Layout keys paired with the project type their directory holds
+    pub const KINDS: [(&'static str, &'static str); 4] = [
+        ("sites", "astro"),
+        ("apps", "react"),
+        ("workers", "durable-object"),
+        ("crates", "crate"),
+    ];
+
+    /// The layout of the workspace in the current directory; defaults when moonflare.toml is
+    /// missing or doesn't parse
+    pub fn current() -> Self {
+        MoonflareConfig::load()
+            .map(|config| config.layout)
+            .unwrap_or_default()
+    }
+
+    pub fn directory(&self, kind: &str) -> Option<&str> {
+        match kind {
+            "sites" => Some(&self.sites),
+            "apps" => Some(&self.apps),
+            "workers" => Some(&self.workers),
+            "crates" => Some(&self.crates),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, kind: &str, directory: &str) -> bool {
+        let field = match kind {
+            "sites" => &mut self.sites,
+            "apps" => &mut self.apps,
+            "workers" => &mut self.workers,
+            "crates" => &mut self.crates,
+            _ => return false,
+        };
+        *field = directory.to_string();
+        true
+    }
+
+    /// Project directories paired with the project type they contain
+    pub fn directories(&self) -> Vec<(String, &'static str)> {
+        Self::KINDS
+            .iter()
+            .map(|(kind, project_type)| {
+                (
+                    self.directory(kind).unwrap_or(kind).to_string(),
+                    *project_type,
+                )
+            })
+            .collect()
+    }
+}
+
+/// How long snapshots, reports and build artifacts are kept by `clean --auto`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    /// Always keep this many of the newest snapshots and reports
+    pub keep_last: Option<usize>,
+    /// Remove artifacts older than this, beyond the newest `keep_last`
+    pub max_age_days: Option<u64>,
+    /// Apply the policy automatically after every successful `moonflare build`
+    pub prune_after_build: bool,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            keep_last: Some(10),
+            max_age_days: None,
+            prune_after_build: false,
+        }
+    }
+}
+
+impl RetentionConfig {
+    pub fn max_age(&self) -> Option<std::time::Duration> {
+        self.max_age_days
+            .map(|days| std::time::Duration::from_secs(days * 24 * 60 * 60))
+    }
+}
+
+/// Limits that make `moonflare loadtest` fail; unset limits aren't checked
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LoadTestThresholds {
+    pub max_p50_ms: Option<f64>,
+    pub max_p95_ms: Option<f64>,
+    pub max_p99_ms: Option<f64>,
+    /// Fraction of failed requests, from 0.0 to 1.0
+    pub max_error_rate: Option<f64>,
+}
+
+impl LoadTestThresholds {
+    /// Fill limits missing here from `defaults`
+    pub fn or(&self, defaults: &LoadTestThresholds) -> LoadTestThresholds {
+        LoadTestThresholds {
+            max_p50_ms: self.max_p50_ms.or(defaults.max_p50_ms),
+            max_p95_ms: self.max_p95_ms.or(defaults.max_p95_ms),
+            max_p99_ms: self.max_p99_ms.or(defaults.max_p99_ms),
+            max_error_rate: self.max_error_rate.or(defaults.max_error_rate),
+        }
+    }
+}
+
+impl MoonflareConfig {
+    /// Load the workspace configuration, or the `--config` file when one was given, falling
+    /// back to defaults when no file exists
+    pub fn load() -> Result<Self> {
+        Self::load_from(&config_path())
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut config: toml::Value =
+            toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+        config_schema::migrate(&mut config)
+            .with_context(|| format!("Invalid {}", path.display()))?;
+        match config.clone().try_into() {
+            Ok(parsed) => {
+                config_schema::warn(path, &config);
+                Ok(parsed)
+            }
+            Err(e) => {
+                // The schema's messages name the key and suggest what was probably meant
+                let problems = config_schema::check(&config);
+                if problems.is_empty() {
+                    anyhow::bail!("Invalid {}: {}", path.display(), e.message());
+                }
+                anyhow::bail!("Invalid {}:\n  {}", path.display(), problems.join("\n  "))
+            }
+        }
+    }
+
+    pub fn project(&self, name: &str) -> Option<&ProjectConfig> {
+        self.projects.get(name)
+    }
+
+    /// Name a project's worker is deployed under, following `[workspace] worker_name`
+    pub fn worker_name(&self, project: &str) -> String {
+        match &self.workspace.worker_name {
+            Some(pattern) => pattern
+                .replace(
+                    "{workspace}",
+                    self.workspace.name.as_deref().unwrap_or_default(),
+                )
+                .replace("{project}", project)
+                .trim_matches('-')
+                .to_string(),
+            None => project.to_string(),
+        }
+    }
+
+    /// The project's deployed URL in `env`, from `url` under `[projects.<name>]` with `{env}`
+    /// replaced (`production` without an environment). A URL without the placeholder is the
+    /// production one only.
+    pub fn project_url(&self, project: &str, env: Option<&str>) -> Option<String> {
+        let url = self.project(project)?.url.as_deref()?;
+        let env = env.unwrap_or("production");
+        if url.contains("{env}") {
+            Some(url.replace("{env}", env))
+        } else {
+            (env == "production").then(|| url.to_string())
+        }
+    }
+
+    /// How a project is deployed, per `[projects.<name>.deploy]`
+    pub fn deploy_settings(&self, project: &str) -> ProjectDeployConfig {
+        self.project(project)
+            .map(|config| config.deploy.clone())
+            .unwrap_or_default()
+    }
+
+    /// Bundle size budget of a project in KB, with the project's own overriding
+    /// `[deploy.bundle] budget_kb`
+    pub fn bundle_budget_kb(&self, project: &str) -> Option<u64> {
+        self.project(project)
+            .and_then(|config| config.deploy.bundle_budget_kb)
+            .or(self.deploy.bundle.budget_kb)
+    }
+
+    /// Load-test limits for a project, with project settings overriding `[loadtest]`
+    pub fn loadtest_thresholds(&self, project: &str) -> LoadTestThresholds {
+        match self.project(project) {
+            Some(config) => config.loadtest.or(&self.loadtest),
+            None => self.loadtest.clone(),
+        }
+    }
+
+    /// Chaos settings for a project, with project settings overriding `[dev.chaos]`
+    pub fn chaos_settings(&self, project: &str) -> ChaosConfig {
+        match self.project(project) {
+            Some(config) => config.dev.chaos.or(&self.dev.chaos),
+            None => self.dev.chaos.clone(),
+        }
+    }
+
+    /// Fail when the workspace requires a newer moonflare than the running binary, or sets a
+    /// min_version that isn't a version
+    pub fn check_min_version(&self) -> std::result::Result<(), Box<MoonflareError>> {
+        let Some(required) = self.workspace.min_version.as_deref() else {
+            return Ok(());
+        };
+
+        let current = Version::current();
+        match Version::parse(required) {
+            Some(required_version) if required_version > current => Err(Box::new(
+                MoonflareError::workspace_requires_newer_version(required, &current.to_string()),
+            )),
+            Some(_) => Ok(()),
+            None => Err(Box::new(MoonflareError::invalid_min_version(required))),
+        }
+    }
+}
+
+impl DeployConfig {
+    /// Rules for `environment` when it's protected
+    pub fn protection(&self, environment: &str) -> Option<&EnvironmentConfig> {
+        self.environments
+            .get(environment)
+            .filter(|rules| rules.protected)
+    }
+
+    /// The environment whose deployments are the only ones `environment` takes
+    pub fn promotion_source(&self, environment: &str) -> Option<&str> {
+        self.environments.get(environment)?.promote_from.as_deref()
+    }
+
+    /// Environment mapped to a branch; exact names win over glob patterns
+    pub fn environment_for_branch(&self, branch: &str) -> Option<&str> {
+        if let Some(env) = self.branches.get(branch) {
+            return Some(env.as_str());
+        }
+
+        self.branches
+            .iter()
+            .find(|(pattern, _)| glob_match(pattern, branch))
+            .map(|(_, env)| env.as_str())
+    }
+}
@@ -0,0 +1,298 @@
+use crate::utils::backup::{Snapshot, create_snapshot};
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::wrangler::{find_wrangler_config, read_wrangler_config, write_wrangler_config};
+use anyhow::Result;
+use serde_json::{Map, Value, json};
+use std::path::{Path, PathBuf};
+
+/// A deprecated key in a Wrangler configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    /// `env.<name>` for keys inside an environment, None at the top level
+    pub scope: Option<String>,
+    pub key: String,
+    /// What the key is replaced with, or what to do by hand
+    pub advice: String,
+    /// Wrangler 4 rejects the key outright rather than ignoring it
+    pub rejected: bool,
+    /// The rule rewrites the key itself
+    pub fixable: bool,
+}
+
+impl Deprecation {
+    pub fn describe(&self) -> String {
+        let key = match &self.scope {
+            Some(scope) => format!("{}.{}", scope, self.key),
+            None => self.key.clone(),
+        };
+        format!("'{}' is deprecated: {}", key, self.advice)
+    }
+}
+
+/// Deprecated keys found in one project's Wrangler configuration
+#[derive(Debug)]
+pub struct ConfigLint {
+    pub project: String,
+    pub path: PathBuf,
+    pub deprecations: Vec<Deprecation>,
+}
+
+/// Deprecated keys in the Wrangler configuration of each project that has one
+pub fn lint_projects(projects: &[WorkspaceProject]) -> Result<Vec<ConfigLint>> {
+    let mut lints = Vec::new();
+    for project in projects {
+        let (Some(path), Some(mut config)) = (
+            find_wrangler_config(&project.path),
+            read_wrangler_config(&project.path)?,
+        ) else {
+            continue;
+        };
+        let deprecations = upgrade(&mut config);
+        if !deprecations.is_empty() {
+            lints.push(ConfigLint {
+                project: project.name.clone(),
+                path,
+                deprecations,
+            });
+        }
+    }
+    Ok(lints)
+}
+
+/// Rewrite the fixable deprecated keys in each configuration `lints` found, after a snapshot
+/// of the files it's about to change. Returns the snapshot, or None when nothing was fixable.
+pub fn fix_projects(lints: &[ConfigLint]) -> Result<Option<Snapshot>> {
+    let paths: Vec<&Path> = lints
+        .iter()
+        .filter(|lint| lint.deprecations.iter().any(|d| d.fixable))
+        .map(|lint| lint.path.as_path())
+        .collect();
+    if paths.is_empty() {
+        return Ok(None);
+    }
+
+    let snapshot = create_snapshot("config lint --fix", &paths)?;
+    for path in paths {
+        let Some(project_path) = path.parent() else {
+            continue;
+        };
+        if let Some(mut config) = read_wrangler_config(project_path)? {
+            upgrade(&mut config);
+            write_wrangler_config(path, &config)?;
+        }
+    }
+    Ok(Some(snapshot))
+}
+
+/// Rewrite the deprecated keys in `config` to their modern equivalents, at the top level and
+/// in every environment, and report each one found, including those that need a hand fix
+pub fn upgrade(config: &mut Value) -> Vec<Deprecation> {
+    let mut deprecations = Vec::new();
+    let Some(top) = config.as_object_mut() else {
+        return deprecations;
+    };
+
+    upgrade_top_level(top, &mut deprecations);
+    upgrade_scope(top, None, &mut deprecations);
+
+    if let Some(envs) = top.get_mut("env").and_then(|e| e.as_object_mut()) {
+        for (name, env) in envs.iter_mut() {
+            if let Some(env) = env.as_object_mut() {
+                upgrade_scope(env, Some(&format!("env.{}", name)), &mut deprecations);
+            }
+        }
+    }
+    deprecations
+}
+
+/// Keys only the top level of a configuration carries
+fn upgrade_top_level(config: &mut Map<String, Value>, deprecations: &mut Vec<Deprecation>) {
+    let mut found = |key: &str, advice: &str, rejected: bool, fixable: bool| {
+        deprecations.push(Deprecation {
+            scope: None,
+            key: key.to_string(),
+            advice: advice.to_string(),
+            rejected,
+            fixable,
+        })
+    };
+
+    // Wrangler 1's project type; webpack builds have no Wrangler 2+ equivalent
+    if let Some(kind) = config
+        .get("type")
+        .and_then(|t| t.as_str())
+        .map(String::from)
+    {
+        if kind == "webpack" || config.contains_key("webpack_config") {
+            found(
+                "type",
+                "Wrangler no longer runs webpack; bundle with the project's own build and point 'main' at the output",
+                true,
+                false,
+            );
+        } else {
+            config.shift_remove("type");
+            found(
+                "type",
+                "removed, Wrangler infers the project type",
+                true,
+                true,
+            );
+        }
+    }
+
+    // The service-worker era custom build output: `main` now names the entry point directly,
+    // and upload rules moved to the top level
+    if let Some(upload) = config
+        .get_mut("build")
+        .and_then(|b| b.as_object_mut())
+        .and_then(|b| b.shift_remove("upload"))
+    {
+        if !config.contains_key("main")
+            && let Some(main) = upload.get("main").and_then(|m| m.as_str())
+        {
+            let dir = upload
+                .get("dir")
+                .and_then(|d| d.as_str())
+                .unwrap_or("dist")
+                .trim_end_matches('/');
+            let main = main.trim_start_matches("./");
+            config.insert("main".to_string(), json!(format!("{}/{}", dir, main)));
+        }
+        if let Some(rules) = upload.get("rules")
+            && !config.contains_key("rules")
+        {
+            config.insert("rules".to_string(), rules.clone());
+        }
+        if config
+            .get("build")
+            .and_then(|b| b.as_object())
+            .is_some_and(|b| b.is_empty())
+        {
+            config.shift_remove("build");
+        }
+        found(
+            "build.upload",
+            "moved to 'main' (and 'rules') at the top level",
+            true,
+            true,
+        );
+    }
+
+    // Workers Sites were superseded by Workers static assets, which serve files without a
+    // KV-backed handler in the Worker
+    if config.contains_key("site") {
+        found(
+            "site",
+            "Workers Sites are replaced by static assets; move the bucket to 'assets.directory' and drop @cloudflare/kv-asset-handler",
+            false,
+            false,
+        );
+    }
+}
+
+/// Keys that may appear at the top level or inside an environment
+fn upgrade_scope(
+    config: &mut Map<String, Value>,
+    scope: Option<&str>,
+    deprecations: &mut Vec<Deprecation>,
+) {
+    let mut found = |key: &str, advice: &str, rejected: bool| {
+        deprecations.push(Deprecation {
+            scope: scope.map(String::from),
+            key: key.to_string(),
+            advice: advice.to_string(),
+            rejected,
+            fixable: true,
+        })
+    };
+
+    // Wrangler 4 removed the polyfill-based Node.js compatibility in favour of the runtime flag
+    if let Some(node_compat) = config.shift_remove("node_compat") {
+        if node_compat.as_bool() == Some(true) {
+            add_compatibility_flag(config, "nodejs_compat");
+            found(
+                "node_compat",
+                "replaced by compatibility_flags = [\"nodejs_compat\"]",
+                true,
+            );
+        } else {
+            found("node_compat", "removed, it was disabled", true);
+        }
+    }
+
+    // nodejs_compat includes the v2 behaviour since the 2024-09-23 compatibility date
+    if let Some(flags) = config
+        .get_mut("compatibility_flags")
+        .and_then(|f| f.as_array_mut())
+        && flags.iter().any(|f| f == "nodejs_compat_v2")
+    {
+        flags.retain(|f| f != "nodejs_compat_v2");
+        add_compatibility_flag(config, "nodejs_compat");
+        found(
+            "compatibility_flags",
+            "\"nodejs_compat_v2\" is now \"nodejs_compat\"",
+            false,
+        );
+    }
+
+    // Standard usage replaced the bundled and unbound models
+    if config.shift_remove("usage_model").is_some() {
+        found(
+            "usage_model",
+            "removed, every Worker now uses Standard pricing",
+            false,
+        );
+    }
+
+    // Wrangler 1 spelled the KV key with a hyphen
+    if let Some(namespaces) = config.shift_remove("kv-namespaces") {
+        if let Some(existing) = config
+            .entry("kv_namespaces")
+            .or_insert_with(|| json!([]))
+            .as_array_mut()
+        {
+            existing.extend(namespaces.as_array().cloned().unwrap_or_default());
+        }
+        found("kv-namespaces", "renamed to kv_namespaces", true);
+    }
+
+    // The beta static assets key
+    if let Some(assets) = config.shift_remove("experimental_assets") {
+        config.entry("assets").or_insert(assets);
+        found("experimental_assets", "renamed to assets", true);
+    }
+
+    // Wrangler 1 took the zone separately from a single route
+    if let Some(zone_id) = config.shift_remove("zone_id") {
+        if let Some(route) = config.shift_remove("route") {
+            let route = match route {
+                Value::String(pattern) => json!({ "pattern": pattern, "zone_id": zone_id }),
+                other => other,
+            };
+            if let Some(routes) = config
+                .entry("routes")
+                .or_insert_with(|| json!([]))
+                .as_array_mut()
+            {
+                routes.push(route);
+            }
+        }
+        found(
+            "zone_id",
+            "moved onto the route as routes = [{ pattern, zone_id }]",
+            true,
+        );
+    }
+}
+
+fn add_compatibility_flag(config: &mut Map<String, Value>, flag: &str) {
+    let flags = config
+        .entry("compatibility_flags")
+        .or_insert_with(|| json!([]));
+    if let Some(flags) = flags.as_array_mut()
+        && !flags.iter().any(|f| f == flag)
+    {
+        flags.push(json!(flag));
+    }
+}
@@ -0,0 +1,462 @@
+use crate::utils::placement::LOCATION_HINTS;
+use crate::utils::wrangler_schema::{self, Problem};
+use anyhow::{Result, bail};
+use colored::*;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Shape of moonflare.toml this moonflare reads and writes, recorded as `config_version`.
+/// Configs without one predate versioning.
+pub const CONFIG_VERSION: u64 = 1;
+
+/// Steps from each older version to the next, in order. Each moves keys, given as dotted
+/// paths, to where the next version reads them.
+const MIGRATIONS: [Migration; 1] = [
+    // Version 0 configs already have version 1's shape; only the version is recorded
+    Migration {
+        from: 0,
+        moves: &[],
+    },
+];
+
+struct Migration {
+    from: u64,
+    moves: &'static [(&'static str, &'static str)],
+}
+
+/// Configs whose problems were already printed, so each is warned about once per invocation
+static WARNED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// The config's `config_version`, 0 when it has none
+pub fn version(config: &toml::Value) -> Result<u64> {
+    match config.get("config_version") {
+        None => Ok(0),
+        Some(toml::Value::Integer(version)) if *version >= 0 => Ok(*version as u64),
+        Some(other) => bail!(
+            "config_version must be a whole number, not {}",
+            other.type_str()
+        ),
+    }
+}
+
+/// Bring a config up to the current version in memory, returning what moved. Configs from a
+/// newer moonflare are refused rather than half understood.
+pub fn migrate(config: &mut toml::Value) -> Result<Vec<String>> {
+    let from = version(config)?;
+    if from > CONFIG_VERSION {
+        bail!(
+            "config_version {} is newer than this moonflare understands ({}); upgrade with 'moonflare self update'",
+            from,
+            CONFIG_VERSION
+        );
+    }
+
+    let mut changes = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= from) {
+        for (old, new) in migration.moves {
+            if let Some(value) = take(config, old) {
+                put(config, new, value);
+                changes.push(format!("moved {} to {}", old, new));
+            }
+        }
+    }
+    if let Some(table) = config.as_table_mut() {
+        table.insert(
+            "config_version".to_string(),
+            toml::Value::Integer(CONFIG_VERSION as i64),
+        );
+    }
+    Ok(changes)
+}
+
+fn take(config: &mut toml::Value, key: &str) -> Option<toml::Value> {
+    let (parent, last) = match key.rsplit_once('.') {
+        Some((parent, last)) => (lookup(config, parent)?, last),
+        None => (config, key),
+    };
+    parent.as_table_mut()?.remove(last)
+}
+
+fn lookup<'a>(config: &'a mut toml::Value, key: &str) -> Option<&'a mut toml::Value> {
+    key.split('.')
+        .try_fold(config, |value, part| value.as_table_mut()?.get_mut(part))
+}
+
+fn put(config: &mut toml::Value, key: &str, value: toml::Value) {
+    let mut table = config;
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().unwrap_or(key);
+    for part in parts {
+        let Some(map) = table.as_table_mut() else {
+            return;
+        };
+        table = map
+            .entry(part)
+            .or_insert_with(|| toml::Value::Table(Default::default()));
+    }
+    if let Some(map) = table.as_table_mut() {
+        map.entry(last).or_insert(value);
+    }
+}
+
+/// Record the current version in a config's text, keeping its comments: replacing a top-level
+/// `config_version`, else ahead of the first table after the file's opening comments
+pub fn set_version(content: &str) -> String {
+    let line = format!("config_version = {}", CONFIG_VERSION);
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let first_table = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+    let existing = (0..first_table).find(|&index| {
+        lines[index]
+            .split_once('=')
+            .is_some_and(|(key, _)| key.trim() == "config_version")
+    });
+    match existing {
+        Some(index) => lines[index] = line,
+        None => {
+            let header = lines
+                .iter()
+                .take_while(|l| l.trim_start().starts_with('#'))
+                .count();
+            let mut insert = vec![line];
+            if header > 0 {
+                insert.insert(0, String::new());
+            }
+            if lines.get(header).is_some_and(|l| !l.trim().is_empty()) {
+                insert.push(String::new());
+            }
+            lines.splice(header..header, insert);
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Where a config departs from the schema, as `key: problem` lines
+pub fn check(config: &toml::Value) -> Vec<String> {
+    let Ok(value) = serde_json::to_value(config) else {
+        return Vec::new();
+    };
+    wrangler_schema::validate(&schema(), &value)
+        .iter()
+        .map(describe)
+        .collect()
+}
+
+/// Print a config's schema problems as warnings, the first time it's loaded
+pub fn warn(path: &Path, config: &toml::Value) {
+    let problems = check(config);
+    if problems.is_empty()
+        || !WARNED
+            .lock()
+            .expect("config warnings poisoned")
+            .insert(path.to_path_buf())
+    {
+        return;
+    }
+    for problem in problems {
+        eprintln!(
+            "{}",
+            format!("Warning: {}: {}", path.display(), problem).yellow()
+        );
+    }
+}
+
+/// `key: message`, with unknown keys reported against the table holding them
+fn describe(problem: &Problem) -> String {
+    let pointer = if problem.at_key {
+        problem
+            .pointer
+            .rsplit_once('/')
+            .map_or("", |(parent, _)| parent)
+    } else {
+        &problem.pointer
+    };
+    let key = pointer
+        .split('/')
+        .skip(1)
+        .map(|part| part.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>()
+        .join(".");
+    if key.is_empty() {
+        problem.message.clone()
+    } else {
+        format!("{}: {}", key, problem.message)
+    }
+}
+
+/// JSON Schema for moonflare.toml, for editors such as Taplo to complete and check it
+pub fn schema() -> Value {
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "moonflare.toml",
+        "description": "Moonflare workspace configuration",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "config_version": {
+                "description": "Shape of this file, upgraded by 'moonflare config migrate'",
+                "type": "integer",
+                "minimum": 0,
+                "maximum": CONFIG_VERSION
+            },
+            "workspace": object(&[
+                ("name", string("Workspace name")),
+                ("min_version", string("Minimum moonflare version required to operate on this workspace")),
+                ("worker_name", string("Pattern for deployed worker names, with {workspace} and {project} placeholders")),
+                ("ignore", strings("Projects (names, paths or globs) that build, dev, deploy and test leave out when run across the workspace")),
+                ("ignore_tags", strings("Projects with any of these tags are left out the same way")),
+            ]),
+            "deploy": object(&[
+                ("branches", map(json!({"type": "string"}), "Git branch (or branch glob such as release/*) to deploy environment")),
+                ("pull_request_env", string("Environment used for pull/merge request builds")),
+                ("audit", object(&[
+                    ("fail_on", one_of(&["low", "moderate", "high", "critical"], "Block the deploy on advisories of this severity or higher")),
+                    ("ignore", strings("Advisory ids (GHSA-..., RUSTSEC-...) that never block a deploy")),
+                ])),
+                ("watch", object(&[
+                    ("environment", string("Environment watch mode deploys to when --env isn't given")),
+                    ("debounce_ms", integer("How long files must stay unchanged before a deploy starts")),
+                ])),
+                ("notify", object(&[
+                    ("enabled", boolean("Notify after every deploy; when false, only deploys run with --notify notify")),
+                    ("webhook", string("Slack, Discord or generic JSON webhook URL, or $VAR to read it from the environment")),
+                    ("template", string("Message with {status}, {env}, {projects}, {duration}, {sha}, {actor} and {error} placeholders")),
+                ])),
+                ("bundle", object(&[
+                    ("budget_kb", integer("Largest a Worker's bundle may be in KB, before compression; deploys over it are refused")),
+                    ("large_input_kb", integer("Flag a single file or package that adds at least this many KB to a bundle")),
+                ])),
+                ("environments", map(json!({"$ref": "#/definitions/environment"}), "Protection rules, keyed by environment name")),
+            ]),
+            "dev": object(&[
+                ("chaos", json!({"$ref": "#/definitions/chaos"})),
+                ("order", strings("Projects whose dev servers start first, in this order, when they start in stages")),
+                ("max_parallel", integer("Start dev servers in stages, at most this many waiting to become ready at once")),
+                ("ready_timeout_secs", integer("How long a dev server gets to become ready before the next starts anyway")),
+            ]),
+            "loadtest": {"$ref": "#/definitions/loadtest"},
+            "retention": object(&[
+                ("keep_last", integer("Always keep this many of the newest snapshots and reports")),
+                ("max_age_days", integer("Remove artifacts older than this, beyond the newest keep_last")),
+                ("prune_after_build", boolean("Apply the policy automatically after every successful moonflare build")),
+            ]),
+            "licenses": object(&[
+                ("disallowed", strings("SPDX identifiers (globs allowed, e.g. GPL-*) that moonflare licenses flags")),
+                ("bundle", boolean("Bundle license notices into every frontend and worker on moonflare build")),
+            ]),
+            "assets": object(&[
+                ("hashing", boolean("Fingerprint gathered WASM and give hashed assets long-lived cache headers")),
+                ("immutable_max_age", integer("max-age in seconds for hashed assets, which are also marked immutable")),
+                ("html_max_age", integer("max-age in seconds for HTML and every other unhashed file")),
+            ]),
+            "pnpm": object(&[
+                ("shamefully_hoist", boolean("Hoist every dependency to the root node_modules")),
+                ("public_hoist_pattern", strings("Packages hoisted to the root node_modules")),
+                ("hoist", boolean("false keeps undeclared dependencies out of .pnpm/node_modules")),
+                ("node_linker", one_of(&["isolated", "hoisted", "pnp"], "pnpm's node-linker")),
+            ]),
+            "env": {"$ref": "#/definitions/env"},
+            "secrets": object(&[
+                ("provider", one_of(&["1password", "vault", "doppler"], "Where env pull and secrets push read secret values from")),
+                ("onepassword", object(&[
+                    ("vault", string("1Password vault")),
+                    ("item", string("Item name, with {project} and {env} placeholders")),
+                ])),
+                ("vault", object(&[
+                    ("path", string("KV path, with {project} and {env} placeholders")),
+                ])),
+                ("doppler", object(&[
+                    ("project", string("Doppler project, with {project} and {env} placeholders")),
+                    ("config", string("Doppler config, with {project} and {env} placeholders")),
+                ])),
+            ]),
+            "layout": object(&[
+                ("sites", string("Directory of Astro sites")),
+                ("apps", string("Directory of React apps")),
+                ("workers", string("Directory of Workers and Durable Objects")),
+                ("crates", string("Directory of Rust crates compiled to WebAssembly")),
+            ]),
+            "ui": object(&[
+                ("profile", one_of(&["full", "compact", "minimal"], "Output profile when --ui isn't given")),
+            ]),
+            "hooks": object(&[
+                ("manager", one_of(&["git", "lefthook"], "How hooks are installed")),
+                ("pre_commit", strings("Commands run before each commit")),
+                ("pre_push", strings("Commands run before each push")),
+            ]),
+            "timeouts": {
+                "description": "Time boxes keyed by command name (build, deploy, test, ...); --timeout takes precedence",
+                "type": "object",
+                "properties": {
+                    "grace": duration("Time a stopped subprocess gets between SIGTERM and SIGKILL, 10s by default")
+                },
+                "additionalProperties": {"$ref": "#/definitions/duration"}
+            },
+            "network": object(&[
+                ("proxy", string("Proxy URL for every request")),
+                ("no_proxy", string("Comma-separated hosts that bypass the proxy")),
+                ("ca_bundle", string("PEM file of extra root certificates")),
+            ]),
+            "retry": object(&[
+                ("attempts", integer("Tries in total, including the first; 3 by default")),
+                ("backoff", duration("Wait after the first failure, doubled after each one after that")),
+                ("max_backoff", duration("Longest wait between attempts")),
+            ]),
+            "metrics": object(&[
+                ("enabled", boolean("Export command timings")),
+                ("format", one_of(&["analytics-engine", "otlp"], "Shape of exported data points")),
+                ("endpoint", string("Collector URL: a Worker writing to Analytics Engine, or an OTLP/HTTP receiver")),
+                ("token", string("Bearer token for the endpoint, or $VAR to read it from the environment")),
+                ("dataset", string("Analytics Engine dataset the collector writes to")),
+                ("commands", strings("Commands whose runs are exported")),
+            ]),
+            "tracing": object(&[
+                ("endpoint", string("OTLP/HTTP base URL; spans go to <endpoint>/v1/traces")),
+                ("token", string("Bearer token for the endpoint, or $VAR to read it from the environment")),
+            ]),
+            "crash": object(&[
+                ("endpoint", string("Where --report sends the diagnostics bundle of a crash")),
+                ("token", string("Bearer token for the endpoint, or $VAR to read it from the environment")),
+            ]),
+            "watch": object(&[
+                ("debounce_ms", integer("How long files must stay unchanged before the targets run")),
+                ("rules", map(json!({"type": "array", "items": {"type": "string"}}), "Glob of workspace files to the Moon targets run when one of them changes")),
+            ]),
+            "matrix": object(&[
+                ("node", strings("Node.js versions moonflare verify-matrix builds with, installed with proto")),
+                ("wrangler", strings("Wrangler versions moonflare verify-matrix forces on every project")),
+                ("moon", strings("Moon versions moonflare verify-matrix builds with, installed with proto")),
+                ("task", string("Moon task run across the workspace in each combination; build by default")),
+            ]),
+            "projects": map(json!({"$ref": "#/definitions/project"}), "Per-project overrides, keyed by project name"),
+        },
+        "definitions": {
+            "environment": object(&[
+                ("protected", boolean("Deploys need --confirm <env> or the environment name typed at a prompt")),
+                ("require_clean", boolean("Refuse deploys with uncommitted changes")),
+                ("require_audit", boolean("Run the audit gate")),
+                ("require_migrations", boolean("Refuse deploys while D1 migrations are pending")),
+                ("promote_from", string("Only take what this environment runs, through moonflare promote")),
+            ]),
+            "chaos": object(&[
+                ("enabled", boolean("Proxy this traffic at all; false leaves it untouched")),
+                ("latency_ms", integer("Delay added before each request is forwarded")),
+                ("jitter_ms", integer("Up to this much more delay, picked at random per request")),
+                ("error_rate", number("Fraction of requests answered with error_status instead, from 0.0 to 1.0")),
+                ("error_status", integer("Status of injected errors")),
+                ("drop_rate", number("Fraction of connections closed without a response, from 0.0 to 1.0")),
+            ]),
+            "loadtest": object(&[
+                ("max_p50_ms", number("Fail when the median latency exceeds this")),
+                ("max_p95_ms", number("Fail when the 95th percentile latency exceeds this")),
+                ("max_p99_ms", number("Fail when the 99th percentile latency exceeds this")),
+                ("max_error_rate", number("Fraction of failed requests, from 0.0 to 1.0")),
+            ]),
+            "env": {
+                "description": "Variables passed to moon and wrangler; tables such as [env.production] apply while that environment is active",
+                "type": "object",
+                "additionalProperties": {
+                    "anyOf": [
+                        {"$ref": "#/definitions/scalar"},
+                        {"type": "object", "additionalProperties": {"$ref": "#/definitions/scalar"}}
+                    ]
+                }
+            },
+            "scalar": {"type": ["string", "number", "boolean"]},
+            "duration": {
+                "description": "Seconds, or a duration with a unit such as \"90s\", \"15m\" or \"2h\"",
+                "type": ["integer", "string"]
+            },
+            "project": object(&[
+                ("url", string("Deployed URL of the project; {env} stands for the environment")),
+                ("dev", object(&[
+                    ("remote", boolean("Run wrangler dev --remote for this project regardless of --remote")),
+                    ("ready", string("Path on the dev server, or a full URL, that answers once the server is ready")),
+                    ("chaos", json!({"$ref": "#/definitions/chaos"})),
+                ])),
+                ("loadtest", json!({"$ref": "#/definitions/loadtest"})),
+                ("pnpm", object(&[
+                    ("public_hoist_pattern", strings("Packages this project needs hoisted to the root node_modules")),
+                ])),
+                ("env", json!({"$ref": "#/definitions/env"})),
+                ("deploy", object(&[
+                    ("strategy", json!({
+                        "description": "wrangler, pages, r2-static or custom:<command>",
+                        "anyOf": [
+                            {"enum": ["wrangler", "pages", "r2-static"]},
+                            {"type": "string", "pattern": "^custom:"}
+                        ]
+                    })),
+                    ("bucket", string("R2 bucket the r2-static strategy uploads to; {env} is replaced with the target environment")),
+                    ("prefix", string("Key prefix for uploaded objects")),
+                    ("pages_project", string("Pages project the pages strategy deploys to")),
+                    ("bundle_budget_kb", integer("Bundle size budget in KB for this Worker, over [deploy.bundle] budget_kb")),
+                ])),
+                ("publish", object(&[
+                    ("registry", string("npm registry URL, or a registry in .cargo/config.toml for crates")),
+                    ("access", one_of(&["public", "restricted"], "npm access level of scoped packages")),
+                    ("tag", string("npm dist-tag, latest by default")),
+                    ("provenance", boolean("Publish npm packages with a provenance attestation")),
+                ])),
+                ("placement", object(&[
+                    ("mode", one_of(&["smart", "off"], "Smart Placement runs the Worker close to the back ends it calls")),
+                    ("location_hints", map(
+                        one_of(&LOCATION_HINTS, "Region new Durable Objects of the binding are created in"),
+                        "Durable Object location hint by binding name",
+                    )),
+                ])),
+                ("skip", json!({
+                    "description": "Commands that leave the project out when run across the workspace",
+                    "type": "array",
+                    "items": {"enum": ["build", "dev", "deploy", "test"]}
+                })),
+            ]),
+        }
+    })
+}
+
+/// A table that takes only `properties`
+fn object(properties: &[(&str, Value)]) -> Value {
+    let properties: Map<String, Value> = properties
+        .iter()
+        .map(|(key, schema)| (key.to_string(), schema.clone()))
+        .collect();
+    json!({"type": "object", "properties": properties, "additionalProperties": false})
+}
+
+/// A table with any keys, each holding `values`
+fn map(values: Value, description: &str) -> Value {
+    json!({"description": description, "type": "object", "additionalProperties": values})
+}
+
+fn string(description: &str) -> Value {
+    json!({"description": description, "type": "string"})
+}
+
+fn strings(description: &str) -> Value {
+    json!({"description": description, "type": "array", "items": {"type": "string"}})
+}
+
+fn boolean(description: &str) -> Value {
+    json!({"description": description, "type": "boolean"})
+}
+
+fn integer(description: &str) -> Value {
+    json!({"description": description, "type": "integer", "minimum": 0})
+}
+
+fn number(description: &str) -> Value {
+    json!({"description": description, "type": "number"})
+}
+
+fn one_of(values: &[&str], description: &str) -> Value {
+    json!({"description": description, "type": "string", "enum": values})
+}
+
+fn duration(description: &str) -> Value {
+    json!({"description": description, "$ref": "#/definitions/duration"})
+}
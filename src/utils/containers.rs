@@ -0,0 +1,179 @@
+use crate::utils::fs::path_to_root;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::wrangler::{find_wrangler_config, read_wrangler_config, write_wrangler_config};
+use anyhow::{Context, Result, bail};
+use convert_case::{Case, Casing};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::Path;
+
+/// Where container images live, next to the Workers that run them
+pub const CONTAINERS_DIR: &str = "containers";
+
+/// Instances Wrangler may start for a newly attached container class
+const DEFAULT_MAX_INSTANCES: u64 = 5;
+
+/// Package providing the `Container` Durable Object class
+const CONTAINERS_PACKAGE: (&str, &str) = ("@cloudflare/containers", "^0.0.25");
+
+/// Durable Object class a Worker runs the container `name` through
+pub fn class_name(name: &str) -> String {
+    format!("{}Container", name.to_case(Case::Pascal))
+}
+
+/// Durable Object binding the Worker reaches the container `name` with
+pub fn binding(name: &str) -> String {
+    format!("{}_CONTAINER", name.to_case(Case::ScreamingSnake))
+}
+
+/// Wire the container in `container_path` into `worker`: a `containers` entry building its
+/// Dockerfile, a Durable Object binding and migration for its class, the class itself in
+/// `src/containers/<name>.ts` exported from the Worker's entry point, and the
+/// `@cloudflare/containers` dependency
+pub fn attach(worker: &WorkspaceProject, name: &str, container_path: &Path) -> Result<()> {
+    let Some(config_path) = find_wrangler_config(&worker.path) else {
+        bail!(
+            "'{}' has no Wrangler configuration to attach the container to",
+            worker.name
+        );
+    };
+    let mut config = read_wrangler_config(&worker.path)?.unwrap_or_else(|| json!({}));
+    let class = class_name(name);
+    let dockerfile = format!(
+        "{}/{}/Dockerfile",
+        path_to_root(&worker.path),
+        container_path.to_string_lossy().replace('\\', "/")
+    );
+    add_container(&mut config, &class, &binding(name), &dockerfile)
+        .with_context(|| format!("Can't update {}", config_path.display()))?;
+    write_wrangler_config(&config_path, &config)?;
+
+    write_class(&worker.path, name, &class)?;
+    add_dependency(&worker.path)?;
+    Ok(())
+}
+
+/// Add (or update) the container, its binding and a migration creating its class
+fn add_container(config: &mut Value, class: &str, binding: &str, image: &str) -> Result<()> {
+    let Some(root) = config.as_object_mut() else {
+        bail!("the configuration isn't an object");
+    };
+
+    let containers = root
+        .entry("containers")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .context("containers isn't a list")?;
+    let entry = json!({
+        "class_name": class,
+        "image": image,
+        "max_instances": DEFAULT_MAX_INSTANCES,
+    });
+    match containers.iter_mut().find(|c| c["class_name"] == class) {
+        Some(existing) => existing["image"] = json!(image),
+        None => containers.push(entry),
+    }
+
+    let bindings = root
+        .entry("durable_objects")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .context("durable_objects isn't an object")?
+        .entry("bindings")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .context("durable_objects.bindings isn't a list")?;
+    if !bindings.iter().any(|b| b["class_name"] == class) {
+        bindings.push(json!({ "name": binding, "class_name": class }));
+    }
+
+    let migrations = root
+        .entry("migrations")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .context("migrations isn't a list")?;
+    let migrated = migrations.iter().any(|migration| {
+        ["new_classes", "new_sqlite_classes"].iter().any(|field| {
+            migration[field]
+                .as_array()
+                .is_some_and(|classes| classes.iter().any(|c| c == class))
+        })
+    });
+    if !migrated {
+        migrations.push(json!({
+            "tag": format!("v{}", migrations.len() + 1),
+            "new_sqlite_classes": [class],
+        }));
+    }
+    Ok(())
+}
+
+/// Write the container class and export it from the Worker's entry point, where Wrangler
+/// looks for Durable Object classes
+fn write_class(worker_path: &Path, name: &str, class: &str) -> Result<()> {
+    let class_file = worker_path
+        .join("src/containers")
+        .join(format!("{}.ts", name));
+    if !class_file.exists() {
+        fs::create_dir_all(class_file.parent().expect("class file has a parent"))?;
+        fs::write(
+            &class_file,
+            format!(
+                r#"import {{ Container }} from "{package}";
+
+/**
+ * Runs the image built from {dir}/{name}. Each instance of the class starts its own
+ * container on the first request and stops it after sleepAfter without traffic.
+ */
+export class {class} extends Container {{
+  defaultPort = 8080;
+  sleepAfter = "10m";
+}}
+"#,
+                package = CONTAINERS_PACKAGE.0,
+                dir = CONTAINERS_DIR,
+                name = name,
+                class = class
+            ),
+        )?;
+    }
+
+    let entry = worker_path.join("src/index.ts");
+    if let Ok(content) = fs::read_to_string(&entry) {
+        let export = format!("export {{ {} }} from \"./containers/{}\";", class, name);
+        if !content.contains(&export) {
+            let separator = if content.ends_with('\n') { "" } else { "\n" };
+            fs::write(&entry, format!("{}{}\n{}\n", content, separator, export))?;
+        }
+    }
+    Ok(())
+}
+
+/// Add `@cloudflare/containers` to the Worker's dependencies
+fn add_dependency(worker_path: &Path) -> Result<()> {
+    let path = worker_path.join("package.json");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let mut package: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let Some(dependencies) = package
+        .as_object_mut()
+        .map(|p| p.entry("dependencies").or_insert_with(|| json!({})))
+        .and_then(Value::as_object_mut)
+    else {
+        return Ok(());
+    };
+    if dependencies.contains_key(CONTAINERS_PACKAGE.0) {
+        return Ok(());
+    }
+    dependencies.insert(
+        CONTAINERS_PACKAGE.0.to_string(),
+        json!(CONTAINERS_PACKAGE.1),
+    );
+    fs::write(
+        &path,
+        format!("{}\n", serde_json::to_string_pretty(&package)?),
+    )?;
+    Ok(())
+}
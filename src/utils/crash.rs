@@ -0,0 +1,303 @@
+use crate::errors::MoonflareError;
+use crate::utils::config::{CrashConfig, MoonflareConfig};
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::http;
+use crate::utils::repro::{REDACTED, SECRET_KEY_PATTERN, redact, tool_versions};
+use crate::utils::version::Version;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use regex::Regex;
+use serde::Serialize;
+use std::backtrace::Backtrace;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Set to make moonflare panic as it starts, or to `error` to make the command fail with an
+/// internal error, to check crash reports reach their endpoint
+#[cfg(feature = "debug-panic")]
+pub const PANIC_ENV: &str = "MOONFLARE_DEBUG_PANIC";
+
+/// Directory crash bundles are written to inside a workspace; elsewhere they go to the
+/// system's temporary directory
+const CRASH_DIR: &str = ".moonflare";
+
+/// Lines kept from the end of the last subprocess's output
+const OUTPUT_LINES: usize = 200;
+
+/// How long `--report` waits for the crash endpoint
+const REPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+const ISSUES_URL: &str = concat!(env!("CARGO_PKG_REPOSITORY"), "/issues/new");
+
+/// The subprocess that finished most recently, for the bundle of a crash that follows it
+static LAST_SUBPROCESS: Mutex<Option<Subprocess>> = Mutex::new(None);
+
+/// The redacted arguments and `--report` flag [`install`] was given
+static INSTALLED: OnceLock<(Vec<String>, bool)> = OnceLock::new();
+
+/// An error that points at a bug in moonflare rather than at the project or its environment,
+/// so it leaves a diagnostics bundle behind as a panic does
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+#[error("{0}")]
+pub struct Internal(pub String);
+
+/// Whether `error`, or anything it wraps, is a bug in moonflare
+pub fn is_internal(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.is::<Internal>()
+            || cause
+                .downcast_ref::<MoonflareError>()
+                .is_some_and(MoonflareError::is_internal)
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Subprocess {
+    pub command: String,
+    pub exit_code: Option<i32>,
+    /// The end of its output; empty when it wrote straight to the terminal
+    pub output: String,
+}
+
+/// Everything written to `.moonflare/crash-<ts>.json` when moonflare panics or fails with an
+/// internal error
+#[derive(Debug, Serialize)]
+pub struct CrashBundle {
+    pub created_at: String,
+    pub moonflare: String,
+    pub os: String,
+    pub arch: String,
+    pub args: Vec<String>,
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: String,
+    pub tools: Vec<(String, Option<String>)>,
+    pub last_subprocess: Option<Subprocess>,
+}
+
+impl CrashBundle {
+    fn from_panic(args: &[String], info: &PanicHookInfo) -> Self {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        Self::capture(
+            args,
+            &message,
+            info.location().map(|location| location.to_string()),
+        )
+    }
+
+    fn capture(args: &[String], message: &str, location: Option<String>) -> Self {
+        let last_subprocess = LAST_SUBPROCESS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+            .map(|subprocess| Subprocess {
+                command: redact_text(&subprocess.command),
+                exit_code: subprocess.exit_code,
+                output: redact_text(&subprocess.output),
+            });
+
+        Self {
+            created_at: chrono::Utc::now().to_rfc3339(),
+            moonflare: Version::current().to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            args: args.to_vec(),
+            message: redact_text(message),
+            location,
+            thread: std::thread::current().name().map(str::to_string),
+            backtrace: Backtrace::force_capture().to_string(),
+            tools: tool_versions(),
+            last_subprocess,
+        }
+    }
+
+    fn write(&self) -> Result<PathBuf> {
+        let dir = if is_moonflare_workspace() {
+            PathBuf::from(CRASH_DIR)
+        } else {
+            std::env::temp_dir()
+        };
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!(
+            "crash-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        std::fs::write(&path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Remember the subprocess that just finished, with what it printed when it was captured
+pub fn record_subprocess(command: &str, exit_code: Option<i32>, output: &str) {
+    let lines: Vec<&str> = output.lines().collect();
+    let output = lines[lines.len().saturating_sub(OUTPUT_LINES)..].join("\n");
+    *LAST_SUBPROCESS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Subprocess {
+        command: command.to_string(),
+        exit_code,
+        output,
+    });
+}
+
+/// Write a diagnostics bundle whenever this invocation panics, after miette has reported the
+/// panic, and point to it in one line. With `report` the bundle is also sent to `[crash]`.
+pub fn install(args: &[String], report: bool) {
+    let args = redact_args(args);
+    let _ = INSTALLED.set((args.clone(), report));
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        keep(&CrashBundle::from_panic(&args, info), report);
+    }));
+}
+
+/// Write a diagnostics bundle for an internal error that ended the command, as the panic hook
+/// does for a panic. Errors that aren't [`Internal`] or an internal [`MoonflareError`] are the
+/// project's or the environment's, and leave nothing behind.
+pub fn report_error(error: &miette::Report) {
+    let internal = error.downcast_ref::<Internal>().is_some()
+        || error
+            .downcast_ref::<MoonflareError>()
+            .is_some_and(MoonflareError::is_internal);
+    let Some((args, report)) = INSTALLED.get().filter(|_| internal) else {
+        return;
+    };
+    let message = error
+        .chain()
+        .map(|cause| cause.to_string())
+        .collect::<Vec<_>>()
+        .join(": ");
+    keep(&CrashBundle::capture(args, &message, None), *report);
+}
+
+/// Write the bundle and say where it is, sending it on with `report`
+fn keep(bundle: &CrashBundle, report: bool) {
+    let path = match bundle.write() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("{} {:#}", "Couldn't write a crash report:".red(), e);
+            return;
+        }
+    };
+    if !report {
+        eprintln!(
+            "{} {} {}",
+            "moonflare crashed; diagnostics are in".red(),
+            path.display(),
+            "(rerun with --report to send them)".dimmed()
+        );
+        return;
+    }
+    eprintln!(
+        "{} {}",
+        "moonflare crashed; diagnostics are in".red(),
+        path.display()
+    );
+    match send(bundle, &path) {
+        Ok(sent) => eprintln!("{}", sent),
+        Err(e) => eprintln!("{} {:#}", "Couldn't send the crash report:".red(), e),
+    }
+}
+
+/// Post the bundle to `[crash] endpoint`, or without one say where to file it by hand
+fn send(bundle: &CrashBundle, path: &Path) -> Result<String> {
+    let config = MoonflareConfig::load().unwrap_or_default().crash;
+    let Some(endpoint) = config.endpoint.clone() else {
+        let url = reqwest::Url::parse_with_params(
+            ISSUES_URL,
+            &[("title", format!("Crash: {}", bundle.message))],
+        )?;
+        return Ok(format!(
+            "Open an issue with {} attached: {}",
+            path.display(),
+            url
+        ));
+    };
+
+    // The hook may run on a runtime thread, which can't block on another future
+    let body = serde_json::to_value(bundle)?;
+    let url = endpoint.clone();
+    std::thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(post(&config, &url, &body))
+    })
+    .join()
+    .map_err(|_| anyhow::anyhow!("Sending the crash report panicked"))??;
+    Ok(format!("Sent the crash report to {}", endpoint))
+}
+
+async fn post(config: &CrashConfig, endpoint: &str, body: &serde_json::Value) -> Result<()> {
+    let mut request = http::builder()?
+        .timeout(REPORT_TIMEOUT)
+        .build()?
+        .post(endpoint)
+        .json(body);
+    if let Some(token) = config.token()? {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .context("Failed to reach the crash endpoint")?;
+    if !response.status().is_success() {
+        bail!("Crash endpoint responded with {}", response.status());
+    }
+    Ok(())
+}
+
+/// Arguments with the values of secret-looking flags and `KEY=value` pairs replaced
+fn redact_args(args: &[String]) -> Vec<String> {
+    let secret = Regex::new(SECRET_KEY_PATTERN).expect("valid regex");
+    let mut value_follows = false;
+    args.iter()
+        .map(|arg| {
+            if std::mem::take(&mut value_follows) && !arg.starts_with('-') {
+                return REDACTED.to_string();
+            }
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (arg.as_str(), None),
+            };
+            if !secret.is_match(name.trim_start_matches('-')) {
+                return arg.clone();
+            }
+            match value {
+                Some(_) => format!("{}={}", name, REDACTED),
+                None => {
+                    value_follows = arg.starts_with("--");
+                    arg.clone()
+                }
+            }
+        })
+        .collect()
+}
+
+/// Text with secret-looking assignments, bearer tokens and the values of secret-looking
+/// environment variables replaced
+fn redact_text(text: &str) -> String {
+    let (text, _) = redact(text);
+    let bearer = Regex::new(r"(?i)(bearer\s+)[A-Za-z0-9._~+/=-]+").expect("valid regex");
+    let mut text = bearer
+        .replace_all(&text, format!("${{1}}{}", REDACTED))
+        .into_owned();
+    let secret = Regex::new(SECRET_KEY_PATTERN).expect("valid regex");
+    for (key, value) in std::env::vars() {
+        // Short values would replace ordinary words
+        if secret.is_match(&key) && value.len() >= 8 {
+            text = text.replace(&value, REDACTED);
+        }
+    }
+    text
+}
@@ -0,0 +1,412 @@
+use crate::utils::chaos::Upstream;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::http;
+use crate::utils::ports::local_port;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use colored::*;
+use std::time::Instant;
+
+/// Years searched for an expression's next run before it's considered never to run, e.g.
+/// `0 0 30 2 *`
+const SEARCH_YEARS: i32 = 5;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Cloudflare numbers weekdays 1-7 from Sunday
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Days matched relative to the month rather than by number
+#[derive(Debug, Clone, PartialEq)]
+enum DayRule {
+    /// `L` in the day of month
+    LastDay,
+    /// `LW`
+    LastWeekday,
+    /// `15W`, the weekday nearest the 15th without leaving the month
+    NearestWeekday(u32),
+    /// `6L` in the day of week, the month's last Friday
+    LastOf(u32),
+    /// `2#1`, the month's first Monday
+    Nth(u32, u32),
+}
+
+/// A cron trigger as Cloudflare runs it: minute, hour, day of month, month and day of week,
+/// in UTC
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    pub expression: String,
+    minutes: u64,
+    hours: u32,
+    /// Bits 1-31
+    days: u32,
+    /// Bits 1-12
+    months: u16,
+    /// Bits 0-6 from Sunday
+    weekdays: u8,
+    day_rules: Vec<DayRule>,
+    weekday_rules: Vec<DayRule>,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields.as_slice() else {
+            bail!(
+                "'{}' needs five fields: minute, hour, day of month, month and day of week",
+                expression
+            );
+        };
+        let mut day_rules = Vec::new();
+        let mut weekday_rules = Vec::new();
+        let days = parse_field(day, 1, 31, &[], |term| {
+            day_rules.push(match term {
+                "L" => DayRule::LastDay,
+                "LW" => DayRule::LastWeekday,
+                _ => DayRule::NearestWeekday(parse_value(term.strip_suffix('W')?, 1, 31, &[])?),
+            });
+            Some(())
+        })?;
+        let weekdays = parse_field(weekday, 1, 7, &WEEKDAYS, |term| {
+            weekday_rules.push(match term.split_once('#') {
+                Some((weekday, nth)) => DayRule::Nth(
+                    parse_value(weekday, 1, 7, &WEEKDAYS)? - 1,
+                    parse_value(nth, 1, 5, &[])?,
+                ),
+                None => DayRule::LastOf(parse_value(term.strip_suffix('L')?, 1, 7, &WEEKDAYS)? - 1),
+            });
+            Some(())
+        })?;
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse_field(minute, 0, 59, &[], |_| None)?,
+            hours: parse_field(hour, 0, 23, &[], |_| None)? as u32,
+            days: days as u32,
+            months: parse_field(month, 1, 12, &MONTHS, |_| None)? as u16,
+            // Stored from bit 0 like chrono's weekday numbers
+            weekdays: (weekdays >> 1) as u8,
+            any_day: matches!(*day, "*" | "?"),
+            any_weekday: matches!(*weekday, "*" | "?"),
+            day_rules,
+            weekday_rules,
+        })
+    }
+
+    /// The first minute after `time` the trigger runs at
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut next = time.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = time.year() + SEARCH_YEARS;
+        while next.year() <= limit {
+            if self.months & (1 << next.month()) == 0 {
+                let (year, month) = if next.month() == 12 {
+                    (next.year() + 1, 1)
+                } else {
+                    (next.year(), next.month() + 1)
+                };
+                next = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.matches_day(next.date_naive()) {
+                next = (next + Duration::days(1)).with_hour(0)?.with_minute(0)?;
+            } else if self.hours & (1 << next.hour()) == 0 {
+                next = (next + Duration::hours(1)).with_minute(0)?;
+            } else if self.minutes & (1 << next.minute()) == 0 {
+                next += Duration::minutes(1);
+            } else {
+                return Some(next);
+            }
+        }
+        None
+    }
+
+    /// A restricted day of month and day of week match either, as in other cron
+    /// implementations
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let last = days_in_month(date);
+        let day = date.day();
+        let weekday = date.weekday().num_days_from_sunday();
+        let by_day = self.days & (1 << day) != 0
+            || self.day_rules.iter().any(|rule| match rule {
+                DayRule::LastDay => day == last,
+                DayRule::LastWeekday => day == nearest_weekday(date, last),
+                DayRule::NearestWeekday(target) => {
+                    day == nearest_weekday(date, (*target).min(last))
+                }
+                _ => false,
+            });
+        let by_weekday = self.weekdays & (1 << weekday) != 0
+            || self.weekday_rules.iter().any(|rule| match rule {
+                DayRule::LastOf(target) => weekday == *target && day + 7 > last,
+                DayRule::Nth(target, nth) => weekday == *target && (day - 1) / 7 + 1 == *nth,
+                _ => false,
+            });
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => by_weekday,
+            (false, true) => by_day,
+            (false, false) => by_day || by_weekday,
+        }
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    (28..=31)
+        .rev()
+        .find(|day| date.with_day(*day).is_some())
+        .unwrap_or(28)
+}
+
+/// Day of `date`'s month that's the weekday nearest `target`, staying within the month
+fn nearest_weekday(date: NaiveDate, target: u32) -> u32 {
+    let Some(target_date) = date.with_day(target) else {
+        return target;
+    };
+    let last = days_in_month(date);
+    match target_date.weekday().num_days_from_sunday() {
+        6 if target == 1 => 3,
+        6 => target - 1,
+        0 if target == last => target - 2,
+        0 => target + 1,
+        _ => target,
+    }
+}
+
+/// Bits of the values a comma-separated field matches. Terms `special` accepts, like `L`,
+/// are handed to it instead.
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    mut special: impl FnMut(&str) -> Option<()>,
+) -> Result<u64> {
+    let mut bits = 0u64;
+    for term in field.split(',') {
+        let term = term.to_ascii_uppercase();
+        let (range, step) = match term.split_once('/') {
+            Some((range, step)) => (range, Some(step)),
+            None => (term.as_str(), None),
+        };
+        let step = match step {
+            Some(step) => step
+                .parse::<u32>()
+                .ok()
+                .filter(|step| *step > 0)
+                .with_context(|| format!("Invalid step in '{}'", term))?,
+            None => 1,
+        };
+        let (start, end) = if range == "*" || range == "?" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            parse_value(start, min, max, names)
+                .zip(parse_value(end, min, max, names))
+                .with_context(|| format!("Invalid range '{}'", term))?
+        } else if let Some(value) = parse_value(range, min, max, names) {
+            // `5/15` runs from 5 to the end in steps of 15
+            (value, if step > 1 { max } else { value })
+        } else if step == 1 && special(&term).is_some() {
+            continue;
+        } else {
+            bail!("'{}' isn't a value between {} and {}", term, min, max);
+        };
+        if start > end {
+            bail!("Invalid range '{}'", term);
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+/// A number within bounds, or a month or weekday name
+fn parse_value(value: &str, min: u32, max: u32, names: &[&str]) -> Option<u32> {
+    let value = value.parse::<u32>().ok().or_else(|| {
+        names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+            .map(|index| index as u32 + min)
+    })?;
+    (min..=max).contains(&value).then_some(value)
+}
+
+/// A Worker's cron triggers and where its dev server answers `/__scheduled`
+#[derive(Debug, Clone)]
+pub struct ScheduledWorker {
+    pub project: String,
+    pub port: u16,
+    pub https: bool,
+    pub schedules: Vec<CronSchedule>,
+}
+
+/// Expressions under `triggers.crons` in the project's Wrangler config
+pub fn triggers(project: &WorkspaceProject) -> Result<Vec<String>> {
+    let Some(config) = read_wrangler_config(&project.path)? else {
+        return Ok(Vec::new());
+    };
+    Ok(config
+        .pointer("/triggers/crons")
+        .and_then(|crons| crons.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|cron| cron.as_str().map(str::to_string))
+        .collect())
+}
+
+/// Whether the project's Worker has cron triggers, so its dev server needs `--test-scheduled`
+pub fn has_triggers(project: &WorkspaceProject) -> bool {
+    project.is_worker() && triggers(project).is_ok_and(|crons| !crons.is_empty())
+}
+
+/// The Worker projects among `projects` with cron triggers. Chaos proxies are bypassed so
+/// triggers aren't dropped; expressions the scheduler can't read are skipped with a warning.
+pub fn plan(
+    projects: &[WorkspaceProject],
+    config: &MoonflareConfig,
+    upstreams: &[Upstream],
+    https: bool,
+) -> Result<Vec<ScheduledWorker>> {
+    let mut workers = Vec::new();
+    for project in projects.iter().filter(|p| p.is_worker()) {
+        let mut schedules = Vec::new();
+        for expression in triggers(project)? {
+            match CronSchedule::parse(&expression) {
+                Ok(schedule) => schedules.push(schedule),
+                Err(e) => println!(
+                    "{}",
+                    format!(
+                        "Skipping cron '{}' of '{}': {}",
+                        expression, project.name, e
+                    )
+                    .yellow()
+                ),
+            }
+        }
+        if schedules.is_empty() {
+            continue;
+        }
+        let port = match upstreams.iter().find(|u| u.project == project.name) {
+            Some(upstream) => upstream.target_port,
+            None => local_port(project, config)?,
+        };
+        workers.push(ScheduledWorker {
+            project: project.name.clone(),
+            port,
+            https,
+            schedules,
+        });
+    }
+    Ok(workers)
+}
+
+/// Trigger each schedule when it comes due, on a clock running `speed` times faster than real
+/// time from now, until the dev servers stop. Each run is logged with the Worker's response.
+pub async fn run(workers: Vec<ScheduledWorker>, speed: u32) {
+    // Dev servers are local and may use the self-signed dev certificate
+    let client = match http::builder().and_then(|builder| {
+        Ok(builder
+            .no_proxy()
+            .danger_accept_invalid_certs(true)
+            .build()?)
+    }) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!(
+                "{}",
+                format!("Cron scheduler failed to start: {}", e).yellow()
+            );
+            return;
+        }
+    };
+    let started = Instant::now();
+    let origin = Utc::now();
+    let clock = move || {
+        origin + Duration::from_std(started.elapsed() * speed).unwrap_or_else(|_| Duration::zero())
+    };
+
+    let mut due: Vec<(DateTime<Utc>, &ScheduledWorker, &CronSchedule)> = workers
+        .iter()
+        .flat_map(|worker| {
+            worker
+                .schedules
+                .iter()
+                .filter_map(move |schedule| Some((schedule.next_after(origin)?, worker, schedule)))
+        })
+        .collect();
+
+    loop {
+        let Some(index) = (0..due.len()).min_by_key(|index| due[*index].0) else {
+            return;
+        };
+        let (time, worker, schedule) = due[index];
+        let wait = (time - clock()).to_std().unwrap_or_default() / speed;
+        tokio::time::sleep(wait).await;
+
+        tokio::spawn(trigger(
+            client.clone(),
+            worker.clone(),
+            schedule.expression.clone(),
+            time,
+        ));
+        match schedule.next_after(time) {
+            Some(next) => due[index].0 = next,
+            None => {
+                due.remove(index);
+            }
+        }
+    }
+}
+
+/// Call the Worker's scheduled handler as `wrangler dev --test-scheduled` exposes it
+async fn trigger(
+    client: reqwest::Client,
+    worker: ScheduledWorker,
+    expression: String,
+    time: DateTime<Utc>,
+) {
+    let scheme = if worker.https { "https" } else { "http" };
+    let url = format!("{}://localhost:{}/__scheduled", scheme, worker.port);
+    let started = Instant::now();
+    let response = client
+        .get(&url)
+        .query(&[
+            ("cron", expression.as_str()),
+            ("time", &time.timestamp_millis().to_string()),
+        ])
+        .send()
+        .await;
+    let label = format!(
+        "cron {} '{}' at {}",
+        worker.project,
+        expression,
+        time.format("%Y-%m-%d %H:%M UTC")
+    );
+    match response {
+        Ok(response) if response.status().is_success() => println!(
+            "{}",
+            format!(
+                "{}: {} ({} ms)",
+                label,
+                response.status().as_u16(),
+                started.elapsed().as_millis()
+            )
+            .dimmed()
+        ),
+        Ok(response) => println!(
+            "{}",
+            format!("{}: {}", label, response.status().as_u16()).yellow()
+        ),
+        Err(_) => println!(
+            "{}",
+            format!(
+                "{}: dev server not reachable on port {}",
+                label, worker.port
+            )
+            .yellow()
+        ),
+    }
+}
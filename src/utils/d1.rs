@@ -0,0 +1,233 @@
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::task_env;
+use crate::utils::wrangler::{find_wrangler_config, read_wrangler_config, write_wrangler_config};
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Where database exports are kept while a branch is being filled
+const EXPORT_DIR: &str = ".moonflare/d1";
+
+/// Branches named after a pull request, which `d1 branch cleanup` removes once it's merged
+const PR_BRANCH: &str = r"-pr-(\d+)$";
+
+/// A D1 database binding of one environment, as declared in the Wrangler config
+#[derive(Debug, Clone)]
+pub struct Database {
+    pub binding: String,
+    pub name: String,
+    /// The whole `d1_databases` entry, so branches keep settings such as `migrations_dir`
+    pub entry: Value,
+}
+
+/// The D1 databases bound in `env`, or at the top level for production. Bindings aren't
+/// inherited, so an environment's own list replaces the top-level one.
+pub fn databases(config: &Value, env: Option<&str>) -> Vec<Database> {
+    let list = match env {
+        Some(env) => config["env"][env]["d1_databases"].as_array(),
+        None => config["d1_databases"].as_array(),
+    };
+    list.into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let binding = entry["binding"].as_str()?;
+            Some(Database {
+                binding: binding.to_string(),
+                name: entry["database_name"]
+                    .as_str()
+                    .unwrap_or(binding)
+                    .to_string(),
+                entry: entry.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Name of the copy of `source` for a branch, e.g. `shop-db-pr-123`
+pub fn branch_database_name(source: &str, branch: &str) -> String {
+    format!("{}-{}", source, branch)
+}
+
+/// Pull request number of a branch database named by `branch_database_name`, if its branch is
+/// named `pr-<number>`
+pub fn pull_request_number(database: &str) -> Option<u64> {
+    Regex::new(PR_BRANCH)
+        .expect("valid regex")
+        .captures(database)
+        .and_then(|captures| captures[1].parse().ok())
+}
+
+fn wrangler(project: &WorkspaceProject, args: &[&str]) -> Command {
+    let mut command = Command::new("pnpm");
+    command
+        .args(["exec", "wrangler", "d1"])
+        .args(args)
+        .current_dir(&project.path)
+        .envs(task_env::vars(Some(&project.name)));
+    command
+}
+
+fn run(project: &WorkspaceProject, args: &[&str]) -> Result<Output> {
+    let output = wrangler(project, args)
+        .output()
+        .with_context(|| format!("Failed to run wrangler d1 {}", args[0]))?;
+    if !output.status.success() {
+        bail!(
+            "wrangler d1 {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output)
+}
+
+/// Export the remote database's schema and data to a SQL file under `.moonflare/d1`
+pub fn export(project: &WorkspaceProject, database: &str, env: Option<&str>) -> Result<PathBuf> {
+    let dir = std::env::current_dir()?.join(EXPORT_DIR);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let file = dir.join(format!("{}.sql", database));
+    let path = file.display().to_string();
+
+    let mut args = vec!["export", database, "--remote", "--output", &path];
+    if let Some(env) = env {
+        args.extend(["--env", env]);
+    }
+    run(project, &args)?;
+    Ok(file)
+}
+
+/// Create a remote database, returning its ID
+pub fn create(project: &WorkspaceProject, database: &str) -> Result<String> {
+    let output = run(project, &["create", database])?;
+    // Wrangler prints the binding to add, as JSON or TOML depending on its version
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Regex::new(r#"database_id"?\s*[:=]\s*"([^"]+)""#)
+        .expect("valid regex")
+        .captures(&stdout)
+        .map(|captures| captures[1].to_string())
+        .with_context(|| format!("wrangler d1 create didn't print the ID of {}", database))
+}
+
+/// Run a SQL file against the remote database bound in `env`
+pub fn import(project: &WorkspaceProject, database: &str, file: &Path, env: &str) -> Result<()> {
+    let path = file.display().to_string();
+    run(
+        project,
+        &[
+            "execute", database, "--remote", "--file", &path, "--env", env, "--yes",
+        ],
+    )?;
+    Ok(())
+}
+
+/// Names of the account's remote databases
+pub fn list(project: &WorkspaceProject) -> Result<Vec<String>> {
+    let output = run(project, &["list", "--json"])?;
+    let databases: Vec<Value> =
+        serde_json::from_slice(&output.stdout).context("wrangler d1 list printed invalid JSON")?;
+    Ok(databases
+        .iter()
+        .filter_map(|database| database["name"].as_str().map(str::to_string))
+        .collect())
+}
+
+pub fn delete(project: &WorkspaceProject, database: &str) -> Result<()> {
+    run(project, &["delete", database, "--skip-confirmation"])?;
+    Ok(())
+}
+
+/// Point `binding` in the environment at a branch database, keeping the rest of the source's
+/// entry. Comments in JSONC configs aren't preserved.
+pub fn bind_branch(
+    project: &WorkspaceProject,
+    env: &str,
+    source: &Database,
+    database: &str,
+    database_id: &str,
+) -> Result<()> {
+    let path = config_path(project)?;
+    let mut config = read_wrangler_config(&project.path)?.unwrap_or_else(|| json!({}));
+
+    let mut entry = source.entry.clone();
+    if let Some(entry) = entry.as_object_mut() {
+        entry.remove("preview_database_id");
+        entry.insert("database_name".to_string(), json!(database));
+        entry.insert("database_id".to_string(), json!(database_id));
+    }
+
+    let Some(root) = config.as_object_mut() else {
+        bail!("{} isn't a Wrangler config object", path.display());
+    };
+    let list = root
+        .entry("env")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .and_then(|envs| envs.entry(env).or_insert_with(|| json!({})).as_object_mut())
+        .map(|env| env.entry("d1_databases").or_insert_with(|| json!([])))
+        .and_then(Value::as_array_mut)
+        .with_context(|| format!("env.{} in {} isn't an object", env, path.display()))?;
+    match list
+        .iter_mut()
+        .find(|existing| existing["binding"] == source.binding.as_str())
+    {
+        Some(existing) => *existing = entry,
+        None => list.push(entry),
+    }
+    write_wrangler_config(&path, &config)
+}
+
+/// Remove bindings to `database` from every environment. Returns the environments changed.
+pub fn unbind(project: &WorkspaceProject, database: &str) -> Result<Vec<String>> {
+    let Some(mut config) = read_wrangler_config(&project.path)? else {
+        return Ok(Vec::new());
+    };
+    let mut changed = Vec::new();
+    if let Some(envs) = config["env"].as_object_mut() {
+        for (name, env) in envs.iter_mut() {
+            if let Some(list) = env.get_mut("d1_databases").and_then(Value::as_array_mut) {
+                let before = list.len();
+                list.retain(|entry| entry["database_name"] != database);
+                if list.len() != before {
+                    changed.push(name.clone());
+                }
+            }
+        }
+    }
+    if !changed.is_empty() {
+        write_wrangler_config(&config_path(project)?, &config)?;
+    }
+    Ok(changed)
+}
+
+fn config_path(project: &WorkspaceProject) -> Result<PathBuf> {
+    find_wrangler_config(&project.path)
+        .with_context(|| format!("'{}' has no Wrangler config", project.name))
+}
+
+/// Whether the pull request has been merged or closed, asked of the GitHub CLI
+pub fn pull_request_closed(number: u64) -> Result<bool> {
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "view",
+            &number.to_string(),
+            "--json",
+            "state",
+            "--jq",
+            ".state",
+        ])
+        .output()
+        .context("Failed to run gh; install the GitHub CLI or pass --name")?;
+    if !output.status.success() {
+        bail!(
+            "gh pr view {} failed: {}",
+            number,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let state = String::from_utf8_lossy(&output.stdout);
+    Ok(matches!(state.trim(), "MERGED" | "CLOSED"))
+}
@@ -0,0 +1,285 @@
+use crate::utils::moon::run_moon_command_silent;
+use crate::utils::watch::FileSnapshot;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const SOCKET_FILE: &str = ".moonflare/daemon.sock";
+pub const LOG_FILE: &str = ".moonflare/daemon.log";
+
+/// Set to bypass a running daemon and always query Moon directly
+pub const NO_DAEMON_ENV: &str = "MOONFLARE_NO_DAEMON";
+
+/// How often the daemon looks for changed files
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One request per connection, written as a line of JSON
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Request {
+    Ping,
+    /// Output of `moon <args>`, served from the cache while no file has changed
+    Query {
+        args: Vec<String>,
+    },
+    Status,
+    /// Drop cached queries now, for commands that just moved or created projects
+    Invalidate,
+    Shutdown,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<DaemonStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub uptime_secs: u64,
+    pub cached_queries: usize,
+    /// Times the cache was dropped because workspace files changed
+    pub invalidations: u64,
+}
+
+impl Response {
+    fn output(output: String) -> Self {
+        Self {
+            ok: true,
+            output: Some(output),
+            ..Default::default()
+        }
+    }
+
+    fn error(error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Default)]
+struct Cache {
+    queries: BTreeMap<Vec<String>, String>,
+    invalidations: u64,
+    /// Bumped by every invalidation, so a query that ran across one doesn't cache its output
+    generation: u64,
+}
+
+impl Cache {
+    fn invalidate(&mut self) {
+        self.generation += 1;
+        if !self.queries.is_empty() {
+            self.queries.clear();
+            self.invalidations += 1;
+        }
+    }
+}
+
+/// Send a request to the workspace daemon, or None when no daemon is listening
+#[cfg(unix)]
+pub async fn request(request: &Request) -> Option<Response> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let stream = tokio::net::UnixStream::connect(SOCKET_FILE).await.ok()?;
+    let (reader, mut writer) = stream.into_split();
+    let mut line = serde_json::to_string(request).ok()?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await.ok()?;
+
+    let mut response = String::new();
+    BufReader::new(reader).read_line(&mut response).await.ok()?;
+    serde_json::from_str(&response).ok()
+}
+
+#[cfg(not(unix))]
+pub async fn request(_request: &Request) -> Option<Response> {
+    None
+}
+
+/// Run `moon <args>` through the daemon when one is up, so repeated invocations reuse its
+/// cached result. None means the caller should run Moon itself.
+pub async fn cached_query(args: &[&str]) -> Option<Result<String>> {
+    if std::env::var_os(NO_DAEMON_ENV).is_some() {
+        return None;
+    }
+
+    let response = request(&Request::Query {
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+    })
+    .await?;
+    Some(match response {
+        Response {
+            ok: true,
+            output: Some(output),
+            ..
+        } => Ok(output),
+        response => Err(anyhow::anyhow!(
+            "{}",
+            response
+                .error
+                .unwrap_or_else(|| "Daemon query failed".to_string())
+        )),
+    })
+}
+
+/// Tell a running daemon that projects changed, so the next query doesn't wait for its file
+/// watcher to notice. Does nothing when no daemon is listening.
+pub async fn invalidate() {
+    let _ = request(&Request::Invalidate).await;
+}
+
+/// Serve requests on the workspace socket until a shutdown request arrives
+#[cfg(unix)]
+pub async fn serve(root: &Path) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    let socket = root.join(SOCKET_FILE);
+    if let Some(parent) = socket.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if socket.exists() {
+        if request(&Request::Ping).await.is_some() {
+            bail!("A daemon is already running for this workspace");
+        }
+        std::fs::remove_file(&socket)?;
+    }
+    let listener = tokio::net::UnixListener::bind(&socket)
+        .with_context(|| format!("Failed to bind {}", socket.display()))?;
+
+    let cache = Arc::new(Mutex::new(Cache::default()));
+    tokio::spawn(watch_workspace(root.to_path_buf(), cache.clone()));
+
+    let started = Instant::now();
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let (reader, mut writer) = stream.into_split();
+        let mut line = String::new();
+        if BufReader::new(reader).read_line(&mut line).await.is_err() {
+            continue;
+        }
+
+        let request: Request = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                respond(&mut writer, Response::error(format!("Bad request: {}", e))).await;
+                continue;
+            }
+        };
+
+        let shutdown = matches!(request, Request::Shutdown);
+        let cache = cache.clone();
+        let handle = tokio::spawn(async move {
+            let response = handle(request, &cache, started).await;
+            respond(&mut writer, response).await;
+        });
+        if shutdown {
+            let _ = handle.await;
+            break;
+        }
+    }
+
+    let _ = std::fs::remove_file(&socket);
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn respond(writer: &mut tokio::net::unix::OwnedWriteHalf, response: Response) {
+    use tokio::io::AsyncWriteExt;
+
+    if let Ok(mut json) = serde_json::to_string(&response) {
+        json.push('\n');
+        let _ = writer.write_all(json.as_bytes()).await;
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn serve(_root: &Path) -> Result<()> {
+    bail!("The daemon is only supported on Unix")
+}
+
+async fn handle(request: Request, cache: &Mutex<Cache>, started: Instant) -> Response {
+    match request {
+        Request::Ping | Request::Shutdown => Response {
+            ok: true,
+            ..Default::default()
+        },
+        Request::Status => {
+            let cache = cache.lock().expect("daemon cache poisoned");
+            Response {
+                ok: true,
+                status: Some(DaemonStatus {
+                    pid: std::process::id(),
+                    uptime_secs: started.elapsed().as_secs(),
+                    cached_queries: cache.queries.len(),
+                    invalidations: cache.invalidations,
+                }),
+                ..Default::default()
+            }
+        }
+        Request::Invalidate => {
+            cache.lock().expect("daemon cache poisoned").invalidate();
+            Response {
+                ok: true,
+                ..Default::default()
+            }
+        }
+        Request::Query { args } => {
+            let (cached, generation) = {
+                let cache = cache.lock().expect("daemon cache poisoned");
+                (cache.queries.get(&args).cloned(), cache.generation)
+            };
+            if let Some(output) = cached {
+                return Response::output(output);
+            }
+
+            let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+            match run_moon_command_silent(&arg_refs).await {
+                Ok(output) => {
+                    let mut cache = cache.lock().expect("daemon cache poisoned");
+                    if cache.generation == generation {
+                        cache.queries.insert(args, output.clone());
+                    }
+                    Response::output(output)
+                }
+                Err(e) => Response::error(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Drop cached queries whenever a workspace file (including Moon's own config) changes
+async fn watch_workspace(root: PathBuf, cache: Arc<Mutex<Cache>>) {
+    let capture = |root: &Path| {
+        let moon_config = root.join(".moon");
+        FileSnapshot::capture(&[
+            root,
+            &moon_config.join("workspace.yml"),
+            &moon_config.join("tasks"),
+        ])
+    };
+
+    let mut previous = capture(&root);
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let current = capture(&root);
+        let changed = current
+            .changed_since(&previous)
+            .into_iter()
+            .any(|path| !path.starts_with(root.join(".moonflare")));
+        if changed {
+            cache.lock().expect("daemon cache poisoned").invalidate();
+        }
+        previous = current;
+    }
+}
@@ -0,0 +1,184 @@
+use crate::utils::artifacts::output_dir;
+#[cfg(feature = "api")]
+use crate::utils::cache_headers::{ASSET_MANIFEST_FILE, HEADERS_FILE};
+#[cfg(not(feature = "api"))]
+use crate::utils::cloudflare::api_unavailable;
+#[cfg(feature = "api")]
+use crate::utils::cloudflare::{CloudflareClient, R2_PERMISSION};
+use crate::utils::config::{AssetsConfig, DeployStrategy, ProjectDeployConfig};
+use crate::utils::projects::WorkspaceProject;
+#[cfg(feature = "api")]
+use crate::utils::r2_sync::sync_dir;
+use crate::utils::task_env;
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::process::Command;
+
+/// Files in the build output that configure Workers static assets rather than being served
+#[cfg(feature = "api")]
+const NOT_SERVED: [&str; 4] = [
+    HEADERS_FILE,
+    "_redirects",
+    ".assetsignore",
+    ASSET_MANIFEST_FILE,
+];
+
+/// Environment used in `{env}` placeholders when deploying without one
+const DEFAULT_ENV: &str = "production";
+
+/// Where and how a strategy deploys
+#[cfg_attr(not(feature = "api"), allow(dead_code))]
+pub struct Target<'a> {
+    pub worker_name: &'a str,
+    /// The wrangler `account_id`, when the project has one
+    pub account_id: Option<&'a str>,
+    pub assets: &'a AssetsConfig,
+    pub env: Option<&'a str>,
+    pub message: Option<&'a str>,
+    /// Delete objects missing from the output from an `r2-static` bucket
+    pub delete: bool,
+}
+
+/// Ship a project's built output with its configured strategy. The build has already run.
+pub async fn deploy(
+    project: &WorkspaceProject,
+    settings: &ProjectDeployConfig,
+    target: &Target<'_>,
+) -> Result<()> {
+    let Target {
+        worker_name,
+        env,
+        message,
+        ..
+    } = *target;
+    match &settings.strategy {
+        DeployStrategy::Wrangler => bail!(
+            "Project '{}' deploys with its Moon deploy task",
+            project.name
+        ),
+        DeployStrategy::Pages => deploy_pages(project, settings, worker_name, env, message),
+        #[cfg(not(feature = "api"))]
+        DeployStrategy::R2Static => Err(api_unavailable()),
+        #[cfg(feature = "api")]
+        DeployStrategy::R2Static => {
+            let bucket = settings.bucket.as_deref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "The r2-static strategy needs a bucket: set bucket under [projects.{}.deploy] in moonflare.toml",
+                    project.name
+                )
+            })?;
+            let bucket = bucket.replace("{env}", env.unwrap_or(DEFAULT_ENV));
+            let prefix = settings.prefix.as_deref().unwrap_or_default();
+            let output = output_dir(project);
+            if !output.is_dir() {
+                bail!(
+                    "Project '{}' has no build output at {}",
+                    project.name,
+                    output.display()
+                );
+            }
+            let client = CloudflareClient::from_env(target.account_id, R2_PERMISSION)?;
+            let stats = sync_dir(
+                client,
+                &output,
+                &bucket,
+                prefix,
+                target.delete,
+                target.assets,
+                &NOT_SERVED,
+            )
+            .await?;
+            println!(
+                "  Synced {} to R2 bucket {}: {}",
+                output.display(),
+                bucket,
+                stats.summary()
+            );
+            Ok(())
+        }
+        DeployStrategy::Custom(command) => run_custom(project, command, env, message),
+    }
+}
+
+fn deploy_pages(
+    project: &WorkspaceProject,
+    settings: &ProjectDeployConfig,
+    worker_name: &str,
+    env: Option<&str>,
+    message: Option<&str>,
+) -> Result<()> {
+    let output = fs::canonicalize(output_dir(project))
+        .with_context(|| format!("Project '{}' has no build output to deploy", project.name))?;
+    let pages_project = settings.pages_project.as_deref().unwrap_or(worker_name);
+
+    let mut args = vec![
+        "pages".to_string(),
+        "deploy".to_string(),
+        output.to_string_lossy().to_string(),
+        "--project-name".to_string(),
+        pages_project.to_string(),
+    ];
+    // Pages has branches rather than environments; anything but production is a preview
+    if let Some(env) = env.filter(|env| *env != DEFAULT_ENV) {
+        args.extend(["--branch".to_string(), env.to_string()]);
+    }
+    if let Some(message) = message {
+        args.extend(["--commit-message".to_string(), message.to_string()]);
+    }
+    wrangler(project, &args)
+}
+
+fn run_custom(
+    project: &WorkspaceProject,
+    command: &str,
+    env: Option<&str>,
+    message: Option<&str>,
+) -> Result<()> {
+    let output = output_dir(project);
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    };
+    shell
+        .current_dir(&project.path)
+        .envs(task_env::vars(Some(&project.name)))
+        .env("MOONFLARE_PROJECT", &project.name)
+        .env(
+            "MOONFLARE_OUTPUT_DIR",
+            fs::canonicalize(&output).unwrap_or(output),
+        );
+    if let Some(environment) = env {
+        shell.env("WRANGLER_ENV", environment);
+    }
+    if let Some(message) = message {
+        shell.env("WRANGLER_MESSAGE", message);
+    }
+
+    let status = shell
+        .status()
+        .with_context(|| format!("Failed to run '{}'", command))?;
+    if !status.success() {
+        bail!("'{}' exited with {}", command, status);
+    }
+    Ok(())
+}
+
+/// Run the project's own wrangler
+fn wrangler(project: &WorkspaceProject, args: &[String]) -> Result<()> {
+    let status = Command::new("pnpm")
+        .args(["exec", "wrangler"])
+        .args(args)
+        .current_dir(&project.path)
+        .envs(task_env::vars(Some(&project.name)))
+        .status()
+        .context("Failed to run wrangler through pnpm")?;
+    if !status.success() {
+        bail!("wrangler {} exited with {}", args[..2].join(" "), status);
+    }
+    Ok(())
+}
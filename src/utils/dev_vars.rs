@@ -0,0 +1,119 @@
+use crate::utils::wrangler::{binding_names, config_vars, read_wrangler_config};
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+pub const DEV_VARS_FILE: &str = ".dev.vars";
+pub const DEV_VARS_EXAMPLE_FILE: &str = ".dev.vars.example";
+
+pub const SOURCE_EXTENSIONS: [&str; 6] = ["ts", "tsx", "js", "jsx", "mjs", "astro"];
+
+/// A variable a project needs during `wrangler dev`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequiredVar {
+    pub name: String,
+    /// Value from `vars` in the Wrangler config; secrets have none
+    pub default: Option<String>,
+}
+
+/// Variables a project expects: its Wrangler `vars` plus any `env.NAME` referenced in source
+/// that isn't a var or binding (and so must be a secret)
+pub fn required_vars(project_path: &Path) -> Result<Vec<RequiredVar>> {
+    let config = read_wrangler_config(project_path)?.unwrap_or_default();
+    let vars = config_vars(&config);
+    let bindings = binding_names(&config);
+
+    let secrets: BTreeSet<String> = referenced_env_names(&project_path.join("src"))
+        .into_iter()
+        .filter(|name| !vars.contains_key(name) && !bindings.contains(name))
+        .collect();
+
+    let mut required: Vec<RequiredVar> = vars
+        .into_iter()
+        .map(|(name, value)| RequiredVar {
+            name,
+            default: Some(value),
+        })
+        .collect();
+    required.extend(secrets.into_iter().map(|name| RequiredVar {
+        name,
+        default: None,
+    }));
+
+    Ok(required)
+}
+
+fn referenced_env_names(source_dir: &Path) -> BTreeSet<String> {
+    let pattern = regex::Regex::new(r"\benv\.([A-Z][A-Z0-9_]*)\b").expect("valid regex");
+    let mut names = BTreeSet::new();
+
+    for entry in WalkDir::new(source_dir).into_iter().flatten() {
+        let is_source = entry
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+        if !entry.file_type().is_file() || !is_source {
+            continue;
+        }
+
+        if let Ok(content) = fs::read_to_string(entry.path()) {
+            names.extend(pattern.captures_iter(&content).map(|c| c[1].to_string()));
+        }
+    }
+
+    names
+}
+
+/// Render a `.dev.vars.example`, with var defaults filled in and secrets left blank
+pub fn render_example(vars: &[RequiredVar]) -> String {
+    let mut output = String::from(
+        "# Generated by moonflare from wrangler vars and secret references in src/\n\
+         # Run 'moonflare env pull' to create .dev.vars from this file\n",
+    );
+    for var in vars {
+        output.push_str(&format!(
+            "{}={}\n",
+            var.name,
+            quote_value(var.default.as_deref().unwrap_or(""))
+        ));
+    }
+    output
+}
+
+/// Parse `KEY=value` lines, ignoring comments and blank lines and unquoting values
+pub fn parse_dev_vars(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), unquote_value(value.trim())))
+        .collect()
+}
+
+pub fn render_dev_vars(values: &BTreeMap<String, String>) -> String {
+    values
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, quote_value(value)))
+        .collect()
+}
+
+fn quote_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn unquote_value(value: &str) -> String {
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted {
+        value[1..value.len() - 1]
+            .replace("\\\"", "\"")
+            .replace("\\\\", "\\")
+    } else {
+        value.to_string()
+    }
+}
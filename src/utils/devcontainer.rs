@@ -0,0 +1,83 @@
+use crate::templates::{embedded, engine::TemplateEngine};
+use crate::utils::config::MoonflareConfig;
+use crate::utils::ports::default_port;
+use crate::utils::projects::discover_projects;
+use crate::utils::toolchain::{GENERATED_MARKER, Toolchain, stale};
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory VS Code and GitHub Codespaces read the container definition from
+pub const DEVCONTAINER_DIR: &str = ".devcontainer";
+
+/// Render the container definition for the workspace at the current directory
+pub fn render(config: &MoonflareConfig) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let toolchain = Toolchain::detect(Path::new("."));
+    let name = config.workspace.name.clone().unwrap_or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "moonflare".to_string())
+    });
+
+    let projects = discover_projects();
+    let mut forward_ports = BTreeSet::new();
+    for project in projects.iter().filter(|p| !p.is_crate()) {
+        forward_ports.insert(default_port(project, config)?);
+    }
+
+    let mut context = HashMap::new();
+    context.insert("name".to_string(), Value::String(name));
+    context.insert(
+        "moonflare_version".to_string(),
+        Value::String(env!("CARGO_PKG_VERSION").to_string()),
+    );
+    context.insert("proto".to_string(), json!(toolchain.proto));
+    context.insert("node".to_string(), json!(toolchain.node));
+    context.insert("pnpm".to_string(), json!(toolchain.pnpm));
+    context.insert("moon".to_string(), json!(toolchain.moon));
+    context.insert("rust".to_string(), json!(toolchain.rust));
+    context.insert("wrangler".to_string(), json!(toolchain.wrangler));
+    context.insert("forward_ports".to_string(), json!(forward_ports));
+    context.insert(
+        "has_crates".to_string(),
+        Value::Bool(projects.iter().any(|p| p.is_crate())),
+    );
+
+    TemplateEngine::new().render_template_to_memory(embedded::DEVCONTAINER_TEMPLATE, &context)
+}
+
+/// Write the rendered files, returning their paths
+pub fn write(config: &MoonflareConfig) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for (path, content) in render(config)? {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Whether moonflare wrote the workspace's container definition
+pub fn is_generated() -> bool {
+    fs::read_to_string(Path::new(DEVCONTAINER_DIR).join("devcontainer.json")).is_ok_and(|content| {
+        content
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .contains(GENERATED_MARKER)
+    })
+}
+
+/// Generated files whose content no longer matches the workspace's toolchain and projects.
+/// Empty unless moonflare wrote them.
+pub fn stale_files(config: &MoonflareConfig) -> Result<Vec<PathBuf>> {
+    if !is_generated() {
+        return Ok(Vec::new());
+    }
+    Ok(stale(render(config)?))
+}
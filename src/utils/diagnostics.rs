@@ -0,0 +1,23 @@
+/// Problem reported by `moonflare doctor`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Errors break builds or deploys; warnings are redundant, ignored or stale settings
+    pub error: bool,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            error: true,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            error: false,
+            message: message.into(),
+        }
+    }
+}
@@ -0,0 +1,528 @@
+use crate::utils::explain::task_words;
+use crate::utils::moon::{MoonProject, find_moon_binary};
+use crate::utils::platform::Shell;
+use crate::utils::projects::{WorkspaceProject, discover_projects};
+use anyhow::{Result, bail};
+use colored::*;
+use serde_yaml::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Run tasks straight from moon.yml even when Moon is installed, like `--no-moon`
+pub const NO_MOON_VAR: &str = "MOONFLARE_NO_MOON";
+
+static FORCED: AtomicBool = AtomicBool::new(false);
+static ANNOUNCED: AtomicBool = AtomicBool::new(false);
+
+/// Run tasks directly rather than through Moon (`--no-moon`)
+pub fn set_forced(enabled: bool) {
+    FORCED.store(enabled, Ordering::Relaxed);
+}
+
+/// Why tasks run directly instead of through Moon, or None when Moon runs them
+pub fn reason() -> Option<&'static str> {
+    static REASON: OnceLock<Option<&'static str>> = OnceLock::new();
+    *REASON.get_or_init(|| {
+        let requested = std::env::var(NO_MOON_VAR).is_ok_and(|v| !v.is_empty() && v != "0");
+        if FORCED.load(Ordering::Relaxed) {
+            Some("--no-moon")
+        } else if requested {
+            Some("MOONFLARE_NO_MOON is set")
+        } else if find_moon_binary().is_none() {
+            Some("Moon isn't installed")
+        } else {
+            None
+        }
+    })
+}
+
+/// Whether tasks run directly from moon.yml because Moon can't (or shouldn't) run them
+pub fn active() -> bool {
+    reason().is_some()
+}
+
+/// Say once per command that tasks run without Moon, and what that leaves out
+fn announce() {
+    if ANNOUNCED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    if let Some(reason) = reason() {
+        eprintln!(
+            "{}",
+            format!(
+                "[no moon] {}: running tasks straight from moon.yml, without caching, affected detection or Moon's task graph",
+                reason
+            )
+            .yellow()
+            .bold()
+        );
+    }
+}
+
+/// What a task runs: a command with its arguments, or a shell script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Invocation {
+    Command(Vec<String>),
+    Script(String),
+}
+
+/// A task resolved from a project's moon.yml and the inherited task files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectTask {
+    /// `project:task`
+    pub target: String,
+    /// Directory the task runs in, relative to the workspace root
+    pub dir: PathBuf,
+    pub run: Invocation,
+    pub env: BTreeMap<String, String>,
+}
+
+/// The tasks of a `moon run`: dependencies, one after another, then the requested targets
+#[derive(Debug, Default)]
+pub struct DirectRun {
+    pub deps: Vec<DirectTask>,
+    pub targets: Vec<DirectTask>,
+    /// Arguments after `--`, passed to the targets' commands
+    pub passthrough: Vec<String>,
+}
+
+/// Resolve Moon `args` (`run <targets> [-- args]`, or bare targets) into the tasks to run.
+/// Targets are `project:task`, `:task` for every project with the task, or `#tag:task`.
+pub fn resolve(args: &[&str]) -> Result<DirectRun> {
+    let (args, passthrough) = match args.iter().position(|arg| *arg == "--") {
+        Some(split) => (&args[..split], &args[split + 1..]),
+        None => (args, &[][..]),
+    };
+    let args = args.strip_prefix(&["run"]).unwrap_or(args);
+    let targets: Vec<&str> = args
+        .iter()
+        .copied()
+        .filter(|arg| !arg.starts_with('-'))
+        .collect();
+    if targets.is_empty() || targets.iter().any(|target| !target.contains(':')) {
+        bail!(
+            "'moon {}' needs Moon, which isn't available ({}). Install it with: {}",
+            args.join(" "),
+            reason().unwrap_or("Moon isn't installed"),
+            Shell::detect().install_moon()
+        );
+    }
+
+    let projects = projects();
+    let mut run = DirectRun {
+        passthrough: passthrough.iter().map(|arg| arg.to_string()).collect(),
+        ..DirectRun::default()
+    };
+    let mut seen = BTreeSet::new();
+    let mut requested = Vec::new();
+    for target in targets {
+        requested.extend(expand(&projects, target)?);
+    }
+    for (project, task) in &requested {
+        for dep in deps(&projects, project, task) {
+            visit(&projects, &dep, &mut run.deps, &mut seen, &mut Vec::new());
+        }
+    }
+    for (project, task) in requested {
+        let target = format!("{}:{}", project, task);
+        if seen.insert(target) {
+            run.targets.extend(task_for(&projects, &project, &task));
+        }
+    }
+    Ok(run)
+}
+
+/// The process that runs `moon <args>` without Moon: one shell running the resolved tasks
+pub fn command(args: &[&str]) -> Result<Command> {
+    let run = resolve(args)?;
+    announce();
+    let root = std::env::current_dir()?;
+    Ok(match Shell::detect() {
+        Shell::Posix => {
+            let mut command = Command::new("sh");
+            command.args(["-c", &posix_script(&run, &root)]);
+            command
+        }
+        Shell::Pwsh | Shell::Cmd => {
+            let mut command = Command::new("cmd");
+            command.args(["/C", &cmd_script(&run, &root)]);
+            command
+        }
+    })
+}
+
+/// Workspace projects as `moon query projects` lists them
+pub fn query_projects() -> Vec<MoonProject> {
+    projects()
+        .into_iter()
+        .map(|(id, path)| {
+            let config = project_config(&path);
+            let field = |key: &str| config.get(key).and_then(|v| v.as_str()).map(str::to_string);
+            MoonProject {
+                source: path.to_string_lossy().replace('\\', "/"),
+                language: field("language").unwrap_or_else(|| "unknown".to_string()),
+                layer: field("layer").or_else(|| field("type")),
+                stack: field("stack"),
+                id,
+            }
+        })
+        .collect()
+}
+
+/// Task names of every project, keyed by project id, as `moon query tasks` lists them
+pub fn query_tasks() -> BTreeMap<String, Vec<String>> {
+    projects()
+        .into_iter()
+        .map(|(id, path)| (id, task_names(&path).into_iter().collect()))
+        .collect()
+}
+
+/// Projects by id: those moonflare discovers plus `projects.sources` in .moon/workspace.yml
+fn projects() -> BTreeMap<String, PathBuf> {
+    let mut projects: BTreeMap<String, PathBuf> = discover_projects()
+        .into_iter()
+        .map(|project| (project.name, project.path))
+        .collect();
+    let workspace = read_yaml(Path::new(".moon/workspace.yml"));
+    if let Some(sources) = workspace
+        .get("projects")
+        .and_then(|p| p.get("sources"))
+        .and_then(|s| s.as_mapping())
+    {
+        for (id, source) in sources {
+            if let (Some(id), Some(source)) = (id.as_str(), source.as_str()) {
+                projects
+                    .entry(id.to_string())
+                    .or_insert_with(|| PathBuf::from(source));
+            }
+        }
+    }
+    projects
+}
+
+fn read_yaml(path: &Path) -> Value {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_yaml::from_str(&content).ok())
+        .unwrap_or(Value::Null)
+}
+
+fn project_config(path: &Path) -> Value {
+    read_yaml(&path.join("moon.yml"))
+}
+
+/// Tasks the project defines or inherits
+fn task_names(path: &Path) -> BTreeSet<String> {
+    WorkspaceProject {
+        name: String::new(),
+        path: path.to_path_buf(),
+    }
+    .tasks()
+}
+
+fn definition(projects: &BTreeMap<String, PathBuf>, project: &str, task: &str) -> Option<Value> {
+    let path = projects.get(project)?;
+    WorkspaceProject {
+        name: project.to_string(),
+        path: path.clone(),
+    }
+    .task_definition(task)
+}
+
+/// The `(project, task)` pairs a target names
+fn expand(projects: &BTreeMap<String, PathBuf>, target: &str) -> Result<Vec<(String, String)>> {
+    let (scope, task) = target.split_once(':').unwrap_or(("", target));
+    let has_task = |path: &PathBuf| task_names(path).contains(task);
+    let matches: Vec<(String, String)> = match scope {
+        "" | "*" => projects
+            .iter()
+            .filter(|(_, path)| has_task(path))
+            .map(|(id, _)| (id.clone(), task.to_string()))
+            .collect(),
+        tag if tag.starts_with('#') => projects
+            .iter()
+            .filter(|(_, path)| has_task(path))
+            .filter(|(_, path)| {
+                project_config(path)
+                    .get("tags")
+                    .and_then(|t| t.as_sequence())
+                    .is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(&tag[1..])))
+            })
+            .map(|(id, _)| (id.clone(), task.to_string()))
+            .collect(),
+        project => {
+            let Some(path) = projects.get(project) else {
+                bail!("No project '{}' in the workspace", project);
+            };
+            if !has_task(path) {
+                bail!("Project '{}' has no '{}' task", project, task);
+            }
+            vec![(project.to_string(), task.to_string())]
+        }
+    };
+    if matches.is_empty() {
+        bail!("No project in the workspace has a '{}' task", task);
+    }
+    Ok(matches)
+}
+
+/// The `(project, task)` pairs a task depends on: `~:task` or a bare name in the same
+/// project, `^:task` in the projects it depends on, `project:task` anywhere
+fn deps(projects: &BTreeMap<String, PathBuf>, project: &str, task: &str) -> Vec<(String, String)> {
+    let Some(definition) = definition(projects, project, task) else {
+        return Vec::new();
+    };
+    let targets = definition
+        .get("deps")
+        .and_then(|d| d.as_sequence())
+        .into_iter()
+        .flatten()
+        .filter_map(|dep| {
+            dep.as_str()
+                .or_else(|| dep.get("target").and_then(|t| t.as_str()))
+        });
+
+    let mut deps = Vec::new();
+    for target in targets {
+        match target.split_once(':') {
+            None => deps.push((project.to_string(), target.to_string())),
+            Some(("~", dep)) => deps.push((project.to_string(), dep.to_string())),
+            Some(("^", dep)) => {
+                let config = projects
+                    .get(project)
+                    .map(|path| project_config(path))
+                    .unwrap_or(Value::Null);
+                let upstream = config
+                    .get("dependsOn")
+                    .and_then(|d| d.as_sequence())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|d| d.as_str().or_else(|| d.get("id").and_then(|i| i.as_str())));
+                for id in upstream {
+                    if projects
+                        .get(id)
+                        .is_some_and(|p| task_names(p).contains(dep))
+                    {
+                        deps.push((id.to_string(), dep.to_string()));
+                    }
+                }
+            }
+            Some((other, dep)) => deps.push((other.to_string(), dep.to_string())),
+        }
+    }
+    deps
+}
+
+/// Add a dependency after its own dependencies, once. A task already running the same thing
+/// from the same directory (like every project's `~:install` from the workspace root) isn't
+/// added again.
+fn visit(
+    projects: &BTreeMap<String, PathBuf>,
+    (project, task): &(String, String),
+    resolved: &mut Vec<DirectTask>,
+    seen: &mut BTreeSet<String>,
+    stack: &mut Vec<String>,
+) {
+    let target = format!("{}:{}", project, task);
+    if seen.contains(&target) || stack.contains(&target) {
+        return;
+    }
+    stack.push(target.clone());
+    for dep in deps(projects, project, task) {
+        visit(projects, &dep, resolved, seen, stack);
+    }
+    stack.pop();
+    seen.insert(target);
+
+    if let Some(task) = task_for(projects, project, task)
+        && !resolved
+            .iter()
+            .any(|other| other.dir == task.dir && other.run == task.run && other.env == task.env)
+    {
+        resolved.push(task);
+    }
+}
+
+/// The task as it runs, or None when it only groups its dependencies (`noop`)
+fn task_for(projects: &BTreeMap<String, PathBuf>, project: &str, task: &str) -> Option<DirectTask> {
+    let path = projects.get(project)?;
+    let definition = definition(projects, project, task)?;
+    let root = std::env::current_dir().unwrap_or_default();
+    let substitute = |value: &str| {
+        value
+            .replace("$projectRoot", &root.join(path).to_string_lossy())
+            .replace("$workspaceRoot", &root.to_string_lossy())
+            .replace("$project", project)
+            .replace("$target", &format!("{}:{}", project, task))
+            .replace("$task", task)
+    };
+    let words = |value: Option<&Value>| -> Vec<String> {
+        task_words(value)
+            .iter()
+            .map(|word| substitute(word))
+            .collect()
+    };
+
+    let run = match definition.get("script").and_then(|s| s.as_str()) {
+        Some(script) => Invocation::Script(substitute(script)),
+        None => {
+            let mut command = words(definition.get("command"));
+            if command.is_empty() || command[0] == "noop" {
+                return None;
+            }
+            command.extend(words(definition.get("args")));
+            Invocation::Command(command)
+        }
+    };
+    let from_root = definition
+        .get("options")
+        .and_then(|o| o.get("runFromWorkspaceRoot"))
+        .and_then(|r| r.as_bool())
+        .unwrap_or(false);
+    let env = definition
+        .get("env")
+        .and_then(|e| e.as_mapping())
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| Some((key.as_str()?.to_string(), substitute(&scalar(value)?))))
+        .collect();
+
+    Some(DirectTask {
+        target: format!("{}:{}", project, task),
+        dir: if from_root {
+            PathBuf::from(".")
+        } else {
+            path.clone()
+        },
+        run,
+        env,
+    })
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// A POSIX shell script running the dependencies in order and then the targets side by side,
+/// so several dev servers keep running together
+fn posix_script(run: &DirectRun, root: &Path) -> String {
+    let shell = Shell::Posix;
+    let block = |task: &DirectTask, passthrough: &[String]| {
+        let mut lines = vec![
+            format!(
+                "printf '%s\\n' {}",
+                shell.quote(&format!("[no moon] {}", task.target))
+            ),
+            format!(
+                "{} || exit 1",
+                shell.change_dir(&task.dir.to_string_lossy())
+            ),
+            format!(
+                "export PATH=\"$PWD/node_modules/.bin:{}:$PATH\"",
+                root.join("node_modules/.bin")
+                    .to_string_lossy()
+                    .replace('"', "\\\"")
+            ),
+        ];
+        lines.extend(
+            task.env
+                .iter()
+                .map(|(key, value)| format!("export {}={}", key, shell.quote(value))),
+        );
+        lines.push(match &task.run {
+            Invocation::Script(script) => script.trim_end().to_string(),
+            Invocation::Command(words) => words
+                .iter()
+                .chain(passthrough)
+                .map(|word| shell.quote(word))
+                .collect::<Vec<_>>()
+                .join(" "),
+        });
+        format!("(\n{}\n)", lines.join("\n"))
+    };
+
+    let mut script: Vec<String> = run
+        .deps
+        .iter()
+        .map(|task| format!("{} || exit $?", block(task, &[])))
+        .collect();
+    match run.targets.as_slice() {
+        [target] => script.push(block(target, &run.passthrough)),
+        targets => {
+            script.push("pids=''".to_string());
+            for target in targets {
+                script.push(format!(
+                    "{} &\npids=\"$pids $!\"",
+                    block(target, &run.passthrough)
+                ));
+            }
+            script.push(
+                "status=0\nfor pid in $pids; do wait \"$pid\" || status=$?; done\nexit $status"
+                    .to_string(),
+            );
+        }
+    }
+    script.join("\n")
+}
+
+/// A cmd.exe command line running every task one after another. Scripts run line by line.
+fn cmd_script(run: &DirectRun, root: &Path) -> String {
+    let shell = Shell::Cmd;
+    let bin = root.join("node_modules").join(".bin");
+    let tasks = run.deps.iter().map(|task| (task, &[][..])).chain(
+        run.targets
+            .iter()
+            .map(|task| (task, run.passthrough.as_slice())),
+    );
+
+    let mut steps = Vec::new();
+    for (task, passthrough) in tasks {
+        let mut step = vec![
+            format!("echo [no moon] {}", task.target),
+            format!("pushd {}", shell.quote(&task.dir.to_string_lossy())),
+            // %PATH% expands when cmd reads the line, before pushd, so the paths are absolute
+            format!(
+                "set \"PATH={};{};%PATH%\"",
+                root.join(&task.dir)
+                    .join("node_modules")
+                    .join(".bin")
+                    .display(),
+                bin.display()
+            ),
+        ];
+        step.extend(
+            task.env
+                .iter()
+                .map(|(key, value)| format!("set \"{}={}\"", key, value)),
+        );
+        match &task.run {
+            Invocation::Script(script) => step.extend(
+                script
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(str::to_string),
+            ),
+            Invocation::Command(words) => step.push(
+                words
+                    .iter()
+                    .chain(passthrough)
+                    .map(|word| shell.quote(word))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+        }
+        step.push("popd".to_string());
+        steps.push(step.join(" && "));
+    }
+    steps.join(" && ")
+}
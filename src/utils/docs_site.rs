@@ -0,0 +1,345 @@
+use crate::errors::ERROR_CODES;
+use crate::utils::config::MoonflareConfig;
+use crate::utils::projects::{discover_projects, find_project};
+use crate::utils::readme::render_section;
+use anyhow::{Context, Result};
+use colored::*;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Port `moonflare docs serve` listens on unless told otherwise
+pub const DEFAULT_PORT: u16 = 4400;
+
+/// A command and its `--help`, e.g. `moonflare deploy`
+#[derive(Debug, Clone)]
+pub struct CommandDoc {
+    pub name: String,
+    pub about: String,
+    pub help: String,
+}
+
+/// A rendered page of the site
+#[derive(Debug)]
+pub struct Page {
+    pub title: String,
+    pub body: String,
+}
+
+/// Every visible command and subcommand of the CLI, depth first
+pub fn command_docs(cli: &mut clap::Command) -> Vec<CommandDoc> {
+    cli.build();
+    let mut docs = Vec::new();
+    collect_commands(cli, &mut docs);
+    docs
+}
+
+fn collect_commands(command: &clap::Command, docs: &mut Vec<CommandDoc>) {
+    for sub in command.get_subcommands() {
+        if sub.is_hide_set() || sub.get_name() == "help" {
+            continue;
+        }
+        docs.push(CommandDoc {
+            name: sub
+                .get_bin_name()
+                .map(str::to_string)
+                .unwrap_or_else(|| sub.get_name().to_string()),
+            about: sub.get_about().map(|a| a.to_string()).unwrap_or_default(),
+            help: sub.clone().render_long_help().to_string(),
+        });
+        collect_commands(sub, docs);
+    }
+}
+
+/// The page at `path`, or None when there is none. Project pages are rendered on every
+/// request, so they follow the workspace as it changes.
+pub fn page(path: &str, commands: &[CommandDoc]) -> Result<Option<Page>> {
+    let page = match path.trim_end_matches('/') {
+        "" => index(),
+        "/commands" => commands_page(commands),
+        "/errors" => errors_page(),
+        other => match other.strip_prefix("/projects/").and_then(find_project) {
+            Some(project) => Page {
+                title: project.name.clone(),
+                body: format!(
+                    "<h1>{}</h1>\n<p><code>{}</code></p>\n{}",
+                    escape_html(&project.name),
+                    escape_html(&project.relative_path()),
+                    markdown_to_html(&render_section(&project)?)
+                ),
+            },
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(page))
+}
+
+fn index() -> Page {
+    let name = MoonflareConfig::load()
+        .ok()
+        .and_then(|config| config.workspace.name)
+        .unwrap_or_else(|| "Workspace".to_string());
+    let mut body = format!("<h1>{}</h1>\n<h2>Projects</h2>\n", escape_html(&name));
+    let projects = discover_projects();
+    if projects.is_empty() {
+        body.push_str("<p>No projects yet. Add one with <code>moonflare add</code>.</p>\n");
+    } else {
+        body.push_str("<table>\n<tr><th>Project</th><th>Path</th></tr>\n");
+        for project in &projects {
+            body.push_str(&format!(
+                "<tr><td><a href=\"/projects/{0}\">{0}</a></td><td><code>{1}</code></td></tr>\n",
+                escape_html(&project.name),
+                escape_html(&project.relative_path())
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+    body.push_str(
+        "<h2>Reference</h2>\n<ul>\n<li><a href=\"/commands\">Commands</a>: every moonflare command and its options</li>\n<li><a href=\"/errors\">Errors</a>: what each error code means</li>\n</ul>\n",
+    );
+    Page { title: name, body }
+}
+
+fn commands_page(commands: &[CommandDoc]) -> Page {
+    let mut body = String::from("<h1>Commands</h1>\n<table>\n");
+    for command in commands {
+        body.push_str(&format!(
+            "<tr><td><a href=\"#{}\"><code>{}</code></a></td><td>{}</td></tr>\n",
+            anchor(&command.name),
+            escape_html(&command.name),
+            escape_html(&command.about)
+        ));
+    }
+    body.push_str("</table>\n");
+    for command in commands {
+        body.push_str(&format!(
+            "<h2 id=\"{}\">{}</h2>\n<pre>{}</pre>\n",
+            anchor(&command.name),
+            escape_html(&command.name),
+            escape_html(command.help.trim_end())
+        ));
+    }
+    Page {
+        title: "Commands".to_string(),
+        body,
+    }
+}
+
+fn errors_page() -> Page {
+    let mut body = String::from(
+        "<h1>Errors</h1>\n<p>Errors with a code show it under the message, e.g. <code>moonflare::build::project_not_found</code>.</p>\n",
+    );
+    for (code, explanation) in ERROR_CODES {
+        body.push_str(&format!(
+            "<h2 id=\"{}\"><code>{}</code></h2>\n<p>{}</p>\n",
+            anchor(code),
+            escape_html(code),
+            escape_html(explanation)
+        ));
+    }
+    Page {
+        title: "Errors".to_string(),
+        body,
+    }
+}
+
+/// The page inside the site's navigation and stylesheet
+fn layout(page: &Page) -> String {
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{} · moonflare docs</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 60rem; margin: 0 auto; padding: 1rem 2rem; line-height: 1.5; color: #1f2328; }}
+nav a {{ margin-right: 1rem; }}
+pre {{ background: #f6f8fa; padding: 1rem; overflow-x: auto; }}
+code {{ background: #f6f8fa; padding: 0 0.2rem; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #d0d7de; padding: 0.3rem 0.6rem; text-align: left; vertical-align: top; }}
+</style>
+</head>
+<body>
+<nav><a href="/">Workspace</a><a href="/commands">Commands</a><a href="/errors">Errors</a></nav>
+{}
+</body>
+</html>
+"#,
+        escape_html(&page.title),
+        page.body
+    )
+}
+
+/// Answer requests with the site's pages until the process exits
+pub async fn serve(listener: TcpListener, commands: Vec<CommandDoc>) {
+    let commands = std::sync::Arc::new(commands);
+    while let Ok((client, _)) = listener.accept().await {
+        let commands = commands.clone();
+        tokio::spawn(async move {
+            let _ = respond(client, &commands).await;
+        });
+    }
+}
+
+async fn respond(client: TcpStream, commands: &[CommandDoc]) -> Result<()> {
+    let mut reader = BufReader::new(client);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let path = target.split(['?', '#']).next().unwrap_or("/");
+
+    let (status, body) = match page(path, commands) {
+        Ok(Some(page)) => (200, layout(&page)),
+        Ok(None) => (
+            404,
+            layout(&Page {
+                title: "Not found".to_string(),
+                body: format!(
+                    "<h1>Not found</h1>\n<p>Nothing at <code>{}</code>.</p>\n",
+                    escape_html(path)
+                ),
+            }),
+        ),
+        Err(e) => (
+            500,
+            layout(&Page {
+                title: "Error".to_string(),
+                body: format!(
+                    "<h1>Error</h1>\n<pre>{}</pre>\n",
+                    escape_html(&e.to_string())
+                ),
+            }),
+        ),
+    };
+    println!("{}", format!("{} {}", status, path).dimmed());
+
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        match status {
+            200 => "OK",
+            404 => "Not Found",
+            _ => "Internal Server Error",
+        },
+        body.len(),
+        body
+    );
+    reader
+        .into_inner()
+        .write_all(response.as_bytes())
+        .await
+        .context("Failed to send the page")?;
+    Ok(())
+}
+
+/// HTML for the Markdown moonflare generates: headings, lists, tables, paragraphs and inline
+/// code. HTML comments are dropped.
+fn markdown_to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut list = false;
+    let mut table: Option<bool> = None;
+    let close = |html: &mut String, list: &mut bool, table: &mut Option<bool>| {
+        if *list {
+            html.push_str("</ul>\n");
+            *list = false;
+        }
+        if table.take().is_some() {
+            html.push_str("</table>\n");
+        }
+    };
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("<!--") && trimmed.ends_with("-->") {
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix("- ") {
+            if !list {
+                close(&mut html, &mut list, &mut table);
+                html.push_str("<ul>\n");
+                list = true;
+            }
+            html.push_str(&format!("<li>{}</li>\n", inline(item)));
+            continue;
+        }
+        if trimmed.starts_with('|') {
+            let cells: Vec<&str> = trimmed
+                .trim_matches('|')
+                .split('|')
+                .map(str::trim)
+                .collect();
+            if cells
+                .iter()
+                .all(|cell| cell.chars().all(|c| c == '-' || c == ':'))
+            {
+                continue;
+            }
+            let tag = match table {
+                None => {
+                    close(&mut html, &mut list, &mut table);
+                    html.push_str("<table>\n");
+                    table = Some(true);
+                    "th"
+                }
+                Some(_) => "td",
+            };
+            html.push_str("<tr>");
+            for cell in cells {
+                html.push_str(&format!("<{0}>{1}</{0}>", tag, inline(cell)));
+            }
+            html.push_str("</tr>\n");
+            continue;
+        }
+
+        close(&mut html, &mut list, &mut table);
+        if trimmed.is_empty() {
+            continue;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            html.push_str(&format!(
+                "<h{0}>{1}</h{0}>\n",
+                level,
+                inline(trimmed[level..].trim())
+            ));
+        } else {
+            html.push_str(&format!("<p>{}</p>\n", inline(trimmed)));
+        }
+    }
+    close(&mut html, &mut list, &mut table);
+    html
+}
+
+/// Escaped text with `code` spans
+fn inline(text: &str) -> String {
+    escape_html(text)
+        .split('`')
+        .enumerate()
+        .map(|(index, part)| {
+            if index % 2 == 1 {
+                format!("<code>{}</code>", part)
+            } else {
+                part.to_string()
+            }
+        })
+        .collect()
+}
+
+fn anchor(name: &str) -> String {
+    name.replace([' ', ':'], "-")
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
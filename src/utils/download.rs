@@ -0,0 +1,60 @@
+use crate::utils::sbom::sha256_hex;
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeMap;
+
+/// SHA-256 checksums published alongside release artifacts, one `<sha256>  <file name>` per
+/// line as goreleaser and `sha256sum` write them
+#[derive(Debug, Default)]
+pub struct Checksums {
+    digests: BTreeMap<String, String>,
+}
+
+impl Checksums {
+    pub fn parse(text: &str) -> Self {
+        let digests = text
+            .lines()
+            .filter_map(|line| {
+                let (digest, name) = line.trim().split_once(char::is_whitespace)?;
+                // `sha256sum -b` marks binary files with a leading '*'
+                let name = name.trim_start().trim_start_matches('*');
+                Some((name.to_string(), digest.to_lowercase()))
+            })
+            .collect();
+        Self { digests }
+    }
+
+    /// Fail unless `bytes` match the published checksum for `name`. An artifact missing from
+    /// the list is an error too: nothing downloaded is installed unverified.
+    pub fn verify(&self, name: &str, bytes: &[u8]) -> Result<()> {
+        let Some(expected) = self.digests.get(name) else {
+            bail!(
+                "No published checksum for '{}'; refusing to install it",
+                name
+            );
+        };
+        let actual = sha256_hex(bytes);
+        if &actual != expected {
+            bail!(
+                "Checksum mismatch for '{}': expected {}, got {}. The download may be corrupt or tampered with.",
+                name,
+                expected,
+                actual
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Download `url` in full
+pub async fn fetch(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let bytes = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {}", url))?
+        .error_for_status()?
+        .bytes()
+        .await
+        .with_context(|| format!("Failed to download {}", url))?;
+    Ok(bytes.to_vec())
+}
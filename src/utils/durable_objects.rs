@@ -0,0 +1,105 @@
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::seeds::LOCAL_STATE_DIR;
+use anyhow::{Result, bail};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+/// Where `wrangler dev` keeps Durable Objects under the local state directory, one
+/// `<worker>-<class>` directory per namespace
+const LOCAL_DO_DIR: &str = "v3/do";
+
+/// Classes the Worker defines itself; bindings with a `script_name` belong to other Workers
+pub fn defined_classes(config: &Value) -> Vec<String> {
+    config["durable_objects"]["bindings"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|binding| binding.get("script_name").is_none())
+        .filter_map(|binding| binding["class_name"].as_str().map(str::to_string))
+        .collect()
+}
+
+/// One Durable Object's state from `wrangler dev`, persisted as a SQLite database whether the
+/// class is SQLite-backed or uses key-value storage (kept in its `_cf_KV` table)
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalObject {
+    pub project: String,
+    pub class: String,
+    pub id: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Every object `wrangler dev` has persisted for the project, by class and id
+pub fn local_objects(project: &WorkspaceProject) -> Vec<LocalObject> {
+    let root = project.path.join(LOCAL_STATE_DIR).join(LOCAL_DO_DIR);
+    let mut objects = Vec::new();
+    for namespace in fs::read_dir(&root).into_iter().flatten().flatten() {
+        let name = namespace.file_name().to_string_lossy().to_string();
+        // Worker names can contain dashes but class names can't
+        let class = name
+            .rsplit_once('-')
+            .map_or(name.as_str(), |(_, class)| class);
+        for file in fs::read_dir(namespace.path())
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let path = file.path();
+            if path.extension().is_none_or(|ext| ext != "sqlite") {
+                continue;
+            }
+            objects.push(LocalObject {
+                project: project.name.clone(),
+                class: class.to_string(),
+                id: path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                size: file.metadata().map(|m| m.len()).unwrap_or(0),
+                path,
+            });
+        }
+    }
+    objects.sort_by(|a, b| (&a.class, &a.id).cmp(&(&b.class, &b.id)));
+    objects
+}
+
+/// The one object matching `class` and an `id` prefix; several matches are listed so one can
+/// be picked
+pub fn find_local_object<'a>(
+    objects: &'a [LocalObject],
+    project: &str,
+    class: Option<&str>,
+    id: Option<&str>,
+) -> Result<&'a LocalObject> {
+    let matching: Vec<&LocalObject> = objects
+        .iter()
+        .filter(|object| class.is_none_or(|class| object.class == class))
+        .filter(|object| id.is_none_or(|id| object.id.starts_with(id)))
+        .collect();
+    match matching.as_slice() {
+        [object] => Ok(object),
+        [] if objects.is_empty() => bail!(
+            "'{}' has no local Durable Object state yet; run 'moonflare dev' and send it a request first",
+            project
+        ),
+        [] => bail!(
+            "No local Durable Object of '{}' matches; 'moonflare do list {} --local' lists them",
+            project,
+            project
+        ),
+        several => bail!(
+            "{} local Durable Objects match; pick one with --object <ID>:\n  {}",
+            several.len(),
+            several
+                .iter()
+                .map(|object| format!("{} {}", object.class, object.id))
+                .collect::<Vec<_>>()
+                .join("\n  ")
+        ),
+    }
+}
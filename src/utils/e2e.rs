@@ -0,0 +1,155 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::manifest::{DEFAULT_ENVIRONMENT, DeploymentManifest};
+use crate::utils::moon::moon_command;
+use crate::utils::ports::dev_url;
+use crate::utils::projects::{ProjectFilter, WorkspaceProject, discover_projects};
+use crate::utils::smoke::SMOKE_TESTS_DIR;
+use crate::utils::task_env;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Name of the project `moonflare add e2e` generates, next to the smoke tests
+pub const E2E_PROJECT: &str = "e2e";
+
+/// Local URLs of the dev servers `moonflare dev` started, read by the Playwright config
+pub const DEV_TARGETS: &str = ".moonflare/dev.json";
+
+/// `--against` value that tests the dev servers rather than a deployed environment
+pub const DEV_AGAINST: &str = "dev";
+
+/// Dev servers recorded by the most recent `moonflare dev`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DevTargets {
+    pub started_at: String,
+    /// Local URL of each project's dev server
+    pub projects: BTreeMap<String, String>,
+}
+
+/// Whether the workspace has the generated end-to-end test project
+pub fn is_set_up() -> bool {
+    Path::new(SMOKE_TESTS_DIR)
+        .join(E2E_PROJECT)
+        .join("moon.yml")
+        .exists()
+}
+
+/// Sites and apps, whose builds the e2e task depends on
+pub fn frontends() -> Vec<WorkspaceProject> {
+    discover_projects()
+        .into_iter()
+        .filter(|project| !project.is_crate() && !project.is_worker())
+        .collect()
+}
+
+/// Record the dev servers about to start, so `moonflare test --e2e` knows where they listen
+pub fn write_dev_targets(
+    projects: &[WorkspaceProject],
+    config: &MoonflareConfig,
+    https: bool,
+) -> Result<()> {
+    let mut targets = DevTargets {
+        started_at: chrono::Utc::now().to_rfc3339(),
+        projects: BTreeMap::new(),
+    };
+    for project in projects.iter().filter(|project| !project.is_crate()) {
+        targets
+            .projects
+            .insert(project.name.clone(), dev_url(project, config, https)?);
+    }
+    let path = Path::new(DEV_TARGETS);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(&targets)?)
+        .with_context(|| format!("Failed to write {}", DEV_TARGETS))
+}
+
+/// URLs to test: the recorded dev servers for `dev`, else the projects the deployment
+/// manifest lists for the environment, at their `url` from moonflare.toml
+pub fn targets(against: &str, config: &MoonflareConfig) -> Result<BTreeMap<String, String>> {
+    if against == DEV_AGAINST {
+        let content = fs::read_to_string(DEV_TARGETS).with_context(|| {
+            format!(
+                "No dev servers recorded in {}; start them with 'moonflare dev' first",
+                DEV_TARGETS
+            )
+        })?;
+        let targets: DevTargets =
+            serde_json::from_str(&content).with_context(|| format!("Invalid {}", DEV_TARGETS))?;
+        return Ok(targets.projects);
+    }
+
+    // Deploys without --env are recorded under the default key and reach production URLs
+    let (key, env) = if against == "production" || against == DEFAULT_ENVIRONMENT {
+        (DEFAULT_ENVIRONMENT, None)
+    } else {
+        (against, Some(against))
+    };
+    let manifest = DeploymentManifest::load()?;
+    let Some(deployed) = manifest.environments.get(key) else {
+        bail!(
+            "Nothing has been deployed to '{}'; deploy with 'moonflare deploy{}' first",
+            against,
+            env.map(|env| format!(" --env {}", env)).unwrap_or_default()
+        );
+    };
+
+    let mut targets = BTreeMap::new();
+    for project in deployed.keys() {
+        match config.project_url(project, env) {
+            Some(url) => {
+                targets.insert(project.clone(), url);
+            }
+            None => println!(
+                "Skipping '{}': set url under [projects.{}] in moonflare.toml to test it",
+                project, project
+            ),
+        }
+    }
+    Ok(targets)
+}
+
+/// Run the Playwright suite against `against`, limited to the projects `filter` selects
+pub async fn run(against: &str, filter: &ProjectFilter) -> Result<()> {
+    if !is_set_up() {
+        bail!(
+            "No end-to-end tests in {}/{}; generate them with 'moonflare add e2e'",
+            SMOKE_TESTS_DIR,
+            E2E_PROJECT
+        );
+    }
+    let config = MoonflareConfig::load()?;
+    let mut targets = targets(against, &config)?;
+    if !filter.is_empty() {
+        let selected: Vec<String> = filter
+            .apply(discover_projects())
+            .into_iter()
+            .map(|project| project.name)
+            .collect();
+        targets.retain(|project, _| selected.contains(project));
+    }
+    if targets.is_empty() {
+        bail!("No project URLs to run the end-to-end tests against");
+    }
+    println!(
+        "Running end-to-end tests against {} ({})...",
+        targets.keys().cloned().collect::<Vec<_>>().join(", "),
+        against
+    );
+
+    let target = format!("{}:e2e", E2E_PROJECT);
+    // Playwright reads its targets from the environment, which moon passes on to the task
+    let mut command = moon_command(&["run", &target])?;
+    task_env::apply(&mut command, &["run", &target]);
+    command
+        .env("E2E_AGAINST", against)
+        .env("E2E_TARGETS", serde_json::to_string(&targets)?);
+    let status = command.status()?;
+    if !status.success() {
+        bail!("End-to-end tests failed against {}", against);
+    }
+    Ok(())
+}
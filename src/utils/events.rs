@@ -0,0 +1,138 @@
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Destination of the event stream; unset when neither `--events-file` nor `--events-fd`
+/// was given, which makes `emit` a no-op
+static SINK: OnceLock<Mutex<File>> = OnceLock::new();
+
+/// Something that happened while a command ran, written as one line of JSON
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    CommandStarted {
+        command: &'a str,
+        args: &'a [String],
+    },
+    /// A Moon target (build, test, deploy, ...) finished
+    TaskFinished {
+        target: &'a str,
+        project_path: Option<&'a str>,
+        success: bool,
+        duration_secs: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<&'a str>,
+    },
+    DeployFinished {
+        project: &'a str,
+        env: Option<&'a str>,
+        success: bool,
+        duration_secs: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<&'a str>,
+    },
+    Error {
+        command: &'a str,
+        message: &'a str,
+    },
+    /// Ctrl-C or SIGTERM stopped the command
+    Cancelled {
+        command: &'a str,
+        /// Targets that finished before the cancellation
+        finished: &'a [&'a str],
+        /// Subprocesses that were still running
+        stopped: &'a [String],
+        duration_secs: f64,
+    },
+    CommandFinished {
+        command: &'a str,
+        success: bool,
+        duration_secs: f64,
+    },
+}
+
+#[derive(Serialize)]
+struct Line<'a> {
+    timestamp: String,
+    #[serde(flatten)]
+    event: &'a Event<'a>,
+}
+
+/// Open the event stream: append to `file`, or write to the inherited descriptor `fd`
+pub fn init(file: Option<&Path>, fd: Option<i32>) -> Result<()> {
+    let sink = match (file, fd) {
+        (Some(path), _) => {
+            if let Some(parent) = path.parent()
+                && !parent.as_os_str().is_empty()
+            {
+                std::fs::create_dir_all(parent)?;
+            }
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open event file {}", path.display()))?
+        }
+        (None, Some(fd)) => open_fd(fd)?,
+        (None, None) => return Ok(()),
+    };
+
+    let _ = SINK.set(Mutex::new(sink));
+    Ok(())
+}
+
+#[cfg(unix)]
+fn open_fd(fd: i32) -> Result<File> {
+    use std::os::fd::BorrowedFd;
+
+    if fd <= 2 {
+        bail!("--events-fd must not be stdin, stdout or stderr");
+    }
+    // Duplicate the descriptor so a bad number fails here instead of on the first write
+    // SAFETY: the borrow only lives for the duration of the duplicate call
+    let owned = unsafe { BorrowedFd::borrow_raw(fd) }
+        .try_clone_to_owned()
+        .with_context(|| format!("File descriptor {} is not open", fd))?;
+    Ok(File::from(owned))
+}
+
+#[cfg(not(unix))]
+fn open_fd(_fd: i32) -> Result<File> {
+    bail!("--events-fd is only supported on Unix; use --events-file instead")
+}
+
+/// Write an event to the stream. Failures to write never fail the command.
+pub fn emit(event: Event) {
+    let Some(sink) = SINK.get() else {
+        return;
+    };
+
+    let line = Line {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        event: &event,
+    };
+    if let Ok(json) = serde_json::to_string(&line)
+        && let Ok(mut sink) = sink.lock()
+    {
+        let _ = writeln!(sink, "{}", json);
+        let _ = sink.flush();
+    }
+}
+
+/// Make sure everything emitted so far has reached the file or descriptor, before exiting
+/// early
+pub fn flush() {
+    if let Some(sink) = SINK.get()
+        && let Ok(sink) = sink.lock()
+    {
+        let _ = sink.sync_all();
+    }
+}
+
+pub fn secs(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1000.0).round() / 1000.0
+}
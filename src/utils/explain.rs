@@ -0,0 +1,293 @@
+use crate::utils::{
+    moon::find_moon_binary,
+    projects::{WorkspaceProject, discover_projects, find_project},
+    repro::{REDACTED, SECRET_KEY_PATTERN},
+    task_env,
+};
+use colored::*;
+use regex::Regex;
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A process build, dev or deploy would start, shown by `--explain-plan` instead of run
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: PathBuf,
+    /// Variables set over the inherited environment
+    pub env: BTreeMap<String, String>,
+    /// What each Moon target in `args` runs
+    pub tasks: Vec<TaskRun>,
+}
+
+impl Step {
+    /// A Moon invocation from the workspace root, with the variables moonflare injects for
+    /// its targets
+    pub fn moon(args: &[&str]) -> Self {
+        Self {
+            program: find_moon_binary()
+                .unwrap_or_else(|| PathBuf::from("moon"))
+                .display()
+                .to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            cwd: workspace_root(),
+            env: task_env::moon_vars(args),
+            tasks: task_runs(args),
+        }
+    }
+
+    /// `program` run directly in the project's directory, with the project's variables
+    pub fn in_project(project: &WorkspaceProject, program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            cwd: workspace_root().join(&project.path),
+            env: task_env::vars(Some(&project.name)),
+            tasks: Vec::new(),
+        }
+    }
+
+    pub fn env(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.env.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn envs<'a>(mut self, vars: impl IntoIterator<Item = (&'a str, String)>) -> Self {
+        for (name, value) in vars {
+            self.env.insert(name.to_string(), value);
+        }
+        self
+    }
+}
+
+/// The command a Moon target runs, resolved from the project's moon.yml and the task
+/// inheritance files
+#[derive(Debug, Clone)]
+pub struct TaskRun {
+    pub target: String,
+    pub cwd: PathBuf,
+    pub command: String,
+    /// The package.json script the command runs, by name
+    pub script: Option<(String, String)>,
+    /// Targets Moon runs first
+    pub deps: Vec<String>,
+}
+
+impl TaskRun {
+    fn resolve(
+        project: &WorkspaceProject,
+        task: &str,
+        definition: &Value,
+        passthrough: &[&str],
+    ) -> Self {
+        let with_passthrough = |line: &str| {
+            [line]
+                .into_iter()
+                .chain(passthrough.iter().copied())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let (command, script) = match definition.get("script").and_then(Value::as_str) {
+            Some(script) => (with_passthrough(script), None),
+            None => {
+                let mut words = task_words(definition.get("command"));
+                words.extend(task_words(definition.get("args")));
+                let script = package_script(&project.path, &words)
+                    .map(|(name, script)| (name, with_passthrough(&script)));
+                words.extend(passthrough.iter().map(|arg| arg.to_string()));
+                (command_line(&words), script)
+            }
+        };
+
+        let from_root = definition
+            .get("options")
+            .and_then(|options| options.get("runFromWorkspaceRoot"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let deps = definition
+            .get("deps")
+            .and_then(Value::as_sequence)
+            .map(|deps| {
+                deps.iter()
+                    .filter_map(|dep| dep.as_str().or_else(|| dep.get("target")?.as_str()))
+                    .map(|dep| match dep.strip_prefix("~:") {
+                        Some(task) => format!("{}:{}", project.name, task),
+                        None => dep.to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            target: format!("{}:{}", project.name, task),
+            cwd: if from_root {
+                workspace_root()
+            } else {
+                workspace_root().join(&project.path)
+            },
+            command,
+            script,
+            deps,
+        }
+    }
+}
+
+/// Everything a command would run, in order, and what moonflare does itself around it
+#[derive(Debug, Default)]
+pub struct Explanation {
+    steps: Vec<Step>,
+    notes: Vec<String>,
+}
+
+impl Explanation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn step(&mut self, step: Step) {
+        self.steps.push(step);
+    }
+
+    /// Work moonflare does in-process, such as writing files or serving proxies
+    pub fn note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into());
+    }
+
+    /// Each step's command line, working directory and variables, then what its Moon
+    /// targets run. Values of variables named like credentials are hidden.
+    pub fn render(&self) {
+        let secret = Regex::new(SECRET_KEY_PATTERN).expect("valid regex");
+        println!("{}", "Execution plan (nothing is run)".cyan().bold());
+        for (index, step) in self.steps.iter().enumerate() {
+            let mut line = vec![step.program.clone()];
+            line.extend(step.args.iter().cloned());
+            println!();
+            println!(
+                "{} {}",
+                format!("{}.", index + 1).bold(),
+                command_line(&line)
+            );
+            println!("   {} {}", "cwd:".dimmed(), step.cwd.display());
+            if step.env.is_empty() {
+                println!("   {} {}", "env:".dimmed(), "(nothing set)".dimmed());
+            }
+            for (position, (name, value)) in step.env.iter().enumerate() {
+                let value = if secret.is_match(name) {
+                    REDACTED.to_string()
+                } else {
+                    quote(value)
+                };
+                let label = if position == 0 { "env:" } else { "    " };
+                println!("   {} {}={}", label.dimmed(), name, value);
+            }
+            for task in &step.tasks {
+                println!("   {} runs {}", task.target.bold(), task.command);
+                println!("     {} {}", "cwd:".dimmed(), task.cwd.display());
+                if let Some((name, script)) = &task.script {
+                    println!("     {} {}", format!("script {}:", name).dimmed(), script);
+                }
+                if !task.deps.is_empty() {
+                    println!("     {} {}", "after:".dimmed(), task.deps.join(", "));
+                }
+            }
+        }
+        if !self.notes.is_empty() {
+            println!();
+            println!("{}", "Done by moonflare itself:".bold());
+            for note in &self.notes {
+                println!("  - {}", note);
+            }
+        }
+    }
+}
+
+fn workspace_root() -> PathBuf {
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+/// Tasks of the Moon targets in `args`: `project:task`, or `:task` for every project
+/// defining it. Arguments after `--` are passed on to each task's command.
+fn task_runs(args: &[&str]) -> Vec<TaskRun> {
+    let (targets, passthrough) = match args.iter().position(|arg| *arg == "--") {
+        Some(index) => (&args[..index], &args[index + 1..]),
+        None => (args, &[][..]),
+    };
+
+    let mut runs = Vec::new();
+    for (project, task) in targets
+        .iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .filter_map(|arg| arg.split_once(':'))
+    {
+        let projects = if project.is_empty() {
+            discover_projects()
+        } else {
+            find_project(project).into_iter().collect()
+        };
+        for project in projects {
+            if let Some(definition) = project.task_definition(task) {
+                runs.push(TaskRun::resolve(&project, task, &definition, passthrough));
+            }
+        }
+    }
+    runs
+}
+
+/// A task's `command` or `args`, written as a string or a list
+pub fn task_words(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(line)) => line.split_whitespace().map(String::from).collect(),
+        Some(Value::Sequence(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(word) => Some(word.clone()),
+                Value::Number(number) => Some(number.to_string()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The package.json script a `pnpm run <name>` or `pnpm <name>` command runs
+fn package_script(project_path: &Path, words: &[String]) -> Option<(String, String)> {
+    let [manager, rest @ ..] = words else {
+        return None;
+    };
+    if !matches!(manager.as_str(), "pnpm" | "npm" | "yarn" | "bun") {
+        return None;
+    }
+    let name = match rest {
+        [run, name, ..] if run == "run" => name,
+        [name, ..] => name,
+        [] => return None,
+    };
+    let package: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(project_path.join("package.json")).ok()?)
+            .ok()?;
+    let script = package["scripts"][name.as_str()].as_str()?;
+    Some((name.clone(), script.to_string()))
+}
+
+/// Words joined into a line a shell would split back into the same words
+fn command_line(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|word| quote(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+%^~".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
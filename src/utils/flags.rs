@@ -0,0 +1,273 @@
+use crate::utils::projects::similar_names;
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Flag definitions at the workspace root
+pub const FLAGS_FILE: &str = "flags.toml";
+/// Project serving flag values, in the workers directory
+pub const FLAGS_PROJECT: &str = "flags";
+/// KV binding of the flags worker
+pub const FLAGS_BINDING: &str = "FLAGS";
+/// Generated typed client, imported as `@packages/flags`
+pub const CLIENT_DIR: &str = "packages/flags";
+/// KV namespace id the scaffolded wrangler.toml holds until the namespace is created
+pub const PLACEHOLDER_NAMESPACE_ID: &str = "REPLACE_WITH_NAMESPACE_ID";
+
+/// First line of generated files, which `moonflare flags init` rewrites
+const GENERATED_HEADER: &str =
+    "// Generated by moonflare from flags.toml; run 'moonflare flags init' after editing it.";
+
+/// flags.toml written by `moonflare flags init` when there's none yet
+pub const STARTER_FLAGS: &str = r#"# Feature flags for every project in the workspace. The default's type (boolean, number or
+# string) is the flag's type. After editing, run 'moonflare flags init' to regenerate the
+# typed client in packages/flags, and 'moonflare flags set <flag> <value>' to change a value.
+
+[flags.new-checkout]
+description = "Serve the redesigned checkout"
+default = false
+"#;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FlagDefinition {
+    pub description: Option<String>,
+    pub default: toml::Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FlagsFile {
+    pub flags: BTreeMap<String, FlagDefinition>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagType {
+    Boolean,
+    Number,
+    String,
+}
+
+impl FlagType {
+    fn typescript(self) -> &'static str {
+        match self {
+            FlagType::Boolean => "boolean",
+            FlagType::Number => "number",
+            FlagType::String => "string",
+        }
+    }
+}
+
+impl FlagDefinition {
+    pub fn flag_type(&self) -> Option<FlagType> {
+        match self.default {
+            toml::Value::Boolean(_) => Some(FlagType::Boolean),
+            toml::Value::Integer(_) | toml::Value::Float(_) => Some(FlagType::Number),
+            toml::Value::String(_) => Some(FlagType::String),
+            _ => None,
+        }
+    }
+
+    /// `value` as the JSON the flags worker stores, if it has the flag's type
+    pub fn parse_value(&self, name: &str, value: &str) -> Result<Value> {
+        match self.flag_type() {
+            Some(FlagType::Boolean) => match value {
+                "true" | "on" => Ok(Value::Bool(true)),
+                "false" | "off" => Ok(Value::Bool(false)),
+                _ => bail!("'{}' is a boolean flag; use true or false", name),
+            },
+            Some(FlagType::Number) => value
+                .parse::<i64>()
+                .map(serde_json::Number::from)
+                .ok()
+                .or_else(|| {
+                    value
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(serde_json::Number::from_f64)
+                })
+                .map(Value::Number)
+                .with_context(|| {
+                    format!("'{}' is a number flag; '{}' isn't a number", name, value)
+                }),
+            Some(FlagType::String) | None => Ok(Value::String(value.to_string())),
+        }
+    }
+}
+
+impl FlagsFile {
+    /// The definition of `name`, suggesting close names when there's none
+    pub fn get(&self, name: &str) -> Result<&FlagDefinition> {
+        if let Some(flag) = self.flags.get(name) {
+            return Ok(flag);
+        }
+        match similar_names(name, self.flags.keys().map(String::as_str)).first() {
+            Some(suggestion) => bail!(
+                "No flag '{}' in {}; did you mean '{}'?",
+                name,
+                FLAGS_FILE,
+                suggestion
+            ),
+            None => bail!("No flag '{}' in {}", name, FLAGS_FILE),
+        }
+    }
+}
+
+/// Flag definitions from flags.toml, checked for types the client can represent
+pub fn read_flags() -> Result<FlagsFile> {
+    let content = fs::read_to_string(FLAGS_FILE).with_context(|| {
+        format!(
+            "No {} in this workspace; run 'moonflare flags init' first",
+            FLAGS_FILE
+        )
+    })?;
+    let flags: FlagsFile =
+        toml::from_str(&content).with_context(|| format!("Invalid {}", FLAGS_FILE))?;
+    for (name, flag) in &flags.flags {
+        if flag.flag_type().is_none() {
+            bail!(
+                "Flag '{}' in {} needs a boolean, number or string default",
+                name,
+                FLAGS_FILE
+            );
+        }
+    }
+    Ok(flags)
+}
+
+fn json_default(flag: &FlagDefinition) -> String {
+    serde_json::to_string(&flag.default).unwrap_or_else(|_| "null".to_string())
+}
+
+/// The typed client in packages/flags: a `Flags` interface, the defaults, and a client that
+/// reads values from the flags worker through a service binding or its URL
+pub fn render_client(flags: &FlagsFile) -> String {
+    let mut fields = String::new();
+    let mut defaults = String::new();
+    for (name, flag) in &flags.flags {
+        if let Some(description) = &flag.description {
+            fields.push_str(&format!("  /** {} */\n", description));
+        }
+        let flag_type = flag.flag_type().map_or("unknown", FlagType::typescript);
+        fields.push_str(&format!("  {:?}: {};\n", name, flag_type));
+        defaults.push_str(&format!("  {:?}: {},\n", name, json_default(flag)));
+    }
+    let boolean_flags: Vec<String> = flags
+        .flags
+        .iter()
+        .filter(|(_, flag)| flag.flag_type() == Some(FlagType::Boolean))
+        .map(|(name, _)| format!("{:?}", name))
+        .collect();
+    let boolean_flags = if boolean_flags.is_empty() {
+        "never".to_string()
+    } else {
+        boolean_flags.join(" | ")
+    };
+
+    format!(
+        r#"{header}
+export interface Flags {{
+{fields}}}
+
+export type FlagName = keyof Flags;
+export type BooleanFlag = {boolean_flags};
+
+export const defaults: Flags = {{
+{defaults}}};
+
+/** Anything with fetch: the flags worker's service binding in a Worker, or globalThis in a browser */
+export interface FlagsSource {{
+  fetch(input: RequestInfo | URL, init?: RequestInit): Promise<Response>;
+}}
+
+/**
+ * Read flags from the flags worker. In a Worker, pass its service binding:
+ * `createFlagsClient(env.FLAGS_SERVICE)`; in a browser, its URL:
+ * `createFlagsClient(globalThis, "https://flags.example.workers.dev")`.
+ * Values fall back to the defaults when the worker can't be reached.
+ */
+export function createFlagsClient(source: FlagsSource = globalThis, baseUrl = "https://flags") {{
+  const all = async (): Promise<Flags> => {{
+    try {{
+      const response = await source.fetch(`${{baseUrl}}/flags`);
+      if (!response.ok) return {{ ...defaults }};
+      return {{ ...defaults, ...((await response.json()) as Partial<Flags>) }};
+    }} catch {{
+      return {{ ...defaults }};
+    }}
+  }};
+  return {{
+    all,
+    async get<K extends FlagName>(name: K): Promise<Flags[K]> {{
+      return (await all())[name];
+    }},
+    async isEnabled(name: BooleanFlag): Promise<boolean> {{
+      return Boolean((await all())[name]);
+    }},
+  }};
+}}
+"#,
+        header = GENERATED_HEADER,
+        fields = fields,
+        boolean_flags = boolean_flags,
+        defaults = defaults,
+    )
+}
+
+/// Write the typed client, unless packages/flags/index.ts was written by hand. Returns
+/// whether the file changed.
+pub fn write_client(flags: &FlagsFile) -> Result<bool> {
+    let path = Path::new(CLIENT_DIR).join("index.ts");
+    let existing = fs::read_to_string(&path).ok();
+    if let Some(existing) = &existing
+        && !existing.starts_with(GENERATED_HEADER)
+    {
+        bail!(
+            "{} wasn't generated by moonflare; remove it to generate the flags client",
+            path.display()
+        );
+    }
+    let rendered = render_client(flags);
+    if existing.as_deref() == Some(rendered.as_str()) {
+        return Ok(false);
+    }
+    fs::create_dir_all(CLIENT_DIR)?;
+    fs::write(&path, rendered).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(true)
+}
+
+/// Refuse to write flags before the KV namespace of `env` (or the top level) has a real id
+pub fn check_namespace(worker_dir: &Path, env: Option<&str>) -> Result<()> {
+    let config = read_wrangler_config(worker_dir)?
+        .with_context(|| format!("{} has no Wrangler config", worker_dir.display()))?;
+    // Bindings aren't inherited, so an environment's own list replaces the top-level one
+    let namespaces = env
+        .and_then(|env| config["env"][env]["kv_namespaces"].as_array())
+        .or_else(|| config["kv_namespaces"].as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let Some(namespace) = namespaces.iter().find(|ns| ns["binding"] == FLAGS_BINDING) else {
+        bail!(
+            "{}'s Wrangler config has no {} KV binding{}",
+            worker_dir.display(),
+            FLAGS_BINDING,
+            env.map(|env| format!(" for '{}'", env)).unwrap_or_default()
+        );
+    };
+    if namespace["id"]
+        .as_str()
+        .is_none_or(|id| id == PLACEHOLDER_NAMESPACE_ID)
+    {
+        bail!(
+            "The {} KV namespace has no id yet; run 'pnpm --dir {} wrangler kv namespace create {}' and put its id in the Wrangler config",
+            FLAGS_BINDING,
+            worker_dir.display(),
+            FLAGS_BINDING
+        );
+    }
+    Ok(())
+}
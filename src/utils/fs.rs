@@ -1,3 +1,6 @@
+use crate::templates::registry;
+use crate::utils::config::LayoutConfig;
+use crate::utils::projects::discover_projects;
 use anyhow::Result;
 use serde_yaml::Value;
 use std::fs;
@@ -10,29 +13,83 @@ pub fn create_directory_if_not_exists(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Error message for a non-empty target directory, naming up to three of its entries
+pub fn non_empty_directory_message(dir_name: &str, entries: &[fs::DirEntry]) -> String {
+    let sample_files = entries
+        .iter()
+        .take(3)
+        .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if entries.len() == 1 {
+        format!(
+            "Directory is not empty. Directory '{}' contains 1 file: {}",
+            dir_name, sample_files
+        )
+    } else if entries.len() <= 3 {
+        format!(
+            "Directory is not empty. Directory '{}' contains {} files: {}",
+            dir_name,
+            entries.len(),
+            sample_files
+        )
+    } else {
+        format!(
+            "Directory is not empty. Directory '{}' contains {} files including: {}",
+            dir_name,
+            entries.len(),
+            sample_files
+        )
+    }
+}
+
+/// Replace whole identifiers (runs of letters, digits, `_` and `-`) matching one of the
+/// `(old, new)` pairs, leaving identifiers that merely contain an old name untouched
+pub fn replace_identifiers(content: &str, replacements: &[(String, String)]) -> String {
+    let identifier = regex::Regex::new(r"[A-Za-z0-9_-]+").expect("valid identifier regex");
+    identifier
+        .replace_all(content, |caps: &regex::Captures| {
+            let token = &caps[0];
+            replacements
+                .iter()
+                .find(|(old, _)| old == token)
+                .map_or_else(|| token.to_string(), |(_, new)| new.clone())
+        })
+        .into_owned()
+}
+
 pub fn is_moonflare_workspace() -> bool {
     Path::new(".moon/workspace.yml").exists() || Path::new("package.json").exists()
 }
 
-pub fn get_project_directory(project_type: &str) -> &'static str {
-    match project_type {
-        "astro" => "sites",
-        "react" => "apps",
-        "durable-object" | "worker" => "workers",
-        "crate" => "crates",
-        _ => "apps", // fallback
+/// The closest workspace above `dir` (not `dir` itself), found by its `.moon/workspace.yml`
+pub fn enclosing_workspace(dir: &Path) -> Option<PathBuf> {
+    let dir = std::path::absolute(dir).ok()?;
+    dir.ancestors()
+        .skip(1)
+        .find(|ancestor| ancestor.join(".moon/workspace.yml").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Directory a new project of `project_type` is created in, following `[layout]`
+pub fn get_project_directory(project_type: &str) -> String {
+    let layout = LayoutConfig::current();
+    match registry::find(project_type) {
+        Some(kind) => kind.directory.resolve(&layout),
+        None => layout.apps, // fallback
     }
 }
 
 /// Check if any crates exist in the workspace
 pub fn has_crates() -> bool {
-    let crates_dir = Path::new("crates");
+    let crates_dir = PathBuf::from(LayoutConfig::current().crates);
     if !crates_dir.exists() {
         return false;
     }
 
     // Check if there are any subdirectories in crates/
-    if let Ok(entries) = fs::read_dir(crates_dir) {
+    if let Ok(entries) = fs::read_dir(&crates_dir) {
         for entry in entries.flatten() {
             if entry.path().is_dir() {
                 return true;
@@ -42,31 +99,19 @@ pub fn has_crates() -> bool {
     false
 }
 
-/// Get all TypeScript projects (astro, react, durable-object)
+/// Get all TypeScript projects (astro, react, durable-object), including grouped ones
 pub fn get_typescript_projects() -> Vec<PathBuf> {
-    let mut projects = Vec::new();
-
-    let directories = ["sites", "apps", "workers"];
-
-    for dir in directories {
-        let dir_path = Path::new(dir);
-        if !dir_path.exists() {
-            continue;
-        }
-
-        if let Ok(entries) = fs::read_dir(dir_path) {
-            for entry in entries.flatten() {
-                if entry.path().is_dir() {
-                    let moon_yml = entry.path().join("moon.yml");
-                    if moon_yml.exists() {
-                        projects.push(entry.path());
-                    }
-                }
-            }
-        }
-    }
+    discover_projects()
+        .into_iter()
+        .filter(|project| !project.is_crate())
+        .map(|project| project.path)
+        .collect()
+}
 
-    projects
+/// Relative path from a workspace-relative directory back to the workspace root, e.g. `../..`
+/// for `apps/web` and `../../..` for `apps/shop/frontend`
+pub fn path_to_root(path: &Path) -> String {
+    vec![".."; path.components().count()].join("/")
 }
 
 /// Update a project's moon.yml to add shared-wasm:gather dependency
@@ -123,11 +168,11 @@ pub fn add_wasm_dependency_to_project(project_path: &Path) -> Result<()> {
         build_mapping.remove(Value::String("command".to_string()));
         build_mapping.remove(Value::String("args".to_string()));
 
-        let script = "pnpm build\n# Copy WASM files to dist for Cloudflare deployment\nmkdir -p dist\ncp -f ../../shared-wasm/*.wasm dist/ 2>/dev/null || true";
-        build_mapping.insert(
-            Value::String("script".to_string()),
-            Value::String(script.to_string()),
+        let script = format!(
+            "pnpm build\n# Copy WASM files to dist for Cloudflare deployment\nmkdir -p dist\ncp -f {}/shared-wasm/*.wasm dist/ 2>/dev/null || true",
+            path_to_root(project_path)
         );
+        build_mapping.insert(Value::String("script".to_string()), Value::String(script));
     }
 
     // Write back to file
@@ -200,3 +245,102 @@ pub fn add_crate_build_dependency_to_shared_wasm(crate_name: &str) -> Result<()>
 
     Ok(())
 }
+
+/// Remove a crate's build task from the dependencies of shared-wasm:gather, returning whether
+/// it was there
+pub fn remove_crate_build_dependency_from_shared_wasm(crate_name: &str) -> Result<bool> {
+    let shared_wasm_moon_yml = Path::new("shared-wasm/moon.yml");
+    if !shared_wasm_moon_yml.exists() {
+        return Ok(false);
+    }
+
+    let content = fs::read_to_string(shared_wasm_moon_yml)?;
+    let mut config: Value = serde_yaml::from_str(&content)?;
+    let crate_target = format!("{}:build", crate_name);
+
+    let Some(deps) = config
+        .get_mut("tasks")
+        .and_then(|tasks| tasks.get_mut("gather"))
+        .and_then(|gather| gather.get_mut("deps"))
+        .and_then(|deps| deps.as_sequence_mut())
+    else {
+        return Ok(false);
+    };
+    let before = deps.len();
+    deps.retain(|dep| dep.as_str() != Some(&crate_target));
+    if deps.len() == before {
+        return Ok(false);
+    }
+
+    fs::write(shared_wasm_moon_yml, serde_yaml::to_string(&config)?)?;
+    Ok(true)
+}
+
+/// Add a tag to a project's moon.yml, returning false if it was already present
+pub fn add_tag_to_project(project_path: &Path, tag: &str) -> Result<bool> {
+    let moon_yml_path = project_path.join("moon.yml");
+    let content = fs::read_to_string(&moon_yml_path)?;
+    let mut config: Value = serde_yaml::from_str(&content)?;
+
+    let Some(mapping) = config.as_mapping_mut() else {
+        anyhow::bail!("Invalid moon.yml at {}", moon_yml_path.display());
+    };
+
+    let mut tags = mapping
+        .get("tags")
+        .and_then(|t| t.as_sequence())
+        .cloned()
+        .unwrap_or_default();
+
+    if tags.iter().any(|t| t.as_str() == Some(tag)) {
+        return Ok(false);
+    }
+
+    tags.push(Value::String(tag.to_string()));
+    mapping.insert(Value::String("tags".to_string()), Value::Sequence(tags));
+
+    let updated_content = serde_yaml::to_string(&config)?;
+    fs::write(&moon_yml_path, updated_content)?;
+
+    Ok(true)
+}
+
+/// Remove a tag from a project's moon.yml, returning false if it wasn't present
+pub fn remove_tag_from_project(project_path: &Path, tag: &str) -> Result<bool> {
+    let moon_yml_path = project_path.join("moon.yml");
+    let content = fs::read_to_string(&moon_yml_path)?;
+    let mut config: Value = serde_yaml::from_str(&content)?;
+
+    let Some(mapping) = config.as_mapping_mut() else {
+        anyhow::bail!("Invalid moon.yml at {}", moon_yml_path.display());
+    };
+
+    let tags = mapping
+        .get("tags")
+        .and_then(|t| t.as_sequence())
+        .cloned()
+        .unwrap_or_default();
+
+    if !tags.iter().any(|t| t.as_str() == Some(tag)) {
+        return Ok(false);
+    }
+
+    let remaining: Vec<Value> = tags
+        .into_iter()
+        .filter(|t| t.as_str() != Some(tag))
+        .collect();
+
+    if remaining.is_empty() {
+        mapping.remove(Value::String("tags".to_string()));
+    } else {
+        mapping.insert(
+            Value::String("tags".to_string()),
+            Value::Sequence(remaining),
+        );
+    }
+
+    let updated_content = serde_yaml::to_string(&config)?;
+    fs::write(&moon_yml_path, updated_content)?;
+
+    Ok(true)
+}
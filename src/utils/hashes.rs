@@ -0,0 +1,176 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Where Moon writes the manifest behind every task hash it computes
+pub const HASHES_DIR: &str = ".moon/cache/hashes";
+
+/// The parts of a Moon task hash manifest that explain why the hash changed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskHash {
+    pub hash: String,
+    pub target: String,
+    pub command: Vec<String>,
+    /// Upstream targets and their hashes
+    pub deps: BTreeMap<String, String>,
+    /// Task environment and `$VAR` inputs
+    pub env: BTreeMap<String, String>,
+    /// Workspace-relative input files and their content hashes
+    pub inputs: BTreeMap<String, String>,
+    pub modified: Option<SystemTime>,
+}
+
+/// Why a task's hash differs between two runs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HashDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+    pub env: Vec<String>,
+    pub deps: Vec<String>,
+    pub command: bool,
+}
+
+impl HashDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.env.is_empty()
+            && self.deps.is_empty()
+            && !self.command
+    }
+}
+
+impl TaskHash {
+    /// Parse a manifest, which holds the task's hash content alongside toolchain content
+    fn parse(hash: &str, manifest: &Value) -> Option<Self> {
+        let contents = match manifest {
+            Value::Array(contents) => contents.iter().collect(),
+            other => vec![other],
+        };
+        let task = contents
+            .into_iter()
+            .find(|content| content.get("target").and_then(|t| t.as_str()).is_some())?;
+
+        let strings = |key: &str| -> BTreeMap<String, String> {
+            task.get(key)
+                .and_then(|v| v.as_object())
+                .map(|map| {
+                    map.iter()
+                        .map(|(k, v)| (k.clone(), v.as_str().unwrap_or_default().to_string()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let mut command: Vec<String> = task
+            .get("command")
+            .and_then(|c| c.as_str())
+            .map(str::to_string)
+            .into_iter()
+            .collect();
+        command.extend(
+            task.get("args")
+                .and_then(|a| a.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|arg| arg.as_str().map(str::to_string)),
+        );
+
+        let mut env = strings("env");
+        env.extend(strings("inputEnv"));
+        env.extend(strings("input_env"));
+
+        Some(Self {
+            hash: hash.to_string(),
+            target: task["target"].as_str()?.to_string(),
+            command,
+            deps: strings("deps"),
+            env,
+            inputs: strings("inputs"),
+            modified: None,
+        })
+    }
+
+    /// What changed from `previous` to this run
+    pub fn diff(&self, previous: &TaskHash) -> HashDiff {
+        let changed_keys = |current: &BTreeMap<String, String>,
+                            before: &BTreeMap<String, String>| {
+            current
+                .iter()
+                .filter(|(key, value)| before.get(*key).is_some_and(|old| old != *value))
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut env = changed_keys(&self.env, &previous.env);
+        env.extend(
+            self.env
+                .keys()
+                .chain(previous.env.keys())
+                .filter(|key| self.env.contains_key(*key) != previous.env.contains_key(*key))
+                .cloned(),
+        );
+        env.sort();
+        env.dedup();
+
+        HashDiff {
+            added: self
+                .inputs
+                .keys()
+                .filter(|path| !previous.inputs.contains_key(*path))
+                .cloned()
+                .collect(),
+            removed: previous
+                .inputs
+                .keys()
+                .filter(|path| !self.inputs.contains_key(*path))
+                .cloned()
+                .collect(),
+            changed: changed_keys(&self.inputs, &previous.inputs),
+            env,
+            deps: self
+                .deps
+                .iter()
+                .filter(|(target, hash)| previous.deps.get(*target) != Some(*hash))
+                .map(|(target, _)| target.clone())
+                .collect(),
+            command: self.command != previous.command,
+        }
+    }
+}
+
+/// Every recorded hash of `target`, oldest first
+pub fn task_hashes(root: &Path, target: &str) -> Vec<TaskHash> {
+    let Ok(entries) = fs::read_dir(root.join(HASHES_DIR)) else {
+        return Vec::new();
+    };
+
+    let mut hashes = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(hash) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Some(manifest) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        else {
+            continue;
+        };
+        if let Some(mut task_hash) = TaskHash::parse(hash, &manifest)
+            && task_hash.target == target
+        {
+            task_hash.modified = entry.metadata().ok().and_then(|m| m.modified().ok());
+            hashes.push(task_hash);
+        }
+    }
+    hashes.sort_by_key(|hash| hash.modified);
+    hashes
+}
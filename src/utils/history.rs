@@ -0,0 +1,232 @@
+use crate::commands::loadtest::parse_duration;
+use crate::utils::fs::is_moonflare_workspace;
+use crate::utils::projects::discover_projects;
+use crate::utils::vcs::triggered_by;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ArgMatches;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Append-only log of the operations that changed the workspace
+pub const HISTORY_FILE: &str = ".moonflare/history.jsonl";
+
+/// Append-only log of every command run, read-only ones included, for `moonflare history stats`
+pub const USAGE_FILE: &str = ".moonflare/usage.jsonl";
+
+/// One mutating moonflare invocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// RFC 3339, in UTC
+    pub timestamp: String,
+    pub user: String,
+    /// Command and subcommand, e.g. `tag add`
+    pub command: String,
+    pub args: Vec<String>,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_secs: f64,
+}
+
+/// One moonflare invocation of any kind, without its arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageEntry {
+    /// RFC 3339, in UTC
+    pub timestamp: String,
+    pub user: String,
+    /// Command and subcommand, e.g. `env sync`
+    pub command: String,
+    /// Workspace projects named in the arguments
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+    pub success: bool,
+    pub duration_secs: f64,
+}
+
+/// The command and subcommand that ran, e.g. `tag add`, or `build` for a command without
+/// subcommands
+pub fn command_name(matches: &ArgMatches) -> Option<String> {
+    let (command, args) = matches.subcommand()?;
+    Some(match args.subcommand() {
+        Some((name, _)) => format!("{} {}", command, name),
+        None => command.to_string(),
+    })
+}
+
+/// The command's name when the invocation changes the workspace or its deployments; read-only
+/// commands, `--check` and `--dry-run` runs aren't recorded
+pub fn mutating_command(matches: &ArgMatches) -> Option<String> {
+    let (command, args) = matches.subcommand()?;
+    let flag = |args: &ArgMatches, id: &str| {
+        args.try_get_one::<bool>(id)
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(false)
+    };
+
+    let action = args.subcommand();
+    let mutating = match (command, action.map(|(name, _)| name)) {
+        ("import" | "rename" | "archive" | "unarchive" | "deploy" | "promote" | "restore", _) => {
+            true
+        }
+        ("add", _) => !flag(args, "list_types"),
+        ("sync-names", _) => !flag(args, "check"),
+        ("doctor" | "audit-bindings", _) => flag(args, "fix"),
+        ("describe", _) => flag(args, "write"),
+        ("clean", _) => !flag(args, "dry_run"),
+        ("tag", Some("add" | "remove")) => true,
+        ("tasks", Some("sync")) => !action.is_some_and(|(_, a)| flag(a, "dry_run")),
+        ("workspace", Some("rename")) => true,
+        ("layout", Some("set")) => true,
+        ("placement", Some("set")) => !action.is_some_and(|(_, a)| flag(a, "dry_run")),
+        ("ci", Some("generate")) => true,
+        ("config", Some("lint")) => action.is_some_and(|(_, a)| flag(a, "fix")),
+        ("env", Some(action)) => action != "diff",
+        ("secrets", Some("push")) => true,
+        ("access", Some("protect")) => true,
+        ("ai", Some("gateway")) => true,
+        ("meta", Some("deploy")) => true,
+        // c3, turbo and nx run before there is a workspace to record them in
+        ("migrate", Some(action)) => !matches!(action, "c3" | "turbo" | "nx"),
+        ("builds", Some("setup")) => true,
+        ("r2", Some("sync")) => true,
+        ("seed", _) => true,
+        ("d1", Some("branch")) => true,
+        ("hooks", Some(_)) => true,
+        ("refactor", Some(_)) => !action.is_some_and(|(_, a)| flag(a, "dry_run")),
+        ("release" | "publish", _) => !flag(args, "dry_run"),
+        ("upgrade" | "types", _) => true,
+        _ => false,
+    };
+
+    command_name(matches).filter(|_| mutating)
+}
+
+/// Append an entry to the workspace's history. The log is best-effort: failing to write it
+/// never fails the command that was recorded.
+pub fn record(entry: &HistoryEntry) {
+    append(HISTORY_FILE, entry);
+}
+
+/// Append a command run to the usage log, best-effort like [`record`]. Only workspaces with a
+/// `.moon/workspace.yml` count, so a package.json directory that `migrate` hasn't converted yet
+/// is left clean.
+pub fn record_usage(entry: &UsageEntry) {
+    if !Path::new(".moon/workspace.yml").is_file() {
+        return;
+    }
+    append(USAGE_FILE, entry);
+}
+
+fn append(file: &str, entry: &impl Serialize) {
+    if !is_moonflare_workspace() {
+        return;
+    }
+    let path = Path::new(file);
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let Ok(line) = serde_json::to_string(entry) else {
+        return;
+    };
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+impl HistoryEntry {
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .ok()
+            .map(|time| time.with_timezone(&Utc))
+    }
+
+    pub fn new(command: String, args: &[String], result: &Result<(), String>, secs: f64) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339(),
+            user: triggered_by(),
+            command,
+            args: args.to_vec(),
+            success: result.is_ok(),
+            error: result.as_ref().err().cloned(),
+            duration_secs: secs,
+        }
+    }
+}
+
+impl UsageEntry {
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .ok()
+            .map(|time| time.with_timezone(&Utc))
+    }
+
+    pub fn new(command: String, args: &[String], success: bool, secs: f64) -> Self {
+        let mut projects: Vec<String> = discover_projects()
+            .into_iter()
+            .map(|project| project.name)
+            .filter(|name| args.iter().skip(1).any(|arg| arg == name))
+            .collect();
+        projects.sort();
+        Self {
+            timestamp: Utc::now().to_rfc3339(),
+            user: triggered_by(),
+            command,
+            projects,
+            success,
+            duration_secs: secs,
+        }
+    }
+}
+
+/// Every recorded entry, oldest first. Lines that don't parse (a crash mid-write) are skipped.
+pub fn read_history() -> Result<Vec<HistoryEntry>> {
+    read_log(HISTORY_FILE)
+}
+
+/// Every recorded command run, oldest first
+pub fn read_usage() -> Result<Vec<UsageEntry>> {
+    read_log(USAGE_FILE)
+}
+
+fn read_log<T: serde::de::DeserializeOwned>(file: &str) -> Result<Vec<T>> {
+    let path = Path::new(file);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", file))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Parse `--since`: a date (`2026-01-31`), an RFC 3339 timestamp, or an age like `7d` or `12h`
+pub fn parse_since(input: &str) -> Result<DateTime<Utc>, String> {
+    let input = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight exists")
+            .and_utc());
+    }
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(input) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    let age = match input.strip_suffix('d') {
+        Some(days) => days
+            .parse::<u64>()
+            .map(|days| std::time::Duration::from_secs(days * 86_400))
+            .map_err(|_| format!("invalid age '{}'", input))?,
+        None => parse_duration(input)
+            .map_err(|_| format!("'{}' isn't a date, timestamp or age like 7d", input))?,
+    };
+    chrono::Duration::from_std(age)
+        .map(|age| Utc::now() - age)
+        .map_err(|_| format!("'{}' is too far back", input))
+}
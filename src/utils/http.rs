@@ -0,0 +1,165 @@
+use crate::utils::config::{MoonflareConfig, NetworkConfig};
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Variables other tools read for extra root certificates, in the order they're used
+const CA_BUNDLE_VARS: [&str; 2] = ["SSL_CERT_FILE", "NODE_EXTRA_CA_CERTS"];
+
+/// Proxy variables, most specific first, as curl and reqwest read them
+const PROXY_VARS: [&str; 6] = [
+    "HTTPS_PROXY",
+    "https_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+];
+
+/// How long `doctor --network` waits for each endpoint
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A service moonflare or the tools it runs talk to
+pub struct Endpoint {
+    pub name: &'static str,
+    pub url: String,
+}
+
+/// Client builder with moonflare's user agent, proxy and extra root certificates. Every HTTP
+/// request moonflare makes starts here.
+pub fn builder() -> Result<reqwest::ClientBuilder> {
+    let network = MoonflareConfig::load().unwrap_or_default().network;
+    let mut builder =
+        reqwest::Client::builder().user_agent(concat!("moonflare/", env!("CARGO_PKG_VERSION")));
+
+    // Without a configured proxy reqwest reads the proxy variables itself
+    if let Some(url) = &network.proxy {
+        let no_proxy = network
+            .no_proxy
+            .clone()
+            .or_else(|| std::env::var("NO_PROXY").ok())
+            .or_else(|| std::env::var("no_proxy").ok())
+            .unwrap_or_default();
+        let proxy = reqwest::Proxy::all(url)
+            .with_context(|| format!("Invalid proxy '{}' under [network]", url))?
+            .no_proxy(reqwest::NoProxy::from_string(&no_proxy));
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(path) = ca_bundle(&network) {
+        let pem = std::fs::read(&path)
+            .with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+        let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("Invalid CA bundle {}", path.display()))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    Ok(builder)
+}
+
+pub fn client() -> Result<reqwest::Client> {
+    Ok(builder()?.build()?)
+}
+
+/// The extra root certificates in use: `[network] ca_bundle`, else `SSL_CERT_FILE` or
+/// `NODE_EXTRA_CA_CERTS`
+pub fn ca_bundle(network: &NetworkConfig) -> Option<PathBuf> {
+    network.ca_bundle.clone().or_else(|| {
+        CA_BUNDLE_VARS
+            .iter()
+            .filter_map(std::env::var_os)
+            .find(|value| !value.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// The proxy requests go through, if any: `[network] proxy`, else the proxy variables
+pub fn proxy(network: &NetworkConfig) -> Option<String> {
+    network.proxy.clone().or_else(|| {
+        PROXY_VARS
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+    })
+}
+
+/// Variables that pass the `[network]` settings on to moon, pnpm and wrangler, which read
+/// the standard proxy and Node.js certificate variables
+pub fn child_env(network: &NetworkConfig) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    if let Some(proxy) = &network.proxy {
+        for name in ["HTTPS_PROXY", "HTTP_PROXY"] {
+            vars.push((name.to_string(), proxy.clone()));
+        }
+    }
+    if let Some(no_proxy) = &network.no_proxy {
+        vars.push(("NO_PROXY".to_string(), no_proxy.clone()));
+    }
+    if let Some(path) = &network.ca_bundle {
+        vars.push((
+            "NODE_EXTRA_CA_CERTS".to_string(),
+            path.display().to_string(),
+        ));
+    }
+    vars
+}
+
+/// Endpoints `build`, `deploy`, `self update` and Moon's toolchain need to reach
+pub fn required_endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint {
+            name: "Cloudflare API",
+            url: std::env::var("CLOUDFLARE_API_BASE_URL")
+                .unwrap_or_else(|_| crate::utils::cloudflare::API_BASE.to_string()),
+        },
+        Endpoint {
+            name: "npm registry",
+            url: std::env::var("npm_config_registry")
+                .unwrap_or_else(|_| "https://registry.npmjs.org/".to_string()),
+        },
+        Endpoint {
+            name: "Moon toolchain",
+            url: "https://moonrepo.dev".to_string(),
+        },
+        Endpoint {
+            name: "GitHub releases",
+            url: "https://api.github.com".to_string(),
+        },
+    ]
+}
+
+/// Whether `url` answers at all. Any HTTP response counts, since endpoints like the
+/// Cloudflare API return 4xx to unauthenticated requests.
+pub async fn probe(client: &reqwest::Client, url: &str) -> Result<reqwest::StatusCode> {
+    let response = client
+        .get(url)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!(describe_error(&e)))?;
+    Ok(response.status())
+}
+
+/// The innermost cause of a request failure, which names the refused connection or the
+/// untrusted certificate, with a hint for certificate errors
+fn describe_error(error: &reqwest::Error) -> String {
+    let mut cause: &dyn std::error::Error = error;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    let message = if error.is_timeout() {
+        "timed out".to_string()
+    } else {
+        cause.to_string()
+    };
+    if message.to_lowercase().contains("certificate") {
+        format!(
+            "{}; if a proxy inspects TLS, point [network] ca_bundle at its CA",
+            message
+        )
+    } else {
+        message
+    }
+}
@@ -0,0 +1,301 @@
+use crate::utils::events::{self, Event};
+use crate::utils::timeout;
+use colored::*;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Exit code after Ctrl-C, as shells report for SIGINT
+pub const EXIT_CANCELLED: i32 = 130;
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Set once the handler has taken over to stop everything and exit
+static STOPPING: AtomicBool = AtomicBool::new(false);
+
+/// Live [`Graceful`] guards; while there are any, the first Ctrl-C is left to the command
+static GRACEFUL: AtomicUsize = AtomicUsize::new(0);
+
+/// Subprocesses started through [`timeout::spawn`] that haven't exited yet
+static RUNNING: Mutex<Vec<Running>> = Mutex::new(Vec::new());
+
+/// Temporary files and locks to remove when the command is cancelled
+static TEMP_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Targets that finished before a cancellation, with whether they succeeded
+static FINISHED: Mutex<Vec<(String, bool)>> = Mutex::new(Vec::new());
+
+struct Running {
+    pid: u32,
+    command: String,
+    /// Whether the child leads its own process group, so signals reach its whole tree
+    own_group: bool,
+}
+
+/// Whether Ctrl-C (or SIGTERM) has cancelled this command
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Whether the Ctrl-C handler is stopping the command and will exit on its own
+pub fn is_stopping() -> bool {
+    STOPPING.load(Ordering::SeqCst)
+}
+
+/// Resolves once Ctrl-C (or SIGTERM) has cancelled this command
+pub async fn cancelled() {
+    while !is_cancelled() {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Held by a command that stops on its own at Ctrl-C, such as a watch loop or a server
+pub struct Graceful(());
+
+impl Drop for Graceful {
+    fn drop(&mut self) {
+        GRACEFUL.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Leave the first Ctrl-C to the running command while the guard lives: it only marks the
+/// command cancelled for [`cancelled`] to pick up. A second Ctrl-C still stops everything.
+pub fn handle_gracefully() -> Graceful {
+    GRACEFUL.fetch_add(1, Ordering::SeqCst);
+    Graceful(())
+}
+
+/// Handle Ctrl-C and SIGTERM for the rest of this invocation: stop the subprocesses and
+/// their children, flush the event stream, remove temporary state, print what finished and
+/// exit with [`EXIT_CANCELLED`]. A second Ctrl-C skips the grace period.
+pub fn install(command: &str) {
+    let command = command.to_string();
+    let started = Instant::now();
+    tokio::spawn(async move {
+        if next_signal().await.is_none() {
+            return;
+        }
+        CANCELLED.store(true, Ordering::SeqCst);
+        if GRACEFUL.load(Ordering::SeqCst) > 0 && next_signal().await.is_none() {
+            return;
+        }
+        STOPPING.store(true, Ordering::SeqCst);
+        eprintln!();
+        eprintln!(
+            "{}",
+            "Cancelling: stopping running processes (Ctrl-C again to force)...".yellow()
+        );
+
+        let stopped = running_commands();
+        terminate_all();
+        tokio::select! {
+            _ = wait_for_children(timeout::grace()) => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
+        kill_all();
+
+        cancel(&command, started.elapsed(), &stopped);
+    });
+}
+
+#[cfg(unix)]
+async fn next_signal() -> Option<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut interrupt = signal(SignalKind::interrupt()).ok()?;
+    let mut terminate = signal(SignalKind::terminate()).ok()?;
+    tokio::select! {
+        _ = interrupt.recv() => Some(()),
+        _ = terminate.recv() => Some(()),
+    }
+}
+
+#[cfg(not(unix))]
+async fn next_signal() -> Option<()> {
+    tokio::signal::ctrl_c().await.ok()
+}
+
+/// Flush what happened, remove temporary state and exit
+fn cancel(command: &str, elapsed: Duration, stopped: &[String]) -> ! {
+    let finished = FINISHED.lock().map(|f| f.clone()).unwrap_or_default();
+    let finished_targets: Vec<&str> = finished.iter().map(|(t, _)| t.as_str()).collect();
+    events::emit(Event::Cancelled {
+        command,
+        finished: &finished_targets,
+        stopped,
+        duration_secs: events::secs(elapsed),
+    });
+    events::emit(Event::CommandFinished {
+        command,
+        success: false,
+        duration_secs: events::secs(elapsed),
+    });
+    events::flush();
+
+    let removed = remove_temp_paths();
+
+    eprintln!(
+        "{}",
+        format!(
+            "Cancelled 'moonflare {}' after {:.1}s",
+            command,
+            elapsed.as_secs_f64()
+        )
+        .yellow()
+        .bold()
+    );
+    for (target, success) in &finished {
+        let mark = if *success { "✓".green() } else { "✗".red() };
+        eprintln!("  {} {}", mark, target);
+    }
+    for command in stopped {
+        eprintln!("  {} {} (stopped)", "■".yellow(), command);
+    }
+    if finished.is_empty() && stopped.is_empty() {
+        eprintln!("  Nothing was running");
+    }
+    if removed > 0 {
+        eprintln!("  Removed {} temporary file(s)", removed);
+    }
+
+    std::process::exit(EXIT_CANCELLED);
+}
+
+/// Note a finished target for the cancellation summary
+pub fn note_task(target: &str, success: bool) {
+    if let Ok(mut finished) = FINISHED.lock() {
+        finished.push((target.to_string(), success));
+    }
+}
+
+/// Remove `path` if the command is cancelled before [`release_temp`] is called
+pub fn register_temp(path: &Path) {
+    if let Ok(mut paths) = TEMP_PATHS.lock() {
+        paths.push(path.to_path_buf());
+    }
+}
+
+/// Forget a path registered with [`register_temp`] once its owner has cleaned it up
+pub fn release_temp(path: &Path) {
+    if let Ok(mut paths) = TEMP_PATHS.lock() {
+        paths.retain(|p| p != path);
+    }
+}
+
+fn remove_temp_paths() -> usize {
+    let paths = TEMP_PATHS
+        .lock()
+        .map(|mut paths| std::mem::take(&mut *paths))
+        .unwrap_or_default();
+    paths
+        .iter()
+        .filter(|path| std::fs::remove_file(path).is_ok())
+        .count()
+}
+
+/// Prepare `cmd` so a cancellation reaches everything it starts. Without a terminal (CI,
+/// scripts) the child gets a process group of its own, since moonflare may share its group
+/// with whatever launched it. In a terminal children stay in moonflare's group, where Ctrl-C
+/// already reaches them and they can still read from the terminal.
+pub fn isolate(cmd: &mut Command) -> bool {
+    #[cfg(unix)]
+    {
+        use std::io::IsTerminal;
+        use std::os::unix::process::CommandExt;
+
+        if !std::io::stdin().is_terminal() {
+            cmd.process_group(0);
+            return true;
+        }
+    }
+    let _ = cmd;
+    false
+}
+
+/// Record a started subprocess until [`untrack`] is called
+pub fn track(pid: u32, command: &str, own_group: bool) {
+    if let Ok(mut running) = RUNNING.lock() {
+        running.push(Running {
+            pid,
+            command: command.to_string(),
+            own_group,
+        });
+    }
+}
+
+pub fn untrack(pid: u32) {
+    if let Ok(mut running) = RUNNING.lock() {
+        running.retain(|r| r.pid != pid);
+    }
+}
+
+fn running_commands() -> Vec<String> {
+    RUNNING
+        .lock()
+        .map(|running| running.iter().map(|r| r.command.clone()).collect())
+        .unwrap_or_default()
+}
+
+async fn wait_for_children(grace: Duration) {
+    let deadline = Instant::now() + grace;
+    while Instant::now() < deadline && RUNNING.lock().is_ok_and(|r| !r.is_empty()) {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// SIGTERM every running subprocess along with its children
+fn terminate_all() {
+    #[cfg(unix)]
+    {
+        // As the leader of its own group (a shell job), moonflare reaches every descendant
+        // at once; the signal it sends itself is caught by the handler
+        // SAFETY: getpgrp, getpid and kill have no memory-safety preconditions
+        if unsafe { libc::getpgrp() == libc::getpid() } {
+            unsafe {
+                libc::kill(0, libc::SIGTERM);
+            }
+        }
+        let pids: Vec<u32> = RUNNING
+            .lock()
+            .map(|running| running.iter().map(|r| r.pid).collect())
+            .unwrap_or_default();
+        for pid in pids {
+            signal(pid, libc::SIGTERM);
+        }
+    }
+}
+
+/// SIGKILL whatever is still running after the grace period
+fn kill_all() {
+    let pids: Vec<u32> = RUNNING
+        .lock()
+        .map(|running| running.iter().map(|r| r.pid).collect())
+        .unwrap_or_default();
+    for pid in pids {
+        #[cfg(unix)]
+        signal(pid, libc::SIGKILL);
+        #[cfg(not(unix))]
+        let _ = Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &pid.to_string()])
+            .status();
+    }
+}
+
+/// Send `sig` to a tracked subprocess, or to its whole process group when it leads one
+#[cfg(unix)]
+pub fn signal(pid: u32, sig: libc::c_int) {
+    let own_group = RUNNING
+        .lock()
+        .is_ok_and(|running| running.iter().any(|r| r.pid == pid && r.own_group));
+    let target = if own_group {
+        -(pid as libc::pid_t)
+    } else {
+        pid as libc::pid_t
+    };
+    // SAFETY: kill(2) has no memory-safety preconditions; the pid is our own unreaped child
+    unsafe {
+        libc::kill(target, sig);
+    }
+}
@@ -0,0 +1,293 @@
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::task_env;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Keys per `wrangler kv bulk get` call, the API's limit for one bulk read
+const BULK_GET_KEYS: usize = 100;
+
+/// Where KV commands read and write: `wrangler dev`'s local state, or the remote namespace
+/// bound in `env` (production without one)
+#[derive(Debug, Clone, Default)]
+pub struct KvTarget {
+    pub env: Option<String>,
+    pub local: bool,
+}
+
+impl KvTarget {
+    pub fn describe(&self) -> String {
+        match (&self.env, self.local) {
+            (_, true) => "local".to_string(),
+            (Some(env), false) => env.clone(),
+            (None, false) => "production".to_string(),
+        }
+    }
+
+    fn args(&self) -> Vec<&str> {
+        let mut args = vec![if self.local { "--local" } else { "--remote" }];
+        if let Some(env) = &self.env {
+            args.extend(["--env", env]);
+        }
+        args
+    }
+}
+
+/// What `put` stores under a key
+#[derive(Debug, Clone, Copy)]
+pub enum KvValue<'a> {
+    Text(&'a str),
+    /// The contents of a file, e.g. an image
+    File(&'a Path),
+}
+
+/// A key as `wrangler kv key list` prints it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KvKey {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// One line of an export or import file, with the fields of Wrangler's bulk put format so
+/// lines can be written back as they are
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KvEntry {
+    pub key: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_ttl: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base64: Option<bool>,
+}
+
+/// The project's KV binding named `binding`, from `env`'s own `kv_namespaces` or the
+/// top-level ones for production. Bindings aren't inherited, so an environment's list replaces
+/// the top-level one.
+pub fn resolve_binding(
+    config: &Value,
+    project: &str,
+    env: Option<&str>,
+    binding: &str,
+) -> Result<String> {
+    let list = env
+        .and_then(|env| config["env"][env]["kv_namespaces"].as_array())
+        .or_else(|| config["kv_namespaces"].as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let names: Vec<&str> = list
+        .iter()
+        .filter_map(|entry| entry["binding"].as_str())
+        .collect();
+    if names.contains(&binding) {
+        return Ok(binding.to_string());
+    }
+    let scope = env.map_or(String::new(), |env| format!(" in '{}'", env));
+    if names.is_empty() {
+        bail!("'{}' has no KV namespaces bound{}", project, scope);
+    }
+    bail!(
+        "'{}' has no KV binding named {}{}; its KV bindings are {}",
+        project,
+        binding,
+        scope,
+        names.join(", ")
+    )
+}
+
+fn wrangler(
+    project: &WorkspaceProject,
+    args: &[&str],
+    binding: &str,
+    target: &KvTarget,
+) -> Command {
+    let mut command = Command::new("pnpm");
+    command
+        .args(["exec", "wrangler", "kv"])
+        .args(args)
+        .args(["--binding", binding])
+        .args(target.args())
+        .current_dir(&project.path)
+        .envs(task_env::vars(Some(&project.name)));
+    command
+}
+
+fn run(
+    project: &WorkspaceProject,
+    args: &[&str],
+    binding: &str,
+    target: &KvTarget,
+) -> Result<Output> {
+    let name = args[..2].join(" ");
+    let output = wrangler(project, args, binding, target)
+        .output()
+        .with_context(|| format!("Failed to run wrangler kv {}", name))?;
+    if !output.status.success() {
+        bail!(
+            "wrangler kv {} failed: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output)
+}
+
+/// The JSON Wrangler printed, skipping any banner before it
+fn parse_output<T: serde::de::DeserializeOwned>(output: &Output, command: &str) -> Result<T> {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let start = stdout.find(['[', '{']).unwrap_or_default();
+    serde_json::from_str(&stdout[start..])
+        .with_context(|| format!("wrangler kv {} printed invalid JSON", command))
+}
+
+/// Keys of the namespace, optionally only those starting with `prefix`
+pub fn list_keys(
+    project: &WorkspaceProject,
+    binding: &str,
+    prefix: Option<&str>,
+    target: &KvTarget,
+) -> Result<Vec<KvKey>> {
+    let mut args = vec!["key", "list"];
+    if let Some(prefix) = prefix {
+        args.extend(["--prefix", prefix]);
+    }
+    let output = run(project, &args, binding, target)?;
+    parse_output(&output, "key list")
+}
+
+/// The value stored under `key`, as the bytes it was written with
+pub fn get(
+    project: &WorkspaceProject,
+    binding: &str,
+    key: &str,
+    target: &KvTarget,
+) -> Result<Vec<u8>> {
+    Ok(run(project, &["key", "get", key], binding, target)?.stdout)
+}
+
+/// Store `value` under `key`, expiring after `ttl` seconds if set
+pub fn put(
+    project: &WorkspaceProject,
+    binding: &str,
+    key: &str,
+    value: KvValue,
+    ttl: Option<u64>,
+    target: &KvTarget,
+) -> Result<()> {
+    let path;
+    let ttl = ttl.map(|ttl| ttl.to_string());
+    let mut args = vec!["key", "put", key];
+    match value {
+        KvValue::Text(value) => args.push(value),
+        KvValue::File(file) => {
+            path = file.display().to_string();
+            args.extend(["--path", &path]);
+        }
+    }
+    if let Some(ttl) = &ttl {
+        args.extend(["--ttl", ttl]);
+    }
+    run(project, &args, binding, target)?;
+    Ok(())
+}
+
+/// Every key and value of the namespace, or those whose key starts with `prefix`
+pub fn export(
+    project: &WorkspaceProject,
+    binding: &str,
+    prefix: Option<&str>,
+    target: &KvTarget,
+) -> Result<Vec<KvEntry>> {
+    let keys = list_keys(project, binding, prefix, target)?;
+    let mut entries = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(BULK_GET_KEYS) {
+        let names: Vec<&str> = chunk.iter().map(|key| key.name.as_str()).collect();
+        let file = temp_json(&names)?;
+        let path = file.path().display().to_string();
+        let output = run(project, &["bulk", "get", &path], binding, target)?;
+        let mut values: Map<String, Value> = parse_output(&output, "bulk get")?;
+        for key in chunk {
+            // A key that expired between listing and reading has no value anymore
+            let Some(value) = values.remove(&key.name).filter(|value| !value.is_null()) else {
+                continue;
+            };
+            entries.push(KvEntry {
+                key: key.name.clone(),
+                value: match value {
+                    Value::String(value) => value,
+                    other => other.to_string(),
+                },
+                expiration: key.expiration,
+                expiration_ttl: None,
+                metadata: key.metadata.clone(),
+                base64: None,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// Write `entries` to the namespace with one `wrangler kv bulk put`, which splits them into
+/// requests the API accepts
+pub fn import(
+    project: &WorkspaceProject,
+    binding: &str,
+    entries: &[KvEntry],
+    target: &KvTarget,
+) -> Result<()> {
+    let file = temp_json(entries)?;
+    let path = file.path().display().to_string();
+    run(project, &["bulk", "put", &path], binding, target)?;
+    Ok(())
+}
+
+/// Entries of a JSON Lines file, one object per line; blank lines are skipped
+pub fn read_jsonl(path: &Path) -> Result<Vec<KvEntry>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).with_context(|| {
+                format!(
+                    "{}:{} isn't a KV entry; each line needs a JSON object with string \"key\" and \"value\" fields",
+                    path.display(),
+                    index + 1
+                )
+            })
+        })
+        .collect()
+}
+
+/// Entries as JSON Lines, in the format `read_jsonl` reads
+pub fn to_jsonl(entries: &[KvEntry]) -> Result<String> {
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(&serde_json::to_string(entry)?);
+        content.push('\n');
+    }
+    Ok(content)
+}
+
+/// A temporary JSON file for Wrangler's bulk commands, removed when dropped
+fn temp_json<T: Serialize + ?Sized>(value: &T) -> Result<tempfile::NamedTempFile> {
+    let mut file = tempfile::Builder::new()
+        .prefix("moonflare-kv-")
+        .suffix(".json")
+        .tempfile()?;
+    file.write_all(serde_json::to_string(value)?.as_bytes())?;
+    file.flush()?;
+    Ok(file)
+}
@@ -0,0 +1,306 @@
+use crate::utils::platform;
+use crate::utils::projects::{WorkspaceProject, discover_projects, glob_match};
+use crate::utils::sbom::{Ecosystem, collect_components};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// License recorded for packages whose metadata couldn't be found or has no license field
+pub const UNKNOWN_LICENSE: &str = "UNKNOWN";
+/// Notice written into each frontend's build output
+pub const NOTICE_FILE: &str = "third-party-licenses.txt";
+/// Module a worker imports to serve its notice at `/third-party-licenses.txt`
+pub const WORKER_NOTICE_MODULE: &str = "src/third-party-licenses.ts";
+
+/// A dependency and the license it is distributed under
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageLicense {
+    pub ecosystem: String,
+    pub name: String,
+    pub version: String,
+    pub license: String,
+    /// Workspace projects that depend on the package
+    pub projects: BTreeSet<String>,
+    pub disallowed: bool,
+}
+
+/// License information for every dependency of the given projects, one entry per package version
+pub fn collect_licenses(
+    projects: &[WorkspaceProject],
+    include_dev: bool,
+    disallowed: &[String],
+) -> Result<Vec<PackageLicense>> {
+    let npm_licenses = npm_license_index(projects);
+    let mut packages: BTreeMap<(String, String, String), PackageLicense> = BTreeMap::new();
+
+    for project in projects {
+        for component in collect_components(project)? {
+            if component.dev && !include_dev {
+                continue;
+            }
+
+            let (ecosystem, license) = match component.ecosystem {
+                Ecosystem::Npm => (
+                    "npm",
+                    npm_licenses
+                        .get(&(component.name.clone(), component.version.clone()))
+                        .cloned(),
+                ),
+                Ecosystem::Cargo => ("cargo", crate_license(&component.name, &component.version)),
+            };
+            let license = license.unwrap_or_else(|| UNKNOWN_LICENSE.to_string());
+
+            packages
+                .entry((
+                    ecosystem.to_string(),
+                    component.name.clone(),
+                    component.version.clone(),
+                ))
+                .or_insert_with(|| PackageLicense {
+                    ecosystem: ecosystem.to_string(),
+                    name: component.name.clone(),
+                    version: component.version.clone(),
+                    disallowed: is_disallowed(&license, disallowed),
+                    license,
+                    projects: BTreeSet::new(),
+                })
+                .projects
+                .insert(project.name.clone());
+        }
+    }
+
+    Ok(packages.into_values().collect())
+}
+
+/// Packages grouped by license, most common license first
+pub fn group_by_license(packages: &[PackageLicense]) -> Vec<(String, Vec<&PackageLicense>)> {
+    let mut groups: BTreeMap<String, Vec<&PackageLicense>> = BTreeMap::new();
+    for package in packages {
+        groups
+            .entry(package.license.clone())
+            .or_default()
+            .push(package);
+    }
+
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+    groups
+}
+
+/// An SPDX expression is disallowed when every `OR` alternative contains a disallowed license.
+/// Patterns may use globs, e.g. `GPL-*`.
+pub fn is_disallowed(expression: &str, disallowed: &[String]) -> bool {
+    if disallowed.is_empty() {
+        return false;
+    }
+
+    let expression = expression.replace(['(', ')'], " ");
+    expression
+        .split(" OR ")
+        .flat_map(|part| part.split('/'))
+        .all(|alternative| {
+            alternative
+                .split(" AND ")
+                .map(str::trim)
+                .filter(|license| !license.is_empty())
+                .any(|license| disallowed.iter().any(|p| glob_match(p, license)))
+        })
+}
+
+/// Plain-text notice listing each package under its license. It carries no timestamp, so
+/// rebuilding with the same dependencies produces the same file.
+pub fn render_notice(project: &str, packages: &[PackageLicense]) -> String {
+    let mut notice = format!(
+        "Third-party licenses for {}\nGenerated by moonflare from the installed dependencies.\n",
+        project
+    );
+    for (license, packages) in group_by_license(packages) {
+        notice.push_str(&format!("\n{} ({})\n", license, packages.len()));
+        for package in packages {
+            notice.push_str(&format!(
+                "  {}@{} ({})\n",
+                package.name, package.version, package.ecosystem
+            ));
+        }
+    }
+    notice
+}
+
+/// Write the license notice of every project in `projects`: a module in each worker's src/,
+/// which must exist before the worker is bundled, or `third-party-licenses.txt` in each
+/// frontend's dist/, which only exists once the build has run. Crates are bundled into the
+/// projects that load their WASM, so their dependencies are listed in every notice.
+pub fn write_notices(projects: &[WorkspaceProject], workers: bool) -> Result<Vec<PathBuf>> {
+    let crates: Vec<WorkspaceProject> = discover_projects()
+        .into_iter()
+        .filter(|p| p.is_crate())
+        .collect();
+
+    let mut written = Vec::new();
+    for project in projects.iter().filter(|p| !p.is_crate()) {
+        if project.is_worker() != workers || (!workers && !project.path.join("dist").is_dir()) {
+            continue;
+        }
+
+        let mut sources = vec![project.clone()];
+        sources.extend(crates.iter().cloned());
+        let notice = render_notice(&project.name, &collect_licenses(&sources, false, &[])?);
+
+        let (path, content) = if workers {
+            (
+                project.path.join(WORKER_NOTICE_MODULE),
+                format!(
+                    "// Generated by moonflare build when [licenses] bundle is enabled; do not edit.\nexport default {};\n",
+                    serde_json::to_string(&notice)?
+                ),
+            )
+        } else {
+            (project.path.join("dist").join(NOTICE_FILE), notice)
+        };
+        if fs::read_to_string(&path).ok().as_deref() != Some(content.as_str()) {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, content)?;
+        }
+        written.push(path);
+    }
+    Ok(written)
+}
+
+pub fn render_csv(packages: &[PackageLicense]) -> String {
+    let mut csv = String::from("ecosystem,name,version,license,disallowed,projects\n");
+    for package in packages {
+        let fields = [
+            package.ecosystem.clone(),
+            package.name.clone(),
+            package.version.clone(),
+            package.license.clone(),
+            package.disallowed.to_string(),
+            package
+                .projects
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(";"),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Licenses of installed npm packages keyed by (name, version). Reads pnpm's virtual store
+/// (`node_modules/.pnpm`) plus each project's own node_modules for other package managers.
+fn npm_license_index(projects: &[WorkspaceProject]) -> BTreeMap<(String, String), String> {
+    let mut manifests: Vec<PathBuf> = Vec::new();
+
+    if let Ok(entries) = fs::read_dir("node_modules/.pnpm") {
+        for entry in entries.flatten() {
+            manifests.extend(package_manifests(&entry.path().join("node_modules")));
+        }
+    }
+    for project in projects {
+        manifests.extend(package_manifests(&project.path.join("node_modules")));
+    }
+
+    let mut index = BTreeMap::new();
+    for manifest in manifests {
+        let Some(package) = fs::read_to_string(&manifest)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        else {
+            continue;
+        };
+        let (Some(name), Some(version)) = (package["name"].as_str(), package["version"].as_str())
+        else {
+            continue;
+        };
+        if let Some(license) = npm_license(&package) {
+            index.insert((name.to_string(), version.to_string()), license);
+        }
+    }
+    index
+}
+
+/// `package.json` files directly inside a node_modules directory, including scoped packages
+fn package_manifests(node_modules: &Path) -> Vec<PathBuf> {
+    let mut manifests = Vec::new();
+    let Ok(entries) = fs::read_dir(node_modules) else {
+        return manifests;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('@') {
+            if let Ok(scoped) = fs::read_dir(&path) {
+                manifests.extend(scoped.flatten().map(|e| e.path().join("package.json")));
+            }
+        } else if !name.starts_with('.') {
+            manifests.push(path.join("package.json"));
+        }
+    }
+    manifests.retain(|m| m.exists());
+    manifests
+}
+
+/// `license` field, or the legacy `licenses: [{ type }]` array
+fn npm_license(package: &serde_json::Value) -> Option<String> {
+    match &package["license"] {
+        serde_json::Value::String(license) => Some(license.clone()),
+        serde_json::Value::Object(license) => license
+            .get("type")
+            .and_then(|t| t.as_str())
+            .map(String::from),
+        _ => {
+            let types: Vec<&str> = package["licenses"]
+                .as_array()?
+                .iter()
+                .filter_map(|l| l["type"].as_str())
+                .collect();
+            (!types.is_empty()).then(|| types.join(" OR "))
+        }
+    }
+}
+
+/// License of a crate from its unpacked source in the cargo registry
+fn crate_license(name: &str, version: &str) -> Option<String> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| platform::home_dir().map(|home| home.join(".cargo")))?;
+
+    let registries = fs::read_dir(cargo_home.join("registry").join("src")).ok()?;
+    for registry in registries.flatten() {
+        let manifest = registry
+            .path()
+            .join(format!("{}-{}", name, version))
+            .join("Cargo.toml");
+        let Some(manifest) = fs::read_to_string(&manifest)
+            .ok()
+            .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+        else {
+            continue;
+        };
+
+        let package = manifest.get("package")?;
+        if let Some(license) = package.get("license").and_then(|l| l.as_str()) {
+            return Some(license.to_string());
+        }
+        if let Some(file) = package.get("license-file").and_then(|l| l.as_str()) {
+            return Some(format!("SEE LICENSE IN {}", file));
+        }
+    }
+    None
+}
@@ -0,0 +1,90 @@
+use crate::utils::interrupt;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const LOCK_FILE: &str = ".moonflare/workspace.lock";
+
+/// Who holds the workspace lock
+#[derive(Debug, Serialize, Deserialize)]
+struct Holder {
+    pid: u32,
+    command: String,
+    started: String,
+}
+
+/// Exclusive hold on the workspace for commands that must not overlap, such as two deploys
+/// writing the deployment manifest. Released when dropped, or by the Ctrl-C handler.
+#[derive(Debug)]
+pub struct WorkspaceLock {
+    path: PathBuf,
+}
+
+impl Drop for WorkspaceLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+        interrupt::release_temp(&self.path);
+    }
+}
+
+/// Take the workspace lock for `command`. A lock left by a process that no longer exists is
+/// taken over.
+pub fn acquire(command: &str) -> Result<WorkspaceLock> {
+    let path = PathBuf::from(LOCK_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let holder = Holder {
+        pid: std::process::id(),
+        command: command.to_string(),
+        started: chrono::Utc::now().to_rfc3339(),
+    };
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(serde_json::to_string(&holder)?.as_bytes())?;
+                interrupt::register_temp(&path);
+                return Ok(WorkspaceLock { path });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                let existing = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|json| serde_json::from_str::<Holder>(&json).ok());
+                if let Some(existing) = existing
+                    && is_running(existing.pid)
+                {
+                    bail!(
+                        "Another 'moonflare {}' (pid {}) has held the workspace since {}. Wait for it to finish, or remove {} if it's gone.",
+                        existing.command,
+                        existing.pid,
+                        existing.started,
+                        LOCK_FILE
+                    );
+                }
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove stale {}", LOCK_FILE))?;
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to create {}", LOCK_FILE));
+            }
+        }
+    }
+    bail!("Failed to take the workspace lock at {}", LOCK_FILE)
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    // SAFETY: kill(2) with signal 0 only checks whether the process exists
+    unsafe {
+        libc::kill(pid as libc::pid_t, 0) == 0
+            || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+}
+
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+    true
+}
@@ -0,0 +1,112 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub const DEPLOYMENT_MANIFEST: &str = ".moonflare/deployments.json";
+
+/// Environment key used for deploys without `--env`
+pub const DEFAULT_ENVIRONMENT: &str = "default";
+
+/// The most recent deployment of each project, per environment
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DeploymentManifest {
+    #[serde(default)]
+    pub environments: BTreeMap<String, BTreeMap<String, DeploymentRecord>>,
+    /// Cloudflare Access applications created by `moonflare access protect`, keyed by project
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub access: BTreeMap<String, AccessRecord>,
+    /// Workers Builds triggers created by `moonflare builds setup`, per environment and project
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub builds: BTreeMap<String, BTreeMap<String, BuildTriggerRecord>>,
+}
+
+/// A Workers Builds trigger that builds and deploys a project from its repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildTriggerRecord {
+    pub account_id: String,
+    pub trigger_uuid: String,
+    /// Worker the trigger deploys
+    pub worker: String,
+    /// Connected repository, as `owner/name`
+    pub repository: String,
+    /// Branch pushes to which build automatically
+    pub branch: String,
+    pub created_at: String,
+}
+
+/// A Cloudflare Access application protecting a project's domain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessRecord {
+    pub account_id: String,
+    pub app_id: String,
+    pub domain: String,
+    /// Email domain the allow policy admits
+    pub policy: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentRecord {
+    pub deployed_at: String,
+    pub git_sha: Option<String>,
+    /// The project had uncommitted changes, so `git_sha` doesn't fully describe what shipped
+    pub dirty: bool,
+    /// SHA-256 of the CycloneDX SBOM generated by `deploy --sbom`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sbom_sha256: Option<String>,
+    /// Environment this deployment was promoted from by `moonflare promote`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promoted_from: Option<String>,
+    /// Worker version (or Pages deployment) Wrangler reported for this deploy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    /// URLs Wrangler reported the deploy is served on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub urls: Vec<String>,
+}
+
+impl DeploymentManifest {
+    pub fn load() -> Result<Self> {
+        Self::load_from(Path::new(DEPLOYMENT_MANIFEST))
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Path::new(DEPLOYMENT_MANIFEST);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", DEPLOYMENT_MANIFEST))
+    }
+
+    /// The Workers Builds trigger for a project in an environment
+    #[cfg(feature = "api")]
+    pub fn build_trigger(
+        &self,
+        environment: Option<&str>,
+        project: &str,
+    ) -> Option<&BuildTriggerRecord> {
+        self.builds
+            .get(environment.unwrap_or(DEFAULT_ENVIRONMENT))?
+            .get(project)
+    }
+
+    pub fn record(&mut self, environment: Option<&str>, project: &str, record: DeploymentRecord) {
+        self.environments
+            .entry(environment.unwrap_or(DEFAULT_ENVIRONMENT).to_string())
+            .or_default()
+            .insert(project.to_string(), record);
+    }
+}
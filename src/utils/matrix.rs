@@ -0,0 +1,201 @@
+use crate::utils::config::MatrixConfig;
+use crate::utils::toolchain::Toolchain;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+use walkdir::WalkDir;
+
+/// Where each combination's output is kept, one log per combination
+pub const LOG_DIR: &str = ".moonflare/matrix";
+
+/// Directories left out of each combination's copy of the workspace: installed dependencies,
+/// build output and local state, which would otherwise hide version differences
+const SKIPPED_DIRECTORIES: [&str; 8] = [
+    "node_modules",
+    "target",
+    "dist",
+    ".wrangler",
+    ".astro",
+    ".git",
+    ".moonflare",
+    "pkg",
+];
+
+/// One combination of tool versions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Cell {
+    pub node: String,
+    pub wrangler: String,
+    pub moon: String,
+}
+
+impl Cell {
+    /// File-name-safe name of the combination, e.g. `node-22_wrangler-4_moon-1.30.0`
+    pub fn label(&self) -> String {
+        format!(
+            "node-{}_wrangler-{}_moon-{}",
+            self.node, self.wrangler, self.moon
+        )
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+    }
+}
+
+/// How one combination went
+#[derive(Debug, Serialize)]
+pub struct CellResult {
+    #[serde(flatten)]
+    pub cell: Cell,
+    pub passed: bool,
+    /// The step that failed: installing a tool or dependencies, or the task
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_step: Option<String>,
+    pub duration_secs: f64,
+    pub log: PathBuf,
+    /// The combination's copy of the workspace, when kept with `--keep`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<PathBuf>,
+}
+
+/// Every combination of the configured versions, in order. A tool without versions stays
+/// on the one the workspace pins.
+pub fn cells(config: &MatrixConfig, toolchain: &Toolchain) -> Vec<Cell> {
+    let axis = |versions: &[String], pinned: &str| match versions {
+        [] => vec![pinned.to_string()],
+        versions => versions.to_vec(),
+    };
+    let mut cells = Vec::new();
+    for node in axis(&config.node, &toolchain.node) {
+        for wrangler in axis(&config.wrangler, &toolchain.wrangler) {
+            for moon in axis(&config.moon, &toolchain.moon) {
+                cells.push(Cell {
+                    node: node.clone(),
+                    wrangler: wrangler.clone(),
+                    moon: moon.clone(),
+                });
+            }
+        }
+    }
+    cells
+}
+
+/// Build a fresh copy of the workspace at `root` with the combination's tools: Node.js and
+/// Moon installed with proto and selected through `PROTO_<TOOL>_VERSION`, Wrangler forced on
+/// every project with a pnpm override, then `moon run :<task>`. Stops at the first step that
+/// fails; every step's output goes to the combination's log under [`LOG_DIR`].
+pub fn run_cell(root: &Path, cell: &Cell, task: &str, keep: bool) -> Result<CellResult> {
+    let started = Instant::now();
+    let copy = tempfile::Builder::new()
+        .prefix("moonflare-matrix-")
+        .tempdir()
+        .context("Failed to create a directory for the matrix workspace")?;
+    copy_workspace(root, copy.path())?;
+    override_wrangler(copy.path(), &cell.wrangler)?;
+
+    let log_path = root.join(LOG_DIR).join(format!("{}.log", cell.label()));
+    fs::create_dir_all(root.join(LOG_DIR))?;
+    let mut log = fs::File::create(&log_path)
+        .with_context(|| format!("Failed to create {}", log_path.display()))?;
+
+    let target = format!(":{}", task);
+    let steps: [(&str, &str, Vec<&str>); 4] = [
+        ("install node", "proto", vec!["install", "node", &cell.node]),
+        ("install moon", "proto", vec!["install", "moon", &cell.moon]),
+        (
+            "install dependencies",
+            "pnpm",
+            vec!["install", "--no-frozen-lockfile"],
+        ),
+        (task, "moon", vec!["run", &target]),
+    ];
+
+    let mut failed_step = None;
+    for (step, program, args) in steps {
+        writeln!(log, "$ {} {}", program, args.join(" "))?;
+        let output = Command::new(program)
+            .args(&args)
+            .current_dir(copy.path())
+            .env("PROTO_NODE_VERSION", &cell.node)
+            .env("PROTO_MOON_VERSION", &cell.moon)
+            .output();
+        match output {
+            Ok(output) => {
+                log.write_all(&output.stdout)?;
+                log.write_all(&output.stderr)?;
+                if !output.status.success() {
+                    writeln!(log, "{} failed with {}", step, output.status)?;
+                    failed_step = Some(step.to_string());
+                    break;
+                }
+            }
+            Err(e) => {
+                writeln!(log, "Couldn't run {}: {}", program, e)?;
+                failed_step = Some(step.to_string());
+                break;
+            }
+        }
+    }
+
+    Ok(CellResult {
+        cell: cell.clone(),
+        passed: failed_step.is_none(),
+        failed_step,
+        duration_secs: started.elapsed().as_secs_f64(),
+        log: log_path,
+        workspace: keep.then(|| copy.keep()),
+    })
+}
+
+/// Copy the workspace's sources, leaving out [`SKIPPED_DIRECTORIES`] and Moon's cache
+fn copy_workspace(root: &Path, target: &Path) -> Result<()> {
+    let walker = WalkDir::new(root).into_iter().filter_entry(|entry| {
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        !(entry.file_type().is_dir()
+            && (SKIPPED_DIRECTORIES
+                .iter()
+                .any(|skipped| entry.file_name() == *skipped)
+                || relative == Path::new(".moon/cache")))
+    });
+    for entry in walker {
+        let entry = entry?;
+        let destination = target.join(entry.path().strip_prefix(root)?);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&destination)?;
+        } else {
+            fs::copy(entry.path(), &destination)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Pin every project's wrangler to `version` through `pnpm.overrides` in the root package.json
+fn override_wrangler(root: &Path, version: &str) -> Result<()> {
+    let path = root.join("package.json");
+    let mut package: Value = match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?,
+        Err(_) => json!({ "private": true }),
+    };
+    if !package["pnpm"].is_object() {
+        package["pnpm"] = json!({});
+    }
+    if !package["pnpm"]["overrides"].is_object() {
+        package["pnpm"]["overrides"] = json!({});
+    }
+    package["pnpm"]["overrides"]["wrangler"] = json!(version);
+    fs::write(&path, serde_json::to_string_pretty(&package)? + "\n")?;
+    Ok(())
+}
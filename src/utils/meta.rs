@@ -0,0 +1,117 @@
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Root-level configuration for a repository holding several workspaces
+pub const META_CONFIG_FILE: &str = "moonflare-meta.toml";
+
+/// Directories never searched for nested workspaces
+const SKIPPED_DIRECTORIES: [&str; 5] = ["node_modules", ".git", "target", "dist", ".moonflare"];
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MetaConfig {
+    pub workspaces: Vec<MetaWorkspaceConfig>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MetaWorkspaceConfig {
+    /// Workspace directory, relative to the repository root
+    pub path: PathBuf,
+    /// Environments the workspace deploys to; `meta deploy --env` skips workspaces that list
+    /// others. Every environment is allowed when empty.
+    pub environments: Vec<String>,
+}
+
+/// A workspace `moonflare meta` operates on
+#[derive(Debug, Clone)]
+pub struct MetaWorkspace {
+    /// Path relative to the repository root, used as its name in reports
+    pub path: PathBuf,
+    pub environments: Vec<String>,
+}
+
+impl MetaWorkspace {
+    pub fn name(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    pub fn deploys_to(&self, environment: Option<&str>) -> bool {
+        match environment {
+            Some(env) => self.environments.is_empty() || self.environments.iter().any(|e| e == env),
+            None => true,
+        }
+    }
+
+    /// Whether `--workspace` selects this workspace, by relative path or directory name
+    pub fn matches(&self, selector: &str) -> bool {
+        let selector = selector.trim_end_matches('/');
+        self.path == Path::new(selector)
+            || self.path.file_name().is_some_and(|name| name == selector)
+    }
+}
+
+/// The workspaces under `root`: those listed in moonflare-meta.toml when it exists, otherwise
+/// every directory below `root` holding a `.moon/workspace.yml`
+pub fn workspaces(root: &Path) -> Result<Vec<MetaWorkspace>> {
+    let config_path = root.join(META_CONFIG_FILE);
+    if config_path.exists() {
+        let content = fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read {}", META_CONFIG_FILE))?;
+        let config: MetaConfig =
+            toml::from_str(&content).with_context(|| format!("Invalid {}", META_CONFIG_FILE))?;
+
+        for workspace in &config.workspaces {
+            if !is_workspace(&root.join(&workspace.path)) {
+                bail!(
+                    "{} lists '{}', which isn't a Moonflare workspace",
+                    META_CONFIG_FILE,
+                    workspace.path.display()
+                );
+            }
+        }
+        return Ok(config
+            .workspaces
+            .into_iter()
+            .map(|w| MetaWorkspace {
+                path: w.path,
+                environments: w.environments,
+            })
+            .collect());
+    }
+
+    let mut found = Vec::new();
+    let mut walker = WalkDir::new(root)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter();
+    while let Some(entry) = walker.next() {
+        let entry = entry?;
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        if SKIPPED_DIRECTORIES
+            .iter()
+            .any(|skipped| entry.file_name() == *skipped)
+        {
+            walker.skip_current_dir();
+            continue;
+        }
+        if is_workspace(entry.path()) {
+            found.push(MetaWorkspace {
+                path: entry.path().strip_prefix(root)?.to_path_buf(),
+                environments: Vec::new(),
+            });
+            // A workspace's own projects aren't searched for further workspaces
+            walker.skip_current_dir();
+        }
+    }
+    Ok(found)
+}
+
+fn is_workspace(path: &Path) -> bool {
+    path.join(".moon/workspace.yml").exists()
+}
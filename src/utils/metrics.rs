@@ -0,0 +1,237 @@
+use crate::utils::build_log::{BuildTask, read_builds};
+use crate::utils::config::{MetricsConfig, MetricsFormat, MoonflareConfig};
+use crate::utils::events::secs;
+use crate::utils::http;
+use anyhow::{Context, Result, bail};
+use serde_json::{Value, json};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long a command waits for the collector before giving up on the export
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What one command run reports: its outcome and, for builds, Moon's per-task timings
+#[derive(Debug)]
+pub struct CommandMetrics {
+    pub workspace: String,
+    pub command: String,
+    pub success: bool,
+    pub duration: Duration,
+    pub finished: SystemTime,
+    pub tasks: Vec<BuildTask>,
+}
+
+impl CommandMetrics {
+    /// Metrics of `command`, which ran from `started` for `duration`. Task timings and cache hits
+    /// come from the build log entry the command wrote, if it wrote one.
+    pub fn collect(
+        config: &MoonflareConfig,
+        command: &str,
+        success: bool,
+        started: SystemTime,
+        duration: Duration,
+    ) -> Self {
+        let started_at = chrono::DateTime::<chrono::Utc>::from(started).to_rfc3339();
+        let tasks = read_builds()
+            .pop()
+            .filter(|build| build.timestamp >= started_at)
+            .map(|build| build.tasks)
+            .unwrap_or_default();
+        let workspace = config.workspace.name.clone().unwrap_or_else(|| {
+            std::env::current_dir()
+                .ok()
+                .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_default()
+        });
+
+        Self {
+            workspace,
+            command: command.to_string(),
+            success,
+            duration,
+            finished: started + duration,
+            tasks,
+        }
+    }
+
+    pub fn cache_hits(&self) -> usize {
+        self.tasks.iter().filter(|t| t.cached == Some(true)).count()
+    }
+
+    fn status(&self) -> &'static str {
+        if self.success { "success" } else { "failure" }
+    }
+
+    /// One data point for the command and one per task, in the shape `writeDataPoint` takes.
+    /// The workspace is the index, so a dataset can hold a whole fleet.
+    pub fn analytics_engine(&self, dataset: Option<&str>) -> Value {
+        let mut points = vec![json!({
+            "indexes": [self.workspace],
+            "blobs": ["command", self.command, self.status(), ""],
+            "doubles": [
+                secs(self.duration),
+                self.cache_hits() as f64,
+                self.tasks.len() as f64,
+                if self.success { 0.0 } else { 1.0 },
+            ],
+        })];
+        points.extend(self.tasks.iter().map(|task| {
+            json!({
+                "indexes": [self.workspace],
+                "blobs": ["task", self.command, self.status(), task.target],
+                "doubles": [
+                    task.duration_secs,
+                    if task.cached == Some(true) { 1.0 } else { 0.0 },
+                    1.0,
+                    0.0,
+                ],
+            })
+        }));
+        json!({ "dataset": dataset, "dataPoints": points })
+    }
+
+    /// An OTLP/HTTP JSON export request: duration gauges and failure and cache-hit counters
+    pub fn otlp(&self) -> Value {
+        let time = self
+            .finished
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+            .to_string();
+        let command = [
+            attribute("command", &self.command),
+            attribute("status", self.status()),
+        ];
+        let task_points: Vec<Value> = self
+            .tasks
+            .iter()
+            .map(|task| {
+                json!({
+                    "timeUnixNano": time,
+                    "asDouble": task.duration_secs,
+                    "attributes": [
+                        attribute("command", &self.command),
+                        attribute("target", &task.target),
+                        attribute("cached", if task.cached == Some(true) { "true" } else { "false" }),
+                    ],
+                })
+            })
+            .collect();
+
+        let mut metrics = vec![
+            json!({
+                "name": "moonflare.command.duration",
+                "unit": "s",
+                "gauge": { "dataPoints": [{
+                    "timeUnixNano": time,
+                    "asDouble": secs(self.duration),
+                    "attributes": command,
+                }] },
+            }),
+            counter(
+                "moonflare.command.failures",
+                &time,
+                u64::from(!self.success),
+                &command,
+            ),
+        ];
+        if !self.tasks.is_empty() {
+            metrics.push(json!({
+                "name": "moonflare.task.duration",
+                "unit": "s",
+                "gauge": { "dataPoints": task_points },
+            }));
+            metrics.push(counter(
+                "moonflare.task.cache_hits",
+                &time,
+                self.cache_hits() as u64,
+                &command,
+            ));
+        }
+
+        json!({ "resourceMetrics": [{
+            "resource": { "attributes": [
+                attribute("service.name", "moonflare"),
+                attribute("service.version", env!("CARGO_PKG_VERSION")),
+                attribute("moonflare.workspace", &self.workspace),
+            ] },
+            "scopeMetrics": [{
+                "scope": { "name": "moonflare" },
+                "metrics": metrics,
+            }],
+        }] })
+    }
+}
+
+/// An OTLP key-value attribute with a string value
+pub fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// A delta sum: what this run adds to the total
+fn counter(name: &str, time: &str, value: u64, attributes: &[Value]) -> Value {
+    json!({
+        "name": name,
+        "sum": {
+            "aggregationTemporality": 1,
+            "isMonotonic": true,
+            "dataPoints": [{
+                "timeUnixNano": time,
+                "asInt": value.to_string(),
+                "attributes": attributes,
+            }],
+        },
+    })
+}
+
+/// Post the metrics to the configured collector
+pub async fn export(config: &MetricsConfig, metrics: &CommandMetrics) -> Result<()> {
+    let Some(endpoint) = config.endpoint.as_deref() else {
+        bail!("No endpoint is configured under [metrics] in moonflare.toml");
+    };
+    let (url, body) = match config.format {
+        MetricsFormat::AnalyticsEngine => (
+            endpoint.to_string(),
+            metrics.analytics_engine(config.dataset.as_deref()),
+        ),
+        MetricsFormat::Otlp => {
+            let url = if endpoint.ends_with("/v1/metrics") {
+                endpoint.to_string()
+            } else {
+                format!("{}/v1/metrics", endpoint.trim_end_matches('/'))
+            };
+            (url, metrics.otlp())
+        }
+    };
+
+    let mut request = http::builder()?
+        .timeout(EXPORT_TIMEOUT)
+        .build()?
+        .post(&url)
+        .json(&body);
+    if let Some(token) = config.token()? {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .context("Failed to reach the metrics endpoint")?;
+    if !response.status().is_success() {
+        bail!("Metrics endpoint responded with {}", response.status());
+    }
+    Ok(())
+}
+
+/// Export a finished command's metrics when `[metrics]` asks for them. Like notifications, a
+/// failed export only warns.
+pub async fn record(command: &str, success: bool, started: SystemTime, duration: Duration) {
+    let Ok(config) = MoonflareConfig::load() else {
+        return;
+    };
+    if !config.metrics.exports(command) {
+        return;
+    }
+    let metrics = CommandMetrics::collect(&config, command, success, started, duration);
+    if let Err(e) = export(&config.metrics, &metrics).await {
+        eprintln!("Warning: couldn't export metrics: {:#}", e);
+    }
+}
@@ -0,0 +1,198 @@
+#[cfg(feature = "api")]
+use crate::utils::cloudflare::CloudflareClient;
+#[cfg(not(feature = "api"))]
+use crate::utils::cloudflare::api_unavailable;
+use crate::utils::d1::databases;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::task_env;
+#[cfg(feature = "api")]
+use crate::utils::wrangler::env_worker_name;
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+/// Wrangler's defaults for where D1 migrations live and which table records them
+const DEFAULT_MIGRATIONS_DIR: &str = "migrations";
+const DEFAULT_MIGRATIONS_TABLE: &str = "d1_migrations";
+
+/// Applied and pending migrations of one D1 database or a Worker's Durable Objects, in one
+/// environment
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub project: String,
+    /// `None` for the top-level (production) configuration
+    pub environment: Option<String>,
+    /// `d1:<binding>` or `durable_objects`
+    pub target: String,
+    pub applied: Vec<String>,
+    pub pending: Vec<String>,
+    /// Why the applied migrations couldn't be read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unknown: Option<String>,
+}
+
+impl MigrationStatus {
+    fn new(project: &WorkspaceProject, env: Option<&str>, target: String) -> Self {
+        Self {
+            project: project.name.clone(),
+            environment: env.map(str::to_string),
+            target,
+            applied: Vec::new(),
+            pending: Vec::new(),
+            unknown: None,
+        }
+    }
+}
+
+/// Migration files in each D1 database's `migrations_dir` compared with the names recorded in
+/// its remote migrations table. Databases without migration files are skipped.
+pub fn d1_status(
+    project: &WorkspaceProject,
+    config: &Value,
+    env: Option<&str>,
+) -> Result<Vec<MigrationStatus>> {
+    let mut statuses = Vec::new();
+    for database in databases(config, env) {
+        let dir = database.entry["migrations_dir"]
+            .as_str()
+            .unwrap_or(DEFAULT_MIGRATIONS_DIR);
+        let files = migration_files(&project.path.join(dir));
+        if files.is_empty() {
+            continue;
+        }
+        let table = database.entry["migrations_table"]
+            .as_str()
+            .unwrap_or(DEFAULT_MIGRATIONS_TABLE);
+        let applied = applied_d1_migrations(project, &database.name, table, env)?;
+
+        let mut status = MigrationStatus::new(project, env, format!("d1:{}", database.binding));
+        status.pending = files
+            .into_iter()
+            .filter(|file| !applied.contains(file))
+            .collect();
+        status.applied = applied;
+        statuses.push(status);
+    }
+    Ok(statuses)
+}
+
+/// `.sql` files in a migrations directory, in the order Wrangler applies them
+fn migration_files(dir: &Path) -> Vec<String> {
+    let mut files: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".sql"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Names in the remote migrations table, or none before the first migration created it
+fn applied_d1_migrations(
+    project: &WorkspaceProject,
+    database: &str,
+    table: &str,
+    env: Option<&str>,
+) -> Result<Vec<String>> {
+    let query = format!("SELECT name FROM {} ORDER BY id", table);
+    let mut command = Command::new("pnpm");
+    command
+        .args([
+            "exec", "wrangler", "d1", "execute", database, "--remote", "--json",
+        ])
+        .args(["--command", &query])
+        .current_dir(&project.path)
+        .envs(task_env::vars(Some(&project.name)));
+    if let Some(env) = env {
+        command.args(["--env", env]);
+    }
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to query the migrations of {}", database))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stdout.contains("no such table") || stderr.contains("no such table") {
+            return Ok(Vec::new());
+        }
+        bail!(
+            "Couldn't read the migrations of {}: {}",
+            database,
+            stderr.trim()
+        );
+    }
+    let results: Value = serde_json::from_str(&stdout)
+        .with_context(|| format!("wrangler printed invalid JSON for {}", database))?;
+    Ok(results[0]["results"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|row| row["name"].as_str().map(str::to_string))
+        .collect())
+}
+
+/// The config's Durable Object migration tags compared with the tag the deployed Worker last
+/// applied. Wrangler applies pending ones on the next deploy.
+#[cfg(feature = "api")]
+pub async fn durable_object_status(
+    project: &WorkspaceProject,
+    config: &Value,
+    env: Option<&str>,
+    client: &std::result::Result<CloudflareClient, String>,
+) -> Result<Option<MigrationStatus>> {
+    let tags = durable_object_tags(config, env);
+    if tags.is_empty() {
+        return Ok(None);
+    }
+
+    let mut status = MigrationStatus::new(project, env, "durable_objects".to_string());
+    let client = match client {
+        Ok(client) => client,
+        Err(reason) => {
+            status.unknown = Some(reason.clone());
+            return Ok(Some(status));
+        }
+    };
+    let worker = env_worker_name(config, &project.name, env);
+    let deployed = client
+        .worker_script(&worker)
+        .await?
+        .and_then(|script| script.migration_tag);
+    let applied = deployed
+        .and_then(|tag| tags.iter().position(|t| *t == tag))
+        .map_or(0, |index| index + 1);
+    status.applied = tags[..applied].to_vec();
+    status.pending = tags[applied..].to_vec();
+    Ok(Some(status))
+}
+
+/// Without the `api` feature the deployed Worker can't be asked which tags it applied, so the
+/// config's Durable Object migrations are listed as unknown
+#[cfg(not(feature = "api"))]
+pub fn durable_object_status(
+    project: &WorkspaceProject,
+    config: &Value,
+    env: Option<&str>,
+) -> Option<MigrationStatus> {
+    if durable_object_tags(config, env).is_empty() {
+        return None;
+    }
+    let mut status = MigrationStatus::new(project, env, "durable_objects".to_string());
+    status.unknown = Some(api_unavailable().to_string());
+    Some(status)
+}
+
+/// Tags of the Durable Object migrations the config declares for `env`, oldest first
+fn durable_object_tags(config: &Value, env: Option<&str>) -> Vec<String> {
+    env.and_then(|env| config["env"][env]["migrations"].as_array())
+        .or_else(|| config["migrations"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|migration| migration["tag"].as_str().map(str::to_string))
+        .collect()
+}
@@ -0,0 +1,309 @@
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::wrangler::dev_port;
+use anyhow::{Context, Result, bail};
+use colored::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use walkdir::WalkDir;
+
+/// Mock responses for a Worker's routes, kept in the Worker project so they can be edited
+pub const MOCK_FILE: &str = "mock-api.json";
+
+/// Source files scanned for route definitions
+const SOURCE_EXTENSIONS: [&str; 4] = ["ts", "js", "mts", "mjs"];
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MockFile {
+    pub routes: Vec<MockRoute>,
+}
+
+/// A canned response. `method` is `*` for routes matched on the path alone, and path segments
+/// starting with `:` match any value, as do trailing `*`s.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MockRoute {
+    pub method: String,
+    pub path: String,
+    #[serde(default = "default_status")]
+    pub status: u16,
+    /// Strings are sent as text, anything else as JSON
+    #[serde(default)]
+    pub body: Value,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+impl MockRoute {
+    fn new(method: &str, path: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            status: default_status(),
+            body: json!({ "mock": true, "route": format!("{} {}", method, path) }),
+            headers: BTreeMap::new(),
+        }
+    }
+
+    fn matches(&self, method: &str, path: &str) -> bool {
+        if self.method != "*" && !self.method.eq_ignore_ascii_case(method) {
+            return false;
+        }
+        let mut pattern = self.path.trim_matches('/').split('/');
+        let mut segments = path.trim_matches('/').split('/');
+        loop {
+            match (pattern.next(), segments.next()) {
+                (Some(p), _) if p.ends_with('*') => return true,
+                (Some(p), Some(s)) if p.starts_with(':') || p == s => continue,
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+/// A Worker's mock routes, served on the port its dev server would use
+#[derive(Debug, Clone)]
+pub struct MockServer {
+    pub project: String,
+    pub port: u16,
+    pub routes: Vec<MockRoute>,
+}
+
+/// Routes the Worker's sources define: `case "/path":` and `pathname === "/path"` checks, and
+/// router calls such as `app.get("/users/:id", ...)`, in the order they're found
+pub fn discover_routes(project_path: &Path) -> Vec<(String, String)> {
+    static PATTERNS: OnceLock<[Regex; 3]> = OnceLock::new();
+    let [case, pathname, router] = PATTERNS.get_or_init(|| {
+        [
+            Regex::new(r#"case\s+["'`](/[^"'`]*)["'`]\s*:"#).unwrap(),
+            Regex::new(r#"pathname\s*===?\s*["'`](/[^"'`]*)["'`]"#).unwrap(),
+            Regex::new(r#"\.(get|post|put|patch|delete|all)\(\s*["'`](/[^"'`]*)["'`]"#).unwrap(),
+        ]
+    });
+
+    let mut sources: Vec<_> = WalkDir::new(project_path.join("src"))
+        .into_iter()
+        .flatten()
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+        })
+        .map(|entry| entry.into_path())
+        .collect();
+    sources.sort();
+
+    let mut routes: Vec<(String, String)> = Vec::new();
+    for source in sources {
+        let Ok(content) = fs::read_to_string(&source) else {
+            continue;
+        };
+        let mut found: Vec<(usize, String, String)> = Vec::new();
+        for regex in [case, pathname] {
+            found.extend(
+                regex
+                    .captures_iter(&content)
+                    .map(|c| (c.get(0).unwrap().start(), "*".to_string(), c[1].to_string())),
+            );
+        }
+        found.extend(router.captures_iter(&content).map(|c| {
+            let method = match &c[1] {
+                "all" => "*".to_string(),
+                method => method.to_uppercase(),
+            };
+            (c.get(0).unwrap().start(), method, c[2].to_string())
+        }));
+        found.sort();
+        for (_, method, path) in found {
+            if !routes.iter().any(|(m, p)| *m == method && *p == path) {
+                routes.push((method, path));
+            }
+        }
+    }
+    routes
+}
+
+/// Add routes found in the Worker's sources to its mock file, keeping the responses already in
+/// it. Returns the mock routes and how many were added.
+pub fn refresh(project: &WorkspaceProject) -> Result<(Vec<MockRoute>, usize)> {
+    let path = project.path.join(MOCK_FILE);
+    let mut mocks = if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        serde_json::from_str::<MockFile>(&content)
+            .with_context(|| format!("Invalid {}", path.display()))?
+    } else {
+        MockFile::default()
+    };
+
+    let mut added = 0;
+    for (method, route) in discover_routes(&project.path) {
+        if !mocks
+            .routes
+            .iter()
+            .any(|mock| mock.method == method && mock.path == route)
+        {
+            mocks.routes.push(MockRoute::new(&method, &route));
+            added += 1;
+        }
+    }
+    if added > 0 || !path.exists() {
+        fs::write(&path, serde_json::to_string_pretty(&mocks)? + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok((mocks.routes, added))
+}
+
+/// Refresh the mock file of every Worker and plan a mock server on each one's dev port
+pub fn prepare(workers: &[WorkspaceProject]) -> Result<Vec<MockServer>> {
+    let mut servers: Vec<MockServer> = Vec::new();
+    for worker in workers {
+        let port = dev_port(&worker.path)?;
+        if let Some(other) = servers.iter().find(|server| server.port == port) {
+            bail!(
+                "'{}' and '{}' both run their dev server on port {}; set dev.port in their wrangler configs so each can be mocked",
+                other.project,
+                worker.name,
+                port
+            );
+        }
+        let (routes, added) = refresh(worker)?;
+        if added > 0 {
+            println!(
+                "{}",
+                format!(
+                    "Added {} route(s) to {}",
+                    added,
+                    worker.path.join(MOCK_FILE).display()
+                )
+                .dimmed()
+            );
+        }
+        servers.push(MockServer {
+            project: worker.name.clone(),
+            port,
+            routes,
+        });
+    }
+    Ok(servers)
+}
+
+/// Listen on the mocked Worker's port
+pub async fn bind(server: &MockServer) -> Result<Vec<TcpListener>> {
+    let listener = TcpListener::bind(("127.0.0.1", server.port))
+        .await
+        .with_context(|| {
+            format!(
+                "Mock API for '{}' couldn't listen on port {}",
+                server.project, server.port
+            )
+        })?;
+    let mut listeners = vec![listener];
+    // `localhost` resolves to ::1 first on some systems; serve it too when it's available
+    if let Ok(ipv6) = TcpListener::bind(("::1", server.port)).await {
+        listeners.push(ipv6);
+    }
+    Ok(listeners)
+}
+
+/// Answer requests with the matching mock until the process exits
+pub async fn serve(listeners: Vec<TcpListener>, server: MockServer) {
+    for listener in listeners {
+        let server = server.clone();
+        tokio::spawn(async move {
+            while let Ok((client, _)) = listener.accept().await {
+                let server = server.clone();
+                tokio::spawn(async move {
+                    let _ = respond(client, &server).await;
+                });
+            }
+        });
+    }
+}
+
+async fn respond(client: TcpStream, server: &MockServer) -> Result<()> {
+    let mut reader = BufReader::new(client);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or("/");
+    let path = target.split(['?', '#']).next().unwrap_or("/");
+
+    // Frontends call the mock from their own dev server's origin
+    let mut headers = vec![
+        ("Access-Control-Allow-Origin".to_string(), "*".to_string()),
+        ("Access-Control-Allow-Headers".to_string(), "*".to_string()),
+        ("Access-Control-Allow-Methods".to_string(), "*".to_string()),
+    ];
+    let (status, body, content_type) = if method == "OPTIONS" {
+        (204, String::new(), "text/plain")
+    } else {
+        match server
+            .routes
+            .iter()
+            .find(|route| route.matches(method, path))
+        {
+            Some(route) => {
+                headers.extend(route.headers.clone());
+                match &route.body {
+                    Value::String(text) => (route.status, text.clone(), "text/plain"),
+                    body => (route.status, body.to_string(), "application/json"),
+                }
+            }
+            None => (
+                404,
+                json!({ "error": format!("No mock for {} {} in {}", method, path, MOCK_FILE) })
+                    .to_string(),
+                "application/json",
+            ),
+        }
+    };
+    println!(
+        "{}",
+        format!("mock {}: {} {} {}", server.project, method, path, status).dimmed()
+    );
+
+    if !headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    {
+        headers.push((
+            "Content-Type".to_string(),
+            format!("{}; charset=utf-8", content_type),
+        ));
+    }
+    let mut response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        reqwest::StatusCode::from_u16(status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or(""),
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(&body);
+    reader.into_inner().write_all(response.as_bytes()).await?;
+    Ok(())
+}
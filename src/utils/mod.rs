@@ -1,2 +1,101 @@
+#[cfg(feature = "api")]
+pub mod ai_gateway;
+pub mod aliases;
+pub mod artifacts;
+pub mod audit;
+pub mod auth;
+pub mod backup;
+pub mod binding_audit;
+pub mod build_log;
+#[cfg(feature = "api")]
+pub mod builds;
+pub mod bundle;
+pub mod cache_headers;
+pub mod cargo_workspace;
+pub mod certs;
+pub mod chaos;
+pub mod cloudflare;
+pub mod config;
+pub mod config_lint;
+pub mod config_schema;
+pub mod containers;
+pub mod crash;
+pub mod crons;
+pub mod d1;
+pub mod daemon;
+pub mod deploy_strategy;
+pub mod dev_vars;
+pub mod devcontainer;
+pub mod diagnostics;
+pub mod direct_tasks;
+pub mod docs_site;
+pub mod download;
+pub mod durable_objects;
+pub mod e2e;
+pub mod events;
+pub mod explain;
+pub mod flags;
 pub mod fs;
+pub mod hashes;
+pub mod history;
+pub mod http;
+pub mod interrupt;
+pub mod kv;
+pub mod licenses;
+pub mod lock;
+pub mod manifest;
+pub mod matrix;
+pub mod meta;
+#[cfg(feature = "telemetry")]
+pub mod metrics;
+pub mod migrations;
+pub mod mock_api;
 pub mod moon;
+pub mod moon_report;
+pub mod moon_tasks;
+pub mod nix;
+pub mod node;
+pub mod notify;
+pub mod npmrc;
+pub mod onboarding;
+pub mod package_config;
+pub mod picker;
+pub mod pipelines;
+pub mod placement;
+pub mod plan;
+pub mod platform;
+pub mod ports;
+pub mod projects;
+pub mod queues;
+#[cfg(feature = "api")]
+pub mod r2_sync;
+pub mod readiness;
+pub mod readme;
+pub mod release;
+pub mod reporter;
+pub mod repro;
+pub mod retention;
+pub mod retry;
+pub mod routes;
+pub mod rpc_types;
+pub mod sbom;
+pub mod secrets;
+pub mod seeds;
+pub mod smoke;
+pub mod task_env;
+pub mod timeout;
+pub mod token_scopes;
+pub mod toolchain;
+#[cfg(feature = "telemetry")]
+pub mod trace;
+pub mod upgrade;
+pub mod vcs;
+pub mod version;
+pub mod wasm_reload;
+pub mod watch;
+pub mod workspace_globs;
+pub mod wrangler;
+pub mod wrangler_defaults;
+pub mod wrangler_edit;
+pub mod wrangler_output;
+pub mod wrangler_schema;
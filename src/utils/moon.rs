@@ -1,13 +1,23 @@
 use crate::errors::MoonflareError;
+use crate::utils::moon_report::RunReport;
+use crate::utils::platform::{self, Shell};
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::readiness::ReadyCheck;
+use crate::utils::{daemon, direct_tasks, task_env, timeout};
 use anyhow::{Result, bail};
 use colored::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 use std::process::Command;
+use std::time::{Duration, Instant, SystemTime};
 use which::which;
 
+/// How often staged dev servers are probed for readiness
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
 // Helper function to find Moon CLI in known locations
-fn find_moon_binary() -> Option<PathBuf> {
+pub fn find_moon_binary() -> Option<PathBuf> {
     let is_ci = std::env::var("CI").unwrap_or_default().to_lowercase() == "true"
         || std::env::var("GITHUB_ACTIONS")
             .unwrap_or_default()
@@ -52,57 +62,42 @@ fn find_moon_binary() -> Option<PathBuf> {
     if is_ci {
         eprintln!();
         eprintln!("Checking expected installation locations:");
+    }
 
-        // Check /usr/local/bin/moon (where we install it in CI)
-        let usr_local_moon = PathBuf::from("/usr/local/bin/moon");
-        eprintln!(
-            "  /usr/local/bin/moon: {}",
-            if usr_local_moon.exists() {
-                format!(
-                    "EXISTS ({})",
-                    if usr_local_moon.is_file() {
-                        "file"
-                    } else {
-                        "not a file"
-                    }
-                )
-            } else {
-                "NOT FOUND".to_string()
-            }
-        );
-
-        if usr_local_moon.exists() && usr_local_moon.is_file() {
-            eprintln!("✓ Using Moon from /usr/local/bin/moon");
-            return Some(usr_local_moon);
+    // Installers put Moon in /usr/local/bin (as CI does) or ~/.moon/bin, or in
+    // %USERPROFILE%\.moon\bin on Windows, which isn't always on the PATH yet
+    for location in platform::moon_install_locations() {
+        if is_ci {
+            eprintln!(
+                "  {}: {}",
+                location.display(),
+                if location.exists() {
+                    format!(
+                        "EXISTS ({})",
+                        if location.is_file() {
+                            "file"
+                        } else {
+                            "not a file"
+                        }
+                    )
+                } else {
+                    "NOT FOUND".to_string()
+                }
+            );
         }
-
-        // Check ~/.moon/bin/moon (default Moon installation location)
-        let home = std::env::var("HOME").unwrap_or_else(|_| "/home/runner".to_string());
-        let home_moon = PathBuf::from(format!("{}/.moon/bin/moon", home));
-        eprintln!(
-            "  {}/.moon/bin/moon: {}",
-            home,
-            if home_moon.exists() {
-                format!(
-                    "EXISTS ({})",
-                    if home_moon.is_file() {
-                        "file"
-                    } else {
-                        "not a file"
-                    }
-                )
-            } else {
-                "NOT FOUND".to_string()
+        if location.is_file() {
+            if is_ci {
+                eprintln!("✓ Using Moon from {}", location.display());
             }
-        );
-
-        if home_moon.exists() && home_moon.is_file() {
-            eprintln!("✓ Using Moon from {}/.moon/bin/moon", home);
-            return Some(home_moon);
+            return Some(location);
         }
+    }
 
+    if is_ci {
         // Check common proto installation paths in CI for debugging
-        let home = std::env::var("HOME").unwrap_or_else(|_| "/home/runner".to_string());
+        let home = platform::home_dir()
+            .map(|home| home.display().to_string())
+            .unwrap_or_else(|| "/home/runner".to_string());
         let proto_paths = [
             format!("{}/.proto/shims/moon", home),
             format!("{}/.proto/bin/moon", home),
@@ -179,7 +174,7 @@ pub fn check_moon_installation() -> Result<()> {
                     _ => {
                         eprintln!("{}", "Failed to install Moon CLI".red());
                         eprintln!("Please install Moon manually:");
-                        eprintln!("  curl -fsSL https://moonrepo.dev/install/moon.sh | bash");
+                        eprintln!("  {}", Shell::detect().install_moon());
                         bail!("Moon CLI installation required");
                     }
                 }
@@ -188,16 +183,30 @@ pub fn check_moon_installation() -> Result<()> {
     }
 }
 
-pub async fn run_moon_command(args: &[&str]) -> Result<()> {
+/// The process for `moon <args>`: Moon itself, or without it the resolved tasks run straight
+/// from moon.yml
+pub fn moon_command(args: &[&str]) -> Result<Command> {
+    if direct_tasks::active() {
+        return direct_tasks::command(args);
+    }
     let moon_binary = find_moon_binary().unwrap_or_else(|| PathBuf::from("moon"));
-
     let mut cmd = Command::new(moon_binary);
     cmd.args(args);
+    Ok(cmd)
+}
 
-    let status = cmd.status()?;
+pub async fn run_moon_command(args: &[&str]) -> Result<()> {
+    let mut cmd = moon_command(args)?;
+    task_env::apply(&mut cmd, args);
+
+    let started = SystemTime::now();
+    let status = timeout::status(cmd).await?;
 
     if status.success() {
         Ok(())
+    } else if let Some(failures) = RunReport::load_since(started).and_then(|r| r.failure_message())
+    {
+        bail!("Moon command '{}' failed: {}", args.join(" "), failures);
     } else {
         bail!(
             "Moon command '{}' failed with exit code: {:?}",
@@ -207,15 +216,148 @@ pub async fn run_moon_command(args: &[&str]) -> Result<()> {
     }
 }
 
+// Run several Moon commands side by side (e.g. dev servers needing different passthrough args)
+pub async fn run_moon_commands_concurrently(
+    commands: &[Vec<String>],
+    envs: &[(&str, String)],
+) -> Result<()> {
+    let mut children = Vec::new();
+    for args in commands {
+        children.push(spawn_moon(args, envs)?);
+    }
+    wait_for_all(children).await
+}
+
+/// A dev server's Moon command, started in stages by [`run_moon_commands_staged`]
+#[derive(Debug, Clone)]
+pub struct StagedCommand {
+    /// Project whose dev server the command runs
+    pub project: String,
+    pub args: Vec<String>,
+    pub ready: ReadyCheck,
+}
+
+/// Run Moon commands side by side, starting them in order with at most `max_parallel` waiting
+/// to become ready at a time. A command that isn't ready within `ready_timeout` makes way for
+/// the next anyway.
+pub async fn run_moon_commands_staged(
+    commands: &[StagedCommand],
+    envs: &[(&str, String)],
+    max_parallel: usize,
+    ready_timeout: Duration,
+) -> Result<()> {
+    let mut children = Vec::new();
+    // Indexes of the commands still starting up, with when they started
+    let mut starting: Vec<(usize, Instant)> = Vec::new();
+    for (index, command) in commands.iter().enumerate() {
+        while starting.len() >= max_parallel.max(1) {
+            wait_for_one_ready(commands, &mut children, &mut starting, ready_timeout).await;
+        }
+        children.push(spawn_moon(&command.args, envs)?);
+        starting.push((index, Instant::now()));
+    }
+    wait_for_all(children).await
+}
+
+/// Poll the commands in `starting` until one is ready, has exited or ran out of time, and
+/// drop it from the list
+async fn wait_for_one_ready(
+    commands: &[StagedCommand],
+    children: &mut [(String, tokio::process::Child)],
+    starting: &mut Vec<(usize, Instant)>,
+    ready_timeout: Duration,
+) {
+    loop {
+        for position in 0..starting.len() {
+            let (index, started) = starting[position];
+            let command = &commands[index];
+            if command.ready.is_ready().await {
+                println!(
+                    "{}",
+                    format!(
+                        "'{}' is ready ({:.1}s)",
+                        command.project,
+                        started.elapsed().as_secs_f64()
+                    )
+                    .green()
+                );
+            } else if matches!(children[index].1.try_wait(), Ok(Some(_))) {
+                println!(
+                    "{}",
+                    format!("'{}' exited before it was ready", command.project).yellow()
+                );
+            } else if started.elapsed() >= ready_timeout {
+                println!(
+                    "{}",
+                    format!(
+                        "'{}' isn't ready after {}s ({}); starting the next dev server anyway",
+                        command.project,
+                        ready_timeout.as_secs(),
+                        command.ready
+                    )
+                    .yellow()
+                );
+            } else {
+                continue;
+            }
+            starting.remove(position);
+            return;
+        }
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
+fn spawn_moon(args: &[String], envs: &[(&str, String)]) -> Result<(String, tokio::process::Child)> {
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    let mut cmd = moon_command(&arg_refs)?;
+    task_env::apply(&mut cmd, &arg_refs);
+    cmd.envs(envs.iter().map(|(k, v)| (*k, v.as_str())));
+    let description = args.join(" ");
+    let child = timeout::spawn(cmd, &format!("moon {}", description))?;
+    Ok((description, child))
+}
+
+async fn wait_for_all(children: Vec<(String, tokio::process::Child)>) -> Result<()> {
+    let mut failures = Vec::new();
+    let mut children = children.into_iter();
+    while let Some((args, mut child)) = children.next() {
+        let status = match timeout::wait(&mut child, &format!("moon {}", args)).await {
+            Ok(status) => status,
+            Err(e) => {
+                // Out of time: the rest are stopped too rather than left running
+                for (_, mut child) in children {
+                    timeout::stop(&mut child).await;
+                }
+                return Err(e);
+            }
+        };
+        if !status.success() {
+            failures.push(format!("'{}' (exit code: {:?})", args, status.code()));
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        bail!("Moon command(s) failed: {}", failures.join(", "));
+    }
+}
+
 // Run a Moon command with direct stdio passthrough for best UX
 pub async fn run_moon_command_with_error(args: &[&str]) -> std::result::Result<(), MoonflareError> {
-    let moon_binary = find_moon_binary().unwrap_or_else(|| PathBuf::from("moon"));
-
-    let mut cmd = Command::new(moon_binary);
-    cmd.args(args);
+    let mut cmd = moon_command(args)
+        .map_err(|e| MoonflareError::moon_command_failed(&args.join(" "), &e.to_string(), None))?;
+    task_env::apply(&mut cmd, args);
 
     // Let Moon's stdout and stderr pass through directly to preserve colors and formatting
-    let status = cmd.status().map_err(|e| {
+    let started = SystemTime::now();
+    let status = timeout::status(cmd).await.map_err(|e| {
+        if timeout::is_timeout(&e) {
+            return MoonflareError::moon_command_timed_out(
+                &args.join(" "),
+                timeout::limit().unwrap_or_default(),
+            );
+        }
         MoonflareError::moon_command_failed(
             &args.join(" "),
             &format!("Failed to execute moon command: {}", e),
@@ -225,6 +367,13 @@ pub async fn run_moon_command_with_error(args: &[&str]) -> std::result::Result<(
 
     if status.success() {
         Ok(())
+    } else if let Some(failures) = RunReport::load_since(started).and_then(|r| r.failure_message())
+    {
+        Err(MoonflareError::moon_tasks_failed(
+            &args.join(" "),
+            &failures,
+            status.code(),
+        ))
     } else {
         // Moon has already printed its error to stderr with full formatting
         // Just return a simple error that indicates the command failed
@@ -264,6 +413,9 @@ pub async fn moon_setup() -> Result<()> {
                 );
             }
         }
+    } else if let Some(reason) = direct_tasks::reason() {
+        println!("{}", format!("Skipping Moon setup ({})", reason).yellow());
+        Ok(())
     } else {
         println!("{}", "Setting up Moon workspace...".blue());
         run_moon_command(&["setup"]).await
@@ -272,12 +424,10 @@ pub async fn moon_setup() -> Result<()> {
 
 // Run a Moon command and return the output without printing it
 pub async fn run_moon_command_silent(args: &[&str]) -> Result<String> {
-    let moon_binary = find_moon_binary().unwrap_or_else(|| PathBuf::from("moon"));
-
-    let mut cmd = Command::new(moon_binary);
-    cmd.args(args);
+    let mut cmd = moon_command(args)?;
+    task_env::apply(&mut cmd, args);
 
-    let output = cmd.output()?;
+    let output = timeout::output(cmd).await?;
 
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
@@ -287,6 +437,14 @@ pub async fn run_moon_command_silent(args: &[&str]) -> Result<String> {
     }
 }
 
+/// Run a read-only Moon query, through the workspace daemon's cache when one is running
+async fn moon_query(args: &[&str]) -> Result<String> {
+    match daemon::cached_query(args).await {
+        Some(result) => result,
+        None => run_moon_command_silent(args).await,
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct MoonProject {
     pub id: String,
@@ -303,7 +461,10 @@ pub struct MoonProjectsResponse {
 
 // Query all available projects in the Moon workspace
 pub async fn query_projects() -> Result<Vec<MoonProject>> {
-    let output = run_moon_command_silent(&["query", "projects", "--json"]).await?;
+    if direct_tasks::active() {
+        return Ok(direct_tasks::query_projects());
+    }
+    let output = moon_query(&["query", "projects", "--json"]).await?;
 
     // Parse the JSON output
     let response: MoonProjectsResponse = serde_json::from_str(&output)
@@ -312,6 +473,83 @@ pub async fn query_projects() -> Result<Vec<MoonProject>> {
     Ok(response.projects)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct MoonTasksResponse {
+    /// Task ids keyed by project id
+    pub tasks: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+}
+
+// Query the tasks of every project, keyed by project id
+pub async fn query_tasks() -> Result<BTreeMap<String, Vec<String>>> {
+    if direct_tasks::active() {
+        return Ok(direct_tasks::query_tasks());
+    }
+    let output = moon_query(&["query", "tasks", "--json"]).await?;
+
+    let response: MoonTasksResponse = serde_json::from_str(&output)
+        .map_err(|e| anyhow::anyhow!("Failed to parse Moon tasks response: {}", e))?;
+
+    Ok(response
+        .tasks
+        .into_iter()
+        .map(|(project, tasks)| (project, tasks.into_keys().collect()))
+        .collect())
+}
+
+/// Workspace projects from Moon's project graph, each with the tasks Moon resolved for it, so
+/// projects outside the layout directories (`projects.sources`, custom globs) are included.
+/// Without Moon there is no graph, only moon.yml files read as is.
+pub async fn graph_projects() -> Result<Vec<(WorkspaceProject, BTreeSet<String>)>> {
+    if let Some(reason) = direct_tasks::reason() {
+        bail!("Moon's project graph isn't available ({})", reason);
+    }
+    let mut tasks = query_tasks().await?;
+    Ok(query_projects()
+        .await?
+        .into_iter()
+        .map(|project| {
+            let project_tasks = tasks.remove(&project.id).unwrap_or_default();
+            (
+                WorkspaceProject {
+                    name: project.id,
+                    path: PathBuf::from(project.source),
+                },
+                project_tasks.into_iter().collect(),
+            )
+        })
+        .collect())
+}
+
+/// Check a `--task` override with `moon query tasks`: the project (or, without one, at least one
+/// project) must define the task. The check is skipped when Moon can't be queried.
+pub async fn validate_task_exists(project: Option<&str>, task: &str) -> Result<()> {
+    let Ok(tasks) = query_tasks().await else {
+        return Ok(());
+    };
+
+    match project {
+        Some(project) => {
+            let Some(available) = tasks.get(project) else {
+                return Ok(());
+            };
+            if !available.iter().any(|t| t == task) {
+                bail!(
+                    "Project '{}' has no '{}' task. Available tasks: {}",
+                    project,
+                    task,
+                    available.join(", ")
+                );
+            }
+        }
+        None => {
+            if !tasks.values().any(|t| t.iter().any(|t| t == task)) {
+                bail!("No project in the workspace has a '{}' task", task);
+            }
+        }
+    }
+    Ok(())
+}
+
 // Check if a specific project exists and return available project names
 pub async fn validate_project_exists(project_name: &str) -> Result<Option<Vec<String>>> {
     match query_projects().await {
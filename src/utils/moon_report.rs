@@ -0,0 +1,111 @@
+use crate::utils::events::secs;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Moon's report of the actions in its most recent run, written by every `moon run`
+pub const RUN_REPORT: &str = ".moon/cache/runReport.json";
+
+/// How far a report's modification time may trail the start of the run that wrote it
+const MTIME_SLACK: Duration = Duration::from_millis(50);
+
+/// The parts of Moon's run report moonflare reads; everything else is ignored, so new fields
+/// don't break it
+#[derive(Debug, Default, Deserialize)]
+pub struct RunReport {
+    #[serde(default)]
+    pub actions: Vec<Action>,
+}
+
+/// One action of the run, e.g. `RunTask(api:deploy)` or `SyncProject(api)`
+#[derive(Debug, Deserialize)]
+pub struct Action {
+    pub label: String,
+    /// `passed`, `cached`, `cached-from-remote`, `failed`, `failed-and-abort`, `timed-out`, ...
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub duration: Option<MoonDuration>,
+    /// Moon's message for a failed action
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Moon serializes durations as `{ "secs": .., "nanos": .. }`; older versions wrote seconds
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(untagged)]
+pub enum MoonDuration {
+    Parts { secs: u64, nanos: u32 },
+    Secs(f64),
+}
+
+impl RunReport {
+    /// The report in the workspace, if Moon wrote it after `since`
+    pub fn load_since(since: SystemTime) -> Option<Self> {
+        let path = Path::new(RUN_REPORT);
+        // File times come from a coarse clock that can lag the system time a little
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+        if modified + MTIME_SLACK < since {
+            return None;
+        }
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
+
+    /// Actions that ran a task
+    pub fn tasks(&self) -> impl Iterator<Item = &Action> {
+        self.actions
+            .iter()
+            .filter(|action| action.target().is_some())
+    }
+
+    /// Task actions that failed, in the order Moon ran them
+    pub fn failures(&self) -> Vec<&Action> {
+        self.tasks().filter(|action| action.failed()).collect()
+    }
+
+    /// `<target> failed: <error>` for each failed task, one per line
+    pub fn failure_message(&self) -> Option<String> {
+        let failures = self.failures();
+        if failures.is_empty() {
+            return None;
+        }
+        let lines: Vec<String> = failures
+            .iter()
+            .map(|action| {
+                let target = action.target().unwrap_or_default();
+                match action.error.as_deref().map(str::trim) {
+                    Some(error) if !error.is_empty() => format!("{} failed: {}", target, error),
+                    _ => format!("{} {}", target, action.status),
+                }
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+}
+
+impl Action {
+    /// The task target for `RunTask(...)` actions (`RunTarget(...)` in older Moon versions)
+    pub fn target(&self) -> Option<&str> {
+        self.label
+            .strip_prefix("RunTask(")
+            .or_else(|| self.label.strip_prefix("RunTarget("))?
+            .strip_suffix(')')
+    }
+
+    pub fn cached(&self) -> bool {
+        self.status.starts_with("cached")
+    }
+
+    pub fn failed(&self) -> bool {
+        self.status.starts_with("failed") || self.status == "timed-out"
+    }
+
+    pub fn duration_secs(&self) -> f64 {
+        match self.duration {
+            Some(MoonDuration::Parts { secs: whole, nanos }) => secs(Duration::new(whole, nanos)),
+            Some(MoonDuration::Secs(value)) => value,
+            None => 0.0,
+        }
+    }
+}
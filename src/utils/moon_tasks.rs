@@ -0,0 +1,234 @@
+use crate::templates::{embedded, engine::TemplateEngine};
+use anyhow::{Context, Result};
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Workspace-wide tasks inherited by every project
+pub const GLOBAL_TASKS_FILE: &str = ".moon/tasks.yml";
+/// Directory of scoped inheritance files (`<language>.yml`, `<stack>-<language>.yml`, ...)
+pub const SCOPED_TASKS_DIR: &str = ".moon/tasks";
+
+/// The moonflare-managed inheritance files with their content, relative to the workspace root
+pub fn render_managed_files() -> Result<Vec<(PathBuf, String)>> {
+    TemplateEngine::new()
+        .render_template_to_memory(embedded::TASKS_TEMPLATE, &Default::default())?
+        .into_iter()
+        .map(|(path, content)| Ok((path, String::from_utf8(content)?)))
+        .collect()
+}
+
+/// Write the moonflare-managed inheritance files into the workspace at `root`
+pub fn write_managed_files(root: &Path) -> Result<()> {
+    TemplateEngine::new().process_template_files(
+        embedded::TASKS_TEMPLATE,
+        root,
+        &Default::default(),
+    )
+}
+
+/// Inheritance files that apply to a project, in Moon's merge order
+pub fn inheritance_files(project: &Value) -> Vec<PathBuf> {
+    let field = |key: &str| project.get(key).and_then(|v| v.as_str());
+    let language = field("language");
+    let stack = field("stack");
+    // `type` was renamed to `layer` in Moon 1.28
+    let layer = field("layer").or_else(|| field("type"));
+
+    let mut scopes: Vec<String> = Vec::new();
+    if let Some(language) = language {
+        scopes.push(language.to_string());
+    }
+    if let Some(stack) = stack {
+        scopes.push(stack.to_string());
+    }
+    if let (Some(stack), Some(language)) = (stack, language) {
+        scopes.push(format!("{}-{}", stack, language));
+    }
+    if let (Some(language), Some(layer)) = (language, layer) {
+        scopes.push(format!("{}-{}", language, layer));
+    }
+    if let (Some(stack), Some(language), Some(layer)) = (stack, language, layer) {
+        scopes.push(format!("{}-{}-{}", stack, language, layer));
+    }
+    if let Some(tags) = project.get("tags").and_then(|t| t.as_sequence()) {
+        scopes.extend(
+            tags.iter()
+                .filter_map(|t| t.as_str())
+                .map(|t| format!("tag-{}", t)),
+        );
+    }
+
+    let mut files = vec![PathBuf::from(GLOBAL_TASKS_FILE)];
+    files.extend(
+        scopes
+            .iter()
+            .map(|scope| Path::new(SCOPED_TASKS_DIR).join(format!("{}.yml", scope))),
+    );
+    files
+}
+
+/// Tasks a project inherits, after `workspace.inheritedTasks` include/exclude filtering
+pub fn inherited_tasks(project: &Value) -> BTreeMap<String, Value> {
+    inherited_tasks_with(project, &[])
+}
+
+/// [`inherited_tasks`] as if the `pending` inheritance files had already been written
+fn inherited_tasks_with(project: &Value, pending: &[(PathBuf, String)]) -> BTreeMap<String, Value> {
+    let mut tasks = BTreeMap::new();
+    for file in inheritance_files(project) {
+        let config = match pending.iter().find(|(path, _)| *path == file) {
+            Some((_, content)) => serde_yaml::from_str(content).ok(),
+            None => read_yaml(&file),
+        };
+        let Some(file_tasks) =
+            config.and_then(|config| config.get("tasks").and_then(|t| t.as_mapping()).cloned())
+        else {
+            continue;
+        };
+        for (name, task) in file_tasks {
+            if let Some(name) = name.as_str() {
+                tasks.insert(name.to_string(), task);
+            }
+        }
+    }
+
+    let inherited = project
+        .get("workspace")
+        .and_then(|w| w.get("inheritedTasks"));
+    let names = |key: &str| -> Option<Vec<String>> {
+        inherited
+            .and_then(|i| i.get(key))
+            .and_then(|list| list.as_sequence())
+            .map(|list| {
+                list.iter()
+                    .filter_map(|n| n.as_str().map(String::from))
+                    .collect()
+            })
+    };
+    if let Some(include) = names("include") {
+        tasks.retain(|name, _| include.contains(name));
+    }
+    if let Some(exclude) = names("exclude") {
+        tasks.retain(|name, _| !exclude.contains(name));
+    }
+
+    tasks
+}
+
+/// Result of slimming one project's moon.yml down to overrides
+#[derive(Debug, Default)]
+pub struct TaskMigration {
+    /// Tasks removed because the inherited definition runs the same command
+    pub removed: Vec<String>,
+    /// Tasks kept because they customize the inherited command
+    pub kept: Vec<String>,
+    /// Whether a `sources` file group was added for the inherited inputs
+    pub added_sources: bool,
+    /// The moon.yml before and after, when anything changed
+    pub content: Option<(String, String)>,
+}
+
+impl TaskMigration {
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && !self.added_sources
+    }
+}
+
+/// Work out which tasks in a project's moon.yml only repeat what it inherits, once the
+/// `managed` inheritance files are written, and the moon.yml without them. Inputs of removed
+/// tasks become the project's `sources` file group, which the inherited tasks reference.
+pub fn migrate_project(moon_yml: &Path, managed: &[(PathBuf, String)]) -> Result<TaskMigration> {
+    let content = fs::read_to_string(moon_yml)
+        .with_context(|| format!("Failed to read {}", moon_yml.display()))?;
+    let mut config: Value = serde_yaml::from_str(&content)
+        .with_context(|| format!("Invalid {}", moon_yml.display()))?;
+
+    let inherited = inherited_tasks_with(&config, managed);
+    let mut migration = TaskMigration::default();
+    let mut sources: Vec<Value> = Vec::new();
+
+    if let Some(tasks) = config.get_mut("tasks").and_then(|t| t.as_mapping_mut()) {
+        let names: Vec<String> = tasks
+            .keys()
+            .filter_map(|k| k.as_str().map(String::from))
+            .collect();
+        for name in names {
+            let Some(parent) = inherited.get(&name) else {
+                continue;
+            };
+            let task = &tasks[name.as_str()];
+            if !same_command(task, parent) {
+                migration.kept.push(name);
+                continue;
+            }
+
+            if uses_sources_group(parent) {
+                for input in string_list(task.get("inputs")) {
+                    let input = Value::String(input);
+                    if !string_list(parent.get("inputs"))
+                        .contains(&input.as_str().unwrap_or_default().to_string())
+                        && !sources.contains(&input)
+                    {
+                        sources.push(input);
+                    }
+                }
+            }
+            tasks.remove(name.as_str());
+            migration.removed.push(name);
+        }
+    }
+
+    if !sources.is_empty()
+        && let Some(mapping) = config.as_mapping_mut()
+    {
+        let groups = mapping
+            .entry(Value::String("fileGroups".to_string()))
+            .or_insert_with(|| Value::Mapping(Mapping::new()));
+        if let Some(groups) = groups.as_mapping_mut()
+            && !groups.contains_key("sources")
+        {
+            groups.insert(
+                Value::String("sources".to_string()),
+                Value::Sequence(sources),
+            );
+            migration.added_sources = true;
+        }
+    }
+
+    if !migration.is_empty() {
+        migration.content = Some((content, serde_yaml::to_string(&config)?));
+    }
+    Ok(migration)
+}
+
+/// Tasks are interchangeable when they run the same command, arguments and script
+fn same_command(task: &Value, parent: &Value) -> bool {
+    ["command", "args", "script"]
+        .iter()
+        .all(|key| task.get(key) == parent.get(key))
+}
+
+fn uses_sources_group(task: &Value) -> bool {
+    string_list(task.get("inputs"))
+        .iter()
+        .any(|input| input == "@group(sources)")
+}
+
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_sequence())
+        .map(|list| {
+            list.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn read_yaml(path: &Path) -> Option<Value> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_yaml::from_str(&content).ok())
+}
@@ -0,0 +1,126 @@
+use crate::templates::{embedded, engine::TemplateEngine};
+use crate::utils::config::MoonflareConfig;
+use crate::utils::toolchain::{GENERATED_MARKER, Toolchain, stale};
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The flake `nix develop` enters, at the workspace root
+pub const FLAKE_FILE: &str = "flake.nix";
+
+/// Leading major version of `version`, if it starts with one
+fn major(version: &str) -> Option<&str> {
+    let digits = version
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?;
+    (!digits.is_empty()).then_some(digits)
+}
+
+/// nixpkgs attribute for the Node.js release line `version` belongs to
+pub fn node_package(version: &str) -> String {
+    match (version, major(version)) {
+        ("lts", _) => "nodejs".to_string(),
+        (_, Some(major)) => format!("nodejs_{}", major),
+        _ => "nodejs_latest".to_string(),
+    }
+}
+
+/// nixpkgs attribute for the pnpm release line `version` belongs to
+pub fn pnpm_package(version: &str) -> String {
+    match major(version) {
+        Some(major) => format!("pnpm_{}", major),
+        None => "pnpm".to_string(),
+    }
+}
+
+/// rust-overlay expression for a rust-toolchain channel: `stable`, `beta`, `nightly`,
+/// `nightly-<date>` or a release like `1.80` or `1.80.0`
+pub fn rust_toolchain(channel: &str) -> String {
+    match channel {
+        "stable" | "latest" => "pkgs.rust-bin.stable.latest.default".to_string(),
+        "beta" => "pkgs.rust-bin.beta.latest.default".to_string(),
+        "nightly" => {
+            "pkgs.rust-bin.selectLatestNightlyWith (toolchain: toolchain.default)".to_string()
+        }
+        _ => match channel.strip_prefix("nightly-") {
+            Some(date) => format!("pkgs.rust-bin.nightly.\"{}\".default", date),
+            None => {
+                let release = if channel.matches('.').count() == 1 {
+                    format!("{}.0", channel)
+                } else {
+                    channel.to_string()
+                };
+                format!("pkgs.rust-bin.stable.\"{}\".default", release)
+            }
+        },
+    }
+}
+
+/// Render the flake for the workspace at the current directory
+pub fn render(config: &MoonflareConfig) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let toolchain = Toolchain::detect(Path::new("."));
+    let name = config.workspace.name.clone().unwrap_or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "moonflare".to_string())
+    });
+
+    let mut context = HashMap::new();
+    context.insert("name".to_string(), Value::String(name));
+    context.insert(
+        "moonflare_version".to_string(),
+        Value::String(env!("CARGO_PKG_VERSION").to_string()),
+    );
+    context.insert("node".to_string(), json!(toolchain.node));
+    context.insert(
+        "node_package".to_string(),
+        json!(node_package(&toolchain.node)),
+    );
+    context.insert("pnpm".to_string(), json!(toolchain.pnpm));
+    context.insert(
+        "pnpm_package".to_string(),
+        json!(pnpm_package(&toolchain.pnpm)),
+    );
+    context.insert("rust".to_string(), json!(toolchain.rust));
+    context.insert(
+        "rust_toolchain".to_string(),
+        json!(rust_toolchain(&toolchain.rust)),
+    );
+    context.insert("moon".to_string(), json!(toolchain.moon));
+    context.insert("wrangler".to_string(), json!(toolchain.wrangler));
+
+    TemplateEngine::new().render_template_to_memory(embedded::NIX_TEMPLATE, &context)
+}
+
+/// Write the flake, returning its path
+pub fn write(config: &MoonflareConfig) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for (path, content) in render(config)? {
+        fs::write(&path, content)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Whether moonflare wrote the workspace's flake
+pub fn is_generated() -> bool {
+    fs::read_to_string(FLAKE_FILE).is_ok_and(|content| {
+        content
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .contains(GENERATED_MARKER)
+    })
+}
+
+/// The flake, when moonflare wrote it and it no longer matches the toolchain versions
+pub fn stale_files(config: &MoonflareConfig) -> Result<Vec<PathBuf>> {
+    if !is_generated() {
+        return Ok(Vec::new());
+    }
+    Ok(stale(render(config)?))
+}
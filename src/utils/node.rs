@@ -0,0 +1,185 @@
+use crate::utils::projects::find_project;
+use crate::utils::version::Version;
+use anyhow::{Result, bail};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use which::which;
+
+/// Node.js versions generated projects declare in `engines`; Vite 6 and wrangler 4 need 20+
+pub const NODE_ENGINE: &str = ">=20.0.0";
+
+/// Set to skip the Node.js version check in `build` and `dev`
+pub const SKIP_CHECK_ENV: &str = "MOONFLARE_SKIP_NODE_CHECK";
+
+/// A Node.js requirement and where it was declared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeRequirement {
+    pub range: String,
+    pub source: String,
+}
+
+/// The `engines.node` range of the workspace package.json, narrowed by the project's own
+/// package.json when one is given
+pub fn requirements(project_path: Option<&Path>) -> Vec<NodeRequirement> {
+    let mut manifests = vec![Path::new("package.json").to_path_buf()];
+    if let Some(path) = project_path {
+        manifests.push(path.join("package.json"));
+    }
+
+    manifests
+        .into_iter()
+        .filter_map(|manifest| {
+            let content = fs::read_to_string(&manifest).ok()?;
+            let package: serde_json::Value = serde_json::from_str(&content).ok()?;
+            Some(NodeRequirement {
+                range: package["engines"]["node"].as_str()?.to_string(),
+                source: manifest.display().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Version reported by the `node` on PATH, or None when Node.js isn't installed
+pub fn active_version() -> Option<Version> {
+    let node = which("node").ok()?;
+    let output = Command::new(node).arg("--version").output().ok()?;
+    Version::parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Whether `version` satisfies an npm-style range: `||`-separated sets of space-separated
+/// comparators (`>=`, `>`, `<=`, `<`, `=`, `^`, `~`, `x` wildcards). Unparseable ranges pass.
+pub fn satisfies(version: Version, range: &str) -> bool {
+    range.split("||").any(|set| {
+        set.split_whitespace()
+            .all(|comparator| comparator_matches(version, comparator).unwrap_or(true))
+    })
+}
+
+fn comparator_matches(version: Version, comparator: &str) -> Option<bool> {
+    let (operator, rest) = match comparator {
+        c if c.starts_with(">=") || c.starts_with("<=") => c.split_at(2),
+        c if c.starts_with(['>', '<', '=', '^', '~']) => c.split_at(1),
+        c => ("", c),
+    };
+    if matches!(rest, "*" | "x" | "X" | "") {
+        return Some(true);
+    }
+
+    // Parts given explicitly; `20`, `20.x` and `20.1.x` only constrain the leading parts
+    let parts = rest
+        .trim_start_matches('v')
+        .split('.')
+        .take_while(|part| part.parse::<u64>().is_ok())
+        .count();
+    let bound = Version::parse(rest.trim_end_matches(".x").trim_end_matches(".X"))?;
+    let upper = match parts {
+        1 => Version::parse(&format!("{}.0.0", bound.major + 1))?,
+        2 => Version::parse(&format!("{}.{}.0", bound.major, bound.minor + 1))?,
+        _ => Version::parse(&format!(
+            "{}.{}.{}",
+            bound.major,
+            bound.minor,
+            bound.patch + 1
+        ))?,
+    };
+
+    Some(match operator {
+        ">=" => version >= bound,
+        ">" => version >= upper,
+        "<=" => version < upper,
+        "<" => version < bound,
+        "^" => {
+            let caret_upper = match (bound.major, bound.minor) {
+                (0, 0) if parts >= 3 => upper,
+                (0, minor) if parts >= 2 => Version::parse(&format!("0.{}.0", minor + 1))?,
+                (major, _) => Version::parse(&format!("{}.0.0", major + 1))?,
+            };
+            version >= bound && version < caret_upper
+        }
+        "~" => {
+            let tilde_upper = if parts == 1 {
+                upper
+            } else {
+                Version::parse(&format!("{}.{}.0", bound.major, bound.minor + 1))?
+            };
+            version >= bound && version < tilde_upper
+        }
+        _ => version >= bound && version < upper,
+    })
+}
+
+/// Fail with install guidance when the active Node.js doesn't satisfy the workspace's (and
+/// project's) `engines.node`. Skipped when Node.js isn't on PATH, since Moon may provide it.
+pub fn check_node_version(project_path: Option<&Path>) -> Result<()> {
+    if std::env::var_os(SKIP_CHECK_ENV).is_some() {
+        return Ok(());
+    }
+    let Some(active) = active_version() else {
+        return Ok(());
+    };
+
+    let unmet: Vec<NodeRequirement> = requirements(project_path)
+        .into_iter()
+        .filter(|requirement| !satisfies(active, &requirement.range))
+        .collect();
+    if unmet.is_empty() {
+        return Ok(());
+    }
+
+    let declared = unmet
+        .iter()
+        .map(|r| format!("  \"node\": \"{}\" in {}", r.range, r.source))
+        .collect::<Vec<_>>()
+        .join("\n");
+    bail!(
+        "Node.js v{} doesn't satisfy the engines this workspace declares:\n{}\n\nVite and wrangler fail with confusing errors on unsupported Node.js versions.\n{}\n\nSet {}=1 to skip this check.",
+        active,
+        declared,
+        install_guidance(),
+        SKIP_CHECK_ENV
+    )
+}
+
+/// `check_node_version` for a command targeting `project`, or the whole workspace without
+/// one. Crates are built with cargo, so they only answer to the workspace requirement.
+pub fn check_project_node_version(project: Option<&str>) -> Result<()> {
+    let project_path = project
+        .and_then(find_project)
+        .filter(|p| !p.is_crate())
+        .map(|p| p.path);
+    check_node_version(project_path.as_deref())
+}
+
+/// How to install a supported Node.js, preferring proto when it manages the toolchain
+pub fn install_guidance() -> String {
+    let major = Version::parse(NODE_ENGINE.trim_start_matches(">="))
+        .map(|v| v.major)
+        .unwrap_or(20);
+    if which("proto").is_ok() {
+        let pinned = fs::read_to_string(".prototools")
+            .ok()
+            .and_then(|content| content.parse::<toml::Table>().ok())
+            .and_then(|tools| {
+                tools
+                    .get("node")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            });
+        match pinned {
+            Some(version) => format!(
+                "proto pins node {} in .prototools. Install it with 'proto install node', or pin a newer version with 'proto pin node {} --to local'.",
+                version, major
+            ),
+            None => format!(
+                "Install and pin a supported version with proto:\n  proto install node {}\n  proto pin node {} --to local",
+                major, major
+            ),
+        }
+    } else {
+        format!(
+            "Install Node.js {} or newer with proto (https://moonrepo.dev/proto), nvm ('nvm install {}') or from https://nodejs.org.",
+            major, major
+        )
+    }
+}
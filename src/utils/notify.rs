@@ -0,0 +1,161 @@
+use crate::utils::config::NotifyConfig;
+use crate::utils::http;
+use crate::utils::projects::WorkspaceProject;
+use anyhow::{Context, Result, bail};
+use serde_json::{Value, json};
+use std::fs;
+use std::time::Duration;
+
+const SUCCESS_TEMPLATE: &str =
+    "✅ Deployed {projects} to {env} in {duration} ({sha}, triggered by {actor})";
+const FAILURE_TEMPLATE: &str = "❌ Deploy to {env} failed after {duration}: {error}\nProjects: {projects} ({sha}, triggered by {actor})";
+
+/// How long to wait for the webhook before giving up on the notification
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One project's part of a deploy
+#[derive(Debug, Clone)]
+pub struct ProjectDeploy {
+    pub name: String,
+    /// `version` from the project's package.json
+    pub version: Option<String>,
+    pub duration: Duration,
+    pub success: bool,
+}
+
+impl ProjectDeploy {
+    pub fn new(project: &WorkspaceProject, duration: Duration, success: bool) -> Self {
+        let version = fs::read_to_string(project.path.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .and_then(|package| package["version"].as_str().map(str::to_string));
+        Self {
+            name: project.name.clone(),
+            version,
+            duration,
+            success,
+        }
+    }
+
+    fn describe(&self) -> String {
+        let mut description = match &self.version {
+            Some(version) => format!("{}@{}", self.name, version),
+            None => self.name.clone(),
+        };
+        description.push_str(&format!(" ({:.1}s)", self.duration.as_secs_f64()));
+        if !self.success {
+            description.push_str(" failed");
+        }
+        description
+    }
+}
+
+/// What a finished `moonflare deploy` reports to the webhook
+#[derive(Debug)]
+pub struct DeploySummary {
+    pub environment: Option<String>,
+    pub projects: Vec<ProjectDeploy>,
+    pub git_sha: Option<String>,
+    pub triggered_by: String,
+    pub duration: Duration,
+    /// Why the deploy failed; `None` when it succeeded
+    pub error: Option<String>,
+}
+
+impl DeploySummary {
+    pub fn success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Fill `{status}`, `{env}`, `{projects}`, `{duration}`, `{sha}`, `{actor}` and `{error}`
+    pub fn render(&self, template: Option<&str>) -> String {
+        let template = template.unwrap_or(if self.success() {
+            SUCCESS_TEMPLATE
+        } else {
+            FAILURE_TEMPLATE
+        });
+        let projects = if self.projects.is_empty() {
+            "no projects".to_string()
+        } else {
+            self.projects
+                .iter()
+                .map(ProjectDeploy::describe)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let sha = self
+            .git_sha
+            .as_deref()
+            .map(|sha| &sha[..sha.len().min(7)])
+            .unwrap_or("no commit");
+
+        template
+            .replace(
+                "{status}",
+                if self.success() {
+                    "succeeded"
+                } else {
+                    "failed"
+                },
+            )
+            .replace("{env}", self.environment.as_deref().unwrap_or("default"))
+            .replace("{projects}", &projects)
+            .replace(
+                "{duration}",
+                &format!("{:.1}s", self.duration.as_secs_f64()),
+            )
+            .replace("{sha}", sha)
+            .replace("{actor}", &self.triggered_by)
+            .replace("{error}", self.error.as_deref().unwrap_or_default())
+    }
+
+    /// Request body for the webhook: Slack and Discord take just the rendered message, other
+    /// webhooks get it alongside the structured summary
+    fn payload(&self, webhook: &str, template: Option<&str>) -> Value {
+        let text = self.render(template);
+        if webhook.contains("hooks.slack.com") {
+            return json!({ "text": text });
+        }
+        if webhook.contains("discord.com/api/webhooks")
+            || webhook.contains("discordapp.com/api/webhooks")
+        {
+            return json!({ "content": text });
+        }
+
+        json!({
+            "text": text,
+            "success": self.success(),
+            "environment": self.environment,
+            "projects": self.projects.iter().map(|p| json!({
+                "name": p.name,
+                "version": p.version,
+                "duration_secs": p.duration.as_secs_f64(),
+                "success": p.success,
+            })).collect::<Vec<_>>(),
+            "git_sha": self.git_sha,
+            "triggered_by": self.triggered_by,
+            "duration_secs": self.duration.as_secs_f64(),
+            "error": self.error,
+        })
+    }
+}
+
+/// Post the summary to the configured webhook
+pub async fn send(config: &NotifyConfig, summary: &DeploySummary) -> Result<()> {
+    let Some(webhook) = config.webhook_url()? else {
+        bail!("No webhook is configured under [deploy.notify] in moonflare.toml");
+    };
+
+    let response = http::builder()?
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()?
+        .post(&webhook)
+        .json(&summary.payload(&webhook, config.template.as_deref()))
+        .send()
+        .await
+        .context("Failed to reach the notification webhook")?;
+    if !response.status().is_success() {
+        bail!("Notification webhook responded with {}", response.status());
+    }
+    Ok(())
+}
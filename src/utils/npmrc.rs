@@ -0,0 +1,158 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::diagnostics::Diagnostic;
+use std::collections::BTreeMap;
+
+pub const NPMRC_FILE: &str = ".npmrc";
+
+/// `.npmrc` keys managed from the `[pnpm]` section of moonflare.toml
+pub const HOISTING_KEYS: [&str; 4] = [
+    "shamefully-hoist",
+    "public-hoist-pattern",
+    "hoist",
+    "node-linker",
+];
+
+const MANAGED_HEADER: &str = "# Hoisting managed by moonflare (moonflare.toml [pnpm])";
+
+/// Hoisting settings in `.npmrc` form; list settings such as `public-hoist-pattern[]`
+/// keep one value per entry
+pub type HoistingSettings = BTreeMap<String, Vec<String>>;
+
+/// Settings the workspace `.npmrc` should contain for the given configuration
+pub fn expected_settings(config: &MoonflareConfig) -> HoistingSettings {
+    let mut settings = HoistingSettings::new();
+
+    if config.pnpm.shamefully_hoist {
+        settings.insert("shamefully-hoist".to_string(), vec!["true".to_string()]);
+    }
+    if let Some(hoist) = config.pnpm.hoist {
+        settings.insert("hoist".to_string(), vec![hoist.to_string()]);
+    }
+    if let Some(linker) = &config.pnpm.node_linker {
+        settings.insert("node-linker".to_string(), vec![linker.clone()]);
+    }
+
+    let mut patterns: Vec<String> = config.pnpm.public_hoist_pattern.clone();
+    for project in config.projects.values() {
+        for pattern in &project.pnpm.public_hoist_pattern {
+            if !patterns.contains(pattern) {
+                patterns.push(pattern.clone());
+            }
+        }
+    }
+    if !patterns.is_empty() {
+        settings.insert("public-hoist-pattern".to_string(), patterns);
+    }
+
+    settings
+}
+
+/// Hoisting settings present in `.npmrc` content
+pub fn parse_settings(content: &str) -> HoistingSettings {
+    let mut settings = HoistingSettings::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().trim_end_matches("[]");
+        if !HOISTING_KEYS.contains(&key) {
+            continue;
+        }
+
+        let value = value.trim().to_string();
+        let values = settings.entry(key.to_string()).or_default();
+        if key == "public-hoist-pattern" {
+            if !value.is_empty() {
+                values.push(value);
+            }
+        } else {
+            *values = vec![value];
+        }
+    }
+    settings
+}
+
+/// `existing` with its hoisting settings replaced by `settings`. Everything else (registries,
+/// auth tokens, ...) is kept as is.
+pub fn render(existing: &str, settings: &HoistingSettings) -> String {
+    let mut content: String = existing
+        .lines()
+        .filter(|line| *line != MANAGED_HEADER)
+        .filter(|line| {
+            let key = line
+                .split_once('=')
+                .map(|(key, _)| key.trim().trim_end_matches("[]"));
+            !key.is_some_and(|key| HOISTING_KEYS.contains(&key))
+        })
+        .map(|line| format!("{}\n", line))
+        .collect();
+
+    while content.ends_with("\n\n") {
+        content.pop();
+    }
+    if settings.is_empty() {
+        return content;
+    }
+
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content.push_str(MANAGED_HEADER);
+    content.push('\n');
+    for (key, values) in settings {
+        if key == "public-hoist-pattern" {
+            for value in values {
+                content.push_str(&format!("{}[]={}\n", key, value));
+            }
+        } else {
+            content.push_str(&format!("{}={}\n", key, values.join(",")));
+        }
+    }
+    content
+}
+
+/// Known-bad combinations in the root `.npmrc` settings
+pub fn check_settings(settings: &HoistingSettings) -> Vec<Diagnostic> {
+    let mut issues = Vec::new();
+    let value = |key: &str| {
+        settings
+            .get(key)
+            .and_then(|v| v.first())
+            .map(String::as_str)
+    };
+    let patterns = settings
+        .get("public-hoist-pattern")
+        .map(Vec::len)
+        .unwrap_or_default();
+
+    match value("node-linker") {
+        Some("pnp") => issues.push(Diagnostic::error(
+            "node-linker=pnp is not supported by wrangler or the Astro/Vite toolchain; use isolated or hoisted",
+        )),
+        Some("hoisted") if value("shamefully-hoist") == Some("true") || patterns > 0 => {
+            issues.push(Diagnostic::warning(
+                "node-linker=hoisted already produces a flat node_modules; shamefully-hoist and public-hoist-pattern have no effect",
+            ))
+        }
+        _ => {}
+    }
+
+    if value("shamefully-hoist") == Some("true") && patterns > 0 {
+        issues.push(Diagnostic::warning(
+            "shamefully-hoist=true hoists everything, so public-hoist-pattern entries are redundant",
+        ));
+    }
+
+    if value("hoist") == Some("false") && value("shamefully-hoist") != Some("true") && patterns == 0
+    {
+        issues.push(Diagnostic::warning(
+            "hoist=false without public-hoist-pattern hides undeclared peer dependencies that wrangler's bundler resolves at build time",
+        ));
+    }
+
+    issues
+}
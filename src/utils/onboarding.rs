@@ -0,0 +1,242 @@
+use crate::templates::{embedded, engine::TemplateEngine};
+use crate::ui::MoonflareUI;
+use crate::utils::auth;
+use crate::utils::projects::discover_projects;
+use anyhow::Result;
+use colored::*;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Project types offered when adding the first project from the checklist
+const FIRST_PROJECT_TYPES: [&str; 4] = ["react", "astro", "durable-object", "crate"];
+
+/// What a checklist step runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    InstallDependencies,
+    AddProject,
+    Dev,
+    WranglerLogin,
+    SetupCi,
+}
+
+impl Action {
+    const ALL: [Action; 5] = [
+        Action::InstallDependencies,
+        Action::AddProject,
+        Action::Dev,
+        Action::WranglerLogin,
+        Action::SetupCi,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Action::InstallDependencies => "Install dependencies",
+            Action::AddProject => "Add your first project",
+            Action::Dev => "Start the dev servers",
+            Action::WranglerLogin => "Log in to Cloudflare",
+            Action::SetupCi => "Set up CI",
+        }
+    }
+
+    fn command(self) -> &'static str {
+        match self {
+            Action::InstallDependencies => "pnpm install",
+            Action::AddProject => "moonflare add <type> <name>",
+            Action::Dev => "moonflare dev",
+            Action::WranglerLogin => "npx wrangler login",
+            Action::SetupCi => "moonflare ci generate",
+        }
+    }
+
+    /// Whether the step's result is already in the workspace in the current directory
+    fn is_done(self) -> bool {
+        match self {
+            Action::InstallDependencies => Path::new("node_modules").is_dir(),
+            Action::AddProject => !discover_projects().is_empty(),
+            // Nothing is left behind by running it; the checklist remembers it instead
+            Action::Dev => false,
+            Action::WranglerLogin => auth::logged_in(),
+            Action::SetupCi => TemplateEngine::template_file_paths(embedded::CI_TEMPLATE)
+                .iter()
+                .any(|file| Path::new(file).exists()),
+        }
+    }
+}
+
+/// Show the steps from a fresh workspace at `root` to a first deploy. On an interactive
+/// terminal each step can be run from the checklist; otherwise it's printed as a reference.
+pub fn run_checklist(root: &Path) -> Result<()> {
+    let root = root.canonicalize()?;
+    let original_dir = std::env::current_dir()?;
+    std::env::set_current_dir(&root)?;
+    let result = checklist_loop(&root);
+    std::env::set_current_dir(original_dir)?;
+    result
+}
+
+fn checklist_loop(root: &Path) -> Result<()> {
+    let ui = MoonflareUI::new();
+    let interactive = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    let mut ran: Vec<Action> = Vec::new();
+
+    loop {
+        let done: Vec<bool> = Action::ALL
+            .iter()
+            .map(|action| ran.contains(action) || action.is_done())
+            .collect();
+        ui.render_checklist(
+            "Get started",
+            Action::ALL
+                .iter()
+                .zip(&done)
+                .map(|(action, done)| (*done, action.title(), action.command()))
+                .collect(),
+        )
+        .map_err(|e| anyhow::anyhow!("UI render error: {}", e))?;
+
+        let next = done.iter().position(|done| !done);
+        if !interactive {
+            return Ok(());
+        }
+        let Some(next) = next else {
+            println!("{}", "All set. Deploy with 'moonflare deploy'.".green());
+            return Ok(());
+        };
+
+        print!(
+            "{} ",
+            format!(
+                "Enter runs step {}, a number runs that step, q finishes:",
+                next + 1
+            )
+            .dimmed()
+        );
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if std::io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let input = line.trim();
+        let action = match input {
+            "" => Action::ALL[next],
+            "q" | "quit" => return Ok(()),
+            _ => match input
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| Action::ALL.get(i))
+            {
+                Some(action) => *action,
+                None => {
+                    println!(
+                        "{}",
+                        format!("Pick a step from 1 to {}", Action::ALL.len()).yellow()
+                    );
+                    continue;
+                }
+            },
+        };
+
+        if run_action(action, root)? {
+            ran.push(action);
+        }
+    }
+}
+
+/// Run a step in the foreground. Returns whether it succeeded; a failure is reported and
+/// leaves the step open.
+fn run_action(action: Action, root: &Path) -> Result<bool> {
+    let moonflare = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("moonflare"));
+    let mut command = match action {
+        Action::InstallDependencies => {
+            let mut command = Command::new("pnpm");
+            command.arg("install");
+            command
+        }
+        Action::AddProject => {
+            let Some((project_type, name)) = ask_for_project()? else {
+                return Ok(false);
+            };
+            let mut command = Command::new(&moonflare);
+            command.args(["add", &project_type, &name]);
+            command
+        }
+        Action::Dev => {
+            if discover_projects().is_empty() {
+                println!(
+                    "{}",
+                    "Add a project first; there's nothing to serve yet.".yellow()
+                );
+                return Ok(false);
+            }
+            let mut command = Command::new(&moonflare);
+            command.arg("dev");
+            command
+        }
+        Action::WranglerLogin => {
+            let mut command = Command::new("npx");
+            command.args(["wrangler", "login"]);
+            command
+        }
+        Action::SetupCi => {
+            let mut command = Command::new(&moonflare);
+            command.args(["ci", "generate"]);
+            command
+        }
+    };
+
+    println!("{} {}", "→".cyan(), action.command().bold());
+    match command.current_dir(root).status() {
+        Ok(status) if status.success() => {
+            if action == Action::WranglerLogin {
+                auth::invalidate()?;
+            }
+            Ok(true)
+        }
+        Ok(status) => {
+            println!(
+                "{}",
+                format!(
+                    "'{}' exited with {}; the step stays open",
+                    action.command(),
+                    status
+                )
+                .yellow()
+            );
+            Ok(false)
+        }
+        Err(e) => {
+            println!(
+                "{}",
+                format!("Couldn't run '{}': {}", action.command(), e).yellow()
+            );
+            Ok(false)
+        }
+    }
+}
+
+/// Ask for the type and name of the first project; `None` when either is left empty
+fn ask_for_project() -> Result<Option<(String, String)>> {
+    let ask = |prompt: &str| -> Result<String> {
+        print!("{} ", prompt);
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        std::io::stdin().lock().read_line(&mut line)?;
+        Ok(line.trim().to_string())
+    };
+
+    let project_type = ask(&format!(
+        "Project type ({}, or another 'moonflare add' type):",
+        FIRST_PROJECT_TYPES.join(", ")
+    ))?;
+    if project_type.is_empty() {
+        return Ok(None);
+    }
+    let name = ask("Project name:")?;
+    if name.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some((project_type, name)))
+}
@@ -0,0 +1,244 @@
+use crate::utils::diagnostics::Diagnostic;
+use crate::utils::projects::{project_directories, subdirectories};
+use crate::utils::wrangler::{binding_names, read_wrangler_config};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Field of a project's package.json that replaces its moon.yml
+pub const PACKAGE_FIELD: &str = "moonflare";
+
+/// First line of every moon.yml generated from package.json; files without it are hand-written
+/// and never overwritten
+const GENERATED_HEADER: &str =
+    "# Generated by moonflare from package.json#moonflare; edit package.json instead.";
+
+/// A project's tasks as JS-first teams write them in package.json:
+///
+/// ```json
+/// "moonflare": {
+///   "dev": "wrangler dev",
+///   "build": "tsc && wrangler deploy --dry-run --outdir dist",
+///   "deploy": "wrangler deploy",
+///   "tasks": { "typecheck": "tsc --noEmit" },
+///   "bindings": ["CACHE", "DB"]
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PackageConfig {
+    /// Project name Moon uses, when it isn't the directory name
+    pub id: Option<String>,
+    /// Moon stack; `backend` under the workers directory and `frontend` elsewhere by default
+    pub stack: Option<String>,
+    pub dev: Option<String>,
+    pub build: Option<String>,
+    pub deploy: Option<String>,
+    /// Further tasks, by name
+    pub tasks: BTreeMap<String, String>,
+    /// Bindings the code expects; `moonflare doctor` reports those the Wrangler config lacks
+    pub bindings: Vec<String>,
+}
+
+/// What [`sync_package_configs`] did
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// moon.yml files written from package.json
+    pub written: Vec<PathBuf>,
+    /// Hand-written moon.yml files left alone although package.json has a `moonflare` field
+    pub skipped: Vec<PathBuf>,
+    /// package.json files whose `moonflare` field doesn't parse
+    pub invalid: Vec<(PathBuf, String)>,
+}
+
+/// The `moonflare` field of `<dir>/package.json`, if it has one
+pub fn read_package_config(dir: &Path) -> Result<Option<PackageConfig>> {
+    let path = dir.join("package.json");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let package: serde_json::Value =
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+    let Some(field) = package.get(PACKAGE_FIELD) else {
+        return Ok(None);
+    };
+    serde_json::from_value(field.clone())
+        .map(Some)
+        .with_context(|| format!("Invalid \"{}\" field in {}", PACKAGE_FIELD, path.display()))
+}
+
+/// Whether moonflare wrote `moon_yml` from package.json
+pub fn is_generated(moon_yml: &Path) -> bool {
+    fs::read_to_string(moon_yml).is_ok_and(|content| content.starts_with(GENERATED_HEADER))
+}
+
+impl PackageConfig {
+    /// The moon.yml these settings translate to. Tasks run through the shell like npm scripts,
+    /// after the project's dependencies are installed; `deploy` builds first.
+    pub fn render(&self, default_stack: &str) -> String {
+        let mut yaml = format!(
+            "{}\n$schema: 'https://moonrepo.dev/schemas/project.json'\n",
+            GENERATED_HEADER
+        );
+        if let Some(id) = &self.id {
+            yaml.push_str(&format!("id: {}\n", quote(id)));
+        }
+        yaml.push_str(&format!(
+            "language: 'typescript'\nlayer: 'application'\nstack: {}\n",
+            quote(self.stack.as_deref().unwrap_or(default_stack))
+        ));
+
+        let mut tasks: Vec<(&str, String)> = Vec::new();
+        if let Some(script) = &self.dev {
+            tasks.push((
+                "dev",
+                format!(
+                    "    script: {}\n    local: true\n    deps:\n      - '~:install'\n",
+                    quote(script)
+                ),
+            ));
+        }
+        if let Some(script) = &self.build {
+            tasks.push((
+                "build",
+                format!(
+                    "    script: {}\n    inputs:\n      - 'src/**/*'\n      - 'package.json'\n      - 'tsconfig.*'\n      - 'wrangler.*'\n    outputs:\n      - 'dist'\n    deps:\n      - '~:install'\n",
+                    quote(script)
+                ),
+            ));
+        }
+        if let Some(script) = &self.deploy {
+            let deps = if self.build.is_some() {
+                "~:build"
+            } else {
+                "~:install"
+            };
+            tasks.push((
+                "deploy",
+                format!(
+                    "    script: {}\n    local: true\n    deps:\n      - '{}'\n",
+                    quote(script),
+                    deps
+                ),
+            ));
+        }
+        for (name, script) in &self.tasks {
+            tasks.push((
+                name,
+                format!(
+                    "    script: {}\n    deps:\n      - '~:install'\n",
+                    quote(script)
+                ),
+            ));
+        }
+
+        if !tasks.is_empty() {
+            yaml.push_str("\ntasks:\n");
+            let tasks: Vec<String> = tasks
+                .into_iter()
+                .map(|(name, body)| format!("  {}:\n{}", name, body))
+                .collect();
+            yaml.push_str(&tasks.join("\n"));
+        }
+        yaml
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Project directories with a package.json, whether or not they have a moon.yml yet
+fn candidate_directories() -> Vec<(PathBuf, &'static str)> {
+    let mut candidates = Vec::new();
+    for (directory, project_type) in project_directories() {
+        let stack = if project_type == "durable-object" {
+            "backend"
+        } else {
+            "frontend"
+        };
+        for path in subdirectories(Path::new(&directory)) {
+            if path.join("package.json").exists() {
+                candidates.push((path, stack));
+                continue;
+            }
+            for nested in subdirectories(&path) {
+                if nested.join("package.json").exists() {
+                    candidates.push((nested, stack));
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// Write a moon.yml for every project that describes itself in package.json#moonflare and
+/// whose moon.yml is missing or was generated before. Runs before every command, so edits
+/// to package.json take effect on the next run.
+pub fn sync_package_configs() -> SyncReport {
+    let mut report = SyncReport::default();
+    for (dir, stack) in candidate_directories() {
+        let config = match read_package_config(&dir) {
+            Ok(Some(config)) => config,
+            Ok(None) => continue,
+            Err(e) => {
+                report
+                    .invalid
+                    .push((dir.join("package.json"), format!("{:#}", e)));
+                continue;
+            }
+        };
+        let moon_yml = dir.join("moon.yml");
+        let existing = fs::read_to_string(&moon_yml).ok();
+        if existing.is_some() && !is_generated(&moon_yml) {
+            report.skipped.push(moon_yml);
+            continue;
+        }
+        let rendered = config.render(stack);
+        if existing.as_deref() != Some(rendered.as_str()) && fs::write(&moon_yml, rendered).is_ok()
+        {
+            report.written.push(moon_yml);
+        }
+    }
+    report
+}
+
+/// Problems with package.json#moonflare fields: ones that don't parse, ones a hand-written
+/// moon.yml overrides, and declared bindings the Wrangler config doesn't define
+pub fn package_config_issues() -> Vec<Diagnostic> {
+    let report = sync_package_configs();
+    let mut issues: Vec<Diagnostic> = report
+        .invalid
+        .iter()
+        .map(|(_, error)| Diagnostic::error(error.clone()))
+        .collect();
+    issues.extend(report.skipped.iter().map(|moon_yml| {
+        Diagnostic::warning(format!(
+            "{} wasn't generated by moonflare, so package.json#moonflare is ignored; delete it to generate it from package.json",
+            moon_yml.display()
+        ))
+    }));
+
+    for (dir, _) in candidate_directories() {
+        let Ok(Some(config)) = read_package_config(&dir) else {
+            continue;
+        };
+        if config.bindings.is_empty() {
+            continue;
+        }
+        let defined = match read_wrangler_config(&dir) {
+            Ok(Some(wrangler)) => binding_names(&wrangler),
+            _ => Default::default(),
+        };
+        for binding in config.bindings.iter().filter(|b| !defined.contains(*b)) {
+            issues.push(Diagnostic::warning(format!(
+                "{}/package.json expects binding '{}', but its Wrangler config doesn't define it",
+                dir.display(),
+                binding
+            )));
+        }
+    }
+    issues
+}
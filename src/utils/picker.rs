@@ -0,0 +1,138 @@
+use crate::utils::moon::query_projects;
+use crate::utils::projects::discover_projects;
+use anyhow::Result;
+use colored::*;
+use std::io::{BufRead, IsTerminal, Write};
+
+/// A project offered by the picker
+#[derive(Debug, Clone)]
+struct Choice {
+    id: String,
+    source: String,
+}
+
+/// Ask which project a command should run against when none was given. Typing narrows the
+/// list with a fuzzy match, a number picks from the list shown, and an empty line keeps the
+/// command's usual behaviour of running every project.
+///
+/// Returns `None` without prompting when stdin or stdout isn't a terminal, so scripts and CI
+/// behave as before, and when the workspace has fewer than two projects.
+pub async fn pick_project(command: &str) -> Result<Option<String>> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Ok(None);
+    }
+
+    // Moon knows every project, including ones outside the layout directories
+    let choices: Vec<Choice> = match query_projects().await {
+        Ok(projects) => projects
+            .into_iter()
+            .map(|p| Choice {
+                id: p.id,
+                source: p.source,
+            })
+            .collect(),
+        Err(_) => discover_projects()
+            .into_iter()
+            .map(|p| Choice {
+                source: p.relative_path(),
+                id: p.name,
+            })
+            .collect(),
+    };
+    if choices.len() < 2 {
+        return Ok(None);
+    }
+
+    println!(
+        "{}",
+        format!("Which project should 'moonflare {}' run?", command).bold()
+    );
+    let mut shown = choices.clone();
+    loop {
+        print_choices(&shown);
+        print!(
+            "{} ",
+            "Type to filter, a number to pick, or Enter for all projects:".dimmed()
+        );
+        std::io::stdout().flush()?;
+
+        let mut line = String::new();
+        if std::io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let input = line.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+        if let Ok(number) = input.parse::<usize>()
+            && let Some(choice) = number.checked_sub(1).and_then(|i| shown.get(i))
+        {
+            return Ok(Some(choice.id.clone()));
+        }
+        if let Some(choice) = choices.iter().find(|c| c.id == input) {
+            return Ok(Some(choice.id.clone()));
+        }
+
+        let matches = fuzzy_filter(&choices, input);
+        match matches.as_slice() {
+            [] => println!("{}", format!("No project matches '{}'", input).yellow()),
+            [choice] => {
+                println!("{} {}", "→".cyan(), choice.id.bold());
+                return Ok(Some(choice.id.clone()));
+            }
+            _ => shown = matches,
+        }
+    }
+}
+
+fn print_choices(choices: &[Choice]) {
+    let width = choices.iter().map(|c| c.id.len()).max().unwrap_or(0);
+    for (index, choice) in choices.iter().enumerate() {
+        println!(
+            "  {:>3}. {:<width$}  {}",
+            index + 1,
+            choice.id,
+            choice.source.dimmed(),
+            width = width
+        );
+    }
+}
+
+/// Projects whose id or source contains the query's characters in order, closest first:
+/// substring matches before scattered ones, then earlier and shorter matches
+fn fuzzy_filter(choices: &[Choice], query: &str) -> Vec<Choice> {
+    let query = query.to_lowercase();
+    let mut scored: Vec<((bool, usize, usize), &Choice)> = choices
+        .iter()
+        .filter_map(|choice| {
+            [&choice.id, &choice.source]
+                .into_iter()
+                .filter_map(|text| score(&text.to_lowercase(), &query))
+                .min()
+                .map(|score| (score, choice))
+        })
+        .collect();
+    scored.sort_by(|(a, x), (b, y)| a.cmp(b).then_with(|| x.id.cmp(&y.id)));
+    scored.into_iter().map(|(_, c)| c.clone()).collect()
+}
+
+/// Lower is better: whether the match is scattered, where it starts, and how far it spans
+fn score(text: &str, query: &str) -> Option<(bool, usize, usize)> {
+    if let Some(start) = text.find(query) {
+        return Some((false, start, query.len()));
+    }
+    let mut start = None;
+    let mut end = 0;
+    let mut chars = query.chars().peekable();
+    for (index, c) in text.char_indices() {
+        if chars.peek() == Some(&c) {
+            start.get_or_insert(index);
+            end = index;
+            chars.next();
+        }
+    }
+    if chars.peek().is_some() {
+        return None;
+    }
+    start.map(|start| (true, start, end - start + 1))
+}
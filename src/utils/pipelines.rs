@@ -0,0 +1,753 @@
+use crate::utils::wrangler::strip_jsonc;
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+use serde_yaml::{Mapping, Value as Yaml};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Directories never searched for projects
+const SKIPPED_DIRECTORIES: [&str; 8] = [
+    ".git",
+    "node_modules",
+    "target",
+    "dist",
+    ".turbo",
+    ".nx",
+    ".wrangler",
+    ".moonflare-c3",
+];
+
+/// How deep below the repository root projects are looked for
+const MAX_PROJECT_DEPTH: usize = 3;
+
+/// Turborepo task keys Moon has no use for: logging and environment passthrough
+const IGNORED_TURBO_KEYS: [&str; 4] = ["outputLogs", "outputMode", "passThroughEnv", "$schema"];
+
+/// Nx's input for every file in the project, used when a named input isn't defined
+const NX_DEFAULT_INPUT: &str = "default";
+
+/// A monorepo tool whose workspace `moonflare migrate` converts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonorepoTool {
+    Turbo,
+    Nx,
+}
+
+impl MonorepoTool {
+    pub fn label(self) -> &'static str {
+        match self {
+            MonorepoTool::Turbo => "Turborepo",
+            MonorepoTool::Nx => "Nx",
+        }
+    }
+
+    /// Workspace-level configuration holding the task pipeline
+    pub fn config_file(self) -> &'static str {
+        match self {
+            MonorepoTool::Turbo => "turbo.json",
+            MonorepoTool::Nx => "nx.json",
+        }
+    }
+
+    /// Whether a package.json dependency belongs to the tool, and goes with it
+    pub fn owns_dependency(self, dependency: &str) -> bool {
+        match self {
+            MonorepoTool::Turbo => dependency == "turbo",
+            MonorepoTool::Nx => {
+                dependency == "nx"
+                    || dependency.starts_with("@nx/")
+                    || dependency.starts_with("@nrwl/")
+            }
+        }
+    }
+
+    /// Whether a root script runs the tool, e.g. `turbo run build` or `nx run-many -t test`
+    pub fn runs_in_script(self, script: &str) -> bool {
+        let binary = match self {
+            MonorepoTool::Turbo => "turbo",
+            MonorepoTool::Nx => "nx",
+        };
+        script
+            .split(|c: char| c.is_whitespace() || c == '&' || c == ';' || c == '|')
+            .any(|word| word == binary)
+    }
+}
+
+/// A project of the workspace being migrated
+#[derive(Debug, Clone)]
+pub struct SourceProject {
+    /// Name the tool knows the project by: the package.json or project.json name
+    pub name: String,
+    /// Directory relative to the repository root
+    pub path: PathBuf,
+    /// Moon project id, the directory name
+    pub id: String,
+    /// package.json scripts
+    scripts: BTreeSet<String>,
+    /// Nx targets from project.json and the package.json `nx` field
+    targets: Map<String, Value>,
+    /// Nx projects this one depends on without importing them
+    implicit_dependencies: Vec<String>,
+}
+
+impl SourceProject {
+    /// Names of the tasks the project can run, from scripts and targets
+    fn task_names(&self) -> BTreeSet<String> {
+        self.scripts
+            .iter()
+            .chain(self.targets.keys())
+            .cloned()
+            .collect()
+    }
+}
+
+/// A Turborepo or Nx workspace's task pipeline and projects
+pub struct Pipeline {
+    pub tool: MonorepoTool,
+    config: Value,
+    pub projects: Vec<SourceProject>,
+    /// Workspace-level settings that couldn't be translated
+    pub notes: Vec<String>,
+}
+
+impl Pipeline {
+    /// Read the tool's configuration and find the projects below `root`
+    pub fn load(root: &Path, tool: MonorepoTool) -> Result<Self> {
+        let path = root.join(tool.config_file());
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let config: Value = serde_json::from_str(&strip_jsonc(&content))
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        let mut notes = Vec::new();
+        let projects = discover(root, tool, &mut notes)?;
+        let mut ids = BTreeMap::new();
+        for project in &projects {
+            if let Some(other) = ids.insert(project.id.clone(), project) {
+                bail!(
+                    "{} and {} would both become the Moon project '{}'; rename one first",
+                    other.path.display(),
+                    project.path.display(),
+                    project.id
+                );
+            }
+        }
+
+        if tool == MonorepoTool::Turbo {
+            if config.get("tasks").is_none() && config.get("pipeline").is_none() {
+                notes.push("turbo.json has no tasks".to_string());
+            }
+            for key in ["remoteCache", "globalPassThroughEnv", "globalDotEnv"] {
+                if config.get(key).is_some() {
+                    notes.push(format!("turbo.json: '{}' has no Moon equivalent", key));
+                }
+            }
+        } else if config.get("plugins").is_some() {
+            notes.push(
+                "nx.json: plugins infer targets Moon can't see; add tasks for them by hand"
+                    .to_string(),
+            );
+        }
+
+        Ok(Self {
+            tool,
+            config,
+            projects,
+            notes,
+        })
+    }
+
+    /// Moon tasks for the project, keyed by name; what couldn't be translated goes to `notes`
+    pub fn moon_tasks(&self, project: &SourceProject, notes: &mut Vec<String>) -> Mapping {
+        match self.tool {
+            MonorepoTool::Turbo => self.turbo_tasks(project, notes),
+            MonorepoTool::Nx => self.nx_tasks(project, notes),
+        }
+    }
+
+    /// Moon ids of the projects Nx's `implicitDependencies` points at
+    pub fn depends_on(&self, project: &SourceProject, notes: &mut Vec<String>) -> Vec<String> {
+        project
+            .implicit_dependencies
+            .iter()
+            .filter_map(|name| {
+                let id = self.id_of(name.trim_start_matches('!'));
+                if id.is_none() || name.starts_with('!') || name.contains('*') {
+                    notes.push(format!(
+                        "{}: implicit dependency '{}' wasn't translated",
+                        project.path.display(),
+                        name
+                    ));
+                    return None;
+                }
+                id
+            })
+            .collect()
+    }
+
+    fn id_of(&self, name: &str) -> Option<String> {
+        self.projects
+            .iter()
+            .find(|project| project.name == name || project.id == name)
+            .map(|project| project.id.clone())
+    }
+
+    fn turbo_tasks(&self, project: &SourceProject, notes: &mut Vec<String>) -> Mapping {
+        let tasks = self
+            .config
+            .get("tasks")
+            .or_else(|| self.config.get("pipeline"))
+            .and_then(Value::as_object);
+        let mut moon_tasks = Mapping::new();
+        let Some(tasks) = tasks else {
+            return moon_tasks;
+        };
+
+        for script in &project.scripts {
+            // `<package>#<task>` configures the task for one package only
+            let Some(config) = tasks
+                .get(&format!("{}#{}", project.name, script))
+                .or_else(|| tasks.get(script))
+            else {
+                continue;
+            };
+            let task = self.turbo_task(project, script, config, notes);
+            moon_tasks.insert(Yaml::from(script.as_str()), Yaml::Mapping(task));
+        }
+        moon_tasks
+    }
+
+    fn turbo_task(
+        &self,
+        project: &SourceProject,
+        name: &str,
+        config: &Value,
+        notes: &mut Vec<String>,
+    ) -> Mapping {
+        let mut task = Mapping::new();
+        task.insert("command".into(), "pnpm".into());
+        task.insert("args".into(), yaml_list(["run", name]));
+
+        let mut deps = Vec::new();
+        let mut env_inputs = Vec::new();
+        for dependency in string_list(config.get("dependsOn")) {
+            if let Some(variable) = dependency.strip_prefix('$') {
+                env_inputs.push(format!("${}", variable));
+            } else if let Some(upstream) = dependency.strip_prefix('^') {
+                deps.push(format!("^:{}", upstream));
+            } else if let Some((package, upstream)) = dependency.split_once('#') {
+                match self.id_of(package) {
+                    Some(id) => deps.push(format!("{}:{}", id, upstream)),
+                    None => notes.push(format!(
+                        "{}: '{}' depends on '{}', which isn't a project here",
+                        project.path.display(),
+                        name,
+                        dependency
+                    )),
+                }
+            } else if project.scripts.contains(&dependency) {
+                deps.push(format!("~:{}", dependency));
+            }
+        }
+        env_inputs.extend(
+            string_list(config.get("env"))
+                .iter()
+                .chain(&string_list(self.config.get("globalEnv")))
+                .map(|variable| format!("${}", variable.trim_start_matches('$'))),
+        );
+
+        let mut inputs: Vec<String> = string_list(config.get("inputs"))
+            .into_iter()
+            .map(|input| {
+                if input == "$TURBO_DEFAULT$" {
+                    "**/*".to_string()
+                } else {
+                    input
+                }
+            })
+            .collect();
+        let global_dependencies: Vec<String> = string_list(self.config.get("globalDependencies"))
+            .iter()
+            .map(|path| format!("/{}", path.trim_start_matches("./")))
+            .collect();
+        if !env_inputs.is_empty() || !global_dependencies.is_empty() {
+            // Moon hashes every project file only while `inputs` is unset
+            if inputs.is_empty() {
+                inputs.push("**/*".to_string());
+            }
+            inputs.extend(global_dependencies);
+            inputs.extend(env_inputs);
+        }
+        let outputs = string_list(config.get("outputs"));
+
+        if !deps.is_empty() {
+            task.insert("deps".into(), yaml_list(deps));
+        }
+        if !inputs.is_empty() {
+            task.insert("inputs".into(), yaml_list(inputs));
+        }
+        if !outputs.is_empty() {
+            task.insert("outputs".into(), yaml_list(outputs));
+        }
+
+        let mut options = Mapping::new();
+        if config.get("cache").and_then(Value::as_bool) == Some(false) {
+            options.insert("cache".into(), false.into());
+        }
+        if config.get("persistent").and_then(Value::as_bool) == Some(true) {
+            options.insert("persistent".into(), true.into());
+        }
+        if config.get("interactive").and_then(Value::as_bool) == Some(true) {
+            options.insert("interactive".into(), true.into());
+        }
+        if !options.is_empty() {
+            task.insert("options".into(), Yaml::Mapping(options));
+        }
+
+        for key in config.as_object().into_iter().flat_map(|c| c.keys()) {
+            let handled = [
+                "dependsOn",
+                "env",
+                "inputs",
+                "outputs",
+                "cache",
+                "persistent",
+                "interactive",
+            ];
+            if !handled.contains(&key.as_str()) && !IGNORED_TURBO_KEYS.contains(&key.as_str()) {
+                notes.push(format!(
+                    "{}: '{}' of task '{}' has no Moon equivalent",
+                    project.path.display(),
+                    key,
+                    name
+                ));
+            }
+        }
+        task
+    }
+
+    fn nx_tasks(&self, project: &SourceProject, notes: &mut Vec<String>) -> Mapping {
+        let defaults = self.config.get("targetDefaults").and_then(Value::as_object);
+        let task_names = project.task_names();
+        let mut moon_tasks = Mapping::new();
+
+        for name in &task_names {
+            let mut config = project
+                .targets
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| Value::Object(Map::new()));
+            // Defaults apply by target name or, failing that, by executor
+            let executor = config
+                .get("executor")
+                .and_then(Value::as_str)
+                .map(String::from);
+            let default = defaults.and_then(|defaults| {
+                defaults
+                    .get(name)
+                    .or_else(|| executor.as_deref().and_then(|e| defaults.get(e)))
+            });
+            if let (Some(Value::Object(default)), Value::Object(target)) = (default, &mut config) {
+                for (key, value) in default {
+                    target.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+            }
+
+            if let Some(task) = self.nx_task(project, name, &config, &task_names, notes) {
+                moon_tasks.insert(Yaml::from(name.as_str()), Yaml::Mapping(task));
+            }
+        }
+        moon_tasks
+    }
+
+    fn nx_task(
+        &self,
+        project: &SourceProject,
+        name: &str,
+        config: &Value,
+        task_names: &BTreeSet<String>,
+        notes: &mut Vec<String>,
+    ) -> Option<Mapping> {
+        let mut options = config.get("options").cloned().unwrap_or(Value::Null);
+        let mut executor = config.get("executor").and_then(Value::as_str);
+        // `"command": "..."` is shorthand for nx:run-commands
+        if executor.is_none()
+            && let Some(command) = config.get("command")
+        {
+            if !options.is_object() {
+                options = Value::Object(Map::new());
+            }
+            options["command"] = command.clone();
+            executor = Some("nx:run-commands");
+        }
+
+        let mut task = Mapping::new();
+        match executor {
+            None if project.scripts.contains(name) => {
+                task.insert("command".into(), "pnpm".into());
+                task.insert("args".into(), yaml_list(["run", name]));
+            }
+            Some("nx:run-script") => {
+                let script = options["script"].as_str().unwrap_or(name);
+                task.insert("command".into(), "pnpm".into());
+                task.insert("args".into(), yaml_list(["run", script]));
+            }
+            Some("nx:run-commands") => {
+                let commands: Vec<String> = match options.get("commands") {
+                    Some(Value::Array(commands)) => commands
+                        .iter()
+                        .filter_map(|command| {
+                            command
+                                .as_str()
+                                .or_else(|| command["command"].as_str())
+                                .map(String::from)
+                        })
+                        .collect(),
+                    _ => options["command"]
+                        .as_str()
+                        .map(String::from)
+                        .into_iter()
+                        .collect(),
+                };
+                if commands.is_empty() {
+                    notes.push(format!(
+                        "{}: target '{}' runs no command Moon could pick up",
+                        project.path.display(),
+                        name
+                    ));
+                    return None;
+                }
+                if options["parallel"].as_bool() != Some(false) && commands.len() > 1 {
+                    notes.push(format!(
+                        "{}: target '{}' ran its commands in parallel; Moon runs them in sequence",
+                        project.path.display(),
+                        name
+                    ));
+                }
+                task.insert("script".into(), commands.join(" && ").into());
+
+                // run-commands starts in the workspace root unless told otherwise
+                let project_root = project.path.to_string_lossy();
+                match options["cwd"].as_str().map(|cwd| cwd.trim_end_matches('/')) {
+                    Some(cwd) if cwd == project_root || cwd == "{projectRoot}" => {}
+                    Some(cwd) => notes.push(format!(
+                        "{}: target '{}' ran in '{}'; Moon runs it in the project",
+                        project.path.display(),
+                        name,
+                        cwd
+                    )),
+                    None => {
+                        let mut task_options = Mapping::new();
+                        task_options.insert("runFromWorkspaceRoot".into(), true.into());
+                        task.insert("options".into(), Yaml::Mapping(task_options));
+                    }
+                }
+            }
+            Some(executor) => {
+                notes.push(format!(
+                    "{}: target '{}' uses the {} executor, which has no Moon equivalent",
+                    project.path.display(),
+                    name,
+                    executor
+                ));
+                return None;
+            }
+            None => return None,
+        }
+
+        let mut deps = Vec::new();
+        for dependency in config["dependsOn"].as_array().into_iter().flatten() {
+            let (target, projects) = match dependency {
+                Value::String(dependency) => match dependency.strip_prefix('^') {
+                    Some(target) => (target.to_string(), Value::from("dependencies")),
+                    None => (dependency.clone(), Value::from("self")),
+                },
+                Value::Object(dependency) => {
+                    let Some(target) = dependency.get("target").and_then(Value::as_str) else {
+                        continue;
+                    };
+                    let projects =
+                        if dependency.get("dependencies").and_then(Value::as_bool) == Some(true) {
+                            Value::from("dependencies")
+                        } else {
+                            dependency
+                                .get("projects")
+                                .cloned()
+                                .unwrap_or_else(|| Value::from("self"))
+                        };
+                    (target.to_string(), projects)
+                }
+                _ => continue,
+            };
+            match projects {
+                Value::String(scope) if scope == "dependencies" => {
+                    deps.push(format!("^:{}", target))
+                }
+                Value::String(scope) if scope == "self" => {
+                    if task_names.contains(&target) {
+                        deps.push(format!("~:{}", target));
+                    }
+                }
+                other => {
+                    for upstream in string_list(Some(&other)) {
+                        match self.id_of(&upstream) {
+                            Some(id) => deps.push(format!("{}:{}", id, target)),
+                            None => notes.push(format!(
+                                "{}: target '{}' depends on '{}:{}', which isn't a project here",
+                                project.path.display(),
+                                name,
+                                upstream,
+                                target
+                            )),
+                        }
+                    }
+                }
+            }
+        }
+        if !deps.is_empty() {
+            task.insert("deps".into(), yaml_list(deps));
+        }
+
+        let mut inputs = Vec::new();
+        let mut seen = BTreeSet::new();
+        for input in config["inputs"].as_array().into_iter().flatten() {
+            self.nx_input(project, name, input, &mut inputs, &mut seen, notes);
+        }
+        if !inputs.is_empty() {
+            task.insert("inputs".into(), yaml_list(inputs));
+        }
+
+        let outputs: Vec<String> = string_list(config.get("outputs"))
+            .into_iter()
+            .filter_map(|output| {
+                let resolved = output
+                    .strip_prefix("{options.outputPath}")
+                    .and_then(|rest| {
+                        options["outputPath"]
+                            .as_str()
+                            .map(|path| format!("{{workspaceRoot}}/{}{}", path, rest))
+                    })
+                    .unwrap_or(output.clone());
+                let translated = nx_path(&resolved);
+                if translated.is_none() {
+                    notes.push(format!(
+                        "{}: output '{}' of target '{}' wasn't translated",
+                        project.path.display(),
+                        output,
+                        name
+                    ));
+                }
+                translated
+            })
+            .collect();
+        if !outputs.is_empty() {
+            task.insert("outputs".into(), yaml_list(outputs));
+        }
+
+        let mut task_options = task
+            .remove("options")
+            .and_then(|options| options.as_mapping().cloned())
+            .unwrap_or_default();
+        if config["cache"].as_bool() == Some(false) {
+            task_options.insert("cache".into(), false.into());
+        }
+        if config["continuous"].as_bool() == Some(true) {
+            task_options.insert("persistent".into(), true.into());
+        }
+        if !task_options.is_empty() {
+            task.insert("options".into(), Yaml::Mapping(task_options));
+        }
+        Some(task)
+    }
+
+    /// Add the Moon inputs for one Nx input, expanding named inputs from `namedInputs`
+    fn nx_input(
+        &self,
+        project: &SourceProject,
+        target: &str,
+        input: &Value,
+        inputs: &mut Vec<String>,
+        seen: &mut BTreeSet<String>,
+        notes: &mut Vec<String>,
+    ) {
+        let untranslated = |notes: &mut Vec<String>| {
+            notes.push(format!(
+                "{}: input {} of target '{}' wasn't translated",
+                project.path.display(),
+                input,
+                target
+            ))
+        };
+        match input {
+            // Inputs of dependencies; Moon's `^:` deps already rebuild when those change
+            Value::String(input) if input.starts_with('^') => {}
+            Value::String(input) if input.contains('{') => match nx_path(input) {
+                Some(path) => inputs.push(path),
+                None => untranslated(notes),
+            },
+            Value::String(named) => {
+                if !seen.insert(named.clone()) {
+                    return;
+                }
+                match self.config["namedInputs"]
+                    .get(named)
+                    .and_then(Value::as_array)
+                {
+                    Some(expanded) => {
+                        for input in expanded {
+                            self.nx_input(project, target, input, inputs, seen, notes);
+                        }
+                    }
+                    None if named == NX_DEFAULT_INPUT => inputs.push("**/*".to_string()),
+                    None => untranslated(notes),
+                }
+            }
+            Value::Object(input) => match input.get("env").and_then(Value::as_str) {
+                Some(variable) => inputs.push(format!("${}", variable)),
+                None => untranslated(notes),
+            },
+            _ => untranslated(notes),
+        }
+    }
+}
+
+/// Projects below `root`: directories with a package.json, and for Nx a project.json
+fn discover(
+    root: &Path,
+    tool: MonorepoTool,
+    notes: &mut Vec<String>,
+) -> Result<Vec<SourceProject>> {
+    let walker = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(MAX_PROJECT_DEPTH)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            !(entry.file_type().is_dir()
+                && SKIPPED_DIRECTORIES
+                    .iter()
+                    .any(|skipped| entry.file_name() == *skipped))
+        });
+
+    let mut projects = Vec::new();
+    for entry in walker.flatten() {
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let directory = entry.path();
+        let package = read_json(&directory.join("package.json"))?;
+        let project_json = match tool {
+            MonorepoTool::Nx => read_json(&directory.join("project.json"))?,
+            MonorepoTool::Turbo => None,
+        };
+        if package.is_none() && project_json.is_none() {
+            continue;
+        }
+        let path = directory.strip_prefix(root)?.to_path_buf();
+
+        if tool == MonorepoTool::Turbo && directory.join("turbo.json").is_file() {
+            notes.push(format!(
+                "{}: its own turbo.json wasn't translated; only the root one was",
+                path.display()
+            ));
+        }
+
+        let name = project_json
+            .as_ref()
+            .and_then(|project| project["name"].as_str())
+            .or_else(|| {
+                package
+                    .as_ref()
+                    .and_then(|package| package["name"].as_str())
+            })
+            .map(String::from)
+            .unwrap_or_else(|| entry.file_name().to_string_lossy().into_owned());
+        let scripts = package
+            .as_ref()
+            .and_then(|package| package["scripts"].as_object())
+            .map(|scripts| scripts.keys().cloned().collect())
+            .unwrap_or_default();
+
+        let mut targets = Map::new();
+        let mut implicit_dependencies = Vec::new();
+        for config in [
+            package.as_ref().map(|package| &package["nx"]),
+            project_json.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if let Some(config_targets) = config["targets"].as_object() {
+                targets.extend(config_targets.clone());
+            }
+            implicit_dependencies.extend(string_list(config.get("implicitDependencies")));
+        }
+
+        projects.push(SourceProject {
+            name,
+            path,
+            id: entry.file_name().to_string_lossy().into_owned(),
+            scripts,
+            targets,
+            implicit_dependencies,
+        });
+    }
+    Ok(projects)
+}
+
+/// An Nx path as a Moon one: `{projectRoot}/x` is project-relative `x`, `{workspaceRoot}/x`
+/// is workspace-relative `/x`, keeping a leading `!`. `None` for other tokens.
+fn nx_path(path: &str) -> Option<String> {
+    let (negation, path) = match path.strip_prefix('!') {
+        Some(path) => ("!", path),
+        None => ("", path),
+    };
+    let translated = if let Some(rest) = path.strip_prefix("{projectRoot}") {
+        rest.trim_start_matches('/').to_string()
+    } else if let Some(rest) = path.strip_prefix("{workspaceRoot}") {
+        format!("/{}", rest.trim_start_matches('/'))
+    } else {
+        path.to_string()
+    };
+    if translated.contains('{') || translated.is_empty() {
+        return None;
+    }
+    Some(format!("{}{}", negation, translated))
+}
+
+fn read_json(path: &Path) -> Result<Option<Value>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    let value = serde_json::from_str(&strip_jsonc(&content))
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(value))
+}
+
+/// A string or list of strings
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(item)) => vec![item.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn yaml_list<I, S>(items: I) -> Yaml
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    Yaml::Sequence(
+        items
+            .into_iter()
+            .map(|item| Yaml::String(item.into()))
+            .collect(),
+    )
+}
@@ -0,0 +1,147 @@
+use crate::utils::config::ProjectPlacementConfig;
+use crate::utils::wrangler_edit::ConfigEdit;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Regions a Durable Object's `locationHint` accepts
+pub const LOCATION_HINTS: [&str; 9] = [
+    "wnam", "enam", "sam", "weur", "eeur", "apac", "oc", "afr", "me",
+];
+
+/// Suffix of the var a Worker reads a Durable Object binding's location hint from, as in
+/// `env.COUNTER.get(id, { locationHint: env.COUNTER_LOCATION_HINT })`
+pub const HINT_VAR_SUFFIX: &str = "_LOCATION_HINT";
+
+pub fn hint_var(binding: &str) -> String {
+    format!("{}{}", binding, HINT_VAR_SUFFIX)
+}
+
+/// Placement set in one scope of a Wrangler configuration
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScopePlacement {
+    pub environment: Option<String>,
+    /// `smart` or `off`; None leaves Wrangler's default, which is off
+    pub mode: Option<String>,
+    /// Whether the mode comes from the top level rather than the environment itself
+    pub inherited: bool,
+    pub location_hints: BTreeMap<String, String>,
+}
+
+/// Durable Object bindings declared at the top level or in any environment
+pub fn durable_object_bindings(config: &Value) -> BTreeSet<String> {
+    std::iter::once(config)
+        .chain(
+            environments(config)
+                .into_iter()
+                .map(|env| &config["env"][env]),
+        )
+        .filter_map(|scope| scope["durable_objects"]["bindings"].as_array())
+        .flatten()
+        .filter_map(|binding| binding["name"].as_str())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The top level, then each `[env.<name>]`
+pub fn scopes(config: &Value) -> Vec<ScopePlacement> {
+    let bindings = durable_object_bindings(config);
+    let top_mode = mode(config);
+    let mut scopes = vec![ScopePlacement {
+        environment: None,
+        mode: top_mode.clone(),
+        inherited: false,
+        location_hints: hints(config, &bindings),
+    }];
+    for env in environments(config) {
+        let scope = &config["env"][env];
+        let own = mode(scope);
+        scopes.push(ScopePlacement {
+            environment: Some(env.to_string()),
+            inherited: own.is_none() && top_mode.is_some(),
+            mode: own.or_else(|| top_mode.clone()),
+            location_hints: hints(scope, &bindings),
+        });
+    }
+    scopes
+}
+
+/// Edits bringing the configuration in line with `declared`. The mode is set at the top level
+/// and in environments that set their own, since Wrangler environments inherit `placement`.
+/// Vars aren't inherited, so each hint var is set in every environment, and hint vars of
+/// bindings no longer hinted are removed.
+pub fn edits(config: &Value, declared: &ProjectPlacementConfig) -> Vec<ConfigEdit> {
+    let bindings = durable_object_bindings(config);
+    let mut edits = Vec::new();
+    let scopes: Vec<(Vec<&str>, &Value)> = std::iter::once((Vec::new(), config))
+        .chain(
+            environments(config)
+                .into_iter()
+                .map(|env| (vec!["env", env], &config["env"][env])),
+        )
+        .collect();
+
+    for (prefix, scope) in scopes {
+        let path = |keys: &[&str]| -> Vec<String> {
+            prefix
+                .iter()
+                .chain(keys)
+                .map(|key| key.to_string())
+                .collect()
+        };
+        if let Some(declared) = declared.mode
+            && (prefix.is_empty() || scope.get("placement").is_some())
+            && mode(scope).as_deref() != Some(declared.as_str())
+        {
+            edits.push(ConfigEdit::set(
+                &path(&["placement", "mode"]),
+                declared.as_str(),
+            ));
+        }
+
+        let current = hints(scope, &bindings);
+        for (binding, region) in &declared.location_hints {
+            if current.get(binding) != Some(region) {
+                edits.push(ConfigEdit::set(
+                    &path(&["vars", &hint_var(binding)]),
+                    region.as_str(),
+                ));
+            }
+        }
+        for binding in current.keys() {
+            if !declared.location_hints.contains_key(binding) {
+                edits.push(ConfigEdit::remove(&path(&["vars", &hint_var(binding)])));
+            }
+        }
+    }
+    edits
+}
+
+fn environments(config: &Value) -> Vec<&str> {
+    config["env"]
+        .as_object()
+        .map(|envs| envs.keys().map(String::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn mode(scope: &Value) -> Option<String> {
+    scope["placement"]["mode"].as_str().map(str::to_string)
+}
+
+/// Hint vars of the scope, by binding
+fn hints(scope: &Value, bindings: &BTreeSet<String>) -> BTreeMap<String, String> {
+    scope["vars"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| {
+            let binding = name.strip_suffix(HINT_VAR_SUFFIX)?;
+            bindings.contains(binding).then(|| {
+                (
+                    binding.to_string(),
+                    value.as_str().unwrap_or_default().to_string(),
+                )
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,287 @@
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::fs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Lines compared with a full diff; longer changes are shown as one replaced block
+const MAX_DIFF_LINES: usize = 2000;
+
+static PLAN_ONLY: AtomicBool = AtomicBool::new(false);
+static AUTO_APPROVE: AtomicBool = AtomicBool::new(false);
+
+/// Print plans without applying them (`--plan`)
+pub fn set_plan_only(enabled: bool) {
+    PLAN_ONLY.store(enabled, Ordering::Relaxed);
+}
+
+pub fn plan_only() -> bool {
+    PLAN_ONLY.load(Ordering::Relaxed)
+}
+
+/// Apply plans without asking (`--auto-approve`)
+pub fn set_auto_approve(enabled: bool) {
+    AUTO_APPROVE.store(enabled, Ordering::Relaxed);
+}
+
+/// One thing a command is about to do
+#[derive(Debug, Clone)]
+pub enum Change {
+    Create {
+        path: PathBuf,
+        content: String,
+    },
+    Modify {
+        path: PathBuf,
+        before: String,
+        after: String,
+    },
+    Delete {
+        path: PathBuf,
+    },
+    /// A process the command runs itself; shown, but not run by [`Plan::apply`]
+    Run {
+        command: String,
+    },
+}
+
+/// Everything a mutating command will change, rendered before it changes anything so the
+/// output can be reviewed, or compared between runs
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+}
+
+impl Plan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create(&mut self, path: impl Into<PathBuf>, content: impl Into<String>) {
+        self.changes.push(Change::Create {
+            path: path.into(),
+            content: content.into(),
+        });
+    }
+
+    /// Rewrite `path`; nothing is planned when the content doesn't change
+    pub fn modify(&mut self, path: impl Into<PathBuf>, before: String, after: String) {
+        if before != after {
+            self.changes.push(Change::Modify {
+                path: path.into(),
+                before,
+                after,
+            });
+        }
+    }
+
+    /// Write `content` to `path`, creating it or modifying what's there
+    pub fn write(&mut self, path: impl Into<PathBuf>, content: String) -> Result<()> {
+        let path = path.into();
+        if path.exists() {
+            let before = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            self.modify(path, before, content);
+        } else {
+            self.create(path, content);
+        }
+        Ok(())
+    }
+
+    pub fn delete(&mut self, path: impl Into<PathBuf>) {
+        self.changes.push(Change::Delete { path: path.into() });
+    }
+
+    pub fn run(&mut self, command: impl Into<String>) {
+        self.changes.push(Change::Run {
+            command: command.into(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Files the plan writes or deletes
+    pub fn paths(&self) -> Vec<&Path> {
+        self.changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Create { path, .. }
+                | Change::Modify { path, .. }
+                | Change::Delete { path } => Some(path.as_path()),
+                Change::Run { .. } => None,
+            })
+            .collect()
+    }
+
+    /// `+` for files created, `~` with a line diff for files modified, `-` for files
+    /// deleted and `>` for processes run, then a count of each
+    pub fn render(&self) {
+        for change in &self.changes {
+            match change {
+                Change::Create { path, content } => {
+                    println!(
+                        "{} {} {}",
+                        "+".green(),
+                        path.display().to_string().bold(),
+                        format!("({} lines)", content.lines().count()).dimmed()
+                    );
+                }
+                Change::Modify {
+                    path,
+                    before,
+                    after,
+                } => {
+                    println!("{} {}", "~".yellow(), path.display().to_string().bold());
+                    print_diff(before, after);
+                }
+                Change::Delete { path } => {
+                    println!("{} {}", "-".red(), path.display().to_string().bold());
+                }
+                Change::Run { command } => println!("{} {}", ">".cyan(), command),
+            }
+        }
+        println!("{}", self.summary().bold());
+    }
+
+    /// `Plan: 1 to create, 2 to modify, 0 to delete, 1 to run`
+    pub fn summary(&self) -> String {
+        let count = |kind: fn(&Change) -> bool| self.changes.iter().filter(|c| kind(c)).count();
+        format!(
+            "Plan: {} to create, {} to modify, {} to delete, {} to run",
+            count(|c| matches!(c, Change::Create { .. })),
+            count(|c| matches!(c, Change::Modify { .. })),
+            count(|c| matches!(c, Change::Delete { .. })),
+            count(|c| matches!(c, Change::Run { .. })),
+        )
+    }
+
+    /// Whether to go ahead: yes with `--auto-approve` or `approved`, otherwise asked at a
+    /// prompt. Without a terminal to ask at, the plan must be approved up front.
+    pub fn confirm(&self, approved: bool) -> Result<bool> {
+        if approved || AUTO_APPROVE.load(Ordering::Relaxed) {
+            return Ok(true);
+        }
+        if !std::io::stdin().is_terminal() {
+            bail!(
+                "Pass --auto-approve to apply this plan without a prompt, or --plan to only show it"
+            );
+        }
+        print!("Apply this plan? [y/N] ");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        std::io::stdin().lock().read_line(&mut line)?;
+        Ok(matches!(line.trim(), "y" | "Y" | "yes"))
+    }
+
+    /// Write and delete the planned files. Snapshot [`Plan::paths`] first for the changes
+    /// to be undone with `moonflare restore`.
+    pub fn apply(&self) -> Result<()> {
+        for change in &self.changes {
+            match change {
+                Change::Create {
+                    path,
+                    content: after,
+                }
+                | Change::Modify { path, after, .. } => {
+                    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(path, after)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                }
+                Change::Delete { path } => {
+                    fs::remove_file(path)
+                        .with_context(|| format!("Failed to delete {}", path.display()))?;
+                }
+                Change::Run { .. } => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Removed lines with their old line numbers, then the lines added in their place
+fn print_diff(before: &str, after: &str) {
+    for line in diff_lines(before, after) {
+        match line {
+            DiffLine::Removed(number, text) => println!(
+                "  {:>4} {}",
+                number.to_string().dimmed(),
+                format!("- {}", text.trim()).red()
+            ),
+            DiffLine::Added(text) => {
+                println!("  {:>4} {}", "", format!("+ {}", text.trim()).green())
+            }
+        }
+    }
+    println!();
+}
+
+#[derive(Debug, PartialEq)]
+enum DiffLine<'a> {
+    Removed(usize, &'a str),
+    Added(&'a str),
+}
+
+/// A line diff from the longest common subsequence of the lines between the common prefix and
+/// suffix
+fn diff_lines<'a>(before: &'a str, after: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    if old_middle.len() * new_middle.len() > MAX_DIFF_LINES * MAX_DIFF_LINES {
+        let mut lines: Vec<DiffLine> = old_middle
+            .iter()
+            .enumerate()
+            .map(|(i, text)| DiffLine::Removed(prefix + i + 1, text))
+            .collect();
+        lines.extend(new_middle.iter().map(|text| DiffLine::Added(text)));
+        return lines;
+    }
+
+    // lengths[i][j]: common subsequence length of old_middle[i..] and new_middle[j..]
+    let mut lengths = vec![vec![0usize; new_middle.len() + 1]; old_middle.len() + 1];
+    for i in (0..old_middle.len()).rev() {
+        for j in (0..new_middle.len()).rev() {
+            lengths[i][j] = if old_middle[i] == new_middle[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    let mut added = Vec::new();
+    while i < old_middle.len() || j < new_middle.len() {
+        if i < old_middle.len() && j < new_middle.len() && old_middle[i] == new_middle[j] {
+            lines.append(&mut added);
+            i += 1;
+            j += 1;
+        } else if j == new_middle.len()
+            || (i < old_middle.len() && lengths[i + 1][j] >= lengths[i][j + 1])
+        {
+            lines.push(DiffLine::Removed(prefix + i + 1, old_middle[i]));
+            i += 1;
+        } else {
+            // Held back so a replaced line reads as its removal followed by its addition
+            added.push(DiffLine::Added(new_middle[j]));
+            j += 1;
+        }
+    }
+    lines.append(&mut added);
+    lines
+}
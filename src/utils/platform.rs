@@ -0,0 +1,253 @@
+use std::path::{Path, PathBuf};
+
+/// Paths this long need the `\\?\` prefix on Windows, where the classic limit is 260
+/// characters including the terminating NUL
+const MAX_PATH: usize = 259;
+
+/// The user's home directory: `HOME`, or `USERPROFILE` on Windows where `HOME` is usually unset
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .or_else(|| {
+            cfg!(windows)
+                .then(|| std::env::var_os("USERPROFILE"))
+                .flatten()
+                .filter(|home| !home.is_empty())
+        })
+        .map(PathBuf::from)
+}
+
+/// The user's configuration directory: `XDG_CONFIG_HOME`, else `~/.config`
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".config")))
+}
+
+/// `name` with the platform's executable suffix, e.g. `moon.exe` on Windows
+pub fn executable(name: &str) -> String {
+    format!("{}{}", name, std::env::consts::EXE_SUFFIX)
+}
+
+/// Where Moon's installers put the binary, in the order they're checked when `moon` isn't on
+/// the PATH: `/usr/local/bin/moon` and `~/.moon/bin/moon`, or `%USERPROFILE%\.moon\bin\moon.exe`
+pub fn moon_install_locations() -> Vec<PathBuf> {
+    let mut locations = Vec::new();
+    if cfg!(unix) {
+        locations.push(PathBuf::from("/usr/local/bin/moon"));
+    }
+    if let Some(home) = home_dir() {
+        locations.push(home.join(".moon").join("bin").join(executable("moon")));
+    }
+    locations
+}
+
+/// The shell a suggested command will be pasted into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Posix,
+    Pwsh,
+    Cmd,
+}
+
+impl Shell {
+    /// POSIX shells everywhere but Windows. There, PowerShell adds the user's module directory
+    /// (under Documents) to `PSModulePath`, which cmd.exe never has.
+    pub fn detect() -> Self {
+        if !cfg!(windows) {
+            return Shell::Posix;
+        }
+        match std::env::var("PSModulePath") {
+            Ok(paths) if paths.to_lowercase().contains("documents") => Shell::Pwsh,
+            _ => Shell::Cmd,
+        }
+    }
+
+    /// `value` as one argument, quoted only when it needs to be
+    pub fn quote(self, value: &str) -> String {
+        let plain = !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_./\\:@+=,".contains(c));
+        if plain {
+            return value.to_string();
+        }
+        match self {
+            Shell::Posix => format!("'{}'", value.replace('\'', r"'\''")),
+            Shell::Pwsh => format!("'{}'", value.replace('\'', "''")),
+            Shell::Cmd => format!("\"{}\"", value.replace('"', "\"\"")),
+        }
+    }
+
+    /// Command that deletes a directory and everything in it
+    pub fn remove_dir(self, path: &Path) -> String {
+        let path = self.quote(&path.display().to_string());
+        match self {
+            Shell::Posix => format!("rm -rf {}", path),
+            Shell::Pwsh => format!("Remove-Item -Recurse -Force {}", path),
+            Shell::Cmd => format!("rmdir /s /q {}", path),
+        }
+    }
+
+    /// Command that changes into a directory
+    pub fn change_dir(self, path: &str) -> String {
+        format!("cd {}", self.quote(path))
+    }
+
+    /// One-line Moon installer for this shell
+    pub fn install_moon(self) -> &'static str {
+        match self {
+            Shell::Posix => "curl -fsSL https://moonrepo.dev/install/moon.sh | bash",
+            Shell::Pwsh | Shell::Cmd => {
+                "powershell -c \"irm https://moonrepo.dev/install/moon.ps1 | iex\""
+            }
+        }
+    }
+}
+
+/// `path` in a form the filesystem APIs accept however deep it is. Long absolute paths get the
+/// `\\?\` prefix on Windows, which lifts the 260-character limit that deep `node_modules` trees
+/// run into; elsewhere, and for short paths, the path is returned unchanged.
+pub fn long_path(path: &Path) -> PathBuf {
+    if !cfg!(windows) {
+        return path.to_path_buf();
+    }
+    let Ok(absolute) = std::path::absolute(path) else {
+        return path.to_path_buf();
+    };
+    let text = absolute.to_string_lossy();
+    if text.len() < MAX_PATH {
+        path.to_path_buf()
+    } else if text.starts_with(r"\\?\") {
+        absolute
+    } else if let Some(share) = text.strip_prefix(r"\\") {
+        PathBuf::from(format!(r"\\?\UNC\{}", share))
+    } else {
+        PathBuf::from(format!(r"\\?\{}", text))
+    }
+}
+
+/// Overrides the detected platform when choosing which release artifacts to download
+pub const TARGET_ENV: &str = "MOONFLARE_TARGET";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Os {
+    Linux,
+    Darwin,
+    Windows,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X86_64,
+    Arm64,
+}
+
+/// The operating system and CPU architecture a downloaded tool has to run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Target {
+    pub os: Os,
+    pub arch: Arch,
+}
+
+impl Target {
+    /// The platform to download for: `MOONFLARE_TARGET` when set, otherwise this machine. An
+    /// x86_64 build running under Rosetta on Apple silicon picks arm64, so an update installs
+    /// the native binary instead of staying translated.
+    pub fn detect() -> Result<Self, String> {
+        match std::env::var(TARGET_ENV) {
+            Ok(value) if !value.trim().is_empty() => {
+                Self::parse(&value).map_err(|e| format!("{} ({})", e, TARGET_ENV))
+            }
+            _ => Self::host(),
+        }
+    }
+
+    fn host() -> Result<Self, String> {
+        let os = match std::env::consts::OS {
+            "linux" => Os::Linux,
+            "macos" => Os::Darwin,
+            "windows" => Os::Windows,
+            other => return Err(format!("unsupported operating system '{}'", other)),
+        };
+        let arch = match std::env::consts::ARCH {
+            "x86_64" => Arch::X86_64,
+            "aarch64" => Arch::Arm64,
+            other => return Err(format!("unsupported architecture '{}'", other)),
+        };
+        let arch = if os == Os::Darwin && arch == Arch::X86_64 && under_rosetta() {
+            Arch::Arm64
+        } else {
+            arch
+        };
+        Ok(Target { os, arch })
+    }
+
+    /// Parse `<os>-<arch>`, e.g. `linux-arm64` or `darwin-x86_64`. Common aliases such as
+    /// `macos`, `aarch64` and `amd64` are accepted.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "invalid target '{}'; use one of linux-x86_64, linux-arm64, darwin-x86_64, darwin-arm64, windows-x86_64",
+                value
+            )
+        };
+        let (os, arch) = value.trim().split_once('-').ok_or_else(invalid)?;
+        let os = match os.to_lowercase().as_str() {
+            "linux" => Os::Linux,
+            "darwin" | "macos" | "osx" => Os::Darwin,
+            "windows" | "win" => Os::Windows,
+            _ => return Err(invalid()),
+        };
+        let arch = match arch.to_lowercase().as_str() {
+            "x86_64" | "amd64" | "x64" => Arch::X86_64,
+            "arm64" | "aarch64" => Arch::Arm64,
+            _ => return Err(invalid()),
+        };
+        Ok(Target { os, arch })
+    }
+
+    /// Archive name goreleaser publishes for `project` on this target, e.g.
+    /// `moonflare_Darwin_arm64.tar.gz`
+    pub fn release_archive(self, project: &str) -> String {
+        let os = match self.os {
+            Os::Linux => "Linux",
+            Os::Darwin => "Darwin",
+            Os::Windows => "Windows",
+        };
+        let extension = match self.os {
+            Os::Windows => "zip",
+            _ => "tar.gz",
+        };
+        format!("{}_{}_{}.{}", project, os, self.arch, extension)
+    }
+}
+
+impl std::fmt::Display for Arch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Arch::X86_64 => "x86_64",
+            Arch::Arm64 => "arm64",
+        })
+    }
+}
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let os = match self.os {
+            Os::Linux => "linux",
+            Os::Darwin => "darwin",
+            Os::Windows => "windows",
+        };
+        write!(f, "{}-{}", os, self.arch)
+    }
+}
+
+/// Whether this process is an x86_64 binary translated by Rosetta 2
+fn under_rosetta() -> bool {
+    std::process::Command::new("sysctl")
+        .args(["-n", "sysctl.proc_translated"])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+}
@@ -0,0 +1,194 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::wrangler::dev_port;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::Path;
+
+/// Dev ports projects were moved to because their usual port was taken
+pub const PORTS_FILE: &str = ".moonflare/ports.json";
+
+/// How far past its usual port a project's replacement port may be
+const SEARCH_RANGE: u16 = 100;
+
+/// Ports reserved for projects in place of their usual dev port, kept until released with
+/// `moonflare ports free`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PortAssignments {
+    #[serde(default)]
+    pub remapped: BTreeMap<String, u16>,
+}
+
+impl PortAssignments {
+    pub fn load() -> Result<Self> {
+        let path = Path::new(PORTS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content =
+            fs::read_to_string(path).with_context(|| format!("Failed to read {}", PORTS_FILE))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", PORTS_FILE))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Path::new(PORTS_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", PORTS_FILE))
+    }
+}
+
+/// The port a project's dev server uses unless moved: the Wrangler config's dev port for
+/// Workers, else the default dev server ports of the project templates
+pub fn default_port(project: &WorkspaceProject, config: &MoonflareConfig) -> Result<u16> {
+    Ok(if project.is_worker() {
+        dev_port(&project.path)?
+    } else if project.path.starts_with(&config.layout.sites) {
+        4321
+    } else {
+        3000
+    })
+}
+
+/// Port a project's dev server listens on, its reservation first
+pub fn local_port(project: &WorkspaceProject, config: &MoonflareConfig) -> Result<u16> {
+    match PortAssignments::load()?.remapped.get(&project.name) {
+        Some(port) => Ok(*port),
+        None => default_port(project, config),
+    }
+}
+
+/// URL a project's dev server listens on
+pub fn dev_url(
+    project: &WorkspaceProject,
+    config: &MoonflareConfig,
+    https: bool,
+) -> Result<String> {
+    let scheme = if https { "https" } else { "http" };
+    Ok(format!(
+        "{}://localhost:{}",
+        scheme,
+        local_port(project, config)?
+    ))
+}
+
+/// Whether nothing listens on `port` yet
+pub fn is_free(port: u16) -> bool {
+    TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// A project's dev port and what else wants it
+#[derive(Debug)]
+pub struct PortStatus {
+    pub project: String,
+    pub port: u16,
+    /// The usual port, when the project was moved off it
+    pub moved_from: Option<u16>,
+    /// Something is listening on the port, maybe the project's own dev server
+    pub in_use: bool,
+    /// Other projects whose dev servers would listen on the same port
+    pub shared_with: Vec<String>,
+}
+
+/// Dev ports of `projects`, with the projects sharing each
+pub fn statuses(
+    projects: &[WorkspaceProject],
+    config: &MoonflareConfig,
+) -> Result<Vec<PortStatus>> {
+    let assignments = PortAssignments::load()?;
+    let mut ports = Vec::new();
+    for project in projects.iter().filter(|p| !p.is_crate()) {
+        let usual = default_port(project, config)?;
+        let port = assignments
+            .remapped
+            .get(&project.name)
+            .copied()
+            .unwrap_or(usual);
+        ports.push((project.name.clone(), port, usual));
+    }
+
+    Ok(ports
+        .iter()
+        .map(|(project, port, usual)| PortStatus {
+            project: project.clone(),
+            port: *port,
+            moved_from: (port != usual).then_some(*usual),
+            in_use: !is_free(*port),
+            shared_with: ports
+                .iter()
+                .filter(|(other, other_port, _)| other != project && other_port == port)
+                .map(|(other, _, _)| other.clone())
+                .collect(),
+        })
+        .collect())
+}
+
+/// A project moved to another port by [`resolve_conflicts`]
+#[derive(Debug)]
+pub struct Move {
+    pub project: String,
+    pub from: u16,
+    pub to: u16,
+}
+
+/// Give each project whose port is taken, by another program, a project earlier in the list or
+/// one of the `reserved` ports, the next free port, and reserve it in .moonflare/ports.json so
+/// the project keeps it
+pub fn resolve_conflicts(
+    projects: &[WorkspaceProject],
+    config: &MoonflareConfig,
+    reserved: &[u16],
+) -> Result<Vec<Move>> {
+    let mut assignments = PortAssignments::load()?;
+    let mut claimed = reserved.to_vec();
+    let mut moves = Vec::new();
+    for project in projects.iter().filter(|p| !p.is_crate()) {
+        let port = match assignments.remapped.get(&project.name) {
+            Some(port) => *port,
+            None => default_port(project, config)?,
+        };
+        if !claimed.contains(&port) && is_free(port) {
+            claimed.push(port);
+            continue;
+        }
+        let Some(replacement) = (1..=SEARCH_RANGE)
+            .filter_map(|offset| port.checked_add(offset))
+            .find(|candidate| !claimed.contains(candidate) && is_free(*candidate))
+        else {
+            bail!(
+                "Port {} for '{}' is taken and so are the {} after it",
+                port,
+                project.name,
+                SEARCH_RANGE
+            );
+        };
+        claimed.push(replacement);
+        assignments
+            .remapped
+            .insert(project.name.clone(), replacement);
+        moves.push(Move {
+            project: project.name.clone(),
+            from: port,
+            to: replacement,
+        });
+    }
+    if !moves.is_empty() {
+        assignments.save()?;
+    }
+    Ok(moves)
+}
+
+/// Drop a project's reservation, returning the port it had
+pub fn free(project: &str) -> Result<Option<u16>> {
+    let mut assignments = PortAssignments::load()?;
+    let port = assignments.remapped.remove(project);
+    if port.is_some() {
+        assignments.save()?;
+    }
+    Ok(port)
+}
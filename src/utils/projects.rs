@@ -0,0 +1,490 @@
+use crate::utils::config::{LayoutConfig, MoonflareConfig};
+use crate::utils::moon_tasks::inherited_tasks;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use walkdir::WalkDir;
+
+/// Workspace directories that hold projects, paired with the project type they contain,
+/// following `[layout]` in moonflare.toml
+pub fn project_directories() -> Vec<(String, &'static str)> {
+    LayoutConfig::current().directories()
+}
+
+/// Directory `moonflare archive` moves projects into, mirroring their original paths
+pub const ARCHIVE_DIR: &str = "archive";
+
+/// Build output, dependencies and tool state inside projects, never worth searching or
+/// rewriting
+pub const GENERATED_DIRS: [&str; 6] = [
+    "node_modules",
+    "dist",
+    "target",
+    ".wrangler",
+    ".astro",
+    ".git",
+];
+
+/// A project discovered in the workspace
+#[derive(Debug, Clone)]
+pub struct WorkspaceProject {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl WorkspaceProject {
+    /// Path relative to the workspace root using forward slashes (e.g. `workers/api`)
+    pub fn relative_path(&self) -> String {
+        self.path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Path below the project's layout directory, e.g. `shop/frontend` for a project nested in
+    /// the `shop` group of `apps/`, or `api` for `workers/api`
+    pub fn grouped_path(&self) -> String {
+        project_directories()
+            .iter()
+            .find_map(|(directory, _)| self.path.strip_prefix(directory).ok())
+            .unwrap_or(&self.path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Whether the project is a Rust crate, built with cargo rather than a JavaScript toolchain
+    pub fn is_crate(&self) -> bool {
+        self.path.starts_with(&LayoutConfig::current().crates)
+    }
+
+    /// Whether the project is a Worker whose dev server is `wrangler dev`
+    pub fn is_worker(&self) -> bool {
+        self.path.starts_with(&LayoutConfig::current().workers)
+    }
+
+    /// Commands (build, dev, deploy, test) the project opts out of when they run across the
+    /// workspace, from `moonflare: { skip: [...] }` in its moon.yml
+    pub fn skipped_commands(&self) -> Vec<String> {
+        fs::read_to_string(self.path.join("moon.yml"))
+            .ok()
+            .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())
+            .and_then(|config| {
+                config
+                    .get("moonflare")
+                    .and_then(|m| m.get("skip"))
+                    .and_then(|s| s.as_sequence())
+                    .cloned()
+            })
+            .map(|commands| {
+                commands
+                    .iter()
+                    .filter_map(|c| c.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether the project matches a pattern by name (`legacy-*`), by relative path
+    /// (`workers/*`) or by grouped path (`shop/*`)
+    pub fn matches_pattern(&self, pattern: &str) -> bool {
+        glob_match(pattern, &self.name)
+            || glob_match(pattern, &self.relative_path())
+            || glob_match(pattern, &self.grouped_path())
+    }
+
+    /// Whether the project has any Wrangler configuration file
+    pub fn has_wrangler_config(&self) -> bool {
+        self.path.join("wrangler.toml").exists()
+            || self.path.join("wrangler.json").exists()
+            || self.path.join("wrangler.jsonc").exists()
+    }
+
+    /// Tags declared in the project's moon.yml
+    pub fn tags(&self) -> Vec<String> {
+        fs::read_to_string(self.path.join("moon.yml"))
+            .ok()
+            .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())
+            .and_then(|config| config.get("tags").and_then(|t| t.as_sequence()).cloned())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether the project defines the given task in its moon.yml or inherits it from
+    /// `.moon/tasks.yml` and `.moon/tasks/*.yml`
+    pub fn has_task(&self, task: &str) -> bool {
+        self.task_definition(task).is_some()
+    }
+
+    /// Names of the tasks the project defines in its moon.yml or inherits
+    pub fn tasks(&self) -> BTreeSet<String> {
+        let config = fs::read_to_string(self.path.join("moon.yml"))
+            .ok()
+            .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())
+            .unwrap_or(serde_yaml::Value::Null);
+        let own = config
+            .get("tasks")
+            .and_then(|tasks| tasks.as_mapping())
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, _)| name.as_str().map(str::to_string));
+        inherited_tasks(&config).into_keys().chain(own).collect()
+    }
+
+    /// The task as Moon would run it: the inherited definition with the fields the project's
+    /// moon.yml sets in its place
+    pub fn task_definition(&self, task: &str) -> Option<serde_yaml::Value> {
+        let config = fs::read_to_string(self.path.join("moon.yml"))
+            .ok()
+            .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())?;
+
+        let own = config.get("tasks").and_then(|tasks| tasks.get(task));
+        let mut definition = inherited_tasks(&config).remove(task);
+        match (definition.as_mut().and_then(|d| d.as_mapping_mut()), own) {
+            (Some(inherited), Some(serde_yaml::Value::Mapping(own))) => {
+                for (key, value) in own {
+                    inherited.insert(key.clone(), value.clone());
+                }
+            }
+            (_, Some(own)) => definition = Some(own.clone()),
+            _ => {}
+        }
+        definition
+    }
+}
+
+/// Discover all projects in the workspace by scanning the project directories for moon.yml
+/// files. A directory without one groups related projects one level further down, such as
+/// `apps/shop/frontend`.
+pub fn discover_projects() -> Vec<WorkspaceProject> {
+    let mut projects = Vec::new();
+
+    for (directory, _) in project_directories() {
+        for path in subdirectories(Path::new(&directory)) {
+            if path.join("moon.yml").exists() {
+                projects.push(project_at(path));
+                continue;
+            }
+            for nested in subdirectories(&path) {
+                if nested.join("moon.yml").exists() {
+                    projects.push(project_at(nested));
+                }
+            }
+        }
+    }
+
+    projects
+}
+
+/// Projects moved under `archive/`, with their paths inside it (e.g. `archive/workers/api`)
+pub fn archived_projects() -> Vec<WorkspaceProject> {
+    WalkDir::new(ARCHIVE_DIR)
+        .min_depth(2)
+        .max_depth(4)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            !GENERATED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref())
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_dir() && entry.path().join("moon.yml").exists())
+        .map(|entry| project_at(entry.into_path()))
+        .collect()
+}
+
+pub fn subdirectories(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+    let mut directories: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    directories.sort();
+    directories
+}
+
+/// The project in `path`, named as Moon names it: by the `id` in its moon.yml, otherwise
+/// after its directory
+fn project_at(path: PathBuf) -> WorkspaceProject {
+    let id = fs::read_to_string(path.join("moon.yml"))
+        .ok()
+        .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())
+        .and_then(|config| {
+            config
+                .get("id")
+                .and_then(|id| id.as_str())
+                .map(String::from)
+        });
+    let name = id.unwrap_or_else(|| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+    WorkspaceProject { name, path }
+}
+
+/// Moon id `moonflare add` gives the project `name` in `group`, e.g. `shop-frontend`
+pub fn grouped_project_id(group: &str, name: &str) -> String {
+    format!("{}-{}", group, name)
+}
+
+/// Find a project in the workspace by its Moon id or its grouped path (`shop/frontend`)
+pub fn find_project(name: &str) -> Option<WorkspaceProject> {
+    discover_projects()
+        .into_iter()
+        .find(|p| p.name == name || p.grouped_path() == name)
+}
+
+/// Whether a project argument is a pattern selecting several projects, such as `shop/*`
+pub fn is_project_pattern(name: &str) -> bool {
+    name.contains(['*', '?'])
+}
+
+/// The Moon id a project argument refers to, so `shop/frontend` can be passed wherever
+/// `shop-frontend` can. With `--auto-correct`, a name that's one close match away from a
+/// single project resolves to it. Patterns and other names pass through unchanged.
+pub fn resolve_project_name(name: &str) -> String {
+    let auto_correct = AUTO_CORRECT.load(Ordering::Relaxed);
+    if is_project_pattern(name) || !(name.contains('/') || auto_correct) {
+        return name.to_string();
+    }
+
+    let projects = discover_projects();
+    if let Some(project) = projects
+        .iter()
+        .find(|p| p.name == name || p.grouped_path() == name)
+    {
+        return project.name.clone();
+    }
+    if auto_correct
+        && let [correction] = similar_names(name, projects.iter().map(|p| p.name.as_str()))[..]
+    {
+        eprintln!("Using '{}' for '{}' (--auto-correct)", correction, name);
+        return correction.to_string();
+    }
+    name.to_string()
+}
+
+static AUTO_CORRECT: AtomicBool = AtomicBool::new(false);
+
+/// Resolve mistyped project arguments to their single close match (`--auto-correct`)
+pub fn set_auto_correct(enabled: bool) {
+    AUTO_CORRECT.store(enabled, Ordering::Relaxed);
+}
+
+/// "Project not found", suggesting the workspace's projects whose names are close to `name`
+pub fn project_not_found(name: &str) -> anyhow::Error {
+    let projects = discover_projects();
+    let suggestions: Vec<String> = similar_names(name, projects.iter().map(|p| p.name.as_str()))
+        .into_iter()
+        .take(3)
+        .map(|n| format!("'{}'", n))
+        .collect();
+    match suggestions.as_slice() {
+        [] => anyhow::anyhow!("Project '{}' not found", name),
+        [suggestion] => anyhow::anyhow!(
+            "Project '{}' not found; did you mean {}? (--auto-correct uses it)",
+            name,
+            suggestion
+        ),
+        _ => anyhow::anyhow!(
+            "Project '{}' not found; did you mean {}?",
+            name,
+            suggestions.join(" or ")
+        ),
+    }
+}
+
+/// Candidates within a few edits of `name`, closest first. A third of the name's length (and
+/// at least one edit) catches typos like `frontned` without matching unrelated short names.
+pub fn similar_names<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<&'a str> {
+    let limit = (name.chars().count() / 3).max(1);
+    let mut close: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (levenshtein(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .collect();
+    close.sort();
+    close.dedup();
+    close.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Insertions, deletions and substitutions needed to turn `a` into `b`
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Include/exclude glob and tag filters applied to the workspace's projects
+#[derive(Debug, Clone, Default)]
+pub struct ProjectFilter {
+    only: Vec<String>,
+    exclude: Vec<String>,
+    tags: Vec<String>,
+    /// Projects left out because they opted out of the command
+    ignored: Vec<String>,
+}
+
+impl ProjectFilter {
+    pub fn new(only: &[String], exclude: &[String], tags: &[String]) -> Self {
+        Self {
+            only: only.to_vec(),
+            exclude: exclude.to_vec(),
+            tags: tags.to_vec(),
+            ignored: Vec::new(),
+        }
+    }
+
+    /// Also leave out the projects ignored for `command` (build, dev, deploy or test):
+    /// those matching `[workspace] ignore` or `ignore_tags`, or skipping it under
+    /// `[projects.<name>]` or in their moon.yml
+    pub fn ignoring(mut self, command: &str) -> Self {
+        self.ignored = ignored_projects(command);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty()
+            && self.exclude.is_empty()
+            && self.tags.is_empty()
+            && self.ignored.is_empty()
+    }
+
+    /// A project matches a pattern by name (`legacy-*`), by relative path (`workers/*`) or by
+    /// grouped path (`shop/*`)
+    pub fn matches(&self, project: &WorkspaceProject) -> bool {
+        let matches_pattern = |pattern: &String| project.matches_pattern(pattern);
+
+        let included = self.only.is_empty() || self.only.iter().any(matches_pattern);
+        let excluded =
+            self.exclude.iter().any(matches_pattern) || self.ignored.contains(&project.name);
+
+        // A project must carry at least one of the requested tags
+        let tagged = self.tags.is_empty() || {
+            let project_tags = project.tags();
+            self.tags.iter().any(|t| project_tags.contains(t))
+        };
+
+        included && !excluded && tagged
+    }
+
+    pub fn apply(&self, projects: Vec<WorkspaceProject>) -> Vec<WorkspaceProject> {
+        projects.into_iter().filter(|p| self.matches(p)).collect()
+    }
+
+    /// Human-readable description of the filter for progress output
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.only.is_empty() {
+            parts.push(format!("only {}", self.only.join(", ")));
+        }
+        if !self.exclude.is_empty() {
+            parts.push(format!("excluding {}", self.exclude.join(", ")));
+        }
+        if !self.tags.is_empty() {
+            parts.push(format!("tagged {}", self.tags.join(", ")));
+        }
+        if !self.ignored.is_empty() {
+            parts.push(format!("ignoring {}", self.ignored.join(", ")));
+        }
+        parts.join("; ")
+    }
+}
+
+/// Names of the projects that opted out of `command` when it runs across the workspace
+pub fn ignored_projects(command: &str) -> Vec<String> {
+    let config = MoonflareConfig::load().unwrap_or_default();
+    discover_projects()
+        .into_iter()
+        .filter(|project| {
+            config
+                .workspace
+                .ignore
+                .iter()
+                .any(|pattern| project.matches_pattern(pattern))
+                || project
+                    .tags()
+                    .iter()
+                    .any(|tag| config.workspace.ignore_tags.contains(tag))
+                || config
+                    .project(&project.name)
+                    .is_some_and(|p| p.skip.iter().any(|c| c == command))
+                || project.skipped_commands().iter().any(|c| c == command)
+        })
+        .map(|project| project.name)
+        .collect()
+}
+
+/// Match text against a glob pattern supporting `*` (any run of characters) and `?` (one character)
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<usize> = None;
+    let mut star_text = 0;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some(p);
+            star_text = t;
+            p += 1;
+        } else if let Some(star_pos) = star {
+            p = star_pos + 1;
+            star_text += 1;
+            t = star_text;
+        } else {
+            return false;
+        }
+    }
+
+    while p < pattern.len() && pattern[p] == '*' {
+        p += 1;
+    }
+
+    p == pattern.len()
+}
+
+/// Resolve the Moon targets (`<project>:<task>`) for every filtered project that defines the task
+pub fn filtered_targets(filter: &ProjectFilter, task: &str) -> anyhow::Result<Vec<String>> {
+    let targets: Vec<String> = filter
+        .apply(discover_projects())
+        .iter()
+        .filter(|p| p.has_task(task))
+        .map(|p| format!("{}:{}", p.name, task))
+        .collect();
+
+    if targets.is_empty() {
+        anyhow::bail!(
+            "No projects with a '{}' task match the filter ({})",
+            task,
+            filter.describe()
+        );
+    }
+
+    Ok(targets)
+}
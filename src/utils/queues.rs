@@ -0,0 +1,199 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::http;
+use crate::utils::ports::local_port;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+
+/// Routes a Worker scaffolded with `--with queue` answers under `wrangler dev` while
+/// QUEUE_DEBUG is "true"
+pub const SEND_ROUTE: &str = "/__queues/send";
+pub const PEEK_ROUTE: &str = "/__queues/peek";
+
+/// A message body to send, with the content type the Queues API stores it as
+#[derive(Debug, Clone)]
+pub struct MessageBody {
+    pub value: Value,
+    /// `json`, or `text` for bodies that aren't JSON
+    pub content_type: &'static str,
+}
+
+impl MessageBody {
+    /// `@path` reads the body from a file; anything else is the body itself. JSON is sent as
+    /// JSON, anything else as text.
+    pub fn parse(arg: &str) -> Result<Self> {
+        let content = match arg.strip_prefix('@') {
+            Some(path) => {
+                fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?
+            }
+            None => arg.to_string(),
+        };
+        Ok(match serde_json::from_str(&content) {
+            Ok(value) => Self {
+                value,
+                content_type: "json",
+            },
+            Err(_) => Self {
+                value: Value::String(content),
+                content_type: "text",
+            },
+        })
+    }
+}
+
+/// A message the consumer of a `--with queue` Worker received, as its peek route lists them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceivedMessage {
+    pub queue: String,
+    pub id: String,
+    pub timestamp: String,
+    pub attempts: u32,
+    pub body: Value,
+}
+
+/// The first project with a producer binding for `queue`, and that binding's name
+pub fn find_producer(
+    projects: &[WorkspaceProject],
+    queue: &str,
+) -> Result<Option<(WorkspaceProject, Value, String)>> {
+    for project in projects.iter().filter(|p| p.is_worker()) {
+        let Some(config) = read_wrangler_config(&project.path)? else {
+            continue;
+        };
+        let binding = entries(&config, "producers")
+            .find(|producer| producer["queue"] == queue)
+            .and_then(|producer| producer["binding"].as_str())
+            .map(str::to_string);
+        if let Some(binding) = binding {
+            return Ok(Some((project.clone(), config, binding)));
+        }
+    }
+    Ok(None)
+}
+
+/// The first project consuming `queue`
+pub fn find_consumer(
+    projects: &[WorkspaceProject],
+    queue: &str,
+) -> Result<Option<WorkspaceProject>> {
+    for project in projects.iter().filter(|p| p.is_worker()) {
+        let Some(config) = read_wrangler_config(&project.path)? else {
+            continue;
+        };
+        if entries(&config, "consumers").any(|consumer| consumer["queue"] == queue) {
+            return Ok(Some(project.clone()));
+        }
+    }
+    Ok(None)
+}
+
+/// Every queue the workspace's Workers produce to or consume, for error messages
+pub fn queue_names(projects: &[WorkspaceProject]) -> BTreeSet<String> {
+    projects
+        .iter()
+        .filter(|p| p.is_worker())
+        .filter_map(|project| read_wrangler_config(&project.path).ok().flatten())
+        .flat_map(|config| {
+            ["producers", "consumers"]
+                .into_iter()
+                .flat_map(|kind| entries(&config, kind))
+                .filter_map(|entry| entry["queue"].as_str().map(str::to_string))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Top-level `queues.producers` or `queues.consumers`; `wrangler dev` runs without an
+/// environment, so environment-specific queues are left out
+fn entries<'a>(config: &'a Value, kind: &str) -> impl Iterator<Item = &'a Value> {
+    config["queues"][kind]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+}
+
+/// Base URL of a project's local dev server
+fn dev_server(project: &WorkspaceProject) -> Result<String> {
+    let port = local_port(project, &MoonflareConfig::load()?)?;
+    Ok(format!("http://localhost:{}", port))
+}
+
+/// Client for dev servers, which are local
+fn client() -> Result<reqwest::Client> {
+    Ok(http::builder()?.no_proxy().build()?)
+}
+
+/// Send a message through `binding` of the project's running dev server, so it takes the same
+/// path a message sent by the Worker's code would
+pub async fn send_local(
+    project: &WorkspaceProject,
+    binding: &str,
+    body: &MessageBody,
+) -> Result<()> {
+    let base = dev_server(project)?;
+    let response = client()?
+        .post(format!("{}{}", base, SEND_ROUTE))
+        .query(&[("binding", binding)])
+        .json(&body.value)
+        .send()
+        .await
+        .with_context(|| not_running(project, &base))?;
+    check_route(project, response, SEND_ROUTE).await?;
+    Ok(())
+}
+
+/// Messages the project's running dev server has consumed from `queue`, most recent first
+pub async fn peek_local(project: &WorkspaceProject, queue: &str) -> Result<Vec<ReceivedMessage>> {
+    let base = dev_server(project)?;
+    let response = client()?
+        .get(format!("{}{}", base, PEEK_ROUTE))
+        .query(&[("queue", queue)])
+        .send()
+        .await
+        .with_context(|| not_running(project, &base))?;
+    let response = check_route(project, response, PEEK_ROUTE).await?;
+    response.json().await.with_context(|| {
+        format!(
+            "'{}' answered {} with invalid JSON",
+            project.name, PEEK_ROUTE
+        )
+    })
+}
+
+fn not_running(project: &WorkspaceProject, base: &str) -> String {
+    format!(
+        "Nothing answered at {}; start '{}' with 'moonflare dev {}' first",
+        base, project.name, project.name
+    )
+}
+
+async fn check_route(
+    project: &WorkspaceProject,
+    response: reqwest::Response,
+    route: &str,
+) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        bail!(
+            "'{}' doesn't answer {}; Workers added with '--with queue' do while QUEUE_DEBUG is \"true\" in their .dev.vars",
+            project.name,
+            route
+        );
+    }
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        bail!(
+            "'{}' answered {} with {}: {}",
+            project.name,
+            route,
+            status,
+            text.trim()
+        );
+    }
+    Ok(response)
+}
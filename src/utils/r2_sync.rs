@@ -0,0 +1,280 @@
+use crate::utils::artifacts::file_hashes;
+use crate::utils::cache_headers::cache_control;
+use crate::utils::cloudflare::CloudflareClient;
+use crate::utils::config::AssetsConfig;
+use crate::utils::retention::format_bytes;
+use crate::utils::sbom::sha256_hex;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// What each bucket and prefix held after the last sync, so unchanged files aren't uploaded again
+const R2_STATE_DIR: &str = ".moonflare/r2";
+
+/// Uploads and deletes in flight at once
+const CONCURRENCY: usize = 8;
+
+/// Objects a bucket held after the last sync, keyed by object key
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct R2State {
+    objects: BTreeMap<String, SyncedObject>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SyncedObject {
+    /// SHA-256 of the local file that was uploaded
+    sha256: String,
+    /// ETag R2 gave the upload, to notice objects replaced by someone else since
+    etag: String,
+}
+
+/// How a sync went
+#[derive(Debug, Default)]
+pub struct SyncStats {
+    pub uploaded: usize,
+    pub uploaded_bytes: u64,
+    pub unchanged: usize,
+    pub deleted: usize,
+    /// Objects missing locally that `--no-delete` left in the bucket
+    pub kept: usize,
+    pub elapsed: Duration,
+}
+
+impl SyncStats {
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} uploaded ({}), {} unchanged, {} deleted",
+            self.uploaded,
+            format_bytes(self.uploaded_bytes),
+            self.unchanged,
+            self.deleted
+        );
+        if self.kept > 0 {
+            summary.push_str(&format!(", {} kept", self.kept));
+        }
+        summary.push_str(&format!(" in {:.1}s", self.elapsed.as_secs_f64()));
+        summary
+    }
+}
+
+/// One upload or delete the sync needs
+enum Transfer {
+    Upload {
+        key: String,
+        file: PathBuf,
+        sha256: String,
+    },
+    Delete {
+        key: String,
+    },
+}
+
+/// Make `bucket` under `prefix` mirror `dir`: list what the bucket holds, upload the files that
+/// are new or changed since the last sync, and delete objects no longer in `dir` unless `delete`
+/// is off. `skip` names files in `dir` that are never uploaded. Objects carry their content type
+/// and the cache policy of `[assets]`. A prefix is a directory, so `v2` syncs under `v2/` and
+/// leaves `v2-old/` alone.
+pub async fn sync_dir(
+    client: CloudflareClient,
+    dir: &Path,
+    bucket: &str,
+    prefix: &str,
+    delete: bool,
+    assets: &AssetsConfig,
+    skip: &[&str],
+) -> Result<SyncStats> {
+    let start = Instant::now();
+    if !dir.is_dir() {
+        bail!("{} is not a directory", dir.display());
+    }
+    let dir = fs::canonicalize(dir)?;
+    let prefix = &directory_prefix(prefix);
+
+    let files: BTreeMap<String, String> = file_hashes(&dir)?
+        .into_iter()
+        .filter(|(path, _)| !skip.contains(&path.as_str()))
+        .map(|(path, hash)| (format!("{}{}", prefix, path), hash))
+        .collect();
+    let remote: HashMap<String, String> = client
+        .list_r2_objects(bucket, prefix)
+        .await
+        .with_context(|| format!("Failed to list R2 bucket '{}'", bucket))?
+        .into_iter()
+        .map(|object| (object.key, object.etag))
+        .collect();
+
+    let state_path = state_path(bucket, prefix);
+    // A state file from an older moonflare only costs a full upload
+    let previous: R2State = fs::read_to_string(&state_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let mut stats = SyncStats::default();
+    let mut state = R2State::default();
+    let mut transfers = Vec::new();
+    for (key, sha256) in &files {
+        let synced = previous.objects.get(key).filter(|synced| {
+            &synced.sha256 == sha256 && remote.get(key).is_some_and(|etag| etag == &synced.etag)
+        });
+        match synced {
+            Some(synced) => {
+                stats.unchanged += 1;
+                state.objects.insert(key.clone(), synced.clone());
+            }
+            None => transfers.push(Transfer::Upload {
+                key: key.clone(),
+                file: dir.join(&key[prefix.len()..]),
+                sha256: sha256.clone(),
+            }),
+        }
+    }
+    for key in remote.keys().filter(|key| !files.contains_key(*key)) {
+        if delete {
+            transfers.push(Transfer::Delete { key: key.clone() });
+        } else {
+            stats.kept += 1;
+        }
+    }
+
+    let client = Arc::new(client);
+    let permits = Arc::new(Semaphore::new(CONCURRENCY));
+    let mut running = JoinSet::new();
+    for transfer in transfers {
+        let client = Arc::clone(&client);
+        let permits = Arc::clone(&permits);
+        let bucket = bucket.to_string();
+        let content_type = match &transfer {
+            Transfer::Upload { key, .. } => content_type(key),
+            Transfer::Delete { .. } => "",
+        };
+        let cache_control = match &transfer {
+            Transfer::Upload { key, .. } => cache_control(&key[prefix.len()..], assets),
+            Transfer::Delete { .. } => String::new(),
+        };
+        running.spawn(async move {
+            let _permit = permits.acquire_owned().await?;
+            match transfer {
+                Transfer::Upload { key, file, sha256 } => {
+                    let body = tokio::fs::read(&file)
+                        .await
+                        .with_context(|| format!("Failed to read {}", file.display()))?;
+                    let size = body.len() as u64;
+                    let object = client
+                        .put_r2_object(&bucket, &key, body, content_type, &cache_control)
+                        .await
+                        .with_context(|| format!("Failed to upload '{}'", key))?;
+                    let synced = SyncedObject {
+                        sha256,
+                        etag: object.etag,
+                    };
+                    Ok::<_, anyhow::Error>((key, Some((synced, size))))
+                }
+                Transfer::Delete { key } => {
+                    client
+                        .delete_r2_object(&bucket, &key)
+                        .await
+                        .with_context(|| format!("Failed to delete '{}'", key))?;
+                    Ok((key, None))
+                }
+            }
+        });
+    }
+
+    let mut failures = Vec::new();
+    while let Some(result) = running.join_next().await {
+        match result? {
+            Ok((key, Some((synced, size)))) => {
+                stats.uploaded += 1;
+                stats.uploaded_bytes += size;
+                state.objects.insert(key, synced);
+            }
+            Ok((_, None)) => stats.deleted += 1,
+            Err(e) => failures.push(format!("{:#}", e)),
+        }
+    }
+
+    // Completed transfers are recorded even when others failed, so a retry only redoes those
+    save_state(&state_path, &state)?;
+    if !failures.is_empty() {
+        bail!(
+            "{} of the transfers to R2 bucket '{}' failed:\n  {}",
+            failures.len(),
+            bucket,
+            failures.join("\n  ")
+        );
+    }
+
+    stats.elapsed = start.elapsed();
+    Ok(stats)
+}
+
+/// `prefix` with the trailing `/` that keeps it from matching sibling keys
+fn directory_prefix(prefix: &str) -> String {
+    let prefix = prefix.trim_start_matches('/');
+    if prefix.is_empty() || prefix.ends_with('/') {
+        prefix.to_string()
+    } else {
+        format!("{}/", prefix)
+    }
+}
+
+/// Syncs of one bucket under different prefixes keep separate state, named after a hash of the
+/// prefix since prefixes may hold any character
+fn state_path(bucket: &str, prefix: &str) -> PathBuf {
+    let file = if prefix.is_empty() {
+        format!("{}.json", bucket)
+    } else {
+        format!("{}-{}.json", bucket, &sha256_hex(prefix.as_bytes())[..12])
+    };
+    Path::new(R2_STATE_DIR).join(file)
+}
+
+fn save_state(path: &Path, state: &R2State) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(state)? + "\n")?;
+    Ok(())
+}
+
+/// MIME type served for a file, by extension
+pub fn content_type(path: &str) -> &'static str {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" | "map" => "application/json",
+        "webmanifest" => "application/manifest+json",
+        "xml" => "application/xml",
+        "txt" => "text/plain; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "wasm" => "application/wasm",
+        "pdf" => "application/pdf",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,85 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::http;
+use crate::utils::ports::{dev_url, local_port};
+use crate::utils::projects::WorkspaceProject;
+use anyhow::Result;
+use std::fmt;
+use std::time::Duration;
+
+/// How long a dev server gets to become ready before the next one starts anyway, unless
+/// `[dev] ready_timeout_secs` says otherwise
+pub const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long each probe waits for an answer
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What makes a dev server count as ready
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadyCheck {
+    /// The dev port accepts connections
+    Port(u16),
+    /// The URL answers with anything but a server error
+    Url(String),
+}
+
+impl fmt::Display for ReadyCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadyCheck::Port(port) => write!(f, "port {}", port),
+            ReadyCheck::Url(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+impl ReadyCheck {
+    /// `[projects.<name>.dev] ready`, a path on the dev server or a full URL, else the dev port
+    pub fn for_project(
+        project: &WorkspaceProject,
+        config: &MoonflareConfig,
+        https: bool,
+    ) -> Result<Self> {
+        let ready = config
+            .project(&project.name)
+            .and_then(|p| p.dev.ready.clone());
+        Ok(match ready {
+            Some(url) if url.contains("://") => ReadyCheck::Url(url),
+            Some(path) => ReadyCheck::Url(format!(
+                "{}/{}",
+                dev_url(project, config, https)?,
+                path.trim_start_matches('/')
+            )),
+            None => ReadyCheck::Port(local_port(project, config)?),
+        })
+    }
+
+    /// Probe once
+    pub async fn is_ready(&self) -> bool {
+        match self {
+            ReadyCheck::Port(port) => matches!(
+                tokio::time::timeout(
+                    PROBE_TIMEOUT,
+                    tokio::net::TcpStream::connect(("127.0.0.1", *port))
+                )
+                .await,
+                Ok(Ok(_))
+            ),
+            ReadyCheck::Url(url) => {
+                // Dev servers are local and may use the self-signed dev certificate
+                let Ok(client) = http::builder().and_then(|builder| {
+                    Ok(builder
+                        .no_proxy()
+                        .danger_accept_invalid_certs(true)
+                        .timeout(PROBE_TIMEOUT)
+                        .build()?)
+                }) else {
+                    return false;
+                };
+                client
+                    .get(url)
+                    .send()
+                    .await
+                    .is_ok_and(|response| !response.status().is_server_error())
+            }
+        }
+    }
+}
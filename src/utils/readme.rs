@@ -0,0 +1,313 @@
+use crate::utils::dev_vars::{DEV_VARS_FILE, required_vars};
+use crate::utils::moon_tasks::inherited_tasks;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::wrangler::{env_worker_name, read_wrangler_config};
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+pub const README_FILE: &str = "README.md";
+
+const SECTION_START: &str = "<!-- moonflare:describe:start -->";
+const SECTION_END: &str = "<!-- moonflare:describe:end -->";
+
+/// Binding kinds in a Wrangler configuration: the key, a label, and the field naming the
+/// resource the binding points at
+const BINDING_KINDS: [(&str, &str, &str); 11] = [
+    ("kv_namespaces", "KV namespace", "id"),
+    ("r2_buckets", "R2 bucket", "bucket_name"),
+    ("d1_databases", "D1 database", "database_name"),
+    ("services", "Service", "service"),
+    ("queues/producers", "Queue producer", "queue"),
+    ("analytics_engine_datasets", "Analytics Engine", "dataset"),
+    ("vectorize", "Vectorize index", "index_name"),
+    ("hyperdrive", "Hyperdrive", "id"),
+    ("workflows", "Workflow", "class_name"),
+    ("durable_objects/bindings", "Durable Object", "class_name"),
+    ("send_email", "Email", "destination_address"),
+];
+
+/// Single bindings declared as an object with a `binding` name
+const SINGLE_BINDINGS: [(&str, &str); 4] = [
+    ("ai", "Workers AI"),
+    ("browser", "Browser Rendering"),
+    ("images", "Images"),
+    ("assets", "Static assets"),
+];
+
+/// The generated documentation for `project`, between the markers that let it be refreshed
+/// without touching the rest of the README
+pub fn render_section(project: &WorkspaceProject) -> Result<String> {
+    let config = read_wrangler_config(&project.path)?;
+    let mut out = vec![
+        SECTION_START.to_string(),
+        format!(
+            "<!-- Generated by moonflare from moon.yml and the Wrangler configuration; refresh with `moonflare describe --project {} --write`. -->",
+            project.name
+        ),
+        String::new(),
+    ];
+
+    out.push("## Development".to_string());
+    out.push(String::new());
+    if project.is_crate() {
+        out.push(format!(
+            "`moonflare build {}` compiles the crate to WebAssembly, which `shared-wasm` gathers for every app and Worker.",
+            project.name
+        ));
+    } else {
+        out.push(format!(
+            "- `moonflare dev {}` starts the dev server",
+            project.name
+        ));
+        out.push(format!(
+            "- `moonflare build {}` builds it for production",
+            project.name
+        ));
+        out.push(format!(
+            "- `moonflare test {}` runs its tests",
+            project.name
+        ));
+    }
+    out.push(String::new());
+
+    let tasks = project_tasks(project);
+    if !tasks.is_empty() {
+        out.push("## Tasks".to_string());
+        out.push(String::new());
+        out.push("| Task | Runs | Defined in |".to_string());
+        out.push("| --- | --- | --- |".to_string());
+        for (name, (runs, inherited)) in &tasks {
+            out.push(format!(
+                "| `{}:{}` | {} | {} |",
+                project.name,
+                name,
+                runs,
+                if *inherited {
+                    "workspace (inherited)"
+                } else {
+                    "moon.yml"
+                }
+            ));
+        }
+        out.push(String::new());
+    }
+
+    if let Some(config) = &config {
+        let bindings = bindings(config);
+        if !bindings.is_empty() {
+            out.push("## Bindings".to_string());
+            out.push(String::new());
+            out.push("| Binding | Type | Resource |".to_string());
+            out.push("| --- | --- | --- |".to_string());
+            for (name, kind, resource) in bindings {
+                out.push(format!("| `{}` | {} | {} |", name, kind, resource));
+            }
+            out.push(String::new());
+        }
+
+        let vars = required_vars(&project.path)?;
+        if !vars.is_empty() {
+            out.push("## Environment Variables".to_string());
+            out.push(String::new());
+            out.push("| Variable | Default |".to_string());
+            out.push("| --- | --- |".to_string());
+            for var in &vars {
+                let default = match &var.default {
+                    Some(value) => format!("`{}`", value),
+                    None => "secret".to_string(),
+                };
+                out.push(format!("| `{}` | {} |", var.name, default));
+            }
+            out.push(String::new());
+            if vars.iter().any(|var| var.default.is_none()) {
+                out.push(format!(
+                    "Secrets are read from `{}` during development (`moonflare env pull` fills it in) and set with `wrangler secret put` for deployed Workers.",
+                    DEV_VARS_FILE
+                ));
+                out.push(String::new());
+            }
+        }
+
+        out.push("## Deployment".to_string());
+        out.push(String::new());
+        out.push(format!(
+            "`moonflare deploy {}` deploys the Worker `{}`.",
+            project.name,
+            env_worker_name(config, &project.name, None)
+        ));
+        let environments: Vec<&String> = config
+            .get("env")
+            .and_then(|env| env.as_object())
+            .map(|env| env.keys().collect())
+            .unwrap_or_default();
+        if !environments.is_empty() {
+            out.push(String::new());
+            out.push("| Environment | Worker | Deploy with |".to_string());
+            out.push("| --- | --- | --- |".to_string());
+            for env in environments {
+                out.push(format!(
+                    "| {} | `{}` | `moonflare deploy {} --env {}` |",
+                    env,
+                    env_worker_name(config, &project.name, Some(env)),
+                    project.name,
+                    env
+                ));
+            }
+        }
+        out.push(String::new());
+    }
+
+    // Trim the blank line ahead of the end marker
+    while out.last().is_some_and(|line| line.is_empty()) {
+        out.pop();
+    }
+    out.push(SECTION_END.to_string());
+    Ok(out.join("\n") + "\n")
+}
+
+/// Write the generated section into `project`'s README, creating the README when it has none.
+/// Returns the README's path when its content changed.
+pub fn write_readme(project: &WorkspaceProject) -> Result<Option<PathBuf>> {
+    let path = project.path.join(README_FILE);
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let section = render_section(project)?;
+
+    let updated = if let (Some(start), Some(end)) =
+        (existing.find(SECTION_START), existing.find(SECTION_END))
+    {
+        let after = existing[end + SECTION_END.len()..]
+            .strip_prefix('\n')
+            .unwrap_or(&existing[end + SECTION_END.len()..]);
+        format!("{}{}{}", &existing[..start], section, after)
+    } else if existing.trim().is_empty() {
+        format!("# {}\n\n{}", project.name, section)
+    } else {
+        format!("{}\n\n{}", existing.trim_end(), section)
+    };
+
+    if updated == existing {
+        return Ok(None);
+    }
+    fs::write(&path, updated)?;
+    Ok(Some(path))
+}
+
+/// Refresh the generated section of projects whose README already has one, after a command
+/// changed their tasks or configuration. READMEs without the section are left alone.
+pub fn refresh_readmes<'a>(projects: impl IntoIterator<Item = &'a WorkspaceProject>) {
+    for project in projects {
+        let has_section = fs::read_to_string(project.path.join(README_FILE))
+            .is_ok_and(|content| content.contains(SECTION_START));
+        if !has_section {
+            continue;
+        }
+        match write_readme(project) {
+            Ok(Some(path)) => println!("Updated {}", path.display()),
+            Ok(None) => {}
+            Err(e) => println!(
+                "Warning: Could not update the README of {}: {}",
+                project.name, e
+            ),
+        }
+    }
+}
+
+/// Tasks the project runs, with what each runs and whether it's inherited from the workspace
+fn project_tasks(project: &WorkspaceProject) -> BTreeMap<String, (String, bool)> {
+    let Some(config) = fs::read_to_string(project.path.join("moon.yml"))
+        .ok()
+        .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())
+    else {
+        return BTreeMap::new();
+    };
+
+    let mut tasks: BTreeMap<String, (String, bool)> = inherited_tasks(&config)
+        .into_iter()
+        .map(|(name, task)| (name, (task_summary(&task), true)))
+        .collect();
+    if let Some(own) = config.get("tasks").and_then(|t| t.as_mapping()) {
+        for (name, task) in own {
+            if let Some(name) = name.as_str() {
+                tasks.insert(name.to_string(), (task_summary(task), false));
+            }
+        }
+    }
+    tasks
+}
+
+/// A task's command line, or the first line of its script
+fn task_summary(task: &serde_yaml::Value) -> String {
+    let text = |value: &serde_yaml::Value| match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+
+    let summary = if let Some(script) = task.get("script").and_then(text) {
+        let mut lines = script.lines().filter(|line| !line.trim().is_empty());
+        let first = lines.next().unwrap_or_default().trim().to_string();
+        if lines.next().is_some() {
+            format!("{} …", first)
+        } else {
+            first
+        }
+    } else {
+        let mut parts: Vec<String> = task.get("command").and_then(text).into_iter().collect();
+        match task.get("args") {
+            Some(serde_yaml::Value::Sequence(args)) => parts.extend(args.iter().filter_map(text)),
+            Some(args) => parts.extend(text(args)),
+            None => {}
+        }
+        parts.join(" ")
+    };
+
+    if summary.is_empty() {
+        "dependencies only".to_string()
+    } else {
+        format!("`{}`", summary.replace('|', "\\|"))
+    }
+}
+
+/// Every binding in the top-level configuration as (name, type, resource)
+fn bindings(config: &Value) -> Vec<(String, &'static str, String)> {
+    let mut bindings = Vec::new();
+    for (key, label, resource) in BINDING_KINDS {
+        let Some(list) = config
+            .pointer(&format!("/{}", key))
+            .and_then(|l| l.as_array())
+        else {
+            continue;
+        };
+        for entry in list {
+            let name = entry
+                .get("binding")
+                .or_else(|| entry.get("name"))
+                .and_then(|n| n.as_str());
+            if let Some(name) = name {
+                let resource = entry
+                    .get(resource)
+                    .and_then(|r| r.as_str())
+                    .unwrap_or("-")
+                    .to_string();
+                bindings.push((name.to_string(), label, resource));
+            }
+        }
+    }
+    for (key, label) in SINGLE_BINDINGS {
+        if let Some(name) = config
+            .pointer(&format!("/{}/binding", key))
+            .and_then(|n| n.as_str())
+        {
+            let resource = config
+                .pointer(&format!("/{}/directory", key))
+                .and_then(|d| d.as_str())
+                .unwrap_or("-")
+                .to_string();
+            bindings.push((name.to_string(), label, resource));
+        }
+    }
+    bindings
+}
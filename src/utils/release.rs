@@ -0,0 +1,292 @@
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::version::Version;
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Changelog each project keeps next to its manifest
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+/// How far a release moves the version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Bump {
+    /// Before 1.0.0 a breaking change only bumps the minor version
+    pub fn apply(self, version: Version) -> Version {
+        match self {
+            Bump::Major if version.major > 0 => Version {
+                major: version.major + 1,
+                minor: 0,
+                patch: 0,
+            },
+            Bump::Major | Bump::Minor => Version {
+                minor: version.minor + 1,
+                patch: 0,
+                ..version
+            },
+            Bump::Patch => Version {
+                patch: version.patch + 1,
+                ..version
+            },
+        }
+    }
+}
+
+/// A commit whose subject follows the Conventional Commits format
+#[derive(Debug, Clone)]
+pub struct ConventionalCommit {
+    pub sha: String,
+    pub kind: String,
+    pub scope: Option<String>,
+    pub description: String,
+    pub breaking: bool,
+}
+
+impl ConventionalCommit {
+    /// Parse `type(scope)!: description`, with `BREAKING CHANGE:` in the body also marking a
+    /// breaking change. None for subjects that aren't conventional.
+    pub fn parse(sha: &str, subject: &str, body: &str) -> Option<Self> {
+        let pattern = Regex::new(r"^(\w+)(?:\(([^)]*)\))?(!)?:\s*(.+)$").expect("valid regex");
+        let caps = pattern.captures(subject.trim())?;
+        Some(Self {
+            sha: sha.to_string(),
+            kind: caps[1].to_lowercase(),
+            scope: caps.get(2).map(|m| m.as_str().to_string()),
+            description: caps[4].trim().to_string(),
+            breaking: caps.get(3).is_some()
+                || body.lines().any(|l| {
+                    l.starts_with("BREAKING CHANGE:") || l.starts_with("BREAKING-CHANGE:")
+                }),
+        })
+    }
+
+    /// The bump this commit calls for; None for types that don't release (chore, docs, ...)
+    pub fn bump(&self) -> Option<Bump> {
+        if self.breaking {
+            return Some(Bump::Major);
+        }
+        match self.kind.as_str() {
+            "feat" => Some(Bump::Minor),
+            "fix" | "perf" => Some(Bump::Patch),
+            _ => None,
+        }
+    }
+
+    /// The changelog heading the commit is listed under, if any
+    fn changelog_section(&self) -> Option<&'static str> {
+        if self.breaking {
+            return Some("Breaking Changes");
+        }
+        match self.kind.as_str() {
+            "feat" => Some("Features"),
+            "fix" => Some("Bug Fixes"),
+            "perf" => Some("Performance"),
+            _ => None,
+        }
+    }
+}
+
+/// Git tag marking a project's release
+pub fn tag_name(project: &str, version: Version) -> String {
+    format!("{}-v{}", project, version)
+}
+
+/// The newest `<project>-vX.Y.Z` tag and its version
+pub fn latest_tag(project: &str) -> Option<(String, Version)> {
+    let prefix = format!("{}-v", project);
+    let output = Command::new("git")
+        .args(["tag", "--list", &format!("{}*", prefix)])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|tag| {
+            let version = tag.strip_prefix(&prefix)?;
+            // `api-v1.0.0` shouldn't match project `api-v1`'s tags
+            version
+                .starts_with(|c: char| c.is_ascii_digit())
+                .then(|| Version::parse(version))
+                .flatten()
+                .map(|v| (tag.to_string(), v))
+        })
+        .max_by_key(|(_, version)| *version)
+}
+
+/// Conventional commits touching the project since the given tag (or ever), newest first.
+/// Commits that don't follow the format are skipped.
+pub fn commits_since(
+    project: &WorkspaceProject,
+    tag: Option<&str>,
+) -> Result<Vec<ConventionalCommit>> {
+    let mut git = Command::new("git");
+    git.args(["log", "--format=%H%x1f%s%x1f%b%x1e"]);
+    if let Some(tag) = tag {
+        git.arg(format!("{}..HEAD", tag));
+    }
+    let output = git
+        .arg("--")
+        .arg(&project.path)
+        .output()
+        .context("Failed to run git log")?;
+    if !output.status.success() {
+        bail!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+            let sha = fields.next()?;
+            let subject = fields.next()?;
+            ConventionalCommit::parse(sha, subject, fields.next().unwrap_or(""))
+        })
+        .collect())
+}
+
+/// The manifest holding a project's version: Cargo.toml for crates, package.json otherwise
+pub fn manifest_path(project: &WorkspaceProject) -> PathBuf {
+    if project.is_crate() {
+        project.path.join("Cargo.toml")
+    } else {
+        project.path.join("package.json")
+    }
+}
+
+/// The version in the project's manifest, if it has one
+pub fn manifest_version(path: &Path) -> Result<Option<Version>> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let version = if path.extension().is_some_and(|e| e == "json") {
+        let package: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid {}", path.display()))?;
+        package["version"].as_str().map(str::to_string)
+    } else {
+        let manifest: toml::Value =
+            toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+        let version = manifest.get("package").and_then(|p| p.get("version"));
+        if version.is_some_and(|v| v.is_table()) {
+            bail!(
+                "{} inherits its version from the Cargo workspace (version.workspace = true)",
+                path.display()
+            );
+        }
+        version.and_then(|v| v.as_str()).map(str::to_string)
+    };
+    Ok(version.as_deref().and_then(Version::parse))
+}
+
+/// Write the version into the manifest, keeping the rest of the file as it was
+pub fn set_manifest_version(path: &Path, version: Version) -> Result<()> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let updated = if path.extension().is_some_and(|e| e == "json") {
+        set_package_json_version(&content, version)
+            .with_context(|| format!("Invalid {}", path.display()))?
+    } else {
+        set_cargo_version(&content, version)
+    };
+    fs::write(path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn set_package_json_version(content: &str, version: Version) -> Result<String> {
+    let pattern = Regex::new(r#"(?m)^(\s*"version"\s*:\s*)"[^"]*""#).expect("valid regex");
+    if pattern.is_match(content) {
+        return Ok(pattern
+            .replace(content, format!("${{1}}\"{}\"", version))
+            .into_owned());
+    }
+    let mut package: serde_json::Value = serde_json::from_str(content)?;
+    package["version"] = version.to_string().into();
+    Ok(serde_json::to_string_pretty(&package)? + "\n")
+}
+
+fn set_cargo_version(content: &str, version: Version) -> String {
+    let mut in_package = false;
+    let mut updated = false;
+    let mut output: String = content
+        .split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                in_package = trimmed == "[package]";
+            } else if in_package
+                && !updated
+                && trimmed
+                    .split_once('=')
+                    .is_some_and(|(key, _)| key.trim() == "version")
+            {
+                updated = true;
+                let ending = if line.ends_with('\n') { "\n" } else { "" };
+                return format!("version = \"{}\"{}", version, ending);
+            }
+            line.to_string()
+        })
+        .collect();
+    if !updated && let Some(index) = output.find("[package]\n") {
+        output.insert_str(
+            index + "[package]\n".len(),
+            &format!("version = \"{}\"\n", version),
+        );
+    }
+    output
+}
+
+/// A changelog section for the release, grouped by breaking changes, features and fixes
+pub fn changelog_entry(version: Version, date: &str, commits: &[ConventionalCommit]) -> String {
+    let mut entry = format!("## {} ({})\n", version, date);
+    for title in ["Breaking Changes", "Features", "Bug Fixes", "Performance"] {
+        let commits: Vec<_> = commits
+            .iter()
+            .filter(|c| c.changelog_section() == Some(title))
+            .collect();
+        if commits.is_empty() {
+            continue;
+        }
+        entry.push_str(&format!("\n### {}\n\n", title));
+        for commit in commits {
+            let scope = commit
+                .scope
+                .as_ref()
+                .map(|s| format!("**{}:** ", s))
+                .unwrap_or_default();
+            entry.push_str(&format!(
+                "- {}{} ({})\n",
+                scope,
+                commit.description,
+                &commit.sha[..commit.sha.len().min(7)]
+            ));
+        }
+    }
+    entry
+}
+
+/// Add the entry to the top of the changelog, below its title, creating the file when missing
+pub fn prepend_changelog(path: &Path, entry: &str) -> Result<()> {
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let (title, rest) = match existing.strip_prefix("# ") {
+        Some(_) => match existing.split_once('\n') {
+            Some((title, rest)) => (title.to_string(), rest.trim_start().to_string()),
+            None => (existing.clone(), String::new()),
+        },
+        None => ("# Changelog".to_string(), existing.trim_start().to_string()),
+    };
+    let mut content = format!("{}\n\n{}", title, entry);
+    if !rest.is_empty() {
+        content.push('\n');
+        content.push_str(&rest);
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
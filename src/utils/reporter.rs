@@ -0,0 +1,490 @@
+use crate::utils::events::{self, Event};
+use crate::utils::moon::run_moon_command;
+use crate::utils::projects::find_project;
+#[cfg(feature = "telemetry")]
+use crate::utils::trace;
+use crate::utils::{interrupt, task_env, timeout};
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How command results are reported, selected with `--reporter`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReporterKind {
+    /// Human-readable output only
+    #[default]
+    Console,
+    /// A JSON summary of the command and its tasks
+    Json,
+    /// GitHub Actions workflow commands (`::error file=...`) for check-run annotations
+    Github,
+    /// JUnit XML, one test case per task
+    Junit,
+    /// A Markdown table of tasks by project, for CI job summaries
+    Markdown,
+}
+
+/// Commands reported to GitHub Actions without `--reporter`
+const ACTIONS_REPORTED: [&str; 1] = ["test"];
+
+/// Where JUnit reports written for GitHub Actions go, as `<command>.xml`
+pub const ACTIONS_REPORT_DIR: &str = ".moonflare/reports";
+
+/// Outcome of one Moon target run by a command
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskResult {
+    pub target: String,
+    /// Workspace-relative path of the target's project, when known
+    pub project_path: Option<String>,
+    pub success: bool,
+    #[serde(rename = "duration_secs", serialize_with = "as_secs")]
+    pub duration: Duration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Everything a reporter needs to describe a finished command
+#[derive(Debug, Serialize)]
+pub struct CommandReport {
+    pub command: String,
+    pub success: bool,
+    #[serde(rename = "duration_secs", serialize_with = "as_secs")]
+    pub duration: Duration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub tasks: Vec<TaskResult>,
+}
+
+fn as_secs<S: serde::Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64((duration.as_secs_f64() * 1000.0).round() / 1000.0)
+}
+
+/// Renders a finished command for a particular consumer
+pub trait Reporter {
+    /// Rendered report, or None when the console output already says everything
+    fn render(&self, report: &CommandReport) -> Option<String>;
+}
+
+pub struct ConsoleReporter;
+pub struct JsonReporter;
+pub struct GithubReporter;
+pub struct JunitReporter;
+pub struct MarkdownReporter;
+
+impl Reporter for ConsoleReporter {
+    fn render(&self, _report: &CommandReport) -> Option<String> {
+        None
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn render(&self, report: &CommandReport) -> Option<String> {
+        serde_json::to_string_pretty(report)
+            .ok()
+            .map(|json| json + "\n")
+    }
+}
+
+impl Reporter for GithubReporter {
+    fn render(&self, report: &CommandReport) -> Option<String> {
+        let mut lines = Vec::new();
+        for task in report.tasks.iter().filter(|t| !t.success) {
+            let mut properties = Vec::new();
+            if let Some(path) = &task.project_path {
+                properties.push(format!(
+                    "file={}",
+                    escape_property(&format!("{}/moon.yml", path))
+                ));
+            }
+            properties.push(format!(
+                "title={}",
+                escape_property(&format!("{} failed", task.target))
+            ));
+            lines.push(format!(
+                "::error {}::{}",
+                properties.join(","),
+                escape_data(task.message.as_deref().unwrap_or("Task failed"))
+            ));
+        }
+
+        // Failures that happened outside any task (configuration, gates, ...) still need surfacing
+        if let Some(error) = &report.error
+            && report.tasks.iter().all(|t| t.success)
+        {
+            lines.push(format!(
+                "::error title={}::{}",
+                escape_property(&format!("moonflare {} failed", report.command)),
+                escape_data(error)
+            ));
+        }
+
+        if report.success {
+            lines.push(format!(
+                "::notice title={}::{} task(s) succeeded in {:.1}s",
+                escape_property(&format!("moonflare {}", report.command)),
+                report.tasks.len(),
+                report.duration.as_secs_f64()
+            ));
+        }
+
+        Some(lines.join("\n") + "\n")
+    }
+}
+
+impl Reporter for JunitReporter {
+    fn render(&self, report: &CommandReport) -> Option<String> {
+        // A command that failed before running any task is reported as a single failed case
+        let tasks = if report.tasks.is_empty() {
+            vec![TaskResult {
+                target: report.command.clone(),
+                project_path: None,
+                success: report.success,
+                duration: report.duration,
+                message: report.error.clone(),
+            }]
+        } else {
+            report.tasks.clone()
+        };
+
+        let failures = tasks.iter().filter(|t| !t.success).count();
+        let suite = format!("moonflare {}", report.command);
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            escape_xml(&suite),
+            tasks.len(),
+            failures,
+            report.duration.as_secs_f64()
+        ));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            escape_xml(&suite),
+            tasks.len(),
+            failures,
+            report.duration.as_secs_f64()
+        ));
+
+        for task in &tasks {
+            let classname = task.project_path.as_deref().unwrap_or(&report.command);
+            let open = format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape_xml(&task.target),
+                escape_xml(classname),
+                task.duration.as_secs_f64()
+            );
+            if task.success {
+                xml.push_str(&format!("{} />\n", open));
+            } else {
+                let message = task.message.as_deref().unwrap_or("Task failed");
+                xml.push_str(&format!(
+                    "{}>\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                    open,
+                    escape_xml(message.lines().next().unwrap_or_default()),
+                    escape_xml(message)
+                ));
+            }
+        }
+
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        Some(xml)
+    }
+}
+
+impl Reporter for MarkdownReporter {
+    fn render(&self, report: &CommandReport) -> Option<String> {
+        let failed: Vec<&TaskResult> = report.tasks.iter().filter(|t| !t.success).collect();
+        let mut projects: Vec<&str> = report.tasks.iter().map(project_of).collect();
+        projects.sort_unstable();
+        projects.dedup();
+
+        let mut markdown = format!(
+            "### {} moonflare {} {}\n\n{} task(s) in {} project(s), {} failed, {:.1}s\n\n",
+            if report.success { "✅" } else { "❌" },
+            report.command,
+            if report.success { "passed" } else { "failed" },
+            report.tasks.len(),
+            projects.len(),
+            failed.len(),
+            report.duration.as_secs_f64()
+        );
+        if !report.tasks.is_empty() {
+            markdown
+                .push_str("| Project | Task | Result | Duration |\n| --- | --- | --- | --- |\n");
+            for task in &report.tasks {
+                markdown.push_str(&format!(
+                    "| {} | `{}` | {} | {:.1}s |\n",
+                    escape_cell(project_of(task)),
+                    escape_cell(&task.target),
+                    if task.success {
+                        "✅ passed"
+                    } else {
+                        "❌ failed"
+                    },
+                    task.duration.as_secs_f64()
+                ));
+            }
+            markdown.push('\n');
+        }
+
+        for task in failed {
+            markdown.push_str(&format!(
+                "<details><summary>{}</summary>\n\n```\n{}\n```\n\n</details>\n\n",
+                escape_xml(&task.target),
+                task.message.as_deref().unwrap_or("Task failed")
+            ));
+        }
+        // Failures that happened outside any task (configuration, gates, ...)
+        if let Some(error) = &report.error
+            && report.tasks.iter().all(|t| t.success)
+        {
+            markdown.push_str(&format!("```\n{}\n```\n\n", error));
+        }
+        Some(markdown)
+    }
+}
+
+pub fn reporter_for(kind: ReporterKind) -> Box<dyn Reporter> {
+    match kind {
+        ReporterKind::Console => Box::new(ConsoleReporter),
+        ReporterKind::Json => Box::new(JsonReporter),
+        ReporterKind::Github => Box::new(GithubReporter),
+        ReporterKind::Junit => Box::new(JunitReporter),
+        ReporterKind::Markdown => Box::new(MarkdownReporter),
+    }
+}
+
+/// The job summary file GitHub Actions renders after the step, when running in a workflow
+pub fn job_summary_path() -> Option<PathBuf> {
+    if std::env::var("GITHUB_ACTIONS").as_deref() != Ok("true") {
+        return None;
+    }
+    std::env::var_os("GITHUB_STEP_SUMMARY")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Whether `command` writes the GitHub Actions reports on top of its console output: it runs
+/// in a workflow and no `--reporter` was chosen
+pub fn reports_to_actions(command: &str, chosen: Option<ReporterKind>) -> bool {
+    chosen.is_none() && ACTIONS_REPORTED.contains(&command) && job_summary_path().is_some()
+}
+
+/// Write JUnit XML to `.moonflare/reports/<command>.xml` and append the Markdown summary to
+/// the job summary
+pub fn emit_actions_reports(report: &CommandReport) -> Result<()> {
+    let Some(summary) = job_summary_path() else {
+        return Ok(());
+    };
+    emit_report(
+        ReporterKind::Junit,
+        report,
+        Some(&Path::new(ACTIONS_REPORT_DIR).join(format!("{}.xml", report.command))),
+    )?;
+    if let Some(markdown) = MarkdownReporter.render(report) {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&summary)
+            .with_context(|| format!("Failed to open {}", summary.display()))?;
+        file.write_all(markdown.as_bytes())
+            .with_context(|| format!("Failed to write {}", summary.display()))?;
+    }
+    Ok(())
+}
+
+/// Render the report and write it to `output`, or stdout when no path is given
+pub fn emit_report(
+    kind: ReporterKind,
+    report: &CommandReport,
+    output: Option<&Path>,
+) -> Result<()> {
+    let Some(rendered) = reporter_for(kind).render(report) else {
+        return Ok(());
+    };
+
+    match output {
+        Some(path) => {
+            if let Some(parent) = path.parent()
+                && !parent.as_os_str().is_empty()
+            {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, rendered)
+                .with_context(|| format!("Failed to write report to {}", path.display()))
+        }
+        None => {
+            print!("{}", rendered);
+            Ok(())
+        }
+    }
+}
+
+/// Task results collected while a command runs. When `granular`, commands run each target
+/// on its own so every target gets an individual outcome.
+#[derive(Debug, Default)]
+pub struct TaskLog {
+    granular: bool,
+    tasks: Mutex<Vec<TaskResult>>,
+}
+
+impl TaskLog {
+    pub fn new(granular: bool) -> Self {
+        Self {
+            granular,
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn is_granular(&self) -> bool {
+        self.granular
+    }
+
+    pub fn record(&self, target: &str, success: bool, duration: Duration, message: Option<String>) {
+        let project_path = target
+            .split(':')
+            .next()
+            .filter(|name| !name.is_empty())
+            .and_then(find_project)
+            .map(|project| project.relative_path());
+
+        interrupt::note_task(target, success);
+        #[cfg(feature = "telemetry")]
+        trace::task(target, success, duration, message.as_deref());
+        events::emit(Event::TaskFinished {
+            target,
+            project_path: project_path.as_deref(),
+            success,
+            duration_secs: events::secs(duration),
+            message: message.as_deref(),
+        });
+
+        self.tasks
+            .lock()
+            .expect("task log poisoned")
+            .push(TaskResult {
+                target: target.to_string(),
+                project_path,
+                success,
+                duration,
+                message,
+            });
+    }
+
+    pub fn into_tasks(self) -> Vec<TaskResult> {
+        self.tasks.into_inner().expect("task log poisoned")
+    }
+}
+
+/// Run `args` as one Moon invocation and record its outcome for every target, or run the
+/// targets one by one when the log is granular
+pub async fn run_targets(args: &[&str], targets: &[String], log: &TaskLog) -> Result<()> {
+    if log.is_granular() || task_env::needs_separate_runs(targets) {
+        return run_targets_individually(targets, log).await;
+    }
+
+    let started = Instant::now();
+    let result = run_moon_command(args).await;
+    let message = result.as_ref().err().map(|e| e.to_string());
+    for target in targets {
+        log.record(target, result.is_ok(), started.elapsed(), message.clone());
+    }
+    result
+}
+
+/// Run each target as its own Moon invocation, recording every outcome and continuing past
+/// failures so the report covers the whole set. When the command's time box runs out, the
+/// targets that never started are recorded as not run and the error lists how far it got.
+pub async fn run_targets_individually(targets: &[String], log: &TaskLog) -> Result<()> {
+    let mut failed = Vec::new();
+    for (index, target) in targets.iter().enumerate() {
+        let started = Instant::now();
+        match run_moon_command(&["run", target]).await {
+            Ok(()) => log.record(target, true, started.elapsed(), None),
+            Err(e) if timeout::is_timeout(&e) => {
+                log.record(target, false, started.elapsed(), Some(e.to_string()));
+                let not_run = &targets[index + 1..];
+                for skipped in not_run {
+                    log.record(
+                        skipped,
+                        false,
+                        Duration::ZERO,
+                        Some("Not run: the time box ran out".to_string()),
+                    );
+                }
+                bail!(
+                    "{}",
+                    timed_out_summary(&targets[..index], &failed, target, not_run)
+                );
+            }
+            Err(e) => {
+                log.record(target, false, started.elapsed(), Some(e.to_string()));
+                failed.push(target.as_str());
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        bail!("{} target(s) failed: {}", failed.len(), failed.join(", "));
+    }
+    Ok(())
+}
+
+/// How far a time-boxed run got: what finished, what was stopped and what never started
+fn timed_out_summary(
+    finished: &[String],
+    failed: &[&str],
+    stopped: &str,
+    not_run: &[String],
+) -> String {
+    let mut summary = format!(
+        "Timed out after {}: {} of {} target(s) finished",
+        timeout::format_duration(timeout::limit().unwrap_or_default()),
+        finished.len(),
+        finished.len() + 1 + not_run.len()
+    );
+    if !failed.is_empty() {
+        summary.push_str(&format!(
+            " ({} failed: {})",
+            failed.len(),
+            failed.join(", ")
+        ));
+    }
+    summary.push_str(&format!("; stopped {}", stopped));
+    if !not_run.is_empty() {
+        summary.push_str(&format!("; not run: {}", not_run.join(", ")));
+    }
+    summary
+}
+
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Project name of a `project:task` target
+fn project_of(task: &TaskResult) -> &str {
+    task.target.split(':').next().unwrap_or(&task.target)
+}
+
+fn escape_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,277 @@
+use crate::utils::artifacts::BUILD_MANIFEST_FILE;
+use crate::utils::history::HISTORY_FILE;
+use crate::utils::manifest::DEPLOYMENT_MANIFEST;
+use crate::utils::ports::PORTS_FILE;
+use crate::utils::version::Version;
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+/// Environment details written at the root of every repro archive
+pub const REPRO_INFO_FILE: &str = "repro.json";
+
+/// Placeholder left where a secret-looking value was
+pub const REDACTED: &str = "<redacted>";
+
+/// Configuration files packed wherever they are in the workspace
+const CONFIG_FILES: [&str; 18] = [
+    "moon.yml",
+    "package.json",
+    "Cargo.toml",
+    "wrangler.toml",
+    "wrangler.json",
+    "wrangler.jsonc",
+    "wrangler-defaults.toml",
+    "wrangler-defaults.json",
+    "wrangler-defaults.jsonc",
+    "moonflare.toml",
+    "moonflare-meta.toml",
+    "pnpm-workspace.yaml",
+    "flags.toml",
+    "mock-api.json",
+    ".prototools",
+    "rust-toolchain.toml",
+    ".npmrc",
+    ".nvmrc",
+];
+
+/// Config files of the frameworks projects build with, matched by name before the extension
+const FRAMEWORK_CONFIGS: [&str; 3] = ["astro.config", "vite.config", "vitest.config"];
+
+/// Moonflare's own records of the workspace, packed when present
+const RECORD_FILES: [&str; 4] = [
+    DEPLOYMENT_MANIFEST,
+    BUILD_MANIFEST_FILE,
+    HISTORY_FILE,
+    PORTS_FILE,
+];
+
+/// Directories never packed: installed dependencies, build output, local state and backups
+const SKIPPED_DIRECTORIES: [&str; 8] = [
+    "node_modules",
+    "target",
+    "dist",
+    ".wrangler",
+    ".astro",
+    ".git",
+    ".moonflare",
+    "pkg",
+];
+
+/// Keys whose values look like credentials
+pub const SECRET_KEY_PATTERN: &str =
+    r"(?i)token|secret|password|passwd|api_?key|_auth|credential|private_key|webhook|dsn";
+
+/// Versions and platform of the machine a repro was packed on
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReproInfo {
+    pub created_at: String,
+    pub moonflare: String,
+    pub os: String,
+    pub arch: String,
+    /// `<tool> --version` output of node, pnpm, moon, cargo and wrangler, or None when missing
+    pub tools: Vec<(String, Option<String>)>,
+    /// Workspace-relative paths of the packed files
+    pub files: Vec<String>,
+    /// Files that had values redacted, with how many
+    pub redacted: Vec<(String, usize)>,
+}
+
+impl ReproInfo {
+    pub fn detect() -> Self {
+        Self {
+            created_at: chrono::Utc::now().to_rfc3339(),
+            moonflare: Version::current().to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            tools: tool_versions(),
+            files: Vec::new(),
+            redacted: Vec::new(),
+        }
+    }
+}
+
+/// `--version` output of node, pnpm, moon, cargo and wrangler, or None for those missing
+pub fn tool_versions() -> Vec<(String, Option<String>)> {
+    ["node", "pnpm", "moon", "cargo"]
+        .iter()
+        .map(|tool| (tool.to_string(), tool_version(tool, &["--version"])))
+        .chain(std::iter::once((
+            "wrangler".to_string(),
+            tool_version("pnpm", &["exec", "wrangler", "--version"]),
+        )))
+        .collect()
+}
+
+fn tool_version(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// Workspace-relative paths of the files a repro packs: configuration anywhere in the
+/// workspace, `.moon` settings and moonflare's manifests and history. `.dev.vars`, `.env`
+/// files and sources are never included.
+pub fn repro_files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !(entry.file_type().is_dir()
+                    && SKIPPED_DIRECTORIES.contains(&entry.file_name().to_string_lossy().as_ref()))
+        })
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(root).ok()?.to_path_buf();
+            let name = entry.file_name().to_string_lossy();
+            let in_moon_dir = relative.starts_with(".moon")
+                && matches!(
+                    relative.extension().and_then(|e| e.to_str()),
+                    Some("yml" | "yaml")
+                );
+            let packed = CONFIG_FILES.contains(&name.as_ref())
+                || (name.starts_with("tsconfig") && name.ends_with(".json"))
+                || FRAMEWORK_CONFIGS
+                    .iter()
+                    .any(|config| name.split('.').take(2).collect::<Vec<_>>().join(".") == *config)
+                || in_moon_dir;
+            packed.then_some(relative)
+        })
+        .collect();
+    files.extend(
+        RECORD_FILES
+            .iter()
+            .map(PathBuf::from)
+            .filter(|path| root.join(path).is_file()),
+    );
+    files.sort();
+    files
+}
+
+/// Replace the values of secret-looking keys (`token`, `password`, `webhook`, ...) on each line
+/// of a TOML, JSON, YAML or `.npmrc` file, keeping the rest of the file as written. Returns the
+/// redacted content and how many values were replaced.
+pub fn redact(content: &str) -> (String, usize) {
+    let assignment = Regex::new(
+        r#"^(\s*-?\s*["']?)([^"'\s:=]+)(["']?\s*[:=]\s*)(["']?)([^"'\[\{\s][^"']*?)(["']?)(\s*,?\s*(?:#.*)?)$"#,
+    )
+    .expect("valid regex");
+    // `.npmrc` keys carry the registry, e.g. `//registry.npmjs.org/:_authToken=...`
+    let npmrc =
+        Regex::new(r"^(\S*:(?:_authToken|_auth|_password)\s*=\s*)(.+)$").expect("valid regex");
+    let secret = Regex::new(SECRET_KEY_PATTERN).expect("valid regex");
+    // References to the environment and plain flags say nothing secret
+    let kept = |value: &str| {
+        value.starts_with('$')
+            || matches!(value, "true" | "false" | "null")
+            || value.parse::<f64>().is_ok()
+    };
+
+    let mut count = 0;
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if let Some(caps) = npmrc.captures(line)
+                && !kept(&caps[2])
+            {
+                count += 1;
+                return format!("{}{}", &caps[1], REDACTED);
+            }
+            match assignment.captures(line) {
+                Some(caps) if secret.is_match(&caps[2]) && !kept(&caps[5]) => {
+                    count += 1;
+                    format!(
+                        "{}{}{}{}{}{}{}",
+                        &caps[1], &caps[2], &caps[3], &caps[4], REDACTED, &caps[6], &caps[7]
+                    )
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect();
+
+    let mut redacted = lines.join("\n");
+    if content.ends_with('\n') {
+        redacted.push('\n');
+    }
+    (redacted, count)
+}
+
+/// Copy the repro files of the workspace at `root` into `staging`, redacted, with the
+/// environment details alongside
+pub fn stage(root: &Path, staging: &Path) -> Result<ReproInfo> {
+    let mut info = ReproInfo::detect();
+    for relative in repro_files(root) {
+        let content = fs::read_to_string(root.join(&relative))
+            .with_context(|| format!("Failed to read {}", relative.display()))?;
+        let (content, count) = redact(&content);
+        let target = staging.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, content)?;
+
+        let path = relative.to_string_lossy().replace('\\', "/");
+        if count > 0 {
+            info.redacted.push((path.clone(), count));
+        }
+        info.files.push(path);
+    }
+    fs::write(
+        staging.join(REPRO_INFO_FILE),
+        serde_json::to_string_pretty(&info)? + "\n",
+    )?;
+    Ok(info)
+}
+
+/// Write `dir` as a gzipped tarball at `archive`
+pub fn pack(dir: &Path, archive: &Path) -> Result<()> {
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dir)
+        .arg(".")
+        .status()
+        .context("Failed to run tar to write the archive")?;
+    if !status.success() {
+        bail!("tar failed to write {}", archive.display());
+    }
+    Ok(())
+}
+
+/// Extract a repro archive into `dir` and read its environment details
+pub fn unpack(archive: &Path, dir: &Path) -> Result<ReproInfo> {
+    fs::create_dir_all(dir)?;
+    let status = Command::new("tar")
+        .arg("-xzf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dir)
+        .status()
+        .context("Failed to run tar to extract the archive")?;
+    if !status.success() {
+        bail!("tar failed to extract {}", archive.display());
+    }
+
+    let info_path = dir.join(REPRO_INFO_FILE);
+    let content = fs::read_to_string(&info_path).with_context(|| {
+        format!(
+            "{} isn't a moonflare repro archive: it has no {}",
+            archive.display(),
+            REPRO_INFO_FILE
+        )
+    })?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid {}", info_path.display()))
+}
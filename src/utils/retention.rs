@@ -0,0 +1,213 @@
+use crate::commands::bench::BENCH_REPORT_DIR;
+use crate::utils::backup::BACKUP_DIR;
+use crate::utils::config::{LayoutConfig, RetentionConfig};
+use crate::utils::fs::get_typescript_projects;
+use crate::utils::platform;
+use anyhow::{Context, Result};
+use convert_case::{Case, Casing};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// An artifact the retention policy would remove
+#[derive(Debug, Clone)]
+pub struct PruneCandidate {
+    pub path: PathBuf,
+    pub reason: String,
+    pub bytes: u64,
+}
+
+/// Everything the retention policy would remove right now
+pub fn prune_candidates(policy: &RetentionConfig) -> Result<Vec<PruneCandidate>> {
+    let mut candidates = Vec::new();
+
+    candidates.extend(expired_entries(Path::new(BACKUP_DIR), "snapshot", policy)?);
+    candidates.extend(expired_entries(
+        Path::new(BENCH_REPORT_DIR),
+        "bench report",
+        policy,
+    )?);
+    candidates.extend(stale_wasm()?);
+
+    if let Some(max_age) = policy.max_age() {
+        for project in get_typescript_projects() {
+            let dist = project.join("dist");
+            if dist.is_dir() && age(&dist) > max_age {
+                candidates.push(candidate(
+                    dist,
+                    format!(
+                        "build output older than {} days",
+                        policy.max_age_days.unwrap_or(0)
+                    ),
+                ));
+            }
+        }
+    }
+
+    // Nothing inside a directory that is already being removed needs listing separately
+    let directories: Vec<PathBuf> = candidates
+        .iter()
+        .filter(|c| c.path.is_dir())
+        .map(|c| c.path.clone())
+        .collect();
+    candidates.retain(|c| {
+        !directories
+            .iter()
+            .any(|dir| c.path != *dir && c.path.starts_with(dir))
+    });
+
+    Ok(candidates)
+}
+
+pub fn remove_candidates(candidates: &[PruneCandidate]) -> Result<()> {
+    for candidate in candidates {
+        if candidate.path.is_dir() {
+            fs::remove_dir_all(platform::long_path(&candidate.path))
+        } else {
+            fs::remove_file(&candidate.path)
+        }
+        .with_context(|| format!("Failed to remove {}", candidate.path.display()))?;
+    }
+    Ok(())
+}
+
+/// Size of a file or directory tree in bytes
+pub fn disk_usage(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn candidate(path: PathBuf, reason: String) -> PruneCandidate {
+    let bytes = disk_usage(&path);
+    PruneCandidate {
+        path,
+        reason,
+        bytes,
+    }
+}
+
+fn age(path: &Path) -> Duration {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .unwrap_or_default()
+}
+
+/// Entries of a timestamp-named directory beyond the newest `keep_last` that are also older
+/// than `max_age_days` (when set)
+fn expired_entries(
+    dir: &Path,
+    kind: &str,
+    policy: &RetentionConfig,
+) -> Result<Vec<PruneCandidate>> {
+    if !dir.exists() || (policy.keep_last.is_none() && policy.max_age_days.is_none()) {
+        return Ok(Vec::new());
+    }
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)?.flatten().map(|e| e.path()).collect();
+    // Names start with a sortable timestamp, so newest sorts last
+    entries.sort();
+    entries.reverse();
+
+    let keep_last = policy.keep_last.unwrap_or(0);
+    let max_age = policy.max_age();
+
+    Ok(entries
+        .into_iter()
+        .enumerate()
+        .filter(|(index, path)| {
+            *index >= keep_last && max_age.is_none_or(|max_age| age(path) > max_age)
+        })
+        .map(|(_, path)| {
+            let reason = if max_age.is_none() {
+                format!("{} beyond the newest {}", kind, keep_last)
+            } else {
+                format!(
+                    "{} older than {} days",
+                    kind,
+                    policy.max_age_days.unwrap_or(0)
+                )
+            };
+            candidate(path, reason)
+        })
+        .collect())
+}
+
+/// WASM in shared-wasm/ with no matching crate, and copies in project dist/ directories that
+/// no longer exist in shared-wasm/
+fn stale_wasm() -> Result<Vec<PruneCandidate>> {
+    let crates: BTreeSet<String> = match fs::read_dir(LayoutConfig::current().crates) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.file_name().to_string_lossy().to_case(Case::Snake))
+            .collect(),
+        Err(_) => BTreeSet::new(),
+    };
+
+    let shared: BTreeSet<String> = wasm_files(Path::new("shared-wasm"))
+        .into_iter()
+        .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .collect();
+
+    let mut candidates = Vec::new();
+    for path in wasm_files(Path::new("shared-wasm")) {
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if !crates.contains(&stem) {
+            candidates.push(candidate(path, "no matching crate".to_string()));
+        }
+    }
+
+    for project in get_typescript_projects() {
+        for path in wasm_files(&project.join("dist")) {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if !shared.contains(&name) {
+                candidates.push(candidate(path, "not present in shared-wasm".to_string()));
+            }
+        }
+    }
+
+    Ok(candidates)
+}
+
+fn wasm_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "wasm"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
@@ -0,0 +1,182 @@
+use crate::utils::config::{MoonflareConfig, RetryConfig};
+use crate::utils::{interrupt, timeout};
+use anyhow::Result;
+use colored::*;
+use std::future::Future;
+use std::time::Duration;
+
+/// Attempts and waits when `[retry]` doesn't set them
+pub const DEFAULT_ATTEMPTS: u32 = 3;
+pub const DEFAULT_BACKOFF: Duration = Duration::from_secs(2);
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Wrangler error messages that point at the network or a Cloudflare outage rather than the
+/// project. Anything else, such as a build or config error, fails straight away.
+const TRANSIENT_OUTPUT: [&str; 13] = [
+    "internal server error",
+    "bad gateway",
+    "service unavailable",
+    "gateway timeout",
+    "too many requests",
+    "econnreset",
+    "econnrefused",
+    "etimedout",
+    "eai_again",
+    "socket hang up",
+    "fetch failed",
+    "network error",
+    "malformed response from the api",
+];
+
+/// A failure worth another attempt: a 5xx or 429 answer, or a connection that dropped
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct Transient(pub String);
+
+/// Whether `error`, or anything it wraps, was classified as [`Transient`]
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| cause.is::<Transient>())
+}
+
+/// Whether the output of a failed subprocess shows a transient network or API failure. Only
+/// Wrangler's `✘ [ERROR]` messages are looked at, so a build or test step that merely prints
+/// "fetch failed" doesn't get the whole deploy rerun.
+pub fn is_transient_output(output: &str) -> bool {
+    wrangler_errors(output).any(|line| {
+        let line = line.to_lowercase();
+        TRANSIENT_OUTPUT
+            .iter()
+            .any(|pattern| line.contains(pattern))
+    })
+}
+
+/// Lines of Wrangler's error messages: each `✘ [ERROR]` line and the blank or indented lines
+/// under it, where Wrangler puts the API's answer. Moon's `<target> | ` prefixes are dropped.
+fn wrangler_errors(output: &str) -> impl Iterator<Item = &str> {
+    let mut in_error = false;
+    output.lines().map(strip_target_prefix).filter(move |line| {
+        if line.contains("[ERROR]") {
+            in_error = true;
+        } else if !line.is_empty() && !line.starts_with(char::is_whitespace) {
+            in_error = false;
+        }
+        in_error
+    })
+}
+
+fn strip_target_prefix(line: &str) -> &str {
+    match line.split_once(" | ") {
+        Some((target, rest)) if target.contains(':') && !target.contains(' ') => rest,
+        _ => line,
+    }
+}
+
+/// How often, and how patiently, a failing step is retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    pub attempts: u32,
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            attempts: DEFAULT_ATTEMPTS,
+            backoff: DEFAULT_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+}
+
+impl Policy {
+    pub fn from_config(config: &RetryConfig) -> Self {
+        let default = Self::default();
+        Self {
+            attempts: config.attempts.unwrap_or(default.attempts).max(1),
+            backoff: config.backoff.map_or(default.backoff, |b| b.0),
+            max_backoff: config.max_backoff.map_or(default.max_backoff, |b| b.0),
+        }
+    }
+
+    /// The wait after failed attempt `attempt`, doubling each time up to `max_backoff`
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+/// The policy set under `[retry]` in moonflare.toml
+pub fn policy() -> Policy {
+    Policy::from_config(&MoonflareConfig::load().unwrap_or_default().retry)
+}
+
+/// Run `step` until it succeeds, fails with an error that isn't [`Transient`], or runs out of
+/// attempts. Waits between attempts follow `[retry]`; a cancelled or timed-out command isn't
+/// retried.
+pub async fn run<T, F, Fut>(what: &str, mut step: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let policy = policy();
+    let mut attempt = 1;
+    loop {
+        let error = match step().await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        let retryable = attempt < policy.attempts
+            && is_transient(&error)
+            && !interrupt::is_cancelled()
+            && !timeout::expired();
+        if !retryable {
+            return Err(if attempt > 1 {
+                error.context(format!("{} failed after {} attempts", what, attempt))
+            } else {
+                error
+            });
+        }
+
+        let delay = policy.delay(attempt);
+        attempt += 1;
+        eprintln!(
+            "{}",
+            format!(
+                "  {} failed: {}; retrying in {} (attempt {}/{})",
+                what,
+                error,
+                timeout::format_duration(delay),
+                attempt,
+                policy.attempts
+            )
+            .yellow()
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Run the command `build` creates until it succeeds, retrying exits whose output shows a
+/// transient failure. Output is shown as it arrives; `input` goes to stdin on every attempt.
+pub async fn command(
+    what: &str,
+    build: impl Fn() -> std::process::Command,
+    input: Option<&[u8]>,
+) -> Result<()> {
+    run(what, || {
+        let cmd = build();
+        async move {
+            let (status, output) = timeout::tee(cmd, input).await?;
+            if status.success() {
+                return Ok(());
+            }
+            let message = format!("{} exited with {}", what, status);
+            if is_transient_output(&output) {
+                Err(Transient(message).into())
+            } else {
+                Err(anyhow::anyhow!(message))
+            }
+        }
+    })
+    .await
+}
@@ -0,0 +1,215 @@
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// A route or custom domain a project's Worker claims in one environment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteClaim {
+    pub project: String,
+    pub pattern: String,
+    pub custom_domain: bool,
+    /// `zone_name`, else `zone_id`, when the route names its zone
+    pub zone: Option<String>,
+}
+
+impl RouteClaim {
+    /// Host and path parts of the pattern, without a scheme. Custom domains claim every path.
+    fn parts(&self) -> (&str, &str) {
+        let pattern = self
+            .pattern
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        match pattern.find('/') {
+            Some(index) if !self.custom_domain => (&pattern[..index], &pattern[index..]),
+            Some(index) => (&pattern[..index], "/*"),
+            None if self.custom_domain => (pattern, "/*"),
+            None => (pattern, "/"),
+        }
+    }
+}
+
+/// Two claims from different projects that can match the same request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteConflict {
+    pub first: RouteClaim,
+    pub second: RouteClaim,
+    pub reason: &'static str,
+}
+
+impl RouteConflict {
+    pub fn involves(&self, project: &str) -> bool {
+        self.first.project == project || self.second.project == project
+    }
+}
+
+/// Wrangler environments declared by any of the projects
+pub fn environments(projects: &[WorkspaceProject]) -> Result<BTreeSet<String>> {
+    let mut environments = BTreeSet::new();
+    for project in projects {
+        if let Some(envs) = read_wrangler_config(&project.path)?
+            .as_ref()
+            .and_then(|config| config.get("env"))
+            .and_then(|envs| envs.as_object())
+        {
+            environments.extend(envs.keys().cloned());
+        }
+    }
+    Ok(environments)
+}
+
+/// Routes and custom domains each project claims in `environment`. Wrangler environments
+/// inherit the top-level `route`/`routes` unless they declare their own.
+pub fn route_claims(
+    projects: &[WorkspaceProject],
+    environment: Option<&str>,
+) -> Result<Vec<RouteClaim>> {
+    let mut claims = Vec::new();
+    for project in projects {
+        let Some(config) = read_wrangler_config(&project.path)? else {
+            continue;
+        };
+
+        let scoped = environment
+            .and_then(|env| config.get("env").and_then(|envs| envs.get(env)))
+            .filter(|env| env.get("route").is_some() || env.get("routes").is_some());
+        let source = scoped.unwrap_or(&config);
+
+        let routes = source
+            .get("routes")
+            .and_then(|r| r.as_array())
+            .cloned()
+            .unwrap_or_default();
+        for route in source.get("route").into_iter().chain(routes.iter()) {
+            if let Some(claim) = parse_claim(&project.name, route) {
+                claims.push(claim);
+            }
+        }
+    }
+    Ok(claims)
+}
+
+fn parse_claim(project: &str, route: &Value) -> Option<RouteClaim> {
+    let (pattern, custom_domain, zone) = match route {
+        Value::String(pattern) => (pattern.clone(), false, None),
+        Value::Object(route) => (
+            route.get("pattern")?.as_str()?.to_string(),
+            route
+                .get("custom_domain")
+                .and_then(|c| c.as_bool())
+                .unwrap_or(false),
+            route
+                .get("zone_name")
+                .or_else(|| route.get("zone_id"))
+                .and_then(|z| z.as_str())
+                .map(str::to_string),
+        ),
+        _ => return None,
+    };
+    Some(RouteClaim {
+        project: project.to_string(),
+        pattern,
+        custom_domain,
+        zone,
+    })
+}
+
+/// Whether the Worker is served on workers.dev in `environment`, and whether the config sets
+/// it rather than leaving Wrangler's default: on unless the Worker has routes. Environments
+/// inherit the top-level `workers_dev`.
+pub fn workers_dev(config: &Value, environment: Option<&str>, has_routes: bool) -> (bool, bool) {
+    let scoped = environment.and_then(|env| config.pointer(&format!("/env/{}/workers_dev", env)));
+    match scoped
+        .or_else(|| config.get("workers_dev"))
+        .and_then(|v| v.as_bool())
+    {
+        Some(enabled) => (enabled, true),
+        None => (!has_routes, false),
+    }
+}
+
+/// Pairs of claims from different projects whose patterns overlap. Cloudflare sends a request
+/// to the most specific matching route (and custom domains win over routes), so the other
+/// Worker silently loses that traffic.
+pub fn find_conflicts(claims: &[RouteClaim]) -> Vec<RouteConflict> {
+    let mut conflicts = Vec::new();
+    for (index, first) in claims.iter().enumerate() {
+        for second in &claims[index + 1..] {
+            if first.project == second.project {
+                continue;
+            }
+            if let Some(reason) = conflict_reason(first, second) {
+                conflicts.push(RouteConflict {
+                    first: first.clone(),
+                    second: second.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+fn conflict_reason(first: &RouteClaim, second: &RouteClaim) -> Option<&'static str> {
+    let (first_host, first_path) = first.parts();
+    let (second_host, second_path) = second.parts();
+    if !hosts_overlap(first_host, second_host) || !paths_overlap(first_path, second_path) {
+        return None;
+    }
+
+    Some(if first.custom_domain || second.custom_domain {
+        "custom domain takes the host"
+    } else if first_host == second_host && first_path == second_path {
+        "same pattern"
+    } else {
+        "overlapping patterns"
+    })
+}
+
+/// Hosts match literally, or through a leading `*` that matches any prefix
+fn hosts_overlap(first: &str, second: &str) -> bool {
+    match (first.strip_prefix('*'), second.strip_prefix('*')) {
+        (Some(a), Some(b)) => a.ends_with(b) || b.ends_with(a),
+        (Some(suffix), None) => second.ends_with(suffix),
+        (None, Some(suffix)) => first.ends_with(suffix),
+        (None, None) => first.eq_ignore_ascii_case(second),
+    }
+}
+
+/// Paths match literally, or through a trailing `*` that matches any suffix
+fn paths_overlap(first: &str, second: &str) -> bool {
+    match (first.strip_suffix('*'), second.strip_suffix('*')) {
+        (Some(a), Some(b)) => a.starts_with(b) || b.starts_with(a),
+        (Some(prefix), None) => second.starts_with(prefix),
+        (None, Some(prefix)) => first.starts_with(prefix),
+        (None, None) => first == second,
+    }
+}
+
+/// Conflicts as an aligned table, one row per conflicting pair
+pub fn render_conflicts(conflicts: &[RouteConflict]) -> String {
+    let describe = |claim: &RouteClaim| {
+        if claim.custom_domain {
+            format!("{} (custom domain)", claim.pattern)
+        } else {
+            claim.pattern.clone()
+        }
+    };
+
+    let mut table = format!(
+        "  {:<20} {:<36} {:<20} {:<36} {}\n",
+        "PROJECT", "CLAIM", "PROJECT", "CLAIM", "CONFLICT"
+    );
+    for conflict in conflicts {
+        table.push_str(&format!(
+            "  {:<20} {:<36} {:<20} {:<36} {}\n",
+            conflict.first.project,
+            describe(&conflict.first),
+            conflict.second.project,
+            describe(&conflict.second),
+            conflict.reason
+        ));
+    }
+    table
+}
@@ -0,0 +1,322 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::plan::Plan;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::wrangler::read_wrangler_config;
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+/// Declarations a provider Worker exports for the Workers bound to it
+pub const BINDINGS_FILE: &str = "bindings.d.ts";
+
+/// Subpath of the provider's package consumers import the declarations from, e.g.
+/// `import type { ChatRoom } from "api/bindings"`
+pub const BINDINGS_EXPORT: &str = "./bindings";
+
+/// Base classes whose methods callers reach over RPC
+const RPC_BASES: [&str; 3] = ["DurableObject", "WorkerEntrypoint", "WorkflowEntrypoint"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    Service,
+    DurableObject,
+}
+
+/// A binding in one Worker to a service or Durable Object class of another in the workspace
+#[derive(Debug, Clone)]
+pub struct RpcBinding {
+    pub consumer: String,
+    pub binding: String,
+    pub provider: String,
+    pub kind: BindingKind,
+    /// The Durable Object class, or the service's named entrypoint; None for a service's
+    /// default export
+    pub class: Option<String>,
+}
+
+/// Bindings between the workspace's Workers, matched on the provider's Worker name
+pub fn rpc_bindings(projects: &[WorkspaceProject]) -> Result<Vec<RpcBinding>> {
+    let config = MoonflareConfig::load().unwrap_or_default();
+    let mut configs = Vec::new();
+    let mut workers = BTreeMap::new();
+    for project in projects {
+        let Some(wrangler) = read_wrangler_config(&project.path)? else {
+            continue;
+        };
+        let name = wrangler["name"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| config.worker_name(&project.name));
+        workers.insert(name, project.name.clone());
+        configs.push((project, wrangler));
+    }
+
+    let mut bindings = Vec::new();
+    for (project, wrangler) in &configs {
+        let services = wrangler["services"].as_array().into_iter().flatten();
+        for service in services {
+            let (Some(binding), Some(worker)) =
+                (service["binding"].as_str(), service["service"].as_str())
+            else {
+                continue;
+            };
+            bindings.extend(workers.get(worker).map(|provider| RpcBinding {
+                consumer: project.name.clone(),
+                binding: binding.to_string(),
+                provider: provider.clone(),
+                kind: BindingKind::Service,
+                class: service["entrypoint"].as_str().map(str::to_string),
+            }));
+        }
+        let objects = wrangler["durable_objects"]["bindings"].as_array();
+        for object in objects.into_iter().flatten() {
+            let (Some(binding), Some(worker)) =
+                (object["name"].as_str(), object["script_name"].as_str())
+            else {
+                continue;
+            };
+            bindings.extend(workers.get(worker).map(|provider| RpcBinding {
+                consumer: project.name.clone(),
+                binding: binding.to_string(),
+                provider: provider.clone(),
+                kind: BindingKind::DurableObject,
+                class: object["class_name"].as_str().map(str::to_string),
+            }));
+        }
+    }
+    bindings.retain(|binding| binding.consumer != binding.provider);
+    Ok(bindings)
+}
+
+/// What a Worker's entry module exports by name, and the name of its default export when
+/// that's a class
+#[derive(Debug, Default)]
+pub struct Exports {
+    /// The entry module, e.g. `src/index.ts`
+    pub module: String,
+    pub names: BTreeSet<String>,
+    /// Named classes extending an RPC base class
+    pub rpc_classes: BTreeSet<String>,
+    pub default_class: Option<String>,
+}
+
+/// Exports of the Worker's `main` module, read from its source
+pub fn exports(project: &WorkspaceProject) -> Result<Option<Exports>> {
+    let Some(module) = read_wrangler_config(&project.path)?
+        .and_then(|wrangler| wrangler["main"].as_str().map(str::to_string))
+    else {
+        return Ok(None);
+    };
+    let path = project.path.join(&module);
+    let source =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let mut exports = Exports {
+        module,
+        ..Exports::default()
+    };
+    let declaration = regex::Regex::new(
+        r"(?m)^\s*export\s+(default\s+)?(?:abstract\s+)?(class|const|let|function|async\s+function)\s+(\w+)(?:\s+extends\s+(\w+))?",
+    )
+    .expect("valid regex");
+    for captures in declaration.captures_iter(&source) {
+        let name = captures[3].to_string();
+        let rpc = captures
+            .get(4)
+            .is_some_and(|base| RPC_BASES.contains(&base.as_str()));
+        if captures.get(1).is_some() {
+            if &captures[2] == "class" {
+                exports.default_class = Some(name);
+            }
+            continue;
+        }
+        if rpc {
+            exports.rpc_classes.insert(name.clone());
+        }
+        exports.names.insert(name);
+    }
+    let list = regex::Regex::new(r"(?m)^\s*export\s+(?:type\s+)?\{([^}]*)\}").expect("valid regex");
+    for captures in list.captures_iter(&source) {
+        for item in captures[1].split(',') {
+            let item = item.trim().trim_start_matches("type ").trim();
+            let exported = item.rsplit(" as ").next().unwrap_or(item).trim();
+            if !exported.is_empty() && exported != "default" {
+                exports.names.insert(exported.to_string());
+            }
+        }
+    }
+    Ok(Some(exports))
+}
+
+/// Declarations re-exporting the classes consumers bind to, plus the entry module's other RPC
+/// classes, from the provider's entry module
+pub fn render_bindings(provider: &str, exports: &Exports, bound: &BTreeSet<String>) -> String {
+    let module = format!(
+        "./{}",
+        exports
+            .module
+            .trim_start_matches("./")
+            .trim_end_matches(".ts")
+            .trim_end_matches(".js")
+    );
+    let mut out = format!(
+        "// Generated by 'moonflare types' from {} for the Workers bound to {}. Don't edit; run\n// 'moonflare types' again after changing what they bind to.\n",
+        exports.module, provider
+    );
+    let names: BTreeSet<&String> = exports
+        .rpc_classes
+        .iter()
+        .chain(bound.iter().filter(|name| exports.names.contains(*name)))
+        .collect();
+    if !names.is_empty() {
+        out.push_str(&format!(
+            "export type {{ {} }} from \"{}\";\n",
+            names
+                .iter()
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            module
+        ));
+    }
+    if let Some(default) = &exports.default_class {
+        out.push_str(&format!(
+            "export type {{ default as {} }} from \"{}\";\n",
+            default, module
+        ));
+    }
+    out
+}
+
+/// The changes that give every provider a bindings declaration file, exported from its
+/// package, and make each consumer depend on its providers through the pnpm workspace. Also
+/// returns bindings to classes the provider doesn't export.
+pub fn plan(projects: &[WorkspaceProject], bindings: &[RpcBinding]) -> Result<(Plan, Vec<String>)> {
+    let mut plan = Plan::new();
+    let mut warnings = Vec::new();
+    let mut packages: BTreeMap<PathBuf, Package> = BTreeMap::new();
+    let providers: BTreeSet<&str> = bindings.iter().map(|b| b.provider.as_str()).collect();
+
+    for provider in projects
+        .iter()
+        .filter(|p| providers.contains(p.name.as_str()))
+    {
+        let Some(exports) = exports(provider)? else {
+            continue;
+        };
+        let Some(package_name) = package(&mut packages, provider)?
+            .and_then(|package| package.edited["name"].as_str().map(str::to_string))
+        else {
+            warnings.push(format!(
+                "{} has no package.json name, so Workers bound to it can't depend on it",
+                provider.name
+            ));
+            continue;
+        };
+
+        let provided: Vec<&RpcBinding> = bindings
+            .iter()
+            .filter(|b| b.provider == provider.name)
+            .collect();
+        let mut bound = BTreeSet::new();
+        for binding in &provided {
+            match &binding.class {
+                Some(class) if exports.names.contains(class) => {
+                    bound.insert(class.clone());
+                }
+                Some(class) => warnings.push(format!(
+                    "{}'s {} binds {}, which {} doesn't export from {}",
+                    binding.consumer, binding.binding, class, provider.name, exports.module
+                )),
+                None if exports.default_class.is_none() => warnings.push(format!(
+                    "{}'s {} binds the default export of {}, which isn't a WorkerEntrypoint class, so it's typed as a plain Fetcher",
+                    binding.consumer, binding.binding, provider.name
+                )),
+                None => {}
+            }
+        }
+        plan.write(
+            provider.path.join(BINDINGS_FILE),
+            render_bindings(&provider.name, &exports, &bound),
+        )?;
+
+        if let Some(package) = package(&mut packages, provider)? {
+            let exports_field = package
+                .edited
+                .as_object_mut()
+                .map(|p| p.entry("exports").or_insert_with(|| json!({})));
+            if let Some(Value::Object(map)) = exports_field {
+                map.insert(
+                    BINDINGS_EXPORT.to_string(),
+                    json!({ "types": format!("./{}", BINDINGS_FILE) }),
+                );
+            }
+        }
+        for consumer in projects
+            .iter()
+            .filter(|p| provided.iter().any(|b| b.consumer == p.name))
+        {
+            let Some(package) = package(&mut packages, consumer)? else {
+                continue;
+            };
+            let listed = ["dependencies", "devDependencies"]
+                .iter()
+                .any(|field| package.edited[field].get(&package_name).is_some());
+            if listed {
+                continue;
+            }
+            if let Some(Value::Object(dependencies)) = package
+                .edited
+                .as_object_mut()
+                .map(|p| p.entry("devDependencies").or_insert_with(|| json!({})))
+            {
+                dependencies.insert(package_name.clone(), json!("workspace:*"));
+            }
+        }
+    }
+
+    // Only files that change are rewritten, so their formatting is otherwise left alone
+    for (path, package) in packages {
+        if package.edited != package.read {
+            plan.modify(
+                path,
+                package.content,
+                format!("{}\n", serde_json::to_string_pretty(&package.edited)?),
+            );
+        }
+    }
+    Ok((plan, warnings))
+}
+
+/// A package.json as read and as edited so far
+struct Package {
+    content: String,
+    read: Value,
+    edited: Value,
+}
+
+/// The project's package.json, read on first use
+fn package<'a>(
+    packages: &'a mut BTreeMap<PathBuf, Package>,
+    project: &WorkspaceProject,
+) -> Result<Option<&'a mut Package>> {
+    let path = project.path.join("package.json");
+    if !packages.contains_key(&path) {
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Ok(None);
+        };
+        let read: Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        packages.insert(
+            path.clone(),
+            Package {
+                content,
+                edited: read.clone(),
+                read,
+            },
+        );
+    }
+    Ok(packages.get_mut(&path))
+}
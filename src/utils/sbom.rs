@@ -0,0 +1,359 @@
+use crate::utils::cargo_workspace::lockfile;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::vcs::current_commit;
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SBOM_DIR: &str = ".moonflare/sbom";
+
+const CYCLONEDX_SPEC_VERSION: &str = "1.5";
+
+/// A single dependency recorded in the SBOM
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SbomComponent {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub version: String,
+    /// Dev-only dependencies are tooling and never ship with the deployed bundle
+    pub dev: bool,
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Ecosystem {
+    Npm,
+    Cargo,
+}
+
+impl SbomComponent {
+    /// Package URL (https://github.com/package-url/purl-spec) of the component
+    pub fn purl(&self) -> String {
+        match self.ecosystem {
+            Ecosystem::Npm => format!(
+                "pkg:npm/{}@{}",
+                self.name.replacen('@', "%40", 1),
+                self.version
+            ),
+            Ecosystem::Cargo => format!("pkg:cargo/{}@{}", self.name, self.version),
+        }
+    }
+}
+
+/// Default location of a project's SBOM
+pub fn sbom_path(project: &str) -> PathBuf {
+    Path::new(SBOM_DIR).join(format!("{}.cdx.json", project))
+}
+
+/// Every npm and cargo dependency of the project, direct and transitive
+pub fn collect_components(project: &WorkspaceProject) -> Result<Vec<SbomComponent>> {
+    let mut components = BTreeSet::new();
+    components.extend(npm_components(project)?);
+    components.extend(cargo_components(&project.path)?);
+    Ok(components.into_iter().collect())
+}
+
+/// Build a CycloneDX JSON document describing the project and its dependencies
+pub fn generate_sbom(project: &WorkspaceProject) -> Result<Value> {
+    let components = collect_components(project)?;
+    let (name, version) = project_identity(project);
+
+    let mut properties =
+        vec![json!({ "name": "moonflare:project-path", "value": project.relative_path() })];
+    if let Some(sha) = current_commit() {
+        properties.push(json!({ "name": "moonflare:git-sha", "value": sha }));
+    }
+
+    Ok(json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": CYCLONEDX_SPEC_VERSION,
+        "serialNumber": format!("urn:uuid:{}", uuid::Uuid::new_v4()),
+        "version": 1,
+        "metadata": {
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "moonflare",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }]
+            },
+            "component": {
+                "type": if project.is_crate() { "library" } else { "application" },
+                "bom-ref": name,
+                "name": name,
+                "version": version,
+            },
+            "properties": properties,
+        },
+        "components": components.iter().map(component_json).collect::<Vec<_>>(),
+    }))
+}
+
+/// Generate the project's SBOM, write it to `output` and return the SHA-256 of the written file
+pub fn write_sbom(project: &WorkspaceProject, output: &Path) -> Result<(String, usize)> {
+    let sbom = generate_sbom(project)?;
+    let count = sbom["components"].as_array().map_or(0, |c| c.len());
+    let content = serde_json::to_string_pretty(&sbom)?;
+
+    if let Some(parent) = output.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output, &content).with_context(|| format!("Failed to write {}", output.display()))?;
+
+    Ok((sha256_hex(content.as_bytes()), count))
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn component_json(component: &SbomComponent) -> Value {
+    let mut value = json!({
+        "type": "library",
+        "bom-ref": component.purl(),
+        "name": component.name,
+        "version": component.version,
+        "purl": component.purl(),
+        "scope": if component.dev { "excluded" } else { "required" },
+    });
+    if let Some(sha256) = &component.sha256 {
+        value["hashes"] = json!([{ "alg": "SHA-256", "content": sha256 }]);
+    }
+    value
+}
+
+fn project_identity(project: &WorkspaceProject) -> (String, String) {
+    let from_package_json = fs::read_to_string(project.path.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .map(|package| {
+            (
+                package["name"]
+                    .as_str()
+                    .unwrap_or(&project.name)
+                    .to_string(),
+                package["version"].as_str().unwrap_or("0.0.0").to_string(),
+            )
+        });
+
+    from_package_json
+        .or_else(|| {
+            cargo_package(&project.path).map(|(name, version)| (name, version.unwrap_or_default()))
+        })
+        .unwrap_or_else(|| (project.name.clone(), "0.0.0".to_string()))
+}
+
+/// npm dependencies resolved through the workspace pnpm-lock.yaml, falling back to the
+/// versions declared in package.json when there is no lockfile
+fn npm_components(project: &WorkspaceProject) -> Result<Vec<SbomComponent>> {
+    let package_json = project.path.join("package.json");
+    if !package_json.exists() {
+        return Ok(Vec::new());
+    }
+
+    if let Ok(content) = fs::read_to_string("pnpm-lock.yaml") {
+        let lockfile: serde_yaml::Value =
+            serde_yaml::from_str(&content).context("Invalid pnpm-lock.yaml")?;
+        if let Some(importer) = lockfile
+            .get("importers")
+            .and_then(|i| i.get(project.relative_path().as_str()))
+        {
+            return Ok(pnpm_components(&lockfile, importer));
+        }
+    }
+
+    let package: Value = serde_json::from_str(&fs::read_to_string(&package_json)?)
+        .with_context(|| format!("Invalid {}", package_json.display()))?;
+    let mut components = Vec::new();
+    for (section, dev) in [("dependencies", false), ("devDependencies", true)] {
+        let Some(deps) = package[section].as_object() else {
+            continue;
+        };
+        for (name, spec) in deps {
+            let spec = spec.as_str().unwrap_or_default();
+            if spec.starts_with("workspace:") || spec.starts_with("link:") {
+                continue;
+            }
+            components.push(SbomComponent {
+                ecosystem: Ecosystem::Npm,
+                name: name.clone(),
+                version: installed_npm_version(&project.path, name)
+                    .unwrap_or_else(|| spec.to_string()),
+                dev,
+                sha256: None,
+            });
+        }
+    }
+    Ok(components)
+}
+
+fn installed_npm_version(project_path: &Path, name: &str) -> Option<String> {
+    let content = fs::read_to_string(
+        project_path
+            .join("node_modules")
+            .join(name)
+            .join("package.json"),
+    )
+    .ok()?;
+    let package: Value = serde_json::from_str(&content).ok()?;
+    package["version"].as_str().map(String::from)
+}
+
+/// Walk a pnpm lockfile from one importer through its transitive dependencies.
+/// Supports lockfile v9 (`snapshots`, `name@version` keys) and v6 (`packages`, `/name@version` keys).
+fn pnpm_components(
+    lockfile: &serde_yaml::Value,
+    importer: &serde_yaml::Value,
+) -> Vec<SbomComponent> {
+    let mut queue = VecDeque::new();
+    // Production dependencies first, so a package reachable from both is reported as required
+    for (section, dev) in [
+        ("dependencies", false),
+        ("optionalDependencies", false),
+        ("devDependencies", true),
+    ] {
+        if let Some(deps) = importer.get(section).and_then(|d| d.as_mapping()) {
+            for (name, entry) in deps {
+                let version = entry
+                    .get("version")
+                    .or(Some(entry))
+                    .and_then(|v| v.as_str());
+                if let (Some(name), Some(version)) = (name.as_str(), version) {
+                    queue.push_back((name.to_string(), version.to_string(), dev));
+                }
+            }
+        }
+    }
+
+    let mut seen = BTreeMap::new();
+    while let Some((name, version, dev)) = queue.pop_front() {
+        if version.starts_with("link:") || seen.contains_key(&(name.clone(), version.clone())) {
+            continue;
+        }
+        seen.insert((name.clone(), version.clone()), dev);
+
+        let key = format!("{}@{}", name, version);
+        let entry = ["snapshots", "packages"].iter().find_map(|section| {
+            let section = lockfile.get(*section)?;
+            section
+                .get(key.as_str())
+                .or_else(|| section.get(format!("/{}", key).as_str()))
+        });
+        let Some(entry) = entry else {
+            continue;
+        };
+
+        for section in ["dependencies", "optionalDependencies"] {
+            if let Some(deps) = entry.get(section).and_then(|d| d.as_mapping()) {
+                for (dep_name, dep_version) in deps {
+                    if let (Some(dep_name), Some(dep_version)) =
+                        (dep_name.as_str(), dep_version.as_str())
+                    {
+                        queue.push_back((dep_name.to_string(), dep_version.to_string(), dev));
+                    }
+                }
+            }
+        }
+    }
+
+    seen.into_iter()
+        .map(|((name, version), dev)| SbomComponent {
+            ecosystem: Ecosystem::Npm,
+            name,
+            // Drop pnpm's peer-dependency suffix, e.g. `19.0.0(react@19.0.0)`
+            version: version.split('(').next().unwrap_or_default().to_string(),
+            dev,
+            sha256: None,
+        })
+        .collect()
+}
+
+fn cargo_package(project_path: &Path) -> Option<(String, Option<String>)> {
+    let content = fs::read_to_string(project_path.join("Cargo.toml")).ok()?;
+    let manifest: toml::Value = toml::from_str(&content).ok()?;
+    let package = manifest.get("package")?;
+    Some((
+        package.get("name")?.as_str()?.to_string(),
+        package
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+    ))
+}
+
+/// Cargo dependencies reachable from the project's package in its Cargo.lock, or the root
+/// Cargo workspace's
+fn cargo_components(project_path: &Path) -> Result<Vec<SbomComponent>> {
+    let Some((root, _)) = cargo_package(project_path) else {
+        return Ok(Vec::new());
+    };
+    let lock_path = lockfile(project_path);
+    let Ok(content) = fs::read_to_string(&lock_path) else {
+        return Ok(Vec::new());
+    };
+    let lockfile: toml::Value =
+        toml::from_str(&content).with_context(|| format!("Invalid {}", lock_path.display()))?;
+
+    let packages: Vec<&toml::Value> = lockfile
+        .get("package")
+        .and_then(|p| p.as_array())
+        .map(|p| p.iter().collect())
+        .unwrap_or_default();
+    let field = |package: &toml::Value, key: &str| {
+        package
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    // Dependency entries are `name`, `name version` or `name version (source)`
+    let resolve = |reference: &str| {
+        let mut parts = reference.split_whitespace();
+        let name = parts.next().unwrap_or_default();
+        let version = parts.next();
+        packages
+            .iter()
+            .copied()
+            .find(|p| field(p, "name") == name && version.is_none_or(|v| field(p, "version") == v))
+    };
+
+    let mut seen = BTreeSet::new();
+    let mut queue: VecDeque<&toml::Value> = resolve(&root).into_iter().collect();
+    let mut components = Vec::new();
+    while let Some(package) = queue.pop_front() {
+        let id = (field(package, "name"), field(package, "version"));
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+
+        // The project itself is the SBOM subject, and path dependencies have no registry identity
+        if package.get("source").is_some() {
+            components.push(SbomComponent {
+                ecosystem: Ecosystem::Cargo,
+                name: id.0,
+                version: id.1,
+                dev: false,
+                sha256: package
+                    .get("checksum")
+                    .and_then(|c| c.as_str())
+                    .map(String::from),
+            });
+        }
+
+        if let Some(deps) = package.get("dependencies").and_then(|d| d.as_array()) {
+            queue.extend(deps.iter().filter_map(|d| d.as_str()).filter_map(resolve));
+        }
+    }
+
+    Ok(components)
+}
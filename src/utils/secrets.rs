@@ -0,0 +1,145 @@
+use crate::utils::config::{
+    DopplerConfig, OnePasswordConfig, SecretProviderKind, SecretsConfig, VaultConfig,
+};
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::process::Command;
+use which::which;
+
+/// `{env}` used for `.dev.vars`
+pub const DEVELOPMENT_ENV: &str = "development";
+/// `{env}` used when secrets go to a Worker's top-level (production) environment
+pub const PRODUCTION_ENV: &str = "production";
+
+/// A secret store moonflare reads from, so secrets never sit in plaintext in the repo
+pub trait SecretProvider {
+    /// Name shown in messages
+    fn name(&self) -> &'static str;
+    /// Every secret the store holds for a project in an environment
+    fn fetch(&self, project: &str, env: &str) -> Result<BTreeMap<String, String>>;
+}
+
+/// The provider configured under `[secrets]`, if any
+pub fn provider_for(config: &SecretsConfig) -> Option<Box<dyn SecretProvider>> {
+    Some(match config.provider? {
+        SecretProviderKind::OnePassword => Box::new(OnePassword(config.onepassword.clone())),
+        SecretProviderKind::Vault => Box::new(Vault(config.vault.clone())),
+        SecretProviderKind::Doppler => Box::new(Doppler(config.doppler.clone())),
+    })
+}
+
+pub struct OnePassword(pub OnePasswordConfig);
+pub struct Vault(pub VaultConfig);
+pub struct Doppler(pub DopplerConfig);
+
+impl SecretProvider for OnePassword {
+    fn name(&self) -> &'static str {
+        "1Password"
+    }
+
+    fn fetch(&self, project: &str, env: &str) -> Result<BTreeMap<String, String>> {
+        let item = expand(&self.0.item, project, env);
+        let mut args = vec!["item".to_string(), "get".to_string(), item];
+        if let Some(vault) = &self.0.vault {
+            args.extend(["--vault".to_string(), expand(vault, project, env)]);
+        }
+        args.extend(["--format".to_string(), "json".to_string()]);
+
+        let item = run_json("op", &args)?;
+        Ok(item["fields"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|field| {
+                Some((
+                    field["label"].as_str()?.to_string(),
+                    field["value"].as_str()?.to_string(),
+                ))
+            })
+            .collect())
+    }
+}
+
+impl SecretProvider for Vault {
+    fn name(&self) -> &'static str {
+        "Vault"
+    }
+
+    fn fetch(&self, project: &str, env: &str) -> Result<BTreeMap<String, String>> {
+        let path = expand(&self.0.path, project, env);
+        let secret = run_json("vault", &["kv", "get", "-format=json", &path])?;
+        // KV v2 nests the values one level deeper than KV v1
+        let data = match &secret["data"]["data"] {
+            Value::Object(_) => &secret["data"]["data"],
+            _ => &secret["data"],
+        };
+        Ok(string_map(data))
+    }
+}
+
+impl SecretProvider for Doppler {
+    fn name(&self) -> &'static str {
+        "Doppler"
+    }
+
+    fn fetch(&self, project: &str, env: &str) -> Result<BTreeMap<String, String>> {
+        let secrets = run_json(
+            "doppler",
+            &[
+                "secrets",
+                "download",
+                "--no-file",
+                "--format",
+                "json",
+                "--project",
+                &expand(&self.0.project, project, env),
+                "--config",
+                &expand(&self.0.config, project, env),
+            ],
+        )?;
+        let mut values = string_map(&secrets);
+        // Doppler adds its own DOPPLER_PROJECT/CONFIG/ENVIRONMENT metadata
+        values.retain(|name, _| !name.starts_with("DOPPLER_"));
+        Ok(values)
+    }
+}
+
+fn expand(template: &str, project: &str, env: &str) -> String {
+    template.replace("{project}", project).replace("{env}", env)
+}
+
+fn run_json<S: AsRef<std::ffi::OsStr>>(program: &str, args: &[S]) -> Result<Value> {
+    if which(program).is_err() {
+        bail!("'{}' is not installed or not on PATH", program);
+    }
+
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        bail!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("Unexpected output from {}", program))
+}
+
+fn string_map(value: &Value) -> BTreeMap<String, String> {
+    value
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                other => other.to_string(),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
@@ -0,0 +1,283 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::task_env;
+use crate::utils::wrangler::{dev_port, read_wrangler_config};
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// Directory of seed files in each project
+pub const SEEDS_DIR: &str = "seeds";
+
+/// Where Wrangler keeps a project's local D1, KV, R2 and Durable Object data
+pub const LOCAL_STATE_DIR: &str = ".wrangler/state";
+
+/// What a seed file loads and how
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedKind {
+    /// SQL executed against a D1 database, by `database_name`
+    Sql { database: String },
+    /// Key-value pairs in Wrangler's bulk format, written to a KV namespace by binding
+    Kv { binding: String },
+    /// A script run with Node (or tsx for TypeScript) that can call the Worker at `SEED_URL`,
+    /// e.g. to fill Durable Objects
+    Script,
+}
+
+#[derive(Debug, Clone)]
+pub struct Seed {
+    /// Path relative to the project directory
+    pub path: PathBuf,
+    pub kind: SeedKind,
+}
+
+/// Where seeds are loaded: Wrangler's local state, or the remote resources of `env`
+#[derive(Debug, Clone, Default)]
+pub struct SeedTarget {
+    pub remote: bool,
+    pub env: Option<String>,
+}
+
+impl SeedTarget {
+    /// Deploy environments are always remote
+    pub fn is_remote(&self) -> bool {
+        self.remote || self.env.is_some()
+    }
+
+    pub fn describe(&self) -> String {
+        match (&self.env, self.remote) {
+            (Some(env), _) => format!("remote {}", env),
+            (None, true) => "remote production".to_string(),
+            (None, false) => "local".to_string(),
+        }
+    }
+}
+
+/// Whether the project has any seed files
+pub fn has_seeds(project: &WorkspaceProject) -> bool {
+    project.path.join(SEEDS_DIR).is_dir()
+}
+
+/// The project's seeds in the order they run: sorted by path, so `seeds/001-users.sql` runs
+/// before `seeds/002-orders.sql`. A file directly in `seeds/` loads into the project's only D1
+/// database (`.sql`) or KV namespace (`.json`); one in `seeds/<BINDING>/` loads into that
+/// binding. `.mjs`, `.js` and `.ts` files are scripts.
+pub fn discover(project: &WorkspaceProject, env: Option<&str>) -> Result<Vec<Seed>> {
+    let dir = project.path.join(SEEDS_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let config = read_wrangler_config(&project.path)?.unwrap_or(Value::Null);
+
+    let mut files: Vec<PathBuf> = walkdir::WalkDir::new(&dir)
+        .max_depth(2)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+
+    let mut seeds = Vec::new();
+    for file in files {
+        let relative = file.strip_prefix(&project.path)?.to_path_buf();
+        let binding = file
+            .parent()
+            .filter(|parent| *parent != dir)
+            .and_then(|parent| parent.file_name())
+            .and_then(|name| name.to_str());
+        let kind = match file.extension().and_then(|ext| ext.to_str()) {
+            Some("sql") => {
+                let database = resolve(&config, env, "d1_databases", binding, &relative)?;
+                let name = database["database_name"]
+                    .as_str()
+                    .or_else(|| database["binding"].as_str())
+                    .unwrap_or_default();
+                SeedKind::Sql {
+                    database: name.to_string(),
+                }
+            }
+            Some("json") => {
+                let namespace = resolve(&config, env, "kv_namespaces", binding, &relative)?;
+                SeedKind::Kv {
+                    binding: namespace["binding"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                }
+            }
+            Some("mjs" | "js" | "ts") => SeedKind::Script,
+            _ => continue,
+        };
+        seeds.push(Seed {
+            path: relative,
+            kind,
+        });
+    }
+    Ok(seeds)
+}
+
+/// The binding of `kind` a seed file loads into: the one named by its directory, or the
+/// project's only one
+fn resolve<'a>(
+    config: &'a Value,
+    env: Option<&str>,
+    kind: &str,
+    binding: Option<&str>,
+    file: &Path,
+) -> Result<&'a Value> {
+    // Bindings aren't inherited, so an environment's own list replaces the top-level one
+    let list = env
+        .and_then(|env| config["env"][env][kind].as_array())
+        .or_else(|| config[kind].as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    match binding {
+        Some(binding) => list
+            .iter()
+            .find(|entry| entry["binding"] == binding)
+            .with_context(|| {
+                format!(
+                    "{} is in seeds/{}/, but there's no {} binding named {}",
+                    file.display(),
+                    binding,
+                    kind,
+                    binding
+                )
+            }),
+        None if list.len() == 1 => Ok(&list[0]),
+        None if list.is_empty() => bail!(
+            "{} needs a binding in {} of the wrangler config",
+            file.display(),
+            kind
+        ),
+        None => bail!(
+            "{} could load into any of {} {} bindings; move it to seeds/<BINDING>/",
+            file.display(),
+            list.len(),
+            kind
+        ),
+    }
+}
+
+/// Load `seeds` into the project's resources
+pub fn run(project: &WorkspaceProject, seeds: &[Seed], target: &SeedTarget) -> Result<()> {
+    let config = MoonflareConfig::load()?;
+    let location = if target.is_remote() {
+        "--remote"
+    } else {
+        "--local"
+    };
+
+    for seed in seeds {
+        let file = seed.path.display().to_string();
+        println!("  {} {}", project.name, file);
+        let mut command = match &seed.kind {
+            SeedKind::Sql { database } => {
+                let mut command = wrangler();
+                command.args(["d1", "execute", database, "--file", &file, location]);
+                command
+            }
+            SeedKind::Kv { binding } => {
+                let mut command = wrangler();
+                command.args(["kv", "bulk", "put", &file, "--binding", binding, location]);
+                command
+            }
+            SeedKind::Script => {
+                let url = seed_url(project, &config, target)?;
+                let mut command = if file.ends_with(".ts") {
+                    let mut command = Command::new("pnpm");
+                    command.args(["exec", "tsx", &file]);
+                    command
+                } else {
+                    let mut command = Command::new("node");
+                    command.arg(&file);
+                    command
+                };
+                command.env("SEED_URL", url);
+                command
+            }
+        };
+        if seed.kind != SeedKind::Script
+            && let Some(env) = &target.env
+        {
+            command.args(["--env", env]);
+        }
+        let status = command
+            .current_dir(&project.path)
+            .envs(task_env::vars(Some(&project.name)))
+            .env("SEED_TARGET", &location[2..])
+            .env("SEED_ENV", target.env.as_deref().unwrap_or("production"))
+            .status()
+            .with_context(|| format!("Failed to run seed {}", file))?;
+        if !status.success() {
+            bail!("Seed {} of '{}' failed ({})", file, project.name, status);
+        }
+    }
+    Ok(())
+}
+
+fn wrangler() -> Command {
+    let mut command = Command::new("pnpm");
+    command.args(["exec", "wrangler"]);
+    command
+}
+
+/// Where seed scripts reach the Worker: its local dev server, or its deployed URL
+fn seed_url(
+    project: &WorkspaceProject,
+    config: &MoonflareConfig,
+    target: &SeedTarget,
+) -> Result<String> {
+    if target.is_remote() {
+        return config
+            .project_url(&project.name, target.env.as_deref())
+            .with_context(|| {
+                format!(
+                    "Seed scripts of '{}' need its deployed URL; set url under [projects.{}] in moonflare.toml",
+                    project.name, project.name
+                )
+            });
+    }
+    Ok(format!("http://localhost:{}", dev_port(&project.path)?))
+}
+
+/// Whether the project's local dev server accepts connections
+pub async fn dev_server_running(project: &WorkspaceProject) -> Result<bool> {
+    let port = dev_port(&project.path)?;
+    Ok(tokio::net::TcpStream::connect(("127.0.0.1", port))
+        .await
+        .is_ok())
+}
+
+/// Wait for the project's local dev server to accept connections, for seed scripts started
+/// alongside it
+pub async fn wait_for_dev_server(project: &WorkspaceProject, timeout: Duration) -> Result<()> {
+    let port = dev_port(&project.path)?;
+    let started = std::time::Instant::now();
+    while !dev_server_running(project).await? {
+        if started.elapsed() > timeout {
+            bail!(
+                "'{}' didn't start listening on port {} within {}s",
+                project.name,
+                port,
+                timeout.as_secs()
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    Ok(())
+}
+
+/// Remove the project's local Wrangler data, so the next dev server starts empty
+pub fn clear_local_state(project: &WorkspaceProject) -> Result<()> {
+    let state = project.path.join(LOCAL_STATE_DIR);
+    if state.exists() {
+        fs::remove_dir_all(&state)
+            .with_context(|| format!("Failed to remove {}", state.display()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::utils::config::{DeployStrategy, MoonflareConfig};
+use crate::utils::moon::moon_command;
+use crate::utils::projects::discover_projects;
+use crate::utils::task_env;
+use anyhow::{Result, bail};
+use serde_json::{Value, json};
+use std::path::Path;
+
+/// Directory holding the smoke test project, next to the project layout directories
+pub const SMOKE_TESTS_DIR: &str = "tests";
+
+/// Name of the project `moonflare add smoke-tests` generates
+pub const SMOKE_PROJECT: &str = "smoke";
+
+/// Environment named to the checks when deploying without one
+const DEFAULT_ENV: &str = "production";
+
+/// Whether the workspace has the generated smoke test project
+pub fn is_set_up() -> bool {
+    Path::new(SMOKE_TESTS_DIR)
+        .join(SMOKE_PROJECT)
+        .join("moon.yml")
+        .exists()
+}
+
+/// One check per deployable project, requesting its root on the production URL when it's known
+pub fn initial_checks(config: &MoonflareConfig) -> Vec<Value> {
+    discover_projects()
+        .into_iter()
+        .filter(|project| {
+            !project.is_crate()
+                && (project.has_wrangler_config()
+                    || config.deploy_settings(&project.name).strategy != DeployStrategy::Wrangler)
+        })
+        .map(|project| {
+            let mut check = json!({ "project": project.name, "path": "/" });
+            if let Some(url) = config.project_url(&project.name, None) {
+                check["url"] = json!(url);
+            }
+            check
+        })
+        .collect()
+}
+
+/// Run the smoke project's checks against `projects` after they deployed to `env`
+pub async fn run(projects: &[&str], env: Option<&str>) -> Result<()> {
+    if projects.is_empty() {
+        return Ok(());
+    }
+    let config = MoonflareConfig::load()?;
+    println!("Running smoke tests against {}...", projects.join(", "));
+
+    let mut vars = vec![
+        ("SMOKE_PROJECTS".to_string(), projects.join(",")),
+        (
+            "SMOKE_ENV".to_string(),
+            env.unwrap_or(DEFAULT_ENV).to_string(),
+        ),
+    ];
+    for project in projects {
+        if let Some(url) = config.project_url(project, env) {
+            vars.push((url_var(project), url));
+        }
+    }
+
+    let target = format!("{}:smoke", SMOKE_PROJECT);
+    // The checks read their URLs from the environment, which moon passes on to the task
+    let mut command = moon_command(&["run", &target])?;
+    task_env::apply(&mut command, &["run", &target]);
+    command.envs(vars);
+    let status = command.status()?;
+    if !status.success() {
+        bail!("Smoke tests failed after deploying {}", projects.join(", "));
+    }
+    Ok(())
+}
+
+/// `SMOKE_URL_<PROJECT>`, with the name upper-cased and dashes as underscores
+fn url_var(project: &str) -> String {
+    format!("SMOKE_URL_{}", project.to_uppercase().replace('-', "_"))
+}
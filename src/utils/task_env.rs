@@ -0,0 +1,135 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::dev_vars::parse_dev_vars;
+use crate::utils::http;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Selects the `[env.<name>]` table for commands that don't choose an environment themselves
+pub const ENVIRONMENT_VAR: &str = "MOONFLARE_ENV";
+
+/// Tells shared-wasm:gather to write content-hashed copies when `[assets] hashing` is on
+const HASH_ASSETS_VAR: &str = "MOONFLARE_HASH_ASSETS";
+
+/// Names the configuration merged with the workspace's Wrangler defaults, for `deploy`
+/// scripts to pass as `--config`
+pub const WRANGLER_CONFIG_VAR: &str = "WRANGLER_CONFIG";
+
+/// Variables from `--env-file`, the environment chosen by the running command and the merged
+/// Wrangler configuration it wrote
+struct TaskEnv {
+    env_file: BTreeMap<String, String>,
+    environment: Option<String>,
+    wrangler_config: Option<String>,
+}
+
+static TASK_ENV: Mutex<TaskEnv> = Mutex::new(TaskEnv {
+    env_file: BTreeMap::new(),
+    environment: None,
+    wrangler_config: None,
+});
+
+/// Read `KEY=value` lines from an `--env-file`; they override everything else
+pub fn load_env_file(path: &Path) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read env file {}", path.display()))?;
+    TASK_ENV.lock().expect("task env poisoned").env_file = parse_dev_vars(&content);
+    Ok(())
+}
+
+/// Use the `[env.<name>]` tables of `environment` (build and deploy set this from `--env`)
+pub fn set_environment(environment: Option<&str>) {
+    TASK_ENV.lock().expect("task env poisoned").environment = environment.map(str::to_string);
+}
+
+/// The environment tasks run for: the one the command chose, otherwise `MOONFLARE_ENV`
+pub fn environment() -> Option<String> {
+    let state = TASK_ENV.lock().expect("task env poisoned");
+    state
+        .environment
+        .clone()
+        .or_else(|| std::env::var(ENVIRONMENT_VAR).ok())
+        .filter(|environment| !environment.is_empty())
+}
+
+/// Point Wrangler at `file`, relative to each project, while merged configurations exist
+pub fn set_wrangler_config(file: Option<&str>) {
+    TASK_ENV.lock().expect("task env poisoned").wrangler_config = file.map(str::to_string);
+}
+
+/// Variables injected for a project's tasks, or for workspace-wide runs without one:
+/// `[env]`, then `[env.<environment>]`, then the project's own tables. Variables already set
+/// in the shell win over moonflare.toml; `--env-file` wins over both. The environment itself is
+/// passed as `MOONFLARE_ENV`, which the managed task configs hash so Moon caches build output
+/// per environment.
+pub fn vars(project: Option<&str>) -> BTreeMap<String, String> {
+    let environment = environment();
+    let state = TASK_ENV.lock().expect("task env poisoned");
+    let config = MoonflareConfig::load().unwrap_or_default();
+
+    let mut vars = config.env.resolve(environment.as_deref());
+    if config.assets.hashing {
+        vars.insert(HASH_ASSETS_VAR.to_string(), "1".to_string());
+    }
+    vars.extend(http::child_env(&config.network));
+    if let Some(file) = &state.wrangler_config {
+        vars.insert(WRANGLER_CONFIG_VAR.to_string(), file.clone());
+    }
+    if let Some(project_config) = project.and_then(|name| config.project(name)) {
+        vars.extend(project_config.env.resolve(environment.as_deref()));
+    }
+    vars.retain(|name, _| std::env::var_os(name).is_none());
+    if let Some(environment) = environment {
+        vars.insert(ENVIRONMENT_VAR.to_string(), environment);
+    }
+    vars.extend(state.env_file.clone());
+    vars
+}
+
+/// Add the injected variables to a moon or wrangler invocation. Project overrides apply when
+/// every Moon target in `args` belongs to the same project.
+pub fn apply(command: &mut Command, args: &[&str]) {
+    command.envs(moon_vars(args));
+}
+
+/// The variables [`apply`] adds for Moon `args`
+pub fn moon_vars(args: &[&str]) -> BTreeMap<String, String> {
+    vars(target_project(args))
+}
+
+/// Whether any of `projects` sets its own variables, so it needs a Moon invocation to itself
+pub fn has_project_overrides<'a>(projects: impl IntoIterator<Item = &'a str>) -> bool {
+    let Ok(config) = MoonflareConfig::load() else {
+        return false;
+    };
+    projects.into_iter().any(|name| {
+        config
+            .project(name)
+            .is_some_and(|project| !project.env.is_empty())
+    })
+}
+
+/// Whether Moon targets spanning several projects must run one at a time so each project
+/// gets its own variables
+pub fn needs_separate_runs(targets: &[String]) -> bool {
+    let projects: BTreeSet<&str> = targets
+        .iter()
+        .filter_map(|target| target.split_once(':'))
+        .map(|(project, _)| project)
+        .collect();
+    projects.len() > 1 && has_project_overrides(projects)
+}
+
+fn target_project<'a>(args: &[&'a str]) -> Option<&'a str> {
+    let mut projects = args
+        .iter()
+        .take_while(|arg| **arg != "--")
+        .filter(|arg| !arg.starts_with('-'))
+        .filter_map(|arg| arg.split_once(':'))
+        .map(|(project, _)| project);
+
+    let first = projects.next().filter(|project| !project.is_empty())?;
+    projects.all(|project| project == first).then_some(first)
+}
@@ -0,0 +1,330 @@
+#[cfg(feature = "telemetry")]
+use crate::utils::trace;
+use crate::utils::{crash, interrupt};
+use anyhow::Result;
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Bytes of output [`tee`] keeps for classifying a failure
+const TAIL_BYTES: usize = 16 * 1024;
+
+/// How long a stopped subprocess gets between SIGTERM and SIGKILL, unless `[timeouts] grace`
+/// says otherwise
+pub const DEFAULT_GRACE: Duration = Duration::from_secs(10);
+
+/// The time box of the running command, from `--timeout` or `[timeouts]`
+struct Deadline {
+    limit: Duration,
+    grace: Duration,
+    expires: Instant,
+}
+
+static DEADLINE: Mutex<Option<Deadline>> = Mutex::new(None);
+
+/// Raised when a subprocess was stopped, or never started, because the command ran out of time
+#[derive(Debug, thiserror::Error)]
+#[error("timed out after {} running '{command}'", format_duration(*.limit))]
+pub struct TimedOut {
+    pub limit: Duration,
+    pub command: String,
+}
+
+/// Give the rest of this invocation `limit` to finish, starting now. Subprocesses still
+/// running when it's up get SIGTERM, then SIGKILL after `grace`. A limit too far off to
+/// represent as an instant is no limit.
+pub fn set_limit(limit: Option<Duration>, grace: Duration) {
+    *DEADLINE.lock().expect("deadline poisoned") = limit.and_then(|limit| {
+        Some(Deadline {
+            limit,
+            grace,
+            expires: Instant::now().checked_add(limit)?,
+        })
+    });
+}
+
+/// The time box of this invocation, if it has one
+pub fn limit() -> Option<Duration> {
+    DEADLINE
+        .lock()
+        .expect("deadline poisoned")
+        .as_ref()
+        .map(|d| d.limit)
+}
+
+/// Whether the time box has run out
+pub fn expired() -> bool {
+    remaining().is_some_and(|remaining| remaining.is_zero())
+}
+
+fn remaining() -> Option<Duration> {
+    DEADLINE
+        .lock()
+        .expect("deadline poisoned")
+        .as_ref()
+        .map(|d| d.expires.saturating_duration_since(Instant::now()))
+}
+
+/// Time a stopped subprocess gets between SIGTERM and SIGKILL
+pub fn grace() -> Duration {
+    DEADLINE
+        .lock()
+        .expect("deadline poisoned")
+        .as_ref()
+        .map_or(DEFAULT_GRACE, |d| d.grace)
+}
+
+fn timed_out(command: &str) -> anyhow::Error {
+    TimedOut {
+        limit: limit().unwrap_or_default(),
+        command: command.to_string(),
+    }
+    .into()
+}
+
+/// Whether `error` comes from running out of time
+pub fn is_timeout(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<TimedOut>().is_some()
+}
+
+/// `program arg...` of a command, for messages
+pub fn describe(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|part| part.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Run `cmd` with inherited stdio like `Command::status`, stopping it when the time box runs out
+pub async fn status(cmd: Command) -> Result<ExitStatus> {
+    let command = describe(&cmd);
+    let mut child = spawn(cmd, &command)?;
+    let status = wait(&mut child, &command).await?;
+    crash::record_subprocess(&command, status.code(), "");
+    Ok(status)
+}
+
+/// Run `cmd` capturing its output like `Command::output`, stopping it when the time box runs out
+pub async fn output(mut cmd: Command) -> Result<Output> {
+    let command = describe(&cmd);
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = spawn(cmd, &command)?;
+
+    let mut stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+    let read_stdout = async {
+        let mut buffer = Vec::new();
+        if let Some(pipe) = stdout.as_mut() {
+            pipe.read_to_end(&mut buffer).await?;
+        }
+        Ok::<_, std::io::Error>(buffer)
+    };
+    let read_stderr = async {
+        let mut buffer = Vec::new();
+        if let Some(pipe) = stderr.as_mut() {
+            pipe.read_to_end(&mut buffer).await?;
+        }
+        Ok::<_, std::io::Error>(buffer)
+    };
+
+    let (status, stdout, stderr) =
+        tokio::join!(wait(&mut child, &command), read_stdout, read_stderr);
+    let output = Output {
+        status: status?,
+        stdout: stdout?,
+        stderr: stderr?,
+    };
+    crash::record_subprocess(
+        &command,
+        output.status.code(),
+        &format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ),
+    );
+    Ok(output)
+}
+
+/// Run `cmd` with its output shown as it arrives and its last few kilobytes kept, so a failure
+/// can be classified. `input` is written to its stdin, which is closed afterwards.
+pub async fn tee(mut cmd: Command, input: Option<&[u8]>) -> Result<(ExitStatus, String)> {
+    let command = describe(&cmd);
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    if input.is_some() {
+        cmd.stdin(Stdio::piped());
+    }
+    let mut child = spawn(cmd, &command)?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input).await?;
+    }
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (status, stdout, stderr) = tokio::join!(
+        wait(&mut child, &command),
+        forward(stdout, tokio::io::stdout()),
+        forward(stderr, tokio::io::stderr())
+    );
+    let (status, output) = (status?, format!("{}{}", stdout?, stderr?));
+    crash::record_subprocess(&command, status.code(), &output);
+    Ok((status, output))
+}
+
+/// Copy `pipe` to `sink` until it closes, returning the end of what passed through
+async fn forward(
+    pipe: Option<impl AsyncRead + Unpin>,
+    mut sink: impl AsyncWrite + Unpin,
+) -> std::io::Result<String> {
+    let Some(mut pipe) = pipe else {
+        return Ok(String::new());
+    };
+    let mut tail = Vec::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = pipe.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        sink.write_all(&buffer[..read]).await?;
+        sink.flush().await?;
+        tail.extend_from_slice(&buffer[..read]);
+        if tail.len() > TAIL_BYTES {
+            tail.drain(..tail.len() - TAIL_BYTES);
+        }
+    }
+    Ok(String::from_utf8_lossy(&tail).into_owned())
+}
+
+/// Start `cmd`, refusing once the time box has run out or the command was cancelled. The
+/// child is tracked so Ctrl-C can stop it, and traced when tracing is on.
+pub fn spawn(mut cmd: Command, command: &str) -> Result<tokio::process::Child> {
+    if interrupt::is_cancelled() {
+        anyhow::bail!("Cancelled before starting '{}'", command);
+    }
+    if expired() {
+        return Err(timed_out(command));
+    }
+    let own_group = interrupt::isolate(&mut cmd);
+    #[cfg(feature = "telemetry")]
+    if let Some(traceparent) = trace::traceparent() {
+        cmd.env(trace::TRACEPARENT_ENV, traceparent);
+    }
+    let child = tokio::process::Command::from(cmd).spawn()?;
+    if let Some(pid) = child.id() {
+        interrupt::track(pid, command, own_group);
+        #[cfg(feature = "telemetry")]
+        trace::subprocess_started(pid, command);
+    }
+    Ok(child)
+}
+
+/// Wait for a child started with [`spawn`], stopping it when the time box runs out
+pub async fn wait(child: &mut tokio::process::Child, command: &str) -> Result<ExitStatus> {
+    let pid = child.id();
+    let result = match remaining() {
+        None => child.wait().await.map_err(Into::into),
+        Some(remaining) => match tokio::time::timeout(remaining, child.wait()).await {
+            Ok(status) => status.map_err(Into::into),
+            Err(_) => {
+                stop(child).await;
+                Err(timed_out(command))
+            }
+        },
+    };
+    if let Some(pid) = pid {
+        interrupt::untrack(pid);
+        #[cfg(feature = "telemetry")]
+        match &result {
+            Ok(status) => trace::subprocess_finished(pid, status.code(), None),
+            Err(e) => trace::subprocess_finished(pid, None, Some(e.to_string())),
+        }
+    }
+    result
+}
+
+/// SIGTERM the child (and its process group, when it has one) so it can clean up, and SIGKILL it if it's still running after the grace
+/// period. Elsewhere than Unix the child is killed straight away.
+pub async fn stop(child: &mut tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        interrupt::signal(pid, libc::SIGTERM);
+        if tokio::time::timeout(grace(), child.wait()).await.is_ok() {
+            return;
+        }
+    }
+    let _ = child.kill().await;
+}
+
+/// Parse `90`, `90s`, `15m` or `2h`; plain numbers are seconds
+pub fn parse_duration(value: &str) -> std::result::Result<Duration, String> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value, "s"),
+    };
+    let multiplier = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => {
+            return Err(format!(
+                "invalid duration '{}'; use e.g. 90s, 15m or 2h",
+                value
+            ));
+        }
+    };
+    match number.parse::<u64>() {
+        Ok(0) => Err("the duration must be greater than zero".to_string()),
+        Ok(n) => n
+            .checked_mul(multiplier)
+            .map(Duration::from_secs)
+            .ok_or_else(|| format!("the duration '{}' is too long", value)),
+        Err(_) => Err(format!(
+            "invalid duration '{}'; use e.g. 90s, 15m or 2h",
+            value
+        )),
+    }
+}
+
+/// `2h`, `15m` or `90s`, whichever unit divides evenly
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 && secs.is_multiple_of(3600) {
+        format!("{}h", secs / 3600)
+    } else if secs >= 60 && secs.is_multiple_of(60) {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_rejects_overflowing_values() {
+        assert_eq!(
+            parse_duration("6000000000000000h"),
+            Err("the duration '6000000000000000h' is too long".to_string())
+        );
+        assert_eq!(
+            parse_duration("18446744073709551615s"),
+            Ok(Duration::from_secs(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn set_limit_treats_an_unrepresentable_deadline_as_none() {
+        set_limit(Some(Duration::from_secs(u64::MAX)), DEFAULT_GRACE);
+        assert_eq!(limit(), None);
+        assert!(!expired());
+
+        set_limit(Some(Duration::from_secs(60)), DEFAULT_GRACE);
+        assert_eq!(limit(), Some(Duration::from_secs(60)));
+        set_limit(None, DEFAULT_GRACE);
+    }
+}
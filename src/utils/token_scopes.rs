@@ -0,0 +1,257 @@
+use crate::utils::cloudflare::{ACCESS_PERMISSION, BUILDS_PERMISSION, R2_PERMISSION};
+use crate::utils::config::{DeployStrategy, MoonflareConfig};
+use crate::utils::manifest::DeploymentManifest;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::routes::{environments, route_claims};
+use crate::utils::wrangler_defaults::effective_config;
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Dashboard page that opens the token form prefilled from its query string
+const TOKEN_TEMPLATE_URL: &str = "https://dash.cloudflare.com/profile/api-tokens";
+
+/// Whether a permission group applies to the account or to zones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resource {
+    Account,
+    Zone,
+}
+
+/// A Cloudflare API token permission group, with the key and level the dashboard's token
+/// template link takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permission {
+    pub name: &'static str,
+    pub key: &'static str,
+    pub level: &'static str,
+    pub resource: Resource,
+}
+
+const fn account(name: &'static str, key: &'static str, level: &'static str) -> Permission {
+    Permission {
+        name,
+        key,
+        level,
+        resource: Resource::Account,
+    }
+}
+
+const fn zone(name: &'static str, key: &'static str, level: &'static str) -> Permission {
+    Permission {
+        name,
+        key,
+        level,
+        resource: Resource::Zone,
+    }
+}
+
+/// Every permission the workspace can call for, in the order they're listed
+const PERMISSIONS: [Permission; 13] = [
+    account("Workers Scripts Edit", "workers_scripts", "edit"),
+    account("Account Settings Read", "account_settings", "read"),
+    account("Workers KV Storage Edit", "workers_kv_storage", "edit"),
+    account("D1 Edit", "d1", "edit"),
+    account(R2_PERMISSION, "workers_r2", "edit"),
+    account("Queues Edit", "queues", "edit"),
+    account("Vectorize Edit", "vectorize", "edit"),
+    account("Hyperdrive Edit", "hyperdrive", "edit"),
+    account("Cloudflare Pages Edit", "page", "edit"),
+    account(ACCESS_PERMISSION, "access", "edit"),
+    account(BUILDS_PERMISSION, "workers_builds_configuration", "edit"),
+    zone("Workers Routes Edit", "workers_routes", "edit"),
+    zone("DNS Edit", "dns", "edit"),
+];
+
+/// Wrangler configuration keys whose resources `wrangler deploy` has to reach, and the
+/// permission each needs beyond Workers Scripts Edit
+const BINDING_PERMISSIONS: [(&str, &str); 6] = [
+    ("kv_namespaces", "Workers KV Storage Edit"),
+    ("d1_databases", "D1 Edit"),
+    ("r2_buckets", R2_PERMISSION),
+    ("queues", "Queues Edit"),
+    ("vectorize", "Vectorize Edit"),
+    ("hyperdrive", "Hyperdrive Edit"),
+];
+
+/// A permission the token needs and what in the workspace needs it
+#[derive(Debug, Serialize)]
+pub struct RequiredPermission {
+    pub permission: &'static str,
+    pub resource: Resource,
+    pub reasons: Vec<String>,
+    #[serde(skip)]
+    key: &'static str,
+    #[serde(skip)]
+    level: &'static str,
+}
+
+/// The narrowest token that deploys and manages the workspace
+#[derive(Debug, Serialize)]
+pub struct TokenScopes {
+    pub permissions: Vec<RequiredPermission>,
+    /// Zones named by routes; the zone permissions can be limited to these
+    pub zones: Vec<String>,
+    pub template_url: String,
+}
+
+impl TokenScopes {
+    /// Work out the permissions from each project's Wrangler config, its deploy strategy and
+    /// the Access apps and Workers Builds triggers moonflare set up
+    pub fn compute(projects: &[WorkspaceProject], name: &str) -> Result<Self> {
+        let config = MoonflareConfig::load()?;
+        let manifest = DeploymentManifest::load()?;
+        let mut needed: Vec<(Permission, BTreeSet<String>)> = Vec::new();
+        let mut need = |permission: &str, reason: String| {
+            let permission = *PERMISSIONS
+                .iter()
+                .find(|p| p.name == permission)
+                .expect("known permission");
+            match needed.iter_mut().find(|(p, _)| *p == permission) {
+                Some((_, reasons)) => {
+                    reasons.insert(reason);
+                }
+                None => needed.push((permission, BTreeSet::from([reason]))),
+            }
+        };
+
+        let account_from_env =
+            std::env::var("CLOUDFLARE_ACCOUNT_ID").is_ok_and(|id| !id.is_empty());
+        for project in projects {
+            let Some(wrangler) = effective_config(&project.path)? else {
+                continue;
+            };
+            need("Workers Scripts Edit", format!("{}: deploy", project.name));
+            if wrangler.get("account_id").is_none() && !account_from_env {
+                need(
+                    "Account Settings Read",
+                    format!(
+                        "{}: no account_id, so wrangler looks the account up",
+                        project.name
+                    ),
+                );
+            }
+            let keys = config_keys(&wrangler);
+            for (key, permission) in BINDING_PERMISSIONS {
+                if keys.contains(key) {
+                    need(permission, format!("{}: {}", project.name, key));
+                }
+            }
+            match config.deploy_settings(&project.name).strategy {
+                DeployStrategy::Pages => {
+                    need(
+                        "Cloudflare Pages Edit",
+                        format!("{}: pages deploy strategy", project.name),
+                    );
+                }
+                DeployStrategy::R2Static => {
+                    need(
+                        R2_PERMISSION,
+                        format!("{}: r2-static deploy strategy", project.name),
+                    );
+                }
+                DeployStrategy::Wrangler | DeployStrategy::Custom(_) => {}
+            }
+        }
+
+        let mut zones = BTreeSet::new();
+        let scopes = std::iter::once(None).chain(environments(projects)?.into_iter().map(Some));
+        for scope in scopes {
+            for claim in route_claims(projects, scope.as_deref())? {
+                let reason = format!("{}: {}", claim.project, claim.pattern);
+                // A custom domain also creates the DNS record pointing at the Worker
+                if claim.custom_domain {
+                    need("DNS Edit", reason.clone());
+                }
+                need("Workers Routes Edit", reason);
+                zones.extend(claim.zone);
+            }
+        }
+
+        for project in manifest.access.keys() {
+            need(ACCESS_PERMISSION, format!("{}: Access app", project));
+        }
+        for project in manifest
+            .builds
+            .values()
+            .flat_map(|triggers| triggers.keys())
+        {
+            need(
+                BUILDS_PERMISSION,
+                format!("{}: Workers Builds trigger", project),
+            );
+        }
+
+        needed.sort_by_key(|(permission, _)| {
+            PERMISSIONS
+                .iter()
+                .position(|p| p == permission)
+                .unwrap_or_default()
+        });
+        let permissions: Vec<RequiredPermission> = needed
+            .into_iter()
+            .map(|(permission, reasons)| RequiredPermission {
+                permission: permission.name,
+                resource: permission.resource,
+                reasons: reasons.into_iter().collect(),
+                key: permission.key,
+                level: permission.level,
+            })
+            .collect();
+        let template_url = template_url(&permissions, name);
+        Ok(Self {
+            permissions,
+            zones: zones.into_iter().collect(),
+            template_url,
+        })
+    }
+}
+
+/// Top-level keys of the config and of each of its environments
+fn config_keys(config: &Value) -> BTreeSet<&str> {
+    let mut keys = BTreeSet::new();
+    let Some(map) = config.as_object() else {
+        return keys;
+    };
+    for (key, value) in map {
+        if key == "env" {
+            for env in value.as_object().into_iter().flat_map(|envs| envs.values()) {
+                keys.extend(config_keys(env));
+            }
+        } else {
+            keys.insert(key.as_str());
+        }
+    }
+    keys
+}
+
+/// Link to the dashboard's token form with the permissions filled in, for all zones and
+/// every account the user picks
+fn template_url(permissions: &[RequiredPermission], name: &str) -> String {
+    let groups: Vec<Value> = permissions
+        .iter()
+        .map(|p| serde_json::json!({ "key": p.key, "type": p.level }))
+        .collect();
+    format!(
+        "{}?permissionGroupKeys={}&name={}&accountId=*&zoneId=all",
+        TOKEN_TEMPLATE_URL,
+        encode_component(&Value::Array(groups).to_string()),
+        encode_component(name)
+    )
+}
+
+/// Percent-encode a query string value
+fn encode_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
@@ -0,0 +1,133 @@
+use crate::utils::projects::discover_projects;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// First line of every generated environment file; files without it were written by hand
+pub const GENERATED_MARKER: &str = "Generated by moonflare";
+
+/// Tool versions a workspace environment installs, pinned where the workspace pins them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toolchain {
+    pub proto: String,
+    pub node: String,
+    pub pnpm: String,
+    pub moon: String,
+    pub rust: String,
+    pub wrangler: String,
+}
+
+impl Toolchain {
+    /// Versions from `.prototools` first, then `.moon/toolchain.yml`, `rust-toolchain.toml`
+    /// and package.json, with unpinned tools on their latest stable release
+    pub fn detect(root: &Path) -> Self {
+        let prototools = fs::read_to_string(root.join(".prototools"))
+            .ok()
+            .and_then(|content| content.parse::<toml::Table>().ok())
+            .unwrap_or_default();
+        let pinned = |tool: &str| {
+            prototools
+                .get(tool)
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+
+        let moon_toolchain: serde_yaml::Value =
+            fs::read_to_string(root.join(".moon/toolchain.yml"))
+                .ok()
+                .and_then(|content| serde_yaml::from_str(&content).ok())
+                .unwrap_or_default();
+        let moon_version = |path: &[&str]| {
+            let mut value = &moon_toolchain;
+            for key in path {
+                value = value.get(key)?;
+            }
+            value
+                .as_str()
+                .filter(|version| *version != "latest")
+                .map(str::to_string)
+        };
+
+        let package: Value = fs::read_to_string(root.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let package_manager = package["packageManager"]
+            .as_str()
+            .and_then(|pm| pm.strip_prefix("pnpm@"))
+            .map(|version| version.split('+').next().unwrap_or(version).to_string());
+
+        Self {
+            proto: pinned("proto")
+                .or_else(|| moon_version(&["proto", "version"]))
+                .unwrap_or_else(|| "latest".to_string()),
+            node: pinned("node")
+                .or_else(|| moon_version(&["node", "version"]))
+                .unwrap_or_else(|| "lts".to_string()),
+            pnpm: pinned("pnpm")
+                .or_else(|| moon_version(&["node", "pnpm", "version"]))
+                .or(package_manager)
+                .unwrap_or_else(|| "latest".to_string()),
+            moon: pinned("moon").unwrap_or_else(|| "latest".to_string()),
+            rust: rust_channel(root)
+                .or_else(|| pinned("rust"))
+                .or_else(|| moon_version(&["rust", "version"]))
+                .unwrap_or_else(|| "stable".to_string()),
+            wrangler: wrangler_version(&package).unwrap_or_else(|| "latest".to_string()),
+        }
+    }
+}
+
+/// `toolchain.channel` from rust-toolchain.toml, or the legacy one-line rust-toolchain file
+fn rust_channel(root: &Path) -> Option<String> {
+    if let Ok(content) = fs::read_to_string(root.join("rust-toolchain.toml")) {
+        return content
+            .parse::<toml::Table>()
+            .ok()?
+            .get("toolchain")?
+            .get("channel")?
+            .as_str()
+            .map(str::to_string);
+    }
+    fs::read_to_string(root.join("rust-toolchain"))
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|channel| !channel.is_empty())
+}
+
+/// The wrangler range the root package.json declares, else the first project's
+fn wrangler_version(package: &Value) -> Option<String> {
+    let declared = |package: &Value| {
+        ["devDependencies", "dependencies"]
+            .iter()
+            .find_map(|field| package[field]["wrangler"].as_str().map(str::to_string))
+    };
+    declared(package).or_else(|| {
+        discover_projects().iter().find_map(|project| {
+            let content = fs::read_to_string(project.path.join("package.json")).ok()?;
+            declared(&serde_json::from_str(&content).ok()?)
+        })
+    })
+}
+
+/// Paths of `rendered` files whose content on disk differs, ignoring the generated header that
+/// names the moonflare version
+pub fn stale(rendered: Vec<(PathBuf, Vec<u8>)>) -> Vec<PathBuf> {
+    let body = |content: &str| {
+        content
+            .lines()
+            .filter(|line| !line.contains(GENERATED_MARKER))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    rendered
+        .into_iter()
+        .filter(|(path, content)| {
+            fs::read_to_string(path)
+                .ok()
+                .map(|existing| body(&existing))
+                != Some(body(&String::from_utf8_lossy(content)))
+        })
+        .map(|(path, _)| path)
+        .collect()
+}
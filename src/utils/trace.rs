@@ -0,0 +1,294 @@
+use crate::utils::config::MoonflareConfig;
+use crate::utils::http;
+use crate::utils::metrics::attribute;
+use anyhow::{Context, Result, bail};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Standard OpenTelemetry variable read when `[tracing]` has no token
+pub const HEADERS_ENV: &str = "OTEL_EXPORTER_OTLP_HEADERS";
+/// W3C trace context; read to join a CI pipeline's trace and passed on to subprocesses
+pub const TRACEPARENT_ENV: &str = "TRACEPARENT";
+
+/// How long a command waits for the receiver before giving up on the export
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+struct Span {
+    id: String,
+    parent: Option<String>,
+    name: String,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(&'static str, String)>,
+    /// Why the span failed; `None` when it succeeded
+    error: Option<String>,
+}
+
+/// The spans of the running command, collected until [`finish`] exports them
+struct Trace {
+    url: String,
+    trace_id: String,
+    root: Span,
+    spans: Vec<Span>,
+    /// Subprocesses still running, by pid
+    running: HashMap<u32, Span>,
+}
+
+static TRACE: Mutex<Option<Trace>> = Mutex::new(None);
+
+fn new_trace_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+fn new_span_id() -> String {
+    new_trace_id()[..16].to_string()
+}
+
+/// `(trace id, parent span id)` of a `00-<trace>-<span>-<flags>` traceparent
+fn parse_traceparent(value: &str) -> Option<(String, String)> {
+    let parts: Vec<&str> = value.trim().split('-').collect();
+    match parts.as_slice() {
+        [_, trace, span, _]
+            if trace.len() == 32
+                && span.len() == 16
+                && trace
+                    .chars()
+                    .chain(span.chars())
+                    .all(|c| c.is_ascii_hexdigit()) =>
+        {
+            Some((trace.to_lowercase(), span.to_lowercase()))
+        }
+        _ => None,
+    }
+}
+
+/// The receiver's traces URL
+fn traces_url(flag: Option<&str>, config: &MoonflareConfig) -> Option<String> {
+    let endpoint = config.tracing.endpoint(flag)?;
+    Some(if endpoint.ends_with("/v1/traces") {
+        endpoint
+    } else {
+        format!("{}/v1/traces", endpoint.trim_end_matches('/'))
+    })
+}
+
+/// Start tracing `command` when an OTLP endpoint is configured; otherwise every other
+/// function here is a no-op
+pub fn init(flag: Option<&str>, command: &str, args: &[String]) {
+    let config = MoonflareConfig::load().unwrap_or_default();
+    let Some(url) = traces_url(flag, &config) else {
+        return;
+    };
+    let (trace_id, parent) = match std::env::var(TRACEPARENT_ENV)
+        .ok()
+        .and_then(|value| parse_traceparent(&value))
+    {
+        Some((trace_id, parent)) => (trace_id, Some(parent)),
+        None => (new_trace_id(), None),
+    };
+    let now = SystemTime::now();
+    let root = Span {
+        id: new_span_id(),
+        parent,
+        name: format!("moonflare {}", command),
+        start: now,
+        end: now,
+        attributes: vec![
+            ("moonflare.command", command.to_string()),
+            ("moonflare.args", args.join(" ")),
+        ],
+        error: None,
+    };
+
+    *TRACE.lock().expect("trace poisoned") = Some(Trace {
+        url,
+        trace_id,
+        root,
+        spans: Vec::new(),
+        running: HashMap::new(),
+    });
+}
+
+/// Traceparent for subprocesses, so tools that trace themselves join the command's trace
+pub fn traceparent() -> Option<String> {
+    TRACE
+        .lock()
+        .expect("trace poisoned")
+        .as_ref()
+        .map(|trace| format!("00-{}-{}-01", trace.trace_id, trace.root.id))
+}
+
+/// A subprocess started; its span ends with [`subprocess_finished`]
+pub fn subprocess_started(pid: u32, command: &str) {
+    let mut trace = TRACE.lock().expect("trace poisoned");
+    let Some(trace) = trace.as_mut() else {
+        return;
+    };
+    let program = command.split_whitespace().next().unwrap_or(command);
+    let program = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    let now = SystemTime::now();
+    let span = Span {
+        id: new_span_id(),
+        parent: Some(trace.root.id.clone()),
+        name: format!("exec {}", program),
+        start: now,
+        end: now,
+        attributes: vec![
+            ("process.command_line", command.to_string()),
+            ("process.pid", pid.to_string()),
+        ],
+        error: None,
+    };
+    trace.running.insert(pid, span);
+}
+
+/// The subprocess `pid` exited, with `error` describing a failure
+pub fn subprocess_finished(pid: u32, exit_code: Option<i32>, error: Option<String>) {
+    let mut trace = TRACE.lock().expect("trace poisoned");
+    let Some(trace) = trace.as_mut() else {
+        return;
+    };
+    let Some(mut span) = trace.running.remove(&pid) else {
+        return;
+    };
+    span.end = SystemTime::now();
+    if let Some(code) = exit_code {
+        span.attributes
+            .push(("process.exit_code", code.to_string()));
+    }
+    span.error = error.or_else(|| {
+        exit_code
+            .filter(|code| *code != 0)
+            .map(|code| format!("exited with code {}", code))
+    });
+    trace.spans.push(span);
+}
+
+/// A Moon task that finished just now after running for `duration`
+pub fn task(target: &str, success: bool, duration: Duration, message: Option<&str>) {
+    let mut trace = TRACE.lock().expect("trace poisoned");
+    let Some(trace) = trace.as_mut() else {
+        return;
+    };
+    let end = SystemTime::now();
+    trace.spans.push(Span {
+        id: new_span_id(),
+        parent: Some(trace.root.id.clone()),
+        name: target.to_string(),
+        start: end.checked_sub(duration).unwrap_or(end),
+        end,
+        attributes: vec![("moon.target", target.to_string())],
+        error: (!success).then(|| message.unwrap_or("failed").to_string()),
+    });
+}
+
+fn nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+impl Span {
+    fn otlp(&self, trace_id: &str) -> Value {
+        let mut span = json!({
+            "traceId": trace_id,
+            "spanId": self.id,
+            "name": self.name,
+            "kind": 1,
+            "startTimeUnixNano": nanos(self.start),
+            "endTimeUnixNano": nanos(self.end),
+            "attributes": self
+                .attributes
+                .iter()
+                .map(|(key, value)| attribute(key, value))
+                .collect::<Vec<_>>(),
+            "status": match &self.error {
+                Some(message) => json!({ "code": 2, "message": message }),
+                None => json!({ "code": 1 }),
+            },
+        });
+        if let Some(parent) = &self.parent {
+            span["parentSpanId"] = json!(parent);
+        }
+        span
+    }
+}
+
+impl Trace {
+    /// An OTLP/HTTP JSON export request holding every span
+    fn otlp(&self, workspace: &str) -> Value {
+        let spans: Vec<Value> = std::iter::once(&self.root)
+            .chain(&self.spans)
+            .chain(self.running.values())
+            .map(|span| span.otlp(&self.trace_id))
+            .collect();
+        json!({ "resourceSpans": [{
+            "resource": { "attributes": [
+                attribute("service.name", "moonflare"),
+                attribute("service.version", env!("CARGO_PKG_VERSION")),
+                attribute("moonflare.workspace", workspace),
+            ] },
+            "scopeSpans": [{
+                "scope": { "name": "moonflare" },
+                "spans": spans,
+            }],
+        }] })
+    }
+}
+
+/// `key=value` pairs of `OTEL_EXPORTER_OTLP_HEADERS`
+fn env_headers() -> Vec<(String, String)> {
+    std::env::var(HEADERS_ENV)
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+async fn export(url: &str, body: &Value, config: &MoonflareConfig) -> Result<()> {
+    let mut request = http::builder()?
+        .timeout(EXPORT_TIMEOUT)
+        .build()?
+        .post(url)
+        .json(body);
+    for (key, value) in env_headers() {
+        request = request.header(key, value);
+    }
+    if let Some(token) = config.tracing.token()? {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .context("Failed to reach the OTLP endpoint")?;
+    if !response.status().is_success() {
+        bail!("OTLP endpoint responded with {}", response.status());
+    }
+    Ok(())
+}
+
+/// End the command's span and export the trace. Like metrics, a failed export only warns.
+pub async fn finish(error: Option<&str>) {
+    let Some(mut trace) = TRACE.lock().expect("trace poisoned").take() else {
+        return;
+    };
+    trace.root.end = SystemTime::now();
+    trace.root.error = error.map(str::to_string);
+
+    let config = MoonflareConfig::load().unwrap_or_default();
+    let workspace = config.workspace.name.clone().unwrap_or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_default()
+    });
+    if let Err(e) = export(&trace.url, &trace.otlp(&workspace), &config).await {
+        eprintln!("Warning: couldn't export trace: {:#}", e);
+    }
+}
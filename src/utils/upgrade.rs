@@ -0,0 +1,226 @@
+use crate::utils::config::config_path;
+use crate::utils::config_lint;
+use crate::utils::config_schema::{self, CONFIG_VERSION};
+use crate::utils::moon_tasks::render_managed_files;
+use crate::utils::plan::Plan;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::version::Version;
+use crate::utils::wrangler::{find_wrangler_config, read_wrangler_config, render_wrangler_config};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Decisions made in `moonflare upgrade`, committed so CI can replay them
+pub const DECISIONS_FILE: &str = ".moonflare/upgrades.json";
+
+/// Wrangler range the project templates depend on
+pub const WRANGLER_RANGE: &str = "^4.32.0";
+
+/// One breaking change between what the workspace was generated with and what this moonflare
+/// generates, with the edits that bring the workspace across
+#[derive(Debug)]
+pub struct UpgradeStep {
+    /// Stable across runs, e.g. `wrangler-major:api`, so a decision can be replayed
+    pub id: String,
+    pub title: String,
+    /// Why the change is needed, and what to check after applying it
+    pub reason: String,
+    pub plan: Plan,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Apply,
+    Skip,
+}
+
+/// What was decided for each upgrade step, by id
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Decisions {
+    #[serde(default)]
+    pub decisions: BTreeMap<String, Decision>,
+}
+
+impl Decisions {
+    pub fn load() -> Result<Self> {
+        let path = Path::new(DECISIONS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", DECISIONS_FILE))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", DECISIONS_FILE))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Path::new(DECISIONS_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, format!("{}\n", serde_json::to_string_pretty(self)?))
+            .with_context(|| format!("Failed to write {}", DECISIONS_FILE))
+    }
+}
+
+/// Breaking changes the workspace hasn't been brought across yet: moonflare.toml's config
+/// version, Wrangler major versions and deprecated keys, Moon's `type` to `layer` rename and
+/// changed task inheritance files
+pub fn detect(projects: &[WorkspaceProject]) -> Result<Vec<UpgradeStep>> {
+    let mut steps = Vec::new();
+    steps.extend(config_version()?);
+    for project in projects {
+        steps.extend(wrangler_major(project)?);
+        steps.extend(wrangler_config(project)?);
+        steps.extend(moon_layer(project)?);
+    }
+    steps.extend(moon_tasks()?);
+    Ok(steps)
+}
+
+fn config_version() -> Result<Option<UpgradeStep>> {
+    let path = config_path();
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let mut config: toml::Value =
+        toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+    let from = config_schema::version(&config)?;
+    if from == CONFIG_VERSION {
+        return Ok(None);
+    }
+    let moved = config_schema::migrate(&mut config)?;
+    let after = if moved.is_empty() {
+        config_schema::set_version(&content)
+    } else {
+        toml::to_string_pretty(&config)?
+    };
+
+    let mut plan = Plan::new();
+    plan.modify(&path, content, after);
+    Ok(Some(UpgradeStep {
+        id: "config-version".to_string(),
+        title: format!(
+            "moonflare.toml config version {} to {}",
+            from, CONFIG_VERSION
+        ),
+        reason: if moved.is_empty() {
+            "Records the config version; nothing else changes.".to_string()
+        } else {
+            format!("{}. Comments are not preserved.", moved.join("; "))
+        },
+        plan,
+    }))
+}
+
+/// The project's package.json pins a Wrangler major older than the templates use
+fn wrangler_major(project: &WorkspaceProject) -> Result<Option<UpgradeStep>> {
+    let path = project.path.join("package.json");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let pattern = regex::Regex::new(r#""wrangler"\s*:\s*"([^"]*)""#).expect("valid regex");
+    let Some(range) = pattern.captures(&content).map(|c| c[1].to_string()) else {
+        return Ok(None);
+    };
+    let current = Version::parse(WRANGLER_RANGE.trim_start_matches(['^', '~']))
+        .expect("valid Wrangler range");
+    let Some(pinned) = Version::parse(range.trim_start_matches(['^', '~', '=', '>', ' '])) else {
+        return Ok(None);
+    };
+    if pinned.major >= current.major {
+        return Ok(None);
+    }
+
+    let after = pattern
+        .replace(
+            &content,
+            regex::NoExpand(&format!("\"wrangler\": \"{}\"", WRANGLER_RANGE)),
+        )
+        .into_owned();
+    let mut plan = Plan::new();
+    plan.modify(&path, content, after);
+    Ok(Some(UpgradeStep {
+        id: format!("wrangler-major:{}", project.name),
+        title: format!(
+            "{}: Wrangler {} to {}",
+            project.name, pinned.major, current.major
+        ),
+        reason: format!(
+            "Wrangler {} is what the project templates are tested against. Run 'pnpm install' afterwards, review its migration guide for changed CLI flags and defaults, then run 'moonflare dev' to check the project.",
+            current.major
+        ),
+        plan,
+    }))
+}
+
+/// The project's Wrangler configuration uses keys Wrangler has deprecated or rejects
+fn wrangler_config(project: &WorkspaceProject) -> Result<Option<UpgradeStep>> {
+    let (Some(path), Some(mut config)) = (
+        find_wrangler_config(&project.path),
+        read_wrangler_config(&project.path)?,
+    ) else {
+        return Ok(None);
+    };
+    let deprecations = config_lint::upgrade(&mut config);
+    if !deprecations.iter().any(|d| d.fixable) {
+        return Ok(None);
+    }
+
+    let before =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut plan = Plan::new();
+    plan.modify(&path, before, render_wrangler_config(&path, &config)?);
+    let mut reason: Vec<String> = deprecations.iter().map(|d| d.describe()).collect();
+    reason.push("Comments are not preserved.".to_string());
+    Ok(Some(UpgradeStep {
+        id: format!("wrangler-config:{}", project.name),
+        title: format!("{}: deprecated Wrangler keys", project.name),
+        reason: reason.join("\n"),
+        plan,
+    }))
+}
+
+/// Moon 1.28 renamed a project's `type` to `layer`
+fn moon_layer(project: &WorkspaceProject) -> Result<Option<UpgradeStep>> {
+    let path = project.path.join("moon.yml");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let pattern = regex::Regex::new(r"(?m)^type(\s*):").expect("valid regex");
+    if !pattern.is_match(&content) {
+        return Ok(None);
+    }
+
+    let after = pattern.replace(&content, "layer$1:").into_owned();
+    let mut plan = Plan::new();
+    plan.modify(&path, content, after);
+    Ok(Some(UpgradeStep {
+        id: format!("moon-layer:{}", project.name),
+        title: format!("{}: Moon project type renamed to layer", project.name),
+        reason: "Moon 1.28 reads the project's layer from 'layer'; 'type' is deprecated and scoped task files match on the layer.".to_string(),
+        plan,
+    }))
+}
+
+/// Managed task inheritance files that differ from what this moonflare generates, e.g. after
+/// a task was renamed. Files the workspace never had are left to 'moonflare tasks sync'.
+fn moon_tasks() -> Result<Option<UpgradeStep>> {
+    let mut plan = Plan::new();
+    for (path, content) in render_managed_files()? {
+        if let Ok(before) = fs::read_to_string(&path) {
+            plan.modify(path, before, content);
+        }
+    }
+    if plan.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(UpgradeStep {
+        id: "moon-tasks".to_string(),
+        title: "Moon task inheritance files".to_string(),
+        reason: "The tasks projects inherit changed. Projects that override a renamed task in their moon.yml keep the old name until it's renamed there too.".to_string(),
+        plan,
+    }))
+}
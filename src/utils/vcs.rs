@@ -0,0 +1,174 @@
+use anyhow::Context;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Current git branch, preferring CI-provided variables (CI checkouts are often detached)
+pub fn current_branch() -> Option<String> {
+    let ci_vars = [
+        "GITHUB_HEAD_REF",    // GitHub Actions pull requests
+        "GITHUB_REF_NAME",    // GitHub Actions pushes
+        "CI_COMMIT_REF_NAME", // GitLab CI
+        "CF_PAGES_BRANCH",    // Cloudflare builds
+        "BRANCH_NAME",        // Jenkins and others
+    ];
+
+    for var in ci_vars {
+        if let Ok(value) = std::env::var(var)
+            && !value.trim().is_empty()
+        {
+            return Some(value.trim().to_string());
+        }
+    }
+
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if branch.is_empty() || branch == "HEAD" {
+        None
+    } else {
+        Some(branch)
+    }
+}
+
+/// Whether the current CI run was triggered by a pull/merge request
+pub fn is_pull_request() -> bool {
+    let github_pr = std::env::var("GITHUB_EVENT_NAME")
+        .map(|e| e == "pull_request" || e == "pull_request_target")
+        .unwrap_or(false);
+    let gitlab_mr = std::env::var("CI_MERGE_REQUEST_IID").is_ok();
+
+    github_pr || gitlab_mr
+}
+
+/// SHA of the checked-out commit, if inside a git repository
+pub fn current_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if sha.is_empty() { None } else { Some(sha) }
+}
+
+/// Uncommitted (staged, unstaged or untracked) files under the given paths, as reported by
+/// `git status --porcelain -z`. Renamed files are listed under their new name. Fails when git
+/// can't be queried, e.g. outside a git repository.
+pub fn uncommitted_changes(paths: &[&Path]) -> anyhow::Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain", "-z", "--"])
+        .args(paths)
+        .output()
+        .context("Failed to run git status")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git status failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.split('\0').filter(|field| !field.is_empty());
+    let mut changes = Vec::new();
+    while let Some(entry) = fields.next() {
+        let (Some(status), Some(path)) = (entry.get(..2), entry.get(3..)) else {
+            continue;
+        };
+        // A rename or copy is followed by the path it came from
+        if status.contains(['R', 'C']) {
+            fields.next();
+        }
+        changes.push(path.to_string());
+    }
+    Ok(changes)
+}
+
+/// Move a project directory, staging the move of the files git tracks so the rename, and
+/// their history, show up in the next commit. Untracked and ignored files move along unstaged.
+pub fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    let tracked: Vec<PathBuf> = Command::new("git")
+        .args(["ls-files", "-z", "--"])
+        .arg(from)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .split('\0')
+                .filter_map(|file| {
+                    let rest = Path::new(file).strip_prefix(from).ok()?;
+                    Some(if rest.as_os_str().is_empty() {
+                        to.to_path_buf()
+                    } else {
+                        to.join(rest)
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(from, to)?;
+    if tracked.is_empty() {
+        return Ok(());
+    }
+
+    let staged = Command::new("git")
+        .args(["rm", "-r", "-q", "--cached", "--"])
+        .arg(from)
+        .status()
+        .is_ok_and(|status| status.success())
+        && Command::new("git")
+            .args(["add", "-f", "--"])
+            .args(&tracked)
+            .status()
+            .is_ok_and(|status| status.success());
+    if !staged {
+        eprintln!(
+            "Warning: moved {} but couldn't stage the move in git",
+            from.display()
+        );
+    }
+    Ok(())
+}
+
+/// Who started the run: the CI actor when available, otherwise the git author or OS user
+pub fn triggered_by() -> String {
+    for var in [
+        "GITHUB_ACTOR",
+        "GITLAB_USER_LOGIN",
+        "BUILDKITE_BUILD_CREATOR",
+    ] {
+        if let Ok(value) = std::env::var(var)
+            && !value.trim().is_empty()
+        {
+            return value.trim().to_string();
+        }
+    }
+
+    let git_user = Command::new("git")
+        .args(["config", "user.name"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|name| !name.is_empty());
+
+    git_user
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
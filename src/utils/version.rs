@@ -0,0 +1,47 @@
+/// A `major.minor.patch` version; pre-release and build suffixes are ignored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    /// Parse versions like `0.1.0`, `v1.2`, or `20.11.1-nightly`
+    pub fn parse(input: &str) -> Option<Self> {
+        let trimmed = input.trim().trim_start_matches('v');
+        let core = trimmed.split(['-', '+']).next()?;
+        let mut parts = core.split('.');
+
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts
+            .next()
+            .map(|p| p.parse())
+            .transpose()
+            .ok()?
+            .unwrap_or(0);
+        let patch = parts
+            .next()
+            .map(|p| p.parse())
+            .transpose()
+            .ok()?
+            .unwrap_or(0);
+
+        Some(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    /// The version of the running moonflare binary
+    pub fn current() -> Self {
+        Self::parse(env!("CARGO_PKG_VERSION")).expect("crate version is valid")
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
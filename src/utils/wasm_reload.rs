@@ -0,0 +1,85 @@
+use crate::templates::{embedded, engine::TemplateEngine};
+use crate::utils::config::LayoutConfig;
+use crate::utils::moon::run_moon_command_silent;
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::watch::FileSnapshot;
+use anyhow::Result;
+use colored::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// File `moonflare dev` touches after gathering rebuilt WASM; the Vite plugin watches it
+pub const SIGNAL_FILE: &str = ".moonflare/wasm-reload";
+/// Environment variable telling the Vite plugin where the signal file is
+pub const SIGNAL_ENV: &str = "MOONFLARE_WASM_SIGNAL";
+/// Vite plugin the React and Astro templates import
+pub const PLUGIN_FILE: &str = "moonflare-wasm-reload.mjs";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Whether the project's dev server loads the reload plugin
+pub fn uses_plugin(project: &WorkspaceProject) -> bool {
+    project.path.join(PLUGIN_FILE).exists()
+}
+
+/// Rewrite the plugin in each project that has one, so upgrading moonflare upgrades it too
+pub fn refresh_plugins(projects: &[WorkspaceProject]) -> Result<()> {
+    let engine = TemplateEngine::new();
+    for project in projects.iter().filter(|p| uses_plugin(p)) {
+        engine.process_template_files(
+            embedded::WASM_RELOAD_TEMPLATE,
+            &project.path,
+            &HashMap::new(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Rebuild shared-wasm whenever crate sources change, and touch the signal file whenever the
+/// gathered modules change, whether this watcher or something else rebuilt them
+pub async fn watch_wasm(root: PathBuf) {
+    let crates = root.join(LayoutConfig::current().crates);
+    let shared_wasm = root.join("shared-wasm");
+    let signal = root.join(SIGNAL_FILE);
+
+    let mut sources = FileSnapshot::capture(&[&crates]);
+    let mut modules = wasm_modules(&shared_wasm);
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let current = FileSnapshot::capture(&[&crates]);
+        if !current.changed_since(&sources).is_empty() {
+            println!("{}", "Crate sources changed; rebuilding WASM...".cyan());
+            if let Err(e) = run_moon_command_silent(&["run", "shared-wasm:gather"]).await {
+                eprintln!("{} {}", "WASM rebuild failed:".red(), e);
+            }
+            // Builds can touch files under crates/ (Cargo.lock), so start from after the build
+            sources = FileSnapshot::capture(&[&crates]);
+        }
+
+        let current = wasm_modules(&shared_wasm);
+        if current != modules {
+            modules = current;
+            match touch_signal(&signal) {
+                Ok(()) => println!("{}", "WASM modules updated; reloading dev servers".cyan()),
+                Err(e) => eprintln!("{} {}", "Couldn't signal dev servers:".red(), e),
+            }
+        }
+    }
+}
+
+/// Gathered modules and component bindings in shared-wasm/
+fn wasm_modules(shared_wasm: &Path) -> FileSnapshot {
+    FileSnapshot::capture(&[shared_wasm])
+}
+
+fn touch_signal(signal: &Path) -> Result<()> {
+    if let Some(parent) = signal.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+    fs::write(signal, format!("{}\n", now))?;
+    Ok(())
+}
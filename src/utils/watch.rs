@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// Directories never watched: dependencies and build output
+const IGNORED_DIRECTORIES: [&str; 7] = [
+    "node_modules",
+    "target",
+    "dist",
+    "pkg",
+    ".wrangler",
+    ".astro",
+    ".moon",
+];
+
+/// Modification times of every watched file under a set of roots
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FileSnapshot {
+    files: BTreeMap<PathBuf, SystemTime>,
+}
+
+impl FileSnapshot {
+    pub fn capture(roots: &[&Path]) -> Self {
+        let mut files = BTreeMap::new();
+        for root in roots {
+            let walker = WalkDir::new(root).into_iter().filter_entry(|entry| {
+                !(entry.file_type().is_dir()
+                    && IGNORED_DIRECTORIES
+                        .iter()
+                        .any(|ignored| entry.file_name() == *ignored))
+            });
+
+            for entry in walker.flatten() {
+                if entry.file_type().is_file()
+                    && let Some(modified) = entry.metadata().ok().and_then(|m| m.modified().ok())
+                {
+                    files.insert(entry.into_path(), modified);
+                }
+            }
+        }
+        Self { files }
+    }
+
+    /// Files added, modified or removed since `previous`
+    pub fn changed_since(&self, previous: &FileSnapshot) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|(path, modified)| previous.files.get(*path) != Some(*modified))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(
+            previous
+                .files
+                .keys()
+                .filter(|path| !self.files.contains_key(*path))
+                .cloned(),
+        );
+        changed.sort();
+        changed
+    }
+}
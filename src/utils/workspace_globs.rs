@@ -0,0 +1,437 @@
+use crate::utils::projects::ARCHIVE_DIR;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PNPM_WORKSPACE_FILE: &str = "pnpm-workspace.yaml";
+pub const MOON_WORKSPACE_FILE: &str = ".moon/workspace.yml";
+
+/// Directories never scanned for projects
+const SKIPPED_DIRECTORIES: [&str; 6] = [
+    "node_modules",
+    "target",
+    "dist",
+    "pkg",
+    "shared-wasm",
+    ARCHIVE_DIR,
+];
+
+/// How deep below the workspace root projects are looked for
+const MAX_PROJECT_DEPTH: usize = 3;
+
+/// A file listing the workspace's project globs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlobSource {
+    /// `packages` in pnpm-workspace.yaml
+    PnpmWorkspace,
+    /// `workspaces` in package.json, used when there's no pnpm-workspace.yaml
+    PackageJson,
+    /// `projects.globs` in .moon/workspace.yml
+    Moon,
+}
+
+impl GlobSource {
+    pub fn file(&self) -> &'static str {
+        match self {
+            GlobSource::PnpmWorkspace => PNPM_WORKSPACE_FILE,
+            GlobSource::PackageJson => "package.json",
+            GlobSource::Moon => MOON_WORKSPACE_FILE,
+        }
+    }
+
+    /// File marking a directory as a project for this source
+    fn manifest(&self) -> &'static str {
+        match self {
+            GlobSource::PnpmWorkspace | GlobSource::PackageJson => "package.json",
+            GlobSource::Moon => "moon.yml",
+        }
+    }
+}
+
+impl fmt::Display for GlobSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.file())
+    }
+}
+
+/// Difference between a source's globs and the project directories on disk
+#[derive(Debug, Clone)]
+pub struct GlobDrift {
+    pub source: GlobSource,
+    /// Globs needed for projects no existing glob covers
+    pub missing: Vec<String>,
+    /// Globs whose base directory no longer exists
+    pub stale: Vec<String>,
+}
+
+impl GlobDrift {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.stale.is_empty()
+    }
+
+    /// Summary of the change a fix makes, e.g. `added services/*, removed legacy/*`
+    pub fn describe(&self) -> String {
+        let mut changes = Vec::new();
+        if !self.missing.is_empty() {
+            changes.push(format!("added {}", self.missing.join(", ")));
+        }
+        if !self.stale.is_empty() {
+            changes.push(format!("removed {}", self.stale.join(", ")));
+        }
+        changes.join("; ")
+    }
+}
+
+/// Drift for every glob source in the workspace at `root`
+pub fn detect_drift(root: &Path) -> Result<Vec<GlobDrift>> {
+    let mut sources = Vec::new();
+    if root.join(PNPM_WORKSPACE_FILE).exists() {
+        sources.push(GlobSource::PnpmWorkspace);
+    } else if read_package_json_globs(root)?.is_some() {
+        sources.push(GlobSource::PackageJson);
+    }
+    if root.join(MOON_WORKSPACE_FILE).exists() {
+        sources.push(GlobSource::Moon);
+    }
+
+    let mut drift = Vec::new();
+    for source in sources {
+        let globs = read_globs(root, source)?;
+        let sources = match source {
+            GlobSource::Moon => moon_sources(root)?,
+            _ => Vec::new(),
+        };
+        let projects = project_directories(root, source.manifest());
+
+        let mut missing: Vec<String> = Vec::new();
+        for project in &projects {
+            if globs.iter().any(|glob| covers(glob, project))
+                || sources
+                    .iter()
+                    .any(|path| path.trim_start_matches("./") == project)
+            {
+                continue;
+            }
+            let glob = match project.rsplit_once('/') {
+                Some((parent, _)) => format!("{}/*", parent),
+                None => project.clone(),
+            };
+            if !missing.contains(&glob) {
+                missing.push(glob);
+            }
+        }
+
+        let stale: Vec<String> = globs
+            .iter()
+            .filter(|glob| !glob.starts_with('!'))
+            .filter(|glob| {
+                let base = glob_base(glob);
+                !base.is_empty() && !root.join(base).exists()
+            })
+            .cloned()
+            .collect();
+
+        let source_drift = GlobDrift {
+            source,
+            missing,
+            stale,
+        };
+        if !source_drift.is_empty() {
+            drift.push(source_drift);
+        }
+    }
+
+    Ok(drift)
+}
+
+/// Remove stale globs and add missing ones
+pub fn fix_drift(root: &Path, drift: &[GlobDrift]) -> Result<()> {
+    for source_drift in drift {
+        let mut globs = read_globs(root, source_drift.source)?;
+        globs.retain(|glob| !source_drift.stale.contains(glob));
+        globs.extend(source_drift.missing.iter().cloned());
+        write_globs(root, source_drift.source, &globs)?;
+    }
+    Ok(())
+}
+
+/// Bring every glob source in line with the project directories on disk, returning what changed
+pub fn sync_workspace_globs(root: &Path) -> Result<Vec<GlobDrift>> {
+    let drift = detect_drift(root)?;
+    fix_drift(root, &drift)?;
+    Ok(drift)
+}
+
+/// Drop the globs and Moon `projects.sources` entries naming one project's path exactly,
+/// such as `workers/api`, returning the files changed. Wildcard globs stay: they simply stop
+/// matching once the project moves.
+pub fn remove_project_globs(root: &Path, project: &str) -> Result<Vec<GlobSource>> {
+    let names_project =
+        |entry: &str| entry.trim_start_matches("./").trim_end_matches('/') == project;
+    let mut changed = Vec::new();
+
+    for source in [
+        GlobSource::PnpmWorkspace,
+        GlobSource::PackageJson,
+        GlobSource::Moon,
+    ] {
+        let exists = match source {
+            GlobSource::PackageJson => read_package_json_globs(root)?.is_some(),
+            _ => root.join(source.file()).exists(),
+        };
+        if !exists {
+            continue;
+        }
+
+        let mut globs = read_globs(root, source)?;
+        let before = globs.len();
+        globs.retain(|glob| !names_project(glob));
+        let mut source_changed = globs.len() != before;
+        if source_changed {
+            write_globs(root, source, &globs)?;
+        }
+
+        if source == GlobSource::Moon {
+            let path = root.join(MOON_WORKSPACE_FILE);
+            let mut config = read_yaml(&path)?;
+            if let Some(sources) = config
+                .get_mut("projects")
+                .and_then(|p| p.get_mut("sources"))
+                .and_then(|s| s.as_mapping_mut())
+            {
+                let before = sources.len();
+                sources.retain(|_, path| !path.as_str().is_some_and(names_project));
+                if sources.len() != before {
+                    fs::write(&path, serde_yaml::to_string(&config)?)?;
+                    source_changed = true;
+                }
+            }
+        }
+
+        if source_changed {
+            changed.push(source);
+        }
+    }
+    Ok(changed)
+}
+
+fn read_globs(root: &Path, source: GlobSource) -> Result<Vec<String>> {
+    match source {
+        GlobSource::PackageJson => Ok(read_package_json_globs(root)?.unwrap_or_default()),
+        GlobSource::PnpmWorkspace => {
+            let config = read_yaml(&root.join(PNPM_WORKSPACE_FILE))?;
+            Ok(string_sequence(config.get("packages")))
+        }
+        GlobSource::Moon => {
+            let config = read_yaml(&root.join(MOON_WORKSPACE_FILE))?;
+            let Some(projects) = config.get("projects") else {
+                return Ok(Vec::new());
+            };
+
+            // `projects` is either a list of globs or a map with `globs` and `sources`
+            if projects.is_sequence() {
+                return Ok(string_sequence(Some(projects)));
+            }
+            Ok(string_sequence(projects.get("globs")))
+        }
+    }
+}
+
+fn write_globs(root: &Path, source: GlobSource, globs: &[String]) -> Result<()> {
+    let sequence = serde_yaml::Value::Sequence(
+        globs
+            .iter()
+            .map(|g| serde_yaml::Value::String(g.clone()))
+            .collect(),
+    );
+
+    match source {
+        GlobSource::PackageJson => {
+            let path = root.join("package.json");
+            let mut package: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)
+                .with_context(|| format!("Invalid {}", path.display()))?;
+            let globs = serde_json::json!(globs);
+            match package.get_mut("workspaces") {
+                Some(serde_json::Value::Object(workspaces)) => {
+                    workspaces.insert("packages".to_string(), globs);
+                }
+                _ => package["workspaces"] = globs,
+            }
+            fs::write(&path, serde_json::to_string_pretty(&package)? + "\n")?;
+        }
+        GlobSource::PnpmWorkspace => {
+            let path = root.join(PNPM_WORKSPACE_FILE);
+            let mut config = read_yaml(&path)?;
+            set_key(&mut config, "packages", sequence);
+            fs::write(&path, serde_yaml::to_string(&config)?)?;
+        }
+        GlobSource::Moon => {
+            let path = root.join(MOON_WORKSPACE_FILE);
+            let mut config = read_yaml(&path)?;
+            let projects = config
+                .as_mapping_mut()
+                .context("Invalid .moon/workspace.yml")?
+                .entry(serde_yaml::Value::String("projects".to_string()))
+                .or_insert_with(|| serde_yaml::Value::Mapping(Default::default()));
+
+            if projects.is_sequence() {
+                *projects = sequence;
+            } else {
+                set_key(projects, "globs", sequence);
+            }
+            fs::write(&path, serde_yaml::to_string(&config)?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Explicit project paths from `projects.sources` in .moon/workspace.yml
+fn moon_sources(root: &Path) -> Result<Vec<String>> {
+    let config = read_yaml(&root.join(MOON_WORKSPACE_FILE))?;
+    Ok(config
+        .get("projects")
+        .and_then(|p| p.get("sources"))
+        .and_then(|s| s.as_mapping())
+        .map(|s| {
+            s.values()
+                .filter_map(|v| v.as_str())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+fn read_package_json_globs(root: &Path) -> Result<Option<Vec<String>>> {
+    let path = root.join("package.json");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let package: serde_json::Value =
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+
+    let workspaces = match &package["workspaces"] {
+        serde_json::Value::Object(workspaces) => workspaces.get("packages"),
+        workspaces => Some(workspaces),
+    };
+    Ok(workspaces.and_then(|w| w.as_array()).map(|globs| {
+        globs
+            .iter()
+            .filter_map(|g| g.as_str())
+            .map(String::from)
+            .collect()
+    }))
+}
+
+fn read_yaml(path: &Path) -> Result<serde_yaml::Value> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_yaml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+}
+
+fn set_key(value: &mut serde_yaml::Value, key: &str, new_value: serde_yaml::Value) {
+    if let Some(mapping) = value.as_mapping_mut() {
+        mapping.insert(serde_yaml::Value::String(key.to_string()), new_value);
+    }
+}
+
+fn string_sequence(value: Option<&serde_yaml::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_sequence())
+        .map(|s| {
+            s.iter()
+                .filter_map(|v| v.as_str())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Workspace-relative paths (forward slashes) of directories containing `manifest`
+fn project_directories(root: &Path, manifest: &str) -> Vec<String> {
+    let mut projects = Vec::new();
+    collect_projects(root, Path::new(""), manifest, 0, &mut projects);
+    projects.sort();
+    projects
+}
+
+fn collect_projects(
+    root: &Path,
+    relative: &Path,
+    manifest: &str,
+    depth: usize,
+    projects: &mut Vec<String>,
+) {
+    if depth >= MAX_PROJECT_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(root.join(relative)) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entry.path().is_dir()
+            || name.starts_with('.')
+            || SKIPPED_DIRECTORIES.contains(&name.as_str())
+        {
+            continue;
+        }
+
+        let path: PathBuf = relative.join(&name);
+        // Projects live below a grouping directory such as apps/ or workers/
+        if depth > 0 && entry.path().join(manifest).exists() {
+            projects.push(
+                path.components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            );
+        } else {
+            collect_projects(root, &path, manifest, depth + 1, projects);
+        }
+    }
+}
+
+/// Whether a workspace glob matches a project path; `*` stays within one path segment and
+/// `**` matches any number of segments. Negated globs cover the projects they exclude.
+fn covers(glob: &str, project: &str) -> bool {
+    let glob = glob.trim_start_matches('!').trim_start_matches("./");
+    let glob = glob.trim_end_matches('/');
+    matches_segments(
+        &glob.split('/').collect::<Vec<_>>(),
+        &project.split('/').collect::<Vec<_>>(),
+    )
+}
+
+/// Whether a glob matches a relative `/`-separated path, with the same `*` and `**` rules
+pub fn path_matches(glob: &str, path: &str) -> bool {
+    matches_segments(
+        &glob.trim_start_matches("./").split('/').collect::<Vec<_>>(),
+        &path.trim_start_matches("./").split('/').collect::<Vec<_>>(),
+    )
+}
+
+fn matches_segments(glob: &[&str], path: &[&str]) -> bool {
+    match (glob.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            matches_segments(&glob[1..], path)
+                || (!path.is_empty() && matches_segments(glob, &path[1..]))
+        }
+        (Some(segment), Some(part)) => {
+            crate::utils::projects::glob_match(segment, part)
+                && matches_segments(&glob[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+/// Literal directory prefix of a glob, e.g. `apps` for `apps/*`
+pub fn glob_base(glob: &str) -> String {
+    glob.trim_start_matches("./")
+        .split('/')
+        .take_while(|segment| !segment.contains(['*', '?', '{', '[']))
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,534 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Wrangler configuration file names, in the order Wrangler itself prefers them
+pub const WRANGLER_CONFIG_FILES: [&str; 3] = ["wrangler.jsonc", "wrangler.json", "wrangler.toml"];
+
+/// Wrangler configuration of a React app's Storybook Worker, named `<worker>-storybook`
+pub const STORYBOOK_WRANGLER_CONFIG: &str = "wrangler.storybook.jsonc";
+
+/// The `deploy` script generated projects use, so `moonflare deploy` can pass the environment,
+/// message, `--keep-vars` and the configuration merged with the workspace defaults through to
+/// Wrangler
+pub const DEPLOY_SCRIPT: &str = "wrangler deploy ${WRANGLER_CONFIG:+--config $WRANGLER_CONFIG} ${WRANGLER_ENV:+--env $WRANGLER_ENV} ${WRANGLER_MESSAGE:+--message \"$WRANGLER_MESSAGE\"} ${WRANGLER_KEEP_VARS:+--keep-vars}";
+
+/// The Worker a deploy to `env` targets: the environment's own `name`, or Wrangler's
+/// `<name>-<env>` default
+pub fn env_worker_name(config: &Value, project: &str, env: Option<&str>) -> String {
+    let name = config["name"].as_str().unwrap_or(project);
+    match env {
+        Some(env) => config["env"][env]["name"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}-{}", name, env)),
+        None => name.to_string(),
+    }
+}
+
+/// Path of the project's Wrangler configuration file, if it has one
+pub fn find_wrangler_config(project_path: &Path) -> Option<PathBuf> {
+    WRANGLER_CONFIG_FILES
+        .iter()
+        .map(|file| project_path.join(file))
+        .find(|path| path.exists())
+}
+
+/// Read the project's Wrangler configuration as JSON regardless of its on-disk format
+pub fn read_wrangler_config(project_path: &Path) -> Result<Option<Value>> {
+    let Some(path) = find_wrangler_config(project_path) else {
+        return Ok(None);
+    };
+    parse_wrangler_file(&path).map(Some)
+}
+
+/// Port `wrangler dev` listens on for the project: the config's `dev.port`, else Wrangler's
+/// default of 8787
+pub fn dev_port(project_path: &Path) -> Result<u16> {
+    let port = read_wrangler_config(project_path)?
+        .and_then(|config| config.pointer("/dev/port").and_then(Value::as_u64))
+        .and_then(|port| u16::try_from(port).ok());
+    Ok(port.unwrap_or(8787))
+}
+
+/// Parse a TOML, JSON or JSONC Wrangler configuration file as JSON
+pub fn parse_wrangler_file(path: &Path) -> Result<Value> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        let toml: toml::Value =
+            toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+        Ok(serde_json::to_value(toml)?)
+    } else {
+        serde_json::from_str(&strip_jsonc(&content))
+            .with_context(|| format!("Invalid {}", path.display()))
+    }
+}
+
+/// Top-level keys Wrangler environments don't inherit: an `[env.X]` section without them
+/// deploys with none of these vars or bindings
+pub const NON_INHERITABLE_KEYS: [&str; 16] = [
+    "vars",
+    "define",
+    "durable_objects",
+    "kv_namespaces",
+    "r2_buckets",
+    "d1_databases",
+    "services",
+    "queues",
+    "analytics_engine_datasets",
+    "vectorize",
+    "hyperdrive",
+    "ai",
+    "browser",
+    "mtls_certificates",
+    "send_email",
+    "tail_consumers",
+];
+
+/// A top-level var or binding an environment doesn't declare
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingBinding {
+    /// Configuration key, e.g. `kv_namespaces`
+    pub key: String,
+    /// Variable or binding name, or the key itself for single bindings like `ai`
+    pub name: String,
+    /// The top-level declaration, to be copied into the environment
+    pub value: Value,
+}
+
+/// Vars and bindings declared at the top level that `environment` doesn't repeat
+pub fn missing_env_bindings(config: &Value, environment: &str) -> Vec<MissingBinding> {
+    let scoped = config.pointer(&format!("/env/{}", environment));
+    let mut missing = Vec::new();
+
+    for key in NON_INHERITABLE_KEYS {
+        let Some(top_level) = config.get(key) else {
+            continue;
+        };
+        let declared = scoped.and_then(|env| env.get(key));
+
+        match top_level {
+            // vars and define map names to values
+            Value::Object(entries) if matches!(key, "vars" | "define") => {
+                for (name, value) in entries {
+                    if declared.and_then(|d| d.get(name)).is_none() {
+                        missing.push(MissingBinding {
+                            key: key.to_string(),
+                            name: name.clone(),
+                            value: value.clone(),
+                        });
+                    }
+                }
+            }
+            // Durable Objects nest their binding list
+            Value::Object(_) if key == "durable_objects" => {
+                let names = binding_list_names(declared.and_then(|d| d.get("bindings")));
+                if let Some(bindings) = top_level.get("bindings").and_then(|b| b.as_array()) {
+                    for binding in bindings {
+                        let name = list_entry_name(binding);
+                        if !names.contains(&name) {
+                            missing.push(MissingBinding {
+                                key: key.to_string(),
+                                name,
+                                value: binding.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+            Value::Array(bindings) => {
+                let names = binding_list_names(declared);
+                for binding in bindings {
+                    let name = list_entry_name(binding);
+                    if !names.contains(&name) {
+                        missing.push(MissingBinding {
+                            key: key.to_string(),
+                            name,
+                            value: binding.clone(),
+                        });
+                    }
+                }
+            }
+            // Single bindings such as `ai` and `browser`
+            _ if declared.is_none() => missing.push(MissingBinding {
+                key: key.to_string(),
+                name: top_level
+                    .get("binding")
+                    .and_then(|b| b.as_str())
+                    .unwrap_or(key)
+                    .to_string(),
+                value: top_level.clone(),
+            }),
+            _ => {}
+        }
+    }
+
+    missing
+}
+
+/// Copy `missing` into the environment's section of `config`
+pub fn add_env_bindings(config: &mut Value, environment: &str, missing: &[MissingBinding]) {
+    // Keys like `ai` hold one binding object rather than a list
+    let single: BTreeSet<String> = missing
+        .iter()
+        .filter(|b| !matches!(b.key.as_str(), "vars" | "define" | "durable_objects"))
+        .filter(|b| config.get(&b.key).is_some_and(|v| v.is_object()))
+        .map(|b| b.key.clone())
+        .collect();
+
+    let Some(root) = config.as_object_mut() else {
+        return;
+    };
+    let Some(env) = root
+        .entry("env")
+        .or_insert_with(|| Value::Object(Default::default()))
+        .as_object_mut()
+        .and_then(|envs| {
+            envs.entry(environment)
+                .or_insert_with(|| Value::Object(Default::default()))
+                .as_object_mut()
+        })
+    else {
+        return;
+    };
+
+    for binding in missing {
+        let key = binding.key.clone();
+        if single.contains(&key) {
+            env.insert(key, binding.value.clone());
+            continue;
+        }
+
+        let entry = match key.as_str() {
+            "vars" | "define" => env
+                .entry(key)
+                .or_insert_with(|| Value::Object(Default::default())),
+            "durable_objects" => env
+                .entry(key)
+                .or_insert_with(|| serde_json::json!({ "bindings": [] }))
+                .as_object_mut()
+                .map(|section| {
+                    section
+                        .entry("bindings")
+                        .or_insert_with(|| Value::Array(Vec::new()))
+                })
+                .expect("durable_objects is an object"),
+            _ => env.entry(key).or_insert_with(|| Value::Array(Vec::new())),
+        };
+        match entry {
+            Value::Object(entries) => {
+                entries.insert(binding.name.clone(), binding.value.clone());
+            }
+            Value::Array(list) => list.push(binding.value.clone()),
+            _ => {}
+        }
+    }
+}
+
+fn binding_list_names(list: Option<&Value>) -> BTreeSet<String> {
+    list.and_then(|l| l.as_array())
+        .map(|entries| entries.iter().map(list_entry_name).collect())
+        .unwrap_or_default()
+}
+
+/// Name of a binding list entry; tail consumers are identified by their service
+fn list_entry_name(entry: &Value) -> String {
+    ["binding", "name", "service"]
+        .iter()
+        .find_map(|field| entry.get(field).and_then(|v| v.as_str()))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Rewrite the top-level `name` in a Wrangler configuration file, keeping its comments and
+/// formatting. Returns false when the file declares no name.
+pub fn set_config_name(path: &Path, name: &str) -> Result<bool> {
+    let Some(updated) = config_with_name(path, name)? else {
+        return Ok(false);
+    };
+    fs::write(path, updated).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(true)
+}
+
+/// The text of a Wrangler configuration file with its top-level `name` replaced, or None
+/// when it declares no name
+pub fn config_with_name(path: &Path, name: &str) -> Result<Option<String>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let (pattern, replacement) = if path.extension().is_some_and(|ext| ext == "toml") {
+        (r#"(?m)^name\s*=\s*"[^"]*""#, format!("name = \"{}\"", name))
+    } else {
+        (r#""name"\s*:\s*"[^"]*""#, format!("\"name\": \"{}\"", name))
+    };
+
+    let name_pattern = regex::Regex::new(pattern).expect("valid regex");
+    if !name_pattern.is_match(&content) {
+        return Ok(None);
+    }
+    Ok(Some(
+        name_pattern
+            .replace(&content, regex::NoExpand(&replacement))
+            .into_owned(),
+    ))
+}
+
+/// Write `config` back in the format of `path`. Comments in JSONC files aren't preserved.
+pub fn write_wrangler_config(path: &Path, config: &Value) -> Result<()> {
+    let content = render_wrangler_config(path, config)?;
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// The text [`write_wrangler_config`] would write
+pub fn render_wrangler_config(path: &Path, config: &Value) -> Result<String> {
+    Ok(if path.extension().is_some_and(|ext| ext == "toml") {
+        let toml = toml::Value::try_from(config)
+            .with_context(|| format!("Can't represent {} as TOML", path.display()))?;
+        toml::to_string_pretty(&toml)?
+    } else {
+        format!("{}\n", serde_json::to_string_pretty(config)?)
+    })
+}
+
+/// Remove comments and trailing commas so JSONC can be parsed as plain JSON
+pub fn strip_jsonc(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    output.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    // Drop commas that directly precede a closing bracket
+    let trailing_comma = regex::Regex::new(r",(\s*[}\]])").expect("valid regex");
+    trailing_comma.replace_all(&output, "$1").to_string()
+}
+
+/// Plain-text `vars` declared at the top level of the configuration
+pub fn config_vars(config: &Value) -> BTreeMap<String, String> {
+    config
+        .get("vars")
+        .and_then(|vars| vars.as_object())
+        .map(|vars| {
+            vars.iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Plain-text `vars` of a Wrangler environment, or of the top level without one. Environments
+/// don't inherit top-level vars, so an environment without `vars` has none.
+pub fn env_vars(config: &Value, env: Option<&str>) -> BTreeMap<String, String> {
+    match env {
+        None => config_vars(config),
+        Some(env) => config
+            .get("env")
+            .and_then(|envs| envs.get(env))
+            .map(config_vars)
+            .unwrap_or_default(),
+    }
+}
+
+/// Names of every binding (KV, D1, R2, services, Durable Objects, ...) in the configuration
+pub fn binding_names(config: &Value) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    collect_binding_names(config, &mut names);
+
+    if let Some(bindings) = config
+        .pointer("/durable_objects/bindings")
+        .and_then(|b| b.as_array())
+    {
+        names.extend(
+            bindings
+                .iter()
+                .filter_map(|b| b.get("name").and_then(|n| n.as_str()).map(String::from)),
+        );
+    }
+
+    names
+}
+
+fn collect_binding_names(value: &Value, names: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                if key == "binding"
+                    && let Some(name) = child.as_str()
+                {
+                    names.insert(name.to_string());
+                }
+                collect_binding_names(child, names);
+            }
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_binding_names(item, names)),
+        _ => {}
+    }
+}
+
+/// Rename a binding or var throughout the configuration, in every environment: the `binding`
+/// of KV, D1, R2, service and other bindings, the `name` of Durable Object bindings and keys
+/// of `vars`. Returns whether anything was renamed.
+pub fn rename_binding(config: &mut Value, old: &str, new: &str) -> bool {
+    let Some(map) = config.as_object_mut() else {
+        return false;
+    };
+    let mut renamed = false;
+
+    if let Some(vars) = map.get_mut("vars").and_then(Value::as_object_mut)
+        && vars.contains_key(old)
+    {
+        // Rebuild the map so the var keeps its position
+        *vars = std::mem::take(vars)
+            .into_iter()
+            .map(|(key, value)| (if key == old { new.to_string() } else { key }, value))
+            .collect();
+        renamed = true;
+    }
+    if let Some(bindings) = map
+        .get_mut("durable_objects")
+        .and_then(|d| d.get_mut("bindings"))
+        .and_then(Value::as_array_mut)
+    {
+        for binding in bindings.iter_mut().filter(|b| b["name"] == old) {
+            binding["name"] = Value::String(new.to_string());
+            renamed = true;
+        }
+    }
+    for (key, value) in map.iter_mut() {
+        match key.as_str() {
+            "vars" | "durable_objects" => {}
+            "env" => {
+                if let Some(envs) = value.as_object_mut() {
+                    for env in envs.values_mut() {
+                        renamed |= rename_binding(env, old, new);
+                    }
+                }
+            }
+            _ => renamed |= rename_binding_entries(value, old, new),
+        }
+    }
+    renamed
+}
+
+fn rename_binding_entries(value: &mut Value, old: &str, new: &str) -> bool {
+    match value {
+        Value::Object(map) => {
+            let mut renamed = false;
+            for (key, child) in map.iter_mut() {
+                if key == "binding" && child == old {
+                    *child = Value::String(new.to_string());
+                    renamed = true;
+                } else {
+                    renamed |= rename_binding_entries(child, old, new);
+                }
+            }
+            renamed
+        }
+        Value::Array(items) => items.iter_mut().fold(false, |renamed, item| {
+            rename_binding_entries(item, old, new) | renamed
+        }),
+        _ => false,
+    }
+}
+
+/// The configuration file's text with the binding renamed, keeping comments and formatting;
+/// unchanged when the file doesn't use it. None when the edit doesn't produce exactly what
+/// `rename_binding` would, e.g. because the Worker shares the binding's name, so the file
+/// must be changed by hand.
+pub fn rename_binding_in_file(path: &Path, old: &str, new: &str) -> Result<Option<String>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut expected = parse_wrangler_file(path)?;
+    if !rename_binding(&mut expected, old, new) {
+        return Ok(Some(content));
+    }
+
+    let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+    let old = regex::escape(old);
+    let edits = if is_toml {
+        [
+            (
+                format!(r#"(\b(?:binding|name)\s*=\s*)"{}""#, old),
+                format!("${{1}}\"{}\"", new),
+            ),
+            // Keys of [vars] or of an inline vars table
+            (
+                format!(r#"(?m)((?:^|[{{,])\s*)"?{}"?(\s*=)"#, old),
+                format!("${{1}}{}${{2}}", new),
+            ),
+        ]
+    } else {
+        [
+            (
+                format!(r#"("(?:binding|name)"\s*:\s*)"{}""#, old),
+                format!("${{1}}\"{}\"", new),
+            ),
+            (format!(r#""{}"(\s*:)"#, old), format!("\"{}\"${{1}}", new)),
+        ]
+    };
+    let mut updated = content;
+    for (pattern, replacement) in edits {
+        updated = regex::Regex::new(&pattern)
+            .expect("valid regex")
+            .replace_all(&updated, replacement.as_str())
+            .into_owned();
+    }
+
+    let parsed = if is_toml {
+        toml::from_str::<toml::Value>(&updated)
+            .ok()
+            .and_then(|toml| serde_json::to_value(toml).ok())
+    } else {
+        serde_json::from_str::<Value>(&strip_jsonc(&updated)).ok()
+    };
+    Ok((parsed.as_ref() == Some(&expected)).then_some(updated))
+}
@@ -0,0 +1,211 @@
+use crate::utils::projects::WorkspaceProject;
+use crate::utils::wrangler::{parse_wrangler_file, read_wrangler_config};
+use crate::utils::{interrupt, task_env};
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Workspace-wide Wrangler settings every project's configuration is layered on, in the order
+/// they're looked for at the workspace root
+pub const DEFAULTS_FILES: [&str; 3] = [
+    "wrangler-defaults.jsonc",
+    "wrangler-defaults.json",
+    "wrangler-defaults.toml",
+];
+
+/// The merged configuration written into each project for the length of a build or deploy.
+/// It sits next to the project's own so Wrangler resolves `main` and asset paths the same way.
+pub const EFFECTIVE_CONFIG_FILE: &str = ".wrangler.effective.json";
+
+/// Path of the workspace's defaults file, if it has one
+pub fn find_defaults() -> Option<PathBuf> {
+    DEFAULTS_FILES
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.exists())
+}
+
+/// The workspace's Wrangler defaults, if it has any
+pub fn load_defaults() -> Result<Option<Value>> {
+    let Some(path) = find_defaults() else {
+        return Ok(None);
+    };
+    let defaults = parse_wrangler_file(&path)?;
+    if !defaults.is_object() {
+        bail!(
+            "{} must contain an object of Wrangler settings",
+            path.display()
+        );
+    }
+    Ok(Some(defaults))
+}
+
+/// The project's Wrangler configuration with the workspace defaults underneath it, or None
+/// when the project has no Wrangler configuration
+pub fn effective_config(project_path: &Path) -> Result<Option<Value>> {
+    let Some(config) = read_wrangler_config(project_path)? else {
+        return Ok(None);
+    };
+    Ok(Some(match load_defaults()? {
+        Some(defaults) => overlay(defaults, config),
+        None => config,
+    }))
+}
+
+/// `config` on top of `defaults`: tables are merged key by key, and anything else the project
+/// sets, arrays included, replaces the default
+pub fn overlay(defaults: Value, config: Value) -> Value {
+    match (defaults, config) {
+        (Value::Object(mut defaults), Value::Object(config)) => {
+            let mut merged = Map::new();
+            for (key, value) in config {
+                let value = match defaults.shift_remove(&key) {
+                    Some(default) => overlay(default, value),
+                    None => value,
+                };
+                merged.insert(key, value);
+            }
+            merged.extend(defaults);
+            Value::Object(merged)
+        }
+        (_, config) => config,
+    }
+}
+
+/// Keys of `effective` that come from the defaults rather than the project, as JSON pointers
+pub fn inherited_keys(config: &Value, effective: &Value) -> Vec<String> {
+    let mut keys = Vec::new();
+    collect_inherited(config, effective, String::new(), &mut keys);
+    keys
+}
+
+fn collect_inherited(config: &Value, effective: &Value, path: String, keys: &mut Vec<String>) {
+    let Some(effective) = effective.as_object() else {
+        return;
+    };
+    for (key, value) in effective {
+        let pointer = format!("{}/{}", path, key);
+        match config.get(key) {
+            None => keys.push(pointer),
+            Some(own) => collect_inherited(own, value, pointer, keys),
+        }
+    }
+}
+
+/// Merged configurations written for a build or deploy. They're removed, and Wrangler goes
+/// back to each project's own configuration, when this is dropped.
+#[derive(Debug, Default)]
+pub struct EffectiveConfigs {
+    paths: Vec<PathBuf>,
+    /// Injected vars that replaced a value the project's configuration set, per project
+    pub overridden: Vec<(String, Vec<String>)>,
+}
+
+impl Drop for EffectiveConfigs {
+    fn drop(&mut self) {
+        if self.paths.is_empty() {
+            return;
+        }
+        task_env::set_wrangler_config(None);
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+            interrupt::release_temp(path);
+        }
+    }
+}
+
+/// Worker vars a deploy sets on top of every project's configuration, from `deploy --vars-file`
+#[derive(Debug, Clone, Copy)]
+pub struct InjectedVars<'a> {
+    pub vars: &'a BTreeMap<String, String>,
+    /// Environment being deployed, whose `vars` table the values go into
+    pub environment: Option<&'a str>,
+}
+
+/// Write the merged configuration of each project with a Wrangler configuration and point
+/// `deploy` scripts at it through `WRANGLER_CONFIG`. Without a defaults file nothing is written
+/// and Wrangler reads the projects' own configurations.
+pub fn materialize(projects: &[WorkspaceProject]) -> Result<EffectiveConfigs> {
+    materialize_with_vars(projects, None)
+}
+
+/// [`materialize`], with `injected` vars set in each merged configuration. Injecting vars
+/// writes merged configurations even without a defaults file.
+pub fn materialize_with_vars(
+    projects: &[WorkspaceProject],
+    injected: Option<InjectedVars>,
+) -> Result<EffectiveConfigs> {
+    let mut configs = EffectiveConfigs::default();
+    let defaults = load_defaults()?;
+    let injected = injected.filter(|injected| !injected.vars.is_empty());
+    if defaults.is_none() && injected.is_none() {
+        return Ok(configs);
+    }
+
+    for project in projects {
+        let Some(config) = read_wrangler_config(&project.path)? else {
+            continue;
+        };
+        let path = project.path.join(EFFECTIVE_CONFIG_FILE);
+        let mut merged = match &defaults {
+            Some(defaults) => overlay(defaults.clone(), config),
+            None => config,
+        };
+        if let Some(injected) = injected {
+            let overridden = inject_vars(&mut merged, injected);
+            if !overridden.is_empty() {
+                configs.overridden.push((project.name.clone(), overridden));
+            }
+        }
+        fs::write(&path, serde_json::to_string_pretty(&merged)? + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        interrupt::register_temp(&path);
+        configs.paths.push(path);
+    }
+    if !configs.paths.is_empty() {
+        task_env::set_wrangler_config(Some(EFFECTIVE_CONFIG_FILE));
+    }
+    Ok(configs)
+}
+
+/// Set the injected vars in the configuration's `vars`, or in `env.<name>.vars` when deploying
+/// an environment, since Wrangler environments don't inherit vars. Returns the keys whose
+/// configured values were replaced.
+pub fn inject_vars(config: &mut Value, injected: InjectedVars) -> Vec<String> {
+    let mut table = &mut *config;
+    if let Some(environment) = injected.environment {
+        table = ensure_object(table, "env");
+        table = ensure_object(table, environment);
+    }
+    let vars = ensure_object(table, "vars")
+        .as_object_mut()
+        .expect("vars is an object");
+
+    let mut overridden = Vec::new();
+    for (key, value) in injected.vars {
+        if vars
+            .insert(key.clone(), Value::String(value.clone()))
+            .is_some_and(|old| old != Value::String(value.clone()))
+        {
+            overridden.push(key.clone());
+        }
+    }
+    overridden
+}
+
+/// The object under `key`, created (or replacing a non-object) when missing
+fn ensure_object<'a>(value: &'a mut Value, key: &str) -> &'a mut Value {
+    if !value.is_object() {
+        *value = Value::Object(Map::new());
+    }
+    let object = value.as_object_mut().expect("checked above");
+    let entry = object
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+    }
+    entry
+}
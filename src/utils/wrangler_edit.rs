@@ -0,0 +1,632 @@
+use crate::utils::wrangler::strip_jsonc;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// A change to one key of a Wrangler or moonflare configuration, by its path of table keys
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigEdit {
+    Set(Vec<String>, Value),
+    Remove(Vec<String>),
+}
+
+impl ConfigEdit {
+    pub fn set(path: &[impl AsRef<str>], value: impl Into<Value>) -> Self {
+        Self::Set(keys(path), value.into())
+    }
+
+    pub fn remove(path: &[impl AsRef<str>]) -> Self {
+        Self::Remove(keys(path))
+    }
+
+    /// `env.staging.vars.REGION`
+    pub fn key(&self) -> String {
+        match self {
+            Self::Set(path, _) | Self::Remove(path) => path.join("."),
+        }
+    }
+
+    fn apply(&self, config: &mut Value) {
+        match self {
+            Self::Set(path, value) => {
+                let mut table = config;
+                for key in &path[..path.len() - 1] {
+                    if !table.is_object() {
+                        *table = Value::Object(Map::new());
+                    }
+                    table = table
+                        .as_object_mut()
+                        .expect("just made an object")
+                        .entry(key.as_str())
+                        .or_insert_with(|| Value::Object(Map::new()));
+                }
+                if let Some(table) = table.as_object_mut() {
+                    table.insert(path[path.len() - 1].clone(), value.clone());
+                }
+            }
+            Self::Remove(path) => {
+                let parent = path[..path.len() - 1]
+                    .iter()
+                    .try_fold(config, |table, key| table.get_mut(key.as_str()));
+                if let Some(table) = parent.and_then(Value::as_object_mut) {
+                    table.remove(&path[path.len() - 1]);
+                }
+            }
+        }
+    }
+}
+
+fn keys(path: &[impl AsRef<str>]) -> Vec<String> {
+    path.iter().map(|key| key.as_ref().to_string()).collect()
+}
+
+/// A configuration's text after a set of edits
+#[derive(Debug)]
+pub struct EditedConfig {
+    pub content: String,
+    /// Whether comments and formatting survived. When the edits can't be made in place the
+    /// text is written out again from the edited configuration.
+    pub preserved: bool,
+}
+
+/// Apply `edits` to the configuration file at `path`, TOML or JSONC by its extension
+pub fn edit_config(path: &Path, edits: &[ConfigEdit]) -> Result<EditedConfig> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let toml = path.extension().is_some_and(|ext| ext == "toml");
+    edit_text(&content, toml, edits).with_context(|| format!("Failed to edit {}", path.display()))
+}
+
+/// Apply `edits` to a configuration's text, keeping comments and formatting when the edited
+/// text parses to exactly what the edits should produce
+pub fn edit_text(content: &str, toml: bool, edits: &[ConfigEdit]) -> Result<EditedConfig> {
+    let mut expected = parse(content, toml)?;
+    for edit in edits {
+        edit.apply(&mut expected);
+    }
+
+    let edited = if toml {
+        edit_toml(content, edits)
+    } else {
+        edit_jsonc(content, edits)
+    };
+    if let Some(edited) = edited
+        && parse(&edited, toml).ok().as_ref() == Some(&expected)
+    {
+        return Ok(EditedConfig {
+            content: edited,
+            preserved: true,
+        });
+    }
+
+    let content = if toml {
+        toml::to_string_pretty(&toml::Value::try_from(&expected)?)?
+    } else {
+        format!("{}\n", serde_json::to_string_pretty(&expected)?)
+    };
+    Ok(EditedConfig {
+        content,
+        preserved: false,
+    })
+}
+
+fn parse(content: &str, toml: bool) -> Result<Value> {
+    Ok(if toml {
+        serde_json::to_value(toml::from_str::<toml::Value>(content)?)?
+    } else {
+        serde_json::from_str(&strip_jsonc(content))?
+    })
+}
+
+/// A table header or the keys before the first one
+struct Section {
+    name: Vec<String>,
+    /// Line of the header, or 0 for the keys before the first table
+    start: usize,
+    /// First line of the section's entries
+    body: usize,
+    end: usize,
+}
+
+fn edit_toml(content: &str, edits: &[ConfigEdit]) -> Option<String> {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    for edit in edits {
+        match edit {
+            ConfigEdit::Set(path, value) => toml_set(&mut lines, path, &toml_value(value)?)?,
+            ConfigEdit::Remove(path) => toml_remove(&mut lines, path)?,
+        }
+    }
+    Some(lines.join("\n") + "\n")
+}
+
+fn toml_set(lines: &mut Vec<String>, path: &[String], value: &toml::Value) -> Option<()> {
+    let (table, key) = path.split_at(path.len() - 1);
+    let key = &key[0];
+    let sections = toml_sections(lines);
+
+    if let Some(section) = sections.iter().find(|section| section.name == table) {
+        let entry = format!("{} = {}", toml_key(key), toml_inline(value));
+        match (section.body..section.end).find(|&i| line_key(&lines[i]).as_ref() == Some(key)) {
+            Some(index) => lines[index] = format!("{}{}", indentation(&lines[index]), entry),
+            None if section.name.is_empty() => {
+                // After the last top-level key, or below the file's opening comments
+                let at = (section.body..section.end)
+                    .rev()
+                    .find(|&i| line_key(&lines[i]).is_some())
+                    .map(|i| i + 1)
+                    .unwrap_or_else(|| {
+                        lines[..section.end]
+                            .iter()
+                            .take_while(|line| line.trim_start().starts_with('#'))
+                            .count()
+                    });
+                lines.insert(at, entry);
+            }
+            None => {
+                let last = (section.start..section.end)
+                    .rev()
+                    .find(|&i| !lines[i].trim().is_empty())
+                    .unwrap_or(section.start);
+                let indent = indentation(&lines[last]).to_string();
+                lines.insert(last + 1, format!("{}{}", indent, entry));
+            }
+        }
+        return Some(());
+    }
+
+    if edit_inline_table(lines, &sections, table, |inline| {
+        inline.insert(key.clone(), value.clone());
+        Some(())
+    })
+    .is_some()
+    {
+        return Some(());
+    }
+
+    // A top-level table goes inline among the top-level keys, as Wrangler's docs write
+    // `placement = { mode = "smart" }`
+    if table.len() == 1 {
+        let inline = toml::Value::Table(toml::Table::from_iter([(key.clone(), value.clone())]));
+        return toml_set(lines, table, &inline);
+    }
+
+    // A new table after its parent's tables, or at the end of the file
+    let parent = &table[..table.len() - 1];
+    let at = sections
+        .iter()
+        .filter(|section| section.start > 0 && section.name.starts_with(parent))
+        .map(|section| section.end)
+        .max()
+        .unwrap_or(lines.len());
+    let mut end = at;
+    while end > 0 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    let mut added = vec![
+        String::new(),
+        format!(
+            "[{}]",
+            table
+                .iter()
+                .map(|key| toml_key(key))
+                .collect::<Vec<_>>()
+                .join(".")
+        ),
+        format!("{} = {}", toml_key(key), toml_inline(value)),
+    ];
+    if end == 0 {
+        added.remove(0);
+    }
+    lines.splice(end..end, added);
+    Some(())
+}
+
+fn toml_remove(lines: &mut Vec<String>, path: &[String]) -> Option<()> {
+    let (table, key) = path.split_at(path.len() - 1);
+    let key = &key[0];
+    let sections = toml_sections(lines);
+    if let Some(section) = sections.iter().find(|section| section.name == table) {
+        if let Some(index) =
+            (section.body..section.end).find(|&i| line_key(&lines[i]).as_ref() == Some(key))
+        {
+            lines.remove(index);
+        }
+        return Some(());
+    }
+    edit_inline_table(lines, &sections, table, |inline| {
+        inline.remove(key);
+        Some(())
+    })
+    .or(Some(()))
+}
+
+/// Change `table` where it or one of its parents is written inline, as `name = { ... }`
+fn edit_inline_table(
+    lines: &mut [String],
+    sections: &[Section],
+    table: &[String],
+    change: impl FnOnce(&mut toml::Table) -> Option<()>,
+) -> Option<()> {
+    let (index, depth) = (1..=table.len()).rev().find_map(|depth| {
+        let section = sections
+            .iter()
+            .find(|section| section.name == table[..depth - 1])?;
+        let index = (section.body..section.end).find(|&i| {
+            line_key(&lines[i]).as_ref() == Some(&table[depth - 1])
+                && lines[i]
+                    .split_once('=')
+                    .is_some_and(|(_, value)| value.trim_start().starts_with('{'))
+        })?;
+        Some((index, depth))
+    })?;
+
+    let (_, value) = lines[index].split_once('=')?;
+    let mut inline = toml::from_str::<toml::Table>(&format!("v = {}", value))
+        .ok()?
+        .remove("v")?;
+    let mut target = inline.as_table_mut()?;
+    for key in &table[depth..] {
+        target = target
+            .entry(key.as_str())
+            .or_insert_with(|| toml::Value::Table(Default::default()))
+            .as_table_mut()?;
+    }
+    change(target)?;
+    lines[index] = format!(
+        "{}{} = {}",
+        indentation(&lines[index]),
+        toml_key(&table[depth - 1]),
+        toml_inline(&inline)
+    );
+    Some(())
+}
+
+/// A value as written on one line, with tables inline
+fn toml_inline(value: &toml::Value) -> String {
+    match value {
+        toml::Value::Table(table) if table.is_empty() => "{}".to_string(),
+        toml::Value::Table(table) => format!(
+            "{{ {} }}",
+            table
+                .iter()
+                .map(|(key, value)| format!("{} = {}", toml_key(key), toml_inline(value)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        value => value.to_string(),
+    }
+}
+
+fn toml_sections(lines: &[String]) -> Vec<Section> {
+    let header = Regex::new(r"^\s*\[([^\[\]]+)\]\s*(#.*)?$").expect("valid regex");
+    let mut sections = vec![Section {
+        name: Vec::new(),
+        start: 0,
+        body: 0,
+        end: lines.len(),
+    }];
+    for (index, line) in lines.iter().enumerate() {
+        let name = if line.trim_start().starts_with("[[") {
+            // Entries of arrays of tables are never edited
+            vec!["[[".to_string()]
+        } else if let Some(caps) = header.captures(line) {
+            caps[1]
+                .split('.')
+                .map(|key| key.trim().trim_matches('"').trim_matches('\'').to_string())
+                .collect()
+        } else {
+            continue;
+        };
+        if let Some(previous) = sections.last_mut() {
+            previous.end = index;
+        }
+        sections.push(Section {
+            name,
+            start: index,
+            body: index + 1,
+            end: lines.len(),
+        });
+    }
+    sections
+}
+
+/// The key an entry line sets
+fn line_key(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('#') || trimmed.starts_with('[') {
+        return None;
+    }
+    let (key, _) = trimmed.split_once('=')?;
+    Some(key.trim().trim_matches('"').trim_matches('\'').to_string())
+}
+
+fn toml_key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        key.to_string()
+    } else {
+        serde_json::to_string(key).expect("strings serialize")
+    }
+}
+
+fn toml_value(value: &Value) -> Option<toml::Value> {
+    toml::Value::try_from(value).ok()
+}
+
+fn indentation(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// A key of a JSONC object, with the spans of its key and value
+struct Member {
+    key: String,
+    key_start: usize,
+    value_start: usize,
+    value_end: usize,
+}
+
+fn edit_jsonc(content: &str, edits: &[ConfigEdit]) -> Option<String> {
+    let mut content = content.to_string();
+    for edit in edits {
+        match edit {
+            ConfigEdit::Set(path, value) => jsonc_set(&mut content, path, value)?,
+            ConfigEdit::Remove(path) => jsonc_remove(&mut content, path)?,
+        }
+    }
+    Some(content)
+}
+
+fn jsonc_set(content: &mut String, path: &[String], value: &Value) -> Option<()> {
+    let mut open = skip_trivia(content.as_bytes(), 0);
+    for (depth, key) in path.iter().enumerate() {
+        let (members, close) = object_members(content, open)?;
+        match members.iter().find(|member| member.key == *key) {
+            Some(member) if depth == path.len() - 1 => {
+                content.replace_range(member.value_start..member.value_end, &inline_json(value));
+                return Some(());
+            }
+            Some(member) if content.as_bytes()[member.value_start] == b'{' => {
+                open = member.value_start;
+            }
+            Some(_) => return None,
+            None => {
+                let nested = path[depth + 1..]
+                    .iter()
+                    .rev()
+                    .fold(value.clone(), |value, key| {
+                        Value::Object(Map::from_iter([(key.clone(), value)]))
+                    });
+                insert_member(content, open, &members, close, key, &nested);
+                return Some(());
+            }
+        }
+    }
+    None
+}
+
+fn jsonc_remove(content: &mut String, path: &[String]) -> Option<()> {
+    let mut open = skip_trivia(content.as_bytes(), 0);
+    for key in &path[..path.len() - 1] {
+        let (members, _) = object_members(content, open)?;
+        match members.iter().find(|member| member.key == *key) {
+            Some(member) if content.as_bytes()[member.value_start] == b'{' => {
+                open = member.value_start;
+            }
+            _ => return Some(()),
+        }
+    }
+    let (members, close) = object_members(content, open)?;
+    let Some(index) = members
+        .iter()
+        .position(|member| member.key == path[path.len() - 1])
+    else {
+        return Some(());
+    };
+
+    let bytes = content.as_bytes();
+    let member = &members[index];
+    let after = skip_trivia(bytes, member.value_end);
+    let following = bytes.get(after) == Some(&b',');
+    let (mut start, mut end) = (
+        member.key_start,
+        if following {
+            after + 1
+        } else {
+            member.value_end
+        },
+    );
+    if members.len() == 1 && content[open + 1..close].trim() == content[start..end].trim() {
+        content.replace_range(open + 1..close, "");
+        return Some(());
+    }
+    // The comma before the member goes when it was the last one, keeping comments after it
+    let comma = (!following && index > 0)
+        .then(|| skip_trivia(bytes, members[index - 1].value_end))
+        .filter(|&at| bytes.get(at) == Some(&b','));
+    // Take the whole line when the member had it to itself
+    let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = content[end..].find('\n').map_or(content.len(), |i| end + i);
+    if content[line_start..start].trim().is_empty() && content[end..line_end].trim().is_empty() {
+        start = line_start;
+        end = (line_end + 1).min(content.len());
+    }
+    content.replace_range(start..end, "");
+    if let Some(at) = comma {
+        content.remove(at);
+    }
+    Some(())
+}
+
+/// Add `"key": value` after the object's last member, on its own line when the members are
+/// one per line
+fn insert_member(
+    content: &mut String,
+    open: usize,
+    members: &[Member],
+    close: usize,
+    key: &str,
+    value: &Value,
+) {
+    let member = format!(
+        "{}: {}",
+        serde_json::to_string(key).expect("strings serialize"),
+        inline_json(value)
+    );
+    let Some(last) = members.last() else {
+        if content[open + 1..close].trim().is_empty() {
+            content.replace_range(open..=close, &format!("{{ {} }}", member));
+        } else {
+            content.insert_str(close, &format!(" {} ", member));
+        }
+        return;
+    };
+
+    let bytes = content.as_bytes();
+    let after = skip_trivia(bytes, last.value_end);
+    let trailing = bytes.get(after) == Some(&b',');
+    let line_start = content[..last.key_start].rfind('\n').map_or(0, |i| i + 1);
+    let indent = &content[line_start..last.key_start];
+    if indent.trim().is_empty() && line_start > 0 {
+        let indent = indent.to_string();
+        let from = if trailing { after } else { last.value_end };
+        let eol = content[from..]
+            .find('\n')
+            .map_or(content.len(), |i| from + i);
+        if trailing {
+            content.insert_str(eol, &format!("\n{}{},", indent, member));
+        } else {
+            content.insert_str(eol, &format!("\n{}{}", indent, member));
+            content.insert(last.value_end, ',');
+        }
+    } else if trailing {
+        content.insert_str(after + 1, &format!(" {},", member));
+    } else {
+        content.insert_str(last.value_end, &format!(", {}", member));
+    }
+}
+
+/// `{ "mode": "smart" }`: objects on one line, with spaces like hand-written JSONC
+fn inline_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) if !map.is_empty() => format!(
+            "{{ {} }}",
+            map.iter()
+                .map(|(key, value)| format!(
+                    "{}: {}",
+                    serde_json::to_string(key).expect("strings serialize"),
+                    inline_json(value)
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        value => serde_json::to_string(value).expect("values serialize"),
+    }
+}
+
+/// The members of the object whose `{` is at `open`, and the offset of its `}`
+fn object_members(content: &str, open: usize) -> Option<(Vec<Member>, usize)> {
+    let bytes = content.as_bytes();
+    if bytes.get(open) != Some(&b'{') {
+        return None;
+    }
+    let mut members = Vec::new();
+    let mut pos = open + 1;
+    loop {
+        pos = skip_trivia(bytes, pos);
+        match bytes.get(pos)? {
+            b'}' => return Some((members, pos)),
+            b'"' => {}
+            _ => return None,
+        }
+        let key_start = pos;
+        let key_end = value_end(bytes, pos)?;
+        let key = serde_json::from_str(&content[key_start..key_end]).ok()?;
+        pos = skip_trivia(bytes, key_end);
+        if bytes.get(pos) != Some(&b':') {
+            return None;
+        }
+        let value_start = skip_trivia(bytes, pos + 1);
+        let end = value_end(bytes, value_start)?;
+        members.push(Member {
+            key,
+            key_start,
+            value_start,
+            value_end: end,
+        });
+        pos = skip_trivia(bytes, end);
+        if bytes.get(pos) == Some(&b',') {
+            pos += 1;
+        }
+    }
+}
+
+/// Offset just past the value starting at `start`
+fn value_end(bytes: &[u8], start: usize) -> Option<usize> {
+    match bytes.get(start)? {
+        b'"' => {
+            let mut pos = start + 1;
+            while *bytes.get(pos)? != b'"' {
+                pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+            }
+            Some(pos + 1)
+        }
+        b'{' | b'[' => {
+            let mut depth = 0;
+            let mut pos = start;
+            loop {
+                pos = skip_trivia(bytes, pos);
+                match bytes.get(pos)? {
+                    b'"' => {
+                        pos = value_end(bytes, pos)?;
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(pos + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                pos += 1;
+            }
+        }
+        _ => {
+            let mut pos = start;
+            while bytes.get(pos).is_some_and(|b| !b",}] \t\r\n/".contains(b)) {
+                pos += 1;
+            }
+            Some(pos)
+        }
+    }
+}
+
+/// Offset of the next character that isn't whitespace or part of a comment
+fn skip_trivia(bytes: &[u8], mut pos: usize) -> usize {
+    loop {
+        match (bytes.get(pos), bytes.get(pos + 1)) {
+            (Some(b), _) if b.is_ascii_whitespace() => pos += 1,
+            (Some(b'/'), Some(b'/')) => {
+                while bytes.get(pos).is_some_and(|b| *b != b'\n') {
+                    pos += 1;
+                }
+            }
+            (Some(b'/'), Some(b'*')) => {
+                pos += 2;
+                while pos < bytes.len()
+                    && !(bytes[pos] == b'*' && bytes.get(pos + 1) == Some(&b'/'))
+                {
+                    pos += 1;
+                }
+                pos += 2;
+            }
+            _ => return pos,
+        }
+    }
+}
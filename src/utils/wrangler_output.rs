@@ -0,0 +1,109 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Wrangler appends one JSON object per line to this file describing what each command did,
+/// the interface Cloudflare's own CI integrations read instead of its terminal output
+pub const OUTPUT_FILE_VAR: &str = "WRANGLER_OUTPUT_FILE_PATH";
+
+/// The entries of Wrangler's output file moonflare reads; other kinds are skipped
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum OutputEntry {
+    /// `wrangler deploy`
+    Deploy {
+        version_id: Option<String>,
+        /// Routes, custom domains and the workers.dev URL the Worker is served on
+        #[serde(default)]
+        targets: Vec<String>,
+    },
+    /// `wrangler versions upload`
+    VersionUpload {
+        version_id: Option<String>,
+        preview_url: Option<String>,
+    },
+    /// `wrangler pages deploy`
+    PagesDeploy {
+        deployment_id: Option<String>,
+        url: Option<String>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// What a deploy shipped, as Wrangler reported it
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DeployOutput {
+    /// Worker version ID, or the Pages deployment ID
+    pub version_id: Option<String>,
+    pub urls: Vec<String>,
+}
+
+impl DeployOutput {
+    /// The last deploy in `entries`, which is the one that stuck when the deploy was retried
+    pub fn from_entries(entries: &[OutputEntry]) -> Option<Self> {
+        entries.iter().rev().find_map(|entry| match entry {
+            OutputEntry::Deploy {
+                version_id,
+                targets,
+                ..
+            } => Some(Self {
+                version_id: version_id.clone(),
+                urls: targets.clone(),
+            }),
+            OutputEntry::VersionUpload {
+                version_id,
+                preview_url,
+                ..
+            } => Some(Self {
+                version_id: version_id.clone(),
+                urls: preview_url.iter().cloned().collect(),
+            }),
+            OutputEntry::PagesDeploy {
+                deployment_id, url, ..
+            } => Some(Self {
+                version_id: deployment_id.clone(),
+                urls: url.iter().cloned().collect(),
+            }),
+            OutputEntry::Other => None,
+        })
+    }
+}
+
+/// Entries of an output file, skipping lines that aren't JSON objects moonflare knows, so a
+/// newer Wrangler adding fields or kinds doesn't break anything
+pub fn parse(content: &str) -> Vec<OutputEntry> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// A temporary output file for one deploy, removed when dropped
+pub struct OutputFile {
+    _dir: TempDir,
+    path: PathBuf,
+}
+
+impl OutputFile {
+    pub fn new() -> Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("wrangler-output.json");
+        Ok(Self { _dir: dir, path })
+    }
+
+    /// Have Wrangler, and the Moon task and scripts running it, write to this file
+    pub fn apply(&self, command: &mut Command) {
+        command.env(OUTPUT_FILE_VAR, &self.path);
+    }
+
+    /// The deploy Wrangler reported, or None when it wrote nothing (an older Wrangler, or a
+    /// deploy script that doesn't run it)
+    pub fn deploy(&self) -> Option<DeployOutput> {
+        let content = std::fs::read_to_string(&self.path).ok()?;
+        DeployOutput::from_entries(&parse(&content))
+    }
+}
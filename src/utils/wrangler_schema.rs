@@ -0,0 +1,611 @@
+use crate::errors::MoonflareError;
+use crate::utils::projects::{
+    ProjectFilter, WorkspaceProject, discover_projects, find_project, similar_names,
+};
+use crate::utils::wrangler::find_wrangler_config;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// The JSON schema Wrangler ships for its configuration, relative to a `node_modules`
+const SCHEMA_FILE: &str = "wrangler/config-schema.json";
+
+/// A problem found in a Wrangler configuration, with the byte range it's about
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+/// Check the JSON and JSONC Wrangler configs of `project`, or of every Worker `filter` selects,
+/// before a command hands them to Wrangler. Syntax errors are always reported; schema
+/// violations once Wrangler is installed, since its bundled schema is what they're checked
+/// against. TOML configs are left to Wrangler.
+pub fn check_wrangler_configs(
+    project: Option<&str>,
+    filter: &ProjectFilter,
+) -> Result<(), Box<MoonflareError>> {
+    let projects: Vec<WorkspaceProject> = match project {
+        Some(name) => find_project(name).into_iter().collect(),
+        None => filter.apply(discover_projects()),
+    };
+    let mut invalid = Vec::new();
+    for project in projects.iter().filter(|p| p.is_worker()) {
+        let Some(path) = find_wrangler_config(&project.path) else {
+            continue;
+        };
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            continue;
+        }
+        let source = fs::read_to_string(&path)
+            .map_err(|e| MoonflareError::file_system_error("read", path.clone(), e))?;
+        let violations = validate_source(&source, &path, &project.path);
+        if !violations.is_empty() {
+            invalid.push((path.display().to_string(), source, violations));
+        }
+    }
+
+    // One diagnostic, with any other invalid configs as related ones
+    let mut invalid = invalid.into_iter();
+    let Some((file, source, violations)) = invalid.next() else {
+        return Ok(());
+    };
+    let others = invalid
+        .map(|(file, source, violations)| {
+            MoonflareError::invalid_wrangler_config(&file, source, violations, Vec::new())
+        })
+        .collect();
+    Err(Box::new(MoonflareError::invalid_wrangler_config(
+        &file, source, violations, others,
+    )))
+}
+
+/// Violations in a JSON or JSONC config: its syntax error, or where it departs from the schema
+pub fn validate_source(source: &str, path: &Path, project_path: &Path) -> Vec<Violation> {
+    let parsed = match Parser::new(source).parse() {
+        Ok(parsed) => parsed,
+        Err(violation) => return vec![violation],
+    };
+    let Some(schema) = load_schema(&parsed.value, path, project_path) else {
+        return Vec::new();
+    };
+
+    validate(&schema, &parsed.value)
+        .into_iter()
+        .map(|problem| {
+            let spans = parsed.spans.get(&problem.pointer);
+            let span = match (spans, problem.at_key) {
+                (Some(spans), true) => spans.key.clone(),
+                (Some(spans), false) => spans.value.clone(),
+                (None, _) => 0..0,
+            };
+            Violation {
+                span,
+                message: problem.message,
+            }
+        })
+        .collect()
+}
+
+/// The config's own `$schema` when it's a local file, else Wrangler's from the project's or
+/// the workspace's `node_modules`
+fn load_schema(config: &Value, path: &Path, project_path: &Path) -> Option<Value> {
+    let own = config["$schema"]
+        .as_str()
+        .filter(|schema| !schema.contains("://"))
+        .map(|schema| path.parent().unwrap_or(Path::new(".")).join(schema));
+    let candidates: Vec<PathBuf> = own
+        .into_iter()
+        .chain([
+            project_path.join("node_modules").join(SCHEMA_FILE),
+            Path::new("node_modules").join(SCHEMA_FILE),
+        ])
+        .collect();
+    candidates
+        .iter()
+        .find(|candidate| candidate.is_file())
+        .and_then(|schema| fs::read_to_string(schema).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Where `value` departs from `schema`; moonflare.toml is checked against its own schema with
+/// the same rules
+pub fn validate(schema: &Value, value: &Value) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    Validator { root: schema }.check(schema, value, "", &mut problems);
+    problems
+}
+
+/// A schema violation at a JSON pointer, before it's placed in the source
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub pointer: String,
+    /// Whether the problem is the key itself, as for unknown keys, rather than its value
+    pub at_key: bool,
+    pub message: String,
+}
+
+/// The subset of JSON Schema Wrangler's schema uses: `$ref`, `type`, `enum`, `const`,
+/// `properties`, `required`, `additionalProperties`, `items` and `anyOf`/`oneOf`/`allOf`
+struct Validator<'a> {
+    root: &'a Value,
+}
+
+impl Validator<'_> {
+    fn check(&self, schema: &Value, value: &Value, pointer: &str, problems: &mut Vec<Problem>) {
+        let schema = self.resolve(schema);
+        if schema == &Value::Bool(false) {
+            problems.push(problem(pointer, "isn't allowed here".to_string()));
+            return;
+        }
+        let Some(schema) = schema.as_object() else {
+            return;
+        };
+
+        if let Some(types) = schema.get("type") {
+            let types: Vec<&str> = match types {
+                Value::String(single) => vec![single.as_str()],
+                Value::Array(many) => many.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+                problems.push(problem(
+                    pointer,
+                    format!(
+                        "expected {}, found {}",
+                        types.join(" or "),
+                        type_name(value)
+                    ),
+                ));
+                return;
+            }
+        }
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+            && !allowed.contains(value)
+        {
+            let hint = value
+                .as_str()
+                .and_then(|value| {
+                    similar_names(value, allowed.iter().filter_map(Value::as_str))
+                        .first()
+                        .map(|close| format!("; did you mean \"{}\"?", close))
+                })
+                .unwrap_or_default();
+            let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            problems.push(problem(
+                pointer,
+                format!("must be one of {}{}", allowed.join(", "), hint),
+            ));
+        }
+        if let Some(expected) = schema.get("const")
+            && expected != value
+        {
+            problems.push(problem(pointer, format!("must be {}", expected)));
+        }
+
+        for branch in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            self.check(branch, value, pointer, problems);
+        }
+        let any = schema.get("anyOf").or_else(|| schema.get("oneOf"));
+        if let Some(branches) = any.and_then(Value::as_array) {
+            self.check_any(branches, value, pointer, problems);
+        }
+
+        match value {
+            Value::Object(map) => self.check_object(schema, map, pointer, problems),
+            Value::Array(items) => self.check_items(schema, items, pointer, problems),
+            _ => {}
+        }
+    }
+
+    fn check_object(
+        &self,
+        schema: &Map<String, Value>,
+        map: &Map<String, Value>,
+        pointer: &str,
+        problems: &mut Vec<Problem>,
+    ) {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for key in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !map.contains_key(key) {
+                problems.push(problem(
+                    pointer,
+                    format!("is missing required key \"{}\"", key),
+                ));
+            }
+        }
+        for (key, value) in map {
+            let child = format!("{}/{}", pointer, escape(key));
+            if let Some(property) = properties.and_then(|properties| properties.get(key)) {
+                self.check(property, value, &child, problems);
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    let known = properties
+                        .into_iter()
+                        .flat_map(|p| p.keys().map(String::as_str));
+                    let hint = similar_names(key, known)
+                        .first()
+                        .map_or(String::new(), |close| {
+                            format!("; did you mean \"{}\"?", close)
+                        });
+                    problems.push(Problem {
+                        pointer: child,
+                        at_key: true,
+                        message: format!("unknown key \"{}\"{}", key, hint),
+                    });
+                }
+                Some(additional @ Value::Object(_)) => {
+                    self.check(additional, value, &child, problems)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn check_items(
+        &self,
+        schema: &Map<String, Value>,
+        items: &[Value],
+        pointer: &str,
+        problems: &mut Vec<Problem>,
+    ) {
+        match schema.get("items") {
+            Some(Value::Array(tuple)) => {
+                for (index, (item, item_schema)) in items.iter().zip(tuple).enumerate() {
+                    self.check(
+                        item_schema,
+                        item,
+                        &format!("{}/{}", pointer, index),
+                        problems,
+                    );
+                }
+            }
+            Some(item_schema) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.check(
+                        item_schema,
+                        item,
+                        &format!("{}/{}", pointer, index),
+                        problems,
+                    );
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Nothing when a branch matches. Otherwise the problems of the closest branch, the one
+    /// whose type matched with the fewest problems, or one problem listing what was expected.
+    fn check_any(
+        &self,
+        branches: &[Value],
+        value: &Value,
+        pointer: &str,
+        problems: &mut Vec<Problem>,
+    ) {
+        let mut closest: Option<Vec<Problem>> = None;
+        for branch in branches {
+            let mut branch_problems = Vec::new();
+            self.check(branch, value, pointer, &mut branch_problems);
+            if branch_problems.is_empty() {
+                return;
+            }
+            let type_matched = !branch_problems
+                .iter()
+                .any(|p| p.pointer == pointer && p.message.starts_with("expected "));
+            if type_matched
+                && closest
+                    .as_ref()
+                    .is_none_or(|c| branch_problems.len() < c.len())
+            {
+                closest = Some(branch_problems);
+            }
+        }
+        match closest {
+            Some(closest) => problems.extend(closest),
+            None => {
+                let mut expected: Vec<String> = branches
+                    .iter()
+                    .map(|branch| self.describe(branch))
+                    .collect();
+                expected.dedup();
+                problems.push(problem(
+                    pointer,
+                    format!(
+                        "expected {}, found {}",
+                        expected.join(" or "),
+                        type_name(value)
+                    ),
+                ));
+            }
+        }
+    }
+
+    /// What a branch accepts, for messages: its constant, or its type
+    fn describe(&self, schema: &Value) -> String {
+        let schema = self.resolve(schema);
+        if let Some(expected) = schema.get("const") {
+            return expected.to_string();
+        }
+        match schema.get("type") {
+            Some(Value::String(single)) => single.clone(),
+            Some(Value::Array(many)) => many
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" or "),
+            _ => "another shape".to_string(),
+        }
+    }
+
+    /// Follow `$ref`s into the schema's `definitions`
+    fn resolve<'s>(&'s self, mut schema: &'s Value) -> &'s Value {
+        while let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+            {
+                Some(target) => schema = target,
+                None => break,
+            }
+        }
+        schema
+    }
+}
+
+fn problem(pointer: &str, message: String) -> Problem {
+    Problem {
+        pointer: pointer.to_string(),
+        at_key: false,
+        message,
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// A key escaped for a JSON pointer
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Where a value and, for object members, its key sit in the source
+struct Spans {
+    key: Range<usize>,
+    value: Range<usize>,
+}
+
+struct Parsed {
+    value: Value,
+    /// Spans by JSON pointer, `""` for the whole document
+    spans: HashMap<String, Spans>,
+}
+
+/// JSONC parser recording where each value is, since `strip_jsonc` and serde_json lose that.
+/// Comments and trailing commas are accepted, as Wrangler accepts them.
+struct Parser<'a> {
+    source: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+    spans: HashMap<String, Spans>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            bytes: source.as_bytes(),
+            position: 0,
+            spans: HashMap::new(),
+        }
+    }
+
+    fn parse(mut self) -> Result<Parsed, Violation> {
+        self.skip_trivia()?;
+        let value = self.value("", None)?;
+        self.skip_trivia()?;
+        if self.position < self.bytes.len() {
+            return Err(self.error("unexpected content after the configuration"));
+        }
+        Ok(Parsed {
+            value,
+            spans: self.spans,
+        })
+    }
+
+    /// A syntax error marking the character at the current position
+    fn error(&self, message: &str) -> Violation {
+        let start = self.position.min(self.bytes.len());
+        let width = self.source[start..]
+            .chars()
+            .next()
+            .map_or(0, char::len_utf8);
+        Violation {
+            span: start..start + width,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    /// Skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<(), Violation> {
+        loop {
+            match (self.peek(), self.bytes.get(self.position + 1)) {
+                (Some(b' ' | b'\t' | b'\n' | b'\r'), _) => self.position += 1,
+                (Some(b'/'), Some(b'/')) => {
+                    while self.peek().is_some_and(|b| b != b'\n') {
+                        self.position += 1;
+                    }
+                }
+                (Some(b'/'), Some(b'*')) => {
+                    let start = self.position;
+                    match self.source[start + 2..].find("*/") {
+                        Some(end) => self.position = start + 2 + end + 2,
+                        None => return Err(self.error("unterminated comment")),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn value(&mut self, pointer: &str, key: Option<Range<usize>>) -> Result<Value, Violation> {
+        let start = self.position;
+        let value = match self.peek() {
+            Some(b'{') => self.object(pointer)?,
+            Some(b'[') => self.array(pointer)?,
+            Some(b'"') => Value::String(self.string()?),
+            Some(b'-' | b'0'..=b'9') => self.number()?,
+            Some(_) if self.source[start..].starts_with("true") => {
+                self.literal(4, Value::Bool(true))
+            }
+            Some(_) if self.source[start..].starts_with("false") => {
+                self.literal(5, Value::Bool(false))
+            }
+            Some(_) if self.source[start..].starts_with("null") => self.literal(4, Value::Null),
+            _ => return Err(self.error("expected a value")),
+        };
+        let value_span = start..self.position;
+        self.spans.insert(
+            pointer.to_string(),
+            Spans {
+                key: key.unwrap_or_else(|| value_span.clone()),
+                value: value_span,
+            },
+        );
+        Ok(value)
+    }
+
+    fn literal(&mut self, length: usize, value: Value) -> Value {
+        self.position += length;
+        value
+    }
+
+    fn object(&mut self, pointer: &str) -> Result<Value, Violation> {
+        self.position += 1;
+        let mut map = Map::new();
+        loop {
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Value::Object(map));
+                }
+                Some(b'"') => {}
+                _ => return Err(self.error("expected a quoted key or '}'")),
+            }
+            let key_start = self.position;
+            let key = self.string()?;
+            let key_span = key_start..self.position;
+            self.skip_trivia()?;
+            if self.peek() != Some(b':') {
+                return Err(self.error("expected ':' after the key"));
+            }
+            self.position += 1;
+            self.skip_trivia()?;
+            let child = format!("{}/{}", pointer, escape(&key));
+            let value = self.value(&child, Some(key_span))?;
+            map.insert(key, value);
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {}
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self, pointer: &str) -> Result<Value, Violation> {
+        self.position += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b']') {
+                self.position += 1;
+                return Ok(Value::Array(items));
+            }
+            let child = format!("{}/{}", pointer, items.len());
+            items.push(self.value(&child, None)?);
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {}
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Violation> {
+        let start = self.position;
+        self.position += 1;
+        loop {
+            match self.peek() {
+                Some(b'"') => break,
+                Some(b'\\') => self.position += 2,
+                Some(b'\n') | None => {
+                    self.position = start;
+                    return Err(self.error("unterminated string"));
+                }
+                Some(_) => self.position += 1,
+            }
+        }
+        self.position += 1;
+        serde_json::from_str(&self.source[start..self.position]).map_err(|e| Violation {
+            span: start..self.position,
+            message: format!("invalid string: {}", e),
+        })
+    }
+
+    fn number(&mut self) -> Result<Value, Violation> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|b| matches!(b, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'))
+        {
+            self.position += 1;
+        }
+        serde_json::from_str(&self.source[start..self.position]).map_err(|_| Violation {
+            span: start..self.position,
+            message: "invalid number".to_string(),
+        })
+    }
+}
@@ -0,0 +1,136 @@
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+mod common;
+
+const WRANGLER_JSONC: &str = r#"{
+  "name": "test-project-admin",
+  "main": "src/index.ts",
+  "compatibility_date": "2024-01-01"
+}
+"#;
+
+/// Minimal Cloudflare API answering the endpoints `access protect` and `doctor` call.
+/// Policy listings return `policies`. Returns the base URL to point the CLI at.
+fn mock_api(policies: &'static str) -> anyhow::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let base = format!("http://{}/client/v4", listener.local_addr()?);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = header.to_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+            let result = if path.ends_with("/workers/subdomain") {
+                r#"{"subdomain":"acme"}"#
+            } else if path.ends_with("/policies") && request_line.starts_with("GET") {
+                policies
+            } else if path.ends_with("/policies") {
+                r#"{"id":"policy-1","name":"Allow example.com"}"#
+            } else {
+                r#"{"id":"app-1","domain":"test-project-admin.acme.workers.dev"}"#
+            };
+            let response = format!(r#"{{"success":true,"errors":[],"result":{}}}"#, result);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+        }
+    });
+
+    Ok(base)
+}
+
+fn setup(workspace: &MoonflareTestWorkspace) -> anyhow::Result<std::path::PathBuf> {
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "admin",
+        &[("wrangler.jsonc", WRANGLER_JSONC)],
+    )?;
+    Ok(workspace_path)
+}
+
+#[test]
+fn test_access_protect_records_app_and_doctor_checks_policies() -> anyhow::Result<()> {
+    log("→ Access Protect Records App And Doctor Checks Policies");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = setup(&workspace)?;
+    let base = mock_api("[]")?;
+    let envs = [
+        ("CLOUDFLARE_API_BASE_URL", base.as_str()),
+        ("CLOUDFLARE_API_TOKEN", "test-token"),
+        ("CLOUDFLARE_ACCOUNT_ID", "account-1"),
+    ];
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["access", "protect", "admin", "--policy", "@example.com"],
+        &envs,
+    )?;
+    assert!(
+        output.status.success(),
+        "Protect should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join(".moonflare/deployments.json"),
+    )?)?;
+    let record = &manifest["access"]["admin"];
+    assert_eq!(record["app_id"], "app-1");
+    assert_eq!(record["domain"], "test-project-admin.acme.workers.dev");
+    assert_eq!(record["policy"], "example.com");
+
+    // The mock reports no policies left on the application
+    let output = workspace.run_with_env("test-project", &["doctor"], &envs)?;
+    assert!(!output.status.success(), "Doctor should report the app");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("app-1"), "{}", stdout);
+    assert!(stdout.contains("has no policies"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_access_protect_requires_api_token() -> anyhow::Result<()> {
+    log("→ Access Protect Requires API Token");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = setup(&workspace)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["access", "protect", "admin", "--policy", "example.com"],
+        &[("CLOUDFLARE_API_TOKEN", "")],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("CLOUDFLARE_API_TOKEN"), "{}", stderr);
+    assert!(!workspace_path.join(".moonflare/deployments.json").exists());
+
+    Ok(())
+}
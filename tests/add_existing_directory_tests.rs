@@ -0,0 +1,75 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_add_into_empty_directory() -> anyhow::Result<()> {
+    log("→ Add Into Empty Directory");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::create_dir_all(workspace_path.join("apps/web"))?;
+
+    let output = workspace.run("test-project", &["add", "react", "web"])?;
+    assert!(
+        output.status.success(),
+        "Add should scaffold into an empty directory: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(workspace_path.join("apps/web/package.json").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_add_refuses_non_empty_directory() -> anyhow::Result<()> {
+    log("→ Add Refuses Non-Empty Directory");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::create_dir_all(workspace_path.join("apps/web"))?;
+    fs::write(workspace_path.join("apps/web/README.md"), "# Starter\n")?;
+
+    let output = workspace.run("test-project", &["add", "react", "web"])?;
+    assert!(
+        !output.status.success(),
+        "Add should refuse a non-empty directory"
+    );
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        combined.contains("contains 1 file: README.md"),
+        "{}",
+        combined
+    );
+    assert!(
+        combined.contains("moonflare add react web --force"),
+        "{}",
+        combined
+    );
+    assert!(!workspace_path.join("apps/web/package.json").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_add_force_into_non_empty_directory() -> anyhow::Result<()> {
+    log("→ Add Force Into Non-Empty Directory");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::create_dir_all(workspace_path.join("apps/web"))?;
+    fs::write(workspace_path.join("apps/web/README.md"), "# Starter\n")?;
+
+    let output = workspace.run("test-project", &["add", "react", "web", "--force"])?;
+    assert!(
+        output.status.success(),
+        "Add --force should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(workspace_path.join("apps/web/package.json").exists());
+    assert!(workspace_path.join("apps/web/README.md").exists());
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_add_from_clones_and_renames_worker() -> anyhow::Result<()> {
+    log("→ Add From Clones And Renames Worker");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["add", "durable-object", "user-state"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let source = workspace_path.join("workers/user-state");
+    fs::write(source.join(".dev.vars"), "API_TOKEN=\"s3cret\"\n")?;
+    fs::create_dir_all(source.join("node_modules/dep"))?;
+    fs::write(
+        source.join("src/client.ts"),
+        "import \"user-state-client\";\n",
+    )?;
+
+    let output = workspace.run(
+        "test-project",
+        &["add", "durable-object", "billing", "--from", "user-state"],
+    )?;
+    assert!(
+        output.status.success(),
+        "Clone should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let clone = workspace_path.join("workers/billing");
+    let package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(clone.join("package.json"))?)?;
+    assert_eq!(package["name"], "billing");
+
+    let wrangler = fs::read_to_string(clone.join("wrangler.toml"))?;
+    assert!(wrangler.contains("name = \"billing\""), "{}", wrangler);
+    assert!(wrangler.contains("BILLING_DURABLE_OBJECT"), "{}", wrangler);
+    assert!(wrangler.contains("BillingDurableObject"), "{}", wrangler);
+    assert!(!wrangler.contains("UserState"), "{}", wrangler);
+
+    let index = fs::read_to_string(clone.join("src/index.ts"))?;
+    assert!(
+        index.contains("export class BillingDurableObject"),
+        "{}",
+        index
+    );
+    assert!(!index.contains("USER_STATE"), "{}", index);
+
+    // Only whole names are renamed, and local secrets and dependencies stay behind
+    let client = fs::read_to_string(clone.join("src/client.ts"))?;
+    assert!(client.contains("user-state-client"), "{}", client);
+    assert!(!clone.join(".dev.vars").exists());
+    assert!(!clone.join("node_modules").exists());
+
+    // The source project is untouched
+    let original = fs::read_to_string(source.join("src/index.ts"))?;
+    assert!(original.contains("UserStateDurableObject"));
+
+    Ok(())
+}
+
+#[test]
+fn test_add_from_requires_existing_project() -> anyhow::Result<()> {
+    log("→ Add From Requires Existing Project");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run(
+        "test-project",
+        &["add", "react", "admin", "--from", "missing"],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'missing' not found"), "{}", stderr);
+    assert!(!workspace_path.join("apps/admin").exists());
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+fn list_types(workspace: &MoonflareTestWorkspace, dir: &str) -> anyhow::Result<serde_json::Value> {
+    let output = workspace.run(dir, &["add", "--list-types", "--json"])?;
+    assert!(
+        output.status.success(),
+        "Listing types should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+fn find_type<'a>(listing: &'a serde_json::Value, name: &str) -> &'a serde_json::Value {
+    listing["types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|kind| kind["name"] == name)
+        .unwrap_or_else(|| panic!("{} should be listed: {}", name, listing))
+}
+
+#[test]
+fn test_list_types_json_describes_every_type() -> anyhow::Result<()> {
+    log("→ List Types JSON Describes Every Type");
+    let workspace = MoonflareTestWorkspace::new()?;
+
+    // Outside a workspace, with the default layout
+    let listing = list_types(&workspace, "")?;
+    assert_eq!(listing["schema"], 1);
+    assert!(listing["moonflare_version"].is_string());
+
+    let names: Vec<&str> = listing["types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|kind| kind["name"].as_str())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "astro",
+            "react",
+            "durable-object",
+            "crate",
+            "maintenance-bot",
+            "smoke-tests",
+            "e2e",
+            "container"
+        ]
+    );
+    for kind in listing["types"].as_array().unwrap() {
+        let version = kind["template_version"].as_str().unwrap();
+        assert_eq!(version.len(), 12, "{}", kind);
+        assert!(version.chars().all(|c| c.is_ascii_hexdigit()), "{}", kind);
+    }
+
+    let react = find_type(&listing, "react");
+    assert_eq!(react["directory"], "apps");
+    let features: Vec<&str> = react["features"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|feature| feature["name"].as_str())
+        .collect();
+    assert_eq!(features, ["storybook", "hashed-assets"]);
+
+    let durable_object = find_type(&listing, "durable-object");
+    assert_eq!(durable_object["aliases"], serde_json::json!(["worker"]));
+
+    let container = find_type(&listing, "container");
+    assert_eq!(container["flags"][0]["name"], "--attach");
+    assert_eq!(container["flags"][0]["required"], true);
+    assert_eq!(container["groupable"], false);
+
+    assert_eq!(find_type(&listing, "smoke-tests")["default_name"], "smoke");
+
+    Ok(())
+}
+
+#[test]
+fn test_list_types_follows_the_workspace_layout() -> anyhow::Result<()> {
+    log("→ List Types Follows The Workspace Layout");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[layout]\nworkers = \"services\"\n",
+    )?;
+
+    let listing = list_types(&workspace, "shop")?;
+    assert_eq!(
+        find_type(&listing, "durable-object")["directory"],
+        "services"
+    );
+    assert_eq!(
+        find_type(&listing, "maintenance-bot")["directory"],
+        "services"
+    );
+    assert_eq!(find_type(&listing, "astro")["directory"], "sites");
+
+    let output = workspace.run("shop", &["add", "--list-types"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("services/"), "{}", stdout);
+    assert!(stdout.contains("--with storybook"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_list_types_conflicts_with_a_project_type() -> anyhow::Result<()> {
+    log("→ List Types Conflicts With A Project Type");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run("shop", &["add", "react", "web", "--list-types"])?;
+    assert!(!output.status.success());
+
+    let output = workspace.run("shop", &["add", "react", "web", "--json"])?;
+    assert!(!output.status.success());
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+mod common;
+
+const WRANGLER_TOML: &str = r#"name = "rag-api"
+main = "src/index.ts"
+compatibility_date = "2024-01-01"
+
+[ai]
+binding = "AI"
+
+[env.staging]
+name = "rag-api-staging"
+"#;
+
+const INDEX_TS: &str = r#"export interface Env {
+  AI: Ai;
+}
+
+export default {
+  async fetch(request: Request, env: Env): Promise<Response> {
+    const { data } = await env.AI.run("@cf/baai/bge-base-en-v1.5", { text: ["hi"] });
+    const answer = await env.AI.run("@cf/meta/llama-3.1-8b-instruct", {
+      messages: [{ role: "user", content: "a, (b)" }],
+    });
+    const raw = await env.AI.run("@cf/meta/llama-3.1-8b-instruct", { prompt: "hi" }, { returnRawResponse: true });
+    return Response.json({ data, answer, raw });
+  },
+};
+"#;
+
+/// Minimal Cloudflare API that creates the gateway it's asked for and sends each request
+/// body down the returned channel. Returns the base URL to point the CLI at.
+fn mock_api() -> anyhow::Result<(String, mpsc::Receiver<serde_json::Value>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let base = format!("http://{}/client/v4", listener.local_addr()?);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = header.to_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap_or_default();
+            let _ = sender.send(body.clone());
+
+            let response = format!(
+                r#"{{"success":true,"errors":[],"result":{{"id":{}}}}}"#,
+                body["id"]
+            );
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+        }
+    });
+
+    Ok((base, receiver))
+}
+
+#[test]
+fn test_ai_gateway_create_routes_calls_and_sets_var() -> anyhow::Result<()> {
+    log("→ AI Gateway Create Routes Calls And Sets Var");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    let project_path = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "rag-api",
+        &[("wrangler.toml", WRANGLER_TOML), ("src/index.ts", INDEX_TS)],
+    )?;
+    let (base, requests) = mock_api()?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &[
+            "ai",
+            "gateway",
+            "create",
+            "rag-gateway",
+            "--cache-ttl",
+            "600",
+            "--rate-limit",
+            "100",
+        ],
+        &[
+            ("CLOUDFLARE_API_BASE_URL", base.as_str()),
+            ("CLOUDFLARE_API_TOKEN", "test-token"),
+            ("CLOUDFLARE_ACCOUNT_ID", "account-1"),
+        ],
+    )?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let request = requests.try_recv()?;
+    assert_eq!(request["id"], "rag-gateway");
+    assert_eq!(request["cache_ttl"], 600);
+    assert_eq!(request["rate_limiting_limit"], 100);
+    assert_eq!(request["rate_limiting_interval"], 60);
+    assert_eq!(request["collect_logs"], true);
+
+    let config: toml::Value =
+        toml::from_str(&fs::read_to_string(project_path.join("wrangler.toml"))?)?;
+    assert_eq!(
+        config["vars"]["AI_GATEWAY_ID"].as_str(),
+        Some("rag-gateway")
+    );
+    assert_eq!(
+        config["env"]["staging"]["vars"]["AI_GATEWAY_ID"].as_str(),
+        Some("rag-gateway")
+    );
+
+    let index = fs::read_to_string(project_path.join("src/index.ts"))?;
+    assert!(
+        index.contains("  AI: Ai;\n  AI_GATEWAY_ID: string;\n"),
+        "{}",
+        index
+    );
+    assert!(
+        index.contains(r#"{ text: ["hi"] }, { gateway: { id: env.AI_GATEWAY_ID } });"#),
+        "{}",
+        index
+    );
+    assert!(
+        index.contains("    }, { gateway: { id: env.AI_GATEWAY_ID } });\n"),
+        "{}",
+        index
+    );
+    // The call that already passes options is left for a person to route
+    assert_eq!(index.matches("gateway:").count(), 2, "{}", index);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("src/index.ts:11"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_ai_gateway_create_requires_ai_binding() -> anyhow::Result<()> {
+    log("→ AI Gateway Create Requires AI Binding");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\nmain = \"src/index.ts\"\n")],
+    )?;
+
+    let output = workspace.run("shop", &["ai", "gateway", "create", "rag-gateway"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("No project has a Workers AI binding"),
+        "{}",
+        stderr
+    );
+
+    let output = workspace.run(
+        "shop",
+        &["ai", "gateway", "create", "Rag_Gateway", "--project", "api"],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("isn't a valid gateway name"));
+
+    Ok(())
+}
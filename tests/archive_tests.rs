@@ -0,0 +1,125 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+const SHARED_WASM_CONFIG: &str =
+    "tasks:\n  gather:\n    command: 'node gather.mjs'\n    deps:\n      - 'math:build'\n";
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+    anyhow::ensure!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[test]
+fn test_archive_and_unarchive_projects() -> anyhow::Result<()> {
+    log("→ Archive And Unarchive Projects");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join(".moon/workspace.yml"),
+        "projects:\n  globs:\n    - \"apps/*\"\n    - \"crates/*\"\n    - \"workers/legacy\"\n",
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "legacy",
+        &[("wrangler.toml", "name = \"legacy\"\n")],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::Crate,
+        "math",
+        &[("Cargo.toml", "[package]\nname = \"math\"\n")],
+    )?;
+    fs::create_dir_all(workspace_path.join("shared-wasm"))?;
+    fs::write(
+        workspace_path.join("shared-wasm/moon.yml"),
+        SHARED_WASM_CONFIG,
+    )?;
+    git(&workspace_path, &["init", "-q"])?;
+    git(&workspace_path, &["add", "-A"])?;
+    git(&workspace_path, &["commit", "-q", "-m", "Initial commit"])?;
+    fs::write(workspace_path.join("workers/legacy/.dev.vars"), "TOKEN=x\n")?;
+
+    let output = workspace.run("shop", &["archive", "legacy"])?;
+    assert!(
+        output.status.success(),
+        "Archive should succeed: {}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let archived = workspace_path.join("archive/workers/legacy");
+    assert!(archived.join("wrangler.toml").exists());
+    assert!(
+        archived.join(".dev.vars").exists(),
+        "Untracked files move too"
+    );
+    assert!(!workspace_path.join("workers/legacy").exists());
+    let moon_workspace = fs::read_to_string(workspace_path.join(".moon/workspace.yml"))?;
+    assert!(
+        !moon_workspace.contains("workers/legacy"),
+        "{}",
+        moon_workspace
+    );
+    assert!(moon_workspace.contains("apps/*"), "{}", moon_workspace);
+    let staged = git(
+        &workspace_path,
+        &["diff", "--cached", "--name-status", "-M"],
+    )?;
+    assert!(
+        staged.contains("workers/legacy/wrangler.toml\tarchive/workers/legacy/wrangler.toml"),
+        "The move should be staged as a rename: {}",
+        staged
+    );
+    assert!(!staged.contains(".dev.vars"), "{}", staged);
+
+    let output = workspace.run("shop", &["archive", "math"])?;
+    assert!(output.status.success());
+    let shared_wasm = fs::read_to_string(workspace_path.join("shared-wasm/moon.yml"))?;
+    assert!(!shared_wasm.contains("math:build"), "{}", shared_wasm);
+
+    let output = workspace.run("shop", &["doctor"])?;
+    assert!(
+        !String::from_utf8_lossy(&output.stdout).contains("archive/"),
+        "Archived projects aren't glob drift: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let output = workspace.run("shop", &["unarchive", "math"])?;
+    assert!(
+        output.status.success(),
+        "Unarchive should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(workspace_path.join("crates/math/Cargo.toml").exists());
+    let shared_wasm = fs::read_to_string(workspace_path.join("shared-wasm/moon.yml"))?;
+    assert!(shared_wasm.contains("math:build"), "{}", shared_wasm);
+
+    let output = workspace.run("shop", &["unarchive", "legacy"])?;
+    assert!(output.status.success());
+    let moon_workspace = fs::read_to_string(workspace_path.join(".moon/workspace.yml"))?;
+    assert!(moon_workspace.contains("workers/*"), "{}", moon_workspace);
+
+    let output = workspace.run("shop", &["unarchive", "legacy"])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("No archived project named 'legacy'"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const WORKER_CONFIG: &str = r#"name = "api"
+main = "src/index.ts"
+
+[vars]
+GREETING = "hello"
+
+[[kv_namespaces]]
+binding = "CACHE"
+id = "cache-id"
+
+[[kv_namespaces]]
+binding = "OLD_CACHE"
+id = "old-cache-id"
+
+[[d1_databases]]
+binding = "DB"
+database_name = "api"
+database_id = "db-id"
+
+[ai]
+binding = "AI"
+
+[[env.staging.kv_namespaces]]
+binding = "OLD_CACHE"
+id = "old-cache-staging-id"
+"#;
+
+const WORKER_SOURCE: &str = r#"export default {
+  async fetch(request, env) {
+    const { DB } = env;
+    const mode = process.env.NODE_ENV;
+    await env.CACHE.put("greeting", env.GREETING);
+    await env.SESSIONS.get(env.API_KEY);
+    return new Response(mode, { status: DB ? 200 : 500 });
+  },
+};
+"#;
+
+#[test]
+fn test_audit_bindings_reports_and_removes_unused_bindings() -> anyhow::Result<()> {
+    log("→ Audit Bindings Reports And Removes Unused Bindings");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    let worker = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.toml", WORKER_CONFIG),
+            ("src/index.ts", WORKER_SOURCE),
+            (".dev.vars", "API_KEY=\"secret\"\n"),
+            (
+                "worker-configuration.d.ts",
+                "interface Env { OLD_CACHE: KVNamespace; AI: Ai; }\n",
+            ),
+        ],
+    )?;
+
+    let output = workspace.run("shop", &["audit-bindings"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "Findings should fail the audit");
+    assert!(
+        stdout.contains("OLD_CACHE (kv_namespaces) is declared but never used"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("AI (ai) is declared but never used"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("env.SESSIONS is used in src/index.ts"),
+        "{}",
+        stdout
+    );
+    for name in [" CACHE (", " DB (", "GREETING", "API_KEY", "NODE_ENV"] {
+        assert!(
+            !stdout.contains(name),
+            "{} isn't a finding: {}",
+            name,
+            stdout
+        );
+    }
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--fix"),
+        "The failure should suggest --fix"
+    );
+
+    let output = workspace.run("shop", &["audit-bindings", "api", "--fix"])?;
+    assert!(
+        !output.status.success(),
+        "The missing SESSIONS binding can't be fixed"
+    );
+    let config: toml::Table = fs::read_to_string(worker.join("wrangler.toml"))?.parse()?;
+    let namespaces = config["kv_namespaces"].as_array().unwrap();
+    assert_eq!(namespaces.len(), 1);
+    assert_eq!(namespaces[0]["binding"].as_str(), Some("CACHE"));
+    assert!(!config.contains_key("ai"));
+    assert!(config.contains_key("d1_databases"));
+    assert!(
+        config["env"]["staging"]
+            .as_table()
+            .is_some_and(|staging| !staging.contains_key("kv_namespaces")),
+        "{:?}",
+        config["env"]
+    );
+
+    let mut content = fs::read_to_string(worker.join("wrangler.toml"))?;
+    content.push_str("\n[[kv_namespaces]]\nbinding = \"SESSIONS\"\nid = \"sessions-id\"\n");
+    fs::write(worker.join("wrangler.toml"), content)?;
+    let output = workspace.run("shop", &["audit-bindings"])?;
+    assert!(
+        output.status.success(),
+        "Audit should pass once every binding is declared: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use common::*;
+
+mod common;
+
+#[test]
+fn test_bench_requires_bench_task() -> anyhow::Result<()> {
+    log("→ Bench Requires Bench Task");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::Crate, "utils", &[])?;
+
+    let output = workspace.run("test-project", &["bench"])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("No projects with a 'bench' task"),
+        "Should explain that nothing is benchmarkable"
+    );
+
+    let output = workspace.run("test-project", &["bench", "utils"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("has no 'bench' task"));
+
+    Ok(())
+}
+
+#[test]
+fn test_crate_template_includes_benchmarks() -> anyhow::Result<()> {
+    log("→ Crate Template Includes Benchmarks");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    std::fs::create_dir_all(workspace_path.join("shared-wasm"))?;
+    std::fs::write(workspace_path.join("shared-wasm/moon.yml"), "tasks: {}\n")?;
+
+    let output = workspace.run("test-project", &["add", "crate", "math-utils"])?;
+    assert!(
+        output.status.success(),
+        "Adding a crate should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let crate_path = workspace_path.join("crates/math-utils");
+    let bench = std::fs::read_to_string(crate_path.join("benches/benchmarks.rs"))?;
+    assert!(
+        bench.contains("use math_utils::"),
+        "Bench should import the crate by its Rust name: {}",
+        bench
+    );
+
+    let moon_yml = std::fs::read_to_string(crate_path.join("moon.yml"))?;
+    assert!(moon_yml.contains("bench:"));
+
+    Ok(())
+}
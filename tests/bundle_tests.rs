@@ -0,0 +1,131 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// esbuild metafile of a Worker that pulls in a large JSON file and lodash, and imports
+/// `node:buffer`
+const METAFILE: &str = r#"{
+  "inputs": {},
+  "outputs": {
+    "out/index.js": {
+      "bytes": 600000,
+      "inputs": {
+        "src/index.ts": {"bytesInOutput": 2000},
+        "src/data/countries.json": {"bytesInOutput": 400000},
+        "../../node_modules/.pnpm/lodash@4.17.21/node_modules/lodash/a.js": {"bytesInOutput": 100000},
+        "../../node_modules/.pnpm/lodash@4.17.21/node_modules/lodash/b.js": {"bytesInOutput": 98000}
+      },
+      "imports": [{"path": "node:buffer", "kind": "import-statement", "external": true}]
+    },
+    "out/index.js.map": {"bytes": 900000, "inputs": {}, "imports": []}
+  }
+}"#;
+
+/// Put a fake `pnpm` first on PATH that writes the metafile wherever `--metafile` points
+fn fake_pnpm(dir: &Path) -> anyhow::Result<String> {
+    fs::write(dir.join("metafile.json"), METAFILE)?;
+    fake_tools(
+        dir,
+        &[(
+            "pnpm",
+            &format!(
+                "while [ $# -gt 0 ]; do\n  if [ \"$1\" = --metafile ]; then cp '{}' \"$2\"; fi\n  shift\ndone\n",
+                dir.join("metafile.json").display()
+            ),
+        )],
+    )
+}
+
+#[test]
+fn test_bundle_analyze_lists_contributors_and_node_builtins() -> anyhow::Result<()> {
+    log("→ Bundle Analyze Lists Contributors And Node Builtins");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let api = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\nmain = \"src/index.ts\"\n")],
+    )?;
+    let path = fake_pnpm(&workspace_path)?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["bundle", "analyze", "api", "--json"],
+        &[("PATH", &path)],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("would be refused at deploy: api"),
+        "{}",
+        stderr
+    );
+
+    let reports: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let report = &reports[0];
+    assert_eq!(report["bytes"], 600000, "Source maps are left out");
+    assert_eq!(report["node_builtins"], serde_json::json!(["buffer"]));
+    let contributors = report["contributors"].as_array().unwrap();
+    assert_eq!(contributors[0]["name"], "src/data/countries.json");
+    assert_eq!(contributors[0]["large"], true);
+    assert_eq!(contributors[1]["name"], "lodash");
+    assert_eq!(contributors[1]["bytes"], 198000);
+    assert_eq!(contributors[1]["files"], 2);
+
+    // With nodejs_compat the builtin import is fine
+    fs::write(
+        api.join("wrangler.toml"),
+        "name = \"api\"\nmain = \"src/index.ts\"\ncompatibility_flags = [\"nodejs_compat\"]\n",
+    )?;
+    let output = workspace.run_with_env(
+        "shop",
+        &["bundle", "analyze", "--top", "2"],
+        &[("PATH", &path)],
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("src/data/countries.json"), "{}", stdout);
+    assert!(stdout.contains("... 1 more"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_deploy_refuses_bundle_over_budget() -> anyhow::Result<()> {
+    log("→ Deploy Refuses Bundle Over Budget");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.toml",
+            "name = \"api\"\nmain = \"src/index.ts\"\ncompatibility_flags = [\"nodejs_compat\"]\n",
+        )],
+    )?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[deploy.bundle]\nbudget_kb = 1024\n\n[projects.api.deploy]\nbundle_budget_kb = 500\n",
+    )?;
+    let path = fake_pnpm(&workspace_path)?;
+
+    let output = workspace.run_with_env("shop", &["deploy", "api"], &[("PATH", &path)])?;
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("Analyzing bundles"), "{}", stdout);
+    assert!(
+        stdout.contains("585.9 KB is over its 500.0 KB budget"),
+        "{}",
+        stdout
+    );
+    assert!(stderr.contains("Refusing to deploy api"), "{}", stderr);
+
+    Ok(())
+}
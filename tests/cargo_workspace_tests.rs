@@ -0,0 +1,91 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_crates_share_a_root_cargo_workspace() -> anyhow::Result<()> {
+    log("→ Crates Share A Root Cargo Workspace");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::create_dir_all(workspace_path.join("shared-wasm"))?;
+    fs::write(
+        workspace_path.join("shared-wasm/moon.yml"),
+        "tasks:\n  gather:\n    command: 'node'\n    args: ['gather-wasm.cjs']\n",
+    )?;
+
+    for args in [
+        &["add", "crate", "math-utils"][..],
+        &["add", "crate", "shared-core", "--lib-only"],
+        &["add", "crate", "codec", "--small"],
+    ] {
+        let output = workspace.run("test-project", args)?;
+        assert!(
+            output.status.success(),
+            "{:?} should succeed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let manifest: toml::Table = fs::read_to_string(workspace_path.join("Cargo.toml"))?.parse()?;
+    let paths = |key: &str| -> Vec<String> {
+        manifest["workspace"][key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|path| path.as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(
+        paths("members"),
+        vec!["crates/math-utils", "crates/shared-core"]
+    );
+    // The small crate keeps its size-optimized profile by building on its own
+    assert_eq!(paths("exclude"), vec!["crates/codec", "archive"]);
+    assert_eq!(
+        manifest["workspace"]["dependencies"]["shared-core"]["path"].as_str(),
+        Some("crates/shared-core")
+    );
+
+    // The lib-only crate is a plain library with no WASM build to gather
+    let lib_path = workspace_path.join("crates/shared-core");
+    assert!(!fs::read_to_string(lib_path.join("Cargo.toml"))?.contains("cdylib"));
+    assert!(fs::read_to_string(lib_path.join("moon.yml"))?.contains("lib-only"));
+    let shared_wasm = fs::read_to_string(workspace_path.join("shared-wasm/moon.yml"))?;
+    assert!(shared_wasm.contains("math-utils:build"), "{}", shared_wasm);
+    assert!(
+        !shared_wasm.contains("shared-core:build"),
+        "{}",
+        shared_wasm
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_user_cargo_workspace_is_left_alone() -> anyhow::Result<()> {
+    log("→ User Cargo Workspace Is Left Alone");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::create_dir_all(workspace_path.join("shared-wasm"))?;
+    fs::write(workspace_path.join("shared-wasm/moon.yml"), "tasks: {}\n")?;
+    let own = "[workspace]\nmembers = [\"tools/cli\"]\n";
+    fs::write(workspace_path.join("Cargo.toml"), own)?;
+
+    let output = workspace.run("test-project", &["add", "crate", "math-utils"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout)
+            .contains("add crates/math-utils to its workspace members by hand"),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert_eq!(fs::read_to_string(workspace_path.join("Cargo.toml"))?, own);
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_ci_generate_with_ai_review() -> anyhow::Result<()> {
+    log("→ CI Generate With AI Review");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::DurableObject, "api", &[])?;
+
+    let output = workspace.run("test-project", &["ci", "generate", "--with-ai-review"])?;
+    assert!(
+        output.status.success(),
+        "CI generate should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let ci = fs::read_to_string(workspace_path.join(".github/workflows/moonflare-ci.yml"))?;
+    assert!(
+        ci.contains("'workers/**'"),
+        "CI should watch workers: {}",
+        ci
+    );
+    assert!(
+        !ci.contains("'apps/**'"),
+        "CI should skip missing dirs: {}",
+        ci
+    );
+
+    let review = fs::read_to_string(workspace_path.join(".github/workflows/ai-review.yml"))?;
+    assert!(
+        review.contains("${{ secrets.ANTHROPIC_API_KEY }}"),
+        "Secret expression should be rendered literally: {}",
+        review
+    );
+    assert!(review.contains("workers/api"));
+
+    let secrets = fs::read_to_string(workspace_path.join(".github/secrets.example"))?;
+    assert!(secrets.contains("ANTHROPIC_API_KEY="));
+
+    Ok(())
+}
+
+#[test]
+fn test_ci_generate_refuses_to_overwrite() -> anyhow::Result<()> {
+    log("→ CI Generate Refuses To Overwrite");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let workflow = workspace_path.join(".github/workflows/moonflare-ci.yml");
+    fs::create_dir_all(workflow.parent().unwrap())?;
+    fs::write(&workflow, "custom")?;
+
+    let output = workspace.run("test-project", &["ci", "generate"])?;
+    assert!(!output.status.success(), "Existing workflow should block");
+    assert_eq!(fs::read_to_string(&workflow)?, "custom");
+
+    let output = workspace.run("test-project", &["ci", "generate", "--force"])?;
+    assert!(
+        output.status.success(),
+        "--force should regenerate: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_ne!(fs::read_to_string(&workflow)?, "custom");
+
+    Ok(())
+}
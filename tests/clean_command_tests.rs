@@ -0,0 +1,72 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_clean_auto_keeps_newest_snapshots() -> anyhow::Result<()> {
+    log("→ Clean Auto Keeps Newest Snapshots");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[retention]\nkeep_last = 1\n",
+    )?;
+    let backups = workspace_path.join(".moonflare/backups");
+    for id in ["20250101-000000", "20250102-000000", "20250103-000000"] {
+        fs::create_dir_all(backups.join(id))?;
+        fs::write(backups.join(id).join("snapshot.json"), "{}")?;
+    }
+
+    let output = workspace.run("test-project", &["clean", "--auto", "--dry-run"])?;
+    assert!(
+        output.status.success(),
+        "Dry run should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("20250101-000000"));
+    assert!(stdout.contains("20250102-000000"));
+    assert!(!stdout.contains("20250103-000000"));
+    assert!(
+        backups.join("20250101-000000").exists(),
+        "Dry run must not delete anything"
+    );
+
+    let output = workspace.run("test-project", &["clean", "--auto"])?;
+    assert!(
+        output.status.success(),
+        "Clean should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!backups.join("20250101-000000").exists());
+    assert!(!backups.join("20250102-000000").exists());
+    assert!(backups.join("20250103-000000").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_clean_auto_removes_stale_wasm() -> anyhow::Result<()> {
+    log("→ Clean Auto Removes Stale WASM");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::Crate, "math-utils", &[])?;
+
+    let shared = workspace_path.join("shared-wasm");
+    fs::create_dir_all(&shared)?;
+    fs::write(shared.join("math_utils.wasm"), b"\0asm")?;
+    fs::write(shared.join("removed_crate.wasm"), b"\0asm")?;
+
+    let output = workspace.run("test-project", &["clean", "--auto"])?;
+    assert!(
+        output.status.success(),
+        "Clean should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(shared.join("math_utils.wasm").exists());
+    assert!(!shared.join("removed_crate.wasm").exists());
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use super::{ProjectAdd, ProjectType};
+use super::{BuildTaskFixture, ProjectAdd, ProjectType, WranglerFixture};
 use proptest::prelude::*;
 
 // Property test generators
@@ -60,3 +60,144 @@ prop_compose! {
         unique_projects
     }
 }
+
+// Config mutation generators: names are upper case and values lower case so a generated value
+// never reads as a binding name
+prop_compose! {
+    pub fn arb_binding_names()(
+        names in prop::collection::btree_set("[A-Z][A-Z0-9_]{2,10}", 2..6)
+    ) -> Vec<String> {
+        names.into_iter().collect()
+    }
+}
+
+prop_compose! {
+    pub fn arb_wrangler_config()(
+        names in arb_binding_names(),
+        kv_count in 1usize..3,
+        values in prop::collection::vec("[a-z0-9]{1,8}", 6),
+        toml in any::<bool>(),
+        staging in any::<bool>(),
+        comment in any::<bool>(),
+    ) -> WranglerFixture {
+        let (kv, vars) = names.split_at(kv_count.min(names.len() - 1));
+        let content = if toml {
+            render_wrangler_toml(kv, vars, &values, staging, comment)
+        } else {
+            render_wrangler_jsonc(kv, vars, &values, staging, comment)
+        };
+        WranglerFixture {
+            file: if toml { "wrangler.toml" } else { "wrangler.jsonc" },
+            content,
+            names,
+        }
+    }
+}
+
+fn render_wrangler_toml(
+    kv: &[String],
+    vars: &[String],
+    values: &[String],
+    staging: bool,
+    comment: bool,
+) -> String {
+    let mut content = String::from("name = \"api\"\n");
+    if comment {
+        content.push_str("# Cached pages\n");
+    }
+    for (i, binding) in kv.iter().enumerate() {
+        content.push_str(&format!(
+            "\n[[kv_namespaces]]\nbinding = \"{}\"\nid = \"id{}\"\n",
+            binding, i
+        ));
+    }
+    content.push_str("\n[vars]\n");
+    for (key, value) in vars.iter().zip(values) {
+        content.push_str(&format!("{} = \"{}\"\n", key, value));
+    }
+    if staging {
+        for (i, binding) in kv.iter().enumerate() {
+            content.push_str(&format!(
+                "\n[[env.staging.kv_namespaces]]\nbinding = \"{}\"\nid = \"staging{}\"\n",
+                binding, i
+            ));
+        }
+    }
+    content
+}
+
+fn render_wrangler_jsonc(
+    kv: &[String],
+    vars: &[String],
+    values: &[String],
+    staging: bool,
+    comment: bool,
+) -> String {
+    let namespaces = |prefix: &str| {
+        kv.iter()
+            .enumerate()
+            .map(|(i, binding)| {
+                format!(
+                    "{{ \"binding\": \"{}\", \"id\": \"{}{}\" }}",
+                    binding, prefix, i
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut content = String::from("{\n");
+    if comment {
+        content.push_str("  // Cached pages\n");
+    }
+    content.push_str("  \"name\": \"api\",\n");
+    content.push_str(&format!("  \"kv_namespaces\": [{}],\n", namespaces("id")));
+    let vars = vars
+        .iter()
+        .zip(values)
+        .map(|(key, value)| format!("\"{}\": \"{}\"", key, value))
+        .collect::<Vec<_>>()
+        .join(", ");
+    content.push_str(&format!("  \"vars\": {{ {} }},\n", vars));
+    if staging {
+        content.push_str(&format!(
+            "  \"env\": {{\n    \"staging\": {{\n      \"kv_namespaces\": [{}],\n    }},\n  }},\n",
+            namespaces("staging")
+        ));
+    }
+    content.push_str("}\n");
+    content
+}
+
+prop_compose! {
+    pub fn arb_build_task()(
+        deps in prop::collection::btree_set("[a-z]{2,8}:[a-z]{2,8}", 0..3),
+        inputs in prop::collection::btree_set("src/[a-z]{1,8}/\\*\\*/\\*", 0..3),
+        script in any::<bool>(),
+    ) -> BuildTaskFixture {
+        let deps: Vec<String> = deps.into_iter().collect();
+        let inputs: Vec<String> = inputs.into_iter().collect();
+        let mut content = String::from("language: typescript\ntasks:\n  build:\n");
+        if script {
+            content.push_str("    script: pnpm build\n");
+        } else {
+            content.push_str("    command: pnpm\n    args: [build]\n");
+        }
+        for (key, items) in [("deps", &deps), ("inputs", &inputs)] {
+            if !items.is_empty() {
+                content.push_str(&format!("    {}:\n", key));
+                for item in items {
+                    content.push_str(&format!("      - '{}'\n", item));
+                }
+            }
+        }
+        BuildTaskFixture { content, deps, inputs }
+    }
+}
+
+prop_compose! {
+    pub fn arb_tags()(
+        tags in prop::collection::btree_set("[a-z][a-z0-9_-]{0,10}", 1..5)
+    ) -> Vec<String> {
+        tags.into_iter().collect()
+    }
+}
@@ -124,3 +124,21 @@ pub struct ProjectAdd {
     pub project_type: ProjectType,
     pub name: String,
 }
+
+// A Wrangler config for the config mutation property tests
+#[derive(Debug, Clone)]
+pub struct WranglerFixture {
+    /// `wrangler.toml` or `wrangler.jsonc`
+    pub file: &'static str,
+    pub content: String,
+    /// KV binding names and var keys, all distinct
+    pub names: Vec<String>,
+}
+
+// A moon.yml build task for the config mutation property tests
+#[derive(Debug, Clone)]
+pub struct BuildTaskFixture {
+    pub content: String,
+    pub deps: Vec<String>,
+    pub inputs: Vec<String>,
+}
@@ -19,7 +19,7 @@ impl MoonflareTestWorkspace {
         let moonflare_binary = std::env::current_dir()?
             .join("target")
             .join("release")
-            .join("moonflare");
+            .join(format!("moonflare{}", std::env::consts::EXE_SUFFIX));
 
         // Ensure the binary exists
         if !moonflare_binary.exists() {
@@ -291,4 +291,89 @@ impl MoonflareTestWorkspace {
         ));
         Ok(stderr)
     }
+
+    /// Create a bare workspace (just the marker files) without running `moonflare init`,
+    /// for exercising commands that don't need Moon to be installed
+    pub fn create_minimal_workspace(&self, workspace_name: &str) -> anyhow::Result<PathBuf> {
+        let workspace_path = self.temp_dir.path().join(workspace_name);
+        std::fs::create_dir_all(workspace_path.join(".moon"))?;
+        std::fs::write(
+            workspace_path.join(".moon").join("workspace.yml"),
+            "projects:\n  globs:\n    - \"apps/*\"\n    - \"sites/*\"\n    - \"workers/*\"\n    - \"crates/*\"\n",
+        )?;
+        std::fs::write(
+            workspace_path.join("package.json"),
+            format!("{{\"name\": \"{}\", \"private\": true}}\n", workspace_name),
+        )?;
+        Ok(workspace_path)
+    }
+
+    /// Create a bare project directory with the given files inside a minimal workspace
+    pub fn create_minimal_project(
+        &self,
+        workspace_name: &str,
+        project_type: &ProjectType,
+        project_name: &str,
+        files: &[(&str, &str)],
+    ) -> anyhow::Result<PathBuf> {
+        let project_path = self
+            .temp_dir
+            .path()
+            .join(workspace_name)
+            .join(project_type.directory())
+            .join(project_name);
+        std::fs::create_dir_all(&project_path)?;
+        std::fs::write(project_path.join("moon.yml"), "tasks: {}\n")?;
+        for (file, content) in files {
+            let path = project_path.join(file);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, content)?;
+        }
+        Ok(project_path)
+    }
+
+    /// Run an arbitrary moonflare command inside a workspace and return its output
+    pub fn run(&self, workspace_name: &str, args: &[&str]) -> anyhow::Result<std::process::Output> {
+        self.run_with_env(workspace_name, args, &[])
+    }
+
+    /// Run a moonflare command with additional environment variables set
+    pub fn run_with_env(
+        &self,
+        workspace_name: &str,
+        args: &[&str],
+        envs: &[(&str, &str)],
+    ) -> anyhow::Result<std::process::Output> {
+        log(&format!("Running: moonflare {}", args.join(" ")));
+
+        let mut cmd = Command::new(&self.moonflare_binary);
+        cmd.args(args)
+            .envs(envs.iter().copied())
+            .current_dir(self.temp_dir.path().join(workspace_name));
+
+        run_command_with_timeout(cmd, 10)
+    }
+}
+
+/// Write shell scripts standing in for `moon`, `pnpm` and other tools to `<dir>/fake-bin`, each
+/// given as its name and the script after the `#!/bin/sh` line, and return a `PATH` that finds
+/// them before the real ones
+pub fn fake_tools(dir: &Path, tools: &[(&str, &str)]) -> anyhow::Result<String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin = dir.join("fake-bin");
+    std::fs::create_dir_all(&bin)?;
+    for (name, script) in tools {
+        let path = bin.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}", script))?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    ))
 }
@@ -0,0 +1,48 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_add_component_crate_declares_wit_and_updates_gather() -> anyhow::Result<()> {
+    log("→ Add Component Crate Declares Wit And Updates Gather");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    // A gather script from before component crates existed
+    fs::create_dir_all(workspace_path.join("shared-wasm"))?;
+    fs::write(
+        workspace_path.join("shared-wasm/gather-wasm.cjs"),
+        "console.log('old gather');\n",
+    )?;
+
+    let output = workspace.run(
+        "test-project",
+        &["add", "crate", "geo-tools", "--component-model"],
+    )?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let crate_path = workspace_path.join("crates/geo-tools");
+    let wit = fs::read_to_string(crate_path.join("wit/world.wit"))?;
+    assert!(wit.contains("package moonflare:geo-tools;"), "{}", wit);
+    assert!(wit.contains("world geo-tools"), "{}", wit);
+
+    let lib = fs::read_to_string(crate_path.join("src/lib.rs"))?;
+    assert!(
+        lib.contains("exports::moonflare::geo_tools::api::Guest"),
+        "{}",
+        lib
+    );
+    assert!(!crate_path.join("benches").exists());
+
+    let moon = fs::read_to_string(crate_path.join("moon.yml"))?;
+    assert!(moon.contains("wasm-tools component new"), "{}", moon);
+
+    let gather = fs::read_to_string(workspace_path.join("shared-wasm/gather-wasm.cjs"))?;
+    assert!(gather.contains("jco"), "{}", gather);
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const LEGACY_TOML: &str = r#"name = "api"
+type = "javascript"
+node_compat = true
+usage_model = "bundled"
+zone_id = "zone123"
+route = "api.example.com/*"
+kv-namespaces = [{ binding = "CACHE", id = "abc" }]
+
+[build.upload]
+format = "modules"
+dir = "dist"
+main = "./index.mjs"
+
+[env.staging]
+node_compat = true
+compatibility_flags = ["nodejs_compat_v2"]
+"#;
+
+#[test]
+fn test_config_lint_reports_and_upgrades_deprecated_keys() -> anyhow::Result<()> {
+    log("→ Config Lint Reports And Upgrades Deprecated Keys");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", LEGACY_TOML)],
+    )?;
+    let config_path = workspace_path.join("workers/api/wrangler.toml");
+
+    let output = workspace.run("shop", &["config", "lint"])?;
+    assert!(!output.status.success(), "Deprecated keys fail the lint");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for key in [
+        "'node_compat'",
+        "'env.staging.node_compat'",
+        "'build.upload'",
+        "'kv-namespaces'",
+        "'zone_id'",
+        "'usage_model'",
+        "'type'",
+    ] {
+        assert!(stdout.contains(key), "Missing {}:\n{}", key, stdout);
+    }
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--fix"));
+    assert_eq!(fs::read_to_string(&config_path)?, LEGACY_TOML);
+
+    let output = workspace.run("shop", &["config", "lint", "--fix"])?;
+    assert!(
+        output.status.success(),
+        "Fix should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let config: toml::Value = toml::from_str(&fs::read_to_string(&config_path)?)?;
+    assert_eq!(config["main"].as_str(), Some("dist/index.mjs"));
+    assert_eq!(
+        config["compatibility_flags"].as_array().unwrap()[0].as_str(),
+        Some("nodejs_compat")
+    );
+    assert_eq!(
+        config["kv_namespaces"][0]["binding"].as_str(),
+        Some("CACHE")
+    );
+    assert_eq!(
+        config["routes"][0]["pattern"].as_str(),
+        Some("api.example.com/*")
+    );
+    assert_eq!(config["routes"][0]["zone_id"].as_str(), Some("zone123"));
+    let staging_flags = config["env"]["staging"]["compatibility_flags"]
+        .as_array()
+        .unwrap();
+    assert_eq!(staging_flags.len(), 1);
+    assert_eq!(staging_flags[0].as_str(), Some("nodejs_compat"));
+    for key in [
+        "node_compat",
+        "type",
+        "usage_model",
+        "build",
+        "kv-namespaces",
+        "zone_id",
+        "route",
+    ] {
+        assert!(config.get(key).is_none(), "{} should be gone", key);
+    }
+
+    // A snapshot can bring the original back
+    assert!(workspace_path.join(".moonflare/backups").is_dir());
+
+    let output = workspace.run("shop", &["config", "lint"])?;
+    assert!(
+        output.status.success(),
+        "Upgraded config lints clean: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_doctor_reports_deprecated_keys_needing_a_hand_fix() -> anyhow::Result<()> {
+    log("→ Doctor Reports Deprecated Keys Needing A Hand Fix");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let original = r#"{
+  // Workers Sites
+  "name": "site",
+  "main": "src/index.ts",
+  "site": { "bucket": "./public" },
+  "experimental_assets": { "directory": "./dist" }
+}"#;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::Astro,
+        "site",
+        &[("wrangler.jsonc", original)],
+    )?;
+
+    let output = workspace.run("shop", &["doctor"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("'site' is deprecated"), "{}", stdout);
+    assert!(stdout.contains("'experimental_assets'"), "{}", stdout);
+    assert!(!output.status.success(), "experimental_assets is rejected");
+
+    let output = workspace.run("shop", &["config", "lint", "--fix"])?;
+    assert!(!output.status.success(), "Sites still need a hand fix");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("by hand"));
+    let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join("sites/site/wrangler.jsonc"),
+    )?)?;
+    assert_eq!(config["assets"]["directory"], "./dist");
+    assert!(config.get("experimental_assets").is_none());
+    assert_eq!(config["site"]["bucket"], "./public");
+
+    Ok(())
+}
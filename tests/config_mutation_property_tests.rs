@@ -0,0 +1,174 @@
+use common::*;
+use proptest::prelude::*;
+use std::fs;
+
+mod common;
+
+const NAMING_CONFIG: &str = r#"[workspace]
+name = "shop"
+worker_name = "{workspace}-{project}"
+"#;
+
+/// Each case runs moonflare a few times, so fewer than proptest's default unless
+/// PROPTEST_CASES asks for more
+fn cases() -> u32 {
+    std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(12)
+}
+
+fn succeeds(output: &std::process::Output) -> bool {
+    if !output.status.success() {
+        log(&String::from_utf8_lossy(&output.stderr));
+    }
+    output.status.success()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig { cases: cases(), ..ProptestConfig::default() })]
+
+    #[test]
+    fn test_rename_binding_round_trips_wrangler_configs(
+        config in arb_wrangler_config(),
+        pick in any::<prop::sample::Index>(),
+        new in "[A-Z][A-Z0-9_]{2,10}",
+    ) {
+        prop_assume!(!config.names.contains(&new));
+        let old = pick.get(&config.names).clone();
+        log(&format!("→ Rename Binding Round Trips {} ({} → {})", config.file, old, new));
+        let workspace = MoonflareTestWorkspace::new().unwrap();
+        workspace.create_minimal_workspace("shop").unwrap();
+        let api = workspace
+            .create_minimal_project(
+                "shop",
+                &ProjectType::DurableObject,
+                "api",
+                &[(config.file, &config.content)],
+            )
+            .unwrap();
+        let path = api.join(config.file);
+
+        let output = workspace
+            .run("shop", &["refactor", "rename-binding", &old, &new, "--dry-run"])
+            .unwrap();
+        prop_assert!(succeeds(&output));
+        prop_assert_eq!(fs::read_to_string(&path).unwrap(), config.content.clone());
+
+        let output = workspace
+            .run("shop", &["refactor", "rename-binding", &old, &new, "--yes"])
+            .unwrap();
+        prop_assert!(succeeds(&output));
+        let renamed = fs::read_to_string(&path).unwrap();
+        prop_assert!(renamed.contains(&new), "{}", renamed);
+
+        let output = workspace
+            .run("shop", &["refactor", "rename-binding", &new, &old, "--yes"])
+            .unwrap();
+        prop_assert!(succeeds(&output));
+        prop_assert_eq!(fs::read_to_string(&path).unwrap(), config.content);
+    }
+
+    #[test]
+    fn test_sync_names_only_rewrites_the_name_and_is_idempotent(config in arb_wrangler_config()) {
+        log(&format!("→ Sync Names Only Rewrites The Name ({})", config.file));
+        let workspace = MoonflareTestWorkspace::new().unwrap();
+        let workspace_path = workspace.create_minimal_workspace("shop").unwrap();
+        fs::write(workspace_path.join("moonflare.toml"), NAMING_CONFIG).unwrap();
+        let api = workspace
+            .create_minimal_project(
+                "shop",
+                &ProjectType::DurableObject,
+                "api",
+                &[(config.file, &config.content)],
+            )
+            .unwrap();
+        let path = api.join(config.file);
+
+        prop_assert!(succeeds(&workspace.run("shop", &["sync-names", "--auto-approve"]).unwrap()));
+        let synced = fs::read_to_string(&path).unwrap();
+        prop_assert_eq!(
+            synced.clone(),
+            config.content.replacen("\"api\"", "\"shop-api\"", 1)
+        );
+
+        prop_assert!(succeeds(&workspace.run("shop", &["sync-names", "--check"]).unwrap()));
+        prop_assert!(succeeds(&workspace.run("shop", &["sync-names", "--auto-approve"]).unwrap()));
+        prop_assert_eq!(fs::read_to_string(&path).unwrap(), synced);
+    }
+
+    #[test]
+    fn test_wasm_dependency_keeps_existing_deps_and_is_idempotent(task in arb_build_task()) {
+        log("→ WASM Dependency Keeps Existing Deps And Is Idempotent");
+        let workspace = MoonflareTestWorkspace::new().unwrap();
+        workspace.create_minimal_workspace("shop").unwrap();
+        let web = workspace
+            .create_minimal_project(
+                "shop",
+                &ProjectType::React,
+                "web",
+                &[("moon.yml", &task.content), ("package.json", "{\"name\": \"web\"}")],
+            )
+            .unwrap();
+        let moon_yml = web.join("moon.yml");
+
+        prop_assert!(succeeds(&workspace.run("shop", &["add", "crate", "alpha"]).unwrap()));
+        let updated = fs::read_to_string(&moon_yml).unwrap();
+        let config: serde_yaml::Value = serde_yaml::from_str(&updated).unwrap();
+        let build = &config["tasks"]["build"];
+        let strings = |key: &str| -> Vec<String> {
+            build[key]
+                .as_sequence()
+                .map(|items| items.iter().filter_map(|i| i.as_str().map(str::to_string)).collect())
+                .unwrap_or_default()
+        };
+        let mut deps = task.deps.clone();
+        deps.push("shared-wasm:gather".to_string());
+        let mut inputs = task.inputs.clone();
+        inputs.push("/shared-wasm/*.wasm".to_string());
+        prop_assert_eq!(strings("deps"), deps);
+        prop_assert_eq!(strings("inputs"), inputs);
+        prop_assert!(build.get("command").is_none() && build.get("args").is_none());
+        prop_assert!(build["script"].as_str().is_some_and(|s| s.starts_with("pnpm build")));
+
+        prop_assert!(succeeds(&workspace.run("shop", &["add", "crate", "beta"]).unwrap()));
+        prop_assert_eq!(fs::read_to_string(&moon_yml).unwrap(), updated);
+    }
+
+    #[test]
+    fn test_tags_are_idempotent_and_remove_cleanly(tags in arb_tags()) {
+        log(&format!("→ Tags Are Idempotent And Remove Cleanly ({})", tags.join(", ")));
+        let workspace = MoonflareTestWorkspace::new().unwrap();
+        workspace.create_minimal_workspace("shop").unwrap();
+        let api = workspace
+            .create_minimal_project("shop", &ProjectType::DurableObject, "api", &[])
+            .unwrap();
+        let moon_yml = api.join("moon.yml");
+        let original: serde_yaml::Value =
+            serde_yaml::from_str(&fs::read_to_string(&moon_yml).unwrap()).unwrap();
+
+        for tag in &tags {
+            prop_assert!(succeeds(&workspace.run("shop", &["tag", "add", "api", tag]).unwrap()));
+        }
+        let tagged = fs::read_to_string(&moon_yml).unwrap();
+        for tag in &tags {
+            prop_assert!(succeeds(&workspace.run("shop", &["tag", "add", "api", tag]).unwrap()));
+        }
+        prop_assert_eq!(fs::read_to_string(&moon_yml).unwrap(), tagged.clone());
+        let config: serde_yaml::Value = serde_yaml::from_str(&tagged).unwrap();
+        let listed: Vec<&str> = config["tags"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .filter_map(|tag| tag.as_str())
+            .collect();
+        prop_assert_eq!(listed, tags.iter().map(String::as_str).collect::<Vec<_>>());
+
+        for tag in &tags {
+            prop_assert!(succeeds(&workspace.run("shop", &["tag", "remove", "api", tag]).unwrap()));
+        }
+        let config: serde_yaml::Value =
+            serde_yaml::from_str(&fs::read_to_string(&moon_yml).unwrap()).unwrap();
+        prop_assert_eq!(config, original);
+    }
+}
@@ -0,0 +1,55 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_config_flag_and_env_override_moonflare_toml() -> anyhow::Result<()> {
+    log("→ Config Flag And Env Override moonflare.toml");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[layout]\nsites = \"websites\"\n",
+    )?;
+    fs::create_dir_all(workspace_path.join("ci"))?;
+    fs::write(
+        workspace_path.join("ci/moonflare.toml"),
+        "[layout]\nsites = \"marketing\"\n",
+    )?;
+    fs::write(
+        workspace_path.join("ci/env.toml"),
+        "[layout]\nsites = \"docs\"\n",
+    )?;
+
+    let layout = |args: &[&str], env: &[(&str, &str)]| -> anyhow::Result<String> {
+        let output = workspace.run_with_env("shop", args, env)?;
+        assert!(
+            output.status.success(),
+            "{:?} should succeed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    assert!(layout(&["layout", "show"], &[])?.contains("websites/"));
+    assert!(
+        layout(&["--config", "ci/moonflare.toml", "layout", "show"], &[])?.contains("marketing/")
+    );
+    assert!(layout(&["layout", "show"], &[("MOONFLARE_CONFIG", "ci/env.toml")])?.contains("docs/"));
+    // The flag takes precedence over the environment variable
+    assert!(
+        layout(
+            &["layout", "show", "--config", "ci/moonflare.toml"],
+            &[("MOONFLARE_CONFIG", "ci/env.toml")]
+        )?
+        .contains("marketing/")
+    );
+
+    let output = workspace.run("shop", &["--config", "ci/missing.toml", "layout", "show"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("doesn't exist"));
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_moonflare_toml_typos_are_reported_with_suggestions() -> anyhow::Result<()> {
+    log("→ Moonflare Toml Typos Are Reported With Suggestions");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let config = workspace_path.join("moonflare.toml");
+
+    // Unknown keys only warn, naming the closest known key
+    fs::write(
+        &config,
+        "[deploy.environments.production]\nprotectd = true\n\n[assets]\nhashng = true\n",
+    )?;
+    let output = workspace.run("shop", &["config", "lint"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "deploy.environments.production: unknown key \"protectd\"; did you mean \"protected\"?"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("assets: unknown key \"hashng\"; did you mean \"hashing\"?"),
+        "{}",
+        stderr
+    );
+    assert_eq!(stderr.matches("protectd").count(), 1, "{}", stderr);
+
+    // Values the settings can't take fail, with the value probably meant
+    fs::write(&config, "[secrets]\nprovider = \"valt\"\n")?;
+    let output = workspace.run("shop", &["tag", "list"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("secrets.provider"), "{}", stderr);
+    assert!(stderr.contains("\"vault\"?"), "{}", stderr);
+
+    fs::write(&config, "[workspace]\nignore = \"experiments/*\"\n")?;
+    let output = workspace.run("shop", &["tag", "list"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("workspace.ignore: expected array, found string"),
+        "{}",
+        stderr
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_config_schema_and_migrate() -> anyhow::Result<()> {
+    log("→ Config Schema And Migrate");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run("shop", &["config", "schema"])?;
+    assert!(output.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(schema["title"], "moonflare.toml");
+    assert_eq!(
+        schema["properties"]["ui"]["properties"]["profile"]["enum"],
+        serde_json::json!(["full", "compact", "minimal"])
+    );
+    assert!(schema["definitions"]["project"]["properties"]["deploy"].is_object());
+
+    let output = workspace.run(
+        "shop",
+        &["config", "schema", "--output", "moonflare.schema.json"],
+    )?;
+    assert!(output.status.success());
+    let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join("moonflare.schema.json"),
+    )?)?;
+    assert_eq!(written, schema);
+
+    // A config from before versioning gains the version line and keeps its comments
+    let config = workspace_path.join("moonflare.toml");
+    fs::write(
+        &config,
+        "# Shop configuration\n\n[workspace]\nname = \"shop\" # the storefront\n",
+    )?;
+    let output = workspace.run("shop", &["config", "migrate"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("from config version 0 to 1"));
+    assert_eq!(
+        fs::read_to_string(&config)?,
+        "# Shop configuration\n\nconfig_version = 1\n\n[workspace]\nname = \"shop\" # the storefront\n"
+    );
+
+    let output = workspace.run("shop", &["config", "migrate"])?;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("already at config version 1"));
+
+    // A newer moonflare's config isn't guessed at
+    fs::write(&config, "config_version = 99\n")?;
+    let output = workspace.run("shop", &["tag", "list"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("config_version 99 is newer"), "{}", stderr);
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const WORKER_CONFIG: &str = r#"name = "api"
+main = "src/index.ts"
+
+[[durable_objects.bindings]]
+name = "API_DURABLE_OBJECT"
+class_name = "ApiDurableObject"
+
+[[migrations]]
+tag = "v1"
+new_classes = ["ApiDurableObject"]
+"#;
+
+#[test]
+fn test_add_container_attaches_it_to_a_worker() -> anyhow::Result<()> {
+    log("→ Add Container Attaches It To A Worker");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let worker = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.toml", WORKER_CONFIG),
+            ("src/index.ts", "export default {};\n"),
+            ("package.json", r#"{ "name": "api", "dependencies": {} }"#),
+        ],
+    )?;
+
+    let output = workspace.run("shop", &["add", "container", "renderer", "--attach", "api"])?;
+    assert!(
+        output.status.success(),
+        "Add container should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let container = workspace_path.join("containers/renderer");
+    assert!(container.join("Dockerfile").exists());
+    let moon = fs::read_to_string(container.join("moon.yml"))?;
+    assert!(
+        moon.contains("docker buildx build --platform linux/amd64"),
+        "{}",
+        moon
+    );
+
+    let config: toml::Table = fs::read_to_string(worker.join("wrangler.toml"))?.parse()?;
+    let containers = config["containers"].as_array().unwrap();
+    assert_eq!(
+        containers[0]["class_name"].as_str(),
+        Some("RendererContainer")
+    );
+    assert_eq!(
+        containers[0]["image"].as_str(),
+        Some("../../containers/renderer/Dockerfile")
+    );
+    let bindings = config["durable_objects"]["bindings"].as_array().unwrap();
+    assert!(
+        bindings
+            .iter()
+            .any(|b| b["name"].as_str() == Some("RENDERER_CONTAINER"))
+    );
+    assert_eq!(bindings.len(), 2, "The existing binding is kept");
+    let migrations = config["migrations"].as_array().unwrap();
+    assert_eq!(migrations[1]["tag"].as_str(), Some("v2"));
+    assert_eq!(
+        migrations[1]["new_sqlite_classes"][0].as_str(),
+        Some("RendererContainer")
+    );
+
+    let class = fs::read_to_string(worker.join("src/containers/renderer.ts"))?;
+    assert!(class.contains("extends Container"), "{}", class);
+    let index = fs::read_to_string(worker.join("src/index.ts"))?;
+    assert!(
+        index.contains(r#"export { RendererContainer } from "./containers/renderer";"#),
+        "{}",
+        index
+    );
+    let package = fs::read_to_string(worker.join("package.json"))?;
+    assert!(package.contains("@cloudflare/containers"), "{}", package);
+
+    Ok(())
+}
+
+#[test]
+fn test_add_container_requires_a_worker() -> anyhow::Result<()> {
+    log("→ Add Container Requires A Worker");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project("shop", &ProjectType::Astro, "web", &[])?;
+
+    let output = workspace.run("shop", &["add", "container", "renderer"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--attach"));
+
+    let output = workspace.run("shop", &["add", "container", "renderer", "--attach", "web"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("isn't a Worker"));
+
+    let output = workspace.run("shop", &["add", "astro", "docs", "--attach", "web"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only available for containers"));
+
+    Ok(())
+}
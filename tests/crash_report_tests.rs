@@ -0,0 +1,114 @@
+// Needs a binary built with `--features debug-panic`, which `just cli-test` builds
+#![cfg(feature = "debug-panic")]
+
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_panic_writes_redacted_crash_bundle() -> anyhow::Result<()> {
+    log("→ Panic Writes Redacted Crash Bundle");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &[
+            "deploy",
+            "--api-token",
+            "abc123",
+            "STRIPE_API_KEY=sk_live_1",
+            "--env",
+            "staging",
+        ],
+        &[("MOONFLARE_DEBUG_PANIC", "1")],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("diagnostics are in"), "{}", stderr);
+    assert!(stderr.contains("--report"), "{}", stderr);
+
+    let bundles: Vec<_> = fs::read_dir(workspace_path.join(".moonflare"))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("crash-"))
+        })
+        .collect();
+    assert_eq!(bundles.len(), 1, "{:?}", bundles);
+    let content = fs::read_to_string(&bundles[0])?;
+    assert!(!content.contains("abc123"), "{}", content);
+    assert!(!content.contains("sk_live_1"), "{}", content);
+
+    let bundle: serde_json::Value = serde_json::from_str(&content)?;
+    let args: Vec<&str> = bundle["args"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|arg| arg.as_str())
+        .collect();
+    assert!(args.ends_with(&[
+        "deploy",
+        "--api-token",
+        "<redacted>",
+        "STRIPE_API_KEY=<redacted>",
+        "--env",
+        "staging",
+    ]));
+    assert_eq!(bundle["message"], "MOONFLARE_DEBUG_PANIC is set");
+    assert!(!bundle["backtrace"].as_str().unwrap().is_empty());
+    assert!(bundle["tools"].as_array().is_some_and(|t| t.len() == 5));
+
+    // Without a [crash] endpoint, --report says where to file the bundle
+    let output = workspace.run_with_env(
+        "shop",
+        &["list", "--report"],
+        &[("MOONFLARE_DEBUG_PANIC", "1")],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Open an issue with"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn test_internal_error_writes_crash_bundle() -> anyhow::Result<()> {
+    log("→ Internal Error Writes Crash Bundle");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let bundles = || -> anyhow::Result<Vec<std::path::PathBuf>> {
+        Ok(fs::read_dir(workspace_path.join(".moonflare"))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with("crash-"))
+            })
+            .collect())
+    };
+
+    // A mistake in the command line is the user's, and leaves nothing behind
+    let output = workspace.run("shop", &["tag", "add", "missing", "edge"])?;
+    assert!(!output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("diagnostics are in"));
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["tag", "list"],
+        &[("MOONFLARE_DEBUG_PANIC", "error")],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("diagnostics are in"), "{}", stderr);
+
+    let bundles = bundles()?;
+    assert_eq!(bundles.len(), 1, "{:?}", bundles);
+    let bundle: serde_json::Value = serde_json::from_str(&fs::read_to_string(&bundles[0])?)?;
+    assert_eq!(bundle["message"], "MOONFLARE_DEBUG_PANIC is set to error");
+    assert!(bundle["location"].is_null());
+
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const WRANGLER_JSONC: &str = r#"{
+  "name": "api",
+  "d1_databases": [
+    { "binding": "DB", "database_name": "shop-db", "database_id": "prod-id", "migrations_dir": "migrations" }
+  ],
+  "env": {
+    "preview": { "vars": { "STAGE": "preview" } }
+  }
+}
+"#;
+
+/// Fake `pnpm` standing in for `wrangler d1`, logging its arguments to pnpm.log and listing the
+/// databases in d1-list.json, and a fake `gh` that reports pull request 1 as merged
+#[cfg(unix)]
+fn fake_bin(dir: &Path, workspace_path: &Path) -> anyhow::Result<String> {
+    let pnpm = format!(
+        r#"echo "$@" >> {log}
+case "$4" in
+  export) echo "CREATE TABLE users (id INTEGER);" > "$8" ;;
+  create) printf '{{\n  "d1_databases": [{{ "binding": "DB", "database_name": "%s", "database_id": "new-id" }}]\n}}\n' "$5" ;;
+  list) cat {list} ;;
+esac
+"#,
+        log = workspace_path.join("pnpm.log").display(),
+        list = workspace_path.join("d1-list.json").display()
+    );
+    let gh = "if [ \"$3\" = 1 ]; then echo MERGED; else echo OPEN; fi\n";
+    fake_tools(dir, &[("pnpm", &pnpm), ("gh", gh)])
+}
+
+#[cfg(unix)]
+#[test]
+fn test_d1_branch_copies_database_into_preview_binding() -> anyhow::Result<()> {
+    log("→ D1 Branch Copies Database Into Preview Binding");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", WRANGLER_JSONC)],
+    )?;
+    fs::write(
+        workspace_path.join("d1-list.json"),
+        r#"[{ "name": "shop-db" }]"#,
+    )?;
+    let path = fake_bin(workspace.path(), &workspace_path)?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["d1", "branch", "api", "--name", "pr-7"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Branch should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let export = workspace_path.join(".moonflare/d1/shop-db.sql");
+    let log = fs::read_to_string(workspace_path.join("pnpm.log"))?;
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines[0], "exec wrangler d1 list --json");
+    assert_eq!(
+        lines[1],
+        format!(
+            "exec wrangler d1 export shop-db --remote --output {}",
+            export.display()
+        )
+    );
+    assert_eq!(lines[2], "exec wrangler d1 create shop-db-pr-7");
+    assert_eq!(
+        lines[3],
+        format!(
+            "exec wrangler d1 execute shop-db-pr-7 --remote --file {} --env preview --yes",
+            export.display()
+        )
+    );
+    assert!(!export.exists(), "The export should be removed");
+
+    let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join("workers/api/wrangler.jsonc"),
+    )?)?;
+    assert_eq!(
+        config["env"]["preview"]["d1_databases"],
+        serde_json::json!([{
+            "binding": "DB",
+            "database_name": "shop-db-pr-7",
+            "database_id": "new-id",
+            "migrations_dir": "migrations"
+        }])
+    );
+    assert_eq!(config["env"]["preview"]["vars"]["STAGE"], "preview");
+    assert_eq!(config["d1_databases"][0]["database_id"], "prod-id");
+
+    // An existing branch isn't overwritten
+    fs::write(
+        workspace_path.join("d1-list.json"),
+        r#"[{ "name": "shop-db" }, { "name": "shop-db-pr-7" }]"#,
+    )?;
+    let output = workspace.run_with_env(
+        "shop",
+        &["d1", "branch", "api", "--name", "pr-7"],
+        &[("PATH", &path)],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_d1_branch_cleanup_removes_merged_branches() -> anyhow::Result<()> {
+    log("→ D1 Branch Cleanup Removes Merged Branches");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.jsonc",
+            r#"{
+  "name": "api",
+  "d1_databases": [{ "binding": "DB", "database_name": "shop-db", "database_id": "prod-id" }],
+  "env": {
+    "preview": {
+      "d1_databases": [{ "binding": "DB", "database_name": "shop-db-pr-1", "database_id": "pr-1-id" }]
+    }
+  }
+}
+"#,
+        )],
+    )?;
+    fs::write(
+        workspace_path.join("d1-list.json"),
+        r#"[{ "name": "shop-db" }, { "name": "shop-db-pr-1" }, { "name": "shop-db-pr-2" }, { "name": "shop-db-analytics" }]"#,
+    )?;
+    let path = fake_bin(workspace.path(), &workspace_path)?;
+    let log = workspace_path.join("pnpm.log");
+
+    let output =
+        workspace.run_with_env("shop", &["d1", "branch", "cleanup"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Cleanup should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(&log)?,
+        "exec wrangler d1 list --json\nexec wrangler d1 delete shop-db-pr-1 --skip-confirmation\n"
+    );
+    let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join("workers/api/wrangler.jsonc"),
+    )?)?;
+    assert_eq!(
+        config["env"]["preview"]["d1_databases"],
+        serde_json::json!([])
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Removed 1 branch database(s)"));
+
+    // A named branch is removed whatever the state of its pull request
+    fs::remove_file(&log)?;
+    let output = workspace.run_with_env(
+        "shop",
+        &["d1", "branch", "cleanup", "api", "--name", "pr-2"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Cleanup should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        fs::read_to_string(&log)?
+            .ends_with("exec wrangler d1 delete shop-db-pr-2 --skip-confirmation\n")
+    );
+
+    Ok(())
+}
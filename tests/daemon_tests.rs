@@ -0,0 +1,153 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` that knows `frontend:compile` and logs every task query
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "if [ \"$1\" = query ] && [ \"$2\" = tasks ]; then\n  echo query >> {}\n  echo '{{\"tasks\":{{\"frontend\":{{\"compile\":{{}}}}}}}}'\nfi\nexit 0\n",
+                dir.join("queries.log").display()
+            ),
+        )],
+    )
+}
+
+/// Stops the daemon even when an assertion fails
+struct DaemonGuard<'a>(&'a MoonflareTestWorkspace);
+
+impl Drop for DaemonGuard<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.run("test-project", &["daemon", "stop"]);
+    }
+}
+
+fn query_count(workspace_path: &Path) -> usize {
+    fs::read_to_string(workspace_path.join("queries.log"))
+        .map(|log| log.lines().count())
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_daemon_caches_moon_queries_until_files_change() -> anyhow::Result<()> {
+    log("→ Daemon Caches Moon Queries Until Files Change");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "frontend",
+        &[("moon.yml", "tasks:\n  compile:\n    command: 'true'\n")],
+    )?;
+    let path = fake_moon(&workspace_path)?;
+    let env = [("PATH", path.as_str())];
+
+    let output = workspace.run_with_env("test-project", &["daemon", "start"], &env)?;
+    let _guard = DaemonGuard(&workspace);
+    assert!(
+        output.status.success(),
+        "Daemon should start: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let build = ["build", "frontend", "--task", "compile"];
+    for _ in 0..2 {
+        assert!(
+            workspace
+                .run_with_env("test-project", &build, &env)?
+                .status
+                .success()
+        );
+    }
+    assert_eq!(
+        query_count(&workspace_path),
+        1,
+        "second build should hit the cache"
+    );
+
+    let status = workspace.run("test-project", &["daemon", "status"])?;
+    let status = String::from_utf8_lossy(&status.stdout);
+    assert!(!status.contains("cached queries: 0"), "{}", status);
+
+    fs::write(workspace_path.join("apps/frontend/moon.yml"), "tasks: {}\n")?;
+    std::thread::sleep(std::time::Duration::from_millis(2500));
+    assert!(
+        workspace
+            .run_with_env("test-project", &build, &env)?
+            .status
+            .success()
+    );
+    assert_eq!(
+        query_count(&workspace_path),
+        2,
+        "changed files should drop the cache"
+    );
+
+    let stop = workspace.run("test-project", &["daemon", "stop"])?;
+    assert!(String::from_utf8_lossy(&stop.stdout).contains("Daemon stopped"));
+    assert!(!workspace_path.join(".moonflare/daemon.sock").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_commands_run_without_daemon() -> anyhow::Result<()> {
+    log("→ Commands Run Without Daemon");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["daemon", "status"])?;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No daemon is running"));
+
+    Ok(())
+}
+
+#[test]
+fn test_rename_drops_the_daemon_cache_straight_away() -> anyhow::Result<()> {
+    log("→ Rename Drops The Daemon Cache Straight Away");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "frontend",
+        &[("moon.yml", "tasks:\n  compile:\n    command: 'true'\n")],
+    )?;
+    let path = fake_moon(&workspace_path)?;
+    let env = [("PATH", path.as_str())];
+
+    let output = workspace.run_with_env("test-project", &["daemon", "start"], &env)?;
+    let _guard = DaemonGuard(&workspace);
+    assert!(
+        output.status.success(),
+        "Daemon should start: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let build = ["build", "frontend", "--task", "compile"];
+    assert!(
+        workspace
+            .run_with_env("test-project", &build, &env)?
+            .status
+            .success()
+    );
+
+    let rename = workspace.run_with_env("test-project", &["rename", "frontend", "web"], &env)?;
+    assert!(
+        rename.status.success(),
+        "{}",
+        String::from_utf8_lossy(&rename.stderr)
+    );
+    let status = workspace.run("test-project", &["daemon", "status"])?;
+    let status = String::from_utf8_lossy(&status.stdout);
+    assert!(status.contains("cached queries: 0"), "{}", status);
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use common::*;
+use sha2::{Digest, Sha256};
+use std::fs;
+
+mod common;
+
+fn sha256(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[test]
+fn test_deploy_from_artifacts_rejects_missing_tampered_and_stale_output() -> anyhow::Result<()> {
+    log("→ Deploy From Artifacts Rejects Missing Tampered And Stale Output");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[(
+            "wrangler.jsonc",
+            r#"{"name": "web", "assets": {"directory": "./dist"}}"#,
+        )],
+    )?;
+    let artifacts = workspace_path.join("artifacts");
+
+    // Nothing was built into the artifact directory
+    let output = workspace.run("shop", &["deploy", "web", "--from-artifacts", "artifacts"])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("No artifacts for 'web'"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    fs::create_dir_all(artifacts.join(".moonflare"))?;
+    fs::create_dir_all(artifacts.join("apps/web/dist"))?;
+    fs::write(
+        artifacts.join(".moonflare/build-manifest.json"),
+        serde_json::to_string_pretty(&serde_json::json!({
+            "projects": {
+                "web": {
+                    "built_at": "2025-01-01T00:00:00+00:00",
+                    "git_sha": "abcdef1234567890",
+                    "output": "apps/web/dist",
+                    "source_sha256": "0".repeat(64),
+                    "files": { "index.html": sha256("<p>v1</p>") }
+                }
+            }
+        }))?,
+    )?;
+
+    // Output that differs from what the build recorded
+    fs::write(artifacts.join("apps/web/dist/index.html"), "<p>edited</p>")?;
+    let output = workspace.run("shop", &["deploy", "web", "--from-artifacts", "artifacts"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("build manifest") && stderr.contains("index.html"),
+        "{}",
+        stderr
+    );
+
+    // Intact output built from other sources
+    fs::write(artifacts.join("apps/web/dist/index.html"), "<p>v1</p>")?;
+    let output = workspace.run("shop", &["deploy", "web", "--from-artifacts", "artifacts"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("stale:") && stderr.contains("abcdef1"),
+        "{}",
+        stderr
+    );
+    assert!(!workspace_path.join("apps/web/dist/index.html").exists());
+
+    let output = workspace.run(
+        "shop",
+        &["deploy", "--from-artifacts", "artifacts", "--build-remote"],
+    )?;
+    assert!(!output.status.success(), "Remote builds rebuild anyway");
+
+    Ok(())
+}
+
+/// Fake `moon` whose `run` writes a build of apps/web with the API URL it was given baked in
+#[cfg(unix)]
+fn fake_moon(dir: &std::path::Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            r#"[ "$1" = query ] && exit 1
+if [ "$1" = run ]; then
+  mkdir -p apps/web/dist
+  echo "<p>$API_BASE_URL ${MOONFLARE_ENV:-none}</p>" > apps/web/dist/index.html
+fi
+exit 0
+"#,
+        )],
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn test_deploy_from_artifacts_rejects_output_built_for_another_environment() -> anyhow::Result<()> {
+    log("→ Deploy From Artifacts Rejects Output Built For Another Environment");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[(
+            "wrangler.jsonc",
+            r#"{"name": "web", "assets": {"directory": "./dist"}}"#,
+        )],
+    )?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[env.staging]\nAPI_BASE_URL = \"https://staging.example.com\"\n",
+    )?;
+    let path = fake_moon(workspace.path())?;
+    let env = [("PATH", path.as_str())];
+
+    let output = workspace.run_with_env("shop", &["build", "web", "--env", "staging"], &env)?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("for staging"));
+    assert_eq!(
+        fs::read_to_string(workspace_path.join("apps/web/dist/index.html"))?.trim(),
+        "<p>https://staging.example.com staging</p>"
+    );
+    let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join(".moonflare/build-manifest.json"),
+    )?)?;
+    assert_eq!(manifest["projects"]["web"]["environment"], "staging");
+
+    // Staging's output never reaches production, or the default environment
+    let output = workspace.run_with_env(
+        "shop",
+        &[
+            "deploy",
+            "web",
+            "--from-artifacts",
+            ".",
+            "--env",
+            "production",
+        ],
+        &env,
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'staging', not 'production'"), "{}", stderr);
+    assert!(stderr.contains("--env production'"), "{}", stderr);
+    let output =
+        workspace.run_with_env("shop", &["deploy", "web", "--from-artifacts", "."], &env)?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("the default environment"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // MOONFLARE_ENV chooses the environment when --env doesn't
+    let output = workspace.run_with_env(
+        "shop",
+        &["build", "web"],
+        &[("PATH", path.as_str()), ("MOONFLARE_ENV", "production")],
+    )?;
+    assert!(output.status.success());
+    let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join(".moonflare/build-manifest.json"),
+    )?)?;
+    assert_eq!(manifest["projects"]["web"]["environment"], "production");
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use common::*;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<()> {
+    let status = Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()?
+        .status;
+    anyhow::ensure!(status.success(), "git {} failed", args.join(" "));
+    Ok(())
+}
+
+#[test]
+fn test_require_clean_blocks_dirty_deploy() -> anyhow::Result<()> {
+    log("→ Require Clean Blocks Dirty Deploy");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    let project_path = workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\n")],
+    )?;
+
+    git(&workspace_path, &["init", "-q"])?;
+    git(&workspace_path, &["add", "-A"])?;
+    git(&workspace_path, &["commit", "-q", "-m", "initial"])?;
+    std::fs::write(project_path.join("index.ts"), "export default {};\n")?;
+
+    let output = workspace.run("test-project", &["deploy", "--require-clean"])?;
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("uncommitted changes"),
+        "Should refuse a dirty deploy: {}",
+        stderr
+    );
+    assert!(
+        stderr.contains("workers/api/index.ts"),
+        "Should list the dirty file: {}",
+        stderr
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_require_clean_ignores_changes_outside_deploy_set() -> anyhow::Result<()> {
+    log("→ Require Clean Ignores Changes Outside Deploy Set");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\n")],
+    )?;
+    let other_path = workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "legacy",
+        &[("wrangler.toml", "name = \"legacy\"\n")],
+    )?;
+
+    git(&workspace_path, &["init", "-q"])?;
+    git(&workspace_path, &["add", "-A"])?;
+    git(&workspace_path, &["commit", "-q", "-m", "initial"])?;
+    std::fs::write(other_path.join("index.ts"), "export default {};\n")?;
+
+    // The clean check passes, so the failure comes from the deploy itself (Moon isn't available)
+    let output = workspace.run(
+        "test-project",
+        &["deploy", "--require-clean", "--exclude", "legacy"],
+    )?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("uncommitted changes"),
+        "Changes outside the deploy set shouldn't block: {}",
+        stderr
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_require_clean_fails_outside_git() -> anyhow::Result<()> {
+    log("→ Require Clean Fails Outside Git");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\n")],
+    )?;
+
+    let output = workspace.run("test-project", &["deploy", "--require-clean"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--require-clean needs git"),
+        "Should refuse when git can't be queried: {}",
+        stderr
+    );
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use common::*;
+
+mod common;
+
+const BRANCH_CONFIG: &str = r#"[deploy]
+pull_request_env = "preview"
+
+[deploy.branches]
+main = "production"
+"release/*" = "staging"
+"#;
+
+#[test]
+fn test_auto_env_fails_for_unmapped_branch() -> anyhow::Result<()> {
+    log("→ Auto Env Fails For Unmapped Branch");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    std::fs::write(workspace_path.join("moonflare.toml"), BRANCH_CONFIG)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["deploy", "--auto-env"],
+        &[
+            ("GITHUB_HEAD_REF", ""),
+            ("GITHUB_EVENT_NAME", "push"),
+            ("GITHUB_REF_NAME", "feature/login"),
+        ],
+    )?;
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+    assert!(
+        stderr.contains("no deploy environment is mapped"),
+        "Should explain missing branch mapping: {}",
+        stderr
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_auto_env_resolves_branch_glob() -> anyhow::Result<()> {
+    log("→ Auto Env Resolves Branch Glob");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    std::fs::write(workspace_path.join("moonflare.toml"), BRANCH_CONFIG)?;
+
+    // No deployable projects exist, so the deploy itself is a no-op after resolving the env
+    let output = workspace.run_with_env(
+        "test-project",
+        &["deploy", "--auto-env"],
+        &[
+            ("GITHUB_HEAD_REF", ""),
+            ("GITHUB_EVENT_NAME", "push"),
+            ("GITHUB_REF_NAME", "release/1.2"),
+        ],
+    )?;
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("maps to environment: staging"));
+
+    Ok(())
+}
+
+#[test]
+fn test_auto_env_conflicts_with_env() -> anyhow::Result<()> {
+    log("→ Auto Env Conflicts With Env");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run(
+        "test-project",
+        &["deploy", "--auto-env", "--env", "staging"],
+    )?;
+    assert!(!output.status.success());
+
+    Ok(())
+}
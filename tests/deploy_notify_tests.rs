@@ -0,0 +1,139 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+mod common;
+
+/// Fake `moon` that exits with `status`
+fn fake_moon(dir: &Path, status: i32) -> anyhow::Result<String> {
+    fake_tools(dir, &[("moon", &format!("exit {}\n", status))])
+}
+
+/// Webhook that accepts any POST and hands each JSON body to the returned receiver
+fn mock_webhook() -> anyhow::Result<(String, Receiver<serde_json::Value>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}/hook", listener.local_addr()?);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = header.to_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok"
+            )
+            .unwrap();
+            sender
+                .send(serde_json::from_slice(&body).unwrap_or_default())
+                .ok();
+        }
+    });
+
+    Ok((url, receiver))
+}
+
+fn setup(workspace: &MoonflareTestWorkspace, webhook: &str) -> anyhow::Result<std::path::PathBuf> {
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "package.json",
+            r#"{"name": "api", "version": "1.4.0", "private": true}"#,
+        )],
+    )?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        format!("[deploy.notify]\nwebhook = \"{}\"\n", webhook),
+    )?;
+    Ok(workspace_path)
+}
+
+#[test]
+fn test_deploy_posts_summary_to_webhook() -> anyhow::Result<()> {
+    log("→ Deploy Posts Summary To Webhook");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (url, received) = mock_webhook()?;
+    let workspace_path = setup(&workspace, &url)?;
+    let path = fake_moon(&workspace_path, 0)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["deploy", "api", "--env", "staging"],
+        &[("PATH", &path), ("GITHUB_ACTOR", "octocat")],
+    )?;
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let summary = received.recv_timeout(Duration::from_secs(5))?;
+    assert_eq!(summary["success"], true);
+    assert_eq!(summary["environment"], "staging");
+    assert_eq!(summary["triggered_by"], "octocat");
+    assert_eq!(summary["projects"][0]["name"], "api");
+    assert_eq!(summary["projects"][0]["version"], "1.4.0");
+    let text = summary["text"].as_str().unwrap_or_default();
+    assert!(text.contains("api@1.4.0"), "{}", text);
+    assert!(text.contains("staging"), "{}", text);
+
+    Ok(())
+}
+
+#[test]
+fn test_deploy_failure_notifies_unless_disabled() -> anyhow::Result<()> {
+    log("→ Deploy Failure Notifies Unless Disabled");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (url, received) = mock_webhook()?;
+    let workspace_path = setup(&workspace, &url)?;
+    let path = fake_moon(&workspace_path, 1)?;
+
+    let output = workspace.run_with_env("test-project", &["deploy", "api"], &[("PATH", &path)])?;
+    assert!(!output.status.success());
+    let summary = received.recv_timeout(Duration::from_secs(5))?;
+    assert_eq!(summary["success"], false);
+    assert_eq!(summary["projects"][0]["success"], false);
+    assert!(
+        summary["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("Deploy of 'api' failed")
+    );
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["deploy", "api", "--no-notify"],
+        &[("PATH", &path)],
+    )?;
+    assert!(!output.status.success());
+    assert!(
+        received.recv_timeout(Duration::from_millis(500)).is_err(),
+        "--no-notify should not post"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` that reports like Wrangler and Moon do: a deploy entry in Wrangler's output
+/// file, and for `fail:deploy` a run report naming the failed task and nothing useful on stderr
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            r#"if [ "$2" = "fail:deploy" ]; then
+  mkdir -p .moon/cache
+  echo '{"actions":[{"label":"SyncProject(fail)","status":"passed"},{"label":"RunTask(fail:deploy)","status":"failed","error":"wrangler exited with 1: Authentication error [code: 10000]"}]}' > .moon/cache/runReport.json
+  echo "▪▪▪▪ fail:deploy (1s)" >&2
+  exit 1
+fi
+echo '{"type":"wrangler-session","version":1,"wrangler_version":"4.0.0"}' >> "$WRANGLER_OUTPUT_FILE_PATH"
+echo '{"type":"deploy","version":1,"worker_name":"api","version_id":"v-123","targets":["https://api.acme.workers.dev"],"some_new_field":true}' >> "$WRANGLER_OUTPUT_FILE_PATH"
+exit 0
+"#,
+        )],
+    )
+}
+
+#[test]
+fn test_deploy_records_what_wrangler_reports() -> anyhow::Result<()> {
+    log("→ Deploy Records What Wrangler Reports");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\n")],
+    )?;
+    let path = fake_moon(workspace.path())?;
+
+    let output = workspace.run_with_env("shop", &["deploy", "api"], &[("PATH", path.as_str())])?;
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("https://api.acme.workers.dev"),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join(".moonflare/deployments.json"),
+    )?)?;
+    let record = &manifest["environments"]["default"]["api"];
+    assert_eq!(record["version_id"], "v-123");
+    assert_eq!(record["urls"][0], "https://api.acme.workers.dev");
+
+    Ok(())
+}
+
+#[test]
+fn test_failed_deploy_reports_the_error_from_moons_run_report() -> anyhow::Result<()> {
+    log("→ Failed Deploy Reports The Error From Moon's Run Report");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "fail",
+        &[("wrangler.toml", "name = \"fail\"\n")],
+    )?;
+    let path = fake_moon(workspace.path())?;
+
+    let output = workspace.run_with_env("shop", &["deploy", "fail"], &[("PATH", path.as_str())])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("fail:deploy failed"), "{}", stderr);
+    assert!(stderr.contains("Authentication error"), "{}", stderr);
+
+    Ok(())
+}
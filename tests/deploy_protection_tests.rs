@@ -0,0 +1,108 @@
+use common::*;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+const PROTECTED_CONFIG: &str = r#"[deploy.environments.production]
+protected = true
+require_clean = true
+
+[deploy.environments.staging]
+require_clean = true
+"#;
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<()> {
+    let status = Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()?
+        .status;
+    anyhow::ensure!(status.success(), "git {} failed", args.join(" "));
+    Ok(())
+}
+
+#[test]
+fn test_protected_environment_requires_confirmation() -> anyhow::Result<()> {
+    log("→ Protected Environment Requires Confirmation");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    std::fs::write(workspace_path.join("moonflare.toml"), PROTECTED_CONFIG)?;
+
+    let output = workspace.run("test-project", &["deploy", "--env", "production"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("is a protected environment"),
+        "Should ask for confirmation: {}",
+        stderr
+    );
+
+    let output = workspace.run(
+        "test-project",
+        &["deploy", "--env", "production", "--confirm", "staging"],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("doesn't match"));
+
+    // Rules without `protected` don't ask for confirmation
+    let output = workspace.run("test-project", &["deploy", "--env", "staging"])?;
+    assert!(
+        output.status.success(),
+        "Unprotected deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_protected_environment_applies_clean_tree_gate() -> anyhow::Result<()> {
+    log("→ Protected Environment Applies Clean Tree Gate");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    std::fs::write(workspace_path.join("moonflare.toml"), PROTECTED_CONFIG)?;
+    let project_path = workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\n")],
+    )?;
+
+    git(&workspace_path, &["init", "-q"])?;
+    git(&workspace_path, &["add", "-A"])?;
+    git(&workspace_path, &["commit", "-q", "-m", "initial"])?;
+    std::fs::write(project_path.join("index.ts"), "export default {};\n")?;
+
+    let output = workspace.run(
+        "test-project",
+        &["deploy", "--env", "production", "--confirm", "production"],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("uncommitted changes"),
+        "Protection should require a clean tree: {}",
+        stderr
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_watch_refuses_protected_environment() -> anyhow::Result<()> {
+    log("→ Watch Refuses Protected Environment");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    std::fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[deploy.environments.live]\nprotected = true\n",
+    )?;
+
+    let output = workspace.run("test-project", &["deploy", "--watch", "--env", "live"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unprotected"));
+
+    Ok(())
+}
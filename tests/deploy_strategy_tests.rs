@@ -0,0 +1,80 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` whose builds write sites/docs/dist and apps/web/dist, and fake `pnpm` logging every wrangler call
+#[cfg(unix)]
+fn fake_build_tools(dir: &Path) -> anyhow::Result<String> {
+    let moon = r#"if [ "$2" = docs:build ]; then
+  rm -rf sites/docs/dist
+  mkdir -p sites/docs/dist/_astro
+  echo '<h1>Docs</h1>' > sites/docs/dist/index.html
+  echo 'console.log(1)' > sites/docs/dist/_astro/app.AbCd1234.js
+elif [ "$2" = web:build ]; then
+  mkdir -p apps/web/dist
+  echo '<div id="root"></div>' > apps/web/dist/index.html
+fi
+exit 0
+"#;
+    let pnpm = "echo \"$*\" >> \"$PNPM_LOG\"\nexit 0\n";
+    fake_tools(dir, &[("moon", moon), ("pnpm", pnpm)])
+}
+
+#[cfg(unix)]
+#[test]
+fn test_custom_and_pages_strategies() -> anyhow::Result<()> {
+    log("→ Custom And Pages Strategies");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project("shop", &ProjectType::Astro, "docs", &[])?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[("wrangler.jsonc", r#"{ "name": "shop-web" }"#)],
+    )?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        r#"[projects.docs.deploy]
+strategy = "custom:echo \"$MOONFLARE_PROJECT $WRANGLER_ENV $(ls $MOONFLARE_OUTPUT_DIR | head -n1)\" > ../../custom.txt"
+
+[projects.web.deploy]
+strategy = "pages"
+"#,
+    )?;
+    let path = fake_build_tools(workspace.path())?;
+    let log_path = workspace_path.join("pnpm.log");
+    let log_file = log_path.to_string_lossy().to_string();
+    let env = [("PATH", path.as_str()), ("PNPM_LOG", log_file.as_str())];
+
+    // Deploying everything includes docs, which has no wrangler config but a strategy
+    let output = workspace.run_with_env("shop", &["deploy", "--env", "preview"], &env)?;
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(workspace_path.join("custom.txt"))?.trim(),
+        "docs preview _astro"
+    );
+    let calls = fs::read_to_string(&log_path)?;
+    assert!(calls.contains("exec wrangler pages deploy "), "{}", calls);
+    assert!(
+        calls.contains("--project-name shop-web --branch preview"),
+        "{}",
+        calls
+    );
+
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[projects.docs.deploy]\nstrategy = \"ftp\"\n",
+    )?;
+    let output = workspace.run_with_env("shop", &["deploy", "docs"], &env)?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid moonflare.toml"));
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` saving the Wrangler config the deploy script would be pointed at
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            "cat \"$PROJECT_DIR/$WRANGLER_CONFIG\" > \"$CAPTURE\"\nexit 0\n",
+        )],
+    )
+}
+
+#[test]
+fn test_vars_file_sets_worker_vars_over_wrangler_config() -> anyhow::Result<()> {
+    log("→ Vars File Sets Worker Vars Over Wrangler Config");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let project_path = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.toml",
+            "name = \"api\"\n\n[env.staging.vars]\nLOG_LEVEL = \"debug\"\nFEATURE = \"on\"\n",
+        )],
+    )?;
+    fs::write(
+        workspace_path.join("staging.env"),
+        "# CI settings\nLOG_LEVEL=warn\nAPI_URL=\"https://api.staging.example.com\"\n",
+    )?;
+
+    let path = fake_moon(workspace.path())?;
+    let capture = workspace.path().join("config.json");
+    let project_dir = project_path.to_string_lossy().to_string();
+    let capture_file = capture.to_string_lossy().to_string();
+    let env = [
+        ("PATH", path.as_str()),
+        ("PROJECT_DIR", project_dir.as_str()),
+        ("CAPTURE", capture_file.as_str()),
+    ];
+    let output = workspace.run_with_env(
+        "shop",
+        &[
+            "deploy",
+            "api",
+            "--env",
+            "staging",
+            "--vars-file",
+            "staging.env",
+        ],
+        &env,
+    )?;
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("API_URL, LOG_LEVEL"), "{}", stdout);
+    assert!(stdout.contains("replaces"), "{}", stdout);
+    assert!(!stdout.contains("api.staging.example.com"), "{}", stdout);
+
+    let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(&capture)?)?;
+    let vars = &config["env"]["staging"]["vars"];
+    assert_eq!(vars["LOG_LEVEL"], "warn");
+    assert_eq!(vars["API_URL"], "https://api.staging.example.com");
+    assert_eq!(vars["FEATURE"], "on");
+    assert!(config["vars"].is_null());
+    // The merged config only exists for the deploy
+    assert!(!project_path.join(".wrangler.effective.json").exists());
+
+    // Credentials don't belong in plain-text vars
+    fs::write(workspace_path.join("bad.env"), "STRIPE_API_KEY=sk_live_1\n")?;
+    let output =
+        workspace.run_with_env("shop", &["deploy", "api", "--vars-file", "bad.env"], &env)?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("looks like a secret"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+mod common;
+
+#[test]
+fn test_watch_requires_environment() -> anyhow::Result<()> {
+    log("→ Watch Requires Environment");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["deploy", "--watch"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("staging environment"));
+
+    Ok(())
+}
+
+#[test]
+fn test_watch_refuses_production() -> anyhow::Result<()> {
+    log("→ Watch Refuses Production");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run(
+        "test-project",
+        &["deploy", "--watch", "--env", "production"],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("non-production"));
+
+    Ok(())
+}
+
+#[test]
+fn test_watch_rejects_auto_env_and_require_clean() -> anyhow::Result<()> {
+    log("→ Watch Rejects Auto Env And Require Clean");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+
+    for flag in ["--auto-env", "--require-clean"] {
+        let output = workspace.run(
+            "test-project",
+            &["deploy", "--watch", "--env", "staging", flag],
+        )?;
+        assert!(!output.status.success(), "{} was accepted", flag);
+        assert!(
+            String::from_utf8_lossy(&output.stderr).contains("cannot be used with"),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_watch_redeploys_changed_project() -> anyhow::Result<()> {
+    log("→ Watch Redeploys Changed Project");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    for name in ["api", "admin"] {
+        workspace.create_minimal_project(
+            "test-project",
+            &ProjectType::DurableObject,
+            name,
+            &[("wrangler.toml", &format!("name = \"{}\"\n", name))],
+        )?;
+    }
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[deploy.watch]\nenvironment = \"staging\"\ndebounce_ms = 100\n",
+    )?;
+
+    // Fake moon that logs each target it is asked to run; queries fail, so the workspace is
+    // scanned for projects instead
+    let log_path = workspace_path.join("moon.log");
+    let path = fake_tools(
+        &workspace_path,
+        &[(
+            "moon",
+            &format!(
+                "[ \"$1\" = query ] && exit 1\necho \"$2 $WRANGLER_ENV\" >> {}\n",
+                log_path.display()
+            ),
+        )],
+    )?;
+
+    let child = Command::new(workspace.moonflare_binary())
+        .args(["deploy", "--watch"])
+        .env("PATH", &path)
+        .current_dir(&workspace_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    let wait_for_lines = |count: usize| -> Vec<String> {
+        let deadline = Instant::now() + Duration::from_secs(8);
+        loop {
+            let lines: Vec<String> = fs::read_to_string(&log_path)
+                .unwrap_or_default()
+                .lines()
+                .map(String::from)
+                .collect();
+            if lines.len() >= count || Instant::now() > deadline {
+                return lines;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    };
+
+    let initial = wait_for_lines(2);
+    // Let the watcher take its baseline before editing
+    std::thread::sleep(Duration::from_millis(700));
+    fs::write(
+        workspace_path.join("workers/api/index.ts"),
+        "export default {};\n",
+    )?;
+    let after_change = wait_for_lines(3);
+    // Ctrl-C ends the watch loop rather than cancelling the command
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    let output = child.wait_with_output()?;
+    assert!(output.status.success(), "{:?}", output.status);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Stopped watching"));
+
+    assert_eq!(
+        initial,
+        vec!["admin:deploy staging", "api:deploy staging"],
+        "Initial deploy covers every project"
+    );
+    assert_eq!(
+        after_change.get(2).map(String::as_str),
+        Some("api:deploy staging"),
+        "Only the changed project is redeployed: {:?}",
+        after_change
+    );
+    assert_eq!(after_change.len(), 3);
+
+    Ok(())
+}
@@ -252,7 +252,7 @@ fn verify_build_outputs(workspace_path: &std::path::Path) -> anyhow::Result<()>
                     .unwrap()
                     .path()
                     .extension()
-                    .map_or(false, |ext| ext == "js")
+                    .is_some_and(|ext| ext == "js")
             }),
             "React should generate JS files"
         );
@@ -267,7 +267,7 @@ fn verify_build_outputs(workspace_path: &std::path::Path) -> anyhow::Result<()>
                     .unwrap()
                     .path()
                     .extension()
-                    .map_or(false, |ext| ext == "js")
+                    .is_some_and(|ext| ext == "js")
             }),
             "Durable Object should generate JS files"
         );
@@ -301,25 +301,27 @@ fn deploy_all_projects(
         );
     }
 
-    // Parse deployment URLs from output
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Wrangler reports where each Worker is served; deploy records it in the manifest
+    let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        workspace_path.join(".moonflare/deployments.json"),
+    )?)?;
     let mut deployed_urls = Vec::new();
-
-    for line in stdout.lines() {
-        if line.trim().starts_with("https://") && line.contains(".workers.dev") {
-            let url = line.trim().to_string();
-            // Extract project name from URL
-            if let Some(project_name) = url
-                .strip_prefix("https://")
-                .and_then(|s| s.split('.').next())
-            {
-                deployed_urls.push((project_name.to_string(), url));
+    if let Some(projects) = manifest["environments"]["default"].as_object() {
+        for (project_name, record) in projects {
+            let url = record["urls"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|url| url.as_str())
+                .find(|url| url.contains(".workers.dev"));
+            if let Some(url) = url {
+                deployed_urls.push((project_name.clone(), url.to_string()));
             }
         }
     }
 
     if deployed_urls.is_empty() {
-        anyhow::bail!("No deployment URLs found in output");
+        anyhow::bail!("No deployment URLs recorded in the deployment manifest");
     }
 
     log(&format!(
@@ -0,0 +1,127 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_add_documents_project_in_readme() -> anyhow::Result<()> {
+    log("→ Add Documents Project In README");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run("shop", &["add", "durable-object", "api"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let readme = fs::read_to_string(workspace_path.join("workers/api/README.md"))?;
+    assert!(readme.starts_with("# api\n"), "{}", readme);
+    assert!(readme.contains("<!-- moonflare:describe:start -->"));
+    assert!(readme.contains("`moonflare dev api`"), "{}", readme);
+    assert!(readme.contains("| `api:build` |"), "{}", readme);
+    assert!(
+        readme.contains("| `API_DURABLE_OBJECT` | Durable Object | ApiDurableObject |"),
+        "{}",
+        readme
+    );
+    assert!(readme.contains("`moonflare deploy api`"), "{}", readme);
+
+    Ok(())
+}
+
+#[test]
+fn test_describe_write_refreshes_generated_section() -> anyhow::Result<()> {
+    log("→ Describe Write Refreshes Generated Section");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            (
+                "wrangler.jsonc",
+                r#"{
+  "name": "shop-api",
+  "vars": { "LOG_LEVEL": "info" },
+  "kv_namespaces": [{ "binding": "CACHE", "id": "abc123" }],
+  "env": { "staging": { "name": "shop-api-staging" } }
+}"#,
+            ),
+            (
+                "moon.yml",
+                "language: 'typescript'\ntasks:\n  migrate:\n    command: 'pnpm'\n    args: ['run', 'migrate']\n",
+            ),
+            (
+                "src/index.ts",
+                "export default { fetch(req, env) { return new Response(env.API_TOKEN); } };\n",
+            ),
+        ],
+    )?;
+    let readme_path = workspace_path.join("workers/api/README.md");
+    fs::write(&readme_path, "# API\n\nHand-written intro.\n")?;
+
+    // Printing leaves the README alone
+    let output = workspace.run("shop", &["describe", "--project", "api"])?;
+    assert!(
+        output.status.success(),
+        "Describe should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("| `CACHE` | KV namespace | abc123 |"),
+        "{}",
+        stdout
+    );
+    assert_eq!(
+        fs::read_to_string(&readme_path)?,
+        "# API\n\nHand-written intro.\n"
+    );
+
+    let output = workspace.run("shop", &["describe", "--project", "api", "--write"])?;
+    assert!(
+        output.status.success(),
+        "Describe should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let readme = fs::read_to_string(&readme_path)?;
+    assert!(
+        readme.starts_with("# API\n\nHand-written intro.\n"),
+        "{}",
+        readme
+    );
+    assert!(
+        readme.contains("| `api:migrate` | `pnpm run migrate` | moon.yml |"),
+        "{}",
+        readme
+    );
+    assert!(readme.contains("| `LOG_LEVEL` | `info` |"), "{}", readme);
+    assert!(readme.contains("| `API_TOKEN` | secret |"), "{}", readme);
+    assert!(
+        readme.contains("| staging | `shop-api-staging` | `moonflare deploy api --env staging` |"),
+        "{}",
+        readme
+    );
+
+    // A binding added by hand replaces the section rather than adding a second one
+    fs::write(
+        workspace_path.join("workers/api/wrangler.jsonc"),
+        r#"{ "name": "shop-api", "r2_buckets": [{ "binding": "UPLOADS", "bucket_name": "uploads" }] }"#,
+    )?;
+    fs::write(&readme_path, readme + "\nFooter.\n")?;
+    let output = workspace.run("shop", &["describe", "--write"])?;
+    assert!(output.status.success());
+    let readme = fs::read_to_string(&readme_path)?;
+    assert_eq!(readme.matches("moonflare:describe:start").count(), 1);
+    assert!(
+        readme.contains("| `UPLOADS` | R2 bucket | uploads |"),
+        "{}",
+        readme
+    );
+    assert!(!readme.contains("CACHE"), "{}", readme);
+    assert!(readme.contains("Hand-written intro.") && readme.ends_with("Footer.\n"));
+
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const DEV_TASK: &str = "tasks:\n  dev:\n    command: 'pnpm'\n    args: ['dev']\n";
+
+#[test]
+fn test_chaos_rejects_invalid_settings() -> anyhow::Result<()> {
+    log("→ Chaos Rejects Invalid Settings");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.jsonc", r#"{ "name": "api" }"#),
+            ("moon.yml", DEV_TASK),
+        ],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "auth",
+        &[
+            ("wrangler.jsonc", r#"{ "name": "auth" }"#),
+            ("moon.yml", DEV_TASK),
+        ],
+    )?;
+
+    // Both Workers would listen on wrangler's default port
+    let output = workspace.run("shop", &["dev", "--chaos"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("both run their dev server"), "{}", stderr);
+
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[projects.auth.dev.chaos]\nenabled = false\n\n[projects.api.dev.chaos]\nerror_rate = 2.0\n",
+    )?;
+    let output = workspace.run("shop", &["dev", "--chaos"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Invalid chaos settings for 'api': error_rate"),
+        "{}",
+        stderr
+    );
+
+    let output = workspace.run("shop", &["dev", "--chaos", "--https"])?;
+    assert!(!output.status.success());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_chaos_proxy_fronts_worker_dev_server() -> anyhow::Result<()> {
+    log("→ Chaos Proxy Fronts Worker Dev Server");
+    if std::process::Command::new("node")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        log("Skipping: node isn't installed");
+        return Ok(());
+    }
+
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let port = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .port();
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.jsonc",
+            &format!(r#"{{ "name": "api", "dev": {{ "port": {} }} }}"#, port),
+        )],
+    )?;
+
+    // Stands in for `wrangler dev` on the port moonflare passes, and for a frontend calling
+    // the Worker's usual port
+    fs::write(
+        workspace_path.join("dev.mjs"),
+        r#"import { createServer } from 'node:http';
+import { writeFileSync } from 'node:fs';
+
+const [target, proxy] = process.argv.slice(2);
+const server = createServer((_, res) => res.end('from api')).listen(Number(target), '127.0.0.1');
+await new Promise((resolve) => server.on('listening', resolve));
+const results = [];
+for (let i = 0; i < 3; i++) {
+  const response = await fetch(`http://127.0.0.1:${proxy}/users`);
+  results.push(`${response.status} ${response.headers.get('x-moonflare-chaos') ?? '-'} ${(await response.text()).trim()}`);
+}
+writeFileSync('results.txt', results.join('\n'));
+server.close();
+"#,
+    )?;
+    let path = fake_tools(
+        workspace.path(),
+        &[(
+            "moon",
+            &format!(
+                "echo \"$@\" > moon.log\nwhile [ \"$1\" != --port ]; do shift; done\nexec node dev.mjs \"$2\" {}\n",
+                port
+            ),
+        )],
+    )?;
+
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[dev.chaos]\nlatency_ms = 0\njitter_ms = 0\nerror_rate = 0.0\ndrop_rate = 0.0\n",
+    )?;
+    let output = workspace.run_with_env("shop", &["dev", "api", "--chaos"], &[("PATH", &path)])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Dev should succeed: {}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains(&format!("api on localhost:{}", port)),
+        "{}",
+        stdout
+    );
+    let args = fs::read_to_string(workspace_path.join("moon.log"))?;
+    assert!(
+        args.starts_with("run api:dev -- --ip 127.0.0.1 --port "),
+        "{}",
+        args
+    );
+    let results = fs::read_to_string(workspace_path.join("results.txt"))?;
+    assert_eq!(results, "200 - from api\n200 - from api\n200 - from api");
+
+    // Project settings override the workspace's
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[dev.chaos]\nlatency_ms = 0\njitter_ms = 0\ndrop_rate = 0.0\n\n[projects.api.dev.chaos]\nerror_rate = 1.0\nerror_status = 502\n",
+    )?;
+    let output = workspace.run_with_env("shop", &["dev", "api", "--chaos"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Dev should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let results = fs::read_to_string(workspace_path.join("results.txt"))?;
+    assert!(
+        results.starts_with("502 error moonflare dev --chaos: injected 502 for api"),
+        "{}",
+        results
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("chaos api: 502 GET /users"));
+
+    Ok(())
+}
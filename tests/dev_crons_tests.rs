@@ -0,0 +1,140 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+mod common;
+
+/// Stands in for `wrangler dev --test-scheduled`, recording the request line of every call
+fn scheduled_endpoint() -> anyhow::Result<(u16, Arc<Mutex<Vec<String>>>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let port = listener.local_addr()?.port();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+            }
+            recorded
+                .lock()
+                .unwrap()
+                .push(request_line.trim().to_string());
+            let body = "Ran scheduled event";
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        }
+    });
+    Ok((port, requests))
+}
+
+#[test]
+fn test_dev_fast_forward_triggers_cron_schedules() -> anyhow::Result<()> {
+    log("→ Dev Fast Forward Triggers Cron Schedules");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let (port, requests) = scheduled_endpoint()?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.jsonc",
+            &format!(
+                r#"{{ "name": "api", "dev": {{ "port": {} }}, "triggers": {{ "crons": ["0 * * * *", "bogus"] }} }}"#,
+                port
+            ),
+        )],
+    )?;
+
+    // The dev server runs for three seconds, three hours on a clock 3600 times faster
+    let path = fake_tools(
+        workspace.path(),
+        &[("moon", "echo \"$@\" > moon.log\nsleep 3\n")],
+    )?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["dev", "api", "--fast-forward", "3600"],
+        &[("PATH", &path)],
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Dev should succeed: {}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Skipping cron 'bogus' of 'api'"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("3600x real time"), "{}", stdout);
+    assert!(
+        stdout.contains("cron api '0 * * * *' at ") && stdout.contains(":00 UTC: 200"),
+        "{}",
+        stdout
+    );
+
+    let args = fs::read_to_string(workspace_path.join("moon.log"))?;
+    assert!(args.contains("--test-scheduled"), "{}", args);
+
+    let requests = requests.lock().unwrap();
+    assert!(requests.len() >= 2, "{:?}", requests);
+    for request in requests.iter() {
+        let time: u64 = request
+            .strip_prefix("GET /__scheduled?cron=0+*+*+*+*&time=")
+            .and_then(|rest| rest.strip_suffix(" HTTP/1.1"))
+            .and_then(|time| time.parse().ok())
+            .unwrap_or_else(|| panic!("Unexpected request: {}", request));
+        assert_eq!(time % 3_600_000, 0, "Runs are on the hour: {}", request);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_dev_crons_without_triggers_has_no_effect() -> anyhow::Result<()> {
+    log("→ Dev Crons Without Triggers Has No Effect");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", r#"{ "name": "api" }"#)],
+    )?;
+    let path = fake_tools(workspace.path(), &[("moon", "echo \"$@\" > moon.log\n")])?;
+
+    let output = workspace.run_with_env("shop", &["dev", "api", "--crons"], &[("PATH", &path)])?;
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("--crons has no effect"),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let args = fs::read_to_string(workspace_path.join("moon.log"))?;
+    assert!(!args.contains("--test-scheduled"), "{}", args);
+
+    let output = workspace.run("shop", &["dev", "api", "--fast-forward", "0"])?;
+    assert!(!output.status.success());
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const DEV_TASK: &str = "tasks:\n  dev:\n    command: 'pnpm'\n    args: ['dev']\n";
+
+const WORKER_SOURCE: &str = r#"import { Hono } from "hono";
+
+const app = new Hono();
+app.get("/users/:id", (c) => c.json({ id: c.req.param("id") }));
+app.post('/orders', (c) => c.json({}, 201));
+
+export default {
+  async fetch(request: Request): Promise<Response> {
+    const url = new URL(request.url);
+    switch (url.pathname) {
+      case "/":
+        return new Response("Hello");
+      case "/increment":
+        return new Response("Count: 1");
+    }
+    return app.fetch(request);
+  },
+};
+"#;
+
+#[cfg(unix)]
+#[test]
+fn test_mock_api_serves_routes_from_worker_sources() -> anyhow::Result<()> {
+    log("→ Mock API Serves Routes From Worker Sources");
+    if std::process::Command::new("node")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        log("Skipping: node isn't installed");
+        return Ok(());
+    }
+
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let port = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .port();
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            (
+                "wrangler.jsonc",
+                &format!(r#"{{ "name": "api", "dev": {{ "port": {} }} }}"#, port),
+            ),
+            ("src/index.ts", WORKER_SOURCE),
+            ("moon.yml", DEV_TASK),
+        ],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[("moon.yml", DEV_TASK)],
+    )?;
+
+    // Stands in for the frontend's dev server, calling the Worker's usual port
+    fs::write(
+        workspace_path.join("frontend.mjs"),
+        r#"import { writeFileSync } from 'node:fs';
+
+const port = process.argv[2];
+const results = [];
+for (const [method, path] of [['GET', '/users/7'], ['POST', '/orders'], ['GET', '/increment'], ['DELETE', '/orders']]) {
+  const response = await fetch(`http://127.0.0.1:${port}${path}`, { method });
+  results.push(`${method} ${path} ${response.status} ${response.headers.get('access-control-allow-origin')} ${await response.text()}`);
+}
+writeFileSync('results.txt', results.join('\n'));
+"#,
+    )?;
+    let path = fake_tools(
+        workspace.path(),
+        &[(
+            "moon",
+            &format!("echo \"$@\" > moon.log\nexec node frontend.mjs {}\n", port),
+        )],
+    )?;
+
+    let output =
+        workspace.run_with_env("shop", &["dev", "web", "--mock-api"], &[("PATH", &path)])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Dev should succeed: {}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Added 4 route(s)"), "{}", stdout);
+    assert_eq!(
+        fs::read_to_string(workspace_path.join("moon.log"))?.trim(),
+        "run web:dev"
+    );
+
+    let mock_file = workspace_path.join("workers/api/mock-api.json");
+    let mut mocks: serde_json::Value = serde_json::from_str(&fs::read_to_string(&mock_file)?)?;
+    let routes: Vec<String> = mocks["routes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|route| format!("{} {}", route["method"], route["path"]).replace('"', ""))
+        .collect();
+    assert_eq!(
+        routes,
+        vec!["GET /users/:id", "POST /orders", "* /", "* /increment"]
+    );
+
+    let results = fs::read_to_string(workspace_path.join("results.txt"))?;
+    let lines: Vec<&str> = results.lines().collect();
+    assert_eq!(
+        lines[0],
+        r#"GET /users/7 200 * {"mock":true,"route":"GET /users/:id"}"#
+    );
+    assert!(lines[3].starts_with("DELETE /orders 404 *"), "{}", results);
+
+    // Edited responses are kept on the next run
+    mocks["routes"][0]["body"] = serde_json::json!({ "id": "7", "name": "Ada" });
+    mocks["routes"][3]["status"] = serde_json::json!(500);
+    mocks["routes"][3]["body"] = serde_json::json!("boom");
+    fs::write(&mock_file, serde_json::to_string_pretty(&mocks)?)?;
+    let output =
+        workspace.run_with_env("shop", &["dev", "web", "--mock-api"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Dev should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Added"));
+    let results = fs::read_to_string(workspace_path.join("results.txt"))?;
+    let lines: Vec<&str> = results.lines().collect();
+    assert_eq!(lines[0], r#"GET /users/7 200 * {"id":"7","name":"Ada"}"#);
+    assert_eq!(lines[2], "GET /increment 500 * boom");
+
+    let output =
+        workspace.run_with_env("shop", &["dev", "api", "--mock-api"], &[("PATH", &path)])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("replaces Workers"));
+
+    Ok(())
+}
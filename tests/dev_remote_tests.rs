@@ -0,0 +1,129 @@
+use common::*;
+
+mod common;
+
+const DEV_TASK: &str = "tasks:\n  dev:\n    command: 'pnpm'\n    args: ['dev']\n";
+
+#[test]
+fn test_remote_selects_worker_projects() -> anyhow::Result<()> {
+    log("→ Remote Selects Worker Projects");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("moon.yml", DEV_TASK)],
+    )?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::Astro,
+        "site",
+        &[("moon.yml", DEV_TASK)],
+    )?;
+
+    let output = workspace.run("test-project", &["dev", "--remote"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("(api using remote Cloudflare resources)"),
+        "Only the worker should run remotely: {}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_remote_config_override() -> anyhow::Result<()> {
+    log("→ Remote Config Override");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("moon.yml", DEV_TASK)],
+    )?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "renderer",
+        &[("moon.yml", DEV_TASK)],
+    )?;
+    std::fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[projects.renderer.dev]\nremote = true\n",
+    )?;
+
+    // Without --remote, only the project configured as remote uses remote resources
+    let output = workspace.run("test-project", &["dev"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("(renderer using remote Cloudflare resources)"),
+        "Configured project should run remotely: {}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_remote_without_workers_warns() -> anyhow::Result<()> {
+    log("→ Remote Without Workers Warns");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "frontend",
+        &[("moon.yml", DEV_TASK)],
+    )?;
+
+    let output = workspace.run("test-project", &["dev", "--remote"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("--remote has no effect"),
+        "Should explain that no workers were selected: {}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_https_generates_local_certificates() -> anyhow::Result<()> {
+    log("→ HTTPS Generates Local Certificates");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("moon.yml", DEV_TASK)],
+    )?;
+
+    // Keep the generated CA out of the real home directory
+    let home = workspace.path().join("home");
+    std::fs::create_dir_all(&home)?;
+    let home = home.to_string_lossy().to_string();
+
+    let output = workspace.run_with_env("test-project", &["dev", "--https"], &[("HOME", &home)])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Generated a new local certificate authority"),
+        "First run should create a CA: {}",
+        stdout
+    );
+
+    let certs = std::path::Path::new(&home).join(".moonflare/certs");
+    for file in ["ca.pem", "ca-key.pem", "localhost.pem", "localhost-key.pem"] {
+        assert!(certs.join(file).exists(), "{} should be generated", file);
+    }
+
+    // The CA is reused on later runs
+    let output = workspace.run_with_env("test-project", &["dev", "--https"], &[("HOME", &home)])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Generated a new local certificate authority"));
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::thread;
+
+mod common;
+
+const DEV_TASK: &str = "tasks:\n  dev:\n    command: 'pnpm'\n    args: ['dev']\n";
+
+/// Fake `moon` that logs the target it runs and stays up for `seconds` like a dev server
+fn fake_moon(dir: &Path, seconds: f32) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "echo \"$2\" >> {}\nsleep {}\n",
+                dir.join("moon.log").display(),
+                seconds
+            ),
+        )],
+    )
+}
+
+/// Health endpoint answering every request with 200
+fn healthy_server() -> anyhow::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}/health", listener.local_addr()?);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+                    break;
+                }
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok"
+            )
+            .ok();
+        }
+    });
+    Ok(url)
+}
+
+fn create_projects(workspace: &MoonflareTestWorkspace) -> anyhow::Result<()> {
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.jsonc", r#"{ "name": "api" }"#),
+            ("moon.yml", DEV_TASK),
+        ],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::Astro,
+        "web",
+        &[("moon.yml", DEV_TASK)],
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_serial_dev_waits_for_readiness_in_configured_order() -> anyhow::Result<()> {
+    log("→ Serial Dev Waits For Readiness In Configured Order");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    create_projects(&workspace)?;
+    let health = healthy_server()?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        format!(
+            "[dev]\norder = [\"web\"]\n\n[projects.web.dev]\nready = \"{}\"\n",
+            health
+        ),
+    )?;
+    let path = fake_moon(&workspace_path, 0.5)?;
+
+    let output =
+        workspace.run_with_env("shop", &["dev", "--serial"], &[("PATH", path.as_str())])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Dev should succeed: {}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("web → api"), "{}", stdout);
+    assert!(stdout.contains("'web' is ready"), "{}", stdout);
+    let log = fs::read_to_string(workspace_path.join("moon.log"))?;
+    assert_eq!(log, "web:dev\napi:dev\n");
+
+    let output = workspace.run("shop", &["dev", "--max-parallel", "0"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("at least 1"));
+
+    Ok(())
+}
+
+#[test]
+fn test_staged_dev_moves_on_when_a_server_isnt_ready() -> anyhow::Result<()> {
+    log("→ Staged Dev Moves On When A Server Isn't Ready");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    create_projects(&workspace)?;
+    let closed = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        format!(
+            "[dev]\nmax_parallel = 1\nready_timeout_secs = 1\n\n[projects.api.dev]\nready = \"http://127.0.0.1:{}/\"\n",
+            closed
+        ),
+    )?;
+    let path = fake_moon(&workspace_path, 2.0)?;
+
+    // Workers start before frontends without an order
+    let output = workspace.run_with_env("shop", &["dev"], &[("PATH", path.as_str())])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("'api' isn't ready after 1s"), "{}", stdout);
+    let log = fs::read_to_string(workspace_path.join("moon.log"))?;
+    assert_eq!(log, "api:dev\nweb:dev\n");
+
+    Ok(())
+}
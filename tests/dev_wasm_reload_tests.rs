@@ -0,0 +1,70 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+
+mod common;
+
+const DEV_TASK: &str = "tasks:\n  dev:\n    command: 'pnpm'\n    args: ['dev']\n";
+
+#[test]
+fn test_dev_rebuilds_wasm_and_signals_vite() -> anyhow::Result<()> {
+    log("→ Dev Rebuilds WASM And Signals Vite");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "web",
+        &[
+            ("moon.yml", DEV_TASK),
+            ("moonflare-wasm-reload.mjs", "// outdated plugin\n"),
+        ],
+    )?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::Crate,
+        "core",
+        &[("src/lib.rs", "pub fn answer() -> u32 { 42 }\n")],
+    )?;
+
+    // Fake moon: gathering writes a module; the dev server edits the crate, then waits for
+    // the reload signal and fails if it never arrives
+    let path = fake_tools(
+        &workspace_path,
+        &[(
+            "moon",
+            r#"case "$2" in
+  shared-wasm:gather)
+    mkdir -p shared-wasm && echo built > shared-wasm/core.wasm ;;
+  web:dev)
+    sleep 1
+    echo "// edited" >> crates/core/src/lib.rs
+    i=0
+    while [ ! -f "$MOONFLARE_WASM_SIGNAL" ] && [ $i -lt 100 ]; do sleep 0.1; i=$((i+1)); done
+    [ -f "$MOONFLARE_WASM_SIGNAL" ] ;;
+esac
+"#,
+        )],
+    )?;
+
+    let output = workspace.run_with_env("test-project", &["dev", "web"], &[("PATH", &path)])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Dev server should see the signal: {}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("rebuilding WASM"), "{}", stdout);
+    assert!(workspace_path.join(".moonflare/wasm-reload").exists());
+
+    let plugin = fs::read_to_string(workspace_path.join("apps/web/moonflare-wasm-reload.mjs"))?;
+    assert!(
+        plugin.contains("MOONFLARE_WASM_SIGNAL"),
+        "Dev should refresh the plugin: {}",
+        plugin
+    );
+
+    Ok(())
+}
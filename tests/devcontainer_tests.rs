@@ -0,0 +1,108 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_devcontainer_generate_pins_workspace_toolchain() -> anyhow::Result<()> {
+    log("→ Devcontainer Generate Pins Workspace Toolchain");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.jsonc",
+            r#"{ "name": "api", "dev": { "port": 8790 } }"#,
+        )],
+    )?;
+    fs::write(
+        workspace_path.join(".prototools"),
+        "node = \"20.11.1\"\npnpm = \"9.1.0\"\nmoon = \"1.29.0\"\n",
+    )?;
+    fs::write(
+        workspace_path.join("rust-toolchain.toml"),
+        "[toolchain]\nchannel = \"1.80.0\"\n",
+    )?;
+
+    let output = workspace.run("shop", &["devcontainer", "generate"])?;
+    assert!(
+        output.status.success(),
+        "Devcontainer generate should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let dockerfile = fs::read_to_string(workspace_path.join(".devcontainer/Dockerfile"))?;
+    assert!(
+        dockerfile.contains("ARG NODE_VERSION=20.11.1"),
+        "{}",
+        dockerfile
+    );
+    assert!(dockerfile.contains("ARG PNPM_VERSION=9.1.0"));
+    assert!(dockerfile.contains("ARG MOON_VERSION=1.29.0"));
+    assert!(dockerfile.contains("ARG RUST_VERSION=1.80.0"));
+    assert!(dockerfile.contains("wasm32-unknown-unknown"));
+    let config = fs::read_to_string(workspace_path.join(".devcontainer/devcontainer.json"))?;
+    assert!(config.contains("\"forwardPorts\": [8790]"), "{}", config);
+
+    let output = workspace.run("shop", &["devcontainer", "generate"])?;
+    assert!(
+        !output.status.success(),
+        "Existing devcontainer should block"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+
+    Ok(())
+}
+
+#[test]
+fn test_doctor_regenerates_stale_devcontainer() -> anyhow::Result<()> {
+    log("→ Doctor Regenerates Stale Devcontainer");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(workspace_path.join(".prototools"), "node = \"20.11.1\"\n")?;
+
+    let output = workspace.run("shop", &["devcontainer", "generate"])?;
+    assert!(output.status.success());
+
+    let output = workspace.run("shop", &["doctor"])?;
+    assert!(
+        !String::from_utf8_lossy(&output.stdout).contains("out of date"),
+        "A fresh devcontainer should match"
+    );
+
+    fs::write(workspace_path.join(".prototools"), "node = \"22.4.0\"\n")?;
+    let output = workspace.run("shop", &["doctor"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "Stale devcontainer is a warning");
+    assert!(stdout.contains("out of date"), "{}", stdout);
+
+    let output = workspace.run("shop", &["doctor", "--fix"])?;
+    assert!(
+        output.status.success(),
+        "Doctor --fix should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let dockerfile = fs::read_to_string(workspace_path.join(".devcontainer/Dockerfile"))?;
+    assert!(
+        dockerfile.contains("ARG NODE_VERSION=22.4.0"),
+        "{}",
+        dockerfile
+    );
+
+    // Hand-written devcontainers are left alone
+    fs::write(
+        workspace_path.join(".devcontainer/devcontainer.json"),
+        "{ \"image\": \"custom\" }\n",
+    )?;
+    fs::write(workspace_path.join(".prototools"), "node = \"18.0.0\"\n")?;
+    let output = workspace.run("shop", &["doctor", "--fix"])?;
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(workspace_path.join(".devcontainer/devcontainer.json"))?,
+        "{ \"image\": \"custom\" }\n"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const BUILD_TASK: &str = r#"language: 'typescript'
+tasks:
+  build:
+    script: |
+      echo "build $project with $GREETING" >> "$workspaceRoot/ran.log"
+      pnpm build
+    env:
+      GREETING: 'hello'
+    deps:
+      - '~:install'
+"#;
+
+/// Put a fake `pnpm` first on PATH that logs its arguments and directory
+fn fake_pnpm(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "pnpm",
+            &format!(
+                "echo \"pnpm $* in ${{PWD##*/}}\" >> '{}'\n",
+                dir.join("ran.log").display()
+            ),
+        )],
+    )
+}
+
+#[test]
+fn test_build_runs_tasks_from_moon_yml_without_moon() -> anyhow::Result<()> {
+    log("→ Build Runs Tasks From moon.yml Without Moon");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join(".moon/tasks.yml"),
+        "tasks:\n  install:\n    command: 'pnpm'\n    args: ['install']\n    options:\n      runFromWorkspaceRoot: true\n",
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("moon.yml", BUILD_TASK)],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "chat",
+        &[("moon.yml", BUILD_TASK)],
+    )?;
+    let path = fake_pnpm(&workspace_path)?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["build", "api"],
+        &[("PATH", &path), ("MOONFLARE_NO_MOON", "1")],
+    )?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("[no moon] MOONFLARE_NO_MOON is set"),
+        "{}",
+        stderr
+    );
+    let ran = fs::read_to_string(workspace_path.join("ran.log"))?;
+    assert_eq!(
+        ran,
+        "pnpm install in shop\nbuild api with hello\npnpm build in api\n"
+    );
+
+    // Every project's build shares the one install from the workspace root
+    fs::remove_file(workspace_path.join("ran.log"))?;
+    let output = workspace.run_with_env(
+        "shop",
+        &["build"],
+        &[("PATH", &path), ("MOONFLARE_NO_MOON", "1")],
+    )?;
+    assert!(output.status.success());
+    let ran = fs::read_to_string(workspace_path.join("ran.log"))?;
+    assert_eq!(ran.matches("pnpm install").count(), 1, "{}", ran);
+    assert!(ran.contains("pnpm build in api"), "{}", ran);
+    assert!(ran.contains("pnpm build in chat"), "{}", ran);
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["build", "api", "--task", "lint"],
+        &[("PATH", &path), ("MOONFLARE_NO_MOON", "1")],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Project 'api' has no 'lint' task"));
+
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::thread;
+
+mod common;
+
+const WRANGLER_JSONC: &str = r#"{
+  "name": "api",
+  "account_id": "acct-1",
+  "durable_objects": {
+    "bindings": [
+      { "name": "COUNTER", "class_name": "Counter" },
+      { "name": "SESSIONS", "class_name": "Sessions", "script_name": "auth" }
+    ]
+  },
+  "migrations": [{ "tag": "v1", "new_sqlite_classes": ["Counter"] }]
+}
+"#;
+
+/// Cloudflare API with a `Counter` namespace for the api Worker holding two objects, and one
+/// belonging to another Worker
+fn mock_api() -> anyhow::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = format!("http://{}", listener.local_addr()?);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+            }
+            let body = if request_line.contains("/namespaces/ns-counter/objects") {
+                r#"{"success":true,"errors":[],"result":[{"id":"0a1b","hasStoredData":true},{"id":"2c3d","hasStoredData":false}],"result_info":{"cursor":""}}"#
+            } else if request_line.contains("/workers/durable_objects/namespaces") {
+                r#"{"success":true,"errors":[],"result":[{"id":"ns-counter","name":"api_Counter","script":"api","class":"Counter","use_sqlite":true},{"id":"ns-other","name":"web_Counter","script":"web","class":"Counter"}]}"#
+            } else {
+                r#"{"success":false,"errors":[{"message":"unexpected request"}],"result":null}"#
+            };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        }
+    });
+    Ok(address)
+}
+
+fn api_project(workspace: &MoonflareTestWorkspace) -> anyhow::Result<std::path::PathBuf> {
+    workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", WRANGLER_JSONC)],
+    )
+}
+
+/// Local state as `wrangler dev` persists it, one SQLite file per object
+fn persist_object(project: &Path, id: &str) -> anyhow::Result<()> {
+    let dir = project.join(".wrangler/state/v3/do/api-Counter");
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(format!("{}.sqlite", id)), "SQLite format 3")?;
+    fs::write(dir.join(format!("{}.sqlite-wal", id)), "")?;
+    Ok(())
+}
+
+#[test]
+fn test_do_list_shows_the_deployed_objects_of_each_worker() -> anyhow::Result<()> {
+    log("→ Do List Shows The Deployed Objects Of Each Worker");
+    let workspace = MoonflareTestWorkspace::new()?;
+    api_project(&workspace)?;
+    let api = mock_api()?;
+    let env = [
+        ("CLOUDFLARE_API_TOKEN", "token"),
+        ("CLOUDFLARE_API_BASE_URL", api.as_str()),
+    ];
+
+    let output = workspace.run_with_env("shop", &["do", "list", "--json"], &env)?;
+    assert!(
+        output.status.success(),
+        "List should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let listings: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let listings = listings.as_array().unwrap();
+    assert_eq!(listings.len(), 1, "{:?}", listings);
+    assert_eq!(listings[0]["class"], "Counter");
+    assert_eq!(listings[0]["worker"], "api");
+    assert_eq!(listings[0]["sqlite"], true);
+    assert_eq!(listings[0]["objects"][0]["id"], "0a1b");
+    assert_eq!(listings[0]["objects"][0]["has_stored_data"], true);
+    assert_eq!(listings[0]["objects"].as_array().unwrap().len(), 2);
+
+    let output = workspace.run_with_env("shop", &["do", "list", "api"], &env)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2 object(s)"), "{}", stdout);
+    assert!(stdout.contains("0a1b stored data"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_do_list_local_reads_persisted_state() -> anyhow::Result<()> {
+    log("→ Do List Local Reads Persisted State");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let project = api_project(&workspace)?;
+
+    let output = workspace.run("shop", &["do", "list", "--local"])?;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No local Durable Object state"));
+
+    persist_object(&project, "5f3a9c")?;
+    let output = workspace.run("shop", &["do", "list", "--local", "--json"])?;
+    assert!(
+        output.status.success(),
+        "List should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let objects: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let objects = objects.as_array().unwrap();
+    assert_eq!(objects.len(), 1, "{:?}", objects);
+    assert_eq!(objects[0]["class"], "Counter");
+    assert_eq!(objects[0]["id"], "5f3a9c");
+    assert_eq!(objects[0]["project"], "api");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_do_inspect_opens_sqlite_on_the_object() -> anyhow::Result<()> {
+    log("→ Do Inspect Opens SQLite On The Object");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let project = api_project(&workspace)?;
+
+    let path = fake_tools(
+        workspace.path(),
+        &[(
+            "sqlite3",
+            &format!(
+                "echo \"$@\" > {}\n",
+                workspace.path().join("sqlite-args.txt").display()
+            ),
+        )],
+    )?;
+    let env = [("PATH", path.as_str())];
+
+    let output = workspace.run_with_env("shop", &["do", "inspect", "api"], &env)?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("no local Durable Object state"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    persist_object(&project, "5f3a9c")?;
+    persist_object(&project, "77e0d1")?;
+    let output = workspace.run_with_env("shop", &["do", "inspect", "api"], &env)?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--object"));
+
+    let output = workspace.run_with_env(
+        "shop",
+        &[
+            "do",
+            "inspect",
+            "api",
+            "--object",
+            "77e",
+            "--sql",
+            "SELECT * FROM _cf_KV",
+        ],
+        &env,
+    )?;
+    assert!(
+        output.status.success(),
+        "Inspect should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let args = fs::read_to_string(workspace.path().join("sqlite-args.txt"))?;
+    assert!(args.starts_with("-readonly -header -column "), "{}", args);
+    assert!(
+        args.contains(".wrangler/state/v3/do/api-Counter/77e0d1.sqlite SELECT * FROM _cf_KV"),
+        "{}",
+        args
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_add_durable_object_with_debug_route() -> anyhow::Result<()> {
+    log("→ Add Durable Object With Debug Route");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run(
+        "shop",
+        &["add", "durable-object", "api", "--with", "debug-route"],
+    )?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let api = workspace_path.join("workers/api");
+    let index = fs::read_to_string(api.join("src/index.ts"))?;
+    assert!(index.contains("case \"/__debug/storage\":"), "{}", index);
+    assert!(
+        index.contains("this.env.DO_DEBUG !== \"true\""),
+        "{}",
+        index
+    );
+    assert!(index.contains("extends DurableObject<Env>"), "{}", index);
+    let wrangler = fs::read_to_string(api.join("wrangler.toml"))?;
+    assert!(wrangler.contains("DO_DEBUG = \"false\""), "{}", wrangler);
+    let example = fs::read_to_string(api.join(".dev.vars.example"))?;
+    assert!(example.contains("DO_DEBUG="), "{}", example);
+
+    // Without the feature, none of it is generated
+    workspace.run("shop", &["add", "durable-object", "plain"])?;
+    let plain = fs::read_to_string(workspace_path.join("workers/plain/src/index.ts"))?;
+    assert!(!plain.contains("__debug"), "{}", plain);
+    assert!(!plain.contains("DO_DEBUG"), "{}", plain);
+
+    let output = workspace.run("shop", &["add", "react", "web", "--with", "debug-route"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only available for Durable Object"));
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use common::*;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+mod common;
+
+const WRANGLER_TOML: &str = r#"name = "api"
+main = "src/index.ts"
+
+[[kv_namespaces]]
+binding = "CACHE"
+id = "abc123"
+"#;
+
+/// Status line and body of a GET to the docs server
+fn get(port: u16, path: &str) -> anyhow::Result<(String, String)> {
+    let mut stream = TcpStream::connect(("127.0.0.1", port))?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        path
+    )?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or_default();
+    Ok((
+        head.lines().next().unwrap_or_default().to_string(),
+        body.to_string(),
+    ))
+}
+
+#[test]
+fn test_docs_serve_workspace_commands_and_errors() -> anyhow::Result<()> {
+    log("→ Docs Serve Workspace Commands And Errors");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", WRANGLER_TOML)],
+    )?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[workspace]\nname = \"shop\"\n",
+    )?;
+
+    let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+    let mut child = Command::new(workspace.moonflare_binary())
+        .args(["docs", "serve", "--port", &port.to_string()])
+        .current_dir(&workspace_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while TcpStream::connect(("127.0.0.1", port)).is_err() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    let pages = (|| -> anyhow::Result<_> {
+        Ok([
+            get(port, "/")?,
+            get(port, "/projects/api")?,
+            get(port, "/commands")?,
+            get(port, "/errors")?,
+            get(port, "/projects/missing")?,
+        ])
+    })();
+    child.kill()?;
+    child.wait()?;
+    let [index, project, commands, errors, missing] = pages?;
+
+    assert!(index.0.contains("200"), "{}", index.0);
+    assert!(index.1.contains("<h1>shop</h1>"), "{}", index.1);
+    assert!(index.1.contains("<a href=\"/projects/api\">api</a>"));
+
+    assert!(project.1.contains("<h2>Bindings</h2>"), "{}", project.1);
+    assert!(
+        project.1.contains("<td><code>CACHE</code></td>"),
+        "{}",
+        project.1
+    );
+    assert!(!project.1.contains("<!--"));
+
+    assert!(
+        commands
+            .1
+            .contains("<h2 id=\"moonflare-docs-serve\">moonflare docs serve</h2>")
+    );
+    assert!(commands.1.contains("--port &lt;PORT&gt;"), "{}", commands.1);
+    assert!(errors.1.contains("moonflare::build::project_not_found"));
+
+    assert!(missing.0.contains("404"), "{}", missing.0);
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_doctor_flags_pnp_linker() -> anyhow::Result<()> {
+    log("→ Doctor Flags PnP Linker");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    fs::write(workspace_path.join(".npmrc"), "node-linker=pnp\n")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[pnpm]\nnode_linker = \"pnp\"\n",
+    )?;
+
+    let output = workspace.run("test-project", &["doctor"])?;
+    assert!(!output.status.success(), "node-linker=pnp should fail");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("node-linker=pnp"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_doctor_fix_syncs_npmrc() -> anyhow::Result<()> {
+    log("→ Doctor Fix Syncs .npmrc");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::Astro, "web", &[])?;
+
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[pnpm]\npublic_hoist_pattern = [\"*wrangler*\"]\n\n[projects.web.pnpm]\npublic_hoist_pattern = [\"@astrojs/*\"]\n",
+    )?;
+    fs::write(
+        workspace_path.join(".npmrc"),
+        "registry=https://registry.example.com/\nshamefully-hoist=true\n",
+    )?;
+    fs::write(
+        workspace_path.join("sites/web/.npmrc"),
+        "public-hoist-pattern[]=*\n",
+    )?;
+
+    let output = workspace.run("test-project", &["doctor"])?;
+    assert!(output.status.success(), "Warnings alone should not fail");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("differ from moonflare.toml"), "{}", stdout);
+    assert!(stdout.contains("sites/web/.npmrc"), "{}", stdout);
+
+    let output = workspace.run("test-project", &["doctor", "--fix"])?;
+    assert!(
+        output.status.success(),
+        "Fix should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let npmrc = fs::read_to_string(workspace_path.join(".npmrc"))?;
+    assert!(npmrc.contains("registry=https://registry.example.com/"));
+    assert!(!npmrc.contains("shamefully-hoist"));
+    assert!(npmrc.contains("public-hoist-pattern[]=*wrangler*"));
+    assert!(npmrc.contains("public-hoist-pattern[]=@astrojs/*"));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("differ from moonflare.toml"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_doctor_fix_syncs_workspace_globs() -> anyhow::Result<()> {
+    log("→ Doctor Fix Syncs Workspace Globs");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::DurableObject, "api", &[])?;
+
+    fs::write(
+        workspace_path.join("pnpm-workspace.yaml"),
+        "packages:\n  - 'workers/*'\n  - 'legacy/*'\n",
+    )?;
+    let custom = workspace_path.join("services/billing");
+    fs::create_dir_all(&custom)?;
+    fs::write(custom.join("moon.yml"), "tasks: {}\n")?;
+    fs::write(custom.join("package.json"), "{\"name\": \"billing\"}\n")?;
+
+    let output = workspace.run("test-project", &["doctor"])?;
+    assert!(!output.status.success(), "Uncovered projects should fail");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("missing 'services/*'"), "{}", stdout);
+    assert!(stdout.contains("'legacy/*'"), "{}", stdout);
+
+    let output = workspace.run("test-project", &["doctor", "--fix"])?;
+    assert!(
+        output.status.success(),
+        "Fix should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let pnpm = fs::read_to_string(workspace_path.join("pnpm-workspace.yaml"))?;
+    assert!(pnpm.contains("workers/*"));
+    assert!(pnpm.contains("services/*"));
+    assert!(!pnpm.contains("legacy/*"));
+
+    let moon = fs::read_to_string(workspace_path.join(".moon/workspace.yml"))?;
+    assert!(moon.contains("workers/*"));
+    assert!(moon.contains("services/*"));
+    assert!(
+        !moon.contains("apps/*"),
+        "Missing directories drop out: {}",
+        moon
+    );
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` that records its arguments and the targets it was handed, then exits 0
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "echo \"$* | $E2E_AGAINST | $E2E_TARGETS\" >> {}\n",
+                dir.join("moon.log").display()
+            ),
+        )],
+    )
+}
+
+#[test]
+fn test_add_e2e_depends_on_frontend_builds() -> anyhow::Result<()> {
+    log("→ Add E2E Depends On Frontend Builds");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project("shop", &ProjectType::Astro, "web", &[])?;
+    workspace.create_minimal_project("shop", &ProjectType::DurableObject, "api", &[])?;
+
+    let output = workspace.run("shop", &["add", "e2e"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let e2e = workspace_path.join("tests/e2e");
+    let moon = fs::read_to_string(e2e.join("moon.yml"))?;
+    assert!(moon.contains("command: 'playwright test'"), "{}", moon);
+    assert!(moon.contains("- 'web:build'"), "{}", moon);
+    assert!(!moon.contains("api:build"), "{}", moon);
+    let config = fs::read_to_string(e2e.join("playwright.config.ts"))?;
+    assert!(config.contains("E2E_TARGETS"), "{}", config);
+    assert!(config.contains("dev.json"), "{}", config);
+    assert!(e2e.join("specs/home.spec.ts").exists());
+
+    // Nothing recorded by `moonflare dev` yet
+    let output = workspace.run("shop", &["test", "--e2e"])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("moonflare dev"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_e2e_runs_against_dev_servers_and_deployments() -> anyhow::Result<()> {
+    log("→ E2E Runs Against Dev Servers And Deployments");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let moon_yml = "tasks:\n  dev:\n    command: 'pnpm dev'\n";
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::Astro,
+        "web",
+        &[("moon.yml", moon_yml)],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("moon.yml", moon_yml),
+            ("wrangler.toml", "name = \"api\"\n\n[dev]\nport = 8790\n"),
+        ],
+    )?;
+    assert!(workspace.run("shop", &["add", "e2e"])?.status.success());
+    let path = fake_moon(&workspace_path)?;
+
+    // `moonflare dev` records where each dev server listens
+    let output = workspace.run_with_env("shop", &["dev"], &[("PATH", path.as_str())])?;
+    assert!(
+        output.status.success(),
+        "Dev should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let dev: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join(".moonflare/dev.json"),
+    )?)?;
+    assert_eq!(dev["projects"]["web"], "http://localhost:4321", "{}", dev);
+    assert_eq!(dev["projects"]["api"], "http://localhost:8790", "{}", dev);
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["test", "--e2e", "--only", "web"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(
+        output.status.success(),
+        "E2E tests should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let log = fs::read_to_string(workspace_path.join("moon.log"))?;
+    assert!(
+        log.contains(r#"run e2e:e2e | dev | {"web":"http://localhost:4321"}"#),
+        "{}",
+        log
+    );
+
+    // Deployments are found through the manifest and moonflare.toml
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[projects.web]\nurl = \"https://web-{env}.example.workers.dev\"\n",
+    )?;
+    fs::write(
+        workspace_path.join(".moonflare/deployments.json"),
+        r#"{"environments": {"preview": {
+  "web": {"deployed_at": "2026-01-01T00:00:00Z", "git_sha": null, "dirty": false},
+  "api": {"deployed_at": "2026-01-01T00:00:00Z", "git_sha": null, "dirty": false}
+}}}"#,
+    )?;
+    let output = workspace.run_with_env(
+        "shop",
+        &["test", "--e2e", "--against", "preview"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(
+        output.status.success(),
+        "E2E tests should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Skipping 'api'"));
+    let log = fs::read_to_string(workspace_path.join("moon.log"))?;
+    assert!(
+        log.contains(
+            r#"run e2e:e2e | preview | {"web":"https://web-preview.example.workers.dev"}"#
+        ),
+        "{}",
+        log
+    );
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["test", "--e2e", "--against", "staging"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Nothing has been deployed"));
+
+    Ok(())
+}
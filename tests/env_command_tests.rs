@@ -0,0 +1,85 @@
+use common::*;
+
+mod common;
+
+const WRANGLER_JSONC: &str = r#"{
+	// Worker configuration
+	"name": "api",
+	"vars": { "API_BASE_URL": "https://example.com" },
+	/* Bindings */
+	"kv_namespaces": [{ "binding": "CACHE", "id": "abc" }],
+}
+"#;
+
+const WORKER_SOURCE: &str = r#"export default {
+	async fetch(request, env) {
+		await env.CACHE.get("key");
+		return fetch(env.API_BASE_URL, { headers: { Authorization: env.API_TOKEN } });
+	},
+};
+"#;
+
+#[test]
+fn test_env_example_lists_vars_and_secrets() -> anyhow::Result<()> {
+    log("→ Env Example Lists Vars And Secrets");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    let project_path = workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.jsonc", WRANGLER_JSONC),
+            ("src/index.ts", WORKER_SOURCE),
+        ],
+    )?;
+
+    let output = workspace.run("test-project", &["env", "example"])?;
+    assert!(
+        output.status.success(),
+        "env example should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let example = std::fs::read_to_string(project_path.join(".dev.vars.example"))?;
+    assert!(example.contains("API_BASE_URL=\"https://example.com\""));
+    assert!(example.contains("API_TOKEN=\"\""));
+    assert!(
+        !example.contains("CACHE"),
+        "Bindings aren't variables: {}",
+        example
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_env_pull_preserves_existing_values() -> anyhow::Result<()> {
+    log("→ Env Pull Preserves Existing Values");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    let project_path = workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.jsonc", WRANGLER_JSONC),
+            ("src/index.ts", WORKER_SOURCE),
+            (".dev.vars", "API_TOKEN=secret-value\n"),
+        ],
+    )?;
+
+    let output = workspace.run("test-project", &["env", "pull"])?;
+    assert!(
+        output.status.success(),
+        "env pull should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let dev_vars = std::fs::read_to_string(project_path.join(".dev.vars"))?;
+    assert!(dev_vars.contains("API_TOKEN=\"secret-value\""));
+    assert!(dev_vars.contains("API_BASE_URL=\"https://example.com\""));
+    assert!(project_path.join(".dev.vars.example").exists());
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+#![cfg(unix)]
+
+use common::*;
+use std::path::Path;
+
+mod common;
+
+const WRANGLER_TOML: &str = r#"name = "api"
+main = "src/index.ts"
+
+[vars]
+API_BASE_URL = "https://api.example.com"
+LOG_LEVEL = "warn"
+
+[env.staging.vars]
+API_BASE_URL = "https://staging.example.com"
+SENTRY_DSN = "https://sentry.example.com/1"
+"#;
+
+/// Fake `pnpm` whose `wrangler secret list` prints different secrets for staging and the top
+/// level
+fn fake_pnpm(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "pnpm",
+            r#"case "$*" in
+  *"--env staging"*) echo '[{"name":"STRIPE_KEY","type":"secret_text"},{"name":"LOG_LEVEL","type":"secret_text"}]' ;;
+  *) echo '[{"name":"STRIPE_KEY","type":"secret_text"},{"name":"SESSION_SECRET","type":"secret_text"}]' ;;
+esac
+"#,
+        )],
+    )
+}
+
+#[test]
+fn test_env_diff_reports_keys_missing_from_either_environment() -> anyhow::Result<()> {
+    log("→ Env Diff Reports Keys Missing From Either Environment");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", WRANGLER_TOML)],
+    )?;
+    let path = fake_pnpm(&workspace_path)?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["env", "diff", "api", "staging", "production"],
+        &[("PATH", path.as_str())],
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "Differences should fail: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("SENTRY_DSN (var) is set in staging but missing from production"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("SESSION_SECRET (secret) is set in production but missing from staging"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("LOG_LEVEL is a secret in staging but a var in production"),
+        "{}",
+        stdout
+    );
+    for same in ["API_BASE_URL", "STRIPE_KEY", "https://"] {
+        assert!(
+            !stdout.contains(same),
+            "{} isn't a difference: {}",
+            same,
+            stdout
+        );
+    }
+    assert!(stderr.contains("3 key(s) differ"), "{}", stderr);
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["env", "diff", "api", "staging", "preview"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("no [env.preview]"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const WRANGLER_TOML: &str = r#"name = "api"
+main = "src/index.ts"
+
+[vars]
+API_BASE_URL = "https://api.example.com"
+
+[[kv_namespaces]]
+binding = "CACHE"
+id = "abc123"
+
+[[durable_objects.bindings]]
+name = "COUNTER"
+class_name = "Counter"
+
+[env.staging.vars]
+API_BASE_URL = "https://staging.example.com"
+"#;
+
+/// Fake `moon` that logs each run with the wrangler variables it received
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "echo \"$@ env=$WRANGLER_ENV keep=$WRANGLER_KEEP_VARS\" >> {}\n",
+                dir.join("moon-runs.log").display()
+            ),
+        )],
+    )
+}
+
+fn setup(workspace: &MoonflareTestWorkspace) -> anyhow::Result<std::path::PathBuf> {
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", WRANGLER_TOML)],
+    )?;
+    Ok(workspace_path)
+}
+
+#[test]
+fn test_env_sync_copies_missing_bindings() -> anyhow::Result<()> {
+    log("→ Env Sync Copies Missing Bindings");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = setup(&workspace)?;
+
+    let output = workspace.run("test-project", &["env", "sync", "api", "staging"])?;
+    assert!(
+        output.status.success(),
+        "Sync should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(workspace_path.join("workers/api/wrangler.toml"))?;
+    let config: toml::Table = content.parse()?;
+    let staging = &config["env"]["staging"];
+    assert_eq!(
+        staging["vars"]["API_BASE_URL"].as_str(),
+        Some("https://staging.example.com"),
+        "Existing environment values are kept"
+    );
+    assert_eq!(
+        staging["kv_namespaces"][0]["binding"].as_str(),
+        Some("CACHE")
+    );
+    assert_eq!(
+        staging["durable_objects"]["bindings"][0]["name"].as_str(),
+        Some("COUNTER")
+    );
+
+    let output = workspace.run("test-project", &["env", "sync", "api", "staging"])?;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("already declares"));
+
+    Ok(())
+}
+
+#[test]
+fn test_deploy_warns_about_missing_bindings_and_keeps_vars() -> anyhow::Result<()> {
+    log("→ Deploy Warns About Missing Bindings And Keeps Vars");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = setup(&workspace)?;
+    let path = fake_moon(&workspace_path)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["deploy", "api", "--env", "staging", "--keep-vars"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("doesn't declare durable_objects COUNTER, kv_namespaces CACHE"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("moonflare env sync api staging"),
+        "{}",
+        stdout
+    );
+
+    let runs = fs::read_to_string(workspace_path.join("moon-runs.log"))?;
+    assert!(
+        runs.contains("run api:deploy env=staging keep=1"),
+        "{}",
+        runs
+    );
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` that knows `frontend:build` and succeeds at every run
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            "if [ \"$1\" = query ] && [ \"$2\" = tasks ]; then\n  echo '{\"tasks\":{\"frontend\":{\"build\":{}}}}'\nfi\nexit 0\n",
+        )],
+    )
+}
+
+fn read_events(path: &Path) -> anyhow::Result<Vec<serde_json::Value>> {
+    fs::read_to_string(path)?
+        .lines()
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[test]
+fn test_build_streams_events_to_file() -> anyhow::Result<()> {
+    log("→ Build Streams Events To File");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "frontend",
+        &[("moon.yml", "tasks:\n  build:\n    command: 'true'\n")],
+    )?;
+    let path = fake_moon(&workspace_path)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build", "frontend", "--events-file", "events.jsonl"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let events = read_events(&workspace_path.join("events.jsonl"))?;
+    let kinds: Vec<&str> = events
+        .iter()
+        .map(|e| e["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        ["command_started", "task_finished", "command_finished"]
+    );
+    assert_eq!(events[0]["command"], "build");
+    assert_eq!(events[1]["target"], "frontend:build");
+    assert_eq!(events[1]["project_path"], "apps/frontend");
+    assert_eq!(events[1]["success"], true);
+    assert_eq!(events[2]["success"], true);
+    assert!(events.iter().all(|e| e["timestamp"].is_string()));
+
+    Ok(())
+}
+
+#[test]
+fn test_failed_command_emits_error_event() -> anyhow::Result<()> {
+    log("→ Failed Command Emits Error Event");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run(
+        "test-project",
+        &["--events-file", "events.jsonl", "preview", "missing"],
+    )?;
+    assert!(!output.status.success());
+
+    let events = read_events(&workspace_path.join("events.jsonl"))?;
+    let kinds: Vec<&str> = events
+        .iter()
+        .map(|e| e["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["command_started", "error", "command_finished"]);
+    assert_eq!(events[1]["command"], "preview");
+    assert!(
+        events[1]["message"]
+            .as_str()
+            .unwrap()
+            .contains("'missing' not found")
+    );
+    assert_eq!(events[2]["success"], false);
+
+    Ok(())
+}
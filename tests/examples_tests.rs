@@ -0,0 +1,73 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_examples_list_and_add_chat() -> anyhow::Result<()> {
+    log("→ Examples List And Add Chat");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[workspace]\nname = \"shop\"\nworker_name = \"{workspace}-{project}\"\n",
+    )?;
+
+    let output = workspace.run("shop", &["examples", "list"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    for example in ["chat", "image-resizer", "rag"] {
+        assert!(stdout.contains(example), "{}", stdout);
+    }
+
+    let output = workspace.run("shop", &["examples", "add", "chat"])?;
+    assert!(
+        output.status.success(),
+        "Adding the example should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let api = workspace_path.join("workers/chat-api");
+    let web = workspace_path.join("apps/chat-web");
+    assert!(api.join("src/index.ts").exists());
+    assert!(api.join("README.md").exists());
+    assert!(web.join("public/index.html").exists());
+
+    let api_wrangler = fs::read_to_string(api.join("wrangler.toml"))?;
+    assert!(
+        api_wrangler.contains("name = \"shop-chat-api\""),
+        "{}",
+        api_wrangler
+    );
+    let web_wrangler = fs::read_to_string(web.join("wrangler.toml"))?;
+    assert!(
+        web_wrangler.contains("service = \"shop-chat-api\""),
+        "{}",
+        web_wrangler
+    );
+
+    // A second add would overwrite the projects, so it's refused
+    let output = workspace.run("shop", &["examples", "add", "chat"])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("already exists"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_examples_add_unknown_suggests_name() -> anyhow::Result<()> {
+    log("→ Examples Add Unknown Suggests Name");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["examples", "add", "chatt"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("did you mean 'chat'"), "{}", stderr);
+
+    Ok(())
+}
@@ -0,0 +1,178 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const TYPESCRIPT_TASKS: &str = r#"tasks:
+  dev:
+    command: 'pnpm'
+    args: ['dev']
+  deploy:
+    command: 'pnpm'
+    args: ['run', 'deploy']
+    deps:
+      - '~:build'
+"#;
+
+const API_MOON: &str = r#"language: 'typescript'
+tasks:
+  build:
+    command: 'pnpm'
+    args: ['run', 'build']
+"#;
+
+const API_PACKAGE: &str = r#"{
+  "name": "api",
+  "scripts": {
+    "dev": "wrangler dev",
+    "build": "tsc",
+    "deploy": "wrangler deploy ${WRANGLER_ENV:+--env $WRANGLER_ENV}"
+  }
+}
+"#;
+
+/// Fake `moon` that leaves a marker behind when asked to run anything
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "case \"$1\" in\n  run|:*) echo \"$@\" >> {} ;;\nesac\nexit 0\n",
+                dir.join("moon-ran.txt").display()
+            ),
+        )],
+    )
+}
+
+fn api_workspace(workspace: &MoonflareTestWorkspace) -> anyhow::Result<std::path::PathBuf> {
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::create_dir_all(workspace_path.join(".moon/tasks"))?;
+    fs::write(
+        workspace_path.join(".moon/tasks/typescript.yml"),
+        TYPESCRIPT_TASKS,
+    )?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[env]\nLOG_LEVEL = \"debug\"\n\n[env.staging]\nAPI_TOKEN = \"s3cret\"\n",
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("moon.yml", API_MOON),
+            ("package.json", API_PACKAGE),
+            ("wrangler.toml", "name = \"api\"\nmain = \"src/index.ts\"\n"),
+        ],
+    )?;
+    Ok(workspace_path)
+}
+
+#[test]
+fn test_deploy_explain_plan_resolves_commands_without_running_them() -> anyhow::Result<()> {
+    log("→ Deploy Explain Plan Resolves Commands Without Running Them");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = api_workspace(&workspace)?;
+    let path = fake_moon(workspace.path())?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["deploy", "api", "--env", "staging", "--explain-plan"],
+        &[("PATH", path.as_str()), ("NO_COLOR", "1")],
+    )?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("moon run api:deploy\n"), "{}", stdout);
+    assert!(
+        stdout.contains(&format!(
+            "cwd: {}\n",
+            workspace_path.canonicalize()?.display()
+        )),
+        "{}",
+        stdout
+    );
+    for var in [
+        "WRANGLER_ENV=staging",
+        "MOONFLARE_ENV=staging",
+        "LOG_LEVEL=debug",
+        "API_TOKEN=<redacted>",
+    ] {
+        assert!(stdout.contains(var), "{}\n{}", var, stdout);
+    }
+    assert!(!stdout.contains("s3cret"), "{}", stdout);
+    assert!(
+        stdout.contains("api:deploy runs pnpm run deploy\n"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("workers/api\n"), "{}", stdout);
+    assert!(
+        stdout.contains("script deploy: wrangler deploy ${WRANGLER_ENV:+--env $WRANGLER_ENV}"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("after: api:build"), "{}", stdout);
+    assert!(!workspace.path().join("moon-ran.txt").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_build_and_dev_explain_plans() -> anyhow::Result<()> {
+    log("→ Build And Dev Explain Plans");
+    let workspace = MoonflareTestWorkspace::new()?;
+    api_workspace(&workspace)?;
+    let path = fake_moon(workspace.path())?;
+    let home = workspace.path().join("home");
+    fs::create_dir_all(&home)?;
+    let env = [
+        ("PATH", path.as_str()),
+        ("NO_COLOR", "1"),
+        ("HOME", home.to_str().unwrap_or_default()),
+    ];
+
+    let output = workspace.run_with_env("shop", &["build", "api", "--explain-plan"], &env)?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("moon run api:build\n"), "{}", stdout);
+    assert!(stdout.contains("env: LOG_LEVEL=debug"), "{}", stdout);
+    assert!(stdout.contains("script build: tsc"), "{}", stdout);
+    assert!(!stdout.contains("Build completed"), "{}", stdout);
+
+    // Passthrough arguments reach the script, and the certificate isn't generated
+    let output =
+        workspace.run_with_env("shop", &["dev", "api", "--https", "--explain-plan"], &env)?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("moon run api:dev -- --local-protocol https"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("MOONFLARE_HTTPS_CERT="), "{}", stdout);
+    assert!(
+        stdout.contains("script dev: wrangler dev --local-protocol https"),
+        "{}",
+        stdout
+    );
+    assert!(!home.join(".moonflare/certs").exists());
+    assert!(!workspace.path().join("moon-ran.txt").exists());
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `pnpm` that records its arguments
+fn fake_pnpm(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "pnpm",
+            &format!("echo \"$@\" >> {}\n", dir.join("pnpm-args.txt").display()),
+        )],
+    )
+}
+
+#[test]
+fn test_flags_init_scaffolds_worker_and_typed_client() -> anyhow::Result<()> {
+    log("→ Flags Init Scaffolds Worker And Typed Client");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["flags", "init"])?;
+    assert!(
+        output.status.success(),
+        "Flags init should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(workspace_path.join("flags.toml").exists());
+    let wrangler = fs::read_to_string(workspace_path.join("workers/flags/wrangler.toml"))?;
+    assert!(wrangler.contains("binding = \"FLAGS\""), "{}", wrangler);
+    let client = fs::read_to_string(workspace_path.join("packages/flags/index.ts"))?;
+    assert!(client.contains("\"new-checkout\": boolean;"), "{}", client);
+
+    // Editing flags.toml and running init again regenerates the client
+    fs::write(
+        workspace_path.join("flags.toml"),
+        "[flags.new-checkout]\ndefault = false\n\n[flags.max-items]\ndefault = 20\n",
+    )?;
+    let output = workspace.run("test-project", &["flags", "init"])?;
+    assert!(output.status.success());
+    let client = fs::read_to_string(workspace_path.join("packages/flags/index.ts"))?;
+    assert!(client.contains("\"max-items\": number;"), "{}", client);
+    assert!(client.contains("\"max-items\": 20,"), "{}", client);
+
+    Ok(())
+}
+
+#[test]
+fn test_flags_set_writes_kv_and_checks_types() -> anyhow::Result<()> {
+    log("→ Flags Set Writes KV And Checks Types");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    let path = fake_pnpm(&workspace_path)?;
+    assert!(
+        workspace
+            .run("test-project", &["flags", "init"])?
+            .status
+            .success()
+    );
+
+    // The namespace has to exist first
+    let output = workspace.run_with_env(
+        "test-project",
+        &["flags", "set", "new-checkout", "true"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("kv namespace create FLAGS"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let wrangler_path = workspace_path.join("workers/flags/wrangler.toml");
+    let wrangler = fs::read_to_string(&wrangler_path)?.replace(
+        "REPLACE_WITH_NAMESPACE_ID",
+        "0f2ac74b498b48028cb68387c421e279",
+    );
+    fs::write(&wrangler_path, wrangler)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["flags", "set", "new-checkout", "true"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(
+        output.status.success(),
+        "Flags set should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let args = fs::read_to_string(workspace_path.join("pnpm-args.txt"))?;
+    assert!(
+        args.contains("exec wrangler kv key put flag:new-checkout true --binding FLAGS --remote"),
+        "{}",
+        args
+    );
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["flags", "set", "new-checkout", "maybe"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is a boolean flag"));
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["flags", "set", "new-checkut", "true"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'new-checkout'"));
+
+    Ok(())
+}
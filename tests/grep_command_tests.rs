@@ -0,0 +1,64 @@
+use common::*;
+
+mod common;
+
+#[test]
+fn test_grep_groups_matches_by_project_and_skips_generated_dirs() -> anyhow::Result<()> {
+    log("→ Grep Groups Matches By Project And Skips Generated Dirs");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            (
+                "src/index.ts",
+                "export default {\n  fetch(req, env) {\n    return env.CACHE.get('k');\n  },\n};\n",
+            ),
+            ("dist/index.js", "env.CACHE.get('k')\n"),
+            ("node_modules/pkg/index.js", "env.CACHE\n"),
+            (".wrangler/state/cache.js", "env.CACHE\n"),
+        ],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[("src/App.tsx", "// reads env.cache through the API\n")],
+    )?;
+
+    let output = workspace.run("shop", &["grep", "-F", "env.CACHE", "--json"])?;
+    assert!(
+        output.status.success(),
+        "Grep should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let matches: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let summary: Vec<String> = matches
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| format!("{} {}:{}", m["project"], m["path"], m["line"]))
+        .collect();
+    assert_eq!(summary, vec![r#""api" "src/index.ts":3"#]);
+
+    // Case-insensitive search, scoped by project type
+    let output = workspace.run("shop", &["grep", "-i", r"env\.cache", "--type", "app"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("web"));
+    assert!(stdout.contains("src/App.tsx"));
+    assert!(!stdout.contains("src/index.ts"));
+    assert!(stdout.contains("1 match(es) in 1 file(s) across 1 project(s)"));
+
+    let output = workspace.run("shop", &["grep", "no-such-text", "api"])?;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No matches in 1 project(s)"));
+
+    let output = workspace.run("shop", &["grep", "("])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid pattern"));
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` whose `run` writes a Vite-style build of apps/web, recording whether it was
+/// asked to hash assets
+#[cfg(unix)]
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            r#"if [ "$1" = run ]; then
+  mkdir -p apps/web/dist/assets
+  echo '<div id="root"></div>' > apps/web/dist/index.html
+  echo 'console.log(1)' > apps/web/dist/assets/index-BXk3a_9z.js
+  echo '1a2b' > apps/web/dist/math.1a2b3c4d.wasm
+  printf '/admin/*\n  X-Frame-Options: DENY\n' > apps/web/dist/_headers
+  echo "${MOONFLARE_HASH_ASSETS:-0}" > hash-assets.txt
+fi
+exit 0
+"#,
+        )],
+    )
+}
+
+#[test]
+fn test_add_react_and_astro_with_hashed_assets() -> anyhow::Result<()> {
+    log("→ Add React And Astro With Hashed Assets");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run("shop", &["add", "react", "web", "--with", "hashed-assets"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let vite = fs::read_to_string(workspace_path.join("apps/web/vite.config.ts"))?;
+    assert!(
+        vite.contains("assetFileNames: 'assets/[name]-[hash][extname]'"),
+        "{}",
+        vite
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("[assets]"));
+
+    let output = workspace.run("shop", &["add", "astro", "docs", "--with", "hashed-assets"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let astro = fs::read_to_string(workspace_path.join("sites/docs/astro.config.mjs"))?;
+    assert!(astro.contains("assets: '_astro'"), "{}", astro);
+    assert!(astro.contains("_astro/[name].[hash].js"), "{}", astro);
+
+    // Without the feature the configs stay as they were
+    let output = workspace.run("shop", &["add", "react", "plain"])?;
+    assert!(output.status.success());
+    let vite = fs::read_to_string(workspace_path.join("apps/plain/vite.config.ts"))?;
+    assert!(!vite.contains("[hash]"), "{}", vite);
+
+    let output = workspace.run("shop", &["add", "worker", "api", "--with", "hashed-assets"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("React and Astro"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_build_writes_cache_headers_for_hashed_assets() -> anyhow::Result<()> {
+    log("→ Build Writes Cache Headers For Hashed Assets");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[(
+            "wrangler.jsonc",
+            r#"{"name": "web", "assets": {"directory": "./dist"}}"#,
+        )],
+    )?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[assets]\nhashing = true\nimmutable_max_age = 600\n",
+    )?;
+    let path = fake_moon(workspace.path())?;
+
+    let output = workspace.run_with_env("shop", &["build", "web"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(workspace_path.join("hash-assets.txt"))?.trim(),
+        "1"
+    );
+
+    let dist = workspace_path.join("apps/web/dist");
+    let headers = fs::read_to_string(dist.join("_headers"))?;
+    assert!(
+        headers.starts_with("# moonflare:cache:start"),
+        "{}",
+        headers
+    );
+    assert!(
+        headers.contains("/*\n  Cache-Control: public, max-age=0, must-revalidate\n"),
+        "{}",
+        headers
+    );
+    assert!(
+        headers.contains(
+            "/assets/*\n  ! Cache-Control\n  Cache-Control: public, max-age=600, immutable\n"
+        ),
+        "{}",
+        headers
+    );
+    assert!(headers.contains("/math.1a2b3c4d.wasm\n"), "{}", headers);
+    assert!(
+        headers.contains("/admin/*\n  X-Frame-Options: DENY"),
+        "{}",
+        headers
+    );
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dist.join("asset-manifest.json"))?)?;
+    assert_eq!(manifest["assets/index.js"], "assets/index-BXk3a_9z.js");
+    assert_eq!(manifest["math.wasm"], "math.1a2b3c4d.wasm");
+    assert!(!manifest.as_object().unwrap().contains_key("index.html"));
+    assert!(fs::read_to_string(dist.join(".assetsignore"))?.contains("asset-manifest.json"));
+
+    // A rebuild replaces the block rather than adding another
+    let output = workspace.run_with_env("shop", &["build", "web"], &[("PATH", &path)])?;
+    assert!(output.status.success());
+    let headers = fs::read_to_string(dist.join("_headers"))?;
+    assert_eq!(headers.matches("moonflare:cache:start").count(), 1);
+
+    // The build manifest deploy --from-artifacts checks lists the output
+    let build_manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join(".moonflare/build-manifest.json"),
+    )?)?;
+    assert_eq!(build_manifest["projects"]["web"]["output"], "apps/web/dist");
+    assert!(build_manifest["projects"]["web"]["files"]["_headers"].is_string());
+
+    Ok(())
+}
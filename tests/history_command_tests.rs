@@ -0,0 +1,146 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_mutating_operations_are_recorded() -> anyhow::Result<()> {
+    log("→ Mutating Operations Are Recorded");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::DurableObject, "api", &[])?;
+
+    let env = [("GITHUB_ACTOR", "octocat")];
+    let output = workspace.run_with_env("test-project", &["tag", "add", "api", "backend"], &env)?;
+    assert!(output.status.success());
+    // Read-only commands and failed operations
+    workspace.run_with_env("test-project", &["tag", "list"], &env)?;
+    let output = workspace.run_with_env("test-project", &["rename", "missing", "other"], &env)?;
+    assert!(!output.status.success());
+
+    let history = fs::read_to_string(workspace_path.join(".moonflare/history.jsonl"))?;
+    let entries: Vec<serde_json::Value> = history
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(entries.len(), 2, "{}", history);
+    assert_eq!(entries[0]["command"], "tag add");
+    assert_eq!(entries[0]["user"], "octocat");
+    assert_eq!(
+        entries[0]["args"],
+        serde_json::json!(["tag", "add", "api", "backend"])
+    );
+    assert_eq!(entries[0]["success"], true);
+    assert_eq!(entries[1]["command"], "rename");
+    assert_eq!(entries[1]["success"], false);
+    assert!(entries[1]["error"].as_str().unwrap().contains("not found"));
+
+    Ok(())
+}
+
+#[test]
+fn test_history_filters_entries() -> anyhow::Result<()> {
+    log("→ History Filters Entries");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::create_dir_all(workspace_path.join(".moonflare"))?;
+    fs::write(
+        workspace_path.join(".moonflare/history.jsonl"),
+        concat!(
+            r#"{"timestamp":"2020-01-01T00:00:00+00:00","user":"alice","command":"env sync","args":["env","sync"],"success":true,"duration_secs":1.0}"#,
+            "\n",
+            r#"{"timestamp":"2099-01-01T00:00:00+00:00","user":"bob","command":"deploy","args":["deploy"],"success":false,"error":"boom","duration_secs":2.0}"#,
+            "\n",
+            r#"{"timestamp":"2099-01-02T00:00:00+00:00","user":"alice","command":"tag add","args":["tag","add"],"success":true,"duration_secs":0.1}"#,
+            "\n",
+        ),
+    )?;
+
+    let json = |filters: &[&str]| -> anyhow::Result<Vec<String>> {
+        let mut args = vec!["history", "--json"];
+        args.extend(filters);
+        let output = workspace.run("test-project", &args)?;
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+        Ok(entries
+            .iter()
+            .map(|e| e["command"].as_str().unwrap().to_string())
+            .collect())
+    };
+
+    assert_eq!(json(&[])?, ["env sync", "deploy", "tag add"]);
+    assert_eq!(json(&["--user", "alice"])?, ["env sync", "tag add"]);
+    assert_eq!(json(&["--failed"])?, ["deploy"]);
+    assert_eq!(json(&["--command", "tag"])?, ["tag add"]);
+    assert_eq!(json(&["--since", "2021-01-01"])?, ["deploy", "tag add"]);
+    assert_eq!(json(&["--limit", "1"])?, ["tag add"]);
+
+    let output = workspace.run("test-project", &["history", "--failed"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("bob"), "{}", stdout);
+    assert!(stdout.contains("boom"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_history_stats_aggregates_usage() -> anyhow::Result<()> {
+    log("→ History Stats Aggregates Usage");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::DurableObject, "api", &[])?;
+
+    // Read-only commands are counted too, with the projects they name
+    workspace.run("test-project", &["tag", "list"])?;
+    workspace.run("test-project", &["tag", "list"])?;
+    let output = workspace.run("test-project", &["rename", "api", "api"])?;
+    assert!(!output.status.success());
+    let usage = fs::read_to_string(workspace_path.join(".moonflare/usage.jsonl"))?;
+    assert_eq!(usage.lines().count(), 3, "{}", usage);
+    assert!(
+        !usage.contains("\"args\""),
+        "Arguments stay out of the usage log"
+    );
+
+    fs::write(
+        workspace_path.join(".moonflare/builds.jsonl"),
+        concat!(
+            r#"{"timestamp":"2099-01-01T00:00:00+00:00","success":true,"duration_secs":12.5,"tasks":[{"target":"api:build","duration_secs":12.5}]}"#,
+            "\n",
+            r#"{"timestamp":"2099-01-02T00:00:00+00:00","success":false,"duration_secs":40.0,"tasks":[{"target":"api:build","duration_secs":40.0}]}"#,
+            "\n",
+        ),
+    )?;
+
+    let output = workspace.run("test-project", &["history", "stats", "--json"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(stats["runs"], 3);
+    assert_eq!(stats["commands"][0]["name"], "tag list");
+    assert_eq!(stats["commands"][0]["runs"], 2);
+    assert_eq!(stats["commands"][1]["name"], "rename");
+    assert_eq!(stats["commands"][1]["failures"], 1);
+    assert_eq!(stats["projects"][0]["name"], "api");
+    assert_eq!(stats["projects"][0]["failures"], 1);
+    assert_eq!(stats["slowest_builds"][0]["duration_secs"], 40.0);
+    assert_eq!(stats["slowest_builds"][1]["duration_secs"], 12.5);
+
+    let output = workspace.run(
+        "test-project",
+        &["history", "stats", "--since", "2098-01-01"],
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("Slowest builds"), "{}", stdout);
+    assert!(stdout.contains("api:build"), "{}", stdout);
+
+    Ok(())
+}
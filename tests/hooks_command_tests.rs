@@ -0,0 +1,103 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+const HOOKS_CONFIG: &str = "[hooks]\npre_commit = [\"echo ran > hook-ran.txt\"]\npre_push = []\n";
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<std::process::Output> {
+    Ok(Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()?)
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hooks_install_runs_commands_from_workspace_root() -> anyhow::Result<()> {
+    log("→ Hooks Install Runs Commands From Workspace Root");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(workspace_path.join("moonflare.toml"), HOOKS_CONFIG)?;
+    // The workspace is a subdirectory of the repository
+    git(workspace.path(), &["init", "-q"])?;
+    let hooks = workspace.path().join(".git/hooks");
+
+    let output = workspace.run("shop", &["hooks", "install"])?;
+    assert!(
+        output.status.success(),
+        "Install should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let script = fs::read_to_string(hooks.join("pre-commit"))?;
+    assert!(script.contains("Managed by moonflare"));
+    assert!(script.contains("/shop/\""), "{}", script);
+    assert!(!hooks.join("pre-push").exists(), "No pre_push commands");
+
+    let commit = git(
+        &workspace_path,
+        &["commit", "-q", "--allow-empty", "-m", "test"],
+    )?;
+    assert!(
+        commit.status.success(),
+        "{}",
+        String::from_utf8_lossy(&commit.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(workspace_path.join("hook-ran.txt"))?.trim(),
+        "ran"
+    );
+
+    // Hooks moonflare didn't write are kept unless --force is passed
+    fs::write(hooks.join("pre-commit"), "#!/bin/sh\nexit 0\n")?;
+    fs::write(hooks.join("pre-push"), "#!/bin/sh\nexit 0\n")?;
+    let output = workspace.run("shop", &["hooks", "install"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+    let output = workspace.run("shop", &["hooks", "install", "--force"])?;
+    assert!(output.status.success());
+
+    let output = workspace.run("shop", &["hooks", "uninstall", "--auto-approve"])?;
+    assert!(output.status.success());
+    assert!(!hooks.join("pre-commit").exists());
+    assert!(hooks.join("pre-push").exists(), "Foreign hooks are kept");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hooks_install_generates_lefthook_config() -> anyhow::Result<()> {
+    log("→ Hooks Install Generates Lefthook Config");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    git(&workspace_path, &["init", "-q"])?;
+
+    let output = workspace.run("shop", &["hooks", "install", "--manager", "lefthook"])?;
+    assert!(
+        output.status.success(),
+        "Install should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let path = workspace_path.join("lefthook.yml");
+    let config: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(&path)?)?;
+    assert_eq!(config["pre-commit"]["piped"], serde_yaml::Value::Bool(true));
+    assert_eq!(
+        config["pre-commit"]["commands"]["01"]["run"].as_str(),
+        Some("moon run :check --affected --status staged")
+    );
+    assert_eq!(
+        config["pre-push"]["commands"]["01"]["run"].as_str(),
+        Some("moonflare config lint")
+    );
+    assert!(config["pre-push"]["commands"]["02"]["root"].is_null());
+
+    let output = workspace.run("shop", &["hooks", "uninstall", "--auto-approve"])?;
+    assert!(output.status.success());
+    assert!(!path.exists());
+
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+fn write_files(root: &Path, files: &[(&str, &str)]) -> anyhow::Result<()> {
+    for (file, content) in files {
+        let path = root.join(file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_import_create_vite_moves_out_dir_and_adds_worker() -> anyhow::Result<()> {
+    log("→ Import Create Vite Moves OutDir And Adds Worker");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let starter = workspace.path().join("starters/my-vite-app");
+    write_files(
+        &starter,
+        &[
+            (
+                "package.json",
+                r#"{"name": "my-vite-app", "scripts": {"dev": "vite", "build": "vite build"}, "dependencies": {"react": "^19.0.0"}, "devDependencies": {"vite": "^6.0.0"}}"#,
+            ),
+            (
+                "vite.config.ts",
+                "export default defineConfig({\n  plugins: [react()],\n  build: { outDir: 'build' },\n})\n",
+            ),
+            ("index.html", "<div id=\"root\"></div>\n"),
+            ("src/main.tsx", "console.log('hi')\n"),
+            ("package-lock.json", "{}\n"),
+            ("node_modules/vite/index.js", "\n"),
+        ],
+    )?;
+
+    let starter_arg = starter.to_string_lossy().into_owned();
+    let output = workspace.run("shop", &["import", &starter_arg, "--name", "storefront"])?;
+    assert!(
+        output.status.success(),
+        "Import should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("create-vite"), "{}", stdout);
+
+    let project = workspace_path.join("apps/storefront");
+    let vite = fs::read_to_string(project.join("vite.config.ts"))?;
+    assert!(vite.contains("outDir: './dist'"), "{}", vite);
+
+    let wrangler = fs::read_to_string(project.join("wrangler.jsonc"))?;
+    assert!(
+        wrangler.contains("\"name\": \"storefront\""),
+        "{}",
+        wrangler
+    );
+    assert!(
+        wrangler.contains("\"directory\": \"./dist\""),
+        "{}",
+        wrangler
+    );
+
+    let package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(project.join("package.json"))?)?;
+    assert_eq!(package["name"], "storefront");
+    assert!(
+        package["scripts"]["deploy"]
+            .as_str()
+            .is_some_and(|deploy| deploy.contains("WRANGLER_ENV"))
+    );
+    assert!(package["devDependencies"]["wrangler"].is_string());
+
+    let moon = fs::read_to_string(project.join("moon.yml"))?;
+    assert!(moon.contains("stack: 'frontend'"), "{}", moon);
+    assert!(moon.contains("      - 'dist'"), "{}", moon);
+
+    assert!(!project.join("package-lock.json").exists());
+    assert!(!project.join("node_modules").exists());
+    assert!(starter.join("package-lock.json").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_import_c3_worker_moves_nested_wrangler_config() -> anyhow::Result<()> {
+    log("→ Import C3 Worker Moves Nested Wrangler Config");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let starter = workspace.path().join("starters/api");
+    write_files(
+        &starter,
+        &[
+            (
+                "package.json",
+                r#"{"name": "api", "scripts": {"deploy": "wrangler deploy", "start": "wrangler dev", "cf-typegen": "wrangler types"}, "devDependencies": {"wrangler": "^4.0.0"}}"#,
+            ),
+            (
+                "worker/wrangler.jsonc",
+                "{\n\t// Generated by C3\n\t\"name\": \"api\",\n\t\"main\": \"src/index.ts\",\n\t\"assets\": { \"directory\": \"../public\" }\n}\n",
+            ),
+            ("worker/src/index.ts", "export default {};\n"),
+            ("public/index.html", "<p>hi</p>\n"),
+        ],
+    )?;
+
+    let starter_arg = starter.to_string_lossy().into_owned();
+    let output = workspace.run("shop", &["import", &starter_arg])?;
+    assert!(
+        output.status.success(),
+        "Import should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("create-cloudflare"), "{}", stdout);
+
+    let project = workspace_path.join("workers/api");
+    assert!(!project.join("worker/wrangler.jsonc").exists());
+    let wrangler = fs::read_to_string(project.join("wrangler.jsonc"))?;
+    assert!(wrangler.contains("// Generated by C3"), "{}", wrangler);
+    assert!(
+        wrangler.contains("\"main\": \"./worker/src/index.ts\""),
+        "{}",
+        wrangler
+    );
+    assert!(
+        wrangler.contains("\"directory\": \"./public\""),
+        "{}",
+        wrangler
+    );
+
+    let package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(project.join("package.json"))?)?;
+    assert_eq!(package["scripts"]["dev"], "wrangler dev");
+    assert!(
+        package["scripts"]["build"]
+            .as_str()
+            .is_some_and(|build| build.contains("--outdir dist"))
+    );
+
+    let moon = fs::read_to_string(project.join("moon.yml"))?;
+    assert!(moon.contains("stack: 'backend'"), "{}", moon);
+
+    Ok(())
+}
+
+#[test]
+fn test_import_cargo_generate_crate() -> anyhow::Result<()> {
+    log("→ Import Cargo Generate Crate");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let starter = workspace.path().join("starters/wasm-lib");
+    write_files(
+        &starter,
+        &[
+            (
+                "Cargo.toml",
+                "[package]\nname = \"wasm-lib\"\nversion = \"0.1.0\"\n\n[dependencies]\nwasm-bindgen = \"0.2\"\n",
+            ),
+            (
+                "src/lib.rs",
+                "pub fn add(a: u32, b: u32) -> u32 { a + b }\n",
+            ),
+            (".cargo-ok", ""),
+        ],
+    )?;
+
+    let starter_arg = starter.to_string_lossy().into_owned();
+    let output = workspace.run("shop", &["import", &starter_arg, "--name", "math"])?;
+    assert!(
+        output.status.success(),
+        "Import should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("cargo-generate"), "{}", stdout);
+
+    let project = workspace_path.join("crates/math");
+    let cargo = fs::read_to_string(project.join("Cargo.toml"))?;
+    assert!(cargo.contains("name = \"math\""), "{}", cargo);
+    assert!(
+        cargo.contains("[lib]\ncrate-type = [\"cdylib\", \"rlib\"]"),
+        "{}",
+        cargo
+    );
+    assert!(!project.join(".cargo-ok").exists());
+    assert!(project.join("moon.yml").exists());
+
+    // An existing project of the same name isn't overwritten
+    let output = workspace.run("shop", &["import", &starter_arg, "--name", "math"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists"));
+
+    Ok(())
+}
@@ -171,7 +171,7 @@ fn test_init_validates_workspace_name_format() -> anyhow::Result<()> {
         ));
 
         let mut cmd = Command::new(workspace.moonflare_binary());
-        cmd.arg("init").arg(&name).current_dir(workspace.path());
+        cmd.arg("init").arg(name).current_dir(workspace.path());
 
         let output = run_command_with_timeout(cmd, 10)?;
 
@@ -0,0 +1,91 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+/// Fake `moon` that succeeds, so init can finish without the real CLI
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(dir, &[("moon", "exit 0\n")])
+}
+
+fn init_in(
+    workspace: &MoonflareTestWorkspace,
+    dir: &Path,
+    args: &[&str],
+) -> anyhow::Result<std::process::Output> {
+    let path = fake_moon(workspace.path())?;
+    let mut cmd = Command::new(workspace.moonflare_binary());
+    cmd.arg("init")
+        .args(args)
+        .env("PATH", path)
+        .env("NO_COLOR", "1")
+        .current_dir(dir);
+    run_command_with_timeout(cmd, 10)
+}
+
+#[test]
+fn test_init_dot_is_refused_inside_a_project_directory() -> anyhow::Result<()> {
+    log("→ Init Dot Is Refused Inside A Project Directory");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let web = workspace_path.join("apps/web");
+    fs::create_dir_all(&web)?;
+
+    let output = init_in(&workspace, &web, &["."])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("inside the workspace"), "{}", stderr);
+    assert!(stderr.contains("'apps' holds"), "{}", stderr);
+    assert!(stderr.contains("moonflare add"), "{}", stderr);
+    assert!(stderr.contains("--force-nested"), "{}", stderr);
+    assert!(!web.join(".moon").exists());
+    assert!(fs::read_dir(&web)?.next().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_init_is_refused_below_a_workspace_root() -> anyhow::Result<()> {
+    log("→ Init Is Refused Below A Workspace Root");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    let output = init_in(&workspace, &workspace_path, &["tools"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("inside the workspace"), "{}", stderr);
+    assert!(stderr.contains("To add a project"), "{}", stderr);
+    assert!(!workspace_path.join("tools").exists());
+
+    // --path pointing into the workspace is caught too
+    let output = init_in(
+        &workspace,
+        workspace.path(),
+        &["tools", "--path", "shop/workers"],
+    )?;
+    assert!(!output.status.success());
+    assert!(!workspace_path.join("workers/tools").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_force_nested_creates_a_sub_workspace() -> anyhow::Result<()> {
+    log("→ Force Nested Creates A Sub Workspace");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    let output = init_in(&workspace, &workspace_path, &["tools", "--force-nested"])?;
+    assert!(
+        output.status.success(),
+        "Init should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(workspace_path.join("tools/.moon/workspace.yml").exists());
+
+    Ok(())
+}
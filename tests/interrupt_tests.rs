@@ -0,0 +1,161 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+mod common;
+
+/// A fake `moon` that writes its pid to moon.pid and hangs
+#[cfg(unix)]
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "case \"$*\" in run*) echo $$ > {}; exec sleep 30;; esac\nexit 1\n",
+                dir.join("moon.pid").display()
+            ),
+        )],
+    )
+}
+
+/// Start moonflare, wait for the fake moon to be running, then send SIGINT
+#[cfg(unix)]
+fn interrupt_after_start(
+    workspace: &MoonflareTestWorkspace,
+    workspace_path: &Path,
+    args: &[&str],
+) -> anyhow::Result<std::process::Output> {
+    let path = fake_moon(workspace.path())?;
+    let child = Command::new(workspace.moonflare_binary())
+        .args(args)
+        .current_dir(workspace_path)
+        .env("PATH", &path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let pid_file = workspace.path().join("moon.pid");
+    let started = Instant::now();
+    while !pid_file.exists() {
+        assert!(
+            started.elapsed() < Duration::from_secs(10),
+            "moon never started"
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    Ok(child.wait_with_output()?)
+}
+
+#[cfg(unix)]
+fn is_running(pid: &str) -> bool {
+    Command::new("kill")
+        .args(["-0", pid])
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ctrl_c_stops_children_and_summarizes() -> anyhow::Result<()> {
+    log("→ Ctrl-C Stops Children And Summarizes");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::create_dir_all(workspace_path.join("apps/web"))?;
+    fs::write(
+        workspace_path.join("apps/web/moon.yml"),
+        "tasks:\n  build:\n    command: 'pnpm build'\n",
+    )?;
+    let events = workspace.path().join("events.jsonl");
+
+    let output = interrupt_after_start(
+        &workspace,
+        &workspace_path,
+        &["build", "web", "--events-file", events.to_str().unwrap()],
+    )?;
+    assert_eq!(output.status.code(), Some(130));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Cancelled 'moonflare build'"), "{}", stderr);
+    assert!(
+        stderr.contains("moon run web:build (stopped)"),
+        "{}",
+        stderr
+    );
+
+    let pid = fs::read_to_string(workspace.path().join("moon.pid"))?;
+    assert!(!is_running(pid.trim()), "moon was left running");
+
+    let events = fs::read_to_string(&events)?;
+    assert!(events.contains("\"event\":\"cancelled\""), "{}", events);
+    assert!(
+        events.contains("\"event\":\"command_finished\""),
+        "{}",
+        events
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ctrl_c_releases_workspace_lock() -> anyhow::Result<()> {
+    log("→ Ctrl-C Releases Workspace Lock");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::create_dir_all(workspace_path.join("workers/api"))?;
+    fs::write(
+        workspace_path.join("workers/api/moon.yml"),
+        "tasks:\n  deploy:\n    command: 'wrangler deploy'\n",
+    )?;
+
+    let output = interrupt_after_start(&workspace, &workspace_path, &["deploy", "api"])?;
+    assert_eq!(
+        output.status.code(),
+        Some(130),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!workspace_path.join(".moonflare/workspace.lock").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_deploy_refuses_while_workspace_locked() -> anyhow::Result<()> {
+    log("→ Deploy Refuses While Workspace Locked");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::create_dir_all(workspace_path.join(".moonflare"))?;
+    // This test process is alive, so the lock isn't stale
+    fs::write(
+        workspace_path.join(".moonflare/workspace.lock"),
+        format!(
+            r#"{{"pid":{},"command":"deploy","started":"2026-01-01T00:00:00Z"}}"#,
+            std::process::id()
+        ),
+    )?;
+
+    let output = workspace.run("test-project", &["deploy"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Another 'moonflare deploy'"), "{}", stderr);
+
+    // A lock whose process is gone is taken over
+    fs::write(
+        workspace_path.join(".moonflare/workspace.lock"),
+        r#"{"pid":999999999,"command":"deploy","started":"2026-01-01T00:00:00Z"}"#,
+    )?;
+    let output = workspace.run("test-project", &["deploy"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Another 'moonflare deploy'"), "{}", stderr);
+    assert!(!workspace_path.join(".moonflare/workspace.lock").exists());
+
+    Ok(())
+}
@@ -0,0 +1,186 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const WRANGLER_JSONC: &str = r#"{
+  "name": "api",
+  "kv_namespaces": [
+    { "binding": "CACHE", "id": "prod-cache" },
+    { "binding": "SESSIONS", "id": "prod-sessions" }
+  ],
+  "env": {
+    "staging": {
+      "kv_namespaces": [{ "binding": "CACHE", "id": "staging-cache" }]
+    }
+  }
+}
+"#;
+
+/// Fake `pnpm` standing in for `wrangler kv`: it logs its arguments to pnpm.log, lists two keys,
+/// answers bulk reads with their values after a banner line, and copies bulk writes to
+/// bulk-put.json
+fn fake_pnpm(workspace_path: &Path) -> anyhow::Result<String> {
+    let pnpm = format!(
+        r#"echo "$@" >> {log}
+case "$4 $5" in
+  "key list") echo '[{{"name":"user:1","expiration":1893456000}},{{"name":"user:2","metadata":{{"v":2}}}}]' ;;
+  "key get") printf 'value of %s' "$6" ;;
+  "bulk get") echo 'wrangler 4.0.0'; echo '{{"user:1":"Ada","user:2":"{{\"name\":\"Grace\"}}"}}' ;;
+  "bulk put") cp "$6" {put} ;;
+esac
+"#,
+        log = workspace_path.join("pnpm.log").display(),
+        put = workspace_path.join("bulk-put.json").display()
+    );
+    fake_tools(workspace_path, &[("pnpm", &pnpm)])
+}
+
+fn api_workspace(
+    workspace: &MoonflareTestWorkspace,
+) -> anyhow::Result<(std::path::PathBuf, String)> {
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", WRANGLER_JSONC)],
+    )?;
+    let path = fake_pnpm(&workspace_path)?;
+    Ok((workspace_path, path))
+}
+
+#[test]
+fn test_kv_keys_get_and_put_resolve_the_binding() -> anyhow::Result<()> {
+    log("→ KV Keys Get And Put Resolve The Binding");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (workspace_path, path) = api_workspace(&workspace)?;
+    let env = [("PATH", path.as_str())];
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["kv", "keys", "api", "CACHE", "--prefix", "user:", "--json"],
+        &env,
+    )?;
+    assert!(
+        output.status.success(),
+        "Keys should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let keys: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(keys[0]["name"], "user:1");
+    assert_eq!(keys[1]["metadata"]["v"], 2);
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["kv", "get", "api", "CACHE", "user:1", "--env", "staging"],
+        &env,
+    )?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "value of user:1");
+
+    let output = workspace.run_with_env(
+        "shop",
+        &[
+            "kv", "put", "api", "SESSIONS", "greeting", "hi", "--ttl", "60", "--local",
+        ],
+        &env,
+    )?;
+    assert!(
+        output.status.success(),
+        "Put should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let log = fs::read_to_string(workspace_path.join("pnpm.log"))?;
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "exec wrangler kv key list --prefix user: --binding CACHE --remote",
+            "exec wrangler kv key get user:1 --binding CACHE --remote --env staging",
+            "exec wrangler kv key put greeting hi --ttl 60 --binding SESSIONS --local",
+        ]
+    );
+
+    // Staging only binds CACHE, and bindings aren't inherited
+    let output = workspace.run_with_env(
+        "shop",
+        &["kv", "keys", "api", "SESSIONS", "--env", "staging"],
+        &env,
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no KV binding named SESSIONS in 'staging'"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("its KV bindings are CACHE"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn test_kv_export_and_import_use_json_lines() -> anyhow::Result<()> {
+    log("→ KV Export And Import Use JSON Lines");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (workspace_path, path) = api_workspace(&workspace)?;
+    let env = [("PATH", path.as_str())];
+
+    let output = workspace.run_with_env("shop", &["kv", "export", "api", "CACHE"], &env)?;
+    assert!(
+        output.status.success(),
+        "Export should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(lines.len(), 2, "{}", stdout);
+    assert_eq!(lines[0]["key"], "user:1");
+    assert_eq!(lines[0]["value"], "Ada");
+    assert_eq!(lines[0]["expiration"], 1893456000);
+    assert_eq!(lines[1]["value"], r#"{"name":"Grace"}"#);
+    assert_eq!(lines[1]["metadata"]["v"], 2);
+
+    // What export writes, import reads back
+    fs::write(workspace_path.join("cache.jsonl"), stdout.as_bytes())?;
+    let output = workspace.run_with_env(
+        "shop",
+        &["kv", "import", "api", "CACHE", "cache.jsonl", "--local"],
+        &env,
+    )?;
+    assert!(
+        output.status.success(),
+        "Import should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let written: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(workspace_path.join("bulk-put.json"))?)?;
+    assert_eq!(written.as_array().unwrap().len(), 2);
+    assert_eq!(written[0]["key"], "user:1");
+    assert_eq!(written[1]["metadata"]["v"], 2);
+    let log = fs::read_to_string(workspace_path.join("pnpm.log"))?;
+    assert!(log.contains("--binding CACHE --local"), "{}", log);
+
+    fs::write(
+        workspace_path.join("broken.jsonl"),
+        "{\"key\":\"a\",\"value\":\"1\"}\n\n{\"key\":\"b\"}\n",
+    )?;
+    let output = workspace.run_with_env(
+        "shop",
+        &["kv", "import", "api", "CACHE", "broken.jsonl"],
+        &env,
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("broken.jsonl:3"), "{}", stderr);
+
+    Ok(())
+}
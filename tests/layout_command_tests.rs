@@ -0,0 +1,133 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_layout_set_moves_directory_and_references() -> anyhow::Result<()> {
+    log("→ Layout Set Moves Directory And References");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "# Moonflare workspace configuration\n\n[workspace]\nname = \"shop\"\n",
+    )?;
+    fs::write(
+        workspace_path.join("pnpm-workspace.yaml"),
+        "packages:\n  - 'apps/*'\n  - 'sites/*'\n  - 'workers/*'\n",
+    )?;
+    fs::write(
+        workspace_path.join("justfile"),
+        "setup:\n    @mkdir -p apps crates sites workers shared-wasm\n    @touch sites/.keep\n",
+    )?;
+    fs::create_dir_all(workspace_path.join("shared-wasm"))?;
+    fs::write(
+        workspace_path.join("shared-wasm/gather-wasm.cjs"),
+        "const cratesDir = path.join(workspaceRoot, 'crates');\n",
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::Astro,
+        "marketing",
+        &[("package.json", "{\"name\": \"marketing\"}\n")],
+    )?;
+    workspace.create_minimal_project("shop", &ProjectType::Crate, "math", &[])?;
+
+    let output = workspace.run("shop", &["layout", "set", "sites=websites", "crates=wasm"])?;
+    assert!(
+        output.status.success(),
+        "Layout set should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert!(workspace_path.join("websites/marketing/moon.yml").exists());
+    assert!(workspace_path.join("wasm/math/moon.yml").exists());
+    assert!(!workspace_path.join("sites").exists());
+
+    let config = fs::read_to_string(workspace_path.join("moonflare.toml"))?;
+    assert!(config.starts_with("# Moonflare workspace configuration"));
+    assert!(
+        config.contains("[layout]\nsites = \"websites\"\ncrates = \"wasm\"\n"),
+        "{}",
+        config
+    );
+
+    let moon = fs::read_to_string(workspace_path.join(".moon/workspace.yml"))?;
+    assert!(
+        moon.contains("websites/*") && moon.contains("wasm/*"),
+        "{}",
+        moon
+    );
+    assert!(
+        !moon.contains("\"sites/*\"") && !moon.contains("crates/*"),
+        "{}",
+        moon
+    );
+
+    let pnpm = fs::read_to_string(workspace_path.join("pnpm-workspace.yaml"))?;
+    assert!(
+        pnpm.contains("'websites/*'") && pnpm.contains("'apps/*'"),
+        "{}",
+        pnpm
+    );
+
+    let justfile = fs::read_to_string(workspace_path.join("justfile"))?;
+    assert!(
+        justfile.contains("@mkdir -p apps wasm websites workers shared-wasm"),
+        "{}",
+        justfile
+    );
+    assert!(justfile.contains("@touch websites/.keep"), "{}", justfile);
+
+    let gather = fs::read_to_string(workspace_path.join("shared-wasm/gather-wasm.cjs"))?;
+    assert!(
+        gather.contains("path.join(workspaceRoot, 'wasm')"),
+        "{}",
+        gather
+    );
+
+    let output = workspace.run("shop", &["layout", "show"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("websites/") && stdout.contains("wasm/"),
+        "{}",
+        stdout
+    );
+
+    // Projects are found in the moved directories
+    let output = workspace.run("shop", &["rename", "marketing", "landing"])?;
+    assert!(
+        output.status.success(),
+        "Rename should find the moved project: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(workspace_path.join("websites/landing").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_layout_set_rejects_invalid_assignments() -> anyhow::Result<()> {
+    log("→ Layout Set Rejects Invalid Assignments");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project("shop", &ProjectType::React, "web", &[])?;
+
+    for (args, message) in [
+        (vec!["layout", "set", "docs=website"], "Unknown layout key"),
+        (vec!["layout", "set", "sites=apps"], "can't share"),
+        (vec!["layout", "set", "apps=shared-wasm"], "reserved"),
+        (
+            vec!["layout", "set", "apps=web/apps"],
+            "valid directory name",
+        ),
+    ] {
+        let output = workspace.run("shop", &args)?;
+        assert!(!output.status.success(), "{:?} should fail", args);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(message), "{:?}: {}", args, stderr);
+    }
+    assert!(workspace_path.join("apps/web").exists());
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` that succeeds at every run
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(dir, &[("moon", "exit 0\n")])
+}
+
+#[test]
+fn test_build_bundles_license_notices() -> anyhow::Result<()> {
+    log("→ Build Bundles License Notices");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[licenses]\nbundle = true\n",
+    )?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "web",
+        &[
+            (
+                "package.json",
+                r#"{"name": "web", "dependencies": {"react": "^19.0.0"}}"#,
+            ),
+            ("moon.yml", "tasks:\n  build:\n    command: 'true'\n"),
+            ("dist/index.html", "<html></html>\n"),
+        ],
+    )?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            (
+                "package.json",
+                r#"{"name": "api", "dependencies": {"hono": "^4.8.2"}}"#,
+            ),
+            ("moon.yml", "tasks:\n  build:\n    command: 'true'\n"),
+        ],
+    )?;
+    fs::write(
+        workspace_path.join("pnpm-lock.yaml"),
+        r#"lockfileVersion: '9.0'
+importers:
+  apps/web:
+    dependencies:
+      react: {specifier: ^19.0.0, version: 19.0.0}
+  workers/api:
+    dependencies:
+      hono: {specifier: ^4.8.2, version: 4.8.2}
+snapshots:
+  react@19.0.0: {}
+  hono@4.8.2: {}
+"#,
+    )?;
+    for (name, version) in [("react", "19.0.0"), ("hono", "4.8.2")] {
+        let dir = workspace_path
+            .join("node_modules/.pnpm")
+            .join(format!("{}@{}", name, version))
+            .join("node_modules")
+            .join(name);
+        fs::create_dir_all(&dir)?;
+        fs::write(
+            dir.join("package.json"),
+            format!(
+                r#"{{"name": "{}", "version": "{}", "license": "MIT"}}"#,
+                name, version
+            ),
+        )?;
+    }
+    let path = fake_moon(&workspace_path)?;
+
+    let output = workspace.run_with_env("test-project", &["build"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let notice = fs::read_to_string(workspace_path.join("apps/web/dist/third-party-licenses.txt"))?;
+    assert!(
+        notice.starts_with("Third-party licenses for web\n"),
+        "{}",
+        notice
+    );
+    assert!(
+        notice.contains("MIT (1)\n  react@19.0.0 (npm)\n"),
+        "{}",
+        notice
+    );
+    assert!(!notice.contains("hono"), "{}", notice);
+
+    let module =
+        fs::read_to_string(workspace_path.join("workers/api/src/third-party-licenses.ts"))?;
+    assert!(
+        module.contains("export default \"Third-party licenses for api\\n"),
+        "{}",
+        module
+    );
+    assert!(module.contains("hono@4.8.2 (npm)"), "{}", module);
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+fn install_npm_package(
+    root: &Path,
+    name: &str,
+    version: &str,
+    license: &str,
+) -> anyhow::Result<()> {
+    let dir = root
+        .join("node_modules/.pnpm")
+        .join(format!("{}@{}", name.replace('/', "+"), version))
+        .join("node_modules")
+        .join(name);
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.join("package.json"),
+        format!(
+            r#"{{"name": "{}", "version": "{}", "license": "{}"}}"#,
+            name, version, license
+        ),
+    )?;
+    Ok(())
+}
+
+fn setup(workspace: &MoonflareTestWorkspace) -> anyhow::Result<std::path::PathBuf> {
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "package.json",
+            r#"{"name": "api", "dependencies": {"hono": "^4.8.2", "copyleft": "^1.0.0", "dual": "^2.0.0"}}"#,
+        )],
+    )?;
+    fs::write(
+        workspace_path.join("pnpm-lock.yaml"),
+        r#"lockfileVersion: '9.0'
+importers:
+  workers/api:
+    dependencies:
+      hono: {specifier: ^4.8.2, version: 4.8.2}
+      copyleft: {specifier: ^1.0.0, version: 1.0.0}
+      dual: {specifier: ^2.0.0, version: 2.0.0}
+snapshots:
+  hono@4.8.2: {}
+  copyleft@1.0.0: {}
+  dual@2.0.0: {}
+"#,
+    )?;
+    install_npm_package(&workspace_path, "hono", "4.8.2", "MIT")?;
+    install_npm_package(&workspace_path, "copyleft", "1.0.0", "GPL-3.0-only")?;
+    install_npm_package(&workspace_path, "dual", "2.0.0", "(GPL-2.0 OR MIT)")?;
+    Ok(workspace_path)
+}
+
+#[test]
+fn test_licenses_flags_disallowed_in_json() -> anyhow::Result<()> {
+    log("→ Licenses Flags Disallowed In JSON");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = setup(&workspace)?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[licenses]\ndisallowed = [\"GPL-*\"]\n",
+    )?;
+
+    let output = workspace.run("test-project", &["licenses", "--format", "json"])?;
+    assert!(
+        !output.status.success(),
+        "Disallowed licenses should fail the command"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("copyleft@1.0.0"));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let find = |name: &str| {
+        report
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["name"] == name)
+            .cloned()
+            .unwrap()
+    };
+    assert_eq!(find("hono")["license"], "MIT");
+    assert_eq!(find("copyleft")["disallowed"], true);
+    assert_eq!(
+        find("dual")["disallowed"],
+        false,
+        "An OR expression with an allowed alternative is fine"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_licenses_writes_csv() -> anyhow::Result<()> {
+    log("→ Licenses Writes CSV");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = setup(&workspace)?;
+
+    let output = workspace.run(
+        "test-project",
+        &["licenses", "--format", "csv", "--output", "licenses.csv"],
+    )?;
+    assert!(
+        output.status.success(),
+        "Report without disallowed licenses should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let csv = fs::read_to_string(workspace_path.join("licenses.csv"))?;
+    assert!(csv.starts_with("ecosystem,name,version,license,disallowed,projects"));
+    assert!(csv.contains("npm,hono,4.8.2,MIT,false,api"));
+    assert!(csv.contains("npm,dual,2.0.0,(GPL-2.0 OR MIT),false,api"));
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use common::*;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+
+mod common;
+
+/// Serve every request with the given status line until the test process exits
+fn spawn_http_server(status: &'static str) -> anyhow::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = format!("http://{}", listener.local_addr()?);
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            std::thread::spawn(move || {
+                let mut stream = stream;
+                let mut buffer = [0u8; 1024];
+                let _ = stream.read(&mut buffer);
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                    status
+                );
+                let _ = stream.write_all(response.as_bytes());
+            });
+        }
+    });
+
+    Ok(address)
+}
+
+#[test]
+fn test_loadtest_reports_percentiles() -> anyhow::Result<()> {
+    log("→ Loadtest Reports Percentiles");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::DurableObject, "api", &[])?;
+    let url = spawn_http_server("200 OK")?;
+
+    let output = workspace.run(
+        "test-project",
+        &[
+            "loadtest",
+            "api",
+            "--url",
+            &url,
+            "--rps",
+            "20",
+            "--duration",
+            "1s",
+        ],
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Load test should pass: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Latency p95"),
+        "Should report percentiles: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Errors       0"),
+        "Should report no errors: {}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_loadtest_fails_when_thresholds_exceeded() -> anyhow::Result<()> {
+    log("→ Loadtest Fails When Thresholds Exceeded");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::DurableObject, "api", &[])?;
+    std::fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[loadtest]\nmax_error_rate = 0.0\n",
+    )?;
+    let url = spawn_http_server("500 Internal Server Error")?;
+
+    let output = workspace.run(
+        "test-project",
+        &[
+            "loadtest",
+            "api",
+            "--url",
+            &url,
+            "--rps",
+            "10",
+            "--duration",
+            "500ms",
+        ],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("error rate"),
+        "Should report the violated threshold: {}",
+        stderr
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_loadtest_deployed_requires_url() -> anyhow::Result<()> {
+    log("→ Loadtest Deployed Requires Url");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::DurableObject, "api", &[])?;
+
+    let output = workspace.run(
+        "test-project",
+        &["loadtest", "api", "--against", "deployed"],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No deployed URL known"));
+
+    Ok(())
+}
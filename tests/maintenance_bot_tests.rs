@@ -0,0 +1,54 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_add_maintenance_bot_watches_workspace_workers() -> anyhow::Result<()> {
+    log("→ Add Maintenance Bot Watches Workspace Workers");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "reports",
+        &[(
+            "wrangler.toml",
+            "name = \"acme-reports\"\nmain = \"src/index.ts\"\n\n[triggers]\ncrons = [\"0 6 * * *\"]\n",
+        )],
+    )?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["add", "maintenance-bot", "ops-bot"],
+        &[("CLOUDFLARE_ACCOUNT_ID", "account-123")],
+    )?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let bot = workspace_path.join("workers/ops-bot");
+    let projects = fs::read_to_string(bot.join("src/projects.ts"))?;
+    assert!(
+        projects.contains("\"worker\": \"acme-reports\""),
+        "{}",
+        projects
+    );
+    assert!(projects.contains("\"0 6 * * *\""), "{}", projects);
+
+    let wrangler = fs::read_to_string(bot.join("wrangler.toml"))?;
+    assert!(wrangler.contains("crons = ["), "{}", wrangler);
+    assert!(
+        wrangler.contains("ACCOUNT_ID = \"account-123\""),
+        "{}",
+        wrangler
+    );
+
+    let example = fs::read_to_string(bot.join(".dev.vars.example"))?;
+    assert!(example.contains("CLOUDFLARE_API_TOKEN"), "{}", example);
+    assert!(example.contains("ALERT_WEBHOOK_URL"), "{}", example);
+
+    Ok(())
+}
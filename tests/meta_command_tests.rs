@@ -0,0 +1,101 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` that logs the workspace it ran in, failing inside `fail_in`
+fn fake_moon(dir: &Path, fail_in: &str) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "echo \"$(basename \"$PWD\") $@ env=$WRANGLER_ENV\" >> {}\ncase \"$PWD\" in *{}) exit 1;; esac\n",
+                dir.join("moon-runs.log").display(),
+                fail_in
+            ),
+        )],
+    )
+}
+
+/// A repository at `repo/` holding the `edge` and `storefront` workspaces, each with a Worker
+fn setup(workspace: &MoonflareTestWorkspace) -> anyhow::Result<std::path::PathBuf> {
+    for name in ["repo/edge", "repo/storefront"] {
+        workspace.create_minimal_workspace(name)?;
+        workspace.create_minimal_project(
+            name,
+            &ProjectType::DurableObject,
+            "api",
+            &[("wrangler.toml", "name = \"api\"\nmain = \"src/index.ts\"\n")],
+        )?;
+    }
+    Ok(workspace.path().join("repo"))
+}
+
+#[test]
+fn test_meta_deploy_respects_workspace_environments() -> anyhow::Result<()> {
+    log("→ Meta Deploy Respects Workspace Environments");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let repo = setup(&workspace)?;
+    let path = fake_moon(workspace.path(), "nothing")?;
+    fs::write(
+        repo.join("moonflare-meta.toml"),
+        "[[workspaces]]\npath = \"edge\"\nenvironments = [\"production\"]\n\n[[workspaces]]\npath = \"storefront\"\n",
+    )?;
+
+    let output = workspace.run_with_env(
+        "repo",
+        &["meta", "deploy", "--env", "staging"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Meta deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("doesn't deploy to 'staging'"), "{}", stdout);
+
+    let runs = fs::read_to_string(workspace.path().join("moon-runs.log"))?;
+    assert!(
+        runs.contains("storefront run api:deploy env=staging"),
+        "{}",
+        runs
+    );
+    assert!(!runs.contains("edge"), "{}", runs);
+
+    let output = workspace.run("repo", &["meta", "status"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("never deployed"), "{}", stdout);
+    assert!(stdout.contains("staging"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_meta_discovers_workspaces_and_reports_failures() -> anyhow::Result<()> {
+    log("→ Meta Discovers Workspaces And Reports Failures");
+    let workspace = MoonflareTestWorkspace::new()?;
+    setup(&workspace)?;
+    let path = fake_moon(workspace.path(), "edge")?;
+
+    let output = workspace.run_with_env("repo", &["meta", "deploy"], &[("PATH", &path)])?;
+    assert!(!output.status.success(), "A failed workspace fails the run");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 of 2 workspace(s) failed"), "{}", stderr);
+
+    // The failure in edge doesn't stop storefront from deploying
+    let runs = fs::read_to_string(workspace.path().join("moon-runs.log"))?;
+    assert!(runs.contains("edge run api:deploy"), "{}", runs);
+    assert!(runs.contains("storefront run api:deploy"), "{}", runs);
+
+    let output = workspace.run("repo", &["meta", "status", "--workspace", "missing"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No workspace matches 'missing'"));
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+mod common;
+
+const RUN_REPORT: &str = r#"{"actions": [
+  {"label": "RunTask(api:build)", "status": "cached", "duration": {"secs": 0, "nanos": 250000000}},
+  {"label": "RunTask(web:build)", "status": "passed", "duration": {"secs": 3, "nanos": 0}}
+]}"#;
+
+/// Fake `moon` that writes a run report and exits with `status`
+fn fake_moon(dir: &Path, status: i32) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "mkdir -p .moon/cache\ncat > .moon/cache/runReport.json <<'EOF'\n{}\nEOF\nexit {}\n",
+                RUN_REPORT, status
+            ),
+        )],
+    )
+}
+
+/// A received request: its request line, lowercased headers and JSON body
+type Request = (String, Vec<String>, serde_json::Value);
+
+/// Collector that accepts any POST and hands each request to the returned receiver
+fn mock_collector() -> anyhow::Result<(String, Receiver<Request>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut headers = Vec::new();
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                let header = header.trim().to_lowercase();
+                if let Some(value) = header.strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+                headers.push(header);
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok"
+            )
+            .unwrap();
+            sender
+                .send((
+                    request_line.trim().to_string(),
+                    headers,
+                    serde_json::from_slice(&body).unwrap_or_default(),
+                ))
+                .ok();
+        }
+    });
+
+    Ok((url, receiver))
+}
+
+#[test]
+fn test_build_exports_analytics_engine_points() -> anyhow::Result<()> {
+    log("→ Build Exports Analytics Engine Points");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (url, received) = mock_collector()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        format!(
+            "[workspace]\nname = \"shop\"\n\n[metrics]\nenabled = true\nendpoint = \"{}/ingest\"\ndataset = \"builds\"\n",
+            url
+        ),
+    )?;
+    let path = fake_moon(&workspace_path, 0)?;
+
+    let output = workspace.run_with_env("test-project", &["build"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let (request_line, _, body) = received.recv_timeout(Duration::from_secs(5))?;
+    assert!(request_line.starts_with("POST /ingest"), "{}", request_line);
+    assert_eq!(body["dataset"], "builds");
+    let points = body["dataPoints"].as_array().cloned().unwrap_or_default();
+    assert_eq!(points.len(), 3, "{}", body);
+    assert_eq!(points[0]["indexes"][0], "shop");
+    assert_eq!(points[0]["blobs"][0], "command");
+    assert_eq!(points[0]["blobs"][1], "build");
+    assert_eq!(points[0]["blobs"][2], "success");
+    assert_eq!(points[0]["doubles"][1], 1.0);
+    assert_eq!(points[1]["blobs"][3], "api:build");
+
+    Ok(())
+}
+
+#[test]
+fn test_failed_build_exports_otlp_with_token() -> anyhow::Result<()> {
+    log("→ Failed Build Exports OTLP With Token");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (url, received) = mock_collector()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        format!(
+            "[metrics]\nenabled = true\nformat = \"otlp\"\nendpoint = \"{}\"\ntoken = \"$METRICS_TOKEN\"\ncommands = [\"build\"]\n",
+            url
+        ),
+    )?;
+    let path = fake_moon(&workspace_path, 1)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build"],
+        &[("PATH", &path), ("METRICS_TOKEN", "s3cret")],
+    )?;
+    assert!(!output.status.success());
+
+    let (request_line, headers, body) = received.recv_timeout(Duration::from_secs(5))?;
+    assert!(
+        request_line.starts_with("POST /v1/metrics"),
+        "{}",
+        request_line
+    );
+    assert!(
+        headers.iter().any(|h| h == "authorization: bearer s3cret"),
+        "{:?}",
+        headers
+    );
+    let metrics = &body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"];
+    let failures = metrics
+        .as_array()
+        .and_then(|m| m.iter().find(|m| m["name"] == "moonflare.command.failures"))
+        .cloned()
+        .unwrap_or_default();
+    assert_eq!(failures["sum"]["dataPoints"][0]["asInt"], "1", "{}", body);
+
+    // Commands left out of `commands` aren't exported
+    workspace.run_with_env("test-project", &["doctor"], &[("PATH", &path)])?;
+    assert!(received.recv_timeout(Duration::from_millis(500)).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+const PACKAGE_JSON: &str = r#"{
+  "name": "my-worker",
+  "version": "0.0.0",
+  "private": true,
+  "scripts": {
+    "deploy": "wrangler deploy",
+    "dev": "wrangler dev",
+    "start": "wrangler dev",
+    "test": "vitest",
+    "cf-typegen": "wrangler types"
+  },
+  "devDependencies": {
+    "wrangler": "^4.0.0",
+    "vitest": "^3.0.0"
+  }
+}
+"#;
+
+const WRANGLER_JSONC: &str = r#"{
+  // Created by create-cloudflare
+  "name": "my-worker",
+  "main": "src/index.ts",
+  "compatibility_date": "2025-01-01"
+}
+"#;
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+    anyhow::ensure!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Fake `moon` that only reports its version
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(dir, &[("moon", "echo moon 1.30.0\n")])
+}
+
+#[test]
+fn test_migrate_c3_moves_the_worker_into_a_new_workspace() -> anyhow::Result<()> {
+    log("→ Migrate C3 Moves The Worker Into A New Workspace");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let repo = workspace.path().join("shop");
+    fs::create_dir_all(repo.join("src"))?;
+    fs::write(repo.join("package.json"), PACKAGE_JSON)?;
+    fs::write(repo.join("wrangler.jsonc"), WRANGLER_JSONC)?;
+    fs::write(repo.join("package-lock.json"), "{}\n")?;
+    fs::write(
+        repo.join("src/index.ts"),
+        "export default { fetch: () => new Response('ok') };\n",
+    )?;
+    fs::write(repo.join(".gitignore"), "node_modules\n")?;
+    git(&repo, &["init", "-q"])?;
+    git(&repo, &["add", "-A"])?;
+    git(&repo, &["commit", "-q", "-m", "Initial commit"])?;
+    let path = fake_moon(workspace.path())?;
+
+    fs::write(repo.join("notes.txt"), "wip\n")?;
+    git(&repo, &["add", "notes.txt"])?;
+    let output = workspace.run_with_env("shop", &["migrate", "c3"], &[("PATH", path.as_str())])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("uncommitted change"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!repo.join(".moon").exists());
+    git(&repo, &["rm", "-q", "-f", "notes.txt"])?;
+
+    let output = workspace.run_with_env("shop", &["migrate", "c3"], &[("PATH", path.as_str())])?;
+    assert!(
+        output.status.success(),
+        "Migration should succeed: {}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let project = repo.join("workers/my-worker");
+    assert!(project.join("wrangler.jsonc").exists());
+    assert!(project.join("src/index.ts").exists());
+    assert!(project.join("moon.yml").exists());
+    assert!(!project.join("package-lock.json").exists());
+    assert!(!repo.join(".moonflare-c3").exists());
+    assert!(repo.join(".moon/workspace.yml").exists());
+    assert!(repo.join("moonflare.toml").exists());
+
+    let root_package = fs::read_to_string(repo.join("package.json"))?;
+    assert!(
+        root_package.contains("\"cf-typegen\": \"pnpm --filter my-worker cf-typegen\""),
+        "{}",
+        root_package
+    );
+
+    let staged = git(&repo, &["diff", "--cached", "--name-status", "-M"])?;
+    assert!(
+        staged.contains("wrangler.jsonc\tworkers/my-worker/wrangler.jsonc"),
+        "The move should be staged as a rename: {}",
+        staged
+    );
+
+    let output = workspace.run_with_env("shop", &["migrate", "c3"], &[("PATH", path.as_str())])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("already a workspace"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
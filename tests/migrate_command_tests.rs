@@ -0,0 +1,128 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_migrate_pages_to_worker_converts_static_site() -> anyhow::Result<()> {
+    log("→ Migrate Pages To Worker Converts Static Site");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::Astro,
+        "docs",
+        &[
+            (
+                "wrangler.toml",
+                "name = \"docs\"\ncompatibility_date = \"2025-01-01\"\npages_build_output_dir = \"./build\"\n",
+            ),
+            (
+                "package.json",
+                r#"{"name": "docs", "scripts": {"build": "astro build", "deploy": "wrangler pages deploy ./build", "preview": "wrangler pages dev ./build"}}"#,
+            ),
+            (
+                "moon.yml",
+                "tasks:\n  publish:\n    command: 'wrangler pages deploy build'\n",
+            ),
+            ("_headers", "/*\n  X-Frame-Options: DENY\n"),
+            ("public/favicon.svg", "<svg/>\n"),
+        ],
+    )?;
+
+    let output = workspace.run("shop", &["migrate", "pages-to-worker", "docs"])?;
+    assert!(
+        output.status.success(),
+        "Migrate should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let project = workspace_path.join("sites/docs");
+    let wrangler: toml::Value =
+        toml::from_str(&fs::read_to_string(project.join("wrangler.toml"))?)?;
+    assert!(wrangler.get("pages_build_output_dir").is_none());
+    assert!(wrangler.get("main").is_none());
+    assert_eq!(wrangler["assets"]["directory"].as_str(), Some("./build"));
+    assert_eq!(
+        wrangler["assets"]["not_found_handling"].as_str(),
+        Some("404-page")
+    );
+
+    let package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(project.join("package.json"))?)?;
+    let deploy = package["scripts"]["deploy"].as_str().unwrap_or_default();
+    assert!(
+        deploy.starts_with("wrangler deploy") && deploy.contains("WRANGLER_ENV"),
+        "{}",
+        deploy
+    );
+    assert_eq!(package["scripts"]["preview"], "wrangler dev");
+
+    let moon = fs::read_to_string(project.join("moon.yml"))?;
+    assert!(moon.contains("command: 'wrangler deploy'"), "{}", moon);
+
+    assert!(project.join("public/_headers").exists());
+    assert!(!project.join("_headers").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_migrate_pages_to_worker_generates_routing_worker() -> anyhow::Result<()> {
+    log("→ Migrate Pages To Worker Generates Routing Worker");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[
+            (
+                "wrangler.jsonc",
+                "{\n  // Pages project\n  \"name\": \"web\",\n  \"pages_build_output_dir\": \"./dist\"\n}\n",
+            ),
+            (
+                "public/_routes.json",
+                r#"{"version": 1, "include": ["/api/*"], "exclude": ["/api/docs/*"]}"#,
+            ),
+            ("functions/api/[id].ts", "export const onRequest = () => {};\n"),
+        ],
+    )?;
+
+    let output = workspace.run(
+        "shop",
+        &["migrate", "pages-to-worker", "web", "--routing-worker"],
+    )?;
+    assert!(
+        output.status.success(),
+        "Migrate should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let project = workspace_path.join("apps/web");
+    let wrangler: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(project.join("wrangler.jsonc"))?)?;
+    assert_eq!(wrangler["main"], "src/worker.ts");
+    assert_eq!(wrangler["assets"]["binding"], "ASSETS");
+    assert_eq!(
+        wrangler["assets"]["not_found_handling"],
+        "single-page-application"
+    );
+    assert_eq!(
+        wrangler["assets"]["run_worker_first"],
+        serde_json::json!(["/api/*", "!/api/docs/*"])
+    );
+    assert!(wrangler["compatibility_date"].is_string());
+    assert!(!project.join("public/_routes.json").exists());
+
+    let worker = fs::read_to_string(project.join("src/worker.ts"))?;
+    assert!(worker.contains("functions/api/[id].ts"), "{}", worker);
+    assert!(worker.contains("env.ASSETS.fetch(request)"), "{}", worker);
+
+    // Once migrated, the project is no longer a Pages project
+    let output = workspace.run("shop", &["migrate", "pages-to-worker", "web"])?;
+    assert!(!output.status.success(), "A Worker isn't a Pages project");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Pages project"));
+
+    Ok(())
+}
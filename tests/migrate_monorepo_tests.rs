@@ -0,0 +1,375 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+const TURBO_JSON: &str = r#"{
+  "$schema": "https://turbo.build/schema.json",
+  // Shared by every package
+  "globalDependencies": ["tsconfig.base.json"],
+  "remoteCache": { "signature": true },
+  "tasks": {
+    "build": {
+      "dependsOn": ["^build"],
+      "outputs": ["dist/**"]
+    },
+    "test": {
+      "dependsOn": ["build"],
+      "env": ["API_URL"]
+    },
+    "dev": {
+      "cache": false,
+      "persistent": true
+    },
+    "lint": {
+      "dotEnv": [".env"]
+    }
+  }
+}
+"#;
+
+const ROOT_PACKAGE_JSON: &str = r#"{
+  "name": "acme",
+  "private": true,
+  "workspaces": ["apps/*", "packages/*"],
+  "scripts": {
+    "build": "turbo run build",
+    "dev": "turbo dev",
+    "release": "turbo run build && changeset publish",
+    "format": "prettier --write ."
+  },
+  "devDependencies": {
+    "prettier": "^3.0.0",
+    "turbo": "^2.0.0"
+  }
+}
+"#;
+
+const WRANGLER_JSONC: &str = r#"{
+  "name": "acme-api",
+  "main": "src/index.ts",
+  "compatibility_date": "2025-01-01"
+}
+"#;
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+    anyhow::ensure!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Fake `moon` that only reports its version
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(dir, &[("moon", "echo moon 1.30.0\n")])
+}
+
+fn write(root: &Path, file: &str, content: &str) -> anyhow::Result<()> {
+    let path = root.join(file);
+    fs::create_dir_all(path.parent().unwrap())?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// A Worker under `worker_dir`, a Vite React app in apps/web and a shared package in
+/// packages/ui, committed
+fn create_turborepo(repo: &Path, worker_dir: &str) -> anyhow::Result<()> {
+    write(repo, "turbo.json", TURBO_JSON)?;
+    write(repo, "package.json", ROOT_PACKAGE_JSON)?;
+    write(repo, "package-lock.json", "{}\n")?;
+    write(repo, "tsconfig.base.json", "{}\n")?;
+    write(repo, "README.md", "# Acme\n")?;
+    write(repo, ".gitignore", "node_modules\n.turbo\n")?;
+
+    write(
+        repo,
+        &format!("{}/package.json", worker_dir),
+        r#"{
+  "name": "@acme/api",
+  "scripts": { "dev": "wrangler dev", "deploy": "wrangler deploy", "build": "tsc", "test": "vitest" },
+  "dependencies": { "@acme/ui": "workspace:*" },
+  "devDependencies": { "wrangler": "^4.0.0" }
+}
+"#,
+    )?;
+    write(
+        repo,
+        &format!("{}/wrangler.jsonc", worker_dir),
+        WRANGLER_JSONC,
+    )?;
+    write(
+        repo,
+        &format!("{}/src/index.ts", worker_dir),
+        "export default { fetch: () => new Response('ok') };\n",
+    )?;
+
+    write(
+        repo,
+        "apps/web/package.json",
+        r#"{
+  "name": "@acme/web",
+  "scripts": { "dev": "vite", "build": "vite build" },
+  "dependencies": { "react": "^19.0.0" },
+  "devDependencies": { "vite": "^7.0.0" }
+}
+"#,
+    )?;
+    write(repo, "apps/web/vite.config.ts", "export default {};\n")?;
+    write(repo, "apps/web/index.html", "<div id=\"root\"></div>\n")?;
+
+    write(
+        repo,
+        "packages/ui/package.json",
+        r#"{
+  "name": "@acme/ui",
+  "scripts": { "build": "tsc", "lint": "eslint ." }
+}
+"#,
+    )?;
+    write(repo, "packages/ui/src/index.ts", "export const ui = 1;\n")?;
+
+    git(repo, &["init", "-q"])?;
+    git(repo, &["add", "-A"])?;
+    git(repo, &["commit", "-q", "-m", "Initial commit"])?;
+    Ok(())
+}
+
+fn assert_success(output: &std::process::Output) {
+    assert!(
+        output.status.success(),
+        "Migration should succeed: {}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_migrate_turbo_translates_the_pipeline_and_moves_cloudflare_projects() -> anyhow::Result<()>
+{
+    log("→ Migrate Turbo Translates The Pipeline And Moves Cloudflare Projects");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let repo = workspace.path().join("acme");
+    create_turborepo(&repo, "apps/api")?;
+    let path = fake_moon(workspace.path())?;
+
+    let output =
+        workspace.run_with_env("acme", &["migrate", "turbo"], &[("PATH", path.as_str())])?;
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // The Worker moves into the layout; the React app is already there and the package stays
+    let api = repo.join("workers/api");
+    assert!(api.join("wrangler.jsonc").exists());
+    assert!(!repo.join("apps/api").exists());
+    assert!(repo.join("apps/web/moon.yml").exists());
+    let staged = git(&repo, &["diff", "--cached", "--name-status", "-M"])?;
+    assert!(
+        staged.contains("apps/api/wrangler.jsonc\tworkers/api/wrangler.jsonc"),
+        "The move should be staged as a rename: {}",
+        staged
+    );
+
+    let api_moon: serde_yaml::Value =
+        serde_yaml::from_str(&fs::read_to_string(api.join("moon.yml"))?)?;
+    assert_eq!(api_moon["tasks"]["build"]["deps"][0], "^:build");
+    assert_eq!(api_moon["tasks"]["build"]["outputs"][0], "dist/**");
+    assert_eq!(api_moon["tasks"]["test"]["deps"][0], "~:build");
+    let test_inputs = serde_yaml::to_string(&api_moon["tasks"]["test"]["inputs"])?;
+    assert!(test_inputs.contains("$API_URL"), "{}", test_inputs);
+    assert!(
+        test_inputs.contains("/tsconfig.base.json"),
+        "{}",
+        test_inputs
+    );
+    assert!(
+        api_moon["tasks"].get("dev").is_none(),
+        "dev comes from moonflare's inherited task"
+    );
+
+    let ui_moon: serde_yaml::Value =
+        serde_yaml::from_str(&fs::read_to_string(repo.join("packages/ui/moon.yml"))?)?;
+    assert_eq!(ui_moon["layer"], "library");
+    assert_eq!(ui_moon["tasks"]["lint"]["args"][1], "lint");
+    assert_eq!(ui_moon["workspace"]["inheritedTasks"]["exclude"][0], "dev");
+
+    let root_package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(repo.join("package.json"))?)?;
+    assert_eq!(root_package["name"], "acme");
+    assert_eq!(root_package["scripts"]["build"], "moon run :build");
+    assert_eq!(root_package["scripts"]["format"], "prettier --write .");
+    assert!(root_package["scripts"].get("release").is_none());
+    assert!(root_package["devDependencies"].get("turbo").is_none());
+    assert!(root_package["devDependencies"].get("prettier").is_some());
+    assert!(root_package.get("workspaces").is_none());
+
+    assert!(!repo.join("turbo.json").exists());
+    assert!(!repo.join("package-lock.json").exists());
+    assert_eq!(fs::read_to_string(repo.join("README.md"))?, "# Acme\n");
+    assert!(fs::read_to_string(repo.join(".gitignore"))?.starts_with("node_modules\n.turbo\n"));
+    let pnpm_workspace = fs::read_to_string(repo.join("pnpm-workspace.yaml"))?;
+    assert!(pnpm_workspace.contains("packages/*"), "{}", pnpm_workspace);
+
+    assert!(
+        stdout.contains("Not translated from Turborepo"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("'remoteCache'"), "{}", stdout);
+    assert!(stdout.contains("'dotEnv' of task 'lint'"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_migrate_turbo_keep_layout_adopts_project_directories() -> anyhow::Result<()> {
+    log("→ Migrate Turbo Keep Layout Adopts Project Directories");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let repo = workspace.path().join("acme");
+    create_turborepo(&repo, "services/api")?;
+    let path = fake_moon(workspace.path())?;
+
+    fs::write(repo.join("notes.txt"), "wip\n")?;
+    let output = workspace.run_with_env(
+        "acme",
+        &["migrate", "turbo", "--keep-layout"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("uncommitted change"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(repo.join("turbo.json").exists());
+    fs::remove_file(repo.join("notes.txt"))?;
+
+    let output = workspace.run_with_env(
+        "acme",
+        &["migrate", "turbo", "--keep-layout"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert_success(&output);
+
+    assert!(repo.join("services/api/moon.yml").exists());
+    assert!(!repo.join("workers/api").exists());
+    let config = fs::read_to_string(repo.join("moonflare.toml"))?;
+    assert!(config.contains("workers = \"services\""), "{}", config);
+    let moon_workspace = fs::read_to_string(repo.join(".moon/workspace.yml"))?;
+    assert!(moon_workspace.contains("services/*"), "{}", moon_workspace);
+
+    Ok(())
+}
+
+#[test]
+fn test_migrate_nx_translates_targets_and_named_inputs() -> anyhow::Result<()> {
+    log("→ Migrate Nx Translates Targets And Named Inputs");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let repo = workspace.path().join("platform");
+    write(
+        &repo,
+        "nx.json",
+        r#"{
+  "namedInputs": {
+    "default": ["{projectRoot}/**/*"],
+    "production": ["default", "!{projectRoot}/**/*.spec.ts", { "env": "NODE_ENV" }]
+  },
+  "targetDefaults": {
+    "build": {
+      "dependsOn": ["^build"],
+      "inputs": ["production", "^production"],
+      "outputs": ["{projectRoot}/dist"],
+      "cache": true
+    }
+  }
+}
+"#,
+    )?;
+    write(
+        &repo,
+        "package.json",
+        r#"{ "name": "platform", "private": true, "scripts": { "build": "nx run-many -t build" }, "devDependencies": { "nx": "^20.0.0", "@nx/js": "^20.0.0" } }
+"#,
+    )?;
+    write(
+        &repo,
+        "libs/shared/project.json",
+        r#"{
+  "name": "shared",
+  "targets": {
+    "build": {
+      "executor": "nx:run-commands",
+      "options": { "command": "tsc -p libs/shared/tsconfig.json" },
+      "outputs": ["{workspaceRoot}/dist/libs/shared"]
+    },
+    "lint": { "executor": "@nx/eslint:lint" }
+  }
+}
+"#,
+    )?;
+    write(
+        &repo,
+        "apps/edge/package.json",
+        r#"{
+  "name": "edge",
+  "scripts": { "build": "wrangler deploy --dry-run --outdir dist", "deploy": "wrangler deploy" },
+  "devDependencies": { "wrangler": "^4.0.0" },
+  "nx": { "implicitDependencies": ["shared"] }
+}
+"#,
+    )?;
+    write(&repo, "apps/edge/wrangler.jsonc", WRANGLER_JSONC)?;
+    write(&repo, "apps/edge/src/index.ts", "export default {};\n")?;
+    git(&repo, &["init", "-q"])?;
+    git(&repo, &["add", "-A"])?;
+    git(&repo, &["commit", "-q", "-m", "Initial commit"])?;
+    let path = fake_moon(workspace.path())?;
+
+    let output =
+        workspace.run_with_env("platform", &["migrate", "nx"], &[("PATH", path.as_str())])?;
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let edge: serde_yaml::Value =
+        serde_yaml::from_str(&fs::read_to_string(repo.join("workers/edge/moon.yml"))?)?;
+    assert_eq!(edge["dependsOn"][0], "shared");
+    let build = &edge["tasks"]["build"];
+    assert_eq!(build["args"][1], "build");
+    assert_eq!(build["deps"][0], "^:build");
+    assert_eq!(build["outputs"][0], "dist");
+    let inputs = serde_yaml::to_string(&build["inputs"])?;
+    assert!(inputs.contains("'**/*'"), "{}", inputs);
+    assert!(inputs.contains("'!**/*.spec.ts'"), "{}", inputs);
+    assert!(inputs.contains("$NODE_ENV"), "{}", inputs);
+
+    let shared: serde_yaml::Value =
+        serde_yaml::from_str(&fs::read_to_string(repo.join("libs/shared/moon.yml"))?)?;
+    let build = &shared["tasks"]["build"];
+    assert_eq!(build["script"], "tsc -p libs/shared/tsconfig.json");
+    assert_eq!(build["options"]["runFromWorkspaceRoot"], true);
+    assert_eq!(build["outputs"][0], "/dist/libs/shared");
+    assert!(shared["tasks"].get("lint").is_none());
+
+    assert!(
+        stdout.contains("target 'lint' uses the @nx/eslint:lint executor"),
+        "{}",
+        stdout
+    );
+    let root_package = fs::read_to_string(repo.join("package.json"))?;
+    assert!(!root_package.contains("\"nx\""), "{}", root_package);
+    assert!(!root_package.contains("@nx/js"), "{}", root_package);
+    assert!(!repo.join("nx.json").exists());
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use common::*;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::thread;
+
+mod common;
+
+const WRANGLER_JSONC: &str = r#"{
+  "name": "api",
+  "account_id": "acct-1",
+  "d1_databases": [{ "binding": "DB", "database_name": "shop-db", "database_id": "prod-id" }],
+  "migrations": [{ "tag": "v1", "new_sqlite_classes": ["Counter"] }, { "tag": "v2", "renamed_classes": [] }],
+  "env": {
+    "staging": {
+      "d1_databases": [{ "binding": "DB", "database_name": "shop-db-staging", "database_id": "staging-id" }]
+    }
+  }
+}
+"#;
+
+/// Cloudflare API listing the deployed scripts with the Durable Object migration each applied
+fn mock_api() -> anyhow::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = format!("http://{}", listener.local_addr()?);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+            }
+            let body = r#"{"success":true,"errors":[],"result":[{"id":"api","tag":"t1","migration_tag":"v1"},{"id":"api-staging","tag":"t2","migration_tag":"v2"}]}"#;
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        }
+    });
+    Ok(address)
+}
+
+/// Fake `pnpm` answering wrangler's migrations-table query: production has applied both
+/// migrations and staging has never migrated. `moon` succeeds.
+#[cfg(unix)]
+fn fake_bin(dir: &Path) -> anyhow::Result<String> {
+    let pnpm = r#"case "$5" in
+  shop-db) echo '[{"results":[{"name":"0001_init.sql"},{"name":"0002_users.sql"}],"success":true}]' ;;
+  *) echo "no such table: d1_migrations: SQLITE_ERROR" >&2; exit 1 ;;
+esac
+"#;
+    fake_tools(dir, &[("pnpm", pnpm), ("moon", "exit 0\n")])
+}
+
+#[cfg(unix)]
+#[test]
+fn test_migrate_status_lists_applied_and_pending_migrations() -> anyhow::Result<()> {
+    log("→ Migrate Status Lists Applied And Pending Migrations");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.jsonc", WRANGLER_JSONC),
+            (
+                "migrations/0001_init.sql",
+                "CREATE TABLE users (id INTEGER);",
+            ),
+            (
+                "migrations/0002_users.sql",
+                "ALTER TABLE users ADD name TEXT;",
+            ),
+        ],
+    )?;
+    let path = fake_bin(workspace.path())?;
+    let api = mock_api()?;
+    let env = [
+        ("PATH", path.as_str()),
+        ("CLOUDFLARE_API_TOKEN", "token"),
+        ("CLOUDFLARE_API_BASE_URL", api.as_str()),
+    ];
+
+    let output = workspace.run_with_env("shop", &["migrate", "status", "--json"], &env)?;
+    assert!(
+        output.status.success(),
+        "Status should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let statuses: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let summary: Vec<String> = statuses
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|status| {
+            format!(
+                "{} {} {} {}",
+                status["environment"].as_str().unwrap_or("production"),
+                status["target"].as_str().unwrap(),
+                status["applied"],
+                status["pending"]
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            r#"production d1:DB ["0001_init.sql","0002_users.sql"] []"#,
+            r#"production durable_objects ["v1"] ["v2"]"#,
+            r#"staging d1:DB [] ["0001_init.sql","0002_users.sql"]"#,
+            r#"staging durable_objects ["v1","v2"] []"#,
+        ]
+    );
+
+    let output =
+        workspace.run_with_env("shop", &["migrate", "status", "--env", "staging"], &env)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("has 2 pending"), "{}", stdout);
+    assert!(stdout.contains("0002_users.sql"), "{}", stdout);
+    assert!(!stdout.contains("production"), "{}", stdout);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_deploy_require_migrations_blocks_pending_d1_migrations() -> anyhow::Result<()> {
+    log("→ Deploy Require Migrations Blocks Pending D1 Migrations");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.jsonc", WRANGLER_JSONC),
+            (
+                "migrations/0001_init.sql",
+                "CREATE TABLE users (id INTEGER);",
+            ),
+        ],
+    )?;
+    let path = fake_bin(workspace.path())?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["deploy", "api", "--env", "staging", "--require-migrations"],
+        &[("PATH", &path)],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("pending D1 migrations"), "{}", stderr);
+    assert!(stderr.contains("0001_init.sql"), "{}", stderr);
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["deploy", "api", "--require-migrations"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Production has no pending migrations: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
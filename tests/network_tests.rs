@@ -0,0 +1,63 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+/// Proxy variables cleared so only the test's settings apply
+const NO_AMBIENT_PROXY: [(&str, &str); 8] = [
+    ("HTTPS_PROXY", ""),
+    ("https_proxy", ""),
+    ("HTTP_PROXY", ""),
+    ("http_proxy", ""),
+    ("ALL_PROXY", ""),
+    ("all_proxy", ""),
+    ("NO_PROXY", ""),
+    ("no_proxy", ""),
+];
+
+#[test]
+fn test_doctor_network_reports_unreachable_proxy() -> anyhow::Result<()> {
+    log("→ Doctor Network Reports Unreachable Proxy");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    let config = workspace_path.join("moonflare.toml");
+    let existing = fs::read_to_string(&config).unwrap_or_default();
+    // Nothing listens on port 1, so every request fails fast
+    fs::write(
+        &config,
+        format!("{}\n[network]\nproxy = \"http://127.0.0.1:1\"\n", existing),
+    )?;
+
+    let output =
+        workspace.run_with_env("test-project", &["doctor", "--network"], &NO_AMBIENT_PROXY)?;
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("Proxy: http://127.0.0.1:1"), "{}", stdout);
+    assert!(stdout.contains("✗ Cloudflare API"), "{}", stdout);
+    assert!(stderr.contains("Couldn't reach"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn test_missing_ca_bundle_is_reported() -> anyhow::Result<()> {
+    log("→ Missing CA Bundle Is Reported");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+
+    let mut env = NO_AMBIENT_PROXY.to_vec();
+    env.push(("SSL_CERT_FILE", "/nonexistent/corp-root.pem"));
+    let output = workspace.run_with_env("test-project", &["doctor", "--network"], &env)?;
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stdout.contains("Extra CA certificates: /nonexistent/corp-root.pem"),
+        "{}",
+        stdout
+    );
+    assert!(stderr.contains("Failed to read CA bundle"), "{}", stderr);
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_nix_generate_maps_toolchain_to_packages() -> anyhow::Result<()> {
+    log("→ Nix Generate Maps Toolchain To Packages");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join(".prototools"),
+        "node = \"20.11.1\"\npnpm = \"9.1.0\"\n",
+    )?;
+    fs::write(
+        workspace_path.join("rust-toolchain.toml"),
+        "[toolchain]\nchannel = \"1.80\"\n",
+    )?;
+
+    let output = workspace.run("shop", &["nix", "generate"])?;
+    assert!(
+        output.status.success(),
+        "Nix generate should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let flake = fs::read_to_string(workspace_path.join("flake.nix"))?;
+    assert!(flake.contains("pkgs.nodejs_20"), "{}", flake);
+    assert!(flake.contains("pkgs.pnpm_9"), "{}", flake);
+    assert!(
+        flake.contains("pkgs.rust-bin.stable.\"1.80.0\".default"),
+        "{}",
+        flake
+    );
+    assert!(flake.contains("wasm32-unknown-unknown"));
+
+    fs::write(workspace_path.join(".prototools"), "node = \"22.4.0\"\n")?;
+    let output = workspace.run("shop", &["doctor", "--fix"])?;
+    assert!(output.status.success());
+    let flake = fs::read_to_string(workspace_path.join("flake.nix"))?;
+    assert!(flake.contains("pkgs.nodejs_22"), "{}", flake);
+
+    let output = workspace.run("shop", &["nix", "generate"])?;
+    assert!(!output.status.success(), "Existing flake should block");
+
+    Ok(())
+}
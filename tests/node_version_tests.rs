@@ -0,0 +1,77 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const PACKAGE_JSON: &str = r#"{
+  "name": "test-project",
+  "private": true,
+  "engines": {
+    "node": ">=20.0.0"
+  }
+}
+"#;
+
+/// Fake `node` reporting an unsupported version, and `moon` that succeeds
+fn fake_old_node(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(dir, &[("node", "echo v16.20.0\n"), ("moon", "exit 0\n")])
+}
+
+fn setup(workspace: &MoonflareTestWorkspace) -> anyhow::Result<String> {
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::write(workspace_path.join("package.json"), PACKAGE_JSON)?;
+    fake_old_node(&workspace_path)
+}
+
+#[test]
+fn test_build_rejects_unsupported_node() -> anyhow::Result<()> {
+    log("→ Build Rejects Unsupported Node");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let path = setup(&workspace)?;
+
+    let output = workspace.run_with_env("test-project", &["build"], &[("PATH", &path)])?;
+    assert!(!output.status.success(), "Build should fail on Node.js 16");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("v16.20.0 doesn't satisfy"), "{}", stderr);
+    assert!(stderr.contains(">=20.0.0"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn test_node_check_can_be_skipped() -> anyhow::Result<()> {
+    log("→ Node Check Can Be Skipped");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let path = setup(&workspace)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build"],
+        &[("PATH", &path), ("MOONFLARE_SKIP_NODE_CHECK", "1")],
+    )?;
+    assert!(
+        output.status.success(),
+        "Build should skip the check: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_doctor_reports_unsupported_node() -> anyhow::Result<()> {
+    log("→ Doctor Reports Unsupported Node");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let path = setup(&workspace)?;
+
+    let output = workspace.run_with_env("test-project", &["doctor"], &[("PATH", &path)])?;
+    assert!(!output.status.success(), "Doctor should report an error");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Checking Node.js"), "{}", stdout);
+    assert!(stdout.contains("v16.20.0"), "{}", stdout);
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` that reports its version and succeeds at everything else
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(dir, &[("moon", "echo moon 1.30.0\n")])
+}
+
+#[test]
+fn test_init_prints_the_onboarding_checklist() -> anyhow::Result<()> {
+    log("→ Init Prints The Onboarding Checklist");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let path = fake_moon(workspace.path())?;
+    let home = workspace.path().join("home");
+    fs::create_dir_all(&home)?;
+
+    let output = workspace.run_with_env(
+        "",
+        &["init", "demo"],
+        &[
+            ("PATH", path.as_str()),
+            ("HOME", home.to_str().unwrap()),
+            ("CLOUDFLARE_API_TOKEN", ""),
+        ],
+    )?;
+    assert!(
+        output.status.success(),
+        "init should succeed: {}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Without a terminal the checklist is printed and nothing is run
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Get started"), "{}", stdout);
+    for step in [
+        "○ 1. Install dependencies",
+        "○ 2. Add your first project",
+        "○ 3. Start the dev servers",
+        "○ 4. Log in to Cloudflare",
+        "○ 5. Set up CI",
+    ] {
+        assert!(stdout.contains(step), "Missing '{}': {}", step, stdout);
+    }
+    assert!(stdout.contains("moonflare ci generate"), "{}", stdout);
+    assert!(!workspace.path().join("demo/node_modules").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_onboarding_checklist_ticks_steps_already_done() -> anyhow::Result<()> {
+    log("→ Onboarding Checklist Ticks Steps Already Done");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let path = fake_moon(workspace.path())?;
+    let home = workspace.path().join("home");
+    fs::create_dir_all(home.join(".wrangler/config"))?;
+    fs::write(
+        home.join(".wrangler/config/default.toml"),
+        "oauth_token = \"x\"\n",
+    )?;
+    fs::create_dir_all(workspace.path().join("demo/node_modules"))?;
+
+    let output = workspace.run_with_env(
+        "",
+        &["init", "demo", "--force"],
+        &[
+            ("PATH", path.as_str()),
+            ("HOME", home.to_str().unwrap()),
+            ("XDG_CONFIG_HOME", home.join(".config").to_str().unwrap()),
+            ("CLOUDFLARE_API_TOKEN", ""),
+        ],
+    )?;
+    assert!(
+        output.status.success(),
+        "init should succeed: {}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✓ 1. Install dependencies"), "{}", stdout);
+    assert!(stdout.contains("○ 2. Add your first project"), "{}", stdout);
+    assert!(stdout.contains("✓ 4. Log in to Cloudflare"), "{}", stdout);
+
+    Ok(())
+}
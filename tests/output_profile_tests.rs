@@ -0,0 +1,81 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+fn rename_output(workspace: &MoonflareTestWorkspace, args: &[&str]) -> anyhow::Result<Vec<String>> {
+    let output = workspace.run("shop", args)?;
+    assert!(
+        output.status.success(),
+        "{:?} should succeed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[test]
+fn test_output_profiles_trim_headers_and_sections() -> anyhow::Result<()> {
+    log("→ Output Profiles Trim Headers And Sections");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[workspace]\nname = \"shop\"\n",
+    )?;
+
+    let full = rename_output(&workspace, &["workspace", "rename", "store"])?;
+    let compact = rename_output(
+        &workspace,
+        &["workspace", "rename", "market", "--ui", "compact"],
+    )?;
+    assert!(compact.len() < full.len(), "{:?}\n{:?}", compact, full);
+    assert!(
+        compact
+            .iter()
+            .any(|line| line.contains("Renaming workspace") && line.contains("'store' to 'market'")),
+        "{:?}",
+        compact
+    );
+    assert!(
+        compact
+            .iter()
+            .any(|line| line.starts_with("✓ Successfully renamed"))
+    );
+
+    // The profile can also come from moonflare.toml; the flag still wins
+    let config = fs::read_to_string(workspace_path.join("moonflare.toml"))?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        format!("{}\n[ui]\nprofile = \"minimal\"\n", config),
+    )?;
+    let minimal = rename_output(&workspace, &["workspace", "rename", "bazaar"])?;
+    assert!(
+        !minimal
+            .iter()
+            .any(|line| line.contains("Renaming workspace")),
+        "{:?}",
+        minimal
+    );
+    assert!(
+        minimal
+            .iter()
+            .any(|line| line.starts_with("✓ Successfully renamed"))
+    );
+
+    let compact = rename_output(
+        &workspace,
+        &["--ui", "compact", "workspace", "rename", "souk"],
+    )?;
+    assert!(
+        compact
+            .iter()
+            .any(|line| line.contains("Renaming workspace"))
+    );
+
+    Ok(())
+}
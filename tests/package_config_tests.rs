@@ -0,0 +1,96 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const PACKAGE_JSON: &str = r#"{
+  "name": "web",
+  "private": true,
+  "moonflare": {
+    "dev": "vite",
+    "build": "vite build",
+    "deploy": "wrangler deploy",
+    "tasks": { "typecheck": "tsc --noEmit" },
+    "bindings": ["CACHE"]
+  }
+}"#;
+
+#[test]
+fn test_package_json_field_generates_moon_yml() -> anyhow::Result<()> {
+    log("→ package.json#moonflare Generates moon.yml");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    let project = workspace_path.join("apps/web");
+    fs::create_dir_all(&project)?;
+    fs::write(project.join("package.json"), PACKAGE_JSON)?;
+
+    let output = workspace.run("test-project", &["doctor"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Generated apps/web/moon.yml from package.json#moonflare"),
+        "{}",
+        stderr
+    );
+    assert!(stdout.contains("expects binding 'CACHE'"), "{}", stdout);
+
+    let moon_yml = fs::read_to_string(project.join("moon.yml"))?;
+    assert!(moon_yml.starts_with("# Generated by moonflare"));
+    assert!(moon_yml.contains("stack: 'frontend'"), "{}", moon_yml);
+    assert!(moon_yml.contains("script: 'vite build'"), "{}", moon_yml);
+    assert!(moon_yml.contains("typecheck:"), "{}", moon_yml);
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&moon_yml)?;
+    assert_eq!(parsed["tasks"]["deploy"]["deps"][0], "~:build");
+
+    // Edits to package.json are picked up on the next run
+    fs::write(
+        project.join("package.json"),
+        PACKAGE_JSON.replace("vite build", "vite build --mode production"),
+    )?;
+    workspace.run("test-project", &["doctor"])?;
+    let moon_yml = fs::read_to_string(project.join("moon.yml"))?;
+    assert!(
+        moon_yml.contains("vite build --mode production"),
+        "{}",
+        moon_yml
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_hand_written_moon_yml_is_kept() -> anyhow::Result<()> {
+    log("→ Hand-Written moon.yml Is Kept");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    let project = workspace_path.join("apps/web");
+    fs::create_dir_all(&project)?;
+    fs::write(project.join("package.json"), PACKAGE_JSON)?;
+    fs::write(project.join("moon.yml"), "language: 'typescript'\n")?;
+
+    let output = workspace.run("test-project", &["doctor"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("wasn't generated by moonflare"),
+        "{}",
+        stdout
+    );
+    assert_eq!(
+        fs::read_to_string(project.join("moon.yml"))?,
+        "language: 'typescript'\n"
+    );
+
+    // Unknown keys are reported rather than silently ignored
+    fs::remove_file(project.join("moon.yml"))?;
+    fs::write(
+        project.join("package.json"),
+        r#"{ "name": "web", "moonflare": { "bulid": "vite build" } }"#,
+    )?;
+    let output = workspace.run("test-project", &["doctor"])?;
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Invalid \"moonflare\" field"), "{}", stdout);
+    assert!(!project.join("moon.yml").exists());
+
+    Ok(())
+}
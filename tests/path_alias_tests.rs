@@ -0,0 +1,82 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_adding_a_crate_adds_wasm_aliases() -> anyhow::Result<()> {
+    log("→ Adding A Crate Adds WASM Aliases");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["add", "react", "web"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let tsconfig_path = workspace_path.join("apps/web/tsconfig.app.json");
+    let tsconfig: serde_json::Value = serde_json::from_str(&fs::read_to_string(&tsconfig_path)?)?;
+    assert!(
+        tsconfig["compilerOptions"].get("paths").is_none(),
+        "No aliases without crates: {}",
+        tsconfig
+    );
+
+    let output = workspace.run("test-project", &["add", "crate", "core"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let tsconfig: serde_json::Value = serde_json::from_str(&fs::read_to_string(&tsconfig_path)?)?;
+    assert_eq!(
+        tsconfig["compilerOptions"]["paths"]["@wasm/*"],
+        serde_json::json!(["../../shared-wasm/*"])
+    );
+
+    let vite = fs::read_to_string(workspace_path.join("apps/web/vite.config.ts"))?;
+    assert!(
+        vite.contains("'@wasm': fileURLToPath(new URL('../../shared-wasm', import.meta.url)),"),
+        "{}",
+        vite
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_astro_aliases_respect_base_url() -> anyhow::Result<()> {
+    log("→ Astro Aliases Respect Base Url");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::create_dir_all(workspace_path.join("packages/ui"))?;
+
+    let output = workspace.run("test-project", &["add", "astro", "docs"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let tsconfig: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join("sites/docs/tsconfig.json"),
+    )?)?;
+    let paths = &tsconfig["compilerOptions"]["paths"];
+    assert_eq!(paths["@/*"], serde_json::json!(["src/*"]));
+    assert_eq!(
+        paths["@packages/*"],
+        serde_json::json!(["../../packages/*"])
+    );
+    assert!(paths.get("@wasm/*").is_none(), "{}", paths);
+
+    let astro = fs::read_to_string(workspace_path.join("sites/docs/astro.config.mjs"))?;
+    assert!(
+        astro.contains("'@packages': fileURLToPath(new URL('../../packages', import.meta.url)),"),
+        "{}",
+        astro
+    );
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const CHAT_TOML: &str = r#"# Chat worker
+name = "chat"
+main = "src/index.ts" # entry
+
+[[durable_objects.bindings]]
+name = "ROOMS"
+class_name = "Room"
+
+[env.staging]
+name = "chat-staging"
+
+[env.staging.vars]
+MODE = "staging" # kept
+
+[env.production]
+name = "chat-prod"
+placement = { mode = "off" }
+"#;
+
+const API_JSONC: &str = r#"{
+  // API worker
+  "name": "api",
+  "main": "src/index.ts",
+  "durable_objects": {
+    "bindings": [{ "name": "COUNTER", "class_name": "Counter" }]
+  },
+  "vars": {
+    "MODE": "prod" // kept
+  },
+  "env": {
+    "staging": {
+      "name": "api-staging"
+    }
+  }
+}
+"#;
+
+#[test]
+fn test_placement_set_writes_every_environment_keeping_comments() -> anyhow::Result<()> {
+    log("→ Placement Set Writes Every Environment Keeping Comments");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let chat = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "chat",
+        &[("wrangler.toml", CHAT_TOML)],
+    )?;
+
+    let output = workspace.run(
+        "shop",
+        &[
+            "placement",
+            "set",
+            "chat",
+            "--mode",
+            "smart",
+            "--hint",
+            "ROOMS=weur",
+            "--yes",
+        ],
+    )?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let config = fs::read_to_string(chat.join("wrangler.toml"))?;
+    for line in [
+        "# Chat worker\n",
+        "main = \"src/index.ts\" # entry\nplacement = { mode = \"smart\" }\nvars = { ROOMS_LOCATION_HINT = \"weur\" }\n",
+        "MODE = \"staging\" # kept\nROOMS_LOCATION_HINT = \"weur\"\n",
+        "name = \"chat-prod\"\nplacement = { mode = \"smart\" }\n\n[env.production.vars]\nROOMS_LOCATION_HINT = \"weur\"\n",
+    ] {
+        assert!(config.contains(line), "{}\n{}", line, config);
+    }
+    let declared: toml::Value =
+        toml::from_str(&fs::read_to_string(workspace_path.join("moonflare.toml"))?)?;
+    let placement = &declared["projects"]["chat"]["placement"];
+    assert_eq!(placement["mode"].as_str(), Some("smart"));
+    assert_eq!(placement["location_hints"]["ROOMS"].as_str(), Some("weur"));
+
+    // Applying again changes nothing
+    let output = workspace.run("shop", &["placement", "set", "chat", "--yes"])?;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("already matches"));
+    assert_eq!(fs::read_to_string(chat.join("wrangler.toml"))?, config);
+
+    // A hand edit shows up as drift
+    fs::write(
+        chat.join("wrangler.toml"),
+        config.replace(
+            "mode = \"smart\" }\n\n[env.production.vars]",
+            "mode = \"off\" }\n\n[env.production.vars]",
+        ),
+    )?;
+    let output = workspace.run("shop", &["placement", "status", "--json"])?;
+    assert!(output.status.success());
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let scopes = status[0]["scopes"].as_array().unwrap();
+    assert_eq!(scopes[0]["mode"], "smart");
+    assert_eq!(scopes[0]["location_hints"]["ROOMS"], "weur");
+    let staging = scopes
+        .iter()
+        .find(|s| s["environment"] == "staging")
+        .unwrap();
+    assert_eq!(staging["inherited"], true);
+    assert_eq!(staging["drift"], serde_json::json!([]));
+    let production = scopes
+        .iter()
+        .find(|s| s["environment"] == "production")
+        .unwrap();
+    assert_eq!(
+        production["drift"],
+        serde_json::json!(["moonflare.toml sets smart"])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_placement_set_jsonc_hints_and_validation() -> anyhow::Result<()> {
+    log("→ Placement Set JSONC Hints And Validation");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    let api = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", API_JSONC)],
+    )?;
+
+    let output = workspace.run(
+        "shop",
+        &["placement", "set", "api", "--hint", "COUNTER=mars", "--yes"],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("isn't a location hint"));
+    let output = workspace.run(
+        "shop",
+        &["placement", "set", "api", "--hint", "ROOMS=weur", "--yes"],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("bindings: COUNTER"));
+
+    let output = workspace.run(
+        "shop",
+        &[
+            "placement",
+            "set",
+            "api",
+            "--hint",
+            "COUNTER=enam",
+            "--dry-run",
+        ],
+    )?;
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(api.join("wrangler.jsonc"))?, API_JSONC);
+
+    let output = workspace.run(
+        "shop",
+        &["placement", "set", "api", "--hint", "COUNTER=enam", "--yes"],
+    )?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let config = fs::read_to_string(api.join("wrangler.jsonc"))?;
+    assert!(
+        config
+            .contains("    \"MODE\": \"prod\", // kept\n    \"COUNTER_LOCATION_HINT\": \"enam\"\n"),
+        "{}",
+        config
+    );
+    assert!(
+        config.contains(
+            "      \"name\": \"api-staging\",\n      \"vars\": { \"COUNTER_LOCATION_HINT\": \"enam\" }\n"
+        ),
+        "{}",
+        config
+    );
+    assert!(!config.contains("placement"), "{}", config);
+
+    let output = workspace.run(
+        "shop",
+        &[
+            "placement",
+            "set",
+            "api",
+            "--clear-hint",
+            "COUNTER",
+            "--yes",
+        ],
+    )?;
+    assert!(output.status.success());
+    let config = fs::read_to_string(api.join("wrangler.jsonc"))?;
+    assert!(!config.contains("COUNTER_LOCATION_HINT"), "{}", config);
+    assert!(config.contains("\"MODE\": \"prod\" // kept"), "{}", config);
+
+    Ok(())
+}
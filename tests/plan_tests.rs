@@ -0,0 +1,207 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const NAMING_CONFIG: &str = r#"[workspace]
+name = "shop"
+worker_name = "{workspace}-{project}"
+"#;
+
+const API_WRANGLER: &str = "# The API worker\nname = \"api\"\nmain = \"src/index.ts\"\n";
+
+/// Fake `moon` that leaves a marker behind when it runs a task, so a plan can show none ran.
+/// Read-only queries fail, so moonflare falls back to scanning the workspace.
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "[ \"$1\" = query ] && exit 1\necho \"$@\" >> {}\nexit 0\n",
+                dir.join("moon-ran.txt").display()
+            ),
+        )],
+    )
+}
+
+fn named_workspace(workspace: &MoonflareTestWorkspace) -> anyhow::Result<std::path::PathBuf> {
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(workspace_path.join("moonflare.toml"), NAMING_CONFIG)?;
+    let api = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", API_WRANGLER)],
+    )?;
+    Ok(api.join("wrangler.toml"))
+}
+
+#[test]
+fn test_plan_prints_the_same_diff_without_changing_anything() -> anyhow::Result<()> {
+    log("→ Plan Prints The Same Diff Without Changing Anything");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let wrangler = named_workspace(&workspace)?;
+    let env = [("NO_COLOR", "1")];
+
+    let output = workspace.run_with_env("shop", &["sync-names", "--plan"], &env)?;
+    assert!(
+        output.status.success(),
+        "Plan should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let plan = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(
+        plan.contains(
+            "~ workers/api/wrangler.toml\n     2 - name = \"api\"\n       + name = \"shop-api\"\n"
+        ),
+        "{}",
+        plan
+    );
+    assert!(
+        plan.contains("Plan: 0 to create, 1 to modify, 0 to delete, 0 to run"),
+        "{}",
+        plan
+    );
+    assert_eq!(fs::read_to_string(&wrangler)?, API_WRANGLER);
+
+    // Nothing changed, so the plan comes out the same
+    let again = workspace.run_with_env("shop", &["sync-names", "--plan"], &env)?;
+    assert_eq!(String::from_utf8_lossy(&again.stdout), plan);
+
+    let output = workspace.run("shop", &["sync-names", "--auto-approve"])?;
+    assert!(
+        output.status.success(),
+        "Apply should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(&wrangler)?,
+        API_WRANGLER.replace("\"api\"", "\"shop-api\"")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_plan_needs_approval_without_a_terminal() -> anyhow::Result<()> {
+    log("→ Plan Needs Approval Without A Terminal");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let wrangler = named_workspace(&workspace)?;
+
+    let output = workspace.run("shop", &["sync-names"])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--auto-approve"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::read_to_string(&wrangler)?, API_WRANGLER);
+
+    Ok(())
+}
+
+#[test]
+fn test_deploy_plan_lists_tasks_without_running_them() -> anyhow::Result<()> {
+    log("→ Deploy Plan Lists Tasks Without Running Them");
+    let workspace = MoonflareTestWorkspace::new()?;
+    named_workspace(&workspace)?;
+    let path = fake_moon(workspace.path())?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["deploy", "--env", "staging", "--plan"],
+        &[("PATH", path.as_str()), ("NO_COLOR", "1")],
+    )?;
+    assert!(
+        output.status.success(),
+        "Plan should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("> WRANGLER_ENV=staging moon run api:deploy"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("0 to modify, 0 to delete, 1 to run"),
+        "{}",
+        stdout
+    );
+    assert!(!workspace.path().join("moon-ran.txt").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_deploy_plan_takes_projects_from_moon_graph() -> anyhow::Result<()> {
+    log("→ Deploy Plan Takes Projects From Moon Graph");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    // Outside the layout directories, so only Moon's graph knows about it
+    fs::create_dir_all(workspace_path.join("packages/edge"))?;
+    fs::write(
+        workspace_path.join("packages/edge/wrangler.toml"),
+        "name = \"edge\"\n",
+    )?;
+    // Wrangler configuration but no deploy task
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "lib",
+        &[("wrangler.toml", "name = \"lib\"\n")],
+    )?;
+
+    let path = fake_tools(
+        workspace.path(),
+        &[(
+            "moon",
+            concat!(
+                "if [ \"$1 $2\" = \"query projects\" ]; then\n",
+                "  echo '{\"projects\":[{\"id\":\"edge\",\"source\":\"packages/edge\",\"language\":\"typescript\"},{\"id\":\"lib\",\"source\":\"workers/lib\",\"language\":\"typescript\"}]}'\n",
+                "elif [ \"$1 $2\" = \"query tasks\" ]; then\n",
+                "  echo '{\"tasks\":{\"edge\":{\"build\":{},\"deploy\":{}},\"lib\":{\"build\":{}}}}'\n",
+                "fi\n",
+                "exit 0\n",
+            ),
+        )],
+    )?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["deploy", "--plan"],
+        &[("PATH", path.as_str()), ("NO_COLOR", "1")],
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("moon run edge:deploy"), "{}", stdout);
+    assert!(!stdout.contains("lib:deploy"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn test_plan_is_rejected_by_other_commands() -> anyhow::Result<()> {
+    log("→ Plan Is Rejected By Other Commands");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run("shop", &["tag", "add", "api", "edge", "--plan"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--plan isn't supported by 'tag'"),
+        "{}",
+        stderr
+    );
+
+    Ok(())
+}
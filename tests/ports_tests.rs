@@ -0,0 +1,125 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::net::TcpListener;
+use std::path::Path;
+
+mod common;
+
+const DEV_TASK: &str = "tasks:\n  dev:\n    command: 'pnpm'\n    args: ['dev']\n";
+
+/// Fake `moon` that records its arguments and exits
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!("echo \"$*\" >> {}\n", dir.join("moon.log").display()),
+        )],
+    )
+}
+
+fn create_worker(workspace: &MoonflareTestWorkspace, name: &str, port: u16) -> anyhow::Result<()> {
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        name,
+        &[
+            (
+                "wrangler.toml",
+                &format!("name = \"{}\"\n\n[dev]\nport = {}\n", name, port),
+            ),
+            ("moon.yml", DEV_TASK),
+        ],
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_dev_moves_conflicting_project_and_ports_free_releases_it() -> anyhow::Result<()> {
+    log("→ Dev Moves Conflicting Project And Ports Free Releases It");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+    create_worker(&workspace, "api", port)?;
+    create_worker(&workspace, "auth", port)?;
+    let path = fake_moon(&workspace_path)?;
+
+    let output = workspace.run("shop", &["ports"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("conflicts with auth"), "{}", stdout);
+
+    let output = workspace.run_with_env("shop", &["dev"], &[("PATH", path.as_str())])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Dev should succeed: {}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("'auth' now runs on port"), "{}", stdout);
+    let reservations: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join(".moonflare/ports.json"),
+    )?)?;
+    let moved = reservations["remapped"]["auth"]
+        .as_u64()
+        .unwrap_or_default();
+    assert!(moved > port as u64, "{}", reservations);
+    let log = fs::read_to_string(workspace_path.join("moon.log"))?;
+    assert!(
+        log.contains(&format!("run auth:dev -- --port {}", moved)),
+        "{}",
+        log
+    );
+    assert!(log.contains("run api:dev\n"), "{}", log);
+
+    let output = workspace.run("shop", &["ports", "list"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!("reserved, usually {}", port)),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("conflicts with"), "{}", stdout);
+
+    let output = workspace.run("shop", &["ports", "free", "auth"])?;
+    assert!(
+        output.status.success(),
+        "Ports free should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = workspace.run("shop", &["ports", "free", "auth"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no port reservation"));
+
+    Ok(())
+}
+
+#[test]
+fn test_dev_moves_project_off_a_port_another_app_holds() -> anyhow::Result<()> {
+    log("→ Dev Moves Project Off A Port Another App Holds");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let taken = TcpListener::bind("127.0.0.1:0")?;
+    let port = taken.local_addr()?.port();
+    create_worker(&workspace, "api", port)?;
+    let path = fake_moon(&workspace_path)?;
+
+    let output = workspace.run("shop", &["ports"])?;
+    assert!(String::from_utf8_lossy(&output.stdout).contains("in use"));
+
+    let output = workspace.run_with_env("shop", &["dev"], &[("PATH", path.as_str())])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains(&format!("Port {} is taken", port)),
+        "{}",
+        stdout
+    );
+    let log = fs::read_to_string(workspace_path.join("moon.log"))?;
+    assert!(log.contains("run api:dev -- --port"), "{}", log);
+
+    Ok(())
+}
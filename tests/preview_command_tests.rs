@@ -0,0 +1,95 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` and `pnpm` that append their arguments to `tools.log`
+fn fake_logging_tools(dir: &Path) -> anyhow::Result<String> {
+    let script = |tool: &str| {
+        format!(
+            "echo \"{} $@ NODE_ENV=$NODE_ENV\" >> {}\n",
+            tool,
+            dir.join("tools.log").display()
+        )
+    };
+    fake_tools(dir, &[("moon", &script("moon")), ("pnpm", &script("pnpm"))])
+}
+
+#[test]
+fn test_preview_serves_worker_with_wrangler_local() -> anyhow::Result<()> {
+    log("→ Preview Serves Worker With Wrangler Local");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", "{ \"name\": \"api\" }")],
+    )?;
+    let path = fake_logging_tools(&workspace_path)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["preview", "api", "--port", "8790"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Preview should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("PRODUCTION PREVIEW"));
+
+    let log = fs::read_to_string(workspace_path.join("tools.log"))?;
+    let lines: Vec<&str> = log.lines().collect();
+    assert!(lines[0].starts_with("moon run api:build"), "{}", log);
+    assert_eq!(
+        lines[1],
+        "pnpm exec wrangler dev --local --port 8790 NODE_ENV=production"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_preview_serves_static_project_with_vite() -> anyhow::Result<()> {
+    log("→ Preview Serves Static Project With Vite");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::React, "frontend", &[])?;
+    let path = fake_logging_tools(&workspace_path)?;
+
+    let output =
+        workspace.run_with_env("test-project", &["preview", "frontend"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Preview should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let log = fs::read_to_string(workspace_path.join("tools.log"))?;
+    assert!(
+        log.contains("pnpm exec vite preview NODE_ENV=production"),
+        "{}",
+        log
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_preview_rejects_crates() -> anyhow::Result<()> {
+    log("→ Preview Rejects Crates");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::Crate, "math", &[])?;
+
+    let output = workspace.run("test-project", &["preview", "math"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is a crate"));
+
+    Ok(())
+}
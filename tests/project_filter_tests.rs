@@ -0,0 +1,62 @@
+use common::*;
+
+mod common;
+
+#[test]
+fn test_deploy_filter_without_matches_fails() -> anyhow::Result<()> {
+    log("→ Deploy Filter Without Matches Fails");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\n")],
+    )?;
+
+    let output = workspace.run("test-project", &["deploy", "--only", "sites/*"])?;
+    assert!(
+        !output.status.success(),
+        "Deploy should fail without matches"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+    assert!(
+        stderr.contains("no deployable projects match"),
+        "Error should explain that the filter matched nothing: {}",
+        stderr
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_conflicts_with_explicit_project() -> anyhow::Result<()> {
+    log("→ Filter Conflicts With Explicit Project");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["build", "api", "--only", "workers/*"])?;
+    assert!(
+        !output.status.success(),
+        "Passing both a project and --only should be rejected"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_build_filter_excluding_everything_fails() -> anyhow::Result<()> {
+    log("→ Build Filter Excluding Everything Fails");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::Crate, "legacy-utils", &[])?;
+
+    let output = workspace.run("test-project", &["build", "--exclude", "legacy-*"])?;
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+    assert!(stderr.contains("no projects with a 'build' task match"));
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// A fake `moon` that appends its arguments to calls.log
+#[cfg(unix)]
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "echo \"moon $*\" >> {}\nexit 0\n",
+                dir.join("calls.log").display()
+            ),
+        )],
+    )
+}
+
+#[test]
+fn test_add_grouped_project_sets_moon_id_and_globs() -> anyhow::Result<()> {
+    log("→ Add Grouped Project Sets Moon Id And Globs");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["add", "react", "shop/frontend"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let project = workspace_path.join("apps/shop/frontend");
+    let moon_yml = fs::read_to_string(project.join("moon.yml"))?;
+    assert!(moon_yml.contains("\nid: 'shop-frontend'\n"), "{}", moon_yml);
+    let package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(project.join("package.json"))?)?;
+    assert_eq!(package["name"], "shop-frontend");
+    let workspace_yml = fs::read_to_string(workspace_path.join(".moon/workspace.yml"))?;
+    assert!(workspace_yml.contains("apps/shop/*"), "{}", workspace_yml);
+
+    // Groups nest one level, and only JavaScript projects
+    let output = workspace.run("test-project", &["add", "react", "shop/web/admin"])?;
+    assert!(!output.status.success());
+    let output = workspace.run("test-project", &["add", "crate", "shop/core"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("can be grouped"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_build_targets_group_pattern_and_grouped_path() -> anyhow::Result<()> {
+    log("→ Build Targets Group Pattern And Grouped Path");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    let tasks = "tasks:\n  build:\n    command: 'pnpm build'\n";
+    for (path, moon_yml) in [
+        (
+            "apps/shop/frontend",
+            format!("id: 'shop-frontend'\n{}", tasks),
+        ),
+        // Without an id, Moon names a grouped project after its directory alone
+        ("workers/shop/api", tasks.to_string()),
+        ("apps/web", tasks.to_string()),
+    ] {
+        fs::create_dir_all(workspace_path.join(path))?;
+        fs::write(workspace_path.join(path).join("moon.yml"), moon_yml)?;
+    }
+    let path = fake_moon(workspace.path())?;
+    let calls = workspace.path().join("calls.log");
+    let env = [("PATH", path.as_str())];
+
+    let output = workspace.run_with_env("test-project", &["build", "shop/*"], &env)?;
+    assert!(
+        output.status.success(),
+        "Group build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let log = fs::read_to_string(&calls)?;
+    assert!(
+        log.contains("moon run shop-frontend:build api:build"),
+        "{}",
+        log
+    );
+    assert!(!log.contains("web:build"), "{}", log);
+
+    fs::remove_file(&calls)?;
+    let output = workspace.run_with_env("test-project", &["build", "shop/frontend"], &env)?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(fs::read_to_string(&calls)?.contains("moon run shop-frontend:build"));
+
+    Ok(())
+}
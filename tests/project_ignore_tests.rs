@@ -0,0 +1,112 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Fake `moon` that records its arguments and exits
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!("echo \"$*\" >> {}\n", dir.join("moon.log").display()),
+        )],
+    )
+}
+
+fn create_worker(
+    workspace: &MoonflareTestWorkspace,
+    name: &str,
+    moon_config: &str,
+) -> anyhow::Result<()> {
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        name,
+        &[
+            ("wrangler.toml", &format!("name = \"{}\"\n", name)),
+            (
+                "moon.yml",
+                &format!("tasks:\n  build:\n    command: 'true'\n{}", moon_config),
+            ),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Run `moonflare build` with the fake moon and return the arguments it passed to moon
+fn built_targets(
+    workspace: &MoonflareTestWorkspace,
+    workspace_path: &Path,
+    path: &str,
+    args: &[&str],
+) -> anyhow::Result<String> {
+    let log_file = workspace_path.join("moon.log");
+    let _ = fs::remove_file(&log_file);
+    let output = workspace.run_with_env("shop", args, &[("PATH", path)])?;
+    assert!(
+        output.status.success(),
+        "{:?} should succeed: {}{}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(fs::read_to_string(log_file).unwrap_or_default())
+}
+
+#[test]
+fn test_build_leaves_out_ignored_projects_unless_named_or_included() -> anyhow::Result<()> {
+    log("→ Build Leaves Out Ignored Projects Unless Named Or Included");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[workspace]\nignore = [\"sandbox-*\"]\nignore_tags = [\"archived\"]\n\n[projects.admin]\nskip = [\"deploy\"]\n",
+    )?;
+    create_worker(&workspace, "api", "")?;
+    create_worker(&workspace, "admin", "")?;
+    create_worker(&workspace, "sandbox-queue", "")?;
+    create_worker(&workspace, "legacy", "tags: ['archived']\n")?;
+    create_worker(&workspace, "reports", "moonflare:\n  skip: ['build']\n")?;
+    let path = fake_moon(&workspace_path)?;
+
+    let log = built_targets(&workspace, &workspace_path, &path, &["build"])?;
+    assert!(log.contains("api:build"), "{}", log);
+    assert!(
+        log.contains("admin:build"),
+        "Skipping deploy shouldn't skip build: {}",
+        log
+    );
+    for ignored in ["sandbox-queue:build", "legacy:build", "reports:build"] {
+        assert!(
+            !log.contains(ignored),
+            "{} should be ignored: {}",
+            ignored,
+            log
+        );
+    }
+
+    let log = built_targets(&workspace, &workspace_path, &path, &["build", "reports"])?;
+    assert!(
+        log.contains("reports:build"),
+        "Naming an ignored project should build it: {}",
+        log
+    );
+
+    let log = built_targets(
+        &workspace,
+        &workspace_path,
+        &path,
+        &["build", "--include-ignored"],
+    )?;
+    assert!(
+        log.split_whitespace().any(|arg| arg == ":build"),
+        "--include-ignored should build every project: {}",
+        log
+    );
+
+    Ok(())
+}
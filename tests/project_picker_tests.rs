@@ -0,0 +1,51 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// A fake `moon` that appends its arguments to calls.log
+#[cfg(unix)]
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "echo \"moon $*\" >> {}\nexit 0\n",
+                dir.join("calls.log").display()
+            ),
+        )],
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn test_build_without_terminal_skips_picker_and_builds_everything() -> anyhow::Result<()> {
+    log("→ Build Without Terminal Skips Picker And Builds Everything");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    for path in ["apps/web", "workers/api"] {
+        fs::create_dir_all(workspace_path.join(path))?;
+        fs::write(
+            workspace_path.join(path).join("moon.yml"),
+            "tasks:\n  build:\n    command: 'pnpm build'\n",
+        )?;
+    }
+    let path = fake_moon(workspace.path())?;
+
+    let output = workspace.run_with_env("test-project", &["build"], &[("PATH", path.as_str())])?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Which project"), "{}", stdout);
+    assert!(stdout.contains("Building all projects"), "{}", stdout);
+    let log = fs::read_to_string(workspace.path().join("calls.log"))?;
+    assert!(log.contains(":build"), "{}", log);
+    assert!(!log.contains("query projects"), "{}", log);
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// A fake `moon` that answers `moon query projects` with the workspace's projects and
+/// appends every other invocation to calls.log
+#[cfg(unix)]
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    let projects = r#"{"projects":[{"id":"frontend","source":"apps/frontend","language":"typescript","stack":"frontend"},{"id":"api","source":"workers/api","language":"typescript","stack":"backend"}]}"#;
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "if [ \"$1 $2\" = \"query projects\" ]; then echo '{}'; exit 0; fi\necho \"moon $*\" >> {}\nexit 0\n",
+                projects,
+                dir.join("calls.log").display()
+            ),
+        )],
+    )
+}
+
+fn create_projects(workspace_path: &Path) -> anyhow::Result<()> {
+    for path in ["apps/frontend", "workers/api"] {
+        fs::create_dir_all(workspace_path.join(path))?;
+        fs::write(
+            workspace_path.join(path).join("moon.yml"),
+            "tasks:\n  build:\n    command: 'pnpm build'\n",
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_mistyped_project_suggests_closest_name() -> anyhow::Result<()> {
+    log("→ Mistyped Project Suggests Closest Name");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    create_projects(&workspace_path)?;
+
+    let output = workspace.run("test-project", &["sbom", "frontned"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("did you mean"), "{}", stderr);
+    assert!(stderr.contains("'frontend'?"), "{}", stderr);
+
+    // Nothing is suggested for names that aren't close to any project
+    let output = workspace.run("test-project", &["sbom", "billing"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Project 'billing' not found"), "{}", stderr);
+    assert!(!stderr.contains("did you mean"), "{}", stderr);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_build_suggests_and_auto_corrects_project() -> anyhow::Result<()> {
+    log("→ Build Suggests And Auto-Corrects Project");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    create_projects(&workspace_path)?;
+    let path = fake_moon(workspace.path())?;
+    let env = [("PATH", path.as_str())];
+
+    let output = workspace.run_with_env("test-project", &["build", "frontned"], &env)?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Did you mean 'frontend'?"), "{}", stderr);
+    assert!(!stderr.contains("Available projects"), "{}", stderr);
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build", "frontned", "--auto-correct"],
+        &env,
+    )?;
+    assert!(
+        output.status.success(),
+        "Auto-corrected build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Using 'frontend' for 'frontned'"));
+    let log = fs::read_to_string(workspace.path().join("calls.log"))?;
+    assert!(log.contains("moon run frontend:build"), "{}", log);
+
+    Ok(())
+}
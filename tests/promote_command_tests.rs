@@ -0,0 +1,195 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<()> {
+    let status = Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()?
+        .status;
+    anyhow::ensure!(status.success(), "git {} failed", args.join(" "));
+    Ok(())
+}
+
+/// Fake `moon` logging each deploy with the environment it targets, and `pnpm` logging the
+/// wrangler commands it runs
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[
+            (
+                "moon",
+                "echo \"$* $WRANGLER_ENV\" >> \"$MOON_LOG\"\nexit 0\n",
+            ),
+            ("pnpm", "echo \"pnpm $*\" >> \"$MOON_LOG\"\nexit 0\n"),
+        ],
+    )
+}
+
+fn head(dir: &Path) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dir)
+        .output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[test]
+fn test_promote_deploys_the_staging_version_and_records_lineage() -> anyhow::Result<()> {
+    log("→ Promote Deploys The Staging Version And Records Lineage");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let project_path = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\n")],
+    )?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[deploy.environments.production]\npromote_from = \"staging\"\n",
+    )?;
+    fs::write(workspace_path.join(".gitignore"), ".moonflare/\nmoon.log\n")?;
+    git(&workspace_path, &["init", "-q"])?;
+    git(&workspace_path, &["add", "-A"])?;
+    git(&workspace_path, &["commit", "-q", "-m", "initial"])?;
+
+    let path = fake_moon(workspace.path())?;
+    let log_path = workspace_path.join("moon.log");
+    let log_file = log_path.to_string_lossy().to_string();
+    let env = [("PATH", path.as_str()), ("MOON_LOG", log_file.as_str())];
+
+    // Nothing to promote before staging has a deploy
+    let output = workspace.run_with_env("shop", &["promote", "staging", "production"], &env)?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Nothing has been deployed to staging"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Production only takes promotions
+    let output = workspace.run_with_env("shop", &["deploy", "api", "--env", "production"], &env)?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("only takes what staging runs"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = workspace.run_with_env("shop", &["deploy", "api", "--env", "staging"], &env)?;
+    assert!(
+        output.status.success(),
+        "Staging deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let staged = head(&workspace_path)?;
+
+    // Wrangler reported no version and nothing was built for production, so there's nothing
+    // to promote without rebuilding
+    let output = workspace.run_with_env("shop", &["promote", "staging", "production"], &env)?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("promoting never rebuilds"), "{}", stderr);
+    assert!(!fs::read_to_string(&log_path)?.contains("production"));
+
+    let manifest_path = workspace_path.join(".moonflare/deployments.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+    manifest["environments"]["staging"]["api"]["version_id"] = "v-123".into();
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+    // The recorded version ships whatever is checked out
+    fs::write(project_path.join("index.ts"), "export default {};\n")?;
+    git(&workspace_path, &["add", "-A"])?;
+    git(&workspace_path, &["commit", "-q", "-m", "next"])?;
+    let output = workspace.run_with_env("shop", &["promote", "staging", "production"], &env)?;
+    assert!(
+        output.status.success(),
+        "Promotion should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let calls = fs::read_to_string(&log_path)?;
+    assert!(
+        calls.contains("pnpm exec wrangler versions deploy v-123@100% --yes --env production"),
+        "{}",
+        calls
+    );
+    assert!(!calls.contains("run api:deploy production"), "{}", calls);
+
+    let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join(".moonflare/deployments.json"),
+    )?)?;
+    let promoted = &manifest["environments"]["production"]["api"];
+    assert_eq!(promoted["git_sha"], staged.as_str());
+    assert_eq!(promoted["promoted_from"], "staging");
+    assert_eq!(promoted["version_id"], "v-123");
+    assert!(manifest["environments"]["staging"]["api"]["promoted_from"].is_null());
+
+    Ok(())
+}
+
+#[test]
+fn test_promote_rejects_artifacts_from_another_commit() -> anyhow::Result<()> {
+    log("→ Promote Rejects Artifacts From Another Commit");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[(
+            "wrangler.jsonc",
+            r#"{"name": "web", "assets": {"directory": "./dist"}}"#,
+        )],
+    )?;
+    fs::write(workspace_path.join(".gitignore"), ".moonflare/\nmoon.log\n")?;
+    git(&workspace_path, &["init", "-q"])?;
+    git(&workspace_path, &["add", "-A"])?;
+    git(&workspace_path, &["commit", "-q", "-m", "initial"])?;
+
+    let path = fake_moon(workspace.path())?;
+    let log_path = workspace_path.join("moon.log");
+    let log_file = log_path.to_string_lossy().to_string();
+    let env = [("PATH", path.as_str()), ("MOON_LOG", log_file.as_str())];
+    let output = workspace.run_with_env("shop", &["deploy", "web", "--env", "staging"], &env)?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    fs::write(
+        workspace_path.join(".moonflare/build-manifest.json"),
+        serde_json::to_string_pretty(&serde_json::json!({
+            "projects": {
+                "web": {
+                    "built_at": "2025-01-01T00:00:00+00:00",
+                    "git_sha": "abcdef1234567890",
+                    "output": "apps/web/dist",
+                    "source_sha256": "0".repeat(64),
+                    "files": {}
+                }
+            }
+        }))?,
+    )?;
+    let output =
+        workspace.run_with_env("shop", &["promote", "staging", "production", "web"], &env)?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("build artifacts of 'web' are from abcdef1"),
+        "{}",
+        stderr
+    );
+    assert!(!fs::read_to_string(&log_path)?.contains("production"));
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const MOON_YML: &str =
+    "tasks:\n  build:\n    command: 'pnpm build'\n  test:\n    command: 'pnpm test'\n";
+
+/// Fake `moon`, `pnpm` and `cargo` that append their arguments to calls.log. `moon` fails
+/// when FAIL_CHECKS is set.
+#[cfg(unix)]
+fn fake_bin(dir: &Path) -> anyhow::Result<String> {
+    let log = dir.join("calls.log");
+    let script = |name: &str| {
+        format!(
+            "echo \"{} $*\" >> {}\n[ \"{}\" = moon ] && [ -n \"$FAIL_CHECKS\" ] && exit 1\nexit 0\n",
+            name,
+            log.display(),
+            name
+        )
+    };
+    fake_tools(
+        dir,
+        &[
+            ("moon", &script("moon")),
+            ("pnpm", &script("pnpm")),
+            ("cargo", &script("cargo")),
+        ],
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn test_publish_runs_checks_then_pnpm_publish_with_project_registry() -> anyhow::Result<()> {
+    log("→ Publish Runs Checks Then pnpm Publish With Project Registry");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[projects.ui-kit.publish]\nregistry = \"https://npm.example.com\"\naccess = \"public\"\ntag = \"next\"\n",
+    )?;
+    let ui_kit = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "ui-kit",
+        &[(
+            "package.json",
+            "{\"name\": \"@shop/ui-kit\", \"version\": \"0.3.0\"}\n",
+        )],
+    )?;
+    fs::write(ui_kit.join("moon.yml"), MOON_YML)?;
+    let path = fake_bin(workspace.path())?;
+    let calls = workspace.path().join("calls.log");
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["publish", "ui-kit", "--dry-run", "--provenance"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(
+        output.status.success(),
+        "Publish should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("@shop/ui-kit@0.3.0"));
+    let log = fs::read_to_string(&calls)?;
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines[0], "moon run ui-kit:build ui-kit:test");
+    assert_eq!(
+        lines[1],
+        "pnpm publish --no-git-checks --registry https://npm.example.com --access public --tag next --provenance --dry-run"
+    );
+
+    // Nothing is published when the checks fail
+    fs::remove_file(&calls)?;
+    let output = workspace.run_with_env(
+        "shop",
+        &["publish", "ui-kit", "--dry-run"],
+        &[("PATH", path.as_str()), ("FAIL_CHECKS", "1")],
+    )?;
+    assert!(!output.status.success());
+    assert!(!fs::read_to_string(&calls)?.contains("pnpm publish"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_publish_crate_and_refuses_private_packages() -> anyhow::Result<()> {
+    log("→ Publish Crate And Refuses Private Packages");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[projects.parser.publish]\nregistry = \"internal\"\n",
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::Crate,
+        "parser",
+        &[(
+            "Cargo.toml",
+            "[package]\nname = \"shop-parser\"\nversion = \"1.0.0\"\nedition = \"2021\"\n",
+        )],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "package.json",
+            "{\"name\": \"api\", \"version\": \"1.0.0\", \"private\": true}\n",
+        )],
+    )?;
+    let path = fake_bin(workspace.path())?;
+    let env = [("PATH", path.as_str())];
+
+    let output = workspace.run_with_env("shop", &["publish", "parser", "--dry-run"], &env)?;
+    assert!(
+        output.status.success(),
+        "Publish should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(workspace.path().join("calls.log"))?.trim(),
+        "cargo publish --registry internal --dry-run"
+    );
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["publish", "parser", "--dry-run", "--provenance"],
+        &env,
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only supported for npm"));
+
+    let output = workspace.run_with_env("shop", &["publish", "api", "--dry-run"], &env)?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("\"private\": true"));
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+mod common;
+
+const PRODUCER_JSONC: &str = r#"{
+  "name": "api",
+  "dev": { "port": PORT },
+  "queues": {
+    "producers": [{ "binding": "ORDERS", "queue": "orders" }],
+    "consumers": [{ "queue": "orders" }]
+  }
+}
+"#;
+
+/// Request line and body of each request the dev server received
+type Requests = Arc<Mutex<Vec<(String, String)>>>;
+
+/// Stands in for a `--with queue` Worker under `wrangler dev`: records each request line and
+/// body, and answers peeks with one consumed message
+fn queue_routes() -> anyhow::Result<(u16, Requests)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let port = listener.local_addr()?.port();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let request_line = request_line.trim().to_string();
+            let response = if request_line.starts_with("GET /__queues/peek") {
+                r#"[{"queue":"orders","id":"m1","timestamp":"2026-10-15T12:00:00.000Z","attempts":1,"body":{"id":7}}]"#
+            } else {
+                ""
+            };
+            let status = if response.is_empty() {
+                "202 Accepted"
+            } else {
+                "200 OK"
+            };
+            recorded
+                .lock()
+                .unwrap()
+                .push((request_line, String::from_utf8_lossy(&body).to_string()));
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                response.len(),
+                response
+            );
+        }
+    });
+    Ok((port, requests))
+}
+
+#[test]
+fn test_queues_send_local_and_peek_use_the_dev_server() -> anyhow::Result<()> {
+    log("→ Queues Send Local And Peek Use The Dev Server");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let (port, requests) = queue_routes()?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.jsonc",
+            &PRODUCER_JSONC.replace("PORT", &port.to_string()),
+        )],
+    )?;
+    fs::write(workspace_path.join("order.json"), r#"{ "id": 7 }"#)?;
+
+    let output = workspace.run(
+        "shop",
+        &[
+            "queues",
+            "send",
+            "orders",
+            "--body",
+            "@order.json",
+            "--local",
+        ],
+    )?;
+    assert!(
+        output.status.success(),
+        "Send should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("through ORDERS of 'api'"),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let output = workspace.run("shop", &["queues", "peek", "orders", "--json"])?;
+    assert!(
+        output.status.success(),
+        "Peek should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let messages: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(messages[0]["id"], "m1");
+    assert_eq!(messages[0]["body"]["id"], 7);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(
+        requests[0].0, "POST /__queues/send?binding=ORDERS HTTP/1.1",
+        "{:?}",
+        requests
+    );
+    let sent: serde_json::Value = serde_json::from_str(&requests[0].1)?;
+    assert_eq!(sent["id"], 7);
+    assert_eq!(requests[1].0, "GET /__queues/peek?queue=orders HTTP/1.1");
+    drop(requests);
+
+    let output = workspace.run(
+        "shop",
+        &["queues", "send", "payments", "--body", "{}", "--local"],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("No Worker produces to queue 'payments'"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("queues are orders"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn test_add_durable_object_with_queue() -> anyhow::Result<()> {
+    log("→ Add Durable Object With Queue");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run(
+        "shop",
+        &[
+            "add",
+            "durable-object",
+            "api",
+            "--with",
+            "queue",
+            "--with",
+            "debug-route",
+        ],
+    )?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let api = workspace_path.join("workers/api");
+    let wrangler = fs::read_to_string(api.join("wrangler.toml"))?;
+    let config: toml::Value = toml::from_str(&wrangler)?;
+    assert_eq!(
+        config["queues"]["producers"][0]["binding"].as_str(),
+        Some("QUEUE")
+    );
+    assert_eq!(
+        config["queues"]["consumers"][0]["queue"].as_str(),
+        Some("api-queue")
+    );
+    assert_eq!(config["vars"]["QUEUE_DEBUG"].as_str(), Some("false"));
+    assert_eq!(config["vars"]["DO_DEBUG"].as_str(), Some("false"));
+
+    let index = fs::read_to_string(api.join("src/index.ts"))?;
+    assert!(
+        index.contains("async queue(batch: MessageBatch)"),
+        "{}",
+        index
+    );
+    assert!(index.contains("\"/__queues/send\""), "{}", index);
+    let script = fs::read_to_string(api.join("scripts/send-test-message.mjs"))?;
+    assert!(script.contains("const QUEUE = 'api-queue';"), "{}", script);
+    let package = fs::read_to_string(api.join("package.json"))?;
+    serde_json::from_str::<serde_json::Value>(&package)?;
+    assert!(package.contains("\"queue:send\""), "{}", package);
+    let example = fs::read_to_string(api.join(".dev.vars.example"))?;
+    assert!(example.contains("QUEUE_DEBUG="), "{}", example);
+    assert!(!example.contains("QUEUE="), "{}", example);
+
+    // Without the feature, none of it is generated
+    workspace.run("shop", &["add", "durable-object", "plain"])?;
+    let plain = workspace_path.join("workers/plain");
+    assert!(!fs::read_to_string(plain.join("wrangler.toml"))?.contains("queues"));
+    assert!(!plain.join("scripts").exists());
+
+    let output = workspace.run("shop", &["add", "react", "web", "--with", "queue"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only available for Durable Object"));
+
+    Ok(())
+}
@@ -0,0 +1,370 @@
+use common::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+mod common;
+
+/// Objects in the mock account's buckets, as `bucket/key` to ETag
+type Buckets = Arc<Mutex<BTreeMap<String, String>>>;
+
+/// Minimal R2 object API over `buckets`. Returns the server's address and the uploads and
+/// deletes it received, as `PUT key content-type cache-control` and `DELETE key`.
+fn mock_r2(buckets: Buckets) -> anyhow::Result<(String, Arc<Mutex<Vec<String>>>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = format!("http://{}", listener.local_addr()?);
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&requests);
+
+    thread::spawn(move || {
+        let mut uploads = 0;
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut headers = BTreeMap::new();
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+                }
+            }
+            let length = headers
+                .get("content-length")
+                .map_or(0, |length| length.parse().unwrap());
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap_or_default().to_string();
+            let target = parts.next().unwrap_or_default().replace("%2F", "/");
+            let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+            let path = path.split("/r2/buckets/").nth(1).unwrap_or_default();
+            let (bucket, rest) = path.split_once('/').unwrap_or((path, ""));
+            let key = rest.strip_prefix("objects/").unwrap_or_default();
+
+            let result = match method.as_str() {
+                "GET" => {
+                    let prefix = query
+                        .split('&')
+                        .find_map(|pair| pair.strip_prefix("prefix="))
+                        .unwrap_or_default();
+                    let listed: Vec<serde_json::Value> = buckets
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .filter_map(|(object, etag)| {
+                            let key = object.strip_prefix(&format!("{}/", bucket))?;
+                            key.starts_with(prefix)
+                                .then(|| serde_json::json!({ "key": key, "etag": etag }))
+                        })
+                        .collect();
+                    serde_json::Value::Array(listed)
+                }
+                "PUT" => {
+                    uploads += 1;
+                    let etag = format!("etag-{}", uploads);
+                    buckets
+                        .lock()
+                        .unwrap()
+                        .insert(format!("{}/{}", bucket, key), etag.clone());
+                    received.lock().unwrap().push(format!(
+                        "PUT {} {} {}",
+                        key,
+                        headers.get("content-type").cloned().unwrap_or_default(),
+                        headers.get("cache-control").cloned().unwrap_or_default()
+                    ));
+                    serde_json::json!({ "key": key, "etag": etag })
+                }
+                _ => {
+                    buckets
+                        .lock()
+                        .unwrap()
+                        .remove(&format!("{}/{}", bucket, key));
+                    received.lock().unwrap().push(format!("DELETE {}", key));
+                    serde_json::Value::Null
+                }
+            };
+            let response = format!(r#"{{"success":true,"errors":[],"result":{}}}"#, result);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+        }
+    });
+
+    Ok((address, requests))
+}
+
+/// Fake `moon` whose docs build writes sites/docs/dist, with old.txt while `with-old` exists
+#[cfg(unix)]
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            r#"if [ "$2" = docs:build ]; then
+  rm -rf sites/docs/dist
+  mkdir -p sites/docs/dist/_astro
+  echo '<h1>Docs</h1>' > sites/docs/dist/index.html
+  echo 'console.log(1)' > sites/docs/dist/_astro/app.AbCd1234.js
+  printf '/*\n  X-Frame-Options: DENY\n' > sites/docs/dist/_headers
+  if [ -f with-old ]; then echo old > sites/docs/dist/old.txt; fi
+fi
+exit 0
+"#,
+        )],
+    )
+}
+
+fn sorted(requests: &Arc<Mutex<Vec<String>>>) -> Vec<String> {
+    let mut requests = std::mem::take(&mut *requests.lock().unwrap());
+    requests.sort();
+    requests
+}
+
+#[cfg(unix)]
+#[test]
+fn test_r2_static_strategy_syncs_changed_files() -> anyhow::Result<()> {
+    log("→ R2 Static Strategy Syncs Changed Files");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project("shop", &ProjectType::Astro, "docs", &[])?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        r#"[assets]
+immutable_max_age = 600
+
+[projects.docs.deploy]
+strategy = "r2-static"
+bucket = "docs-{env}"
+prefix = "v2/"
+"#,
+    )?;
+    fs::write(workspace_path.join("with-old"), "")?;
+
+    let buckets: Buckets = Arc::default();
+    buckets.lock().unwrap().extend([
+        (
+            "docs-staging/v2/stale.txt".to_string(),
+            "etag-old".to_string(),
+        ),
+        (
+            "docs-staging/v1/index.html".to_string(),
+            "etag-v1".to_string(),
+        ),
+    ]);
+    let (address, requests) = mock_r2(Arc::clone(&buckets))?;
+    let api = format!("{}/client/v4", address);
+    let path = fake_moon(workspace.path())?;
+    let env = [
+        ("PATH", path.as_str()),
+        ("CLOUDFLARE_API_TOKEN", "token"),
+        ("CLOUDFLARE_ACCOUNT_ID", "acct-1"),
+        ("CLOUDFLARE_API_BASE_URL", api.as_str()),
+    ];
+
+    // --no-delete leaves the object the output doesn't have
+    let output = workspace.run_with_env(
+        "shop",
+        &["deploy", "docs", "--env", "staging", "--no-delete"],
+        &env,
+    )?;
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        sorted(&requests),
+        vec![
+            "PUT v2/_astro/app.AbCd1234.js text/javascript; charset=utf-8 public, max-age=600, immutable",
+            "PUT v2/index.html text/html; charset=utf-8 public, max-age=0, must-revalidate",
+            "PUT v2/old.txt text/plain; charset=utf-8 public, max-age=0, must-revalidate",
+        ]
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("3 uploaded (") && stdout.contains("0 unchanged, 0 deleted, 1 kept"),
+        "{}",
+        stdout
+    );
+
+    // Unchanged files are skipped, and objects gone from the output are deleted, but only under
+    // the prefix
+    fs::remove_file(workspace_path.join("with-old"))?;
+    let output = workspace.run_with_env("shop", &["deploy", "docs", "--env", "staging"], &env)?;
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        sorted(&requests),
+        vec!["DELETE v2/old.txt", "DELETE v2/stale.txt"]
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2 unchanged, 2 deleted"));
+    assert!(
+        buckets
+            .lock()
+            .unwrap()
+            .contains_key("docs-staging/v1/index.html")
+    );
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["deploy", "docs", "--env", "staging", "--build-remote"],
+        &env,
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("r2-static strategy"));
+
+    Ok(())
+}
+
+#[test]
+fn test_r2_sync_uploads_changed_and_replaced_objects() -> anyhow::Result<()> {
+    log("→ R2 Sync Uploads Changed And Replaced Objects");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(workspace_path.join("moonflare.toml"), "")?;
+    let media = workspace_path.join("media");
+    fs::create_dir_all(media.join("photos"))?;
+    fs::write(media.join("logo.svg"), "<svg/>")?;
+    fs::write(media.join("photos/team.jpg"), "jpeg")?;
+
+    let buckets: Buckets = Arc::default();
+    let (address, requests) = mock_r2(Arc::clone(&buckets))?;
+    let api = format!("{}/client/v4", address);
+    let env = [
+        ("CLOUDFLARE_API_TOKEN", "token"),
+        ("CLOUDFLARE_ACCOUNT_ID", "acct-1"),
+        ("CLOUDFLARE_API_BASE_URL", api.as_str()),
+    ];
+    let sync = ["r2", "sync", "media", "media-backup", "--prefix", "2026/"];
+
+    let output = workspace.run_with_env("shop", &sync, &env)?;
+    assert!(
+        output.status.success(),
+        "Sync should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let uploads = sorted(&requests);
+    assert_eq!(uploads.len(), 2, "{:?}", uploads);
+    assert!(uploads[0].starts_with("PUT 2026/logo.svg image/svg+xml"));
+    assert!(uploads[1].starts_with("PUT 2026/photos/team.jpg image/jpeg"));
+    let states: Vec<_> = fs::read_dir(workspace_path.join(".moonflare/r2"))?
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    assert!(
+        states.len() == 1 && states[0].starts_with("media-backup-"),
+        "{:?}",
+        states
+    );
+
+    // A changed file and an object someone else replaced are uploaded again
+    fs::write(media.join("logo.svg"), "<svg></svg>")?;
+    buckets.lock().unwrap().insert(
+        "media-backup/2026/photos/team.jpg".to_string(),
+        "etag-elsewhere".to_string(),
+    );
+    let output = workspace.run_with_env("shop", &sync, &env)?;
+    assert!(output.status.success());
+    let uploads = sorted(&requests);
+    assert_eq!(uploads.len(), 2, "{:?}", uploads);
+
+    let output = workspace.run_with_env("shop", &sync, &env)?;
+    assert!(output.status.success());
+    assert!(sorted(&requests).is_empty());
+    assert!(
+        String::from_utf8_lossy(&output.stdout)
+            .contains("0 uploaded (0 B), 2 unchanged, 0 deleted")
+    );
+
+    let output =
+        workspace.run_with_env("shop", &["r2", "sync", "missing", "media-backup"], &env)?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not a directory"));
+
+    Ok(())
+}
+
+#[test]
+fn test_r2_sync_keeps_prefixes_apart() -> anyhow::Result<()> {
+    log("→ R2 Sync Keeps Prefixes Apart");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(workspace_path.join("moonflare.toml"), "")?;
+    let media = workspace_path.join("media");
+    fs::create_dir_all(&media)?;
+    fs::write(media.join("logo.svg"), "<svg/>")?;
+
+    let buckets: Buckets = Arc::default();
+    buckets.lock().unwrap().insert(
+        "media-backup/v2-old/logo.svg".to_string(),
+        "etag-old".to_string(),
+    );
+    let (address, requests) = mock_r2(Arc::clone(&buckets))?;
+    let api = format!("{}/client/v4", address);
+    let env = [
+        ("CLOUDFLARE_API_TOKEN", "token"),
+        ("CLOUDFLARE_ACCOUNT_ID", "acct-1"),
+        ("CLOUDFLARE_API_BASE_URL", api.as_str()),
+    ];
+
+    // A prefix without its slash still leaves the sibling v2-old/ alone
+    let output = workspace.run_with_env(
+        "shop",
+        &["r2", "sync", "media", "media-backup", "--prefix", "v2"],
+        &env,
+    )?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let transfers = sorted(&requests);
+    assert_eq!(transfers.len(), 1, "{:?}", transfers);
+    assert!(
+        transfers[0].starts_with("PUT v2/logo.svg"),
+        "{:?}",
+        transfers
+    );
+
+    // Syncing another prefix of the same bucket doesn't forget what v2/ holds
+    for prefix in ["v3/", "v2/"] {
+        let output = workspace.run_with_env(
+            "shop",
+            &["r2", "sync", "media", "media-backup", "--prefix", prefix],
+            &env,
+        )?;
+        assert!(output.status.success());
+    }
+    let transfers = sorted(&requests);
+    assert_eq!(
+        transfers,
+        vec!["PUT v3/logo.svg image/svg+xml public, max-age=0, must-revalidate"]
+    );
+    assert!(
+        buckets
+            .lock()
+            .unwrap()
+            .contains_key("media-backup/v2-old/logo.svg")
+    );
+
+    Ok(())
+}
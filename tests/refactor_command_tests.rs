@@ -0,0 +1,177 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const API_WRANGLER: &str = r#"{
+  // Cache for rendered pages
+  "name": "api",
+  "kv_namespaces": [{ "binding": "CACHE", "id": "abc" }],
+  "vars": { "API_URL": "https://example.com", "CACHE_TTL": "60" },
+  "env": {
+    "staging": {
+      "kv_namespaces": [{ "binding": "CACHE", "id": "def" }],
+    },
+  },
+}
+"#;
+
+const JOBS_WRANGLER: &str = r#"name = "jobs"
+
+# Queue state
+[[durable_objects.bindings]]
+name = "CACHE"
+class_name = "Cache"
+
+[vars]
+CACHE_TTL = "60"
+"#;
+
+#[test]
+fn test_rename_binding_rewrites_configs_sources_and_dev_vars() -> anyhow::Result<()> {
+    log("→ Rename Binding Rewrites Configs, Sources And Dev Vars");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    let api = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.jsonc", API_WRANGLER),
+            (
+                "src/index.ts",
+                "const page = await env.CACHE.get(key);\nconst ttl = env.CACHE_TTL;\nconst kv = c.env[\"CACHE\"];\n",
+            ),
+            (".dev.vars", "CACHE=local\nCACHE_TTL=1\n"),
+            ("dist/index.js", "env.CACHE.get(key)\n"),
+        ],
+    )?;
+    let jobs = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "jobs",
+        &[
+            ("wrangler.toml", JOBS_WRANGLER),
+            ("src/index.ts", "export const cache = (env) => env.CACHE;\n"),
+        ],
+    )?;
+
+    let output = workspace.run(
+        "shop",
+        &["refactor", "rename-binding", "CACHE", "PAGES", "--dry-run"],
+    )?;
+    assert!(
+        output.status.success(),
+        "Dry run should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("- const page = await env.CACHE.get(key);"));
+    assert!(stdout.contains("+ const page = await env.PAGES.get(key);"));
+    assert_eq!(
+        fs::read_to_string(api.join("wrangler.jsonc"))?,
+        API_WRANGLER
+    );
+
+    // Without a terminal the rename needs approving up front
+    let output = workspace.run("shop", &["refactor", "rename-binding", "CACHE", "PAGES"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--auto-approve"));
+
+    let output = workspace.run(
+        "shop",
+        &["refactor", "rename-binding", "CACHE", "PAGES", "--yes"],
+    )?;
+    assert!(
+        output.status.success(),
+        "Rename should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(api.join("wrangler.jsonc"))?,
+        API_WRANGLER.replace("\"CACHE\"", "\"PAGES\"")
+    );
+    assert_eq!(
+        fs::read_to_string(api.join("src/index.ts"))?,
+        "const page = await env.PAGES.get(key);\nconst ttl = env.CACHE_TTL;\nconst kv = c.env[\"PAGES\"];\n"
+    );
+    assert_eq!(
+        fs::read_to_string(api.join(".dev.vars"))?,
+        "PAGES=local\nCACHE_TTL=1\n"
+    );
+    assert_eq!(
+        fs::read_to_string(api.join("dist/index.js"))?,
+        "env.CACHE.get(key)\n"
+    );
+    assert_eq!(
+        fs::read_to_string(jobs.join("wrangler.toml"))?,
+        JOBS_WRANGLER.replace("name = \"CACHE\"", "name = \"PAGES\"")
+    );
+    assert_eq!(
+        fs::read_to_string(jobs.join("src/index.ts"))?,
+        "export const cache = (env) => env.PAGES;\n"
+    );
+
+    let output = workspace.run("shop", &["restore", "latest"])?;
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(api.join("wrangler.jsonc"))?,
+        API_WRANGLER
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_rename_binding_renames_vars_and_refuses_names_in_use() -> anyhow::Result<()> {
+    log("→ Rename Binding Renames Vars And Refuses Names In Use");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    let api = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", API_WRANGLER)],
+    )?;
+    let jobs = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "jobs",
+        &[("wrangler.toml", JOBS_WRANGLER)],
+    )?;
+
+    let output = workspace.run(
+        "shop",
+        &["refactor", "rename-binding", "CACHE", "CACHE_TTL", "--yes"],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already has a binding"));
+
+    let output = workspace.run(
+        "shop",
+        &[
+            "refactor",
+            "rename-binding",
+            "CACHE_TTL",
+            "PAGE_TTL",
+            "--yes",
+        ],
+    )?;
+    assert!(
+        output.status.success(),
+        "Rename should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(fs::read_to_string(api.join("wrangler.jsonc"))?.contains("\"PAGE_TTL\": \"60\""));
+    assert!(
+        fs::read_to_string(jobs.join("wrangler.toml"))?.contains("[vars]\nPAGE_TTL = \"60\"\n")
+    );
+
+    let output = workspace.run(
+        "shop",
+        &["refactor", "rename-binding", "MISSING", "OTHER", "--yes"],
+    )?;
+    assert!(!output.status.success());
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+/// A repository whose identity the release commit and tag are made with
+fn init_repo(dir: &Path) -> anyhow::Result<()> {
+    git(dir, &["init", "-q"])?;
+    git(dir, &["config", "user.name", "Test"])?;
+    git(dir, &["config", "user.email", "test@example.com"])?;
+    Ok(())
+}
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn commit(dir: &Path, file: &str, message: &str) -> anyhow::Result<()> {
+    fs::write(dir.join(file), message)?;
+    git(dir, &["add", "-A"])?;
+    git(dir, &["commit", "-q", "-m", message])?;
+    Ok(())
+}
+
+#[test]
+fn test_release_bumps_version_from_conventional_commits() -> anyhow::Result<()> {
+    log("→ Release Bumps Version From Conventional Commits");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let api = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "package.json",
+            "{\n  \"name\": \"api\",\n  \"version\": \"1.2.3\",\n  \"private\": true\n}\n",
+        )],
+    )?;
+    let web = workspace.create_minimal_project("shop", &ProjectType::React, "web", &[])?;
+    init_repo(&workspace_path)?;
+    git(&workspace_path, &["add", "-A"])?;
+    git(
+        &workspace_path,
+        &["commit", "-q", "-m", "chore: initial commit"],
+    )?;
+    git(&workspace_path, &["tag", "api-v1.2.3"])?;
+    commit(&api, "a.ts", "fix(api): handle empty carts")?;
+    commit(&api, "b.ts", "feat(api): add checkout endpoint")?;
+    commit(&api, "c.ts", "not a conventional commit")?;
+    // Breaking changes elsewhere in the workspace don't count
+    commit(&web, "d.ts", "feat!: redesign")?;
+
+    let output = workspace.run("shop", &["release", "api", "--dry-run"])?;
+    assert!(
+        output.status.success(),
+        "Dry run should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1.3.0"));
+    assert!(!api.join("CHANGELOG.md").exists());
+
+    let output = workspace.run("shop", &["release", "api"])?;
+    assert!(
+        output.status.success(),
+        "Release should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(fs::read_to_string(api.join("package.json"))?.contains("\"version\": \"1.3.0\""));
+    let changelog = fs::read_to_string(api.join("CHANGELOG.md"))?;
+    assert!(
+        changelog.starts_with("# Changelog\n\n## 1.3.0 ("),
+        "{}",
+        changelog
+    );
+    assert!(changelog.contains("### Features\n\n- **api:** add checkout endpoint"));
+    assert!(changelog.contains("### Bug Fixes\n\n- **api:** handle empty carts"));
+    assert!(!changelog.contains("redesign"));
+    assert_eq!(
+        git(&workspace_path, &["tag", "--list", "api-v*"])?,
+        "api-v1.2.3\napi-v1.3.0"
+    );
+    assert_eq!(
+        git(&workspace_path, &["log", "-1", "--format=%s"])?,
+        "chore(release): api v1.3.0"
+    );
+
+    // Nothing to release until another commit touches the project
+    let output = workspace.run("shop", &["release", "api"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--bump"));
+
+    Ok(())
+}
+
+#[test]
+fn test_release_updates_crate_version_and_breaking_bump() -> anyhow::Result<()> {
+    log("→ Release Updates Crate Version And Breaking Bump");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let core = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::Crate,
+        "core",
+        &[(
+            "Cargo.toml",
+            "[package]\nname = \"core\"\nversion = \"0.4.1\"\nedition = \"2021\"\n\n[dependencies]\nserde = { version = \"1\" }\n",
+        )],
+    )?;
+    init_repo(&workspace_path)?;
+    commit(&core, "lib.rs", "feat: initial api")?;
+    fs::write(core.join("lib.rs"), "changed")?;
+    git(&workspace_path, &["add", "-A"])?;
+    git(
+        &workspace_path,
+        &[
+            "commit",
+            "-q",
+            "-m",
+            "refactor: rename types",
+            "-m",
+            "BREAKING CHANGE: Foo is now Bar",
+        ],
+    )?;
+
+    let output = workspace.run("shop", &["release", "core"])?;
+    assert!(
+        output.status.success(),
+        "Release should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // Breaking changes only bump the minor version before 1.0.0
+    let manifest = fs::read_to_string(core.join("Cargo.toml"))?;
+    assert!(manifest.contains("version = \"0.5.0\"\n"));
+    assert!(manifest.contains("serde = { version = \"1\" }"));
+    let changelog = fs::read_to_string(core.join("CHANGELOG.md"))?;
+    assert!(changelog.contains("### Breaking Changes\n\n- rename types"));
+    assert_eq!(
+        git(&workspace_path, &["tag", "--list", "core-v*"])?,
+        "core-v0.5.0"
+    );
+
+    Ok(())
+}
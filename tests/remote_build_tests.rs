@@ -0,0 +1,201 @@
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+mod common;
+
+const WRANGLER_JSONC: &str = r#"{
+  "name": "web",
+  "account_id": "acct-1",
+  "compatibility_date": "2024-01-01"
+}
+"#;
+
+/// Minimal GitHub and Cloudflare APIs answering the endpoints `builds setup` and
+/// `deploy --build-remote` call. Returns the server's address and the requests it received.
+fn mock_api() -> anyhow::Result<(String, Arc<Mutex<Vec<String>>>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = format!("http://{}", listener.local_addr()?);
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&requests);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = header.to_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            received.lock().unwrap().push(format!(
+                "{} {}",
+                request_line.trim(),
+                String::from_utf8_lossy(&body)
+            ));
+
+            let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+            let response = if path.starts_with("/repos/") {
+                r#"{"id":42,"name":"shop","owner":{"id":7,"login":"acme"}}"#.to_string()
+            } else {
+                let result = if path.ends_with("/workers/scripts") {
+                    r#"[{"id":"web","tag":"tag-web"}]"#
+                } else if path.ends_with("/builds/repos/connections") {
+                    r#"{"repo_connection_uuid":"conn-1"}"#
+                } else if path.ends_with("/builds/triggers") {
+                    r#"{"trigger_uuid":"trigger-1"}"#
+                } else if path.ends_with("/builds/triggers/trigger-1/builds") {
+                    r#"{"build_uuid":"build-1","status":"queued"}"#
+                } else {
+                    r#"{"build_uuid":"build-1","status":"stopped","build_outcome":"success"}"#
+                };
+                format!(r#"{{"success":true,"errors":[],"result":{}}}"#, result)
+            };
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+        }
+    });
+
+    Ok((address, requests))
+}
+
+fn git(path: &Path, args: &[&str]) -> anyhow::Result<()> {
+    let status = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(path)
+        .status()?;
+    anyhow::ensure!(status.success(), "git {:?} failed", args);
+    Ok(())
+}
+
+#[test]
+fn test_builds_setup_and_deploy_build_remote() -> anyhow::Result<()> {
+    log("→ Builds Setup And Deploy Build Remote");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[("wrangler.jsonc", WRANGLER_JSONC)],
+    )?;
+    git(&workspace_path, &["init", "-q", "-b", "main"])?;
+    git(&workspace_path, &["add", "-A"])?;
+    git(&workspace_path, &["commit", "-qm", "init"])?;
+    git(
+        &workspace_path,
+        &["remote", "add", "origin", "git@github.com:acme/shop.git"],
+    )?;
+
+    let (address, requests) = mock_api()?;
+    let api_base = format!("{}/client/v4", address);
+    let env = [
+        ("CLOUDFLARE_API_TOKEN", "token"),
+        ("CLOUDFLARE_API_BASE_URL", api_base.as_str()),
+        ("GITHUB_API_URL", address.as_str()),
+        ("GITHUB_HEAD_REF", ""),
+        ("GITHUB_REF_NAME", ""),
+    ];
+
+    // Not set up yet
+    let output = workspace.run_with_env("shop", &["deploy", "web", "--build-remote"], &env)?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("builds setup"));
+
+    let output = workspace.run_with_env("shop", &["builds", "setup", "web"], &env)?;
+    assert!(
+        output.status.success(),
+        "Setup should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    {
+        let requests = requests.lock().unwrap();
+        let trigger = requests
+            .iter()
+            .find(|r| r.starts_with("POST /client/v4/accounts/acct-1/builds/triggers "))
+            .expect("trigger created");
+        assert!(
+            trigger.contains("\"external_script_id\":\"tag-web\""),
+            "{}",
+            trigger
+        );
+        assert!(
+            trigger.contains("\"repo_connection_uuid\":\"conn-1\""),
+            "{}",
+            trigger
+        );
+        assert!(trigger.contains("moon run web:build"), "{}", trigger);
+        assert!(
+            trigger.contains("\"branch_includes\":[\"main\"]"),
+            "{}",
+            trigger
+        );
+        let connection = requests
+            .iter()
+            .find(|r| r.contains("/builds/repos/connections"))
+            .expect("repository connected");
+        assert!(connection.contains("\"repo_id\":\"42\""), "{}", connection);
+        assert!(
+            connection.contains("\"provider_account_name\":\"acme\""),
+            "{}",
+            connection
+        );
+    }
+    let manifest = fs::read_to_string(workspace_path.join(".moonflare/deployments.json"))?;
+    assert!(
+        manifest.contains("\"trigger_uuid\": \"trigger-1\""),
+        "{}",
+        manifest
+    );
+
+    // The commit has to be on a remote branch for Cloudflare to check it out
+    let output = workspace.run_with_env("shop", &["deploy", "web", "--build-remote"], &env)?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("push it"));
+
+    git(
+        &workspace_path,
+        &["update-ref", "refs/remotes/origin/main", "HEAD"],
+    )?;
+    let output = workspace.run_with_env("shop", &["deploy", "web", "--build-remote"], &env)?;
+    assert!(
+        output.status.success(),
+        "Remote deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Workers Build build-1 started"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Build succeeded"), "{}", stdout);
+    assert!(requests.lock().unwrap().iter().any(|r| {
+        r.starts_with("POST /client/v4/accounts/acct-1/builds/triggers/trigger-1/builds")
+            && r.contains("\"branch\":\"main\"")
+    }));
+
+    Ok(())
+}
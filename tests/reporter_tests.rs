@@ -0,0 +1,179 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const TEST_TASK: &str = "tasks:\n  test:\n    command: 'true'\n";
+
+/// Put a fake `moon` first on PATH that passes `passing:test` and fails every other target
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            "case \"$2\" in\n  passing:test) exit 0 ;;\n  *) exit 1 ;;\nesac\n",
+        )],
+    )
+}
+
+fn setup(workspace: &MoonflareTestWorkspace) -> anyhow::Result<String> {
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    for name in ["passing", "failing"] {
+        workspace.create_minimal_project(
+            "test-project",
+            &ProjectType::Crate,
+            name,
+            &[("moon.yml", TEST_TASK)],
+        )?;
+    }
+    fake_moon(&workspace_path)
+}
+
+#[test]
+fn test_junit_reporter_has_a_case_per_target() -> anyhow::Result<()> {
+    log("→ JUnit Reporter Has A Case Per Target");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let path = setup(&workspace)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &[
+            "test",
+            "--reporter",
+            "junit",
+            "--reporter-output",
+            "junit.xml",
+        ],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        !output.status.success(),
+        "A failing target fails the command"
+    );
+
+    let xml = fs::read_to_string(workspace.path().join("test-project/junit.xml"))?;
+    assert!(xml.contains("tests=\"2\" failures=\"1\""), "{}", xml);
+    assert!(xml.contains("<testcase name=\"passing:test\" classname=\"crates/passing\""));
+    assert!(xml.contains("<testcase name=\"failing:test\" classname=\"crates/failing\""));
+    assert!(xml.contains("<failure message="));
+
+    Ok(())
+}
+
+#[test]
+fn test_github_reporter_annotates_failed_targets() -> anyhow::Result<()> {
+    log("→ GitHub Reporter Annotates Failed Targets");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let path = setup(&workspace)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["test", "--reporter", "github"],
+        &[("PATH", &path)],
+    )?;
+    assert!(!output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("::error file=crates/failing/moon.yml,title=failing%3Atest failed::"),
+        "Should annotate the failing project: {}",
+        stdout
+    );
+    assert!(!stdout.contains("file=crates/passing"));
+
+    Ok(())
+}
+
+#[test]
+fn test_json_reporter_summarizes_command() -> anyhow::Result<()> {
+    log("→ JSON Reporter Summarizes Command");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let path = setup(&workspace)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &[
+            "test",
+            "passing",
+            "--reporter",
+            "json",
+            "--reporter-output",
+            "report.json",
+        ],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace.path().join("test-project/report.json"),
+    )?)?;
+    assert_eq!(report["command"], "test");
+    assert_eq!(report["success"], true);
+    assert_eq!(report["tasks"][0]["target"], "passing:test");
+    assert_eq!(report["tasks"][0]["project_path"], "crates/passing");
+
+    Ok(())
+}
+
+#[test]
+fn test_github_actions_gets_junit_and_job_summary() -> anyhow::Result<()> {
+    log("→ GitHub Actions Gets JUnit And Job Summary");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let path = setup(&workspace)?;
+    let summary = workspace.path().join("step-summary.md");
+    fs::write(&summary, "Earlier step\n")?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["test"],
+        &[
+            ("PATH", path.as_str()),
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_STEP_SUMMARY", summary.to_str().unwrap()),
+        ],
+    )?;
+    assert!(!output.status.success());
+
+    let markdown = fs::read_to_string(&summary)?;
+    assert!(markdown.starts_with("Earlier step\n"), "{}", markdown);
+    assert!(
+        markdown.contains("### ❌ moonflare test failed"),
+        "{}",
+        markdown
+    );
+    assert!(markdown.contains("2 task(s) in 2 project(s), 1 failed"));
+    assert!(markdown.contains("| passing | `passing:test` | ✅ passed |"));
+    assert!(markdown.contains("| failing | `failing:test` | ❌ failed |"));
+    assert!(markdown.contains("<details><summary>failing:test</summary>"));
+
+    let xml = fs::read_to_string(
+        workspace
+            .path()
+            .join("test-project/.moonflare/reports/test.xml"),
+    )?;
+    assert!(xml.contains("tests=\"2\" failures=\"1\""), "{}", xml);
+
+    // An explicit reporter replaces the automatic ones
+    fs::remove_file(&summary)?;
+    let output = workspace.run_with_env(
+        "test-project",
+        &["test", "passing", "--reporter", "markdown"],
+        &[
+            ("PATH", path.as_str()),
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_STEP_SUMMARY", summary.to_str().unwrap()),
+        ],
+    )?;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("### ✅ moonflare test passed"));
+    assert!(!summary.exists());
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_repro_pack_and_unpack_round_trip_without_secrets() -> anyhow::Result<()> {
+    log("→ Repro Pack And Unpack Round Trip Without Secrets");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let project_path = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            (
+                "wrangler.jsonc",
+                r#"{
+  "name": "api",
+  "vars": {
+    "LOG_LEVEL": "warn",
+    "STRIPE_API_KEY": "sk_live_123"
+  }
+}
+"#,
+            ),
+            ("package.json", r#"{"name": "api", "private": true}"#),
+            ("src/index.ts", "export default {};\n"),
+            (".dev.vars", "SESSION_SECRET=hunter2\n"),
+            ("node_modules/dep/package.json", "{}"),
+        ],
+    )?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[metrics]\ntoken = \"$METRICS_TOKEN\"\n\n[deploy.notify]\nwebhook = \"https://hooks.example.com/abc\"\n",
+    )?;
+    fs::write(
+        workspace_path.join(".npmrc"),
+        "//registry.npmjs.org/:_authToken=npm_abcdef\nauto-install-peers=true\n",
+    )?;
+    fs::create_dir_all(workspace_path.join(".moonflare"))?;
+    fs::write(
+        workspace_path.join(".moonflare/history.jsonl"),
+        "{\"command\":\"deploy\"}\n",
+    )?;
+
+    let output = workspace.run("shop", &["repro", "pack", "--output", "repro.tar.gz"])?;
+    assert!(
+        output.status.success(),
+        "pack should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("redacted"), "{}", stdout);
+    assert!(workspace_path.join("repro.tar.gz").is_file());
+
+    let output = workspace.run(
+        "",
+        &[
+            "repro",
+            "unpack",
+            workspace_path.join("repro.tar.gz").to_str().unwrap(),
+            "--into",
+            "restored",
+        ],
+    )?;
+    assert!(
+        output.status.success(),
+        "unpack should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("with moonflare"),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let restored = workspace.path().join("restored");
+    assert!(restored.join(".moon/workspace.yml").is_file());
+    assert!(restored.join("workers/api/moon.yml").is_file());
+    assert!(restored.join("workers/api/package.json").is_file());
+    assert!(restored.join(".moonflare/history.jsonl").is_file());
+    assert!(restored.join("repro.json").is_file());
+    assert!(!restored.join("workers/api/src/index.ts").exists());
+    assert!(!restored.join("workers/api/.dev.vars").exists());
+    assert!(!restored.join("workers/api/node_modules").exists());
+
+    let wrangler = fs::read_to_string(restored.join("workers/api/wrangler.jsonc"))?;
+    assert!(wrangler.contains("\"LOG_LEVEL\": \"warn\""), "{}", wrangler);
+    assert!(
+        wrangler.contains("\"STRIPE_API_KEY\": \"<redacted>\""),
+        "{}",
+        wrangler
+    );
+    let config = fs::read_to_string(restored.join("moonflare.toml"))?;
+    assert!(config.contains("token = \"$METRICS_TOKEN\""), "{}", config);
+    assert!(config.contains("webhook = \"<redacted>\""), "{}", config);
+    let npmrc = fs::read_to_string(restored.join(".npmrc"))?;
+    assert!(!npmrc.contains("npm_abcdef"), "{}", npmrc);
+    assert!(npmrc.contains("auto-install-peers=true"), "{}", npmrc);
+    assert!(project_path.join(".dev.vars").exists());
+
+    // Unpacking over a directory with files in it is refused
+    let output = workspace.run(
+        "",
+        &[
+            "repro",
+            "unpack",
+            workspace_path.join("repro.tar.gz").to_str().unwrap(),
+            "--into",
+            "restored",
+        ],
+    )?;
+    assert!(!output.status.success());
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use common::*;
+
+mod common;
+
+#[test]
+fn test_restore_undoes_rename() -> anyhow::Result<()> {
+    log("→ Restore Undoes Rename");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\n")],
+    )?;
+
+    let output = workspace.run("test-project", &["rename", "api", "backend"])?;
+    assert!(
+        output.status.success(),
+        "Rename should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(workspace_path.join("workers/backend").exists());
+
+    let output = workspace.run("test-project", &["restore", "latest"])?;
+    assert!(
+        output.status.success(),
+        "Restore should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert!(!workspace_path.join("workers/backend").exists());
+    let wrangler = std::fs::read_to_string(workspace_path.join("workers/api/wrangler.toml"))?;
+    assert!(
+        wrangler.contains("name = \"api\""),
+        "Original wrangler name should be restored: {}",
+        wrangler
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_restore_lists_snapshots() -> anyhow::Result<()> {
+    log("→ Restore Lists Snapshots");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::Crate, "utils", &[])?;
+
+    let output = workspace.run("test-project", &["restore"])?;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No snapshots found"));
+
+    workspace.run("test-project", &["rename", "utils", "core"])?;
+
+    let output = workspace.run("test-project", &["restore"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("rename utils core"),
+        "Snapshot list should name the operation: {}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_restore_unknown_snapshot_fails() -> anyhow::Result<()> {
+    log("→ Restore Unknown Snapshot Fails");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["restore", "19700101-000000"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not found"));
+
+    Ok(())
+}
+
+#[test]
+fn test_restore_keeps_installed_dependencies() -> anyhow::Result<()> {
+    log("→ Restore Keeps Installed Dependencies");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.toml", "name = \"api\"\n"),
+            ("node_modules/dep/index.js", "module.exports = {};\n"),
+        ],
+    )?;
+
+    let output = workspace.run("test-project", &["rename", "api", "backend"])?;
+    assert!(output.status.success());
+    assert!(
+        workspace_path
+            .join("workers/backend/node_modules/dep/index.js")
+            .exists()
+    );
+
+    let output = workspace.run("test-project", &["restore", "latest"])?;
+    assert!(
+        output.status.success(),
+        "Restore should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert!(!workspace_path.join("workers/backend").exists());
+    assert!(
+        workspace_path
+            .join("workers/api/node_modules/dep/index.js")
+            .exists(),
+        "node_modules should move back with the project"
+    );
+
+    Ok(())
+}
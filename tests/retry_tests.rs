@@ -0,0 +1,147 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// A fake `moon` whose first deploy fails with `failure` on stderr and whose later deploys
+/// succeed. Every deploy attempt is appended to deploys.log.
+#[cfg(unix)]
+fn fake_moon(dir: &Path, failure: &str) -> anyhow::Result<String> {
+    let log = dir.join("deploys.log");
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "case \"$*\" in\n  run*deploy*)\n    echo attempt >> {log}\n    if [ \"$(wc -l < {log})\" -eq 1 ]; then printf '%b\\n' '{failure}' >&2; exit 1; fi\n    exit 0;;\n  run*) exit 0;;\nesac\nexit 1\n",
+                log = log.display(),
+                failure = failure
+            ),
+        )],
+    )
+}
+
+fn create_worker(workspace_path: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(workspace_path.join("workers/api"))?;
+    fs::write(
+        workspace_path.join("workers/api/moon.yml"),
+        "tasks:\n  deploy:\n    command: 'wrangler deploy'\n",
+    )?;
+    let config = workspace_path.join("moonflare.toml");
+    let existing = fs::read_to_string(&config).unwrap_or_default();
+    fs::write(
+        &config,
+        format!("{}\n[retry]\nattempts = 3\nbackoff = \"1s\"\n", existing),
+    )?;
+    Ok(())
+}
+
+fn attempts(dir: &Path) -> usize {
+    fs::read_to_string(dir.join("deploys.log"))
+        .map(|log| log.lines().count())
+        .unwrap_or_default()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_deploy_retries_transient_failure() -> anyhow::Result<()> {
+    log("→ Deploy Retries Transient Failure");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    create_worker(&workspace_path)?;
+    let path = fake_moon(
+        workspace.path(),
+        "✘ [ERROR] A request to the Cloudflare API failed: 503 Service Unavailable",
+    )?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["deploy", "api"],
+        &[("PATH", path.as_str())],
+    )?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("retrying in 1s (attempt 2/3)"),
+        "{}",
+        stderr
+    );
+    assert_eq!(attempts(workspace.path()), 2);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_deploy_does_not_retry_project_errors() -> anyhow::Result<()> {
+    log("→ Deploy Does Not Retry Project Errors");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    create_worker(&workspace_path)?;
+    let path = fake_moon(
+        workspace.path(),
+        "✘ [ERROR] Could not resolve \"./missing\"",
+    )?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["deploy", "api"],
+        &[("PATH", path.as_str())],
+    )?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(!stderr.contains("retrying"), "{}", stderr);
+    assert_eq!(attempts(workspace.path()), 1);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_deploy_retries_wrangler_api_error_block() -> anyhow::Result<()> {
+    log("→ Deploy Retries Wrangler API Error Block");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    create_worker(&workspace_path)?;
+    let path = fake_moon(
+        workspace.path(),
+        "api:deploy | ✘ [ERROR] A request to the Cloudflare API (/accounts/abc/workers/scripts/api) failed.\\n\\n  Internal Server Error [code: 10013]",
+    )?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["deploy", "api"],
+        &[("PATH", path.as_str())],
+    )?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(attempts(workspace.path()), 2);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_deploy_ignores_network_words_outside_wrangler_errors() -> anyhow::Result<()> {
+    log("→ Deploy Ignores Network Words Outside Wrangler Errors");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    create_worker(&workspace_path)?;
+    let path = fake_moon(
+        workspace.path(),
+        "FAIL src/index.test.ts > renders the error page\\n  expected \"Internal Server Error\" after fetch failed",
+    )?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["deploy", "api"],
+        &[("PATH", path.as_str())],
+    )?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(!stderr.contains("retrying"), "{}", stderr);
+    assert_eq!(attempts(workspace.path()), 1);
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use common::*;
+
+mod common;
+
+fn setup(workspace: &MoonflareTestWorkspace) -> anyhow::Result<()> {
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.toml",
+            "name = \"api\"\nroutes = [\"example.com/api/*\"]\n\n[env.staging]\nroutes = [{ pattern = \"staging.example.com\", custom_domain = true }]\n",
+        )],
+    )?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "web",
+        &[(
+            "wrangler.jsonc",
+            r#"{
+  "name": "web",
+  "routes": ["example.com/app/*"],
+  "env": { "staging": { "routes": ["staging.example.com/*"] } }
+}"#,
+        )],
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_deploy_refuses_conflicting_routes() -> anyhow::Result<()> {
+    log("→ Deploy Refuses Conflicting Routes");
+    let workspace = MoonflareTestWorkspace::new()?;
+    setup(&workspace)?;
+
+    let output = workspace.run("test-project", &["deploy", "web", "--env", "staging"])?;
+    assert!(
+        !output.status.success(),
+        "Deploy should refuse the conflict"
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Route conflicts in environment 'staging'"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("staging.example.com (custom domain)"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("custom domain takes the host"),
+        "{}",
+        stdout
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 route conflict(s)"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn test_doctor_reports_route_conflicts_per_environment() -> anyhow::Result<()> {
+    log("→ Doctor Reports Route Conflicts Per Environment");
+    let workspace = MoonflareTestWorkspace::new()?;
+    setup(&workspace)?;
+
+    let output = workspace.run("test-project", &["doctor"])?;
+    assert!(
+        !output.status.success(),
+        "Doctor should report the conflict"
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Checking routes"), "{}", stdout);
+    assert!(stdout.contains("staging: web claims"), "{}", stdout);
+    // example.com/api/* and example.com/app/* don't overlap
+    assert!(!stdout.contains("default environment:"), "{}", stdout);
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use common::*;
+
+mod common;
+
+fn add_workers(workspace: &MoonflareTestWorkspace) -> anyhow::Result<()> {
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.jsonc",
+            r#"{
+  "name": "shop-api",
+  "routes": [
+    { "pattern": "api.example.com/*", "zone_name": "example.com" },
+    { "pattern": "status.example.com", "custom_domain": true }
+  ],
+  "env": {
+    "staging": {
+      "name": "shop-api-staging",
+      "routes": [{ "pattern": "api.staging.example.com/*", "zone_name": "example.com" }]
+    }
+  }
+}"#,
+        )],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "edge",
+        &[(
+            "wrangler.jsonc",
+            r#"{
+  "name": "shop-edge",
+  "route": "*.example.com/*",
+  "env": { "staging": { "workers_dev": false, "routes": [] } }
+}"#,
+        )],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "jobs",
+        &[("wrangler.jsonc", r#"{ "name": "shop-jobs" }"#)],
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_routes_list_table() -> anyhow::Result<()> {
+    log("→ Routes List Table");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    add_workers(&workspace)?;
+
+    let output = workspace.run("shop", &["routes", "list"])?;
+    assert!(
+        output.status.success(),
+        "Routes list should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for expected in [
+        "api.example.com/*",
+        "zone example.com",
+        "custom domain",
+        "status.example.com",
+        "api.staging.example.com/*",
+        "shop-api-staging",
+        "conflicts with api",
+        "conflicts with edge",
+        "shop-jobs.<subdomain>.workers.dev",
+        "Wrangler default",
+        "not publicly reachable",
+        "moonflare doctor",
+    ] {
+        assert!(
+            stdout.contains(expected),
+            "Missing {}:\n{}",
+            expected,
+            stdout
+        );
+    }
+
+    let output = workspace.run("shop", &["routes", "list", "--env", "staging"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("api.staging.example.com/*"), "{}", stdout);
+    assert!(!stdout.contains("(top-level)"), "{}", stdout);
+    assert!(
+        !stdout.contains("shop-jobs"),
+        "jobs has no staging env: {}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_routes_list_json() -> anyhow::Result<()> {
+    log("→ Routes List Json");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    add_workers(&workspace)?;
+
+    let output = workspace.run("shop", &["routes", "list", "--json"])?;
+    assert!(
+        output.status.success(),
+        "Routes list should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let exposures: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+    let find = |project: &str, environment: Option<&str>| {
+        exposures
+            .iter()
+            .find(|e| e["project"] == project && e["environment"].as_str() == environment)
+            .cloned()
+            .unwrap_or_else(|| panic!("No {} {:?} in {:?}", project, environment, exposures))
+    };
+
+    let api = find("api", None);
+    assert_eq!(api["worker"], "shop-api");
+    assert_eq!(api["routes"][0]["zone"], "example.com");
+    assert_eq!(api["routes"][0]["conflicts_with"][0], "edge");
+    assert_eq!(api["routes"][1]["custom_domain"], true);
+    assert_eq!(api["workers_dev"], false);
+
+    let edge = find("edge", Some("staging"));
+    assert_eq!(edge["routes"].as_array().unwrap().len(), 0);
+    assert_eq!(edge["workers_dev"], false);
+    assert_eq!(edge["workers_dev_explicit"], true);
+
+    let jobs = find("jobs", None);
+    assert_eq!(jobs["workers_dev"], true);
+    assert_eq!(jobs["workers_dev_explicit"], false);
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const PNPM_LOCK: &str = r#"lockfileVersion: '9.0'
+
+importers:
+  workers/api:
+    dependencies:
+      hono:
+        specifier: ^4.8.2
+        version: 4.8.2
+      '@scope/client':
+        specifier: ^1.0.0
+        version: 1.2.0(hono@4.8.2)
+    devDependencies:
+      wrangler:
+        specifier: ^4.0.0
+        version: 4.1.0
+
+snapshots:
+  hono@4.8.2: {}
+  '@scope/client@1.2.0(hono@4.8.2)':
+    dependencies:
+      tiny-invariant: 1.3.3
+  tiny-invariant@1.3.3: {}
+  wrangler@4.1.0: {}
+"#;
+
+#[test]
+fn test_sbom_resolves_pnpm_lockfile() -> anyhow::Result<()> {
+    log("→ SBOM Resolves pnpm Lockfile");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "package.json",
+            r#"{"name": "api", "version": "1.0.0", "dependencies": {"hono": "^4.8.2"}}"#,
+        )],
+    )?;
+    fs::write(workspace_path.join("pnpm-lock.yaml"), PNPM_LOCK)?;
+
+    let output = workspace.run("test-project", &["sbom", "api"])?;
+    assert!(
+        output.status.success(),
+        "SBOM should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("sha256:"));
+
+    let sbom: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join(".moonflare/sbom/api.cdx.json"),
+    )?)?;
+    assert_eq!(sbom["bomFormat"], "CycloneDX");
+    assert_eq!(sbom["metadata"]["component"]["name"], "api");
+
+    let components = sbom["components"].as_array().unwrap();
+    let find = |purl: &str| components.iter().find(|c| c["purl"] == purl);
+    assert_eq!(find("pkg:npm/hono@4.8.2").unwrap()["scope"], "required");
+    assert!(find("pkg:npm/%40scope/client@1.2.0").is_some());
+    assert!(
+        find("pkg:npm/tiny-invariant@1.3.3").is_some(),
+        "Transitive dependencies should be included"
+    );
+    assert_eq!(find("pkg:npm/wrangler@4.1.0").unwrap()["scope"], "excluded");
+
+    Ok(())
+}
+
+#[test]
+fn test_sbom_reads_cargo_lock() -> anyhow::Result<()> {
+    log("→ SBOM Reads Cargo.lock");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::Crate,
+        "math",
+        &[
+            (
+                "Cargo.toml",
+                "[package]\nname = \"math\"\nversion = \"0.2.0\"\n",
+            ),
+            (
+                "Cargo.lock",
+                r#"version = 4
+
+[[package]]
+name = "math"
+version = "0.2.0"
+dependencies = ["wasm-bindgen"]
+
+[[package]]
+name = "wasm-bindgen"
+version = "0.2.100"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "abc123"
+dependencies = ["once_cell"]
+
+[[package]]
+name = "once_cell"
+version = "1.21.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "def456"
+
+[[package]]
+name = "unrelated"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+            ),
+        ],
+    )?;
+
+    let output = workspace.run(
+        "test-project",
+        &["sbom", "math", "--output", "math-sbom.json"],
+    )?;
+    assert!(
+        output.status.success(),
+        "SBOM should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let sbom: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(workspace_path.join("math-sbom.json"))?)?;
+    assert_eq!(sbom["metadata"]["component"]["version"], "0.2.0");
+    let purls: Vec<&str> = sbom["components"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|c| c["purl"].as_str())
+        .collect();
+    assert_eq!(
+        purls,
+        vec![
+            "pkg:cargo/once_cell@1.21.3",
+            "pkg:cargo/wasm-bindgen@0.2.100"
+        ]
+    );
+
+    Ok(())
+}
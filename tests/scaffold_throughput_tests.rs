@@ -0,0 +1,84 @@
+use common::*;
+use std::time::{Duration, Instant};
+
+mod common;
+
+/// Each scaffold runs this many times, in a fresh workspace each time, and the fastest counts
+const RUNS: usize = 3;
+
+/// The most any single scaffold may take, rendering and writing included;
+/// `MOONFLARE_SCAFFOLD_BUDGET_MS` overrides it on slow machines
+fn budget() -> Duration {
+    Duration::from_millis(
+        std::env::var("MOONFLARE_SCAFFOLD_BUDGET_MS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(1000),
+    )
+}
+
+#[test]
+fn test_large_presets_scaffold_within_budget() -> anyhow::Result<()> {
+    log("→ Large Presets Scaffold Within Budget");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let limit = budget();
+
+    let mut scaffolds: Vec<Vec<&str>> = ["chat", "image-resizer", "rag"]
+        .iter()
+        .map(|example| vec!["examples", "add", example])
+        .collect();
+    scaffolds.push(vec!["add", "react", "storefront"]);
+    scaffolds.push(vec!["add", "astro", "docs"]);
+
+    let mut fastest = vec![Duration::MAX; scaffolds.len()];
+    let mut written = 0;
+    for run in 0..RUNS {
+        let name = format!("shop-{}", run);
+        let workspace_path = workspace.create_minimal_workspace(&name)?;
+        for (args, fastest) in scaffolds.iter().zip(fastest.iter_mut()) {
+            let before = count_files(&workspace_path);
+            let start = Instant::now();
+            let output = workspace.run(&name, args)?;
+            let elapsed = start.elapsed();
+            assert!(
+                output.status.success(),
+                "{}: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            *fastest = (*fastest).min(elapsed);
+            if run == 0 {
+                written += count_files(&workspace_path) - before;
+            }
+        }
+    }
+
+    for (args, fastest) in scaffolds.iter().zip(&fastest) {
+        log(&format!("{}: {:?}", args.join(" "), fastest));
+        assert!(
+            *fastest < limit,
+            "'moonflare {}' took {:?}, over the {:?} budget",
+            args.join(" "),
+            fastest,
+            limit
+        );
+    }
+
+    let elapsed: Duration = fastest.iter().sum();
+    log(&format!(
+        "{} files in {:?} ({:.0} files/s)",
+        written,
+        elapsed,
+        written as f64 / elapsed.as_secs_f64()
+    ));
+
+    Ok(())
+}
+
+fn count_files(dir: &std::path::Path) -> usize {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .count()
+}
@@ -0,0 +1,142 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const WORKER_SOURCE: &str = r#"export default {
+	async fetch(request, env) {
+		return fetch(env.API_BASE_URL, { headers: { Authorization: env.API_TOKEN, "X-Key": env.SIGNING_KEY } });
+	},
+};
+"#;
+
+const DOPPLER_CONFIG: &str = r#"[secrets]
+provider = "doppler"
+
+[secrets.doppler]
+project = "shop-{project}"
+config = "{env}"
+"#;
+
+/// Fake `doppler` returning API_TOKEN for any config, and `pnpm` logging wrangler calls
+fn fake_secret_tools(dir: &Path) -> anyhow::Result<String> {
+    let log = dir.join("tools.log");
+    fake_tools(
+        dir,
+        &[
+            (
+                "doppler",
+                &format!(
+                    "echo \"doppler $@\" >> {}\necho '{{\"API_TOKEN\":\"s3cret\",\"DOPPLER_CONFIG\":\"dev\"}}'\n",
+                    log.display()
+                ),
+            ),
+            (
+                "pnpm",
+                &format!(
+                    "echo \"pnpm $@\" >> {}\ncat >> {}\n",
+                    log.display(),
+                    log.display()
+                ),
+            ),
+        ],
+    )
+}
+
+fn setup(workspace: &MoonflareTestWorkspace) -> anyhow::Result<(std::path::PathBuf, String)> {
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::write(workspace_path.join("moonflare.toml"), DOPPLER_CONFIG)?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            (
+                "wrangler.jsonc",
+                r#"{ "name": "api", "vars": { "API_BASE_URL": "https://example.com" } }"#,
+            ),
+            ("src/index.ts", WORKER_SOURCE),
+        ],
+    )?;
+    let path = fake_secret_tools(&workspace_path)?;
+    Ok((workspace_path, path))
+}
+
+#[test]
+fn test_env_pull_reads_secrets_from_provider() -> anyhow::Result<()> {
+    log("→ Env Pull Reads Secrets From Provider");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (workspace_path, path) = setup(&workspace)?;
+
+    let output = workspace.run_with_env("test-project", &["env", "pull"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "env pull should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let dev_vars = fs::read_to_string(workspace_path.join("workers/api/.dev.vars"))?;
+    assert!(dev_vars.contains("API_TOKEN=\"s3cret\""), "{}", dev_vars);
+    assert!(dev_vars.contains("SIGNING_KEY=\"\""), "{}", dev_vars);
+    assert!(!dev_vars.contains("DOPPLER_CONFIG"), "{}", dev_vars);
+
+    let log = fs::read_to_string(workspace_path.join("tools.log"))?;
+    assert!(
+        log.contains("--project shop-api --config development"),
+        "{}",
+        log
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_secrets_push_pipes_secrets_to_wrangler() -> anyhow::Result<()> {
+    log("→ Secrets Push Pipes Secrets To Wrangler");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (workspace_path, path) = setup(&workspace)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["secrets", "push", "api", "--env", "staging"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "secrets push should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Not found in Doppler: SIGNING_KEY"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("s3cret"), "Values must not be printed");
+
+    let log = fs::read_to_string(workspace_path.join("tools.log"))?;
+    assert!(log.contains("--config staging"), "{}", log);
+    assert!(
+        log.contains("pnpm exec wrangler secret bulk --env staging\n{\"API_TOKEN\":\"s3cret\"}"),
+        "{}",
+        log
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_secrets_push_requires_provider() -> anyhow::Result<()> {
+    log("→ Secrets Push Requires Provider");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["secrets", "push", "api"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No secret provider configured"));
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const WRANGLER_JSONC: &str = r#"{
+  "name": "api",
+  "d1_databases": [{ "binding": "DB", "database_name": "shop-db", "database_id": "d1-1" }],
+  "kv_namespaces": [
+    { "binding": "CACHE", "id": "kv-1" },
+    { "binding": "FLAGS", "id": "kv-2" }
+  ]
+}
+"#;
+
+/// Fake `pnpm` and `moon` on PATH; pnpm appends its arguments to pnpm.log in the workspace
+#[cfg(unix)]
+fn fake_bin(dir: &Path, workspace_path: &Path) -> anyhow::Result<String> {
+    let pnpm = format!(
+        "echo \"$@\" >> {}\nexit 0\n",
+        workspace_path.join("pnpm.log").display()
+    );
+    fake_tools(dir, &[("pnpm", &pnpm), ("moon", "exit 0\n")])
+}
+
+#[cfg(unix)]
+#[test]
+fn test_seed_loads_sql_and_kv_seeds() -> anyhow::Result<()> {
+    log("→ Seed Loads SQL And KV Seeds");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.jsonc", WRANGLER_JSONC),
+            ("seeds/001-schema.sql", "CREATE TABLE users (id INTEGER);"),
+            ("seeds/002-users.sql", "INSERT INTO users VALUES (1);"),
+            ("seeds/CACHE/keys.json", r#"[{ "key": "a", "value": "1" }]"#),
+            ("seeds/README.md", "Seed data"),
+        ],
+    )?;
+    let path = fake_bin(workspace.path(), &workspace_path)?;
+    let log = workspace_path.join("pnpm.log");
+
+    let output = workspace.run_with_env("shop", &["seed"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Seed should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(&log)?,
+        "exec wrangler d1 execute shop-db --file seeds/001-schema.sql --local\n\
+         exec wrangler d1 execute shop-db --file seeds/002-users.sql --local\n\
+         exec wrangler kv bulk put seeds/CACHE/keys.json --binding CACHE --local\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Loaded 3 seed(s)"));
+
+    fs::remove_file(&log)?;
+    let output = workspace.run_with_env(
+        "shop",
+        &["seed", "api", "--env", "preview"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Seed should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(fs::read_to_string(&log)?.starts_with(
+        "exec wrangler d1 execute shop-db --file seeds/001-schema.sql --remote --env preview\n"
+    ));
+
+    // Protected environments are never seeded
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[deploy.environments.production]\nprotected = true\n",
+    )?;
+    let output = workspace.run_with_env("shop", &["seed", "--remote"], &[("PATH", &path)])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("protected"));
+
+    // A KV seed outside a binding directory is ambiguous with two namespaces
+    fs::write(workspace_path.join("workers/api/seeds/flags.json"), "[]")?;
+    let output = workspace.run_with_env("shop", &["seed", "api"], &[("PATH", &path)])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("seeds/<BINDING>/"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dev_fresh_clears_local_state_and_seeds() -> anyhow::Result<()> {
+    log("→ Dev Fresh Clears Local State And Seeds");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.jsonc", WRANGLER_JSONC),
+            ("seeds/001-schema.sql", "CREATE TABLE users (id INTEGER);"),
+            (".wrangler/state/v3/d1/db.sqlite", "old data"),
+        ],
+    )?;
+    let path = fake_bin(workspace.path(), &workspace_path)?;
+
+    let output = workspace.run_with_env("shop", &["dev", "api", "--fresh"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Dev should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!workspace_path.join("workers/api/.wrangler/state").exists());
+    assert_eq!(
+        fs::read_to_string(workspace_path.join("pnpm.log"))?,
+        "exec wrangler d1 execute shop-db --file seeds/001-schema.sql --local\n"
+    );
+
+    // Seed scripts call the local dev server, so `seed` refuses to run them without one
+    fs::write(
+        workspace_path.join("workers/api/wrangler.jsonc"),
+        r#"{ "name": "api", "dev": { "port": 1 } }"#,
+    )?;
+    fs::remove_file(workspace_path.join("workers/api/seeds/001-schema.sql"))?;
+    fs::write(workspace_path.join("workers/api/seeds/rooms.mjs"), "")?;
+    let output = workspace.run_with_env("shop", &["seed", "api"], &[("PATH", &path)])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("moonflare dev api"));
+
+    Ok(())
+}
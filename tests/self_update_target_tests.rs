@@ -0,0 +1,40 @@
+use common::*;
+
+mod common;
+
+#[test]
+fn test_self_update_rejects_unknown_target() -> anyhow::Result<()> {
+    log("→ Self Update Rejects Unknown Target");
+    let workspace = MoonflareTestWorkspace::new()?;
+
+    let output = std::process::Command::new(workspace.moonflare_binary())
+        .args(["self", "update", "--target", "solaris-sparc"])
+        .current_dir(workspace.path())
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("invalid target 'solaris-sparc'"),
+        "{}",
+        stderr
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_self_update_rejects_unknown_target_from_env() -> anyhow::Result<()> {
+    log("→ Self Update Rejects Unknown MOONFLARE_TARGET");
+    let workspace = MoonflareTestWorkspace::new()?;
+
+    let output = std::process::Command::new(workspace.moonflare_binary())
+        .args(["self", "update", "--check"])
+        .env("MOONFLARE_TARGET", "linux")
+        .current_dir(workspace.path())
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("MOONFLARE_TARGET"), "{}", stderr);
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_add_small_crate_generates_no_std_crate() -> anyhow::Result<()> {
+    log("→ Add Small Crate Generates No Std Crate");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["add", "crate", "codec", "--small"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let crate_path = workspace_path.join("crates/codec");
+    let cargo: toml::Table = fs::read_to_string(crate_path.join("Cargo.toml"))?.parse()?;
+    let release = &cargo["profile"]["release"];
+    assert_eq!(release["opt-level"].as_str(), Some("z"));
+    assert_eq!(release["panic"].as_str(), Some("abort"));
+    assert!(cargo["features"].get("std").is_some(), "std escape hatch");
+
+    let lib = fs::read_to_string(crate_path.join("src/lib.rs"))?;
+    assert!(lib.contains("no_std"), "{}", lib);
+    assert!(lib.contains("#[global_allocator]"), "{}", lib);
+
+    let moon = fs::read_to_string(crate_path.join("moon.yml"))?;
+    assert!(moon.contains("WASM_SIZE_LIMIT"), "{}", moon);
+    assert!(moon.contains("release/codec.wasm"), "{}", moon);
+
+    Ok(())
+}
+
+#[test]
+fn test_small_is_only_for_crates() -> anyhow::Result<()> {
+    log("→ Small Is Only For Crates");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["add", "react", "web", "--small"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only available for crates"));
+    assert!(!workspace_path.join("apps/web").exists());
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+mod common;
+
+const MOONFLARE_TOML: &str = r#"[projects.web]
+url = "https://web-{env}.example.workers.dev"
+
+[projects.api]
+url = "https://api.example.com"
+"#;
+
+#[test]
+fn test_add_smoke_tests_generates_checks_for_deployable_projects() -> anyhow::Result<()> {
+    log("→ Add Smoke Tests Generates Checks For Deployable Projects");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(workspace_path.join("moonflare.toml"), MOONFLARE_TOML)?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::Astro,
+        "web",
+        &[("wrangler.jsonc", r#"{ "name": "web" }"#)],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", r#"{ "name": "api" }"#)],
+    )?;
+    workspace.create_minimal_project("shop", &ProjectType::Crate, "math", &[])?;
+
+    let output = workspace.run("shop", &["add", "smoke-tests"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let smoke = workspace_path.join("tests/smoke");
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(smoke.join("smoke.config.json"))?)?;
+    let checks = config["checks"].as_array().unwrap();
+    assert_eq!(checks.len(), 2, "{:?}", checks);
+    assert_eq!(checks[0]["project"], "web");
+    assert_eq!(
+        checks[0]["url"],
+        "https://web-production.example.workers.dev"
+    );
+    assert_eq!(checks[1]["project"], "api");
+    assert_eq!(checks[1]["url"], "https://api.example.com");
+    assert!(fs::read_to_string(smoke.join("moon.yml"))?.contains("smoke:"));
+    assert!(smoke.join("smoke.mjs").exists());
+
+    let globs = fs::read_to_string(workspace_path.join(".moon/workspace.yml"))?;
+    assert!(globs.contains("tests/*"), "{}", globs);
+
+    let output = workspace.run("shop", &["add", "react"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("project name is required"));
+
+    let output = workspace.run("shop", &["deploy", "web", "--smoke", "--watch"])?;
+    assert!(!output.status.success());
+
+    Ok(())
+}
+
+#[test]
+fn test_deploy_smoke_requires_smoke_tests() -> anyhow::Result<()> {
+    log("→ Deploy Smoke Requires Smoke Tests");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(workspace_path.join("moonflare.toml"), MOONFLARE_TOML)?;
+    workspace.create_minimal_project("shop", &ProjectType::Astro, "web", &[])?;
+
+    let output = workspace.run("shop", &["deploy", "web", "--smoke"])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("moonflare add smoke-tests"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
+
+/// Serves `/health` with a healthy body and anything else with a 500
+fn mock_site() -> anyhow::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = format!("http://{}", listener.local_addr()?);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+            }
+
+            let (status, body) = if request_line.contains(" /health ") {
+                ("200 OK", "status: healthy")
+            } else {
+                ("500 Internal Server Error", "boom")
+            };
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+
+    Ok(address)
+}
+
+#[cfg(unix)]
+#[test]
+fn test_deploy_smoke_runs_checks_against_deployed_projects() -> anyhow::Result<()> {
+    log("→ Deploy Smoke Runs Checks Against Deployed Projects");
+    if std::process::Command::new("node")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        log("Skipping: node isn't installed");
+        return Ok(());
+    }
+
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project("shop", &ProjectType::Astro, "web", &[])?;
+    workspace.create_minimal_project("shop", &ProjectType::React, "admin", &[])?;
+    let address = mock_site()?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        format!("[projects.web]\nurl = \"{}\"\n", address),
+    )?;
+    let output = workspace.run("shop", &["add", "smoke-tests"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Fake `moon` that deploys nothing and runs the smoke task the way moon would
+    let path = fake_tools(
+        workspace.path(),
+        &[(
+            "moon",
+            "echo \"$2 $SMOKE_PROJECTS $SMOKE_ENV\" >> moon.log\nif [ \"$2\" = smoke:smoke ]; then cd tests/smoke && exec node smoke.mjs; fi\nexit 0\n",
+        )],
+    )?;
+    let checks = workspace_path.join("tests/smoke/smoke.config.json");
+
+    let write_checks = |path: &str| -> anyhow::Result<()> {
+        fs::write(
+            &checks,
+            format!(
+                r#"{{"timeoutMs": 5000, "checks": [
+  {{"project": "web", "path": "{}", "contains": "healthy"}},
+  {{"project": "admin", "path": "/"}}
+]}}"#,
+                path
+            ),
+        )?;
+        Ok(())
+    };
+
+    write_checks("/health")?;
+    let output =
+        workspace.run_with_env("shop", &["deploy", "web", "--smoke"], &[("PATH", &path)])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("✓ web /health"), "{}", stdout);
+    assert!(
+        stdout.contains("1 of 1 smoke check(s) passed"),
+        "{}",
+        stdout
+    );
+    let log = fs::read_to_string(workspace_path.join("moon.log"))?;
+    assert!(log.contains("smoke:smoke web production"), "{}", log);
+
+    // A failing check fails the deploy
+    write_checks("/broken")?;
+    let output =
+        workspace.run_with_env("shop", &["deploy", "web", "--smoke"], &[("PATH", &path)])?;
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✗ web /broken: status 500"), "{}", stdout);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Smoke tests failed"));
+
+    // Without a URL for the environment the check is skipped
+    let output = workspace.run_with_env(
+        "shop",
+        &["deploy", "web", "--env", "staging", "--smoke"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("web /broken: skipped"));
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use common::*;
+use std::time::{Duration, Instant};
+
+mod common;
+
+/// Fastest of a few runs, so a busy machine doesn't fail the budget on one slow start
+fn fastest_run(workspace: &MoonflareTestWorkspace, args: &[&str]) -> anyhow::Result<Duration> {
+    let mut fastest = Duration::MAX;
+    for _ in 0..5 {
+        let started = Instant::now();
+        let output = workspace.run("", args)?;
+        let elapsed = started.elapsed();
+        assert!(
+            output.status.success(),
+            "moonflare {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        fastest = fastest.min(elapsed);
+    }
+    Ok(fastest)
+}
+
+fn budget(var: &str, default: u64) -> u64 {
+    std::env::var(var)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+#[test]
+fn test_startup_stays_within_budget() -> anyhow::Result<()> {
+    log("→ Startup Stays Within Budget");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let limit = Duration::from_millis(budget("MOONFLARE_STARTUP_BUDGET_MS", 300));
+
+    for args in [&["--version"][..], &["--help"], &["deploy", "--help"]] {
+        let fastest = fastest_run(&workspace, args)?;
+        log(&format!("moonflare {}: {:?}", args.join(" "), fastest));
+        assert!(
+            fastest <= limit,
+            "moonflare {} took {:?}, over the {:?} startup budget",
+            args.join(" "),
+            fastest,
+            limit
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_release_binary_stays_within_size_budget() -> anyhow::Result<()> {
+    log("→ Release Binary Stays Within Size Budget");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let size = std::fs::metadata(workspace.moonflare_binary())?.len();
+    let limit = budget("MOONFLARE_SIZE_BUDGET_MB", 40) * 1024 * 1024;
+    log(&format!("moonflare binary: {} bytes", size));
+    assert!(
+        size <= limit,
+        "The release binary is {} bytes, over the {} byte budget",
+        size,
+        limit
+    );
+
+    Ok(())
+}
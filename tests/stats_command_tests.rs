@@ -0,0 +1,81 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_stats_summarizes_workspace() -> anyhow::Result<()> {
+    log("→ Stats Summarizes Workspace");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "web",
+        &[
+            ("src/main.tsx", "import x from 'y';\n\nconsole.log(x);\n"),
+            ("dist/assets/index.js", &"x".repeat(4096)),
+            ("node_modules/dep/index.js", "ignored();\n"),
+        ],
+    )?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::Crate,
+        "core",
+        &[("src/lib.rs", "pub fn a() {}\npub fn b() {}\n")],
+    )?;
+    fs::create_dir_all(workspace_path.join("shared-wasm"))?;
+    fs::write(
+        workspace_path.join("shared-wasm/core.wasm"),
+        vec![0u8; 1024],
+    )?;
+    fs::create_dir_all(workspace_path.join(".moonflare"))?;
+    fs::write(
+        workspace_path.join(".moonflare/builds.jsonl"),
+        concat!(
+            r#"{"timestamp":"2026-01-01T00:00:00+00:00","success":true,"duration_secs":10.0,"tasks":[{"target":"web:build","duration_secs":8.0,"cached":false},{"target":"core:build","duration_secs":2.0,"cached":true}]}"#,
+            "\n",
+            r#"{"timestamp":"2026-01-02T00:00:00+00:00","success":false,"duration_secs":20.0,"tasks":[{"target":"web:build","duration_secs":12.0,"cached":false},{"target":"core:build","duration_secs":0.5,"cached":true}]}"#,
+            "\n",
+        ),
+    )?;
+
+    let output = workspace.run("test-project", &["stats", "--json"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    assert_eq!(stats["projects"]["react"], 1);
+    assert_eq!(stats["projects"]["crate"], 1);
+    assert_eq!(stats["lines_of_code"]["TypeScript"]["lines"], 2);
+    assert_eq!(stats["lines_of_code"]["Rust"]["lines"], 2);
+    assert!(
+        stats["lines_of_code"].get("JavaScript").is_none(),
+        "node_modules and dist are skipped: {}",
+        stats
+    );
+
+    let builds = &stats["builds"];
+    assert_eq!(builds["runs"], 2);
+    assert_eq!(builds["succeeded"], 1);
+    assert_eq!(builds["average_secs"], 15.0);
+    assert_eq!(builds["cache_hit_rate"], 0.5);
+    assert_eq!(
+        builds["slowest_targets"][0],
+        serde_json::json!(["web:build", 10.0])
+    );
+
+    let bundles = stats["largest_bundles"].as_array().unwrap();
+    assert_eq!(bundles[0]["path"], "apps/web/dist");
+    assert_eq!(bundles[1]["path"], "shared-wasm/core.wasm");
+
+    // Only the most recent runs are averaged
+    let output = workspace.run("test-project", &["stats", "--json", "--runs", "1"])?;
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(stats["builds"]["average_secs"], 20.0);
+
+    Ok(())
+}
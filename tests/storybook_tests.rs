@@ -0,0 +1,82 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_add_react_with_storybook() -> anyhow::Result<()> {
+    log("→ Add React With Storybook");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run(
+        "test-project",
+        &["add", "react", "ui", "--with", "storybook"],
+    )?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let app = workspace_path.join("apps/ui");
+    assert!(app.join(".storybook/main.ts").exists());
+    assert!(app.join("src/stories/Button.stories.tsx").exists());
+
+    let wrangler = fs::read_to_string(app.join("wrangler.storybook.jsonc"))?;
+    assert!(wrangler.contains("\"name\": \"ui-storybook\""));
+    assert!(wrangler.contains("./storybook-static"));
+
+    let package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(app.join("package.json"))?)?;
+    assert!(package["scripts"]["build-storybook"].is_string());
+    assert!(package["devDependencies"]["storybook"].is_string());
+
+    let moon: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(app.join("moon.yml"))?)?;
+    for task in ["build", "storybook", "build:storybook", "deploy:storybook"] {
+        assert!(moon["tasks"].get(task).is_some(), "missing task {}", task);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_add_react_without_storybook() -> anyhow::Result<()> {
+    log("→ Add React Without Storybook");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run("test-project", &["add", "react", "web"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let app = workspace_path.join("apps/web");
+    assert!(!app.join(".storybook").exists());
+    let package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(app.join("package.json"))?)?;
+    assert!(package["scripts"].get("storybook").is_none());
+    let moon: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(app.join("moon.yml"))?)?;
+    assert!(moon["tasks"].get("storybook").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_storybook_requires_react() -> anyhow::Result<()> {
+    log("→ Storybook Requires React");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+
+    let output = workspace.run(
+        "test-project",
+        &["add", "astro", "site", "--with", "storybook"],
+    )?;
+    assert!(!output.status.success(), "Storybook on Astro should fail");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only available for React"));
+    assert!(!workspace_path.join("sites/site").exists());
+
+    Ok(())
+}
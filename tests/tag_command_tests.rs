@@ -0,0 +1,77 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_tag_add_and_remove_updates_moon_yml() -> anyhow::Result<()> {
+    log("→ Tag Add And Remove Updates moon.yml");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    let project_path = workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[],
+    )?;
+
+    let output = workspace.run("test-project", &["tag", "add", "api", "backend"])?;
+    assert!(
+        output.status.success(),
+        "tag add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let config: serde_yaml::Value =
+        serde_yaml::from_str(&fs::read_to_string(project_path.join("moon.yml"))?)?;
+    let tags = config
+        .get("tags")
+        .and_then(|t| t.as_sequence())
+        .expect("moon.yml should have tags");
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].as_str(), Some("backend"));
+
+    let output = workspace.run("test-project", &["tag", "remove", "api", "backend"])?;
+    assert!(output.status.success());
+
+    let config: serde_yaml::Value =
+        serde_yaml::from_str(&fs::read_to_string(project_path.join("moon.yml"))?)?;
+    assert!(config.get("tags").is_none(), "tags should be removed");
+
+    Ok(())
+}
+
+#[test]
+fn test_tag_rejects_invalid_names() -> anyhow::Result<()> {
+    log("→ Tag Rejects Invalid Names");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::React, "frontend", &[])?;
+
+    let output = workspace.run("test-project", &["tag", "add", "frontend", "Public Facing"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+    assert!(stderr.contains("invalid tag"));
+
+    Ok(())
+}
+
+#[test]
+fn test_deploy_tag_filter_only_considers_tagged_projects() -> anyhow::Result<()> {
+    log("→ Deploy Tag Filter Only Considers Tagged Projects");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"api\"\n")],
+    )?;
+
+    let output = workspace.run("test-project", &["deploy", "--tag", "public"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+    assert!(stderr.contains("no deployable projects match"));
+
+    Ok(())
+}
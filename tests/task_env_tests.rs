@@ -0,0 +1,126 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const ENV_CONFIG: &str = r#"[env]
+API_BASE_URL = "http://localhost:8787"
+FEATURE_SEARCH = false
+
+[env.production]
+API_BASE_URL = "https://api.example.com"
+
+[projects.web.env]
+SITE_NAME = "Web"
+"#;
+
+/// Fake `moon` that logs each run with the variables it received
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "[ \"$1\" = query ] && exit 1\necho \"$@ api=$API_BASE_URL search=$FEATURE_SEARCH site=$SITE_NAME\" >> {}\n",
+                dir.join("moon-runs.log").display()
+            ),
+        )],
+    )
+}
+
+fn setup(workspace: &MoonflareTestWorkspace) -> anyhow::Result<(std::path::PathBuf, String)> {
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::write(workspace_path.join("moonflare.toml"), ENV_CONFIG)?;
+    for name in ["web", "admin"] {
+        workspace.create_minimal_project(
+            "test-project",
+            &ProjectType::React,
+            name,
+            &[
+                ("moon.yml", "tasks:\n  build:\n    command: 'true'\n"),
+                ("wrangler.jsonc", "{}"),
+            ],
+        )?;
+    }
+    let path = fake_moon(&workspace_path)?;
+    Ok((workspace_path, path))
+}
+
+#[test]
+fn test_build_injects_workspace_and_project_env() -> anyhow::Result<()> {
+    log("→ Build Injects Workspace And Project Env");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (workspace_path, path) = setup(&workspace)?;
+
+    let output = workspace.run_with_env("test-project", &["build"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // web has its own variables, so each project runs separately
+    let runs = fs::read_to_string(workspace_path.join("moon-runs.log"))?;
+    assert!(
+        runs.contains("run admin:build api=http://localhost:8787 search=false site=\n"),
+        "{}",
+        runs
+    );
+    assert!(
+        runs.contains("run web:build api=http://localhost:8787 search=false site=Web\n"),
+        "{}",
+        runs
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_environment_tables_and_env_file() -> anyhow::Result<()> {
+    log("→ Environment Tables And Env File");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (workspace_path, path) = setup(&workspace)?;
+    fs::write(workspace_path.join("ci.env"), "SITE_NAME=\"From file\"\n")?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build", "web", "--env-file", "ci.env"],
+        &[("PATH", &path), ("MOONFLARE_ENV", "production")],
+    )?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let runs = fs::read_to_string(workspace_path.join("moon-runs.log"))?;
+    assert!(
+        runs.contains("api=https://api.example.com search=false site=From file"),
+        "{}",
+        runs
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_shell_env_wins_over_config() -> anyhow::Result<()> {
+    log("→ Shell Env Wins Over Config");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (workspace_path, path) = setup(&workspace)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build", "admin"],
+        &[("PATH", &path), ("API_BASE_URL", "http://shell")],
+    )?;
+    assert!(output.status.success());
+
+    let runs = fs::read_to_string(workspace_path.join("moon-runs.log"))?;
+    assert!(runs.contains("api=http://shell"), "{}", runs);
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const STORYBOOK_TASKS: &str =
+    "tasks:\n  build:\n    command: 'true'\n  build:storybook:\n    command: 'true'\n";
+
+/// Fake `moon` that reports `frontend` with build and build:storybook tasks and logs every run
+fn fake_moon(dir: &Path) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "if [ \"$1\" = query ] && [ \"$2\" = tasks ]; then\n  echo '{{\"tasks\":{{\"frontend\":{{\"build\":{{}},\"build:storybook\":{{}}}}}}}}'\n  exit 0\nfi\necho \"$@\" >> {}\n",
+                dir.join("moon-runs.log").display()
+            ),
+        )],
+    )
+}
+
+#[test]
+fn test_build_runs_overridden_task() -> anyhow::Result<()> {
+    log("→ Build Runs Overridden Task");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "frontend",
+        &[("moon.yml", STORYBOOK_TASKS)],
+    )?;
+    let path = fake_moon(&workspace_path)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build", "frontend", "--task", "build:storybook"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let runs = fs::read_to_string(workspace_path.join("moon-runs.log"))?;
+    assert!(runs.contains("run frontend:build:storybook"), "{}", runs);
+
+    Ok(())
+}
+
+#[test]
+fn test_build_rejects_unknown_task() -> anyhow::Result<()> {
+    log("→ Build Rejects Unknown Task");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "frontend",
+        &[("moon.yml", STORYBOOK_TASKS)],
+    )?;
+    let path = fake_moon(&workspace_path)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build", "frontend", "--task", "build:docs"],
+        &[("PATH", &path)],
+    )?;
+    assert!(!output.status.success(), "Unknown task should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no 'build:docs' task"), "{}", stderr);
+    assert!(stderr.contains("build:storybook"), "{}", stderr);
+    assert!(!workspace_path.join("moon-runs.log").exists());
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const LEGACY_ASTRO_MOON_YML: &str = r#"language: 'typescript'
+layer: 'application'
+stack: 'frontend'
+
+tasks:
+  build:
+    command: 'pnpm'
+    args: ['build']
+  lint:
+    command: 'pnpm'
+    args: ['biome', 'lint', '.']
+    inputs:
+      - "src/**/*"
+      - "*.astro"
+      - "biome.json"
+      - "package.json"
+  format:
+    command: 'pnpm'
+    args: ['biome', 'format', '--write', 'src']
+  dev:
+    command: 'pnpm'
+    args: ['dev']
+    local: true
+"#;
+
+#[test]
+fn test_tasks_sync_migrates_existing_projects() -> anyhow::Result<()> {
+    log("→ Tasks Sync Migrates Existing Projects");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    let project = workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::Astro,
+        "web",
+        &[("moon.yml", LEGACY_ASTRO_MOON_YML)],
+    )?;
+
+    let output = workspace.run("test-project", &["tasks", "sync", "--dry-run"])?;
+    assert!(
+        output.status.success(),
+        "Dry run should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!workspace_path.join(".moon/tasks/typescript.yml").exists());
+    assert_eq!(
+        fs::read_to_string(project.join("moon.yml"))?,
+        LEGACY_ASTRO_MOON_YML
+    );
+
+    let output = workspace.run("test-project", &["tasks", "sync", "--auto-approve"])?;
+    assert!(
+        output.status.success(),
+        "Sync should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("keeps customized format"), "{}", stdout);
+
+    assert!(workspace_path.join(".moon/tasks/typescript.yml").exists());
+    assert!(workspace_path.join(".moon/tasks/rust.yml").exists());
+
+    let moon: serde_yaml::Value =
+        serde_yaml::from_str(&fs::read_to_string(project.join("moon.yml"))?)?;
+    let tasks = &moon["tasks"];
+    assert!(tasks.get("build").is_some(), "build is project-specific");
+    assert!(tasks.get("format").is_some(), "customized tasks stay");
+    assert!(tasks.get("lint").is_none(), "lint is inherited");
+    assert!(tasks.get("dev").is_none(), "dev is inherited");
+    let sources: Vec<&str> = moon["fileGroups"]["sources"]
+        .as_sequence()
+        .unwrap()
+        .iter()
+        .filter_map(|s| s.as_str())
+        .collect();
+    assert_eq!(sources, vec!["src/**/*", "*.astro"]);
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use common::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Every file under `dir`, keyed by relative path
+fn snapshot(dir: &Path) -> anyhow::Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    for entry in walkdir::WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let relative = entry.path().strip_prefix(dir)?.display().to_string();
+            files.insert(relative, fs::read(entry.path())?);
+        }
+    }
+    Ok(files)
+}
+
+#[test]
+fn test_generated_projects_are_deterministic() -> anyhow::Result<()> {
+    log("→ Generated Projects Are Deterministic");
+    let projects = [
+        ("astro", "sites/docs", "docs"),
+        ("react", "apps/web", "web"),
+        ("durable-object", "workers/api", "api"),
+        ("crate", "crates/math", "math"),
+    ];
+
+    let mut snapshots = Vec::new();
+    for _ in 0..2 {
+        let workspace = MoonflareTestWorkspace::new()?;
+        let workspace_path = workspace.create_minimal_workspace("test-project")?;
+        let mut files = BTreeMap::new();
+        for (project_type, dir, name) in projects {
+            let output = workspace.run("test-project", &["add", project_type, name])?;
+            assert!(
+                output.status.success(),
+                "Add {} should succeed: {}",
+                project_type,
+                String::from_utf8_lossy(&output.stderr)
+            );
+            files.insert(dir, snapshot(&workspace_path.join(dir))?);
+        }
+        snapshots.push(files);
+    }
+
+    for (dir, first) in &snapshots[0] {
+        let second = &snapshots[1][dir];
+        assert_eq!(
+            first.keys().collect::<Vec<_>>(),
+            second.keys().collect::<Vec<_>>(),
+            "{} generated different files",
+            dir
+        );
+        for (path, content) in first {
+            assert!(
+                content == &second[path],
+                "{}/{} differs between runs",
+                dir,
+                path
+            );
+        }
+    }
+
+    Ok(())
+}
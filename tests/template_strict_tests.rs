@@ -0,0 +1,48 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_every_project_type_renders_in_strict_mode() -> anyhow::Result<()> {
+    log("→ Every Project Type Renders In Strict Mode");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    for (project_type, name) in [
+        ("astro", "site"),
+        ("react", "app"),
+        ("durable-object", "api"),
+        ("crate", "math"),
+    ] {
+        let output = workspace.run("shop", &["add", project_type, name])?;
+        assert!(
+            output.status.success(),
+            "Adding a {} should render every template variable: {}",
+            project_type,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let config = fs::read_to_string(workspace_path.join("workers/api/wrangler.toml"))?;
+    assert!(!config.contains("name = \"\""), "{}", config);
+
+    Ok(())
+}
+
+#[test]
+fn test_lenient_flag_is_accepted() -> anyhow::Result<()> {
+    log("→ Lenient Flag Is Accepted");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run("shop", &["add", "durable-object", "api", "--lenient"])?;
+    assert!(
+        output.status.success(),
+        "Add with --lenient should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(workspace_path.join("workers/api/wrangler.toml").exists());
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+mod common;
+
+/// A fake `moon` that hangs (running `hang` first, e.g. to ignore SIGTERM) for targets
+/// containing `slow` and appends every other invocation to calls.log
+#[cfg(unix)]
+fn fake_moon(dir: &Path, hang: &str) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "case \"$*\" in *slow*) {} exec sleep 30;; esac\necho \"moon $*\" >> {}\nexit 0\n",
+                hang,
+                dir.join("calls.log").display()
+            ),
+        )],
+    )
+}
+
+fn create_projects(workspace_path: &Path, names: &[&str]) -> anyhow::Result<()> {
+    for name in names {
+        let path = workspace_path.join("apps").join(name);
+        fs::create_dir_all(&path)?;
+        fs::write(
+            path.join("moon.yml"),
+            "tasks:\n  build:\n    command: 'pnpm build'\n",
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_timeout_flag_stops_hung_build() -> anyhow::Result<()> {
+    log("→ Timeout Flag Stops Hung Build");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    create_projects(&workspace_path, &["slow"])?;
+    let path = fake_moon(workspace.path(), "")?;
+
+    let started = Instant::now();
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build", "slow", "--timeout", "1s"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(!output.status.success());
+    assert!(started.elapsed() < Duration::from_secs(20));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("timed out after 1s"), "{}", stderr);
+
+    let output = workspace.run("test-project", &["build", "--timeout", "soon"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid duration 'soon'"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_configured_timeout_kills_after_grace() -> anyhow::Result<()> {
+    log("→ Configured Timeout Kills After Grace");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    create_projects(&workspace_path, &["slow"])?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[timeouts]\ngrace = \"1s\"\nbuild = 1\n",
+    )?;
+    // The hung process ignores SIGTERM, so only SIGKILL stops it
+    let path = fake_moon(workspace.path(), "trap '' TERM;")?;
+
+    let started = Instant::now();
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build", "slow"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(!output.status.success());
+    assert!(started.elapsed() < Duration::from_secs(20));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("timed out after 1s"), "{}", stderr);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_timeout_reports_partial_results() -> anyhow::Result<()> {
+    log("→ Timeout Reports Partial Results");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    create_projects(&workspace_path, &["alpha", "slow", "zulu"])?;
+    let path = fake_moon(workspace.path(), "")?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build", "--reporter", "json", "--timeout", "2"],
+        &[("PATH", path.as_str())],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 of 3 target(s) finished"), "{}", stderr);
+    assert!(stderr.contains("stopped slow:build"), "{}", stderr);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let report: serde_json::Value = serde_json::from_str(&stdout[stdout.find('{').unwrap()..])?;
+    let tasks = report["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 3);
+    assert_eq!(tasks[0]["target"], "alpha:build");
+    assert_eq!(tasks[0]["success"], true);
+    assert_eq!(tasks[2]["message"], "Not run: the time box ran out");
+
+    let log = fs::read_to_string(workspace.path().join("calls.log"))?;
+    assert!(log.contains("alpha:build"), "{}", log);
+    assert!(!log.contains("zulu:build"), "{}", log);
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+const WHOAMI: &str = "Getting User settings...
+👋 You are logged in with an OAuth Token, associated with the email dev@example.com.
+┌──────────────┬──────────────────────────────────┐
+│ Account Name │ Account ID                       │
+├──────────────┼──────────────────────────────────┤
+│ Acme         │ 0123456789abcdef0123456789abcdef │
+└──────────────┴──────────────────────────────────┘
+🔓 Token Permissions: If scopes are missing, you may need to logout and re-login.
+Scope (Access)
+- account:read
+- workers:write
+";
+
+/// Put a fake `npx` first on PATH that logs each call, answers `wrangler whoami` and writes
+/// Wrangler's OAuth config for `wrangler login`
+fn fake_npx(dir: &Path) -> anyhow::Result<String> {
+    fs::write(dir.join("whoami.txt"), WHOAMI)?;
+    fake_tools(
+        dir,
+        &[(
+            "npx",
+            &format!(
+                r#"echo "$*" >> '{log}'
+case "$2" in
+  whoami) cat '{whoami}' ;;
+  login) mkdir -p '{config}' && echo "oauth_token = \"$(date +%s%N)\"" > '{config}/default.toml' ;;
+esac
+"#,
+                log = dir.join("calls.log").display(),
+                whoami = dir.join("whoami.txt").display(),
+                config = dir.join("config/.wrangler/config").display(),
+            ),
+        )],
+    )
+}
+
+fn whoami_calls(dir: &Path) -> usize {
+    fs::read_to_string(dir.join("calls.log"))
+        .unwrap_or_default()
+        .matches("wrangler whoami")
+        .count()
+}
+
+#[test]
+fn test_whoami_is_cached_until_login() -> anyhow::Result<()> {
+    log("→ Whoami Is Cached Until Login");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    let path = fake_npx(workspace.path())?;
+    let home = workspace.path().display().to_string();
+    let config = workspace.path().join("config").display().to_string();
+    let env = [
+        ("PATH", path.as_str()),
+        ("HOME", home.as_str()),
+        ("XDG_CONFIG_HOME", config.as_str()),
+        ("CLOUDFLARE_API_TOKEN", ""),
+    ];
+
+    let output = workspace.run_with_env("shop", &["token", "whoami"], &env)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("dev@example.com"), "{}", stdout);
+    assert!(
+        stdout.contains("0123456789abcdef0123456789abcdef"),
+        "{}",
+        stdout
+    );
+    assert!(
+        workspace
+            .path()
+            .join("config/moonflare/auth.json")
+            .is_file()
+    );
+
+    // The second command reuses the first one's answer
+    let output = workspace.run_with_env("shop", &["token", "whoami", "--json"], &env)?;
+    assert!(output.status.success());
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(info["accounts"][0]["name"], "Acme");
+    assert_eq!(info["scopes"][1], "workers:write");
+    assert_eq!(whoami_calls(workspace.path()), 1);
+
+    // Logging in drops the cache and asks again once
+    let output = workspace.run_with_env("shop", &["token", "login"], &env)?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(whoami_calls(workspace.path()), 2);
+    let output = workspace.run_with_env("shop", &["token", "whoami"], &env)?;
+    assert!(output.status.success());
+    assert_eq!(whoami_calls(workspace.path()), 2);
+
+    // Other credentials never see the cached accounts, and --refresh always asks
+    let mut token_env = env;
+    token_env[3] = ("CLOUDFLARE_API_TOKEN", "other-token");
+    let output = workspace.run_with_env("shop", &["token", "whoami"], &token_env)?;
+    assert!(output.status.success());
+    assert_eq!(whoami_calls(workspace.path()), 3);
+    let output = workspace.run_with_env("shop", &["token", "whoami", "--refresh"], &token_env)?;
+    assert!(output.status.success());
+    assert_eq!(whoami_calls(workspace.path()), 4);
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const API_WRANGLER: &str = r#"{
+  "name": "api",
+  "main": "src/index.ts",
+  "kv_namespaces": [{ "binding": "CACHE", "id": "abc" }],
+  "routes": [{ "pattern": "api.example.com", "custom_domain": true, "zone_name": "example.com" }],
+  "env": {
+    "staging": {
+      "d1_databases": [{ "binding": "DB", "database_name": "shop-staging", "database_id": "def" }]
+    }
+  }
+}
+"#;
+
+const WEB_WRANGLER: &str = r#"name = "web"
+account_id = "0123456789abcdef"
+
+[assets]
+directory = "./dist"
+"#;
+
+#[test]
+fn test_token_scopes_follow_bindings_and_routes() -> anyhow::Result<()> {
+    log("→ Token Scopes Follow Bindings And Routes");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", API_WRANGLER)],
+    )?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["token", "scopes", "--json"],
+        &[("CLOUDFLARE_ACCOUNT_ID", "")],
+    )?;
+    assert!(
+        output.status.success(),
+        "token scopes should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let scopes: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let permissions: Vec<&str> = scopes["permissions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["permission"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        permissions,
+        [
+            "Workers Scripts Edit",
+            "Account Settings Read",
+            "Workers KV Storage Edit",
+            "D1 Edit",
+            "Workers Routes Edit",
+            "DNS Edit",
+        ]
+    );
+    assert_eq!(scopes["permissions"][4]["resource"], "zone");
+    assert_eq!(scopes["permissions"][3]["reasons"][0], "api: d1_databases");
+    assert_eq!(scopes["zones"], serde_json::json!(["example.com"]));
+    let url = scopes["template_url"].as_str().unwrap();
+    assert!(
+        url.starts_with("https://dash.cloudflare.com/profile/api-tokens?permissionGroupKeys="),
+        "{}",
+        url
+    );
+    assert!(url.contains("%22workers_kv_storage%22"), "{}", url);
+
+    Ok(())
+}
+
+#[test]
+fn test_token_scopes_print_only_what_the_workspace_uses() -> anyhow::Result<()> {
+    log("→ Token Scopes Print Only What The Workspace Uses");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[("wrangler.toml", WEB_WRANGLER)],
+    )?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[projects.web.deploy]\nstrategy = \"r2-static\"\nbucket = \"web-{env}\"\n",
+    )?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["token", "scopes"],
+        &[("CLOUDFLARE_ACCOUNT_ID", "")],
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Workers Scripts Edit"), "{}", stdout);
+    assert!(stdout.contains("Workers R2 Storage Edit"), "{}", stdout);
+    assert!(
+        stdout.contains("web: r2-static deploy strategy"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("Account Settings Read"), "{}", stdout);
+    assert!(!stdout.contains("Zone permissions"), "{}", stdout);
+    assert!(
+        stdout.contains("https://dash.cloudflare.com/profile/api-tokens?"),
+        "{}",
+        stdout
+    );
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+mod common;
+
+/// Fake `moon` that records the TRACEPARENT it was given and exits with `status`
+fn fake_moon(dir: &Path, status: i32) -> anyhow::Result<String> {
+    fake_tools(
+        dir,
+        &[(
+            "moon",
+            &format!(
+                "echo \"$TRACEPARENT\" > {}\nexit {}\n",
+                dir.join("traceparent.txt").display(),
+                status
+            ),
+        )],
+    )
+}
+
+/// OTLP receiver that accepts any POST and hands `(request line, JSON body)` to the receiver
+fn mock_receiver() -> anyhow::Result<(String, Receiver<(String, serde_json::Value)>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = header.to_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok"
+            )
+            .unwrap();
+            sender
+                .send((
+                    request_line.trim().to_string(),
+                    serde_json::from_slice(&body).unwrap_or_default(),
+                ))
+                .ok();
+        }
+    });
+
+    Ok((url, receiver))
+}
+
+fn spans(body: &serde_json::Value) -> Vec<serde_json::Value> {
+    body["resourceSpans"][0]["scopeSpans"][0]["spans"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_otel_endpoint_exports_command_and_subprocess_spans() -> anyhow::Result<()> {
+    log("→ OTel Endpoint Exports Command And Subprocess Spans");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (url, received) = mock_receiver()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    let path = fake_moon(&workspace_path, 0)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build", "--otel-endpoint", &url],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "Build should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let (request_line, body) = received.recv_timeout(Duration::from_secs(5))?;
+    assert!(
+        request_line.starts_with("POST /v1/traces"),
+        "{}",
+        request_line
+    );
+    let spans = spans(&body);
+    let root = spans
+        .iter()
+        .find(|span| span["name"] == "moonflare build")
+        .cloned()
+        .unwrap_or_default();
+    assert_eq!(root["status"]["code"], 1, "{}", body);
+    let exec = spans
+        .iter()
+        .find(|span| span["name"] == "exec moon")
+        .cloned()
+        .unwrap_or_default();
+    assert_eq!(exec["parentSpanId"], root["spanId"], "{}", body);
+    assert_eq!(exec["traceId"], root["traceId"]);
+
+    // The subprocess was handed the command's trace context
+    let traceparent = fs::read_to_string(workspace_path.join("traceparent.txt"))?;
+    let trace_id = root["traceId"].as_str().unwrap_or_default();
+    assert!(
+        traceparent.starts_with(&format!("00-{}-", trace_id)),
+        "{}",
+        traceparent
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_tracing_joins_incoming_traceparent_and_marks_failure() -> anyhow::Result<()> {
+    log("→ Tracing Joins Incoming Traceparent And Marks Failure");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let (url, received) = mock_receiver()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        format!("[tracing]\nendpoint = \"{}\"\n", url),
+    )?;
+    let path = fake_moon(&workspace_path, 1)?;
+
+    let output = workspace.run_with_env(
+        "test-project",
+        &["build"],
+        &[
+            ("PATH", &path),
+            (
+                "TRACEPARENT",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            ),
+        ],
+    )?;
+    assert!(!output.status.success());
+
+    let (_, body) = received.recv_timeout(Duration::from_secs(5))?;
+    let spans = spans(&body);
+    let root = spans
+        .iter()
+        .find(|span| span["name"] == "moonflare build")
+        .cloned()
+        .unwrap_or_default();
+    assert_eq!(root["traceId"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert_eq!(root["parentSpanId"], "00f067aa0ba902b7");
+    assert_eq!(root["status"]["code"], 2, "{}", body);
+
+    Ok(())
+}
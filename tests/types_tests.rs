@@ -0,0 +1,105 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const AUTH_SOURCE: &str = r#"import { DurableObject, WorkerEntrypoint } from "cloudflare:workers";
+
+export class AuthService extends WorkerEntrypoint<Env> {
+  async verify(token: string): Promise<boolean> {
+    return token.length > 0;
+  }
+}
+
+export class Sessions extends DurableObject<Env> {}
+
+export default {
+  async fetch(): Promise<Response> {
+    return new Response("auth");
+  },
+};
+"#;
+
+const WEB_WRANGLER: &str = r#"name = "web"
+main = "src/index.ts"
+
+[[services]]
+binding = "AUTH"
+service = "auth"
+entrypoint = "AuthService"
+
+[[durable_objects.bindings]]
+name = "SESSIONS"
+class_name = "Sessions"
+script_name = "auth"
+
+[[durable_objects.bindings]]
+name = "CARTS"
+class_name = "Carts"
+script_name = "auth"
+"#;
+
+#[test]
+fn test_types_shares_rpc_types_with_consumers() -> anyhow::Result<()> {
+    log("→ Types Shares RPC Types With Consumers");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    let auth = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "auth",
+        &[
+            (
+                "wrangler.toml",
+                "name = \"auth\"\nmain = \"src/index.ts\"\n",
+            ),
+            ("package.json", "{\n  \"name\": \"auth\"\n}\n"),
+            ("src/index.ts", AUTH_SOURCE),
+        ],
+    )?;
+    let web = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "web",
+        &[
+            ("wrangler.toml", WEB_WRANGLER),
+            ("package.json", "{\n  \"name\": \"web\"\n}\n"),
+            ("src/index.ts", "export default {};\n"),
+        ],
+    )?;
+
+    let output = workspace.run("shop", &["types"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("web's CARTS binds Carts, which auth doesn't export"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("pnpm install"), "{}", stdout);
+
+    let bindings = fs::read_to_string(auth.join("bindings.d.ts"))?;
+    assert!(
+        bindings.contains("export type { AuthService, Sessions } from \"./src/index\";"),
+        "{}",
+        bindings
+    );
+    let package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(auth.join("package.json"))?)?;
+    assert_eq!(package["exports"]["./bindings"]["types"], "./bindings.d.ts");
+    let package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(web.join("package.json"))?)?;
+    assert_eq!(package["devDependencies"]["auth"], "workspace:*");
+    assert!(!web.join("bindings.d.ts").exists(), "Nothing binds to web");
+
+    // A second run has nothing left to change
+    let output = workspace.run("shop", &["types"])?;
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("pnpm install"));
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const PACKAGE_JSON: &str = r#"{
+  "name": "api",
+  "devDependencies": {
+    "typescript": "^5.0.0",
+    "wrangler": "^3.60.0"
+  }
+}
+"#;
+
+const MOON_YML: &str = "language: 'typescript'\ntype: 'application'\ntasks: {}\n";
+
+#[test]
+fn test_upgrade_replays_recorded_decisions() -> anyhow::Result<()> {
+    log("→ Upgrade Replays Recorded Decisions");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let api = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("package.json", PACKAGE_JSON), ("moon.yml", MOON_YML)],
+    )?;
+
+    // Without a terminal, only recorded decisions can be applied
+    let output = workspace.run("shop", &["upgrade"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--apply-recorded"));
+
+    let output = workspace.run("shop", &["upgrade", "--apply-recorded"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no decision for"), "{}", stderr);
+    assert!(stderr.contains("wrangler-major:api"), "{}", stderr);
+    assert!(stderr.contains("moon-layer:api"), "{}", stderr);
+    assert_eq!(fs::read_to_string(api.join("package.json"))?, PACKAGE_JSON);
+
+    fs::create_dir_all(workspace_path.join(".moonflare"))?;
+    fs::write(
+        workspace_path.join(".moonflare/upgrades.json"),
+        r#"{"decisions": {"wrangler-major:api": "apply", "moon-layer:api": "skip"}}"#,
+    )?;
+    let output = workspace.run("shop", &["upgrade", "--apply-recorded"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Applied 1 change(s), skipped 1"),
+        "{}",
+        stdout
+    );
+    assert_eq!(
+        fs::read_to_string(api.join("package.json"))?,
+        PACKAGE_JSON.replace("^3.60.0", "^4.32.0")
+    );
+    assert_eq!(fs::read_to_string(api.join("moon.yml"))?, MOON_YML);
+
+    // The applied change is gone, and the skipped one stays skipped
+    let output = workspace.run("shop", &["upgrade", "--apply-recorded"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Applied 0 change(s), skipped 1"),
+        "{}",
+        stdout
+    );
+
+    // --plan shows each change with its diff
+    let output = workspace.run("shop", &["--plan", "upgrade"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("api: Moon project type renamed to layer"));
+    assert!(stdout.contains("+ layer: 'application'"), "{}", stdout);
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// Put fake `proto`, `pnpm` and `moon` first on PATH. Each logs its arguments with the proto
+/// versions it was given; pnpm also logs the wrangler override, and moon fails on Node.js 18
+/// or when installed dependencies were copied along.
+fn fake_matrix_tools(dir: &Path) -> anyhow::Result<String> {
+    let log = dir.join("ran.log");
+    let proto = format!("echo \"proto $*\" >> '{}'\n", log.display());
+    let pnpm = format!(
+        "echo \"pnpm $* wrangler=$(grep -o '\"wrangler\": \"[^\"]*\"' package.json | cut -d'\"' -f4)\" >> '{}'\n",
+        log.display()
+    );
+    let moon = format!(
+        "echo \"moon $* node=$PROTO_NODE_VERSION moon=$PROTO_MOON_VERSION\" >> '{}'\n\
+         [ -e node_modules/marker ] && exit 3\n\
+         [ \"$PROTO_NODE_VERSION\" = 18 ] && {{ echo 'SyntaxError: Unexpected token' >&2; exit 1; }}\n\
+         exit 0\n",
+        log.display()
+    );
+    fake_tools(dir, &[("proto", &proto), ("pnpm", &pnpm), ("moon", &moon)])
+}
+
+#[test]
+fn test_verify_matrix_reports_each_combination() -> anyhow::Result<()> {
+    log("→ Verify Matrix Reports Each Combination");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[matrix]\nnode = [\"18\", \"22\"]\nmoon = [\"1.30.0\"]\n",
+    )?;
+    fs::create_dir_all(workspace_path.join("node_modules"))?;
+    fs::write(workspace_path.join("node_modules/marker"), "")?;
+    let path = fake_matrix_tools(workspace.path())?;
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["verify-matrix", "--wrangler", "4.10.0"],
+        &[("PATH", &path)],
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("1 of 2 combinations failed"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let failed = stdout
+        .lines()
+        .find(|line| line.trim_start().starts_with("18 "))
+        .unwrap_or_default();
+    assert!(failed.contains("failed"), "{}", stdout);
+    assert!(failed.contains("(build failed)"), "{}", stdout);
+    let passed = stdout
+        .lines()
+        .find(|line| line.trim_start().starts_with("22 "))
+        .unwrap_or_default();
+    assert!(passed.contains("passed"), "{}", stdout);
+
+    let ran = fs::read_to_string(workspace.path().join("ran.log"))?;
+    assert!(ran.contains("proto install node 18"), "{}", ran);
+    assert!(ran.contains("proto install moon 1.30.0"), "{}", ran);
+    assert!(
+        ran.contains("pnpm install --no-frozen-lockfile wrangler=4.10.0"),
+        "{}",
+        ran
+    );
+    assert!(
+        ran.contains("moon run :build node=22 moon=1.30.0"),
+        "{}",
+        ran
+    );
+
+    // The failure's output is kept, and the workspace itself is left as it was
+    let log = fs::read_to_string(
+        workspace_path.join(".moonflare/matrix/node-18_wrangler-4.10.0_moon-1.30.0.log"),
+    )?;
+    assert!(log.contains("SyntaxError: Unexpected token"), "{}", log);
+    assert!(!fs::read_to_string(workspace_path.join("package.json"))?.contains("overrides"));
+
+    let output = workspace.run_with_env(
+        "shop",
+        &["verify-matrix", "--node", "22", "--task", "test", "--json"],
+        &[("PATH", &path)],
+    )?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(results.as_array().map(Vec::len), Some(1));
+    assert_eq!(results[0]["node"], "22");
+    assert_eq!(results[0]["passed"], true);
+    assert!(
+        fs::read_to_string(workspace.path().join("ran.log"))?.contains("moon run :test node=22")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+#![cfg(unix)]
+
+use common::*;
+use std::fs;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+mod common;
+
+const WATCH_CONFIG: &str = r#"[watch]
+debounce_ms = 200
+
+[watch.rules]
+"workers/api/src/**" = ["api:test"]
+"workers/*/wrangler.toml" = ["api:lint", "api:test"]
+"#;
+
+#[test]
+fn test_watch_requires_rules() -> anyhow::Result<()> {
+    log("→ Watch Requires Rules");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run("shop", &["watch"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[watch.rules]"));
+
+    Ok(())
+}
+
+#[test]
+fn test_watch_rejects_rules_without_targets() -> anyhow::Result<()> {
+    log("→ Watch Rejects Rules Without Targets");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[watch.rules]\n\"src/**\" = [\"test\"]\n",
+    )?;
+
+    let output = workspace.run("shop", &["watch"])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("runs 'test'"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_watch_batches_changes_into_one_run() -> anyhow::Result<()> {
+    log("→ Watch Batches Changes Into One Run");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    let api = workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[
+            ("wrangler.toml", "name = \"api\"\n"),
+            ("src/index.ts", "export default {};\n"),
+        ],
+    )?;
+    fs::write(workspace_path.join("moonflare.toml"), WATCH_CONFIG)?;
+
+    // Fake moon that logs the targets of each run
+    let log_path = workspace_path.join("moon.log");
+    let path = fake_tools(
+        &workspace_path,
+        &[("moon", &format!("echo \"$@\" >> {}\n", log_path.display()))],
+    )?;
+
+    let stdout_path = workspace_path.join("watch.out");
+    let mut child = Command::new(workspace.moonflare_binary())
+        .arg("watch")
+        .env("PATH", &path)
+        .env("NO_COLOR", "1")
+        .current_dir(&workspace_path)
+        .stdout(fs::File::create(&stdout_path)?)
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    let wait_for_lines = |count: usize| -> Vec<String> {
+        let deadline = Instant::now() + Duration::from_secs(8);
+        loop {
+            let lines: Vec<String> = fs::read_to_string(&log_path)
+                .unwrap_or_default()
+                .lines()
+                .map(String::from)
+                .collect();
+            if lines.len() >= count || Instant::now() > deadline {
+                return lines;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    };
+
+    // Let the watcher take its baseline before editing
+    std::thread::sleep(Duration::from_millis(700));
+    fs::write(api.join("src/index.ts"), "export default { v: 2 };\n")?;
+    fs::write(
+        api.join("wrangler.toml"),
+        "name = \"api\"\nmain = \"src/index.ts\"\n",
+    )?;
+    let first = wait_for_lines(1);
+
+    // Files no rule matches don't start a run
+    std::thread::sleep(Duration::from_millis(300));
+    fs::write(api.join("README.md"), "# api\n")?;
+    std::thread::sleep(Duration::from_millis(1500));
+    let after_unmatched = fs::read_to_string(&log_path)?;
+    child.kill()?;
+    child.wait()?;
+
+    assert_eq!(
+        first,
+        vec!["run api:lint api:test"],
+        "Both saves run as one batch, each target once"
+    );
+    assert_eq!(after_unmatched.lines().count(), 1, "{}", after_unmatched);
+    let stdout = fs::read_to_string(&stdout_path)?;
+    assert!(stdout.contains("✓ api:lint"), "{}", stdout);
+    assert!(stdout.contains("✓ api:test"), "{}", stdout);
+
+    Ok(())
+}
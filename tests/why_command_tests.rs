@@ -0,0 +1,114 @@
+use common::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+fn write_manifest(
+    workspace_path: &Path,
+    hash: &str,
+    manifest: serde_json::Value,
+) -> anyhow::Result<()> {
+    let hashes = workspace_path.join(".moon/cache/hashes");
+    fs::create_dir_all(&hashes)?;
+    fs::write(hashes.join(format!("{}.json", hash)), manifest.to_string())?;
+    // Runs are ordered by when Moon wrote their manifests
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    Ok(())
+}
+
+#[test]
+fn test_why_explains_changed_inputs_and_downstream_targets() -> anyhow::Result<()> {
+    log("→ Why Explains Changed Inputs And Downstream Targets");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::Crate, "core", &[])?;
+    workspace.create_minimal_project(
+        "test-project",
+        &ProjectType::React,
+        "web",
+        &[(
+            "moon.yml",
+            "tasks:\n  build:\n    command: 'vite'\n    deps:\n      - '~:install'\n      - 'shared-wasm:gather'\n",
+        )],
+    )?;
+    fs::create_dir_all(workspace_path.join("shared-wasm"))?;
+    fs::write(
+        workspace_path.join("shared-wasm/moon.yml"),
+        "tasks:\n  gather:\n    command: 'node'\n    deps:\n      - 'core:build'\n",
+    )?;
+
+    for (hash, lib_hash, profile) in [("aaa111", "1", "dev"), ("bbb222", "2", "release")] {
+        write_manifest(
+            &workspace_path,
+            hash,
+            serde_json::json!([
+                {
+                    "target": "core:build",
+                    "command": "cargo",
+                    "args": ["build"],
+                    "deps": {},
+                    "env": { "CARGO_PROFILE": profile },
+                    "inputs": {
+                        "crates/core/Cargo.toml": "c",
+                        "crates/core/src/lib.rs": lib_hash
+                    }
+                },
+                { "rust": { "version": "1.89.0" } }
+            ]),
+        )?;
+    }
+
+    let output = workspace.run("test-project", &["why", "core"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Why should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("~ crates/core/src/lib.rs"), "{}", stdout);
+    assert!(!stdout.contains("Cargo.toml"), "{}", stdout);
+    assert!(stdout.contains("~ CARGO_PROFILE"), "{}", stdout);
+    assert!(stdout.contains("shared-wasm:gather"), "{}", stdout);
+    assert!(stdout.contains("web:build"), "{}", stdout);
+    assert!(
+        stdout.contains("moon query hash-diff aaa111 bbb222"),
+        "{}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_why_needs_two_runs() -> anyhow::Result<()> {
+    log("→ Why Needs Two Runs");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    workspace.create_minimal_project("test-project", &ProjectType::React, "web", &[])?;
+
+    let output = workspace.run("test-project", &["why", "web"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("hasn't recorded a run of web:build"));
+
+    write_manifest(
+        &workspace_path,
+        "ccc333",
+        serde_json::json!({ "target": "web:build", "inputs": { "shared-wasm/core.wasm": "1" } }),
+    )?;
+    let output = workspace.run("test-project", &["why", "web"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("single run"));
+
+    write_manifest(
+        &workspace_path,
+        "ddd444",
+        serde_json::json!({ "target": "web:build", "inputs": { "shared-wasm/core.wasm": "2" } }),
+    )?;
+    let output = workspace.run("test-project", &["why", "web"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("WASM modules changed"), "{}", stdout);
+
+    Ok(())
+}
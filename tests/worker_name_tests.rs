@@ -0,0 +1,102 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const NAMING_CONFIG: &str = r#"[workspace]
+name = "shop"
+worker_name = "{workspace}-{project}"
+"#;
+
+#[test]
+fn test_add_applies_worker_name_pattern() -> anyhow::Result<()> {
+    log("→ Add Applies Worker Name Pattern");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(workspace_path.join("moonflare.toml"), NAMING_CONFIG)?;
+
+    let output = workspace.run("shop", &["add", "durable-object", "api"])?;
+    assert!(
+        output.status.success(),
+        "Add should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let wrangler = fs::read_to_string(workspace_path.join("workers/api/wrangler.toml"))?;
+    assert!(wrangler.contains("name = \"shop-api\""), "{}", wrangler);
+    let package: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace_path.join("workers/api/package.json"),
+    )?)?;
+    assert_eq!(package["name"], "api");
+
+    Ok(())
+}
+
+#[test]
+fn test_sync_names_rewrites_existing_configs() -> anyhow::Result<()> {
+    log("→ Sync Names Rewrites Existing Configs");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(workspace_path.join("moonflare.toml"), NAMING_CONFIG)?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "frontend",
+        &[
+            (
+                "wrangler.jsonc",
+                "{\n  // App\n  \"name\": \"frontend\"\n}\n",
+            ),
+            (
+                "wrangler.storybook.jsonc",
+                "{\n  \"name\": \"frontend-storybook\"\n}\n",
+            ),
+        ],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.toml", "name = \"shop-api\"\n")],
+    )?;
+
+    let output = workspace.run("shop", &["sync-names", "--check"])?;
+    assert!(
+        !output.status.success(),
+        "Check should fail on drifted names"
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("'frontend' → 'shop-frontend'"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("shop-api'"), "{}", stdout);
+    let app = workspace_path.join("apps/frontend");
+    assert!(fs::read_to_string(app.join("wrangler.jsonc"))?.contains("\"frontend\""));
+
+    let output = workspace.run("shop", &["sync-names", "--auto-approve"])?;
+    assert!(
+        output.status.success(),
+        "Sync should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let wrangler = fs::read_to_string(app.join("wrangler.jsonc"))?;
+    assert!(
+        wrangler.contains("\"name\": \"shop-frontend\""),
+        "{}",
+        wrangler
+    );
+    assert!(wrangler.contains("// App"), "{}", wrangler);
+    let storybook = fs::read_to_string(app.join("wrangler.storybook.jsonc"))?;
+    assert!(
+        storybook.contains("\"name\": \"shop-frontend-storybook\""),
+        "{}",
+        storybook
+    );
+
+    let output = workspace.run("shop", &["sync-names", "--check"])?;
+    assert!(output.status.success(), "Names should now be in sync");
+
+    Ok(())
+}
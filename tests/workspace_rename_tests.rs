@@ -0,0 +1,94 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_workspace_rename_updates_references() -> anyhow::Result<()> {
+    log("→ Workspace Rename Updates References");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    fs::write(
+        workspace_path.join("moonflare.toml"),
+        "# Moonflare workspace configuration\n\n[workspace]\nname = \"shop\"\n",
+    )?;
+    fs::write(
+        workspace_path.join("README.md"),
+        "# shop\n\nThe shop-admin app is separate.\n",
+    )?;
+
+    let output = workspace.run("shop", &["workspace", "rename", "storefront"])?;
+    assert!(
+        output.status.success(),
+        "Rename should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let config = fs::read_to_string(workspace_path.join("moonflare.toml"))?;
+    assert!(config.contains("name = \"storefront\""), "{}", config);
+    assert!(config.starts_with("# Moonflare workspace configuration"));
+
+    let package: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(workspace_path.join("package.json"))?)?;
+    assert_eq!(package["name"], "storefront");
+
+    let readme = fs::read_to_string(workspace_path.join("README.md"))?;
+    assert!(readme.starts_with("# storefront\n"), "{}", readme);
+    assert!(readme.contains("shop-admin"), "{}", readme);
+
+    Ok(())
+}
+
+#[test]
+fn test_workspace_rename_prefixes_workers() -> anyhow::Result<()> {
+    log("→ Workspace Rename Prefixes Workers");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.toml",
+            "name = \"shop-api\"\nmain = \"src/index.ts\"\n",
+        )],
+    )?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::React,
+        "web",
+        &[("wrangler.jsonc", "{\n  // Site\n  \"name\": \"web\"\n}\n")],
+    )?;
+
+    let output = workspace.run(
+        "shop",
+        &["workspace", "rename", "storefront", "--prefix-workers"],
+    )?;
+    assert!(
+        output.status.success(),
+        "Rename should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let api = fs::read_to_string(workspace_path.join("workers/api/wrangler.toml"))?;
+    assert!(api.contains("name = \"storefront-api\""), "{}", api);
+    let web = fs::read_to_string(workspace_path.join("apps/web/wrangler.jsonc"))?;
+    assert!(web.contains("\"name\": \"storefront-web\""), "{}", web);
+    assert!(web.contains("// Site"), "{}", web);
+
+    Ok(())
+}
+
+#[test]
+fn test_workspace_rename_validates_name() -> anyhow::Result<()> {
+    log("→ Workspace Rename Validates Name");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+
+    let output = workspace.run("shop", &["workspace", "rename", "My Shop"])?;
+    assert!(!output.status.success());
+    let package = fs::read_to_string(workspace_path.join("package.json"))?;
+    assert!(package.contains("\"shop\""), "{}", package);
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use common::*;
+
+mod common;
+
+#[test]
+fn test_newer_min_version_blocks_commands() -> anyhow::Result<()> {
+    log("→ Newer Min Version Blocks Commands");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    std::fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[workspace]\nmin_version = \"99.0.0\"\n",
+    )?;
+
+    let output = workspace.run("test-project", &["tag", "list"])?;
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+    assert!(
+        stderr.contains("requires moonflare 99.0.0"),
+        "Should report the required version: {}",
+        stderr
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_older_min_version_is_accepted() -> anyhow::Result<()> {
+    log("→ Older Min Version Is Accepted");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    std::fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[workspace]\nmin_version = \"0.0.1\"\n",
+    )?;
+
+    let output = workspace.run("test-project", &["tag", "list"])?;
+    assert!(
+        output.status.success(),
+        "Older min_version should not block commands: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_unparseable_min_version_is_an_error() -> anyhow::Result<()> {
+    log("→ Unparseable Min Version Is An Error");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("test-project")?;
+    std::fs::write(
+        workspace_path.join("moonflare.toml"),
+        "[workspace]\nmin_version = \">=0.4\"\n",
+    )?;
+
+    let output = workspace.run("test-project", &["tag", "list"])?;
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("min_version '>=0.4' isn't a version"),
+        "Should reject the min_version: {}",
+        stderr
+    );
+
+    Ok(())
+}
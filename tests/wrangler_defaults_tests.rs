@@ -0,0 +1,115 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+const DEFAULTS: &str = r#"{
+  // Shared by every Worker
+  "account_id": "acct-1",
+  "compatibility_date": "2025-06-01",
+  "compatibility_flags": ["nodejs_compat"],
+  "observability": { "enabled": true, "head_sampling_rate": 1 },
+}
+"#;
+
+const WRANGLER_JSONC: &str = r#"{
+  "name": "api",
+  "main": "src/index.ts",
+  "compatibility_date": "2024-01-01",
+  "observability": { "head_sampling_rate": 0.5 }
+}
+"#;
+
+#[test]
+fn test_config_effective_merges_workspace_defaults() -> anyhow::Result<()> {
+    log("→ Config Effective Merges Workspace Defaults");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", WRANGLER_JSONC)],
+    )?;
+
+    // Without defaults the project's own configuration is the effective one
+    let output = workspace.run("shop", &["config", "effective", "api", "--json"])?;
+    assert!(output.status.success());
+    let effective: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert!(effective.get("account_id").is_none());
+
+    fs::write(workspace_path.join("wrangler-defaults.jsonc"), DEFAULTS)?;
+    let output = workspace.run("shop", &["config", "effective", "api", "--json"])?;
+    assert!(
+        output.status.success(),
+        "Effective should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let effective: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(effective["name"], "api");
+    assert_eq!(effective["account_id"], "acct-1");
+    assert_eq!(effective["compatibility_date"], "2024-01-01");
+    assert_eq!(effective["compatibility_flags"][0], "nodejs_compat");
+    assert_eq!(effective["observability"]["enabled"], true);
+    assert_eq!(effective["observability"]["head_sampling_rate"], 0.5);
+
+    let output = workspace.run("shop", &["config", "effective", "api"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("From wrangler-defaults.jsonc:"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("/observability/enabled"), "{}", stdout);
+    assert!(!stdout.contains("/compatibility_date"), "{}", stdout);
+
+    let output = workspace.run("shop", &["config", "effective", "missing"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not found"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_deploy_passes_merged_config_to_wrangler() -> anyhow::Result<()> {
+    log("→ Deploy Passes Merged Config To Wrangler");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", WRANGLER_JSONC)],
+    )?;
+    fs::write(workspace_path.join("wrangler-defaults.jsonc"), DEFAULTS)?;
+
+    // Fake `moon` keeping a copy of the configuration a deploy script would pass to Wrangler
+    let path = fake_tools(
+        workspace.path(),
+        &[(
+            "moon",
+            "if [ -n \"$WRANGLER_CONFIG\" ]; then cp \"workers/api/$WRANGLER_CONFIG\" seen.json; fi\nexit 0\n",
+        )],
+    )?;
+
+    let output = workspace.run_with_env("shop", &["deploy", "api"], &[("PATH", &path)])?;
+    assert!(
+        output.status.success(),
+        "Deploy should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let seen: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(workspace_path.join("seen.json"))?)?;
+    assert_eq!(seen["account_id"], "acct-1");
+    assert_eq!(seen["main"], "src/index.ts");
+    assert!(
+        !workspace_path
+            .join("workers/api/.wrangler.effective.json")
+            .exists(),
+        "The merged config is removed after the deploy"
+    );
+
+    Ok(())
+}
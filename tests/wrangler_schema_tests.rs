@@ -0,0 +1,160 @@
+use common::*;
+use std::fs;
+
+mod common;
+
+/// A slice of Wrangler's config-schema.json, in the shape it's generated in
+const SCHEMA: &str = r##"{
+  "$ref": "#/definitions/RawConfig",
+  "definitions": {
+    "RawConfig": {
+      "type": "object",
+      "properties": {
+        "$schema": { "type": "string" },
+        "name": { "type": "string" },
+        "main": { "type": "string" },
+        "compatibility_date": { "type": "string" },
+        "kv_namespaces": {
+          "type": "array",
+          "items": { "$ref": "#/definitions/KvNamespace" }
+        },
+        "workers_dev": { "type": "boolean" },
+        "observability": {
+          "anyOf": [
+            { "type": "boolean" },
+            {
+              "type": "object",
+              "properties": { "enabled": { "type": "boolean" } },
+              "additionalProperties": false
+            }
+          ]
+        }
+      },
+      "additionalProperties": false
+    },
+    "KvNamespace": {
+      "type": "object",
+      "properties": {
+        "binding": { "type": "string" },
+        "id": { "type": "string" }
+      },
+      "required": ["binding"],
+      "additionalProperties": false
+    }
+  }
+}
+"##;
+
+const BROKEN_JSONC: &str = r#"{
+  // Deployed as the shop's API
+  "name": "api",
+  "main": "src/index.ts",
+  "compatability_date": "2025-08-15",
+  "workers_dev": "yes",
+  "kv_namespaces": [
+    { "id": "abc123" },
+  ],
+  "observability": { "enabled": true, "sampling": 1 },
+}
+"#;
+
+#[test]
+fn test_build_reports_schema_violations_in_wrangler_jsonc() -> anyhow::Result<()> {
+    log("→ Build Reports Schema Violations In Wrangler JSONC");
+    let workspace = MoonflareTestWorkspace::new()?;
+    let workspace_path = workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[("wrangler.jsonc", BROKEN_JSONC)],
+    )?;
+    let schema = workspace_path.join("node_modules/wrangler/config-schema.json");
+    fs::create_dir_all(schema.parent().unwrap())?;
+    fs::write(&schema, SCHEMA)?;
+
+    let output = workspace.run("shop", &["build", "api"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("isn't a valid Wrangler configuration"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("moonflare::wrangler::invalid_config"),
+        "{}",
+        stderr
+    );
+    for problem in [
+        "unknown key \"compatability_date\"; did you mean \"compatibility_date\"?",
+        "expected boolean, found string",
+        "is missing required key \"binding\"",
+        "unknown key \"sampling\"",
+    ] {
+        assert!(
+            stderr.contains(problem),
+            "Missing '{}': {}",
+            problem,
+            stderr
+        );
+    }
+    // Labels point into the source, which is shown with its line numbers
+    assert!(
+        stderr.contains("\"compatability_date\": \"2025-08-15\""),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains(" 5 │"), "{}", stderr);
+
+    // A valid config gets past the check
+    fs::write(
+        workspace_path.join("workers/api/wrangler.jsonc"),
+        BROKEN_JSONC
+            .replace("compatability_date", "compatibility_date")
+            .replace("\"yes\"", "true")
+            .replace("{ \"id\"", "{ \"binding\": \"CACHE\", \"id\"")
+            .replace(", \"sampling\": 1", ""),
+    )?;
+    let output = workspace.run("shop", &["build", "api"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Wrangler configuration"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn test_dev_reports_jsonc_syntax_errors_without_a_schema() -> anyhow::Result<()> {
+    log("→ Dev Reports JSONC Syntax Errors Without A Schema");
+    let workspace = MoonflareTestWorkspace::new()?;
+    workspace.create_minimal_workspace("shop")?;
+    workspace.create_minimal_project(
+        "shop",
+        &ProjectType::DurableObject,
+        "api",
+        &[(
+            "wrangler.jsonc",
+            "{\n  /* no schema installed */\n  \"name\": \"api\"\n  \"main\": \"src/index.ts\"\n}\n",
+        )],
+    )?;
+
+    let output = workspace.run("shop", &["dev", "api"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("expected ',' or '}'"), "{}", stderr);
+    assert!(stderr.contains(" 4 │"), "{}", stderr);
+    assert!(!stderr.contains("Dev command failed"), "{}", stderr);
+
+    // TOML configs are left to Wrangler
+    let api = workspace.path().join("shop/workers/api");
+    fs::remove_file(api.join("wrangler.jsonc"))?;
+    fs::write(
+        api.join("wrangler.toml"),
+        "name = \"api\"\nworkers_dev = \"yes\"\n",
+    )?;
+    let output = workspace.run("shop", &["dev", "api"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Wrangler configuration"), "{}", stderr);
+
+    Ok(())
+}